@@ -37,6 +37,26 @@ fn bench_argument_parsing(c: &mut Criterion) {
         })
     });
 
+    // A map of vectors of structs, nested a few levels deep, to catch work
+    // that grows with depth times size.
+    group.bench_function("deeply_nested", |b| {
+        let mut value = serde_json::json!({"amount": {"type": "u64", "value": 1}, "memo": "m"});
+        for depth in 0..8 {
+            value = if depth % 2 == 0 {
+                serde_json::json!([value.clone(), value.clone(), value])
+            } else {
+                serde_json::json!({"type": "map", "value": {"left": value.clone(), "right": value}})
+            };
+        }
+        let json = serde_json::json!([value]).to_string();
+        b.iter(|| {
+            let env = Env::default();
+            let parser = ArgumentParser::new(env);
+            let result = parser.parse_args_string(black_box(&json)).unwrap();
+            black_box(result);
+        })
+    });
+
     group.finish();
 }
 
@@ -1,5 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use soroban_debugger::repeat::RepeatRunner;
 use soroban_debugger::runtime::executor::ContractExecutor;
+use soroban_debugger::runtime::ring::TraceRing;
 use std::fs;
 use std::path::PathBuf;
 
@@ -34,5 +36,60 @@ fn bench_contract_execution(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, bench_contract_execution);
+/// The always-on trace ring against no ring at all; its overhead on a call
+/// should stay within a few percent.
+fn bench_trace_ring(c: &mut Criterion) {
+    let mut wasm_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    wasm_path.push("tests/fixtures/wasm/counter.wasm");
+    let wasm_bytes = fs::read(wasm_path).expect("Failed to read counter.wasm");
+
+    let mut group = c.benchmark_group("trace_ring");
+    for (name, ring) in [
+        ("ring_disabled", TraceRing::new(0, None)),
+        ("ring_default", TraceRing::default()),
+    ] {
+        let mut executor = ContractExecutor::new(wasm_bytes.clone()).unwrap();
+        executor.set_trace_ring(ring);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                executor.env().cost_estimate().budget().reset_unlimited();
+                let result = executor
+                    .execute(black_box("increment"), black_box(None))
+                    .unwrap();
+                black_box(result);
+            })
+        });
+    }
+    group.finish();
+}
+
+/// `--repeat 20` on one shared environment against a new one per iteration;
+/// sharing should stay well ahead.
+fn bench_repeat(c: &mut Criterion) {
+    let mut wasm_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    wasm_path.push("tests/fixtures/wasm/counter.wasm");
+    let wasm_bytes = fs::read(wasm_path).expect("Failed to read counter.wasm");
+
+    let mut group = c.benchmark_group("repeat");
+    for (name, fresh) in [("shared_env", false), ("fresh_state", true)] {
+        let runner =
+            RepeatRunner::new(wasm_bytes.clone(), Vec::new(), None).with_fresh_state(fresh);
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let stats = runner
+                    .run(black_box("increment"), black_box(None), 20)
+                    .unwrap();
+                black_box(stats);
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_contract_execution,
+    bench_trace_ring,
+    bench_repeat
+);
 criterion_main!(benches);
@@ -0,0 +1,56 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use soroban_debugger::cli::args::SnapshotFormat;
+use soroban_debugger::simulator::format::entry_count;
+use soroban_debugger::simulator::{AccountState, ContractState, NetworkSnapshot, SnapshotManager};
+
+/// Roughly 100k entries: 1,000 accounts plus 100 contracts with 989 storage entries each.
+fn large_snapshot() -> NetworkSnapshot {
+    let mut snapshot = NetworkSnapshot::new(1_000_000, "Test SDF Network ; September 2015", 0);
+    for i in 0..1_000 {
+        snapshot
+            .add_account(AccountState::new(
+                format!("GACCOUNT{:06}", i),
+                "1000000000",
+                i,
+            ))
+            .unwrap();
+    }
+    for c in 0..100 {
+        let mut contract = ContractState::new(format!("CCONTRACT{:04}", c), "ab".repeat(32));
+        for k in 0..989 {
+            contract.storage.insert(
+                format!("balance:{:04}:{:04}", c, k),
+                serde_json::json!({ "amount": k * 1_000, "authorized": k % 2 == 0 }),
+            );
+        }
+        snapshot.contracts.push(contract);
+    }
+    snapshot
+}
+
+fn bench_snapshot_loading(c: &mut Criterion) {
+    let snapshot = large_snapshot();
+    assert_eq!(entry_count(&snapshot), 100_000);
+
+    let dir = tempfile::tempdir().unwrap();
+    let v1_path = dir.path().join("snapshot.v1.json");
+    let v2_path = dir.path().join("snapshot.v2");
+    SnapshotManager::save_as(&snapshot, &v1_path, SnapshotFormat::V1Json).unwrap();
+    SnapshotManager::save_as(&snapshot, &v2_path, SnapshotFormat::V2).unwrap();
+
+    let mut group = c.benchmark_group("snapshot_loading");
+    group.sample_size(10);
+
+    group.bench_function("load_100k_v1_json", |b| {
+        b.iter(|| black_box(SnapshotManager::load(&v1_path).unwrap()))
+    });
+
+    group.bench_function("load_100k_v2", |b| {
+        b.iter(|| black_box(SnapshotManager::load(&v2_path).unwrap()))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_snapshot_loading);
+criterion_main!(benches);
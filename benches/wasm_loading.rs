@@ -1,4 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use soroban_debugger::utils::artifact_cache::{ArtifactCache, ContractArtifacts};
 use soroban_debugger::ContractExecutor;
 use std::fs;
 use std::path::PathBuf;
@@ -18,5 +19,29 @@ fn bench_wasm_loading(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, bench_wasm_loading);
+/// Cold parse of exports/spec/names versus loading them from the on-disk cache,
+/// i.e. the per-invocation startup work a second `run` skips.
+fn bench_artifact_cache(c: &mut Criterion) {
+    let mut wasm_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    wasm_path.push("tests/fixtures/wasm/counter.wasm");
+    let wasm_bytes = fs::read(wasm_path).expect("Failed to read counter.wasm");
+    let cache_dir = tempfile::TempDir::new().expect("Failed to create cache dir");
+    let cache = ArtifactCache::new(cache_dir.path().to_path_buf());
+    let wasm_hash = cache.get_or_compute(&wasm_bytes).unwrap().wasm_hash;
+
+    let mut group = c.benchmark_group("artifact_cache");
+    group.bench_function("cold_parse", |b| {
+        b.iter(|| {
+            black_box(
+                ContractArtifacts::compute(black_box(&wasm_bytes), wasm_hash.clone()).unwrap(),
+            )
+        })
+    });
+    group.bench_function("warm_disk_hit", |b| {
+        b.iter(|| black_box(cache.load(black_box(&wasm_hash)).unwrap()))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_wasm_loading, bench_artifact_cache);
 criterion_main!(benches);
@@ -11,6 +11,10 @@ mod config {
     pub struct Config {
         pub debug: DebugConfig,
         pub output: OutputConfig,
+        pub conversions: std::collections::BTreeMap<String, ConversionConfig>,
+        pub conventions: ConventionsConfig,
+        pub ledger: LedgerConfig,
+        pub rent: RentConfig,
     }
 
     pub struct DebugConfig {
@@ -21,6 +25,51 @@ mod config {
     pub struct OutputConfig {
         pub format: Option<String>,
         pub show_events: Option<bool>,
+        pub show: Option<Vec<String>>,
+    }
+
+    #[derive(Clone)]
+    pub struct ConversionConfig;
+
+    #[derive(Clone, Default)]
+    pub struct RentConfig;
+
+    pub struct ConventionsConfig {
+        pub readonly_patterns: Vec<String>,
+    }
+
+    pub struct LedgerConfig {
+        pub timestamp: Option<LedgerTimestamp>,
+        pub sequence: Option<u32>,
+        pub network_passphrase: Option<String>,
+        pub protocol_version: Option<u32>,
+    }
+
+    pub struct LedgerTimestamp;
+
+    impl std::fmt::Display for LedgerTimestamp {
+        fn fmt(&self, _f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            Ok(())
+        }
+    }
+}
+
+#[allow(dead_code)]
+mod warnings {
+    #[derive(Clone, clap::ValueEnum)]
+    pub enum WarningCategory {
+        Ttl,
+        Deprecation,
+        Expectation,
+        Mock,
+        Event,
+        Ledger,
+        Codegen,
+        Trace,
+        Convention,
+        Observer,
+        Code,
+        Arguments,
     }
 }
 
@@ -0,0 +1,183 @@
+//! Checks that functions named as read-only behave that way.
+//!
+//! A function is read-only when it matches a `[conventions] readonly_patterns`
+//! glob, or when its contract spec doc comment contains [`READONLY_ANNOTATION`].
+//! After a run, the storage writes captured by the executor and the contract
+//! events emitted are checked against that intent.
+
+use crate::config::ConventionsConfig;
+use crate::runtime::policy::glob_matches;
+use crate::Result;
+use soroban_env_host::{xdr::ContractEventType, Host};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Doc comment marker that makes a function read-only.
+pub const READONLY_ANNOTATION: &str = "@readonly";
+
+/// Which functions are expected not to mutate state.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyConvention {
+    patterns: Vec<String>,
+    docs: HashMap<String, String>,
+}
+
+impl ReadOnlyConvention {
+    pub fn new(config: &ConventionsConfig, wasm_bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            patterns: config.readonly_patterns.clone(),
+            docs: crate::utils::wasm::parse_function_docs(wasm_bytes)?,
+        })
+    }
+
+    /// Why `function` is read-only, or `None` if it is not.
+    pub fn reason(&self, function: &str) -> Option<String> {
+        if let Some(pattern) = self.patterns.iter().find(|p| glob_matches(p, function)) {
+            return Some(format!("matches readonly pattern `{}`", pattern));
+        }
+        self.docs
+            .get(function)
+            .filter(|doc| {
+                doc.split_whitespace()
+                    .any(|word| word == READONLY_ANNOTATION)
+            })
+            .map(|_| format!("is annotated {} in the contract spec", READONLY_ANNOTATION))
+    }
+
+    /// Check one invocation of `function`.
+    ///
+    /// `writes` are the storage keys it wrote, as reported by
+    /// [`ContractExecutor::last_storage_writes`](crate::runtime::ContractExecutor::last_storage_writes).
+    pub fn check(
+        &self,
+        function: &str,
+        writes: &HashSet<String>,
+        events: usize,
+    ) -> Option<ReadOnlyViolation> {
+        if writes.is_empty() && events == 0 {
+            return None;
+        }
+        let reason = self.reason(function)?;
+        let mut writes: Vec<String> = writes.iter().cloned().collect();
+        writes.sort();
+        Some(ReadOnlyViolation {
+            function: function.to_string(),
+            reason,
+            writes,
+            events,
+        })
+    }
+}
+
+/// A read-only function that mutated state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReadOnlyViolation {
+    pub function: String,
+    pub reason: String,
+    /// Storage keys written, sorted.
+    pub writes: Vec<String>,
+    /// Contract events emitted.
+    pub events: usize,
+}
+
+impl ReadOnlyViolation {
+    /// One line per violation: each written key, then the events.
+    pub fn details(&self) -> Vec<String> {
+        let mut details: Vec<String> = self
+            .writes
+            .iter()
+            .map(|key| format!("wrote {}", key))
+            .collect();
+        if self.events > 0 {
+            details.push(format!("emitted {} contract event(s)", self.events));
+        }
+        details
+    }
+}
+
+impl fmt::Display for ReadOnlyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` {} but mutated state ({} storage write(s), {} event(s))",
+            self.function,
+            self.reason,
+            self.writes.len(),
+            self.events
+        )
+    }
+}
+
+/// Contract events (not diagnostic or system events) recorded by `host`.
+pub fn contract_event_count(host: &Host) -> usize {
+    host.get_events()
+        .map(|events| {
+            events
+                .0
+                .iter()
+                .filter(|event| {
+                    !event.failed_call && event.event.type_ == ContractEventType::Contract
+                })
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convention(patterns: &[&str], docs: &[(&str, &str)]) -> ReadOnlyConvention {
+        ReadOnlyConvention {
+            patterns: patterns.iter().map(|p| p.to_string()).collect(),
+            docs: docs
+                .iter()
+                .map(|(f, d)| (f.to_string(), d.to_string()))
+                .collect(),
+        }
+    }
+
+    fn writes(keys: &[&str]) -> HashSet<String> {
+        keys.iter().map(|k| k.to_string()).collect()
+    }
+
+    #[test]
+    fn pattern_matching_getter_that_writes_is_a_violation() {
+        let convention = convention(&["get_*", "view_*"], &[]);
+        let violation = convention
+            .check(
+                "get_price",
+                &writes(&["instance:c", "contract_instance"]),
+                0,
+            )
+            .unwrap();
+        assert!(violation.reason.contains("get_*"), "{}", violation);
+        assert_eq!(violation.writes, ["contract_instance", "instance:c"]);
+        assert_eq!(
+            violation.details(),
+            ["wrote contract_instance", "wrote instance:c"]
+        );
+    }
+
+    #[test]
+    fn events_alone_are_a_violation() {
+        let convention = convention(&["view_*"], &[]);
+        let violation = convention.check("view_pool", &writes(&[]), 2).unwrap();
+        assert_eq!(violation.details(), ["emitted 2 contract event(s)"]);
+    }
+
+    #[test]
+    fn spec_annotation_marks_functions_read_only() {
+        let convention = convention(&[], &[("price", "Current price.\n@readonly")]);
+        let violation = convention.check("price", &writes(&["k"]), 0).unwrap();
+        assert!(violation.reason.contains("@readonly"), "{}", violation);
+        assert!(convention.reason("price_of").is_none());
+    }
+
+    #[test]
+    fn clean_or_unmarked_functions_pass() {
+        let convention = convention(&["get_*"], &[]);
+        assert!(convention.check("get_price", &writes(&[]), 0).is_none());
+        assert!(convention.check("set_price", &writes(&["k"]), 1).is_none());
+    }
+}
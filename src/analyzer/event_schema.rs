@@ -0,0 +1,1315 @@
+//! Event schema extraction and validation.
+//!
+//! Soroban contracts publish events through the `contract_event` host function
+//! (module `x`, export `1`), passing a vector of topics and the data. The
+//! first topic names the event and is almost always a constant symbol: a
+//! `SymbolSmall` held in an `i64.const`, or for names over 9 characters a
+//! `SymbolObject` built from a data segment. Following the values each
+//! function hands to `contract_event` gives the topics a contract can emit,
+//! and the kind of data it sends with them.
+//!
+//! The v22 contract spec has no event entries; it is only used for the types
+//! of parameters passed through as event data. Schemas serialize to JSON, so
+//! users can edit the output of `inspect --events` to pin down data shapes
+//! and feed it back into `run --validate-events --event-schema`.
+
+use crate::inspector::events::ContractEvent;
+use crate::utils::wasm::{parse_function_signatures, parse_type_definitions, TypeDefinition};
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use wasmparser::{
+    BinaryReaderError, BlockType, CompositeType, DataKind, ExternalKind, Operator, Parser, Payload,
+    TypeRef,
+};
+
+/// Host import module and function name for `contract_event`.
+const CONTRACT_EVENT_IMPORT: (&str, &str) = ("x", "1");
+
+/// `Val` tag used for small symbols (up to 9 characters packed into 54 bits).
+const SYMBOL_SMALL_TAG: u64 = 14;
+
+/// `Val` tag of a `u32`, held in the upper 32 bits.
+const U32_TAG: u64 = 4;
+
+/// The `Void` `Val`.
+const VOID: i64 = 2;
+
+/// Topics emitted by the host itself when diagnostics are enabled.
+const DIAGNOSTIC_TOPICS: &[&str] = &["fn_call", "fn_return", "log", "error", "core_metrics"];
+
+/// Where an event shape was learned from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventShapeSource {
+    StaticAnalysis,
+    UserSchema,
+}
+
+/// One event a contract can emit, keyed by its first topic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventShape {
+    /// First topic symbol, e.g. `transfer`.
+    pub topic: String,
+    /// Exported functions whose bodies publish this topic.
+    #[serde(default)]
+    pub emitted_by: Vec<String>,
+    /// Allowed `ScVal` kinds for the event data (e.g. `i128`, `map`).
+    /// `None` accepts any payload.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<String>>,
+    pub source: EventShapeSource,
+}
+
+/// JSON-serializable description of the events a contract can emit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventSchema {
+    pub events: Vec<EventShape>,
+}
+
+/// A mismatch between an emitted event and the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventSchemaViolation {
+    UnknownTopic {
+        index: usize,
+        topic: String,
+        suggestion: Option<String>,
+    },
+    UnexpectedData {
+        index: usize,
+        topic: String,
+        found: String,
+        expected: Vec<String>,
+    },
+}
+
+impl std::fmt::Display for EventSchemaViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventSchemaViolation::UnknownTopic {
+                index,
+                topic,
+                suggestion,
+            } => {
+                write!(f, "event #{}: unknown topic '{}'", index, topic)?;
+                if let Some(s) = suggestion {
+                    write!(f, " (did you mean '{}'?)", s)?;
+                }
+                Ok(())
+            }
+            EventSchemaViolation::UnexpectedData {
+                index,
+                topic,
+                found,
+                expected,
+            } => write!(
+                f,
+                "event #{}: '{}' data is {} but schema expects {}",
+                index,
+                topic,
+                found,
+                expected.join(" | ")
+            ),
+        }
+    }
+}
+
+impl EventSchema {
+    /// Load a schema previously written by `inspect --events --format json`.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read event schema {:?}: {}", path, e))
+        })?;
+        let mut schema: EventSchema = serde_json::from_str(&content).map_err(|e| {
+            DebuggerError::InvalidArguments(format!("Invalid event schema {:?}: {}", path, e))
+        })?;
+        for event in &mut schema.events {
+            event.source = EventShapeSource::UserSchema;
+        }
+        Ok(schema)
+    }
+
+    pub fn get(&self, topic: &str) -> Option<&EventShape> {
+        self.events.iter().find(|e| e.topic == topic)
+    }
+
+    /// Check emitted events against the schema.
+    ///
+    /// Events whose first topic is not a symbol, and host diagnostic events,
+    /// are skipped since they carry no contract-defined name.
+    pub fn validate(&self, events: &[ContractEvent]) -> Vec<EventSchemaViolation> {
+        let mut violations = Vec::new();
+        for (index, event) in events.iter().enumerate() {
+            let Some(topic) = event.topics.first().and_then(|t| symbol_topic_name(t)) else {
+                continue;
+            };
+            if DIAGNOSTIC_TOPICS.contains(&topic) {
+                continue;
+            }
+
+            let Some(shape) = self.get(topic) else {
+                violations.push(EventSchemaViolation::UnknownTopic {
+                    index,
+                    topic: topic.to_string(),
+                    suggestion: self.closest_topic(topic),
+                });
+                continue;
+            };
+
+            if let Some(expected) = &shape.data {
+                let found = data_kind(&event.data);
+                if !expected.iter().any(|k| k.eq_ignore_ascii_case(&found)) {
+                    violations.push(EventSchemaViolation::UnexpectedData {
+                        index,
+                        topic: topic.to_string(),
+                        found,
+                        expected: expected.clone(),
+                    });
+                }
+            }
+        }
+        violations
+    }
+
+    fn closest_topic(&self, topic: &str) -> Option<String> {
+        self.events
+            .iter()
+            .map(|e| (edit_distance(&e.topic, topic), &e.topic))
+            .filter(|(d, _)| *d <= 2)
+            .min_by_key(|(d, _)| *d)
+            .map(|(_, t)| t.clone())
+    }
+}
+
+/// Canonical signature of an emitted event, used to compare the events of
+/// different runs: the first topic's symbol followed by the kinds of the
+/// remaining topics, e.g. `transfer(address, address)`, or just `init` for
+/// a single topic.
+///
+/// Events without a symbol as their first topic and host diagnostic events
+/// have no signature, matching what [`EventSchema::validate`] skips.
+pub fn topic_signature(event: &ContractEvent) -> Option<String> {
+    let (first, rest) = event.topics.split_first()?;
+    let name = symbol_topic_name(first)?;
+    if DIAGNOSTIC_TOPICS.contains(&name) {
+        return None;
+    }
+    if rest.is_empty() {
+        return Some(name.to_string());
+    }
+    let kinds: Vec<String> = rest.iter().map(|topic| data_kind(topic)).collect();
+    Some(format!("{}({})", name, kinds.join(", ")))
+}
+
+/// The distinct [`topic_signature`]s of `events`, sorted.
+pub fn topic_signatures(events: &[ContractEvent]) -> Vec<String> {
+    events
+        .iter()
+        .filter_map(topic_signature)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// Derive the event schema of a contract from its WASM.
+///
+/// Every function that calls `contract_event` is run abstractly over its
+/// operators, so only the first element of the topics vector it passes is
+/// taken as a topic, whether that is a constant `SymbolSmall` or a longer
+/// symbol built from a data segment. The data kind comes from the constant
+/// or host object passed as data, or from the spec type of the parameter it
+/// was read from; when it can't be told, the shape accepts any data.
+pub fn extract_event_schema(wasm_bytes: &[u8]) -> Result<EventSchema> {
+    let module = ModuleInfo::parse(wasm_bytes)?;
+    if module.bodies.is_empty() {
+        return Ok(EventSchema::default());
+    }
+
+    let types = parse_type_definitions(wasm_bytes)?;
+    let spec_params: HashMap<String, Vec<Option<String>>> = parse_function_signatures(wasm_bytes)?
+        .into_iter()
+        .map(|sig| {
+            let kinds = sig
+                .params
+                .iter()
+                .map(|param| spec_kind(&param.type_name, &types))
+                .collect();
+            (sig.name, kinds)
+        })
+        .collect();
+
+    // Per topic: the functions emitting it, and its data kinds, `None` once
+    // one emitting site passes data of an unknown kind.
+    let mut shapes: BTreeMap<String, (BTreeSet<String>, Option<BTreeSet<String>>)> =
+        BTreeMap::new();
+    for body in &module.bodies {
+        let export = module.exports.get(&body.index);
+        let emitter = export
+            .cloned()
+            .unwrap_or_else(|| format!("func_{}", body.index));
+        let params = export.and_then(|name| spec_params.get(name));
+        for event in FunctionScan::new(&module, body, params).run() {
+            let (emitters, data) = shapes
+                .entry(event.topic)
+                .or_insert_with(|| (BTreeSet::new(), Some(BTreeSet::new())));
+            emitters.insert(emitter.clone());
+            match (data.as_mut(), event.data) {
+                (Some(kinds), Some(kind)) => {
+                    kinds.insert(kind);
+                }
+                _ => *data = None,
+            }
+        }
+    }
+
+    Ok(EventSchema {
+        events: shapes
+            .into_iter()
+            .map(|(topic, (emitters, data))| EventShape {
+                topic,
+                emitted_by: emitters.into_iter().collect(),
+                data: data.map(|kinds| kinds.into_iter().collect()),
+                source: EventShapeSource::StaticAnalysis,
+            })
+            .collect(),
+    })
+}
+
+/// Lowercased `ScVal` kind of a value of spec type `type_name`, as
+/// [`data_kind`] reads it off an emitted event. `None` for types whose
+/// values can be of several kinds, such as `Option`.
+fn spec_kind(type_name: &str, types: &[TypeDefinition]) -> Option<String> {
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    let kind = match base {
+        "Val" | "Option" | "Result" => return None,
+        "Bool" | "Void" | "Error" | "U32" | "I32" | "U64" | "I64" | "Timepoint" | "Duration"
+        | "U128" | "I128" | "U256" | "I256" | "Bytes" | "String" | "Symbol" | "Address" | "Vec"
+        | "Map" => return Some(base.to_ascii_lowercase()),
+        "Tuple" => "vec",
+        "BytesN" => "bytes",
+        udt => match types.iter().find(|ty| ty.name() == udt)? {
+            // Tuple structs are stored as vectors, named-field structs as maps.
+            TypeDefinition::Struct { fields, .. }
+                if !fields.is_empty()
+                    && fields.iter().all(|(name, _)| name.parse::<u32>().is_ok()) =>
+            {
+                "vec"
+            }
+            TypeDefinition::Struct { .. } => "map",
+            TypeDefinition::Union { .. } => "vec",
+            TypeDefinition::Enum { .. } => "u32",
+        },
+    };
+    Some(kind.to_string())
+}
+
+/// Host functions the analysis follows, by import module and name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HostFn {
+    ContractEvent,
+    VecNew,
+    VecPushBack,
+    VecNewFromLinearMemory,
+    SymbolNewFromLinearMemory,
+    /// Returns a new object of the given kind and touches no linear memory.
+    Object(&'static str),
+    Other,
+}
+
+impl HostFn {
+    fn from_import(module: &str, name: &str) -> Self {
+        match (module, name) {
+            CONTRACT_EVENT_IMPORT => HostFn::ContractEvent,
+            ("v", "_") => HostFn::VecNew,
+            ("v", "6") => HostFn::VecPushBack,
+            ("v", "g") => HostFn::VecNewFromLinearMemory,
+            ("b", "j") => HostFn::SymbolNewFromLinearMemory,
+            ("i", "_") => HostFn::Object("u64"),
+            ("i", "1") => HostFn::Object("i64"),
+            ("i", "3") => HostFn::Object("u128"),
+            ("i", "6") => HostFn::Object("i128"),
+            ("i", "9") => HostFn::Object("u256"),
+            ("i", "g") => HostFn::Object("i256"),
+            ("i", "D") => HostFn::Object("timepoint"),
+            ("i", "F") => HostFn::Object("duration"),
+            ("m", "_") | ("m", "9") => HostFn::Object("map"),
+            ("b", "3") | ("b", "4") => HostFn::Object("bytes"),
+            ("b", "i") => HostFn::Object("string"),
+            _ => HostFn::Other,
+        }
+    }
+}
+
+/// The parts of a module the analysis reads.
+struct ModuleInfo<'a> {
+    /// Parameter and result counts of each function type.
+    types: Vec<(usize, usize)>,
+    /// Type index of every function, imported ones first.
+    functions: Vec<u32>,
+    /// The host function behind each imported function.
+    imports: Vec<HostFn>,
+    exports: HashMap<u32, String>,
+    /// Active data segments as `(address, bytes)`.
+    data: Vec<(u32, &'a [u8])>,
+    /// Bodies of the functions that call `contract_event`.
+    bodies: Vec<FunctionBody<'a>>,
+}
+
+struct FunctionBody<'a> {
+    index: u32,
+    /// Declared locals, after the parameters.
+    locals: usize,
+    operators: Vec<Operator<'a>>,
+}
+
+impl<'a> ModuleInfo<'a> {
+    fn parse(wasm_bytes: &'a [u8]) -> Result<Self> {
+        let mut module = ModuleInfo {
+            types: Vec::new(),
+            functions: Vec::new(),
+            imports: Vec::new(),
+            exports: HashMap::new(),
+            data: Vec::new(),
+            bodies: Vec::new(),
+        };
+        let mut next_body = 0u32;
+
+        for payload in Parser::new(0).parse_all(wasm_bytes) {
+            match payload
+                .map_err(|e| DebuggerError::WasmLoadError(format!("Failed to parse WASM: {}", e)))?
+            {
+                Payload::TypeSection(reader) => {
+                    for rec_group in reader {
+                        let rec_group = rec_group.map_err(|e| read_error("type", e))?;
+                        for ty in rec_group.types() {
+                            if let CompositeType::Func(func) = &ty.composite_type {
+                                module
+                                    .types
+                                    .push((func.params().len(), func.results().len()));
+                            }
+                        }
+                    }
+                }
+                Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.map_err(|e| read_error("import", e))?;
+                        if let TypeRef::Func(ty) = import.ty {
+                            module.functions.push(ty);
+                            module
+                                .imports
+                                .push(HostFn::from_import(import.module, import.name));
+                        }
+                    }
+                }
+                Payload::FunctionSection(reader) => {
+                    for ty in reader {
+                        module
+                            .functions
+                            .push(ty.map_err(|e| read_error("function", e))?);
+                    }
+                }
+                Payload::ExportSection(reader) => {
+                    for export in reader {
+                        let export = export.map_err(|e| read_error("export", e))?;
+                        if matches!(export.kind, ExternalKind::Func) {
+                            module.exports.insert(export.index, export.name.to_string());
+                        }
+                    }
+                }
+                Payload::CodeSectionEntry(body) => {
+                    let index = module.imports.len() as u32 + next_body;
+                    next_body += 1;
+
+                    let mut operators = Vec::new();
+                    let mut reader = body
+                        .get_operators_reader()
+                        .map_err(|e| read_error("code", e))?;
+                    while !reader.eof() {
+                        operators.push(reader.read().map_err(|e| read_error("operator", e))?);
+                    }
+                    let publishes = operators.iter().any(|op| {
+                        matches!(op, Operator::Call { function_index }
+                            if module.imports.get(*function_index as usize)
+                                == Some(&HostFn::ContractEvent))
+                    });
+                    if !publishes {
+                        continue;
+                    }
+
+                    let mut locals = 0usize;
+                    for entry in body
+                        .get_locals_reader()
+                        .map_err(|e| read_error("locals", e))?
+                    {
+                        locals += entry.map_err(|e| read_error("locals", e))?.0 as usize;
+                    }
+                    module.bodies.push(FunctionBody {
+                        index,
+                        locals,
+                        operators,
+                    });
+                }
+                Payload::DataSection(reader) => {
+                    for segment in reader {
+                        let segment = segment.map_err(|e| read_error("data", e))?;
+                        let DataKind::Active { offset_expr, .. } = segment.kind else {
+                            continue;
+                        };
+                        if let Ok(Operator::I32Const { value }) =
+                            offset_expr.get_operators_reader().read()
+                        {
+                            module.data.push((value as u32, segment.data));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(module)
+    }
+
+    fn func_type(&self, function_index: u32) -> Option<(usize, usize)> {
+        let ty = *self.functions.get(function_index as usize)?;
+        self.types.get(ty as usize).copied()
+    }
+
+    /// `len` bytes of the data segments at `address`, if one segment holds them.
+    fn read_data(&self, address: u32, len: usize) -> Option<&'a [u8]> {
+        self.data.iter().find_map(|(start, bytes)| {
+            let at = address.checked_sub(*start)? as usize;
+            bytes.get(at..at.checked_add(len)?)
+        })
+    }
+}
+
+fn read_error(what: &str, e: BinaryReaderError) -> DebuggerError {
+    DebuggerError::WasmLoadError(format!("Failed to read {}: {}", what, e))
+}
+
+/// What the analysis knows about a value on the operand stack, in a local
+/// or in linear memory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    Unknown,
+    /// An `i32` or `i64` constant, including `Val`s with an inline body.
+    Const(i64),
+    /// An address `offset` bytes from the stack pointer read by one
+    /// `global.get`, or that address shifted into a `U32Val`.
+    Frame {
+        base: u32,
+        offset: i64,
+    },
+    /// A host vector with these elements.
+    Vec(Vec<Value>),
+    /// A `SymbolObject` built from a data segment.
+    Symbol(String),
+    /// A value of a known `ScVal` kind, such as a typed parameter.
+    Kind(String),
+}
+
+impl Value {
+    fn symbol(&self) -> Option<String> {
+        match self {
+            Value::Const(raw) => decode_symbol_small(*raw as u64),
+            Value::Symbol(name) => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    fn kind(&self) -> Option<String> {
+        match self {
+            Value::Const(raw) => small_val_kind(*raw as u64).map(str::to_string),
+            Value::Vec(_) => Some("vec".to_string()),
+            Value::Symbol(_) => Some("symbol".to_string()),
+            Value::Kind(kind) => Some(kind.clone()),
+            Value::Unknown | Value::Frame { .. } => None,
+        }
+    }
+
+    fn u32_val(&self) -> Option<u32> {
+        match self {
+            Value::Const(raw) if *raw as u64 & 0xff == U32_TAG => Some((*raw as u64 >> 32) as u32),
+            _ => None,
+        }
+    }
+}
+
+/// Kind of a `Val` whose body is held inline, by its tag.
+fn small_val_kind(raw: u64) -> Option<&'static str> {
+    Some(match raw & 0xff {
+        0 | 1 => "bool",
+        2 => "void",
+        3 => "error",
+        U32_TAG => "u32",
+        5 => "i32",
+        6 => "u64",
+        7 => "i64",
+        8 => "timepoint",
+        9 => "duration",
+        10 => "u128",
+        11 => "i128",
+        12 => "u256",
+        13 => "i256",
+        SYMBOL_SMALL_TAG => "symbol",
+        _ => return None,
+    })
+}
+
+/// Words stored in linear memory, keyed by frame and offset.
+type Memory = HashMap<(u32, i64), Value>;
+
+/// A `contract_event` call whose first topic is a known symbol.
+struct EmittedEvent {
+    topic: String,
+    data: Option<String>,
+}
+
+/// A block, loop or `if` being scanned.
+struct Control {
+    /// Operand stack height at entry, below the block's parameters.
+    height: usize,
+    params: usize,
+    results: usize,
+    /// Locals written inside, forgotten when the block ends.
+    writes_locals: Vec<u32>,
+    /// Whether memory may be written inside; if so it is forgotten at the end.
+    writes_memory: bool,
+    /// Locals and memory at the start of an `if`, where its `else` starts over.
+    entry: Option<(Vec<Value>, Memory)>,
+}
+
+/// Abstract run over one function body.
+///
+/// Branches are not followed: whatever a block may change is forgotten at
+/// its end, and after an operator the scan doesn't model, the operands in
+/// the current block read as unknown. Memory is modelled as the 8-byte
+/// words stored at known offsets from a stack pointer.
+struct FunctionScan<'m, 'a> {
+    module: &'m ModuleInfo<'a>,
+    operators: &'m [Operator<'a>],
+    stack: Vec<Value>,
+    locals: Vec<Value>,
+    memory: Memory,
+    controls: Vec<Control>,
+    next_base: u32,
+    emitted: Vec<EmittedEvent>,
+}
+
+impl<'m, 'a> FunctionScan<'m, 'a> {
+    fn new(
+        module: &'m ModuleInfo<'a>,
+        body: &'m FunctionBody<'a>,
+        param_kinds: Option<&Vec<Option<String>>>,
+    ) -> Self {
+        let (params, results) = module.func_type(body.index).unwrap_or((0, 0));
+        let mut locals: Vec<Value> = (0..params)
+            .map(|i| match param_kinds.and_then(|kinds| kinds.get(i)) {
+                Some(Some(kind)) => Value::Kind(kind.clone()),
+                _ => Value::Unknown,
+            })
+            .collect();
+        // Declared locals start out zeroed.
+        locals.resize(params + body.locals, Value::Const(0));
+        Self {
+            module,
+            operators: &body.operators,
+            stack: Vec::new(),
+            locals,
+            memory: HashMap::new(),
+            controls: vec![Control {
+                height: 0,
+                params: 0,
+                results,
+                writes_locals: Vec::new(),
+                writes_memory: false,
+                entry: None,
+            }],
+            next_base: 0,
+            emitted: Vec::new(),
+        }
+    }
+
+    fn run(mut self) -> Vec<EmittedEvent> {
+        for at in 0..self.operators.len() {
+            self.step(at);
+        }
+        self.emitted
+    }
+
+    fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    fn pop(&mut self) -> Value {
+        let floor = self.controls.last().map_or(0, |c| c.height);
+        if self.stack.len() > floor {
+            self.stack.pop().unwrap_or(Value::Unknown)
+        } else {
+            Value::Unknown
+        }
+    }
+
+    fn pop_n(&mut self, n: usize) -> Vec<Value> {
+        let mut values: Vec<Value> = (0..n).map(|_| self.pop()).collect();
+        values.reverse();
+        values
+    }
+
+    /// Drop what is known of the current block's operands, e.g. after a
+    /// branch or an operator the scan doesn't model.
+    fn lose_operands(&mut self) {
+        let floor = self.controls.last().map_or(0, |c| c.height);
+        self.stack.truncate(floor);
+    }
+
+    fn set_local(&mut self, index: u32, value: Value) {
+        if let Some(local) = self.locals.get_mut(index as usize) {
+            *local = value;
+        }
+    }
+
+    fn block_arity(&self, blockty: BlockType) -> (usize, usize) {
+        match blockty {
+            BlockType::Empty => (0, 0),
+            BlockType::Type(_) => (0, 1),
+            BlockType::FuncType(ty) => self
+                .module
+                .types
+                .get(ty as usize)
+                .copied()
+                .unwrap_or((0, 0)),
+        }
+    }
+
+    fn step(&mut self, at: usize) {
+        let operators = self.operators;
+        match &operators[at] {
+            Operator::I32Const { value } => self.push(Value::Const(i64::from(*value))),
+            Operator::I64Const { value } => self.push(Value::Const(*value)),
+            Operator::LocalGet { local_index } => {
+                let value = self
+                    .locals
+                    .get(*local_index as usize)
+                    .cloned()
+                    .unwrap_or(Value::Unknown);
+                self.push(value);
+            }
+            Operator::LocalSet { local_index } => {
+                let value = self.pop();
+                self.set_local(*local_index, value);
+            }
+            Operator::LocalTee { local_index } => {
+                let value = self.pop();
+                self.set_local(*local_index, value.clone());
+                self.push(value);
+            }
+            // Contracts keep their stack pointer in a global, so each read
+            // starts a frame that stores and loads are tracked against.
+            Operator::GlobalGet { .. } => {
+                self.push(Value::Frame {
+                    base: self.next_base,
+                    offset: 0,
+                });
+                self.next_base += 1;
+            }
+            Operator::GlobalSet { .. } | Operator::Drop | Operator::BrIf { .. } => {
+                self.pop();
+            }
+            Operator::Select | Operator::TypedSelect { .. } => {
+                self.pop();
+                let b = self.pop();
+                let a = self.pop();
+                self.push(if a == b { a } else { Value::Unknown });
+            }
+            Operator::Nop => {}
+            Operator::I32Add | Operator::I64Add | Operator::I32Sub | Operator::I64Sub => {
+                let b = self.pop();
+                let a = self.pop();
+                let negate = matches!(operators[at], Operator::I32Sub | Operator::I64Sub);
+                let wide = matches!(operators[at], Operator::I64Add | Operator::I64Sub);
+                let value = match (a, b) {
+                    (Value::Const(a), Value::Const(b)) => {
+                        let sum = if negate {
+                            a.wrapping_sub(b)
+                        } else {
+                            a.wrapping_add(b)
+                        };
+                        Value::Const(if wide { sum } else { i64::from(sum as i32) })
+                    }
+                    (Value::Frame { base, offset }, Value::Const(c)) => Value::Frame {
+                        base,
+                        offset: if negate { offset - c } else { offset + c },
+                    },
+                    (Value::Const(c), Value::Frame { base, offset }) if !negate => Value::Frame {
+                        base,
+                        offset: offset + c,
+                    },
+                    _ => Value::Unknown,
+                };
+                self.push(value);
+            }
+            Operator::I64ExtendI32U | Operator::I32WrapI64 => {
+                let value = match self.pop() {
+                    Value::Const(v) if matches!(operators[at], Operator::I64ExtendI32U) => {
+                        Value::Const(i64::from(v as u32))
+                    }
+                    Value::Const(v) => Value::Const(i64::from(v as i32)),
+                    frame @ Value::Frame { .. } => frame,
+                    _ => Value::Unknown,
+                };
+                self.push(value);
+            }
+            // `U32Val`s are built as `(n << 32) | 4`; a frame address keeps
+            // its identity through that encoding.
+            Operator::I64Shl | Operator::I64Or => {
+                let b = self.pop();
+                let a = self.pop();
+                let shl = matches!(operators[at], Operator::I64Shl);
+                let value = match (a, b) {
+                    (Value::Const(a), Value::Const(b)) if shl => {
+                        Value::Const(a.wrapping_shl((b & 63) as u32))
+                    }
+                    (Value::Const(a), Value::Const(b)) => Value::Const(a | b),
+                    (frame @ Value::Frame { .. }, Value::Const(32)) if shl => frame,
+                    (frame @ Value::Frame { .. }, Value::Const(c))
+                    | (Value::Const(c), frame @ Value::Frame { .. })
+                        if !shl && c as u64 == U32_TAG =>
+                    {
+                        frame
+                    }
+                    _ => Value::Unknown,
+                };
+                self.push(value);
+            }
+            Operator::I64Load { memarg } => {
+                let value = match self.pop() {
+                    Value::Frame { base, offset } => self
+                        .memory
+                        .get(&(base, offset + memarg.offset as i64))
+                        .cloned()
+                        .unwrap_or(Value::Unknown),
+                    Value::Const(address) => self
+                        .module
+                        .read_data((address as u32).wrapping_add(memarg.offset as u32), 8)
+                        .map(|bytes| {
+                            let mut word = [0u8; 8];
+                            word.copy_from_slice(bytes);
+                            Value::Const(i64::from_le_bytes(word))
+                        })
+                        .unwrap_or(Value::Unknown),
+                    _ => Value::Unknown,
+                };
+                self.push(value);
+            }
+            Operator::I32Load { .. }
+            | Operator::I32Load8S { .. }
+            | Operator::I32Load8U { .. }
+            | Operator::I32Load16S { .. }
+            | Operator::I32Load16U { .. }
+            | Operator::I64Load8S { .. }
+            | Operator::I64Load8U { .. }
+            | Operator::I64Load16S { .. }
+            | Operator::I64Load16U { .. }
+            | Operator::I64Load32S { .. }
+            | Operator::I64Load32U { .. }
+            | Operator::MemoryGrow { .. } => {
+                self.pop();
+                self.push(Value::Unknown);
+            }
+            Operator::I64Store { memarg } => {
+                let value = self.pop();
+                let address = self.pop();
+                self.store(address, memarg.offset, 8, value);
+            }
+            Operator::I32Store { memarg }
+            | Operator::I32Store8 { memarg }
+            | Operator::I32Store16 { memarg }
+            | Operator::I64Store8 { memarg }
+            | Operator::I64Store16 { memarg }
+            | Operator::I64Store32 { memarg }
+            | Operator::F32Store { memarg }
+            | Operator::F64Store { memarg } => {
+                self.pop();
+                let address = self.pop();
+                let width = match operators[at] {
+                    Operator::I32Store8 { .. } | Operator::I64Store8 { .. } => 1,
+                    Operator::I32Store16 { .. } | Operator::I64Store16 { .. } => 2,
+                    Operator::F64Store { .. } => 8,
+                    _ => 4,
+                };
+                self.store(address, memarg.offset, width, Value::Unknown);
+            }
+            Operator::MemoryCopy { .. }
+            | Operator::MemoryFill { .. }
+            | Operator::MemoryInit { .. } => {
+                self.pop_n(3);
+                self.memory.clear();
+            }
+            Operator::MemorySize { .. } => self.push(Value::Unknown),
+            Operator::Call { function_index } => self.call(*function_index),
+            Operator::CallIndirect { type_index, .. } => {
+                self.pop();
+                let (params, results) = self
+                    .module
+                    .types
+                    .get(*type_index as usize)
+                    .copied()
+                    .unwrap_or((0, 0));
+                self.pop_n(params);
+                self.memory.clear();
+                for _ in 0..results {
+                    self.push(Value::Unknown);
+                }
+            }
+            Operator::Block { blockty } | Operator::Loop { blockty } | Operator::If { blockty } => {
+                if matches!(operators[at], Operator::If { .. }) {
+                    self.pop();
+                }
+                let (params, results) = self.block_arity(*blockty);
+                let (writes_locals, writes_memory) = block_effects(operators, at, self.module);
+                let entry = matches!(operators[at], Operator::If { .. })
+                    .then(|| (self.locals.clone(), self.memory.clone()));
+                // A loop body may run again with whatever it wrote.
+                if matches!(operators[at], Operator::Loop { .. }) {
+                    self.forget(&writes_locals, writes_memory);
+                }
+                self.controls.push(Control {
+                    height: self.stack.len().saturating_sub(params),
+                    params,
+                    results,
+                    writes_locals,
+                    writes_memory,
+                    entry,
+                });
+            }
+            Operator::Else => {
+                if let Some(control) = self.controls.last() {
+                    let (height, params) = (control.height, control.params);
+                    if let Some((locals, memory)) = control.entry.clone() {
+                        self.locals = locals;
+                        self.memory = memory;
+                    }
+                    self.stack.truncate(height);
+                    for _ in 0..params {
+                        self.push(Value::Unknown);
+                    }
+                }
+            }
+            Operator::End => {
+                if let Some(control) = self.controls.pop() {
+                    self.stack.truncate(control.height);
+                    self.forget(&control.writes_locals, control.writes_memory);
+                    for _ in 0..control.results {
+                        self.push(Value::Unknown);
+                    }
+                }
+            }
+            Operator::Br { .. }
+            | Operator::BrTable { .. }
+            | Operator::Return
+            | Operator::Unreachable => self.lose_operands(),
+            Operator::I32Eqz
+            | Operator::I64Eqz
+            | Operator::I32Clz
+            | Operator::I32Ctz
+            | Operator::I32Popcnt
+            | Operator::I64Clz
+            | Operator::I64Ctz
+            | Operator::I64Popcnt
+            | Operator::I64ExtendI32S
+            | Operator::I32Extend8S
+            | Operator::I32Extend16S
+            | Operator::I64Extend8S
+            | Operator::I64Extend16S
+            | Operator::I64Extend32S => {
+                self.pop();
+                self.push(Value::Unknown);
+            }
+            Operator::I32Eq
+            | Operator::I32Ne
+            | Operator::I32LtS
+            | Operator::I32LtU
+            | Operator::I32GtS
+            | Operator::I32GtU
+            | Operator::I32LeS
+            | Operator::I32LeU
+            | Operator::I32GeS
+            | Operator::I32GeU
+            | Operator::I64Eq
+            | Operator::I64Ne
+            | Operator::I64LtS
+            | Operator::I64LtU
+            | Operator::I64GtS
+            | Operator::I64GtU
+            | Operator::I64LeS
+            | Operator::I64LeU
+            | Operator::I64GeS
+            | Operator::I64GeU
+            | Operator::I32Mul
+            | Operator::I32DivS
+            | Operator::I32DivU
+            | Operator::I32RemS
+            | Operator::I32RemU
+            | Operator::I32And
+            | Operator::I32Or
+            | Operator::I32Xor
+            | Operator::I32Shl
+            | Operator::I32ShrS
+            | Operator::I32ShrU
+            | Operator::I32Rotl
+            | Operator::I32Rotr
+            | Operator::I64Mul
+            | Operator::I64DivS
+            | Operator::I64DivU
+            | Operator::I64RemS
+            | Operator::I64RemU
+            | Operator::I64And
+            | Operator::I64Xor
+            | Operator::I64ShrS
+            | Operator::I64ShrU
+            | Operator::I64Rotl
+            | Operator::I64Rotr => {
+                self.pop_n(2);
+                self.push(Value::Unknown);
+            }
+            // Floats, SIMD and threads are rejected by the Soroban host, so
+            // anything left writes neither locals nor memory.
+            _ => self.lose_operands(),
+        }
+    }
+
+    fn store(&mut self, address: Value, offset: u64, width: i64, value: Value) {
+        let Value::Frame {
+            base,
+            offset: start,
+        } = address
+        else {
+            self.memory.clear();
+            return;
+        };
+        let start = start + offset as i64;
+        self.memory
+            .retain(|&(b, at), _| b != base || at + 8 <= start || at >= start + width);
+        if width == 8 && value != Value::Unknown {
+            self.memory.insert((base, start), value);
+        }
+    }
+
+    fn forget(&mut self, locals: &[u32], memory: bool) {
+        for &index in locals {
+            self.set_local(index, Value::Unknown);
+        }
+        if memory {
+            self.memory.clear();
+        }
+    }
+
+    fn call(&mut self, function_index: u32) {
+        let Some((params, results)) = self.module.func_type(function_index) else {
+            self.lose_operands();
+            self.memory.clear();
+            return;
+        };
+        let args = self.pop_n(params);
+        let arg = |i: usize| args.get(i).cloned().unwrap_or(Value::Unknown);
+        let result = match self.module.imports.get(function_index as usize) {
+            Some(HostFn::ContractEvent) => {
+                let topic = match arg(0) {
+                    Value::Vec(items) => items.first().and_then(Value::symbol),
+                    _ => None,
+                };
+                if let Some(topic) = topic {
+                    self.emitted.push(EmittedEvent {
+                        topic,
+                        data: arg(1).kind(),
+                    });
+                }
+                Value::Const(VOID)
+            }
+            Some(HostFn::VecNew) => Value::Vec(Vec::new()),
+            Some(HostFn::VecPushBack) => match arg(0) {
+                Value::Vec(mut items) => {
+                    items.push(arg(1));
+                    Value::Vec(items)
+                }
+                _ => Value::Kind("vec".to_string()),
+            },
+            Some(HostFn::VecNewFromLinearMemory) => self.vec_from_memory(&arg(0), &arg(1)),
+            Some(HostFn::SymbolNewFromLinearMemory) => arg(0)
+                .u32_val()
+                .zip(arg(1).u32_val())
+                .and_then(|(at, len)| self.module.read_data(at, len as usize))
+                .and_then(|bytes| std::str::from_utf8(bytes).ok())
+                .map(|name| Value::Symbol(name.to_string()))
+                .unwrap_or_else(|| Value::Kind("symbol".to_string())),
+            Some(HostFn::Object(kind)) => Value::Kind(kind.to_string()),
+            Some(HostFn::Other) | None => {
+                self.memory.clear();
+                Value::Unknown
+            }
+        };
+        if results == 1 {
+            self.push(result);
+        } else {
+            for _ in 0..results {
+                self.push(Value::Unknown);
+            }
+        }
+    }
+
+    /// The vector `vec_new_from_linear_memory(pos, len)` builds, from the
+    /// words stored in the frame at `pos` or a data segment.
+    fn vec_from_memory(&self, pos: &Value, len: &Value) -> Value {
+        let Some(len) = len.u32_val().filter(|len| *len <= 64) else {
+            return Value::Kind("vec".to_string());
+        };
+        let items = (0..i64::from(len))
+            .map(|i| match pos {
+                Value::Frame { base, offset } => self
+                    .memory
+                    .get(&(*base, offset + 8 * i))
+                    .cloned()
+                    .unwrap_or(Value::Unknown),
+                _ => pos
+                    .u32_val()
+                    .and_then(|at| self.module.read_data(at.wrapping_add(8 * i as u32), 8))
+                    .map(|bytes| {
+                        let mut word = [0u8; 8];
+                        word.copy_from_slice(bytes);
+                        Value::Const(i64::from_le_bytes(word))
+                    })
+                    .unwrap_or(Value::Unknown),
+            })
+            .collect();
+        Value::Vec(items)
+    }
+}
+
+/// Locals written, and whether memory may be written, inside the block
+/// opened at `ops[start]` up to its `end`.
+fn block_effects(ops: &[Operator], start: usize, module: &ModuleInfo) -> (Vec<u32>, bool) {
+    let mut depth = 0usize;
+    let mut locals = Vec::new();
+    let mut memory = false;
+    for op in &ops[start + 1..] {
+        match op {
+            Operator::Block { .. } | Operator::Loop { .. } | Operator::If { .. } => depth += 1,
+            Operator::End if depth == 0 => break,
+            Operator::End => depth -= 1,
+            Operator::LocalSet { local_index } | Operator::LocalTee { local_index } => {
+                locals.push(*local_index)
+            }
+            Operator::Call { function_index } => {
+                memory |= !matches!(
+                    module.imports.get(*function_index as usize),
+                    Some(h) if *h != HostFn::Other
+                )
+            }
+            Operator::CallIndirect { .. }
+            | Operator::I32Store { .. }
+            | Operator::I64Store { .. }
+            | Operator::F32Store { .. }
+            | Operator::F64Store { .. }
+            | Operator::I32Store8 { .. }
+            | Operator::I32Store16 { .. }
+            | Operator::I64Store8 { .. }
+            | Operator::I64Store16 { .. }
+            | Operator::I64Store32 { .. }
+            | Operator::MemoryCopy { .. }
+            | Operator::MemoryFill { .. }
+            | Operator::MemoryInit { .. } => memory = true,
+            _ => {}
+        }
+    }
+    (locals, memory)
+}
+
+/// Decode a raw `Val` holding a `SymbolSmall` into its string form.
+fn decode_symbol_small(raw: u64) -> Option<String> {
+    if raw & 0xff != SYMBOL_SMALL_TAG {
+        return None;
+    }
+    let mut body = raw >> 8;
+    if body == 0 {
+        return None;
+    }
+    let mut chars = Vec::new();
+    while body != 0 {
+        let c = match (body & 0x3f) as u8 {
+            1 => '_',
+            n @ 2..=11 => (b'0' + n - 2) as char,
+            n @ 12..=37 => (b'A' + n - 12) as char,
+            n @ 38..=63 => (b'a' + n - 38) as char,
+            _ => return None,
+        };
+        chars.push(c);
+        body >>= 6;
+    }
+    chars.reverse();
+    Some(chars.into_iter().collect())
+}
+
+/// Extract `name` from a rendered `Symbol(ScSymbol(StringM(name)))` topic.
+fn symbol_topic_name(rendered: &str) -> Option<&str> {
+    rendered
+        .strip_prefix("Symbol(ScSymbol(StringM(")?
+        .strip_suffix(")))")
+}
+
+/// Lowercased `ScVal` variant name of a rendered value, e.g. `i128`.
+fn data_kind(rendered: &str) -> String {
+    rendered
+        .split(['(', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode_symbol_small(name: &str) -> i64 {
+        let mut body = 0u64;
+        for c in name.chars() {
+            let code = match c {
+                '_' => 1,
+                '0'..='9' => 2 + (c as u64 - '0' as u64),
+                'A'..='Z' => 12 + (c as u64 - 'A' as u64),
+                'a'..='z' => 38 + (c as u64 - 'a' as u64),
+                _ => panic!("invalid symbol char"),
+            };
+            body = (body << 6) | code;
+        }
+        ((body << 8) | SYMBOL_SMALL_TAG) as i64
+    }
+
+    /// Schema of the `emitter` fixture: `transfer` and `relay` publish a
+    /// `transfer` event with `i128` data, `set_price` a `price_updated` event
+    /// whose topic is a `SymbolObject`.
+    fn emitter_schema() -> EventSchema {
+        let wasm = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wasm/emitter.wasm");
+        extract_event_schema(&std::fs::read(wasm).unwrap()).unwrap()
+    }
+
+    fn symbol_event(topic: &str, data: &str) -> ContractEvent {
+        ContractEvent {
+            contract_id: None,
+            contract: None,
+            topics: vec![format!("Symbol(ScSymbol(StringM({})))", topic)],
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn topic_signatures_are_canonical_and_skip_diagnostics() {
+        let transfer = |to: &str, data: &str| ContractEvent {
+            contract_id: Some("C1".to_string()),
+            contract: None,
+            topics: vec![
+                "Symbol(ScSymbol(StringM(transfer)))".to_string(),
+                "Address(Contract(Hash(aa)))".to_string(),
+                to.to_string(),
+            ],
+            data: data.to_string(),
+        };
+        let events = [
+            transfer("Address(Account(AccountId(bb)))", "I128(5)"),
+            transfer("Address(Contract(Hash(cc)))", "I128(9)"),
+            symbol_event("init", "Void"),
+            symbol_event("fn_call", "Void"),
+            ContractEvent {
+                contract_id: None,
+                contract: None,
+                topics: vec!["String(StringM(untyped))".to_string()],
+                data: "Void".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            topic_signatures(&events),
+            ["init", "transfer(address, address)"]
+        );
+    }
+
+    #[test]
+    fn decodes_symbol_small_constants() {
+        let raw = encode_symbol_small("mint_2") as u64;
+        assert_eq!(decode_symbol_small(raw).as_deref(), Some("mint_2"));
+        assert_eq!(decode_symbol_small(2), None);
+    }
+
+    #[test]
+    fn extracts_first_topics_and_data_kinds_of_published_events() {
+        let shape = |topic: &str, emitted_by: &[&str], data: &str| EventShape {
+            topic: topic.to_string(),
+            emitted_by: emitted_by.iter().map(|f| f.to_string()).collect(),
+            data: Some(vec![data.to_string()]),
+            source: EventShapeSource::StaticAnalysis,
+        };
+
+        // `transfer` also uses the symbol `total` as a storage key, and the
+        // second event of `relay` takes its topic from an argument; neither
+        // is a known topic.
+        assert_eq!(
+            emitter_schema().events,
+            vec![
+                shape("price_updated", &["set_price"], "u32"),
+                shape("transfer", &["relay", "transfer"], "i128"),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_flags_off_schema_event_and_suggests_topic() {
+        let events = vec![
+            symbol_event("transfer", "I128(Int128Parts { hi: 0, lo: 5 })"),
+            symbol_event("transfr", "I128(Int128Parts { hi: 0, lo: 5 })"),
+        ];
+
+        let violations = emitter_schema().validate(&events);
+        assert_eq!(
+            violations,
+            vec![EventSchemaViolation::UnknownTopic {
+                index: 1,
+                topic: "transfr".to_string(),
+                suggestion: Some("transfer".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn validate_checks_data_kind_when_schema_pins_it() {
+        let schema = EventSchema {
+            events: vec![EventShape {
+                topic: "mint".to_string(),
+                emitted_by: vec![],
+                data: Some(vec!["i128".to_string()]),
+                source: EventShapeSource::UserSchema,
+            }],
+        };
+        let events = vec![
+            symbol_event("mint", "I128(Int128Parts { hi: 0, lo: 5 })"),
+            symbol_event("mint", "U32(5)"),
+        ];
+
+        let violations = schema.validate(&events);
+        assert_eq!(violations.len(), 1);
+        assert!(violations[0].to_string().contains("data is u32"));
+    }
+}
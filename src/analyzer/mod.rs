@@ -1,4 +1,7 @@
+pub mod conventions;
+pub mod event_schema;
 pub mod graph;
 pub mod security;
+pub mod smoke;
 pub mod symbolic;
 pub mod upgrade;
@@ -1507,10 +1507,10 @@ fn analyze_reentrancy_pattern_dynamic(trace: &[DynamicTraceEvent]) -> Vec<Securi
 
 fn find_writes_seen_by_frame(writes_seen_by_frame: &HashMap<FrameKey, usize>, frame: &FrameKey) -> usize {
     if let Some(count) = writes_seen_by_frame.get(frame) {
-        return count;
+        return *count;
     }
 
-    if let Some(_) = frame.call_depth {
+    if frame.call_depth.is_some() {
         writes_seen_by_frame
             .iter()
             .filter(|(key, _)| key.matches(frame))
@@ -0,0 +1,411 @@
+//! Invoke every function in a contract's spec with generated arguments.
+//!
+//! Backs `run --smoke` (one call per function with the simplest arguments)
+//! and `symbolic --all-functions` (up to N generated inputs per function).
+//! Each call runs on a fresh executor with all authorizations mocked, so a
+//! function is judged on its own rather than on what ran before it.
+
+use crate::analyzer::symbolic::{SymbolicAnalyzer, SymbolicConfig};
+use crate::runtime::executor::ContractExecutor;
+use crate::runtime::policy::FunctionPolicy;
+use crate::utils::wasm::{parse_function_signatures, ContractFunctionSignature};
+use crate::{DebuggerError, Result};
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Outcome of calling one function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmokeStatus {
+    /// Every call returned.
+    Pass,
+    /// The contract panicked, returned an error or aborted.
+    Trap,
+    /// The debugger could not make the call (e.g. arguments were rejected).
+    Error,
+    /// The function was not called.
+    Skipped,
+}
+
+impl SmokeStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            SmokeStatus::Pass => "pass",
+            SmokeStatus::Trap => "trap",
+            SmokeStatus::Error => "error",
+            SmokeStatus::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SmokeCase {
+    pub function: String,
+    pub status: SmokeStatus,
+    /// Calls made; `0` when skipped.
+    pub runs: usize,
+    /// Arguments of the first failing call, or of the only call.
+    pub args: Option<String>,
+    /// Failure message or skip reason.
+    pub detail: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SmokeReport {
+    pub cases: Vec<SmokeCase>,
+}
+
+impl SmokeReport {
+    pub fn count(&self, status: SmokeStatus) -> usize {
+        self.cases
+            .iter()
+            .filter(|case| case.status == status)
+            .count()
+    }
+
+    /// Whether any function trapped or could not be called.
+    pub fn has_failures(&self) -> bool {
+        self.count(SmokeStatus::Trap) + self.count(SmokeStatus::Error) > 0
+    }
+
+    /// Plain-text table with one row per function.
+    pub fn render(&self) -> String {
+        let name_width = self
+            .cases
+            .iter()
+            .map(|case| case.function.len())
+            .chain(std::iter::once("Function".len()))
+            .max()
+            .unwrap_or(0);
+        let mut out = format!(
+            "{:<name_width$}  {:<7}  {:>4}  {}\n",
+            "Function", "Status", "Runs", "Detail"
+        );
+        out.push_str(&"-".repeat(name_width + 27));
+        out.push('\n');
+        for case in &self.cases {
+            out.push_str(&format!(
+                "{:<name_width$}  {:<7}  {:>4}  {}\n",
+                case.function,
+                case.status.label(),
+                case.runs,
+                case.detail
+                    .as_deref()
+                    .unwrap_or("")
+                    .lines()
+                    .next()
+                    .unwrap_or("")
+            ));
+        }
+        out.push_str(&format!(
+            "{} passed, {} trapped, {} errors, {} skipped",
+            self.count(SmokeStatus::Pass),
+            self.count(SmokeStatus::Trap),
+            self.count(SmokeStatus::Error),
+            self.count(SmokeStatus::Skipped)
+        ));
+        out
+    }
+
+    /// JUnit XML with one test case per function, for CI test reports.
+    pub fn to_junit_xml(&self, suite: &str) -> String {
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        let _ = writeln!(
+            xml,
+            "<testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" skipped=\"{}\">",
+            xml_escape(suite),
+            self.cases.len(),
+            self.count(SmokeStatus::Trap),
+            self.count(SmokeStatus::Error),
+            self.count(SmokeStatus::Skipped)
+        );
+        for case in &self.cases {
+            let _ = write!(
+                xml,
+                "  <testcase classname=\"{}\" name=\"{}\"",
+                xml_escape(suite),
+                xml_escape(&case.function)
+            );
+            let detail = xml_escape(case.detail.as_deref().unwrap_or(""));
+            let args = xml_escape(case.args.as_deref().unwrap_or(""));
+            match case.status {
+                SmokeStatus::Pass => xml.push_str("/>\n"),
+                SmokeStatus::Trap => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n    <failure message=\"{}\">args: {}</failure>\n  </testcase>",
+                        detail, args
+                    );
+                }
+                SmokeStatus::Error => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n    <error message=\"{}\">args: {}</error>\n  </testcase>",
+                        detail, args
+                    );
+                }
+                SmokeStatus::Skipped => {
+                    let _ = writeln!(
+                        xml,
+                        ">\n    <skipped message=\"{}\"/>\n  </testcase>",
+                        detail
+                    );
+                }
+            }
+        }
+        xml.push_str("</testsuite>\n");
+        xml
+    }
+
+    pub fn write_junit(&self, path: &std::path::Path, suite: &str) -> Result<()> {
+        std::fs::write(path, self.to_junit_xml(suite)).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to write JUnit report {:?}: {}", path, e))
+                .into()
+        })
+    }
+}
+
+/// Calls every spec function `runs` times with generated arguments.
+pub struct SmokeRunner {
+    runs: usize,
+    config: SymbolicConfig,
+    policy: FunctionPolicy,
+    storage: Option<String>,
+}
+
+impl SmokeRunner {
+    /// Up to `runs` calls per function, with inputs generated under `config`.
+    ///
+    /// The first input uses the simplest value of each type, so `runs == 1`
+    /// is a smoke test. Functions blocked by the `[policy]` config are skipped.
+    pub fn new(runs: usize, config: SymbolicConfig) -> Self {
+        Self {
+            runs: runs.max(1),
+            config,
+            policy: crate::runtime::policy::function_policy(),
+            storage: None,
+        }
+    }
+
+    /// Storage seeded into every executor before the call.
+    pub fn with_storage(mut self, storage: Option<String>) -> Self {
+        self.storage = storage;
+        self
+    }
+
+    pub fn run(&self, wasm: &[u8]) -> Result<SmokeReport> {
+        let signatures = parse_function_signatures(wasm)?;
+        if signatures.is_empty() {
+            return Err(DebuggerError::WasmLoadError(
+                "Contract has no contractspecv0 section; cannot enumerate its functions"
+                    .to_string(),
+            )
+            .into());
+        }
+        let cases = signatures
+            .iter()
+            .map(|sig| self.run_function(wasm, sig))
+            .collect();
+        Ok(SmokeReport { cases })
+    }
+
+    fn run_function(&self, wasm: &[u8], sig: &ContractFunctionSignature) -> SmokeCase {
+        let skipped = |reason: String| SmokeCase {
+            function: sig.name.clone(),
+            status: SmokeStatus::Skipped,
+            runs: 0,
+            args: None,
+            detail: Some(reason),
+        };
+        if let Err(e) = self.policy.check(&sig.name) {
+            return skipped(e.to_string());
+        }
+        if let Some(param) = sig
+            .params
+            .iter()
+            .find(|param| !is_generatable(&param.type_name))
+        {
+            return skipped(format!(
+                "cannot generate `{}: {}` (no spec information for custom types)",
+                param.name, param.type_name
+            ));
+        }
+
+        let inputs = SymbolicAnalyzer::new().input_combinations(sig, &self.config);
+        let mut runs = 0;
+        for args in inputs.iter().take(self.runs) {
+            runs += 1;
+            if let Err((status, detail)) = self.call(wasm, &sig.name, args) {
+                return SmokeCase {
+                    function: sig.name.clone(),
+                    status,
+                    runs,
+                    args: Some(args.clone()),
+                    detail: Some(detail),
+                };
+            }
+        }
+        SmokeCase {
+            function: sig.name.clone(),
+            status: SmokeStatus::Pass,
+            runs,
+            args: (runs == 1).then(|| inputs[0].clone()),
+            detail: None,
+        }
+    }
+
+    fn call(
+        &self,
+        wasm: &[u8],
+        function: &str,
+        args: &str,
+    ) -> std::result::Result<(), (SmokeStatus, String)> {
+        let outcome = std::panic::catch_unwind(|| -> Result<String> {
+            let mut executor = ContractExecutor::new(wasm.to_vec())?;
+            executor.set_timeout(self.config.timeout_secs);
+            executor.enable_mock_all_auths();
+            if let Some(storage) = &self.storage {
+                executor.set_initial_storage(storage.clone())?;
+            }
+            executor.execute(function, Some(args))
+        });
+        match outcome {
+            Ok(Ok(_)) => Ok(()),
+            Ok(Err(e)) => {
+                let status = match e.downcast_ref::<DebuggerError>() {
+                    Some(DebuggerError::ExecutionError(_)) => SmokeStatus::Trap,
+                    _ => SmokeStatus::Error,
+                };
+                Err((status, e.to_string()))
+            }
+            Err(_) => Err((SmokeStatus::Trap, "Host panic".to_string())),
+        }
+    }
+}
+
+/// Whether the symbolic generator can produce a value of this spec type.
+fn is_generatable(type_name: &str) -> bool {
+    const SCALARS: &[&str] = &[
+        "Val",
+        "Bool",
+        "Void",
+        "U32",
+        "I32",
+        "U64",
+        "I64",
+        "Timepoint",
+        "Duration",
+        "U128",
+        "I128",
+        "U256",
+        "I256",
+        "Bytes",
+        "String",
+        "Symbol",
+        "Address",
+    ];
+    if SCALARS.contains(&type_name) {
+        return true;
+    }
+    if type_name.starts_with("BytesN<") {
+        return true;
+    }
+    for prefix in ["Option<", "Vec<", "Map<", "Tuple<"] {
+        if let Some(inner) = type_name
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix('>'))
+        {
+            return split_type_args(inner).into_iter().all(is_generatable);
+        }
+    }
+    false
+}
+
+/// Split `A, Map<B, C>` into `["A", "Map<B, C>"]`.
+fn split_type_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0usize);
+    for (i, c) in args.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(args[start..].trim());
+    parts
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn case(function: &str, status: SmokeStatus, detail: Option<&str>) -> SmokeCase {
+        SmokeCase {
+            function: function.to_string(),
+            status,
+            runs: usize::from(status != SmokeStatus::Skipped),
+            args: Some("[]".to_string()),
+            detail: detail.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn custom_types_are_not_generatable() {
+        assert!(is_generatable("U32"));
+        assert!(is_generatable("BytesN<32>"));
+        assert!(is_generatable("Map<Symbol, Vec<Address>>"));
+        assert!(is_generatable("Tuple<I128, Option<String>>"));
+        assert!(!is_generatable("DataKey"));
+        assert!(!is_generatable("Vec<TokenMetadata>"));
+        assert!(!is_generatable("Map<Address, Allowance>"));
+    }
+
+    #[test]
+    fn junit_has_one_case_per_function() {
+        let report = SmokeReport {
+            cases: vec![
+                case("get", SmokeStatus::Pass, None),
+                case("burn", SmokeStatus::Trap, Some("error code: 3")),
+                case(
+                    "init",
+                    SmokeStatus::Skipped,
+                    Some("cannot generate `key: <DataKey>`"),
+                ),
+            ],
+        };
+        let xml = report.to_junit_xml("token.wasm");
+        assert!(xml.contains(
+            "<testsuite name=\"token.wasm\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"1\">"
+        ));
+        assert!(xml.contains("<testcase classname=\"token.wasm\" name=\"get\"/>"));
+        assert!(xml.contains("<failure message=\"error code: 3\">args: []</failure>"));
+        assert!(xml.contains("<skipped message=\"cannot generate `key: &lt;DataKey&gt;`\"/>"));
+        assert!(report.has_failures());
+    }
+
+    #[test]
+    fn table_summarises_statuses() {
+        let report = SmokeReport {
+            cases: vec![
+                case("increment", SmokeStatus::Pass, None),
+                case("init", SmokeStatus::Skipped, Some("blocked")),
+            ],
+        };
+        let table = report.render();
+        assert!(table.contains("increment  pass"), "{}", table);
+        assert!(table.ends_with("1 passed, 0 trapped, 0 errors, 1 skipped"));
+        assert!(!report.has_failures());
+    }
+}
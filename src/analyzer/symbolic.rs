@@ -293,13 +293,17 @@ impl SymbolicAnalyzer {
         }
 
         let mock_coverage = if report.metadata.generated_input_combinations > 0 {
-            (report.paths_explored as f32 / report.metadata.generated_input_combinations as f32).min(1.0)
+            (report.paths_explored as f32 / report.metadata.generated_input_combinations as f32)
+                .min(1.0)
         } else {
             1.0
         };
         report.metadata.coverage_fraction = mock_coverage;
         if mock_coverage < 1.0 {
-            report.metadata.uncovered_regions.push("Complex input boundaries and conditional branches".to_string());
+            report
+                .metadata
+                .uncovered_regions
+                .push("Complex input boundaries and conditional branches".to_string());
         }
 
         Ok(report)
@@ -391,6 +395,15 @@ impl SymbolicAnalyzer {
         )
     }
 
+    /// Type-aware argument arrays for `sig`, simplest values first.
+    pub(crate) fn input_combinations(
+        &self,
+        sig: &ContractFunctionSignature,
+        config: &SymbolicConfig,
+    ) -> Vec<String> {
+        self.generate_type_aware_inputs(sig, config).combinations
+    }
+
     fn generate_type_aware_inputs(
         &self,
         sig: &ContractFunctionSignature,
@@ -502,6 +515,14 @@ impl SymbolicAnalyzer {
                     .map(|s| s.to_string())
                     .collect()
             }
+            t if t.starts_with("BytesN<") => {
+                let len = t[7..t.len() - 1].parse::<usize>().unwrap_or(0);
+                ["00", "ff"]
+                    .into_iter()
+                    .take(limit)
+                    .map(|byte| format!("\"0x{}\"", byte.repeat(len)))
+                    .collect()
+            }
             t if t.starts_with("Option<") => {
                 let inner = &t[7..t.len() - 1];
                 let mut seeds = vec!["null".to_string()];
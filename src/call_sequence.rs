@@ -0,0 +1,153 @@
+//! Ordered contract calls for `run --call` and `run --calls-file`.
+//!
+//! Every call runs against the same environment and contract instance, so
+//! storage written by one call is visible to the next. The last call is the
+//! run's main call: `--expect` and the budget report refer to it, while the
+//! storage diff covers the whole sequence.
+
+use crate::inspector::events::ContractEvent;
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// One call of a sequence.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallStep {
+    pub function: String,
+    /// Arguments as a JSON array, in `--args` syntax.
+    pub args: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum CallStepInput {
+    Structured {
+        function: String,
+        #[serde(default)]
+        args: Option<Value>,
+    },
+    Function(String),
+}
+
+impl CallStep {
+    /// Parse a `--call` value: `FUNCTION` or `FUNCTION:ARGS_JSON`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (function, args) = match spec.split_once(':') {
+            Some((function, args)) => (function.trim(), Some(args.trim())),
+            None => (spec.trim(), None),
+        };
+        if function.is_empty() {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "--call '{}' names no function; use FUNCTION or FUNCTION:ARGS_JSON",
+                spec
+            ))
+            .into());
+        }
+        Ok(Self {
+            function: function.to_string(),
+            args: args.filter(|args| !args.is_empty()).map(str::to_string),
+        })
+    }
+
+    /// Load a JSON array of calls, each a function name or
+    /// `{"function": "...", "args": [...]}`.
+    pub fn load_file(path: &Path) -> Result<Vec<Self>> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read calls file {:?}: {}", path, e))
+        })?;
+        let inputs: Vec<CallStepInput> = serde_json::from_str(&content).map_err(|e| {
+            DebuggerError::InvalidArguments(format!(
+                "Calls file {:?} must be a JSON array of function names or {{\"function\", \"args\"}} objects: {}",
+                path, e
+            ))
+        })?;
+        if inputs.is_empty() {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "Calls file {:?} lists no calls",
+                path
+            ))
+            .into());
+        }
+        Ok(inputs
+            .into_iter()
+            .map(|input| match input {
+                CallStepInput::Structured { function, args } => Self {
+                    function,
+                    args: args.map(|args| match args {
+                        Value::String(raw) => raw,
+                        other => other.to_string(),
+                    }),
+                },
+                CallStepInput::Function(function) => Self {
+                    function,
+                    args: None,
+                },
+            })
+            .collect())
+    }
+}
+
+/// What one call of a sequence returned.
+#[derive(Debug, Clone, Serialize)]
+pub struct CallOutcome {
+    /// Position in the sequence, from 1.
+    pub index: usize,
+    pub function: String,
+    pub args: Option<String>,
+    pub result: String,
+    pub return_value: Value,
+    /// Events the call emitted, after `--event-filter`.
+    pub events: Vec<ContractEvent>,
+}
+
+/// The error for call `index` of `total` failing.
+pub fn call_failed(
+    index: usize,
+    total: usize,
+    function: &str,
+    error: miette::Report,
+) -> miette::Report {
+    DebuggerError::ExecutionError(format!(
+        "Call {}/{} ({}) failed; the calls after it were not run: {}",
+        index, total, function, error
+    ))
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_call_specs() {
+        assert_eq!(
+            CallStep::parse("deposit:[100, 2]").unwrap(),
+            CallStep {
+                function: "deposit".to_string(),
+                args: Some("[100, 2]".to_string()),
+            }
+        );
+        assert_eq!(CallStep::parse("initialize").unwrap().args, None);
+        assert!(CallStep::parse(":[1]").is_err());
+    }
+
+    #[test]
+    fn loads_names_and_objects_from_a_calls_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("calls.json");
+        std::fs::write(
+            &path,
+            r#"["initialize", {"function": "deposit", "args": [100]}, {"function": "get"}]"#,
+        )
+        .unwrap();
+        let calls = CallStep::load_file(&path).unwrap();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[0].function, "initialize");
+        assert_eq!(calls[1].args.as_deref(), Some("[100]"));
+        assert_eq!(calls[2].args, None);
+
+        std::fs::write(&path, "[]").unwrap();
+        assert!(CallStep::load_file(&path).is_err());
+    }
+}
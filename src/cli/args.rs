@@ -2,6 +2,7 @@ use crate::config::Config;
 use clap::{Parser, Subcommand, ValueEnum};
 
 use clap_complete::Shell;
+use std::collections::BTreeSet;
 use std::path::PathBuf;
 
 /// Mapping of deprecated CLI flags to their new equivalents
@@ -41,6 +42,76 @@ pub enum OutputFormat {
     Json,
 }
 
+/// How failed expectations affect the exit status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum ExpectMode {
+    /// Failed expectations fail the command
+    #[default]
+    Strict,
+    /// Failed expectations are reported but the command still succeeds
+    Warn,
+}
+
+impl ExpectMode {
+    /// The name used on the command line and in scenario files.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ExpectMode::Strict => "strict",
+            ExpectMode::Warn => "warn",
+        }
+    }
+}
+
+/// A section `run` collects and prints, selected with `--show`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum)]
+pub enum ShowItem {
+    /// Contract events emitted during execution
+    Events,
+    /// Authorization tree
+    Auth,
+    /// Ledger entries accessed during execution
+    Ledger,
+    /// Contract calls made through the host, from diagnostic events
+    HostCalls,
+    /// Messages contracts logged with `log!`, from diagnostic events
+    Logs,
+    /// Every diagnostic event the host recorded, also printed when the call fails
+    Diagnostics,
+    /// Storage entries the call added, changed or removed
+    StorageDiff,
+    /// Every section above
+    All,
+}
+
+impl ShowItem {
+    /// What `--show` resolves to when neither it nor `output.show` is set.
+    pub const DEFAULT: &'static [ShowItem] = &[ShowItem::StorageDiff];
+
+    /// The name used on the command line and in the config file.
+    pub fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// Storage accesses `--full-debug` records in the trace unless `--trace-limit` is given.
+pub const FULL_DEBUG_TRACE_LIMIT: usize = 10_000;
+
+/// Most `--repeat` iterations `--full-debug` allows, since every diagnostic of
+/// every iteration is collected.
+pub const FULL_DEBUG_MAX_REPEAT: u32 = 3;
+
+/// On-disk format for network snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum SnapshotFormat {
+    /// Checksummed container with a descriptive header (compressed with the `zstd` feature)
+    #[default]
+    V2,
+    /// Plain pretty-printed JSON, readable by older debugger versions
+    V1Json,
+}
+
 /// Format for dependency graph output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
 pub enum GraphFormat {
@@ -48,6 +119,29 @@ pub enum GraphFormat {
     Mermaid,
 }
 
+/// Order of the `run` storage listing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum StorageOrder {
+    /// By storage, then key
+    #[default]
+    Key,
+    /// Largest first
+    Size,
+    /// Soonest live-until ledger first
+    Ttl,
+    /// Entries the run wrote first
+    Modified,
+}
+
+/// Storage a contract data key lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
+pub enum KeyDurability {
+    Instance,
+    #[default]
+    Persistent,
+    Temporary,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum SymbolicProfile {
     Fast,
@@ -95,6 +189,25 @@ pub struct Cli {
     )]
     pub history_file: Option<PathBuf>,
 
+    /// Ignore the `[policy]` section of the config file
+    ///
+    /// Lets functions matched by `deny_functions`, or missing from
+    /// `allow_functions`, be called anyway.
+    #[arg(long, global = true)]
+    pub no_policy: bool,
+
+    /// Nesting depth at which rendered values are truncated (default: 1000)
+    ///
+    /// Deeper parts of a value are replaced with a `<truncated: ...>` marker.
+    #[arg(
+        long,
+        global = true,
+        env = "SOROBAN_DEBUG_MAX_VALUE_DEPTH",
+        value_name = "DEPTH",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub max_value_depth: Option<u32>,
+
     /// Show historical budget trend visualization
     #[arg(long)]
     pub budget_trend: bool,
@@ -185,6 +298,9 @@ pub enum Commands {
     /// Connect to remote debug server
     Remote(RemoteArgs),
 
+    /// Observe a running server session read-only
+    Attach(AttachArgs),
+
     /// Analyze contract for security vulnerabilities
     Analyze(AnalyzeArgs),
 
@@ -194,6 +310,30 @@ pub enum Commands {
     /// Prune or compact run history according to a retention policy
     HistoryPrune(HistoryPruneArgs),
 
+    /// Maintain the run history file
+    History(HistoryArgs),
+
+    /// Inspect or clear the on-disk contract artifact cache
+    Cache(CacheArgs),
+
+    /// Check `.soroban-debug.toml` files for unknown keys and invalid values
+    Config(ConfigArgs),
+
+    /// Show or convert network snapshot files
+    Snapshot(SnapshotArgs),
+
+    /// Create, list and delete named environments used with `--env`
+    Env(EnvArgs),
+
+    /// Replay, list, show and delete invocations saved with `run --save-preset`
+    Preset(PresetArgs),
+
+    /// Build the base64 LedgerKey XDR of a contract storage entry, or decode one
+    Key(KeyArgs),
+
+    /// Import an on-chain transaction's contract invocation and replay it locally
+    ImportTx(ImportTxArgs),
+
     /// Plugin-provided subcommand (loaded at runtime)
     #[command(external_subcommand)]
     External(Vec<String>),
@@ -201,14 +341,40 @@ pub enum Commands {
 
 #[derive(Parser)]
 pub struct RunArgs {
-    /// Path to the contract WASM file
+    /// Path to the contract WASM file, or to a cargo crate/workspace directory
     #[arg(
         short,
         long,
-        required_unless_present_any = ["server", "remote"]
+        required_unless_present_any = ["server", "remote", "package", "env"]
     )]
     pub contract: Option<PathBuf>,
 
+    /// Run inside a named environment (see `soroban-debug env`): its snapshot,
+    /// contract, mocks and committed state are loaded before execution
+    #[arg(
+        long,
+        value_name = "NAME",
+        conflicts_with_all = ["server", "remote", "batch_args", "repeat", "dry_run", "smoke"]
+    )]
+    pub env: Option<String>,
+
+    /// Persist the post-execution state back into the --env environment
+    #[arg(long, requires = "env")]
+    pub commit: bool,
+
+    /// Cargo package whose WASM to debug; the workspace is found from --contract
+    /// or the current directory
+    #[arg(long, value_name = "CRATE")]
+    pub package: Option<String>,
+
+    /// Build the cargo package for the wasm target before debugging
+    #[arg(long)]
+    pub build: bool,
+
+    /// Cargo profile to build with and to look for the WASM under
+    #[arg(long, value_name = "PROFILE", default_value = "release")]
+    pub cargo_profile: String,
+
     /// Deprecated: use --contract instead
     #[arg(long, hide = true, alias = "wasm", alias = "contract-path")]
     pub wasm: Option<PathBuf>,
@@ -217,22 +383,98 @@ pub struct RunArgs {
     #[arg(
         short,
         long,
-        required_unless_present_any = ["server", "remote"]
+        required_unless_present_any = ["server", "remote", "smoke", "call", "calls_file"]
     )]
     pub function: Option<String>,
 
-    /// Function arguments as JSON array (e.g., '["arg1", "arg2"]')
+    /// Function arguments as JSON array (e.g., '["arg1", "arg2"]'), or
+    /// @FILE to read them from a file (@- for stdin)
     #[arg(short, long)]
     pub args: Option<String>,
 
-    /// Initial storage state as JSON object
+    /// Function arguments as base64 XDR of an ScVec, e.g. the `args` of an
+    /// InvokeContract host function returned by RPC
+    #[arg(
+        long,
+        value_name = "BASE64",
+        conflicts_with_all = ["args", "call", "calls_file", "batch_args"]
+    )]
+    pub args_xdr: Option<String>,
+
+    /// Call FUNCTION with ARGS_JSON, in order with the other --call flags and
+    /// against the same contract state (repeatable), e.g. `--call initialize
+    /// --call 'deposit:[100]'`
+    #[arg(
+        long,
+        value_name = "FUNCTION[:ARGS_JSON]",
+        conflicts_with_all = ["function", "args", "calls_file", "smoke", "server", "remote"]
+    )]
+    pub call: Vec<String>,
+
+    /// JSON array of calls to make in order against the same contract state:
+    /// function names or {"function": ..., "args": [...]} objects
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["function", "args", "smoke", "server", "remote"]
+    )]
+    pub calls_file: Option<PathBuf>,
+
+    /// Initial storage state as JSON object, or @FILE to read it from a file
     #[arg(short, long)]
     pub storage: Option<String>,
 
-    /// Set breakpoint at function name
-    #[arg(short, long)]
+    /// Seed storage from a file written by --dump-storage, with each entry's
+    /// durability and TTL. Applied before --storage, so inline entries win
+    #[arg(long, value_name = "FILE")]
+    pub storage_file: Option<PathBuf>,
+
+    /// Instance storage to seed, as a JSON object mapping keys to values
+    #[arg(long, value_name = "JSON")]
+    pub instance_storage: Option<String>,
+
+    /// Persistent storage to seed, as a JSON object mapping keys to values
+    #[arg(long, value_name = "JSON")]
+    pub persistent_storage: Option<String>,
+
+    /// Temporary storage to seed, as a JSON object mapping keys to values
+    #[arg(long, value_name = "JSON")]
+    pub temporary_storage: Option<String>,
+
+    /// Set breakpoint at function name, optionally only when a condition on
+    /// its arguments holds: 'transfer if args[1] == "G..."' (repeatable)
+    #[arg(short, long, value_name = "FUNCTION[ if CONDITION]")]
     pub breakpoint: Vec<String>,
 
+    /// Stop when a storage key matching the pattern is written or removed,
+    /// by the called contract or one it calls, and report the write and its
+    /// call stack. Same syntax as --storage-filter key patterns (repeatable)
+    #[arg(long, value_name = "KEY_PATTERN")]
+    pub break_on_write: Vec<String>,
+
+    /// Stop when an event with a topic containing PATTERN is emitted, and
+    /// report the decoded event and its call stack. Matches any topic, like
+    /// --filter-topic, or only topic N as 'N:PATTERN' (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub break_on_event: Vec<String>,
+
+    /// Only check contract events against --break-on-event, not diagnostic ones
+    #[arg(long, requires = "break_on_event")]
+    pub break_contract_events_only: bool,
+
+    /// Report each call the contract makes to the named host function, e.g.
+    /// require_auth, call or get_ledger_timestamp (extend_ttl covers every
+    /// TTL extension), with its arguments and the calling frame, once the
+    /// call returns. The contract's WASM is rewritten to catch the calls
+    /// (repeatable)
+    #[arg(long, value_name = "NAME")]
+    pub report_host_fn: Vec<String>,
+
+    /// Print each breakpoint stop and carry on instead of prompting at it.
+    /// Implied when stdin or stdout is not a terminal
+    #[arg(long)]
+    pub non_interactive: bool,
+
     /// Network snapshot file to load before execution
     #[arg(long)]
     pub network_snapshot: Option<PathBuf>,
@@ -241,6 +483,22 @@ pub struct RunArgs {
     #[arg(long, hide = true, alias = "snapshot")]
     pub snapshot: Option<PathBuf>,
 
+    /// Fail instead of warning when the --contract WASM differs from the code
+    /// deployed in the network snapshot
+    #[arg(long)]
+    pub require_matching_code: bool,
+
+    /// Execute the code deployed in the network snapshot instead of the
+    /// --contract WASM
+    #[arg(long)]
+    pub use_snapshot_code: bool,
+
+    /// Register every contract in the network snapshot at its own address,
+    /// running the code the snapshot holds for it, so cross-contract calls
+    /// reach them
+    #[arg(long)]
+    pub register_all_from_snapshot: bool,
+
     /// Enable verbose output
     #[arg(short, long)]
     pub verbose: bool,
@@ -276,14 +534,33 @@ pub struct RunArgs {
     #[arg(long = "output", value_enum, default_value_t = OutputFormat::Pretty)]
     pub output_format: OutputFormat,
 
-    /// Show contract events emitted during execution
+    /// Sections to collect and print, comma-separated: events, auth, ledger,
+    /// host-calls, logs, diagnostics, storage-diff or all [default: storage-diff]
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "ITEMS")]
+    pub show: Vec<ShowItem>,
+
+    /// Do not print the storage changes, even when --show or output.show
+    /// lists storage-diff. Triggered --alert-on-change alerts still print
+    #[arg(long)]
+    pub no_storage_diff: bool,
+
+    /// Deprecated: use --show events instead
     #[arg(long)]
     pub show_events: bool,
 
-    /// Show authorization tree during execution
+    /// Deprecated: use --show auth instead
     #[arg(long)]
     pub show_auth: bool,
 
+    /// Run with recorded authorization and write the required authorization entries to FILE
+    /// as base64 XDR plus decoded JSON, with signature placeholders for an off-chain signer
+    #[arg(long, value_name = "FILE")]
+    pub export_auth: Option<PathBuf>,
+
+    /// Signature expiration ledger to put in entries written by --export-auth
+    #[arg(long, value_name = "LEDGER", requires = "export_auth")]
+    pub auth_expiration_ledger: Option<u32>,
+
     /// Output format as JSON
     #[arg(long)]
     pub json: bool,
@@ -296,21 +573,115 @@ pub struct RunArgs {
     #[arg(long, value_name = "PATTERN")]
     pub event_filter: Vec<String>,
 
+    /// Check emitted events against the contract's event schema and warn on mismatches
+    #[arg(long)]
+    pub validate_events: bool,
+
+    /// Event schema JSON to validate against instead of the one derived from the WASM
+    #[arg(long, value_name = "FILE")]
+    pub event_schema: Option<PathBuf>,
+
+    /// Write emitted events to FILE as NDJSON in the RPC `getEvents` event shape
+    #[arg(long, value_name = "FILE")]
+    pub events_ndjson: Option<PathBuf>,
+
     /// Execute the contract call N times for stress testing
     #[arg(long)]
     pub repeat: Option<u32>,
 
-    /// Mock cross-contract return: CONTRACT_ID.function=return_value (repeatable)
-    #[arg(long, value_name = "CONTRACT_ID.function=return_value")]
+    /// Seed the host PRNG behind `env.prng()` so the run can be reproduced.
+    /// Without it a random seed is drawn and printed
+    #[arg(long, value_name = "U64")]
+    pub seed: Option<u64>,
+
+    /// With --repeat, run iteration i with seed+i instead of reusing the seed
+    #[arg(long)]
+    pub vary_seed: bool,
+
+    /// With --repeat, load the contract into a new environment for every
+    /// iteration instead of sharing one and rolling its storage back
+    #[arg(long)]
+    pub fresh_state: bool,
+
+    /// Ledger timestamp to run at, as Unix seconds or an RFC 3339 date
+    /// (e.g. 2024-01-01T00:00:00Z)
+    #[arg(long, value_name = "TIME")]
+    pub ledger_timestamp: Option<String>,
+
+    /// Ledger sequence number to run at
+    #[arg(long, value_name = "SEQ")]
+    pub ledger_sequence: Option<u32>,
+
+    /// Network passphrase the ledger's network ID is derived from
+    #[arg(long, value_name = "PASSPHRASE")]
+    pub network_passphrase: Option<String>,
+
+    /// Ledger protocol version the host runs at; must be one the linked
+    /// host supports
+    #[arg(long, value_name = "VERSION")]
+    pub protocol_version: Option<u32>,
+
+    /// Turn on every diagnostic at once: --show all, a bounded trace, the
+    /// cost table and verbose output. Prints the flags it expanded to
+    #[arg(long)]
+    pub full_debug: bool,
+
+    /// Mock cross-contract return: CONTRACT_ID.function[:policy]=value[,value...] (repeatable).
+    /// Values are served in call order; once exhausted, `repeat-last` (default),
+    /// `cycle` or `error` decides what further calls get.
+    #[arg(long, value_name = "CONTRACT_ID.function[:policy]=value[,value...]")]
     pub mock: Vec<String>,
 
-    /// Filter storage output by key pattern (repeatable). Supports:
+    /// Register another contract as NAME=path.wasm (repeatable). Pass it to the
+    /// contract under test as {"type": "contract", "value": "NAME"}
+    #[arg(long, value_name = "NAME=PATH")]
+    pub link: Vec<String>,
+
+    /// Arguments for the contract's __constructor as a JSON array, in --args
+    /// syntax (including @FILE). Required when the constructor takes any
+    #[arg(long, value_name = "JSON")]
+    pub constructor_args: Option<String>,
+
+    /// Run the call on behalf of ADDRESS: a G... account becomes the source
+    /// account, a C... contract (linked or from a snapshot) the invoker.
+    /// Without it a random account is used and printed with --verbose
+    #[arg(long, value_name = "ADDRESS")]
+    pub source_account: Option<String>,
+
+    /// Filter storage output by key or value pattern (repeatable). Supports:
     ///   prefix*       — match keys starting with prefix
     ///   re:<regex>    — match keys by regex
     ///   exact_key     — match key exactly
+    ///   val:<pattern> — match the rendered value with any of the above
+    ///   !<pattern>    — exclude what the pattern matches, e.g. '!__meta:*'
+    /// An entry must match a key pattern and a value pattern when both are given,
+    /// and no exclusion.
+    /// Keys and values match as listed, e.g. Balance(G...), or in snapshot form.
     #[arg(long, value_name = "PATTERN")]
     pub storage_filter: Vec<String>,
 
+    /// List only the storage entries in this storage (repeatable). Lists
+    /// storage on its own, or narrows --storage-filter
+    #[arg(long, value_enum, value_name = "STORAGE")]
+    pub durability: Vec<KeyDurability>,
+
+    /// Order of the storage listing
+    #[arg(long, value_enum, value_name = "ORDER", default_value_t = StorageOrder::Key)]
+    pub sort: StorageOrder,
+
+    /// List at most N storage entries, in text and JSON output; 0 lists all
+    #[arg(long, value_name = "N", default_value_t = 50)]
+    pub limit: usize,
+
+    /// Skip the first N storage entries of the listing
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub offset: usize,
+
+    /// Also print the base64 XDR of each listed storage key and value, for
+    /// pasting into other tools. Lists storage on its own, like --durability
+    #[arg(long)]
+    pub raw_xdr: bool,
+
     /// Enable instruction-level debugging
     #[arg(long)]
     pub instruction_debug: bool,
@@ -326,6 +697,15 @@ pub struct RunArgs {
     #[arg(long)]
     pub dry_run: bool,
 
+    /// Call every function in the contract spec once with generated arguments
+    /// and report pass/trap/error/skipped per function
+    #[arg(long, conflicts_with_all = ["function", "args", "repeat", "dry_run"])]
+    pub smoke: bool,
+
+    /// Write the `--smoke` results as a JUnit XML report, one test case per function
+    #[arg(long, value_name = "FILE", requires = "smoke")]
+    pub junit: Option<PathBuf>,
+
     /// Export storage state to JSON file after execution
     #[arg(long)]
     pub export_storage: Option<PathBuf>,
@@ -334,6 +714,22 @@ pub struct RunArgs {
     #[arg(long)]
     pub import_storage: Option<PathBuf>,
 
+    /// Write the contract's storage after the run to FILE, with each entry's
+    /// typed key and value, durability and TTL, in the form --storage @FILE
+    /// reads. Refuses an existing FILE unless --overwrite is given
+    #[arg(long, value_name = "FILE")]
+    pub dump_storage: Option<PathBuf>,
+
+    /// Save the whole post-run state (storage, ledger info) to FILE, to
+    /// resume with --load-state
+    #[arg(long, value_name = "FILE")]
+    pub save_state: Option<PathBuf>,
+
+    /// Resume from a state saved with --save-state instead of a freshly
+    /// loaded contract
+    #[arg(long, value_name = "FILE")]
+    pub load_state: Option<PathBuf>,
+
     /// Path to JSON file containing array of argument sets for batch execution
     #[arg(long)]
     pub batch_args: Option<PathBuf>,
@@ -342,8 +738,8 @@ pub struct RunArgs {
     #[arg(long, value_name = "FILE")]
     pub generate_test: Option<PathBuf>,
 
-    /// Overwrite the test file if it already exists (default: append)
-
+    /// Overwrite the test file if it already exists (default: append), and
+    /// replace an existing --dump-storage file
     #[arg(long)]
     pub overwrite: bool,
 
@@ -351,15 +747,37 @@ pub struct RunArgs {
     #[arg(long, default_value = "30")]
     pub timeout: u64,
 
-    /// Trigger a prominent alert when a critical storage key is modified (repeatable)
-    #[arg(long, value_name = "KEY_PATTERN")]
+    /// CPU instructions the calls may spend; overrides --limits
+    #[arg(long, value_name = "INSNS")]
+    pub cpu_limit: Option<u64>,
+
+    /// Memory the calls may use, in bytes; overrides --limits
+    #[arg(long, value_name = "BYTES")]
+    pub mem_limit: Option<u64>,
+
+    /// Budget limits to run the calls under: mainnet, testnet, default,
+    /// unlimited, or an RPC URL to read the network's current limits from
+    #[arg(long, value_name = "PRESET")]
+    pub limits: Option<String>,
+
+    /// Trigger a prominent alert when a critical storage key is modified (repeatable).
+    /// Append a predicate to narrow it: `KEY!=old` (value actually changed),
+    /// `KEY==old` (rewritten with the same value), `KEY==VALUE` (new value matches).
+    /// `!KEY_PATTERN` keeps matching keys from alerting
+    #[arg(long, value_name = "KEY_PATTERN[==|!=VALUE]")]
     pub alert_on_change: Vec<String>,
 
+    /// Record every storage write with the call that made it. Lists the writes of
+    /// keys matching --storage-filter; JSON output includes all of them, and alerts
+    /// name the call that made the write they fired on
+    #[arg(long)]
+    pub track_writes: bool,
+
     /// Expected SHA-256 hash of the WASM file. If provided, loading will fail if the computed hash does not match.
     #[arg(long)]
     pub expected_hash: Option<String>,
 
-    /// Show ledger entries accessed during execution
+    /// Deprecated: use --show ledger instead
     #[arg(long)]
     pub show_ledger: bool,
 
@@ -367,9 +785,87 @@ pub struct RunArgs {
     #[arg(long, default_value = "1000")]
     pub ttl_warning_threshold: u32,
 
-    /// Export execution trace to JSON file
+    /// Export execution trace to JSON file. Authorizations are recorded for
+    /// the trace, so `require_auth` passes as with --export-auth
     #[arg(long)]
     pub trace_output: Option<PathBuf>,
+
+    /// Record only every RATE-th storage access in the trace and the storage
+    /// heatmap, whose counts become estimates. Contract calls and accesses
+    /// to --alert-on-change keys are always recorded
+    #[arg(long, value_name = "RATE", value_parser = clap::value_parser!(u64).range(1..), conflicts_with = "repeat")]
+    pub trace_sample: Option<u64>,
+
+    /// Stop recording storage accesses in the trace after N have been
+    /// recorded. Counts recorded accesses, so with --trace-sample it covers
+    /// about N * RATE accesses
+    #[arg(long, value_name = "N", conflicts_with = "repeat")]
+    pub trace_limit: Option<usize>,
+
+    /// Records kept in the always-on trace ring of recent calls and storage
+    /// writes, which is printed when the run fails or times out. 0 turns it off
+    #[arg(long, value_name = "N", default_value_t = 4096)]
+    pub ring_size: usize,
+
+    /// Drop trace ring records older than this many seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 300)]
+    pub ring_window: u64,
+
+    /// Print the trace ring after a successful run as well
+    #[arg(long)]
+    pub dump_ring: bool,
+
+    /// Write a self-contained HTML report of the run to FILE
+    #[arg(long, value_name = "FILE")]
+    pub report_html: Option<PathBuf>,
+
+    /// Expected return value; compared against the rendered result after execution
+    #[arg(long, value_name = "VALUE")]
+    pub expect: Option<String>,
+
+    /// Whether failed expectations fail the run (strict) or are only reported (warn)
+    #[arg(long, value_enum, default_value_t = ExpectMode::Strict)]
+    pub expect_mode: ExpectMode,
+
+    /// Print a per-function instruction cost table (aggregated across --repeat iterations)
+    #[arg(long)]
+    pub cost_table: bool,
+
+    /// Print the N most accessed storage keys (default 10), aggregated across --repeat iterations.
+    /// Honours --storage-filter; JSON output includes every key.
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    pub storage_heatmap: Option<usize>,
+
+    /// Print the value at PATH from the return value or storage, e.g. `result.balances["alice"]`
+    /// or `storage["counter"]` (repeatable). With --quiet or JSON output only the values are
+    /// printed, one per line; fails if a path does not resolve.
+    #[arg(long, value_name = "PATH")]
+    pub extract: Vec<String>,
+
+    /// Fail with exit code 3 when the run emits more than N warnings (default: unlimited)
+    #[arg(long, value_name = "N")]
+    pub max_warnings: Option<usize>,
+
+    /// Leave a warning category out of the --max-warnings count (repeatable)
+    #[arg(long, value_enum, value_name = "CATEGORY")]
+    pub allow_warning: Vec<crate::warnings::WarningCategory>,
+
+    /// Argument literal conversions loaded from the `[conversions]` config table
+    #[arg(skip)]
+    pub conversions: std::collections::BTreeMap<String, crate::config::ConversionConfig>,
+
+    /// Rent estimate rates loaded from the `[rent]` config table
+    #[arg(skip)]
+    pub rent: crate::config::RentConfig,
+
+    /// Fail the run when a read-only function (by `[conventions] readonly_patterns`
+    /// or an `@readonly` spec annotation) writes storage or emits events
+    #[arg(long)]
+    pub strict_conventions: bool,
+
+    /// Read-only function patterns loaded from the `[conventions]` config table
+    #[arg(skip)]
+    pub readonly_patterns: Vec<String>,
     /// Path to file where execution results should be saved
     #[arg(long, value_name = "FILE")]
     pub save_output: Option<PathBuf>,
@@ -377,6 +873,25 @@ pub struct RunArgs {
     /// Append to output file instead of overwriting (used with --save-output)
     #[arg(long)]
     pub append: bool,
+
+    /// Save this invocation, with config defaults and the contract path resolved,
+    /// as a preset for `soroban-debug preset run NAME`
+    #[arg(long, value_name = "NAME")]
+    pub save_preset: Option<String>,
+
+    /// Reuse the stored report of an identical earlier run instead of executing.
+    /// Runs whose contract uses the PRNG or ledger clock always execute.
+    #[arg(long)]
+    pub cache_results: bool,
+
+    /// Execute even when --cache-results would reuse a stored result, and do not store one
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// The `run` flags as given on the command line, recorded for --save-preset
+    /// and --cache-results
+    #[arg(skip)]
+    pub command_line: Vec<String>,
 }
 
 impl RunArgs {
@@ -390,13 +905,81 @@ impl RunArgs {
                 .unwrap_or(false)
     }
 
+    /// The sections to collect: `--show` (or `output.show`, or
+    /// [`ShowItem::DEFAULT`]) plus the deprecated `--show-*` flags, with
+    /// `all` expanded.
+    pub fn shown(&self) -> BTreeSet<ShowItem> {
+        let listed = if self.show.is_empty() {
+            ShowItem::DEFAULT
+        } else {
+            &self.show
+        };
+        let legacy = [
+            (self.show_events, ShowItem::Events),
+            (self.show_auth, ShowItem::Auth),
+            (self.show_ledger, ShowItem::Ledger),
+        ];
+        let mut shown: BTreeSet<ShowItem> = listed
+            .iter()
+            .copied()
+            .chain(legacy.iter().filter(|(set, _)| *set).map(|(_, item)| *item))
+            .collect();
+        if shown.remove(&ShowItem::All) {
+            shown.extend(
+                ShowItem::value_variants()
+                    .iter()
+                    .filter(|item| **item != ShowItem::All),
+            );
+        }
+        if self.no_storage_diff {
+            shown.remove(&ShowItem::StorageDiff);
+        }
+        shown
+    }
+
+    pub fn shows(&self, item: ShowItem) -> bool {
+        self.shown().contains(&item)
+    }
+
+    /// Set the flags `--full-debug` stands for and return them as they would
+    /// be typed, leaving flags that were given explicitly alone.
+    pub fn expand_full_debug(&mut self) -> Vec<String> {
+        let mut expanded = Vec::new();
+        if !self.show.contains(&ShowItem::All) {
+            self.show = vec![ShowItem::All];
+            expanded.push("--show all".to_string());
+        }
+        // `--repeat` keeps no trace, and clap rejects --trace-limit next to it.
+        if self.trace_limit.is_none() && self.repeat.is_none() {
+            self.trace_limit = Some(FULL_DEBUG_TRACE_LIMIT);
+            expanded.push(format!("--trace-limit {}", FULL_DEBUG_TRACE_LIMIT));
+        }
+        if !self.cost_table {
+            self.cost_table = true;
+            expanded.push("--cost-table".to_string());
+        }
+        if !self.verbose {
+            self.verbose = true;
+            expanded.push("--verbose".to_string());
+        }
+        expanded
+    }
+
     pub fn merge_config(&mut self, config: &Config) {
         // Breakpoints
         if self.breakpoint.is_empty() && !config.debug.breakpoints.is_empty() {
             self.breakpoint = config.debug.breakpoints.clone();
         }
 
-        // Show events
+        // Sections to show; `config validate` reports unknown items
+        if self.show.is_empty() {
+            if let Some(items) = &config.output.show {
+                self.show = items
+                    .iter()
+                    .filter_map(|item| ShowItem::from_str(item, true).ok())
+                    .collect();
+            }
+        }
         if !self.show_events {
             if let Some(show) = config.output.show_events {
                 self.show_events = show;
@@ -408,6 +991,33 @@ impl RunArgs {
             self.format = config.output.format.clone();
         }
 
+        // Argument conversions
+        if self.conversions.is_empty() {
+            self.conversions = config.conversions.clone();
+        }
+
+        // Rent estimate rates
+        self.rent = config.rent.clone();
+
+        // Read-only conventions
+        if self.readonly_patterns.is_empty() {
+            self.readonly_patterns = config.conventions.readonly_patterns.clone();
+        }
+
+        // Ledger
+        if self.ledger_timestamp.is_none() {
+            self.ledger_timestamp = config.ledger.timestamp.as_ref().map(ToString::to_string);
+        }
+        if self.ledger_sequence.is_none() {
+            self.ledger_sequence = config.ledger.sequence;
+        }
+        if self.network_passphrase.is_none() {
+            self.network_passphrase = config.ledger.network_passphrase.clone();
+        }
+        if self.protocol_version.is_none() {
+            self.protocol_version = config.ledger.protocol_version;
+        }
+
         // Verbosity: if config has a level > 0 and CLI verbose is false, enable it
         if !self.verbose {
             if let Some(level) = config.debug.verbosity {
@@ -453,12 +1063,39 @@ pub struct InteractiveArgs {
     #[arg(long)]
     pub import_storage: Option<PathBuf>,
 
-    /// Set breakpoint at function name
-    #[arg(short, long)]
+    /// Set breakpoint at function name, optionally only when a condition on
+    /// its arguments holds: 'transfer if args[1] == "G..."' (repeatable)
+    #[arg(short, long, value_name = "FUNCTION[ if CONDITION]")]
     pub breakpoint: Vec<String>,
 
-    /// Mock cross-contract return: CONTRACT_ID.function=return_value (repeatable)
-    #[arg(long, value_name = "CONTRACT_ID.function=return_value")]
+    /// Stop when a storage key matching the pattern is written or removed,
+    /// by the called contract or one it calls, and report the write and its
+    /// call stack. Same syntax as --storage-filter key patterns (repeatable)
+    #[arg(long, value_name = "KEY_PATTERN")]
+    pub break_on_write: Vec<String>,
+
+    /// Stop when an event with a topic containing PATTERN is emitted, and
+    /// report the decoded event and its call stack. Matches any topic, like
+    /// --filter-topic, or only topic N as 'N:PATTERN' (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub break_on_event: Vec<String>,
+
+    /// Only check contract events against --break-on-event, not diagnostic ones
+    #[arg(long, requires = "break_on_event")]
+    pub break_contract_events_only: bool,
+
+    /// Report each call the contract makes to the named host function, e.g.
+    /// require_auth, call or get_ledger_timestamp (extend_ttl covers every
+    /// TTL extension), with its arguments and the calling frame, once the
+    /// call returns. The contract's WASM is rewritten to catch the calls
+    /// (repeatable)
+    #[arg(long, value_name = "NAME")]
+    pub report_host_fn: Vec<String>,
+
+    /// Mock cross-contract return: CONTRACT_ID.function[:policy]=value[,value...] (repeatable).
+    /// Values are served in call order; once exhausted, `repeat-last` (default),
+    /// `cycle` or `error` decides what further calls get.
+    #[arg(long, value_name = "CONTRACT_ID.function[:policy]=value[,value...]")]
     pub mock: Vec<String>,
 
     /// Execution timeout in seconds (default: 30)
@@ -514,6 +1151,19 @@ pub struct ReplArgs {
     /// Expected SHA-256 hash of the WASM file. If provided, loading will fail if the computed hash does not match.
     #[arg(long)]
     pub expected_hash: Option<String>,
+
+    /// Start the session inside a named environment (see `soroban-debug env`)
+    #[arg(long, value_name = "NAME")]
+    pub env: Option<String>,
+
+    /// Persist the state at the end of the session back into the --env environment
+    #[arg(long, requires = "env")]
+    pub commit: bool,
+
+    /// Run the REPL commands and `assert <condition>` lines in FILE instead of
+    /// reading from the terminal; stops at the first failure
+    #[arg(long, value_name = "FILE")]
+    pub script: Option<PathBuf>,
 }
 
 impl ReplArgs {
@@ -529,6 +1179,283 @@ pub struct CompletionsArgs {
     pub shell: Shell,
 }
 
+#[derive(Parser)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub action: CacheAction,
+}
+
+#[derive(Subcommand)]
+pub enum CacheAction {
+    /// Show the cache location, entry count and size, and the number of cached run results
+    Info,
+    /// Delete all cached contract artifacts and run results
+    Clear,
+}
+
+#[derive(Parser)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub action: ConfigAction,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Report every problem in the given config files, with line numbers
+    Validate {
+        /// Config files to check (default: .soroban-debug.toml)
+        #[arg(value_name = "PATH")]
+        paths: Vec<PathBuf>,
+    },
+}
+
+#[derive(Parser)]
+pub struct SnapshotArgs {
+    #[command(subcommand)]
+    pub action: SnapshotAction,
+}
+
+#[derive(Subcommand)]
+pub enum SnapshotAction {
+    /// Print the header and a summary of a v1 or v2 snapshot, verifying its checksum
+    Show {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+    /// Rewrite a snapshot in another format
+    Convert {
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+        #[arg(value_name = "OUTPUT")]
+        output: PathBuf,
+        /// Format to write
+        #[arg(long, value_enum, default_value_t = SnapshotFormat::V2)]
+        format: SnapshotFormat,
+    },
+    /// Apply a patch file of storage edits (set, delete, extend-ttl, scale)
+    Patch {
+        #[arg(value_name = "INPUT")]
+        input: PathBuf,
+        /// JSON patch file listing the operations to apply in order
+        #[arg(long, value_name = "FILE")]
+        patch: PathBuf,
+        /// Where to write the patched snapshot
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+        /// Format to write; defaults to the format of INPUT
+        #[arg(long, value_enum)]
+        format: Option<SnapshotFormat>,
+    },
+    /// Fetch a deployed contract's instance and code from RPC into a snapshot
+    Fetch {
+        /// Contract ID (C...) to fetch
+        #[arg(value_name = "CONTRACT")]
+        contract: String,
+        /// Soroban RPC URL to fetch from
+        #[arg(long, env = "SOROBAN_RPC_URL")]
+        rpc_url: String,
+        /// Where to write the snapshot
+        #[arg(long, value_name = "FILE")]
+        out: PathBuf,
+        /// Also fetch the contracts whose addresses appear in instance storage
+        #[arg(long)]
+        follow_deps: bool,
+        /// Levels of references to follow with --follow-deps
+        #[arg(long, value_name = "N", default_value_t = 1, requires = "follow_deps")]
+        depth: u32,
+        /// Stop fetching once this many ledger entries have been read
+        #[arg(long, value_name = "N", default_value_t = 200)]
+        max_entries: usize,
+        /// Format to write
+        #[arg(long, value_enum, default_value_t = SnapshotFormat::V2)]
+        format: SnapshotFormat,
+        /// RPC request timeout in seconds
+        #[arg(long, value_name = "SECONDS", default_value_t = 30)]
+        timeout: u64,
+    },
+}
+
+#[derive(Parser)]
+pub struct HistoryArgs {
+    #[command(subcommand)]
+    pub action: HistoryAction,
+}
+
+#[derive(Parser)]
+pub struct EnvArgs {
+    #[command(subcommand)]
+    pub action: EnvAction,
+}
+
+#[derive(Subcommand)]
+pub enum EnvAction {
+    /// Create a named environment
+    Create {
+        /// Environment name (letters, digits, '-', '_' and '.')
+        name: String,
+
+        /// Network snapshot to copy into the environment
+        #[arg(long, value_name = "FILE")]
+        from_snapshot: Option<PathBuf>,
+
+        /// Contract WASM to register (repeatable); used when --contract is omitted
+        #[arg(long, value_name = "FILE")]
+        with_contract: Vec<PathBuf>,
+
+        /// Label as KEY=VALUE (repeatable)
+        #[arg(long, value_name = "KEY=VALUE")]
+        label: Vec<String>,
+
+        /// Mock spec applied to every run, in `run --mock` syntax (repeatable)
+        #[arg(long, value_name = "CONTRACT_ID.function[:policy]=value[,value...]")]
+        mock: Vec<String>,
+    },
+    /// List environments
+    List,
+    /// Delete an environment and its committed state
+    Delete {
+        /// Environment name
+        name: String,
+    },
+}
+
+#[derive(Parser)]
+pub struct PresetArgs {
+    #[command(subcommand)]
+    pub action: PresetAction,
+}
+
+#[derive(Subcommand)]
+pub enum PresetAction {
+    /// Run a preset, optionally overriding its flags
+    Run {
+        /// Preset name
+        name: String,
+
+        /// `run` flags to apply on top of the preset. Single-valued flags
+        /// replace the saved value; repeatable ones such as --breakpoint add to it
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            value_name = "OVERRIDES"
+        )]
+        overrides: Vec<String>,
+    },
+    /// List presets
+    List,
+    /// Print a preset as a command line
+    Show {
+        /// Preset name
+        name: String,
+    },
+    /// Delete a preset
+    Delete {
+        /// Preset name
+        name: String,
+    },
+}
+
+#[derive(Parser)]
+pub struct KeyArgs {
+    /// Contract address (C...) owning the entry; entries in --keys-file may name their own
+    #[arg(long, value_name = "CONTRACT_ID")]
+    pub contract: Option<String>,
+
+    /// Storage key as typed JSON, e.g. '{"type":"symbol","value":"Counter"}'.
+    /// Not needed for instance storage, which lives in a single entry
+    #[arg(long, value_name = "JSON", conflicts_with_all = ["keys_file", "decode"])]
+    pub key: Option<String>,
+
+    /// Storage the key lives in
+    #[arg(long, value_enum, default_value_t = KeyDurability::Persistent)]
+    pub durability: KeyDurability,
+
+    /// JSON array of keys: typed keys, or `--storage` list entries
+    /// ({"key", "durability"[, "contract"]}, values ignored)
+    #[arg(long, value_name = "FILE", conflicts_with = "decode")]
+    pub keys_file: Option<PathBuf>,
+
+    /// Write the keys built from --keys-file to FILE, one base64 LedgerKey per line
+    #[arg(short, long, value_name = "FILE", requires = "keys_file")]
+    pub output: Option<PathBuf>,
+
+    /// Decode a base64 LedgerKey instead of building one
+    #[arg(long, value_name = "XDR")]
+    pub decode: Option<String>,
+
+    /// Output format: pretty (default) or json
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
+}
+
+#[derive(Parser)]
+pub struct ImportTxArgs {
+    /// Hash of the transaction to import
+    #[arg(required_unless_present = "from")]
+    pub hash: Option<String>,
+
+    /// Soroban RPC URL to fetch the transaction and ledger state from
+    #[arg(long, env = "SOROBAN_RPC_URL", required_unless_present = "from")]
+    pub rpc_url: Option<String>,
+
+    /// Directory to save the scenario in [default: tx-<first 12 characters of the hash>]
+    #[arg(short, long, value_name = "DIR")]
+    pub out: Option<PathBuf>,
+
+    /// Replay the invocation locally and compare it with the on-chain result
+    #[arg(long)]
+    pub run: bool,
+
+    /// Replay a scenario saved by an earlier import instead of fetching one
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["hash", "rpc_url", "out"])]
+    pub from: Option<PathBuf>,
+
+    /// Let every require_auth pass instead of checking the transaction's own auth entries
+    #[arg(long)]
+    pub mock_auth: bool,
+
+    /// RPC request timeout in seconds
+    #[arg(long, value_name = "SECONDS", default_value_t = 30)]
+    pub timeout: u64,
+}
+
+#[derive(Subcommand)]
+pub enum HistoryAction {
+    /// Rewrite the history file, dropping unreadable records
+    Compact {
+        /// Also drop all but the N most-recent records
+        #[arg(long, value_name = "COUNT")]
+        keep_last: Option<usize>,
+    },
+    /// Show when each event topic signature first and last appeared in a contract's runs
+    Events {
+        /// Contract WASM whose recorded runs to summarize
+        #[arg(short, long, value_name = "FILE")]
+        contract: PathBuf,
+
+        /// Number of most recent runs of each function compared against the earlier ones
+        #[arg(long, value_name = "COUNT", default_value_t = 1)]
+        recent: usize,
+
+        /// Exit with an error when a topic changed this way (comma-separated)
+        #[arg(long, value_enum, value_delimiter = ',', value_name = "CHANGE")]
+        fail_on: Vec<TopicChange>,
+
+        /// Output format: pretty (default) or json
+        #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+        format: OutputFormat,
+    },
+}
+
+/// A change in the event topics of a function between older and recent runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TopicChange {
+    /// Emitted by older runs but not by the recent ones
+    Missing,
+    /// Emitted only by the recent runs
+    Added,
+}
+
 #[derive(Parser)]
 pub struct HistoryPruneArgs {
     /// Keep only the N most-recent records
@@ -581,6 +1508,14 @@ pub struct InspectArgs {
     /// Show cross-contract dependency graph in specified format
     #[arg(long, value_enum)]
     pub dependency_graph: Option<GraphFormat>,
+
+    /// Show the event schema derived from constant topic symbols
+    #[arg(long)]
+    pub events: bool,
+
+    /// Decode the contract spec again instead of reading the cached copy
+    #[arg(long)]
+    pub no_spec_cache: bool,
 }
 
 #[derive(Parser)]
@@ -648,8 +1583,10 @@ pub struct OptimizeArgs {
 
 #[cfg(test)]
 mod tests {
-    use super::{Cli, Commands, OutputFormat, SymbolicProfile};
-    use clap::Parser;
+    use super::{
+        Cli, Commands, OutputFormat, RunArgs, ShowItem, SymbolicProfile, FULL_DEBUG_TRACE_LIMIT,
+    };
+    use clap::{Parser, ValueEnum};
 
     #[test]
     fn run_output_defaults_to_pretty() {
@@ -846,6 +1783,162 @@ mod tests {
         assert_eq!(args.source_map_limit, 5);
         assert_eq!(args.format, OutputFormat::Json);
     }
+
+    fn run_with(extra: &[&str]) -> Result<RunArgs, clap::Error> {
+        let base = [
+            "soroban-debug",
+            "run",
+            "--contract",
+            "contract.wasm",
+            "--function",
+            "increment",
+        ];
+        let cli = Cli::try_parse_from(base.iter().chain(extra))?;
+        let Some(Commands::Run(args)) = cli.command else {
+            panic!("run command expected");
+        };
+        Ok(args)
+    }
+
+    #[test]
+    fn show_defaults_to_storage_diff() {
+        let args = run_with(&[]).unwrap();
+        assert!(args.show.is_empty());
+        assert_eq!(
+            args.shown().into_iter().collect::<Vec<_>>(),
+            vec![ShowItem::StorageDiff]
+        );
+    }
+
+    #[test]
+    fn show_accepts_comma_separated_and_repeated_items() {
+        let args = run_with(&["--show", "events,host-calls", "--show", "auth"]).unwrap();
+        assert_eq!(
+            args.shown().into_iter().collect::<Vec<_>>(),
+            vec![ShowItem::Events, ShowItem::Auth, ShowItem::HostCalls]
+        );
+        // An explicit list replaces the default.
+        assert!(!args.shows(ShowItem::StorageDiff));
+    }
+
+    #[test]
+    fn no_storage_diff_drops_it_from_any_list() {
+        for extra in [&[][..], &["--show", "all"][..]] {
+            let args = run_with(&[extra, &["--no-storage-diff"][..]].concat()).unwrap();
+            assert!(!args.shows(ShowItem::StorageDiff), "{:?}", extra);
+        }
+    }
+
+    #[test]
+    fn show_duplicates_collapse() {
+        let args = run_with(&["--show", "events,events", "--show=events"]).unwrap();
+        assert_eq!(args.show.len(), 3);
+        assert_eq!(
+            args.shown().into_iter().collect::<Vec<_>>(),
+            vec![ShowItem::Events]
+        );
+    }
+
+    #[test]
+    fn show_all_expands_to_every_item() {
+        let args = run_with(&["--show", "ledger,all"]).unwrap();
+        assert_eq!(
+            args.shown().into_iter().collect::<Vec<_>>(),
+            vec![
+                ShowItem::Events,
+                ShowItem::Auth,
+                ShowItem::Ledger,
+                ShowItem::HostCalls,
+                ShowItem::Logs,
+                ShowItem::Diagnostics,
+                ShowItem::StorageDiff,
+            ]
+        );
+    }
+
+    #[test]
+    fn full_debug_expands_to_the_flags_it_lists() {
+        let mut args = run_with(&["--full-debug", "--show", "events"]).unwrap();
+        assert_eq!(
+            args.expand_full_debug(),
+            vec![
+                "--show all",
+                "--trace-limit 10000",
+                "--cost-table",
+                "--verbose"
+            ]
+        );
+        assert_eq!(args.shown().len(), ShowItem::value_variants().len() - 1);
+        assert_eq!(args.trace_limit, Some(FULL_DEBUG_TRACE_LIMIT));
+        assert!(args.cost_table && args.verbose);
+
+        // Flags given explicitly are kept and left out of the note.
+        let mut args = run_with(&[
+            "--full-debug",
+            "--trace-limit",
+            "50",
+            "--cost-table",
+            "--verbose",
+        ])
+        .unwrap();
+        assert_eq!(args.expand_full_debug(), vec!["--show all"]);
+        assert_eq!(args.trace_limit, Some(50));
+
+        // `--repeat` records no trace, so no limit is added.
+        let mut args = run_with(&["--full-debug", "--repeat", "2"]).unwrap();
+        assert!(!args
+            .expand_full_debug()
+            .iter()
+            .any(|flag| flag.starts_with("--trace-limit")));
+    }
+
+    #[test]
+    fn deprecated_show_flags_feed_the_same_set() {
+        let args = run_with(&["--show-events", "--show-ledger"]).unwrap();
+        assert_eq!(
+            args.shown().into_iter().collect::<Vec<_>>(),
+            vec![ShowItem::Events, ShowItem::Ledger, ShowItem::StorageDiff]
+        );
+
+        let args = run_with(&["--show", "auth", "--show-auth", "--show-events"]).unwrap();
+        assert_eq!(
+            args.shown().into_iter().collect::<Vec<_>>(),
+            vec![ShowItem::Events, ShowItem::Auth]
+        );
+    }
+
+    #[test]
+    fn show_rejects_unknown_items_with_a_suggestion() {
+        let Err(err) = run_with(&["--show", "events,evnts"]) else {
+            panic!("--show evnts should be rejected");
+        };
+        let err = err.to_string();
+        assert!(err.contains("'evnts'"), "{}", err);
+        assert!(err.contains("a similar value exists: 'events'"), "{}", err);
+
+        let Err(err) = run_with(&["--show", "host_calls"]) else {
+            panic!("--show host_calls should be rejected");
+        };
+        assert!(err.to_string().contains("'host-calls'"), "{}", err);
+    }
+
+    #[test]
+    fn ledger_flags_take_precedence_over_the_config() {
+        let config: crate::config::Config = toml::from_str(
+            "[ledger]\ntimestamp = 1700000000\nsequence = 100\nnetwork_passphrase = \"Config\"\nprotocol_version = 21\n",
+        )
+        .unwrap();
+
+        let mut args = run_with(&["--ledger-timestamp", "2024-01-01T00:00:00Z"]).unwrap();
+        args.merge_config(&config);
+        assert_eq!(
+            args.ledger_timestamp.as_deref(),
+            Some("2024-01-01T00:00:00Z")
+        );
+        assert_eq!(args.ledger_sequence, Some(100));
+        assert_eq!(args.network_passphrase.as_deref(), Some("Config"));
+        assert_eq!(args.protocol_version, Some(21));
+    }
 }
 
 #[derive(Parser)]
@@ -871,6 +1964,10 @@ pub struct CompareArgs {
     /// Repeatable. Useful for timestamps, sequence numbers, and similar metadata.
     #[arg(long, value_name = "FIELD")]
     pub ignore_field: Vec<String>,
+
+    /// Output format: pretty (default) or json
+    #[arg(long, value_enum, default_value_t = OutputFormat::Pretty)]
+    pub format: OutputFormat,
 }
 
 /// Arguments for the TUI dashboard subcommand
@@ -880,9 +1977,10 @@ pub struct TuiArgs {
     #[arg(short, long)]
     pub contract: PathBuf,
 
-    /// Function name to execute inside the TUI
+    /// Function name to execute inside the debugger dashboard. Without it,
+    /// the storage browser opens instead (needs the `tui` feature).
     #[arg(short, long)]
-    pub function: String,
+    pub function: Option<String>,
 
     /// Function arguments as JSON array (e.g., '["arg1", "arg2"]')
     #[arg(short, long)]
@@ -892,13 +1990,18 @@ pub struct TuiArgs {
     #[arg(short, long)]
     pub storage: Option<String>,
 
-    /// Set breakpoints at function names
-    #[arg(short, long)]
+    /// Set breakpoints at function names, each optionally followed by
+    /// `if <condition>` on its arguments
+    #[arg(short, long, value_name = "FUNCTION[ if CONDITION]")]
     pub breakpoint: Vec<String>,
 
     /// Network snapshot file to load before execution
     #[arg(long)]
     pub network_snapshot: Option<PathBuf>,
+
+    /// Storage file (as written by `run --export-storage`) to browse
+    #[arg(long, value_name = "FILE", conflicts_with = "storage")]
+    pub load_storage: Option<PathBuf>,
 }
 
 #[derive(Parser)]
@@ -938,8 +2041,17 @@ pub struct SymbolicArgs {
     pub contract: PathBuf,
 
     /// Function name to execute
-    #[arg(short, long)]
-    pub function: String,
+    #[arg(short, long, required_unless_present = "all_functions")]
+    pub function: Option<String>,
+
+    /// Explore every function in the contract spec, up to `--path-cap` inputs
+    /// each, and report pass/trap/error/skipped per function
+    #[arg(long, conflicts_with_all = ["function", "output"])]
+    pub all_functions: bool,
+
+    /// Write the `--all-functions` results as a JUnit XML report
+    #[arg(long, value_name = "FILE", requires = "all_functions")]
+    pub junit: Option<PathBuf>,
 
     /// Output file for the scenario TOML
     #[arg(short, long)]
@@ -1029,6 +2141,10 @@ pub struct ServerArgs {
     /// TLS private key file path (optional)
     #[arg(long)]
     pub tls_key: Option<PathBuf>,
+
+    /// Session name read-only observers pass to `attach --session`
+    #[arg(long, default_value = "default")]
+    pub session: String,
 }
 
 #[derive(Parser)]
@@ -1054,6 +2170,21 @@ pub struct RemoteArgs {
     pub args: Option<String>,
 }
 
+#[derive(Parser)]
+pub struct AttachArgs {
+    /// Server address (e.g., localhost:9229)
+    #[arg(long)]
+    pub url: String,
+
+    /// Session to attach to, as named by `server --session`
+    #[arg(long, default_value = "default")]
+    pub session: String,
+
+    /// Authentication token (if required by server)
+    #[arg(short, long)]
+    pub token: Option<String>,
+}
+
 #[derive(Parser)]
 pub struct AnalyzeArgs {
     /// Path to the contract WASM file
@@ -1064,11 +2195,12 @@ pub struct AnalyzeArgs {
     #[arg(short, long)]
     pub function: Option<String>,
 
-    /// Function arguments as JSON array for dynamic analysis (optional)
+    /// Function arguments as JSON array for dynamic analysis, or @FILE
+    /// (optional)
     #[arg(short, long)]
     pub args: Option<String>,
 
-    /// Initial storage state as JSON object (optional)
+    /// Initial storage state as JSON object, or @FILE (optional)
     #[arg(short, long)]
     pub storage: Option<String>,
 
@@ -1111,4 +2243,35 @@ pub struct ScenarioArgs {
     /// Use 0 to disable the timeout entirely.
     #[arg(long)]
     pub timeout: Option<u64>,
+
+    /// Whether failed step expectations fail the scenario (strict) or are only reported (warn).
+    /// Steps can override this with `expect_mode`.
+    #[arg(long, value_enum, default_value_t = ExpectMode::Strict)]
+    pub expect_mode: ExpectMode,
+
+    /// Print a per-function instruction cost table across all scenario steps
+    #[arg(long)]
+    pub cost_table: bool,
+
+    /// Print the N most accessed storage keys (default 10) across all scenario steps
+    #[arg(long, value_name = "N", num_args = 0..=1, default_missing_value = "10")]
+    pub storage_heatmap: Option<usize>,
+
+    /// Restrict the storage heatmap to keys matching a pattern (repeatable).
+    /// Same syntax as `run --storage-filter`.
+    #[arg(long, value_name = "PATTERN")]
+    pub storage_filter: Vec<String>,
+
+    /// Write events from every step to FILE as NDJSON in the RPC `getEvents` event shape.
+    /// Each step counts as the next transaction, so ids increase across steps.
+    #[arg(long, value_name = "FILE")]
+    pub events_ndjson: Option<PathBuf>,
+
+    /// Run the scenario inside a named environment (see `soroban-debug env`)
+    #[arg(long, value_name = "NAME")]
+    pub env: Option<String>,
+
+    /// Persist the post-scenario state back into the --env environment when every step passes
+    #[arg(long, requires = "env")]
+    pub commit: bool,
 }
@@ -1,24 +1,38 @@
+use crate::analyzer::smoke::{SmokeReport, SmokeRunner, SmokeStatus};
 use crate::analyzer::symbolic::SymbolicConfig;
 use crate::analyzer::upgrade::{CompatibilityReport, ExecutionDiff, UpgradeAnalyzer};
 use crate::analyzer::{security::SecurityAnalyzer, symbolic::SymbolicAnalyzer};
 use crate::cli::args::{
-    AnalyzeArgs, CompareArgs, HistoryPruneArgs, InspectArgs, InteractiveArgs, OptimizeArgs,
-    OutputFormat, ProfileArgs, RemoteArgs, ReplArgs, ReplayArgs, RunArgs, ScenarioArgs, ServerArgs,
-    SymbolicArgs, SymbolicProfile, TuiArgs, UpgradeCheckArgs, Verbosity,
+    AnalyzeArgs, AttachArgs, CacheAction, CacheArgs, Cli, Commands, CompareArgs, ConfigAction,
+    ConfigArgs, EnvAction, EnvArgs, ExpectMode, HistoryAction, HistoryArgs, HistoryPruneArgs,
+    ImportTxArgs, InspectArgs, InteractiveArgs, KeyArgs, KeyDurability, OptimizeArgs, OutputFormat,
+    PresetAction, PresetArgs, ProfileArgs, RemoteArgs, ReplArgs, ReplayArgs, RunArgs, ScenarioArgs,
+    ServerArgs, ShowItem, SnapshotAction, SnapshotArgs, SnapshotFormat, StorageOrder, SymbolicArgs,
+    SymbolicProfile, TopicChange, TuiArgs, UpgradeCheckArgs, Verbosity,
 };
 use crate::debugger::engine::DebuggerEngine;
 use crate::debugger::instruction_pointer::StepMode;
+use crate::environments::{Environment, EnvironmentStore, NewEnvironment};
 use crate::history::{HistoryManager, RunHistory};
 use crate::inspector::events::{ContractEvent, EventInspector};
 use crate::logging;
 use crate::output::OutputWriter;
+use crate::presets::{run_flags, InvocationPlan, PresetStore};
 use crate::repeat::RepeatRunner;
 use crate::repl::ReplConfig;
-use crate::runtime::executor::ContractExecutor;
+use crate::runtime::executor::{ContractExecutor, ExecutionOutcome};
+use crate::runtime::loader::{constructor_params, missing_constructor_args, CONSTRUCTOR};
+use crate::simulator::code_pin::{self, CodeFingerprint, CodeMatch, CodeSource};
 use crate::simulator::SnapshotLoader;
+use crate::ui::break_shell::{BreakAction, BreakShell};
 use crate::ui::formatter::Formatter;
-use crate::ui::{run_dashboard, DebuggerUI};
+use crate::ui::{run_browser, run_dashboard, DebuggerUI};
+use crate::utils::cargo::{CargoContract, CargoRequest};
+use crate::utils::json_path::{render_extracted, JsonPath};
+use crate::utils::spec_cache::{SpecCache, SpecCacheStatus};
+use crate::warnings::{WarningCategory, WarningSummary};
 use crate::{DebuggerError, Result};
+use clap::{CommandFactory, FromArgMatches};
 use miette::WrapErr;
 use std::fs;
 
@@ -40,6 +54,13 @@ fn print_warning(message: impl AsRef<str>) {
     }
 }
 
+/// Print a warning and collect it for the end-of-run summary.
+fn report_warning(category: WarningCategory, message: impl Into<String>) {
+    let message = message.into();
+    print_warning(&message);
+    crate::warnings::record(category, message);
+}
+
 /// Print the final contract return value — always shown regardless of verbosity.
 fn print_result(message: impl AsRef<str>) {
     if !Formatter::is_quiet() {
@@ -75,6 +96,8 @@ struct DynamicAnalysisMetadata {
 struct AnalyzeCommandOutput {
     findings: Vec<crate::analyzer::security::SecurityFinding>,
     dynamic_analysis: Option<DynamicAnalysisMetadata>,
+    /// Error enum cases the contract spec declares it can return.
+    errors: Vec<crate::utils::wasm::CustomError>,
     warnings: Vec<String>,
 }
 
@@ -222,6 +245,14 @@ fn render_security_report(output: &AnalyzeCommandOutput) -> String {
         lines.push(String::new());
     }
 
+    if !output.errors.is_empty() {
+        lines.push("Possible errors:".to_string());
+        for error in &output.errors {
+            lines.push(format!("  {} (#{})", error.name, error.code));
+        }
+        lines.push(String::new());
+    }
+
     if !output.warnings.is_empty() {
         lines.push("Warnings:".to_string());
         for warning in &output.warnings {
@@ -411,24 +442,221 @@ fn parse_step_mode(mode: &str) -> StepMode {
     }
 }
 
+/// Display the per-function instruction cost table
+fn display_cost_table(table: &crate::inspector::cost_table::CostTable) {
+    print_info("\n--- Cost Table ---");
+    for line in table.render().lines() {
+        print_info(line);
+    }
+}
+
+/// Print the grouped warning summary and enforce `--max-warnings`.
+///
+/// Exits with [`crate::warnings::MAX_WARNINGS_EXIT_CODE`] when the limit is exceeded.
+fn finish_warnings(summary: &WarningSummary, args: &RunArgs) {
+    if !summary.is_empty() && !args.is_json_output() {
+        print_warning("\n--- Warnings ---");
+        for line in summary.render().lines() {
+            print_warning(line);
+        }
+    }
+    if summary.exceeds(args.max_warnings) {
+        eprintln!(
+            "{}",
+            Formatter::error(format!(
+                "{} warning(s) exceed --max-warnings {}",
+                summary.total,
+                args.max_warnings.unwrap_or_default()
+            ))
+        );
+        std::process::exit(crate::warnings::MAX_WARNINGS_EXIT_CODE);
+    }
+}
+
+/// Parse `--storage-filter` patterns for the storage heatmap and write history
+fn heatmap_filter(patterns: &[String]) -> Result<crate::inspector::storage::StorageFilter> {
+    crate::inspector::storage::StorageFilter::new(patterns)
+        .map_err(|e| DebuggerError::StorageError(format!("Invalid storage filter: {}", e)).into())
+}
+
+/// Check `--breakpoint` specs, and those from `debug.breakpoints`, before
+/// anything runs, so a bad condition is reported at startup.
+fn validate_breakpoints(specs: &[String]) -> Result<()> {
+    for spec in specs {
+        crate::debugger::breakpoint::BreakpointSpec::parse(spec)
+            .map_err(DebuggerError::BreakpointError)?;
+    }
+    Ok(())
+}
+
+/// The `--break-on-write` patterns as write breakpoints, `None` when there
+/// are none.
+fn write_breakpoints(
+    patterns: &[String],
+) -> Result<Option<crate::debugger::breakpoint::WriteBreakpoints>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    crate::debugger::breakpoint::WriteBreakpoints::new(patterns)
+        .map(Some)
+        .map_err(|e| DebuggerError::BreakpointError(e).into())
+}
+
+/// The `--break-on-event` patterns as event breakpoints, `None` when there
+/// are none.
+fn event_breakpoints(
+    patterns: &[String],
+    contract_only: bool,
+) -> Result<Option<crate::debugger::breakpoint::EventBreakpoints>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    crate::debugger::breakpoint::EventBreakpoints::new(patterns, contract_only)
+        .map(Some)
+        .map_err(|e| DebuggerError::BreakpointError(e).into())
+}
+
+/// The `--report-host-fn` names as the host functions to watch, `None`
+/// when there are none.
+fn host_fn_watch(names: &[String]) -> Result<Option<crate::debugger::host_fn::HostFnWatch>> {
+    if names.is_empty() {
+        return Ok(None);
+    }
+    crate::debugger::host_fn::HostFnWatch::new(names)
+        .map(Some)
+        .map_err(|e| DebuggerError::BreakpointError(e).into())
+}
+
+/// `wasm_bytes` with the calls `watched` catches logged, or as they are
+/// when no host function is watched.
+fn instrument_host_fns(
+    wasm_bytes: Vec<u8>,
+    watched: Option<&crate::debugger::host_fn::HostFnWatch>,
+) -> Result<Vec<u8>> {
+    let Some(watched) = watched else {
+        return Ok(wasm_bytes);
+    };
+    let instrumented = watched.instrument(&wasm_bytes).map_err(|e| {
+        DebuggerError::BreakpointError(format!("Cannot watch host functions: {}", e))
+    })?;
+    print_verbose(format!(
+        "Watching host functions: {}",
+        watched.names().collect::<Vec<_>>().join(", ")
+    ));
+    Ok(instrumented)
+}
+
+/// Print what each write and event breakpoint the last call hit reports,
+/// then the watched host functions it called.
+fn print_breakpoint_stops(engine: &DebuggerEngine) {
+    let names = engine.executor().contract_names();
+    let reports = engine
+        .write_hits()
+        .iter()
+        .map(|mutation| crate::debugger::breakpoint::WriteBreakpoints::describe(mutation, &names))
+        .chain(
+            engine
+                .event_hits()
+                .iter()
+                .map(crate::debugger::breakpoint::EventBreakpoints::describe),
+        );
+    for report in reports {
+        let mut lines = report.lines();
+        if let Some(first) = lines.next() {
+            print_warning(first);
+        }
+        lines.for_each(print_info);
+    }
+    // The calls are reported once the call has returned; they do not stop.
+    for call in engine.host_fn_calls() {
+        crate::debugger::host_fn::HostFnWatch::describe(call)
+            .lines()
+            .for_each(print_info);
+    }
+}
+
+/// Prompt after a call that was stepped into or over from its breakpoint,
+/// or that hit write or event breakpoints.
+fn break_after_call(
+    shell: &mut BreakShell,
+    engine: &mut DebuggerEngine,
+    step: Option<bool>,
+) -> Result<BreakAction> {
+    if let Some(into) = step {
+        return shell.step(engine, into);
+    }
+    if !engine.write_hits().is_empty() || !engine.event_hits().is_empty() {
+        shell.after_call(engine)
+    } else {
+        Ok(BreakAction::Continue)
+    }
+}
+
+fn run_aborted(function: &str) -> miette::Report {
+    DebuggerError::ExecutionError(format!("Run aborted at a breakpoint in {}", function)).into()
+}
+
+/// Display the most accessed storage keys
+fn display_storage_heatmap(
+    heatmap: &crate::inspector::storage_heatmap::StorageHeatmap,
+    top: usize,
+    filter: &crate::inspector::storage::StorageFilter,
+) {
+    print_info("\n--- Storage Heatmap ---");
+    for line in heatmap.render(top, filter).lines() {
+        print_info(line);
+    }
+}
+
+/// Display configured mocks with their exhaustion policy and served values
+fn display_mock_summary(mocks: &[crate::runtime::mocking::MockSummary]) {
+    if mocks.is_empty() {
+        return;
+    }
+    print_info("\n--- Mocks ---");
+    for mock in mocks {
+        print_info(format!(
+            "{}.{} [{}] values: {} | served: {}",
+            mock.contract_id,
+            mock.function,
+            mock.policy,
+            mock.values.join(", "),
+            if mock.served.is_empty() {
+                "(none)".to_string()
+            } else {
+                mock.served.join(", ")
+            }
+        ));
+        if mock.exhausted_calls > 0 {
+            report_warning(
+                WarningCategory::Mock,
+                format!(
+                    "{}.{} ran out of values; {} call(s) failed",
+                    mock.contract_id, mock.function, mock.exhausted_calls
+                ),
+            );
+        }
+    }
+}
+
 /// Display mock call log
 fn display_mock_call_log(calls: &[crate::runtime::executor::MockCallEntry]) {
     if calls.is_empty() {
         return;
     }
     print_info("\n--- Mock Contract Calls ---");
-    for (i, entry) in calls.iter().enumerate() {
+    for entry in calls {
         let status = if entry.mocked { "MOCKED" } else { "REAL" };
         print_info(format!(
             "{}. {} {} (args: {}) -> {}",
-            i + 1,
+            entry.sequence + 1,
             status,
             entry.function,
             entry.args_count,
-            if entry.returned.is_some() {
-                "returned"
-            } else {
-                "pending"
+            match (&entry.returned, entry.exhausted) {
+                (Some(value), _) => value.clone(),
+                (None, true) => "exhausted".to_string(),
+                (None, false) => "pending".to_string(),
             }
         ));
     }
@@ -509,9 +737,78 @@ fn run_batch(args: &RunArgs, batch_file: &std::path::Path) -> Result<()> {
     Ok(())
 }
 
+/// The calls given with `--call` or `--calls-file`, in order.
+fn run_calls(args: &RunArgs) -> Result<Vec<crate::call_sequence::CallStep>> {
+    use crate::call_sequence::CallStep;
+    match &args.calls_file {
+        Some(path) => CallStep::load_file(path),
+        None => args.call.iter().map(|spec| CallStep::parse(spec)).collect(),
+    }
+}
+
+/// Print call `index` of a sequence and the events it emitted, and return
+/// it for the JSON output.
+fn report_call(
+    args: &RunArgs,
+    index: usize,
+    total: usize,
+    call_args: Option<&str>,
+    outcome: &ExecutionOutcome,
+) -> crate::call_sequence::CallOutcome {
+    let events = outcome.events.clone();
+    let filter = if !args.event_filter.is_empty() {
+        Some(args.event_filter.join(","))
+    } else {
+        args.filter_topic.clone()
+    };
+    let show_events = args.shown().contains(&ShowItem::Events) || filter.is_some();
+    let events = match filter {
+        Some(filter) => EventInspector::filter_events(&events, &filter),
+        None => events,
+    };
+    if !args.is_json_output() {
+        print_result(format!(
+            "Call {}/{}: {} -> {}",
+            index, total, outcome.function, outcome.display
+        ));
+        if show_events {
+            for line in EventInspector::format_events(&events) {
+                print_info(format!("  {}", line));
+            }
+        }
+    }
+    crate::call_sequence::CallOutcome {
+        index,
+        function: outcome.function.clone(),
+        args: call_args.map(str::to_string),
+        result: outcome.display.clone(),
+        return_value: outcome.return_value.clone(),
+        events: if show_events { events } else { Vec::new() },
+    }
+}
+
 /// Execute the run command.
 #[tracing::instrument(skip_all, fields(contract = ?args.contract, function = args.function))]
-pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
+pub fn run(mut args: RunArgs, mut verbosity: Verbosity) -> Result<()> {
+    crate::utils::json_input::resolve_json_flags(&mut [
+        ("--args", &mut args.args),
+        ("--storage", &mut args.storage),
+        ("--instance-storage", &mut args.instance_storage),
+        ("--persistent-storage", &mut args.persistent_storage),
+        ("--temporary-storage", &mut args.temporary_storage),
+        ("--constructor-args", &mut args.constructor_args),
+    ])?;
+    if let Some(xdr) = args.args_xdr.take() {
+        args.args = Some(
+            crate::utils::ArgumentParser::xdr_args_to_json(&xdr)
+                .map_err(|e| DebuggerError::InvalidArguments(e.to_string()))?,
+        );
+    }
+    validate_breakpoints(&args.breakpoint)?;
+    let watched_writes = write_breakpoints(&args.break_on_write)?;
+    let watched_events = event_breakpoints(&args.break_on_event, args.break_contract_events_only)?;
+    let watched_host_fns = host_fn_watch(&args.report_host_fn)?;
+
     // Start debug server if requested
     if args.server {
         return server(ServerArgs {
@@ -519,6 +816,7 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
             token: args.token,
             tls_cert: args.tls_cert,
             tls_key: args.tls_key,
+            session: "default".to_string(),
         });
     }
 
@@ -536,6 +834,35 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
         );
     }
 
+    let cargo_contract = resolve_cargo_contract(&args)?;
+    if let Some(resolved) = &cargo_contract {
+        args.contract = Some(resolved.wasm_path.clone());
+    }
+
+    if let Some(name) = &args.save_preset {
+        let plan = InvocationPlan::resolve(&args)?;
+        let path = PresetStore::open_default()?.save(name, &plan)?;
+        print_success(format!("Saved preset '{}' to {:?}", name, path));
+    }
+
+    if args.full_debug {
+        let expanded = args.expand_full_debug();
+        if verbosity == Verbosity::Normal {
+            verbosity = Verbosity::Verbose;
+            if !args.is_json_output() {
+                Formatter::set_verbosity(2);
+            }
+        }
+        if !expanded.is_empty() {
+            print_info(format!("--full-debug expanded to: {}", expanded.join(" ")));
+        }
+    }
+
+    let mut environment = match args.env.clone() {
+        Some(name) => Some(enter_environment(&mut args, &name)?),
+        None => None,
+    };
+
     // Initialize output writer
     let mut output_writer = OutputWriter::new(args.save_output.as_deref(), args.append)?;
 
@@ -548,6 +875,23 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
         return run_dry_run(&args);
     }
 
+    if args.smoke {
+        return run_smoke(&args);
+    }
+
+    // The last call of a sequence is the run's main call; the ones before it
+    // run first, against the same state.
+    let mut earlier_calls = run_calls(&args)?;
+    if let Some(last) = earlier_calls.pop() {
+        args.function = Some(last.function);
+        args.args = last.args;
+    }
+    let total_calls = if args.call.is_empty() && args.calls_file.is_none() {
+        0
+    } else {
+        earlier_calls.len() + 1
+    };
+
     let contract = args
         .contract
         .as_ref()
@@ -563,8 +907,8 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
 
     let wasm_file = crate::utils::wasm::load_wasm(contract)
         .with_context(|| format!("Failed to read WASM file: {:?}", contract))?;
-    let wasm_bytes = wasm_file.bytes;
-    let wasm_hash = wasm_file.sha256_hash;
+    let mut wasm_bytes = wasm_file.bytes;
+    let mut wasm_hash = wasm_file.sha256_hash;
 
     if let Some(expected) = &args.expected_hash {
         if expected.to_lowercase() != wasm_hash {
@@ -592,25 +936,92 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
             print_verbose("Checksum verified ✓");
             output_writer.write("Checksum verified ✓")?;
         }
+    } else if cargo_contract.is_some() {
+        // A cargo-resolved WASM changes with every build, so always say which one ran.
+        print_info(format!("SHA-256: {}", wasm_hash));
+        output_writer.write(&format!("SHA-256: {}", wasm_hash))?;
     }
 
     logging::log_contract_loaded(wasm_bytes.len());
 
-    if let Some(snapshot_path) = &args.network_snapshot {
+    let snapshot = match &args.network_snapshot {
+        Some(snapshot_path) => Some(SnapshotLoader::from_file(snapshot_path)?),
+        None => None,
+    };
+    let code_fingerprint = match (&args.network_snapshot, &snapshot) {
+        (Some(snapshot_path), Some(loader)) => Some(pin_snapshot_code(
+            &args,
+            snapshot_path,
+            loader.snapshot(),
+            &mut wasm_bytes,
+            &mut wasm_hash,
+        )?),
+        _ => None,
+    };
+
+    let result_cache = if args.cache_results && !args.no_cache {
+        open_result_cache(&args, &wasm_bytes)?
+    } else {
+        None
+    };
+    if let Some((cache, key)) = &result_cache {
+        if let Some(cached) = cache.load(key) {
+            return show_cached_result(&args, cached);
+        }
+    }
+    let wasm_bytes = instrument_host_fns(wasm_bytes, watched_host_fns.as_ref())?;
+
+    if let (Some(snapshot_path), Some(loader)) = (&args.network_snapshot, &snapshot) {
         print_info(format!("\nLoading network snapshot: {:?}", snapshot_path));
         output_writer.write(&format!("Loading network snapshot: {:?}", snapshot_path))?;
         logging::log_loading_snapshot(&snapshot_path.to_string_lossy());
-        let loader = SnapshotLoader::from_file(snapshot_path)?;
         let loaded_snapshot = loader.apply_to_environment()?;
         output_writer.write(&loaded_snapshot.format_summary())?;
         logging::log_display(loaded_snapshot.format_summary(), logging::LogLevel::Info);
     }
+    if let Some(code) = &code_fingerprint {
+        print_info(format!("Code: {}", code));
+        output_writer.write(&format!("Code: {}", code))?;
+    }
 
-    let parsed_args = if let Some(args_json) = &args.args {
-        Some(parse_args(args_json)?)
-    } else {
-        None
+    let conversions = crate::utils::conversions::Conversions::from_config(&args.conversions)?;
+    // With a spec, literals are converted only where it expects an integer.
+    let artifacts = crate::utils::artifact_cache::artifacts_for(&wasm_bytes).ok();
+    let convert_args = |function: Option<&str>, args_json: &str| -> Result<String> {
+        let spec = artifacts
+            .as_ref()
+            .zip(function)
+            .and_then(|(artifacts, function)| {
+                let signature = artifacts.signature(function)?;
+                Some(crate::utils::conversions::ArgumentSpec {
+                    params: &signature.params,
+                    types: &artifacts.types,
+                })
+            });
+        let (converted, applied) = conversions.apply(&parse_args(args_json)?, spec.as_ref())?;
+        if args.verbose || verbosity == Verbosity::Verbose {
+            for conversion in &applied {
+                print_verbose(format!("Converted argument: {}", conversion));
+            }
+        }
+        Ok(converted)
     };
+    let earlier_calls = earlier_calls
+        .into_iter()
+        .map(|step| {
+            let converted = step
+                .args
+                .as_deref()
+                .map(|json| convert_args(Some(step.function.as_str()), json))
+                .transpose()?;
+            Ok((step.function, converted))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let parsed_args = args
+        .args
+        .as_deref()
+        .map(|json| convert_args(args.function.as_deref(), json))
+        .transpose()?;
 
     let mut initial_storage = if let Some(storage_json) = &args.storage {
         Some(parse_storage(storage_json)?)
@@ -628,35 +1039,209 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
         })?);
     }
 
+    let initial_storage = with_durable_storage(initial_storage, &args)?;
+    let initial_storage = with_storage_file(initial_storage, args.storage_file.as_deref())?;
+
+    let prng_seed = args
+        .seed
+        .unwrap_or_else(crate::runtime::executor::random_prng_seed);
+    let ledger = crate::runtime::LedgerOverrides {
+        timestamp: args
+            .ledger_timestamp
+            .as_deref()
+            .map(|text| {
+                crate::utils::time::parse_timepoint(text).map_err(|e| {
+                    DebuggerError::InvalidFlag(format!("--ledger-timestamp {}", e))
+                })
+            })
+            .transpose()?,
+        sequence: args.ledger_sequence,
+        network_passphrase: args.network_passphrase.clone(),
+        protocol_version: args
+            .protocol_version
+            .map(crate::runtime::loader::check_protocol_version)
+            .transpose()?,
+    };
+    let budget_limits = crate::runtime::limits::requested_limits(
+        args.limits.as_deref(),
+        args.cpu_limit,
+        args.mem_limit,
+    )?;
+
     if let Some(n) = args.repeat {
         logging::log_repeat_execution(function, n as usize);
-        let runner = RepeatRunner::new(wasm_bytes, args.breakpoint, initial_storage);
+        let json_output = args.is_json_output();
+        let runner = RepeatRunner::new(wasm_bytes, args.breakpoint.clone(), initial_storage)
+            .with_prng_seed(prng_seed, args.vary_seed)
+            .with_ledger(ledger)
+            .with_fresh_state(args.fresh_state)
+            .with_budget_limits(budget_limits);
         let stats = runner.run(function, parsed_args.as_deref(), n)?;
-        stats.display();
+        if json_output {
+            stats.log();
+            let mut output = serde_json::json!({ "repeat": stats.to_json() });
+            if args.cost_table {
+                output["cost_table"] = serde_json::to_value(&stats.cost_table).unwrap_or_default();
+            }
+            if args.storage_heatmap.is_some() {
+                let filter = heatmap_filter(&args.storage_filter)?;
+                output["storage_heatmap"] = stats.storage_heatmap.to_json(&filter);
+            }
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&output).map_err(|e| {
+                    DebuggerError::FileError(format!("Failed to serialize repeat summary: {}", e))
+                })?
+            );
+        } else {
+            stats.display();
+            if args.cost_table {
+                display_cost_table(&stats.cost_table);
+            }
+            if let Some(top) = args.storage_heatmap {
+                let filter = heatmap_filter(&args.storage_filter)?;
+                display_storage_heatmap(&stats.storage_heatmap, top, &filter);
+            }
+        }
+        let warnings = WarningSummary::new(crate::warnings::collected(), &args.allow_warning);
+        finish_warnings(&warnings, &args);
         return Ok(());
     }
 
     print_info("\nStarting debugger...");
     output_writer.write("Starting debugger...")?;
-    print_info(format!("Function: {}", function));
-    output_writer.write(&format!("Function: {}", function))?;
+    if total_calls > 0 {
+        let names: Vec<&str> = earlier_calls
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .chain(std::iter::once(function.as_str()))
+            .collect();
+        print_info(format!("Calls: {}", names.join(", ")));
+        output_writer.write(&format!("Calls: {}", names.join(", ")))?;
+    } else {
+        print_info(format!("Function: {}", function));
+        output_writer.write(&format!("Function: {}", function))?;
+    }
     if let Some(ref parsed) = parsed_args {
         print_info(format!("Arguments: {}", parsed));
         output_writer.write(&format!("Arguments: {}", parsed))?;
     }
+    print_info(format!("PRNG seed: {}", prng_seed));
+    output_writer.write(&format!("PRNG seed: {}", prng_seed))?;
     logging::log_execution_start(function, parsed_args.as_deref());
 
-    let mut executor = ContractExecutor::new(wasm_bytes.clone())?;
+    let mut executor = if let Some(state_path) = &args.load_state {
+        let state = crate::runtime::SessionState::read_file(state_path)?;
+        let mut executor = ContractExecutor::from_state(wasm_bytes.clone(), state)?;
+        // The saved state carries its own ledger; the flags still win.
+        executor.apply_ledger_overrides(&ledger);
+        print_info(format!("Resumed state from {:?}", state_path));
+        output_writer.write(&format!("Resumed state from {:?}", state_path))?;
+        executor
+    } else {
+        match constructor_params(&wasm_bytes)? {
+            Some(params) if args.constructor_args.is_none() && !params.is_empty() => {
+                return Err(missing_constructor_args(&params).into());
+            }
+            Some(_) => ContractExecutor::unconstructed(wasm_bytes.clone(), &ledger)?,
+            None if args.constructor_args.is_some() => {
+                return Err(DebuggerError::InvalidArguments(format!(
+                    "--constructor-args was given, but the contract declares no {}",
+                    CONSTRUCTOR
+                ))
+                .into());
+            }
+            None => ContractExecutor::with_ledger(wasm_bytes.clone(), &ledger)?,
+        }
+    };
     executor.set_timeout(args.timeout);
+    // Alerts name the frames that wrote their key, which takes the history.
+    if args.track_writes || !args.alert_on_change.is_empty() {
+        executor.track_writes();
+    }
+    executor.set_trace_ring(crate::runtime::ring::TraceRing::new(
+        args.ring_size,
+        Some(std::time::Duration::from_secs(args.ring_window)),
+    ));
+    if args.trace_sample.is_some() || args.trace_limit.is_some() {
+        executor.set_trace_policy(crate::runtime::env::TracePolicy {
+            sample_rate: args.trace_sample.unwrap_or(1),
+            limit: args.trace_limit,
+            watched: args
+                .alert_on_change
+                .iter()
+                .filter_map(|spec| crate::inspector::storage::AlertRule::parse(spec).ok())
+                .filter(|rule| !rule.excludes())
+                .map(|rule| rule.pattern().clone())
+                .collect(),
+        });
+    }
 
-    if let Some(storage) = initial_storage {
-        executor.set_initial_storage(storage)?;
+    if let Some(environment) = &environment {
+        if let Some(state) = environment.load_state(&wasm_hash)? {
+            executor.restore_env_snapshot(&state)?;
+            // The restored state carries its own ledger; the flags still win.
+            executor.apply_ledger_overrides(&ledger);
+            print_info(format!(
+                "Restored state of environment '{}' ({} commit(s))",
+                environment.name(),
+                environment.manifest().commits
+            ));
+        }
+    }
+    print_verbose(format!(
+        "Ledger timestamp: {}",
+        crate::utils::time::format_timepoint(executor.ledger_timestamp())
+    ));
+    print_verbose(format!("Ledger sequence: {}", executor.ledger_sequence()));
+    let protocol_version = executor.protocol_version();
+    print_info(format!("Protocol version: {}", protocol_version));
+    output_writer.write(&format!("Protocol version: {}", protocol_version))?;
+    if let Some(passphrase) = &ledger.network_passphrase {
+        print_verbose(format!("Network passphrase: {}", passphrase));
+    }
+    if let (true, Some(snapshot_path), Some(loader)) = (
+        args.register_all_from_snapshot,
+        &args.network_snapshot,
+        &snapshot,
+    ) {
+        let registered =
+            register_snapshot_contracts(&mut executor, snapshot_path, loader.snapshot())?;
+        print_info(format!(
+            "Registered {} contract(s) from the snapshot: {}",
+            registered.len(),
+            registered.join(", ")
+        ));
+        output_writer.write(&format!(
+            "Registered {} contract(s) from the snapshot",
+            registered.len()
+        ))?;
+    }
+    for (name, address) in link_contracts(&mut executor, &args.link)? {
+        print_info(format!("Linked contract {}: {}", name, address));
+        output_writer.write(&format!("Linked contract {}: {}", name, address))?;
     }
     if !args.mock.is_empty() {
         executor.set_mock_specs(&args.mock)?;
     }
+    let source_account = match &args.source_account {
+        Some(source) => crate::utils::ledger_key::parse_source_account(source)?,
+        None => crate::runtime::executor::random_source_account(),
+    };
+    executor.set_source_account(&source_account)?;
+    print_verbose(format!("Source account: {}", source_account));
+    executor.set_prng_seed(prng_seed)?;
 
     let mut engine = DebuggerEngine::new(executor, args.breakpoint.clone());
+    if let Some(watched) = watched_writes {
+        engine.set_write_breakpoints(watched);
+    }
+    if let Some(watched) = watched_events {
+        engine.set_event_breakpoints(watched);
+    }
+    if let Some(watched) = watched_host_fns {
+        engine.set_watched_host_fns(watched);
+    }
 
     // Server mode is handled at the beginning of the function
     // Remote mode is not yet implemented
@@ -668,10 +1253,35 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
         .into());
     }
 
+    if args.export_auth.is_some() || args.trace_output.is_some() {
+        // Recording mode lets every require_auth pass and records what it needed.
+        engine.executor().enable_mock_all_auths();
+    }
+
     // Execute locally with debugging
     if !args.is_json_output() {
         println!("\n--- Execution Start ---\n");
     }
+    let constructor_record = if engine.executor().is_constructed() {
+        None
+    } else {
+        Some(run_constructor(&args, &mut engine, prng_seed)?)
+    };
+    // The host clears its events when the next call starts, so the
+    // constructor's are kept to be shown ahead of the call's.
+    let constructor_events = match constructor_record {
+        Some(_) => engine.executor().get_events()?,
+        None => Vec::new(),
+    };
+    if let Some(storage) = initial_storage {
+        engine.executor_mut().set_initial_storage(storage)?;
+    }
+    // The limits cover the calls, not the constructor or storage seeding.
+    if let Some(limits) = budget_limits {
+        engine.executor_mut().set_budget_limits(limits);
+        print_info(format!("Budget limits: {}", limits));
+        output_writer.write(&format!("Budget limits: {}", limits))?;
+    }
     if args.instruction_debug {
         print_info("Enabling instruction-level debugging...");
         engine.enable_instruction_debug(&wasm_bytes)?;
@@ -691,13 +1301,151 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
     print_info("\n--- Execution Start ---\n");
     output_writer.write("\n--- Execution Start ---\n")?;
     let storage_before = engine.executor().get_storage_snapshot()?;
-    let result = engine.execute(function, parsed_args.as_deref())?;
+    let instance_before = engine.executor().instance_storage_snapshot();
+    // The storage listing counts the entries the run wrote apart from the rest.
+    let list_storage =
+        !args.storage_filter.is_empty() || !args.durability.is_empty() || args.raw_xdr;
+    let entries_before = engine.executor().storage_entries();
+    let started = std::time::Instant::now();
+    let mut call_outcomes = Vec::new();
+    let calls = earlier_calls
+        .iter()
+        .map(|(name, call_args)| (name.as_str(), call_args.as_deref()))
+        .chain(std::iter::once((function.as_str(), parsed_args.as_deref())));
+    let mut last_outcome = None;
+    let mut write_hits = Vec::new();
+    let mut event_hits = Vec::new();
+    let mut host_fn_calls = Vec::new();
+    // Breakpoints prompt on a terminal; elsewhere each stop is printed and
+    // the run goes on. JSON output lists the stops in the result instead.
+    let mut break_shell = if args.is_json_output() {
+        None
+    } else {
+        use std::io::IsTerminal;
+        let interactive = !args.non_interactive
+            && std::io::stdin().is_terminal()
+            && std::io::stdout().is_terminal();
+        Some(BreakShell::new(interactive)?)
+    };
+    for (index, (call_function, call_args)) in calls.enumerate() {
+        let mut step = None;
+        let mut storage_before_call = None;
+        let result = match break_shell.as_mut() {
+            None => engine.execute_detailed(call_function, call_args),
+            Some(shell) => {
+                if engine.breakpoint_hit(call_function, call_args) {
+                    engine.prepare_breakpoint_stop(call_function, call_args);
+                    match shell.at_entry(&mut engine)? {
+                        BreakAction::Continue => {}
+                        BreakAction::Abort => return Err(run_aborted(call_function)),
+                        BreakAction::StepIn => step = Some(true),
+                        BreakAction::StepOver => step = Some(false),
+                    }
+                }
+                if shell.is_interactive() {
+                    engine.walk_next_calls();
+                    storage_before_call = Some(engine.executor().snapshot_storage()?);
+                }
+                engine.execute_detailed_without_breakpoints(call_function, call_args)
+            }
+        };
+        let outcome = match result {
+            Ok(outcome) => outcome,
+            Err(e) => {
+                // A failed call writes nothing, but its events can still stop.
+                if let Some(shell) = break_shell.as_mut() {
+                    print_breakpoint_stops(&engine);
+                    // The run fails either way; the prompt is for looking
+                    // around first.
+                    break_after_call(shell, &mut engine, step)?;
+                }
+                report_call_failure(&args, &engine, prng_seed);
+                if total_calls > 0 {
+                    return Err(crate::call_sequence::call_failed(
+                        index + 1,
+                        total_calls,
+                        call_function,
+                        e,
+                    ));
+                }
+                return Err(e);
+            }
+        };
+        if total_calls > 0 {
+            let call = report_call(&args, index + 1, total_calls, call_args, &outcome);
+            output_writer.write(&format!(
+                "Call {}/{}: {} -> {}",
+                call.index, total_calls, call.function, call.result
+            ))?;
+            call_outcomes.push(call);
+        }
+        if let Some(shell) = break_shell.as_mut() {
+            print_breakpoint_stops(&engine);
+            if break_after_call(shell, &mut engine, step)? == BreakAction::Abort {
+                if let Some(snapshot) = &storage_before_call {
+                    engine.executor_mut().restore_storage(snapshot)?;
+                }
+                return Err(run_aborted(call_function));
+            }
+        }
+        write_hits.extend_from_slice(engine.write_hits());
+        event_hits.extend_from_slice(engine.event_hits());
+        host_fn_calls.extend_from_slice(engine.host_fn_calls());
+        last_outcome = Some(outcome);
+    }
+    let outcome = last_outcome.expect("the run calls at least its own function");
+    let result = outcome.display.clone();
+    let elapsed = started.elapsed();
     let storage_after = engine.executor().get_storage_snapshot()?;
     print_success("\n--- Execution Complete ---\n");
     output_writer.write("\n--- Execution Complete ---\n")?;
-    print_result(format!("Result: {:?}", result));
-    output_writer.write(&format!("Result: {:?}", result))?;
+    let shown = crate::runtime::result::pretty_display(&result, &outcome.return_value);
+    print_result(format!("Result: {}", shown));
+    output_writer.write(&format!("Result: {}", shown))?;
     logging::log_execution_complete(&result);
+    if args.verbose || verbosity == Verbosity::Verbose {
+        print_verbose(format!("Execution time: {:.2?}", elapsed));
+    }
+    let extracted = extract_paths(&args.extract, engine.executor())?;
+
+    let mut failed_expectations = Vec::new();
+    let mut json_expectations = Vec::new();
+    if let Some(expected) = &args.expect {
+        let passed = result.trim() == expected.trim();
+        if passed {
+            print_success("Return value expectation passed");
+        } else {
+            let message = format!(
+                "Return value expectation failed! Expected '{}', got '{}'",
+                expected, result
+            );
+            if args.expect_mode == ExpectMode::Warn {
+                report_warning(
+                    WarningCategory::Expectation,
+                    format!("!!! {} (expect mode: warn)", message),
+                );
+            } else {
+                print_warning(&message);
+            }
+            failed_expectations.push(message);
+        }
+        json_expectations.push(serde_json::json!({
+            "kind": "return_value",
+            "expected": expected,
+            "actual": result,
+            "passed": passed,
+            "mode": args.expect_mode.as_str(),
+        }));
+    }
+
+    let expectation_failure =
+        (!failed_expectations.is_empty() && args.expect_mode == ExpectMode::Strict).then(|| {
+            format!(
+                "{} expectation(s) failed: {}",
+                failed_expectations.len(),
+                failed_expectations.join("; ")
+            )
+        });
 
     // Generate test if requested
     if let Some(test_path) = &args.generate_test {
@@ -710,32 +1458,132 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
                 test_path
             ));
         } else {
-            print_warning("No execution record found to generate test.");
+            report_warning(
+                WarningCategory::Codegen,
+                "No execution record found to generate test.",
+            );
         }
     }
 
-    let storage_diff = crate::inspector::storage::StorageInspector::compute_diff(
+    let mut storage_diff = crate::inspector::storage::StorageInspector::compute_diff_with_writes(
         &storage_before,
         &storage_after,
+        engine.executor().last_storage_writes(),
+        &args.alert_on_change,
+    )
+    .with_entries(&entries_before, &engine.executor().storage_entries())
+    .with_raw_xdr(args.raw_xdr)
+    .with_writers(engine.executor().storage_provenance())
+    .with_instance_alerts(
+        &instance_before,
+        &engine.executor().instance_storage_snapshot(),
         &args.alert_on_change,
     );
-    if !storage_diff.is_empty() || !args.alert_on_change.is_empty() {
+    if let Some(history) = engine.executor().storage_history() {
+        storage_diff = storage_diff.with_history(history);
+    }
+    let shown = args.shown();
+    if shown.contains(&ShowItem::StorageDiff)
+        && (!storage_diff.is_empty() || !args.alert_on_change.is_empty())
+    {
         print_info("\n--- Storage Changes ---");
         crate::inspector::storage::StorageInspector::display_diff(&storage_diff);
+    } else if !storage_diff.triggered_alerts.is_empty() {
+        crate::inspector::storage::StorageInspector::display_alerts(&storage_diff);
+    }
+
+    let conventions = crate::analyzer::conventions::ReadOnlyConvention::new(
+        &crate::config::ConventionsConfig {
+            readonly_patterns: args.readonly_patterns.clone(),
+        },
+        &wasm_bytes,
+    )?;
+    if let Some(violation) = conventions.check(
+        function,
+        engine.executor().last_storage_writes(),
+        crate::analyzer::conventions::contract_event_count(engine.executor().host()),
+    ) {
+        if !args.strict_conventions {
+            report_warning(WarningCategory::Convention, violation.to_string());
+        }
+        for detail in violation.details() {
+            print_warning(format!("  {}", detail));
+        }
+        if args.strict_conventions {
+            return Err(DebuggerError::ConventionViolation(violation.to_string()).into());
+        }
     }
 
     if let Some(export_path) = &args.export_storage {
         print_info(format!("\nExporting storage to: {:?}", export_path));
         crate::inspector::storage::StorageState::export_to_file(&storage_after, export_path)?;
     }
+    if let Some(dump_path) = &args.dump_storage {
+        let dump = crate::inspector::storage_dump::StorageDump::from_host(
+            engine.executor().host(),
+            &soroban_sdk::xdr::ScAddress::from(engine.executor().contract_address()),
+        )?;
+        dump.write_to(dump_path, args.overwrite)?;
+        print_info(format!(
+            "Dumped {} storage entr{} to {:?}",
+            dump.entries.len(),
+            if dump.entries.len() == 1 { "y" } else { "ies" },
+            dump_path
+        ));
+    }
     let mock_calls = engine.executor().get_mock_call_log();
+    let mock_summary = engine.executor().get_mock_summary();
     if !args.mock.is_empty() {
+        display_mock_summary(&mock_summary);
         display_mock_call_log(&mock_calls);
     }
+    let cost_table = args.cost_table.then(|| {
+        let mut costs = crate::inspector::cost_table::CostTableBuilder::new();
+        costs.record_all(engine.executor().call_costs());
+        costs.build()
+    });
+    if let Some(ref table) = cost_table {
+        if !args.is_json_output() {
+            display_cost_table(table);
+        }
+    }
+    if let Some(sampling) = engine.executor().debug_env().trace_sampling() {
+        print_info(format!(
+            "\nTrace sampled: recorded {} of {} storage access(es) (1 in {}){}",
+            sampling.recorded,
+            sampling.observed,
+            sampling.rate,
+            if sampling.truncated {
+                ", stopped at --trace-limit"
+            } else {
+                ""
+            }
+        ));
+    }
+    let storage_heatmap = match args.storage_heatmap {
+        Some(top) => {
+            let filter = heatmap_filter(&args.storage_filter)?;
+            if !args.is_json_output() {
+                display_storage_heatmap(engine.executor().storage_heatmap(), top, &filter);
+            }
+            Some(engine.executor().storage_heatmap().to_json(&filter))
+        }
+        None => None,
+    };
 
     // Save budget info to history
-    let host = engine.executor().host();
-    let budget = crate::inspector::budget::BudgetInspector::get_cpu_usage(host);
+    let budget = engine.executor().budget_usage();
+    let (setup_budget, execution_budget) = (&outcome.setup_budget, &outcome.budget);
+    if !args.is_json_output() {
+        print_info("\n--- Budget ---");
+        print_info(crate::inspector::budget::BudgetInspector::format_breakdown(
+            setup_budget,
+            execution_budget,
+        ));
+        print_info(crate::inspector::budget::BudgetInspector::format_footer(
+            &budget,
+        ));
+    }
     if let Ok(manager) = HistoryManager::new() {
         let record = RunHistory {
             date: chrono::Utc::now().to_rfc3339(),
@@ -743,6 +1591,13 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
             function: function.clone(),
             cpu_used: budget.cpu_instructions,
             memory_used: budget.memory_bytes,
+            setup_cpu_used: setup_budget.cpu_instructions,
+            setup_memory_used: setup_budget.memory_bytes,
+            event_topics: engine
+                .executor()
+                .get_events()
+                .ok()
+                .map(|events| crate::analyzer::event_schema::topic_signatures(&events)),
         };
         let _ = manager.append_record(record);
     }
@@ -759,12 +1614,30 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
         ));
     }
 
+    if args.commit {
+        if let Some(environment) = environment.as_mut() {
+            environment.commit(&engine.executor().env_snapshot(), &wasm_hash)?;
+            print_success(format!(
+                "Committed state to environment '{}'",
+                environment.name()
+            ));
+        }
+    }
+    if let Some(state_path) = &args.save_state {
+        engine.executor().export_state().write_file(state_path)?;
+        print_success(format!("Saved state to {:?}", state_path));
+    }
+
     let mut json_events = None;
-    if args.show_events || !args.event_filter.is_empty() || args.filter_topic.is_some() {
+    if shown.contains(&ShowItem::Events)
+        || !args.event_filter.is_empty()
+        || args.filter_topic.is_some()
+    {
         print_info("\n--- Events ---");
 
         // Attempt to read raw events from executor
-        let raw_events = engine.executor().get_events()?;
+        let mut raw_events = constructor_events;
+        raw_events.extend(engine.executor().get_events()?);
 
         // Convert runtime event objects into our inspector::events::ContractEvent via serde translation.
         // This is a generic, safe conversion as long as runtime events are serializable with sensible fields.
@@ -773,15 +1646,19 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
                 Ok(evts) => evts,
                 Err(e) => {
                     // If conversion fails, fall back to attempting to stringify each raw event for display.
-                    print_warning(format!(
-                        "Failed to convert runtime events for structured display: {}",
-                        e
-                    ));
+                    report_warning(
+                        WarningCategory::Event,
+                        format!(
+                            "Failed to convert runtime events for structured display: {}",
+                            e
+                        ),
+                    );
                     // Fallback: attempt a best-effort stringification
                     let fallback: Vec<ContractEvent> = raw_events
                         .into_iter()
                         .map(|r| ContractEvent {
                             contract_id: None,
+                            contract: None,
                             topics: vec![],
                             data: format!("{:?}", r),
                         })
@@ -816,21 +1693,192 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
         json_events = Some(filtered_events);
     }
 
-    if !args.storage_filter.is_empty() {
-        let storage_filter = crate::inspector::storage::StorageFilter::new(&args.storage_filter)
-            .map_err(|e| DebuggerError::StorageError(format!("Invalid storage filter: {}", e)))?;
-
-        print_info("\n--- Storage ---");
-        let inspector =
-            crate::inspector::storage::StorageInspector::with_state(storage_after.clone());
-        inspector.display_filtered(&storage_filter);
-    }
-
-    let mut json_auth = None;
-    if args.show_auth {
-        let auth_tree = engine.executor().get_auth_tree()?;
-        if args.json {
-            // JSON mode: print the auth tree inline (will also be included in
+    if args.validate_events || args.event_schema.is_some() {
+        let schema = match &args.event_schema {
+            Some(path) => crate::analyzer::event_schema::EventSchema::from_file(path)?,
+            None => crate::analyzer::event_schema::extract_event_schema(&wasm_bytes)?,
+        };
+        let events = engine.executor().get_events()?;
+        let violations = schema.validate(&events);
+        if violations.is_empty() {
+            print_success(format!(
+                "All {} event(s) match the event schema",
+                events.len()
+            ));
+        } else {
+            print_warning(format!(
+                "{} event(s) do not match the event schema:",
+                violations.len()
+            ));
+            for violation in &violations {
+                print_warning(format!("  {}", violation));
+                crate::warnings::record(WarningCategory::Event, violation.to_string());
+            }
+        }
+    }
+
+    if let Some(path) = &args.events_ndjson {
+        use crate::inspector::event_export::{host_events, LedgerPosition, NdjsonEventWriter};
+        let mut writer = NdjsonEventWriter::create(path)?;
+        let written = writer.write_invocation(
+            &host_events(engine.executor().host())?,
+            LedgerPosition::current(engine.executor().env()),
+        )?;
+        print_info(format!("Wrote {} event(s) to {:?}", written, path));
+    }
+
+    if let Some(path) = &args.export_auth {
+        let export = crate::inspector::auth_export::AuthExport::from_host(
+            engine.executor().host(),
+            engine.executor().env().ledger().sequence(),
+            args.auth_expiration_ledger,
+        )?;
+        export.write_to(path)?;
+        print_info(format!(
+            "Wrote {} authorization entr{} to {:?}",
+            export.entries.len(),
+            if export.entries.len() == 1 {
+                "y"
+            } else {
+                "ies"
+            },
+            path
+        ));
+        if args.auth_expiration_ledger.is_none() && !export.entries.is_empty() {
+            print_info("Signature expiration left at 0; set it with --auth-expiration-ledger");
+        }
+    }
+
+    let mut json_storage = None;
+    if list_storage {
+        let storage_filter = crate::inspector::storage::StorageFilter::new(&args.storage_filter)
+            .map_err(|e| DebuggerError::StorageError(format!("Invalid storage filter: {}", e)))?
+            .with_durabilities(
+                args.durability
+                    .iter()
+                    .map(|d| storage_durability(*d))
+                    .collect(),
+            );
+        let entries = engine.executor().storage_entries();
+        let written = crate::inspector::storage_rent::written_keys(&entries_before, &entries);
+        let inspector = crate::inspector::storage::StorageInspector::with_entries(entries)
+            .at_ledger(engine.executor().ledger_sequence())
+            .with_raw_xdr(args.raw_xdr)
+            .with_written(written)
+            .sorted_by(storage_sort(args.sort))
+            .paged(args.offset, (args.limit > 0).then_some(args.limit));
+        let ttl_warnings = inspector.ttl_warnings(&storage_filter, args.ttl_warning_threshold);
+        for warning in &ttl_warnings {
+            crate::warnings::record(WarningCategory::Ttl, warning.to_string());
+        }
+        let rent_estimate = inspector.rent_estimate(
+            &storage_filter,
+            crate::inspector::storage_rent::RentRates::from_config(&args.rent),
+        );
+        if args.is_json_output() {
+            json_storage = Some((
+                inspector.filtered_json(&storage_filter),
+                ttl_warnings,
+                rent_estimate,
+            ));
+        } else {
+            print_info("\n--- Storage ---");
+            for line in inspector.render_filtered(&storage_filter) {
+                print_info(line);
+            }
+            crate::inspector::storage::StorageInspector::display_ttl_warnings(
+                &ttl_warnings,
+                args.ttl_warning_threshold,
+            );
+            print_info("");
+            for line in rent_estimate.render().lines() {
+                print_info(line);
+            }
+        }
+    }
+
+    let mut json_storage_writes = None;
+    if let Some(history) = engine
+        .executor()
+        .storage_history()
+        .filter(|_| args.track_writes)
+    {
+        if args.is_json_output() {
+            json_storage_writes = Some(history.to_json());
+        } else {
+            let filter = heatmap_filter(&args.storage_filter)?;
+            print_info("\n--- Storage Writes ---");
+            for line in history.render(&filter).lines() {
+                print_info(line);
+            }
+        }
+    }
+
+    let mut json_host_calls = None;
+    if shown.contains(&ShowItem::HostCalls) {
+        let events = crate::inspector::event_export::host_events(engine.executor().host())?;
+        let calls = crate::inspector::host_calls::host_calls(&events);
+        if !args.is_json_output() {
+            print_info("\n--- Host Calls ---");
+            if calls.is_empty() {
+                print_info("No contract calls recorded");
+            }
+            for line in crate::inspector::host_calls::format_calls(&calls) {
+                print_info(line);
+            }
+        }
+        json_host_calls = Some(calls);
+    }
+
+    let mut json_logs = None;
+    if shown.contains(&ShowItem::Logs) {
+        let events = crate::inspector::event_export::host_events(engine.executor().host())?;
+        let logs = crate::inspector::logs::contract_logs(&events);
+        if !args.is_json_output() {
+            print_info("\n--- Contract Logs ---");
+            if logs.is_empty() {
+                print_info("No contract logs recorded");
+            }
+            for line in crate::inspector::logs::format_logs(&logs) {
+                print_info(line);
+            }
+        }
+        json_logs = Some(logs);
+    }
+
+    let mut json_diagnostics = None;
+    if shown.contains(&ShowItem::Diagnostics) {
+        let events = crate::inspector::event_export::host_events(engine.executor().host())?;
+        let diagnostics = crate::inspector::diagnostics::diagnostic_events(&events);
+        if !args.is_json_output() {
+            print_info("\n--- Diagnostic Events ---");
+            if diagnostics.is_empty() {
+                print_info("No diagnostic events recorded");
+            }
+            for line in crate::inspector::diagnostics::format_diagnostics(&diagnostics) {
+                print_info(line);
+            }
+        }
+        json_diagnostics = Some(diagnostics);
+    }
+
+    let mut json_ring = None;
+    if args.dump_ring {
+        let ring = engine.executor().trace_ring();
+        if !args.is_json_output() {
+            print_info("");
+            for line in ring.report() {
+                print_info(line);
+            }
+        }
+        json_ring = Some(ring.entries());
+    }
+
+    let mut json_auth = None;
+    if shown.contains(&ShowItem::Auth) {
+        let auth_tree = engine.executor().get_auth_tree()?;
+        if args.json {
+            // JSON mode: print the auth tree inline (will also be included in
             // the combined JSON object further below).
             let json_output = crate::inspector::auth::AuthInspector::to_json(&auth_tree)?;
             logging::log_display(json_output, logging::LogLevel::Info);
@@ -842,11 +1890,14 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
     }
 
     let mut json_ledger = None;
-    if args.show_ledger {
+    if shown.contains(&ShowItem::Ledger) {
         print_info("\n--- Ledger Entries ---");
         let mut ledger_inspector = crate::inspector::ledger::LedgerEntryInspector::new();
         ledger_inspector.set_ttl_warning_threshold(args.ttl_warning_threshold);
 
+        use crate::inspector::ledger::StorageType;
+        use soroban_env_host::xdr::{ContractDataDurability, LedgerKey, ScVal};
+        let sequence = engine.executor().ledger_sequence();
         match engine.executor_mut().finish() {
             Ok((footprint, storage)) => {
                 #[allow(clippy::clone_on_copy)]
@@ -860,18 +1911,19 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
                 for (key, val_opt) in &storage.map {
                     if let Some(access_type) = footprint_map.get(key) {
                         if let Some((entry, ttl)) = val_opt {
-                            let key_str = format!("{:?}", **key);
-                            let storage_type =
-                                if key_str.contains("Temporary") || key_str.contains("temporary") {
-                                    crate::inspector::ledger::StorageType::Temporary
-                                } else if key_str.contains("Instance")
-                                    || key_str.contains("instance")
-                                    || key_str.contains("LedgerKeyContractInstance")
+                            let storage_type = match &**key {
+                                LedgerKey::ContractData(cd)
+                                    if cd.key == ScVal::LedgerKeyContractInstance =>
+                                {
+                                    StorageType::Instance
+                                }
+                                LedgerKey::ContractData(cd)
+                                    if cd.durability == ContractDataDurability::Temporary =>
                                 {
-                                    crate::inspector::ledger::StorageType::Instance
-                                } else {
-                                    crate::inspector::ledger::StorageType::Persistent
-                                };
+                                    StorageType::Temporary
+                                }
+                                _ => StorageType::Persistent,
+                            };
 
                             use soroban_env_host::storage::AccessType;
                             let is_read = true; // Everything in the footprint is at least read
@@ -881,7 +1933,9 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
                                 format!("{:?}", **key),
                                 format!("{:?}", **entry),
                                 storage_type,
-                                ttl.unwrap_or(0),
+                                // Ledgers left, so the warning threshold means
+                                // the same at any sequence.
+                                ttl.map_or(0, |live_until| live_until.saturating_sub(sequence)),
                                 is_read,
                                 is_write,
                             );
@@ -890,29 +1944,168 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
                 }
             }
             Err(e) => {
-                print_warning(format!("Failed to extract ledger footprint: {}", e));
+                report_warning(
+                    WarningCategory::Ledger,
+                    format!("Failed to extract ledger footprint: {}", e),
+                );
             }
         }
 
         ledger_inspector.display();
         ledger_inspector.display_warnings();
-        json_ledger = Some(ledger_inspector);
+        for entry in ledger_inspector.get_near_expiry_entries() {
+            crate::warnings::record(
+                WarningCategory::Ttl,
+                format!(
+                    "[{}] {} near expiry (TTL {})",
+                    entry.storage_type, entry.key, entry.ttl
+                ),
+            );
+        }
+
+        let footprint = engine.executor().last_footprint().clone();
+        if !args.is_json_output() {
+            print_info("\n--- Footprint ---");
+            for line in footprint.format() {
+                print_info(line);
+            }
+        }
+        json_ledger = Some((ledger_inspector, footprint));
     }
 
-    if args.is_json_output() {
+    if args.report_html.is_some() || result_cache.is_some() {
+        let args_str = parsed_args
+            .as_ref()
+            .map(|a| serde_json::to_string(a).unwrap_or_default());
+        // Events not already rendered for output stay unrendered until the
+        // report shows them.
+        let events = match &json_events {
+            Some(events) => events.iter().map(Into::into).collect(),
+            None => {
+                crate::report::ReportEvent::from_host(engine.executor().host()).unwrap_or_default()
+            }
+        };
+        let auth = match &json_auth {
+            Some(tree) => tree.clone(),
+            None => engine.executor().get_auth_tree().unwrap_or_default(),
+        };
+        let trace = build_execution_trace(
+            function,
+            contract.to_string_lossy().as_ref(),
+            args_str.clone(),
+            &storage_after,
+            &result,
+            budget.clone(),
+            engine.executor(),
+            &[],
+            usize::MAX,
+        );
+        let report = crate::report::ExecutionReport {
+            contract: contract.display().to_string(),
+            function: function.clone(),
+            args: args_str,
+            result: result.clone(),
+            sha256: wasm_hash.clone(),
+            generated_at: chrono::Utc::now().to_rfc3339(),
+            budget: budget.clone(),
+            events,
+            storage_diff: (&storage_diff).into(),
+            auth,
+            call_trace: trace.call_sequence.iter().map(Into::into).collect(),
+        };
+        if let Some(report_path) = &args.report_html {
+            report.write_html(report_path)?;
+            print_success(format!("HTML report written to {:?}", report_path));
+        }
+        if let Some((cache, key)) = &result_cache {
+            // A read-only or full cache directory must never fail a run.
+            match cache.store(key, &report) {
+                Ok(()) => print_info(format!("Cached result as {}", &key[..12])),
+                Err(e) => tracing::debug!("Skipping result cache write: {}", e),
+            }
+        }
+    }
+
+    if !extracted.is_empty() {
+        if args.is_json_output() || Formatter::is_quiet() {
+            for (_, value) in &extracted {
+                println!("{}", value);
+            }
+        } else {
+            println!("\n--- Extracted ---");
+            for (path, value) in &extracted {
+                println!("{} = {}", path, value);
+            }
+        }
+    } else if args.is_json_output() {
+        // `null` for `None`/`Void`, the inner value for `Some`, matching --args syntax.
         let mut result_obj = serde_json::json!({
             "result": result,
+            "return_value": outcome.return_value,
+            "prng_seed": prng_seed,
+            "source_account": source_account.to_string(),
+            "protocol_version": protocol_version,
             "sha256": wasm_hash,
             "budget": {
                 "cpu_instructions": budget.cpu_instructions,
+                "cpu_limit": budget.cpu_limit,
                 "memory_bytes": budget.memory_bytes,
+                "memory_limit": budget.memory_limit,
+                "setup": {
+                    "cpu_instructions": setup_budget.cpu_instructions,
+                    "memory_bytes": setup_budget.memory_bytes,
+                },
+                "execution": {
+                    "cpu_instructions": execution_budget.cpu_instructions,
+                    "memory_bytes": execution_budget.memory_bytes,
+                },
             },
             "storage_diff": storage_diff,
+            "collected": shown.iter().map(|item| item.name()).collect::<Vec<_>>(),
         });
+        if let Some(record) = &constructor_record {
+            result_obj["constructor"] = serde_json::json!({
+                "args": serde_json::from_str::<serde_json::Value>(
+                    args.constructor_args.as_deref().unwrap_or("[]")
+                )
+                .unwrap_or_default(),
+                "budget": {
+                    "cpu_instructions": record.budget.cpu_instructions,
+                    "memory_bytes": record.budget.memory_bytes,
+                },
+            });
+        }
+        let linked = engine.executor().linked_contracts();
+        if !linked.is_empty() {
+            result_obj["linked_contracts"] = linked
+                .iter()
+                .map(|(name, address)| {
+                    serde_json::json!({
+                        "name": name,
+                        "address": soroban_sdk::xdr::ScAddress::from(address).to_string(),
+                    })
+                })
+                .collect();
+        }
 
         if let Some(ref events) = json_events {
             result_obj["events"] = EventInspector::to_json_value(events);
         }
+        if let Some(ref calls) = json_host_calls {
+            result_obj["host_calls"] = serde_json::to_value(calls).unwrap_or_default();
+        }
+        if let Some(ref logs) = json_logs {
+            result_obj["logs"] = serde_json::to_value(logs).unwrap_or_default();
+        }
+        if let Some(ref diagnostics) = json_diagnostics {
+            result_obj["diagnostics"] = serde_json::to_value(diagnostics).unwrap_or_default();
+        }
+        if let Some(ref ring) = json_ring {
+            result_obj["trace_ring"] = serde_json::to_value(ring).unwrap_or_default();
+        }
+        if !call_outcomes.is_empty() {
+            result_obj["calls"] = serde_json::to_value(&call_outcomes).unwrap_or_default();
+        }
         if let Some(auth_tree) = json_auth {
             result_obj["auth"] = crate::inspector::auth::AuthInspector::to_json_value(&auth_tree);
         }
@@ -927,27 +2120,84 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
                             "args_count": entry.args_count,
                             "mocked": entry.mocked,
                             "returned": entry.returned,
+                            "sequence": entry.sequence,
+                            "exhausted": entry.exhausted,
                         })
                     })
                     .collect(),
             );
         }
-        if let Some(ref ledger) = json_ledger {
+        if !mock_summary.is_empty() {
+            result_obj["mocks"] = serde_json::to_value(&mock_summary).unwrap_or_default();
+        }
+        if let Some(ref table) = cost_table {
+            result_obj["cost_table"] = serde_json::to_value(table).unwrap_or_default();
+        }
+        if let Some(heatmap) = storage_heatmap {
+            result_obj["storage_heatmap"] = heatmap;
+        }
+        if let Some(writes) = json_storage_writes {
+            result_obj["storage_writes"] = writes;
+        }
+        if !args.break_on_write.is_empty() {
+            result_obj["write_breakpoints"] = serde_json::to_value(&write_hits).unwrap_or_default();
+        }
+        if !args.break_on_event.is_empty() {
+            result_obj["event_breakpoints"] = serde_json::to_value(&event_hits).unwrap_or_default();
+        }
+        if !args.report_host_fn.is_empty() {
+            result_obj["host_fn_calls"] = serde_json::to_value(&host_fn_calls).unwrap_or_default();
+        }
+        if !args.alert_on_change.is_empty() {
+            result_obj["alerts"] = serde_json::to_value(storage_diff.alerts()).unwrap_or_default();
+        }
+        if let Some((storage, ttl_warnings, rent_estimate)) = json_storage {
+            result_obj["storage"] = serde_json::Value::Array(storage);
+            result_obj["ttl_warnings"] = serde_json::to_value(ttl_warnings).unwrap_or_default();
+            result_obj["rent_estimate"] = serde_json::to_value(rent_estimate).unwrap_or_default();
+        }
+        if let Some(ref code) = code_fingerprint {
+            result_obj["code"] = serde_json::to_value(code).unwrap_or_default();
+        }
+        if let Some(sampling) = engine.executor().debug_env().trace_sampling() {
+            result_obj["trace_sampling"] = serde_json::to_value(sampling).unwrap_or_default();
+        }
+        if let Some((ref ledger, ref footprint)) = json_ledger {
             result_obj["ledger_entries"] = ledger.to_json();
+            result_obj["footprint"] = serde_json::to_value(footprint).unwrap_or_default();
+        }
+        if !json_expectations.is_empty() {
+            result_obj["expectations"] = serde_json::Value::Array(json_expectations);
+        }
+        let warnings = WarningSummary::new(crate::warnings::collected(), &args.allow_warning);
+        if !warnings.is_empty() {
+            result_obj["warnings"] = serde_json::to_value(&warnings).unwrap_or_default();
         }
 
         let output = serde_json::json!({
             "schema_version": "1.0",
             "command": "run",
-            "status": "success",
+            "status": if expectation_failure.is_some() { "error" } else { "success" },
             "result": result_obj,
             "sha256": wasm_hash,
             "budget": {
                 "cpu_instructions": budget.cpu_instructions,
+                "cpu_limit": budget.cpu_limit,
                 "memory_bytes": budget.memory_bytes,
+                "memory_limit": budget.memory_limit,
+                "setup": {
+                    "cpu_instructions": setup_budget.cpu_instructions,
+                    "memory_bytes": setup_budget.memory_bytes,
+                },
+                "execution": {
+                    "cpu_instructions": execution_budget.cpu_instructions,
+                    "memory_bytes": execution_budget.memory_bytes,
+                },
             },
             "storage_diff": storage_diff,
-            "error": serde_json::Value::Null
+            "error": expectation_failure
+                .as_ref()
+                .map(|message| serde_json::json!({ "message": message })),
         });
 
         match serde_json::to_string_pretty(&output) {
@@ -993,16 +2243,356 @@ pub fn run(args: RunArgs, verbosity: Verbosity) -> Result<()> {
 
         if let Ok(json) = trace.to_json() {
             if let Err(e) = std::fs::write(trace_path, json) {
-                print_warning(format!("Failed to write trace to {:?}: {}", trace_path, e));
+                report_warning(
+                    WarningCategory::Trace,
+                    format!("Failed to write trace to {:?}: {}", trace_path, e),
+                );
             } else {
                 print_success(format!("Successfully exported trace to {:?}", trace_path));
             }
         }
     }
 
+    let warnings = WarningSummary::new(crate::warnings::collected(), &args.allow_warning);
+    finish_warnings(&warnings, &args);
+
+    if let Some(message) = expectation_failure {
+        if args.is_json_output() {
+            // The JSON document already carries the failure as its error.
+            eprintln!("{}", Formatter::error(&message));
+            std::process::exit(1);
+        }
+        return Err(DebuggerError::ExecutionError(message).into());
+    }
+
+    Ok(())
+}
+
+/// The result cache and this run's key, or `None` when the run has to
+/// execute anyway.
+fn open_result_cache(
+    args: &RunArgs,
+    wasm_bytes: &[u8],
+) -> Result<Option<(crate::report::cache::ResultCache, String)>> {
+    if let Some(reason) = crate::report::cache::bypass_reason(args, wasm_bytes)? {
+        print_info(format!("Result cache bypassed: {}", reason));
+        return Ok(None);
+    }
+    let Some(cache) = crate::report::cache::ResultCache::open_default() else {
+        return Ok(None);
+    };
+    let plan = InvocationPlan::resolve(args)?;
+    let key = crate::report::cache::result_key(&plan)?;
+    Ok(Some((cache, key)))
+}
+
+/// Compare the `--contract` WASM with the code deployed in the network
+/// snapshot. A mismatch is a warning, or an error with
+/// `--require-matching-code`; `--use-snapshot-code` swaps in the deployed code.
+fn pin_snapshot_code(
+    args: &RunArgs,
+    snapshot_path: &std::path::Path,
+    snapshot: &crate::simulator::NetworkSnapshot,
+    wasm_bytes: &mut Vec<u8>,
+    wasm_hash: &mut String,
+) -> Result<CodeFingerprint> {
+    let code_match = code_pin::compare(snapshot, wasm_hash);
+
+    if args.use_snapshot_code {
+        let contract = code_pin::deployed_contract(&code_match)?;
+        let snapshot_dir = snapshot_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."));
+        *wasm_bytes = code_pin::snapshot_code(contract, snapshot_dir)?;
+        *wasm_hash = crate::utils::wasm::compute_wasm_sha256(wasm_bytes);
+        print_info(format!(
+            "Executing the code deployed as {} in the snapshot instead of the --contract WASM",
+            contract.contract_id
+        ));
+        return Ok(CodeFingerprint {
+            source: CodeSource::Snapshot,
+            sha256: wasm_hash.clone(),
+            contract_id: Some(contract.contract_id.clone()),
+            matches_snapshot: true,
+        });
+    }
+
+    let local = |contract_id: Option<&str>, matches_snapshot| CodeFingerprint {
+        source: CodeSource::Local,
+        sha256: wasm_hash.clone(),
+        contract_id: contract_id.map(str::to_string),
+        matches_snapshot,
+    };
+    match code_match {
+        CodeMatch::Matches(contract) => Ok(local(Some(&contract.contract_id), true)),
+        CodeMatch::NoContracts if args.require_matching_code => {
+            Err(DebuggerError::InvalidArguments(
+                "--require-matching-code: the network snapshot holds no contract instances to compare the --contract WASM with".to_string(),
+            )
+            .into())
+        }
+        CodeMatch::NoContracts => Ok(local(None, false)),
+        CodeMatch::Differs(contracts) => {
+            let deployed = contracts
+                .iter()
+                .map(|contract| format!("{} {}", contract.contract_id, contract.wasm_hash))
+                .collect::<Vec<_>>()
+                .join(", ");
+            if args.require_matching_code {
+                return Err(DebuggerError::ChecksumMismatch(deployed, wasm_hash.clone()).into());
+            }
+            report_warning(
+                WarningCategory::Code,
+                format!(
+                    "The --contract WASM is not the code deployed in the snapshot: local sha256 {}, deployed {}. \
+                     Pass --use-snapshot-code to run the deployed code, or --require-matching-code to fail instead.",
+                    wasm_hash, deployed
+                ),
+            );
+            Ok(local(None, false))
+        }
+    }
+}
+
+/// Register every snapshot contract that carries code at its own address.
+/// Contracts without code are skipped with a warning.
+fn register_snapshot_contracts(
+    executor: &mut ContractExecutor,
+    snapshot_path: &std::path::Path,
+    snapshot: &crate::simulator::NetworkSnapshot,
+) -> Result<Vec<String>> {
+    let snapshot_dir = snapshot_path
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+    let mut registered = Vec::new();
+    for contract in &snapshot.contracts {
+        if contract.code.is_none() && contract.wasm_ref.is_none() {
+            report_warning(
+                WarningCategory::Code,
+                format!(
+                    "Contract {} has no code in the snapshot and was not registered",
+                    contract.contract_id
+                ),
+            );
+            continue;
+        }
+        let wasm = code_pin::snapshot_code(contract, snapshot_dir)?;
+        executor.register_contract_at(&contract.contract_id, &wasm)?;
+        registered.push(contract.contract_id.clone());
+    }
+    Ok(registered)
+}
+
+/// Print what led up to a failed call to stderr, before the caller prints the
+/// error itself, so the calls leading up to the failure stay visible.
+fn report_call_failure(args: &RunArgs, engine: &DebuggerEngine, prng_seed: u64) {
+    let ring = engine.executor().trace_ring();
+    if ring.is_enabled() {
+        for line in ring.report() {
+            eprintln!("{}", line);
+        }
+    }
+    crate::inspector::budget::BudgetInspector::report_failure(engine.executor().budget_usage());
+    report_failure_call_stack(args, engine.executor().call_stack());
+    if args.shows(ShowItem::Diagnostics) {
+        report_failure_diagnostics(args, engine.executor().host());
+    }
+    eprintln!(
+        "PRNG seed: {} (pass --seed {} to replay)",
+        prng_seed, prng_seed
+    );
+}
+
+/// Register the contract by running its `__constructor` with
+/// `--constructor-args`, as a call of its own before the others.
+fn run_constructor(
+    args: &RunArgs,
+    engine: &mut DebuggerEngine,
+    prng_seed: u64,
+) -> Result<crate::runtime::ExecutionRecord> {
+    let constructor_args = args.constructor_args.as_deref().unwrap_or("[]");
+    print_info(format!("Constructor arguments: {}", constructor_args));
+    if let Err(e) = engine.execute(CONSTRUCTOR, Some(constructor_args)) {
+        report_call_failure(args, engine, prng_seed);
+        return Err(e);
+    }
+    print_success(format!("{} completed", CONSTRUCTOR));
+    engine.executor().last_execution().cloned().ok_or_else(|| {
+        DebuggerError::ExecutionError(format!("{} left no execution record", CONSTRUCTOR)).into()
+    })
+}
+
+/// Print the calls open when an invocation failed to stderr, innermost last,
+/// or keep them for the JSON error output.
+fn report_failure_call_stack(args: &RunArgs, stack: &crate::runtime::call_stack::CallStack) {
+    if args.is_json_output() {
+        crate::output::record_failure_call_stack(stack.frames().to_vec());
+        return;
+    }
+    if stack.is_empty() {
+        return;
+    }
+    eprintln!("--- Call Stack (innermost last) ---");
+    for line in stack.render() {
+        eprintln!("  {}", line);
+    }
+}
+
+/// Print the diagnostic events of a failed invocation to stderr, next to the
+/// error, or keep them for the JSON error output.
+fn report_failure_diagnostics(args: &RunArgs, host: &soroban_env_host::Host) {
+    let events = match crate::inspector::event_export::host_events(host) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Could not read diagnostic events: {}", e);
+            return;
+        }
+    };
+    let diagnostics = crate::inspector::diagnostics::diagnostic_events(&events);
+    if args.is_json_output() {
+        crate::output::record_failure_diagnostics(diagnostics);
+        return;
+    }
+    eprintln!("--- Diagnostic Events ---");
+    if diagnostics.is_empty() {
+        eprintln!("No diagnostic events recorded");
+    }
+    for line in crate::inspector::diagnostics::format_diagnostics(&diagnostics) {
+        eprintln!("{}", line);
+    }
+}
+
+/// Register each `--link NAME=PATH` contract, returning names and addresses.
+fn link_contracts(
+    executor: &mut ContractExecutor,
+    specs: &[String],
+) -> Result<Vec<(String, String)>> {
+    let mut linked = Vec::new();
+    for spec in specs {
+        let Some((name, path)) = spec.split_once('=') else {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "--link expects NAME=path.wasm, got '{}'",
+                spec
+            ))
+            .into());
+        };
+        let wasm = fs::read(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read linked contract {}: {}", path, e))
+        })?;
+        let address = executor.link_contract(name, &wasm)?;
+        linked.push((
+            name.to_string(),
+            soroban_sdk::xdr::ScAddress::from(&address).to_string(),
+        ));
+    }
+    Ok(linked)
+}
+
+/// Render a cached report in place of executing, marked as cached so it is
+/// never mistaken for a fresh run.
+fn show_cached_result(args: &RunArgs, cached: crate::report::cache::CachedResult) -> Result<()> {
+    let report = cached.report;
+    crate::runtime::policy::function_policy().check(&report.function)?;
+
+    if args.is_json_output() {
+        let output = serde_json::json!({
+            "schema_version": "1.0",
+            "command": "run",
+            "status": "success",
+            "cached": true,
+            "cached_at": cached.stored_at,
+            "result": {
+                "result": report.result,
+                "sha256": report.sha256,
+                "budget": {
+                    "cpu_instructions": report.budget.cpu_instructions,
+                    "memory_bytes": report.budget.memory_bytes,
+                },
+                "storage_diff": report.storage_diff,
+                "events": report.events,
+                "auth": report.auth,
+            },
+            "sha256": report.sha256,
+            "budget": {
+                "cpu_instructions": report.budget.cpu_instructions,
+                "memory_bytes": report.budget.memory_bytes,
+            },
+            "storage_diff": report.storage_diff,
+            "error": serde_json::Value::Null
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output).map_err(|e| {
+                DebuggerError::FileError(format!("Failed to serialize cached result: {}", e))
+            })?
+        );
+    } else {
+        print_warning(format!(
+            "CACHED RESULT from {} (key {}): execution skipped. Pass --no-cache to re-run.",
+            cached.stored_at,
+            &cached.key[..12]
+        ));
+        println!("\n{}", crate::report::render_pretty(&report, false));
+    }
+
+    if let Some(report_path) = &args.report_html {
+        report.write_html(report_path)?;
+        print_success(format!("HTML report written to {:?}", report_path));
+    }
+
+    if let Some(expected) = &args.expect {
+        if report.result.trim() == expected.trim() {
+            print_success("Return value expectation passed");
+        } else {
+            let message = format!(
+                "Return value expectation failed! Expected '{}', got '{}'",
+                expected, report.result
+            );
+            if args.expect_mode == ExpectMode::Strict {
+                return Err(DebuggerError::ExecutionError(format!(
+                    "1 expectation(s) failed: {}",
+                    message
+                ))
+                .into());
+            }
+            report_warning(
+                WarningCategory::Expectation,
+                format!("!!! {} (expect mode: warn)", message),
+            );
+        }
+    }
     Ok(())
 }
 
+/// Resolve each `--extract` path, in order, against the return value and storage.
+///
+/// Values are rendered for scripts: scalars raw, containers as compact JSON.
+fn extract_paths(paths: &[String], executor: &ContractExecutor) -> Result<Vec<(String, String)>> {
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let return_value = executor
+        .last_execution()
+        .and_then(|record| record.result.as_ref().ok())
+        .map(crate::utils::arguments::scval_to_json)
+        .unwrap_or(serde_json::Value::Null);
+    let document = serde_json::json!({
+        "result": return_value,
+        "storage": crate::inspector::storage::StorageInspector::capture_json(executor.host()),
+    });
+
+    paths
+        .iter()
+        .map(|raw| {
+            let path = JsonPath::parse(raw)?;
+            let value = path.resolve(&document).ok_or_else(|| {
+                DebuggerError::ExecutionError(format!("--extract path '{}' did not resolve", path))
+            })?;
+            Ok((path.to_string(), render_extracted(value)))
+        })
+        .collect()
+}
+
 #[allow(clippy::too_many_arguments)]
 fn build_execution_trace(
     function: &str,
@@ -1085,6 +2675,23 @@ fn build_execution_trace(
         return_value: Some(return_val),
         call_sequence,
         events: trace_events,
+        auth: crate::compare::trace::AuthEntry::from_host(executor.host()),
+        storage_accesses: executor
+            .debug_env()
+            .storage_accesses()
+            .iter()
+            .map(|access| crate::compare::trace::StorageAccessEntry {
+                sequence: access.sequence,
+                kind: match access.access_type {
+                    crate::runtime::env::StorageAccessType::Read => "read",
+                    crate::runtime::env::StorageAccessType::Write => "write",
+                }
+                .to_string(),
+                key: access.key.clone(),
+                value: access.value.clone(),
+            })
+            .collect(),
+        sampling: executor.debug_env().trace_sampling(),
     }
 }
 
@@ -1427,16 +3034,115 @@ pub fn parse_storage(json: &str) -> Result<String> {
     Ok(json.to_string())
 }
 
-/// Execute the optimize command.
-pub fn optimize(args: OptimizeArgs, _verbosity: Verbosity) -> Result<()> {
-    print_info(format!(
-        "Analyzing contract for gas optimization: {:?}",
-        args.contract
-    ));
-    logging::log_loading_contract(&args.contract.to_string_lossy());
+/// Add the entries of `--instance-storage`, `--persistent-storage` and
+/// `--temporary-storage` to the `--storage` seed, in its list form so each
+/// keeps its durability.
+fn with_durable_storage(storage: Option<String>, args: &RunArgs) -> Result<Option<String>> {
+    use crate::utils::ledger_key::Durability;
+    let seeds = [
+        (
+            "--instance-storage",
+            Durability::Instance,
+            &args.instance_storage,
+        ),
+        (
+            "--persistent-storage",
+            Durability::Persistent,
+            &args.persistent_storage,
+        ),
+        (
+            "--temporary-storage",
+            Durability::Temporary,
+            &args.temporary_storage,
+        ),
+    ];
+    if seeds.iter().all(|(_, _, json)| json.is_none()) {
+        return Ok(storage);
+    }
 
-    let wasm_file = crate::utils::wasm::load_wasm(&args.contract)
-        .with_context(|| format!("Failed to read WASM file: {:?}", args.contract))?;
+    let mut entries = Vec::new();
+    if let Some(storage) = storage {
+        entries = storage_list(&storage, "the per-durability storage flags")?;
+    }
+    for (flag, durability, json) in seeds {
+        let Some(json) = json else {
+            continue;
+        };
+        let Ok(serde_json::Value::Object(map)) = serde_json::from_str(json) else {
+            return Err(DebuggerError::StorageError(format!(
+                "{} expects a JSON object mapping keys to values, e.g. {{\"nonce\": 7}}",
+                flag
+            ))
+            .into());
+        };
+        entries.extend(
+            map.into_iter()
+                .map(|(key, value)| storage_list_entry(key, value, durability)),
+        );
+    }
+    Ok(Some(serde_json::Value::Array(entries).to_string()))
+}
+
+/// Put the entries of a `--storage-file` dump ahead of the other storage
+/// seeds. Entries are set in order, so a key also given inline takes the
+/// inline value.
+fn with_storage_file(
+    storage: Option<String>,
+    path: Option<&std::path::Path>,
+) -> Result<Option<String>> {
+    let Some(path) = path else {
+        return Ok(storage);
+    };
+    let mut entries = crate::inspector::storage_dump::read_dump_file(path)?;
+    if let Some(storage) = storage {
+        entries.extend(storage_list(&storage, "--storage-file")?);
+    }
+    Ok(Some(serde_json::Value::Array(entries).to_string()))
+}
+
+/// `--storage` JSON in its list form: objects become instance entries and
+/// dumps their entries.
+fn storage_list(storage: &str, combined_with: &str) -> Result<Vec<serde_json::Value>> {
+    use crate::inspector::storage_dump::{dump_entries, is_dump};
+    use crate::utils::ledger_key::Durability;
+    match serde_json::from_str(storage) {
+        Ok(root) if is_dump(&root) => dump_entries(root),
+        Ok(serde_json::Value::Array(items)) => Ok(items),
+        Ok(serde_json::Value::Object(map)) => Ok(map
+            .into_iter()
+            .map(|(key, value)| storage_list_entry(key, value, Durability::Instance))
+            .collect()),
+        _ => Err(DebuggerError::StorageError(format!(
+            "--storage must be a JSON object or list to combine with {}",
+            combined_with
+        ))
+        .into()),
+    }
+}
+
+/// A `--storage` list entry under the symbol `key`.
+fn storage_list_entry(
+    key: String,
+    value: serde_json::Value,
+    durability: crate::utils::ledger_key::Durability,
+) -> serde_json::Value {
+    serde_json::json!({
+        "key": { "type": "symbol", "value": key },
+        "value": value,
+        "durability": durability.name(),
+    })
+}
+
+/// Execute the optimize command.
+pub fn optimize(args: OptimizeArgs, _verbosity: Verbosity) -> Result<()> {
+    print_info(format!(
+        "Analyzing contract for gas optimization: {:?}",
+        args.contract
+    ));
+    logging::log_loading_contract(&args.contract.to_string_lossy());
+
+    let wasm_file = crate::utils::wasm::load_wasm(&args.contract)
+        .with_context(|| format!("Failed to read WASM file: {:?}", args.contract))?;
     let wasm_bytes = wasm_file.bytes;
     let wasm_hash = wasm_file.sha256_hash;
 
@@ -1643,9 +3349,23 @@ pub fn compare(args: CompareArgs) -> Result<()> {
         args.ignore_field.clone(),
     )?;
     let report = crate::compare::CompareEngine::compare_with_filters(&trace_a, &trace_b, &filters);
-    let rendered = crate::compare::CompareEngine::render_report(&report);
 
-    if let Some(output_path) = &args.output {
+    if args.format == OutputFormat::Json {
+        let json = crate::compare::CompareEngine::report_json(&report)?;
+        match &args.output {
+            Some(output_path) => {
+                fs::write(output_path, &json).map_err(|e| {
+                    DebuggerError::FileError(format!(
+                        "Failed to write report to {:?}: {}",
+                        output_path, e
+                    ))
+                })?;
+                print_success(format!("Comparison report written to: {:?}", output_path));
+            }
+            None => println!("{}", json),
+        }
+    } else if let Some(output_path) = &args.output {
+        let rendered = crate::compare::CompareEngine::render_report(&report);
         fs::write(output_path, &rendered).map_err(|e| {
             DebuggerError::FileError(format!(
                 "Failed to write report to {:?}: {}",
@@ -1654,7 +3374,17 @@ pub fn compare(args: CompareArgs) -> Result<()> {
         })?;
         print_success(format!("Comparison report written to: {:?}", output_path));
     } else {
-        println!("{}", rendered);
+        let width = crossterm::terminal::size()
+            .map(|(columns, _)| columns as usize)
+            .unwrap_or(80);
+        let options = crate::compare::renderer::RenderOptions {
+            width,
+            color: std::env::var_os("NO_COLOR").is_none(),
+        };
+        print!(
+            "{}",
+            crate::compare::renderer::render_side_by_side(&report, options)
+        );
     }
 
     Ok(())
@@ -1666,6 +3396,16 @@ pub fn replay(args: ReplayArgs, verbosity: Verbosity) -> Result<()> {
     print_info(format!("Loading trace file: {:?}", args.trace_file));
     let original_trace = crate::compare::ExecutionTrace::from_file(&args.trace_file)?;
 
+    if let Some(sampling) = original_trace.sampling.filter(|s| s.is_sampled()) {
+        return Err(DebuggerError::ExecutionError(format!(
+            "Trace {:?} was recorded with --trace-sample {} and only holds {} of {} storage \
+             accesses, so it cannot be a baseline for replay. Record it again without \
+             --trace-sample",
+            args.trace_file, sampling.rate, sampling.recorded, sampling.observed
+        ))
+        .into());
+    }
+
     // Determine which contract to use
     let contract_path = if let Some(path) = &args.contract {
         path.clone()
@@ -1731,6 +3471,11 @@ pub fn replay(args: ReplayArgs, verbosity: Verbosity) -> Result<()> {
         executor.set_initial_storage(storage)?;
     }
 
+    if original_trace.auth.is_some() {
+        // Record authorizations the same way the original run did.
+        executor.enable_mock_all_auths();
+    }
+
     let mut engine = DebuggerEngine::new(executor, vec![]);
 
     logging::log_execution_start(function, args_str);
@@ -1830,7 +3575,9 @@ pub fn server(args: ServerArgs) -> Result<()> {
         args.token.clone(),
         args.tls_cert.as_deref(),
         args.tls_key.as_deref(),
-    )?;
+    )?
+    .with_session(args.session.clone());
+    print_info(format!("Session: {}", args.session));
 
     tokio::runtime::Runtime::new()
         .map_err(|e: std::io::Error| miette::miette!(e))
@@ -1859,8 +3606,30 @@ pub fn remote(args: RemoteArgs, _verbosity: Verbosity) -> Result<()> {
     print_success("Remote debugger is reachable");
     Ok(())
 }
+/// Attach read-only to a running server session
+pub fn attach(args: AttachArgs) -> Result<()> {
+    use std::io::IsTerminal;
+
+    print_info(format!(
+        "Attaching to session '{}' at {}",
+        args.session, args.url
+    ));
+    let url = args.url.trim_start_matches("tcp://");
+    let mut client = crate::client::RemoteClient::connect(url, args.token.clone())?;
+    client.attach(&args.session)?;
+
+    let stdin = std::io::stdin();
+    let prompt = stdin.is_terminal();
+    let mut session = crate::client::AttachSession::new(client, args.session);
+    session.run(stdin.lock(), &mut std::io::stdout(), prompt)
+}
+
 /// Launch interactive debugger UI
 pub fn interactive(args: InteractiveArgs, _verbosity: Verbosity) -> Result<()> {
+    validate_breakpoints(&args.breakpoint)?;
+    let watched_writes = write_breakpoints(&args.break_on_write)?;
+    let watched_events = event_breakpoints(&args.break_on_event, args.break_contract_events_only)?;
+    let watched_host_fns = host_fn_watch(&args.report_host_fn)?;
     print_info(format!("Loading contract: {:?}", args.contract));
     logging::log_loading_contract(&args.contract.to_string_lossy());
 
@@ -1913,6 +3682,7 @@ pub fn interactive(args: InteractiveArgs, _verbosity: Verbosity) -> Result<()> {
         })?);
     }
 
+    let wasm_bytes = instrument_host_fns(wasm_bytes, watched_host_fns.as_ref())?;
     let mut executor = ContractExecutor::new(wasm_bytes.clone())?;
     executor.set_timeout(args.timeout);
 
@@ -1924,6 +3694,15 @@ pub fn interactive(args: InteractiveArgs, _verbosity: Verbosity) -> Result<()> {
     }
 
     let mut engine = DebuggerEngine::new(executor, args.breakpoint.clone());
+    if let Some(watched) = watched_writes {
+        engine.set_write_breakpoints(watched);
+    }
+    if let Some(watched) = watched_events {
+        engine.set_event_breakpoints(watched);
+    }
+    if let Some(watched) = watched_host_fns {
+        engine.set_watched_host_fns(watched);
+    }
 
     if args.instruction_debug {
         print_info("Enabling instruction-level debugging...");
@@ -1943,6 +3722,10 @@ pub fn interactive(args: InteractiveArgs, _verbosity: Verbosity) -> Result<()> {
 
 /// Launch TUI debugger
 pub fn tui(args: TuiArgs, _verbosity: Verbosity) -> Result<()> {
+    let Some(function) = args.function.clone() else {
+        return tui_browser(args);
+    };
+    validate_breakpoints(&args.breakpoint)?;
     print_info(format!("Loading contract: {:?}", args.contract));
     let wasm_file = crate::utils::wasm::load_wasm(&args.contract)
         .with_context(|| format!("Failed to read WASM file: {:?}", args.contract))?;
@@ -1980,13 +3763,35 @@ pub fn tui(args: TuiArgs, _verbosity: Verbosity) -> Result<()> {
     }
 
     let mut engine = DebuggerEngine::new(executor, args.breakpoint.clone());
-    engine.stage_execution(&args.function, parsed_args.as_deref());
+    engine.stage_execution(&function, parsed_args.as_deref());
 
-    run_dashboard(engine, &args.function)
+    run_dashboard(engine, &function)
+}
+
+/// Open the storage browser: `tui` without `--function`.
+fn tui_browser(args: TuiArgs) -> Result<()> {
+    let mut storage = match &args.storage {
+        Some(storage_json) => Some(parse_storage(storage_json)?),
+        None => None,
+    };
+    if let Some(import_path) = &args.load_storage {
+        let imported = crate::inspector::storage::StorageState::import_from_file(import_path)?;
+        storage = Some(serde_json::to_string(&imported).map_err(|e| {
+            DebuggerError::StorageError(format!("Failed to serialize imported storage: {}", e))
+        })?);
+    }
+
+    run_browser(&ReplConfig {
+        contract_path: args.contract,
+        network_snapshot: args.network_snapshot,
+        storage,
+        initial_state: None,
+        mocks: Vec::new(),
+    })
 }
 
 /// Inspect a WASM contract
-pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
+pub fn inspect(args: InspectArgs, verbosity: Verbosity) -> Result<()> {
     let wasm_file = crate::utils::wasm::load_wasm(&args.contract)
         .with_context(|| format!("Failed to read WASM file: {:?}", args.contract))?;
     if let Some(expected) = &args.expected_hash {
@@ -1999,6 +3804,7 @@ pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
         }
     }
 
+    let wasm_hash = wasm_file.sha256_hash;
     let bytes = wasm_file.bytes;
 
     if args.source_map_diagnostics {
@@ -2006,20 +3812,38 @@ pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
     }
 
     let info = crate::utils::wasm::get_module_info(&bytes)?;
+    let event_schema = if args.events {
+        Some(crate::analyzer::event_schema::extract_event_schema(&bytes)?)
+    } else {
+        None
+    };
+    let spec = if args.functions {
+        let (spec, status) =
+            crate::utils::spec_cache::spec_for(&bytes, &wasm_hash, !args.no_spec_cache)?;
+        Some((spec, status))
+    } else {
+        None
+    };
+    let verbose = verbosity == Verbosity::Verbose;
+
     if args.format == OutputFormat::Json {
-        let exported_functions = if args.functions {
-            Some(crate::utils::wasm::parse_function_signatures(&bytes)?)
-        } else {
-            None
-        };
-        let result = serde_json::json!({
+        let mut result = serde_json::json!({
             "contract": args.contract.display().to_string(),
             "size_bytes": info.total_size,
             "types": info.type_count,
             "functions": info.function_count,
             "exports": info.export_count,
-            "exported_functions": exported_functions,
+            "exported_functions": spec.as_ref().map(|(spec, _)| &spec.functions),
+            "event_schema": event_schema,
         });
+        if let Some((spec, status)) = &spec {
+            result["function_docs"] = serde_json::json!(spec.docs);
+            result["user_types"] = serde_json::json!(spec.user_types);
+            result["errors"] = serde_json::json!(spec.errors);
+            if verbose {
+                result["spec_cache"] = serde_json::json!(status);
+            }
+        }
         let envelope = crate::output::VersionedOutput::success("inspect", result);
         println!(
             "{}",
@@ -2035,10 +3859,19 @@ pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
     println!("Types: {}", info.type_count);
     println!("Functions: {}", info.function_count);
     println!("Exports: {}", info.export_count);
-    if args.functions {
-        let sigs = crate::utils::wasm::parse_function_signatures(&bytes)?;
+    if let Some((spec, status)) = &spec {
+        if verbose {
+            match SpecCache::default_dir() {
+                Some(dir) if *status != SpecCacheStatus::Disabled => println!(
+                    "Spec cache: {} ({})",
+                    status,
+                    SpecCache::new(dir).entry_path(&wasm_hash).display()
+                ),
+                _ => println!("Spec cache: {}", status),
+            }
+        }
         println!("Exported functions:");
-        for sig in &sigs {
+        for sig in &spec.functions {
             let params: Vec<String> = sig
                 .params
                 .iter()
@@ -2046,6 +3879,47 @@ pub fn inspect(args: InspectArgs, _verbosity: Verbosity) -> Result<()> {
                 .collect();
             let ret = sig.return_type.as_deref().unwrap_or("()");
             println!("  {}({}) -> {}", sig.name, params.join(", "), ret);
+            if let Some(doc) = spec.docs.get(&sig.name) {
+                for line in doc.lines() {
+                    println!("      {}", line);
+                }
+            }
+        }
+        if !spec.user_types.is_empty() {
+            println!("User-defined types:");
+            for udt in &spec.user_types {
+                println!(
+                    "  {} {} {{ {} }}",
+                    udt.kind,
+                    udt.name,
+                    udt.members.join(", ")
+                );
+            }
+        }
+        if !spec.errors.is_empty() {
+            println!("Errors:");
+            for error in &spec.errors {
+                println!("  {} = {}", error.name, error.code);
+            }
+        }
+    }
+    if let Some(schema) = event_schema {
+        println!("Events:");
+        if schema.events.is_empty() {
+            println!("  (no constant event topics found)");
+        }
+        for event in &schema.events {
+            let data = event
+                .data
+                .as_ref()
+                .map(|kinds| kinds.join(" | "))
+                .unwrap_or_else(|| "any".to_string());
+            println!(
+                "  {} (data: {}) emitted by {}",
+                event.topic,
+                data,
+                event.emitted_by.join(", ")
+            );
         }
     }
     Ok(())
@@ -2122,6 +3996,146 @@ fn inspect_source_map_diagnostics(args: &InspectArgs, wasm_bytes: &[u8]) -> Resu
     Ok(())
 }
 
+/// Open and lock the `--env` environment and fill in the contract, snapshot
+/// and mocks it provides.
+fn enter_environment(args: &mut RunArgs, name: &str) -> Result<Environment> {
+    let environment = EnvironmentStore::open_default()?.open(name)?;
+    if args.contract.is_none() {
+        let contract = environment.default_contract()?.ok_or_else(|| {
+            DebuggerError::InvalidArguments(format!(
+                "Environment '{}' has no registered contract; pass --contract",
+                name
+            ))
+        })?;
+        args.contract = Some(contract);
+    }
+    if args.network_snapshot.is_none() {
+        args.network_snapshot = environment.snapshot_path();
+    }
+    args.mock
+        .splice(0..0, environment.manifest().mocks.iter().cloned());
+    print_info(format!("Using environment '{}'", name));
+    Ok(environment)
+}
+
+/// Resolve `--package`, or a `--contract` pointing at a cargo project, to the
+/// WASM it builds, running the build first with `--build`.
+fn resolve_cargo_contract(args: &RunArgs) -> Result<Option<CargoContract>> {
+    let project = args
+        .contract
+        .as_deref()
+        .filter(|path| crate::utils::cargo::is_cargo_project(path));
+    if project.is_none() {
+        if args.package.is_some() && args.contract.is_some() {
+            return Err(DebuggerError::InvalidArguments(
+                "--contract must be a cargo crate or workspace directory when --package is given"
+                    .to_string(),
+            )
+            .into());
+        }
+        if args.package.is_none() {
+            if args.build {
+                return Err(DebuggerError::InvalidArguments(
+                    "--build needs --package or a --contract pointing at a cargo project"
+                        .to_string(),
+                )
+                .into());
+            }
+            return Ok(None);
+        }
+    }
+
+    if args.build {
+        print_info(format!(
+            "Building {} with cargo ({} profile)...",
+            args.package.as_deref().unwrap_or("contract"),
+            args.cargo_profile
+        ));
+    }
+    let resolved = crate::utils::cargo::resolve_contract(&CargoRequest {
+        contract: project,
+        package: args.package.as_deref(),
+        build: args.build,
+        profile: &args.cargo_profile,
+    })?;
+    print_info(format!(
+        "Resolved cargo package `{}` to {}",
+        resolved.package,
+        resolved.wasm_path.display()
+    ));
+    Ok(Some(resolved))
+}
+
+/// Call every spec function once with the simplest generated arguments.
+fn run_smoke(args: &RunArgs) -> Result<()> {
+    let contract = args
+        .contract
+        .as_ref()
+        .expect("contract is required for run");
+    print_info(format!("Loading contract: {:?}", contract));
+    let wasm_file = crate::utils::wasm::load_wasm(contract)
+        .with_context(|| format!("Failed to read WASM file: {:?}", contract))?;
+
+    let storage = match &args.storage {
+        Some(storage_json) => Some(parse_storage(storage_json)?),
+        None => None,
+    };
+    let config = SymbolicConfig {
+        timeout_secs: args.timeout,
+        ..SymbolicConfig::fast()
+    };
+    let report = SmokeRunner::new(1, config)
+        .with_storage(storage)
+        .run(&wasm_file.bytes)?;
+
+    finish_smoke_report(
+        &report,
+        contract,
+        args.junit.as_deref(),
+        args.is_json_output(),
+    )
+}
+
+/// Print a smoke report, write it as JUnit if asked, and fail on traps or errors.
+fn finish_smoke_report(
+    report: &SmokeReport,
+    contract: &std::path::Path,
+    junit: Option<&std::path::Path>,
+    json: bool,
+) -> Result<()> {
+    if json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(report).map_err(|e| {
+                DebuggerError::FileError(format!("Failed to serialize smoke report: {}", e))
+            })?
+        );
+    } else {
+        print_info("\n--- Functions ---");
+        for line in report.render().lines() {
+            print_info(line);
+        }
+    }
+
+    if let Some(path) = junit {
+        let suite = contract
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| contract.display().to_string());
+        report.write_junit(path, &suite)?;
+        print_success(format!("JUnit report written to: {:?}", path));
+    }
+
+    if report.has_failures() {
+        return Err(DebuggerError::ExecutionError(format!(
+            "{} function(s) trapped or errored",
+            report.count(SmokeStatus::Trap) + report.count(SmokeStatus::Error)
+        ))
+        .into());
+    }
+    Ok(())
+}
+
 /// Run symbolic execution analysis
 pub fn symbolic(args: SymbolicArgs, _verbosity: Verbosity) -> Result<()> {
     print_info(format!("Loading contract: {:?}", args.contract));
@@ -2130,7 +4144,20 @@ pub fn symbolic(args: SymbolicArgs, _verbosity: Verbosity) -> Result<()> {
 
     let analyzer = SymbolicAnalyzer::new();
     let config = symbolic_config_from_args(&args)?;
-    let report = analyzer.analyze_with_config(&wasm_file.bytes, &args.function, &config)?;
+
+    if args.all_functions {
+        let storage = config.storage_seed.clone();
+        let report = SmokeRunner::new(config.max_paths, config)
+            .with_storage(storage)
+            .run(&wasm_file.bytes)?;
+        return finish_smoke_report(&report, &args.contract, args.junit.as_deref(), false);
+    }
+
+    let function = args
+        .function
+        .as_deref()
+        .expect("function is required unless --all-functions is set");
+    let report = analyzer.analyze_with_config(&wasm_file.bytes, function, &config)?;
 
     println!("{}", render_symbolic_report(&report));
 
@@ -2149,7 +4176,11 @@ pub fn symbolic(args: SymbolicArgs, _verbosity: Verbosity) -> Result<()> {
 }
 
 /// Analyze a contract
-pub fn analyze(args: AnalyzeArgs, _verbosity: Verbosity) -> Result<()> {
+pub fn analyze(mut args: AnalyzeArgs, _verbosity: Verbosity) -> Result<()> {
+    crate::utils::json_input::resolve_json_flags(&mut [
+        ("--args", &mut args.args),
+        ("--storage", &mut args.storage),
+    ])?;
     print_info(format!("Loading contract: {:?}", args.contract));
     let wasm_file = crate::utils::wasm::load_wasm(&args.contract)
         .with_context(|| format!("Failed to read WASM file: {:?}", args.contract))?;
@@ -2211,6 +4242,7 @@ pub fn analyze(args: AnalyzeArgs, _verbosity: Verbosity) -> Result<()> {
     let output = AnalyzeCommandOutput {
         findings: report.findings,
         dynamic_analysis,
+        errors: crate::utils::wasm::parse_custom_errors(&wasm_file.bytes)?,
         warnings,
     };
 
@@ -2253,12 +4285,40 @@ pub async fn repl(args: ReplArgs) -> Result<()> {
         print_verbose("Checksum verified ✓");
     }
 
-    crate::repl::start_repl(ReplConfig {
+    let mut environment = match &args.env {
+        Some(name) => Some(EnvironmentStore::open_default()?.open(name)?),
+        None => None,
+    };
+    let config = ReplConfig {
+        network_snapshot: args
+            .network_snapshot
+            .or_else(|| environment.as_ref().and_then(Environment::snapshot_path)),
+        initial_state: match &environment {
+            Some(environment) => environment.load_state(&wasm_file.sha256_hash)?,
+            None => None,
+        },
+        mocks: environment
+            .as_ref()
+            .map(|environment| environment.manifest().mocks.clone())
+            .unwrap_or_default(),
         contract_path: args.contract,
-        network_snapshot: args.network_snapshot,
         storage: args.storage,
-    })
-    .await
+    };
+
+    let state = match &args.script {
+        Some(script) => crate::repl::run_script(config, script).await?,
+        None => crate::repl::run_repl(config).await?,
+    };
+    if args.commit {
+        if let Some(environment) = environment.as_mut() {
+            environment.commit(&state, &wasm_file.sha256_hash)?;
+            print_success(format!(
+                "Committed session state to environment '{}'",
+                environment.name()
+            ));
+        }
+    }
+    Ok(())
 }
 
 /// Show budget trend chart
@@ -2268,7 +4328,16 @@ pub fn show_budget_trend(
     regression: crate::history::RegressionConfig,
 ) -> Result<()> {
     let manager = HistoryManager::new()?;
-    let mut records = manager.filter_history(contract, function)?;
+    let loaded = manager.read_history()?;
+    warn_skipped_history(loaded.skipped);
+    let mut records: Vec<RunHistory> = loaded
+        .records
+        .into_iter()
+        .filter(|r| {
+            contract.is_none_or(|c| r.contract_hash == c)
+                && function.is_none_or(|f| r.function == f)
+        })
+        .collect();
 
     crate::history::sort_records_by_date(&mut records);
 
@@ -2342,6 +4411,239 @@ pub fn show_budget_trend(
     Ok(())
 }
 
+/// Show or clear the on-disk contract artifact cache.
+pub fn cache(args: CacheArgs) -> Result<()> {
+    let dir = crate::utils::artifact_cache::ArtifactCache::default_dir().ok_or_else(|| {
+        DebuggerError::FileError("Could not determine cache directory".to_string())
+    })?;
+    let results = crate::report::cache::ResultCache::new(dir.join("results"));
+    let specs = SpecCache::new(dir.join("specs"));
+    let cache = crate::utils::artifact_cache::ArtifactCache::new(dir);
+
+    match args.action {
+        CacheAction::Info => {
+            let stats = cache.stats();
+            let results = results.stats();
+            let specs = specs.stats();
+            println!("Cache directory: {}", cache.dir().display());
+            println!("Entries: {}", stats.entries);
+            println!("Size: {} bytes", stats.total_bytes);
+            println!(
+                "Cached results: {} ({} bytes)",
+                results.entries, results.total_bytes
+            );
+            println!(
+                "Cached specs: {} ({} bytes)",
+                specs.entries, specs.total_bytes
+            );
+        }
+        CacheAction::Clear => {
+            let mut removed = cache.clear()?;
+            for extra in [results.clear()?, specs.clear()?] {
+                removed.entries += extra.entries;
+                removed.total_bytes += extra.total_bytes;
+            }
+            print_success(format!(
+                "Removed {} cache entr{} ({} bytes) from {}",
+                removed.entries,
+                if removed.entries == 1 { "y" } else { "ies" },
+                removed.total_bytes,
+                cache.dir().display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub fn config(args: ConfigArgs) -> Result<()> {
+    match args.action {
+        ConfigAction::Validate { paths } => {
+            let paths = if paths.is_empty() {
+                vec![std::path::PathBuf::from(crate::config::DEFAULT_CONFIG_FILE)]
+            } else {
+                paths
+            };
+
+            let mut total = 0;
+            for path in &paths {
+                let problems = crate::config::validate_config_file(path)?;
+                if problems.is_empty() {
+                    print_success(format!("{}: OK", path.display()));
+                    continue;
+                }
+                total += problems.len();
+                for problem in &problems {
+                    match problem.line {
+                        Some(_) => eprintln!("{}:{}", path.display(), problem),
+                        None => eprintln!("{}: {}", path.display(), problem),
+                    }
+                }
+            }
+
+            if total > 0 {
+                return Err(DebuggerError::FileError(format!(
+                    "Found {} problem{} in config",
+                    total,
+                    if total == 1 { "" } else { "s" }
+                ))
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn snapshot(args: SnapshotArgs) -> Result<()> {
+    match args.action {
+        SnapshotAction::Show { path } => {
+            let file = crate::simulator::format::read_file(&path)?;
+            let snapshot = &file.snapshot;
+            let storage_entries: usize = snapshot
+                .contracts
+                .iter()
+                .map(|contract| contract.storage.len())
+                .sum();
+
+            println!("Snapshot: {}", path.display());
+            match &file.header {
+                Some(header) => {
+                    println!(
+                        "Format: v{} ({} body, {} bytes)",
+                        header.version, header.compression, header.body_len
+                    );
+                    println!("Checksum: sha256:{} (verified)", header.checksum);
+                }
+                None => println!("Format: v1-json"),
+            }
+            println!("Network: {}", snapshot.ledger.network_passphrase);
+            println!("Ledger sequence: {}", snapshot.ledger.sequence);
+            println!("Ledger timestamp: {}", snapshot.ledger.timestamp);
+            println!(
+                "Entries: {} ({} accounts, {} contracts, {} storage entries)",
+                crate::simulator::format::entry_count(snapshot),
+                snapshot.accounts.len(),
+                snapshot.contracts.len(),
+                storage_entries
+            );
+            for (contract, dependencies) in &snapshot.dependencies {
+                println!("Depends: {} -> {}", contract, dependencies.join(", "));
+            }
+        }
+        SnapshotAction::Convert {
+            input,
+            output,
+            format,
+        } => {
+            let file = crate::simulator::format::read_file(&input)?;
+            let written =
+                crate::simulator::SnapshotManager::save_as(&file.snapshot, &output, format)?;
+            let format_name = match format {
+                SnapshotFormat::V2 => "v2",
+                SnapshotFormat::V1Json => "v1-json",
+            };
+            print_success(format!(
+                "Wrote {} snapshot to {} ({} bytes)",
+                format_name,
+                output.display(),
+                written
+            ));
+        }
+        SnapshotAction::Patch {
+            input,
+            patch,
+            out,
+            format,
+        } => {
+            let mut file = crate::simulator::format::read_file(&input)?;
+            let format = format.unwrap_or_else(|| file.format());
+            let patch = crate::simulator::patch::SnapshotPatch::load(&patch)?;
+            let summaries = patch.apply(&mut file.snapshot)?;
+            crate::simulator::SnapshotManager::save_as(&file.snapshot, &out, format)?;
+
+            for summary in &summaries {
+                print_info(format!(
+                    "{}: {} entr{}",
+                    summary.operation,
+                    summary.entries.len(),
+                    if summary.entries.len() == 1 {
+                        "y"
+                    } else {
+                        "ies"
+                    }
+                ));
+                for (contract, key) in &summary.entries {
+                    println!("  {} {}", contract, key);
+                }
+            }
+            let total: usize = summaries.iter().map(|s| s.entries.len()).sum();
+            print_success(format!(
+                "Applied {} operation(s) to {} entr{}; wrote {}",
+                summaries.len(),
+                total,
+                if total == 1 { "y" } else { "ies" },
+                out.display()
+            ));
+        }
+        SnapshotAction::Fetch {
+            contract,
+            rpc_url,
+            out,
+            follow_deps,
+            depth,
+            max_entries,
+            format,
+            timeout,
+        } => {
+            use crate::rpc::{fetch_snapshot, FetchOptions, RpcClient};
+
+            let address = crate::utils::ledger_key::parse_contract(&contract)?;
+            let client =
+                RpcClient::new(&rpc_url)?.with_timeout(std::time::Duration::from_secs(timeout));
+            print_info(format!("Fetching {} from {}", contract, rpc_url));
+            let fetched = fetch_snapshot(
+                &client,
+                &address,
+                &FetchOptions {
+                    follow_deps,
+                    depth,
+                    max_entries,
+                },
+            )?;
+            let snapshot = &fetched.snapshot;
+
+            for state in &snapshot.contracts {
+                println!(
+                    "  {} (depth {}, sha256 {}, {} instance entr{})",
+                    state.contract_id,
+                    fetched.depths.get(&state.contract_id).copied().unwrap_or(0),
+                    state.wasm_hash,
+                    state.storage.len(),
+                    if state.storage.len() == 1 { "y" } else { "ies" }
+                );
+            }
+            if !snapshot.dependencies.is_empty() {
+                print_info("Dependencies:");
+                for (contract, dependencies) in &snapshot.dependencies {
+                    println!("  {} -> {}", contract, dependencies.join(", "));
+                }
+            }
+            for note in &fetched.notes {
+                print_warning(note);
+            }
+
+            crate::simulator::SnapshotManager::save_as(snapshot, &out, format)?;
+            print_success(format!(
+                "Fetched {} contract(s) ({} ledger entries) at ledger {}; wrote {}",
+                snapshot.contracts.len(),
+                fetched.entries_read,
+                snapshot.ledger.sequence,
+                out.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
 /// Prune run history according to retention policy.
 pub fn history_prune(args: HistoryPruneArgs) -> Result<()> {
     let policy = crate::history::RetentionPolicy {
@@ -2359,7 +4661,9 @@ pub fn history_prune(args: HistoryPruneArgs) -> Result<()> {
     let manager = HistoryManager::new()?;
 
     if args.dry_run {
-        let mut records = manager.load_history()?;
+        let loaded = manager.read_history()?;
+        warn_skipped_history(loaded.skipped);
+        let mut records = loaded.records;
         let before = records.len();
         HistoryManager::apply_retention(&mut records, &policy);
         let remaining = records.len();
@@ -2392,6 +4696,517 @@ pub fn history_prune(args: HistoryPruneArgs) -> Result<()> {
     Ok(())
 }
 
+pub fn env(args: EnvArgs) -> Result<()> {
+    let store = EnvironmentStore::open_default()?;
+    match args.action {
+        EnvAction::Create {
+            name,
+            from_snapshot,
+            with_contract,
+            label,
+            mock,
+        } => {
+            let mut labels = std::collections::BTreeMap::new();
+            for entry in &label {
+                let (key, value) = entry.split_once('=').ok_or_else(|| {
+                    DebuggerError::InvalidArguments(format!(
+                        "Invalid --label '{}': expected KEY=VALUE",
+                        entry
+                    ))
+                })?;
+                labels.insert(key.trim().to_string(), value.trim().to_string());
+            }
+            let manifest = store.create(NewEnvironment {
+                name,
+                snapshot: from_snapshot,
+                contracts: with_contract,
+                labels,
+                mocks: mock,
+            })?;
+            print_success(format!(
+                "Created environment '{}' in {:?}",
+                manifest.name,
+                store.dir().join(&manifest.name)
+            ));
+        }
+        EnvAction::List => {
+            let manifests = store.list()?;
+            if manifests.is_empty() {
+                print_info("No environments. Create one with `soroban-debug env create NAME`.");
+            }
+            for manifest in manifests {
+                let labels = manifest
+                    .labels
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!(
+                    "{:<24} {} contract(s), {} commit(s), snapshot: {}{}",
+                    manifest.name,
+                    manifest.contracts.len(),
+                    manifest.commits,
+                    if manifest.snapshot.is_some() {
+                        "yes"
+                    } else {
+                        "no"
+                    },
+                    if labels.is_empty() {
+                        String::new()
+                    } else {
+                        format!(", labels: {}", labels)
+                    }
+                );
+            }
+        }
+        EnvAction::Delete { name } => {
+            store.delete(&name)?;
+            print_success(format!("Deleted environment '{}'", name));
+        }
+    }
+    Ok(())
+}
+
+pub fn preset(args: PresetArgs, verbosity: Verbosity) -> Result<()> {
+    let store = PresetStore::open_default()?;
+    match args.action {
+        PresetAction::Run { name, overrides } => {
+            let preset = store.load(&name)?;
+            if let Some(drift) = preset.plan.contract_drift() {
+                print_warning(drift);
+            }
+            let matches = Cli::command()
+                .mut_subcommand("run", |run| run.args_override_self(true))
+                .try_get_matches_from(preset.plan.argv(&overrides))
+                .map_err(|e| {
+                    DebuggerError::InvalidArguments(format!(
+                        "Preset '{}' with the given overrides is not a valid run: {}",
+                        name, e
+                    ))
+                })?;
+            let cli = Cli::from_arg_matches(&matches)
+                .map_err(|e| DebuggerError::InvalidArguments(e.to_string()))?;
+            let (Some(Commands::Run(mut run_args)), Some(run_matches)) =
+                (cli.command, matches.subcommand_matches("run"))
+            else {
+                unreachable!("preset argv always starts with `run`");
+            };
+            crate::cli::flag_rules::check_run(&run_args).map_err(|violation| {
+                DebuggerError::InvalidArguments(format!(
+                    "Preset '{}' with the given overrides is not a valid run: {}",
+                    name, violation
+                ))
+            })?;
+            run_args.command_line = run_flags(run_matches);
+            run_args.conversions = preset.plan.conversions;
+            print_info(format!("Running preset '{}'", name));
+            run(run_args, verbosity)
+        }
+        PresetAction::List => {
+            let presets = store.list()?;
+            if presets.is_empty() {
+                print_info("No presets. Save one with `soroban-debug run ... --save-preset NAME`.");
+            }
+            for preset in presets {
+                let function = preset
+                    .plan
+                    .args
+                    .iter()
+                    .find_map(|token| token.strip_prefix("--function="))
+                    .unwrap_or("-");
+                let contract = preset
+                    .plan
+                    .contract
+                    .as_ref()
+                    .and_then(|path| path.file_name())
+                    .map(|file| file.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "-".to_string());
+                println!(
+                    "{:<24} {} {} (saved {})",
+                    preset.name, contract, function, preset.created_at
+                );
+            }
+            Ok(())
+        }
+        PresetAction::Show { name } => {
+            let preset = store.load(&name)?;
+            println!("{}", preset.plan.command_line());
+            if let Some(hash) = &preset.plan.contract_sha256 {
+                print_info(format!("Contract SHA-256: {}", hash));
+            }
+            if !preset.plan.conversions.is_empty() {
+                print_info(format!(
+                    "Argument conversions: {}",
+                    preset
+                        .plan
+                        .conversions
+                        .keys()
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            if let Some(drift) = preset.plan.contract_drift() {
+                print_warning(drift);
+            }
+            Ok(())
+        }
+        PresetAction::Delete { name } => {
+            store.delete(&name)?;
+            print_success(format!("Deleted preset '{}'", name));
+            Ok(())
+        }
+    }
+}
+
+/// The storage a `--durability` value names.
+fn storage_durability(durability: KeyDurability) -> crate::utils::ledger_key::Durability {
+    use crate::utils::ledger_key::Durability;
+    match durability {
+        KeyDurability::Instance => Durability::Instance,
+        KeyDurability::Persistent => Durability::Persistent,
+        KeyDurability::Temporary => Durability::Temporary,
+    }
+}
+
+fn storage_sort(order: StorageOrder) -> crate::inspector::storage::StorageSort {
+    use crate::inspector::storage::StorageSort;
+    match order {
+        StorageOrder::Key => StorageSort::Key,
+        StorageOrder::Size => StorageSort::Size,
+        StorageOrder::Ttl => StorageSort::Ttl,
+        StorageOrder::Modified => StorageSort::Modified,
+    }
+}
+
+/// Build the ledger key of a contract storage entry, or decode one.
+pub fn key(args: KeyArgs) -> Result<()> {
+    use crate::utils::ledger_key::{self, Durability, KeySpec};
+
+    let durability = storage_durability(args.durability);
+
+    let keys = if let Some(xdr) = &args.decode {
+        vec![ledger_key::decode(xdr)?]
+    } else {
+        let specs = match &args.keys_file {
+            Some(path) => {
+                let list = fs::read_to_string(path).map_err(|e| {
+                    DebuggerError::FileError(format!("Failed to read key list {:?}: {}", path, e))
+                })?;
+                KeySpec::parse_list(&list, durability)?
+            }
+            None => {
+                let key = args
+                    .key
+                    .as_deref()
+                    .map(serde_json::from_str::<serde_json::Value>)
+                    .transpose()
+                    .map_err(|e| {
+                        DebuggerError::InvalidArguments(format!(
+                            "--key must be typed JSON such as '{{\"type\":\"symbol\",\"value\":\"Counter\"}}': {}",
+                            e
+                        ))
+                    })?;
+                if durability == Durability::Instance && key.is_some() {
+                    print_warning(
+                        "Instance storage lives in the contract instance entry; --key does not change its ledger key",
+                    );
+                }
+                vec![KeySpec {
+                    contract: None,
+                    key,
+                    durability,
+                }]
+            }
+        };
+        let env = soroban_sdk::Env::default();
+        specs
+            .iter()
+            .map(|spec| spec.ledger_key(&env, args.contract.as_deref()))
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let encoded = keys
+        .iter()
+        .map(ledger_key::encode)
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(path) = &args.output {
+        let mut body = encoded.join("\n");
+        body.push('\n');
+        fs::write(path, body).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to write key list {:?}: {}", path, e))
+        })?;
+        print_success(format!(
+            "Wrote {} ledger key(s) to {:?}",
+            encoded.len(),
+            path
+        ));
+        return Ok(());
+    }
+
+    if args.format == OutputFormat::Json {
+        let entries: Vec<serde_json::Value> = keys
+            .iter()
+            .zip(&encoded)
+            .map(|(key, xdr)| {
+                serde_json::json!({
+                    "xdr": xdr,
+                    "decoded": ledger_key::describe(key),
+                })
+            })
+            .collect();
+        let output = if args.keys_file.is_some() {
+            serde_json::Value::Array(entries)
+        } else {
+            entries.into_iter().next().unwrap_or_default()
+        };
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output).map_err(|e| {
+                DebuggerError::FileError(format!("Failed to serialize output: {}", e))
+            })?
+        );
+        return Ok(());
+    }
+
+    for (key, xdr) in keys.iter().zip(&encoded) {
+        println!("{}", xdr);
+        if let serde_json::Value::Object(fields) = ledger_key::describe(key) {
+            for (field, value) in fields {
+                match value {
+                    serde_json::Value::String(text) => println!("  {:<12} {}", field, text),
+                    other => println!("  {:<12} {}", field, other),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Import an on-chain invocation with the ledger state it ran against, save
+/// it, and optionally replay it and compare with the on-chain result.
+pub fn import_tx(args: ImportTxArgs) -> Result<()> {
+    use crate::rpc::{import_transaction, ImportedTransaction, RpcClient};
+    use crate::utils::arguments::scval_to_json;
+
+    let (imported, saved_to) = match (&args.from, &args.hash, &args.rpc_url) {
+        (Some(dir), _, _) => (ImportedTransaction::load(dir)?, None),
+        (None, Some(hash), Some(url)) => {
+            let client =
+                RpcClient::new(url)?.with_timeout(std::time::Duration::from_secs(args.timeout));
+            print_info(format!("Fetching transaction {} from {}", hash, url));
+            let imported = import_transaction(&client, hash)?;
+            let dir = args.out.clone().unwrap_or_else(|| {
+                std::path::PathBuf::from(format!(
+                    "tx-{}",
+                    hash.chars().take(12).collect::<String>()
+                ))
+            });
+            imported.save(&dir)?;
+            print_success(format!("Saved scenario to {:?}", dir));
+            (imported, Some(dir))
+        }
+        _ => {
+            return Err(DebuggerError::InvalidArguments(
+                "import-tx needs a transaction hash and --rpc-url, or --from".to_string(),
+            )
+            .into())
+        }
+    };
+
+    let invocation = &imported.invocation;
+    let call_args: Vec<serde_json::Value> = invocation.args.iter().map(scval_to_json).collect();
+    print_info(format!(
+        "Transaction {} (ledger {}, {})",
+        imported.hash, imported.ledger, imported.network_passphrase
+    ));
+    print_info(format!("  Contract: {}", invocation.contract));
+    print_info(format!("  Function: {}", invocation.function));
+    print_info(format!(
+        "  Args:     {}",
+        serde_json::Value::Array(call_args)
+    ));
+    print_info(format!("  Auth:     {} entry(ies)", invocation.auth.len()));
+    print_info(format!("  On-chain: {}", imported.outcome));
+    for note in &imported.notes {
+        print_warning(note);
+    }
+
+    if let (false, Some(dir)) = (args.run, &saved_to) {
+        print_info(format!(
+            "Replay it with: soroban-debug import-tx --from {}",
+            dir.display()
+        ));
+        return Ok(());
+    }
+
+    let mut executor = imported.executor(args.mock_auth)?;
+    println!("\n--- Execution Start ---\n");
+    let local = match executor.execute_xdr_args(&invocation.function, &invocation.args) {
+        Ok(_) => executor
+            .last_execution()
+            .map(|record| record.result.clone())
+            .unwrap_or_else(|| Err("no execution record".to_string())),
+        Err(e) => Err(e.to_string()),
+    };
+    println!("\n--- Execution Complete ---\n");
+    match &local {
+        Ok(value) => print_result(format!("Result: {}", scval_to_json(value))),
+        Err(error) => print_warning(format!("Execution failed: {}", error)),
+    }
+
+    if let Some(record) = executor.last_execution() {
+        let storage_diff = crate::inspector::storage::StorageInspector::compute_diff_with_writes(
+            &record.storage_before,
+            &record.storage_after,
+            executor.last_storage_writes(),
+            &[],
+        );
+        if !storage_diff.is_empty() {
+            print_info("\n--- Storage Changes ---");
+            crate::inspector::storage::StorageInspector::display_diff(&storage_diff);
+        }
+    }
+    let events = executor.get_events()?;
+    if !events.is_empty() {
+        print_info("\n--- Events ---");
+        for line in EventInspector::format_events(&events) {
+            print_info(line);
+        }
+    }
+    crate::inspector::budget::BudgetInspector::display(executor.host());
+
+    match imported.outcome.divergence(&local) {
+        Some(divergence) => Err(DebuggerError::ExecutionError(format!(
+            "Local replay diverged from the chain: the call {}",
+            divergence
+        ))
+        .into()),
+        None => {
+            print_success("Local replay matches the on-chain result");
+            Ok(())
+        }
+    }
+}
+
+pub fn history(args: HistoryArgs) -> Result<()> {
+    match args.action {
+        HistoryAction::Compact { keep_last } => {
+            let report = HistoryManager::new()?.compact_history(keep_last)?;
+            if !Formatter::is_quiet() {
+                println!(
+                    "Dropped {} unreadable line(s) and {} old record(s). {} record(s) remaining.",
+                    report.corrupt, report.truncated, report.remaining
+                );
+            }
+        }
+        HistoryAction::Events {
+            contract,
+            recent,
+            fail_on,
+            format,
+        } => history_events(&contract, recent, &fail_on, format)?,
+    }
+    Ok(())
+}
+
+/// `history events`: per-function topic signatures of a contract's runs.
+fn history_events(
+    contract: &std::path::Path,
+    recent: usize,
+    fail_on: &[TopicChange],
+    format: OutputFormat,
+) -> Result<()> {
+    use crate::history::TopicStatus;
+
+    let loaded = HistoryManager::new()?.read_history()?;
+    warn_skipped_history(loaded.skipped);
+    let canonical = contract.canonicalize().ok();
+    let records: Vec<RunHistory> = loaded
+        .records
+        .into_iter()
+        .filter(|r| {
+            let recorded = std::path::Path::new(&r.contract_hash);
+            recorded == contract || canonical.is_some() && recorded.canonicalize().ok() == canonical
+        })
+        .collect();
+    let presence = crate::history::event_topic_history(&records, recent);
+
+    if format == OutputFormat::Json {
+        let output = serde_json::json!({
+            "contract": contract.display().to_string(),
+            "recent": recent,
+            "topics": presence,
+        });
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&output).map_err(|e| {
+                DebuggerError::FileError(format!("Failed to serialize history events: {}", e))
+            })?
+        );
+    } else if !Formatter::is_quiet() {
+        println!("Event Topics: {}", contract.display());
+        if presence.is_empty() {
+            println!("No runs with recorded events found.");
+            println!("Tip: events are recorded by `soroban-debug run` from this version on.");
+        }
+        let mut current = None;
+        for topic in &presence {
+            if current != Some(&topic.function) {
+                println!("\n{} ({} run(s))", topic.function, topic.tracked_runs);
+                current = Some(&topic.function);
+            }
+            let marker = match topic.status {
+                TopicStatus::Present => "",
+                TopicStatus::Added => "  [added]",
+                TopicStatus::Missing => "  [missing]",
+            };
+            println!(
+                "  {}: {} run(s), first {}, last {}{}",
+                topic.signature, topic.runs, topic.first_seen, topic.last_seen, marker
+            );
+        }
+    }
+
+    let failed: Vec<String> = presence
+        .iter()
+        .filter(|topic| match topic.status {
+            TopicStatus::Present => false,
+            TopicStatus::Added => fail_on.contains(&TopicChange::Added),
+            TopicStatus::Missing => fail_on.contains(&TopicChange::Missing),
+        })
+        .map(|topic| {
+            let change = if topic.status == TopicStatus::Added {
+                "added"
+            } else {
+                "missing"
+            };
+            format!("{}: {} ({})", topic.function, topic.signature, change)
+        })
+        .collect();
+    if !failed.is_empty() {
+        return Err(DebuggerError::ExecutionError(format!(
+            "{} event topic(s) changed in recent runs: {}",
+            failed.len(),
+            failed.join(", ")
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+/// Tell the user when a crashed writer left unreadable lines at the end of the history.
+fn warn_skipped_history(skipped: usize) {
+    if skipped > 0 {
+        print_warning(format!(
+            "Skipped {} unreadable line(s) at the end of the history file \
+             (run `soroban-debug history compact` to remove them)",
+            skipped
+        ));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
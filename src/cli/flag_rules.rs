@@ -0,0 +1,425 @@
+//! Flag combinations `run` rejects, and why.
+//!
+//! Relations that explain themselves stay on the clap attributes in
+//! [`super::args`] (`--commit` requires `--env`, `--junit` requires
+//! `--smoke`). The ones a user would not guess are listed in [`RUN_RULES`]
+//! with a reason, and [`check_run`] enforces them right after parsing so an
+//! invalid combination fails with one line saying what to do instead, rather
+//! than being silently ignored halfway through the run. A new `run` flag that
+//! is ignored or misbehaves next to another one belongs in the table; its test
+//! exercises every entry.
+
+use super::args::{RunArgs, FULL_DEBUG_MAX_REPEAT};
+use clap::error::ErrorKind;
+use std::fmt;
+
+/// A `run` flag and whether it was given.
+pub struct Flag {
+    /// The long name, without dashes.
+    pub name: &'static str,
+    pub given: fn(&RunArgs) -> bool,
+}
+
+pub enum Relation {
+    /// `flag` does nothing without `other`.
+    Requires,
+    /// One of the two would be ignored.
+    ConflictsWith,
+}
+
+pub struct FlagRule {
+    pub flag: Flag,
+    pub relation: Relation,
+    pub other: Flag,
+    /// Why the combination is invalid and what to do instead.
+    pub reason: &'static str,
+}
+
+macro_rules! flag {
+    ($name:literal, $given:expr) => {
+        Flag {
+            name: $name,
+            given: $given,
+        }
+    };
+}
+
+macro_rules! rule {
+    ($flag:expr, conflicts $other:expr, $reason:literal) => {
+        FlagRule {
+            flag: $flag,
+            relation: Relation::ConflictsWith,
+            other: $other,
+            reason: $reason,
+        }
+    };
+    ($flag:expr, requires $other:expr, $reason:literal) => {
+        FlagRule {
+            flag: $flag,
+            relation: Relation::Requires,
+            other: $other,
+            reason: $reason,
+        }
+    };
+}
+
+const BATCH_ARGS: Flag = flag!("batch-args", |a| a.batch_args.is_some());
+const REPEAT: Flag = flag!("repeat", |a| a.repeat.is_some());
+const VARY_SEED: Flag = flag!("vary-seed", |a| a.vary_seed);
+const FRESH_STATE: Flag = flag!("fresh-state", |a| a.fresh_state);
+const REPEAT_MANY: Flag = flag!("repeat", |a| {
+    a.repeat.is_some_and(|n| n > FULL_DEBUG_MAX_REPEAT)
+});
+const DRY_RUN: Flag = flag!("dry-run", |a| a.dry_run);
+const BREAKPOINT: Flag = flag!("breakpoint", |a| !a.breakpoint.is_empty());
+const ARGS: Flag = flag!("args", |a| a.args.is_some());
+const INSTRUCTION_DEBUG: Flag = flag!("instruction-debug", |a| a.instruction_debug);
+const STEP_INSTRUCTIONS: Flag = flag!("step-instructions", |a| a.step_instructions);
+const TRACE_OUTPUT: Flag = flag!("trace-output", |a| a.trace_output.is_some());
+const EXPORT_STORAGE: Flag = flag!("export-storage", |a| a.export_storage.is_some());
+const GENERATE_TEST: Flag = flag!("generate-test", |a| a.generate_test.is_some());
+const OVERWRITE: Flag = flag!("overwrite", |a| a.overwrite);
+// `--overwrite` also replaces an existing `--dump-storage` file.
+const REPLACED_FILE: Flag = flag!("generate-test", |a| {
+    a.generate_test.is_some() || a.dump_storage.is_some()
+});
+const SAVE_OUTPUT: Flag = flag!("save-output", |a| a.save_output.is_some());
+const APPEND: Flag = flag!("append", |a| a.append);
+const STORAGE: Flag = flag!("storage", |a| a.storage.is_some());
+const IMPORT_STORAGE: Flag = flag!("import-storage", |a| a.import_storage.is_some());
+const SERVER: Flag = flag!("server", |a| a.server);
+const REMOTE: Flag = flag!("remote", |a| a.remote.is_some());
+const TLS_CERT: Flag = flag!("tls-cert", |a| a.tls_cert.is_some());
+const TLS_KEY: Flag = flag!("tls-key", |a| a.tls_key.is_some());
+const CACHE_RESULTS: Flag = flag!("cache-results", |a| a.cache_results);
+const FULL_DEBUG: Flag = flag!("full-debug", |a| a.full_debug);
+const NO_CACHE: Flag = flag!("no-cache", |a| a.no_cache);
+// `--env` supplies the environment's snapshot after parsing.
+const NETWORK_SNAPSHOT: Flag = flag!("network-snapshot", |a| {
+    a.network_snapshot.is_some() || a.env.is_some()
+});
+const REQUIRE_MATCHING_CODE: Flag = flag!("require-matching-code", |a| a.require_matching_code);
+const USE_SNAPSHOT_CODE: Flag = flag!("use-snapshot-code", |a| a.use_snapshot_code);
+const DUMP_RING: Flag = flag!("dump-ring", |a| a.dump_ring);
+const RING_OFF: Flag = flag!("ring-size", |a| a.ring_size == 0);
+// `--calls-file` is a list of `--call`s.
+const CALL: Flag = flag!("call", |a| !a.call.is_empty() || a.calls_file.is_some());
+const SAVE_PRESET: Flag = flag!("save-preset", |a| a.save_preset.is_some());
+const LINK: Flag = flag!("link", |a| !a.link.is_empty());
+const CONSTRUCTOR_ARGS: Flag = flag!("constructor-args", |a| a.constructor_args.is_some());
+const SOURCE_ACCOUNT: Flag = flag!("source-account", |a| a.source_account.is_some());
+const SAVE_STATE: Flag = flag!("save-state", |a| a.save_state.is_some());
+const LOAD_STATE: Flag = flag!("load-state", |a| a.load_state.is_some());
+const REGISTER_ALL_FROM_SNAPSHOT: Flag = flag!("register-all-from-snapshot", |a| {
+    a.register_all_from_snapshot
+});
+
+/// Every `run` flag combination rejected after parsing.
+pub static RUN_RULES: &[FlagRule] = &[
+    rule!(BATCH_ARGS, conflicts BREAKPOINT,
+        "batch cases run in parallel and never pause; debug a single case with --args instead"),
+    rule!(BATCH_ARGS, conflicts REPEAT,
+        "both run the function many times; list the argument sets to repeat in the batch file"),
+    rule!(BATCH_ARGS, conflicts ARGS,
+        "each batch case brings its own arguments; add these arguments to the batch file"),
+    rule!(BATCH_ARGS, conflicts DRY_RUN,
+        "a dry run does not execute anything; drop --dry-run to run the batch"),
+    rule!(BATCH_ARGS, conflicts INSTRUCTION_DEBUG,
+        "instruction stepping needs a single interactive run; debug one case with --args instead"),
+    rule!(BATCH_ARGS, conflicts TRACE_OUTPUT,
+        "batch runs do not record a trace; run the case to trace with --args instead"),
+    rule!(REPEAT, conflicts INSTRUCTION_DEBUG,
+        "repeated runs do not pause for stepping; drop --repeat to step through one run"),
+    rule!(REPEAT, conflicts TRACE_OUTPUT,
+        "repeated runs only report aggregate statistics; drop --repeat to trace one run"),
+    rule!(REPEAT, conflicts EXPORT_STORAGE,
+        "repeated runs only report aggregate statistics; drop --repeat to export the storage of one run"),
+    rule!(REPEAT, conflicts GENERATE_TEST,
+        "repeated runs only report aggregate statistics; drop --repeat to generate a test from one run"),
+    rule!(VARY_SEED, requires REPEAT,
+        "a single run uses a single seed; add --repeat N to give each iteration its own"),
+    rule!(FRESH_STATE, requires REPEAT,
+        "a single run always starts from a new environment; add --repeat N to isolate each iteration"),
+    rule!(FULL_DEBUG, conflicts REPEAT_MANY,
+        "every diagnostic of every iteration is collected; use --repeat 3 or fewer, or pick sections with --show"),
+    rule!(DRY_RUN, conflicts BREAKPOINT,
+        "a dry run only loads the contract, so no breakpoint can be hit; drop --dry-run"),
+    rule!(DRY_RUN, conflicts TRACE_OUTPUT,
+        "a dry run only loads the contract, so there is nothing to trace; drop --dry-run"),
+    rule!(DRY_RUN, conflicts EXPORT_STORAGE,
+        "a dry run only loads the contract, so there is no resulting storage to export; drop --dry-run"),
+    rule!(DRY_RUN, conflicts GENERATE_TEST,
+        "a dry run only loads the contract, so there is no execution to generate a test from; drop --dry-run"),
+    rule!(IMPORT_STORAGE, conflicts STORAGE,
+        "the imported file replaces the --storage JSON; merge the entries into one of them"),
+    rule!(SERVER, conflicts REMOTE,
+        "--server waits for a client and --remote is one; start them as separate processes"),
+    rule!(STEP_INSTRUCTIONS, requires INSTRUCTION_DEBUG,
+        "instruction stepping only exists in instruction debug mode; add --instruction-debug"),
+    rule!(APPEND, requires SAVE_OUTPUT,
+        "it appends to the --save-output file; add --save-output FILE"),
+    rule!(OVERWRITE, requires REPLACED_FILE,
+        "it replaces the --generate-test or --dump-storage file; add one of them"),
+    rule!(TLS_CERT, requires SERVER,
+        "only the debug server terminates TLS; add --server or drop the TLS flags"),
+    rule!(TLS_CERT, requires TLS_KEY,
+        "the server needs the certificate's private key as well; add --tls-key FILE"),
+    rule!(TLS_KEY, requires TLS_CERT,
+        "the server needs the certificate for the key as well; add --tls-cert FILE"),
+    rule!(NO_CACHE, requires CACHE_RESULTS,
+        "results are only cached with --cache-results, so there is nothing to skip; drop --no-cache"),
+    rule!(REQUIRE_MATCHING_CODE, requires NETWORK_SNAPSHOT,
+        "the code is compared with the contracts deployed in a snapshot; add --network-snapshot FILE"),
+    rule!(USE_SNAPSHOT_CODE, requires NETWORK_SNAPSHOT,
+        "the code is taken from a contract deployed in a snapshot; add --network-snapshot FILE"),
+    rule!(REQUIRE_MATCHING_CODE, conflicts USE_SNAPSHOT_CODE,
+        "the snapshot's own code always matches it; drop --require-matching-code"),
+    rule!(DUMP_RING, conflicts RING_OFF,
+        "--ring-size 0 turns the trace ring off, so there is nothing to dump; drop one of them"),
+    rule!(REGISTER_ALL_FROM_SNAPSHOT, requires NETWORK_SNAPSHOT,
+        "the contracts are registered from a snapshot's code; add --network-snapshot FILE"),
+    rule!(REGISTER_ALL_FROM_SNAPSHOT, conflicts REPEAT,
+        "repeated runs do not set up other contracts; drop --repeat to call the snapshot's contracts"),
+    rule!(LINK, conflicts REPEAT,
+        "repeated runs do not set up other contracts; drop --repeat to call the linked contracts"),
+    rule!(LINK, conflicts BATCH_ARGS,
+        "batch cases do not set up other contracts; run each case with --args instead"),
+    rule!(CONSTRUCTOR_ARGS, conflicts REPEAT,
+        "repeated runs do not pass constructor arguments; drop --repeat to run the constructor"),
+    rule!(CONSTRUCTOR_ARGS, conflicts BATCH_ARGS,
+        "batch cases do not pass constructor arguments; run each case with --args instead"),
+    rule!(SOURCE_ACCOUNT, conflicts REPEAT,
+        "repeated runs use a fresh source account; drop --repeat to run as ADDRESS"),
+    rule!(SOURCE_ACCOUNT, conflicts BATCH_ARGS,
+        "batch cases use a fresh source account; run each case with --args instead"),
+    rule!(SAVE_STATE, conflicts REPEAT,
+        "repeated runs only report aggregate statistics; drop --repeat to save the state of one run"),
+    rule!(SAVE_STATE, conflicts BATCH_ARGS,
+        "batch cases each run on their own state; run one case with --args to save it"),
+    rule!(LOAD_STATE, conflicts REPEAT,
+        "repeated runs start from a freshly loaded contract; drop --repeat to resume the saved state"),
+    rule!(LOAD_STATE, conflicts BATCH_ARGS,
+        "batch cases start from a freshly loaded contract; run one case with --args to resume the saved state"),
+    rule!(LOAD_STATE, conflicts NETWORK_SNAPSHOT,
+        "the saved state already holds the ledger; drop --network-snapshot or --env"),
+    rule!(LOAD_STATE, conflicts CONSTRUCTOR_ARGS,
+        "the saved contract was constructed in the run that saved it; drop --constructor-args"),
+    rule!(CALL, conflicts REPEAT,
+        "repeated runs call one function; drop --repeat to run the call sequence once"),
+    rule!(CALL, conflicts BATCH_ARGS,
+        "batch cases call one function in parallel; list the calls to make in order with --call"),
+    rule!(CALL, conflicts DRY_RUN,
+        "a dry run only loads the contract, so no call is made; drop --dry-run"),
+    rule!(CALL, conflicts INSTRUCTION_DEBUG,
+        "instruction stepping follows a single call; step through it with --function and --args"),
+    rule!(CALL, conflicts CACHE_RESULTS,
+        "cached results are keyed on a single call; drop --cache-results to run the sequence"),
+    rule!(CALL, conflicts SAVE_PRESET,
+        "presets hold a single call; save the sequence as a --calls-file instead"),
+];
+
+/// A [`FlagRule`] the arguments break.
+#[derive(Clone, Copy)]
+pub struct FlagViolation {
+    pub rule: &'static FlagRule,
+}
+
+impl FlagViolation {
+    /// The clap error kind to report the violation as.
+    pub fn kind(&self) -> ErrorKind {
+        match self.rule.relation {
+            Relation::Requires => ErrorKind::MissingRequiredArgument,
+            Relation::ConflictsWith => ErrorKind::ArgumentConflict,
+        }
+    }
+}
+
+impl fmt::Display for FlagViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rule = self.rule;
+        let relation = match rule.relation {
+            Relation::Requires => "requires",
+            Relation::ConflictsWith => "cannot be used with",
+        };
+        write!(
+            f,
+            "--{} {} --{}: {}",
+            rule.flag.name, relation, rule.other.name, rule.reason
+        )
+    }
+}
+
+impl fmt::Debug for FlagViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// The first rule in [`RUN_RULES`] that `args` break, if any.
+pub fn check_run(args: &RunArgs) -> std::result::Result<(), FlagViolation> {
+    let broken = RUN_RULES.iter().find(|rule| {
+        (rule.flag.given)(args)
+            && match rule.relation {
+                Relation::Requires => !(rule.other.given)(args),
+                Relation::ConflictsWith => (rule.other.given)(args),
+            }
+    });
+    match broken {
+        Some(rule) => Err(FlagViolation { rule }),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Commands};
+    use clap::{CommandFactory, Parser};
+
+    /// Command-line tokens that give each flag in [`RUN_RULES`].
+    fn tokens(flag: &Flag) -> &'static [&'static str] {
+        match flag.name {
+            "batch-args" => &["--batch-args", "cases.json"],
+            "repeat" => &["--repeat", "10"],
+            "vary-seed" => &["--vary-seed"],
+            "fresh-state" => &["--fresh-state"],
+            "link" => &["--link", "token=token.wasm"],
+            "constructor-args" => &["--constructor-args", "[1]"],
+            "source-account" => &[
+                "--source-account",
+                "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+            ],
+            "save-state" => &["--save-state", "state.json"],
+            "load-state" => &["--load-state", "state.json"],
+            "dry-run" => &["--dry-run"],
+            "breakpoint" => &["--breakpoint", "transfer"],
+            "args" => &["--args", "[1]"],
+            "instruction-debug" => &["--instruction-debug"],
+            "step-instructions" => &["--step-instructions"],
+            "trace-output" => &["--trace-output", "trace.json"],
+            "export-storage" => &["--export-storage", "storage.json"],
+            "generate-test" => &["--generate-test", "test.rs"],
+            "overwrite" => &["--overwrite"],
+            "save-output" => &["--save-output", "out.txt"],
+            "append" => &["--append"],
+            "storage" => &["--storage", "{}"],
+            "import-storage" => &["--import-storage", "storage.json"],
+            "server" => &["--server"],
+            "remote" => &["--remote", "localhost:9229"],
+            "tls-cert" => &["--tls-cert", "cert.pem"],
+            "tls-key" => &["--tls-key", "key.pem"],
+            "cache-results" => &["--cache-results"],
+            "no-cache" => &["--no-cache"],
+            "full-debug" => &["--full-debug"],
+            "network-snapshot" => &["--network-snapshot", "snapshot.json"],
+            "require-matching-code" => &["--require-matching-code"],
+            "use-snapshot-code" => &["--use-snapshot-code"],
+            "register-all-from-snapshot" => &["--register-all-from-snapshot"],
+            "dump-ring" => &["--dump-ring"],
+            "ring-size" => &["--ring-size", "0"],
+            "call" => &["--call", "f"],
+            "save-preset" => &["--save-preset", "p"],
+            other => panic!("add command-line tokens for --{} to this test", other),
+        }
+    }
+
+    fn check(flags: &[&Flag]) -> std::result::Result<(), FlagViolation> {
+        let mut argv = vec!["soroban-debug", "run", "--contract", "c.wasm"];
+        // `--call` names the function itself.
+        if !flags.iter().any(|flag| flag.name == "call") {
+            argv.extend_from_slice(&["--function", "f"]);
+        }
+        for flag in flags {
+            argv.extend_from_slice(tokens(flag));
+        }
+        let cli = Cli::try_parse_from(&argv).unwrap_or_else(|e| panic!("{:?}: {}", argv, e));
+        let Some(Commands::Run(args)) = cli.command else {
+            unreachable!()
+        };
+        check_run(&args)
+    }
+
+    /// `flags` plus everything they require, except `missing`.
+    fn with_requirements<'a>(flags: &[&'a Flag], missing: Option<&str>) -> Vec<&'a Flag> {
+        let mut all: Vec<&Flag> = flags.to_vec();
+        let mut i = 0;
+        while i < all.len() {
+            for rule in RUN_RULES {
+                if matches!(rule.relation, Relation::Requires)
+                    && rule.flag.name == all[i].name
+                    && Some(rule.other.name) != missing
+                    && !all.iter().any(|flag| flag.name == rule.other.name)
+                {
+                    all.push(&rule.other);
+                }
+            }
+            i += 1;
+        }
+        all
+    }
+
+    #[test]
+    fn every_rule_rejects_its_combination_and_accepts_the_fix() {
+        for rule in RUN_RULES {
+            let (invalid, valid): (Vec<&Flag>, Vec<Vec<&Flag>>) = match rule.relation {
+                Relation::ConflictsWith => (
+                    with_requirements(&[&rule.flag, &rule.other], None),
+                    vec![
+                        with_requirements(&[&rule.flag], None),
+                        with_requirements(&[&rule.other], None),
+                    ],
+                ),
+                Relation::Requires => (
+                    with_requirements(&[&rule.flag], Some(rule.other.name)),
+                    vec![with_requirements(&[&rule.flag, &rule.other], None)],
+                ),
+            };
+
+            let violation = check(&invalid).expect_err(rule.reason);
+            let message = violation.to_string();
+            assert!(
+                message.contains(&format!("--{}", rule.flag.name))
+                    && message.contains(&format!("--{}", rule.other.name))
+                    && message.ends_with(rule.reason),
+                "{}",
+                message
+            );
+            assert!(!message.contains('\n'), "{}", message);
+
+            for flags in valid {
+                let names: Vec<&str> = flags.iter().map(|flag| flag.name).collect();
+                assert!(check(&flags).is_ok(), "{:?} should be valid", names);
+            }
+        }
+    }
+
+    #[test]
+    fn rules_name_real_run_flags() {
+        let cli = Cli::command();
+        let run = cli.find_subcommand("run").unwrap();
+        for rule in RUN_RULES {
+            for flag in [&rule.flag, &rule.other] {
+                assert!(
+                    run.get_arguments()
+                        .any(|arg| arg.get_long() == Some(flag.name)),
+                    "--{} is not a run flag",
+                    flag.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn violations_map_to_clap_error_kinds() {
+        let conflict = check(&[&DRY_RUN, &BREAKPOINT]).unwrap_err();
+        assert_eq!(conflict.kind(), ErrorKind::ArgumentConflict);
+        assert_eq!(
+            conflict.to_string(),
+            "--dry-run cannot be used with --breakpoint: a dry run only loads the contract, \
+             so no breakpoint can be hit; drop --dry-run"
+        );
+        let missing = check(&[&APPEND]).unwrap_err();
+        assert_eq!(missing.kind(), ErrorKind::MissingRequiredArgument);
+        assert!(check(&[]).is_ok());
+    }
+}
@@ -1,5 +1,6 @@
 pub mod args;
 pub mod commands;
+pub mod flag_rules;
 pub mod output;
 
 pub use args::{
@@ -0,0 +1,351 @@
+//! Read-only observer for a running `server` session (`soroban-debug attach`).
+//!
+//! Understands the REPL's commands plus a few that only make sense against a
+//! live session. Read commands are answered by the server; commands that would
+//! invoke or change the session are still sent, so the refusal comes from the
+//! server's permission check rather than from this client.
+
+use crate::client::RemoteClient;
+use crate::repl::commands::{ReplCommand, ResetTarget};
+use crate::Result;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+/// One line typed at the `attach>` prompt.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttachCommand {
+    /// A command shared with the REPL
+    Repl(ReplCommand),
+    /// Events emitted so far: events
+    Events,
+    /// Function calls made so far: trace
+    Trace,
+    /// Current call stack: stack
+    Stack,
+    /// Paused function, step count and stack: inspect
+    Inspect,
+    /// Budget consumed so far: budget
+    Budget,
+    /// Evaluate once: eval <expr>
+    Eval(String),
+    /// Add a watch expression and evaluate it: watch <expr>
+    Watch(String),
+    /// Re-evaluate every watch expression: watches
+    Watches,
+}
+
+impl AttachCommand {
+    pub fn parse(input: &str) -> Result<Self> {
+        let trimmed = input.trim();
+        let (verb, rest) = trimmed
+            .split_once(char::is_whitespace)
+            .map(|(verb, rest)| (verb, rest.trim()))
+            .unwrap_or((trimmed, ""));
+        let expression = |verb: &str| {
+            if rest.is_empty() {
+                Err(miette::miette!("{} requires an expression", verb))
+            } else {
+                Ok(rest.to_string())
+            }
+        };
+        match verb {
+            "events" => Ok(Self::Events),
+            "trace" => Ok(Self::Trace),
+            "stack" => Ok(Self::Stack),
+            "inspect" => Ok(Self::Inspect),
+            "budget" => Ok(Self::Budget),
+            "eval" | "print" => Ok(Self::Eval(expression(verb)?)),
+            "watch" => Ok(Self::Watch(expression(verb)?)),
+            "watches" => Ok(Self::Watches),
+            _ => ReplCommand::parse(trimmed).map(Self::Repl),
+        }
+    }
+}
+
+/// Prompt loop over an attached client.
+pub struct AttachSession {
+    client: RemoteClient,
+    session: String,
+    watches: Vec<String>,
+    history: Vec<String>,
+}
+
+impl AttachSession {
+    /// `client` must already be attached to `session`.
+    pub fn new(client: RemoteClient, session: impl Into<String>) -> Self {
+        Self {
+            client,
+            session: session.into(),
+            watches: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Read commands from `input` until `exit` or end of input. Errors from
+    /// individual commands are printed and the loop carries on.
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, out: &mut W, prompt: bool) -> Result<()> {
+        let io = |e: std::io::Error| miette::miette!("Failed to write output: {}", e);
+        writeln!(
+            out,
+            "Attached read-only to session '{}'. Type 'help' for commands.",
+            self.session
+        )
+        .map_err(io)?;
+        let mut lines = input.lines();
+        loop {
+            if prompt {
+                write!(out, "attach> ").map_err(io)?;
+                out.flush().map_err(io)?;
+            }
+            let Some(line) = lines.next() else {
+                break;
+            };
+            let line = line.map_err(|e| miette::miette!("Failed to read input: {}", e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            self.history.push(line.trim().to_string());
+            let outcome =
+                AttachCommand::parse(&line).and_then(|command| self.execute(command, out));
+            match outcome {
+                Ok(true) => break,
+                Ok(false) => {}
+                Err(e) => writeln!(out, "Error: {}", e).map_err(io)?,
+            }
+        }
+        let _ = self.client.disconnect();
+        Ok(())
+    }
+
+    /// Run one command. Returns `true` when the session should end.
+    fn execute<W: Write>(&mut self, command: AttachCommand, out: &mut W) -> Result<bool> {
+        let io = |e: std::io::Error| miette::miette!("Failed to write output: {}", e);
+        match command {
+            AttachCommand::Repl(ReplCommand::Exit) => return Ok(true),
+            AttachCommand::Repl(ReplCommand::Help) => print_help(out).map_err(io)?,
+            AttachCommand::Repl(ReplCommand::History) => {
+                for (i, line) in self.history.iter().enumerate() {
+                    writeln!(out, "  {}  {}", i + 1, line).map_err(io)?;
+                }
+            }
+            AttachCommand::Repl(ReplCommand::Clear) => {
+                write!(out, "\x1B[2J\x1B[1;1H").map_err(io)?
+            }
+            AttachCommand::Repl(ReplCommand::Storage) => {
+                let storage: BTreeMap<String, String> =
+                    serde_json::from_str(&self.client.get_storage()?)
+                        .map_err(|e| miette::miette!("Server sent unreadable storage: {}", e))?;
+                if storage.is_empty() {
+                    writeln!(out, "Storage: (empty)").map_err(io)?;
+                }
+                for (key, value) in storage {
+                    writeln!(out, "  {} = {}", key, value).map_err(io)?;
+                }
+            }
+            AttachCommand::Repl(ReplCommand::ListBreaks) => {
+                let breakpoints = self.client.list_breakpoints()?;
+                if breakpoints.is_empty() {
+                    writeln!(out, "No breakpoints set").map_err(io)?;
+                }
+                for function in breakpoints {
+                    writeln!(out, "  {}", function).map_err(io)?;
+                }
+            }
+            AttachCommand::Repl(ReplCommand::Call { function, args }) => {
+                let args = call_args_json(&args);
+                let result = self.client.execute(&function, args.as_deref())?;
+                writeln!(out, "Result: {}", result).map_err(io)?;
+            }
+            AttachCommand::Repl(ReplCommand::Break {
+                function,
+                condition,
+            }) => {
+                self.client.set_breakpoint(&function, condition)?;
+                writeln!(out, "Breakpoint set at {}", function).map_err(io)?;
+            }
+            AttachCommand::Repl(ReplCommand::ClearBreak { function }) => {
+                self.client.clear_breakpoint(&function)?;
+                writeln!(out, "Breakpoint cleared at {}", function).map_err(io)?;
+            }
+            AttachCommand::Repl(
+                command @ (ReplCommand::Reset(_)
+                | ReplCommand::Checkpoint { .. }
+                | ReplCommand::Rollback { .. }
+                | ReplCommand::StorageWriters
+                | ReplCommand::Functions),
+            ) => {
+                return Err(miette::miette!(
+                    "'{}' is only available in a local REPL",
+                    repl_verb(&command)
+                ));
+            }
+            AttachCommand::Events => {
+                let events = self.client.get_events()?;
+                if events.is_empty() {
+                    writeln!(out, "No events").map_err(io)?;
+                }
+                for (i, event) in events.iter().enumerate() {
+                    writeln!(
+                        out,
+                        "  #{} [{}] {} => {}",
+                        i,
+                        event.contract_id.as_deref().unwrap_or("-"),
+                        event.topics.join(", "),
+                        event.data
+                    )
+                    .map_err(io)?;
+                }
+            }
+            AttachCommand::Trace => {
+                let calls = self.client.get_trace()?;
+                if calls.is_empty() {
+                    writeln!(out, "No calls yet").map_err(io)?;
+                }
+                for call in calls {
+                    writeln!(out, "  {}", call).map_err(io)?;
+                }
+            }
+            AttachCommand::Stack => {
+                let stack = self.client.get_stack()?;
+                if stack.is_empty() {
+                    writeln!(out, "Call stack: (empty)").map_err(io)?;
+                }
+                for (depth, frame) in stack.iter().enumerate() {
+                    writeln!(out, "  {}: {}", depth, frame).map_err(io)?;
+                }
+            }
+            AttachCommand::Inspect => {
+                let (function, step_count, paused, stack) = self.client.inspect()?;
+                writeln!(
+                    out,
+                    "Function: {}\nSteps: {}\nPaused: {}\nStack depth: {}",
+                    function.as_deref().unwrap_or("-"),
+                    step_count,
+                    paused,
+                    stack.len()
+                )
+                .map_err(io)?;
+            }
+            AttachCommand::Budget => {
+                let (cpu, memory) = self.client.get_budget()?;
+                writeln!(out, "CPU instructions: {}\nMemory bytes: {}", cpu, memory).map_err(io)?;
+            }
+            AttachCommand::Eval(expression) => {
+                let value = self.client.evaluate(&expression)?;
+                writeln!(out, "{} = {}", expression, value).map_err(io)?;
+            }
+            AttachCommand::Watch(expression) => {
+                if !self.watches.contains(&expression) {
+                    self.watches.push(expression.clone());
+                }
+                self.print_watch(&expression, out)?;
+            }
+            AttachCommand::Watches => {
+                if self.watches.is_empty() {
+                    writeln!(out, "No watch expressions").map_err(io)?;
+                }
+                for expression in self.watches.clone() {
+                    self.print_watch(&expression, out)?;
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    fn print_watch<W: Write>(&mut self, expression: &str, out: &mut W) -> Result<()> {
+        let value = match self.client.evaluate(expression) {
+            Ok(value) => value,
+            Err(e) => format!("<{}>", e),
+        };
+        writeln!(out, "  watch {} = {}", expression, value)
+            .map_err(|e| miette::miette!("Failed to write output: {}", e))
+    }
+}
+
+/// REPL call arguments as a JSON array: JSON values are kept, anything else
+/// becomes a string.
+fn call_args_json(args: &[String]) -> Option<String> {
+    if args.is_empty() {
+        return None;
+    }
+    let values: Vec<serde_json::Value> = args
+        .iter()
+        .map(|arg| {
+            serde_json::from_str(arg).unwrap_or_else(|_| serde_json::Value::String(arg.clone()))
+        })
+        .collect();
+    Some(serde_json::Value::Array(values).to_string())
+}
+
+fn repl_verb(command: &ReplCommand) -> &'static str {
+    match command {
+        ReplCommand::Reset(ResetTarget::Storage) => "reset storage",
+        ReplCommand::Reset(ResetTarget::Events) => "reset events",
+        ReplCommand::Reset(ResetTarget::All) => "reset all",
+        ReplCommand::Checkpoint { .. } => "checkpoint",
+        ReplCommand::Rollback { .. } => "rollback",
+        ReplCommand::StorageWriters => "storage writer",
+        _ => "functions",
+    }
+}
+
+fn print_help<W: Write>(out: &mut W) -> std::io::Result<()> {
+    writeln!(out, "Read-only commands:")?;
+    writeln!(out, "  storage            Show contract storage")?;
+    writeln!(out, "  events             Show events emitted so far")?;
+    writeln!(out, "  trace              Show function calls made so far")?;
+    writeln!(out, "  stack              Show the call stack")?;
+    writeln!(
+        out,
+        "  inspect            Show the paused function and step count"
+    )?;
+    writeln!(out, "  budget             Show CPU and memory consumed")?;
+    writeln!(out, "  list-breaks        List breakpoints")?;
+    writeln!(
+        out,
+        "  eval <expr>        Evaluate a storage key or function/args/step_count"
+    )?;
+    writeln!(out, "  watch <expr>       Add a watch expression")?;
+    writeln!(out, "  watches            Re-evaluate watch expressions")?;
+    writeln!(out, "  history            Show command history")?;
+    writeln!(out, "  exit               Detach")?;
+    writeln!(
+        out,
+        "call, break and clear-break are refused: this connection cannot invoke or modify the session."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attach_commands_extend_the_repl_commands() {
+        assert_eq!(
+            AttachCommand::parse("events").unwrap(),
+            AttachCommand::Events
+        );
+        assert_eq!(
+            AttachCommand::parse("watch  step_count ").unwrap(),
+            AttachCommand::Watch("step_count".to_string())
+        );
+        assert!(AttachCommand::parse("eval").is_err());
+        assert!(matches!(
+            AttachCommand::parse("call increment").unwrap(),
+            AttachCommand::Repl(ReplCommand::Call { .. })
+        ));
+        assert!(matches!(
+            AttachCommand::parse("storage").unwrap(),
+            AttachCommand::Repl(ReplCommand::Storage)
+        ));
+    }
+
+    #[test]
+    fn call_arguments_become_a_json_array() {
+        assert_eq!(call_args_json(&[]), None);
+        assert_eq!(
+            call_args_json(&["5".to_string(), "alice".to_string()]).as_deref(),
+            Some("[5,\"alice\"]")
+        );
+    }
+}
@@ -1,3 +1,5 @@
+pub mod attach;
 pub mod remote_client;
 
+pub use attach::{AttachCommand, AttachSession};
 pub use remote_client::{RemoteClient, RemoteClientConfig, RequestTimeouts, RetryPolicy};
@@ -1,5 +1,7 @@
+use crate::inspector::events::ContractEvent;
 use crate::server::protocol::{
-    DebugMessage, DebugRequest, DebugResponse, PROTOCOL_MAX_VERSION, PROTOCOL_MIN_VERSION,
+    ConnectionCapabilities, DebugMessage, DebugRequest, DebugResponse, PROTOCOL_MAX_VERSION,
+    PROTOCOL_MIN_VERSION,
 };
 use crate::{DebuggerError, Result};
 use std::io::{BufRead, BufReader, Write};
@@ -58,6 +60,8 @@ pub struct RemoteClient {
     message_id: u64,
     authenticated: bool,
     config: RemoteClientConfig,
+    /// Session attached to read-only; re-attached after a reconnect.
+    attached_session: Option<String>,
 }
 
 impl RemoteClient {
@@ -83,6 +87,7 @@ impl RemoteClient {
             message_id: 0,
             authenticated: token.is_none(),
             config,
+            attached_session: None,
         };
 
         client.handshake("rust-remote-client", env!("CARGO_PKG_VERSION"))?;
@@ -147,6 +152,28 @@ impl RemoteClient {
         }
     }
 
+    /// Attach read-only to the server's session named `session`.
+    ///
+    /// The server refuses every request that would invoke or modify the
+    /// session from then on, for the lifetime of the connection.
+    pub fn attach(&mut self, session: &str) -> Result<ConnectionCapabilities> {
+        let response = self.send_request(DebugRequest::Attach {
+            session: session.to_string(),
+        })?;
+
+        match response {
+            DebugResponse::Attached { capabilities, .. } => {
+                info!("Attached read-only to session {}", session);
+                self.attached_session = Some(session.to_string());
+                Ok(capabilities)
+            }
+            DebugResponse::Error { message } => Err(DebuggerError::ExecutionError(message).into()),
+            _ => Err(
+                DebuggerError::ExecutionError("Unexpected response to Attach".to_string()).into(),
+            ),
+        }
+    }
+
     /// Load a contract on the server
     pub fn load_contract(&mut self, contract_path: &str) -> Result<usize> {
         let response = self.send_request(DebugRequest::LoadContract {
@@ -330,6 +357,55 @@ impl RemoteClient {
         }
     }
 
+    /// Get the events emitted in the session so far
+    pub fn get_events(&mut self) -> Result<Vec<ContractEvent>> {
+        let response =
+            self.send_request_with_retry(DebugRequest::GetEvents, RequestClass::Default, true)?;
+
+        match response {
+            DebugResponse::Events { events } => Ok(events),
+            DebugResponse::Error { message } => Err(DebuggerError::ExecutionError(message).into()),
+            _ => Err(
+                DebuggerError::ExecutionError("Unexpected response to GetEvents".to_string())
+                    .into(),
+            ),
+        }
+    }
+
+    /// Get the function calls made in the session so far
+    pub fn get_trace(&mut self) -> Result<Vec<String>> {
+        let response =
+            self.send_request_with_retry(DebugRequest::GetTrace, RequestClass::Default, true)?;
+
+        match response {
+            DebugResponse::Trace { calls } => Ok(calls),
+            DebugResponse::Error { message } => Err(DebuggerError::ExecutionError(message).into()),
+            _ => Err(
+                DebuggerError::ExecutionError("Unexpected response to GetTrace".to_string()).into(),
+            ),
+        }
+    }
+
+    /// Evaluate a storage key or built-in field (`function`, `args`, `step_count`)
+    pub fn evaluate(&mut self, expression: &str) -> Result<String> {
+        let response = self.send_request_with_retry(
+            DebugRequest::Evaluate {
+                expression: expression.to_string(),
+                frame_id: None,
+            },
+            RequestClass::Inspect,
+            true,
+        )?;
+
+        match response {
+            DebugResponse::EvaluateResult { result, .. } => Ok(result),
+            DebugResponse::Error { message } => Err(DebuggerError::ExecutionError(message).into()),
+            _ => Err(
+                DebuggerError::ExecutionError("Unexpected response to Evaluate".to_string()).into(),
+            ),
+        }
+    }
+
     /// Set a breakpoint
     pub fn set_breakpoint(&mut self, function: &str, _condition: Option<String>) -> Result<()> {
         let response = self.send_request(DebugRequest::SetBreakpoint {
@@ -455,7 +531,7 @@ impl RemoteClient {
         let response = match self.send_request(DebugRequest::Cancel) {
             Ok(resp) => resp,
             Err(e) if e.to_string().contains("No response") => {
-                // If the server immediately exited as part of cancelling, it drops the connection.
+                // Servers that exit to cancel an invocation drop the connection.
                 return Ok(());
             }
             Err(e) => return Err(e),
@@ -466,6 +542,7 @@ impl RemoteClient {
                 info!("Server acknowledged cancellation");
                 Ok(())
             }
+            DebugResponse::Error { message } => Err(DebuggerError::ExecutionError(message).into()),
             _ => Err(
                 DebuggerError::ExecutionError("Unexpected response to Cancel".to_string()).into(),
             ),
@@ -501,6 +578,9 @@ impl RemoteClient {
         if let Some(token) = self.token.clone() {
             self.authenticate(&token)?;
         }
+        if let Some(session) = self.attached_session.clone() {
+            self.attach(&session)?;
+        }
         Ok(())
     }
 
@@ -600,17 +680,26 @@ impl RemoteClient {
             .flush()
             .map_err(|e| SendFailure::io("flush", e, timeout))?;
 
-        let mut response_line = String::new();
-        let n = self
-            .stream
-            .read_line(&mut response_line)
-            .map_err(|e| SendFailure::io("read", e, timeout))?;
-        if n == 0 {
-            return Err(SendFailure::Disconnected);
-        }
+        loop {
+            let mut response_line = String::new();
+            let n = self
+                .stream
+                .read_line(&mut response_line)
+                .map_err(|e| SendFailure::io("read", e, timeout))?;
+            if n == 0 {
+                return Err(SendFailure::Disconnected);
+            }
 
-        parse_response_line(expected_id, response_line.trim_end())
-            .map_err(|e| SendFailure::Protocol(e.to_string()))
+            match parse_response_line(expected_id, response_line.trim_end())
+                .map_err(|e| SendFailure::Protocol(e.to_string()))?
+            {
+                // Another client holds the session; the real response follows.
+                DebugResponse::Queued { position } => {
+                    info!("Request queued behind {} other invocation(s)", position);
+                }
+                response => return Ok(response),
+            }
+        }
     }
 }
 
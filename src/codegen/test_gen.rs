@@ -138,6 +138,7 @@ mod tests {
                 memory_bytes: 0,
                 memory_limit: 0,
             },
+            setup_budget: crate::inspector::budget::BudgetInfo::default(),
             storage_before: HashMap::new(),
             storage_after: HashMap::new(),
         };
@@ -1,14 +1,16 @@
 //! Comparison engine that loads two execution traces and produces a
-//! structured report covering storage, budget, return values, and
-//! execution flow differences.
+//! structured report covering storage, budget, return values, execution
+//! flow, event and authorization differences.
 
-use super::trace::{BudgetTrace, CallEntry, EventEntry, ExecutionTrace};
+use super::trace::{AuthEntry, BudgetTrace, CallEntry, EventEntry, ExecutionTrace};
+use crate::utils::value_depth::{max_value_depth, rebuild_json};
+use serde::Serialize;
 use std::collections::{BTreeMap, BTreeSet};
 
 // ─── Diff types ──────────────────────────────────────────────────────
 
 /// Overall comparison report returned by [`CompareEngine::compare`].
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ComparisonReport {
     pub label_a: String,
     pub label_b: String,
@@ -17,10 +19,11 @@ pub struct ComparisonReport {
     pub return_value_diff: ReturnValueDiff,
     pub flow_diff: FlowDiff,
     pub event_diff: EventDiff,
+    pub auth_diff: AuthDiff,
 }
 
 /// Storage key-level differences.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct StorageDiff {
     /// Keys present only in trace A
     pub only_in_a: BTreeMap<String, serde_json::Value>,
@@ -33,7 +36,7 @@ pub struct StorageDiff {
 }
 
 /// Numeric deltas for resource budgets.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct BudgetDiff {
     pub a: Option<BudgetTrace>,
     pub b: Option<BudgetTrace>,
@@ -43,7 +46,7 @@ pub struct BudgetDiff {
 }
 
 /// Return value comparison.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ReturnValueDiff {
     pub a: Option<serde_json::Value>,
     pub b: Option<serde_json::Value>,
@@ -51,7 +54,7 @@ pub struct ReturnValueDiff {
 }
 
 /// Call-sequence comparison.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct FlowDiff {
     pub a_calls: Vec<CallEntry>,
     pub b_calls: Vec<CallEntry>,
@@ -63,7 +66,7 @@ pub struct FlowDiff {
 }
 
 /// Event comparison.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct EventDiff {
     pub a_events: Vec<EventEntry>,
     pub b_events: Vec<EventEntry>,
@@ -72,8 +75,43 @@ pub struct EventDiff {
     pub identical: bool,
 }
 
+/// Required-authorization comparison.
+///
+/// Nodes are matched by their path from the signer down, e.g.
+/// `GABC… > CDEF…::transfer > CXYZ…::burn`. A path that occurs several times
+/// pairs up in order of appearance.
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthDiff {
+    /// Both traces recorded authorizations. When `false` nothing was compared.
+    pub recorded: bool,
+    /// Authorizations required in A but not in B
+    pub only_in_a: Vec<AuthRequirement>,
+    /// Authorizations required in B but not in A
+    pub only_in_b: Vec<AuthRequirement>,
+    /// Authorizations required in both, with different arguments
+    pub modified: Vec<AuthArgsChange>,
+    pub unchanged_count: usize,
+    pub identical: bool,
+}
+
+/// One node of an authorization tree, flattened to its path.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthRequirement {
+    pub path: String,
+    pub args: serde_json::Value,
+}
+
+/// Arguments of an authorization required by both traces.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AuthArgsChange {
+    pub path: String,
+    pub a: serde_json::Value,
+    pub b: serde_json::Value,
+}
+
 /// A single line in a unified-style diff.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "call", rename_all = "snake_case")]
 pub enum DiffLine {
     /// Present in both traces at the same position.
     Same(String),
@@ -179,9 +217,17 @@ impl CompareEngine {
             ),
             flow_diff: Self::diff_flow(&trace_a.call_sequence, &trace_b.call_sequence, filters),
             event_diff: Self::diff_events(&trace_a.events, &trace_b.events, filters),
+            auth_diff: Self::diff_auth(trace_a.auth.as_deref(), trace_b.auth.as_deref(), filters),
         }
     }
 
+    /// The report as JSON, for `compare --format json`.
+    pub fn report_json(report: &ComparisonReport) -> crate::Result<String> {
+        serde_json::to_string_pretty(report).map_err(|e| {
+            crate::DebuggerError::FileError(format!("Failed to serialize comparison: {}", e)).into()
+        })
+    }
+
     // ── Storage ──────────────────────────────────────────────────────
 
     fn diff_storage(
@@ -361,6 +407,120 @@ impl CompareEngine {
         }
     }
 
+    // ── Authorizations ───────────────────────────────────────────────
+
+    fn diff_auth(
+        a: Option<&[AuthEntry]>,
+        b: Option<&[AuthEntry]>,
+        filters: &CompareFilters,
+    ) -> AuthDiff {
+        let (Some(a), Some(b)) = (a, b) else {
+            return AuthDiff {
+                recorded: false,
+                only_in_a: Vec::new(),
+                only_in_b: Vec::new(),
+                modified: Vec::new(),
+                unchanged_count: 0,
+                identical: true,
+            };
+        };
+        let flat_a = Self::flatten_auth(a, filters);
+        let flat_b = Self::flatten_auth(b, filters);
+
+        let mut paths: Vec<&String> = Vec::new();
+        let mut by_path_a: BTreeMap<&String, Vec<&serde_json::Value>> = BTreeMap::new();
+        let mut by_path_b: BTreeMap<&String, Vec<&serde_json::Value>> = BTreeMap::new();
+        for (path, args) in &flat_a {
+            if !by_path_a.contains_key(path) {
+                paths.push(path);
+            }
+            by_path_a.entry(path).or_default().push(args);
+        }
+        for (path, args) in &flat_b {
+            if !by_path_a.contains_key(path) && !by_path_b.contains_key(path) {
+                paths.push(path);
+            }
+            by_path_b.entry(path).or_default().push(args);
+        }
+
+        let mut only_in_a = Vec::new();
+        let mut only_in_b = Vec::new();
+        let mut modified = Vec::new();
+        let mut unchanged_count = 0;
+        for path in paths {
+            let in_a = by_path_a.get(path).map(Vec::as_slice).unwrap_or_default();
+            let in_b = by_path_b.get(path).map(Vec::as_slice).unwrap_or_default();
+            for (args_a, args_b) in in_a.iter().zip(in_b) {
+                if args_a == args_b {
+                    unchanged_count += 1;
+                } else {
+                    modified.push(AuthArgsChange {
+                        path: path.clone(),
+                        a: (*args_a).clone(),
+                        b: (*args_b).clone(),
+                    });
+                }
+            }
+            let requirement = |args: &&serde_json::Value| AuthRequirement {
+                path: path.clone(),
+                args: (*args).clone(),
+            };
+            only_in_a.extend(in_a.iter().skip(in_b.len()).map(requirement));
+            only_in_b.extend(in_b.iter().skip(in_a.len()).map(requirement));
+        }
+
+        let identical = only_in_a.is_empty() && only_in_b.is_empty() && modified.is_empty();
+        AuthDiff {
+            recorded: true,
+            only_in_a,
+            only_in_b,
+            modified,
+            unchanged_count,
+            identical,
+        }
+    }
+
+    /// Every node of the trees as `(path, args)`, parents before children.
+    fn flatten_auth(
+        entries: &[AuthEntry],
+        filters: &CompareFilters,
+    ) -> Vec<(String, serde_json::Value)> {
+        let mut flat = Vec::new();
+        for entry in entries {
+            let Some(value) = serde_json::to_value(entry)
+                .ok()
+                .and_then(|value| Self::normalize_value(&value, &["auth"], filters))
+            else {
+                continue;
+            };
+            let signer = value
+                .get("address")
+                .and_then(|address| address.as_str())
+                .unwrap_or("source-account")
+                .to_string();
+            let mut pending = vec![(signer, &value)];
+            while let Some((parent, node)) = pending.pop() {
+                let field = |name: &str| {
+                    node.get(name)
+                        .and_then(|value| value.as_str())
+                        .unwrap_or("<unknown>")
+                };
+                let path = format!(
+                    "{} > {}::{}",
+                    parent,
+                    field("contract_id"),
+                    field("function")
+                );
+                let args = node.get("args").cloned().unwrap_or(serde_json::Value::Null);
+                if let Some(children) = node.get("sub_invocations").and_then(|v| v.as_array()) {
+                    pending.extend(children.iter().rev().map(|child| (path.clone(), child)));
+                }
+                flat.push((path, args));
+            }
+        }
+        flat
+    }
+
     fn normalize_storage_map(
         storage: &BTreeMap<String, serde_json::Value>,
         filters: &CompareFilters,
@@ -409,46 +569,16 @@ impl CompareEngine {
         Self::normalize_value_with_path(value, path_segments, filters)
     }
 
+    /// Drops ignored paths and fields. Iterative, so deeply nested trace
+    /// values are truncated at the configured depth instead of overflowing.
     fn normalize_value_with_path(
         value: &serde_json::Value,
         path: Vec<String>,
         filters: &CompareFilters,
     ) -> Option<serde_json::Value> {
-        if filters.ignores_path(&path) {
-            return None;
-        }
-
-        match value {
-            serde_json::Value::Object(map) => {
-                let mut normalized = serde_json::Map::new();
-                for (key, child) in map {
-                    if filters.ignores_field(key) {
-                        continue;
-                    }
-
-                    let mut child_path = path.clone();
-                    child_path.push(key.clone());
-                    if let Some(child) = Self::normalize_value_with_path(child, child_path, filters)
-                    {
-                        normalized.insert(key.clone(), child);
-                    }
-                }
-                Some(serde_json::Value::Object(normalized))
-            }
-            serde_json::Value::Array(items) => {
-                let mut normalized = Vec::with_capacity(items.len());
-                for (index, child) in items.iter().enumerate() {
-                    let mut child_path = path.clone();
-                    child_path.push(index.to_string());
-                    if let Some(child) = Self::normalize_value_with_path(child, child_path, filters)
-                    {
-                        normalized.push(child);
-                    }
-                }
-                Some(serde_json::Value::Array(normalized))
-            }
-            _ => Some(value.clone()),
-        }
+        rebuild_json(value, path, max_value_depth(), |path, field| {
+            !field.is_some_and(|field| filters.ignores_field(field)) && !filters.ignores_path(path)
+        })
     }
 
     fn format_call_value(value: &serde_json::Value) -> String {
@@ -652,6 +782,51 @@ impl CompareEngine {
             }
         }
 
+        // ── Authorizations ─────────────────────────────────────────
+        out.push_str("\n───────────────── Authorizations ──────────────────\n\n");
+        let ad = &report.auth_diff;
+
+        if !ad.recorded {
+            out.push_str("  (authorizations not recorded in both traces)\n");
+        } else if ad.identical {
+            if ad.unchanged_count == 0 {
+                out.push_str("  (no authorizations required in either trace)\n");
+            } else {
+                out.push_str(&format!(
+                    "  (identical — {} authorization(s))\n",
+                    ad.unchanged_count
+                ));
+            }
+        } else {
+            if !ad.only_in_a.is_empty() {
+                out.push_str(&format!("  Required only in A ({}):\n", ad.only_in_a.len()));
+                for auth in &ad.only_in_a {
+                    out.push_str(&format!("    - {} {}\n", auth.path, auth.args));
+                }
+                out.push('\n');
+            }
+
+            if !ad.only_in_b.is_empty() {
+                out.push_str(&format!("  Required only in B ({}):\n", ad.only_in_b.len()));
+                for auth in &ad.only_in_b {
+                    out.push_str(&format!("    + {} {}\n", auth.path, auth.args));
+                }
+                out.push('\n');
+            }
+
+            if !ad.modified.is_empty() {
+                out.push_str(&format!("  Arguments changed ({}):\n", ad.modified.len()));
+                for change in &ad.modified {
+                    out.push_str(&format!("    ~ {}\n", change.path));
+                    out.push_str(&format!("        A: {}\n", change.a));
+                    out.push_str(&format!("        B: {}\n", change.b));
+                }
+                out.push('\n');
+            }
+
+            out.push_str(&format!("  Unchanged: {}\n", ad.unchanged_count));
+        }
+
         out.push_str("\n═══════════════════════════════════════════════════════════════\n");
 
         out
@@ -716,6 +891,9 @@ mod tests {
                 topics: vec!["transfer".to_string()],
                 data: Some("Alice→Bob 100".to_string()),
             }],
+            auth: None,
+            storage_accesses: Vec::new(),
+            sampling: None,
         }
     }
 
@@ -777,6 +955,9 @@ mod tests {
                     data: Some("50".to_string()),
                 },
             ],
+            auth: None,
+            storage_accesses: Vec::new(),
+            sampling: None,
         }
     }
 
@@ -954,4 +1135,86 @@ mod tests {
         assert!(report.flow_diff.identical);
         assert_eq!(report.flow_diff.filtered_a_calls, vec!["transfer()"]);
     }
+
+    fn auth_fixture(name: &str) -> ExecutionTrace {
+        let path = format!(
+            "{}/tests/fixtures/compare/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        );
+        ExecutionTrace::from_file(path).expect("auth fixture should load")
+    }
+
+    #[test]
+    fn test_auth_diff_catches_dropped_second_signer() {
+        let before = auth_fixture("auth_two_signers");
+        let after = auth_fixture("auth_second_signer_dropped");
+        let report = CompareEngine::compare(&before, &after);
+        let ad = &report.auth_diff;
+
+        assert!(ad.recorded);
+        assert!(!ad.identical);
+        assert!(ad.only_in_b.is_empty());
+        assert!(ad.modified.is_empty());
+        // Alice's swap and her token transfer still match.
+        assert_eq!(ad.unchanged_count, 2);
+        let bob = "GC3TZDFAHO3JMOGSCT6CNXLEEWVKT4PKZXP4YSS5BY4CHKEU3VHBZ5DA";
+        let dropped: Vec<&str> = ad.only_in_a.iter().map(|a| a.path.as_str()).collect();
+        assert_eq!(dropped.len(), 2);
+        assert!(dropped[0].starts_with(bob) && dropped[0].ends_with("::swap"));
+        assert!(dropped[1].starts_with(bob) && dropped[1].ends_with("::transfer"));
+
+        let rendered = CompareEngine::render_report(&report);
+        assert!(rendered.contains("Required only in A (2):"));
+        let json: serde_json::Value =
+            serde_json::from_str(&CompareEngine::report_json(&report).unwrap()).unwrap();
+        assert_eq!(json["auth_diff"]["only_in_a"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_auth_diff_reports_argument_changes_on_matched_nodes() {
+        let before = auth_fixture("auth_two_signers");
+        let mut after = before.clone();
+        after.auth.as_mut().unwrap()[1].sub_invocations[0].args[2] = serde_json::json!(250);
+        let report = CompareEngine::compare(&before, &after);
+        let ad = &report.auth_diff;
+
+        assert!(ad.only_in_a.is_empty() && ad.only_in_b.is_empty());
+        assert_eq!(ad.modified.len(), 1);
+        assert!(ad.modified[0].path.ends_with("::transfer"));
+        assert_eq!(ad.modified[0].b[2], serde_json::json!(250));
+
+        let ignored =
+            CompareEngine::compare_with_filters(&before, &after, &filters(&[], &["args"]));
+        assert!(ignored.auth_diff.identical);
+    }
+
+    #[test]
+    fn test_auth_diff_skipped_when_a_trace_did_not_record() {
+        let before = auth_fixture("auth_two_signers");
+        let mut after = before.clone();
+        after.auth = None;
+        let report = CompareEngine::compare(&before, &after);
+
+        assert!(!report.auth_diff.recorded);
+        assert!(report.auth_diff.identical);
+    }
+
+    #[test]
+    fn test_deeply_nested_return_values_are_truncated_not_overflowed() {
+        use crate::utils::value_depth::pathological::{dismantle_json, nested_json};
+
+        // Built separately: the derived `Clone` on a value this deep would recurse.
+        let a = Some(nested_json(100_000));
+        let b = Some(nested_json(100_000));
+        let diff = CompareEngine::diff_return_value(&a, &b, &filters(&[], &[]));
+        assert!(diff.equal);
+        let rendered = diff.a.expect("return value should survive normalization");
+        assert!(rendered.to_string().contains("<truncated"));
+
+        dismantle_json(rendered);
+        for value in [a, b].into_iter().flatten() {
+            dismantle_json(value);
+        }
+    }
 }
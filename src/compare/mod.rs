@@ -1,4 +1,5 @@
 pub mod engine;
+pub mod renderer;
 pub mod trace;
 
 pub use engine::{CompareEngine, ComparisonReport};
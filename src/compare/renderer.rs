@@ -0,0 +1,490 @@
+//! Terminal layout for comparison reports.
+//!
+//! [`render_side_by_side`] puts the two runs next to each other in aligned
+//! columns, marking rows that differ with `*`. Terminals narrower than
+//! [`MIN_SIDE_BY_SIDE_WIDTH`] get the same rows stacked as `A:`/`B:` lines
+//! instead. The full unified report is still available from
+//! [`CompareEngine::render_report`](super::CompareEngine::render_report).
+
+use super::engine::{ComparisonReport, DiffLine};
+use crate::ui::formatter::Formatter;
+
+/// Narrowest terminal that still gets the column layout.
+pub const MIN_SIDE_BY_SIDE_WIDTH: usize = 100;
+
+/// Storage changes listed before the rest are summarised as a count.
+pub const TOP_STORAGE_CHANGES: usize = 10;
+
+const MAX_LABEL_WIDTH: usize = 28;
+const SEPARATOR: &str = " │ ";
+
+/// Layout settings for [`render_side_by_side`].
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    /// Terminal width in columns.
+    pub width: usize,
+    /// Colour differing rows.
+    pub color: bool,
+}
+
+/// One row of the comparison layout.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Row {
+    /// Section heading spanning the full width.
+    Section(String),
+    /// A value from each run.
+    Pair { label: String, a: String, b: String },
+    /// Free text spanning both columns, e.g. a unified diff line.
+    Line { text: String, highlight: bool },
+}
+
+impl Row {
+    fn pair(label: impl Into<String>, a: impl ToString, b: impl ToString) -> Self {
+        Row::Pair {
+            label: label.into(),
+            a: a.to_string(),
+            b: b.to_string(),
+        }
+    }
+
+    fn line(text: impl Into<String>, highlight: bool) -> Self {
+        Row::Line {
+            text: text.into(),
+            highlight,
+        }
+    }
+}
+
+/// Render `report` side by side, or stacked when `options.width` is too narrow.
+pub fn render_side_by_side(report: &ComparisonReport, options: RenderOptions) -> String {
+    let rows = overview_rows(report);
+    let mut lines = vec!["Execution Trace Comparison".to_string()];
+    if options.width >= MIN_SIDE_BY_SIDE_WIDTH {
+        lines.extend(columns(
+            &rows,
+            &format!("A: {}", report.label_a),
+            &format!("B: {}", report.label_b),
+            options,
+        ));
+    } else {
+        lines.extend(stacked(
+            &rows,
+            &format!("A: {}", report.label_a),
+            &format!("B: {}", report.label_b),
+            options,
+        ));
+    }
+    let mut out = lines.join("\n");
+    out.push('\n');
+    out
+}
+
+/// Summary, budget, top storage changes, the call-sequence diff and any
+/// authorization differences as rows.
+pub fn overview_rows(report: &ComparisonReport) -> Vec<Row> {
+    let mut rows = Vec::new();
+    let or_none = |value: Option<&serde_json::Value>| {
+        value.map_or_else(|| "(none)".to_string(), |v| v.to_string())
+    };
+
+    let sd = &report.storage_diff;
+    rows.push(Row::Section("Summary".to_string()));
+    rows.push(Row::pair(
+        "Return value",
+        or_none(report.return_value_diff.a.as_ref()),
+        or_none(report.return_value_diff.b.as_ref()),
+    ));
+    rows.push(Row::pair(
+        "Storage keys",
+        sd.unchanged_count + sd.modified.len() + sd.only_in_a.len(),
+        sd.unchanged_count + sd.modified.len() + sd.only_in_b.len(),
+    ));
+    rows.push(Row::pair(
+        "Events",
+        report.event_diff.filtered_a_events.len(),
+        report.event_diff.filtered_b_events.len(),
+    ));
+    rows.push(Row::pair(
+        "Calls",
+        report.flow_diff.filtered_a_calls.len(),
+        report.flow_diff.filtered_b_calls.len(),
+    ));
+
+    let bd = &report.budget_diff;
+    rows.push(Row::Section("Budget".to_string()));
+    let with_delta = |value: Option<u64>, delta: Option<i128>| match (value, delta) {
+        (Some(value), Some(delta)) if delta != 0 => format!("{} ({:+})", value, delta),
+        (Some(value), _) => value.to_string(),
+        (None, _) => "(none)".to_string(),
+    };
+    rows.push(Row::pair(
+        "CPU instructions",
+        with_delta(bd.a.as_ref().map(|a| a.cpu_instructions), None),
+        with_delta(bd.b.as_ref().map(|b| b.cpu_instructions), bd.cpu_delta),
+    ));
+    rows.push(Row::pair(
+        "Memory (bytes)",
+        with_delta(bd.a.as_ref().map(|a| a.memory_bytes), None),
+        with_delta(bd.b.as_ref().map(|b| b.memory_bytes), bd.memory_delta),
+    ));
+
+    let absent = || "(absent)".to_string();
+    let changes: Vec<(&String, String, String)> = sd
+        .modified
+        .iter()
+        .map(|(key, (a, b))| (key, a.to_string(), b.to_string()))
+        .chain(
+            sd.only_in_b
+                .iter()
+                .map(|(key, b)| (key, absent(), b.to_string())),
+        )
+        .chain(
+            sd.only_in_a
+                .iter()
+                .map(|(key, a)| (key, a.to_string(), absent())),
+        )
+        .collect();
+    if changes.is_empty() {
+        rows.push(Row::Section("Storage changes".to_string()));
+        rows.push(Row::line("(identical)", false));
+    } else {
+        let shown = changes.len().min(TOP_STORAGE_CHANGES);
+        rows.push(Row::Section(format!(
+            "Storage changes ({} of {})",
+            shown,
+            changes.len()
+        )));
+        rows.extend(
+            changes
+                .into_iter()
+                .take(shown)
+                .map(|(key, a, b)| Row::pair(key.clone(), a, b)),
+        );
+    }
+
+    let fd = &report.flow_diff;
+    rows.push(Row::Section("Execution flow".to_string()));
+    if fd.identical {
+        rows.push(Row::line("(identical call sequences)", false));
+    } else {
+        rows.extend(fd.diff_lines.iter().map(|line| match line {
+            DiffLine::Same(call) => Row::line(format!("  {}", call), false),
+            DiffLine::OnlyA(call) => Row::line(format!("- {}", call), true),
+            DiffLine::OnlyB(call) => Row::line(format!("+ {}", call), true),
+        }));
+    }
+
+    let ad = &report.auth_diff;
+    let matched = ad.unchanged_count + ad.modified.len();
+    if ad.recorded && matched + ad.only_in_a.len() + ad.only_in_b.len() > 0 {
+        rows.push(Row::Section("Authorizations".to_string()));
+        rows.push(Row::pair(
+            "Required auths",
+            matched + ad.only_in_a.len(),
+            matched + ad.only_in_b.len(),
+        ));
+        rows.extend(
+            ad.only_in_a
+                .iter()
+                .map(|auth| Row::line(format!("- {}", auth.path), true)),
+        );
+        rows.extend(
+            ad.only_in_b
+                .iter()
+                .map(|auth| Row::line(format!("+ {}", auth.path), true)),
+        );
+        rows.extend(
+            ad.modified
+                .iter()
+                .map(|change| Row::pair(change.path.clone(), &change.a, &change.b)),
+        );
+    }
+    rows
+}
+
+/// Lay rows out as `label │ A │ B` with a `*` gutter on differing rows.
+pub fn columns(
+    rows: &[Row],
+    header_a: &str,
+    header_b: &str,
+    options: RenderOptions,
+) -> Vec<String> {
+    let width = options.width;
+    let label_width = rows
+        .iter()
+        .filter_map(|row| match row {
+            Row::Pair { label, .. } => Some(char_len(label)),
+            _ => None,
+        })
+        .max()
+        .unwrap_or(0)
+        .min(MAX_LABEL_WIDTH);
+    let fixed = 2 + label_width + 2 * char_len(SEPARATOR);
+    let value_width = (width.saturating_sub(fixed) / 2).max(1);
+
+    let mut lines = vec![
+        format!(
+            "  {}{}{}{}{}",
+            pad("", label_width),
+            SEPARATOR,
+            pad(&fit(header_a, value_width), value_width),
+            SEPARATOR,
+            fit(header_b, value_width)
+        )
+        .trim_end()
+        .to_string(),
+        "─".repeat(width),
+    ];
+    for row in rows {
+        match row {
+            Row::Section(title) => {
+                lines.push(String::new());
+                lines.push(section(title, width));
+            }
+            Row::Pair { label, a, b } => {
+                let differs = a != b;
+                let line = format!(
+                    "{}{}{}{}{}{}",
+                    if differs { "* " } else { "  " },
+                    pad(&fit(label, label_width), label_width),
+                    SEPARATOR,
+                    pad(&fit(a, value_width), value_width),
+                    SEPARATOR,
+                    fit(b, value_width)
+                );
+                lines.push(highlight(line.trim_end(), differs && options.color));
+            }
+            Row::Line {
+                text,
+                highlight: hl,
+            } => {
+                let line = fit(&format!("  {}", text), width);
+                lines.push(highlight(&line, *hl && options.color));
+            }
+        }
+    }
+    lines
+}
+
+/// Lay rows out one run below the other, for narrow terminals.
+pub fn stacked(
+    rows: &[Row],
+    header_a: &str,
+    header_b: &str,
+    options: RenderOptions,
+) -> Vec<String> {
+    let width = options.width;
+    let mut lines = vec![
+        fit(header_a, width),
+        fit(header_b, width),
+        "─".repeat(width),
+    ];
+    for row in rows {
+        match row {
+            Row::Section(title) => {
+                lines.push(String::new());
+                lines.push(section(title, width));
+            }
+            Row::Pair { label, a, b } if a == b => {
+                lines.push(fit(&format!("  {}: {}", label, a), width));
+            }
+            Row::Pair { label, a, b } => {
+                let color = options.color;
+                lines.push(highlight(&fit(&format!("* {}:", label), width), color));
+                lines.push(highlight(&fit(&format!("    A: {}", a), width), color));
+                lines.push(highlight(&fit(&format!("    B: {}", b), width), color));
+            }
+            Row::Line {
+                text,
+                highlight: hl,
+            } => {
+                let line = fit(&format!("  {}", text), width);
+                lines.push(highlight(&line, *hl && options.color));
+            }
+        }
+    }
+    lines
+}
+
+fn section(title: &str, width: usize) -> String {
+    let heading = fit(&format!("── {} ", title), width);
+    let rule = width.saturating_sub(char_len(&heading));
+    format!("{}{}", heading, "─".repeat(rule))
+}
+
+fn highlight(line: &str, enabled: bool) -> String {
+    if enabled {
+        Formatter::warning(line)
+    } else {
+        line.to_string()
+    }
+}
+
+fn char_len(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Shorten `text` to `width` characters, ending in `…` when cut.
+fn fit(text: &str, width: usize) -> String {
+    if char_len(text) <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(char_len(text)))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::trace::{BudgetTrace, CallEntry, ExecutionTrace};
+    use crate::compare::CompareEngine;
+    use std::collections::BTreeMap;
+
+    fn trace(
+        label: &str,
+        storage: &[(&str, serde_json::Value)],
+        cpu: u64,
+        calls: &[(&str, Option<&str>, u32)],
+    ) -> ExecutionTrace {
+        ExecutionTrace {
+            label: Some(label.to_string()),
+            contract: Some("token.wasm".to_string()),
+            function: Some("transfer".to_string()),
+            args: None,
+            storage: storage
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect::<BTreeMap<_, _>>(),
+            budget: Some(BudgetTrace {
+                cpu_instructions: cpu,
+                memory_bytes: 15360,
+                cpu_limit: None,
+                memory_limit: None,
+            }),
+            return_value: Some(serde_json::json!({"status": "ok"})),
+            call_sequence: calls
+                .iter()
+                .map(|(function, args, depth)| CallEntry {
+                    function: function.to_string(),
+                    args: args.map(str::to_string),
+                    depth: *depth,
+                })
+                .collect(),
+            events: Vec::new(),
+            auth: None,
+            storage_accesses: Vec::new(),
+            sampling: None,
+        }
+    }
+
+    fn sample_report() -> ComparisonReport {
+        let a = trace(
+            "v1.0 baseline",
+            &[
+                ("balance:Alice", serde_json::json!(900)),
+                ("balance:Bob", serde_json::json!(100)),
+                ("total_supply", serde_json::json!(1000)),
+            ],
+            45000,
+            &[("transfer", None, 0), ("get_balance", Some("Alice"), 1)],
+        );
+        let b = trace(
+            "v1.1 candidate",
+            &[
+                ("balance:Alice", serde_json::json!(850)),
+                ("balance:Bob", serde_json::json!(150)),
+                ("fee_pool", serde_json::json!(5)),
+                ("total_supply", serde_json::json!(1000)),
+            ],
+            47500,
+            &[
+                ("transfer", None, 0),
+                ("get_balance", Some("Alice"), 1),
+                ("charge_fee", None, 1),
+            ],
+        );
+        CompareEngine::compare(&a, &b)
+    }
+
+    fn render(width: usize) -> String {
+        render_side_by_side(
+            &sample_report(),
+            RenderOptions {
+                width,
+                color: false,
+            },
+        )
+    }
+
+    #[test]
+    fn golden_160_columns_is_side_by_side() {
+        assert_eq!(
+            render(160),
+            include_str!("../../tests/fixtures/compare/side_by_side_160.txt")
+        );
+    }
+
+    #[test]
+    fn golden_80_columns_falls_back_to_stacked() {
+        assert_eq!(
+            render(80),
+            include_str!("../../tests/fixtures/compare/side_by_side_80.txt")
+        );
+    }
+
+    #[test]
+    fn no_line_exceeds_the_terminal_width() {
+        let mut report = sample_report();
+        report.return_value_diff.b = Some(serde_json::json!({"status": "x".repeat(300)}));
+        for width in [40, 80, 99, 100, 160, 240] {
+            let rendered = render_side_by_side(
+                &report,
+                RenderOptions {
+                    width,
+                    color: false,
+                },
+            );
+            for line in rendered.lines() {
+                assert!(
+                    char_len(line) <= width,
+                    "{} > {}: {}",
+                    char_len(line),
+                    width,
+                    line
+                );
+            }
+            assert!(rendered.contains('…'));
+        }
+    }
+
+    #[test]
+    fn storage_changes_are_capped() {
+        let many: Vec<(String, serde_json::Value)> = (0..25)
+            .map(|i| (format!("key{:02}", i), serde_json::json!(i)))
+            .collect();
+        let storage: Vec<(&str, serde_json::Value)> = many
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.clone()))
+            .collect();
+        let a = trace("a", &[], 1, &[]);
+        let b = trace("b", &storage, 1, &[]);
+        let rows = overview_rows(&CompareEngine::compare(&a, &b));
+        assert!(rows.contains(&Row::Section(format!(
+            "Storage changes ({} of 25)",
+            TOP_STORAGE_CHANGES
+        ))));
+        assert!(!rows.iter().any(|row| matches!(
+            row,
+            Row::Pair { label, .. } if label == "key10"
+        )));
+    }
+}
@@ -4,6 +4,7 @@
 //! contract invocation so that two traces can be compared side-by-side
 //! for regression testing.
 
+pub use crate::runtime::env::TraceSampling;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fs;
@@ -48,6 +49,20 @@ pub struct ExecutionTrace {
     /// Events emitted during execution
     #[serde(default)]
     pub events: Vec<EventEntry>,
+
+    /// Authorizations the invocation required, one tree per signer. `None`
+    /// when the run did not record authorizations.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Vec<AuthEntry>>,
+
+    /// Storage accesses in execution order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub storage_accesses: Vec<StorageAccessEntry>,
+
+    /// Set when `--trace-sample` or `--trace-limit` left accesses out of
+    /// `storage_accesses`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<TraceSampling>,
 }
 
 /// Budget / resource usage captured in a trace.
@@ -85,6 +100,55 @@ pub struct EventEntry {
     pub data: Option<String>,
 }
 
+/// A single storage read or write.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StorageAccessEntry {
+    /// Position among all operations of the invocation, including skipped ones
+    pub sequence: usize,
+    /// `read` or `write`
+    pub kind: String,
+    pub key: String,
+    /// Value written, for writes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// A required authorization and the calls it covers.
+///
+/// Shaped like the `invocation` object written by `--export-auth`, with the
+/// signing address on the root entry only; sub-invocations share it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AuthEntry {
+    /// Signing address, or `None` for the transaction source account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    pub contract_id: String,
+    pub function: String,
+    #[serde(default)]
+    pub args: Vec<serde_json::Value>,
+    #[serde(default)]
+    pub sub_invocations: Vec<AuthEntry>,
+}
+
+impl AuthEntry {
+    /// Authorizations recorded by the host during the last invocation, or
+    /// `None` when the host was not recording (`mock_all_auths`).
+    pub fn from_host(host: &soroban_env_host::Host) -> Option<Vec<Self>> {
+        let payloads = host.get_recorded_auth_payloads().ok()?;
+        let entries = payloads
+            .iter()
+            .filter_map(|payload| {
+                let invocation =
+                    crate::inspector::auth_export::invocation_json(&payload.invocation);
+                let mut entry: AuthEntry = serde_json::from_value(invocation).ok()?;
+                entry.address = payload.address.as_ref().map(|address| address.to_string());
+                Some(entry)
+            })
+            .collect();
+        Some(entries)
+    }
+}
+
 impl std::fmt::Display for CallEntry {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let indent = "  ".repeat(self.depth as usize);
@@ -1,5 +1,8 @@
+use crate::analyzer::event_schema::edit_distance;
 use crate::{DebuggerError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::fs;
 use std::path::Path;
 use tracing::warn;
@@ -8,14 +11,27 @@ use tracing::warn;
 pub const DEFAULT_CONFIG_FILE: &str = ".soroban-debug.toml";
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     #[serde(default)]
     pub debug: DebugConfig,
     #[serde(default)]
     pub output: OutputConfig,
+    /// Argument literal conversions keyed by suffix, e.g. `[conversions.USDC]`
+    #[serde(default)]
+    pub conversions: BTreeMap<String, ConversionConfig>,
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    #[serde(default)]
+    pub conventions: ConventionsConfig,
+    #[serde(default)]
+    pub ledger: LedgerConfig,
+    #[serde(default)]
+    pub rent: RentConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct DebugConfig {
     /// Default breakpoints to set
     #[serde(default)]
@@ -26,6 +42,7 @@ pub struct DebugConfig {
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
 pub struct OutputConfig {
     /// Default output format (e.g., "text", "json")
     #[serde(default)]
@@ -33,6 +50,110 @@ pub struct OutputConfig {
     /// Show events by default
     #[serde(default)]
     pub show_events: Option<bool>,
+    /// Sections `run` collects by default, as for `--show`
+    #[serde(default)]
+    pub show: Option<Vec<String>>,
+    /// Nesting depth at which rendered values are truncated
+    #[serde(default)]
+    pub max_value_depth: Option<usize>,
+    /// Render timepoints as dates and durations as spans (default true)
+    #[serde(default)]
+    pub humanize_time: Option<bool>,
+}
+
+/// Which contract functions a debugging session may call.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicyConfig {
+    /// Glob patterns of functions that must not be called, e.g. `emergency_*`
+    #[serde(default)]
+    pub deny_functions: Vec<String>,
+    /// When non-empty, only functions matching one of these globs may be called
+    #[serde(default)]
+    pub allow_functions: Vec<String>,
+}
+
+/// Naming conventions checked against what a function actually does.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ConventionsConfig {
+    /// Glob patterns of read-only functions, e.g. `get_*`; a run of one that
+    /// writes storage or emits events is reported
+    #[serde(default)]
+    pub readonly_patterns: Vec<String>,
+}
+
+/// Ledger values `run` starts from, as for `--ledger-timestamp`,
+/// `--ledger-sequence`, `--network-passphrase` and `--protocol-version`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LedgerConfig {
+    #[serde(default)]
+    pub timestamp: Option<LedgerTimestamp>,
+    #[serde(default)]
+    pub sequence: Option<u32>,
+    #[serde(default)]
+    pub network_passphrase: Option<String>,
+    #[serde(default)]
+    pub protocol_version: Option<u32>,
+}
+
+/// Rates of the rent estimate shown with the `run` storage listing.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct RentConfig {
+    /// Network whose default rates are used (default testnet)
+    #[serde(default)]
+    pub network: Option<RentNetwork>,
+    /// Stroops per byte per ledger for persistent and instance entries
+    #[serde(default)]
+    pub persistent_rate: Option<f64>,
+    /// Stroops per byte per ledger for temporary entries
+    #[serde(default)]
+    pub temporary_rate: Option<f64>,
+    /// Ledgers of rent to estimate
+    #[serde(default)]
+    pub ledgers: Option<u32>,
+}
+
+/// `rent.network`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RentNetwork {
+    Testnet,
+    Mainnet,
+}
+
+/// `ledger.timestamp`: Unix seconds, or an RFC 3339 date as a string.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum LedgerTimestamp {
+    Seconds(u64),
+    Date(String),
+}
+
+impl fmt::Display for LedgerTimestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LedgerTimestamp::Seconds(seconds) => write!(f, "{}", seconds),
+            LedgerTimestamp::Date(date) => f.write_str(date),
+        }
+    }
+}
+
+/// Scaling rule for `<number>_<SUFFIX>` argument literals.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ConversionConfig {
+    /// Number of decimal places; `100.5_USDC` with 7 decimals sends 1005000000
+    pub decimals: u32,
+    /// Target integer type (u32, i32, u64, i64, u128, i128)
+    #[serde(rename = "type", default = "default_conversion_type")]
+    pub target_type: String,
+}
+
+fn default_conversion_type() -> String {
+    "i128".to_string()
 }
 
 impl Config {
@@ -52,9 +173,18 @@ impl Config {
         })?;
 
         let config: Config = toml::from_str(&content).map_err(|e| {
+            let location = e
+                .span()
+                .map(|span| {
+                    let (line, column) = line_col(&content, span.start);
+                    format!(" at line {}, column {}", line, column)
+                })
+                .unwrap_or_default();
             DebuggerError::FileError(format!(
-                "Failed to parse TOML config from {:?}: {}",
-                config_path, e
+                "Failed to parse TOML config from {:?}{}: {}",
+                config_path,
+                location,
+                e.message().trim_end()
             ))
         })?;
 
@@ -72,3 +202,623 @@ impl Config {
         }
     }
 }
+
+/// A problem found by [`validate_config`], located in the source file when possible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// 1-based line of the offending key or value.
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    /// Dotted path of the key, e.g. `output.show_events`.
+    pub key: Option<String>,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{}:{}: {}", line, column, self.message),
+            (Some(line), None) => write!(f, "{}: {}", line, self.message),
+            _ => write!(f, "{}", self.message),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Expected {
+    Bool,
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// A non-negative integer or float.
+    Rate,
+    TextList,
+    /// A list of `--breakpoint` specs: function names, each optionally
+    /// followed by `if <condition>`.
+    Breakpoints,
+    /// Unix seconds, or a string holding an RFC 3339 date.
+    Timepoint,
+    Text,
+    /// A list whose items are each one of the given strings.
+    ListOf(&'static [&'static str]),
+    OneOf(&'static [&'static str]),
+    Section(&'static [Field]),
+    /// A table whose keys are user-chosen names, each holding the given section.
+    SectionMap(&'static [Field]),
+}
+
+struct Field {
+    name: &'static str,
+    expected: Expected,
+    required: bool,
+}
+
+const fn field(name: &'static str, expected: Expected) -> Field {
+    Field {
+        name,
+        expected,
+        required: false,
+    }
+}
+
+const DEBUG_FIELDS: &[Field] = &[
+    field("breakpoints", Expected::Breakpoints),
+    field("verbosity", Expected::Integer { min: 0, max: 3 }),
+];
+
+/// Items accepted by `--show` and `output.show`.
+pub const SHOW_ITEMS: &[&str] = &[
+    "events",
+    "auth",
+    "ledger",
+    "host-calls",
+    "diagnostics",
+    "storage-diff",
+    "all",
+];
+
+const OUTPUT_FIELDS: &[Field] = &[
+    field("format", Expected::OneOf(&["text", "json"])),
+    field("show_events", Expected::Bool),
+    field("show", Expected::ListOf(SHOW_ITEMS)),
+    field(
+        "max_value_depth",
+        Expected::Integer {
+            min: 1,
+            max: i64::MAX,
+        },
+    ),
+    field("humanize_time", Expected::Bool),
+];
+
+const CONVERSION_FIELDS: &[Field] = &[
+    Field {
+        name: "decimals",
+        expected: Expected::Integer { min: 0, max: 38 },
+        required: true,
+    },
+    field(
+        "type",
+        Expected::OneOf(&["u32", "i32", "u64", "i64", "u128", "i128"]),
+    ),
+];
+
+const POLICY_FIELDS: &[Field] = &[
+    field("deny_functions", Expected::TextList),
+    field("allow_functions", Expected::TextList),
+];
+
+const CONVENTIONS_FIELDS: &[Field] = &[field("readonly_patterns", Expected::TextList)];
+
+const LEDGER_FIELDS: &[Field] = &[
+    field("timestamp", Expected::Timepoint),
+    field(
+        "sequence",
+        Expected::Integer {
+            min: 0,
+            max: u32::MAX as i64,
+        },
+    ),
+    field("network_passphrase", Expected::Text),
+    field(
+        "protocol_version",
+        Expected::Integer {
+            min: 0,
+            max: u32::MAX as i64,
+        },
+    ),
+];
+
+const RENT_FIELDS: &[Field] = &[
+    field("network", Expected::OneOf(&["testnet", "mainnet"])),
+    field("persistent_rate", Expected::Rate),
+    field("temporary_rate", Expected::Rate),
+    field(
+        "ledgers",
+        Expected::Integer {
+            min: 1,
+            max: u32::MAX as i64,
+        },
+    ),
+];
+
+const CONFIG_FIELDS: &[Field] = &[
+    field("debug", Expected::Section(DEBUG_FIELDS)),
+    field("output", Expected::Section(OUTPUT_FIELDS)),
+    field("conversions", Expected::SectionMap(CONVERSION_FIELDS)),
+    field("policy", Expected::Section(POLICY_FIELDS)),
+    field("conventions", Expected::Section(CONVENTIONS_FIELDS)),
+    field("ledger", Expected::Section(LEDGER_FIELDS)),
+    field("rent", Expected::Section(RENT_FIELDS)),
+];
+
+/// Check a config file against the schema and report every problem found.
+pub fn validate_config_file(path: &Path) -> Result<Vec<ConfigProblem>> {
+    let content = fs::read_to_string(path).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to read config file {:?}: {}", path, e))
+    })?;
+    Ok(validate_config(&content))
+}
+
+/// Check config file contents against the schema.
+///
+/// Unlike loading, which stops at the first error, this reports all unknown
+/// keys, wrong types and out-of-range values, with "did you mean"
+/// suggestions for misspelt keys.
+pub fn validate_config(content: &str) -> Vec<ConfigProblem> {
+    let table = match content.parse::<toml::Table>() {
+        Ok(table) => table,
+        Err(e) => {
+            let location = e.span().map(|span| line_col(content, span.start));
+            return vec![ConfigProblem {
+                line: location.map(|(line, _)| line),
+                column: location.map(|(_, column)| column),
+                key: None,
+                message: format!("invalid TOML: {}", e.message().trim_end()),
+            }];
+        }
+    };
+    let mut validator = Validator {
+        content,
+        problems: Vec::new(),
+    };
+    validator.check_table(&table, CONFIG_FIELDS, &[]);
+    let mut problems = validator.problems;
+    problems.sort_by_key(|problem| (problem.line.unwrap_or(usize::MAX), problem.column));
+    problems
+}
+
+struct Validator<'a> {
+    content: &'a str,
+    problems: Vec<ConfigProblem>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, path: &[String], message: String) {
+        let location = locate_key(self.content, path);
+        self.problems.push(ConfigProblem {
+            line: location.map(|(line, _)| line),
+            column: location.map(|(_, column)| column),
+            key: Some(path.join(".")),
+            message,
+        });
+    }
+
+    fn check_table(&mut self, table: &toml::Table, fields: &[Field], path: &[String]) {
+        for (key, value) in table {
+            let mut key_path = path.to_vec();
+            key_path.push(key.clone());
+            match fields.iter().find(|field| field.name == key) {
+                Some(field) => self.check_value(value, field.expected, &key_path),
+                None => {
+                    let suggestion = closest(key, fields.iter().map(|field| field.name))
+                        .map(|name| format!(" (did you mean `{}`?)", name))
+                        .unwrap_or_default();
+                    self.report(
+                        &key_path,
+                        format!("unknown key `{}`{}", key_path.join("."), suggestion),
+                    );
+                }
+            }
+        }
+        for field in fields.iter().filter(|field| field.required) {
+            if !table.contains_key(field.name) {
+                self.report(
+                    path,
+                    format!(
+                        "`{}` is missing required key `{}`",
+                        path.join("."),
+                        field.name
+                    ),
+                );
+            }
+        }
+    }
+
+    fn check_value(&mut self, value: &toml::Value, expected: Expected, path: &[String]) {
+        let name = path.join(".");
+        match (expected, value) {
+            (Expected::Bool, toml::Value::Boolean(_)) => {}
+            (Expected::Integer { min, max }, toml::Value::Integer(n)) => {
+                if *n < min || *n > max {
+                    let range = if max == i64::MAX {
+                        format!("at least {}", min)
+                    } else {
+                        format!("between {} and {}", min, max)
+                    };
+                    self.report(path, format!("`{}` must be {}, found {}", name, range, n));
+                }
+            }
+            (Expected::Rate, toml::Value::Integer(n)) if *n < 0 => {
+                self.report(path, format!("`{}` must be at least 0, found {}", name, n));
+            }
+            (Expected::Rate, toml::Value::Float(rate)) if rate.is_nan() || *rate < 0.0 => {
+                self.report(
+                    path,
+                    format!("`{}` must be at least 0, found {}", name, rate),
+                );
+            }
+            (Expected::Rate, toml::Value::Integer(_) | toml::Value::Float(_)) => {}
+            (Expected::Timepoint, toml::Value::Integer(n)) => {
+                if *n < 0 {
+                    self.report(path, format!("`{}` must be at least 0, found {}", name, n));
+                }
+            }
+            (Expected::Timepoint, toml::Value::String(text)) => {
+                if let Err(e) = crate::utils::time::parse_timepoint(text) {
+                    self.report(path, format!("`{}`: {}", name, e));
+                }
+            }
+            (Expected::Text, toml::Value::String(_)) => {}
+            (Expected::TextList, toml::Value::Array(items)) => {
+                if let Some(item) = items.iter().find(|item| !item.is_str()) {
+                    self.report(
+                        path,
+                        format!(
+                            "`{}` must be a list of strings, found a {}",
+                            name,
+                            item.type_str()
+                        ),
+                    );
+                }
+            }
+            (Expected::Breakpoints, toml::Value::Array(items)) => {
+                for item in items {
+                    let Some(spec) = item.as_str() else {
+                        self.report(
+                            path,
+                            format!(
+                                "`{}` must be a list of strings, found a {}",
+                                name,
+                                item.type_str()
+                            ),
+                        );
+                        continue;
+                    };
+                    if let Err(e) = crate::debugger::breakpoint::BreakpointSpec::parse(spec) {
+                        self.report(path, format!("`{}`: {}", name, e));
+                    }
+                }
+            }
+            (Expected::ListOf(allowed), toml::Value::Array(items)) => {
+                for item in items {
+                    let Some(text) = item.as_str() else {
+                        self.report(
+                            path,
+                            format!(
+                                "`{}` must be a list of strings, found a {}",
+                                name,
+                                item.type_str()
+                            ),
+                        );
+                        continue;
+                    };
+                    if !allowed.contains(&text.to_ascii_lowercase().as_str()) {
+                        let suggestion = closest(text, allowed.iter().copied())
+                            .map(|name| format!(" (did you mean `{}`?)", name))
+                            .unwrap_or_default();
+                        self.report(
+                            path,
+                            format!(
+                                "`{}` items must be one of {}, found \"{}\"{}",
+                                name,
+                                allowed.join(", "),
+                                text,
+                                suggestion
+                            ),
+                        );
+                    }
+                }
+            }
+            (Expected::OneOf(allowed), toml::Value::String(text)) => {
+                if !allowed.contains(&text.to_ascii_lowercase().as_str()) {
+                    let suggestion = closest(text, allowed.iter().copied())
+                        .map(|name| format!(" (did you mean `{}`?)", name))
+                        .unwrap_or_default();
+                    self.report(
+                        path,
+                        format!(
+                            "`{}` must be one of {}, found \"{}\"{}",
+                            name,
+                            allowed.join(", "),
+                            text,
+                            suggestion
+                        ),
+                    );
+                }
+            }
+            (Expected::Section(fields), toml::Value::Table(table)) => {
+                self.check_table(table, fields, path)
+            }
+            (Expected::SectionMap(fields), toml::Value::Table(table)) => {
+                for (entry, value) in table {
+                    let mut entry_path = path.to_vec();
+                    entry_path.push(entry.clone());
+                    self.check_value(value, Expected::Section(fields), &entry_path);
+                }
+            }
+            (expected, value) => {
+                let wanted = match expected {
+                    Expected::Bool => "a boolean",
+                    Expected::Integer { .. } => "an integer",
+                    Expected::Rate => "a number",
+                    Expected::Timepoint => "an integer or an RFC 3339 date string",
+                    Expected::OneOf(_) | Expected::Text => "a string",
+                    Expected::TextList | Expected::Breakpoints | Expected::ListOf(_) => {
+                        "a list of strings"
+                    }
+                    Expected::Section(_) | Expected::SectionMap(_) => "a table",
+                };
+                self.report(
+                    path,
+                    format!("`{}` must be {}, found {}", name, wanted, value.type_str()),
+                );
+            }
+        }
+    }
+}
+
+/// Closest candidate within a couple of edits, for "did you mean" hints.
+fn closest<'a>(input: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let input = input.to_ascii_lowercase();
+    candidates
+        .map(|candidate| (edit_distance(&input, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// 1-based line and column of a byte offset.
+fn line_col(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset.min(content.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |tail| tail.chars().count())
+        + 1;
+    (line, column)
+}
+
+/// Find the line defining `path`, either as a `[table]` header or a `key =`
+/// line. Falls back to the closest enclosing key (e.g. for inline tables).
+fn locate_key(content: &str, path: &[String]) -> Option<(usize, usize)> {
+    let split = |key: &str| -> Vec<String> {
+        key.split('.')
+            .map(|part| {
+                part.trim()
+                    .trim_matches(|c: char| c == '"' || c == '\'')
+                    .to_string()
+            })
+            .collect()
+    };
+    for len in (1..=path.len()).rev() {
+        let wanted = &path[..len];
+        let mut table: Vec<String> = Vec::new();
+        for (index, raw) in content.lines().enumerate() {
+            let line = raw.trim_start();
+            let column = raw.len() - line.len() + 1;
+            if line.starts_with('#') {
+                continue;
+            }
+            if let Some(header) = line.strip_prefix('[') {
+                let header = header.trim_start_matches('[');
+                let Some(end) = header.find(']') else {
+                    continue;
+                };
+                table = split(&header[..end]);
+                if table == wanted {
+                    return Some((index + 1, column));
+                }
+                continue;
+            }
+            let Some(eq) = line.find('=') else {
+                continue;
+            };
+            let mut full = table.clone();
+            full.extend(split(&line[..eq]));
+            if full == wanted {
+                return Some((index + 1, column));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(content: &str) -> Vec<String> {
+        validate_config(content)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn valid_config_has_no_problems() {
+        let content = r#"
+[debug]
+breakpoints = ["verify", "auth"]
+verbosity = 2
+
+[output]
+format = "json"
+show_events = true
+show = ["events", "host-calls"]
+
+[conversions.USDC]
+decimals = 7
+type = "i128"
+
+[policy]
+deny_functions = ["emergency_*"]
+allow_functions = ["get_*", "transfer"]
+
+[conventions]
+readonly_patterns = ["get_*", "view_*"]
+
+[ledger]
+timestamp = "2024-01-01T00:00:00Z"
+sequence = 1000
+network_passphrase = "Test SDF Network ; September 2015"
+protocol_version = 22
+
+[rent]
+network = "mainnet"
+persistent_rate = 0.006
+temporary_rate = 0
+ledgers = 120960
+"#;
+        assert!(validate_config(content).is_empty());
+        assert!(toml::from_str::<Config>(content).is_ok());
+    }
+
+    #[test]
+    fn unknown_keys_are_reported_with_line_and_suggestion() {
+        let content = "[debug]\nbreakpoints = []\n\n[output]\nshow_event = true\n\n[outptu]\nformat = \"text\"\n";
+        let problems = validate_config(content);
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        // Reported in file order, not the order keys were visited.
+        assert_eq!(problems[0].line, Some(5));
+        assert_eq!(
+            problems[0].message,
+            "unknown key `output.show_event` (did you mean `show_events`?)"
+        );
+        assert_eq!(problems[1].line, Some(7));
+        assert!(problems[1].message.contains("did you mean `output`?"));
+
+        // Loading rejects the same typo instead of silently ignoring it.
+        let err = toml::from_str::<Config>(content).unwrap_err();
+        assert!(err.message().contains("show_event"));
+    }
+
+    #[test]
+    fn wrong_types_are_all_reported() {
+        let problems = messages(
+            "[debug]\nbreakpoints = \"verify\"\nverbosity = 7\n\n[output]\nshow_events = \"yes\"\n",
+        );
+        assert_eq!(
+            problems,
+            vec![
+                "2:1: `debug.breakpoints` must be a list of strings, found string",
+                "3:1: `debug.verbosity` must be between 0 and 3, found 7",
+                "6:1: `output.show_events` must be a boolean, found string",
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_enum_values_and_missing_required_keys() {
+        let problems =
+            messages("[output]\nformat = \"jsn\"\n\n[conversions.USDC]\ntype = \"i129\"\n");
+        assert_eq!(
+            problems,
+            vec![
+                "2:1: `output.format` must be one of text, json, found \"jsn\" (did you mean `json`?)",
+                "4:1: `conversions.USDC` is missing required key `decimals`",
+                "5:1: `conversions.USDC.type` must be one of u32, i32, u64, i64, u128, i128, found \"i129\" (did you mean `i128`?)",
+            ]
+        );
+    }
+
+    #[test]
+    fn breakpoint_conditions_are_checked() {
+        assert!(
+            messages("[debug]\nbreakpoints = [\"withdraw if args[0] > 1000000\"]\n").is_empty()
+        );
+        assert_eq!(
+            messages("[debug]\nbreakpoints = [\"verify\", \"withdraw if args[0] >\"]\n"),
+            vec!["2:1: `debug.breakpoints`: Invalid breakpoint 'withdraw if args[0] >': Unexpected end of expression"]
+        );
+    }
+
+    #[test]
+    fn show_list_items_are_checked() {
+        let problems = messages("[output]\nshow = [\"events\", \"evnts\", 3]\n");
+        assert_eq!(
+            problems,
+            vec![
+                "2:1: `output.show` items must be one of events, auth, ledger, host-calls, diagnostics, storage-diff, all, found \"evnts\" (did you mean `events`?)",
+                "2:1: `output.show` must be a list of strings, found a integer",
+            ]
+        );
+        assert_eq!(
+            messages("[output]\nshow = \"events\"\n"),
+            vec!["2:1: `output.show` must be a list of strings, found string"]
+        );
+    }
+
+    #[test]
+    fn ledger_timestamps_are_seconds_or_dates() {
+        let config: Config = toml::from_str("[ledger]\ntimestamp = 1700000000\n").unwrap();
+        assert_eq!(
+            config.ledger.timestamp,
+            Some(LedgerTimestamp::Seconds(1_700_000_000))
+        );
+
+        let problems = messages("[ledger]\ntimestamp = \"next week\"\nsequence = -1\n");
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(
+            problems[0].starts_with(
+                "2:1: `ledger.timestamp`: 'next week' is neither Unix seconds nor an RFC 3339 date"
+            ),
+            "{}",
+            problems[0]
+        );
+        assert_eq!(
+            problems[1],
+            "3:1: `ledger.sequence` must be between 0 and 4294967295, found -1"
+        );
+        assert_eq!(
+            messages("[ledger]\ntimestamp = 1.5\n"),
+            vec!["2:1: `ledger.timestamp` must be an integer or an RFC 3339 date string, found float"]
+        );
+    }
+
+    #[test]
+    fn rent_rates_are_non_negative_numbers() {
+        let config: Config = toml::from_str("[rent]\nnetwork = \"mainnet\"\n").unwrap();
+        assert_eq!(config.rent.network, Some(RentNetwork::Mainnet));
+
+        assert_eq!(
+            messages("[rent]\nnetwork = \"pubnet\"\npersistent_rate = -0.5\ntemporary_rate = \"1\"\nledgers = 0\n"),
+            vec![
+                "2:1: `rent.network` must be one of testnet, mainnet, found \"pubnet\"",
+                "3:1: `rent.persistent_rate` must be at least 0, found -0.5",
+                "4:1: `rent.temporary_rate` must be a number, found string",
+                "5:1: `rent.ledgers` must be between 1 and 4294967295, found 0",
+            ]
+        );
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_offending_line() {
+        let problems = validate_config("[output]\nshow_events = = true\n");
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(2));
+        assert!(problems[0].message.starts_with("invalid TOML"));
+    }
+}
@@ -0,0 +1,149 @@
+//! Assertions checked after a contract call.
+//!
+//! Scenario steps (`assertions = [...]`) and REPL scripts (`assert` lines)
+//! share this evaluation. An assertion is an
+//! [expression](crate::debugger::expression) over `result`, the return value
+//! of the last call, `now()`, the ledger timestamp, and the storage
+//! provenance functions (see [`StorageProvenance::register_functions`]),
+//! e.g. `writer(admin) == initialize && result > 0`. The contract's function
+//! names, and `none`, stand for themselves.
+
+use crate::debugger::breakpoint::ConditionEvaluator;
+use crate::debugger::expression::call_arguments;
+use crate::debugger::expression::{ExprValue, ExpressionEvaluator};
+use crate::inspector::storage_provenance::StorageProvenance;
+use crate::runtime::executor::ContractExecutor;
+use crate::utils::arguments::scval_to_json;
+use crate::Result;
+
+/// Provenance functions whose key a failed assertion lists the writers of.
+const PROVENANCE_FUNCTIONS: &[&str] = &["writer", "writers", "step"];
+
+/// What assertions are evaluated against after a call.
+pub struct AssertionContext<'a> {
+    provenance: &'a StorageProvenance,
+    evaluator: ExpressionEvaluator,
+}
+
+impl<'a> AssertionContext<'a> {
+    /// The state of `executor` after its last call. `result` is only defined
+    /// when that call returned; fields of a structured return value read as
+    /// paths, e.g. `result.balance`.
+    pub fn for_executor(executor: &'a ContractExecutor) -> Self {
+        let mut evaluator = ExpressionEvaluator::new(executor.ledger_timestamp());
+        for function in executor.exported_functions() {
+            evaluator.set(&function, ExprValue::Str(function.clone()));
+        }
+        if let Some(Ok(value)) = executor.last_execution().map(|record| &record.result) {
+            let return_type = executor
+                .last_execution()
+                .and_then(|record| executor.function_signature(&record.function))
+                .and_then(|signature| signature.return_type)
+                .unwrap_or_default();
+            evaluator.set_json("result", scval_to_json(value), &return_type);
+        }
+        Self::new(executor.storage_provenance(), evaluator)
+    }
+
+    fn new(provenance: &'a StorageProvenance, mut evaluator: ExpressionEvaluator) -> Self {
+        evaluator.set("none", ExprValue::Str("none".to_string()));
+        provenance.register_functions(&mut evaluator);
+        Self {
+            provenance,
+            evaluator,
+        }
+    }
+
+    /// Whether `assertion` holds. Malformed assertions, and expressions that
+    /// are not true or false, are errors.
+    pub fn check(&self, assertion: &str) -> Result<bool> {
+        self.evaluator.evaluate(assertion)
+    }
+
+    /// Detail appended to a failed assertion: the writers of the first key a
+    /// provenance function names, and the value of `result`.
+    pub fn failure_detail(&self, assertion: &str) -> String {
+        let mut detail = String::new();
+        let provenance = PROVENANCE_FUNCTIONS
+            .iter()
+            .flat_map(|function| call_arguments(assertion, function))
+            .find_map(|key| self.provenance.lookup(&key));
+        if let Some(provenance) = provenance {
+            detail.push_str(&format!(
+                " (written by {})",
+                provenance
+                    .writers()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+        match self.evaluator.eval("result") {
+            Ok(result) if assertion.contains("result") => {
+                detail.push_str(&format!(" (result = {})", result))
+            }
+            _ => {}
+        }
+        detail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector::storage_provenance::Writer;
+
+    fn context(provenance: &StorageProvenance, result: Option<ExprValue>) -> AssertionContext<'_> {
+        let mut evaluator = ExpressionEvaluator::new(1_700_000_000);
+        for function in ["initialize", "set_admin"] {
+            evaluator.set(function, ExprValue::Str(function.to_string()));
+        }
+        if let Some(result) = result {
+            evaluator.set("result", result);
+        }
+        AssertionContext::new(provenance, evaluator)
+    }
+
+    #[test]
+    fn provenance_and_expression_assertions_share_one_entry_point() {
+        let mut provenance = StorageProvenance::new();
+        provenance.record_write(
+            "contract_data:Persistent:Symbol(ScSymbol(StringM(admin)))",
+            Writer {
+                step: 1,
+                function: "initialize".to_string(),
+                sequence: 10,
+            },
+        );
+        let context = context(&provenance, Some(ExprValue::Int(3)));
+
+        assert!(context.check("writer(admin) == initialize").unwrap());
+        assert!(context
+            .check("writer(admin) == initialize && result > 0")
+            .unwrap());
+        assert!(context
+            .check("writer(owner) == none || step(owner) == 0")
+            .unwrap());
+        assert!(context.check("result >= 3 && result < 10").unwrap());
+        assert!(!context.check("result == 4").unwrap());
+        assert!(context.check("result + 1").is_err());
+        assert_eq!(context.failure_detail("result == 4"), " (result = 3)");
+        assert_eq!(
+            context.failure_detail("writer(admin) == set_admin"),
+            " (written by step 1 initialize (#10))"
+        );
+        assert_eq!(
+            context.failure_detail("result == 4 && writers(admin) == set_admin"),
+            " (written by step 1 initialize (#10)) (result = 3)"
+        );
+    }
+
+    #[test]
+    fn result_is_undefined_without_a_numeric_return() {
+        let provenance = StorageProvenance::new();
+        let context = context(&provenance, None);
+        let err = context.check("result == 1").unwrap_err().to_string();
+        assert!(err.contains("Unknown name 'result'"), "{}", err);
+        assert_eq!(context.failure_detail("result == 1"), "");
+    }
+}
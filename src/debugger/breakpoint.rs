@@ -1,4 +1,13 @@
+use crate::inspector::events::{ContractEvent, EventInspector};
+use crate::inspector::render_cache::RenderCache;
+use crate::inspector::storage::StorageFilter;
+use crate::inspector::storage_decode::decode_text;
+use crate::inspector::storage_history::{StackFrame, StorageMutation};
+use crate::runtime::call_stack::CallStack;
+use crate::runtime::observer::{call_args, call_target, symbol};
 use serde::{Deserialize, Serialize};
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType};
 use std::collections::HashMap;
 
 /// Represents a single breakpoint with optional conditions and logging
@@ -87,6 +96,42 @@ pub struct BreakpointSpec {
     pub log_message: Option<String>,
 }
 
+impl BreakpointSpec {
+    /// Parse a `--breakpoint` or `debug.breakpoints` entry: a function name,
+    /// optionally followed by `if <condition>`, e.g.
+    /// `transfer if args[1] == "GDQP..."`. The condition is checked here so
+    /// a typo fails at startup rather than at the first call.
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
+        let (function, condition) = match spec.split_once(" if ") {
+            Some((function, condition)) => (function.trim(), Some(condition)),
+            None => (spec.trim(), None),
+        };
+        if function.is_empty() || function.contains(char::is_whitespace) {
+            return Err(format!(
+                "Invalid breakpoint '{}': expected <function> or <function> if <condition>",
+                spec
+            ));
+        }
+        let condition = condition
+            .map(BreakpointManager::parse_condition)
+            .transpose()
+            .map_err(|e| {
+                let reason = match e.downcast_ref::<crate::DebuggerError>() {
+                    Some(crate::DebuggerError::BreakpointError(reason)) => reason.clone(),
+                    _ => e.to_string(),
+                };
+                format!("Invalid breakpoint '{}': {}", spec, reason)
+            })?;
+        Ok(Self {
+            id: function.to_string(),
+            function: function.to_string(),
+            condition,
+            hit_condition: None,
+            log_message: None,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct BreakpointHit {
     pub should_pause: bool,
@@ -222,11 +267,15 @@ impl BreakpointManager {
         self.breakpoints.values().collect()
     }
 
+    /// Record a hit on `function`'s breakpoint and decide what it does.
+    ///
+    /// `None` if there is no breakpoint or its hit condition or condition
+    /// does not hold. Log messages whose placeholders cannot be evaluated are
+    /// logged as written.
     pub fn on_hit(
         &mut self,
         function: &str,
-        _storage: &HashMap<String, String>,
-        _args: Option<&str>,
+        evaluator: &dyn ConditionEvaluator,
     ) -> crate::Result<Option<BreakpointHit>> {
         let Some(bp) = self.breakpoints.get_mut(function) else {
             return Ok(None);
@@ -240,7 +289,21 @@ impl BreakpointManager {
             }
         }
 
-        let log_messages = bp.log_message.clone().into_iter().collect();
+        if let Some(condition) = &bp.condition {
+            if !evaluator.evaluate(condition)? {
+                return Ok(None);
+            }
+        }
+
+        let log_messages = bp
+            .log_message
+            .iter()
+            .map(|template| {
+                evaluator
+                    .interpolate_log(template)
+                    .unwrap_or_else(|_| template.clone())
+            })
+            .collect();
         Ok(Some(BreakpointHit {
             should_pause: !bp.is_log_point(),
             log_messages,
@@ -282,7 +345,7 @@ impl BreakpointManager {
             .into());
         }
 
-        // Additional validation could be added here
+        crate::debugger::expression::validate(s)?;
         Ok(s.to_string())
     }
 
@@ -454,6 +517,254 @@ impl Default for BreakpointManager {
     }
 }
 
+/// Storage keys whose writes stop execution (`--break-on-write`).
+///
+/// The host runs an invocation to completion, so the stop comes right after
+/// the invocation that wrote a watched key, with the write as the storage
+/// history recorded it: the frame that made it, nested calls included, and
+/// the calls that led there. Sets, removals and TTL extensions all count.
+#[derive(Debug, Clone)]
+pub struct WriteBreakpoints {
+    patterns: Vec<String>,
+    filter: StorageFilter,
+}
+
+impl WriteBreakpoints {
+    /// Watch the keys matching any of `patterns`, which take the
+    /// `--storage-filter` key syntax: exact keys, `prefix*`, `re:<regex>`
+    /// and `!` exclusions.
+    pub fn new(patterns: &[String]) -> std::result::Result<Self, String> {
+        if let Some(pattern) = patterns
+            .iter()
+            .find(|p| p.trim_start_matches('!').starts_with("val:"))
+        {
+            return Err(format!(
+                "Invalid write breakpoint '{}': only key patterns are supported",
+                pattern
+            ));
+        }
+        let filter =
+            StorageFilter::new(patterns).map_err(|e| format!("Invalid write breakpoint: {}", e))?;
+        Ok(Self {
+            patterns: patterns.to_vec(),
+            filter,
+        })
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// The writes among `mutations` of a watched key, in order.
+    pub fn hits<'a>(&self, mutations: &'a [StorageMutation]) -> Vec<&'a StorageMutation> {
+        mutations
+            .iter()
+            .filter(|mutation| self.filter.matches(&mutation.key))
+            .collect()
+    }
+
+    /// What a stop on `mutation` reports: the key, the values around the
+    /// write, the frame that made it and its call stack with the arguments
+    /// of each call, innermost last. `names` maps contract IDs to their
+    /// linked names.
+    pub fn describe(mutation: &StorageMutation, names: &HashMap<String, String>) -> String {
+        let mut frames = mutation.callers.clone();
+        frames.push(StackFrame {
+            contract: mutation.contract.clone(),
+            function: mutation.function.clone(),
+            args: mutation.args.clone(),
+        });
+        let mut lines = vec![
+            format!(
+                "Write breakpoint hit: {} (step {})",
+                mutation.key, mutation.step
+            ),
+            format!(
+                "  {}: {} -> {}",
+                mutation.operation.name(),
+                mutation.old.as_deref().unwrap_or("<absent>"),
+                mutation.new.as_deref().unwrap_or("<absent>")
+            ),
+            format!("  in {}", mutation.frame()),
+            "  call stack (innermost last):".to_string(),
+        ];
+        lines.extend(
+            CallStack::from_frames(&frames, names)
+                .render()
+                .into_iter()
+                .map(|line| format!("    {}", line)),
+        );
+        lines.join("\n")
+    }
+}
+
+/// One `--break-on-event` pattern: a case-insensitive substring of any
+/// topic, as `--filter-topic` matches, or of topic N only with an `N:`
+/// prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TopicPattern {
+    index: Option<usize>,
+    text: String,
+}
+
+impl TopicPattern {
+    fn parse(pattern: &str) -> std::result::Result<Self, String> {
+        let (index, text) = match pattern.split_once(':') {
+            Some((index, text))
+                if !index.is_empty() && index.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                (index.parse().ok(), text)
+            }
+            _ => (None, pattern),
+        };
+        if text.is_empty() {
+            return Err(format!(
+                "Invalid event breakpoint '{}': expected a topic pattern, optionally as <index>:<pattern>",
+                pattern
+            ));
+        }
+        Ok(Self {
+            index,
+            text: text.to_lowercase(),
+        })
+    }
+
+    fn matches(&self, topics: &[String]) -> bool {
+        let matches = |topic: &String| topic.to_lowercase().contains(&self.text);
+        match self.index {
+            Some(index) => topics.get(index).is_some_and(matches),
+            None => topics.iter().any(matches),
+        }
+    }
+}
+
+/// An event that matched a `--break-on-event` pattern.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventHit {
+    #[serde(flatten)]
+    pub event: ContractEvent,
+    /// The topics and data decoded from their ScVals, as the storage
+    /// listing shows keys and values.
+    pub decoded_topics: Vec<String>,
+    pub decoded_data: String,
+    /// True for a diagnostic event, false for a contract or system event.
+    pub diagnostic: bool,
+    /// The calls open when the event was emitted, outermost first; empty
+    /// when the host recorded no calls (diagnostics off).
+    pub stack: Vec<StackFrame>,
+}
+
+/// Event topics that stop execution (`--break-on-event`).
+///
+/// Like write breakpoints these stop once the host returns from the
+/// invocation, with the event as it was emitted and the calls that were open
+/// at that point, read from the `fn_call` / `fn_return` diagnostic events.
+#[derive(Debug, Clone)]
+pub struct EventBreakpoints {
+    patterns: Vec<TopicPattern>,
+    contract_only: bool,
+}
+
+impl EventBreakpoints {
+    /// Stop on events matching any of `patterns`; with `contract_only`
+    /// diagnostic events are not checked.
+    pub fn new(patterns: &[String], contract_only: bool) -> std::result::Result<Self, String> {
+        Ok(Self {
+            patterns: patterns
+                .iter()
+                .map(|pattern| TopicPattern::parse(pattern))
+                .collect::<std::result::Result<_, _>>()?,
+            contract_only,
+        })
+    }
+
+    /// Whether `topics`, rendered or decoded, match one of the patterns.
+    pub fn matches(&self, topics: &[String]) -> bool {
+        self.patterns.iter().any(|pattern| pattern.matches(topics))
+    }
+
+    /// The matching events among `events`, in the order they were emitted,
+    /// each with the calls open at the time.
+    pub fn hits(&self, events: &[HostEvent]) -> Vec<EventHit> {
+        let mut cache = RenderCache::new();
+        let mut open: Vec<StackFrame> = Vec::new();
+        let mut hits = Vec::new();
+        for host_event in events {
+            let diagnostic = host_event.event.type_ == ContractEventType::Diagnostic;
+            let ContractEventBody::V0(body) = &host_event.event.body;
+            let topics = body.topics.as_slice();
+            // A `fn_call` stops inside the call it opens, a `fn_return`
+            // inside the call it closes.
+            let marker = if diagnostic {
+                topics.first().and_then(symbol)
+            } else {
+                None
+            };
+            if marker.as_deref() == Some("fn_call") {
+                let (contract, function) = call_target(topics);
+                open.push(StackFrame {
+                    contract,
+                    function,
+                    args: call_args(&body.data),
+                });
+            }
+            if !(diagnostic && self.contract_only) {
+                let event = EventInspector::convert(&host_event.event, &mut cache);
+                let decoded_topics: Vec<String> = topics.iter().map(decode_text).collect();
+                if self.matches(&event.topics) || self.matches(&decoded_topics) {
+                    hits.push(EventHit {
+                        event,
+                        decoded_topics,
+                        decoded_data: decode_text(&body.data),
+                        diagnostic,
+                        stack: open.clone(),
+                    });
+                }
+            }
+            if marker.as_deref() == Some("fn_return") {
+                open.pop();
+            }
+        }
+        hits
+    }
+
+    /// What a stop on `hit` reports: the decoded event, the contract that
+    /// emitted it and the call stack, innermost first.
+    pub fn describe(hit: &EventHit) -> String {
+        let kind = if hit.diagnostic {
+            "diagnostic"
+        } else {
+            "contract"
+        };
+        let contract = hit.event.contract_id.as_deref().unwrap_or("<none>");
+        let mut lines = vec![
+            format!(
+                "Event breakpoint hit: {} event ({})",
+                kind,
+                hit.decoded_topics.join(", ")
+            ),
+            match &hit.event.contract {
+                Some(name) => format!("  contract: {} ({})", name, contract),
+                None => format!("  contract: {}", contract),
+            },
+            format!("  topics: [{}]", hit.decoded_topics.join(", ")),
+            format!("  data: {}", hit.decoded_data),
+        ];
+        if hit.stack.is_empty() {
+            lines.push("  call stack: unknown, no calls were recorded".to_string());
+        } else {
+            lines.push("  call stack:".to_string());
+            for (index, frame) in hit.stack.iter().rev().enumerate() {
+                lines.push(format!(
+                    "    #{} {} in {}",
+                    index, frame.function, frame.contract
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -573,6 +884,23 @@ mod tests {
         assert!(log.is_none());
     }
 
+    #[test]
+    fn test_on_hit_checks_condition() {
+        let mut manager = BreakpointManager::new();
+        let mut evaluator = MockEvaluator::new();
+        evaluator.set("amount", 10);
+        manager.set(Breakpoint::with_condition(
+            "transfer".to_string(),
+            "amount > 100".to_string(),
+        ));
+        assert!(manager.on_hit("transfer", &evaluator).unwrap().is_none());
+
+        evaluator.set("amount", 500);
+        let hit = manager.on_hit("transfer", &evaluator).unwrap().unwrap();
+        assert!(hit.should_pause);
+        assert_eq!(manager.get("transfer").unwrap().hit_count, 2);
+    }
+
     #[test]
     fn test_hit_condition_greater_than() {
         let mut manager = BreakpointManager::new();
@@ -742,6 +1070,37 @@ mod tests {
         assert!(BreakpointManager::parse_condition("just_a_variable").is_err());
     }
 
+    #[test]
+    fn test_breakpoint_specs_take_a_condition() {
+        let spec = BreakpointSpec::parse("transfer").unwrap();
+        assert_eq!(spec.function, "transfer");
+        assert_eq!(spec.condition, None);
+
+        let spec = BreakpointSpec::parse(r#"transfer if args[1] == "GDQP""#).unwrap();
+        assert_eq!(spec.function, "transfer");
+        assert_eq!(spec.condition.as_deref(), Some(r#"args[1] == "GDQP""#));
+        let spec = BreakpointSpec::parse("withdraw if args[0] > 1000000 || amount < 0").unwrap();
+        assert_eq!(spec.id, "withdraw");
+
+        for bad in [
+            "",
+            "withdraw if",
+            "withdraw if args[0] >",
+            r#"transfer if args[1] == "GDQP"#,
+            "with draw",
+        ] {
+            assert!(
+                BreakpointSpec::parse(bad).is_err(),
+                "{:?} should not parse",
+                bad
+            );
+        }
+        assert_eq!(
+            BreakpointSpec::parse("withdraw if args[0] >").unwrap_err(),
+            "Invalid breakpoint 'withdraw if args[0] >': Unexpected end of expression"
+        );
+    }
+
     #[test]
     fn test_parse_hit_condition_validation() {
         // Valid hit conditions
@@ -779,4 +1138,137 @@ mod tests {
             .unwrap();
         assert_eq!(manager.get("transfer").unwrap().hit_count, 3);
     }
+
+    #[test]
+    fn test_write_breakpoints_stop_on_watched_keys() {
+        use crate::inspector::storage_history::{StackFrame, WriteOperation};
+
+        let mutation = |key: &str, new: Option<&str>, operation| StorageMutation {
+            step: 2,
+            depth: 1,
+            contract: "CTOKEN".to_string(),
+            function: "transfer".to_string(),
+            key: key.to_string(),
+            old: Some("I128(5)".to_string()),
+            new: new.map(str::to_string),
+            operation,
+            args: vec!["GA".to_string(), "3".to_string()],
+            callers: vec![StackFrame {
+                contract: "CPOOL".to_string(),
+                function: "swap".to_string(),
+                args: vec!["100".to_string()],
+            }],
+        };
+        let mutations = [
+            mutation("balance:alice", Some("I128(2)"), WriteOperation::Set),
+            mutation("balance:bob", None, WriteOperation::Remove),
+            mutation("nonce", Some("U32(1)"), WriteOperation::Set),
+        ];
+
+        let watch = WriteBreakpoints::new(&["balance:*".to_string(), "!balance:alice".to_string()])
+            .unwrap();
+        let hits = watch.hits(&mutations);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            WriteBreakpoints::describe(
+                hits[0],
+                &HashMap::from([("CTOKEN".to_string(), "token".to_string())])
+            ),
+            "Write breakpoint hit: balance:bob (step 2)\n  \
+             remove: I128(5) -> <absent>\n  \
+             in transfer at depth 1 in CTOKEN\n  \
+             call stack (innermost last):\n    \
+             0: swap(100) in CPOOL\n    \
+             1: transfer(GA, 3) in token"
+        );
+
+        assert!(WriteBreakpoints::new(&["re:(".to_string()]).is_err());
+        assert!(WriteBreakpoints::new(&["val:I128*".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_event_breakpoints_match_topics_with_their_call_stack() {
+        use soroban_env_host::xdr::{
+            ContractEvent, ContractEventV0, ExtensionPoint, Hash, ScSymbol, ScVal,
+        };
+
+        let sym = |name: &str| ScVal::Symbol(ScSymbol(name.try_into().unwrap()));
+        let event = |type_, contract: Option<u8>, topics: Vec<ScVal>| HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: contract.map(|id| Hash([id; 32])),
+                type_,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data: ScVal::U32(5),
+                }),
+            },
+            failed_call: false,
+        };
+        let call = |contract: u8, function: &str| {
+            event(
+                ContractEventType::Diagnostic,
+                None,
+                vec![
+                    sym("fn_call"),
+                    ScVal::Bytes(vec![contract; 32].try_into().unwrap()),
+                    sym(function),
+                ],
+            )
+        };
+        let ret = |function: &str| {
+            event(
+                ContractEventType::Diagnostic,
+                None,
+                vec![sym("fn_return"), sym(function)],
+            )
+        };
+        let events = [
+            call(1, "swap"),
+            call(2, "burn"),
+            event(
+                ContractEventType::Contract,
+                Some(2),
+                vec![sym("burn"), sym("alice")],
+            ),
+            ret("burn"),
+            event(
+                ContractEventType::Contract,
+                Some(1),
+                vec![sym("swap"), sym("burn")],
+            ),
+            ret("swap"),
+        ];
+
+        let anywhere = EventBreakpoints::new(&["BURN".to_string()], true).unwrap();
+        let hits = anywhere.hits(&events);
+        assert_eq!(hits.len(), 2);
+        let stack: Vec<&str> = hits[0]
+            .stack
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect();
+        assert_eq!(stack, ["swap", "burn"]);
+        assert_eq!(hits[1].stack.len(), 1);
+        let report = EventBreakpoints::describe(&hits[0]);
+        assert!(
+            report.starts_with("Event breakpoint hit: contract event (burn, alice)"),
+            "{}",
+            report
+        );
+        assert!(report.contains("  data: 5"), "{}", report);
+        assert!(report.contains("    #0 burn in C"), "{}", report);
+        assert!(report.contains("    #1 swap in C"), "{}", report);
+
+        let anchored = EventBreakpoints::new(&["0:burn".to_string()], true).unwrap();
+        assert_eq!(anchored.hits(&events).len(), 1);
+
+        // Diagnostic events match too unless restricted: the call to burn.
+        let with_diagnostics = EventBreakpoints::new(&["2:burn".to_string()], false).unwrap();
+        let hits = with_diagnostics.hits(&events);
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].diagnostic);
+
+        assert!(EventBreakpoints::new(&["1:".to_string()], false).is_err());
+    }
 }
@@ -0,0 +1,458 @@
+//! Following an invocation into the contracts it calls (`step-in` and
+//! `step-over` in `interactive`).
+//!
+//! The host runs an invocation to completion before the debugger sees it, so
+//! the walk replays the calls it made instead, from the `fn_call` /
+//! `fn_return` diagnostic events. `step-in` moves to the entry of the next
+//! call, whatever its depth, and `step-over` runs the current call to its
+//! return and comes back in its caller with the value it returned. A call
+//! that trapped has no `fn_return`; it ends when its caller returns or the
+//! events run out, with the error the host recorded in it.
+
+use crate::inspector::storage_decode::decode_text;
+use crate::inspector::storage_history::StackFrame;
+use crate::runtime::observer::{call_args, call_target, symbol};
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, ScAddress, ScVal};
+
+/// One contract call of the invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalkedCall {
+    /// 0 for the top-level call.
+    pub depth: usize,
+    pub contract: String,
+    pub function: String,
+    /// The arguments, decoded from their ScVals.
+    pub args: Vec<String>,
+    /// The return value; `None` if the call trapped.
+    pub result: Option<String>,
+    /// Why the call trapped, when the host recorded it.
+    pub error: Option<String>,
+    /// Index of the calling call.
+    pub parent: Option<usize>,
+}
+
+impl WalkedCall {
+    fn frame(&self) -> StackFrame {
+        StackFrame {
+            contract: self.contract.clone(),
+            function: self.function.clone(),
+            args: self.args.clone(),
+        }
+    }
+}
+
+/// Where a step stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallStop {
+    /// At the entry of a call.
+    Entered(usize),
+    /// Back in the caller after a call returned or trapped.
+    Returned(usize),
+}
+
+/// The calls of one invocation and the stop the walk is at.
+#[derive(Debug, Clone)]
+pub struct CallWalk {
+    calls: Vec<WalkedCall>,
+    /// Every entry and return, in the order they happened.
+    stops: Vec<CallStop>,
+    /// Calls still open when the events end, outermost first.
+    unfinished: Vec<usize>,
+    position: usize,
+}
+
+impl CallWalk {
+    /// The walk of the calls among `events`, at the entry of the top-level
+    /// call. `error` is what the invocation failed with, for the calls
+    /// still open when the events end. `None` if no calls were recorded
+    /// (diagnostics off).
+    pub fn from_events(events: &[HostEvent], error: Option<&str>) -> Option<Self> {
+        let mut calls: Vec<WalkedCall> = Vec::new();
+        let mut stops = Vec::new();
+        let mut open: Vec<usize> = Vec::new();
+        // Calls from `at` up trapped: they end innermost first.
+        let trap = |open: &mut Vec<usize>, at: usize, stops: &mut Vec<CallStop>| {
+            stops.extend(open.drain(at..).rev().map(CallStop::Returned));
+        };
+        for event in events {
+            if event.event.type_ != ContractEventType::Diagnostic {
+                continue;
+            }
+            let ContractEventBody::V0(body) = &event.event.body;
+            let topics = body.topics.as_slice();
+            // `fn_call` is recorded by the calling contract and `fn_return`
+            // by the returning one.
+            let recorder = event
+                .event
+                .contract_id
+                .as_ref()
+                .map(|id| ScAddress::Contract(id.clone()).to_string());
+            match topics.first().and_then(symbol).as_deref() {
+                Some("fn_call") => {
+                    // Open calls above the caller trapped into a try_call.
+                    let caller = match &recorder {
+                        Some(contract) => open
+                            .iter()
+                            .rposition(|&index| calls[index].contract == *contract)
+                            .map_or(open.len(), |at| at + 1),
+                        None => 0,
+                    };
+                    trap(&mut open, caller, &mut stops);
+                    let (contract, function) = call_target(topics);
+                    stops.push(CallStop::Entered(calls.len()));
+                    calls.push(WalkedCall {
+                        depth: open.len(),
+                        contract,
+                        function,
+                        args: call_args(&body.data),
+                        result: None,
+                        error: None,
+                        parent: open.last().copied(),
+                    });
+                    open.push(calls.len() - 1);
+                }
+                Some("fn_return") => {
+                    let function = topics.get(1).and_then(symbol).unwrap_or_default();
+                    let Some(at) = open.iter().rposition(|&index| {
+                        calls[index].function == function
+                            && recorder
+                                .as_ref()
+                                .is_none_or(|contract| *contract == calls[index].contract)
+                    }) else {
+                        continue;
+                    };
+                    trap(&mut open, at + 1, &mut stops);
+                    let index = open.remove(at);
+                    calls[index].result = Some(decode_text(&body.data));
+                    stops.push(CallStop::Returned(index));
+                }
+                Some("error") => {
+                    if let Some(&innermost) = open.last() {
+                        calls[innermost]
+                            .error
+                            .get_or_insert_with(|| error_message(&body.data));
+                    }
+                }
+                _ => {}
+            }
+        }
+        for &index in &open {
+            if calls[index].error.is_none() {
+                calls[index].error = error.map(str::to_string);
+            }
+        }
+        let unfinished = open.clone();
+        trap(&mut open, 0, &mut stops);
+        if calls.is_empty() {
+            return None;
+        }
+        Some(Self {
+            calls,
+            stops,
+            unfinished,
+            position: 0,
+        })
+    }
+
+    pub fn calls(&self) -> &[WalkedCall] {
+        &self.calls
+    }
+
+    /// The calls still open when the events end, outermost first: the stack
+    /// the invocation stopped in.
+    pub fn unfinished(&self) -> Vec<StackFrame> {
+        self.unfinished
+            .iter()
+            .map(|&index| self.calls[index].frame())
+            .collect()
+    }
+
+    /// The stop the walk is at; `None` once it has run past the end.
+    pub fn current(&self) -> Option<CallStop> {
+        self.stops.get(self.position).copied()
+    }
+
+    /// Move to the entry of the next call, or to the next call that
+    /// trapped. `None` if there is neither, which ends the walk.
+    pub fn step_in(&mut self) -> Option<CallStop> {
+        self.position = (self.position + 1..self.stops.len())
+            .find(|&at| match self.stops[at] {
+                CallStop::Entered(_) => true,
+                CallStop::Returned(index) => self.calls[index].result.is_none(),
+            })
+            .unwrap_or(self.stops.len());
+        self.current()
+    }
+
+    /// Run the current call to its return. Back in a caller, run the next
+    /// call it makes, or stop where the caller itself returns. `None` once
+    /// the walk has run past the top-level return.
+    pub fn step_over(&mut self) -> Option<CallStop> {
+        let call = match self.current()? {
+            CallStop::Entered(index) => Some(index),
+            CallStop::Returned(_) => match self.stops.get(self.position + 1) {
+                Some(CallStop::Entered(index) | CallStop::Returned(index)) => Some(*index),
+                None => None,
+            },
+        };
+        self.position = call
+            .and_then(|index| {
+                self.stops
+                    .iter()
+                    .position(|stop| *stop == CallStop::Returned(index))
+            })
+            .unwrap_or(self.stops.len());
+        self.current()
+    }
+
+    /// The calls open at `stop`, outermost first. After a return that is
+    /// the caller's stack, after a trap the stack the call trapped in.
+    pub fn stack(&self, stop: CallStop) -> Vec<StackFrame> {
+        let innermost = match stop {
+            CallStop::Entered(index) => Some(index),
+            CallStop::Returned(index) if self.calls[index].result.is_none() => Some(index),
+            CallStop::Returned(index) => self.calls[index].parent,
+        };
+        let mut stack = Vec::new();
+        let mut next = innermost;
+        while let Some(index) = next {
+            stack.push(self.calls[index].frame());
+            next = self.calls[index].parent;
+        }
+        stack.reverse();
+        stack
+    }
+
+    /// What a stop reports: the call entered with its arguments, or the
+    /// call returned from with its value or error, then the call stack,
+    /// innermost first.
+    pub fn describe(&self, stop: CallStop) -> String {
+        let mut lines = match stop {
+            CallStop::Entered(index) => {
+                let call = &self.calls[index];
+                vec![format!(
+                    "Stepped into {} in {} (depth {})\n  args: ({})",
+                    call.function,
+                    call.contract,
+                    call.depth,
+                    call.args.join(", ")
+                )]
+            }
+            CallStop::Returned(index) => {
+                let call = &self.calls[index];
+                match &call.result {
+                    Some(result) => {
+                        let mut lines = vec![format!(
+                            "Stepped over {} in {}: returned {}",
+                            call.function, call.contract, result
+                        )];
+                        if let Some(caller) = call.parent.map(|parent| &self.calls[parent]) {
+                            lines.push(format!(
+                                "  back in: {} in {}",
+                                caller.function, caller.contract
+                            ));
+                        }
+                        lines
+                    }
+                    None => vec![format!(
+                        "{} in {} trapped: {}",
+                        call.function,
+                        call.contract,
+                        call.error.as_deref().unwrap_or("no error was recorded")
+                    )],
+                }
+            }
+        };
+        let stack = self.stack(stop);
+        if !stack.is_empty() {
+            lines.push("  call stack:".to_string());
+            for (index, frame) in stack.iter().rev().enumerate() {
+                lines.push(format!(
+                    "    #{} {} in {}",
+                    index, frame.function, frame.contract
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// The message of an `error` diagnostic event, with its arguments.
+fn error_message(data: &ScVal) -> String {
+    match data {
+        ScVal::Vec(Some(items)) => items.iter().map(decode_text).collect::<Vec<_>>().join(" "),
+        other => decode_text(other),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventV0, ExtensionPoint, Hash, ScString, ScSymbol,
+    };
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn diagnostic(recorder: Option<u8>, topics: Vec<ScVal>, data: ScVal) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: recorder.map(|id| Hash([id; 32])),
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data,
+                }),
+            },
+            failed_call: false,
+        }
+    }
+
+    fn call(caller: Option<u8>, callee: u8, function: &str, args: Vec<ScVal>) -> HostEvent {
+        let data = match args.len() {
+            0 => ScVal::Void,
+            1 => args[0].clone(),
+            _ => ScVal::Vec(Some(args.try_into().unwrap())),
+        };
+        diagnostic(
+            caller,
+            vec![
+                sym("fn_call"),
+                ScVal::Bytes(vec![callee; 32].try_into().unwrap()),
+                sym(function),
+            ],
+            data,
+        )
+    }
+
+    fn ret(callee: u8, function: &str, value: ScVal) -> HostEvent {
+        diagnostic(Some(callee), vec![sym("fn_return"), sym(function)], value)
+    }
+
+    fn address(id: u8) -> String {
+        ScAddress::Contract(Hash([id; 32])).to_string()
+    }
+
+    /// A pool's `swap` calling a token's `transfer`, which calls a hook's
+    /// `on_transfer`, then the pool's own `sync`.
+    fn swap() -> Vec<HostEvent> {
+        vec![
+            call(None, 1, "swap", vec![ScVal::U32(100)]),
+            call(Some(1), 2, "transfer", vec![ScVal::U32(7), ScVal::U32(100)]),
+            call(Some(2), 3, "on_transfer", vec![]),
+            ret(3, "on_transfer", ScVal::Void),
+            ret(2, "transfer", ScVal::Bool(true)),
+            call(Some(1), 1, "sync", vec![]),
+            ret(1, "sync", ScVal::U32(1)),
+            ret(1, "swap", ScVal::U32(95)),
+        ]
+    }
+
+    #[test]
+    fn step_in_follows_calls_two_levels_deep() {
+        let mut walk = CallWalk::from_events(&swap(), None).unwrap();
+        assert_eq!(walk.current(), Some(CallStop::Entered(0)));
+
+        let stop = walk.step_in().unwrap();
+        assert_eq!(
+            walk.describe(stop),
+            format!(
+                "Stepped into transfer in {token} (depth 1)\n  args: (7, 100)\n  call stack:\n    \
+                 #0 transfer in {token}\n    #1 swap in {pool}",
+                token = address(2),
+                pool = address(1)
+            )
+        );
+        let stop = walk.step_in().unwrap();
+        let frames = walk.stack(stop);
+        let stack: Vec<&str> = frames.iter().map(|frame| frame.function.as_str()).collect();
+        assert_eq!(stack, ["swap", "transfer", "on_transfer"]);
+        assert_eq!(walk.calls()[2].depth, 2);
+        assert_eq!(walk.calls()[2].parent, Some(1));
+
+        assert_eq!(walk.step_in(), Some(CallStop::Entered(3)));
+        assert_eq!(walk.step_in(), None);
+    }
+
+    #[test]
+    fn step_over_comes_back_in_the_caller_with_the_return_value() {
+        let mut walk = CallWalk::from_events(&swap(), None).unwrap();
+        walk.step_in();
+        let stop = walk.step_over().unwrap();
+        assert_eq!(stop, CallStop::Returned(1));
+        let report = walk.describe(stop);
+        assert!(
+            report.starts_with(&format!(
+                "Stepped over transfer in {}: returned true\n  back in: swap in {}",
+                address(2),
+                address(1)
+            )),
+            "{}",
+            report
+        );
+        let stack = walk.stack(stop);
+        assert_eq!(stack.len(), 1);
+        assert_eq!(stack[0].function, "swap");
+
+        // Back in `swap`, the next step runs `sync`, then `swap` returns.
+        assert_eq!(walk.step_over(), Some(CallStop::Returned(3)));
+        assert_eq!(walk.step_over(), Some(CallStop::Returned(0)));
+        assert_eq!(walk.step_over(), None);
+    }
+
+    #[test]
+    fn traps_are_reported_with_the_stack_they_happened_in() {
+        let error = diagnostic(
+            Some(3),
+            vec![sym("error"), ScVal::U32(0)],
+            ScVal::String(ScString("hook rejected".try_into().unwrap())),
+        );
+        // `on_transfer` traps and `transfer` recovers through try_call, then
+        // `swap` fails the invocation in `sync`.
+        let events = vec![
+            call(None, 1, "swap", vec![ScVal::U32(100)]),
+            call(Some(1), 2, "transfer", vec![ScVal::U32(7), ScVal::U32(100)]),
+            call(Some(2), 3, "on_transfer", vec![]),
+            error,
+            ret(2, "transfer", ScVal::Bool(false)),
+            call(Some(1), 1, "sync", vec![]),
+        ];
+        let mut walk = CallWalk::from_events(&events, Some("HostError: reserves")).unwrap();
+        assert_eq!(walk.step_in(), Some(CallStop::Entered(1)));
+        assert_eq!(walk.step_in(), Some(CallStop::Entered(2)));
+
+        let stop = walk.step_in().unwrap();
+        assert_eq!(stop, CallStop::Returned(2));
+        assert_eq!(
+            walk.describe(stop),
+            format!(
+                "on_transfer in {hook} trapped: hook rejected\n  call stack:\n    \
+                 #0 on_transfer in {hook}\n    #1 transfer in {token}\n    #2 swap in {pool}",
+                hook = address(3),
+                token = address(2),
+                pool = address(1)
+            )
+        );
+        assert_eq!(walk.calls()[1].result.as_deref(), Some("false"));
+
+        assert_eq!(walk.step_in(), Some(CallStop::Entered(3)));
+        let stop = walk.step_over().unwrap();
+        assert_eq!(stop, CallStop::Returned(3));
+        let report = walk.describe(stop);
+        assert!(
+            report.starts_with(&format!(
+                "sync in {} trapped: HostError: reserves",
+                address(1)
+            )),
+            "{}",
+            report
+        );
+        assert_eq!(walk.stack(stop).len(), 2);
+        assert_eq!(walk.step_over(), Some(CallStop::Returned(0)));
+        assert_eq!(walk.step_over(), None);
+    }
+}
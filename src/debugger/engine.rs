@@ -1,15 +1,21 @@
-use crate::debugger::breakpoint::BreakpointManager;
+use crate::debugger::breakpoint::{
+    BreakpointManager, BreakpointSpec, EventBreakpoints, EventHit, WriteBreakpoints,
+};
+use crate::debugger::call_walk::CallWalk;
+use crate::debugger::expression::ExpressionEvaluator;
+use crate::debugger::host_fn::{HostFnCall, HostFnWatch};
 use crate::debugger::instruction_pointer::StepMode;
 use crate::debugger::source_map::{SourceLocation, SourceMap};
 use crate::debugger::state::DebugState;
 use crate::debugger::stepper::Stepper;
+use crate::inspector::storage_history::{StackFrame, StorageMutation};
 use crate::plugin::{EventContext, ExecutionEvent};
-use crate::runtime::executor::ContractExecutor;
+use crate::runtime::executor::{ContractExecutor, ExecutionOutcome};
 use crate::runtime::instruction::Instruction;
 use crate::runtime::instrumentation::Instrumenter;
 use crate::Result;
 use std::sync::{Arc, Mutex};
-use tracing::info;
+use tracing::{info, warn};
 
 pub struct StepOverResult {
     pub paused: bool,
@@ -20,6 +26,18 @@ pub struct StepOverResult {
 pub struct DebuggerEngine {
     executor: ContractExecutor,
     breakpoints: BreakpointManager,
+    write_breakpoints: Option<WriteBreakpoints>,
+    /// The writes of watched keys the last execution made.
+    write_hits: Vec<StorageMutation>,
+    event_breakpoints: Option<EventBreakpoints>,
+    /// The watched events the last execution emitted.
+    event_hits: Vec<EventHit>,
+    watched_host_fns: Option<HostFnWatch>,
+    /// The watched host calls the last execution made.
+    host_fn_calls: Vec<HostFnCall>,
+    /// Whether the next execution keeps its calls for stepping.
+    walk_next_calls: bool,
+    call_walk: Option<CallWalk>,
     state: Arc<Mutex<DebugState>>,
     stepper: Stepper,
     instrumenter: Instrumenter,
@@ -41,13 +59,26 @@ impl DebuggerEngine {
         let mut breakpoints = BreakpointManager::new();
 
         for bp in initial_breakpoints {
-            breakpoints.add_simple(&bp);
-            info!("Breakpoint set at function: {}", bp);
+            match BreakpointSpec::parse(&bp) {
+                Ok(spec) => {
+                    info!("Breakpoint set at function: {}", spec.function);
+                    breakpoints.add_spec(spec);
+                }
+                Err(e) => warn!("Skipping breakpoint: {}", e),
+            }
         }
 
         Self {
             executor,
             breakpoints,
+            write_breakpoints: None,
+            write_hits: Vec::new(),
+            event_breakpoints: None,
+            event_hits: Vec::new(),
+            watched_host_fns: None,
+            host_fn_calls: Vec::new(),
+            walk_next_calls: false,
+            call_walk: None,
             state: Arc::new(Mutex::new(DebugState::new())),
             stepper: Stepper::new(),
             instrumenter: Instrumenter::new(),
@@ -57,6 +88,88 @@ impl DebuggerEngine {
         }
     }
 
+    /// Register `observer` on the executor, see [`crate::runtime::observer`].
+    pub fn with_observer(
+        mut self,
+        observer: impl crate::runtime::observer::InvocationObserver + 'static,
+    ) -> Self {
+        self.executor.add_observer(Box::new(observer));
+        self
+    }
+
+    /// Stop after any execution that writes a key `breakpoints` watch,
+    /// which needs the executor to track writes.
+    pub fn set_write_breakpoints(&mut self, breakpoints: WriteBreakpoints) {
+        self.executor.track_writes();
+        self.write_breakpoints = Some(breakpoints);
+    }
+
+    pub fn write_breakpoints(&self) -> Option<&WriteBreakpoints> {
+        self.write_breakpoints.as_ref()
+    }
+
+    /// The writes of watched keys the last execution made, in order.
+    pub fn write_hits(&self) -> &[StorageMutation] {
+        &self.write_hits
+    }
+
+    /// Stop after any execution that emits an event `breakpoints` match.
+    pub fn set_event_breakpoints(&mut self, breakpoints: EventBreakpoints) {
+        self.event_breakpoints = Some(breakpoints);
+    }
+
+    pub fn event_breakpoints(&self) -> Option<&EventBreakpoints> {
+        self.event_breakpoints.as_ref()
+    }
+
+    /// The watched events the last execution emitted, in order.
+    pub fn event_hits(&self) -> &[EventHit] {
+        &self.event_hits
+    }
+
+    /// Collect the calls each execution makes to the host functions
+    /// `watched` names. They are read back once it returns and do not pause
+    /// it. The contract must have been rewritten with
+    /// [`HostFnWatch::instrument`] for the calls to show up.
+    pub fn set_watched_host_fns(&mut self, watched: HostFnWatch) {
+        self.watched_host_fns = Some(watched);
+    }
+
+    pub fn watched_host_fns(&self) -> Option<&HostFnWatch> {
+        self.watched_host_fns.as_ref()
+    }
+
+    /// The watched host calls the last execution made, in order.
+    pub fn host_fn_calls(&self) -> &[HostFnCall] {
+        &self.host_fn_calls
+    }
+
+    /// Keep the calls the next execution makes, to step through them with
+    /// [`Self::step_into_call`] and [`Self::step_over_call`] once it
+    /// returns.
+    pub fn walk_next_calls(&mut self) {
+        self.walk_next_calls = true;
+    }
+
+    /// The calls of the last execution being stepped through, if any.
+    pub fn call_walk(&self) -> Option<&CallWalk> {
+        self.call_walk.as_ref()
+    }
+
+    /// Pause at the entry of the next call, or where a call trapped, and
+    /// return what the stop reports. `None` once no call is left, which
+    /// ends the walk.
+    pub fn step_into_call(&mut self) -> Option<String> {
+        self.step_call(true)
+    }
+
+    /// Run the current call to its return and pause back in its caller,
+    /// returning what the stop reports. `None` once the top-level call has
+    /// returned, which ends the walk.
+    pub fn step_over_call(&mut self) -> Option<String> {
+        self.step_call(false)
+    }
+
     /// Best-effort DWARF source map loading.
     ///
     /// Missing or malformed debug information does not fail execution; it simply leaves the
@@ -134,8 +247,18 @@ impl DebuggerEngine {
     }
 
     /// Execute a contract function with debugging.
-    #[tracing::instrument(skip(self), fields(function = function))]
     pub fn execute(&mut self, function: &str, args: Option<&str>) -> Result<String> {
+        self.execute_detailed(function, args)
+            .map(|outcome| outcome.display)
+    }
+
+    /// Like [`Self::execute`], returning everything the call produced.
+    #[tracing::instrument(skip(self), fields(function = function))]
+    pub fn execute_detailed(
+        &mut self,
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<ExecutionOutcome> {
         self.execute_internal(function, args, true)
     }
 
@@ -144,6 +267,18 @@ impl DebuggerEngine {
         function: &str,
         args: Option<&str>,
     ) -> Result<String> {
+        self.execute_internal(function, args, false)
+            .map(|outcome| outcome.display)
+    }
+
+    /// Like [`Self::execute_without_breakpoints`], returning everything the
+    /// call produced. Write and event breakpoints still fire, and watched
+    /// host function calls are still collected.
+    pub fn execute_detailed_without_breakpoints(
+        &mut self,
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<ExecutionOutcome> {
         self.execute_internal(function, args, false)
     }
 
@@ -152,7 +287,7 @@ impl DebuggerEngine {
         function: &str,
         args: Option<&str>,
         check_breakpoints: bool,
-    ) -> Result<String> {
+    ) -> Result<ExecutionOutcome> {
         info!("Executing function: {}", function);
         self.paused = false;
 
@@ -177,29 +312,46 @@ impl DebuggerEngine {
             &mut plugin_ctx,
         );
 
-        let (step_count, current_args) = self
-            .state
-            .lock()
-            .map(|s| (s.step_count(), s.current_args().map(String::from)))
-            .unwrap_or((0, None));
-
-        if check_breakpoints {
-            if let Some(bp) = self.breakpoints().get_breakpoint(function) {
-                let condition = bp.condition.clone();
-                let _ = step_count;
-                let _ = current_args;
-                self.pause_at_function(function, condition);
-            }
+        if check_breakpoints && self.breakpoint_hit(function, args) {
+            let condition = self
+                .breakpoints
+                .get_breakpoint(function)
+                .and_then(|bp| bp.condition.clone());
+            self.pause_at_function(function, condition);
         }
 
+        let writes_before = self
+            .executor
+            .storage_history()
+            .map_or(0, |history| history.mutations().len());
+        // Reading the host events copies them, so only count them when an
+        // event breakpoint or a watched host function needs it.
+        let events_before = if self.event_breakpoints.is_some()
+            || self.watched_host_fns.is_some()
+            || self.walk_next_calls
+        {
+            crate::inspector::event_export::events_before_call(self.executor.host())
+        } else {
+            0
+        };
         let start_time = std::time::Instant::now();
-        let result = self.executor.execute(function, args);
+        let result = self.executor.execute_detailed(function, args);
         let duration = start_time.elapsed();
 
         self.update_call_stack(duration)?;
+        // Write and event breakpoints fire whether or not function
+        // breakpoints are checked: the call is already past its entry when
+        // they do.
+        self.check_write_breakpoints(writes_before);
+        self.check_event_breakpoints(events_before);
+        self.collect_host_fn_calls(events_before);
+        if std::mem::take(&mut self.walk_next_calls) {
+            let error = result.as_ref().err().map(ToString::to_string);
+            self.call_walk = self.walk_calls(events_before, error.as_deref());
+        }
 
         let event_result = match &result {
-            Ok(output) => Ok(output.clone()),
+            Ok(outcome) => Ok(outcome.display.clone()),
             Err(e) => Err(e.to_string()),
         };
         crate::plugin::registry::dispatch_global_event(
@@ -225,6 +377,65 @@ impl DebuggerEngine {
         result
     }
 
+    /// The scope breakpoint conditions on a call to `function` are evaluated
+    /// in: its arguments by parameter name and as `args[N]`, typed by the
+    /// contract spec and with fields such as `order.amount`, and `now()` as
+    /// the current ledger timestamp.
+    pub fn condition_evaluator(&self, function: &str, args: Option<&str>) -> ExpressionEvaluator {
+        let mut evaluator = ExpressionEvaluator::new(self.executor.ledger_timestamp());
+        let params = self
+            .executor
+            .function_signature(function)
+            .map(|signature| signature.params)
+            .unwrap_or_default();
+        let values: Vec<serde_json::Value> = args
+            .and_then(|args| serde_json::from_str(args).ok())
+            .unwrap_or_default();
+        let args: Vec<(serde_json::Value, String)> = values
+            .into_iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let type_name = params.get(index).map_or("", |param| &param.type_name);
+                (value, type_name.to_string())
+            })
+            .collect();
+        for (param, (value, type_name)) in params.iter().zip(&args) {
+            evaluator.set_json(&param.name, value.clone(), type_name);
+        }
+        evaluator.set_args(args);
+        evaluator
+    }
+
+    /// Record a hit on `function`'s breakpoint, if any, and report whether
+    /// the call should pause. Log points print their message instead. A
+    /// condition that cannot be evaluated pauses with a warning rather than
+    /// being skipped.
+    pub fn breakpoint_hit(&mut self, function: &str, args: Option<&str>) -> bool {
+        if !self.breakpoints.should_break(function) {
+            return false;
+        }
+        let evaluator = self.condition_evaluator(function, args);
+        match self.breakpoints.on_hit(function, &evaluator) {
+            Ok(Some(hit)) => {
+                for message in hit.log_messages {
+                    crate::logging::log_display(message, crate::logging::LogLevel::Info);
+                }
+                hit.should_pause
+            }
+            Ok(None) => false,
+            Err(e) => {
+                crate::logging::log_display(
+                    format!(
+                        "Breakpoint condition on {} could not be evaluated: {}",
+                        function, e
+                    ),
+                    crate::logging::LogLevel::Warn,
+                );
+                true
+            }
+        }
+    }
+
     pub fn prepare_breakpoint_stop(&mut self, function: &str, args: Option<&str>) {
         if let Ok(mut state) = self.state.lock() {
             state.set_current_function(function.to_string(), args.map(str::to_string));
@@ -420,6 +631,7 @@ impl DebuggerEngine {
     /// Continue execution until next breakpoint.
     pub fn continue_execution(&mut self) -> Result<()> {
         self.paused = false;
+        self.call_walk = None;
         if let Ok(mut state) = self.state.lock() {
             self.stepper.continue_execution(&mut state);
         }
@@ -433,6 +645,145 @@ impl DebuggerEngine {
         Ok(())
     }
 
+    /// Collect the writes of watched keys since the first `writes_before`
+    /// of the history and, if there are any, pause on the first with the
+    /// call stack of its frame.
+    fn check_write_breakpoints(&mut self, writes_before: usize) {
+        self.write_hits.clear();
+        let (Some(watch), Some(history)) = (
+            self.write_breakpoints.as_ref(),
+            self.executor.storage_history(),
+        ) else {
+            return;
+        };
+        let new = history.mutations().get(writes_before..).unwrap_or_default();
+        self.write_hits = watch.hits(new).into_iter().cloned().collect();
+        let Some(first) = self.write_hits.first() else {
+            return;
+        };
+        let mut stack = first.callers.clone();
+        stack.push(StackFrame {
+            contract: first.contract.clone(),
+            function: first.function.clone(),
+            args: first.args.clone(),
+        });
+        let condition = format!("write to {}", first.key);
+        self.pause_in_call(stack, condition, "write breakpoint");
+    }
+
+    /// Collect the watched events after the first `events_before` host
+    /// events and, if there are any, pause on the first in the call that
+    /// emitted it.
+    fn check_event_breakpoints(&mut self, events_before: usize) {
+        self.event_hits.clear();
+        let Some(watch) = self.event_breakpoints.as_ref() else {
+            return;
+        };
+        let events = crate::inspector::event_export::host_events(self.executor.host())
+            .unwrap_or_else(|e| {
+                warn!("Failed to read events for event breakpoints: {:?}", e);
+                Vec::new()
+            });
+        self.event_hits = watch.hits(events.get(events_before..).unwrap_or_default());
+        let Some(first) = self.event_hits.first() else {
+            return;
+        };
+        let stack = first.stack.clone();
+        let condition = format!("event ({})", first.decoded_topics.join(", "));
+        self.pause_in_call(stack, condition, "event breakpoint");
+    }
+
+    /// Collect the watched host calls after the first `events_before` host
+    /// events. The execution has returned by now, so they are a report of
+    /// what it called rather than a place to pause.
+    fn collect_host_fn_calls(&mut self, events_before: usize) {
+        self.host_fn_calls.clear();
+        if self.watched_host_fns.is_none() {
+            return;
+        }
+        let events = crate::inspector::event_export::host_events(self.executor.host())
+            .unwrap_or_else(|e| {
+                warn!("Failed to read events for watched host functions: {:?}", e);
+                Vec::new()
+            });
+        self.host_fn_calls = HostFnWatch::calls(events.get(events_before..).unwrap_or_default());
+    }
+
+    /// The walk of the calls after the first `events_before` host events,
+    /// at the entry of the top-level call.
+    fn walk_calls(&self, events_before: usize, error: Option<&str>) -> Option<CallWalk> {
+        let events = crate::inspector::event_export::host_events(self.executor.host())
+            .unwrap_or_else(|e| {
+                warn!("Failed to read events for stepping into calls: {:?}", e);
+                Vec::new()
+            });
+        CallWalk::from_events(events.get(events_before..).unwrap_or_default(), error)
+    }
+
+    fn step_call(&mut self, into: bool) -> Option<String> {
+        let walk = self.call_walk.as_mut()?;
+        let stop = if into {
+            walk.step_in()
+        } else {
+            walk.step_over()
+        };
+        let Some(stop) = stop else {
+            self.call_walk = None;
+            self.paused = false;
+            return None;
+        };
+        let stack = walk.stack(stop);
+        let report = walk.describe(stop);
+        let condition = report.lines().next().unwrap_or_default().to_string();
+        let reason = if into { "step-in" } else { "step-over" };
+        self.pause_in_call(stack, condition, reason);
+        Some(report)
+    }
+
+    /// Pause with `stack`, outermost call first, as the call stack and its
+    /// innermost call as the current function.
+    fn pause_in_call(&mut self, stack: Vec<StackFrame>, condition: String, reason: &str) {
+        let function = stack
+            .last()
+            .map(|frame| frame.function.clone())
+            .or_else(|| {
+                self.state
+                    .lock()
+                    .ok()
+                    .and_then(|state| state.current_function().map(str::to_string))
+            })
+            .unwrap_or_default();
+        crate::logging::log_breakpoint(&function);
+        self.paused = true;
+        if let Ok(mut state) = self.state.lock() {
+            state.set_current_function(function.clone(), None);
+            if !stack.is_empty() {
+                let call_stack = state.call_stack_mut();
+                call_stack.clear();
+                for frame in &stack {
+                    call_stack.push(frame.function.clone(), Some(frame.contract.clone()));
+                }
+            }
+        }
+
+        let mut plugin_ctx = EventContext::new();
+        plugin_ctx.stack_depth = stack.len();
+        plugin_ctx.is_paused = true;
+        crate::plugin::registry::dispatch_global_event(
+            &ExecutionEvent::BreakpointHit {
+                function,
+                condition: Some(condition),
+            },
+            &mut plugin_ctx,
+        );
+        crate::plugin::registry::dispatch_global_event(
+            &ExecutionEvent::ExecutionPaused {
+                reason: reason.to_string(),
+            },
+            &mut plugin_ctx,
+        );
+    }
+
     fn pause_at_function(&mut self, function: &str, condition: Option<String>) {
         crate::logging::log_breakpoint(function);
         self.paused = true;
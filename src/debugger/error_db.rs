@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorExplanation {
@@ -10,9 +11,38 @@ pub struct ErrorExplanation {
     pub suggested_fix: String,
 }
 
+/// A code a contract returned as `Error(Contract, #code)`, named after the
+/// error enum case its spec declares for it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractErrorCode {
+    pub code: u32,
+    /// Case name from the spec, if any declares the code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Linked contract whose spec named the code, when it was not the
+    /// contract under test.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+}
+
+impl fmt::Display for ContractErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.name, &self.contract) {
+            (Some(name), Some(contract)) => {
+                write!(f, "{} (#{}, from {})", name, self.code, contract)
+            }
+            (Some(name), None) => write!(f, "{} (#{})", name, self.code),
+            (None, _) => write!(f, "#{}", self.code),
+        }
+    }
+}
+
 pub struct ErrorDatabase {
     standard_errors: HashMap<u32, ErrorExplanation>,
     custom_errors: HashMap<u32, ErrorExplanation>,
+    /// Error enum cases of other registered contracts, by `--link` name or
+    /// contract id.
+    linked_errors: BTreeMap<String, HashMap<u32, String>>,
 }
 
 impl ErrorDatabase {
@@ -20,6 +50,7 @@ impl ErrorDatabase {
         let mut db = Self {
             standard_errors: HashMap::new(),
             custom_errors: HashMap::new(),
+            linked_errors: BTreeMap::new(),
         };
         db.init_standard_errors();
         db
@@ -139,6 +170,47 @@ impl ErrorDatabase {
         Ok(())
     }
 
+    /// Also name codes after the error enums of `contract`, a contract the one
+    /// under test can call, so errors it propagates are named too.
+    pub fn load_linked_errors_from_wasm(
+        &mut self,
+        contract: &str,
+        wasm_bytes: &[u8],
+    ) -> Result<(), String> {
+        let custom_errors = crate::utils::wasm::parse_custom_errors(wasm_bytes)
+            .map_err(|e| format!("Failed to parse custom errors from WASM: {:?}", e))?;
+        self.linked_errors.insert(
+            contract.to_string(),
+            custom_errors
+                .into_iter()
+                .map(|err| (err.code, err.name))
+                .collect(),
+        );
+        Ok(())
+    }
+
+    /// Name a contract error code. The spec of the contract under test wins,
+    /// then linked contracts in name order. Host error codes are never used:
+    /// they number a different enum.
+    pub fn contract_error(&self, code: u32) -> ContractErrorCode {
+        if let Some(err) = self.custom_errors.get(&code) {
+            return ContractErrorCode {
+                code,
+                name: Some(err.name.clone()),
+                contract: None,
+            };
+        }
+        let linked = self
+            .linked_errors
+            .iter()
+            .find_map(|(contract, errors)| Some((contract, errors.get(&code)?)));
+        ContractErrorCode {
+            code,
+            name: linked.map(|(_, name)| name.clone()),
+            contract: linked.map(|(contract, _)| contract.clone()),
+        }
+    }
+
     pub fn display_error(&self, code: u32) {
         if let Some(explanation) = self.lookup(code) {
             crate::logging::log_display(
@@ -222,4 +294,28 @@ mod tests {
         let err = db.lookup(1001).expect("Should find custom error");
         assert_eq!(err.name, "MyCustomError");
     }
+
+    #[test]
+    fn test_contract_error_names() {
+        let mut db = ErrorDatabase::new();
+        db.add_custom_error(ErrorExplanation {
+            code: 4,
+            name: "InsufficientBalance".to_string(),
+            description: String::new(),
+            common_cause: String::new(),
+            suggested_fix: String::new(),
+        });
+        db.linked_errors.insert(
+            "token".to_string(),
+            HashMap::from([(4, "Frozen".to_string()), (9, "NotAllowed".to_string())]),
+        );
+
+        assert_eq!(db.contract_error(4).to_string(), "InsufficientBalance (#4)");
+        assert_eq!(
+            db.contract_error(9).to_string(),
+            "NotAllowed (#9, from token)"
+        );
+        // Code 6 is the host's AuthError, which says nothing about the contract.
+        assert_eq!(db.contract_error(6).to_string(), "#6");
+    }
 }
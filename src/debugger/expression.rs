@@ -0,0 +1,850 @@
+//! Breakpoint condition expressions.
+//!
+//! A condition such as `unlock_time < now() + 1h && amount > 0` is evaluated
+//! against the arguments of the call being made. Values are typed so that
+//! time arithmetic stays meaningful: a timepoint plus a duration is a
+//! timepoint, two timepoints subtract to a duration, and adding two
+//! timepoints is an error. Plain integers mix with either and count seconds.
+//!
+//! Supported syntax: integers, duration literals (`30s`, `15m`, `2h`, `7d`,
+//! `1w`), `true`/`false`, double-quoted strings, argument names, `args[N]`
+//! (the Nth argument, from 0), `now()` (the ledger timestamp), `+ - * / %`,
+//! comparisons, `&& || !` and parentheses. Strings, such as addresses and
+//! symbols, only compare with `==` and `!=`. Callers can register functions
+//! taking one name, bare or quoted, such as `writer(admin)`.
+//!
+//! Names are [`JsonPath`]s, as `--extract` takes them, so fields of
+//! structured values read the same way: `args[0].amount`,
+//! `result.balances["alice"]`.
+
+use crate::debugger::breakpoint::ConditionEvaluator;
+use crate::utils::json_path::{resolve_segments, JsonPath, Segment};
+use crate::utils::time::{format_timepoint, humanize_duration, humanize_time};
+use crate::{DebuggerError, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// A value an expression evaluates to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprValue {
+    Int(i128),
+    Bool(bool),
+    /// Seconds since the Unix epoch.
+    Timepoint(i128),
+    /// Seconds.
+    Duration(i128),
+    /// An address, symbol or string, as the JSON argument gives it.
+    Str(String),
+}
+
+impl ExprValue {
+    /// The value of a JSON call argument whose spec type is `type_name`.
+    ///
+    /// Accepts numbers, decimal strings, other strings (addresses, symbols)
+    /// and `{"value": N}` objects (typed arguments and the tagged JSON
+    /// output). `None` for vectors, maps, structs and the like.
+    pub fn from_json(value: &serde_json::Value, type_name: &str) -> Option<Self> {
+        let n = match value {
+            serde_json::Value::Bool(b) => return Some(Self::Bool(*b)),
+            serde_json::Value::Number(n) => n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))?,
+            serde_json::Value::String(s) if is_text_type(type_name) => {
+                return Some(Self::Str(s.clone()))
+            }
+            serde_json::Value::String(s) => match s.parse() {
+                Ok(n) => n,
+                Err(_) => return Some(Self::Str(s.clone())),
+            },
+            // A typed argument or tagged value; structs are not values.
+            serde_json::Value::Object(map)
+                if map
+                    .keys()
+                    .all(|key| matches!(key.as_str(), "type" | "$type" | "value")) =>
+            {
+                let type_name = map
+                    .get("$type")
+                    .or_else(|| map.get("type"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or(type_name);
+                return Self::from_json(map.get("value")?, type_name);
+            }
+            _ => return None,
+        };
+        Some(match type_name.to_ascii_lowercase().as_str() {
+            "timepoint" => Self::Timepoint(n),
+            "duration" => Self::Duration(n),
+            _ => Self::Int(n),
+        })
+    }
+
+    /// `integer`, `boolean`, `timepoint`, `duration` or `string`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Int(_) => "integer",
+            Self::Bool(_) => "boolean",
+            Self::Timepoint(_) => "timepoint",
+            Self::Duration(_) => "duration",
+            Self::Str(_) => "string",
+        }
+    }
+}
+
+/// Spec types whose JSON strings are text even when they look numeric.
+fn is_text_type(type_name: &str) -> bool {
+    matches!(
+        type_name.to_ascii_lowercase().as_str(),
+        "address" | "string" | "symbol" | "bytes"
+    ) || type_name.to_ascii_lowercase().starts_with("bytesn")
+}
+
+impl fmt::Display for ExprValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Int(n) => write!(f, "{}", n),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Str(ref s) => f.write_str(s),
+            Self::Timepoint(t) if humanize_time() => match u64::try_from(t) {
+                Ok(t) => f.write_str(&format_timepoint(t)),
+                Err(_) => write!(f, "{}", t),
+            },
+            Self::Duration(d) if humanize_time() => match u64::try_from(d) {
+                Ok(d) => f.write_str(&humanize_duration(d)),
+                Err(_) => write!(f, "{}s", d),
+            },
+            Self::Timepoint(n) | Self::Duration(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(i128),
+    Duration(i128),
+    Str(String),
+    Path(JsonPath),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+const OPERATORS: &[&str] = &[
+    "&&", "||", "==", "!=", "<=", ">=", "<", ">", "+", "-", "*", "/", "%", "!",
+];
+
+fn error(message: String) -> miette::Report {
+    DebuggerError::BreakpointError(message).into()
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        if c.is_ascii_digit() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let n: i128 = rest[..digits]
+                .parse()
+                .map_err(|_| error(format!("Number too large: {}", &rest[..digits])))?;
+            rest = &rest[digits..];
+            let suffix = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            let scale = match &rest[..suffix] {
+                "" => None,
+                "s" => Some(1),
+                "m" => Some(60),
+                "h" => Some(3_600),
+                "d" => Some(86_400),
+                "w" => Some(604_800),
+                unit => {
+                    return Err(error(format!(
+                        "Unknown duration unit '{}' (expected s, m, h, d or w)",
+                        unit
+                    )))
+                }
+            };
+            tokens.push(match scale {
+                Some(scale) => Token::Duration(
+                    n.checked_mul(scale)
+                        .ok_or_else(|| error(format!("Duration too large: {}", n)))?,
+                ),
+                None => Token::Number(n),
+            });
+            rest = &rest[suffix..];
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let (path, end) = JsonPath::parse_prefix(rest).map_err(|reason| {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                error(format!("Invalid path '{}': {}", &rest[..end], reason))
+            })?;
+            tokens.push(Token::Path(path));
+            rest = &rest[end..];
+        } else if c == '"' {
+            let end = rest[1..]
+                .find('"')
+                .ok_or_else(|| error(format!("Unclosed string {}", rest)))?;
+            tokens.push(Token::Str(rest[1..=end].to_string()));
+            rest = &rest[end + 2..];
+        } else if c == '(' {
+            tokens.push(Token::LParen);
+            rest = &rest[1..];
+        } else if c == ')' {
+            tokens.push(Token::RParen);
+            rest = &rest[1..];
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            rest = &rest[op.len()..];
+        } else {
+            return Err(error(format!("Unexpected character '{}' in expression", c)));
+        }
+        rest = rest.trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Literal(ExprValue),
+    /// A name, possibly followed by fields and indices.
+    Path(JsonPath),
+    /// `name()` or `name(arg)`.
+    Call(String, Option<String>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(&'static str, Box<Expr>, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    /// Binary operators by binding strength, loosest first.
+    const LEVELS: &'static [&'static [&'static str]] = &[
+        &["||"],
+        &["&&"],
+        &["==", "!=", "<=", ">=", "<", ">"],
+        &["+", "-"],
+        &["*", "/", "%"],
+    ];
+
+    fn binary(&mut self, level: usize) -> Result<Expr> {
+        let Some(ops) = Self::LEVELS.get(level) else {
+            return self.unary();
+        };
+        let mut lhs = self.binary(level + 1)?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let op = *op;
+            if !ops.contains(&op) {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(level + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        match self.advance() {
+            Some(Token::Op("!")) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Op("-")) => Ok(Expr::Neg(Box::new(self.unary()?))),
+            Some(Token::Number(n)) => Ok(Expr::Literal(ExprValue::Int(n))),
+            Some(Token::Duration(n)) => Ok(Expr::Literal(ExprValue::Duration(n))),
+            Some(Token::Str(s)) => Ok(Expr::Literal(ExprValue::Str(s))),
+            Some(Token::Path(path)) => {
+                let Some(name) = path.as_name() else {
+                    return Ok(Expr::Path(path));
+                };
+                if self.peek() == Some(&Token::LParen) {
+                    self.pos += 1;
+                    let arg = match self.advance() {
+                        Some(Token::RParen) => return Ok(Expr::Call(name.to_string(), None)),
+                        Some(Token::Path(arg)) if arg.as_name().is_some() => arg.to_string(),
+                        Some(Token::Str(arg)) => arg,
+                        _ => {
+                            return Err(error(format!("Expected a name or ')' after '{}('", name)))
+                        }
+                    };
+                    if self.advance() != Some(Token::RParen) {
+                        return Err(error(format!("Expected ')' after '{}({}'", name, arg)));
+                    }
+                    return Ok(Expr::Call(name.to_string(), Some(arg)));
+                }
+                Ok(match name {
+                    "true" => Expr::Literal(ExprValue::Bool(true)),
+                    "false" => Expr::Literal(ExprValue::Bool(false)),
+                    _ => Expr::Path(path),
+                })
+            }
+            Some(Token::LParen) => {
+                let inner = self.binary(0)?;
+                match self.advance() {
+                    Some(Token::RParen) => Ok(inner),
+                    _ => Err(error("Unclosed '(' in expression".to_string())),
+                }
+            }
+            Some(token) => Err(error(format!("Unexpected {:?} in expression", token))),
+            None => Err(error("Unexpected end of expression".to_string())),
+        }
+    }
+}
+
+fn parse(source: &str) -> Result<Expr> {
+    let mut parser = Parser {
+        tokens: tokenize(source)?,
+        pos: 0,
+    };
+    let expr = parser.binary(0)?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(error(format!(
+            "Unexpected {:?} after the end of the expression",
+            token
+        ))),
+    }
+}
+
+/// Check that `source` is a well-formed expression without evaluating it.
+pub fn validate(source: &str) -> Result<()> {
+    parse(source).map(|_| ())
+}
+
+/// The names `source` passes to `function`, in order, e.g. `["admin"]` for
+/// `writer(admin) == initialize` and `writer`. Empty if it does not parse.
+pub fn call_arguments(source: &str, function: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut stack: Vec<Expr> = parse(source).into_iter().collect();
+    while let Some(expr) = stack.pop() {
+        match expr {
+            Expr::Call(name, Some(arg)) if name == function => names.push(arg),
+            Expr::Not(inner) | Expr::Neg(inner) => stack.push(*inner),
+            Expr::Binary(_, lhs, rhs) => {
+                stack.push(*rhs);
+                stack.push(*lhs);
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+fn arithmetic(op: &str, lhs: ExprValue, rhs: ExprValue) -> Result<ExprValue> {
+    use ExprValue::*;
+    let overflow = || error(format!("Arithmetic overflow in {} {} {}", lhs, op, rhs));
+    let apply = |a: i128, b: i128| -> Result<i128> {
+        match op {
+            "+" => a.checked_add(b),
+            "-" => a.checked_sub(b),
+            "*" => a.checked_mul(b),
+            "/" if b == 0 => return Err(error("Division by zero".to_string())),
+            "/" => a.checked_div(b),
+            "%" if b == 0 => return Err(error("Division by zero".to_string())),
+            _ => a.checked_rem(b),
+        }
+        .ok_or_else(overflow)
+    };
+    let additive = matches!(op, "+" | "-");
+    Ok(match (lhs.clone(), rhs.clone()) {
+        (Int(a), Int(b)) => Int(apply(a, b)?),
+        (Timepoint(a), Duration(b) | Int(b)) if additive => Timepoint(apply(a, b)?),
+        (Duration(a) | Int(a), Timepoint(b)) if op == "+" => Timepoint(apply(a, b)?),
+        (Timepoint(a), Timepoint(b)) if op == "-" => Duration(apply(a, b)?),
+        (Duration(a), Duration(b) | Int(b)) if additive => Duration(apply(a, b)?),
+        (Int(a), Duration(b)) if additive => Duration(apply(a, b)?),
+        (Duration(a), Int(b)) if op != "%" => Duration(apply(a, b)?),
+        (Int(a), Duration(b)) if op == "*" => Duration(apply(a, b)?),
+        (Duration(a), Duration(b)) if op == "/" => Int(apply(a, b)?),
+        _ => {
+            return Err(error(format!(
+                "Cannot apply '{}' to a {} and a {}",
+                op,
+                lhs.kind(),
+                rhs.kind()
+            )))
+        }
+    })
+}
+
+fn compare(op: &str, lhs: ExprValue, rhs: ExprValue) -> Result<bool> {
+    use ExprValue::*;
+    let (a, b) = match (&lhs, &rhs) {
+        (Bool(a), Bool(b)) if matches!(op, "==" | "!=") => (i128::from(*a), i128::from(*b)),
+        (Str(a), Str(b)) if matches!(op, "==" | "!=") => {
+            return Ok((a == b) == (op == "=="));
+        }
+        (Int(a), Int(b))
+        | (Timepoint(a), Timepoint(b) | Int(b))
+        | (Int(a), Timepoint(b))
+        | (Duration(a), Duration(b) | Int(b))
+        | (Int(a), Duration(b)) => (*a, *b),
+        _ => {
+            return Err(error(format!(
+                "Cannot compare a {} with a {} using '{}'",
+                lhs.kind(),
+                rhs.kind(),
+                op
+            )))
+        }
+    };
+    Ok(match op {
+        "==" => a == b,
+        "!=" => a != b,
+        "<" => a < b,
+        "<=" => a <= b,
+        ">" => a > b,
+        _ => a >= b,
+    })
+}
+
+/// A registered function, called with the name it is given.
+type Function = Rc<dyn Fn(&str) -> Result<ExprValue>>;
+
+/// Registered functions by name.
+#[derive(Clone, Default)]
+struct Functions(HashMap<String, Function>);
+
+impl fmt::Debug for Functions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+/// Evaluates expressions over named values, with `now()` fixed at creation.
+#[derive(Debug, Clone, Default)]
+pub struct ExpressionEvaluator {
+    now: u64,
+    vars: HashMap<String, ExprValue>,
+    /// Values whose fields paths read, as JSON with their spec type.
+    documents: HashMap<String, (serde_json::Value, String)>,
+    /// The call's arguments in order, for `args[N]`, as JSON with their
+    /// spec type.
+    args: Vec<(serde_json::Value, String)>,
+    functions: Functions,
+}
+
+impl ExpressionEvaluator {
+    /// An evaluator whose `now()` is `now` (seconds since the Unix epoch).
+    pub fn new(now: u64) -> Self {
+        Self {
+            now,
+            ..Self::default()
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: ExprValue) {
+        self.vars.insert(name.to_string(), value);
+    }
+
+    /// Bind `name` to a JSON value whose spec type is `type_name`. Paths
+    /// read its fields, and the value itself reads as in
+    /// [`ExprValue::from_json`].
+    pub fn set_json(&mut self, name: &str, value: serde_json::Value, type_name: &str) {
+        self.documents
+            .insert(name.to_string(), (value, type_name.to_string()));
+    }
+
+    /// Set the positional arguments `args[N]` reads, as JSON with their
+    /// spec types.
+    pub fn set_args(&mut self, args: Vec<(serde_json::Value, String)>) {
+        self.args = args;
+    }
+
+    /// Make `name(arg)` call `function` with `arg`.
+    pub fn register(&mut self, name: &str, function: impl Fn(&str) -> Result<ExprValue> + 'static) {
+        self.functions.0.insert(name.to_string(), Rc::new(function));
+    }
+
+    /// Evaluate `source` to a value.
+    pub fn eval(&self, source: &str) -> Result<ExprValue> {
+        self.eval_expr(&parse(source)?)
+    }
+
+    fn eval_expr(&self, expr: &Expr) -> Result<ExprValue> {
+        match expr {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Path(path) => self.eval_path(path),
+            Expr::Call(name, None) if name == "now" => {
+                Ok(ExprValue::Timepoint(i128::from(self.now)))
+            }
+            Expr::Call(name, arg) => match (self.functions.0.get(name), arg) {
+                (Some(function), Some(arg)) => function(arg),
+                (Some(_), None) => Err(error(format!(
+                    "'{}()' needs a name, e.g. '{}(admin)'",
+                    name, name
+                ))),
+                (None, _) if name == "now" => Err(error("'now()' takes no name".to_string())),
+                (None, _) => {
+                    let mut known: Vec<&str> =
+                        self.functions.0.keys().map(String::as_str).collect();
+                    known.push("now");
+                    known.sort_unstable();
+                    Err(error(format!(
+                        "Unknown function '{}()' (available: {})",
+                        name,
+                        known.join(", ")
+                    )))
+                }
+            },
+            Expr::Not(inner) => match self.eval_expr(inner)? {
+                ExprValue::Bool(b) => Ok(ExprValue::Bool(!b)),
+                other => Err(error(format!("Cannot apply '!' to a {}", other.kind()))),
+            },
+            Expr::Neg(inner) => match self.eval_expr(inner)? {
+                ExprValue::Int(n) => Ok(ExprValue::Int(-n)),
+                ExprValue::Duration(n) => Ok(ExprValue::Duration(-n)),
+                other => Err(error(format!("Cannot negate a {}", other.kind()))),
+            },
+            Expr::Binary(op @ ("&&" | "||"), lhs, rhs) => {
+                let lhs = self.eval_bool(lhs, op)?;
+                // Short-circuit, so `x != 0 && total / x > 2` is safe.
+                if (*op == "&&" && !lhs) || (*op == "||" && lhs) {
+                    return Ok(ExprValue::Bool(lhs));
+                }
+                Ok(ExprValue::Bool(self.eval_bool(rhs, op)?))
+            }
+            Expr::Binary(op @ ("==" | "!=" | "<" | "<=" | ">" | ">="), lhs, rhs) => Ok(
+                ExprValue::Bool(compare(op, self.eval_expr(lhs)?, self.eval_expr(rhs)?)?),
+            ),
+            Expr::Binary(op, lhs, rhs) => {
+                arithmetic(op, self.eval_expr(lhs)?, self.eval_expr(rhs)?)
+            }
+        }
+    }
+
+    /// The value at `path`: a name set with [`Self::set`], or the field of
+    /// an argument or [JSON value](Self::set_json) the path leads to.
+    fn eval_path(&self, path: &JsonPath) -> Result<ExprValue> {
+        let (name, rest) = match path.segments() {
+            [Segment::Key(name), rest @ ..] => (name.as_str(), rest),
+            _ => return Err(error(format!("Invalid path '{}'", path))),
+        };
+        let (root, type_name, fields) = match (name, rest) {
+            ("args", [Segment::Index(index), fields @ ..]) => match self.args.get(*index) {
+                Some((value, type_name)) => (value, type_name.as_str(), fields),
+                None => {
+                    return Err(error(format!(
+                        "args[{}] is out of range: the call has {} argument(s)",
+                        index,
+                        self.args.len()
+                    )))
+                }
+            },
+            _ => match (self.vars.get(name), self.documents.get(name)) {
+                (Some(value), _) if rest.is_empty() => return Ok(value.clone()),
+                (Some(value), _) => {
+                    return Err(error(format!(
+                        "'{}' has no fields: its value is the {} {}",
+                        name,
+                        value.kind(),
+                        value
+                    )))
+                }
+                (None, Some((value, type_name))) => (value, type_name.as_str(), rest),
+                (None, None) => {
+                    let mut known: Vec<&str> = self
+                        .vars
+                        .keys()
+                        .chain(self.documents.keys())
+                        .map(String::as_str)
+                        .collect();
+                    known.sort_unstable();
+                    return Err(error(format!(
+                        "Unknown name '{}' (available: {})",
+                        name,
+                        if known.is_empty() {
+                            "none".to_string()
+                        } else {
+                            known.join(", ")
+                        }
+                    )));
+                }
+            },
+        };
+        let value = resolve_segments(fields, root)
+            .ok_or_else(|| error(format!("'{}' does not resolve", path)))?;
+        // Fields are read without a spec type; tagged timepoints and
+        // durations still carry theirs.
+        let type_name = if fields.is_empty() { type_name } else { "" };
+        ExprValue::from_json(value, type_name)
+            .ok_or_else(|| error(format!("{} is not a number, boolean or string", path)))
+    }
+
+    fn eval_bool(&self, expr: &Expr, op: &str) -> Result<bool> {
+        match self.eval_expr(expr)? {
+            ExprValue::Bool(b) => Ok(b),
+            other => Err(error(format!(
+                "'{}' needs true or false, found a {}",
+                op,
+                other.kind()
+            ))),
+        }
+    }
+}
+
+impl ConditionEvaluator for ExpressionEvaluator {
+    fn evaluate(&self, condition: &str) -> Result<bool> {
+        match self.eval(condition)? {
+            ExprValue::Bool(b) => Ok(b),
+            other => Err(error(format!(
+                "Condition '{}' must be true or false, found a {}",
+                condition,
+                other.kind()
+            ))),
+        }
+    }
+
+    /// Replace each `{expression}` in `template` with its value.
+    fn interpolate_log(&self, template: &str) -> Result<String> {
+        let mut out = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or_else(|| error(format!("Unclosed '{{' in log message: {}", template)))?;
+            out.push_str(&rest[..start]);
+            out.push_str(&self.eval(&rest[start + 1..end])?.to_string());
+            rest = &rest[end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_000;
+
+    /// The arguments of `claim` on a vesting contract: a grant that started
+    /// 30 days ago with a 90 day cliff.
+    fn vesting() -> ExpressionEvaluator {
+        let mut evaluator = ExpressionEvaluator::new(NOW);
+        let start = i128::from(NOW) - 30 * 86_400;
+        evaluator.set("start", ExprValue::Timepoint(start));
+        evaluator.set("cliff", ExprValue::Duration(90 * 86_400));
+        evaluator.set("unlock_time", ExprValue::Timepoint(start + 90 * 86_400));
+        evaluator.set("amount", ExprValue::Int(1_000));
+        evaluator
+    }
+
+    #[test]
+    fn vesting_conditions_use_time_arithmetic() {
+        let vesting = vesting();
+        assert!(!vesting.evaluate("unlock_time < now() + 3600").unwrap());
+        assert!(vesting.evaluate("unlock_time > now() + 59d").unwrap());
+        assert!(vesting.evaluate("unlock_time == start + cliff").unwrap());
+        assert!(vesting.evaluate("now() - start == 30d").unwrap());
+        assert!(vesting
+            .evaluate("unlock_time - now() < cliff && amount > 0")
+            .unwrap());
+        assert!(vesting
+            .evaluate("(unlock_time - start) / 1d == 90")
+            .unwrap());
+        assert!(vesting.evaluate("cliff / 3 == 30d").unwrap());
+        assert!(!vesting
+            .evaluate("!(amount >= 1000) || now() > unlock_time")
+            .unwrap());
+
+        assert_eq!(
+            vesting.eval("unlock_time - start").unwrap(),
+            ExprValue::Duration(90 * 86_400)
+        );
+        assert_eq!(
+            vesting.eval("now() + 1h").unwrap(),
+            ExprValue::Timepoint(i128::from(NOW) + 3_600)
+        );
+    }
+
+    #[test]
+    fn meaningless_time_arithmetic_is_rejected() {
+        let vesting = vesting();
+        let err = vesting.eval("unlock_time + start").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Cannot apply '+' to a timepoint and a timepoint"),
+            "{}",
+            err
+        );
+        assert!(vesting.eval("cliff * start").is_err());
+        assert!(vesting.evaluate("cliff < now()").is_err());
+        assert!(vesting.evaluate("amount + 1").is_err());
+    }
+
+    #[test]
+    fn precedence_and_literals() {
+        let evaluator = ExpressionEvaluator::new(0);
+        assert_eq!(evaluator.eval("1 + 2 * 3").unwrap(), ExprValue::Int(7));
+        assert_eq!(evaluator.eval("(1 + 2) * 3").unwrap(), ExprValue::Int(9));
+        assert_eq!(evaluator.eval("-2 + 10 % 4").unwrap(), ExprValue::Int(0));
+        assert_eq!(
+            evaluator.eval("1w - 2d + 30m").unwrap(),
+            ExprValue::Duration(5 * 86_400 + 1_800)
+        );
+        assert!(evaluator.evaluate("1 < 2 && 2 < 3 || false").unwrap());
+        // The right side is not evaluated once the left decides.
+        assert!(!evaluator.evaluate("false && 1 / 0 == 1").unwrap());
+    }
+
+    #[test]
+    fn malformed_expressions_are_reported() {
+        for bad in ["", "1 +", "(1 < 2", "3x > 1", "a $ b", "now( > 1", "1 2"] {
+            assert!(validate(bad).is_err(), "{:?} should not parse", bad);
+        }
+        let evaluator = ExpressionEvaluator::new(0);
+        let err = evaluator.evaluate("balance > 1").unwrap_err();
+        assert!(
+            err.to_string().contains("Unknown name 'balance'"),
+            "{}",
+            err
+        );
+        assert!(evaluator.evaluate("later() > 1").is_err());
+        assert!(evaluator.evaluate("1 + 1").is_err());
+    }
+
+    #[test]
+    fn arguments_are_typed_by_their_spec() {
+        let timepoint = ExprValue::from_json(&serde_json::json!(1_700_000_000u64), "Timepoint");
+        assert_eq!(timepoint, Some(ExprValue::Timepoint(1_700_000_000)));
+        let tagged = serde_json::json!({"$type": "duration", "value": 60});
+        assert_eq!(
+            ExprValue::from_json(&tagged, "u64"),
+            Some(ExprValue::Duration(60))
+        );
+        assert_eq!(
+            ExprValue::from_json(&serde_json::json!("-5"), "i128"),
+            Some(ExprValue::Int(-5))
+        );
+        assert_eq!(
+            ExprValue::from_json(&serde_json::json!("GDQP"), "Address"),
+            Some(ExprValue::Str("GDQP".to_string()))
+        );
+        assert_eq!(
+            ExprValue::from_json(&serde_json::json!("42"), "Symbol"),
+            Some(ExprValue::Str("42".to_string()))
+        );
+        assert_eq!(ExprValue::from_json(&serde_json::json!([1]), "Vec"), None);
+    }
+
+    #[test]
+    fn positional_arguments_and_strings() {
+        let mut evaluator = ExpressionEvaluator::new(NOW);
+        evaluator.set_args(vec![
+            (serde_json::json!("GALICE"), "Address".to_string()),
+            (serde_json::json!("GDQPXYZ"), "Address".to_string()),
+            (serde_json::json!(2_000_000), "i128".to_string()),
+            (serde_json::json!([1, 2]), "Vec<u32>".to_string()),
+        ]);
+        assert!(evaluator.evaluate(r#"args[1] == "GDQPXYZ""#).unwrap());
+        assert!(!evaluator.evaluate(r#"args[0] == "GDQPXYZ""#).unwrap());
+        assert!(evaluator
+            .evaluate(r#"args[0] != args[1] && args[2] > 1000000"#)
+            .unwrap());
+        assert!(evaluator
+            .evaluate(r#"args[2] < 10 || args[1] == "GDQPXYZ""#)
+            .unwrap());
+
+        let err = evaluator.evaluate("args[9] > 1").unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+        assert!(evaluator.evaluate("args[3] > 1").is_err());
+        assert!(evaluator.evaluate(r#"args[0] > "GB""#).is_err());
+        assert!(evaluator.evaluate(r#"args[2] == "2000000""#).is_err());
+        for bad in [
+            r#"args[0] == "open"#,
+            "args[x] > 1",
+            "args[1 > 1",
+            "args[-1] > 1",
+        ] {
+            assert!(validate(bad).is_err(), "{:?} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn paths_read_fields_of_structured_values() {
+        let mut evaluator = ExpressionEvaluator::new(NOW);
+        let order = serde_json::json!({
+            "amount": "1500",
+            "expires": {"$type": "timepoint", "value": NOW + 60},
+            "owners": ["GA", "GB"],
+        });
+        evaluator.set_json("order", order.clone(), "Order");
+        evaluator.set_args(vec![(order, "Order".to_string())]);
+        evaluator.set_json(
+            "result",
+            serde_json::json!({"balances": {"alice": 100}}),
+            "Receipt",
+        );
+        evaluator.set("fee", ExprValue::Int(5));
+
+        assert!(evaluator
+            .evaluate(r#"order.amount > 1000 && args[0].owners[1] == "GB""#)
+            .unwrap());
+        assert!(evaluator.evaluate("order.expires > now()").unwrap());
+        assert!(evaluator
+            .evaluate(r#"result.balances["alice"] == 100"#)
+            .unwrap());
+
+        for (bad, message) in [
+            ("order.owners == 1", "order.owners is not a number"),
+            ("order.price > 1", "'order.price' does not resolve"),
+            ("args[0].owners[5] > 1", "does not resolve"),
+            (
+                "fee.amount > 1",
+                "'fee' has no fields: its value is the integer 5",
+            ),
+            ("order > 1", "order is not a number"),
+        ] {
+            let err = evaluator.evaluate(bad).unwrap_err().to_string();
+            assert!(err.contains(message), "{}: {}", bad, err);
+        }
+        assert!(validate("order.").is_err());
+        assert!(validate(r#"result.balances["alice"] == 1"#).is_ok());
+    }
+
+    #[test]
+    fn log_messages_interpolate_expressions() {
+        let vesting = vesting();
+        assert_eq!(
+            vesting
+                .interpolate_log("claiming {amount} with {unlock_time - now()} left")
+                .unwrap(),
+            "claiming 1000 with 60d (5184000s) left"
+        );
+        assert!(vesting.interpolate_log("{amount").is_err());
+    }
+
+    #[test]
+    fn registered_functions_take_a_name() {
+        let mut evaluator = ExpressionEvaluator::new(0);
+        evaluator.register("len", |name| Ok(ExprValue::Int(name.len() as i128)));
+        assert!(evaluator
+            .evaluate(r#"len(admin) == 5 && len("a:b") == 3"#)
+            .unwrap());
+        assert!(evaluator.eval("len()").is_err());
+        assert!(evaluator.eval("now(admin)").is_err());
+        let err = evaluator.eval("size(admin)").unwrap_err().to_string();
+        assert!(err.contains("available: len, now"), "{}", err);
+
+        assert_eq!(
+            call_arguments("!(len(a) > 1) || len(b) == 2 && size(c) > 0", "len"),
+            ["a", "b"]
+        );
+    }
+}
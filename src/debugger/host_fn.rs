@@ -0,0 +1,474 @@
+//! Reports of the calls a contract made to chosen host functions
+//! (`--report-host-fn require_auth`).
+//!
+//! The host keeps its call-dispatch hook to itself, so calls are caught on
+//! the guest side instead: the contract's WASM is rewritten so every call to
+//! a watched host import first goes through a wrapper that logs the
+//! arguments with `log_from_linear_memory`, then makes the original call.
+//! The log lands among the diagnostic events in order with the `fn_call` /
+//! `fn_return` events, which gives the frame and call stack of the call.
+//! The wrapper borrows the first bytes of linear memory for the message and
+//! arguments and puts them back before the call goes on.
+//!
+//! The wrapper can only log, so nothing stops the contract at the call: the
+//! calls are read back from the diagnostic events and reported once the
+//! invocation has returned.
+//!
+//! Only the contract being debugged is rewritten, and only when a host
+//! function is watched, so other runs execute the WASM as given. A rewritten
+//! contract has a different code hash, and the extra log calls are metered
+//! like any other.
+
+use crate::inspector::storage_decode::decode_text;
+use crate::inspector::storage_history::StackFrame;
+use crate::runtime::observer::{call_args, call_target, symbol};
+use serde::Serialize;
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, ScAddress, ScVal};
+use std::collections::{BTreeSet, HashMap};
+use walrus::ir::{Call, LoadKind, MemArg, StoreKind, VisitorMut};
+use walrus::{FunctionBuilder, FunctionId, ImportKind, Module, ValType};
+
+/// Start of the message the wrappers log, followed by the host function.
+pub const HOST_FN_LOG_PREFIX: &str = "soroban-debug host fn: ";
+
+/// Host functions that can be watched, with the module and function names
+/// they are imported under, from the host interface.
+const HOST_FUNCTIONS: &[(&str, &str, &str)] = &[
+    ("require_auth_for_args", "a", "_"),
+    ("require_auth", "a", "0"),
+    ("authorize_as_curr_contract", "a", "3"),
+    ("call", "d", "_"),
+    ("try_call", "d", "0"),
+    ("put_contract_data", "l", "_"),
+    ("has_contract_data", "l", "0"),
+    ("get_contract_data", "l", "1"),
+    ("del_contract_data", "l", "2"),
+    ("create_contract", "l", "3"),
+    ("create_contract_with_constructor", "l", "e"),
+    ("update_current_contract_wasm", "l", "6"),
+    ("extend_contract_data_ttl", "l", "7"),
+    ("extend_current_contract_instance_and_code_ttl", "l", "8"),
+    ("extend_contract_instance_and_code_ttl", "l", "9"),
+    ("extend_contract_instance_ttl", "l", "c"),
+    ("extend_contract_code_ttl", "l", "d"),
+    ("contract_event", "x", "1"),
+    ("get_ledger_sequence", "x", "3"),
+    ("get_ledger_timestamp", "x", "4"),
+    ("fail_with_error", "x", "5"),
+    ("get_ledger_network_id", "x", "6"),
+    ("get_current_contract_address", "x", "7"),
+    ("get_max_live_until_ledger", "x", "8"),
+    ("prng_bytes_new", "p", "0"),
+];
+
+/// `extend_ttl` stands for every host function the SDK's `extend_ttl`
+/// methods call.
+const EXTEND_TTL: &[&str] = &[
+    "extend_contract_data_ttl",
+    "extend_current_contract_instance_and_code_ttl",
+    "extend_contract_instance_and_code_ttl",
+    "extend_contract_instance_ttl",
+    "extend_contract_code_ttl",
+];
+
+/// `log_from_linear_memory`, which the wrappers call.
+const LOG_IMPORT: (&str, &str) = ("x", "_");
+
+/// A call to a watched host function.
+#[derive(Debug, Clone, Serialize)]
+pub struct HostFnCall {
+    pub host_fn: String,
+    /// The arguments, decoded from their ScVals.
+    pub args: Vec<String>,
+    /// The contract that made the call.
+    pub contract: Option<String>,
+    /// The calls open at the time, outermost first; the last one made the
+    /// host call.
+    pub stack: Vec<StackFrame>,
+}
+
+/// Host functions whose calls are reported after each invocation.
+#[derive(Debug, Clone)]
+pub struct HostFnWatch {
+    names: BTreeSet<&'static str>,
+}
+
+impl HostFnWatch {
+    /// Watch `names`, each a host function or `extend_ttl`. An unknown name
+    /// is an error listing the supported ones.
+    pub fn new(names: &[String]) -> std::result::Result<Self, String> {
+        let mut watched = BTreeSet::new();
+        for name in names {
+            if name == "extend_ttl" {
+                watched.extend(EXTEND_TTL);
+                continue;
+            }
+            match HOST_FUNCTIONS.iter().find(|(known, _, _)| known == name) {
+                Some((known, _, _)) => {
+                    watched.insert(*known);
+                }
+                None => {
+                    return Err(format!(
+                        "Unknown host function '{}'; supported: {}",
+                        name,
+                        Self::supported().join(", ")
+                    ))
+                }
+            }
+        }
+        Ok(Self { names: watched })
+    }
+
+    /// Every name `--report-host-fn` accepts.
+    pub fn supported() -> Vec<&'static str> {
+        let mut names: Vec<&str> = HOST_FUNCTIONS.iter().map(|(name, _, _)| *name).collect();
+        names.push("extend_ttl");
+        names.sort_unstable();
+        names
+    }
+
+    /// The watched host functions.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.names.iter().copied()
+    }
+
+    /// Rewrite `wasm` so calls to the watched host functions it imports
+    /// are logged first. A contract importing none of them comes back
+    /// unchanged.
+    pub fn instrument(&self, wasm: &[u8]) -> std::result::Result<Vec<u8>, String> {
+        let mut module =
+            Module::from_buffer(wasm).map_err(|e| format!("Failed to parse WASM module: {}", e))?;
+        let watched: Vec<(&str, FunctionId)> = module
+            .imports
+            .iter()
+            .filter_map(|import| {
+                let ImportKind::Function(func) = import.kind else {
+                    return None;
+                };
+                let (name, _, _) = HOST_FUNCTIONS.iter().find(|(name, module, field)| {
+                    self.names.contains(name) && *module == import.module && *field == import.name
+                })?;
+                Some((*name, func))
+            })
+            .collect();
+        if watched.is_empty() {
+            return Ok(wasm.to_vec());
+        }
+        let memory = module
+            .memories
+            .iter()
+            .next()
+            .map(|memory| memory.id())
+            .ok_or_else(|| "the contract has no linear memory to log calls from".to_string())?;
+        let existing = module.imports.iter().find_map(|import| match import.kind {
+            ImportKind::Function(func)
+                if (import.module.as_str(), import.name.as_str()) == LOG_IMPORT =>
+            {
+                Some(func)
+            }
+            _ => None,
+        });
+        let log = match existing {
+            Some(func) => func,
+            None => {
+                let ty = module.types.add(&[ValType::I64; 4], &[ValType::I64]);
+                module.add_import_func(LOG_IMPORT.0, LOG_IMPORT.1, ty).0
+            }
+        };
+
+        let mut wrappers = HashMap::new();
+        for (name, original) in watched {
+            let wrapper = add_wrapper(&mut module, name, original, log, memory)?;
+            wrappers.insert(original, wrapper);
+        }
+        let wrapper_ids: Vec<FunctionId> = wrappers.values().copied().collect();
+        let mut redirect = Redirect {
+            wrappers: &wrappers,
+        };
+        for (id, func) in module.funcs.iter_local_mut() {
+            if !wrapper_ids.contains(&id) {
+                let entry = func.entry_block();
+                walrus::ir::dfs_pre_order_mut(&mut redirect, func, entry);
+            }
+        }
+        Ok(module.emit_wasm())
+    }
+
+    /// The watched host calls among `events`, in the order they were made,
+    /// each with the calls open at the time.
+    pub fn calls(events: &[HostEvent]) -> Vec<HostFnCall> {
+        let mut open: Vec<StackFrame> = Vec::new();
+        let mut calls = Vec::new();
+        for event in events {
+            if event.event.type_ != ContractEventType::Diagnostic {
+                continue;
+            }
+            let ContractEventBody::V0(body) = &event.event.body;
+            let topics = body.topics.as_slice();
+            match topics.first().and_then(symbol).as_deref() {
+                Some("fn_call") => {
+                    let (contract, function) = call_target(topics);
+                    open.push(StackFrame {
+                        contract,
+                        function,
+                        args: call_args(&body.data),
+                    });
+                }
+                Some("fn_return") => {
+                    open.pop();
+                }
+                Some("log") => {
+                    // The message alone, or a vector of it and the arguments.
+                    let (message, args) = match &body.data {
+                        ScVal::Vec(Some(items)) => match items.split_first() {
+                            Some((message, args)) => (message, args),
+                            None => continue,
+                        },
+                        message => (message, &[][..]),
+                    };
+                    let ScVal::String(message) = message else {
+                        continue;
+                    };
+                    let message = message.to_utf8_string_lossy();
+                    let Some(host_fn) = message.strip_prefix(HOST_FN_LOG_PREFIX) else {
+                        continue;
+                    };
+                    calls.push(HostFnCall {
+                        host_fn: host_fn.to_string(),
+                        args: args.iter().map(decode_text).collect(),
+                        contract: event
+                            .event
+                            .contract_id
+                            .as_ref()
+                            .map(|id| ScAddress::Contract(id.clone()).to_string()),
+                        stack: open.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        calls
+    }
+
+    /// The report of `call`: the host function, its arguments, and the
+    /// frame that called it with its call stack, innermost first.
+    pub fn describe(call: &HostFnCall) -> String {
+        let mut lines = vec![
+            format!(
+                "Host function called: {}({})",
+                call.host_fn,
+                call.args.join(", ")
+            ),
+            format!(
+                "  called from: {}",
+                call.stack
+                    .last()
+                    .map(|frame| format!("{} in {}", frame.function, frame.contract))
+                    .or_else(|| call.contract.clone())
+                    .unwrap_or_else(|| "<unknown>".to_string())
+            ),
+        ];
+        if !call.stack.is_empty() {
+            lines.push("  call stack:".to_string());
+            for (index, frame) in call.stack.iter().rev().enumerate() {
+                lines.push(format!(
+                    "    #{} {} in {}",
+                    index, frame.function, frame.contract
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+/// Send calls of the original imports to their wrappers.
+struct Redirect<'a> {
+    wrappers: &'a HashMap<FunctionId, FunctionId>,
+}
+
+impl VisitorMut for Redirect<'_> {
+    fn visit_call_mut(&mut self, instr: &mut Call) {
+        if let Some(wrapper) = self.wrappers.get(&instr.func) {
+            instr.func = *wrapper;
+        }
+    }
+}
+
+/// A `U32Val` holding `value`, as host functions take it.
+fn u32_val(value: u32) -> i64 {
+    ((u64::from(value) << 32) | 4) as i64
+}
+
+/// Add a function with the signature of `original` that logs its arguments
+/// under `name`, restores the memory it used and calls `original`.
+fn add_wrapper(
+    module: &mut Module,
+    name: &str,
+    original: FunctionId,
+    log: FunctionId,
+    memory: walrus::MemoryId,
+) -> std::result::Result<FunctionId, String> {
+    let ty = module.types.get(module.funcs.get(original).ty());
+    let params = ty.params().to_vec();
+    let results = ty.results().to_vec();
+    if params.iter().any(|param| *param != ValType::I64) {
+        return Err(format!("host function {} does not take Vals", name));
+    }
+
+    let message = format!("{}{}", HOST_FN_LOG_PREFIX, name).into_bytes();
+    let message_words: Vec<i64> = message
+        .chunks(8)
+        .map(|chunk| {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            i64::from_le_bytes(word)
+        })
+        .collect();
+    let vals_at = (message_words.len() * 8) as u32;
+    let words = message_words.len() + params.len();
+
+    let args: Vec<_> = params.iter().map(|ty| module.locals.add(*ty)).collect();
+    let saved: Vec<_> = (0..words)
+        .map(|_| module.locals.add(ValType::I64))
+        .collect();
+    let at = |word: usize| MemArg {
+        align: 8,
+        offset: (word * 8) as u32,
+    };
+    let load = LoadKind::I64 { atomic: false };
+    let store = StoreKind::I64 { atomic: false };
+
+    let mut builder = FunctionBuilder::new(&mut module.types, &params, &results);
+    let mut body = builder.func_body();
+    for (word, local) in saved.iter().enumerate() {
+        body.i32_const(0)
+            .load(memory, load, at(word))
+            .local_set(*local);
+    }
+    for (word, value) in message_words.iter().enumerate() {
+        body.i32_const(0)
+            .i64_const(*value)
+            .store(memory, store, at(word));
+    }
+    for (index, arg) in args.iter().enumerate() {
+        body.i32_const(0)
+            .local_get(*arg)
+            .store(memory, store, at(message_words.len() + index));
+    }
+    body.i64_const(u32_val(0))
+        .i64_const(u32_val(message.len() as u32))
+        .i64_const(u32_val(vals_at))
+        .i64_const(u32_val(params.len() as u32))
+        .call(log)
+        .drop();
+    for (word, local) in saved.iter().enumerate() {
+        body.i32_const(0)
+            .local_get(*local)
+            .store(memory, store, at(word));
+    }
+    for arg in &args {
+        body.local_get(*arg);
+    }
+    body.call(original);
+    Ok(builder.finish(args, &mut module.funcs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_names_list_the_supported_ones() {
+        let watched =
+            HostFnWatch::new(&["require_auth".to_string(), "extend_ttl".to_string()]).unwrap();
+        let names: Vec<&str> = watched.names().collect();
+        assert!(names.contains(&"require_auth"));
+        assert!(names.contains(&"extend_contract_data_ttl"));
+        assert_eq!(names.len(), 1 + EXTEND_TTL.len());
+
+        let error = HostFnWatch::new(&["require_auth2".to_string()]).unwrap_err();
+        assert!(error.starts_with("Unknown host function 'require_auth2'; supported: "));
+        assert!(error.contains("call, contract_event"), "{}", error);
+        assert!(error.contains("get_ledger_timestamp"), "{}", error);
+    }
+
+    #[test]
+    fn wrapped_calls_are_read_back_with_their_stack() {
+        use soroban_env_host::xdr::{
+            ContractEvent, ContractEventV0, ExtensionPoint, Hash, ScString, ScSymbol,
+        };
+
+        let sym = |name: &str| ScVal::Symbol(ScSymbol(name.try_into().unwrap()));
+        let diagnostic = |topics: Vec<ScVal>, data: ScVal| HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: Some(Hash([2; 32])),
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data,
+                }),
+            },
+            failed_call: false,
+        };
+        let call = |contract: u8, function: &str| {
+            diagnostic(
+                vec![
+                    sym("fn_call"),
+                    ScVal::Bytes(vec![contract; 32].try_into().unwrap()),
+                    sym(function),
+                ],
+                ScVal::Void,
+            )
+        };
+        let log = |message: &str, args: Vec<ScVal>| {
+            let message = ScVal::String(ScString(message.try_into().unwrap()));
+            let data = if args.is_empty() {
+                message
+            } else {
+                let items: Vec<ScVal> = std::iter::once(message).chain(args).collect();
+                ScVal::Vec(Some(items.try_into().unwrap()))
+            };
+            diagnostic(vec![sym("log")], data)
+        };
+        let events = [
+            call(1, "swap"),
+            call(2, "transfer"),
+            log("an ordinary log", vec![ScVal::U32(1)]),
+            log(
+                "soroban-debug host fn: require_auth",
+                vec![ScVal::Address(ScAddress::Contract(Hash([9; 32])))],
+            ),
+            diagnostic(vec![sym("fn_return"), sym("transfer")], ScVal::Void),
+            log("soroban-debug host fn: get_ledger_timestamp", vec![]),
+        ];
+
+        let calls = HostFnWatch::calls(&events);
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].host_fn, "require_auth");
+        assert!(calls[0].args[0].starts_with('C'));
+        assert_eq!(calls[0].stack.len(), 2);
+        assert_eq!(calls[1].host_fn, "get_ledger_timestamp");
+        assert_eq!(calls[1].stack.len(), 1);
+
+        let report = HostFnWatch::describe(&calls[0]);
+        assert!(
+            report.starts_with("Host function called: require_auth(C"),
+            "{}",
+            report
+        );
+        assert!(
+            report.contains("  called from: transfer in C"),
+            "{}",
+            report
+        );
+        assert!(report.contains("    #1 swap in C"), "{}", report);
+    }
+
+    #[test]
+    fn u32_vals_carry_their_tag() {
+        assert_eq!(u32_val(0), 4);
+        assert_eq!(u32_val(3), (3 << 32) | 4);
+    }
+}
@@ -1,6 +1,10 @@
+pub mod assertion;
 pub mod breakpoint;
+pub mod call_walk;
 pub mod engine;
 pub mod error_db;
+pub mod expression;
+pub mod host_fn;
 pub mod instruction_pointer;
 pub mod source_map;
 pub mod state;
@@ -0,0 +1,434 @@
+//! Named, long-lived debugging environments.
+//!
+//! An environment bundles a network snapshot, registered contracts, labels
+//! and mock specs under `~/.soroban-debug/envs/<NAME>/`, together with the
+//! ledger state committed by earlier runs. `--env NAME` on `run`, `repl` and
+//! `scenario` loads it and `--commit` writes the post-run state back. The
+//! environment's `.lock` file is held for as long as it is open, so two
+//! processes never use the same environment at once.
+
+use crate::{DebuggerError, Result};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+use soroban_sdk::testutils::Snapshot;
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the environments directory.
+pub const ENV_DIR_ENV: &str = "SOROBAN_DEBUG_ENV_DIR";
+
+const MANIFEST_FILE: &str = "env.json";
+const STATE_FILE: &str = "state.json";
+const LOCK_FILE: &str = ".lock";
+
+/// What an environment holds, stored as `env.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentManifest {
+    pub name: String,
+    pub created_at: String,
+    /// Network snapshot copied into the environment directory, by file name.
+    #[serde(default)]
+    pub snapshot: Option<String>,
+    /// Contract WASM files, as absolute paths.
+    #[serde(default)]
+    pub contracts: Vec<PathBuf>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// `--mock` specs applied to every run.
+    #[serde(default)]
+    pub mocks: Vec<String>,
+    /// Number of `--commit` runs folded into the state.
+    #[serde(default)]
+    pub commits: u64,
+    #[serde(default)]
+    pub committed_at: Option<String>,
+    /// SHA-256 of the contract whose post-state was last committed.
+    #[serde(default)]
+    pub state_wasm_hash: Option<String>,
+}
+
+/// Inputs to `env create`.
+#[derive(Debug, Clone, Default)]
+pub struct NewEnvironment {
+    pub name: String,
+    pub snapshot: Option<PathBuf>,
+    pub contracts: Vec<PathBuf>,
+    pub labels: BTreeMap<String, String>,
+    pub mocks: Vec<String>,
+}
+
+/// The directory holding every named environment.
+#[derive(Debug, Clone)]
+pub struct EnvironmentStore {
+    dir: PathBuf,
+}
+
+impl EnvironmentStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `$SOROBAN_DEBUG_ENV_DIR`, or `~/.soroban-debug/envs`.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Ok(dir) = std::env::var(ENV_DIR_ENV) {
+            return Some(PathBuf::from(dir));
+        }
+        dirs::home_dir().map(|home| home.join(".soroban-debug").join("envs"))
+    }
+
+    pub fn open_default() -> Result<Self> {
+        Self::default_dir().map(Self::new).ok_or_else(|| {
+            DebuggerError::FileError("Could not determine home directory".to_string()).into()
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn env_dir(&self, name: &str) -> Result<PathBuf> {
+        validate_name(name)?;
+        Ok(self.dir.join(name))
+    }
+
+    pub fn create(&self, spec: NewEnvironment) -> Result<EnvironmentManifest> {
+        let dir = self.env_dir(&spec.name)?;
+        if dir.join(MANIFEST_FILE).exists() {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "Environment '{}' already exists; delete it first with `soroban-debug env delete {}`",
+                spec.name, spec.name
+            ))
+            .into());
+        }
+
+        let mut contracts = Vec::with_capacity(spec.contracts.len());
+        for contract in &spec.contracts {
+            contracts.push(contract.canonicalize().map_err(|e| {
+                DebuggerError::FileError(format!("Contract {:?} not found: {}", contract, e))
+            })?);
+        }
+        if let Some(snapshot) = &spec.snapshot {
+            crate::simulator::SnapshotLoader::from_file(snapshot)?;
+        }
+
+        fs::create_dir_all(&dir).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to create environment directory {:?}: {}",
+                dir, e
+            ))
+        })?;
+        let _lock = lock(&dir, &spec.name)?;
+
+        let snapshot = match &spec.snapshot {
+            Some(source) => {
+                let file_name = match source.extension() {
+                    Some(ext) => format!("snapshot.{}", ext.to_string_lossy()),
+                    None => "snapshot".to_string(),
+                };
+                fs::copy(source, dir.join(&file_name)).map_err(|e| {
+                    DebuggerError::FileError(format!(
+                        "Failed to copy snapshot {:?} into environment: {}",
+                        source, e
+                    ))
+                })?;
+                Some(file_name)
+            }
+            None => None,
+        };
+
+        let manifest = EnvironmentManifest {
+            name: spec.name,
+            created_at: chrono::Utc::now().to_rfc3339(),
+            snapshot,
+            contracts,
+            labels: spec.labels,
+            mocks: spec.mocks,
+            commits: 0,
+            committed_at: None,
+            state_wasm_hash: None,
+        };
+        write_manifest(&dir, &manifest)?;
+        Ok(manifest)
+    }
+
+    /// Every environment, sorted by name. Unreadable ones are skipped.
+    pub fn list(&self) -> Result<Vec<EnvironmentManifest>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(DebuggerError::FileError(format!(
+                    "Failed to read environments directory {:?}: {}",
+                    self.dir, e
+                ))
+                .into())
+            }
+        };
+        let mut manifests: Vec<EnvironmentManifest> = entries
+            .flatten()
+            .filter_map(|entry| read_manifest(&entry.path()).ok())
+            .collect();
+        manifests.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(manifests)
+    }
+
+    /// Open and lock an environment. Fails if another process holds it.
+    pub fn open(&self, name: &str) -> Result<Environment> {
+        let dir = self.env_dir(name)?;
+        if !dir.join(MANIFEST_FILE).exists() {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "Environment '{}' does not exist; create it with `soroban-debug env create {}`",
+                name, name
+            ))
+            .into());
+        }
+        let lock = lock(&dir, name)?;
+        let manifest = read_manifest(&dir)?;
+        Ok(Environment {
+            dir,
+            manifest,
+            _lock: lock,
+        })
+    }
+
+    pub fn delete(&self, name: &str) -> Result<()> {
+        let environment = self.open(name)?;
+        let dir = environment.dir.clone();
+        // Release the lock first; Windows cannot remove a file that is open.
+        drop(environment);
+        fs::remove_dir_all(&dir).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to delete environment directory {:?}: {}",
+                dir, e
+            ))
+            .into()
+        })
+    }
+}
+
+/// An open environment. Holds the environment's lock until dropped.
+#[derive(Debug)]
+pub struct Environment {
+    dir: PathBuf,
+    manifest: EnvironmentManifest,
+    _lock: File,
+}
+
+impl Environment {
+    pub fn manifest(&self) -> &EnvironmentManifest {
+        &self.manifest
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    pub fn snapshot_path(&self) -> Option<PathBuf> {
+        self.manifest
+            .snapshot
+            .as_ref()
+            .map(|file| self.dir.join(file))
+    }
+
+    /// The contract to use when none is given: the only registered one.
+    pub fn default_contract(&self) -> Result<Option<PathBuf>> {
+        match self.manifest.contracts.as_slice() {
+            [] => Ok(None),
+            [contract] => Ok(Some(contract.clone())),
+            contracts => Err(DebuggerError::InvalidArguments(format!(
+                "Environment '{}' has {} contracts; choose one with --contract ({})",
+                self.manifest.name,
+                contracts.len(),
+                contracts
+                    .iter()
+                    .map(|c| c.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ))
+            .into()),
+        }
+    }
+
+    /// State committed by earlier runs, if any.
+    ///
+    /// The committed ledger holds the code of the contract that produced it,
+    /// so it is refused for a different WASM rather than silently running
+    /// the old code.
+    pub fn load_state(&self, wasm_hash: &str) -> Result<Option<Snapshot>> {
+        let path = self.dir.join(STATE_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        if let Some(committed) = &self.manifest.state_wasm_hash {
+            if committed != wasm_hash {
+                return Err(DebuggerError::StorageError(format!(
+                    "Environment '{}' holds state committed by contract {}, not {}. Recreate the environment to use the new build.",
+                    self.manifest.name, committed, wasm_hash
+                ))
+                .into());
+            }
+        }
+        Snapshot::read_file(&path).map(Some).map_err(|e| {
+            DebuggerError::StorageError(format!(
+                "Failed to read environment state {:?}: {}",
+                path, e
+            ))
+            .into()
+        })
+    }
+
+    /// Persist the post-run state of the contract with `wasm_hash`.
+    pub fn commit(&mut self, state: &Snapshot, wasm_hash: &str) -> Result<()> {
+        let path = self.dir.join(STATE_FILE);
+        let tmp = self.dir.join(format!("{}.tmp", STATE_FILE));
+        state.write_file(&tmp).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to write environment state {:?}: {}",
+                tmp, e
+            ))
+        })?;
+        fs::rename(&tmp, &path).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to replace environment state {:?}: {}",
+                path, e
+            ))
+        })?;
+
+        self.manifest.commits += 1;
+        self.manifest.committed_at = Some(chrono::Utc::now().to_rfc3339());
+        self.manifest.state_wasm_hash = Some(wasm_hash.to_string());
+        write_manifest(&self.dir, &self.manifest)
+    }
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(DebuggerError::InvalidArguments(format!(
+            "Invalid environment name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        ))
+        .into())
+    }
+}
+
+fn lock(dir: &Path, name: &str) -> Result<File> {
+    let lock_path = dir.join(LOCK_FILE);
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to open environment lock {:?}: {}",
+                lock_path, e
+            ))
+        })?;
+    match file.try_lock_exclusive() {
+        Ok(()) => Ok(file),
+        Err(e) if e.raw_os_error() == fs2::lock_contended_error().raw_os_error() => {
+            Err(DebuggerError::FileError(format!(
+                "Environment '{}' is in use by another soroban-debug process",
+                name
+            ))
+            .into())
+        }
+        Err(e) => Err(DebuggerError::FileError(format!(
+            "Failed to lock environment {:?}: {}",
+            lock_path, e
+        ))
+        .into()),
+    }
+}
+
+fn read_manifest(dir: &Path) -> Result<EnvironmentManifest> {
+    let path = dir.join(MANIFEST_FILE);
+    let content = fs::read_to_string(&path).map_err(|e| {
+        DebuggerError::FileError(format!(
+            "Failed to read environment manifest {:?}: {}",
+            path, e
+        ))
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        DebuggerError::FileError(format!("Invalid environment manifest {:?}: {}", path, e)).into()
+    })
+}
+
+fn write_manifest(dir: &Path, manifest: &EnvironmentManifest) -> Result<()> {
+    let path = dir.join(MANIFEST_FILE);
+    let json = serde_json::to_string_pretty(manifest).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to serialize environment manifest: {}", e))
+    })?;
+    fs::write(&path, json).map_err(|e| {
+        DebuggerError::FileError(format!(
+            "Failed to write environment manifest {:?}: {}",
+            path, e
+        ))
+        .into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> (tempfile::TempDir, EnvironmentStore) {
+        let dir = tempfile::tempdir().unwrap();
+        let store = EnvironmentStore::new(dir.path().join("envs"));
+        (dir, store)
+    }
+
+    fn new_env(name: &str) -> NewEnvironment {
+        NewEnvironment {
+            name: name.to_string(),
+            labels: BTreeMap::from([("ticket".to_string(), "INC-42".to_string())]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn create_list_delete() {
+        let (_dir, store) = store();
+        assert!(store.list().unwrap().is_empty());
+
+        store.create(new_env("staging-repro")).unwrap();
+        store.create(new_env("audit-2024-06")).unwrap();
+        assert!(store.create(new_env("audit-2024-06")).is_err());
+
+        let names: Vec<_> = store.list().unwrap().into_iter().map(|m| m.name).collect();
+        assert_eq!(names, ["audit-2024-06", "staging-repro"]);
+        assert_eq!(store.list().unwrap()[0].labels["ticket"], "INC-42");
+
+        store.delete("audit-2024-06").unwrap();
+        assert_eq!(store.list().unwrap().len(), 1);
+        assert!(store.delete("audit-2024-06").is_err());
+    }
+
+    #[test]
+    fn open_environment_is_exclusive() {
+        let (_dir, store) = store();
+        store.create(new_env("shared")).unwrap();
+
+        let held = store.open("shared").unwrap();
+        let err = store.open("shared").unwrap_err().to_string();
+        assert!(err.contains("in use"), "{}", err);
+        drop(held);
+        assert!(store.open("shared").is_ok());
+    }
+
+    #[test]
+    fn names_cannot_escape_the_store() {
+        let (_dir, store) = store();
+        for name in ["", "../outside", ".hidden", "a/b"] {
+            assert!(store.create(new_env(name)).is_err(), "{}", name);
+        }
+    }
+}
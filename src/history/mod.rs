@@ -1,11 +1,12 @@
 use crate::{DebuggerError, Result};
 use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, NaiveDateTime, Utc};
+use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::{self, File, OpenOptions};
-use std::io::{BufReader, BufWriter, Write};
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
-use std::time::{Duration, SystemTime};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RunHistory {
@@ -14,6 +15,17 @@ pub struct RunHistory {
     pub function: String,
     pub cpu_used: u64,
     pub memory_used: u64,
+    /// Part of `cpu_used` spent before the contract was entered (argument
+    /// conversion, storage seeding). Zero for records written before it was tracked.
+    #[serde(default)]
+    pub setup_cpu_used: u64,
+    #[serde(default)]
+    pub setup_memory_used: u64,
+    /// Canonical signatures of the contract events the run emitted, see
+    /// [`topic_signature`](crate::analyzer::event_schema::topic_signature).
+    /// `None` for records written before events were tracked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_topics: Option<Vec<String>>,
 }
 
 /// Retention policy controlling how many records to keep and their maximum age.
@@ -42,6 +54,38 @@ pub struct PruneReport {
     pub remaining: usize,
 }
 
+/// Summary returned by [`HistoryManager::compact_history`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactReport {
+    /// Unreadable lines that were dropped.
+    pub corrupt: usize,
+    /// Readable records dropped to honour `keep_last`.
+    pub truncated: usize,
+    pub remaining: usize,
+}
+
+/// Records read from the history file.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryLoad {
+    pub records: Vec<RunHistory>,
+    /// Unreadable lines after the last good record, left by a writer that
+    /// died mid-append. They are ignored on read and dropped by the next append.
+    pub skipped: usize,
+    /// Unreadable lines between good records. Reading fails on these; only
+    /// [`HistoryManager::compact_history`] drops them.
+    corrupt_inner: usize,
+    /// The file is a pre-NDJSON JSON array and is rewritten on the next append.
+    legacy: bool,
+    /// Length of the file up to the end of the last good record.
+    valid_len: u64,
+    /// The last good record is not newline-terminated.
+    needs_newline: bool,
+}
+
+/// Run history, stored as one JSON record per line.
+///
+/// Appends write a single line while holding an advisory lock on a sibling
+/// `.lock` file, so parallel runs (e.g. CI shards) never interleave records.
 pub struct HistoryManager {
     file_path: PathBuf,
 }
@@ -109,14 +153,47 @@ pub fn sort_records_by_date(records: &mut [RunHistory]) {
     records.sort_by(compare_run_history_date);
 }
 
-struct HistoryLockGuard {
-    lock_path: PathBuf,
-}
+/// Parse the history file contents, tolerating unreadable lines.
+///
+/// A leading `[` marks the JSON-array format used before NDJSON; it is read
+/// as a whole and any error is returned.
+fn parse_history(bytes: &[u8]) -> std::result::Result<HistoryLoad, serde_json::Error> {
+    if bytes.trim_ascii_start().starts_with(b"[") {
+        let records: Vec<RunHistory> = serde_json::from_slice(bytes)?;
+        return Ok(HistoryLoad {
+            records,
+            legacy: true,
+            ..HistoryLoad::default()
+        });
+    }
 
-impl Drop for HistoryLockGuard {
-    fn drop(&mut self) {
-        let _ = fs::remove_file(&self.lock_path);
+    let mut load = HistoryLoad::default();
+    let mut offset = 0usize;
+    let mut pending_corrupt = 0usize;
+    for line in bytes.split_inclusive(|b| *b == b'\n') {
+        offset += line.len();
+        let line = line.trim_ascii();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_slice::<RunHistory>(line) {
+            Ok(record) => {
+                load.corrupt_inner += pending_corrupt;
+                pending_corrupt = 0;
+                load.records.push(record);
+                load.valid_len = offset as u64;
+            }
+            Err(_) => pending_corrupt += 1,
+        }
     }
+    load.skipped = pending_corrupt;
+    load.needs_newline = load.valid_len > 0 && bytes[load.valid_len as usize - 1] != b'\n';
+    Ok(load)
+}
+
+/// Holds the advisory lock on the history's `.lock` file until dropped.
+struct HistoryLockGuard {
+    _file: File,
 }
 
 impl HistoryManager {
@@ -184,44 +261,63 @@ impl HistoryManager {
     ///
     /// Surfacing the error lets the caller decide on a recovery strategy and
     /// prevents any write path from silently clobbering salvageable data.
+    ///
+    /// The one exception is a torn final line left by a writer that died
+    /// mid-append: see [`read_history`](Self::read_history).
     pub fn load_history(&self) -> Result<Vec<RunHistory>> {
-        if !self.file_path.exists() {
-            return Ok(Vec::new());
-        }
+        Ok(self.read_history()?.records)
+    }
 
-        let file = File::open(&self.file_path).map_err(|e| {
-            DebuggerError::FileError(format!(
-                "Failed to open history file {:?}: {}",
-                self.file_path, e
-            ))
-        })?;
+    /// Read historical run data, reporting unreadable trailing lines.
+    ///
+    /// Unreadable lines after the last good record are skipped and counted in
+    /// [`HistoryLoad::skipped`]. Unreadable lines between good records, or a
+    /// file with no readable record at all, are an error.
+    pub fn read_history(&self) -> Result<HistoryLoad> {
+        let bytes = match fs::read(&self.file_path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HistoryLoad::default()),
+            Err(e) => {
+                return Err(DebuggerError::FileError(format!(
+                    "Failed to open history file {:?}: {}",
+                    self.file_path, e
+                ))
+                .into())
+            }
+        };
 
-        let reader = BufReader::new(file);
+        let load = parse_history(&bytes).map_err(|e| self.parse_error(e.to_string()))?;
+        if load.records.is_empty() && load.skipped > 0 {
+            return Err(self.parse_error("no line holds a valid history record".to_string()));
+        }
+        if load.corrupt_inner > 0 {
+            return Err(self.parse_error(format!(
+                "{} unreadable line(s) between valid records",
+                load.corrupt_inner
+            )));
+        }
+        Ok(load)
+    }
 
-        // Surface parse failures rather than swallowing them.
-        //
-        // A corrupt or truncated file must not be treated as an empty history.
-        // Doing so would cause `append_record` to overwrite the file with a
-        // single-record list, destroying all salvageable data.
-        let history: Vec<RunHistory> = serde_json::from_reader(reader).map_err(|e| {
-            DebuggerError::FileError(format!(
-                "History file \"{}\" could not be parsed ({}). \
-                 The file may be corrupt or was written by an incompatible version. \
-                 Recovery options:\n\
-                 \x20 1. Inspect the file with `cat \"{}\"` and fix any JSON syntax errors.\n\
-                 \x20 2. Back up and remove the file (`mv \"{}\" \"{}.bak\"`) to start fresh.\n\
-                 \x20 3. Restore from a previous backup if one exists.",
-                self.file_path.display(),
-                e,
-                self.file_path.display(),
-                self.file_path.display(),
-                self.file_path.display(),
-            ))
-        })?;
-        Ok(history)
+    fn parse_error(&self, reason: String) -> miette::Report {
+        DebuggerError::FileError(format!(
+            "History file \"{}\" could not be parsed ({}). \
+             The file may be corrupt or was written by an incompatible version. \
+             Recovery options:\n\
+             \x20 1. Run `soroban-debug history compact` to drop unreadable records.\n\
+             \x20 2. Inspect the file with `cat \"{}\"` and fix any JSON syntax errors.\n\
+             \x20 3. Back up and remove the file (`mv \"{}\" \"{}.bak\"`) to start fresh.\n\
+             \x20 4. Restore from a previous backup if one exists.",
+            self.file_path.display(),
+            reason,
+            self.file_path.display(),
+            self.file_path.display(),
+            self.file_path.display(),
+        ))
+        .into()
     }
 
-    /// Append a new record optimizing with BufWriter.
+    /// Append a new record as one line at the end of the file.
     ///
     /// No retention policy is applied. Use [`append_record_with_policy`] to
     /// automatically prune after appending.
@@ -233,24 +329,52 @@ impl HistoryManager {
     ///
     /// The sequence is:
     /// 1. Acquire the file lock.
-    /// 2. Load current history.
-    /// 3. Push the new record.
-    /// 4. Apply retention (sort → prune).
-    /// 5. Atomically replace the file.
+    /// 2. Load current history, failing if it is unreadable.
+    /// 3. With an empty policy, drop any torn trailing lines and append the
+    ///    record as a single write.
+    /// 4. Otherwise (or for a legacy JSON-array file) push the record, apply
+    ///    retention (sort → prune) and atomically replace the file.
     pub fn append_record_with_policy(
         &self,
         record: RunHistory,
         policy: &RetentionPolicy,
     ) -> Result<()> {
         let _lock = self.acquire_lock()?;
-        let mut history = self.load_history()?;
-        history.push(record);
+        let loaded = self.read_history()?;
 
-        if !policy.is_empty() {
+        if loaded.legacy || !policy.is_empty() {
+            let mut history = loaded.records;
+            history.push(record);
             Self::apply_retention(&mut history, policy);
+            return self.flush_history(&history);
         }
 
-        self.flush_history(&history)
+        let mut line = Vec::new();
+        if loaded.needs_newline {
+            line.push(b'\n');
+        }
+        serde_json::to_writer(&mut line, &record).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize history record: {}", e))
+        })?;
+        line.push(b'\n');
+
+        let write_error = |e: std::io::Error| {
+            DebuggerError::FileError(format!(
+                "Failed to append to history file {:?}: {}",
+                self.file_path, e
+            ))
+        };
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.file_path)
+            .map_err(write_error)?;
+        if loaded.skipped > 0 {
+            file.set_len(loaded.valid_len).map_err(write_error)?;
+        }
+        file.write_all(&line).map_err(write_error)?;
+        let _ = file.sync_data();
+        Ok(())
     }
 
     /// Prune the history file according to `policy` and return a [`PruneReport`].
@@ -273,6 +397,47 @@ impl HistoryManager {
         Ok(PruneReport { removed, remaining })
     }
 
+    /// Rewrite the history file keeping only readable records.
+    ///
+    /// Unlike every other read path this drops unreadable lines wherever they
+    /// are. With `keep_last`, only the N most-recent records (by date) are kept.
+    /// A legacy JSON-array file is converted to one record per line.
+    pub fn compact_history(&self, keep_last: Option<usize>) -> Result<CompactReport> {
+        let _lock = self.acquire_lock()?;
+        let bytes = match fs::read(&self.file_path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(DebuggerError::FileError(format!(
+                    "Failed to open history file {:?}: {}",
+                    self.file_path, e
+                ))
+                .into())
+            }
+        };
+        let loaded = parse_history(&bytes).map_err(|e| self.parse_error(e.to_string()))?;
+
+        let mut history = loaded.records;
+        let readable = history.len();
+        Self::apply_retention(
+            &mut history,
+            &RetentionPolicy {
+                max_records: keep_last,
+                max_age_days: None,
+            },
+        );
+
+        if !bytes.is_empty() {
+            self.flush_history(&history)?;
+        }
+
+        Ok(CompactReport {
+            corrupt: loaded.skipped + loaded.corrupt_inner,
+            truncated: readable - history.len(),
+            remaining: history.len(),
+        })
+    }
+
     /// Apply `policy` to `records` in-place.
     ///
     /// Records are first sorted chronologically (oldest → newest). The age
@@ -313,7 +478,8 @@ impl HistoryManager {
 
     // ── private helpers ─────────────────────────────────────────────────────
 
-    /// Write `history` to disk atomically: tmp file → fsync → rename.
+    /// Write `history` to disk atomically, one record per line: tmp file →
+    /// fsync → rename.
     fn flush_history(&self, history: &[RunHistory]) -> Result<()> {
         let tmp_path = self.file_path.with_extension("json.tmp");
         let file = File::create(&tmp_path).map_err(|e| {
@@ -323,12 +489,17 @@ impl HistoryManager {
             ))
         })?;
         let mut writer = BufWriter::new(file);
-        serde_json::to_writer_pretty(&mut writer, history).map_err(|e| {
-            DebuggerError::FileError(format!(
-                "Failed to write history file {:?}: {}",
-                self.file_path, e
-            ))
-        })?;
+        for record in history {
+            serde_json::to_writer(&mut writer, record)
+                .map_err(std::io::Error::from)
+                .and_then(|()| writer.write_all(b"\n"))
+                .map_err(|e| {
+                    DebuggerError::FileError(format!(
+                        "Failed to write history file {:?}: {}",
+                        self.file_path, e
+                    ))
+                })?;
+        }
         writer.flush().map_err(|e| {
             DebuggerError::FileError(format!(
                 "Failed to flush temp history file {:?}: {}",
@@ -375,48 +546,31 @@ impl HistoryManager {
         Ok(filtered)
     }
 
+    /// Block until this process holds the exclusive advisory lock.
+    ///
+    /// The lock is taken on a sibling `.lock` file rather than the history
+    /// itself because rewrites replace the history file by rename. The OS
+    /// releases it if the process dies, so there are no stale locks to break.
     fn acquire_lock(&self) -> Result<HistoryLockGuard> {
         let lock_path = self.file_path.with_extension("lock");
-        let start = SystemTime::now();
-
-        loop {
-            match OpenOptions::new()
-                .write(true)
-                .create_new(true)
-                .open(&lock_path)
-            {
-                Ok(mut f) => {
-                    let _ = writeln!(f, "pid={}", std::process::id());
-                    return Ok(HistoryLockGuard { lock_path });
-                }
-                Err(_) => {
-                    // If a previous process crashed, allow breaking a stale lock after a grace period.
-                    if let Ok(meta) = fs::metadata(&lock_path) {
-                        if let Ok(modified) = meta.modified() {
-                            if modified
-                                .elapsed()
-                                .unwrap_or(Duration::from_secs(0))
-                                .as_secs()
-                                > 30
-                            {
-                                let _ = fs::remove_file(&lock_path);
-                                continue;
-                            }
-                        }
-                    }
-
-                    if start.elapsed().unwrap_or(Duration::from_secs(0)).as_secs() > 30 {
-                        return Err(DebuggerError::FileError(format!(
-                            "Timed out waiting for history lock at {:?}",
-                            lock_path
-                        ))
-                        .into());
-                    }
-
-                    std::thread::sleep(Duration::from_millis(10));
-                }
-            }
-        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|e| {
+                DebuggerError::FileError(format!(
+                    "Failed to open history lock {:?}: {}",
+                    lock_path, e
+                ))
+            })?;
+        file.lock_exclusive().map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to lock history file {:?}: {}",
+                lock_path, e
+            ))
+        })?;
+        Ok(HistoryLockGuard { _file: file })
     }
 }
 
@@ -583,6 +737,93 @@ pub fn budget_trend_stats(records: &[RunHistory]) -> Option<BudgetTrendStats> {
     })
 }
 
+/// How a topic signature's presence changed across a function's runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TopicStatus {
+    /// Emitted by the recent runs, and by earlier ones if it is not new.
+    Present,
+    /// First emitted within the recent runs.
+    Added,
+    /// Emitted by earlier runs but by none of the recent ones.
+    Missing,
+}
+
+/// Where one event topic signature appeared in the history of a function.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopicPresence {
+    pub function: String,
+    pub signature: String,
+    /// Date of the first and last run that emitted the topic.
+    pub first_seen: String,
+    pub last_seen: String,
+    /// Runs that emitted the topic, out of the function's tracked runs.
+    pub runs: usize,
+    pub tracked_runs: usize,
+    pub status: TopicStatus,
+}
+
+/// Correlate the event topics of `records` per function.
+///
+/// Only records that tracked their events count. The last `recent` tracked
+/// runs of each function are compared against the ones before them; a
+/// function with no earlier runs has nothing to compare, so all of its
+/// topics are [`TopicStatus::Present`]. Results are sorted by function, then
+/// signature.
+pub fn event_topic_history(records: &[RunHistory], recent: usize) -> Vec<TopicPresence> {
+    let mut by_function: BTreeMap<&str, Vec<&RunHistory>> = BTreeMap::new();
+    for record in records.iter().filter(|r| r.event_topics.is_some()) {
+        by_function
+            .entry(&record.function)
+            .or_default()
+            .push(record);
+    }
+
+    let mut presence = Vec::new();
+    for (function, mut runs) in by_function {
+        runs.sort_by(|a, b| compare_run_history_date(a, b));
+        let window_start = runs.len().saturating_sub(recent.max(1));
+
+        // Indices of the runs that emitted each signature, oldest first.
+        let mut seen: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (index, run) in runs.iter().enumerate() {
+            let topics: BTreeSet<&str> = run
+                .event_topics
+                .iter()
+                .flatten()
+                .map(String::as_str)
+                .collect();
+            for topic in topics {
+                seen.entry(topic).or_default().push(index);
+            }
+        }
+
+        for (signature, indices) in seen {
+            let first = indices[0];
+            let last = indices[indices.len() - 1];
+            let status = if window_start == 0 {
+                TopicStatus::Present
+            } else if last < window_start {
+                TopicStatus::Missing
+            } else if first >= window_start {
+                TopicStatus::Added
+            } else {
+                TopicStatus::Present
+            };
+            presence.push(TopicPresence {
+                function: function.to_string(),
+                signature: signature.to_string(),
+                first_seen: runs[first].date.clone(),
+                last_seen: runs[last].date.clone(),
+                runs: indices.len(),
+                tracked_runs: runs.len(),
+                status,
+            });
+        }
+    }
+    presence
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -604,6 +845,9 @@ mod tests {
             function: "func".into(),
             cpu_used: cpu,
             memory_used: mem,
+            setup_cpu_used: 0,
+            setup_memory_used: 0,
+            event_topics: None,
         }
     }
 
@@ -751,6 +995,135 @@ mod tests {
         );
     }
 
+    // ── NDJSON storage, recovery and compaction ─────────────────────────────
+
+    fn ndjson(records: &[RunHistory]) -> String {
+        records
+            .iter()
+            .map(|r| serde_json::to_string(r).unwrap() + "\n")
+            .collect()
+    }
+
+    #[test]
+    fn append_writes_one_line_per_record() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("history.json");
+        let manager = HistoryManager::with_path(path.clone());
+        manager
+            .append_record(make_record("2026-01-01T00:00:00Z", 1, 1))
+            .unwrap();
+        manager
+            .append_record(make_record("2026-01-02T00:00:00Z", 2, 2))
+            .unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 2);
+        assert!(contents.ends_with('\n'));
+    }
+
+    #[test]
+    fn torn_trailing_line_is_skipped_and_reported() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("history.json");
+        let mut contents = ndjson(&[
+            make_record("2026-01-01T00:00:00Z", 1, 1),
+            make_record("2026-01-02T00:00:00Z", 2, 2),
+        ]);
+        contents.push_str("{\"date\":\"2026-01-03T00:00:00Z\",\"contract_");
+        fs::write(&path, contents).unwrap();
+
+        let manager = HistoryManager::with_path(path);
+        let load = manager.read_history().unwrap();
+        assert_eq!(load.records.len(), 2);
+        assert_eq!(load.skipped, 1);
+    }
+
+    #[test]
+    fn append_after_torn_line_drops_it() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("history.json");
+        let mut contents = ndjson(&[make_record("2026-01-01T00:00:00Z", 1, 1)]);
+        contents.push_str("{\"date\":\"2026-01-02");
+        fs::write(&path, contents).unwrap();
+
+        let manager = HistoryManager::with_path(path);
+        manager
+            .append_record(make_record("2026-01-03T00:00:00Z", 3, 3))
+            .unwrap();
+
+        let load = manager.read_history().unwrap();
+        assert_eq!(load.skipped, 0);
+        let cpus: Vec<u64> = load.records.iter().map(|r| r.cpu_used).collect();
+        assert_eq!(cpus, vec![1, 3]);
+    }
+
+    #[test]
+    fn unreadable_line_between_records_is_an_error_until_compacted() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("history.json");
+        let contents = format!(
+            "{}garbage\n{}",
+            ndjson(&[make_record("2026-01-01T00:00:00Z", 1, 1)]),
+            ndjson(&[make_record("2026-01-02T00:00:00Z", 2, 2)])
+        );
+        fs::write(&path, contents).unwrap();
+
+        let manager = HistoryManager::with_path(path);
+        let msg = manager.load_history().unwrap_err().to_string();
+        assert!(msg.contains("history compact"), "got: {msg}");
+
+        let report = manager.compact_history(None).unwrap();
+        assert_eq!(
+            report,
+            CompactReport {
+                corrupt: 1,
+                truncated: 0,
+                remaining: 2,
+            }
+        );
+        assert_eq!(manager.load_history().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn compact_keeps_the_last_n_records() {
+        let temp = TempDir::new().unwrap();
+        let manager = HistoryManager::with_path(temp.path().join("history.json"));
+        for i in 1..=5 {
+            manager
+                .append_record(make_record(&format!("2026-01-0{}T00:00:00Z", i), i, i))
+                .unwrap();
+        }
+
+        let report = manager.compact_history(Some(2)).unwrap();
+        assert_eq!(report.truncated, 3);
+        assert_eq!(report.remaining, 2);
+        let cpus: Vec<u64> = manager
+            .load_history()
+            .unwrap()
+            .iter()
+            .map(|r| r.cpu_used)
+            .collect();
+        assert_eq!(cpus, vec![4, 5]);
+    }
+
+    #[test]
+    fn legacy_json_array_is_read_and_converted_on_append() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("history.json");
+        let legacy = vec![make_record("2026-01-01T00:00:00Z", 1, 1)];
+        fs::write(&path, serde_json::to_string_pretty(&legacy).unwrap()).unwrap();
+
+        let manager = HistoryManager::with_path(path.clone());
+        assert_eq!(manager.load_history().unwrap().len(), 1);
+
+        manager
+            .append_record(make_record("2026-01-02T00:00:00Z", 2, 2))
+            .unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(!contents.starts_with('['));
+        assert_eq!(manager.load_history().unwrap().len(), 2);
+    }
+
     // ── pre-existing tests (unchanged) ───────────────────────────────────────
 
     #[test]
@@ -762,6 +1135,9 @@ mod tests {
             function: "func".into(),
             cpu_used: 1150,    // 15% increase
             memory_used: 1050, // 5% increase
+            setup_cpu_used: 0,
+            setup_memory_used: 0,
+            event_topics: None,
         };
 
         let records = vec![p1, p2];
@@ -916,6 +1292,9 @@ mod tests {
                         function: "func".into(),
                         cpu_used: (t as u64) * 10 + i as u64,
                         memory_used: (t as u64) * 10 + i as u64,
+                        setup_cpu_used: 0,
+                        setup_memory_used: 0,
+                        event_topics: None,
                     };
                     manager.append_record(record).unwrap();
                 }
@@ -946,6 +1325,9 @@ mod tests {
                 function: "f".into(),
                 cpu_used: 1,
                 memory_used: 1,
+                setup_cpu_used: 0,
+                setup_memory_used: 0,
+                event_topics: None,
             })
             .unwrap();
 
@@ -1164,4 +1546,69 @@ mod tests {
         assert!(cpus.contains(&3));
         assert!(cpus.contains(&4));
     }
+
+    fn events_record(date: &str, function: &str, topics: &[&str]) -> RunHistory {
+        RunHistory {
+            function: function.into(),
+            event_topics: Some(topics.iter().map(|t| t.to_string()).collect()),
+            ..make_record(date, 1, 1)
+        }
+    }
+
+    #[test]
+    fn event_topics_are_correlated_per_function() {
+        let temp = TempDir::new().unwrap();
+        let path = temp.path().join("history.json");
+        // An untracked legacy record, then runs out of date order.
+        let mut contents = String::from(
+            "{\"date\":\"2025-12-31T00:00:00Z\",\"contract_hash\":\"c.wasm\",\
+             \"function\":\"transfer\",\"cpu_used\":1,\"memory_used\":1}\n",
+        );
+        contents.push_str(&ndjson(&[
+            events_record("2026-01-03T00:00:00Z", "transfer", &["transfer(address)"]),
+            events_record(
+                "2026-01-01T00:00:00Z",
+                "transfer",
+                &["approve(address)", "transfer(address)"],
+            ),
+            events_record(
+                "2026-01-02T00:00:00Z",
+                "transfer",
+                &["approve(address)", "transfer(address)"],
+            ),
+            events_record(
+                "2026-01-04T00:00:00Z",
+                "transfer",
+                &["fee", "transfer(address)"],
+            ),
+            events_record("2026-01-04T00:00:00Z", "init", &["init"]),
+        ]));
+        fs::write(&path, contents).unwrap();
+        let records = HistoryManager::with_path(path).load_history().unwrap();
+        assert_eq!(records.len(), 6);
+
+        let presence = event_topic_history(&records, 2);
+        let summary: Vec<(&str, &str, TopicStatus, usize)> = presence
+            .iter()
+            .map(|p| (p.function.as_str(), p.signature.as_str(), p.status, p.runs))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("init", "init", TopicStatus::Present, 1),
+                ("transfer", "approve(address)", TopicStatus::Missing, 2),
+                ("transfer", "fee", TopicStatus::Added, 1),
+                ("transfer", "transfer(address)", TopicStatus::Present, 4),
+            ]
+        );
+        let approve = &presence[1];
+        assert_eq!(approve.first_seen, "2026-01-01T00:00:00Z");
+        assert_eq!(approve.last_seen, "2026-01-02T00:00:00Z");
+        assert_eq!(approve.tracked_runs, 4);
+
+        // With every run in the recent window there is nothing to compare against.
+        assert!(event_topic_history(&records, 10)
+            .iter()
+            .all(|p| p.status == TopicStatus::Present));
+    }
 }
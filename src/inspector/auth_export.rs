@@ -0,0 +1,234 @@
+//! Export of recorded authorizations as unsigned `SorobanAuthorizationEntry`s.
+//!
+//! A run in recording mode learns exactly which addresses must authorize which
+//! invocations. Each recorded payload becomes an entry whose signature is left
+//! as `Void` for an off-chain signer to fill in. The expiration ledger is taken
+//! from `--auth-expiration-ledger` when given and left as `0` otherwise.
+
+use crate::utils::arguments::scval_to_json;
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use soroban_env_host::xdr::{
+    Limits, ScAddress, ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry,
+    SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, WriteXdr,
+};
+use soroban_env_host::Host;
+use std::path::Path;
+
+/// One authorization entry ready to be signed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedAuthEntry {
+    /// Address that must sign, or `None` when the transaction source account authorizes.
+    pub address: Option<String>,
+    pub nonce: Option<i64>,
+    /// `None` until filled in with `--auth-expiration-ledger`.
+    pub signature_expiration_ledger: Option<u32>,
+    /// Always `None`: the signature is the signer's to produce.
+    pub signature: Option<String>,
+    /// Base64 XDR `SorobanAuthorizationEntry` with a `Void` signature.
+    pub xdr: String,
+    /// Decoded root invocation, in the shape of the authorization tree.
+    pub invocation: serde_json::Value,
+}
+
+/// Contents of the file written by `--export-auth`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuthExport {
+    /// Ledger the run simulated; expirations must lie after it.
+    pub ledger: u32,
+    pub entries: Vec<ExportedAuthEntry>,
+}
+
+impl AuthExport {
+    /// Entries for the authorizations recorded during the last invocation.
+    ///
+    /// The host must have been in recording mode (`mock_all_auths`) for it.
+    pub fn from_host(host: &Host, ledger: u32, expiration_ledger: Option<u32>) -> Result<Self> {
+        let payloads = host.get_recorded_auth_payloads().map_err(|e| {
+            DebuggerError::ExecutionError(format!("Failed to get recorded authorizations: {}", e))
+        })?;
+        let entries = payloads
+            .into_iter()
+            .map(|payload| match (payload.address, payload.nonce) {
+                (Some(address), Some(nonce)) => {
+                    export_address(address, nonce, payload.invocation, expiration_ledger)
+                }
+                _ => export_source_account(payload.invocation),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { ledger, entries })
+    }
+
+    pub fn write_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize authorizations: {}", e))
+        })?;
+        std::fs::write(path, json).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to write authorizations to {:?}: {}",
+                path, e
+            ))
+            .into()
+        })
+    }
+}
+
+fn export_address(
+    address: ScAddress,
+    nonce: i64,
+    invocation: SorobanAuthorizedInvocation,
+    expiration_ledger: Option<u32>,
+) -> Result<ExportedAuthEntry> {
+    let credentials = SorobanCredentials::Address(SorobanAddressCredentials {
+        address: address.clone(),
+        nonce,
+        signature_expiration_ledger: expiration_ledger.unwrap_or(0),
+        signature: ScVal::Void,
+    });
+    Ok(ExportedAuthEntry {
+        address: Some(address.to_string()),
+        nonce: Some(nonce),
+        signature_expiration_ledger: expiration_ledger,
+        signature: None,
+        invocation: invocation_json(&invocation),
+        xdr: entry_xdr(credentials, invocation)?,
+    })
+}
+
+fn export_source_account(invocation: SorobanAuthorizedInvocation) -> Result<ExportedAuthEntry> {
+    Ok(ExportedAuthEntry {
+        address: None,
+        nonce: None,
+        signature_expiration_ledger: None,
+        signature: None,
+        invocation: invocation_json(&invocation),
+        xdr: entry_xdr(SorobanCredentials::SourceAccount, invocation)?,
+    })
+}
+
+fn entry_xdr(
+    credentials: SorobanCredentials,
+    root_invocation: SorobanAuthorizedInvocation,
+) -> Result<String> {
+    SorobanAuthorizationEntry {
+        credentials,
+        root_invocation,
+    }
+    .to_xdr_base64(Limits::none())
+    .map_err(|e| {
+        DebuggerError::ExecutionError(format!("Failed to encode authorization entry: {:?}", e))
+            .into()
+    })
+}
+
+/// The invocation as `{contract_id, function, args, sub_invocations}`.
+pub fn invocation_json(invocation: &SorobanAuthorizedInvocation) -> serde_json::Value {
+    let (contract_id, function, args) = match &invocation.function {
+        SorobanAuthorizedFunction::ContractFn(call) => (
+            call.contract_address.to_string(),
+            call.function_name.to_utf8_string_lossy(),
+            call.args.iter().map(scval_to_json).collect(),
+        ),
+        SorobanAuthorizedFunction::CreateContractHostFn(_) => (
+            "Host".to_string(),
+            "create_contract".to_string(),
+            Vec::new(),
+        ),
+        SorobanAuthorizedFunction::CreateContractV2HostFn(_) => (
+            "Host".to_string(),
+            "create_contract_v2".to_string(),
+            Vec::new(),
+        ),
+    };
+    serde_json::json!({
+        "contract_id": contract_id,
+        "function": function,
+        "args": args,
+        "sub_invocations": invocation
+            .sub_invocations
+            .iter()
+            .map(invocation_json)
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Hash, InvokeContractArgs, ReadXdr, ScSymbol, Uint256};
+
+    fn invocation(
+        function: &str,
+        sub_invocations: Vec<SorobanAuthorizedInvocation>,
+    ) -> SorobanAuthorizedInvocation {
+        SorobanAuthorizedInvocation {
+            function: SorobanAuthorizedFunction::ContractFn(InvokeContractArgs {
+                contract_address: ScAddress::Contract(Hash([3; 32])),
+                function_name: ScSymbol(function.try_into().unwrap()),
+                args: vec![ScVal::I128(soroban_env_host::xdr::Int128Parts {
+                    hi: 0,
+                    lo: 50,
+                })]
+                .try_into()
+                .unwrap(),
+            }),
+            sub_invocations: sub_invocations.try_into().unwrap(),
+        }
+    }
+
+    fn signer() -> ScAddress {
+        ScAddress::Account(soroban_env_host::xdr::AccountId(
+            soroban_env_host::xdr::PublicKey::PublicKeyTypeEd25519(Uint256([9; 32])),
+        ))
+    }
+
+    #[test]
+    fn address_entry_round_trips_through_xdr() {
+        let root = invocation("transfer", vec![invocation("burn", vec![])]);
+        let entry = export_address(signer(), 42, root.clone(), Some(5000)).unwrap();
+
+        let decoded =
+            SorobanAuthorizationEntry::from_xdr_base64(&entry.xdr, Limits::none()).unwrap();
+        assert_eq!(decoded.root_invocation, root);
+        let SorobanCredentials::Address(credentials) = decoded.credentials else {
+            panic!("expected address credentials");
+        };
+        assert_eq!(credentials.address, signer());
+        assert_eq!(credentials.nonce, 42);
+        assert_eq!(credentials.signature_expiration_ledger, 5000);
+        assert_eq!(credentials.signature, ScVal::Void);
+
+        assert_eq!(entry.address, Some(signer().to_string()));
+        assert_eq!(entry.signature_expiration_ledger, Some(5000));
+        assert_eq!(entry.signature, None);
+    }
+
+    #[test]
+    fn expiration_is_a_placeholder_unless_given() {
+        let entry = export_address(signer(), 1, invocation("transfer", vec![]), None).unwrap();
+        assert_eq!(entry.signature_expiration_ledger, None);
+
+        let decoded =
+            SorobanAuthorizationEntry::from_xdr_base64(&entry.xdr, Limits::none()).unwrap();
+        let SorobanCredentials::Address(credentials) = decoded.credentials else {
+            panic!("expected address credentials");
+        };
+        assert_eq!(credentials.signature_expiration_ledger, 0);
+    }
+
+    #[test]
+    fn invocation_json_mirrors_the_tree() {
+        let root = invocation("transfer", vec![invocation("burn", vec![])]);
+        let json = invocation_json(&root);
+        assert_eq!(json["function"], "transfer");
+        assert!(json["contract_id"].as_str().unwrap().starts_with('C'));
+        assert_eq!(json["args"], serde_json::json!(["50"]));
+        assert_eq!(json["sub_invocations"][0]["function"], "burn");
+
+        let entry = export_source_account(root).unwrap();
+        assert_eq!(entry.address, None);
+        let decoded =
+            SorobanAuthorizationEntry::from_xdr_base64(&entry.xdr, Limits::none()).unwrap();
+        assert_eq!(decoded.credentials, SorobanCredentials::SourceAccount);
+    }
+}
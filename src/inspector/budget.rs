@@ -161,6 +161,69 @@ impl BudgetInspector {
             format!("{} B", bytes)
         }
     }
+
+    /// Split an invocation's cost into setup (argument conversion, storage
+    /// seeding) and the contract's own execution, one aligned row each.
+    pub fn format_breakdown(setup: &BudgetInfo, execution: &BudgetInfo) -> String {
+        let row = |label: &str, info: &BudgetInfo| {
+            format!(
+                "  {:<9}  {:>10} CPU  {:>10} memory",
+                label,
+                Self::format_cpu_insns(info.cpu_instructions),
+                Self::format_memory_bytes(info.memory_bytes)
+            )
+        };
+        format!("{}\n{}", row("setup", setup), row("execution", execution))
+    }
+
+    /// One line summing up a run's budget against the limits in force, e.g.
+    /// `Budget: 1,234,567 of 100,000,000 insns (1.2% of limit), 48.00 KB of
+    /// 40.00 MB mem (0.1% of limit)`.
+    pub fn format_footer(info: &BudgetInfo) -> String {
+        let cpu = match info.cpu_limit {
+            u64::MAX => format!("{} insns (no limit)", group_digits(info.cpu_instructions)),
+            limit => format!(
+                "{} of {} insns ({:.1}% of limit)",
+                group_digits(info.cpu_instructions),
+                group_digits(limit),
+                info.cpu_percentage()
+            ),
+        };
+        let mem = match info.memory_limit {
+            u64::MAX => format!(
+                "{} mem (no limit)",
+                Self::format_memory_bytes(info.memory_bytes)
+            ),
+            limit => format!(
+                "{} of {} mem ({:.1}% of limit)",
+                Self::format_memory_bytes(info.memory_bytes),
+                Self::format_memory_bytes(limit),
+                info.memory_percentage()
+            ),
+        };
+        format!("Budget: {}, {}", cpu, mem)
+    }
+
+    /// Report the budget a failed invocation had spent: printed to stderr,
+    /// since the run stops before its usual budget report, and kept for the
+    /// JSON error output.
+    pub fn report_failure(info: BudgetInfo) {
+        eprintln!("{}", Self::format_footer(&info));
+        crate::output::record_failure_budget(info);
+    }
+}
+
+/// `1234567` as `1,234,567`.
+pub(crate) fn group_digits(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
 }
 
 /// Severity level for budget warnings
@@ -179,7 +242,7 @@ pub struct BudgetWarning {
 }
 
 /// Budget information snapshot
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct BudgetInfo {
     pub cpu_instructions: u64,
     pub cpu_limit: u64,
@@ -217,6 +280,16 @@ impl BudgetInfo {
             memory_limit: self.memory_limit,
         }
     }
+
+    /// What `self` and `other` spent together, under `self`'s limits.
+    pub fn plus(&self, other: &BudgetInfo) -> BudgetInfo {
+        BudgetInfo {
+            cpu_instructions: self.cpu_instructions.saturating_add(other.cpu_instructions),
+            cpu_limit: self.cpu_limit,
+            memory_bytes: self.memory_bytes.saturating_add(other.memory_bytes),
+            memory_limit: self.memory_limit,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -235,6 +308,32 @@ mod tests {
         assert_eq!(info.memory_percentage(), 25.0);
     }
 
+    #[test]
+    fn footer_groups_digits_and_shows_the_share_of_the_limit() {
+        let info = BudgetInfo {
+            cpu_instructions: 1_234_567,
+            cpu_limit: 100_000_000,
+            memory_bytes: 48 * 1024,
+            memory_limit: 40 * 1024 * 1024,
+        };
+        assert_eq!(
+            BudgetInspector::format_footer(&info),
+            "Budget: 1,234,567 of 100,000,000 insns (1.2% of limit), 48.00 KB of 40.00 MB mem (0.1% of limit)"
+        );
+        let unlimited = BudgetInfo {
+            cpu_limit: u64::MAX,
+            memory_limit: u64::MAX,
+            ..info
+        };
+        assert_eq!(
+            BudgetInspector::format_footer(&unlimited),
+            "Budget: 1,234,567 insns (no limit), 48.00 KB mem (no limit)"
+        );
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(1_000), "1,000");
+    }
+
     #[test]
     fn test_check_thresholds_none() {
         let info = BudgetInfo {
@@ -287,6 +386,24 @@ mod tests {
         assert!(warnings[0].suggestion.is_some());
     }
 
+    #[test]
+    fn test_format_breakdown_rows() {
+        let setup = BudgetInfo {
+            cpu_instructions: 2_500_000,
+            memory_bytes: 600 * 1024,
+            ..BudgetInfo::default()
+        };
+        let execution = BudgetInfo {
+            cpu_instructions: 40_000,
+            memory_bytes: 512,
+            ..BudgetInfo::default()
+        };
+        assert_eq!(
+            BudgetInspector::format_breakdown(&setup, &execution),
+            "  setup           2.50M CPU   600.00 KB memory\n  execution      40.00K CPU       512 B memory"
+        );
+    }
+
     #[test]
     fn test_budget_delta_from_previous_snapshot() {
         let previous = BudgetInfo {
@@ -416,7 +533,7 @@ impl MemoryTracker {
 
     pub fn get_top_allocations(&self, count: usize) -> Vec<MemoryAllocation> {
         let mut sorted: Vec<MemoryAllocation> = self.allocations.iter().cloned().collect();
-        sorted.sort_by(|a, b| b.size.cmp(&a.size));
+        sorted.sort_by_key(|a| std::cmp::Reverse(a.size));
         sorted.into_iter().take(count).collect()
     }
 
@@ -0,0 +1,155 @@
+//! Per-function instruction cost table aggregated over many invocations.
+//!
+//! Answers "where does this workload spend its instructions" for a whole
+//! scenario or `--repeat` run, rather than for one call at a time.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Aggregated cost of one exported function.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostRow {
+    pub function: String,
+    pub calls: u64,
+    pub total_instructions: u64,
+    pub avg_instructions: u64,
+    /// Share of the table total, in percent.
+    pub share_percent: f64,
+}
+
+/// Rows sorted by total instructions, most expensive first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CostTable {
+    pub rows: Vec<CostRow>,
+    pub total_instructions: u64,
+    pub total_calls: u64,
+}
+
+/// Accumulates `(function, instructions)` samples into a [`CostTable`].
+#[derive(Debug, Clone, Default)]
+pub struct CostTableBuilder {
+    totals: HashMap<String, (u64, u64)>,
+}
+
+impl CostTableBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one invocation of `function` that cost `instructions`.
+    pub fn record(&mut self, function: &str, instructions: u64) {
+        let (calls, total) = self.totals.entry(function.to_string()).or_default();
+        *calls += 1;
+        *total = total.saturating_add(instructions);
+    }
+
+    /// Record every invocation made through an executor.
+    pub fn record_all<'a>(&mut self, costs: impl IntoIterator<Item = &'a (String, u64)>) {
+        for (function, instructions) in costs {
+            self.record(function, *instructions);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.totals.is_empty()
+    }
+
+    pub fn build(&self) -> CostTable {
+        let total_instructions = self
+            .totals
+            .values()
+            .fold(0u64, |acc, (_, total)| acc.saturating_add(*total));
+        let mut rows: Vec<CostRow> = self
+            .totals
+            .iter()
+            .map(|(function, &(calls, total))| CostRow {
+                function: function.clone(),
+                calls,
+                total_instructions: total,
+                avg_instructions: total / calls.max(1),
+                share_percent: if total_instructions == 0 {
+                    0.0
+                } else {
+                    total as f64 * 100.0 / total_instructions as f64
+                },
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.total_instructions
+                .cmp(&a.total_instructions)
+                .then_with(|| a.function.cmp(&b.function))
+        });
+        CostTable {
+            total_calls: rows.iter().map(|row| row.calls).sum(),
+            rows,
+            total_instructions,
+        }
+    }
+}
+
+impl CostTable {
+    /// Render the table as aligned text, one line per function plus a total line.
+    pub fn render(&self) -> String {
+        let name_width = self
+            .rows
+            .iter()
+            .map(|row| row.function.len())
+            .chain(std::iter::once("Function".len()))
+            .max()
+            .unwrap_or(0);
+        let mut out = format!(
+            "{:<name_width$}  {:>7}  {:>15}  {:>13}  {:>6}\n",
+            "Function", "Calls", "Total instr", "Avg instr", "Share"
+        );
+        out.push_str(&"-".repeat(name_width + 49));
+        out.push('\n');
+        for row in &self.rows {
+            out.push_str(&format!(
+                "{:<name_width$}  {:>7}  {:>15}  {:>13}  {:>5.1}%\n",
+                row.function,
+                row.calls,
+                row.total_instructions,
+                row.avg_instructions,
+                row.share_percent
+            ));
+        }
+        out.push_str(&format!(
+            "{:<name_width$}  {:>7}  {:>15}",
+            "TOTAL", self.total_calls, self.total_instructions
+        ));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_and_sorts_by_total_descending() {
+        let mut builder = CostTableBuilder::new();
+        builder.record("get", 100);
+        builder.record("increment", 400);
+        builder.record("get", 300);
+        builder.record("increment", 600);
+
+        let table = builder.build();
+        assert_eq!(table.total_instructions, 1_400);
+        assert_eq!(table.total_calls, 4);
+        assert_eq!(table.rows[0].function, "increment");
+        assert_eq!(table.rows[0].calls, 2);
+        assert_eq!(table.rows[0].avg_instructions, 500);
+        assert!((table.rows[0].share_percent - 71.43).abs() < 0.01);
+        assert_eq!(table.rows[1].function, "get");
+        assert_eq!(table.rows[1].avg_instructions, 200);
+    }
+
+    #[test]
+    fn zero_cost_calls_do_not_divide_by_zero() {
+        let mut builder = CostTableBuilder::new();
+        builder.record("noop", 0);
+        let table = builder.build();
+        assert_eq!(table.rows[0].share_percent, 0.0);
+        assert!(table.render().contains("noop"));
+    }
+}
@@ -0,0 +1,118 @@
+//! Every diagnostic event the host records in debug mode (`fn_call`,
+//! `fn_return`, `log`, `error`, ...) with topics and data decoded, for
+//! `--show diagnostics`.
+
+use crate::runtime::observer::symbol;
+use crate::utils::arguments::scval_to_json;
+use serde::Serialize;
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, ScAddress};
+
+/// One diagnostic event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiagnosticEvent {
+    /// The first topic, e.g. `fn_call`; empty when it is not a symbol.
+    pub kind: String,
+    /// The contract the event was recorded in; `None` for the host itself.
+    pub contract: Option<String>,
+    /// Whether the call that recorded it failed and was rolled back.
+    pub failed_call: bool,
+    pub topics: Vec<serde_json::Value>,
+    pub data: serde_json::Value,
+}
+
+/// The diagnostic events among `events`, in the order they were recorded.
+pub fn diagnostic_events(events: &[HostEvent]) -> Vec<DiagnosticEvent> {
+    events
+        .iter()
+        .filter(|event| event.event.type_ == ContractEventType::Diagnostic)
+        .map(|event| {
+            let ContractEventBody::V0(body) = &event.event.body;
+            DiagnosticEvent {
+                kind: body.topics.first().and_then(symbol).unwrap_or_default(),
+                contract: event
+                    .event
+                    .contract_id
+                    .as_ref()
+                    .map(|id| ScAddress::Contract(id.clone()).to_string()),
+                failed_call: event.failed_call,
+                topics: body.topics.iter().map(scval_to_json).collect(),
+                data: scval_to_json(&body.data),
+            }
+        })
+        .collect()
+}
+
+/// One line per event: `contract: topics data`, with events of failed calls
+/// marked.
+pub fn format_diagnostics(events: &[DiagnosticEvent]) -> Vec<String> {
+    events
+        .iter()
+        .map(|event| {
+            format!(
+                "{}: {} {}{}",
+                event.contract.as_deref().unwrap_or("host"),
+                serde_json::Value::Array(event.topics.clone()),
+                event.data,
+                if event.failed_call {
+                    " (failed call)"
+                } else {
+                    ""
+                }
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventV0, ExtensionPoint, Hash, ScSymbol, ScVal,
+    };
+
+    fn event(type_: ContractEventType, topics: Vec<ScVal>, failed_call: bool) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: Some(Hash([7; 32])),
+                type_,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data: ScVal::U32(5),
+                }),
+            },
+            failed_call,
+        }
+    }
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    #[test]
+    fn only_diagnostic_events_are_kept() {
+        let events = diagnostic_events(&[
+            event(
+                ContractEventType::Diagnostic,
+                vec![sym("fn_call"), sym("increment")],
+                false,
+            ),
+            event(ContractEventType::Contract, vec![sym("transfer")], false),
+            event(ContractEventType::Diagnostic, vec![sym("error")], true),
+        ]);
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, "fn_call");
+        assert_eq!(events[0].data, serde_json::json!(5));
+        assert!(events[1].failed_call);
+
+        let lines = format_diagnostics(&events);
+        assert!(
+            lines[0].ends_with(r#": ["fn_call","increment"] 5"#),
+            "{}",
+            lines[0]
+        );
+        assert!(lines[1].ends_with(" (failed call)"), "{}", lines[1]);
+    }
+}
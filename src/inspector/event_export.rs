@@ -0,0 +1,322 @@
+//! NDJSON export of events in the shape of the RPC `getEvents` response.
+//!
+//! Each line is one [`RpcEvent`]. The debugger has no real ledger history, so
+//! ids are synthesised from the simulated ledger sequence: every exported
+//! invocation counts as the next transaction in that ledger, which keeps ids
+//! (and therefore cursors) increasing across the steps of a scenario.
+
+use crate::utils::arguments::scval_to_json;
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, Limits, ScAddress, WriteXdr};
+use soroban_env_host::Host;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// One event as returned by RPC `getEvents`, plus decoded JSON for topics and value.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcEvent {
+    /// `contract` or `system`.
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub ledger: u32,
+    /// RFC 3339 close time derived from the simulated ledger timestamp.
+    pub ledger_closed_at: String,
+    pub contract_id: String,
+    pub id: String,
+    /// Same as `id`; kept for indexers that still page with it.
+    pub paging_token: String,
+    /// Topics as base64 XDR `ScVal`s.
+    pub topic: Vec<String>,
+    pub topic_json: Vec<serde_json::Value>,
+    /// Value as a base64 XDR `ScVal`.
+    pub value: String,
+    pub value_json: serde_json::Value,
+    pub in_successful_contract_call: bool,
+}
+
+/// Simulated ledger an invocation ran in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerPosition {
+    pub sequence: u32,
+    /// Unix timestamp in seconds.
+    pub timestamp: u64,
+}
+
+impl LedgerPosition {
+    /// The ledger `env` is currently simulating.
+    pub fn current(env: &soroban_sdk::Env) -> Self {
+        Self {
+            sequence: env.ledger().sequence(),
+            timestamp: env.ledger().timestamp(),
+        }
+    }
+}
+
+/// RPC event id: the operation's TOID, then the event's index within it.
+pub fn event_id(ledger: u32, transaction: u32, event_index: u32) -> String {
+    // TOID layout: 32 bits ledger, 20 bits transaction order, 12 bits operation index.
+    let toid = (u64::from(ledger) << 32) | (u64::from(transaction & 0xF_FFFF) << 12) | 1;
+    format!("{:019}-{:010}", toid, event_index)
+}
+
+/// Host events recorded so far, including diagnostic ones that are not exported.
+pub fn host_events(host: &Host) -> Result<Vec<HostEvent>> {
+    Ok(host
+        .get_events()
+        .map_err(|e| DebuggerError::ExecutionError(format!("Failed to get events: {}", e)))?
+        .0)
+}
+
+/// How many of the host's events will precede those of the next call. The
+/// host clears its events when a top-level invocation starts, so that is none
+/// unless a frame is already open.
+pub fn events_before_call(host: &Host) -> usize {
+    if host.has_frame().unwrap_or(false) {
+        host_events(host).map_or(0, |events| events.len())
+    } else {
+        0
+    }
+}
+
+/// Writes events as NDJSON, one invocation at a time.
+pub struct NdjsonEventWriter<W: Write> {
+    out: W,
+    /// Transaction order of the last exported invocation, per ledger.
+    transaction: u32,
+    ledger: Option<u32>,
+}
+
+impl<W: Write> NdjsonEventWriter<W> {
+    pub fn new(out: W) -> Self {
+        Self {
+            out,
+            transaction: 0,
+            ledger: None,
+        }
+    }
+
+    /// Append the contract and system events of one invocation and return how many were written.
+    pub fn write_invocation(
+        &mut self,
+        events: &[HostEvent],
+        ledger: LedgerPosition,
+    ) -> Result<usize> {
+        if self.ledger != Some(ledger.sequence) {
+            self.ledger = Some(ledger.sequence);
+            self.transaction = 0;
+        }
+        self.transaction += 1;
+
+        let mut written = 0;
+        for event in events {
+            let Some(rpc) = rpc_event(event, ledger, self.transaction, written as u32)? else {
+                continue;
+            };
+            let line = serde_json::to_string(&rpc).map_err(|e| {
+                DebuggerError::FileError(format!("Failed to serialize event: {}", e))
+            })?;
+            writeln!(self.out, "{}", line)
+                .map_err(|e| DebuggerError::FileError(format!("Failed to write event: {}", e)))?;
+            written += 1;
+        }
+        self.out
+            .flush()
+            .map_err(|e| DebuggerError::FileError(format!("Failed to write events: {}", e)))?;
+        Ok(written)
+    }
+}
+
+impl NdjsonEventWriter<BufWriter<File>> {
+    /// Create or truncate `path` for writing.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to create events file {:?}: {}", path, e))
+        })?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+fn rpc_event(
+    event: &HostEvent,
+    ledger: LedgerPosition,
+    transaction: u32,
+    event_index: u32,
+) -> Result<Option<RpcEvent>> {
+    let event_type = match event.event.type_ {
+        ContractEventType::Contract => "contract",
+        ContractEventType::System => "system",
+        ContractEventType::Diagnostic => return Ok(None),
+    };
+    let ContractEventBody::V0(body) = &event.event.body;
+    let encode = |val: &soroban_env_host::xdr::ScVal| {
+        val.to_xdr_base64(Limits::none()).map_err(|e| {
+            DebuggerError::ExecutionError(format!("Failed to encode event as XDR: {:?}", e))
+        })
+    };
+    let id = event_id(ledger.sequence, transaction, event_index);
+
+    Ok(Some(RpcEvent {
+        event_type: event_type.to_string(),
+        ledger: ledger.sequence,
+        ledger_closed_at: chrono::DateTime::from_timestamp(ledger.timestamp as i64, 0)
+            .unwrap_or_default()
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        contract_id: event
+            .event
+            .contract_id
+            .as_ref()
+            .map(|hash| ScAddress::Contract(hash.clone()).to_string())
+            .unwrap_or_default(),
+        paging_token: id.clone(),
+        id,
+        topic: body
+            .topics
+            .iter()
+            .map(encode)
+            .collect::<std::result::Result<_, DebuggerError>>()?,
+        topic_json: body.topics.iter().map(scval_to_json).collect(),
+        value: encode(&body.data)?,
+        value_json: scval_to_json(&body.data),
+        in_successful_contract_call: !event.failed_call,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64::Engine;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventV0, ExtensionPoint, Hash, ReadXdr, ScSymbol, ScVal,
+    };
+
+    /// Mirrors the RPC `getEvents` event object; unknown fields are rejected.
+    #[derive(Debug, Deserialize)]
+    #[serde(rename_all = "camelCase", deny_unknown_fields)]
+    struct GetEventsEvent {
+        #[serde(rename = "type")]
+        event_type: String,
+        ledger: u32,
+        ledger_closed_at: String,
+        contract_id: String,
+        id: String,
+        paging_token: String,
+        topic: Vec<String>,
+        value: String,
+        in_successful_contract_call: bool,
+        // Debugger extension: decoded copies of `topic` and `value`.
+        topic_json: Vec<serde_json::Value>,
+        value_json: serde_json::Value,
+    }
+
+    fn host_event(type_: ContractEventType, topic: &str, value: u32) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: Some(Hash([7; 32])),
+                type_,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: vec![ScVal::Symbol(ScSymbol(topic.try_into().unwrap()))]
+                        .try_into()
+                        .unwrap(),
+                    data: ScVal::U32(value),
+                }),
+            },
+            failed_call: false,
+        }
+    }
+
+    fn export(steps: &[(Vec<HostEvent>, LedgerPosition)]) -> Vec<GetEventsEvent> {
+        let mut out = Vec::new();
+        let mut writer = NdjsonEventWriter::new(&mut out);
+        for (events, ledger) in steps {
+            writer.write_invocation(events, *ledger).unwrap();
+        }
+        String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("line matches the RPC event schema"))
+            .collect()
+    }
+
+    #[test]
+    fn lines_match_the_rpc_event_schema() {
+        let ledger = LedgerPosition {
+            sequence: 1234,
+            timestamp: 1_700_000_000,
+        };
+        let lines = export(&[(
+            vec![
+                host_event(ContractEventType::Contract, "transfer", 50),
+                host_event(ContractEventType::Diagnostic, "fn_call", 0),
+            ],
+            ledger,
+        )]);
+
+        assert_eq!(lines.len(), 1, "diagnostic events are not exported");
+        let event = &lines[0];
+        assert_eq!(event.event_type, "contract");
+        assert_eq!(event.ledger, 1234);
+        assert_eq!(event.ledger_closed_at, "2023-11-14T22:13:20Z");
+        assert!(event.contract_id.starts_with('C'));
+        assert_eq!(event.id, event.paging_token);
+        assert!(event.in_successful_contract_call);
+
+        let topic = ScVal::from_xdr_base64(&event.topic[0], Limits::none()).unwrap();
+        assert_eq!(
+            topic,
+            ScVal::Symbol(ScSymbol("transfer".try_into().unwrap()))
+        );
+        assert_eq!(event.topic_json, vec![serde_json::json!("transfer")]);
+        let value = base64::engine::general_purpose::STANDARD
+            .decode(&event.value)
+            .unwrap();
+        assert_eq!(
+            ScVal::from_xdr(value, Limits::none()).unwrap(),
+            ScVal::U32(50)
+        );
+        assert_eq!(event.value_json, serde_json::json!(50));
+    }
+
+    #[test]
+    fn cursors_increase_across_steps() {
+        let same_ledger = LedgerPosition {
+            sequence: 10,
+            timestamp: 0,
+        };
+        let next_ledger = LedgerPosition {
+            sequence: 11,
+            timestamp: 5,
+        };
+        let lines = export(&[
+            (
+                vec![
+                    host_event(ContractEventType::Contract, "a", 1),
+                    host_event(ContractEventType::Contract, "b", 2),
+                ],
+                same_ledger,
+            ),
+            (vec![], same_ledger),
+            (
+                vec![host_event(ContractEventType::System, "c", 3)],
+                same_ledger,
+            ),
+            (
+                vec![host_event(ContractEventType::Contract, "d", 4)],
+                next_ledger,
+            ),
+        ]);
+
+        let ids: Vec<&str> = lines.iter().map(|event| event.id.as_str()).collect();
+        assert_eq!(ids.len(), 4);
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", ids);
+        assert_eq!(ids[0], event_id(10, 1, 0));
+        assert_eq!(ids[1], event_id(10, 1, 1));
+        assert_eq!(ids[2], event_id(10, 3, 0));
+        assert_eq!(ids[3], event_id(11, 1, 0));
+        assert_eq!(lines[2].event_type, "system");
+    }
+}
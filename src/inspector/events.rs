@@ -1,6 +1,8 @@
+use crate::inspector::render_cache::RenderCache;
 use crate::{DebuggerError, Result};
 use serde::{Deserialize, Serialize};
-use soroban_env_host::{xdr::ContractEventBody, Host};
+use soroban_env_host::xdr::{ContractEventBody, ScAddress};
+use soroban_env_host::Host;
 
 /// Represents a captured contract event
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +10,10 @@ pub struct ContractEvent {
     /// Contract id that emitted the event (if present)
     pub contract_id: Option<String>,
 
+    /// Name of the emitting contract when it was linked with `--link`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+
     /// Event topics (ordered)
     pub topics: Vec<String>,
 
@@ -20,39 +26,58 @@ pub struct EventInspector;
 impl EventInspector {
     /// Extract events from the host and convert them to a friendly format
     pub fn get_events(host: &Host) -> Result<Vec<ContractEvent>> {
+        // Topics repeat heavily across large event streams; render each once.
+        Self::get_events_with_cache(host, &mut RenderCache::new())
+    }
+
+    /// [`Self::get_events`] through a shared `cache`, which also names
+    /// events of linked contracts.
+    pub fn get_events_with_cache(
+        host: &Host,
+        cache: &mut RenderCache,
+    ) -> Result<Vec<ContractEvent>> {
         let events = host
             .get_events()
             .map_err(|e| DebuggerError::ExecutionError(format!("Failed to get events: {}", e)))?
             .0;
-        let mut contract_events = Vec::new();
-
-        for host_event in events.iter() {
-            let event = &host_event.event;
-
-            // Extract topics and data from event body
-            let (topics, data) = match &event.body {
-                ContractEventBody::V0(v0) => {
-                    let mut topics = Vec::new();
-                    for topic in v0.topics.iter() {
-                        topics.push(format!("{:?}", topic));
-                    }
-                    let data = format!("{:?}", v0.data);
-                    (topics, data)
-                }
-            };
-
-            // Parse contract ID
-            // contract_id is Option<Hash>
-            let contract_id = event.contract_id.as_ref().map(|h| format!("{:?}", h));
+        Ok(events
+            .iter()
+            .map(|host_event| Self::convert(&host_event.event, cache))
+            .collect())
+    }
 
-            contract_events.push(ContractEvent {
-                contract_id,
-                topics,
-                data,
-            });
+    /// Render one host event, sharing `cache` across a stream of events.
+    pub fn convert(
+        event: &soroban_env_host::xdr::ContractEvent,
+        cache: &mut RenderCache,
+    ) -> ContractEvent {
+        // Extract topics and data from event body
+        let (topics, data) = match &event.body {
+            ContractEventBody::V0(v0) => {
+                let mut topics = Vec::new();
+                for topic in v0.topics.iter() {
+                    topics.push(cache.render_val(topic).to_string());
+                }
+                let data = cache.render_val(&v0.data).to_string();
+                (topics, data)
+            }
+        };
+
+        // Parse contract ID
+        // contract_id is Option<Hash>
+        let contract_id = event.contract_id.as_ref().map(|h| format!("{:?}", h));
+        let contract = event.contract_id.as_ref().and_then(|h| {
+            cache
+                .contract_label(&ScAddress::Contract(h.clone()))
+                .map(str::to_string)
+        });
+
+        ContractEvent {
+            contract_id,
+            contract,
+            topics,
+            data,
         }
-
-        Ok(contract_events)
     }
 
     /// Filter events by topic substring. If `topic_filter` is empty,
@@ -82,10 +107,11 @@ impl EventInspector {
         let mut out = Vec::new();
         for (i, ev) in events.iter().enumerate() {
             out.push(format!("Event #{}:", i));
-            out.push(format!(
-                "  Contract: {}",
-                ev.contract_id.as_deref().unwrap_or("<none>")
-            ));
+            let contract_id = ev.contract_id.as_deref().unwrap_or("<none>");
+            match &ev.contract {
+                Some(name) => out.push(format!("  Contract: {} ({})", name, contract_id)),
+                None => out.push(format!("  Contract: {}", contract_id)),
+            }
             out.push(format!("  Topics: {:?}", ev.topics));
             out.push(format!("  Data: {}", ev.data));
         }
@@ -97,11 +123,15 @@ impl EventInspector {
         let arr: Vec<serde_json::Value> = events
             .iter()
             .map(|e| {
-                serde_json::json!({
+                let mut event = serde_json::json!({
                     "contract_id": e.contract_id,
                     "topics": e.topics,
                     "data": e.data,
-                })
+                });
+                if let Some(name) = &e.contract {
+                    event["contract"] = serde_json::json!(name);
+                }
+                event
             })
             .collect();
         serde_json::Value::Array(arr)
@@ -122,16 +152,19 @@ mod tests {
         let events = vec![
             ContractEvent {
                 contract_id: None,
+                contract: None,
                 topics: vec!["topic1".to_string(), "common".to_string()],
                 data: "data1".to_string(),
             },
             ContractEvent {
                 contract_id: None,
+                contract: None,
                 topics: vec!["topic2".to_string(), "common".to_string()],
                 data: "data2".to_string(),
             },
             ContractEvent {
                 contract_id: None,
+                contract: None,
                 topics: vec!["topic3".to_string()],
                 data: "data3".to_string(),
             },
@@ -153,11 +186,13 @@ mod tests {
         let events = vec![
             ContractEvent {
                 contract_id: None,
+                contract: None,
                 topics: vec!["topic1".to_string()],
                 data: "data1".to_string(),
             },
             ContractEvent {
                 contract_id: None,
+                contract: None,
                 topics: vec!["topic2".to_string()],
                 data: "data2".to_string(),
             },
@@ -0,0 +1,164 @@
+//! The ledger entries an invocation read and wrote, split the way a
+//! transaction footprint lists them, for `--show ledger`.
+
+use crate::utils::arguments::scval_to_json;
+use serde::Serialize;
+use soroban_env_host::storage::AccessType;
+use soroban_env_host::xdr::{
+    ContractDataDurability, LedgerKey, Limits, ScAddress, ScVal, WriteXdr,
+};
+
+/// One footprint entry, decoded and as XDR.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FootprintEntry {
+    /// `contract_data`, `contract_instance`, `contract_code`, `account`, or
+    /// the XDR name of any other ledger key kind.
+    pub kind: String,
+    /// Contract owning the data or instance, or the account.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// `persistent` or `temporary`, for contract data.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub durability: Option<String>,
+    /// The data key in `--args` syntax, or the hash of contract code.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<serde_json::Value>,
+    /// Base64 `LedgerKey` XDR, as transaction builders take it.
+    pub xdr: String,
+}
+
+/// Read-only and read-write entries of one invocation.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct InvocationFootprint {
+    pub read_only: Vec<FootprintEntry>,
+    pub read_write: Vec<FootprintEntry>,
+}
+
+impl InvocationFootprint {
+    /// Split recorded accesses into the two sets; a key that was written is
+    /// only listed as read-write.
+    pub fn from_accesses<'a>(
+        accesses: impl IntoIterator<Item = (&'a LedgerKey, AccessType)>,
+    ) -> Self {
+        let mut footprint = Self::default();
+        for (key, access) in accesses {
+            let entry = FootprintEntry::decode(key);
+            match access {
+                AccessType::ReadOnly => footprint.read_only.push(entry),
+                AccessType::ReadWrite => footprint.read_write.push(entry),
+            }
+        }
+        footprint
+    }
+
+    /// Both sets, one entry per line under a count.
+    pub fn format(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (label, entries) in [
+            ("Read-only", &self.read_only),
+            ("Read-write", &self.read_write),
+        ] {
+            lines.push(format!("{} ({}):", label, entries.len()));
+            lines.extend(entries.iter().map(|entry| format!("  {}", entry)));
+        }
+        lines
+    }
+}
+
+impl FootprintEntry {
+    fn decode(key: &LedgerKey) -> Self {
+        let mut entry = Self {
+            kind: key.name().to_ascii_lowercase(),
+            address: None,
+            durability: None,
+            key: None,
+            xdr: key.to_xdr_base64(Limits::none()).unwrap_or_default(),
+        };
+        match key {
+            LedgerKey::ContractData(data) => {
+                entry.address = Some(data.contract.to_string());
+                if data.key == ScVal::LedgerKeyContractInstance {
+                    entry.kind = "contract_instance".to_string();
+                } else {
+                    entry.kind = "contract_data".to_string();
+                    entry.durability = Some(
+                        match data.durability {
+                            ContractDataDurability::Persistent => "persistent",
+                            ContractDataDurability::Temporary => "temporary",
+                        }
+                        .to_string(),
+                    );
+                    entry.key = Some(scval_to_json(&data.key));
+                }
+            }
+            LedgerKey::ContractCode(code) => {
+                entry.kind = "contract_code".to_string();
+                entry.key = Some(serde_json::json!(hex::encode(code.hash.0)));
+            }
+            LedgerKey::Account(account) => {
+                entry.address = Some(ScAddress::Account(account.account_id.clone()).to_string());
+            }
+            _ => {}
+        }
+        entry
+    }
+}
+
+impl std::fmt::Display for FootprintEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)?;
+        for part in [&self.address, &self.durability].into_iter().flatten() {
+            write!(f, " {}", part)?;
+        }
+        if let Some(key) = &self.key {
+            write!(f, " {}", key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Hash, LedgerKeyContractCode, LedgerKeyContractData, ReadXdr};
+
+    fn data(key: ScVal) -> LedgerKey {
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::Contract(Hash([1; 32])),
+            key,
+            durability: ContractDataDurability::Persistent,
+        })
+    }
+
+    #[test]
+    fn accesses_split_into_read_only_and_read_write() {
+        let instance = data(ScVal::LedgerKeyContractInstance);
+        let code = LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: Hash([2; 32]),
+        });
+        let balance = data(ScVal::U32(7));
+        let footprint = InvocationFootprint::from_accesses([
+            (&instance, AccessType::ReadOnly),
+            (&code, AccessType::ReadOnly),
+            (&balance, AccessType::ReadWrite),
+        ]);
+
+        let kinds =
+            |entries: &[FootprintEntry]| entries.iter().map(|e| e.kind.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds(&footprint.read_only),
+            ["contract_instance", "contract_code"]
+        );
+        assert_eq!(kinds(&footprint.read_write), ["contract_data"]);
+
+        let written = &footprint.read_write[0];
+        assert_eq!(written.key, Some(serde_json::json!(7)));
+        assert_eq!(
+            LedgerKey::from_xdr_base64(&written.xdr, Limits::none()).unwrap(),
+            balance
+        );
+        assert!(written.to_string().starts_with("contract_data C"));
+        assert!(written.to_string().ends_with(" persistent 7"));
+        assert_eq!(footprint.format()[0], "Read-only (2):");
+    }
+}
@@ -0,0 +1,176 @@
+//! Contract calls made through the host, read back from the `fn_call` and
+//! `fn_return` diagnostic events it records in debug mode.
+
+use crate::runtime::observer::{
+    invocation_notifications, replay, CallEnd, CallStart, InvocationObserver, ObserverResult,
+};
+use serde::Serialize;
+use soroban_env_host::events::HostEvent;
+
+/// One contract call, nested by `depth`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HostCall {
+    /// 0 for the top-level invocation.
+    pub depth: usize,
+    pub contract: String,
+    pub function: String,
+    pub args: serde_json::Value,
+    /// The return value; `None` if the call trapped.
+    pub result: Option<serde_json::Value>,
+}
+
+/// Collects the calls it observes, in call order.
+#[derive(Debug, Default)]
+pub struct HostCallRecorder {
+    calls: Vec<HostCall>,
+    open: Vec<usize>,
+}
+
+impl HostCallRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn calls(&self) -> &[HostCall] {
+        &self.calls
+    }
+
+    pub fn into_calls(self) -> Vec<HostCall> {
+        self.calls
+    }
+}
+
+impl InvocationObserver for HostCallRecorder {
+    fn on_call_start(&mut self, call: &CallStart) -> ObserverResult {
+        self.open.push(self.calls.len());
+        self.calls.push(HostCall {
+            depth: call.depth,
+            contract: call.contract.clone(),
+            function: call.function.clone(),
+            args: call.args.clone(),
+            result: None,
+        });
+        Ok(())
+    }
+
+    fn on_call_end(&mut self, call: &CallEnd) -> ObserverResult {
+        if let Some(index) = self.open.pop() {
+            self.calls[index].result = call.result.clone();
+        }
+        Ok(())
+    }
+}
+
+/// Pair up `fn_call` and `fn_return` events into calls, in call order.
+pub fn host_calls(events: &[HostEvent]) -> Vec<HostCall> {
+    let mut recorder = HostCallRecorder::new();
+    // The recorder never fails.
+    let _ = replay(&invocation_notifications(events, Vec::new()), &mut recorder);
+    recorder.into_calls()
+}
+
+/// One indented line per call: `contract.function(args) -> result`.
+pub fn format_calls(calls: &[HostCall]) -> Vec<String> {
+    calls
+        .iter()
+        .map(|call| {
+            let args = match &call.args {
+                serde_json::Value::Array(items) => items
+                    .iter()
+                    .map(|item| item.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                serde_json::Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            let result = call
+                .result
+                .as_ref()
+                .map_or_else(|| "trapped".to_string(), |value| value.to_string());
+            format!(
+                "{}{}.{}({}) -> {}",
+                "  ".repeat(call.depth),
+                call.contract,
+                call.function,
+                args,
+                result
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ExtensionPoint,
+        ScSymbol, ScVal,
+    };
+
+    fn diagnostic(topics: Vec<ScVal>, data: ScVal) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: None,
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data,
+                }),
+            },
+            failed_call: false,
+        }
+    }
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn call(contract: u8, function: &str, args: Vec<ScVal>) -> HostEvent {
+        diagnostic(
+            vec![
+                sym("fn_call"),
+                ScVal::Bytes(vec![contract; 32].try_into().unwrap()),
+                sym(function),
+            ],
+            ScVal::Vec(Some(args.try_into().unwrap())),
+        )
+    }
+
+    fn ret(function: &str, value: ScVal) -> HostEvent {
+        diagnostic(vec![sym("fn_return"), sym(function)], value)
+    }
+
+    #[test]
+    fn nested_calls_pair_with_their_returns() {
+        let calls = host_calls(&[
+            call(1, "swap", vec![ScVal::U32(5)]),
+            call(2, "transfer", vec![ScVal::U32(5), ScVal::Bool(true)]),
+            ret("transfer", ScVal::Void),
+            diagnostic(vec![sym("log")], ScVal::U32(0)),
+            ret("swap", ScVal::U32(4)),
+        ]);
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!((calls[0].depth, calls[1].depth), (0, 1));
+        assert!(calls[0].contract.starts_with('C'));
+        assert_eq!(calls[1].function, "transfer");
+        assert_eq!(calls[0].result, Some(serde_json::json!(4)));
+
+        let lines = format_calls(&calls);
+        assert!(lines[0].ends_with(".swap(5) -> 4"), "{}", lines[0]);
+        assert!(lines[1].starts_with("  C"), "{}", lines[1]);
+        assert!(
+            lines[1].ends_with(".transfer(5, true) -> null"),
+            "{}",
+            lines[1]
+        );
+    }
+
+    #[test]
+    fn calls_without_a_return_trapped() {
+        let calls = host_calls(&[call(1, "withdraw", vec![])]);
+        assert_eq!(calls[0].result, None);
+        assert!(format_calls(&calls)[0].ends_with(".withdraw() -> trapped"));
+    }
+}
@@ -0,0 +1,136 @@
+//! Messages contracts log with `log!`, read back from the `log` diagnostic
+//! events the host records in debug mode.
+
+use crate::debugger::host_fn::HOST_FN_LOG_PREFIX;
+use crate::runtime::observer::symbol;
+use crate::utils::arguments::scval_to_json;
+use serde::Serialize;
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, ScAddress, ScVal};
+
+/// One `log!` call.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContractLog {
+    /// The logging contract; `None` when logged outside any contract.
+    pub contract: Option<String>,
+    pub message: String,
+    pub args: Vec<serde_json::Value>,
+}
+
+/// The `log` diagnostic events among `events`, in the order they were logged.
+pub fn contract_logs(events: &[HostEvent]) -> Vec<ContractLog> {
+    events
+        .iter()
+        .filter(|event| event.event.type_ == ContractEventType::Diagnostic)
+        .filter_map(|event| {
+            let ContractEventBody::V0(body) = &event.event.body;
+            if body.topics.first().and_then(symbol).as_deref() != Some("log") {
+                return None;
+            }
+            // The host sends the message alone, or a vector of the message
+            // followed by the arguments.
+            let (message, args) = match &body.data {
+                ScVal::Vec(Some(items)) => match items.split_first() {
+                    Some((message, args)) => (message, args),
+                    None => return None,
+                },
+                message => (message, &[][..]),
+            };
+            let message = match message {
+                ScVal::String(message) => message.to_utf8_string_lossy(),
+                other => scval_to_json(other).to_string(),
+            };
+            // Calls `--report-host-fn` logs are the debugger's, not the
+            // contract's.
+            if message.starts_with(HOST_FN_LOG_PREFIX) {
+                return None;
+            }
+            Some(ContractLog {
+                contract: event
+                    .event
+                    .contract_id
+                    .as_ref()
+                    .map(|id| ScAddress::Contract(id.clone()).to_string()),
+                message,
+                args: args.iter().map(scval_to_json).collect(),
+            })
+        })
+        .collect()
+}
+
+/// One line per log: `contract: message args`.
+pub fn format_logs(logs: &[ContractLog]) -> Vec<String> {
+    logs.iter()
+        .map(|log| {
+            let mut line = format!(
+                "{}: {}",
+                log.contract.as_deref().unwrap_or("host"),
+                log.message
+            );
+            for arg in &log.args {
+                line.push(' ');
+                line.push_str(&arg.to_string());
+            }
+            line
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventV0, ExtensionPoint, Hash, ScString, ScSymbol,
+    };
+
+    fn diagnostic(topics: Vec<ScVal>, data: ScVal) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: Some(Hash([7; 32])),
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data,
+                }),
+            },
+            failed_call: false,
+        }
+    }
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn string(text: &str) -> ScVal {
+        ScVal::String(ScString(text.try_into().unwrap()))
+    }
+
+    #[test]
+    fn log_events_become_messages_with_arguments() {
+        let logs = contract_logs(&[
+            diagnostic(vec![sym("log")], string("starting")),
+            diagnostic(vec![sym("fn_call")], ScVal::Void),
+            diagnostic(
+                vec![sym("log")],
+                string("soroban-debug host fn: get_ledger_timestamp"),
+            ),
+            diagnostic(
+                vec![sym("log")],
+                ScVal::Vec(Some(
+                    vec![string("balance"), ScVal::U32(5)].try_into().unwrap(),
+                )),
+            ),
+        ]);
+
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].message, "starting");
+        assert!(logs[0].args.is_empty());
+        assert_eq!(logs[1].args, vec![serde_json::json!(5)]);
+
+        let lines = format_logs(&logs);
+        assert!(lines[0].starts_with('C'), "{}", lines[0]);
+        assert!(lines[0].ends_with(": starting"), "{}", lines[0]);
+        assert!(lines[1].ends_with(": balance 5"), "{}", lines[1]);
+    }
+}
@@ -1,14 +1,33 @@
 pub mod auth;
+pub mod auth_export;
 pub mod budget;
+pub mod cost_table;
+pub mod diagnostics;
+pub mod event_export;
 pub mod events;
+pub mod footprint;
+pub mod host_calls;
 pub mod instructions;
 pub mod ledger;
+pub mod logs;
+pub mod panic;
+pub mod render_cache;
 pub mod stack;
 pub mod storage;
+pub mod storage_decode;
+pub mod storage_dump;
+pub mod storage_heatmap;
+pub mod storage_history;
+pub mod storage_provenance;
+pub mod storage_rent;
 
 pub use auth::AuthInspector;
 pub use budget::{BudgetInfo, BudgetInspector, MemorySummary, MemoryTracker};
+pub use cost_table::{CostTable, CostTableBuilder};
 pub use instructions::{FunctionInstructionCount, InstructionCounter};
 pub use ledger::LedgerEntryInspector;
+pub use render_cache::RenderCache;
 pub use stack::CallStackInspector;
 pub use storage::{StorageFilter, StorageInspector};
+pub use storage_heatmap::StorageHeatmap;
+pub use storage_provenance::StorageProvenance;
@@ -0,0 +1,172 @@
+//! What a contract panicked with, read back from the diagnostic events the
+//! host records for the failed call.
+//!
+//! `panic_with_error!` leaves an `error` event carrying the contract error
+//! code. A `panic!` message survives only when the host catches the panic
+//! itself and logs `caught panic '...'`, which it does for contracts running
+//! natively; a WASM contract traps and its message never reaches the host.
+
+use crate::debugger::error_db::ErrorDatabase;
+use crate::runtime::observer::symbol;
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, ScError, ScVal};
+
+/// Prefix and suffix of the `log` message the host records for a caught panic.
+const CAUGHT_PANIC: (&str, &str) = ("caught panic '", "' from contract function ");
+
+/// Message of the `error` event `fail_with_error` records.
+const FAILING_WITH_CONTRACT_ERROR: &str = "failing with contract error";
+
+/// Why a contract panicked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GuestPanic {
+    /// `panic!` with this message.
+    Message(String),
+    /// `panic_with_error!` with this contract error code.
+    ContractError(u32),
+}
+
+impl GuestPanic {
+    /// The message to show, with contract errors named from the spec.
+    pub fn describe(&self, error_db: &ErrorDatabase) -> String {
+        match self {
+            Self::Message(message) => message.clone(),
+            Self::ContractError(code) => error_db.contract_error(*code).to_string(),
+        }
+    }
+}
+
+/// The last panic recorded among `events`, which should be those of a single
+/// failed invocation.
+pub fn guest_panic(events: &[HostEvent]) -> Option<GuestPanic> {
+    events
+        .iter()
+        .rev()
+        .filter(|event| event.event.type_ == ContractEventType::Diagnostic)
+        .find_map(|event| {
+            let ContractEventBody::V0(body) = &event.event.body;
+            // The host sends the message alone, or a vector of the message
+            // followed by the arguments.
+            let message = match &body.data {
+                ScVal::Vec(Some(items)) => items.first(),
+                message => Some(message),
+            };
+            let message = match message {
+                Some(ScVal::String(message)) => message.to_utf8_string_lossy(),
+                _ => return None,
+            };
+            match body.topics.first().and_then(symbol).as_deref() {
+                Some("log") => {
+                    let (prefix, suffix) = CAUGHT_PANIC;
+                    let (text, _) = message.strip_prefix(prefix)?.rsplit_once(suffix)?;
+                    Some(GuestPanic::Message(text.to_string()))
+                }
+                Some("error") if message == FAILING_WITH_CONTRACT_ERROR => {
+                    match body.topics.get(1) {
+                        Some(ScVal::Error(ScError::Contract(code))) => {
+                            Some(GuestPanic::ContractError(*code))
+                        }
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventV0, ExtensionPoint, Hash, ScErrorCode, ScString, ScSymbol,
+    };
+
+    fn diagnostic(topics: Vec<ScVal>, data: ScVal) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: Some(Hash([7; 32])),
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data,
+                }),
+            },
+            failed_call: true,
+        }
+    }
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn string(text: &str) -> ScVal {
+        ScVal::String(ScString(text.try_into().unwrap()))
+    }
+
+    #[test]
+    fn panic_message_is_taken_from_the_caught_panic_log() {
+        let events = [
+            diagnostic(vec![sym("log")], string("checking balance")),
+            diagnostic(
+                vec![sym("log")],
+                ScVal::Vec(Some(
+                    vec![
+                        string(
+                            "caught panic 'insufficient balance: 3 < 10' from contract function 'Symbol(transfer)'",
+                        ),
+                        ScVal::U32(3),
+                    ]
+                    .try_into()
+                    .unwrap(),
+                )),
+            ),
+            diagnostic(
+                vec![
+                    sym("error"),
+                    ScVal::Error(ScError::WasmVm(ScErrorCode::InvalidAction)),
+                ],
+                string("caught error from function"),
+            ),
+        ];
+        assert_eq!(
+            guest_panic(&events),
+            Some(GuestPanic::Message(
+                "insufficient balance: 3 < 10".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn panic_with_error_gives_the_contract_error() {
+        let events = [diagnostic(
+            vec![sym("error"), ScVal::Error(ScError::Contract(4))],
+            ScVal::Vec(Some(
+                vec![string(FAILING_WITH_CONTRACT_ERROR), ScVal::U32(4)]
+                    .try_into()
+                    .unwrap(),
+            )),
+        )];
+        let panic = guest_panic(&events).unwrap();
+        assert_eq!(panic, GuestPanic::ContractError(4));
+        assert_eq!(panic.describe(&ErrorDatabase::new()), "#4");
+    }
+
+    #[test]
+    fn returned_errors_and_traps_are_not_panics() {
+        let events = [
+            diagnostic(
+                vec![sym("error"), ScVal::Error(ScError::Contract(4))],
+                string("escalating Ok(ScErrorType::Contract) frame-exit to Err"),
+            ),
+            diagnostic(
+                vec![
+                    sym("error"),
+                    ScVal::Error(ScError::WasmVm(ScErrorCode::InvalidAction)),
+                ],
+                string("VM call trapped: UnreachableCodeReached"),
+            ),
+        ];
+        assert_eq!(guest_panic(&events), None);
+    }
+}
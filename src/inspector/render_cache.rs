@@ -0,0 +1,347 @@
+//! Memoized rendering of `ScVal`s for large storage and event dumps.
+//!
+//! Rendering tens of thousands of storage entries spends most of its time
+//! re-formatting the same addresses. [`RenderCache`] formats each distinct
+//! address once and hands out shared `Rc<str>` copies afterwards, and writes
+//! the common scalars without going through the derived `Debug` machinery.
+//! Whole values are not cached: hashing and cloning a value to key it costs
+//! more than writing it out, so each value is rendered in one pass and
+//! callers keep the rendering they need, such as the canonical key stored on
+//! a [`StorageEntry`](crate::inspector::storage::StorageEntry).
+//!
+//! The output is byte-for-byte identical to `format!("{:?}", val)` so callers
+//! can switch between the naive and memoized paths freely. Values nested
+//! deeper than [`MEMOIZE_MAX_DEPTH`] go through the iterative, depth-limited
+//! renderer instead.
+
+use crate::utils::value_depth::{max_value_depth, render_scval, scval_exceeds_depth};
+use soroban_env_host::xdr::{ContractDataDurability, ScAddress, ScVal};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::rc::Rc;
+
+/// Upper bound on interned addresses before the cache is reset.
+///
+/// Keeps memory bounded when a single session renders millions of distinct
+/// addresses; a reset only costs re-formatting, never correctness.
+pub const DEFAULT_RENDER_CACHE_CAPACITY: usize = 200_000;
+
+/// Deepest value rendered through the memoized, recursive path.
+pub const MEMOIZE_MAX_DEPTH: usize = 64;
+
+/// Interning cache for rendered addresses.
+#[derive(Debug)]
+pub struct RenderCache {
+    addresses: HashMap<ScAddress, Rc<str>>,
+    /// Names of contracts linked with `--link`; not an interned rendering, so
+    /// [`Self::clear`] keeps them.
+    contract_labels: HashMap<ScAddress, Rc<str>>,
+    capacity: usize,
+    hits: u64,
+    misses: u64,
+}
+
+impl RenderCache {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_RENDER_CACHE_CAPACITY)
+    }
+
+    /// Create a cache that resets itself after holding `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            addresses: HashMap::new(),
+            contract_labels: HashMap::new(),
+            capacity: capacity.max(1),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Render a value, reusing earlier renderings of the addresses inside it.
+    pub fn render_val(&mut self, val: &ScVal) -> Rc<str> {
+        let mut out = String::new();
+        if !self.write_val(&mut out, val, 1) {
+            return Rc::from(render_scval(val, max_value_depth()));
+        }
+        Rc::from(out)
+    }
+
+    /// Render an address, formatting each distinct address only once.
+    pub fn render_address(&mut self, address: &ScAddress) -> Rc<str> {
+        if let Some(hit) = self.addresses.get(address) {
+            self.hits += 1;
+            return Rc::clone(hit);
+        }
+        self.misses += 1;
+        let rendered: Rc<str> = Rc::from(format!("{:?}", address));
+        self.evict_if_full();
+        self.addresses.insert(address.clone(), Rc::clone(&rendered));
+        rendered
+    }
+
+    /// Canonical storage key rendering used by the storage inspector.
+    pub fn render_storage_key(
+        &mut self,
+        durability: ContractDataDurability,
+        key: &ScVal,
+    ) -> String {
+        format!("contract_data:{:?}:{}", durability, self.render_val(key))
+    }
+
+    /// Name the storage and events of the contract at `address` after `label`.
+    pub fn set_contract_label(&mut self, address: ScAddress, label: &str) {
+        self.contract_labels.insert(address, Rc::from(label));
+    }
+
+    /// The label given to the contract at `address`, if any.
+    pub fn contract_label(&self, address: &ScAddress) -> Option<&str> {
+        self.contract_labels.get(address).map(|label| &**label)
+    }
+
+    /// Number of lookups served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Number of lookups that had to format a fresh address.
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Number of interned addresses.
+    pub fn len(&self) -> usize {
+        self.addresses.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.addresses.clear();
+    }
+
+    fn evict_if_full(&mut self) {
+        if self.len() >= self.capacity {
+            self.clear();
+        }
+    }
+
+    /// Mirrors the derived `Debug` output of `ScVal` at nesting `depth`.
+    /// Returns `false`, leaving `out` partly written, once the value nests
+    /// deeper than [`MEMOIZE_MAX_DEPTH`].
+    fn write_val(&mut self, out: &mut String, val: &ScVal, depth: usize) -> bool {
+        if depth > MEMOIZE_MAX_DEPTH {
+            return false;
+        }
+        match val {
+            ScVal::Address(address) => {
+                let rendered = self.render_address(address);
+                let _ = write!(out, "Address({})", rendered);
+            }
+            ScVal::Vec(Some(items)) => {
+                out.push_str("Vec(Some(ScVec(VecM([");
+                for (i, item) in items.0.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    if !self.write_val(out, item, depth + 1) {
+                        return false;
+                    }
+                }
+                out.push_str("]))))");
+            }
+            ScVal::Map(Some(entries)) => {
+                out.push_str("Map(Some(ScMap(VecM([");
+                for (i, entry) in entries.0.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    out.push_str("ScMapEntry { key: ");
+                    if !self.write_val(out, &entry.key, depth + 1) {
+                        return false;
+                    }
+                    out.push_str(", val: ");
+                    if !self.write_val(out, &entry.val, depth + 1) {
+                        return false;
+                    }
+                    out.push_str(" }");
+                }
+                out.push_str("]))))");
+            }
+            // Instance storage nests like a map but is written by `Debug`,
+            // which would recurse through all of it.
+            ScVal::ContractInstance(_)
+                if scval_exceeds_depth(val, MEMOIZE_MAX_DEPTH - depth + 1) =>
+            {
+                return false
+            }
+            ScVal::Symbol(symbol) => match std::str::from_utf8(symbol.0.as_slice()) {
+                Ok(name) if name.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'_') => {
+                    out.push_str("Symbol(ScSymbol(StringM(");
+                    out.push_str(name);
+                    out.push_str(")))");
+                }
+                _ => {
+                    let _ = write!(out, "{:?}", val);
+                }
+            },
+            ScVal::U32(n) => {
+                let _ = write!(out, "U32({})", n);
+            }
+            ScVal::I32(n) => {
+                let _ = write!(out, "I32({})", n);
+            }
+            ScVal::U64(n) => {
+                let _ = write!(out, "U64({})", n);
+            }
+            ScVal::I64(n) => {
+                let _ = write!(out, "I64({})", n);
+            }
+            other => {
+                let _ = write!(out, "{:?}", other);
+            }
+        }
+        true
+    }
+}
+
+impl Default for RenderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Hash, ScMap, ScMapEntry, ScSymbol, ScVec, StringM};
+
+    fn contract_address(seed: u8) -> ScAddress {
+        ScAddress::Contract(Hash([seed; 32]))
+    }
+
+    fn symbol(name: &str) -> ScSymbol {
+        ScSymbol(StringM::try_from(name).unwrap())
+    }
+
+    fn balance_key(seed: u8) -> ScVal {
+        ScVal::Vec(Some(ScVec(
+            vec![
+                ScVal::Symbol(symbol("Balance")),
+                ScVal::Address(contract_address(seed)),
+            ]
+            .try_into()
+            .unwrap(),
+        )))
+    }
+
+    fn nested_value(i: u32) -> ScVal {
+        ScVal::Map(Some(ScMap(
+            vec![
+                ScMapEntry {
+                    key: ScVal::Symbol(symbol("owner")),
+                    val: ScVal::Address(contract_address((i % 7) as u8)),
+                },
+                ScMapEntry {
+                    key: ScVal::Symbol(symbol("amount")),
+                    val: ScVal::U32(i),
+                },
+                ScMapEntry {
+                    key: ScVal::Symbol(symbol("tags")),
+                    val: ScVal::Vec(Some(ScVec(vec![ScVal::Void].try_into().unwrap()))),
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        )))
+    }
+
+    #[test]
+    fn memoized_rendering_matches_debug_output_byte_for_byte() {
+        let mut cache = RenderCache::new();
+        for i in 0..200u32 {
+            let key = balance_key((i % 5) as u8);
+            let value = nested_value(i);
+            assert_eq!(&*cache.render_val(&key), format!("{:?}", key));
+            assert_eq!(&*cache.render_val(&value), format!("{:?}", value));
+        }
+        assert!(cache.hits() > 0);
+    }
+
+    #[test]
+    fn empty_composites_and_scalars_match_debug_output() {
+        let mut cache = RenderCache::new();
+        for val in [
+            ScVal::Vec(None),
+            ScVal::Map(None),
+            ScVal::Vec(Some(ScVec(Vec::new().try_into().unwrap()))),
+            ScVal::Map(Some(ScMap(Vec::new().try_into().unwrap()))),
+            ScVal::Void,
+            ScVal::Bool(true),
+            ScVal::I64(-42),
+            ScVal::I64(i64::MIN),
+            ScVal::U64(u64::MAX),
+            ScVal::I32(-1),
+            ScVal::U32(7),
+            ScVal::Symbol(symbol("a_B9")),
+            ScVal::Symbol(ScSymbol(StringM::try_from(b"tab\t".to_vec()).unwrap())),
+        ] {
+            assert_eq!(&*cache.render_val(&val), format!("{:?}", val));
+        }
+    }
+
+    #[test]
+    fn only_addresses_are_interned() {
+        let mut cache = RenderCache::new();
+        for i in 0..50u32 {
+            cache.render_val(&nested_value(i));
+        }
+        assert_eq!(cache.len(), 7);
+        assert_eq!(cache.misses(), 7);
+        assert_eq!(cache.hits(), 43);
+    }
+
+    #[test]
+    fn storage_key_rendering_matches_inspector_format() {
+        let mut cache = RenderCache::new();
+        let key = balance_key(1);
+        assert_eq!(
+            cache.render_storage_key(ContractDataDurability::Persistent, &key),
+            format!(
+                "contract_data:{:?}:{:?}",
+                ContractDataDurability::Persistent,
+                key
+            )
+        );
+    }
+
+    #[test]
+    fn cache_resets_when_capacity_is_reached() {
+        let mut cache = RenderCache::with_capacity(4);
+        for seed in 0..10u8 {
+            cache.render_address(&contract_address(seed));
+        }
+        assert!(cache.len() < 4);
+    }
+
+    #[test]
+    fn pathologically_deep_values_render_truncated_without_overflow() {
+        use crate::utils::value_depth::pathological::{dismantle, nested_map, nested_vec};
+        use crate::utils::value_depth::truncation_marker;
+
+        let mut cache = RenderCache::new();
+        for val in [
+            nested_vec(100_000, ScVal::Void),
+            nested_map(100_000, ScVal::Symbol(symbol("leaf"))),
+        ] {
+            let rendered = cache.render_val(&val);
+            assert!(rendered.contains(&truncation_marker(max_value_depth())));
+            dismantle(val);
+        }
+
+        // Values just under the memoization limit still take the cached path.
+        let shallow = nested_vec(MEMOIZE_MAX_DEPTH, ScVal::Void);
+        assert_eq!(&*cache.render_val(&shallow), format!("{:?}", shallow));
+    }
+}
@@ -1,11 +1,25 @@
+use crate::debugger::breakpoint::ConditionEvaluator;
+use crate::debugger::expression::{ExprValue, ExpressionEvaluator};
+use crate::inspector::render_cache::RenderCache;
+use crate::inspector::storage_decode::{decode_json, decode_text, raw_xdr};
+use crate::inspector::storage_history::{StorageHistory, StorageMutation};
+use crate::inspector::storage_provenance::{StorageProvenance, Writer};
+use crate::inspector::storage_rent::{RentEstimate, RentRates};
+use crate::runtime::observer::{storage_writes, InvocationObserver, ObserverResult, StorageWrite};
+use crate::ui::formatter::Formatter;
+use crate::utils::arguments::{int256_to_decimal, scval_to_json};
+use crate::utils::ledger_key::Durability;
+use crate::utils::time::parse_duration;
 use crate::{DebuggerError, Result};
 use crossterm::style::{Color, Stylize};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use soroban_env_host::budget::AsBudget;
-use soroban_env_host::xdr::{LedgerEntryData, LedgerKey};
+use soroban_env_host::xdr::{LedgerEntryData, LedgerKey, Limits, ScVal, WriteXdr};
 use soroban_env_host::Host;
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::Path;
 
@@ -92,84 +106,864 @@ impl FilterPattern {
     }
 }
 
-/// A collection of filter patterns combined with OR logic
+/// Marks a filter pattern that matches an entry's value instead of its key.
+const VALUE_PREFIX: &str = "val:";
+
+/// Marks a filter pattern that excludes the entries it matches.
+const EXCLUDE_PREFIX: char = '!';
+
+/// The patterns a key or value is checked against.
+#[derive(Debug, Clone, Default)]
+struct PatternSet {
+    include: Vec<FilterPattern>,
+    exclude: Vec<FilterPattern>,
+}
+
+impl PatternSet {
+    /// True if one of `forms` matches an include pattern, or there are
+    /// none, and no form matches an exclude pattern.
+    fn admits(&self, forms: &[&str]) -> bool {
+        let any = |patterns: &[FilterPattern]| {
+            patterns
+                .iter()
+                .any(|p| forms.iter().any(|form| p.matches(form)))
+        };
+        (self.include.is_empty() || any(&self.include)) && !any(&self.exclude)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    fn summary(&self, prefix: &str) -> Vec<String> {
+        let render = |p: &FilterPattern| match p {
+            FilterPattern::Prefix(prefix) => format!("{}*", prefix),
+            FilterPattern::Regex(regex) => format!("re:{}", regex.as_str()),
+            FilterPattern::Exact(exact) => exact.clone(),
+        };
+        let included = self
+            .include
+            .iter()
+            .map(|p| format!("{}{}", prefix, render(p)));
+        let excluded = self
+            .exclude
+            .iter()
+            .map(|p| format!("{}{}{}", EXCLUDE_PREFIX, prefix, render(p)));
+        included.chain(excluded).collect()
+    }
+}
+
+/// A collection of filter patterns, optionally limited to entries of some
+/// storages. Key patterns are combined with OR logic, as are `val:`
+/// patterns on the rendered value; an entry must match both kinds when
+/// both are given. A pattern starting with `!` excludes what it matches,
+/// whatever the other patterns admit.
 #[derive(Debug, Clone)]
 pub struct StorageFilter {
-    patterns: Vec<FilterPattern>,
+    keys: PatternSet,
+    values: PatternSet,
+    durabilities: Vec<Durability>,
 }
 
 impl StorageFilter {
-    /// Create a new storage filter from a list of pattern strings
+    /// Create a new storage filter from a list of pattern strings. A
+    /// pattern starting with `val:` matches values with the rest of it,
+    /// e.g. `val:re:^I128` or `val:U32(7)`, and one starting with `!`
+    /// excludes, e.g. `!__meta:*` or `!val:Bool(false)`.
     pub fn new(patterns: &[String]) -> std::result::Result<Self, String> {
-        let parsed: std::result::Result<Vec<FilterPattern>, String> =
-            patterns.iter().map(|p| FilterPattern::parse(p)).collect();
-        Ok(Self { patterns: parsed? })
+        let mut filter = Self {
+            keys: PatternSet::default(),
+            values: PatternSet::default(),
+            durabilities: Vec::new(),
+        };
+        for raw in patterns {
+            let (excluded, rest) = match raw.strip_prefix(EXCLUDE_PREFIX) {
+                Some(rest) => (true, rest),
+                None => (false, raw.as_str()),
+            };
+            let (pattern, set) = match rest.strip_prefix(VALUE_PREFIX) {
+                Some(pattern) => (pattern, &mut filter.values),
+                None => (rest, &mut filter.keys),
+            };
+            let prefix = &raw[..raw.len() - pattern.len()];
+            let parsed = match pattern.strip_prefix("re:") {
+                Some(regex) => Regex::new(regex).map(FilterPattern::Regex).map_err(|e| {
+                    format!(
+                        "pattern '{}': invalid regex after '{}re:': {}",
+                        raw, prefix, e
+                    )
+                })?,
+                None => FilterPattern::parse(pattern)?,
+            };
+            if excluded {
+                set.exclude.push(parsed);
+            } else {
+                set.include.push(parsed);
+            }
+        }
+        Ok(filter)
     }
 
-    /// Check if any filter matches the given key.
-    /// Returns true if no filters are set (show everything).
-    pub fn matches(&self, key: &str) -> bool {
-        if self.patterns.is_empty() {
-            return true;
+    /// Only match entries in one of `durabilities`; an empty list keeps
+    /// every storage.
+    pub fn with_durabilities(mut self, durabilities: Vec<Durability>) -> Self {
+        self.durabilities = durabilities;
+        self
+    }
+
+    /// Check an entry against the key and value patterns and the storages.
+    /// Patterns match either the snapshot key and value or, for entries
+    /// holding their ScVals, the decoded ones the listing shows; an
+    /// exclusion matching either form drops the entry. Entries that are
+    /// not contract data only match when no storage is asked for.
+    pub fn matches_entry(&self, key: &str, entry: &StorageEntry) -> bool {
+        let in_storage = self.durabilities.is_empty()
+            || entry
+                .durability
+                .is_some_and(|d| self.durabilities.contains(&d));
+        if !in_storage {
+            return false;
         }
-        self.patterns.iter().any(|p| p.matches(key))
+        let decoded_key = entry.decoded_key();
+        let decoded_value = entry.decoded_value();
+        let keys: Vec<&str> = std::iter::once(key).chain(decoded_key.as_deref()).collect();
+        let values: Vec<&str> = std::iter::once(split_ttl(&entry.value).0)
+            .chain(decoded_value.as_deref())
+            .collect();
+        self.keys.admits(&keys) && self.values.admits(&values)
+    }
+
+    /// Check the key against the key patterns: it must match one of the
+    /// positive ones, if any, and none of the `!` ones. Returns true if no
+    /// key patterns are set (show everything).
+    pub fn matches(&self, key: &str) -> bool {
+        self.keys.admits(&[key])
+    }
+
+    /// Check the rendered value, without its TTL, against the `val:`
+    /// patterns as [`Self::matches`] does keys. Returns true if no value
+    /// patterns are set.
+    pub fn matches_value(&self, value: &str) -> bool {
+        self.values.admits(&[split_ttl(value).0])
     }
 
     /// Returns true if no filters are configured
     pub fn is_empty(&self) -> bool {
-        self.patterns.is_empty()
+        self.keys.is_empty() && self.values.is_empty() && self.durabilities.is_empty()
     }
 
     /// Get a human-readable summary of active filters
     pub fn summary(&self) -> String {
-        self.patterns
+        let mut parts = self.keys.summary("");
+        parts.extend(self.values.summary(VALUE_PREFIX));
+        if !self.durabilities.is_empty() {
+            let names: Vec<&str> = self.durabilities.iter().map(|d| d.name()).collect();
+            parts.push(format!("durability: {}", names.join("|")));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Right-hand side of an alert predicate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertOperand {
+    /// `old`: the value before the call
+    Old,
+    /// An expression literal compared against the new value: a number,
+    /// duration, `true`/`false` or string. Bare text is taken as a string.
+    Literal(String),
+}
+
+/// A `--alert-on-change` rule: a key pattern with an optional predicate.
+///
+/// - `<pattern>` alerts when a matching key is added, modified or deleted
+/// - `<pattern>!=old` alerts only when the value itself changed (TTL bumps
+///   are ignored)
+/// - `<pattern>==old` alerts when the key was written with the value it
+///   already held, i.e. a wasted write
+/// - `<pattern>==<value>` / `<pattern>!=<value>` alert when a changed or
+///   written key's new value does (not) match `<value>`
+/// - `!<pattern>` never alerts on a matching key; with only such rules,
+///   every other changed key alerts
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    spec: String,
+    pattern: FilterPattern,
+    predicate: Option<(bool, AlertOperand)>,
+    exclude: bool,
+}
+
+impl AlertRule {
+    /// Parse a rule; the predicate starts at the first `==` or `!=`.
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
+        if let Some(pattern) = spec
+            .strip_prefix(EXCLUDE_PREFIX)
+            .filter(|_| !spec.starts_with("!="))
+        {
+            if split_comparison(pattern).is_some() {
+                return Err(format!(
+                    "Invalid alert '{}': an exclusion '!<key>' takes no predicate",
+                    spec
+                ));
+            }
+            return Ok(Self {
+                spec: spec.to_string(),
+                pattern: FilterPattern::parse(pattern)?,
+                predicate: None,
+                exclude: true,
+            });
+        }
+        let Some((key, equal, operand)) = split_comparison(spec) else {
+            return Ok(Self {
+                spec: spec.to_string(),
+                pattern: FilterPattern::parse(spec)?,
+                predicate: None,
+                exclude: false,
+            });
+        };
+
+        if key.is_empty() || operand.is_empty() {
+            return Err(format!(
+                "Invalid alert '{}': expected <key>==<value> or <key>!=<value>",
+                spec
+            ));
+        }
+        let operand = match operand {
+            "old" => AlertOperand::Old,
+            literal if ExpressionEvaluator::default().eval(literal).is_ok() => {
+                AlertOperand::Literal(literal.to_string())
+            }
+            text => AlertOperand::Literal(format!("\"{}\"", text)),
+        };
+        Ok(Self {
+            spec: spec.to_string(),
+            pattern: FilterPattern::parse(key)?,
+            predicate: Some((equal, operand)),
+            exclude: false,
+        })
+    }
+
+    /// The rule as given on the command line.
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    pub fn has_predicate(&self) -> bool {
+        self.predicate.is_some()
+    }
+
+    /// Whether this is a `!<pattern>` rule, keeping its keys from alerting.
+    pub fn excludes(&self) -> bool {
+        self.exclude
+    }
+
+    /// The key pattern the rule watches.
+    pub fn pattern(&self) -> &FilterPattern {
+        &self.pattern
+    }
+
+    /// The predicate as a condition over `old` and `new`, e.g. `new != old`.
+    pub fn condition(&self) -> Option<String> {
+        self.predicate.as_ref().map(|(equal, operand)| {
+            let op = if *equal { "==" } else { "!=" };
+            match operand {
+                AlertOperand::Old => format!("new {} old", op),
+                AlertOperand::Literal(value) => format!("new {} {}", op, value),
+            }
+        })
+    }
+
+    /// Whether the rule fires for `key`. `changed` is true when the diff
+    /// records the key as added, modified or deleted, `written` when the
+    /// call wrote it (possibly with an identical value). Exclusions never
+    /// fire.
+    pub fn fires(
+        &self,
+        key: &str,
+        old: Option<&str>,
+        new: Option<&str>,
+        changed: bool,
+        written: bool,
+    ) -> bool {
+        if self.exclude || !self.pattern.matches(key) {
+            return false;
+        }
+        let (Some(condition), Some((equal, operand))) = (self.condition(), &self.predicate) else {
+            return changed;
+        };
+        if !changed && !written {
+            return false;
+        }
+        // A deleted key matches no value, and a created one differs from
+        // its missing old value.
+        let Some(new) = new else {
+            return !equal;
+        };
+        let mut values = ExpressionEvaluator::default();
+        values.set("new", alert_value(new));
+        match old {
+            Some(old) => values.set("old", alert_value(old)),
+            None if *operand == AlertOperand::Old => return !equal,
+            None => {}
+        }
+        values.evaluate(&condition).unwrap_or_else(|e| {
+            tracing::warn!("Failed to evaluate alert '{}': {}", self.spec, e);
+            false
+        })
+    }
+}
+
+/// Collects the storage writes that fire an [`AlertRule`] and match no
+/// exclusion.
+#[derive(Debug, Default)]
+pub struct AlertObserver {
+    rules: Vec<AlertRule>,
+    /// Keys that fired a rule, in the order they were written.
+    pub triggered: Vec<String>,
+    /// The spec of the predicate rule each triggered key fired, if any.
+    pub predicates: HashMap<String, String>,
+    /// Value before and after the write each triggered key fired on.
+    pub values: HashMap<String, (Option<String>, Option<String>)>,
+}
+
+impl AlertObserver {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        Self {
+            rules,
+            ..Self::default()
+        }
+    }
+}
+
+impl AlertObserver {
+    /// The alerts `specs` raise on the keys that changed between `before`
+    /// and `after`, or that are in `written`.
+    fn over(
+        before: &HashMap<String, String>,
+        after: &HashMap<String, String>,
+        written: &HashSet<String>,
+        specs: &[String],
+    ) -> Self {
+        let rules: Vec<AlertRule> = specs
             .iter()
-            .map(|p| match p {
-                FilterPattern::Prefix(prefix) => format!("{}*", prefix),
-                FilterPattern::Regex(regex) => format!("re:{}", regex.as_str()),
-                FilterPattern::Exact(exact) => exact.clone(),
+            .filter_map(|spec| {
+                AlertRule::parse(spec)
+                    .map_err(|e| tracing::warn!("Invalid alert pattern: {}", e))
+                    .ok()
             })
-            .collect::<Vec<_>>()
-            .join(", ")
+            .collect();
+
+        let mut alerts = AlertObserver::new(rules);
+        if !alerts.rules.is_empty() {
+            for write in storage_writes(before, after, written) {
+                let _ = alerts.on_storage_write(&write);
+            }
+        }
+        alerts
+    }
+}
+
+impl InvocationObserver for AlertObserver {
+    fn on_storage_write(&mut self, write: &StorageWrite) -> ObserverResult {
+        let old = write.old.as_deref();
+        let new = write.new.as_deref();
+        if self
+            .rules
+            .iter()
+            .any(|rule| rule.excludes() && rule.pattern().matches(&write.key))
+        {
+            return Ok(());
+        }
+        let mut firing = self.rules.iter().filter(|rule| !rule.excludes()).peekable();
+        let fired = match firing.peek() {
+            // Only exclusions: every change of any other key alerts.
+            None => (old != new).then_some(None),
+            Some(_) => firing
+                .find(|rule| rule.fires(&write.key, old, new, old != new, true))
+                .map(Some),
+        };
+        let Some(rule) = fired else {
+            return Ok(());
+        };
+        self.triggered.push(write.key.clone());
+        self.values
+            .insert(write.key.clone(), (write.old.clone(), write.new.clone()));
+        if let Some(rule) = rule.filter(|rule| rule.has_predicate()) {
+            self.predicates
+                .insert(write.key.clone(), rule.spec().to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Split `lhs==rhs` / `lhs!=rhs` at the first operator, returning the trimmed
+/// sides and whether the operator is `==`.
+pub(crate) fn split_comparison(s: &str) -> Option<(&str, bool, &str)> {
+    let (at, equal) = [("==", true), ("!=", false)]
+        .into_iter()
+        .filter_map(|(op, equal)| s.find(op).map(|at| (at, equal)))
+        .min_by_key(|(at, _)| *at)?;
+    Some((s[..at].trim(), equal, s[at + 2..].trim()))
+}
+
+/// A rendered storage value as an expression value, for alert predicates.
+///
+/// Integers of any width that fit, booleans, timepoints and durations keep
+/// their type; symbols and strings become their text and addresses their
+/// strkey. Anything else compares as its rendering.
+pub(crate) fn alert_value(rendered: &str) -> ExprValue {
+    let rendered = split_ttl(rendered).0;
+    scalar_value(rendered).unwrap_or_else(|| ExprValue::Str(rendered.to_string()))
+}
+
+/// The value of a rendered scalar such as `U32(5)`, `Bool(true)` or
+/// `Symbol(ScSymbol(StringM(paused)))`.
+fn scalar_value(rendered: &str) -> Option<ExprValue> {
+    use soroban_env_host::xdr::{AccountId, Hash, PublicKey, ScAddress, Uint256};
+
+    let (variant, inner) = rendered.strip_suffix(')')?.split_once('(')?;
+    let hex32 = |text: &str| -> Option<[u8; 32]> { hex::decode(text).ok()?.try_into().ok() };
+    Some(match variant {
+        "U32" | "I32" | "U64" | "I64" => ExprValue::Int(inner.parse().ok()?),
+        "Bool" => ExprValue::Bool(inner.parse().ok()?),
+        "U128" => {
+            let [hi, lo] = part_fields(inner, ["hi", "lo"])?;
+            let (hi, lo): (u64, u64) = (hi.parse().ok()?, lo.parse().ok()?);
+            ExprValue::Int(((u128::from(hi) << 64) | u128::from(lo)).try_into().ok()?)
+        }
+        "I128" => {
+            let [hi, lo] = part_fields(inner, ["hi", "lo"])?;
+            let (hi, lo): (i64, u64) = (hi.parse().ok()?, lo.parse().ok()?);
+            ExprValue::Int((i128::from(hi) << 64) | i128::from(lo))
+        }
+        "U256" | "I256" => {
+            let [hi_hi, hi_lo, lo_hi, lo_lo] =
+                part_fields(inner, ["hi_hi", "hi_lo", "lo_hi", "lo_lo"])?;
+            let hi_hi = match variant {
+                "I256" => hi_hi.parse::<i64>().ok()? as u64,
+                _ => hi_hi.parse().ok()?,
+            };
+            let limbs = [
+                hi_hi,
+                hi_lo.parse().ok()?,
+                lo_hi.parse().ok()?,
+                lo_lo.parse().ok()?,
+            ];
+            ExprValue::Int(int256_to_decimal(limbs, variant == "I256").parse().ok()?)
+        }
+        // Humanized as `<date> (<seconds>)`, raw as `TimePoint(<seconds>)`.
+        "Timepoint" => {
+            let seconds = match inner.strip_prefix("TimePoint(") {
+                Some(raw) => raw.strip_suffix(')')?,
+                None => inner.rsplit_once(" (")?.1.strip_suffix(')')?,
+            };
+            ExprValue::Timepoint(seconds.parse().ok()?)
+        }
+        // Humanized as `5s` or `1h (3600s)`, raw as `Duration(<seconds>)`.
+        "Duration" => {
+            let seconds = match inner.strip_prefix("Duration(") {
+                Some(raw) => raw.strip_suffix(')')?.parse().ok()?,
+                None => match inner.rsplit_once(" (") {
+                    Some((_, raw)) => raw.strip_suffix("s)")?.parse().ok()?,
+                    None => parse_duration(inner).ok()?,
+                },
+            };
+            ExprValue::Duration(i128::from(seconds))
+        }
+        "Symbol" => ExprValue::Str(
+            inner
+                .strip_prefix("ScSymbol(StringM(")?
+                .strip_suffix("))")?
+                .to_string(),
+        ),
+        "String" => ExprValue::Str(
+            inner
+                .strip_prefix("ScString(StringM(")?
+                .strip_suffix("))")?
+                .to_string(),
+        ),
+        "Address" => {
+            let address = match inner.strip_prefix("Contract(Hash(") {
+                Some(hash) => ScAddress::Contract(Hash(hex32(hash.strip_suffix("))")?)?)),
+                None => {
+                    let key = inner
+                        .strip_prefix("Account(AccountId(PublicKeyTypeEd25519(Uint256(")?
+                        .strip_suffix("))))")?;
+                    let key = PublicKey::PublicKeyTypeEd25519(Uint256(hex32(key)?));
+                    ScAddress::Account(AccountId(key))
+                }
+            };
+            ExprValue::Str(address.to_string())
+        }
+        _ => return None,
+    })
+}
+
+/// The values of the fields `names` of a rendered struct such as
+/// `Int128Parts { hi: 0, lo: 5 }`, in order.
+fn part_fields<'a, const N: usize>(rendered: &'a str, names: [&str; N]) -> Option<[&'a str; N]> {
+    let body = rendered.split_once(" { ")?.1.strip_suffix(" }")?;
+    let values: Vec<&str> = body
+        .split(", ")
+        .zip(names)
+        .map(|(field, name)| field.strip_prefix(name)?.strip_prefix(": "))
+        .collect::<Option<_>>()?;
+    values.try_into().ok()
+}
+
+/// Whether the snapshot key `key`, e.g.
+/// `contract_data:Persistent:Symbol(ScSymbol(StringM(admin)))`, is `name`
+/// either in full or by the stored key alone (`admin`).
+pub(crate) fn key_matches(key: &str, name: &str) -> bool {
+    if key == name {
+        return true;
+    }
+    let stored = match key.strip_prefix("instance:") {
+        Some(stored) => stored,
+        None => key.splitn(3, ':').nth(2).unwrap_or(key),
+    };
+    scalar_value(stored).is_some_and(|value| value.to_string() == name)
+}
+
+/// Split the ` (ttl=N)` suffix a snapshot adds off a value.
+pub(crate) fn split_ttl(value: &str) -> (&str, Option<u32>) {
+    match value.rsplit_once(" (ttl=") {
+        Some((rest, ttl)) => match ttl.strip_suffix(')').map(str::parse::<u32>) {
+            Some(Ok(live_until)) => (rest, Some(live_until)),
+            _ => (value, None),
+        },
+        None => (value, None),
+    }
+}
+
+/// A storage entry as the inspector holds it: the rendered value, the
+/// storage it lives in and the ledger it lives until.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageEntry {
+    /// The canonical key the entry is captured under, see
+    /// [`StorageInspector::render_ledger_key`]; rendered once, when the
+    /// entry is captured.
+    #[serde(skip)]
+    pub key: String,
+    pub value: String,
+    /// `None` for ledger entries that are not contract data, such as
+    /// contract code.
+    pub durability: Option<Durability>,
+    /// Last ledger the entry is live in; instance items share the contract
+    /// instance's.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub live_until: Option<u32>,
+    /// Size of the entry's ledger XDR, or of its key and value for instance
+    /// items; `None` when not read from a host.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u32>,
+    /// The stored key, when read from a host; `None` for ledger entries
+    /// that are not contract data.
+    #[serde(skip)]
+    pub scval_key: Option<ScVal>,
+    /// The stored value, when read from a host.
+    #[serde(skip)]
+    pub scval_value: Option<ScVal>,
+}
+
+impl StorageEntry {
+    /// An entry built from a snapshot key and value: its storage is read
+    /// from the key, see [`StorageInspector::durability`], and its live-until
+    /// ledger from the value's ` (ttl=N)` suffix.
+    pub fn from_key(key: &str, value: impl Into<String>) -> Self {
+        let value = value.into();
+        let (rendered, live_until) = split_ttl(&value);
+        Self {
+            key: key.to_string(),
+            value: rendered.to_string(),
+            durability: StorageInspector::durability(key),
+            live_until,
+            size_bytes: None,
+            scval_key: None,
+            scval_value: None,
+        }
+    }
+
+    /// The key as the listing shows it, see [`decode_text`], when the entry
+    /// holds its ScVal key.
+    pub fn decoded_key(&self) -> Option<String> {
+        self.scval_key.as_ref().map(decode_text)
+    }
+
+    /// The value as the listing shows it, when the entry holds its ScVal
+    /// value.
+    pub fn decoded_value(&self) -> Option<String> {
+        self.scval_value.as_ref().map(decode_text)
+    }
+
+    /// The value as a snapshot holds it, with the ` (ttl=N)` suffix.
+    pub fn snapshot_value(&self) -> String {
+        match self.live_until {
+            Some(live_until) => format!("{} (ttl={})", self.value, live_until),
+            None => self.value.clone(),
+        }
+    }
+
+    /// Ledgers the entry has left at `sequence`, and whether it has expired
+    /// (temporary) or been archived (persistent and instance) by then.
+    fn ttl_at(&self, sequence: u32) -> Option<(u32, bool)> {
+        self.live_until
+            .map(|live_until| (live_until.saturating_sub(sequence), live_until < sequence))
+    }
+}
+
+/// Serialized XDR size of `value`, as the network measures it for rent.
+fn xdr_size(value: &impl WriteXdr) -> Option<u32> {
+    value
+        .to_xdr(Limits::none())
+        .ok()
+        .and_then(|bytes| u32::try_from(bytes.len()).ok())
+}
+
+/// Storage, instance to temporary with entries that are not contract data
+/// last, then key.
+fn by_key(a: (&String, &StorageEntry), b: (&String, &StorageEntry)) -> Ordering {
+    (a.1.durability.is_none(), a.1.durability, a.0).cmp(&(
+        b.1.durability.is_none(),
+        b.1.durability,
+        b.0,
+    ))
+}
+
+/// `n` with its thousands separated by commas, e.g. `40,213`.
+fn with_commas(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+/// The `<label>` of a snapshot key prefixed `<label>/`; labels carry no `:`.
+fn contract_label(key: &str) -> Option<&str> {
+    key.split_once('/')
+        .map(|(label, _)| label)
+        .filter(|label| !label.contains(':'))
+}
+
+/// What becomes of an entry past its TTL: temporary entries are deleted
+/// (`expired`), the others are `archived` and can be restored.
+fn expiry_word(durability: Option<Durability>) -> &'static str {
+    match durability {
+        Some(Durability::Temporary) => "expired",
+        _ => "archived",
+    }
+}
+
+/// Order of the storage listing (`run --sort`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageSort {
+    /// By storage, then key.
+    #[default]
+    Key,
+    /// Largest first.
+    Size,
+    /// Soonest live-until ledger first; entries without a TTL last.
+    Ttl,
+    /// Entries the run wrote first, see [`StorageInspector::with_written`].
+    Modified,
+}
+
+/// One window of the storage listing.
+#[derive(Debug)]
+pub struct StoragePage<'a> {
+    pub entries: Vec<(&'a String, &'a StorageEntry)>,
+    /// Matched entries skipped before the window.
+    pub offset: usize,
+    /// Entries the filter kept.
+    pub matched: usize,
+    /// Entries in the storage.
+    pub total: usize,
+}
+
+impl StoragePage<'_> {
+    /// `showing 50 of 40,213 entries (filtered from 40,213)` when the window
+    /// leaves entries out.
+    pub fn summary(&self) -> Option<String> {
+        (self.offset > 0 || self.entries.len() < self.matched).then(|| {
+            let shown = if self.offset > 0 && !self.entries.is_empty() {
+                format!(
+                    "{}-{}",
+                    with_commas(self.offset + 1),
+                    with_commas(self.offset + self.entries.len())
+                )
+            } else {
+                with_commas(self.entries.len())
+            };
+            format!(
+                "showing {} of {} entries (filtered from {})",
+                shown,
+                with_commas(self.matched),
+                with_commas(self.total)
+            )
+        })
+    }
+}
+
+/// A storage entry whose TTL runs out within `--ttl-warning-threshold`
+/// ledgers of the configured ledger sequence.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TtlWarning {
+    pub key: String,
+    pub durability: Option<Durability>,
+    pub live_until: u32,
+    pub ledgers_left: u32,
+    /// Already past its live-until ledger at the configured sequence.
+    pub expired: bool,
+}
+
+impl fmt::Display for TtlWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let storage = self.durability.map_or("other", Durability::name);
+        if self.expired {
+            write!(
+                f,
+                "[{}] {} {} (live until {})",
+                storage,
+                self.key,
+                expiry_word(self.durability),
+                self.live_until
+            )
+        } else {
+            write!(
+                f,
+                "[{}] {} near expiry ({} ledgers left, live until {})",
+                storage, self.key, self.ledgers_left, self.live_until
+            )
+        }
     }
 }
 
+/// Headings of the storage listing, in order.
+const DURABILITY_GROUPS: [(Option<Durability>, &str); 4] = [
+    (Some(Durability::Instance), "Instance storage"),
+    (Some(Durability::Persistent), "Persistent storage"),
+    (Some(Durability::Temporary), "Temporary storage"),
+    (None, "Other ledger entries"),
+];
+
 /// Inspects and displays contract storage
 pub struct StorageInspector {
     // Storage will be tracked here
-    storage: HashMap<String, String>,
+    storage: HashMap<String, StorageEntry>,
     // Tracks frequency of key reads
     reads: HashMap<String, usize>,
     // Tracks frequency of key writes
     writes: HashMap<String, usize>,
+    // Ledger sequence TTLs are counted from, when known
+    sequence: Option<u32>,
+    // Also print the base64 XDR of listed keys and values
+    raw_xdr: bool,
+    // Order and window of the listing
+    sort: StorageSort,
+    offset: usize,
+    limit: Option<usize>,
+    // Keys the run wrote
+    written: HashSet<String>,
 }
 
 impl StorageInspector {
     pub fn new() -> Self {
-        Self {
-            storage: HashMap::new(),
-            reads: HashMap::new(),
-            writes: HashMap::new(),
-        }
+        Self::with_entries(HashMap::new())
     }
 
-    /// Create a StorageInspector from an existing storage snapshot
+    /// Create a StorageInspector from an existing storage snapshot, each
+    /// entry's storage read from its key
     pub fn with_state(storage: HashMap<String, String>) -> Self {
+        Self::with_entries(
+            storage
+                .into_iter()
+                .map(|(key, value)| {
+                    let entry = StorageEntry::from_key(&key, value);
+                    (key, entry)
+                })
+                .collect(),
+        )
+    }
+
+    /// Create a StorageInspector from entries that already know their
+    /// storage, such as those of [`Self::capture_entries_with_cache`]
+    pub fn with_entries(storage: HashMap<String, StorageEntry>) -> Self {
         Self {
             storage,
             reads: HashMap::new(),
             writes: HashMap::new(),
+            sequence: None,
+            raw_xdr: false,
+            sort: StorageSort::Key,
+            offset: 0,
+            limit: None,
+            written: HashSet::new(),
         }
     }
 
+    /// Count TTLs from ledger `sequence`, so that the listing shows how many
+    /// ledgers each entry has left and [`Self::ttl_warnings`] can flag them.
+    pub fn at_ledger(mut self, sequence: u32) -> Self {
+        self.sequence = Some(sequence);
+        self
+    }
+
+    /// List the base64 XDR of each key and value along with its decoded
+    /// form, for `--raw-xdr`.
+    pub fn with_raw_xdr(mut self, raw_xdr: bool) -> Self {
+        self.raw_xdr = raw_xdr;
+        self
+    }
+
+    /// List entries in `sort` order.
+    pub fn sorted_by(mut self, sort: StorageSort) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// List at most `limit` entries (all with `None`), after skipping the
+    /// first `offset` the filter keeps.
+    pub fn paged(mut self, offset: usize, limit: Option<usize>) -> Self {
+        self.offset = offset;
+        self.limit = limit;
+        self
+    }
+
+    /// The keys the run wrote, for [`StorageSort::Modified`] and the
+    /// written share of [`Self::rent_estimate`].
+    pub fn with_written(mut self, written: HashSet<String>) -> Self {
+        self.written = written;
+        self
+    }
+
     /// Get all storage entries
-    pub fn get_all(&self) -> &HashMap<String, String> {
+    pub fn get_all(&self) -> &HashMap<String, StorageEntry> {
         &self.storage
     }
 
+    /// The values with their TTLs, keyed like a storage snapshot.
+    fn values(&self) -> HashMap<String, String> {
+        self.storage
+            .iter()
+            .map(|(key, entry)| (key.clone(), entry.snapshot_value()))
+            .collect()
+    }
+
+    /// What changed from this storage to `other`'s: entries `other` added,
+    /// changed or removed, decoded where they were captured from a host.
+    pub fn diff(&self, other: &StorageInspector) -> StorageDiff {
+        Self::compute_diff(&self.values(), &other.values(), &[])
+            .with_entries(&self.storage, &other.storage)
+    }
+
     /// Get a specific storage value
     pub fn get(&self, key: &str) -> Option<&String> {
-        self.storage.get(key)
+        self.storage.get(key).map(|entry| &entry.value)
     }
 
     /// Display storage in a readable format (no filtering)
@@ -180,56 +974,313 @@ impl StorageInspector {
         }
 
         tracing::info!(entries = self.storage.len(), "Storage entries");
-        for (key, value) in &self.storage {
-            tracing::debug!(key, value, "Storage entry");
+        for (key, entry) in &self.storage {
+            tracing::debug!(
+                key,
+                value = entry.value,
+                durability = entry.durability.map(Durability::name),
+                "Storage entry"
+            );
+        }
+    }
+
+    /// Entries the filter keeps, in no particular order. Nothing is cloned,
+    /// so a page of a large storage can be picked from it cheaply.
+    pub fn iter_filtered<'a: 'f, 'f>(
+        &'a self,
+        filter: &'f StorageFilter,
+    ) -> impl Iterator<Item = (&'a String, &'a StorageEntry)> + 'f {
+        self.storage
+            .iter()
+            .filter(move |(key, entry)| filter.matches_entry(key, entry))
+    }
+
+    /// Entries the filter keeps, grouped instance, persistent, temporary,
+    /// then the rest, and sorted by key within each group.
+    pub fn entries_filtered(&self, filter: &StorageFilter) -> Vec<(&String, &StorageEntry)> {
+        let mut matched: Vec<(&String, &StorageEntry)> = self.iter_filtered(filter).collect();
+        matched.sort_by(|a, b| by_key(*a, *b));
+        matched
+    }
+
+    /// The entries the filter keeps in the order of [`Self::sorted_by`],
+    /// cut to the window of [`Self::paged`]. Only the entries up to the end
+    /// of the window are sorted, so a page costs little more than a pass
+    /// over the storage.
+    pub fn page(&self, filter: &StorageFilter) -> StoragePage<'_> {
+        let compare =
+            |a: &(&String, &StorageEntry), b: &(&String, &StorageEntry)| self.compare(*a, *b);
+        let mut matched: Vec<(&String, &StorageEntry)> = self.iter_filtered(filter).collect();
+        let count = matched.len();
+        let end = self
+            .limit
+            .map_or(count, |limit| self.offset.saturating_add(limit).min(count));
+        if end < count {
+            matched.select_nth_unstable_by(end, compare);
+            matched.truncate(end);
+        }
+        matched.sort_by(compare);
+        StoragePage {
+            entries: matched.into_iter().skip(self.offset).collect(),
+            offset: self.offset,
+            matched: count,
+            total: self.storage.len(),
         }
     }
 
-    /// Display storage filtered by the given patterns.
-    /// Prints a notice when filtering is active.
+    fn compare(&self, a: (&String, &StorageEntry), b: (&String, &StorageEntry)) -> Ordering {
+        let first = match self.sort {
+            StorageSort::Key => Ordering::Equal,
+            // Largest first; entries of unknown size last.
+            StorageSort::Size => b.1.size_bytes.cmp(&a.1.size_bytes),
+            StorageSort::Ttl => (a.1.live_until.is_none(), a.1.live_until)
+                .cmp(&(b.1.live_until.is_none(), b.1.live_until)),
+            StorageSort::Modified => {
+                (!self.written.contains(a.0)).cmp(&!self.written.contains(b.0))
+            }
+        };
+        first.then_with(|| by_key(a, b))
+    }
+
+    /// Display the page of storage the filter and [`Self::paged`] select,
+    /// as rendered by [`Self::render_filtered`].
     pub fn display_filtered(&self, filter: &StorageFilter) {
-        if self.storage.is_empty() {
-            tracing::info!("Storage is empty");
-            return;
+        for line in self.render_filtered(filter) {
+            crate::logging::log_display(line, crate::logging::LogLevel::Info);
         }
+    }
 
-        let mut matched = 0;
-        let keys: Vec<&String> = self.storage.keys().collect();
+    /// The lines listing the page of storage the filter and [`Self::paged`]
+    /// select, grouped by the storage each entry lives in. Includes a notice
+    /// when filtering is active and says how much of it the page shows.
+    pub fn render_filtered(&self, filter: &StorageFilter) -> Vec<String> {
+        if self.storage.is_empty() {
+            return vec!["Storage is empty".to_string()];
+        }
 
-        for key in keys {
-            if filter.matches(key) {
-                tracing::debug!(key, value = self.storage[key], "Filtered storage entry");
-                matched += 1;
+        let mut lines = Vec::new();
+        let page = self.page(filter);
+        for (durability, heading) in DURABILITY_GROUPS {
+            let group: Vec<_> = page
+                .entries
+                .iter()
+                .filter(|(_, entry)| entry.durability == durability)
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+            lines.push(
+                format!("{} ({}):", heading, group.len())
+                    .with(Color::DarkGrey)
+                    .to_string(),
+            );
+            for (key, entry) in group {
+                let (shown_key, shown_value) = Self::shown(key, entry);
+                lines.push(format!(
+                    "  {} = {}{}",
+                    shown_key,
+                    shown_value,
+                    self.entry_note(entry)
+                ));
+                if self.raw_xdr {
+                    let xdr = [("key", &entry.scval_key), ("value", &entry.scval_value)];
+                    for (part, val) in xdr {
+                        if let Some(encoded) = val.as_ref().and_then(raw_xdr) {
+                            lines.push(
+                                format!("    {} xdr: {}", part, encoded)
+                                    .with(Color::DarkGrey)
+                                    .to_string(),
+                            );
+                        }
+                    }
+                }
             }
         }
 
-        if matched == 0 && !filter.is_empty() {
-            tracing::info!("No storage entries matched the filter");
+        if page.matched == 0 && !filter.is_empty() {
+            lines.push("No storage entries matched the filter".to_string());
+        }
+        if let Some(summary) = page.summary() {
+            lines.push(summary.with(Color::DarkGrey).to_string());
         }
 
-        let total = self.storage.len();
         tracing::info!(
-            matched = matched,
-            total = total,
+            matched = page.matched,
+            total = self.storage.len(),
             filter = filter.summary(),
             "Storage filtering complete"
         );
+        lines
+    }
+
+    /// The key and value the listing shows: decoded when the entry holds
+    /// its ScVals, keeping the `<label>/` of a labelled contract's data.
+    fn shown(key: &str, entry: &StorageEntry) -> (String, String) {
+        let decoded_key = entry
+            .decoded_key()
+            .map(|decoded| match contract_label(key) {
+                Some(label) => format!("{}/{}", label, decoded),
+                None => decoded,
+            });
+        (
+            decoded_key.unwrap_or_else(|| key.to_string()),
+            entry.decoded_value().unwrap_or_else(|| entry.value.clone()),
+        )
+    }
+
+    /// The size and live-until ledger shown after a listed value, with the
+    /// ledgers left when the sequence is known.
+    fn entry_note(&self, entry: &StorageEntry) -> String {
+        let mut parts = Vec::new();
+        if let Some(size) = entry.size_bytes {
+            parts.push(format!("{} B", size));
+        }
+        if let Some(live_until) = entry.live_until {
+            let left = match self.sequence.and_then(|sequence| entry.ttl_at(sequence)) {
+                Some((_, true)) => format!(", {}", expiry_word(entry.durability)),
+                Some((left, false)) => format!(", {} left", left),
+                None => String::new(),
+            };
+            parts.push(format!("live until {}{}", live_until, left));
+        }
+        if parts.is_empty() {
+            return String::new();
+        }
+        format!("  ({})", parts.join(", "))
+            .with(Color::DarkGrey)
+            .to_string()
+    }
+
+    /// The filtered entries as JSON objects with `key`, `value`,
+    /// `durability` and, when known, `live_until`, `size_bytes` and the
+    /// `decoded_key` and `decoded_value`, plus their base64 `key_xdr` and
+    /// `value_xdr` with [`Self::with_raw_xdr`]: the page
+    /// [`Self::display_filtered`] lists, in the same order.
+    pub fn filtered_json(&self, filter: &StorageFilter) -> Vec<serde_json::Value> {
+        self.page(filter)
+            .entries
+            .into_iter()
+            .map(|(key, entry)| {
+                let mut json = serde_json::json!({
+                    "key": key,
+                    "value": entry.value,
+                    "durability": entry.durability,
+                });
+                if let Some(live_until) = entry.live_until {
+                    json["live_until"] = live_until.into();
+                }
+                if let Some(size) = entry.size_bytes {
+                    json["size_bytes"] = size.into();
+                }
+                if let (Some(key), Some(value)) = (&entry.scval_key, &entry.scval_value) {
+                    json["decoded_key"] = decode_json(key);
+                    json["decoded_value"] = decode_json(value);
+                    if self.raw_xdr {
+                        json["key_xdr"] = raw_xdr(key).into();
+                        json["value_xdr"] = raw_xdr(value).into();
+                    }
+                }
+                json
+            })
+            .collect()
+    }
+
+    /// Filtered entries with fewer than `threshold` ledgers left at the
+    /// sequence given to [`Self::at_ledger`], expired and archived ones
+    /// included, fewest ledgers left first. Empty without a sequence.
+    pub fn ttl_warnings(&self, filter: &StorageFilter, threshold: u32) -> Vec<TtlWarning> {
+        let Some(sequence) = self.sequence else {
+            return Vec::new();
+        };
+        let mut warnings: Vec<TtlWarning> = self
+            .iter_filtered(filter)
+            .filter_map(|(key, entry)| {
+                let live_until = entry.live_until?;
+                let (ledgers_left, expired) = entry.ttl_at(sequence)?;
+                (expired || ledgers_left < threshold).then(|| TtlWarning {
+                    key: key.clone(),
+                    durability: entry.durability,
+                    live_until,
+                    ledgers_left,
+                    expired,
+                })
+            })
+            .collect();
+        warnings.sort_by(|a, b| (a.live_until, &a.key).cmp(&(b.live_until, &b.key)));
+        warnings
+    }
+
+    /// Size and rent of every filtered entry, not just the listed page,
+    /// those the run wrote counted apart.
+    pub fn rent_estimate(&self, filter: &StorageFilter, rates: RentRates) -> RentEstimate {
+        RentEstimate::of(self.iter_filtered(filter), &self.written, rates)
+    }
+
+    /// Print `warnings` under a bold heading, expired and archived entries
+    /// in red.
+    pub fn display_ttl_warnings(warnings: &[TtlWarning], threshold: u32) {
+        if warnings.is_empty() {
+            return;
+        }
+        crate::logging::log_display(
+            format!(
+                "\n{}",
+                format!(
+                    "⚠ {} storage entr{} near expiration (TTL < {}):",
+                    warnings.len(),
+                    if warnings.len() == 1 { "y" } else { "ies" },
+                    threshold
+                )
+                .with(Color::Yellow)
+                .bold()
+            ),
+            crate::logging::LogLevel::Warn,
+        );
+        for warning in warnings {
+            let line = if warning.expired {
+                warning.to_string().with(Color::Red).bold()
+            } else {
+                warning.to_string().with(Color::Yellow)
+            };
+            crate::logging::log_display(format!("  {}", line), crate::logging::LogLevel::Warn);
+        }
+    }
+
+    /// Storage a snapshot key belongs to: `instance:<key>` items and the
+    /// contract instance entry itself are instance storage, other contract
+    /// data is persistent or temporary as its key says. `None` for entries
+    /// that are not contract data, such as contract code.
+    pub fn durability(key: &str) -> Option<Durability> {
+        let key = match contract_label(key) {
+            Some(label) => &key[label.len() + 1..],
+            None => key,
+        };
+        if key.starts_with("instance:") {
+            return Some(Durability::Instance);
+        }
+        let (durability, stored) = key.strip_prefix("contract_data:")?.split_once(':')?;
+        if stored == "LedgerKeyContractInstance" {
+            return Some(Durability::Instance);
+        }
+        match durability {
+            "Persistent" => Some(Durability::Persistent),
+            "Temporary" => Some(Durability::Temporary),
+            _ => None,
+        }
     }
 
     /// Get filtered storage entries as a new HashMap
     pub fn get_filtered(&self, filter: &StorageFilter) -> HashMap<String, String> {
-        self.storage
-            .iter()
-            .filter(|(key, _)| filter.matches(key))
-            .map(|(k, v)| (k.clone(), v.clone()))
+        self.iter_filtered(filter)
+            .map(|(key, entry)| (key.clone(), entry.snapshot_value()))
             .collect()
     }
 
     /// Insert a storage entry (used for testing and state tracking)
     pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
         let k = key.into();
-        let v = value.into();
-        self.storage.insert(k.clone(), v.clone());
+        let entry = StorageEntry::from_key(&k, value);
+        self.storage.insert(k.clone(), entry);
         self.track_write(&k);
     }
 
@@ -376,75 +1427,345 @@ impl StorageInspector {
 
     /// Capture a snapshot of all storage entries from the host
     pub fn capture_snapshot(host: &Host) -> HashMap<String, String> {
-        match host.with_mut_storage(|storage| {
-            let mut snapshot = HashMap::new();
+        Self::capture_snapshot_with_cache(host, &mut RenderCache::new())
+    }
+
+    /// Capture a snapshot, reusing renderings of repeated keys and values.
+    ///
+    /// Callers that capture several snapshots of the same host (before/after
+    /// diffs, repeat runs) should keep one [`RenderCache`] alive across calls.
+    pub fn capture_snapshot_with_cache(
+        host: &Host,
+        cache: &mut RenderCache,
+    ) -> HashMap<String, String> {
+        match host.with_mut_storage(|storage| {
+            let mut snapshot = HashMap::new();
 
             for (key, entry_opt) in storage.map.iter(host.as_budget())? {
                 let Some((entry, ttl)) = entry_opt.as_ref() else {
                     continue;
                 };
 
-                let key_str = match key.as_ref() {
-                    LedgerKey::ContractData(cd) => {
-                        format!("contract_data:{:?}:{:?}", cd.durability, cd.key)
+                let key_str = Self::render_ledger_key(key, cache);
+                snapshot.insert(
+                    key_str,
+                    Self::render_entry_value(&entry.as_ref().data, *ttl, cache),
+                );
+            }
+
+            Ok(snapshot)
+        }) {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                tracing::warn!("Failed to capture storage snapshot: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// A snapshot value: the contract data value, or the debug form of
+    /// other entries, followed by the ledger it lives until.
+    fn render_entry_value(
+        data: &LedgerEntryData,
+        ttl: Option<u32>,
+        cache: &mut RenderCache,
+    ) -> String {
+        let mut value = match data {
+            LedgerEntryData::ContractData(cd) => cache.render_val(&cd.val).to_string(),
+            other => format!("{:?}", other),
+        };
+        if let Some(live_until) = ttl {
+            value.push_str(&format!(" (ttl={})", live_until));
+        }
+        value
+    }
+
+    /// Capture every storage entry with the storage it lives in, read from
+    /// the ledger key rather than its rendering, and the ledger it lives
+    /// until.
+    ///
+    /// Contract instances are listed by their items, keyed `instance:<key>`
+    /// as in [`Self::capture_instance_with_cache`], instead of as the one
+    /// instance entry; each item carries the instance's live-until ledger.
+    pub fn capture_entries_with_cache(
+        host: &Host,
+        cache: &mut RenderCache,
+    ) -> HashMap<String, StorageEntry> {
+        match host.with_mut_storage(|storage| {
+            let mut entries = HashMap::new();
+            for (key, entry_opt) in storage.map.iter(host.as_budget())? {
+                let Some((entry, ttl)) = entry_opt.as_ref() else {
+                    continue;
+                };
+                let cd = match &entry.as_ref().data {
+                    LedgerEntryData::ContractData(cd) => cd,
+                    other => {
+                        let stored = StorageEntry {
+                            key: Self::render_ledger_key(key, cache),
+                            value: Self::render_entry_value(other, None, cache),
+                            durability: None,
+                            live_until: *ttl,
+                            size_bytes: xdr_size(entry.as_ref()),
+                            scval_key: None,
+                            scval_value: None,
+                        };
+                        entries.insert(stored.key.clone(), stored);
+                        continue;
+                    }
+                };
+                if let ScVal::ContractInstance(instance) = &cd.val {
+                    let prefix = match cache.contract_label(&cd.contract) {
+                        Some(label) => format!("{}/instance:", label),
+                        None => "instance:".to_string(),
+                    };
+                    for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                        let stored = StorageEntry {
+                            key: format!("{}{}", prefix, cache.render_val(&item.key)),
+                            value: cache.render_val(&item.val).to_string(),
+                            durability: Some(Durability::Instance),
+                            live_until: *ttl,
+                            size_bytes: xdr_size(item),
+                            scval_key: Some(item.key.clone()),
+                            scval_value: Some(item.val.clone()),
+                        };
+                        entries.insert(stored.key.clone(), stored);
                     }
-                    LedgerKey::ContractCode(_) => "contract_code".to_string(),
-                    other => format!("{:?}", other),
+                    continue;
+                }
+                let stored = StorageEntry {
+                    key: Self::render_ledger_key(key, cache),
+                    value: cache.render_val(&cd.val).to_string(),
+                    durability: Some(Durability::of(&cd.key, cd.durability)),
+                    live_until: *ttl,
+                    size_bytes: xdr_size(entry.as_ref()),
+                    scval_key: Some(cd.key.clone()),
+                    scval_value: Some(cd.val.clone()),
                 };
+                entries.insert(stored.key.clone(), stored);
+            }
+            Ok(entries)
+        }) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to capture storage entries: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Capture contract data values without rendering them.
+    ///
+    /// Keys are rendered as in [`Self::capture_snapshot_with_cache`]; each
+    /// value is kept with the ledger it lives until so that it can be
+    /// rendered later, e.g. as a [`LazyValue`](crate::report::lazy::LazyValue).
+    pub fn capture_values(
+        host: &Host,
+        cache: &mut RenderCache,
+    ) -> HashMap<String, (ScVal, Option<u32>)> {
+        match host.with_mut_storage(|storage| {
+            let mut values = HashMap::new();
 
-                let mut value_str = match &entry.as_ref().data {
-                    LedgerEntryData::ContractData(cd) => format!("{:?}", cd.val),
-                    other => format!("{:?}", other),
+            for (key, entry_opt) in storage.map.iter(host.as_budget())? {
+                let Some((entry, ttl)) = entry_opt.as_ref() else {
+                    continue;
                 };
+                if let LedgerEntryData::ContractData(cd) = &entry.as_ref().data {
+                    values.insert(Self::render_ledger_key(key, cache), (cd.val.clone(), *ttl));
+                }
+            }
 
-                if let Some(live_until) = ttl {
-                    value_str.push_str(&format!(" (ttl={})", live_until));
+            Ok(values)
+        }) {
+            Ok(values) => values,
+            Err(e) => {
+                tracing::warn!("Failed to capture storage values: {}", e);
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Snapshot key for a ledger key, as used by [`Self::capture_snapshot_with_cache`].
+    ///
+    /// Data of a contract the cache has a label for is prefixed `<label>/`.
+    pub fn render_ledger_key(key: &LedgerKey, cache: &mut RenderCache) -> String {
+        match key {
+            LedgerKey::ContractData(cd) => {
+                let rendered = cache.render_storage_key(cd.durability, &cd.key);
+                match cache.contract_label(&cd.contract) {
+                    Some(label) => format!("{}/{}", label, rendered),
+                    None => rendered,
                 }
+            }
+            LedgerKey::ContractCode(_) => "contract_code".to_string(),
+            other => format!("{:?}", other),
+        }
+    }
 
-                snapshot.insert(key_str, value_str);
+    /// Capture the entries inside contract instances, keyed `instance:<key>`.
+    ///
+    /// Instance storage is a single ledger entry in [`Self::capture_snapshot`];
+    /// this tells its keys apart, e.g. for per-key write provenance.
+    pub fn capture_instance_with_cache(
+        host: &Host,
+        cache: &mut RenderCache,
+    ) -> HashMap<String, String> {
+        match host.with_mut_storage(|storage| {
+            let mut entries = HashMap::new();
+            for (_, entry_opt) in storage.map.iter(host.as_budget())? {
+                let Some((entry, _)) = entry_opt.as_ref() else {
+                    continue;
+                };
+                let LedgerEntryData::ContractData(cd) = &entry.as_ref().data else {
+                    continue;
+                };
+                let ScVal::ContractInstance(instance) = &cd.val else {
+                    continue;
+                };
+                let prefix = match cache.contract_label(&cd.contract) {
+                    Some(label) => format!("{}/instance:", label),
+                    None => "instance:".to_string(),
+                };
+                for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                    entries.insert(
+                        format!("{}{}", prefix, cache.render_val(&item.key)),
+                        cache.render_val(&item.val).to_string(),
+                    );
+                }
             }
+            Ok(entries)
+        }) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to capture instance storage: {}", e);
+                HashMap::new()
+            }
+        }
+    }
 
-            Ok(snapshot)
+    /// The contract each contract data key belongs to, as a strkey, keyed
+    /// like [`Self::capture_snapshot_with_cache`], with the items inside
+    /// contract instances keyed `instance:<key>` and the instance entries
+    /// themselves left out. Removed entries are included.
+    pub fn capture_owners_with_cache(
+        host: &Host,
+        cache: &mut RenderCache,
+    ) -> HashMap<String, String> {
+        match host.with_mut_storage(|storage| {
+            let mut owners = HashMap::new();
+            for (key, entry_opt) in storage.map.iter(host.as_budget())? {
+                let LedgerKey::ContractData(cd) = key.as_ref() else {
+                    continue;
+                };
+                if cd.key != ScVal::LedgerKeyContractInstance {
+                    owners.insert(Self::render_ledger_key(key, cache), cd.contract.to_string());
+                    continue;
+                }
+                let Some((entry, _)) = entry_opt.as_ref() else {
+                    continue;
+                };
+                let LedgerEntryData::ContractData(data) = &entry.as_ref().data else {
+                    continue;
+                };
+                let ScVal::ContractInstance(instance) = &data.val else {
+                    continue;
+                };
+                let prefix = match cache.contract_label(&cd.contract) {
+                    Some(label) => format!("{}/instance:", label),
+                    None => "instance:".to_string(),
+                };
+                for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                    owners.insert(
+                        format!("{}{}", prefix, cache.render_val(&item.key)),
+                        cd.contract.to_string(),
+                    );
+                }
+            }
+            Ok(owners)
         }) {
-            Ok(snapshot) => snapshot,
+            Ok(owners) => owners,
             Err(e) => {
-                tracing::warn!("Failed to capture storage snapshot: {}", e);
+                tracing::warn!("Failed to capture storage owners: {}", e);
                 HashMap::new()
             }
         }
     }
 
+    /// Capture contract storage as JSON keyed by the decoded storage key.
+    ///
+    /// Symbol and string keys are used as-is and other keys as compact JSON.
+    /// Instance storage is flattened in alongside persistent and temporary
+    /// entries, so `--extract 'storage["c"]'` finds a key wherever it lives.
+    pub fn capture_json(host: &Host) -> serde_json::Map<String, serde_json::Value> {
+        let json_key = |key: &ScVal| match scval_to_json(key) {
+            serde_json::Value::String(s) => s,
+            other => other.to_string(),
+        };
+        match host.with_mut_storage(|storage| {
+            let mut entries = serde_json::Map::new();
+            for (_, entry_opt) in storage.map.iter(host.as_budget())? {
+                let Some((entry, _)) = entry_opt.as_ref() else {
+                    continue;
+                };
+                let LedgerEntryData::ContractData(cd) = &entry.as_ref().data else {
+                    continue;
+                };
+                match (&cd.key, &cd.val) {
+                    (ScVal::LedgerKeyContractInstance, ScVal::ContractInstance(instance)) => {
+                        for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                            entries.insert(json_key(&item.key), scval_to_json(&item.val));
+                        }
+                    }
+                    (key, val) => {
+                        entries.insert(json_key(key), scval_to_json(val));
+                    }
+                }
+            }
+            Ok(entries)
+        }) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Failed to capture storage as JSON: {}", e);
+                serde_json::Map::new()
+            }
+        }
+    }
+
     /// Compute the difference between two storage snapshots
     pub fn compute_diff(
         before: &HashMap<String, String>,
         after: &HashMap<String, String>,
         alerts: &[String],
+    ) -> StorageDiff {
+        Self::compute_diff_with_writes(before, after, &HashSet::new(), alerts)
+    }
+
+    /// Like [`Self::compute_diff`], with the keys the call wrote so that
+    /// `==` alert predicates also see writes that left the value unchanged.
+    pub fn compute_diff_with_writes(
+        before: &HashMap<String, String>,
+        after: &HashMap<String, String>,
+        written: &HashSet<String>,
+        alerts: &[String],
     ) -> StorageDiff {
         let mut added = HashMap::new();
         let mut modified = HashMap::new();
+        let mut live_until = HashMap::new();
         let mut deleted = Vec::new();
-        let mut triggered_alerts = Vec::new();
-
-        let alert_filter = StorageFilter::new(alerts).unwrap_or_else(|e| {
-            tracing::warn!("Invalid alert pattern: {}", e);
-            StorageFilter::new(&[]).unwrap()
-        });
 
         for (key, val_after) in after {
             match before.get(key) {
                 Some(val_before) => {
                     if val_before != val_after {
                         modified.insert(key.clone(), (val_before.clone(), val_after.clone()));
-                        if !alerts.is_empty() && alert_filter.matches(key) {
-                            triggered_alerts.push(key.clone());
+                        let (ttl_before, ttl_after) =
+                            (split_ttl(val_before).1, split_ttl(val_after).1);
+                        if ttl_before != ttl_after {
+                            live_until.insert(key.clone(), (ttl_before, ttl_after));
                         }
                     }
                 }
                 None => {
                     added.insert(key.clone(), val_after.clone());
-                    if !alerts.is_empty() && alert_filter.matches(key) {
-                        triggered_alerts.push(key.clone());
-                    }
                 }
             }
         }
@@ -452,101 +1773,630 @@ impl StorageInspector {
         for key in before.keys() {
             if !after.contains_key(key) {
                 deleted.push(key.clone());
-                if !alerts.is_empty() && alert_filter.matches(key) {
-                    triggered_alerts.push(key.clone());
-                }
             }
         }
 
+        let alerts = AlertObserver::over(before, after, written, alerts);
+
         StorageDiff {
             added,
             modified,
+            live_until,
             deleted,
-            triggered_alerts,
+            triggered_alerts: alerts.triggered,
+            alert_predicates: alerts.predicates,
+            alert_values: alerts.values,
+            writers: HashMap::new(),
+            alert_writes: HashMap::new(),
+            entries: None,
+            raw_xdr: false,
         }
     }
 
-    /// Display a color-coded storage diff
+    /// Print the color-coded storage diff, with any triggered alerts, to
+    /// stdout (with `--quiet` it is left out), and log the alerts as errors.
     pub fn display_diff(diff: &StorageDiff) {
-        if diff.is_empty() {
-            crate::logging::log_display("Storage: (no changes)", crate::logging::LogLevel::Info);
-            return;
+        if !Formatter::is_quiet() {
+            println!("{}", diff);
+        }
+        Self::display_alerts(diff);
+    }
+
+    /// Log the alerts `diff` triggered as errors, with the old and new value
+    /// of each key.
+    pub fn display_alerts(diff: &StorageDiff) {
+        let alerts = diff.alert_lines();
+        if !alerts.is_empty() {
+            crate::logging::log_display(
+                format!("\n{}", paint_bold(ALERT_HEADER, Color::Red)),
+                crate::logging::LogLevel::Error,
+            );
+            for line in alerts {
+                crate::logging::log_display(line, crate::logging::LogLevel::Error);
+            }
         }
+    }
+}
 
-        crate::logging::log_display("Storage Changes:", crate::logging::LogLevel::Info);
+/// Heading of the triggered alerts in a rendered [`StorageDiff`].
+const ALERT_HEADER: &str = "!!! CRITICAL STORAGE ALERT !!!";
 
-        // Sort keys for deterministic output
-        let mut added_keys: Vec<_> = diff.added.keys().collect();
+/// `text` in `color`, or as it is with colors off (`NO_COLOR`).
+fn paint(text: impl fmt::Display, color: Color) -> String {
+    if Formatter::colors_enabled() {
+        text.to_string().with(color).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Like [`paint`], in bold.
+fn paint_bold(text: impl fmt::Display, color: Color) -> String {
+    if Formatter::colors_enabled() {
+        text.to_string().with(color).bold().to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Represents the differences between two storage states
+#[derive(Debug, Clone, Default)]
+pub struct StorageDiff {
+    pub added: HashMap<String, String>,
+    pub modified: HashMap<String, (String, String)>,
+    /// Live-until ledger before and after, for modified keys whose TTL
+    /// changed, e.g. through `extend_ttl`.
+    pub live_until: HashMap<String, (Option<u32>, Option<u32>)>,
+    pub deleted: Vec<String>,
+    pub triggered_alerts: Vec<String>,
+    /// The predicate rule that fired, for alerts raised by one.
+    pub alert_predicates: HashMap<String, String>,
+    /// Value before and after the write each alert fired on; `None` when
+    /// the key was absent.
+    pub alert_values: HashMap<String, (Option<String>, Option<String>)>,
+    /// Who wrote each changed key, when provenance is tracked.
+    pub writers: HashMap<String, Writer>,
+    /// Every write of each alerted key, oldest first, with the frame that
+    /// made it, when writes are tracked.
+    pub alert_writes: HashMap<String, Vec<StorageMutation>>,
+    /// The changed entries, with contract instances expanded per key, once
+    /// attached with [`Self::with_entries`]: shown and serialized decoded
+    /// from their ScVals in place of the snapshot strings above.
+    pub entries: Option<BTreeMap<String, EntryChange>>,
+    /// Also show the base64 XDR of the changed entries, see
+    /// [`Self::with_raw_xdr`].
+    pub raw_xdr: bool,
+}
+
+/// A captured storage entry that a call added, changed or removed: `before`
+/// is `None` for an added entry and `after` for a removed one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntryChange {
+    pub before: Option<StorageEntry>,
+    pub after: Option<StorageEntry>,
+}
+
+impl EntryChange {
+    /// The entry after the call, or before it if it was removed.
+    fn entry(&self) -> Option<&StorageEntry> {
+        self.after.as_ref().or(self.before.as_ref())
+    }
+
+    /// Added first, then modified, then removed, as the diff lists them.
+    fn rank(&self) -> u8 {
+        match (&self.before, &self.after) {
+            (None, _) => 0,
+            (Some(_), Some(_)) => 1,
+            (Some(_), None) => 2,
+        }
+    }
+}
+
+/// How the key of a triggered alert changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertChange {
+    Added,
+    Modified,
+    Removed,
+    /// Written with the value it already held.
+    Rewritten,
+}
+
+impl AlertChange {
+    fn of(old: Option<&str>, new: Option<&str>) -> Self {
+        match (old, new) {
+            (None, _) => Self::Added,
+            (Some(_), None) => Self::Removed,
+            (Some(old), Some(new)) if old == new => Self::Rewritten,
+            _ => Self::Modified,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Modified => "modified",
+            Self::Removed => "removed",
+            Self::Rewritten => "rewritten",
+        }
+    }
+}
+
+/// A triggered `--alert-on-change` alert, as listed under `result.alerts`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageAlert {
+    pub key: String,
+    pub durability: Option<Durability>,
+    pub change: AlertChange,
+    /// `None` when the key was absent before.
+    pub old: Option<String>,
+    /// `None` when the key was removed.
+    pub new: Option<String>,
+    /// The predicate rule that fired, if one did.
+    pub rule: Option<String>,
+    /// Every tracked write of the key, oldest first, each with the
+    /// contract and function that made it.
+    pub writes: Vec<StorageMutation>,
+}
+
+impl StorageDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+
+    /// Attach the entries captured before and after the call, see
+    /// [`StorageInspector::capture_entries_with_cache`], so that the changes
+    /// are shown decoded and a contract instance's changes key by key.
+    pub fn with_entries(
+        mut self,
+        before: &HashMap<String, StorageEntry>,
+        after: &HashMap<String, StorageEntry>,
+    ) -> Self {
+        let mut entries = BTreeMap::new();
+        for key in before.keys().chain(after.keys()) {
+            let (old, new) = (before.get(key), after.get(key));
+            let unchanged = matches!((old, new), (Some(old), Some(new))
+                if old.value == new.value
+                    && old.scval_value == new.scval_value
+                    && old.live_until == new.live_until);
+            if !unchanged {
+                entries.insert(
+                    key.clone(),
+                    EntryChange {
+                        before: old.cloned(),
+                        after: new.cloned(),
+                    },
+                );
+            }
+        }
+        self.entries = Some(entries);
+        self
+    }
+
+    /// Show the base64 XDR of each changed key and value as well, for
+    /// `--raw-xdr`; only entries attached with [`Self::with_entries`] have it.
+    pub fn with_raw_xdr(mut self, raw_xdr: bool) -> Self {
+        self.raw_xdr = raw_xdr;
+        self
+    }
+
+    /// Attach the last writer of every changed key.
+    pub fn with_writers(mut self, provenance: &StorageProvenance) -> Self {
+        self.writers = self
+            .added
+            .keys()
+            .chain(self.modified.keys())
+            .chain(&self.deleted)
+            .chain(self.entries.iter().flat_map(BTreeMap::keys))
+            .filter_map(|key| Some((key.clone(), provenance.get(key)?.writer.clone())))
+            .collect();
+        self
+    }
+
+    /// Also raise `alerts` on the keys inside contract instances, keyed
+    /// `instance:<key>` as by [`StorageInspector::capture_instance_with_cache`],
+    /// which the ledger entries see as one change of the whole instance.
+    pub fn with_instance_alerts(
+        mut self,
+        before: &HashMap<String, String>,
+        after: &HashMap<String, String>,
+        alerts: &[String],
+    ) -> Self {
+        let instance = AlertObserver::over(before, after, &HashSet::new(), alerts);
+        self.triggered_alerts.extend(instance.triggered);
+        self.alert_predicates.extend(instance.predicates);
+        self.alert_values.extend(instance.values);
+        self
+    }
+
+    /// Attach every write of each key a triggered alert fired on.
+    pub fn with_history(mut self, history: &StorageHistory) -> Self {
+        self.alert_writes = self
+            .triggered_alerts
+            .iter()
+            .filter_map(|key| {
+                let writes: Vec<StorageMutation> = history.writes_of(key).cloned().collect();
+                (!writes.is_empty()).then(|| (key.clone(), writes))
+            })
+            .collect();
+        self
+    }
+
+    /// The triggered alerts, sorted by key.
+    pub fn alerts(&self) -> Vec<StorageAlert> {
+        let mut keys = self.triggered_alerts.clone();
+        keys.sort();
+        keys.into_iter()
+            .map(|key| {
+                let (old, new) = self.alert_values.get(&key).cloned().unwrap_or_default();
+                StorageAlert {
+                    durability: StorageInspector::durability(&key),
+                    change: AlertChange::of(old.as_deref(), new.as_deref()),
+                    old,
+                    new,
+                    rule: self.alert_predicates.get(&key).cloned(),
+                    writes: self.alert_writes.get(&key).cloned().unwrap_or_default(),
+                    key,
+                }
+            })
+            .collect()
+    }
+
+    /// The changes as the pretty renderer shows them, sorted by key.
+    fn change_lines(&self) -> Vec<String> {
+        if self.is_empty() {
+            return vec!["Storage: (no changes)".to_string()];
+        }
+        let mut lines = vec!["Storage Changes:".to_string()];
+        if self.entries.is_some() {
+            for (label, key, change) in self.entry_changes() {
+                self.push_entry_change(&mut lines, &label, key, change);
+            }
+            return lines;
+        }
+
+        let mut added_keys: Vec<_> = self.added.keys().collect();
         added_keys.sort();
         for key in added_keys {
-            crate::logging::log_display(
-                format!(
-                    "  {} {} = {}",
-                    "+".with(Color::Green),
-                    key,
-                    diff.added[key].clone().with(Color::Green)
-                ),
-                crate::logging::LogLevel::Info,
-            );
+            lines.push(format!(
+                "  {} {} = {}{}",
+                paint("+", Color::Green),
+                key,
+                paint(&self.added[key], Color::Green),
+                self.writer_note(key)
+            ));
         }
 
-        let mut modified_keys: Vec<_> = diff.modified.keys().collect();
+        let mut modified_keys: Vec<_> = self.modified.keys().collect();
         modified_keys.sort();
         for key in modified_keys {
-            let (old, new) = &diff.modified[key];
-            crate::logging::log_display(
+            let (old, new) = &self.modified[key];
+            let ttl = self.live_until.get(key);
+            let change = match ttl {
+                Some(_) => Self::value_change(split_ttl(old).0, split_ttl(new).0, ttl),
+                None => Self::value_change(old, new, None),
+            };
+            lines.push(format!(
+                "  {} {}: {}{}",
+                paint("~", Color::Yellow),
+                key,
+                change,
+                self.writer_note(key)
+            ));
+        }
+
+        let mut deleted_keys = self.deleted.clone();
+        deleted_keys.sort();
+        for key in deleted_keys {
+            let note = self.writer_note(&key);
+            lines.push(format!(
+                "  {} {}{}",
+                paint("-", Color::Red),
+                paint(&key, Color::Red),
+                note
+            ));
+        }
+        lines
+    }
+
+    /// `old -> new`, with the live-until change when `ttl` is given, or that
+    /// alone when only the TTL moved, as after `extend_ttl`.
+    fn value_change(old: &str, new: &str, ttl: Option<&(Option<u32>, Option<u32>)>) -> String {
+        match ttl {
+            Some(ttl) if old == new => format!(
+                "{} (value unchanged)",
+                paint(Self::live_until_change(ttl), Color::Green)
+            ),
+            Some(ttl) => format!(
+                "{} -> {}  ({})",
+                paint(old, Color::Red),
+                paint(new, Color::Green),
+                Self::live_until_change(ttl)
+            ),
+            None => format!("{} -> {}", paint(old, Color::Red), paint(new, Color::Green)),
+        }
+    }
+
+    /// The attached entry changes with the key each is shown under: the
+    /// decoded key, or the snapshot key where two entries decode alike.
+    /// Added, then modified, then removed entries, each by storage and key.
+    fn entry_changes(&self) -> Vec<(String, &String, &EntryChange)> {
+        let Some(entries) = &self.entries else {
+            return Vec::new();
+        };
+        let mut changes: Vec<(String, &String, &EntryChange)> = entries
+            .iter()
+            .filter_map(|(key, change)| {
+                let (label, _) = StorageInspector::shown(key, change.entry()?);
+                Some((label, key, change))
+            })
+            .collect();
+        let mut uses: HashMap<String, usize> = HashMap::new();
+        for (label, _, _) in &changes {
+            *uses.entry(label.clone()).or_default() += 1;
+        }
+        for (label, key, _) in &mut changes {
+            if uses[label.as_str()] > 1 {
+                *label = key.to_string();
+            }
+        }
+        let group = |change: &EntryChange| {
+            let durability = change.entry().and_then(|entry| entry.durability);
+            DURABILITY_GROUPS
+                .iter()
+                .position(|(group, _)| *group == durability)
+        };
+        changes.sort_by(|a, b| (a.2.rank(), group(a.2), &a.0).cmp(&(b.2.rank(), group(b.2), &b.0)));
+        changes
+    }
+
+    /// The lines of one attached entry change shown as `label`, followed by
+    /// the base64 XDR of its key and values with [`Self::with_raw_xdr`].
+    fn push_entry_change(
+        &self,
+        lines: &mut Vec<String>,
+        label: &str,
+        key: &str,
+        change: &EntryChange,
+    ) {
+        let shown = |entry: &StorageEntry| StorageInspector::shown(key, entry).1;
+        let line = match (&change.before, &change.after) {
+            (None, Some(new)) => format!(
+                "  {} {} = {}",
+                paint("+", Color::Green),
+                label,
+                paint(shown(new), Color::Green)
+            ),
+            (Some(old), Some(new)) => {
+                let ttl =
+                    (old.live_until != new.live_until).then_some((old.live_until, new.live_until));
                 format!(
-                    "  {} {}: {} -> {}",
-                    "~".with(Color::Yellow),
-                    key,
-                    old.clone().with(Color::Red),
-                    new.clone().with(Color::Green)
+                    "  {} {}: {}",
+                    paint("~", Color::Yellow),
+                    label,
+                    Self::value_change(&shown(old), &shown(new), ttl.as_ref())
+                )
+            }
+            (Some(_), None) => format!("  {} {}", paint("-", Color::Red), paint(label, Color::Red)),
+            (None, None) => return,
+        };
+        lines.push(format!("{}{}", line, self.writer_note(key)));
+
+        if self.raw_xdr {
+            let stored_key = change.entry().and_then(|entry| entry.scval_key.as_ref());
+            let values = [
+                ("key", stored_key),
+                (
+                    "old",
+                    change.before.as_ref().and_then(|e| e.scval_value.as_ref()),
                 ),
-                crate::logging::LogLevel::Info,
+                (
+                    "new",
+                    change.after.as_ref().and_then(|e| e.scval_value.as_ref()),
+                ),
+            ];
+            for (part, val) in values {
+                if let Some(encoded) = val.and_then(raw_xdr) {
+                    lines.push(paint(
+                        format!("    {} xdr: {}", part, encoded),
+                        Color::DarkGrey,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// One line per triggered alert, sorted by key: how the key changed,
+    /// its storage, and the value before and after. A key written once is
+    /// followed by the frame that wrote it; a key written several times by
+    /// one line per write.
+    fn alert_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for alert in self.alerts() {
+            let storage = match alert.durability {
+                Some(durability) => format!(" in {} storage", durability.name()),
+                None => String::new(),
+            };
+            let matched = match &alert.rule {
+                Some(rule) => format!(" matched `{}`", rule),
+                None => String::new(),
+            };
+            let frame = match alert.writes.as_slice() {
+                [write] => format!(
+                    "  {}",
+                    paint(
+                        format!("[{} by {}]", write.operation.name(), write.frame()),
+                        Color::DarkGrey
+                    )
+                ),
+                _ => String::new(),
+            };
+            lines.push(format!(
+                "  {} was {}{}: {} -> {}{}{}",
+                paint_bold(&alert.key, Color::Red),
+                alert.change.name(),
+                storage,
+                alert.old.as_deref().unwrap_or("<absent>"),
+                alert.new.as_deref().unwrap_or("<absent>"),
+                matched,
+                frame
+            ));
+            if alert.writes.len() > 1 {
+                for write in &alert.writes {
+                    lines.push(paint(
+                        format!(
+                            "    step {} {}: {} -> {}  [{}]",
+                            write.step,
+                            write.operation.name(),
+                            write.old.as_deref().unwrap_or("<absent>"),
+                            write.new.as_deref().unwrap_or("<absent>"),
+                            write.frame()
+                        ),
+                        Color::DarkGrey,
+                    ));
+                }
+            }
+        }
+        lines
+    }
+
+    /// `live until A -> B`, with `-` for a side that had no TTL.
+    fn live_until_change((before, after): &(Option<u32>, Option<u32>)) -> String {
+        let ledger = |ttl: &Option<u32>| ttl.map_or("-".to_string(), |l| l.to_string());
+        format!("live until {} -> {}", ledger(before), ledger(after))
+    }
+
+    fn writer_note(&self, key: &str) -> String {
+        match self.writers.get(key) {
+            Some(writer) => format!("  {}", paint(format!("[{}]", writer), Color::DarkGrey)),
+            None => String::new(),
+        }
+    }
+
+    /// The diff as JSON output shows it: `added`, `modified` (`[old, new]`),
+    /// `deleted` and `triggered_alerts`, plus `live_until`,
+    /// `alert_predicates`, `alert_values`, `writers` and `alert_writes` when
+    /// set. Attached entries are listed by decoded key with decoded values,
+    /// and with [`Self::with_raw_xdr`] the base64 XDR of each under `xdr`.
+    fn to_json(&self) -> serde_json::Value {
+        use serde_json::{json, Map, Value};
+
+        let mut out = Map::new();
+        let mut writers = Map::new();
+        if self.entries.is_some() {
+            let decoded = |key: &str, entry: &StorageEntry| match &entry.scval_value {
+                Some(value) => decode_json(value),
+                None => Value::String(StorageInspector::shown(key, entry).1),
+            };
+            let (mut added, mut modified, mut live_until, mut xdr) =
+                (Map::new(), Map::new(), Map::new(), Map::new());
+            let mut deleted = Vec::new();
+            for (label, key, change) in self.entry_changes() {
+                match (&change.before, &change.after) {
+                    (None, Some(new)) => {
+                        added.insert(label.clone(), decoded(key, new));
+                    }
+                    (Some(old), Some(new)) => {
+                        modified
+                            .insert(label.clone(), json!([decoded(key, old), decoded(key, new)]));
+                        if old.live_until != new.live_until {
+                            live_until
+                                .insert(label.clone(), json!([old.live_until, new.live_until]));
+                        }
+                    }
+                    (Some(_), None) => deleted.push(label.clone()),
+                    (None, None) => continue,
+                }
+                if let Some(writer) = self.writers.get(key) {
+                    writers.insert(label.clone(), json!(writer));
+                }
+                if self.raw_xdr {
+                    let encoded = |entry: &Option<StorageEntry>, key: bool| {
+                        let entry = entry.as_ref()?;
+                        raw_xdr(if key {
+                            entry.scval_key.as_ref()?
+                        } else {
+                            entry.scval_value.as_ref()?
+                        })
+                    };
+                    let stored = change
+                        .after
+                        .as_ref()
+                        .map_or(&change.before, |_| &change.after);
+                    xdr.insert(
+                        label,
+                        json!({
+                            "key": encoded(stored, true),
+                            "old": encoded(&change.before, false),
+                            "new": encoded(&change.after, false),
+                        }),
+                    );
+                }
+            }
+            out.insert("added".into(), Value::Object(added));
+            out.insert("modified".into(), Value::Object(modified));
+            out.insert("deleted".into(), json!(deleted));
+            if !live_until.is_empty() {
+                out.insert("live_until".into(), Value::Object(live_until));
+            }
+            if self.raw_xdr {
+                out.insert("xdr".into(), Value::Object(xdr));
+            }
+        } else {
+            out.insert("added".into(), json!(self.added));
+            out.insert("modified".into(), json!(self.modified));
+            out.insert("deleted".into(), json!(self.deleted));
+            if !self.live_until.is_empty() {
+                out.insert("live_until".into(), json!(self.live_until));
+            }
+            writers.extend(
+                self.writers
+                    .iter()
+                    .map(|(key, writer)| (key.clone(), json!(writer))),
             );
         }
-
-        let mut deleted_keys = diff.deleted.clone();
-        deleted_keys.sort();
-        for key in deleted_keys {
-            crate::logging::log_display(
-                format!("  {} {}", "-".with(Color::Red), key.with(Color::Red)),
-                crate::logging::LogLevel::Info,
-            );
+        out.insert("triggered_alerts".into(), json!(self.triggered_alerts));
+        if !self.alert_predicates.is_empty() {
+            out.insert("alert_predicates".into(), json!(self.alert_predicates));
         }
-
-        if !diff.triggered_alerts.is_empty() {
-            crate::logging::log_display(
-                format!(
-                    "\n{}",
-                    "!!! CRITICAL STORAGE ALERT !!!".with(Color::Red).bold()
-                ),
-                crate::logging::LogLevel::Error,
-            );
-            let mut alerts = diff.triggered_alerts.clone();
-            alerts.sort();
-            for key in alerts {
-                crate::logging::log_display(
-                    format!("  {} was modified!", key.with(Color::Red).bold()),
-                    crate::logging::LogLevel::Error,
-                );
-            }
+        if !self.alert_values.is_empty() {
+            out.insert("alert_values".into(), json!(self.alert_values));
+        }
+        if !writers.is_empty() {
+            out.insert("writers".into(), Value::Object(writers));
         }
+        if !self.alert_writes.is_empty() {
+            out.insert("alert_writes".into(), json!(self.alert_writes));
+        }
+        Value::Object(out)
     }
 }
 
-/// Represents the differences between two storage states
-#[derive(Debug, Clone, Default, Serialize)]
-pub struct StorageDiff {
-    pub added: HashMap<String, String>,
-    pub modified: HashMap<String, (String, String)>,
-    pub deleted: Vec<String>,
-    pub triggered_alerts: Vec<String>,
+impl Serialize for StorageDiff {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        self.to_json().serialize(serializer)
+    }
 }
 
-impl StorageDiff {
-    pub fn is_empty(&self) -> bool {
-        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+/// The pretty rendering: one line per added (`+`), modified (`~`) and removed
+/// (`-`) key, then the triggered alerts.
+impl fmt::Display for StorageDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.change_lines().join("\n"))?;
+        let alerts = self.alert_lines();
+        if !alerts.is_empty() {
+            write!(
+                f,
+                "\n\n{}\n{}",
+                paint_bold(ALERT_HEADER, Color::Red),
+                alerts.join("\n")
+            )?;
+        }
+        Ok(())
     }
 }
 
@@ -675,6 +2525,135 @@ mod tests {
         assert!(summary.contains("admin"));
     }
 
+    #[test]
+    fn test_value_patterns_combine_with_key_patterns() {
+        let matches = |filter: &StorageFilter, key: &str, value: &str| {
+            filter.matches_entry(key, &StorageEntry::from_key(key, value))
+        };
+        let filter = StorageFilter::new(&[
+            "balance:*".to_string(),
+            r"val:re:^I128\(\d{4,}\)$".to_string(),
+            "val:Bool*".to_string(),
+        ])
+        .unwrap();
+        assert!(matches(&filter, "balance:alice", "I128(1000)"));
+        assert!(matches(&filter, "balance:bob", "Bool(true) (ttl=40)"));
+        assert!(!matches(&filter, "balance:carol", "I128(999)"));
+        assert!(!matches(&filter, "total_supply", "I128(5000)"));
+        assert!(filter.matches("balance:carol"));
+        assert_eq!(
+            filter.summary(),
+            r"balance:*, val:re:^I128\(\d{4,}\)$, val:Bool*"
+        );
+
+        let values_only = StorageFilter::new(&["val:I64(7)".to_string()]).unwrap();
+        assert!(!values_only.is_empty());
+        assert!(matches(&values_only, "instance:c", "I64(7)"));
+        assert!(!matches(&values_only, "instance:c", "I64(70)"));
+    }
+
+    #[test]
+    fn test_patterns_match_decoded_keys_and_values() {
+        let holder = soroban_env_host::xdr::ScAddress::Contract([7; 32].into());
+        let key = ScVal::Vec(Some(soroban_env_host::xdr::ScVec(
+            vec![
+                ScVal::Symbol("Balance".to_string().try_into().unwrap()),
+                ScVal::Address(holder.clone()),
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+        let snapshot_key = format!("contract_data:Persistent:{:?}", key);
+        let entry = StorageEntry {
+            scval_key: Some(key),
+            scval_value: Some(ScVal::U64(25_000)),
+            ..StorageEntry::from_key(&snapshot_key, "U64(25000)")
+        };
+
+        let matches = |patterns: &[&str]| {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            StorageFilter::new(&patterns)
+                .unwrap()
+                .matches_entry(&snapshot_key, &entry)
+        };
+        assert!(matches(&["Balance*"]));
+        assert!(matches(&[&format!("Balance({})", holder)]));
+        assert!(matches(&["contract_data:Persistent:*", r"val:re:^\d{4,}$"]));
+        assert!(matches(&["val:U64(25000)"]));
+        assert!(!matches(&["Allowance*"]));
+        assert!(!matches(&["Balance*", "val:12"]));
+        assert!(!matches(&["!Balance*"]));
+        assert!(!matches(&["!contract_data:*"]));
+        assert!(matches(&["!Allowance*", "!val:0"]));
+    }
+
+    #[test]
+    fn test_exclusions_with_only_negative_patterns() {
+        let filter =
+            StorageFilter::new(&["!__meta:*".to_string(), "!re:^tmp_".to_string()]).unwrap();
+        assert!(!filter.is_empty());
+        assert!(filter.matches("balance:alice"));
+        assert!(filter.matches("admin"));
+        assert!(!filter.matches("__meta:version"));
+        assert!(!filter.matches("tmp_lock"));
+        assert_eq!(filter.summary(), "!__meta:*, !re:^tmp_");
+    }
+
+    #[test]
+    fn test_exclusions_with_only_positive_patterns() {
+        let filter = StorageFilter::new(&["balance:*".to_string(), "admin".to_string()]).unwrap();
+        assert!(filter.matches("balance:__meta"));
+        assert!(filter.matches("admin"));
+        assert!(!filter.matches("__meta:version"));
+    }
+
+    #[test]
+    fn test_exclusions_win_over_positive_patterns() {
+        let filter = StorageFilter::new(&[
+            "!__meta:*".to_string(),
+            "balance:*".to_string(),
+            "__meta:*".to_string(),
+            "!balance:frozen".to_string(),
+        ])
+        .unwrap();
+        assert!(filter.matches("balance:alice"));
+        assert!(!filter.matches("balance:frozen"));
+        assert!(!filter.matches("__meta:version"));
+        assert!(!filter.matches("admin"));
+        assert_eq!(
+            filter.summary(),
+            "balance:*, __meta:*, !__meta:*, !balance:frozen"
+        );
+
+        let values = StorageFilter::new(&["!val:Bool(false)".to_string()]).unwrap();
+        assert!(values.matches_value("Bool(true) (ttl=40)"));
+        assert!(!values.matches_value("Bool(false) (ttl=40)"));
+        assert!(values.matches("anything"));
+        assert_eq!(values.summary(), "!val:Bool(false)");
+    }
+
+    #[test]
+    fn test_invalid_pattern_names_pattern_and_prefix() {
+        let err = StorageFilter::new(&["admin".to_string(), "val:re:[x".to_string()]).unwrap_err();
+        assert!(
+            err.starts_with("pattern 'val:re:[x': invalid regex after 'val:re:':"),
+            "{}",
+            err
+        );
+        let err = StorageFilter::new(&["re:(".to_string()]).unwrap_err();
+        assert!(
+            err.starts_with("pattern 're:(': invalid regex after 're:':"),
+            "{}",
+            err
+        );
+        let err = StorageFilter::new(&["!val:re:(".to_string()]).unwrap_err();
+        assert!(
+            err.starts_with("pattern '!val:re:(': invalid regex after '!val:re:':"),
+            "{}",
+            err
+        );
+    }
+
     // ── StorageInspector filtering tests ─────────────────────────────
 
     #[test]
@@ -685,6 +2664,356 @@ mod tests {
         inspector.display_filtered(&filter);
     }
 
+    #[test]
+    fn test_durability_of_snapshot_keys() {
+        let cases = [
+            (
+                "instance:Symbol(ScSymbol(StringM(admin)))",
+                Some(Durability::Instance),
+            ),
+            ("token/instance:U32(1)", Some(Durability::Instance)),
+            (
+                "contract_data:Persistent:LedgerKeyContractInstance",
+                Some(Durability::Instance),
+            ),
+            (
+                "contract_data:Persistent:U32(1)",
+                Some(Durability::Persistent),
+            ),
+            (
+                "token/contract_data:Temporary:U32(1)",
+                Some(Durability::Temporary),
+            ),
+            ("contract_code", None),
+            ("balance:alice", None),
+        ];
+        for (key, expected) in cases {
+            assert_eq!(StorageInspector::durability(key), expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_pages_are_sorted_then_cut() {
+        let entry = |key: &str, size: u32, live_until: Option<u32>| {
+            let entry = StorageEntry {
+                size_bytes: Some(size),
+                ..StorageEntry::from_key(key, "1")
+            };
+            (
+                key.to_string(),
+                StorageEntry {
+                    live_until,
+                    ..entry
+                },
+            )
+        };
+        let storage = HashMap::from([
+            entry("instance:a", 40, Some(900)),
+            entry("instance:b", 10, Some(900)),
+            entry("contract_data:Persistent:c", 70, Some(300)),
+            entry("contract_data:Temporary:d", 20, Some(100)),
+            entry("contract_data:Temporary:e", 50, None),
+        ]);
+        let all = StorageFilter::new(&[]).unwrap();
+        let keys = |inspector: &StorageInspector| -> Vec<String> {
+            inspector
+                .page(&all)
+                .entries
+                .iter()
+                .map(|(key, _)| key.to_string())
+                .collect()
+        };
+
+        let by_size = StorageInspector::with_entries(storage.clone())
+            .sorted_by(StorageSort::Size)
+            .paged(1, Some(2));
+        assert_eq!(keys(&by_size), ["contract_data:Temporary:e", "instance:a"]);
+        assert_eq!(
+            by_size.page(&all).summary().as_deref(),
+            Some("showing 2-3 of 5 entries (filtered from 5)")
+        );
+
+        let by_ttl = StorageInspector::with_entries(storage.clone())
+            .sorted_by(StorageSort::Ttl)
+            .paged(0, Some(3));
+        assert_eq!(
+            keys(&by_ttl),
+            [
+                "contract_data:Temporary:d",
+                "contract_data:Persistent:c",
+                "instance:a"
+            ]
+        );
+
+        let modified = StorageInspector::with_entries(storage)
+            .sorted_by(StorageSort::Modified)
+            .with_written(HashSet::from(["contract_data:Temporary:d".to_string()]));
+        assert_eq!(
+            keys(&modified)[..2],
+            ["contract_data:Temporary:d", "instance:a"]
+        );
+        assert_eq!(modified.page(&all).summary(), None);
+
+        assert_eq!(with_commas(40_213), "40,213");
+        assert_eq!(with_commas(1_000_000), "1,000,000");
+        assert_eq!(with_commas(999), "999");
+    }
+
+    #[test]
+    fn test_entries_are_grouped_and_filtered_by_durability() {
+        let inspector = StorageInspector::with_state(HashMap::from([
+            ("contract_code".to_string(), "code".to_string()),
+            (
+                "contract_data:Temporary:U32(1)".to_string(),
+                "1 (ttl=20)".to_string(),
+            ),
+            (
+                "contract_data:Persistent:U32(2)".to_string(),
+                "2 (ttl=90)".to_string(),
+            ),
+            ("instance:Symbol(admin)".to_string(), "3".to_string()),
+        ]));
+
+        let all = StorageFilter::new(&[]).unwrap();
+        let keys: Vec<&str> = inspector
+            .entries_filtered(&all)
+            .into_iter()
+            .map(|(key, _)| key.as_str())
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                "instance:Symbol(admin)",
+                "contract_data:Persistent:U32(2)",
+                "contract_data:Temporary:U32(1)",
+                "contract_code",
+            ]
+        );
+
+        let temporary = StorageFilter::new(&[])
+            .unwrap()
+            .with_durabilities(vec![Durability::Temporary]);
+        assert!(!temporary.is_empty());
+        assert_eq!(temporary.summary(), "durability: temporary");
+        assert_eq!(
+            inspector.filtered_json(&temporary),
+            vec![serde_json::json!({
+                "key": "contract_data:Temporary:U32(1)",
+                "value": "1",
+                "durability": "temporary",
+                "live_until": 20,
+            })]
+        );
+
+        let narrowed = StorageFilter::new(&["instance:*".to_string()])
+            .unwrap()
+            .with_durabilities(vec![Durability::Persistent]);
+        assert!(inspector.get_filtered(&narrowed).is_empty());
+    }
+
+    #[test]
+    fn test_captured_entries_take_durability_from_the_ledger_key() {
+        use soroban_env_host::xdr::{
+            ContractDataDurability, ContractDataEntry, ContractExecutable, ExtensionPoint, Hash,
+            LedgerEntry, LedgerEntryExt, LedgerKeyContractData, ScAddress, ScContractInstance,
+            ScMap, ScMapEntry, ScSymbol,
+        };
+        use std::rc::Rc;
+
+        let host = Host::default();
+        let contract = ScAddress::Contract(Hash([1; 32]));
+        let instance = ScVal::ContractInstance(ScContractInstance {
+            executable: ContractExecutable::StellarAsset,
+            storage: Some(
+                ScMap::sorted_from(vec![ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("admin".try_into().unwrap())),
+                    val: ScVal::U32(1),
+                }])
+                .unwrap(),
+            ),
+        });
+        let data = [
+            (
+                ScVal::LedgerKeyContractInstance,
+                ContractDataDurability::Persistent,
+                instance,
+            ),
+            (
+                ScVal::U32(7),
+                ContractDataDurability::Temporary,
+                ScVal::U32(8),
+            ),
+        ];
+        host.with_mut_storage(|storage| {
+            for (key, durability, val) in data {
+                let ledger_key = LedgerKey::ContractData(LedgerKeyContractData {
+                    contract: contract.clone(),
+                    key: key.clone(),
+                    durability,
+                });
+                let entry = LedgerEntry {
+                    last_modified_ledger_seq: 1,
+                    data: LedgerEntryData::ContractData(ContractDataEntry {
+                        contract: contract.clone(),
+                        key,
+                        durability,
+                        val,
+                        ext: ExtensionPoint::V0,
+                    }),
+                    ext: LedgerEntryExt::V0,
+                };
+                storage.map = storage.map.insert(
+                    Rc::new(ledger_key),
+                    Some((Rc::new(entry), Some(50))),
+                    host.as_budget(),
+                )?;
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        let entries = StorageInspector::capture_entries_with_cache(&host, &mut RenderCache::new());
+        let durabilities: Vec<Option<Durability>> = {
+            let mut list: Vec<_> = entries.values().map(|entry| entry.durability).collect();
+            list.sort();
+            list
+        };
+        assert_eq!(
+            durabilities,
+            [Some(Durability::Instance), Some(Durability::Temporary)]
+        );
+        assert!(entries
+            .keys()
+            .any(|key| key.starts_with("instance:") && key.contains("admin")));
+        assert!(entries
+            .values()
+            .any(|entry| entry.durability == Some(Durability::Temporary)
+                && entry.value == "U32(8)"
+                && entry.live_until == Some(50)));
+        // Instance items live as long as the instance.
+        assert!(entries.values().all(|entry| entry.live_until == Some(50)));
+    }
+
+    #[test]
+    fn test_entries_keep_the_live_until_ledger_apart_from_the_value() {
+        let entry = StorageEntry::from_key("contract_data:Temporary:U32(1)", "U32(5) (ttl=120)");
+        assert_eq!(entry.value, "U32(5)");
+        assert_eq!(entry.live_until, Some(120));
+        assert_eq!(entry.snapshot_value(), "U32(5) (ttl=120)");
+
+        let entry = StorageEntry::from_key("instance:Symbol(admin)", "String(a (ttl=x))");
+        assert_eq!(entry.value, "String(a (ttl=x))");
+        assert_eq!(entry.live_until, None);
+    }
+
+    #[test]
+    fn test_ttl_warnings_flag_entries_below_the_threshold() {
+        let inspector = StorageInspector::with_state(HashMap::from([
+            (
+                "contract_data:Temporary:U32(1)".to_string(),
+                "1 (ttl=90)".to_string(),
+            ),
+            (
+                "contract_data:Temporary:U32(2)".to_string(),
+                "2 (ttl=120)".to_string(),
+            ),
+            (
+                "contract_data:Persistent:U32(3)".to_string(),
+                "3 (ttl=95)".to_string(),
+            ),
+            (
+                "contract_data:Persistent:U32(4)".to_string(),
+                "4 (ttl=5000)".to_string(),
+            ),
+            ("instance:Symbol(admin)".to_string(), "5".to_string()),
+        ]));
+        let all = StorageFilter::new(&[]).unwrap();
+        assert!(inspector.ttl_warnings(&all, 1000).is_empty());
+
+        let inspector = inspector.at_ledger(100);
+        let warnings = inspector.ttl_warnings(&all, 1000);
+        let keys: Vec<(&str, u32, bool)> = warnings
+            .iter()
+            .map(|w| (w.key.as_str(), w.ledgers_left, w.expired))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                ("contract_data:Temporary:U32(1)", 0, true),
+                ("contract_data:Persistent:U32(3)", 0, true),
+                ("contract_data:Temporary:U32(2)", 20, false),
+            ]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "[temporary] contract_data:Temporary:U32(1) expired (live until 90)"
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "[persistent] contract_data:Persistent:U32(3) archived (live until 95)"
+        );
+        assert_eq!(
+            warnings[2].to_string(),
+            "[temporary] contract_data:Temporary:U32(2) near expiry (20 ledgers left, live until 120)"
+        );
+        assert_eq!(
+            serde_json::to_value(&warnings[2]).unwrap(),
+            serde_json::json!({
+                "key": "contract_data:Temporary:U32(2)",
+                "durability": "temporary",
+                "live_until": 120,
+                "ledgers_left": 20,
+                "expired": false,
+            })
+        );
+        assert_eq!(inspector.ttl_warnings(&all, 10).len(), 2);
+    }
+
+    #[test]
+    fn test_storage_diff_shows_extended_ttls() {
+        let before = HashMap::from([
+            ("nonce".to_string(), "U32(7) (ttl=100)".to_string()),
+            ("counter".to_string(), "U32(1) (ttl=100)".to_string()),
+            ("config".to_string(), "U32(1) (ttl=100)".to_string()),
+        ]);
+        let after = HashMap::from([
+            ("nonce".to_string(), "U32(7) (ttl=600)".to_string()),
+            ("counter".to_string(), "U32(2) (ttl=600)".to_string()),
+            ("config".to_string(), "U32(2) (ttl=100)".to_string()),
+        ]);
+        let diff = StorageInspector::compute_diff(&before, &after, &[]);
+        assert_eq!(diff.live_until["nonce"], (Some(100), Some(600)));
+        assert_eq!(diff.live_until["counter"], (Some(100), Some(600)));
+        assert!(!diff.live_until.contains_key("config"));
+
+        let rendered = diff.to_string();
+        let line = |key: &str| {
+            rendered
+                .lines()
+                .find(|line| line.contains(&format!(" {}:", key)))
+                .unwrap()
+                .to_string()
+        };
+        assert!(
+            line("nonce").contains("live until 100 -> 600")
+                && line("nonce").contains("(value unchanged)"),
+            "{}",
+            rendered
+        );
+        assert!(
+            line("counter").contains("U32(2)")
+                && line("counter").contains("(live until 100 -> 600)")
+                && !line("counter").contains("ttl="),
+            "{}",
+            rendered
+        );
+        assert!(line("config").contains("U32(2) (ttl=100)"), "{}", rendered);
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap()["live_until"]["nonce"],
+            serde_json::json!([100, 600])
+        );
+    }
+
     #[test]
     fn test_get_filtered() {
         let mut inspector = StorageInspector::new();
@@ -904,6 +3233,433 @@ mod tests {
         assert!(diff.triggered_alerts.is_empty());
     }
 
+    #[test]
+    fn test_inspector_diff_and_its_rendering() {
+        let before = StorageInspector::with_state(HashMap::from([
+            ("balance".to_string(), "I128(1)".to_string()),
+            ("admin".to_string(), "Address(alice)".to_string()),
+        ]));
+        let after = StorageInspector::with_state(HashMap::from([
+            ("balance".to_string(), "I128(2)".to_string()),
+            ("nonce".to_string(), "U32(7)".to_string()),
+        ]));
+        let diff = before.diff(&after);
+        assert_eq!(diff.added["nonce"], "U32(7)");
+        assert_eq!(
+            diff.modified["balance"],
+            ("I128(1)".to_string(), "I128(2)".to_string())
+        );
+        assert_eq!(diff.deleted, vec!["admin".to_string()]);
+        assert!(after.diff(&after).is_empty());
+        assert_eq!(after.diff(&after).to_string(), "Storage: (no changes)");
+
+        let rendered = diff.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "Storage Changes:");
+        assert!(lines[1].contains("nonce") && lines[1].contains("U32(7)"));
+        assert!(lines[2].contains("balance") && lines[2].contains("I128(2)"));
+        assert!(lines[3].contains("admin"));
+        assert!(!rendered.contains("CRITICAL STORAGE ALERT"));
+    }
+
+    #[test]
+    fn test_diff_of_captured_entries_is_decoded() {
+        let symbol = |text: &str| ScVal::Symbol(text.try_into().unwrap());
+        let entry = |key: &str, stored: ScVal, value: ScVal, durability| StorageEntry {
+            key: key.to_string(),
+            value: format!("{:?}", value),
+            durability: Some(durability),
+            live_until: Some(100),
+            size_bytes: None,
+            scval_key: Some(stored),
+            scval_value: Some(value),
+        };
+        let counter = "instance:Symbol(ScSymbol(StringM(c)))";
+        let balance = "contract_data:Persistent:Vec(Some(ScVec(VecM([Symbol(ScSymbol(StringM(Balance))), U32(7)]))))";
+        let balance_key = ScVal::Vec(Some(
+            vec![symbol("Balance"), ScVal::U32(7)].try_into().unwrap(),
+        ));
+        let before = HashMap::from([(
+            counter.to_string(),
+            entry(counter, symbol("c"), ScVal::I64(0), Durability::Instance),
+        )]);
+        let after = HashMap::from([
+            (
+                counter.to_string(),
+                entry(counter, symbol("c"), ScVal::I64(1), Durability::Instance),
+            ),
+            (
+                balance.to_string(),
+                entry(
+                    balance,
+                    balance_key,
+                    ScVal::I128(soroban_env_host::xdr::Int128Parts { hi: 0, lo: 5 }),
+                    Durability::Persistent,
+                ),
+            ),
+        ]);
+        let snapshot = |entries: &HashMap<String, StorageEntry>| {
+            entries
+                .iter()
+                .map(|(key, entry)| (key.clone(), entry.snapshot_value()))
+                .collect::<HashMap<_, _>>()
+        };
+        let diff = StorageInspector::compute_diff(&snapshot(&before), &snapshot(&after), &[])
+            .with_entries(&before, &after);
+
+        let rendered = diff.to_string();
+        let line = |key: &str| {
+            rendered
+                .lines()
+                .find(|line| line.contains(key))
+                .unwrap_or_default()
+        };
+        assert!(line("Balance(7) = ").contains('5'), "{}", rendered);
+        assert!(
+            line(" c: ").contains('0') && line(" c: ").contains('1'),
+            "{}",
+            rendered
+        );
+        assert!(
+            !rendered.contains("I64(") && !rendered.contains("xdr:"),
+            "{}",
+            rendered
+        );
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["modified"]["c"], serde_json::json!([0, 1]));
+        assert_eq!(json["added"]["Balance(7)"], serde_json::json!("5"));
+        assert!(json.get("xdr").is_none());
+
+        let raw = diff.with_raw_xdr(true);
+        assert!(raw.to_string().contains("    new xdr: "));
+        let json = serde_json::to_value(&raw).unwrap();
+        assert_eq!(json["xdr"]["c"]["old"], "AAAABgAAAAAAAAAA");
+        assert!(json["xdr"]["Balance(7)"]["old"].is_null());
+    }
+
+    #[test]
+    fn test_alerts_show_the_old_and_new_value() {
+        let before = HashMap::from([("admin".to_string(), "Address(alice)".to_string())]);
+        let after = HashMap::from([("admin".to_string(), "Address(mallory)".to_string())]);
+        let diff = StorageInspector::compute_diff(&before, &after, &["admin".to_string()]);
+        assert_eq!(
+            diff.alert_values["admin"],
+            (
+                Some("Address(alice)".to_string()),
+                Some("Address(mallory)".to_string())
+            )
+        );
+        let rendered = diff.to_string();
+        assert!(rendered.contains("CRITICAL STORAGE ALERT"), "{}", rendered);
+        assert!(
+            rendered.contains("was modified: Address(alice) -> Address(mallory)"),
+            "{}",
+            rendered
+        );
+
+        let diff = StorageInspector::compute_diff(&before, &HashMap::new(), &["admin".to_string()]);
+        assert_eq!(
+            diff.alert_values["admin"],
+            (Some("Address(alice)".to_string()), None)
+        );
+        assert!(diff.to_string().contains("Address(alice) -> <absent>"));
+    }
+
+    #[test]
+    fn test_alerts_name_the_frame_of_the_tracked_write() {
+        use crate::runtime::observer::{CallStart, StorageWrite};
+
+        let before = HashMap::from([("admin".to_string(), "Address(alice)".to_string())]);
+        let after = HashMap::from([("admin".to_string(), "Address(mallory)".to_string())]);
+        let calls = [
+            CallStart {
+                depth: 0,
+                contract: "CROUTER".to_string(),
+                function: "route".to_string(),
+                args: serde_json::json!([]),
+            },
+            CallStart {
+                depth: 1,
+                contract: "CVAULT".to_string(),
+                function: "set_admin".to_string(),
+                args: serde_json::json!([]),
+            },
+        ];
+        let mut history = StorageHistory::new();
+        history.record_invocation(
+            1,
+            &calls[0],
+            &calls,
+            &[StorageWrite {
+                key: "admin".to_string(),
+                old: Some("Address(alice)".to_string()),
+                new: Some("Address(mallory)".to_string()),
+            }],
+            &HashMap::from([("admin".to_string(), "CVAULT".to_string())]),
+        );
+
+        let diff = StorageInspector::compute_diff(&before, &after, &["admin".to_string()])
+            .with_history(&history);
+        assert_eq!(diff.alert_writes["admin"][0].function, "set_admin");
+        let rendered = diff.to_string();
+        assert!(
+            rendered.contains("[set by set_admin at depth 1 in CVAULT]"),
+            "{}",
+            rendered
+        );
+        assert_eq!(
+            serde_json::to_value(&diff).unwrap()["alert_writes"]["admin"][0]["depth"],
+            1
+        );
+        let alerts = serde_json::to_value(diff.alerts()).unwrap();
+        assert_eq!(alerts[0]["change"], "modified");
+        assert_eq!(alerts[0]["writes"][0]["contract"], "CVAULT");
+        assert_eq!(alerts[0]["writes"][0]["function"], "set_admin");
+    }
+
+    #[test]
+    fn test_alerts_list_every_write_of_the_key() {
+        use crate::runtime::observer::{CallStart, StorageWrite};
+
+        let key = "contract_data:Persistent:Symbol(owner)";
+        let top = CallStart {
+            depth: 0,
+            contract: "CVAULT".to_string(),
+            function: "set_owner".to_string(),
+            args: serde_json::json!([]),
+        };
+        let owners = HashMap::from([(key.to_string(), "CVAULT".to_string())]);
+        let mut history = StorageHistory::new();
+        for (step, old, new) in [(1, "U32(1)", Some("U32(2)")), (2, "U32(2)", None)] {
+            history.record_invocation(
+                step,
+                &top,
+                &[],
+                &[StorageWrite {
+                    key: key.to_string(),
+                    old: Some(old.to_string()),
+                    new: new.map(str::to_string),
+                }],
+                &owners,
+            );
+        }
+
+        let before = HashMap::from([(key.to_string(), "U32(1)".to_string())]);
+        let diff = StorageInspector::compute_diff(&before, &HashMap::new(), &[key.to_string()])
+            .with_history(&history);
+        let alerts = diff.alerts();
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].change, AlertChange::Removed);
+        assert_eq!(alerts[0].durability, Some(Durability::Persistent));
+        assert_eq!(alerts[0].writes.len(), 2);
+
+        let rendered = diff.to_string();
+        let lines: Vec<&str> = rendered.lines().collect();
+        let at = lines
+            .iter()
+            .position(|line| line.contains("was removed in persistent storage: U32(1) -> <absent>"))
+            .unwrap_or_else(|| panic!("{}", rendered));
+        assert!(lines[at + 1]
+            .contains("step 1 set: U32(1) -> U32(2)  [set_owner at depth 0 in CVAULT]"));
+        assert!(lines[at + 2].contains("step 2 remove: U32(2) -> <absent>"));
+    }
+
+    #[test]
+    fn test_alert_not_equal_old_ignores_ttl_only_changes() {
+        let before = HashMap::from([
+            ("admin".to_string(), "Address(alice) (ttl=100)".to_string()),
+            ("owner".to_string(), "Address(bob) (ttl=100)".to_string()),
+        ]);
+        let after = HashMap::from([
+            ("admin".to_string(), "Address(alice) (ttl=500)".to_string()),
+            ("owner".to_string(), "Address(carol) (ttl=100)".to_string()),
+        ]);
+        let alerts = vec!["admin!=old".to_string(), "owner!=old".to_string()];
+        let diff = StorageInspector::compute_diff(&before, &after, &alerts);
+        assert_eq!(diff.triggered_alerts, vec!["owner".to_string()]);
+        assert_eq!(diff.alert_predicates["owner"], "owner!=old");
+
+        // Without a predicate the TTL bump still counts as a change.
+        let diff = StorageInspector::compute_diff(&before, &after, &["admin".to_string()]);
+        assert_eq!(diff.triggered_alerts, vec!["admin".to_string()]);
+        assert!(diff.alert_predicates.is_empty());
+    }
+
+    #[test]
+    fn test_alert_equal_value_matches_new_value() {
+        let before = HashMap::from([("paused".to_string(), "Bool(false)".to_string())]);
+        let paused = HashMap::from([("paused".to_string(), "Bool(true)".to_string())]);
+        let alerts = vec!["paused==true".to_string()];
+
+        let diff = StorageInspector::compute_diff(&before, &paused, &alerts);
+        assert_eq!(diff.triggered_alerts, vec!["paused".to_string()]);
+        assert_eq!(diff.alert_predicates["paused"], "paused==true");
+
+        let diff = StorageInspector::compute_diff(&paused, &before, &alerts);
+        assert!(diff.triggered_alerts.is_empty());
+
+        // An untouched key never fires, even if it matches.
+        let diff = StorageInspector::compute_diff(&paused, &paused, &alerts);
+        assert!(diff.triggered_alerts.is_empty());
+    }
+
+    #[test]
+    fn test_alert_equal_old_reports_wasted_writes() {
+        let state = HashMap::from([
+            ("counter".to_string(), "U32(7) (ttl=100)".to_string()),
+            ("config".to_string(), "U32(1) (ttl=100)".to_string()),
+        ]);
+        let alerts = vec!["counter==old".to_string(), "config==old".to_string()];
+
+        let written = HashSet::from(["counter".to_string()]);
+        let diff = StorageInspector::compute_diff_with_writes(&state, &state, &written, &alerts);
+        assert!(diff.is_empty());
+        assert_eq!(diff.triggered_alerts, vec!["counter".to_string()]);
+        assert_eq!(diff.alert_predicates["counter"], "counter==old");
+
+        // A write that changed the value is not wasted.
+        let mut after = state.clone();
+        after.insert("counter".to_string(), "U32(8) (ttl=100)".to_string());
+        let diff = StorageInspector::compute_diff_with_writes(&state, &after, &written, &alerts);
+        assert!(diff.triggered_alerts.is_empty());
+    }
+
+    #[test]
+    fn test_alert_rule_parsing() {
+        let rule = AlertRule::parse("balance:*!=old").unwrap();
+        assert_eq!(rule.condition().as_deref(), Some("new != old"));
+        assert!(rule.fires("balance:alice", Some("1"), Some("2"), true, true));
+        assert!(!rule.fires("supply", Some("1"), Some("2"), true, true));
+
+        let rule = AlertRule::parse("status == \"Active\"").unwrap();
+        assert_eq!(rule.condition().as_deref(), Some("new == \"Active\""));
+        assert!(rule.fires(
+            "status",
+            None,
+            Some("Symbol(ScSymbol(StringM(Active)))"),
+            true,
+            true
+        ));
+
+        assert!(AlertRule::parse("admin==").is_err());
+        assert!(AlertRule::parse("!=old").is_err());
+        assert!(!AlertRule::parse("admin").unwrap().has_predicate());
+
+        let rule = AlertRule::parse("!__meta:*").unwrap();
+        assert!(rule.excludes());
+        assert!(rule.pattern().matches("__meta:version"));
+        assert!(!rule.fires("__meta:version", Some("1"), Some("2"), true, true));
+        assert!(AlertRule::parse("!admin!=old").is_err());
+    }
+
+    #[test]
+    fn test_alert_predicates_compare_typed_values() {
+        let rule = AlertRule::parse("balance==100").unwrap();
+        assert_eq!(rule.condition().as_deref(), Some("new == 100"));
+        assert!(rule.fires(
+            "balance",
+            None,
+            Some("I128(Int128Parts { hi: 0, lo: 100 }) (ttl=50)"),
+            true,
+            true
+        ));
+        assert!(!rule.fires("balance", None, Some("U32(99)"), true, true));
+
+        let rule = AlertRule::parse("paused==true").unwrap();
+        assert!(rule.fires(
+            "paused",
+            Some("Bool(false)"),
+            Some("Bool(true)"),
+            true,
+            true
+        ));
+
+        let rule = AlertRule::parse("admin==alice").unwrap();
+        assert_eq!(rule.condition().as_deref(), Some("new == \"alice\""));
+        assert!(rule.fires(
+            "admin",
+            None,
+            Some("String(ScString(StringM(alice)))"),
+            true,
+            true
+        ));
+
+        // The TTL suffix is not part of the value, and a deleted key matches
+        // no value.
+        let rule = AlertRule::parse("counter!=old").unwrap();
+        assert!(!rule.fires(
+            "counter",
+            Some("U32(7) (ttl=10)"),
+            Some("U32(7) (ttl=90)"),
+            false,
+            true
+        ));
+        assert!(rule.fires("counter", Some("U32(7)"), None, true, true));
+        assert!(rule.fires("counter", None, Some("U32(7)"), true, true));
+    }
+
+    #[test]
+    fn test_alert_values_keep_their_type() {
+        assert_eq!(alert_value("U64(5) (ttl=9)"), ExprValue::Int(5));
+        assert_eq!(
+            alert_value("I128(Int128Parts { hi: -1, lo: 18446744073709551615 })"),
+            ExprValue::Int(-1)
+        );
+        assert_eq!(
+            alert_value("U256(UInt256Parts { hi_hi: 0, hi_lo: 0, lo_hi: 1, lo_lo: 0 })"),
+            ExprValue::Int(1 << 64)
+        );
+        assert_eq!(
+            alert_value("Timepoint(1970-01-01T00:00:05Z (5))"),
+            ExprValue::Timepoint(5)
+        );
+        assert_eq!(
+            alert_value("Duration(1h (3600s))"),
+            ExprValue::Duration(3_600)
+        );
+        assert_eq!(alert_value("Duration(5s)"), ExprValue::Duration(5));
+        assert_eq!(
+            alert_value("Symbol(ScSymbol(StringM(paused)))"),
+            ExprValue::Str("paused".to_string())
+        );
+        assert_eq!(
+            alert_value(&format!("Address(Contract(Hash({})))", "00".repeat(32))),
+            ExprValue::Str(soroban_env_host::xdr::ScAddress::Contract([0; 32].into()).to_string())
+        );
+        assert_eq!(alert_value("Void"), ExprValue::Str("Void".to_string()));
+    }
+
+    #[test]
+    fn test_alert_exclusions() {
+        let before = HashMap::from([
+            ("admin".to_string(), "U32(1)".to_string()),
+            ("__meta:version".to_string(), "U32(1)".to_string()),
+            ("balance:alice".to_string(), "I128(5)".to_string()),
+        ]);
+        let after: HashMap<String, String> = before
+            .keys()
+            .map(|key| (key.clone(), "U32(2)".to_string()))
+            .collect();
+        let triggered = |alerts: &[&str]| {
+            let alerts: Vec<String> = alerts.iter().map(|a| a.to_string()).collect();
+            let mut keys =
+                StorageInspector::compute_diff(&before, &after, &alerts).triggered_alerts;
+            keys.sort();
+            keys
+        };
+
+        assert_eq!(
+            triggered(&["!__meta:*"]),
+            ["admin".to_string(), "balance:alice".to_string()]
+        );
+        assert_eq!(
+            triggered(&["re:.*", "!__meta:*", "!balance:*"]),
+            ["admin".to_string()]
+        );
+        assert_eq!(triggered(&["__meta:*", "!__meta:*"]), Vec::<String>::new());
+    }
+
     #[test]
     fn test_storage_diff_large_mixed_changes() {
         let mut before = HashMap::new();
@@ -979,4 +3735,25 @@ mod tests {
         // Ensure display_diff doesn't panic with these values
         StorageInspector::display_diff(&diff);
     }
+
+    #[test]
+    fn test_ledger_key_of_labelled_contract_is_prefixed() {
+        use soroban_env_host::xdr::{
+            ContractDataDurability, Hash, LedgerKeyContractData, ScAddress,
+        };
+
+        let key = |seed: u8| {
+            LedgerKey::ContractData(LedgerKeyContractData {
+                contract: ScAddress::Contract(Hash([seed; 32])),
+                key: ScVal::U32(7),
+                durability: ContractDataDurability::Persistent,
+            })
+        };
+        let mut cache = RenderCache::new();
+        cache.set_contract_label(ScAddress::Contract(Hash([2; 32])), "token");
+
+        let plain = StorageInspector::render_ledger_key(&key(1), &mut cache);
+        let labelled = StorageInspector::render_ledger_key(&key(2), &mut cache);
+        assert_eq!(labelled, format!("token/{}", plain));
+    }
 }
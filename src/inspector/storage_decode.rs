@@ -0,0 +1,108 @@
+//! Readable forms of the ScVal keys and values of storage entries.
+//!
+//! [`decode_json`] gives the structured JSON of `--output json`: symbols and
+//! strings as strings, addresses as strkeys, vectors and maps expanded.
+//! [`decode_text`] is the compact form the storage listing shows and
+//! `--storage-filter` matches: a vector led by a symbol reads as the enum
+//! variant it usually is, so `["Balance", G...]` becomes `Balance(G...)`.
+
+use crate::utils::arguments::scval_to_json;
+use serde_json::Value;
+use soroban_env_host::xdr::{Limits, ScVal, WriteXdr};
+
+/// Structured JSON of a key or value.
+pub fn decode_json(val: &ScVal) -> Value {
+    scval_to_json(val)
+}
+
+/// Compact text of a key or value, e.g. `Balance(GABC...)`, `[1, 2]` or
+/// `{owner: GABC..., amount: 5}`.
+pub fn decode_text(val: &ScVal) -> String {
+    match val {
+        ScVal::Vec(Some(items)) => match items.first() {
+            Some(ScVal::Symbol(variant)) if items.len() == 1 => variant.to_utf8_string_lossy(),
+            Some(ScVal::Symbol(variant)) => format!(
+                "{}({})",
+                variant.to_utf8_string_lossy(),
+                join(items.iter().skip(1))
+            ),
+            _ => format!("[{}]", join(items.iter())),
+        },
+        ScVal::Map(Some(entries)) => {
+            let fields: Vec<String> = entries
+                .iter()
+                .map(|entry| format!("{}: {}", decode_text(&entry.key), decode_text(&entry.val)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+        other => match decode_json(other) {
+            Value::String(text) => text,
+            json => json.to_string(),
+        },
+    }
+}
+
+/// Base64 XDR of a key or value, for `--raw-xdr`.
+pub fn raw_xdr(val: &ScVal) -> Option<String> {
+    val.to_xdr_base64(Limits::none()).ok()
+}
+
+fn join<'a>(items: impl Iterator<Item = &'a ScVal>) -> String {
+    items.map(decode_text).collect::<Vec<_>>().join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Int128Parts, ScAddress, ScMap, ScMapEntry, ScSymbol, ScVec};
+
+    fn symbol(text: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol::try_from(text.to_string()).unwrap())
+    }
+
+    fn tuple(items: Vec<ScVal>) -> ScVal {
+        ScVal::Vec(Some(ScVec(items.try_into().unwrap())))
+    }
+
+    #[test]
+    fn tuple_keys_read_as_enum_variants() {
+        let holder = ScAddress::Contract([7; 32].into());
+        let strkey = holder.to_string();
+        let key = tuple(vec![symbol("Balance"), ScVal::Address(holder)]);
+
+        assert_eq!(decode_text(&key), format!("Balance({})", strkey));
+        assert_eq!(
+            decode_json(&key),
+            serde_json::json!(["Balance", strkey.clone()])
+        );
+        assert_eq!(decode_text(&tuple(vec![symbol("Admin")])), "Admin");
+        assert_eq!(
+            decode_text(&tuple(vec![ScVal::U32(1), ScVal::Bool(true)])),
+            "[1, true]"
+        );
+        assert!(raw_xdr(&key).is_some());
+    }
+
+    #[test]
+    fn maps_and_scalars_are_expanded() {
+        let value = ScVal::Map(Some(ScMap(
+            vec![
+                ScMapEntry {
+                    key: symbol("amount"),
+                    val: ScVal::I128(Int128Parts { hi: 0, lo: 5 }),
+                },
+                ScMapEntry {
+                    key: symbol("memo"),
+                    val: ScVal::Void,
+                },
+            ]
+            .try_into()
+            .unwrap(),
+        )));
+        assert_eq!(decode_text(&value), "{amount: 5, memo: null}");
+        assert_eq!(
+            decode_json(&value),
+            serde_json::json!({"amount": "5", "memo": null})
+        );
+    }
+}
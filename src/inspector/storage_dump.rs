@@ -0,0 +1,280 @@
+//! `run --dump-storage`: the contract's storage after the run, written so
+//! that `--storage-file` (or `--storage @FILE`) seeds a later run with it.
+//!
+//! The file carries a `format` marker, a `version` and the `entries` in the
+//! list form `--storage` reads. Each entry has its key and value as type
+//! annotations (see [`scval_to_typed_json`]), the storage it lives in and, for
+//! persistent and temporary entries, the ledger it lives until. Instance
+//! storage is written key by key; it shares the contract instance's TTL,
+//! which is not written. Dumps from a newer build are refused.
+
+use crate::utils::arguments::scval_to_typed_json;
+use crate::utils::ledger_key::Durability;
+use crate::{DebuggerError, Result};
+use serde::ser::{SerializeMap, Serializer};
+use serde::Serialize;
+use serde_json::Value;
+use soroban_env_host::budget::AsBudget;
+use soroban_env_host::xdr::{LedgerEntryData, ScAddress, ScVal};
+use soroban_env_host::Host;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+/// Value of the `format` field.
+pub const STORAGE_DUMP_FORMAT: &str = "soroban-debug-storage";
+
+/// Newest dump version this build reads and writes.
+pub const STORAGE_DUMP_VERSION: u32 = 1;
+
+/// One storage entry of the dump.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DumpedEntry {
+    pub key: ScVal,
+    pub value: ScVal,
+    pub durability: Durability,
+    /// Ledger the entry lives until; `None` for instance storage.
+    pub live_until: Option<u32>,
+}
+
+impl Serialize for DumpedEntry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("key", &scval_to_typed_json(&self.key))?;
+        map.serialize_entry("value", &scval_to_typed_json(&self.value))?;
+        map.serialize_entry("durability", &self.durability)?;
+        if let Some(live_until) = self.live_until {
+            map.serialize_entry("live_until", &live_until)?;
+        }
+        map.end()
+    }
+}
+
+/// Contents of the file written by `--dump-storage`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageDump {
+    pub entries: Vec<DumpedEntry>,
+}
+
+impl Serialize for StorageDump {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(3))?;
+        map.serialize_entry("format", STORAGE_DUMP_FORMAT)?;
+        map.serialize_entry("version", &STORAGE_DUMP_VERSION)?;
+        map.serialize_entry("entries", &self.entries)?;
+        map.end()
+    }
+}
+
+/// Whether parsed `--storage` JSON is a `--dump-storage` file.
+pub fn is_dump(root: &Value) -> bool {
+    root.get("format").and_then(Value::as_str) == Some(STORAGE_DUMP_FORMAT)
+}
+
+/// The entries of a parsed dump, as `--storage` list entries, once its
+/// version has been checked.
+pub fn dump_entries(mut root: Value) -> Result<Vec<Value>> {
+    let version = root
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| invalid("missing `version`".to_string()))?;
+    if version > u64::from(STORAGE_DUMP_VERSION) {
+        return Err(invalid(format!(
+            "storage dump version {} is newer than this debugger supports ({}); upgrade soroban-debug to load it",
+            version, STORAGE_DUMP_VERSION
+        )));
+    }
+    if version < u64::from(STORAGE_DUMP_VERSION) {
+        return Err(invalid(format!(
+            "storage dump version {} is not supported",
+            version
+        )));
+    }
+    match root.get_mut("entries").map(Value::take) {
+        Some(Value::Array(entries)) => Ok(entries),
+        _ => Err(invalid("`entries` must be a list".to_string())),
+    }
+}
+
+/// Read a `--storage-file`, which must be a dump, as `--storage` list
+/// entries.
+pub fn read_dump_file(path: &Path) -> Result<Vec<Value>> {
+    let text = std::fs::read_to_string(path).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to read storage file {:?}: {}", path, e))
+    })?;
+    let root: Value = serde_json::from_str(&text).map_err(|e| {
+        DebuggerError::FileError(format!("Storage file {:?} is not valid JSON: {}", path, e))
+    })?;
+    if !is_dump(&root) {
+        return Err(DebuggerError::FileError(format!(
+            "{:?} is not a --dump-storage file; pass inline storage JSON with --storage @FILE",
+            path
+        ))
+        .into());
+    }
+    dump_entries(root).map_err(|e| e.wrap_err(format!("Failed to load storage file {:?}", path)))
+}
+
+fn invalid(reason: String) -> miette::Report {
+    DebuggerError::StorageError(format!("Invalid storage dump: {}", reason)).into()
+}
+
+impl StorageDump {
+    /// The storage of `contract`, sorted by storage and then key so that
+    /// dumps of the same state are identical.
+    pub fn from_host(host: &Host, contract: &ScAddress) -> Result<Self> {
+        let mut entries = host
+            .with_mut_storage(|storage| {
+                let mut entries = Vec::new();
+                for (_, entry_opt) in storage.map.iter(host.as_budget())? {
+                    let Some((entry, live_until)) = entry_opt.as_ref() else {
+                        continue;
+                    };
+                    let LedgerEntryData::ContractData(cd) = &entry.as_ref().data else {
+                        continue;
+                    };
+                    if &cd.contract != contract {
+                        continue;
+                    }
+                    match &cd.val {
+                        ScVal::ContractInstance(instance) => {
+                            for item in instance.storage.iter().flat_map(|map| map.iter()) {
+                                entries.push(DumpedEntry {
+                                    key: item.key.clone(),
+                                    value: item.val.clone(),
+                                    durability: Durability::Instance,
+                                    live_until: None,
+                                });
+                            }
+                        }
+                        val => entries.push(DumpedEntry {
+                            key: cd.key.clone(),
+                            value: val.clone(),
+                            durability: Durability::of(&cd.key, cd.durability),
+                            live_until: *live_until,
+                        }),
+                    }
+                }
+                Ok(entries)
+            })
+            .map_err(|e| {
+                DebuggerError::StorageError(format!("Failed to read storage to dump: {}", e))
+            })?;
+        entries.sort_by(|a, b| (a.durability, &a.key).cmp(&(b.durability, &b.key)));
+        Ok(Self { entries })
+    }
+
+    /// Write the dump to `path` as pretty-printed JSON, one entry at a time.
+    /// An existing file is only replaced when `overwrite` is set.
+    pub fn write_to(&self, path: &Path, overwrite: bool) -> Result<()> {
+        let mut options = OpenOptions::new();
+        options.write(true);
+        if overwrite {
+            options.create(true).truncate(true);
+        } else {
+            options.create_new(true);
+        }
+        let file = options.open(path).map_err(|e| match e.kind() {
+            ErrorKind::AlreadyExists => DebuggerError::FileError(format!(
+                "{:?} already exists; pass --overwrite to replace it",
+                path
+            )),
+            _ => DebuggerError::FileError(format!("Failed to create {:?}: {}", path, e)),
+        })?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, self)
+            .map_err(|e| e.to_string())
+            .and_then(|()| writer.flush().map_err(|e| e.to_string()))
+            .map_err(|e| {
+                DebuggerError::FileError(format!("Failed to write storage to {:?}: {}", path, e))
+                    .into()
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Hash, ScSymbol};
+
+    fn symbol(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    #[test]
+    fn dump_is_the_storage_list_form() {
+        let dump = StorageDump {
+            entries: vec![
+                DumpedEntry {
+                    key: symbol("admin"),
+                    value: ScVal::U32(1),
+                    durability: Durability::Instance,
+                    live_until: None,
+                },
+                DumpedEntry {
+                    key: symbol("nonce"),
+                    value: ScVal::I64(-3),
+                    durability: Durability::Temporary,
+                    live_until: Some(120),
+                },
+            ],
+        };
+        assert_eq!(
+            serde_json::to_value(&dump).unwrap()["entries"],
+            serde_json::json!([
+                {
+                    "key": {"type": "symbol", "value": "admin"},
+                    "value": {"type": "u32", "value": 1},
+                    "durability": "instance",
+                },
+                {
+                    "key": {"type": "symbol", "value": "nonce"},
+                    "value": {"type": "i64", "value": -3},
+                    "durability": "temporary",
+                    "live_until": 120,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn existing_file_is_kept_without_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+        std::fs::write(&path, "keep").unwrap();
+
+        let dump =
+            StorageDump::from_host(&Host::default(), &ScAddress::Contract(Hash([1; 32]))).unwrap();
+        let err = dump.write_to(&path, false).unwrap_err();
+        assert!(err.to_string().contains("pass --overwrite"), "{}", err);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep");
+
+        dump.write_to(&path, true).unwrap();
+        assert_eq!(read_dump_file(&path).unwrap(), Vec::<Value>::new());
+    }
+
+    #[test]
+    fn newer_and_malformed_dumps_are_refused() {
+        let dump = serde_json::to_value(StorageDump {
+            entries: Vec::new(),
+        })
+        .unwrap();
+        assert!(is_dump(&dump));
+        assert!(dump_entries(dump.clone()).unwrap().is_empty());
+
+        let mut newer = dump.clone();
+        newer["version"] = serde_json::json!(STORAGE_DUMP_VERSION + 1);
+        let err = dump_entries(newer).unwrap_err().to_string();
+        assert!(
+            err.contains("is newer than this debugger supports"),
+            "{}",
+            err
+        );
+        assert!(err.contains("upgrade soroban-debug"), "{}", err);
+
+        let mut unlisted = dump;
+        unlisted["entries"] = serde_json::json!({"c": 1});
+        assert!(dump_entries(unlisted).is_err());
+        assert!(!is_dump(&serde_json::json!({"c": 1})));
+    }
+}
@@ -0,0 +1,353 @@
+//! Per-key storage access counters aggregated over many invocations.
+//!
+//! The executor feeds every before/after storage snapshot pair into a
+//! [`StorageHeatmap`], so a scenario or `--repeat` run ends with one table of
+//! the hottest keys. Sizes are measured on the rendered value, which is what
+//! the rest of the storage inspector shows.
+
+use crate::inspector::storage::StorageFilter;
+use crate::output::OutputConfig;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Number of keys shown when `--storage-heatmap` is given without a count.
+pub const DEFAULT_HEATMAP_KEYS: usize = 10;
+
+const BAR_WIDTH: usize = 20;
+
+/// Access counters for one storage key.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct KeyAccess {
+    pub key: String,
+    pub reads: u64,
+    pub writes: u64,
+    pub bytes_read: u64,
+    pub bytes_written: u64,
+}
+
+impl KeyAccess {
+    pub fn accesses(&self) -> u64 {
+        self.reads + self.writes
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes_read + self.bytes_written
+    }
+}
+
+/// Storage accesses keyed by storage key.
+#[derive(Debug, Clone, Default)]
+pub struct StorageHeatmap {
+    keys: HashMap<String, KeyAccess>,
+    invocations: u64,
+    /// Only every `sample_rate`-th access is counted (0 and 1 count all).
+    sample_rate: u64,
+    /// Accesses seen, counted or not.
+    seen: u64,
+}
+
+impl StorageHeatmap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count only every `rate`-th access from now on (`run --trace-sample`).
+    /// Reported counts are then scaled back up by `rate` and labelled as
+    /// estimates.
+    pub fn set_sample_rate(&mut self, rate: u64) {
+        self.sample_rate = rate;
+    }
+
+    /// The sampling rate, if counts are estimates.
+    pub fn sample_rate(&self) -> Option<u64> {
+        (self.sample_rate > 1).then_some(self.sample_rate)
+    }
+
+    fn sampled(&mut self) -> bool {
+        let keep = self.seen % self.sample_rate.max(1) == 0;
+        self.seen += 1;
+        keep
+    }
+
+    pub fn record_read(&mut self, key: &str, bytes: usize) {
+        if !self.sampled() {
+            return;
+        }
+        let entry = self.entry(key);
+        entry.reads += 1;
+        entry.bytes_read = entry.bytes_read.saturating_add(bytes as u64);
+    }
+
+    pub fn record_write(&mut self, key: &str, bytes: usize) {
+        if !self.sampled() {
+            return;
+        }
+        let entry = self.entry(key);
+        entry.writes += 1;
+        entry.bytes_written = entry.bytes_written.saturating_add(bytes as u64);
+    }
+
+    /// Record one invocation from the storage snapshots taken around it.
+    ///
+    /// New, modified and removed keys count as writes; keys present on both
+    /// sides count as reads, matching how the executor tracks accesses.
+    pub fn record_invocation(
+        &mut self,
+        before: &HashMap<String, String>,
+        after: &HashMap<String, String>,
+    ) {
+        self.invocations += 1;
+        for (key, value) in after {
+            match before.get(key) {
+                Some(old) if old == value => self.record_read(key, old.len()),
+                Some(old) => {
+                    self.record_read(key, old.len());
+                    self.record_write(key, value.len());
+                }
+                None => self.record_write(key, value.len()),
+            }
+        }
+        for key in before.keys().filter(|key| !after.contains_key(*key)) {
+            self.record_write(key, 0);
+        }
+    }
+
+    /// Fold another heatmap into this one, e.g. one per `--repeat` iteration.
+    pub fn merge(&mut self, other: &StorageHeatmap) {
+        self.invocations += other.invocations;
+        self.sample_rate = self.sample_rate.max(other.sample_rate);
+        for access in other.keys.values() {
+            let entry = self.entry(&access.key);
+            entry.reads += access.reads;
+            entry.writes += access.writes;
+            entry.bytes_read = entry.bytes_read.saturating_add(access.bytes_read);
+            entry.bytes_written = entry.bytes_written.saturating_add(access.bytes_written);
+        }
+    }
+
+    pub fn invocations(&self) -> u64 {
+        self.invocations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Keys matching `filter`, most accessed first; ties go to the key moving more bytes.
+    ///
+    /// Counts of a sampled heatmap are scaled by the sampling rate. Accesses
+    /// carry no value, so `val:` patterns do not narrow the ranking.
+    pub fn ranked(&self, filter: &StorageFilter) -> Vec<KeyAccess> {
+        let scale = self.sample_rate.max(1);
+        let mut rows: Vec<KeyAccess> = self
+            .keys
+            .values()
+            .filter(|access| filter.matches(&access.key))
+            .map(|access| KeyAccess {
+                key: access.key.clone(),
+                reads: access.reads * scale,
+                writes: access.writes * scale,
+                bytes_read: access.bytes_read.saturating_mul(scale),
+                bytes_written: access.bytes_written.saturating_mul(scale),
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            b.accesses()
+                .cmp(&a.accesses())
+                .then_with(|| b.bytes().cmp(&a.bytes()))
+                .then_with(|| a.key.cmp(&b.key))
+        });
+        rows
+    }
+
+    /// Render the `top` hottest keys as aligned text with an access bar.
+    pub fn render(&self, top: usize, filter: &StorageFilter) -> String {
+        let ranked = self.ranked(filter);
+        let rows = &ranked[..top.min(ranked.len())];
+        if rows.is_empty() {
+            return "No storage accesses recorded".to_string();
+        }
+
+        let key_width = rows
+            .iter()
+            .map(|row| row.key.chars().count())
+            .chain(std::iter::once("Key".len()))
+            .max()
+            .unwrap_or(0);
+        let busiest = rows[0].accesses().max(1);
+        let mut out = format!(
+            "Top {} of {} key(s) over {} invocation(s)\n",
+            rows.len(),
+            ranked.len(),
+            self.invocations
+        );
+        if let Some(rate) = self.sample_rate() {
+            out.push_str(&format!(
+                "Estimated counts: 1 in {} accesses sampled, scaled by {}\n",
+                rate, rate
+            ));
+        }
+        out.push_str(&format!(
+            "{:<key_width$}  {:>7}  {:>7}  {:>10}  Accesses\n",
+            "Key", "Reads", "Writes", "Bytes"
+        ));
+        out.push_str(&"-".repeat(key_width + 32 + BAR_WIDTH));
+        for row in rows {
+            let filled = ((row.accesses() * BAR_WIDTH as u64).div_ceil(busiest)) as usize;
+            out.push('\n');
+            out.push_str(&format!(
+                "{:<key_width$}  {:>7}  {:>7}  {:>10}  {}",
+                row.key,
+                row.reads,
+                row.writes,
+                row.bytes(),
+                bar(filled)
+            ));
+        }
+        out
+    }
+
+    /// Every key matching `filter`, most accessed first.
+    pub fn to_json(&self, filter: &StorageFilter) -> serde_json::Value {
+        let mut json = serde_json::json!({
+            "invocations": self.invocations,
+            "keys": self.ranked(filter),
+        });
+        if let Some(rate) = self.sample_rate() {
+            json["estimated"] = serde_json::Value::Bool(true);
+            json["sample_rate"] = serde_json::Value::from(rate);
+        }
+        json
+    }
+
+    fn entry(&mut self, key: &str) -> &mut KeyAccess {
+        self.keys
+            .entry(key.to_string())
+            .or_insert_with(|| KeyAccess {
+                key: key.to_string(),
+                ..KeyAccess::default()
+            })
+    }
+}
+
+fn bar(filled: usize) -> String {
+    let block = if OutputConfig::no_unicode() {
+        "#"
+    } else {
+        "█"
+    };
+    block.repeat(filled.min(BAR_WIDTH))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn no_filter() -> StorageFilter {
+        StorageFilter::new(&[]).unwrap()
+    }
+
+    #[test]
+    fn aggregates_across_a_three_step_script() {
+        let step0 = snapshot(&[("config", "{\"admin\":\"GABC\"}")]);
+        let step1 = snapshot(&[("config", "{\"admin\":\"GABC\"}"), ("counter", "1")]);
+        let step2 = snapshot(&[("config", "{\"admin\":\"GABC\"}"), ("counter", "2")]);
+        let step3 = snapshot(&[("counter", "3")]);
+
+        let mut heatmap = StorageHeatmap::new();
+        heatmap.record_invocation(&step0, &step1);
+        heatmap.record_invocation(&step1, &step2);
+        heatmap.record_invocation(&step2, &step3);
+
+        assert_eq!(heatmap.invocations(), 3);
+        let ranked = heatmap.ranked(&no_filter());
+        assert_eq!(ranked[0].key, "counter");
+        assert_eq!((ranked[0].reads, ranked[0].writes), (2, 3));
+        assert_eq!((ranked[0].bytes_read, ranked[0].bytes_written), (2, 3));
+        assert_eq!(ranked[1].key, "config");
+        assert_eq!((ranked[1].reads, ranked[1].writes), (2, 1));
+        assert_eq!(ranked[1].bytes_written, 0);
+    }
+
+    #[test]
+    fn merge_matches_recording_into_one_heatmap() {
+        let before = snapshot(&[("a", "1")]);
+        let after = snapshot(&[("a", "2"), ("b", "x")]);
+
+        let mut combined = StorageHeatmap::new();
+        combined.record_invocation(&before, &after);
+        combined.record_invocation(&before, &after);
+
+        let mut merged = StorageHeatmap::new();
+        for _ in 0..2 {
+            let mut iteration = StorageHeatmap::new();
+            iteration.record_invocation(&before, &after);
+            merged.merge(&iteration);
+        }
+
+        assert_eq!(merged.invocations(), combined.invocations());
+        assert_eq!(merged.ranked(&no_filter()), combined.ranked(&no_filter()));
+    }
+
+    #[test]
+    fn filter_and_top_limit_rows() {
+        let mut heatmap = StorageHeatmap::new();
+        heatmap.record_write("balance:alice", 8);
+        heatmap.record_write("balance:alice", 8);
+        heatmap.record_read("balance:bob", 8);
+        heatmap.record_read("admin", 56);
+
+        let filter = StorageFilter::new(&["balance:*".to_string()]).unwrap();
+        let rendered = heatmap.render(1, &filter);
+        assert!(rendered.starts_with("Top 1 of 2 key(s)"));
+        assert!(rendered.contains("balance:alice"));
+        assert!(!rendered.contains("balance:bob"));
+        assert!(!rendered.contains("admin"));
+
+        let json = heatmap.to_json(&filter);
+        assert_eq!(json["keys"].as_array().unwrap().len(), 2);
+        assert_eq!(json["keys"][0]["writes"], 2);
+    }
+
+    #[test]
+    fn sampled_counts_are_scaled_and_labelled() {
+        let mut heatmap = StorageHeatmap::new();
+        heatmap.set_sample_rate(4);
+        for _ in 0..8 {
+            heatmap.record_read("hot", 2);
+        }
+
+        let ranked = heatmap.ranked(&no_filter());
+        assert_eq!((ranked[0].reads, ranked[0].bytes_read), (8, 16));
+        let rendered = heatmap.render(DEFAULT_HEATMAP_KEYS, &no_filter());
+        assert!(rendered.contains("Estimated counts: 1 in 4 accesses sampled"));
+        let json = heatmap.to_json(&no_filter());
+        assert_eq!(json["estimated"], true);
+        assert_eq!(json["sample_rate"], 4);
+        assert!(StorageHeatmap::new().to_json(&no_filter())["estimated"].is_null());
+    }
+
+    #[test]
+    fn bars_scale_to_the_busiest_key() {
+        let mut heatmap = StorageHeatmap::new();
+        for _ in 0..4 {
+            heatmap.record_read("hot", 1);
+        }
+        heatmap.record_read("cold", 1);
+
+        let rendered = heatmap.render(DEFAULT_HEATMAP_KEYS, &no_filter());
+        let bar_len = |key: &str| {
+            let line = rendered.lines().find(|line| line.starts_with(key)).unwrap();
+            line.chars().filter(|c| *c == '█' || *c == '#').count()
+        };
+        assert_eq!(bar_len("hot"), BAR_WIDTH);
+        assert_eq!(bar_len("cold"), BAR_WIDTH / 4);
+    }
+}
@@ -0,0 +1,378 @@
+//! Every storage write of a session, in order, with the call that made it
+//! (`run --track-writes`).
+//!
+//! The host only exposes storage as a snapshot before and after a whole
+//! invocation, so a key written several times within one invocation gets one
+//! [`StorageMutation`], from its value before the invocation to its value
+//! after. Each mutation is put on the frame that made it: the latest call
+//! into the contract that owns the key, read from the `fn_call` diagnostic
+//! events, since a contract can only write its own storage. Keys inside the
+//! contract instance are tracked one by one as `instance:<key>`; the
+//! instance ledger entry itself is left out.
+
+use crate::inspector::storage::{split_ttl, StorageFilter};
+use crate::runtime::observer::{CallStart, StorageWrite};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// What a write did to its key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WriteOperation {
+    /// Stored a value, possibly the one the key already held.
+    Set,
+    Remove,
+    /// Kept the value and moved the ledger it lives until.
+    ExtendTtl,
+}
+
+impl WriteOperation {
+    pub fn of(write: &StorageWrite) -> Self {
+        match (&write.old, &write.new) {
+            (_, None) => Self::Remove,
+            (Some(old), Some(new)) if old != new && split_ttl(old).0 == split_ttl(new).0 => {
+                Self::ExtendTtl
+            }
+            _ => Self::Set,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Set => "set",
+            Self::Remove => "remove",
+            Self::ExtendTtl => "extend_ttl",
+        }
+    }
+}
+
+/// A call on the stack of a write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StackFrame {
+    pub contract: String,
+    pub function: String,
+    /// The arguments, decoded.
+    pub args: Vec<String>,
+}
+
+/// One write of a storage key and the frame that made it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageMutation {
+    /// 1-based invocation index, as in provenance.
+    pub step: usize,
+    /// 0 for the top-level call, 1 for a contract it called, and so on.
+    pub depth: usize,
+    pub contract: String,
+    pub function: String,
+    pub key: String,
+    /// `None` if the write created the key.
+    pub old: Option<String>,
+    /// `None` if the write removed the key.
+    pub new: Option<String>,
+    pub operation: WriteOperation,
+    /// The arguments of the writing frame, decoded.
+    pub args: Vec<String>,
+    /// The calls that led to the writing frame, outermost first; empty for
+    /// a write of the top-level call.
+    pub callers: Vec<StackFrame>,
+}
+
+impl StorageMutation {
+    /// The frame that wrote the key, e.g. `transfer at depth 1 in CABC...`.
+    pub fn frame(&self) -> String {
+        format!(
+            "{} at depth {} in {}",
+            self.function, self.depth, self.contract
+        )
+    }
+}
+
+impl fmt::Display for StorageMutation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step {} {} {}: {} -> {}  [{}]",
+            self.step,
+            self.operation.name(),
+            self.key,
+            self.old.as_deref().unwrap_or("<absent>"),
+            self.new.as_deref().unwrap_or("<absent>"),
+            self.frame()
+        )
+    }
+}
+
+/// Storage mutations in the order they were made.
+#[derive(Debug, Clone, Default)]
+pub struct StorageHistory {
+    mutations: Vec<StorageMutation>,
+}
+
+impl StorageHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the writes of invocation `step`.
+    ///
+    /// `calls` are the calls the invocation made, in the order they started,
+    /// and `owners` maps each written key to the contract it belongs to.
+    /// Writes of keys without an owner, such as the instance entry or
+    /// contract code, are skipped. Without `calls` (diagnostics off) every
+    /// write goes to `top`.
+    pub fn record_invocation(
+        &mut self,
+        step: usize,
+        top: &CallStart,
+        calls: &[CallStart],
+        writes: &[StorageWrite],
+        owners: &HashMap<String, String>,
+    ) {
+        let mut mutations: Vec<(usize, StorageMutation)> = writes
+            .iter()
+            .filter_map(|write| {
+                let owner = owners.get(&write.key)?;
+                let (order, call) = calls
+                    .iter()
+                    .enumerate()
+                    .rfind(|(_, call)| &call.contract == owner)
+                    .unwrap_or((0, top));
+                Some((
+                    order,
+                    StorageMutation {
+                        step,
+                        depth: call.depth,
+                        contract: call.contract.clone(),
+                        function: call.function.clone(),
+                        key: write.key.clone(),
+                        old: write.old.clone(),
+                        new: write.new.clone(),
+                        operation: WriteOperation::of(write),
+                        args: json_args(&call.args),
+                        callers: callers_of(calls, order, call.depth),
+                    },
+                ))
+            })
+            .collect();
+        // Writes come sorted by key; keep that within each frame.
+        mutations.sort_by_key(|(order, _)| *order);
+        self.mutations
+            .extend(mutations.into_iter().map(|(_, mutation)| mutation));
+    }
+
+    pub fn mutations(&self) -> &[StorageMutation] {
+        &self.mutations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mutations.is_empty()
+    }
+
+    /// Every write of `key`, oldest first.
+    pub fn writes_of<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a StorageMutation> {
+        self.mutations
+            .iter()
+            .filter(move |mutation| mutation.key == key)
+    }
+
+    /// The latest write of `key`.
+    pub fn last_write(&self, key: &str) -> Option<&StorageMutation> {
+        self.mutations
+            .iter()
+            .rev()
+            .find(|mutation| mutation.key == key)
+    }
+
+    /// The writes of each key the filter keeps, oldest first, keys sorted.
+    /// A write passes `val:` patterns if its old or new value does.
+    pub fn render(&self, filter: &StorageFilter) -> String {
+        let mut by_key: BTreeMap<&str, Vec<&StorageMutation>> = BTreeMap::new();
+        for mutation in &self.mutations {
+            let value_matches = [&mutation.old, &mutation.new]
+                .into_iter()
+                .flatten()
+                .any(|value| filter.matches_value(value));
+            if filter.matches(&mutation.key) && value_matches {
+                by_key.entry(&mutation.key).or_default().push(mutation);
+            }
+        }
+        if by_key.is_empty() {
+            return "No storage writes recorded".to_string();
+        }
+        let mut lines = Vec::new();
+        for (key, mutations) in by_key {
+            lines.push(format!("{} ({} write(s)):", key, mutations.len()));
+            for mutation in mutations {
+                lines.push(format!(
+                    "  step {} {}: {} -> {}  [{}]",
+                    mutation.step,
+                    mutation.operation.name(),
+                    mutation.old.as_deref().unwrap_or("<absent>"),
+                    mutation.new.as_deref().unwrap_or("<absent>"),
+                    mutation.frame()
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Every mutation, in order.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(&self.mutations).unwrap_or_default()
+    }
+}
+
+/// The call stack of `calls[order]`, a call at `depth`: the latest call
+/// started before it at each lower depth, outermost first.
+fn callers_of(calls: &[CallStart], order: usize, depth: usize) -> Vec<StackFrame> {
+    let mut callers = Vec::new();
+    let mut below = depth;
+    for call in calls[..order.min(calls.len())].iter().rev() {
+        if below == 0 {
+            break;
+        }
+        if call.depth == below - 1 {
+            callers.push(StackFrame {
+                contract: call.contract.clone(),
+                function: call.function.clone(),
+                args: json_args(&call.args),
+            });
+            below -= 1;
+        }
+    }
+    callers.reverse();
+    callers
+}
+
+/// The arguments of a [`CallStart`], one per item of its JSON array; a
+/// single argument comes without the array.
+fn json_args(args: &serde_json::Value) -> Vec<String> {
+    match args {
+        serde_json::Value::Null => Vec::new(),
+        serde_json::Value::Array(items) => items.iter().map(ToString::to_string).collect(),
+        other => vec![other.to_string()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(depth: usize, contract: &str, function: &str) -> CallStart {
+        CallStart {
+            depth,
+            contract: contract.to_string(),
+            function: function.to_string(),
+            args: serde_json::json!([]),
+        }
+    }
+
+    fn write(key: &str, old: Option<&str>, new: Option<&str>) -> StorageWrite {
+        StorageWrite {
+            key: key.to_string(),
+            old: old.map(str::to_string),
+            new: new.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn writes_go_to_the_latest_frame_of_the_owning_contract() {
+        let calls = [
+            call(0, "CPOOL", "swap"),
+            call(1, "CTOKEN", "transfer"),
+            call(2, "CPOOL", "on_transfer"),
+        ];
+        let owners = HashMap::from([
+            ("balance".to_string(), "CTOKEN".to_string()),
+            ("reserve".to_string(), "CPOOL".to_string()),
+            ("nonce".to_string(), "CTOKEN".to_string()),
+        ]);
+        let writes = [
+            write(
+                "balance",
+                Some("I128(5) (ttl=100)"),
+                Some("I128(2) (ttl=100)"),
+            ),
+            write("contract_code", None, Some("code")),
+            write("nonce", Some("U32(1) (ttl=100)"), Some("U32(1) (ttl=900)")),
+            write("reserve", Some("I128(7) (ttl=100)"), None),
+        ];
+        let mut history = StorageHistory::new();
+        history.record_invocation(1, &calls[0], &calls, &writes, &owners);
+
+        let summary: Vec<(&str, &str, usize, WriteOperation)> = history
+            .mutations()
+            .iter()
+            .map(|m| (m.key.as_str(), m.function.as_str(), m.depth, m.operation))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("balance", "transfer", 1, WriteOperation::Set),
+                ("nonce", "transfer", 1, WriteOperation::ExtendTtl),
+                ("reserve", "on_transfer", 2, WriteOperation::Remove),
+            ]
+        );
+        assert_eq!(
+            history.last_write("reserve").unwrap().to_string(),
+            "step 1 remove reserve: I128(7) (ttl=100) -> <absent>  [on_transfer at depth 2 in CPOOL]"
+        );
+        let callers: Vec<&str> = history.mutations()[2]
+            .callers
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect();
+        assert_eq!(callers, ["swap", "transfer"]);
+        assert_eq!(history.mutations()[0].callers[0].contract, "CPOOL");
+    }
+
+    #[test]
+    fn history_is_listed_per_key_and_filtered() {
+        let top = call(0, "CCOUNTER", "increment");
+        let owners = HashMap::from([
+            ("instance:c".to_string(), "CCOUNTER".to_string()),
+            ("instance:d".to_string(), "CCOUNTER".to_string()),
+        ]);
+        let mut history = StorageHistory::new();
+        history.record_invocation(
+            1,
+            &top,
+            &[],
+            &[write("instance:c", None, Some("I64(1)"))],
+            &owners,
+        );
+        history.record_invocation(
+            2,
+            &top,
+            &[],
+            &[
+                write("instance:c", Some("I64(1)"), Some("I64(2)")),
+                write("instance:d", None, Some("Bool(true)")),
+            ],
+            &owners,
+        );
+
+        let only_c = StorageFilter::new(&["instance:c".to_string()]).unwrap();
+        assert_eq!(
+            history.render(&only_c),
+            "instance:c (2 write(s)):\n  \
+             step 1 set: <absent> -> I64(1)  [increment at depth 0 in CCOUNTER]\n  \
+             step 2 set: I64(1) -> I64(2)  [increment at depth 0 in CCOUNTER]"
+        );
+        let none = StorageFilter::new(&["balance:*".to_string()]).unwrap();
+        assert_eq!(history.render(&none), "No storage writes recorded");
+        let bools = StorageFilter::new(&["val:Bool*".to_string()]).unwrap();
+        assert_eq!(
+            history.render(&bools),
+            "instance:d (1 write(s)):\n  \
+             step 2 set: <absent> -> Bool(true)  [increment at depth 0 in CCOUNTER]"
+        );
+
+        let json = history.to_json();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[2]["key"], "instance:d");
+        assert_eq!(json[2]["operation"], "set");
+        assert_eq!(json[2]["old"], serde_json::Value::Null);
+    }
+}
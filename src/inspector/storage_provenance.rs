@@ -0,0 +1,288 @@
+//! Which invocation last wrote each storage entry.
+//!
+//! The executor records the writes of every completed invocation in a
+//! [`StorageProvenance`], so when a later check fails the offending value can
+//! be traced back to the step and function that stored it. Keys inside the
+//! contract instance are tracked one by one as `instance:<key>` next to the
+//! instance ledger entry itself.
+
+use crate::debugger::expression::{ExprValue, ExpressionEvaluator};
+use crate::inspector::storage::key_matches;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+
+/// Earlier writers kept per key besides the last one.
+pub const PROVENANCE_HISTORY: usize = 8;
+
+/// One write of a storage entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Writer {
+    /// 1-based invocation index; one per scenario step.
+    pub step: usize,
+    pub function: String,
+    /// Sequence number of the call in the execution trace.
+    pub sequence: usize,
+}
+
+impl fmt::Display for Writer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step {} {} (#{})",
+            self.step, self.function, self.sequence
+        )
+    }
+}
+
+/// The writers of one storage entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Provenance {
+    pub writer: Writer,
+    /// Earlier writers, newest first, at most [`PROVENANCE_HISTORY`].
+    #[serde(skip_serializing_if = "VecDeque::is_empty")]
+    pub history: VecDeque<Writer>,
+}
+
+impl Provenance {
+    /// Every recorded writer, newest first.
+    pub fn writers(&self) -> impl Iterator<Item = &Writer> {
+        std::iter::once(&self.writer).chain(&self.history)
+    }
+}
+
+/// Last writers keyed by storage key.
+#[derive(Debug, Clone, Default)]
+pub struct StorageProvenance {
+    entries: HashMap<String, Provenance>,
+}
+
+impl StorageProvenance {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one invocation from the storage snapshots taken around it.
+    ///
+    /// New, modified and removed keys count as written, as do `rewrites`:
+    /// keys the invocation stored again with the value they already held.
+    pub fn record_invocation(
+        &mut self,
+        writer: &Writer,
+        before: &HashMap<String, String>,
+        after: &HashMap<String, String>,
+        rewrites: &HashSet<String>,
+    ) {
+        let mut written: Vec<&String> = after
+            .iter()
+            .filter(|(key, value)| before.get(*key) != Some(*value) || rewrites.contains(*key))
+            .map(|(key, _)| key)
+            .chain(before.keys().filter(|key| !after.contains_key(*key)))
+            .collect();
+        written.sort();
+        for key in written {
+            self.record_write(key, writer.clone());
+        }
+    }
+
+    pub fn record_write(&mut self, key: &str, writer: Writer) {
+        match self.entries.get_mut(key) {
+            Some(provenance) => {
+                let previous = std::mem::replace(&mut provenance.writer, writer);
+                provenance.history.push_front(previous);
+                provenance.history.truncate(PROVENANCE_HISTORY);
+            }
+            None => {
+                self.entries.insert(
+                    key.to_string(),
+                    Provenance {
+                        writer,
+                        history: VecDeque::new(),
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Provenance> {
+        self.entries.get(key)
+    }
+
+    /// Find a key by its full storage key, or by the stored key alone as in
+    /// `writer(admin)`. Of several matches the most recently written wins.
+    pub fn lookup(&self, key: &str) -> Option<&Provenance> {
+        self.get(key).or_else(|| {
+            self.entries
+                .iter()
+                .filter(|(stored, _)| key_matches(stored, key))
+                .map(|(_, provenance)| provenance)
+                .max_by_key(|provenance| provenance.writer.sequence)
+        })
+    }
+
+    pub fn entries(&self) -> &HashMap<String, Provenance> {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Provenance keyed by storage key, as JSON.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+        serde_json::Value::Object(
+            keys.into_iter()
+                .map(|key| (key.clone(), serde_json::json!(self.entries[key])))
+                .collect(),
+        )
+    }
+}
+
+impl StorageProvenance {
+    /// Make the provenance functions available to expressions:
+    ///
+    /// - `writer(KEY)` — the function that made the last write; `none` if it
+    ///   was never written
+    /// - `writers(KEY)` — the function that made every recorded write, e.g.
+    ///   "only initialize may write admin"; several are listed comma-separated
+    /// - `step(KEY)` — the step of the last write; 0 if it was never written
+    pub fn register_functions(&self, evaluator: &mut ExpressionEvaluator) {
+        let provenance = Rc::new(self.clone());
+        let of = Rc::clone(&provenance);
+        evaluator.register("writer", move |key| {
+            Ok(ExprValue::Str(match of.lookup(key) {
+                Some(provenance) => provenance.writer.function.clone(),
+                None => "none".to_string(),
+            }))
+        });
+        let of = Rc::clone(&provenance);
+        evaluator.register("writers", move |key| {
+            let mut functions: Vec<&str> = Vec::new();
+            for writer in of.lookup(key).into_iter().flat_map(Provenance::writers) {
+                if !functions.contains(&writer.function.as_str()) {
+                    functions.push(&writer.function);
+                }
+            }
+            Ok(ExprValue::Str(if functions.is_empty() {
+                "none".to_string()
+            } else {
+                functions.join(", ")
+            }))
+        });
+        evaluator.register("step", move |key| {
+            let step = provenance.lookup(key).map_or(0, |p| p.writer.step);
+            Ok(ExprValue::Int(step as i128))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADMIN: &str = "contract_data:Persistent:Symbol(ScSymbol(StringM(admin)))";
+
+    fn writer(step: usize, function: &str) -> Writer {
+        Writer {
+            step,
+            function: function.to_string(),
+            sequence: step * 10,
+        }
+    }
+
+    fn storage(entries: &[(&str, &str)]) -> HashMap<String, String> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn records_changed_removed_and_rewritten_keys() {
+        let mut provenance = StorageProvenance::new();
+        let before = storage(&[("a", "1"), ("b", "2"), ("c", "3")]);
+        let after = storage(&[("a", "1"), ("b", "5"), ("d", "4")]);
+        let rewrites: HashSet<String> = ["a".to_string()].into();
+        provenance.record_invocation(&writer(1, "update"), &before, &after, &rewrites);
+
+        for key in ["a", "b", "c", "d"] {
+            assert_eq!(provenance.get(key).unwrap().writer, writer(1, "update"));
+        }
+
+        let mut untouched = StorageProvenance::new();
+        untouched.record_invocation(&writer(1, "get"), &before, &before, &HashSet::new());
+        assert!(untouched.is_empty());
+    }
+
+    #[test]
+    fn keeps_a_bounded_history_newest_first() {
+        let mut provenance = StorageProvenance::new();
+        for step in 1..=PROVENANCE_HISTORY + 3 {
+            provenance.record_write("k", writer(step, "set"));
+        }
+        let entry = provenance.get("k").unwrap();
+        assert_eq!(entry.writer.step, PROVENANCE_HISTORY + 3);
+        assert_eq!(entry.history.len(), PROVENANCE_HISTORY);
+        assert_eq!(entry.history[0].step, PROVENANCE_HISTORY + 2);
+    }
+
+    #[test]
+    fn lookup_accepts_the_stored_key_alone() {
+        let mut provenance = StorageProvenance::new();
+        provenance.record_write(ADMIN, writer(1, "initialize"));
+        provenance.record_write(
+            "instance:Symbol(ScSymbol(StringM(admin)))",
+            writer(2, "rotate"),
+        );
+        assert_eq!(
+            provenance.lookup(ADMIN).unwrap().writer.function,
+            "initialize"
+        );
+        assert_eq!(
+            provenance.lookup("admin").unwrap().writer.function,
+            "rotate"
+        );
+        assert!(provenance.lookup("owner").is_none());
+    }
+
+    #[test]
+    fn functions_report_last_and_all_writers() {
+        let mut provenance = StorageProvenance::new();
+        provenance.record_write(ADMIN, writer(1, "initialize"));
+        let mut evaluator = ExpressionEvaluator::new(0);
+        provenance.register_functions(&mut evaluator);
+        let str = |s: &str| ExprValue::Str(s.to_string());
+        assert_eq!(evaluator.eval("writer(admin)").unwrap(), str("initialize"));
+        assert_eq!(evaluator.eval("writers(admin)").unwrap(), str("initialize"));
+        assert_eq!(evaluator.eval("step(admin)").unwrap(), ExprValue::Int(1));
+        assert_eq!(evaluator.eval("writer(owner)").unwrap(), str("none"));
+        assert_eq!(evaluator.eval("step(owner)").unwrap(), ExprValue::Int(0));
+
+        provenance.record_write(ADMIN, writer(3, "set_admin"));
+        provenance.register_functions(&mut evaluator);
+        assert_eq!(
+            evaluator.eval(&format!("writer(\"{}\")", ADMIN)).unwrap(),
+            str("set_admin")
+        );
+        assert_eq!(
+            evaluator.eval("writers(admin)").unwrap(),
+            str("set_admin, initialize")
+        );
+        assert!(evaluator.eval("writer()").is_err());
+        assert!(evaluator.eval("balance(admin)").is_err());
+    }
+
+    #[test]
+    fn json_lists_writer_and_history() {
+        let mut provenance = StorageProvenance::new();
+        provenance.record_write("k", writer(1, "init"));
+        provenance.record_write("k", writer(2, "bump"));
+        let json = provenance.to_json();
+        assert_eq!(json["k"]["writer"]["function"], "bump");
+        assert_eq!(json["k"]["writer"]["step"], 2);
+        assert_eq!(json["k"]["history"][0]["function"], "init");
+    }
+}
@@ -0,0 +1,304 @@
+//! Serialized size and estimated rent of storage entries, shown with the
+//! `run` storage listing.
+//!
+//! The network charges rent per byte of an entry's ledger XDR for every
+//! ledger the entry lives. The estimate multiplies each entry's size by a
+//! per-byte rate over a fixed number of ledgers, with instance storage paying
+//! the persistent rate. Instance items are sized as their key and value
+//! inside the instance, so the instance total stands for the instance entry.
+
+use crate::config::{RentConfig, RentNetwork};
+use crate::inspector::storage::StorageEntry;
+use crate::utils::ledger_key::Durability;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Ledgers in 30 days at 5 seconds a ledger.
+pub const DEFAULT_RENT_LEDGERS: u32 = 518_400;
+
+/// Per-byte rent rates and the number of ledgers the estimate covers.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RentRates {
+    pub network: RentNetwork,
+    /// Stroops per byte per ledger for persistent and instance entries.
+    pub persistent: f64,
+    /// Stroops per byte per ledger for temporary entries.
+    pub temporary: f64,
+    pub ledgers: u32,
+}
+
+impl RentRates {
+    /// Rates close to the network's rent settings, over 30 days.
+    pub fn network(network: RentNetwork) -> Self {
+        let (persistent, temporary) = match network {
+            RentNetwork::Testnet => (0.0049, 0.0024),
+            RentNetwork::Mainnet => (0.0055, 0.0027),
+        };
+        Self {
+            network,
+            persistent,
+            temporary,
+            ledgers: DEFAULT_RENT_LEDGERS,
+        }
+    }
+
+    /// The rates of the `[rent]` config table: those of its network
+    /// (testnet by default), each of which it can override.
+    pub fn from_config(config: &RentConfig) -> Self {
+        let defaults = Self::network(config.network.unwrap_or(RentNetwork::Testnet));
+        Self {
+            persistent: config.persistent_rate.unwrap_or(defaults.persistent),
+            temporary: config.temporary_rate.unwrap_or(defaults.temporary),
+            ledgers: config.ledgers.unwrap_or(defaults.ledgers),
+            ..defaults
+        }
+    }
+
+    /// Stroops of rent `bytes` in `durability` pay over the estimated
+    /// ledgers, rounded up.
+    pub fn rent(&self, durability: Durability, bytes: u64) -> u64 {
+        let rate = match durability {
+            Durability::Temporary => self.temporary,
+            Durability::Instance | Durability::Persistent => self.persistent,
+        };
+        (bytes as f64 * rate * self.ledgers as f64).ceil() as u64
+    }
+
+    fn network_name(&self) -> &'static str {
+        match self.network {
+            RentNetwork::Testnet => "testnet",
+            RentNetwork::Mainnet => "mainnet",
+        }
+    }
+}
+
+/// Number of entries, their size and their rent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RentTotal {
+    pub entries: usize,
+    pub bytes: u64,
+    pub stroops: u64,
+}
+
+impl RentTotal {
+    fn add(&mut self, bytes: u64, stroops: u64) {
+        self.entries += 1;
+        self.bytes += bytes;
+        self.stroops += stroops;
+    }
+
+    fn add_total(&mut self, other: &RentTotal) {
+        self.entries += other.entries;
+        self.bytes += other.bytes;
+        self.stroops += other.stroops;
+    }
+}
+
+impl fmt::Display for RentTotal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} entr{}, {} B, {} stroops",
+            self.entries,
+            if self.entries == 1 { "y" } else { "ies" },
+            self.bytes,
+            self.stroops
+        )
+    }
+}
+
+/// Totals of one storage.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RentClass {
+    pub durability: Durability,
+    #[serde(flatten)]
+    pub total: RentTotal,
+    /// The entries the run created or changed.
+    pub written: RentTotal,
+}
+
+/// Size and rent of the listed entries per storage, and of those the run
+/// wrote, which is what the call itself adds.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RentEstimate {
+    pub rates: RentRates,
+    pub classes: Vec<RentClass>,
+    pub total: RentTotal,
+    pub written: RentTotal,
+}
+
+impl RentEstimate {
+    /// Estimate the rent of `entries`, counting those in `written` apart.
+    /// Entries that are not contract data or have no known size are left out.
+    pub fn of<'a>(
+        entries: impl IntoIterator<Item = (&'a String, &'a StorageEntry)>,
+        written: &HashSet<String>,
+        rates: RentRates,
+    ) -> Self {
+        let mut classes: Vec<RentClass> = [
+            Durability::Instance,
+            Durability::Persistent,
+            Durability::Temporary,
+        ]
+        .into_iter()
+        .map(|durability| RentClass {
+            durability,
+            total: RentTotal::default(),
+            written: RentTotal::default(),
+        })
+        .collect();
+        for (key, entry) in entries {
+            let (Some(durability), Some(size)) = (entry.durability, entry.size_bytes) else {
+                continue;
+            };
+            let bytes = u64::from(size);
+            let stroops = rates.rent(durability, bytes);
+            let Some(class) = classes.iter_mut().find(|c| c.durability == durability) else {
+                continue;
+            };
+            class.total.add(bytes, stroops);
+            if written.contains(key) {
+                class.written.add(bytes, stroops);
+            }
+        }
+        classes.retain(|class| class.total.entries > 0);
+
+        let mut total = RentTotal::default();
+        let mut written = RentTotal::default();
+        for class in &classes {
+            total.add_total(&class.total);
+            written.add_total(&class.written);
+        }
+        Self {
+            rates,
+            classes,
+            total,
+            written,
+        }
+    }
+
+    /// One line per storage and one for the total, each with the share of
+    /// the entries this run wrote.
+    pub fn render(&self) -> String {
+        let mut lines = vec![format!(
+            "Rent estimate ({} rates, {} ledgers):",
+            self.rates.network_name(),
+            self.rates.ledgers
+        )];
+        if self.classes.is_empty() {
+            lines.push("  No contract data listed".to_string());
+            return lines.join("\n");
+        }
+        for class in &self.classes {
+            lines.push(format!(
+                "  {}: {} (this run: {})",
+                class.durability.name(),
+                class.total,
+                class.written
+            ));
+        }
+        lines.push(format!(
+            "  total: {} (this run: {})",
+            self.total, self.written
+        ));
+        lines.join("\n")
+    }
+}
+
+/// Keys of `after` that are new or hold a different entry than in `before`.
+pub fn written_keys(
+    before: &HashMap<String, StorageEntry>,
+    after: &HashMap<String, StorageEntry>,
+) -> HashSet<String> {
+    after
+        .iter()
+        .filter(|(key, entry)| before.get(*key) != Some(*entry))
+        .map(|(key, _)| key.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(durability: Option<Durability>, size: u32, value: &str) -> StorageEntry {
+        StorageEntry {
+            key: String::new(),
+            value: value.to_string(),
+            durability,
+            live_until: Some(1000),
+            size_bytes: Some(size),
+            scval_key: None,
+            scval_value: None,
+        }
+    }
+
+    #[test]
+    fn rates_come_from_the_network_and_config() {
+        let testnet = RentRates::from_config(&RentConfig::default());
+        assert_eq!(testnet, RentRates::network(RentNetwork::Testnet));
+
+        let config = RentConfig {
+            network: Some(RentNetwork::Mainnet),
+            temporary_rate: Some(0.5),
+            ledgers: Some(10),
+            ..RentConfig::default()
+        };
+        let rates = RentRates::from_config(&config);
+        assert_eq!(rates.network, RentNetwork::Mainnet);
+        assert_eq!(
+            rates.persistent,
+            RentRates::network(RentNetwork::Mainnet).persistent
+        );
+        assert_eq!(rates.rent(Durability::Temporary, 3), 15);
+    }
+
+    #[test]
+    fn totals_are_per_storage_with_the_written_share() {
+        let rates = RentRates {
+            network: RentNetwork::Testnet,
+            persistent: 1.0,
+            temporary: 0.5,
+            ledgers: 10,
+        };
+        let before = HashMap::from([
+            (
+                "instance:c".to_string(),
+                entry(Some(Durability::Instance), 20, "I64(1)"),
+            ),
+            (
+                "instance:admin".to_string(),
+                entry(Some(Durability::Instance), 60, "Address(..)"),
+            ),
+        ]);
+        let mut after = before.clone();
+        after.insert(
+            "instance:c".to_string(),
+            entry(Some(Durability::Instance), 20, "I64(2)"),
+        );
+        after.insert(
+            "contract_data:Temporary:Symbol(lock)".to_string(),
+            entry(Some(Durability::Temporary), 41, "Bool(true)"),
+        );
+        after.insert("contract_code".to_string(), entry(None, 900, "code"));
+
+        let written = written_keys(&before, &after);
+        assert_eq!(written.len(), 3);
+        let estimate = RentEstimate::of(&after, &written, rates);
+
+        assert_eq!(
+            estimate.render(),
+            "Rent estimate (testnet rates, 10 ledgers):\n  \
+             instance: 2 entries, 80 B, 800 stroops (this run: 1 entry, 20 B, 200 stroops)\n  \
+             temporary: 1 entry, 41 B, 205 stroops (this run: 1 entry, 41 B, 205 stroops)\n  \
+             total: 3 entries, 121 B, 1005 stroops (this run: 2 entries, 61 B, 405 stroops)"
+        );
+        let json = serde_json::to_value(&estimate).unwrap();
+        assert_eq!(json["classes"][0]["durability"], "instance");
+        assert_eq!(json["classes"][0]["bytes"], 80);
+        assert_eq!(json["classes"][0]["written"]["stroops"], 200);
+        assert_eq!(json["written"]["entries"], 2);
+        assert_eq!(json["rates"]["network"], "testnet");
+    }
+}
@@ -3,32 +3,40 @@
 pub mod analyzer;
 pub mod batch;
 pub mod benchmarks;
+pub mod call_sequence;
 pub mod cli;
 pub mod client;
 pub mod codegen;
 pub mod compare;
 pub mod config;
 pub mod debugger;
+pub mod environments;
 pub mod history;
 pub mod inspector;
 pub mod logging;
 pub mod output;
 pub mod plugin;
+pub mod presets;
 pub mod profiler;
 pub mod protocol;
 pub mod repeat;
 pub mod repl;
+pub mod report;
+pub mod rpc;
 pub mod runtime;
 pub mod scenario;
 pub mod server;
 pub mod simulator;
 pub mod ui;
 pub mod utils;
+pub mod warnings;
 
 use miette::Diagnostic;
 
 pub use debugger::engine::DebuggerEngine;
 pub use runtime::executor::ContractExecutor;
+pub use runtime::result::ExecutionOutcome;
+pub use runtime::observer::InvocationObserver;
 
 /// Result type alias for the debugger
 pub type Result<T> = miette::Result<T>;
@@ -64,6 +72,13 @@ pub enum DebuggerError {
     )]
     InvalidArguments(String),
 
+    #[error("Invalid flag value: {0}")]
+    #[diagnostic(
+        code(debugger::invalid_flag),
+        help("Action: Correct the flag named above, or its key in the `[ledger]` section of .soroban-debug.toml if the value came from there.\nContext: Flags that set up the ledger and the invoker of the call, such as `--ledger-timestamp`, `--protocol-version`, `--limits` and `--source-account`, are checked before the contract runs.")
+    )]
+    InvalidFlag(String),
+
     #[error("Breakpoint error: {0}")]
     #[diagnostic(
         code(debugger::breakpoint_error),
@@ -112,4 +127,25 @@ pub enum DebuggerError {
         help("Action: Ensure the shared security token matches the server, and the transport protocol is correct.\nContext: The server rejected communication because authentication wasn't verified.")
     )]
     AuthenticationFailed(String),
+
+    #[error("Function blocked by policy: {0}")]
+    #[diagnostic(
+        code(debugger::policy_violation),
+        help("Action: Call a permitted function, or pass `--no-policy` to ignore the `[policy]` section of .soroban-debug.toml for this run.\nContext: The project config denies or does not allow this contract function.")
+    )]
+    PolicyViolation(String),
+
+    #[error("Convention violated: {0}")]
+    #[diagnostic(
+        code(debugger::convention_violation),
+        help("Action: Move the write or event out of the read-only function, or drop it from `[conventions] readonly_patterns`.\nContext: --strict-conventions turns read-only convention warnings into errors.")
+    )]
+    ConventionViolation(String),
+
+    #[error("Contract build failed: {0}")]
+    #[diagnostic(
+        code(debugger::build_failed),
+        help("Action: Fix the errors reported by cargo above, or build the contract yourself and pass the .wasm path to --contract.\nContext: The debugger ran cargo to locate or build the contract before debugging it.")
+    )]
+    BuildError(String),
 }
@@ -3,6 +3,7 @@ use clap::{CommandFactory, Parser};
 use clap_complete::generate;
 use soroban_debugger::cli::{Cli, Commands, Verbosity};
 use soroban_debugger::ui::formatter::Formatter;
+use soroban_debugger::warnings::WarningCategory;
 use std::io;
 
 fn verbosity_to_level(v: Verbosity) -> u8 {
@@ -34,13 +35,12 @@ fn initialize_tracing(verbosity: Verbosity) {
 }
 
 fn print_deprecation_warning(deprecated_flag: &str, new_flag: &str) {
-    eprintln!(
-        "{}",
-        Formatter::warning(format!(
-            " Flag '{}' is deprecated. Please use '{}' instead.",
-            deprecated_flag, new_flag
-        ))
+    let message = format!(
+        " Flag '{}' is deprecated. Please use '{}' instead.",
+        deprecated_flag, new_flag
     );
+    eprintln!("{}", Formatter::warning(&message));
+    soroban_debugger::warnings::record(WarningCategory::Deprecation, message.trim());
 }
 
 fn handle_deprecations(cli: &mut Cli) {
@@ -54,6 +54,16 @@ fn handle_deprecations(cli: &mut Cli) {
                 print_deprecation_warning("--snapshot", "--network-snapshot");
                 args.network_snapshot = Some(snapshot);
             }
+            // The booleans stay set and are folded into `RunArgs::shown`.
+            for (given, flag, item) in [
+                (args.show_events, "--show-events", "--show events"),
+                (args.show_auth, "--show-auth", "--show auth"),
+                (args.show_ledger, "--show-ledger", "--show ledger"),
+            ] {
+                if given {
+                    print_deprecation_warning(flag, item);
+                }
+            }
         }
         Some(Commands::Interactive(args)) => {
             if let Some(wasm) = args.wasm.take() {
@@ -134,6 +144,11 @@ fn should_show_banner(args: &Cli) -> bool {
 
 fn main() -> miette::Result<()> {
     Formatter::configure_colors_from_env();
+    // Errors quote signatures, paths and values, so they are not wrapped to
+    // the terminal width.
+    let _ = miette::set_hook(Box::new(|_| {
+        Box::new(miette::MietteHandlerOpts::new().wrap_lines(false).build())
+    }));
 
     let mut cli = Cli::parse();
     if let Some(ref history_file) = cli.history_file {
@@ -163,10 +178,36 @@ fn main() -> miette::Result<()> {
     let _ = soroban_debugger::plugin::registry::init_global_plugin_registry();
 
     let config = soroban_debugger::config::Config::load_or_default();
+    if let Some(depth) = cli
+        .max_value_depth
+        .map(|depth| depth as usize)
+        .or(config.output.max_value_depth)
+    {
+        soroban_debugger::utils::value_depth::set_max_value_depth(depth);
+    }
+    if let Some(humanize) = config.output.humanize_time {
+        soroban_debugger::utils::time::set_humanize_time(humanize);
+    }
+    if !cli.no_policy {
+        soroban_debugger::runtime::policy::set_function_policy(
+            soroban_debugger::runtime::policy::FunctionPolicy::new(&config.policy),
+        );
+    }
 
     let result = match cli.command {
         Some(Commands::Run(mut args)) => {
+            if let Err(violation) = soroban_debugger::cli::flag_rules::check_run(&args) {
+                Cli::command().error(violation.kind(), violation).exit();
+            }
             args.merge_config(&config);
+            // The JSON document is the whole of stdout, so progress messages
+            // are silenced as with --quiet.
+            if args.is_json_output() {
+                Formatter::set_verbosity(verbosity_to_level(Verbosity::Quiet));
+            }
+            if args.save_preset.is_some() || args.cache_results {
+                args.command_line = soroban_debugger::presets::run_flags_from_env()?;
+            }
             soroban_debugger::cli::commands::run(args, verbosity)
         }
         Some(Commands::Interactive(mut args)) => {
@@ -192,11 +233,20 @@ fn main() -> miette::Result<()> {
         }
         Some(Commands::Server(args)) => soroban_debugger::cli::commands::server(args),
         Some(Commands::Remote(args)) => soroban_debugger::cli::commands::remote(args, verbosity),
+        Some(Commands::Attach(args)) => soroban_debugger::cli::commands::attach(args),
         Some(Commands::Analyze(args)) => soroban_debugger::cli::commands::analyze(args, verbosity),
         Some(Commands::Scenario(args)) => {
             soroban_debugger::cli::commands::scenario(args, verbosity)
         }
         Some(Commands::HistoryPrune(args)) => soroban_debugger::cli::commands::history_prune(args),
+        Some(Commands::History(args)) => soroban_debugger::cli::commands::history(args),
+        Some(Commands::Cache(args)) => soroban_debugger::cli::commands::cache(args),
+        Some(Commands::Config(args)) => soroban_debugger::cli::commands::config(args),
+        Some(Commands::Snapshot(args)) => soroban_debugger::cli::commands::snapshot(args),
+        Some(Commands::Env(args)) => soroban_debugger::cli::commands::env(args),
+        Some(Commands::Preset(args)) => soroban_debugger::cli::commands::preset(args, verbosity),
+        Some(Commands::Key(args)) => soroban_debugger::cli::commands::key(args),
+        Some(Commands::ImportTx(args)) => soroban_debugger::cli::commands::import_tx(args),
         Some(Commands::Repl(mut args)) => {
             args.merge_config(&config);
             tokio::runtime::Runtime::new()
@@ -259,12 +309,12 @@ fn main() -> miette::Result<()> {
                         functions: true,
                         metadata: false,
                         format: soroban_debugger::cli::args::OutputFormat::Pretty,
-                        source_map_diagnostics: false,
-                        source_map_limit: 0,
                         expected_hash: None,
                         dependency_graph: None,
                         source_map_diagnostics: false,
                         source_map_limit: 20,
+                        events: false,
+                        no_spec_cache: false,
                     },
                     verbosity,
                 );
@@ -2,11 +2,21 @@
 //!
 //! Supports `NO_COLOR` (disable ANSI colors) and `--no-unicode` (ASCII-only output).
 
+use crate::debugger::error_db::ContractErrorCode;
+use crate::inspector::budget::BudgetInfo;
+use crate::inspector::diagnostics::DiagnosticEvent;
+use crate::runtime::call_stack::ContractFrame;
 use serde::Serialize;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
 static NO_UNICODE: AtomicBool = AtomicBool::new(false);
 static COLORS_ENABLED: AtomicBool = AtomicBool::new(true);
+static FAILURE_BUDGET: Mutex<Option<BudgetInfo>> = Mutex::new(None);
+static FAILURE_CONTRACT_ERROR: Mutex<Option<ContractErrorCode>> = Mutex::new(None);
+static FAILURE_DIAGNOSTICS: Mutex<Option<Vec<DiagnosticEvent>>> = Mutex::new(None);
+static FAILURE_PANIC_MESSAGE: Mutex<Option<String>> = Mutex::new(None);
+static FAILURE_CALL_STACK: Mutex<Option<Vec<ContractFrame>>> = Mutex::new(None);
 pub const SCHEMA_VERSION: &str = "1.0.0";
 
 #[derive(Debug, Clone, Copy, Serialize)]
@@ -19,6 +29,21 @@ pub enum OutputStatus {
 #[derive(Debug, Clone, Serialize)]
 pub struct OutputError {
     pub message: String,
+    /// Budget spent before the failure, when an invocation failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetInfo>,
+    /// Code and spec name of the error the contract returned, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_error: Option<ContractErrorCode>,
+    /// What the contract panicked with, when the host recorded it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub panic_message: Option<String>,
+    /// Diagnostic events up to the failure, with `--show diagnostics`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Vec<DiagnosticEvent>>,
+    /// The calls open when the invocation failed, outermost first.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub call_stack: Option<Vec<ContractFrame>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -55,11 +80,96 @@ where
             result: None,
             error: Some(OutputError {
                 message: message.into(),
+                budget: failure_budget(),
+                contract_error: failure_contract_error(),
+                panic_message: failure_panic_message(),
+                diagnostics: failure_diagnostics(),
+                call_stack: failure_call_stack(),
             }),
         }
     }
 }
 
+/// Keep the budget of an invocation that failed for the JSON error output.
+pub fn record_failure_budget(budget: BudgetInfo) {
+    match FAILURE_BUDGET.lock() {
+        Ok(mut slot) => *slot = Some(budget),
+        Err(poisoned) => *poisoned.into_inner() = Some(budget),
+    }
+}
+
+/// The budget recorded by [`record_failure_budget`], if any.
+pub fn failure_budget() -> Option<BudgetInfo> {
+    match FAILURE_BUDGET.lock() {
+        Ok(slot) => slot.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Keep the error code a failed invocation returned for the JSON error output.
+pub fn record_failure_contract_error(error: ContractErrorCode) {
+    match FAILURE_CONTRACT_ERROR.lock() {
+        Ok(mut slot) => *slot = Some(error),
+        Err(poisoned) => *poisoned.into_inner() = Some(error),
+    }
+}
+
+/// The error recorded by [`record_failure_contract_error`], if any.
+pub fn failure_contract_error() -> Option<ContractErrorCode> {
+    match FAILURE_CONTRACT_ERROR.lock() {
+        Ok(slot) => slot.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Keep the panic message of a failed invocation for the JSON error output.
+pub fn record_failure_panic_message(message: String) {
+    match FAILURE_PANIC_MESSAGE.lock() {
+        Ok(mut slot) => *slot = Some(message),
+        Err(poisoned) => *poisoned.into_inner() = Some(message),
+    }
+}
+
+/// The message recorded by [`record_failure_panic_message`], if any.
+pub fn failure_panic_message() -> Option<String> {
+    match FAILURE_PANIC_MESSAGE.lock() {
+        Ok(slot) => slot.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Keep the diagnostic events of a failed invocation for the JSON error output.
+pub fn record_failure_diagnostics(events: Vec<DiagnosticEvent>) {
+    match FAILURE_DIAGNOSTICS.lock() {
+        Ok(mut slot) => *slot = Some(events),
+        Err(poisoned) => *poisoned.into_inner() = Some(events),
+    }
+}
+
+/// The events recorded by [`record_failure_diagnostics`], if any.
+pub fn failure_diagnostics() -> Option<Vec<DiagnosticEvent>> {
+    match FAILURE_DIAGNOSTICS.lock() {
+        Ok(slot) => slot.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Keep the call stack of a failed invocation for the JSON error output.
+pub fn record_failure_call_stack(frames: Vec<ContractFrame>) {
+    match FAILURE_CALL_STACK.lock() {
+        Ok(mut slot) => *slot = Some(frames),
+        Err(poisoned) => *poisoned.into_inner() = Some(frames),
+    }
+}
+
+/// The frames recorded by [`record_failure_call_stack`], if any.
+pub fn failure_call_stack() -> Option<Vec<ContractFrame>> {
+    match FAILURE_CALL_STACK.lock() {
+        Ok(slot) => slot.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
 /// Global output/accessibility configuration.
 pub struct OutputConfig;
 
@@ -0,0 +1,508 @@
+//! Saved `run` invocations (`run --save-preset NAME`, `soroban-debug preset`).
+//!
+//! A preset holds an [`InvocationPlan`]: the `run` flags with deprecated
+//! aliases, cargo package resolution and config-file defaults already
+//! applied, so replaying it does not depend on the directory or config it was
+//! saved from. Presets live in `~/.soroban-debug/presets/<NAME>.json`. The
+//! plan records the contract's SHA-256 so a replay can warn when the WASM at
+//! the stored path has been rebuilt since.
+
+use crate::cli::args::{Cli, RunArgs};
+use crate::config::ConversionConfig;
+use crate::{DebuggerError, Result};
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable overriding the presets directory.
+pub const PRESET_DIR_ENV: &str = "SOROBAN_DEBUG_PRESET_DIR";
+
+/// Hidden `run` flags kept for compatibility, and the flag each stands for.
+const DEPRECATED_FLAGS: &[(&str, &str)] = &[("wasm", "contract"), ("snapshot", "network-snapshot")];
+
+/// Flags that describe how to find the contract rather than which contract
+/// to run; a plan pins the resolved `--contract` instead.
+const CONTRACT_SOURCE_FLAGS: &[&str] = &["contract", "package", "build", "cargo-profile"];
+
+/// A fully resolved `run` invocation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvocationPlan {
+    /// `run` flags, one `--flag` or `--flag=value` token each.
+    pub args: Vec<String>,
+    /// Argument conversions from the config file in effect when resolved.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conversions: BTreeMap<String, ConversionConfig>,
+    /// Absolute path of the contract WASM, when one was resolved.
+    #[serde(default)]
+    pub contract: Option<PathBuf>,
+    #[serde(default)]
+    pub contract_sha256: Option<String>,
+}
+
+/// A named plan, stored as `<NAME>.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    pub created_at: String,
+    pub plan: InvocationPlan,
+}
+
+impl InvocationPlan {
+    /// Resolve `args`, which must already have had the config merged in and
+    /// any cargo package resolved to a WASM path.
+    pub fn resolve(args: &RunArgs) -> Result<Self> {
+        let mut flags: Vec<String> = args
+            .command_line
+            .iter()
+            .filter(|token| {
+                let name = flag_name(token);
+                name != "save-preset" && !CONTRACT_SOURCE_FLAGS.contains(&name)
+            })
+            .cloned()
+            .collect();
+
+        let contract = match &args.contract {
+            Some(path) => Some(path.canonicalize().map_err(|e| {
+                DebuggerError::FileError(format!("Contract {:?} not found: {}", path, e))
+            })?),
+            None => None,
+        };
+        let contract_sha256 = match &contract {
+            Some(path) => Some(crate::utils::wasm::load_wasm(path)?.sha256_hash),
+            None => None,
+        };
+        if let Some(path) = &contract {
+            flags.insert(0, format!("--contract={}", path.display()));
+        }
+
+        // Defaults the config file filled in.
+        let given = |name: &str| flags.iter().any(|token| flag_name(token) == name);
+        let mut from_config = Vec::new();
+        if !given("breakpoint") {
+            from_config.extend(
+                args.breakpoint
+                    .iter()
+                    .map(|function| format!("--breakpoint={}", function)),
+            );
+        }
+        let config_show = !args.show.is_empty() || (args.show_events && !given("show-events"));
+        if config_show && !given("show") {
+            from_config.extend(
+                args.shown()
+                    .into_iter()
+                    .map(|item| format!("--show={}", item.name())),
+            );
+        }
+        if let (Some(format), false) = (&args.format, given("format")) {
+            from_config.push(format!("--format={}", format));
+        }
+        if args.verbose && !given("verbose") {
+            from_config.push("--verbose".to_string());
+        }
+        flags.extend(from_config);
+
+        Ok(Self {
+            args: flags,
+            conversions: args.conversions.clone(),
+            contract,
+            contract_sha256,
+        })
+    }
+
+    /// The argv that replays the plan, with `overrides` appended. Parsed with
+    /// `args_override_self`, an override replaces a single-valued flag and
+    /// adds to a repeatable one such as `--breakpoint`.
+    pub fn argv(&self, overrides: &[String]) -> Vec<String> {
+        ["soroban-debug", "run"]
+            .into_iter()
+            .map(str::to_string)
+            .chain(self.args.iter().cloned())
+            .chain(overrides.iter().cloned())
+            .collect()
+    }
+
+    /// The plan as a command line that can be pasted into a shell.
+    pub fn command_line(&self) -> String {
+        self.argv(&[])
+            .iter()
+            .map(|token| shell_quote(token))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Why the contract at the stored path is not the one the plan was
+    /// resolved against, if it is not.
+    pub fn contract_drift(&self) -> Option<String> {
+        let (path, expected) = (self.contract.as_ref()?, self.contract_sha256.as_ref()?);
+        match crate::utils::wasm::load_wasm(path) {
+            Ok(wasm) if wasm.sha256_hash.eq_ignore_ascii_case(expected) => None,
+            Ok(wasm) => Some(format!(
+                "Contract {:?} has changed since the preset was saved (SHA-256 {} -> {})",
+                path, expected, wasm.sha256_hash
+            )),
+            Err(_) => Some(format!(
+                "Contract {:?} no longer exists; the preset was saved against SHA-256 {}",
+                path, expected
+            )),
+        }
+    }
+}
+
+/// The `run` flags given in `matches`, as `--flag` or `--flag=value` tokens
+/// in definition order. Short flags and aliases become the long flag, and
+/// clap defaults are left out.
+pub fn run_flags(matches: &ArgMatches) -> Vec<String> {
+    let command = RunArgs::command();
+    let mut tokens = Vec::new();
+    for arg in command.get_arguments() {
+        let id = arg.get_id().as_str();
+        let Some(long) = arg.get_long() else {
+            continue;
+        };
+        if !matches!(
+            matches.value_source(id),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+        let long = DEPRECATED_FLAGS
+            .iter()
+            .find(|(deprecated, _)| *deprecated == id)
+            .map_or(long, |(_, replacement)| *replacement);
+        if !arg.get_action().takes_values() {
+            tokens.push(format!("--{}", long));
+            continue;
+        }
+        for occurrence in matches.get_raw_occurrences(id).into_iter().flatten() {
+            for value in occurrence {
+                tokens.push(format!("--{}={}", long, value.to_string_lossy()));
+            }
+        }
+    }
+    tokens
+}
+
+/// [`run_flags`] for this process's own command line, when it is a `run`.
+pub fn run_flags_from_env() -> Result<Vec<String>> {
+    let matches = Cli::command()
+        .try_get_matches_from(std::env::args_os())
+        .map_err(|e| DebuggerError::InvalidArguments(e.to_string()))?;
+    Ok(match matches.subcommand() {
+        Some(("run", run)) => run_flags(run),
+        _ => Vec::new(),
+    })
+}
+
+/// `--flag=value` -> `flag`.
+pub(crate) fn flag_name(token: &str) -> &str {
+    let flag = token.trim_start_matches('-');
+    flag.split_once('=').map_or(flag, |(name, _)| name)
+}
+
+fn shell_quote(token: &str) -> String {
+    let plain = !token.is_empty()
+        && token
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_=./:,@+".contains(c));
+    if plain {
+        token.to_string()
+    } else {
+        format!("'{}'", token.replace('\'', r"'\''"))
+    }
+}
+
+/// The directory holding every preset.
+#[derive(Debug, Clone)]
+pub struct PresetStore {
+    dir: PathBuf,
+}
+
+impl PresetStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `$SOROBAN_DEBUG_PRESET_DIR`, or `~/.soroban-debug/presets`.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Ok(dir) = std::env::var(PRESET_DIR_ENV) {
+            return Some(PathBuf::from(dir));
+        }
+        dirs::home_dir().map(|home| home.join(".soroban-debug").join("presets"))
+    }
+
+    pub fn open_default() -> Result<Self> {
+        Self::default_dir().map(Self::new).ok_or_else(|| {
+            DebuggerError::FileError("Could not determine home directory".to_string()).into()
+        })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn preset_path(&self, name: &str) -> Result<PathBuf> {
+        validate_name(name)?;
+        Ok(self.dir.join(format!("{}.json", name)))
+    }
+
+    /// Save `plan` as `name`, replacing an older preset of that name.
+    pub fn save(&self, name: &str, plan: &InvocationPlan) -> Result<PathBuf> {
+        let path = self.preset_path(name)?;
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to create presets directory {:?}: {}",
+                self.dir, e
+            ))
+        })?;
+        let preset = Preset {
+            name: name.to_string(),
+            created_at: chrono::Utc::now().to_rfc3339(),
+            plan: plan.clone(),
+        };
+        let json = serde_json::to_string_pretty(&preset).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize preset '{}': {}", name, e))
+        })?;
+        fs::write(&path, json).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to write preset {:?}: {}", path, e))
+        })?;
+        Ok(path)
+    }
+
+    pub fn load(&self, name: &str) -> Result<Preset> {
+        let path = self.preset_path(name)?;
+        if !path.exists() {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "Preset '{}' does not exist; save one with `soroban-debug run ... --save-preset {}`",
+                name, name
+            ))
+            .into());
+        }
+        read_preset(&path)
+    }
+
+    /// Every preset, sorted by name. Unreadable files are skipped.
+    pub fn list(&self) -> Result<Vec<Preset>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(DebuggerError::FileError(format!(
+                    "Failed to read presets directory {:?}: {}",
+                    self.dir, e
+                ))
+                .into())
+            }
+        };
+        let mut presets: Vec<Preset> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|path| read_preset(&path).ok())
+            .collect();
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(presets)
+    }
+
+    pub fn delete(&self, name: &str) -> Result<()> {
+        self.load(name)?;
+        let path = self.preset_path(name)?;
+        fs::remove_file(&path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to delete preset {:?}: {}", path, e)).into()
+        })
+    }
+}
+
+fn read_preset(path: &Path) -> Result<Preset> {
+    let contents = fs::read_to_string(path).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to read preset {:?}: {}", path, e))
+    })?;
+    serde_json::from_str(&contents).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to parse preset {:?}: {}", path, e)).into()
+    })
+}
+
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(DebuggerError::InvalidArguments(format!(
+            "Invalid preset name '{}': use letters, digits, '-', '_' and '.'",
+            name
+        ))
+        .into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Commands;
+    use clap::FromArgMatches;
+
+    fn run_matches(argv: &[&str]) -> ArgMatches {
+        let matches = Cli::command().try_get_matches_from(argv).unwrap();
+        matches.subcommand_matches("run").unwrap().clone()
+    }
+
+    fn run_args(matches: &ArgMatches) -> RunArgs {
+        RunArgs::from_arg_matches(matches).unwrap()
+    }
+
+    fn wasm_file(dir: &Path) -> PathBuf {
+        let path = dir.join("counter.wasm");
+        fs::write(&path, b"\0asm\x01\0\0\0").unwrap();
+        path
+    }
+
+    #[test]
+    fn run_flags_use_long_names_and_skip_defaults() {
+        let matches = run_matches(&[
+            "soroban-debug",
+            "run",
+            "-c",
+            "counter.wasm",
+            "-f",
+            "add",
+            "--args",
+            "[-5]",
+            "-b",
+            "add",
+            "-b",
+            "inc",
+            "--show-events",
+            "--snapshot",
+            "net.json",
+        ]);
+        assert_eq!(
+            run_flags(&matches),
+            [
+                "--contract=counter.wasm",
+                "--function=add",
+                "--args=[-5]",
+                "--breakpoint=add",
+                "--breakpoint=inc",
+                "--network-snapshot=net.json",
+                "--show-events",
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_pins_the_contract_and_folds_in_config_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let wasm = wasm_file(dir.path());
+        let matches = run_matches(&[
+            "soroban-debug",
+            "run",
+            "--contract",
+            wasm.to_str().unwrap(),
+            "--function",
+            "add",
+            "--save-preset",
+            "overflow",
+        ]);
+        let mut args = run_args(&matches);
+        args.command_line = run_flags(&matches);
+        args.breakpoint = vec!["add".to_string()];
+        args.format = Some("json".to_string());
+
+        let plan = InvocationPlan::resolve(&args).unwrap();
+        let contract = wasm.canonicalize().unwrap();
+        assert_eq!(
+            plan.args,
+            [
+                format!("--contract={}", contract.display()),
+                "--function=add".to_string(),
+                "--breakpoint=add".to_string(),
+                "--format=json".to_string(),
+            ]
+        );
+        assert_eq!(plan.contract, Some(contract));
+        assert!(plan.contract_drift().is_none());
+
+        fs::write(&wasm, b"\0asm\x01\0\0\0\x00").unwrap();
+        assert!(plan
+            .contract_drift()
+            .unwrap()
+            .contains("has changed since the preset was saved"));
+    }
+
+    #[test]
+    fn overrides_replace_single_values_and_extend_lists() {
+        let plan = InvocationPlan {
+            args: vec![
+                "--contract=/tmp/counter.wasm".to_string(),
+                "--function=add".to_string(),
+                "--args=[1]".to_string(),
+                "--breakpoint=add".to_string(),
+            ],
+            conversions: BTreeMap::new(),
+            contract: None,
+            contract_sha256: None,
+        };
+        let argv = plan.argv(&[
+            "--args".to_string(),
+            "[2]".to_string(),
+            "-b".to_string(),
+            "inc".to_string(),
+        ]);
+        let matches = Cli::command()
+            .mut_subcommand("run", |run| run.args_override_self(true))
+            .try_get_matches_from(argv)
+            .unwrap();
+        let Some(Commands::Run(args)) = Cli::from_arg_matches(&matches).unwrap().command else {
+            panic!("expected a run command");
+        };
+        assert_eq!(args.function.as_deref(), Some("add"));
+        assert_eq!(args.args.as_deref(), Some("[2]"));
+        assert_eq!(args.breakpoint, ["add", "inc"]);
+    }
+
+    #[test]
+    fn store_round_trips_and_lists_by_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = PresetStore::new(dir.path().join("presets"));
+        assert!(store.list().unwrap().is_empty());
+
+        let plan = InvocationPlan {
+            args: vec!["--function=add".to_string()],
+            conversions: BTreeMap::new(),
+            contract: None,
+            contract_sha256: None,
+        };
+        store.save("zeta", &plan).unwrap();
+        store.save("alpha", &plan).unwrap();
+        assert_eq!(store.load("zeta").unwrap().plan, plan);
+        let names: Vec<String> = store.list().unwrap().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["alpha", "zeta"]);
+
+        store.delete("alpha").unwrap();
+        assert!(store.load("alpha").is_err());
+        assert!(store.save("../escape", &plan).is_err());
+    }
+
+    #[test]
+    fn command_line_quotes_what_the_shell_would_split() {
+        let plan = InvocationPlan {
+            args: vec![
+                "--function=add".to_string(),
+                r#"--args=["a b"]"#.to_string(),
+            ],
+            conversions: BTreeMap::new(),
+            contract: None,
+            contract_sha256: None,
+        };
+        assert_eq!(
+            plan.command_line(),
+            r#"soroban-debug run --function=add '--args=["a b"]'"#
+        );
+    }
+}
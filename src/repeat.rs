@@ -1,7 +1,10 @@
 use crate::debugger::engine::DebuggerEngine;
 use crate::inspector::budget::{BudgetInfo, BudgetInspector};
+use crate::inspector::cost_table::{CostTable, CostTableBuilder};
+use crate::inspector::storage_heatmap::StorageHeatmap;
 use crate::logging;
-use crate::runtime::executor::ContractExecutor;
+use crate::runtime::executor::{ContractExecutor, LedgerOverrides};
+use crate::runtime::limits::BudgetLimits;
 use crate::Result;
 use std::time::{Duration, Instant};
 
@@ -12,6 +15,8 @@ pub struct RunStats {
     pub duration: Duration,
     pub budget: BudgetInfo,
     pub result: String,
+    /// Seed the host PRNG ran with.
+    pub prng_seed: u64,
 }
 
 /// Aggregate statistics computed over N runs.
@@ -24,10 +29,22 @@ pub struct AggregateStats {
     pub min_cpu: u64,
     pub max_cpu: u64,
     pub avg_cpu: u64,
+    /// Population standard deviation of the CPU instructions.
+    pub cpu_stddev: u64,
     pub min_memory: u64,
     pub max_memory: u64,
     pub avg_memory: u64,
+    pub memory_stddev: u64,
     pub inconsistent_results: bool,
+    /// Per-function instruction costs summed over every iteration.
+    pub cost_table: CostTable,
+    /// Storage accesses summed over every iteration.
+    pub storage_heatmap: StorageHeatmap,
+    /// Wall time spent loading the contract and seeding storage, over every
+    /// environment built.
+    pub setup_duration: Duration,
+    /// Whether each iteration had an environment of its own.
+    pub fresh_state: bool,
 }
 
 impl AggregateStats {
@@ -88,6 +105,8 @@ impl AggregateStats {
             }
         }
 
+        let cpu_stddev = stddev(runs.iter().map(|run| run.budget.cpu_instructions));
+        let memory_stddev = stddev(runs.iter().map(|run| run.budget.memory_bytes));
         AggregateStats {
             runs,
             min_duration: min_dur,
@@ -96,10 +115,16 @@ impl AggregateStats {
             min_cpu,
             max_cpu,
             avg_cpu: total_cpu / n,
+            cpu_stddev,
             min_memory: min_mem,
             max_memory: max_mem,
             avg_memory: total_mem / n,
+            memory_stddev,
             inconsistent_results: inconsistent,
+            cost_table: CostTable::default(),
+            storage_heatmap: StorageHeatmap::default(),
+            setup_duration: Duration::ZERO,
+            fresh_state: false,
         }
     }
 
@@ -114,7 +139,39 @@ impl AggregateStats {
                 Formatter::info(format!("--- Repeat Execution Summary ({} runs) ---", n))
             );
 
-            println!("{}", Formatter::info("Duration:"));
+            let first_seed = self.runs[0].prng_seed;
+            let last_seed = self.runs[n - 1].prng_seed;
+            if first_seed == last_seed {
+                println!("{}", Formatter::info(format!("PRNG seed: {}", first_seed)));
+            } else {
+                println!(
+                    "{}",
+                    Formatter::info(format!("PRNG seeds: {} to {}", first_seed, last_seed))
+                );
+            }
+
+            let setup_ms = self.setup_duration.as_secs_f64() * 1000.0;
+            if self.fresh_state {
+                println!(
+                    "{}",
+                    Formatter::info(format!(
+                        "Setup: {:.2} ms over {} environments (--fresh-state)",
+                        setup_ms, n
+                    ))
+                );
+            } else {
+                // Each further iteration would have paid the setup again.
+                println!(
+                    "{}",
+                    Formatter::info(format!(
+                        "Setup: {:.2} ms, once (about {:.2} ms saved over --fresh-state)",
+                        setup_ms,
+                        setup_ms * (n - 1) as f64
+                    ))
+                );
+            }
+
+            println!("{}", Formatter::info("Duration per iteration:"));
             println!(
                 "{}",
                 Formatter::info(format!(
@@ -137,15 +194,46 @@ impl AggregateStats {
                 ))
             );
 
+            let limits = &self.runs[0].budget;
+            let share = |used: u64, limit: u64| {
+                if limit == 0 {
+                    String::new()
+                } else {
+                    format!(" ({:.1}% of limit)", used as f64 / limit as f64 * 100.0)
+                }
+            };
+
             println!("{}", Formatter::info("CPU Instructions:"));
             println!("{}", Formatter::info(format!("  Min: {}", self.min_cpu)));
-            println!("{}", Formatter::info(format!("  Max: {}", self.max_cpu)));
+            println!(
+                "{}",
+                Formatter::info(format!(
+                    "  Max: {}{}",
+                    self.max_cpu,
+                    share(self.max_cpu, limits.cpu_limit)
+                ))
+            );
             println!("{}", Formatter::info(format!("  Avg: {}", self.avg_cpu)));
+            println!(
+                "{}",
+                Formatter::info(format!("  Std dev: {}", self.cpu_stddev))
+            );
 
             println!("{}", Formatter::info("Memory (bytes):"));
             println!("{}", Formatter::info(format!("  Min: {}", self.min_memory)));
-            println!("{}", Formatter::info(format!("  Max: {}", self.max_memory)));
+            println!(
+                "{}",
+                Formatter::info(format!(
+                    "  Max: {}{}",
+                    self.max_memory,
+                    share(self.max_memory, limits.memory_limit)
+                ))
+            );
             println!("{}", Formatter::info(format!("  Avg: {}", self.avg_memory)));
+            println!(
+                "{}",
+                Formatter::info(format!("  Std dev: {}", self.memory_stddev))
+            );
 
             if self.inconsistent_results {
                 println!(
@@ -153,12 +241,21 @@ impl AggregateStats {
                     Formatter::warning("WARNING: Inconsistent results detected across runs!")
                 );
                 let first = &self.runs[0].result;
-                println!("{}", Formatter::warning(format!("  Run 1: {}", first)));
+                println!(
+                    "{}",
+                    Formatter::warning(format!(
+                        "  Run 1 (seed {}): {}",
+                        self.runs[0].prng_seed, first
+                    ))
+                );
                 for run in &self.runs {
                     if run.result != *first {
                         println!(
                             "{}",
-                            Formatter::warning(format!("  Run {}: {}", run.iteration, run.result))
+                            Formatter::warning(format!(
+                                "  Run {} (seed {}): {}",
+                                run.iteration, run.prng_seed, run.result
+                            ))
                         );
                     }
                 }
@@ -174,18 +271,74 @@ impl AggregateStats {
             }
         }
 
-        // Log aggregate statistics with structured fields
+        self.log();
+    }
+
+    /// The summary [`Self::display`] prints, as one JSON object for
+    /// `--output json`.
+    pub fn to_json(&self) -> serde_json::Value {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let limits = &self.runs[0].budget;
+        let runs: Vec<_> = self
+            .runs
+            .iter()
+            .map(|run| {
+                serde_json::json!({
+                    "iteration": run.iteration,
+                    "prng_seed": run.prng_seed,
+                    "duration_ms": ms(run.duration),
+                    "cpu_instructions": run.budget.cpu_instructions,
+                    "memory_bytes": run.budget.memory_bytes,
+                    "result": run.result,
+                })
+            })
+            .collect();
+        serde_json::json!({
+            "runs": runs,
+            "setup_ms": ms(self.setup_duration),
+            "fresh_state": self.fresh_state,
+            "duration_ms": {
+                "min": ms(self.min_duration),
+                "max": ms(self.max_duration),
+                "avg": ms(self.avg_duration),
+            },
+            "cpu_instructions": {
+                "min": self.min_cpu,
+                "max": self.max_cpu,
+                "avg": self.avg_cpu,
+                "stddev": self.cpu_stddev,
+                "limit": limits.cpu_limit,
+            },
+            "memory_bytes": {
+                "min": self.min_memory,
+                "max": self.max_memory,
+                "avg": self.avg_memory,
+                "stddev": self.memory_stddev,
+                "limit": limits.memory_limit,
+            },
+            "inconsistent_results": self.inconsistent_results,
+        })
+    }
+
+    /// Log the aggregate statistics, and any run whose result differs from
+    /// the first, as structured tracing events.
+    pub fn log(&self) {
+        let n = self.runs.len();
         tracing::info!(
             runs = n,
             min_duration_ms = self.min_duration.as_secs_f64() * 1000.0,
             max_duration_ms = self.max_duration.as_secs_f64() * 1000.0,
             avg_duration_ms = self.avg_duration.as_secs_f64() * 1000.0,
+            setup_ms = self.setup_duration.as_secs_f64() * 1000.0,
+            fresh_state = self.fresh_state,
             min_cpu = self.min_cpu,
             max_cpu = self.max_cpu,
             avg_cpu = self.avg_cpu,
+            cpu_stddev = self.cpu_stddev,
             min_memory = self.min_memory,
             max_memory = self.max_memory,
             avg_memory = self.avg_memory,
+            memory_stddev = self.memory_stddev,
             inconsistent = self.inconsistent_results,
             "Repeat run summary"
         );
@@ -197,6 +350,7 @@ impl AggregateStats {
                 if run.result != *first {
                     tracing::warn!(
                         iteration = run.iteration,
+                        prng_seed = run.prng_seed,
                         result = %run.result,
                         "Inconsistent result detected"
                     );
@@ -206,6 +360,17 @@ impl AggregateStats {
     }
 }
 
+/// Population standard deviation of `values`, rounded.
+fn stddev(values: impl Iterator<Item = u64> + Clone) -> u64 {
+    let n = values.clone().count();
+    if n == 0 {
+        return 0;
+    }
+    let mean = values.clone().map(|v| v as f64).sum::<f64>() / n as f64;
+    let variance = values.map(|v| (v as f64 - mean).powi(2)).sum::<f64>() / n as f64;
+    variance.sqrt().round() as u64
+}
+
 /// Truncate a string to `max_len` characters, adding "…" if truncated.
 #[allow(dead_code)]
 fn truncate(s: &str, max_len: usize) -> String {
@@ -218,10 +383,20 @@ fn truncate(s: &str, max_len: usize) -> String {
 }
 
 /// Orchestrates repeated contract execution.
+///
+/// By default the contract is registered once and every iteration calls it on
+/// the same environment, with storage rolled back to where the first one
+/// started. [`Self::with_fresh_state`] builds a new environment per iteration
+/// instead.
 pub struct RepeatRunner {
     wasm_bytes: Vec<u8>,
     breakpoints: Vec<String>,
     initial_storage: Option<String>,
+    prng_seed: u64,
+    vary_seed: bool,
+    ledger: LedgerOverrides,
+    fresh_state: bool,
+    budget_limits: Option<BudgetLimits>,
 }
 
 impl RepeatRunner {
@@ -234,14 +409,66 @@ impl RepeatRunner {
             wasm_bytes,
             breakpoints,
             initial_storage,
+            prng_seed: 0,
+            vary_seed: false,
+            ledger: LedgerOverrides::default(),
+            fresh_state: false,
+            budget_limits: None,
         }
     }
 
+    /// Run every iteration at the ledger values `ledger` sets.
+    pub fn with_ledger(mut self, ledger: LedgerOverrides) -> Self {
+        self.ledger = ledger;
+        self
+    }
+
+    /// Seed every iteration's PRNG with `seed`, or iteration i with `seed + i`
+    /// (from 0) when `vary` is set.
+    pub fn with_prng_seed(mut self, seed: u64, vary: bool) -> Self {
+        self.prng_seed = seed;
+        self.vary_seed = vary;
+        self
+    }
+
+    /// Load the contract into a new environment for every iteration, so
+    /// nothing one iteration leaves in the host reaches the next.
+    pub fn with_fresh_state(mut self, fresh: bool) -> Self {
+        self.fresh_state = fresh;
+        self
+    }
+
+    /// Run every iteration under `limits` rather than the host's default
+    /// budget.
+    pub fn with_budget_limits(mut self, limits: Option<BudgetLimits>) -> Self {
+        self.budget_limits = limits;
+        self
+    }
+
+    /// An engine on a newly loaded contract with the initial storage seeded.
+    fn engine(&self) -> Result<DebuggerEngine> {
+        let mut executor = ContractExecutor::with_ledger(self.wasm_bytes.clone(), &self.ledger)?;
+        if let Some(ref storage) = self.initial_storage {
+            executor.set_initial_storage(storage.clone())?;
+        }
+        if let Some(limits) = self.budget_limits {
+            executor.set_budget_limits(limits);
+        }
+        Ok(DebuggerEngine::new(executor, self.breakpoints.clone()))
+    }
+
     /// Run the contract function `n` times and return aggregate stats.
     pub fn run(&self, function: &str, args: Option<&str>, n: u32) -> Result<AggregateStats> {
         logging::log_repeat_execution(function, n as usize);
 
         let mut all_runs = Vec::with_capacity(n as usize);
+        let mut costs = CostTableBuilder::new();
+        let mut heatmap = StorageHeatmap::new();
+
+        let setup_start = Instant::now();
+        let mut engine = self.engine()?;
+        let initial = engine.executor().snapshot_storage()?;
+        let mut setup_duration = setup_start.elapsed();
 
         for i in 1..=n {
             tracing::debug!(
@@ -250,20 +477,38 @@ impl RepeatRunner {
                 "Starting repeat execution iteration"
             );
 
-            // Fresh executor and engine per run for isolation
-            let mut executor = ContractExecutor::new(self.wasm_bytes.clone())?;
-
-            if let Some(ref storage) = self.initial_storage {
-                executor.set_initial_storage(storage.clone())?;
+            if i > 1 {
+                if self.fresh_state {
+                    let setup_start = Instant::now();
+                    engine = self.engine()?;
+                    setup_duration += setup_start.elapsed();
+                } else {
+                    engine.executor_mut().restore_storage(&initial)?;
+                }
             }
-
-            let mut engine = DebuggerEngine::new(executor, self.breakpoints.clone());
+            let prng_seed = if self.vary_seed {
+                self.prng_seed.wrapping_add(u64::from(i - 1))
+            } else {
+                self.prng_seed
+            };
+            let executor = engine.executor_mut();
+            executor.set_prng_seed(prng_seed)?;
+            // Each iteration's figures count only its own call, whether or not
+            // it shares the environment.
+            executor.reset_budget();
+            let costs_before = executor.call_costs().len();
 
             let start = Instant::now();
-            let result = engine.execute(function, args)?;
+            let result = engine.execute(function, args).inspect_err(|_| {
+                BudgetInspector::report_failure(engine.executor().budget_usage())
+            })?;
             let duration = start.elapsed();
 
             let budget = BudgetInspector::get_cpu_usage(engine.executor().host());
+            costs.record_all(&engine.executor().call_costs()[costs_before..]);
+            if self.fresh_state {
+                heatmap.merge(engine.executor().storage_heatmap());
+            }
 
             tracing::debug!(
                 iteration = i,
@@ -278,10 +523,19 @@ impl RepeatRunner {
                 duration,
                 budget,
                 result,
+                prng_seed,
             });
         }
+        if !self.fresh_state {
+            // One executor counted every iteration's accesses.
+            heatmap.merge(engine.executor().storage_heatmap());
+        }
 
-        let stats = AggregateStats::from_runs(all_runs);
+        let mut stats = AggregateStats::from_runs(all_runs);
+        stats.cost_table = costs.build();
+        stats.storage_heatmap = heatmap;
+        stats.setup_duration = setup_duration;
+        stats.fresh_state = self.fresh_state;
         Ok(stats)
     }
 }
@@ -305,6 +559,7 @@ mod tests {
             duration: Duration::from_millis(duration_ms),
             budget: make_budget(cpu, mem),
             result: result.to_string(),
+            prng_seed: 0,
         }
     }
 
@@ -343,6 +598,8 @@ mod tests {
         assert_eq!(stats.min_memory, 1000);
         assert_eq!(stats.max_memory, 3000);
         assert_eq!(stats.avg_memory, 2000);
+        assert_eq!(stats.cpu_stddev, 1225);
+        assert_eq!(stats.memory_stddev, 816);
         assert!(!stats.inconsistent_results);
     }
 
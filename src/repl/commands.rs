@@ -4,12 +4,17 @@
 use crate::Result;
 
 /// Represents a REPL command
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ReplCommand {
     /// Call a contract function: call <function> [args...]
-    Call { function: String, args: Vec<String> },
+    Call {
+        function: String,
+        args: Vec<String>,
+    },
     /// Inspect storage: storage
     Storage,
+    /// Inspect storage with the last writer of each key: storage writer
+    StorageWriters,
     /// Show command history: history
     History,
     /// Clear screen: clear
@@ -26,8 +31,31 @@ pub enum ReplCommand {
     /// List breakpoints: list-breaks
     ListBreaks,
     /// Clear a breakpoint: clear-break <function>
-    ClearBreak { function: String },
+    ClearBreak {
+        function: String,
+    },
     Functions,
+    /// Reset part of the environment: reset <storage|events|all>
+    Reset(ResetTarget),
+    /// Snapshot the environment: checkpoint <name>
+    Checkpoint {
+        name: String,
+    },
+    /// Restore a snapshot taken with `checkpoint`: rollback <name>
+    Rollback {
+        name: String,
+    },
+}
+
+/// What `reset` puts back to its initial state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetTarget {
+    /// Storage as it was when the session started
+    Storage,
+    /// The event log
+    Events,
+    /// Everything: the contract is registered again from the loaded WASM
+    All,
 }
 
 impl ReplCommand {
@@ -45,6 +73,9 @@ impl ReplCommand {
             "list-breaks",
             "clear-break",
             "functions",
+            "reset",
+            "checkpoint",
+            "rollback",
         ]
     }
 
@@ -89,7 +120,30 @@ impl ReplCommand {
                 let function = parts[1].to_string();
                 Ok(ReplCommand::ClearBreak { function })
             }
-            "storage" => Ok(ReplCommand::Storage),
+            "reset" => match parts.get(1).copied() {
+                Some("storage") => Ok(ReplCommand::Reset(ResetTarget::Storage)),
+                Some("events") => Ok(ReplCommand::Reset(ResetTarget::Events)),
+                Some("all") => Ok(ReplCommand::Reset(ResetTarget::All)),
+                _ => Err(miette::miette!(
+                    "reset requires one of: storage, events, all"
+                )),
+            },
+            "checkpoint" => match parts.get(1) {
+                Some(name) => Ok(ReplCommand::Checkpoint {
+                    name: name.to_string(),
+                }),
+                None => Err(miette::miette!("checkpoint requires a name")),
+            },
+            "rollback" => match parts.get(1) {
+                Some(name) => Ok(ReplCommand::Rollback {
+                    name: name.to_string(),
+                }),
+                None => Err(miette::miette!("rollback requires a checkpoint name")),
+            },
+            "storage" => match parts.get(1).copied() {
+                Some("writer") => Ok(ReplCommand::StorageWriters),
+                _ => Ok(ReplCommand::Storage),
+            },
             "history" => Ok(ReplCommand::History),
             "functions" => Ok(ReplCommand::Functions),
             "clear" => Ok(ReplCommand::Clear),
@@ -123,6 +177,9 @@ mod tests {
     fn test_parse_storage_command() {
         let cmd = ReplCommand::parse("storage").unwrap();
         assert!(matches!(cmd, ReplCommand::Storage));
+
+        let cmd = ReplCommand::parse("storage writer").unwrap();
+        assert!(matches!(cmd, ReplCommand::StorageWriters));
     }
 
     #[test]
@@ -152,6 +209,30 @@ mod tests {
         assert!(matches!(cmd, ReplCommand::Functions));
     }
 
+    #[test]
+    fn test_parse_reset_and_checkpoint_commands() {
+        assert!(matches!(
+            ReplCommand::parse("reset storage").unwrap(),
+            ReplCommand::Reset(ResetTarget::Storage)
+        ));
+        assert!(matches!(
+            ReplCommand::parse("reset all").unwrap(),
+            ReplCommand::Reset(ResetTarget::All)
+        ));
+        assert!(ReplCommand::parse("reset").is_err());
+        assert!(ReplCommand::parse("reset ledger").is_err());
+
+        match ReplCommand::parse("checkpoint before_mint").unwrap() {
+            ReplCommand::Checkpoint { name } => assert_eq!(name, "before_mint"),
+            _ => panic!("Expected Checkpoint command"),
+        }
+        match ReplCommand::parse("rollback before_mint").unwrap() {
+            ReplCommand::Rollback { name } => assert_eq!(name, "before_mint"),
+            _ => panic!("Expected Rollback command"),
+        }
+        assert!(ReplCommand::parse("rollback").is_err());
+    }
+
     #[test]
     fn test_unknown_command_fails() {
         let result = ReplCommand::parse("unknown");
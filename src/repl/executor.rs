@@ -2,6 +2,7 @@
 ///
 /// Handles execution of function calls and storage inspection
 /// against the loaded contract.
+use super::commands::ResetTarget;
 use super::ReplConfig;
 use crate::inspector::StorageInspector;
 use crate::runtime::executor::ContractExecutor;
@@ -9,6 +10,7 @@ use crate::utils::wasm::{parse_function_signatures, ContractFunctionSignature};
 use crate::Result;
 use serde_json::json;
 use serde_json::Value;
+use soroban_sdk::testutils::Snapshot;
 use std::collections::HashMap;
 use std::fs;
 
@@ -17,6 +19,12 @@ pub struct ReplExecutor {
     engine: crate::debugger::engine::DebuggerEngine,
     signatures: HashMap<String, ContractFunctionSignature>,
     address_aliases: HashMap<String, String>,
+    config: ReplConfig,
+    wasm_bytes: Vec<u8>,
+    /// Environment as it was after setup, restored by `reset storage`.
+    pristine: Snapshot,
+    /// Named checkpoints, oldest first.
+    checkpoints: Vec<(String, Snapshot)>,
 }
 
 impl ReplExecutor {
@@ -32,7 +40,32 @@ impl ReplExecutor {
             .into_iter()
             .map(|sig| (sig.name.clone(), sig))
             .collect();
-        let executor = ContractExecutor::new(wasm_bytes)?;
+        let engine = Self::build_engine(&wasm_bytes, config)?;
+        let pristine = engine.executor().env_snapshot();
+
+        Ok(ReplExecutor {
+            engine,
+            signatures,
+            address_aliases: HashMap::new(),
+            config: config.clone(),
+            wasm_bytes,
+            pristine,
+            checkpoints: Vec::new(),
+        })
+    }
+
+    /// Register the contract in a fresh environment and apply the session setup.
+    fn build_engine(
+        wasm_bytes: &[u8],
+        config: &ReplConfig,
+    ) -> Result<crate::debugger::engine::DebuggerEngine> {
+        let mut executor = ContractExecutor::new(wasm_bytes.to_vec())?;
+        if let Some(state) = &config.initial_state {
+            executor.restore_env_snapshot(state)?;
+        }
+        if !config.mocks.is_empty() {
+            executor.set_mock_specs(&config.mocks)?;
+        }
         let mut engine = crate::debugger::engine::DebuggerEngine::new(executor, Vec::new());
         engine.executor_mut().enable_mock_all_auths();
 
@@ -52,11 +85,87 @@ impl ReplExecutor {
                 .set_initial_storage(storage_json.clone())?;
         }
 
-        Ok(ReplExecutor {
-            engine,
-            signatures,
-            address_aliases: HashMap::new(),
-        })
+        Ok(engine)
+    }
+
+    /// Put storage, the event log or the whole environment back to its initial state.
+    pub fn reset(&mut self, target: ResetTarget) -> Result<()> {
+        match target {
+            ResetTarget::Storage => {
+                // Keep the ledger clock and address generators so existing
+                // aliases never collide with newly generated ones.
+                let current = self.engine.executor().env_snapshot();
+                let mut snapshot = self.pristine.clone();
+                snapshot
+                    .ledger
+                    .set_ledger_info(current.ledger.ledger_info());
+                snapshot.generators = current.generators;
+                self.restore(&snapshot)
+            }
+            ResetTarget::Events => {
+                // A rebuilt environment starts with an empty event log.
+                let current = self.engine.executor().env_snapshot();
+                self.restore(&current)
+            }
+            ResetTarget::All => {
+                let breakpoints = self.list_breakpoints();
+                self.engine = Self::build_engine(&self.wasm_bytes, &self.config)?;
+                for breakpoint in breakpoints {
+                    self.engine.breakpoints_mut().set(breakpoint);
+                }
+                self.pristine = self.engine.executor().env_snapshot();
+                self.address_aliases.clear();
+                self.checkpoints.clear();
+                Ok(())
+            }
+        }
+    }
+
+    /// Snapshot the full environment under `name`, replacing an older checkpoint of that name.
+    pub fn checkpoint(&mut self, name: &str) {
+        let snapshot = self.engine.executor().env_snapshot();
+        self.checkpoints.retain(|(existing, _)| existing != name);
+        self.checkpoints.push((name.to_string(), snapshot));
+    }
+
+    /// Restore the checkpoint `name`.
+    ///
+    /// Like a savepoint, the checkpoint itself is kept and any taken after it
+    /// are discarded.
+    pub fn rollback(&mut self, name: &str) -> Result<()> {
+        let index = self
+            .checkpoints
+            .iter()
+            .position(|(existing, _)| existing == name)
+            .ok_or_else(|| miette::miette!("No checkpoint named '{}'", name))?;
+        let snapshot = self.checkpoints[index].1.clone();
+        self.restore(&snapshot)?;
+        self.checkpoints.truncate(index + 1);
+        Ok(())
+    }
+
+    /// Names of the checkpoints that can still be rolled back to, oldest first.
+    pub fn checkpoint_names(&self) -> Vec<&str> {
+        self.checkpoints
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    }
+
+    fn restore(&mut self, snapshot: &Snapshot) -> Result<()> {
+        self.engine.executor_mut().restore_env_snapshot(snapshot)?;
+        self.engine.executor_mut().enable_mock_all_auths();
+        Ok(())
+    }
+
+    /// The whole environment as it is now.
+    pub fn env_snapshot(&self) -> Snapshot {
+        self.engine.executor().env_snapshot()
+    }
+
+    /// Current contract storage, keyed by rendered key.
+    pub fn storage_snapshot(&self) -> Result<HashMap<String, String>> {
+        self.engine.executor().get_storage_snapshot()
     }
 
     /// Call a contract function
@@ -69,7 +178,7 @@ impl ReplExecutor {
         };
 
         // Check if we should break before starting
-        if self.engine.breakpoints().should_break(function) {
+        if self.engine.breakpoint_hit(function, args_ref) {
             self.engine.prepare_breakpoint_stop(function, args_ref);
             crate::logging::log_display(
                 format!("Execution paused at function: {}", function),
@@ -79,7 +188,9 @@ impl ReplExecutor {
         }
 
         let storage_before = self.engine.executor().get_storage_snapshot()?;
-        let result = self.engine.execute(function, args_ref)?;
+        let result = self
+            .engine
+            .execute_without_breakpoints(function, args_ref)?;
         let storage_after = self.engine.executor().get_storage_snapshot()?;
 
         crate::logging::log_display(
@@ -87,7 +198,8 @@ impl ReplExecutor {
             crate::logging::LogLevel::Info,
         );
 
-        let diff = StorageInspector::compute_diff(&storage_before, &storage_after, &[]);
+        let diff = StorageInspector::compute_diff(&storage_before, &storage_after, &[])
+            .with_writers(self.engine.executor().storage_provenance());
         if diff.is_empty() {
             crate::logging::log_display("Storage: (no changes)", crate::logging::LogLevel::Info);
         } else {
@@ -97,6 +209,24 @@ impl ReplExecutor {
         Ok(())
     }
 
+    /// Call a contract function with REPL-style arguments and return the
+    /// rendered result without printing it. Breakpoints are not consulted.
+    pub fn invoke(&mut self, function: &str, args: &[String]) -> Result<String> {
+        let args_json = self.args_to_json_array_for(function, args)?;
+        let args_ref = (args_json != "[]").then_some(args_json.as_str());
+        self.engine.execute_without_breakpoints(function, args_ref)
+    }
+
+    /// Signatures of the exported functions, in no particular order.
+    pub fn signatures(&self) -> Vec<ContractFunctionSignature> {
+        self.signatures.values().cloned().collect()
+    }
+
+    /// The engine driving the session, for reading events and the call trace.
+    pub fn engine(&self) -> &crate::debugger::engine::DebuggerEngine {
+        &self.engine
+    }
+
     /// Return known exported function names for REPL completion.
     pub fn function_names(&self) -> Vec<String> {
         let mut names: Vec<String> = self.signatures.keys().cloned().collect();
@@ -168,8 +298,10 @@ impl ReplExecutor {
     }
 
     /// Inspect and display contract storage
-    pub fn inspect_storage(&self) -> Result<()> {
-        let entries = self.engine.executor().get_storage_snapshot()?;
+    /// Print the contract storage, with the last writer of each key when
+    /// `show_writers` is set.
+    pub fn inspect_storage(&self, show_writers: bool) -> Result<()> {
+        let entries = self.storage_snapshot()?;
 
         if entries.is_empty() {
             crate::logging::log_display("Storage is empty", crate::logging::LogLevel::Warn);
@@ -181,13 +313,16 @@ impl ReplExecutor {
         crate::logging::log_display("", crate::logging::LogLevel::Info);
 
         let mut items: Vec<_> = entries.iter().collect();
-        items.sort_by(|(ka, _), (kb, _)| ka.cmp(kb));
+        items.sort_by_key(|(k, _)| *k);
 
+        let provenance = self.engine.executor().storage_provenance();
         for (key, value) in items {
-            crate::logging::log_display(
-                format!("  {}: {}", key, value),
-                crate::logging::LogLevel::Info,
-            );
+            let line = match provenance.get(key).filter(|_| show_writers) {
+                Some(provenance) => format!("  {}: {}  [{}]", key, value, provenance.writer),
+                None if show_writers => format!("  {}: {}  [-]", key, value),
+                None => format!("  {}: {}", key, value),
+            };
+            crate::logging::log_display(line, crate::logging::LogLevel::Info);
         }
         crate::logging::log_display("", crate::logging::LogLevel::Info);
 
@@ -195,10 +330,11 @@ impl ReplExecutor {
     }
     pub fn add_breakpoint(&mut self, function: &str, condition: Option<&str>) -> Result<()> {
         if let Some(condition) = condition {
+            let condition = crate::debugger::BreakpointManager::parse_condition(condition)?;
             self.engine.breakpoints_mut().set(
                 crate::debugger::breakpoint::Breakpoint::with_condition(
                     function.to_string(),
-                    condition.to_string(),
+                    condition,
                 ),
             );
         } else {
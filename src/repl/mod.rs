@@ -5,12 +5,14 @@
 /// exploring contract state without restarting.
 pub mod commands;
 pub mod executor;
+pub mod script;
 pub mod session;
 
 pub use session::ReplSession;
 
 use crate::Result;
-use std::path::PathBuf;
+use soroban_sdk::testutils::Snapshot;
+use std::path::{Path, PathBuf};
 
 /// Configuration for starting the REPL
 #[derive(Debug, Clone)]
@@ -18,11 +20,31 @@ pub struct ReplConfig {
     pub contract_path: PathBuf,
     pub network_snapshot: Option<PathBuf>,
     pub storage: Option<String>,
+    /// Environment to start from instead of a fresh one, e.g. the state
+    /// committed to a named environment.
+    pub initial_state: Option<Snapshot>,
+    /// `--mock` specs applied to the session.
+    pub mocks: Vec<String>,
 }
 
 /// Start the REPL interactive session
 pub async fn start_repl(config: ReplConfig) -> Result<()> {
+    run_repl(config).await.map(|_| ())
+}
+
+/// Run a REPL session and return the environment as it was when it ended.
+pub async fn run_repl(config: ReplConfig) -> Result<Snapshot> {
     let mut session = ReplSession::new(config)?;
     session.run().await?;
-    Ok(())
+    Ok(session.env_snapshot())
+}
+
+/// Run the commands and assertions of the script at `path` instead of
+/// reading from the terminal, and return the environment as it was when the
+/// script ended.
+pub async fn run_script(config: ReplConfig, path: &Path) -> Result<Snapshot> {
+    let lines = script::load_script(path)?;
+    let mut session = ReplSession::new(config)?;
+    session.run_script(path, &lines).await?;
+    Ok(session.env_snapshot())
 }
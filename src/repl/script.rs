@@ -0,0 +1,106 @@
+/// REPL command files for scripted, non-interactive sessions
+///
+/// A script holds one REPL command per line plus `assert <condition>` lines,
+/// checked with [`AssertionContext`](crate::debugger::assertion::AssertionContext)
+/// against the last call. Blank lines and lines starting with `#` are skipped.
+use super::commands::ReplCommand;
+use crate::{DebuggerError, Result};
+use std::path::Path;
+
+/// One executable line of a script
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptStep {
+    Command(ReplCommand),
+    Assert(String),
+}
+
+/// A script step with the 1-based line it came from and its source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptLine {
+    pub number: usize,
+    pub text: String,
+    pub step: ScriptStep,
+}
+
+/// Parse a whole script, so a typo on line 40 is reported before line 1 runs.
+pub fn parse_script(source: &str) -> Result<Vec<ScriptLine>> {
+    let mut lines = Vec::new();
+    for (index, raw) in source.lines().enumerate() {
+        let number = index + 1;
+        let text = raw.trim();
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+        let step = match text.strip_prefix("assert") {
+            Some(condition)
+                if condition.is_empty() || condition.starts_with(char::is_whitespace) =>
+            {
+                let condition = condition.trim();
+                if condition.is_empty() {
+                    return Err(script_error(number, "assert requires a condition"));
+                }
+                ScriptStep::Assert(condition.to_string())
+            }
+            _ => {
+                ScriptStep::Command(ReplCommand::parse(text).map_err(|e| script_error(number, e))?)
+            }
+        };
+        lines.push(ScriptLine {
+            number,
+            text: text.to_string(),
+            step,
+        });
+    }
+    Ok(lines)
+}
+
+/// Read and parse the script at `path`.
+pub fn load_script(path: &Path) -> Result<Vec<ScriptLine>> {
+    let source = std::fs::read_to_string(path).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to read REPL script {:?}: {}", path, e))
+    })?;
+    parse_script(&source)
+}
+
+fn script_error(line: usize, message: impl std::fmt::Display) -> miette::Report {
+    DebuggerError::InvalidArguments(format!("line {}: {}", line, message)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands_and_asserts_with_line_numbers() {
+        let script = "# setup\ncall increment\n\n  assert result == 1\nassertions\n";
+        let err = parse_script(script).unwrap_err().to_string();
+        assert!(
+            err.contains("line 5: Unknown command: 'assertions'"),
+            "{}",
+            err
+        );
+
+        let lines = parse_script("# setup\ncall increment\n\n  assert result == 1\n").unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].number, 2);
+        assert_eq!(
+            lines[0].step,
+            ScriptStep::Command(ReplCommand::Call {
+                function: "increment".to_string(),
+                args: Vec::new(),
+            })
+        );
+        assert_eq!(lines[1].number, 4);
+        assert_eq!(lines[1].step, ScriptStep::Assert("result == 1".to_string()));
+    }
+
+    #[test]
+    fn assert_without_condition_is_rejected() {
+        let err = parse_script("call get\nassert\n").unwrap_err().to_string();
+        assert!(
+            err.contains("line 2: assert requires a condition"),
+            "{}",
+            err
+        );
+    }
+}
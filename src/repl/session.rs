@@ -2,11 +2,13 @@
 ///
 /// Handles user input, command history, and persistent state across
 /// multiple function calls within a single REPL session.
-use super::commands::ReplCommand;
+use super::commands::{ReplCommand, ResetTarget};
 use super::executor::ReplExecutor;
+use super::script::{ScriptLine, ScriptStep};
 use super::ReplConfig;
+use crate::debugger::assertion::AssertionContext;
 use crate::ui::formatter::Formatter;
-use crate::Result;
+use crate::{DebuggerError, Result};
 use rustyline::completion::{Completer, Pair};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::Highlighter;
@@ -144,17 +146,29 @@ impl ReplSession {
         })
     }
 
+    /// The session's environment as it is now.
+    pub fn env_snapshot(&self) -> soroban_sdk::testutils::Snapshot {
+        self.executor.env_snapshot()
+    }
+
     /// Run the REPL event loop
     pub async fn run(&mut self) -> Result<()> {
         self.print_welcome();
 
         loop {
+            let checkpoints = self.executor.checkpoint_names();
+            let checkpoint_note = if checkpoints.is_empty() {
+                String::new()
+            } else {
+                format!(" (checkpoints: {})", checkpoints.join(", "))
+            };
             let prompt = format!(
                 "{}> ",
                 Formatter::info(
                     format!(
-                        "soroban-debug repl [{}]",
-                        self.config.contract_path.display()
+                        "soroban-debug repl [{}]{}",
+                        self.config.contract_path.display(),
+                        checkpoint_note
                     )
                     .as_str()
                 )
@@ -200,6 +214,50 @@ impl ReplSession {
         Ok(())
     }
 
+    /// Run `lines` top to bottom, echoing each as `+ <line>` first. Stops at
+    /// the first command that fails or assertion that does not hold, printing
+    /// why with its line in `script`.
+    pub async fn run_script(
+        &mut self,
+        script: &std::path::Path,
+        lines: &[ScriptLine],
+    ) -> Result<()> {
+        let at = |line: &ScriptLine| format!("{}:{}", script.display(), line.number);
+        for line in lines {
+            eprintln!("+ {}", line.text);
+            let failure = match &line.step {
+                ScriptStep::Command(_) => match self.execute_command(&line.text).await {
+                    Ok(true) => break,
+                    Ok(false) => None,
+                    Err(e) => Some(format!("{}: `{}` failed: {}", at(line), line.text, e)),
+                },
+                ScriptStep::Assert(condition) => {
+                    let context = AssertionContext::for_executor(self.executor.engine().executor());
+                    match context.check(condition) {
+                        Ok(true) => None,
+                        Ok(false) => Some(format!(
+                            "{}: assertion failed: {}{}",
+                            at(line),
+                            condition,
+                            context.failure_detail(condition)
+                        )),
+                        Err(e) => Some(format!(
+                            "{}: cannot evaluate assertion `{}`: {}",
+                            at(line),
+                            condition,
+                            e
+                        )),
+                    }
+                }
+            };
+            if let Some(failure) = failure {
+                eprintln!("{}", Formatter::error(failure));
+                return Err(DebuggerError::ExecutionError("REPL script failed".into()).into());
+            }
+        }
+        Ok(())
+    }
+
     /// Execute a single command
     async fn execute_command(&mut self, line: &str) -> Result<bool> {
         let cmd = ReplCommand::parse(line)?;
@@ -215,7 +273,11 @@ impl ReplSession {
                 Ok(false)
             }
             ReplCommand::Storage => {
-                self.executor.inspect_storage()?;
+                self.executor.inspect_storage(false)?;
+                Ok(false)
+            }
+            ReplCommand::StorageWriters => {
+                self.executor.inspect_storage(true)?;
                 Ok(false)
             }
             ReplCommand::Call { function, args } => {
@@ -273,6 +335,32 @@ impl ReplSession {
                 self.executor.display_functions()?;
                 Ok(false)
             }
+            ReplCommand::Reset(target) => {
+                self.executor.reset(target)?;
+                let message = match target {
+                    ResetTarget::Storage => "Storage reset to its initial state",
+                    ResetTarget::Events => "Event log cleared",
+                    ResetTarget::All => "Contract re-registered in a fresh environment",
+                };
+                tracing::info!("{}", Formatter::success(message));
+                Ok(false)
+            }
+            ReplCommand::Checkpoint { name } => {
+                self.executor.checkpoint(&name);
+                tracing::info!(
+                    "{}",
+                    Formatter::success(format!("Checkpoint saved: {}", name).as_str())
+                );
+                Ok(false)
+            }
+            ReplCommand::Rollback { name } => {
+                self.executor.rollback(&name)?;
+                tracing::info!(
+                    "{}",
+                    Formatter::success(format!("Rolled back to checkpoint: {}", name).as_str())
+                );
+                Ok(false)
+            }
         }
     }
 
@@ -297,6 +385,10 @@ impl ReplSession {
             "  {}                 Show contract storage state",
             Formatter::info("storage")
         );
+        tracing::info!(
+            "  {}          Show storage with the step that last wrote each key",
+            Formatter::info("storage writer")
+        );
         tracing::info!(
             "  {}                 Show command history",
             Formatter::info("history")
@@ -325,6 +417,18 @@ impl ReplSession {
             "  {}                 Show available contract functions",
             Formatter::info("functions")
         );
+        tracing::info!(
+            "  {} <storage|events|all> Reset storage, the event log, or everything",
+            Formatter::info("reset")
+        );
+        tracing::info!(
+            "  {} <name>          Snapshot the current environment",
+            Formatter::info("checkpoint")
+        );
+        tracing::info!(
+            "  {} <name>            Restore a checkpoint",
+            Formatter::info("rollback")
+        );
         tracing::info!(
             "  {}                     Exit the REPL",
             Formatter::info("exit")
@@ -0,0 +1,344 @@
+//! Opt-in cache of `run` results (`run --cache-results`).
+//!
+//! Re-running an expensive invocation only to look at its output again is
+//! wasted time when nothing that feeds the execution has changed. A cached
+//! result is the [`ExecutionReport`] of an earlier run, stored under a hash
+//! of the resolved [`InvocationPlan`]: the contract's SHA-256, every flag
+//! that affects execution, and the contents of any file a flag names (such
+//! as `--network-snapshot`). Flags that only change how a run is displayed
+//! are left out of the key. Entries live in `<cache dir>/results/<key>.json`
+//! and are removed by `soroban-debug cache clear`.
+
+use super::ExecutionReport;
+use crate::cli::args::{RunArgs, ShowItem};
+use crate::presets::{flag_name, InvocationPlan};
+use crate::utils::artifact_cache::{clear_dir, dir_stats, ArtifactCache, CacheStats, NO_CACHE_ENV};
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Bumped whenever [`CachedResult`] or the key derivation changes.
+pub const RESULT_CACHE_VERSION: u32 = 1;
+
+/// `run` flags that only affect presentation, so do not invalidate a result.
+const PRESENTATION_FLAGS: &[&str] = &[
+    "contract",
+    "cache-results",
+    "no-cache",
+    "verbose",
+    "format",
+    "output",
+    "json",
+    "show",
+    "show-events",
+    "show-auth",
+    "filter-topic",
+    "event-filter",
+    "storage-filter",
+    "validate-events",
+    "event-schema",
+    "report-html",
+    "expect",
+    "expect-mode",
+    "max-warnings",
+    "allow-warning",
+    "strict-conventions",
+    "save-preset",
+    "append",
+];
+
+/// A stored result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedResult {
+    pub version: u32,
+    pub key: String,
+    pub stored_at: String,
+    pub report: ExecutionReport,
+}
+
+/// Why `args` has to execute even though `--cache-results` was given, if
+/// it does: its output is not part of a report, it has side effects, or the
+/// contract can return something different for the same inputs.
+pub fn bypass_reason(args: &RunArgs, wasm_bytes: &[u8]) -> Result<Option<String>> {
+    let shown = args.shown();
+    let live_only = [
+        (args.env.is_some(), "--env"),
+        (args.batch_args.is_some(), "--batch-args"),
+        (args.repeat.is_some(), "--repeat"),
+        (args.dry_run, "--dry-run"),
+        (args.instruction_debug, "--instruction-debug"),
+        (args.export_storage.is_some(), "--export-storage"),
+        (args.save_state.is_some(), "--save-state"),
+        (args.load_state.is_some(), "--load-state"),
+        (args.export_auth.is_some(), "--export-auth"),
+        (args.trace_output.is_some(), "--trace-output"),
+        (args.generate_test.is_some(), "--generate-test"),
+        (args.events_ndjson.is_some(), "--events-ndjson"),
+        (!args.link.is_empty(), "--link"),
+        (!args.report_host_fn.is_empty(), "--report-host-fn"),
+        (args.constructor_args.is_some(), "--constructor-args"),
+        (
+            args.limits
+                .as_deref()
+                .is_some_and(crate::runtime::limits::BudgetLimits::is_rpc_url),
+            "--limits <RPC URL>",
+        ),
+        (args.source_account.is_some(), "--source-account"),
+        (args.save_output.is_some(), "--save-output"),
+        (!args.extract.is_empty(), "--extract"),
+        (args.cost_table, "--cost-table"),
+        (args.storage_heatmap.is_some(), "--storage-heatmap"),
+        (shown.contains(&ShowItem::Ledger), "--show ledger"),
+        (shown.contains(&ShowItem::HostCalls), "--show host-calls"),
+        (shown.contains(&ShowItem::Logs), "--show logs"),
+        (shown.contains(&ShowItem::Diagnostics), "--show diagnostics"),
+    ];
+    if let Some((_, flag)) = live_only.iter().find(|(given, _)| *given) {
+        return Ok(Some(format!("{} needs a live run", flag)));
+    }
+    let mut signals = crate::utils::wasm::nondeterminism_signals(wasm_bytes)?;
+    // An explicit seed is part of the key, so the PRNG replays exactly.
+    if args.seed.is_some() {
+        signals.retain(|signal| !signal.starts_with("PRNG"));
+    }
+    if !signals.is_empty() {
+        return Ok(Some(format!(
+            "the contract may not be deterministic; it uses {}",
+            signals.join(", ")
+        )));
+    }
+    Ok(None)
+}
+
+/// The cache key for `plan`: a SHA-256 over everything that feeds the
+/// execution.
+pub fn result_key(plan: &InvocationPlan) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(RESULT_CACHE_VERSION.to_le_bytes());
+    hasher.update(env!("CARGO_PKG_VERSION"));
+    hasher.update([0]);
+    hasher.update(plan.contract_sha256.as_deref().unwrap_or_default());
+    hasher.update([0]);
+    let mut flags: Vec<&String> = plan
+        .args
+        .iter()
+        .filter(|token| !PRESENTATION_FLAGS.contains(&flag_name(token)))
+        .collect();
+    // Repeatable flags keep their relative order; the order of different
+    // flags on the command line does not matter.
+    flags.sort_by(|a, b| flag_name(a).cmp(flag_name(b)));
+    for token in flags {
+        hasher.update(token);
+        hasher.update([0]);
+        if let Some((_, value)) = token.split_once('=') {
+            let path = Path::new(value);
+            if path.is_file() {
+                let contents = fs::read(path).map_err(|e| {
+                    DebuggerError::FileError(format!("Failed to read {:?}: {}", path, e))
+                })?;
+                hasher.update(Sha256::digest(contents));
+            }
+        }
+    }
+    let conversions = serde_json::to_string(&plan.conversions)
+        .map_err(|e| DebuggerError::FileError(format!("Failed to serialize conversions: {}", e)))?;
+    hasher.update(conversions);
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// A directory of [`CachedResult`]s.
+#[derive(Debug, Clone)]
+pub struct ResultCache {
+    dir: PathBuf,
+}
+
+impl ResultCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `results/` under [`ArtifactCache::default_dir`].
+    pub fn default_dir() -> Option<PathBuf> {
+        ArtifactCache::default_dir().map(|dir| dir.join("results"))
+    }
+
+    /// The cache at [`ResultCache::default_dir`], unless disabled via `SOROBAN_DEBUG_NO_CACHE`.
+    pub fn open_default() -> Option<Self> {
+        if std::env::var(NO_CACHE_ENV).is_ok_and(|v| v == "1") {
+            return None;
+        }
+        Self::default_dir().map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Load the result stored under `key`. Missing, corrupt or outdated entries are a miss.
+    pub fn load(&self, key: &str) -> Option<CachedResult> {
+        let content = fs::read_to_string(self.entry_path(key)).ok()?;
+        let cached: CachedResult = serde_json::from_str(&content).ok()?;
+        (cached.version == RESULT_CACHE_VERSION && cached.key == key).then_some(cached)
+    }
+
+    /// Store `report` under `key`, atomically so concurrent runs never see a partial file.
+    pub fn store(&self, key: &str, report: &ExecutionReport) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to create result cache directory {:?}: {}",
+                self.dir, e
+            ))
+        })?;
+        let cached = CachedResult {
+            version: RESULT_CACHE_VERSION,
+            key: key.to_string(),
+            stored_at: chrono::Utc::now().to_rfc3339(),
+            report: report.clone(),
+        };
+        let json = serde_json::to_string(&cached).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize cached result: {}", e))
+        })?;
+        let final_path = self.entry_path(key);
+        let tmp_path = self.dir.join(format!("{}.{}.tmp", key, std::process::id()));
+        fs::write(&tmp_path, json)
+            .and_then(|_| fs::rename(&tmp_path, &final_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp_path);
+                DebuggerError::FileError(format!(
+                    "Failed to write cached result {:?}: {}",
+                    final_path, e
+                ))
+                .into()
+            })
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        dir_stats(&self.dir)
+    }
+
+    /// Delete every cached result, returning what was removed.
+    pub fn clear(&self) -> Result<CacheStats> {
+        clear_dir(&self.dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector::budget::BudgetInfo;
+    use std::collections::BTreeMap;
+    use tempfile::TempDir;
+
+    fn plan(args: &[&str]) -> InvocationPlan {
+        InvocationPlan {
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            conversions: BTreeMap::new(),
+            contract: None,
+            contract_sha256: Some("ab".repeat(32)),
+        }
+    }
+
+    fn report(result: &str) -> ExecutionReport {
+        ExecutionReport {
+            contract: "counter.wasm".to_string(),
+            function: "get".to_string(),
+            args: None,
+            result: result.to_string(),
+            sha256: "ab".repeat(32),
+            generated_at: "2026-01-01T00:00:00Z".to_string(),
+            budget: BudgetInfo::default(),
+            events: Vec::new(),
+            storage_diff: Default::default(),
+            auth: Vec::new(),
+            call_trace: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn key_changes_with_arguments_but_not_presentation() {
+        let base = result_key(&plan(&["--function=add", "--args=[1]"])).unwrap();
+        assert_ne!(
+            base,
+            result_key(&plan(&["--function=add", "--args=[2]"])).unwrap()
+        );
+        assert_eq!(
+            base,
+            result_key(&plan(&[
+                "--contract=/elsewhere/counter.wasm",
+                "--args=[1]",
+                "--function=add",
+                "--output=json",
+                "--show=events",
+                "--cache-results",
+            ]))
+            .unwrap()
+        );
+
+        let mut rebuilt = plan(&["--function=add", "--args=[1]"]);
+        rebuilt.contract_sha256 = Some("cd".repeat(32));
+        assert_ne!(base, result_key(&rebuilt).unwrap());
+    }
+
+    #[test]
+    fn key_covers_the_contents_of_named_files() {
+        let dir = TempDir::new().unwrap();
+        let snapshot = dir.path().join("snapshot.json");
+        fs::write(&snapshot, "{\"ledger\": 1}").unwrap();
+        let flag = format!("--network-snapshot={}", snapshot.display());
+        let before = result_key(&plan(&["--function=get", &flag])).unwrap();
+        fs::write(&snapshot, "{\"ledger\": 2}").unwrap();
+        assert_ne!(
+            before,
+            result_key(&plan(&["--function=get", &flag])).unwrap()
+        );
+    }
+
+    #[test]
+    fn stored_reports_are_hits_until_cleared() {
+        let dir = TempDir::new().unwrap();
+        let cache = ResultCache::new(dir.path().join("results"));
+        let key = result_key(&plan(&["--function=get"])).unwrap();
+        assert!(cache.load(&key).is_none());
+
+        cache.store(&key, &report("I64(5)")).unwrap();
+        let hit = cache.load(&key).unwrap();
+        assert_eq!(hit.report.result, "I64(5)");
+        assert!(cache.load(&"00".repeat(32)).is_none());
+        assert_eq!(cache.stats().entries, 1);
+
+        assert_eq!(cache.clear().unwrap().entries, 1);
+        assert!(cache.load(&key).is_none());
+    }
+
+    #[test]
+    fn outdated_entries_are_a_miss() {
+        let dir = TempDir::new().unwrap();
+        let cache = ResultCache::new(dir.path().to_path_buf());
+        let mut cached = CachedResult {
+            version: RESULT_CACHE_VERSION + 1,
+            key: "k".to_string(),
+            stored_at: String::new(),
+            report: report("Void"),
+        };
+        fs::write(
+            dir.path().join("k.json"),
+            serde_json::to_string(&cached).unwrap(),
+        )
+        .unwrap();
+        assert!(cache.load("k").is_none());
+
+        cached.version = RESULT_CACHE_VERSION;
+        fs::write(
+            dir.path().join("k.json"),
+            serde_json::to_string(&cached).unwrap(),
+        )
+        .unwrap();
+        assert!(cache.load("k").is_some());
+    }
+}
@@ -0,0 +1,488 @@
+//! Standalone HTML rendering of an [`ExecutionReport`].
+//!
+//! The output is a single file with inline CSS and JS and no external assets,
+//! so it can be attached to a ticket or opened offline. Sections use
+//! `<details>` so they collapse without scripting; long tables render their
+//! first [`ROW_WINDOW`] rows and page in the rest client-side.
+
+use super::{ExecutionReport, LazyValue};
+use crate::inspector::auth::AuthNode;
+use std::fmt::Write as _;
+
+/// Rows rendered up front (and per "show more" click) for large tables.
+pub const ROW_WINDOW: usize = 100;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2rem; color: #1f2328; }
+h1 { font-size: 1.4rem; }
+details { border: 1px solid #d0d7de; border-radius: 6px; margin-bottom: 1rem; padding: 0.5rem 1rem; }
+summary { font-weight: 600; cursor: pointer; }
+table { border-collapse: collapse; width: 100%; margin-top: 0.5rem; }
+th, td { border-bottom: 1px solid #eaeef2; padding: 4px 8px; text-align: left; vertical-align: top; }
+td { font-family: ui-monospace, Menlo, Consolas, monospace; font-size: 0.85rem; word-break: break-all; }
+pre { background: #f6f8fa; padding: 0.5rem; overflow-x: auto; }
+.added { color: #1a7f37; } .modified { color: #9a6700; } .deleted { color: #cf222e; }
+.alert { color: #cf222e; font-weight: 600; }
+.empty { color: #656d76; font-style: italic; }
+button.more { margin-top: 0.5rem; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll("button.more").forEach(function (button) {
+  var id = button.getAttribute("data-target");
+  var rest = JSON.parse(document.getElementById(id + "-rest").textContent);
+  var body = document.getElementById(id);
+  var step = parseInt(button.getAttribute("data-window"), 10);
+  button.addEventListener("click", function () {
+    rest.splice(0, step).forEach(function (row) {
+      var tr = document.createElement("tr");
+      row.forEach(function (cell) {
+        var td = document.createElement("td");
+        td.textContent = cell;
+        tr.appendChild(td);
+      });
+      body.appendChild(tr);
+    });
+    if (rest.length === 0) {
+      button.remove();
+    } else {
+      button.textContent = "Show more (" + rest.length + " remaining)";
+    }
+  });
+});
+"#;
+
+/// Render `report` into a self-contained HTML document.
+pub fn render_html(report: &ExecutionReport) -> String {
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    let _ = writeln!(
+        out,
+        "<title>{} - soroban-debug report</title>",
+        escape(&report.function)
+    );
+    let _ = writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE);
+    let _ = writeln!(
+        out,
+        "<h1>Execution report: <code>{}</code></h1>",
+        escape(&report.function)
+    );
+
+    section(&mut out, "Summary", |out| {
+        let event_count = report.events.len().to_string();
+        let change_count = report.storage_diff.change_count().to_string();
+        out.push_str("<table>\n");
+        for (label, value) in [
+            ("Contract", report.contract.as_str()),
+            ("Function", report.function.as_str()),
+            ("SHA-256", report.sha256.as_str()),
+            ("Generated", report.generated_at.as_str()),
+            ("Events", event_count.as_str()),
+            ("Storage changes", change_count.as_str()),
+        ] {
+            let _ = writeln!(out, "<tr><th>{}</th><td>{}</td></tr>", label, escape(value));
+        }
+        out.push_str("</table>\n");
+    });
+
+    section(&mut out, "Arguments", |out| match &report.args {
+        Some(args) => {
+            let _ = writeln!(out, "<pre>{}</pre>", escape(args));
+        }
+        None => out.push_str("<p class=\"empty\">No arguments</p>\n"),
+    });
+
+    section(&mut out, "Return value", |out| {
+        let _ = writeln!(out, "<pre>{}</pre>", escape(&report.result));
+    });
+
+    section(&mut out, "Events", |out| {
+        let rows = report
+            .events
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let topics: Vec<&str> = e.topics.iter().map(LazyValue::text).collect();
+                vec![
+                    i.to_string(),
+                    e.contract_id.clone().unwrap_or_default(),
+                    topics.join(", "),
+                    e.data.text().to_string(),
+                ]
+            })
+            .collect();
+        windowed_table(out, "events", &["#", "Contract", "Topics", "Data"], rows);
+    });
+
+    section(&mut out, "Storage diff", |out| {
+        let diff = &report.storage_diff;
+        for alert in &diff.triggered_alerts {
+            match diff.alert_predicates.get(alert) {
+                Some(predicate) => {
+                    let _ = writeln!(
+                        out,
+                        "<p class=\"alert\">Critical key matched <code>{}</code>: {}</p>",
+                        escape(predicate),
+                        escape(alert)
+                    );
+                }
+                None => {
+                    let _ = writeln!(
+                        out,
+                        "<p class=\"alert\">Critical key changed: {}</p>",
+                        escape(alert)
+                    );
+                }
+            }
+        }
+        let mut rows = Vec::new();
+        let mut added: Vec<_> = diff.added.iter().collect();
+        added.sort_by_key(|(key, _)| *key);
+        for (key, value) in added {
+            rows.push(vec![
+                "added".to_string(),
+                key.clone(),
+                String::new(),
+                value.text().to_string(),
+            ]);
+        }
+        let mut modified: Vec<_> = diff.modified.iter().collect();
+        modified.sort_by_key(|(key, _)| *key);
+        for (key, (before, after)) in modified {
+            rows.push(vec![
+                "modified".to_string(),
+                key.clone(),
+                before.text().to_string(),
+                after.text().to_string(),
+            ]);
+        }
+        let mut deleted = diff.deleted.clone();
+        deleted.sort();
+        for key in deleted {
+            rows.push(vec![
+                "deleted".to_string(),
+                key,
+                String::new(),
+                String::new(),
+            ]);
+        }
+        windowed_table(out, "storage", &["Change", "Key", "Before", "After"], rows);
+    });
+
+    section(&mut out, "Authorization tree", |out| {
+        if report.auth.is_empty() {
+            out.push_str("<p class=\"empty\">No authorizations recorded</p>\n");
+        } else {
+            auth_list(out, &report.auth);
+        }
+    });
+
+    section(&mut out, "Budget", |out| {
+        let budget = &report.budget;
+        out.push_str(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"520\" height=\"70\" role=\"img\">\n",
+        );
+        budget_bar(
+            out,
+            0,
+            "CPU",
+            budget.cpu_percentage(),
+            &format!(
+                "{} / {} instructions",
+                budget.cpu_instructions, budget.cpu_limit
+            ),
+        );
+        budget_bar(
+            out,
+            36,
+            "Memory",
+            budget.memory_percentage(),
+            &format!("{} / {} bytes", budget.memory_bytes, budget.memory_limit),
+        );
+        out.push_str("</svg>\n");
+    });
+
+    section(&mut out, "Call trace", |out| {
+        let rows = report
+            .call_trace
+            .iter()
+            .map(|call| {
+                vec![
+                    call.depth.to_string(),
+                    format!("{}{}", "  ".repeat(call.depth as usize), call.function),
+                    call.args
+                        .as_ref()
+                        .map(|args| args.text().to_string())
+                        .unwrap_or_default(),
+                ]
+            })
+            .collect();
+        windowed_table(out, "trace", &["Depth", "Function", "Arguments"], rows);
+    });
+
+    let _ = writeln!(out, "<script>{}</script>\n</body>\n</html>", SCRIPT);
+    out
+}
+
+fn section(out: &mut String, title: &str, body: impl FnOnce(&mut String)) {
+    let _ = writeln!(out, "<details open>\n<summary>{}</summary>", title);
+    body(out);
+    out.push_str("</details>\n");
+}
+
+/// Render a table whose rows beyond the first window are paged in by script.
+fn windowed_table(out: &mut String, id: &str, headers: &[&str], rows: Vec<Vec<String>>) {
+    if rows.is_empty() {
+        out.push_str("<p class=\"empty\">None</p>\n");
+        return;
+    }
+
+    out.push_str("<table>\n<thead><tr>");
+    for header in headers {
+        let _ = write!(out, "<th>{}</th>", header);
+    }
+    let _ = writeln!(out, "</tr></thead>\n<tbody id=\"{}\">", id);
+    for row in rows.iter().take(ROW_WINDOW) {
+        out.push_str("<tr>");
+        for (i, cell) in row.iter().enumerate() {
+            // The storage table tints its change column.
+            if id == "storage" && i == 0 {
+                let _ = write!(out, "<td class=\"{0}\">{0}</td>", escape(cell));
+            } else {
+                let _ = write!(out, "<td>{}</td>", escape(cell));
+            }
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</tbody>\n</table>\n");
+
+    if rows.len() > ROW_WINDOW {
+        let rest = &rows[ROW_WINDOW..];
+        let json = serde_json::to_string(rest).unwrap_or_else(|_| "[]".to_string());
+        let _ = writeln!(
+            out,
+            "<script type=\"application/json\" id=\"{}-rest\">{}</script>",
+            id,
+            escape_script(&json)
+        );
+        let _ = writeln!(
+            out,
+            "<button class=\"more\" data-target=\"{}\" data-window=\"{}\">Show more ({} remaining)</button>",
+            id,
+            ROW_WINDOW,
+            rest.len()
+        );
+    }
+}
+
+fn auth_list(out: &mut String, nodes: &[AuthNode]) {
+    out.push_str("<ul>\n");
+    for node in nodes {
+        let address = if node.address.is_empty() {
+            "<unknown>"
+        } else {
+            node.address.as_str()
+        };
+        let _ = write!(
+            out,
+            "<li><code>{}</code> authorized <code>{}.{}</code> ({:?})",
+            escape(address),
+            escape(&node.contract_id),
+            escape(&node.function),
+            node.status
+        );
+        if !node.sub_invocations.is_empty() {
+            out.push('\n');
+            auth_list(out, &node.sub_invocations);
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ul>\n");
+}
+
+fn budget_bar(out: &mut String, y: u32, label: &str, percent: f64, detail: &str) {
+    let percent = percent.clamp(0.0, 100.0);
+    let width = (percent * 3.0).round();
+    let color = if percent >= 90.0 {
+        "#cf222e"
+    } else if percent >= 70.0 {
+        "#bf8700"
+    } else {
+        "#2da44e"
+    };
+    let _ = writeln!(
+        out,
+        "<text x=\"0\" y=\"{}\" font-size=\"12\">{}</text>\
+         <rect x=\"70\" y=\"{}\" width=\"300\" height=\"20\" fill=\"#eaeef2\"/>\
+         <rect x=\"70\" y=\"{}\" width=\"{}\" height=\"20\" fill=\"{}\"/>\
+         <text x=\"380\" y=\"{}\" font-size=\"12\">{:.1}% ({})</text>",
+        y + 15,
+        label,
+        y + 2,
+        y + 2,
+        width,
+        color,
+        y + 15,
+        percent,
+        escape(detail)
+    );
+}
+
+fn escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Keep embedded JSON from terminating its `<script>` element early. `<` can
+/// only occur inside JSON strings, where `\u003c` is an equivalent escape.
+fn escape_script(json: &str) -> String {
+    json.replace('<', "\\u003c")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::trace::CallEntry;
+    use crate::inspector::auth::AuthStatus;
+    use crate::inspector::budget::BudgetInfo;
+    use crate::inspector::events::ContractEvent;
+    use crate::inspector::storage::StorageDiff;
+    use crate::report::{ReportCall, ReportEvent, ReportStorageDiff};
+
+    fn sample_report(event_count: usize) -> ExecutionReport {
+        let mut storage_diff = StorageDiff::default();
+        storage_diff
+            .added
+            .insert("balance:alice".to_string(), "100".to_string());
+        storage_diff.modified.insert(
+            "counter".to_string(),
+            ("U32(1)".to_string(), "U32(2)".to_string()),
+        );
+        storage_diff.deleted.push("stale".to_string());
+
+        ExecutionReport {
+            contract: "counter.wasm".to_string(),
+            function: "increment".to_string(),
+            args: Some("[1]".to_string()),
+            result: "U32(2)".to_string(),
+            sha256: "abc123".to_string(),
+            generated_at: "2026-01-01T00:00:00Z".to_string(),
+            budget: BudgetInfo {
+                cpu_instructions: 50,
+                cpu_limit: 100,
+                memory_bytes: 95,
+                memory_limit: 100,
+            },
+            events: (0..event_count)
+                .map(|i| {
+                    ReportEvent::from(&ContractEvent {
+                        contract_id: None,
+                        contract: None,
+                        topics: vec![format!("topic_{}", i), "<script>".to_string()],
+                        data: format!("U32({})", i),
+                    })
+                })
+                .collect(),
+            storage_diff: ReportStorageDiff::from(&storage_diff),
+            auth: vec![AuthNode {
+                address: "GALICE".to_string(),
+                function: "increment".to_string(),
+                contract_id: "CCOUNTER".to_string(),
+                status: AuthStatus::Authorized,
+                sub_invocations: vec![],
+            }],
+            call_trace: vec![ReportCall::from(&CallEntry {
+                function: "increment".to_string(),
+                args: Some("[1]".to_string()),
+                depth: 0,
+            })],
+        }
+    }
+
+    /// Minimal well-formedness check: every non-void element is closed in order.
+    fn assert_balanced(html: &str) {
+        const VOID: &[&str] = &["meta", "br", "hr", "img", "input", "link"];
+        let mut stack: Vec<String> = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            rest = &rest[start..];
+            let end = rest.find('>').expect("unterminated tag");
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if tag.starts_with('!') {
+                continue;
+            }
+            if let Some(name) = tag.strip_prefix('/') {
+                assert_eq!(stack.pop().as_deref(), Some(name), "mismatched </{}>", name);
+                continue;
+            }
+            let name = tag
+                .split(|c: char| c.is_whitespace() || c == '/')
+                .next()
+                .unwrap()
+                .to_string();
+            if tag.ends_with('/') || VOID.contains(&name.as_str()) {
+                continue;
+            }
+            if name == "script" || name == "style" {
+                let close = format!("</{}>", name);
+                let idx = rest.find(&close).expect("unclosed raw text element");
+                rest = &rest[idx + close.len()..];
+                continue;
+            }
+            stack.push(name);
+        }
+        assert!(stack.is_empty(), "unclosed elements: {:?}", stack);
+    }
+
+    #[test]
+    fn report_is_well_formed_and_self_contained() {
+        let html = render_html(&sample_report(3));
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_balanced(&html);
+        assert!(!html.contains("<link"));
+        assert!(!html.contains("src=\"http"));
+    }
+
+    #[test]
+    fn report_contains_key_data_points() {
+        let html = render_html(&sample_report(3));
+        for needle in [
+            "increment",
+            "counter.wasm",
+            "abc123",
+            "U32(2)",
+            "topic_2",
+            "balance:alice",
+            "stale",
+            "GALICE",
+            "50.0%",
+            "95.0%",
+        ] {
+            assert!(html.contains(needle), "missing {}", needle);
+        }
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn large_tables_are_windowed() {
+        let html = render_html(&sample_report(ROW_WINDOW + 25));
+        assert_balanced(&html);
+        assert!(html.contains("id=\"events-rest\""));
+        assert!(html.contains("Show more (25 remaining)"));
+        assert!(html.contains("<td>topic_99, &lt;script&gt;</td>"));
+        assert!(!html.contains("<td>topic_100"));
+        assert!(html.contains("topic_124"));
+        assert!(!html.contains("\"<script>\""));
+    }
+}
@@ -0,0 +1,144 @@
+//! Report values that are rendered to text on first use.
+//!
+//! A report over a large run can hold tens of thousands of storage values and
+//! event payloads. Most of them are never looked at when only the summary is
+//! printed, so [`LazyValue`] keeps the host value and renders it the first
+//! time a renderer asks for [`LazyValue::text`]. The text is identical to what
+//! the eager snapshot and event paths produce.
+
+use crate::utils::value_depth::{max_value_depth, render_scval};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use soroban_env_host::xdr::ScVal;
+use std::cell::OnceCell;
+use std::fmt;
+
+#[derive(Debug, Clone)]
+enum Raw {
+    /// Already rendered by the caller.
+    Text,
+    /// An event topic, event payload or argument.
+    Val(ScVal),
+    /// A storage value and the ledger it lives until, rendered as in snapshots.
+    Stored { val: ScVal, live_until: Option<u32> },
+}
+
+/// A host value plus its rendering, computed at most once.
+#[derive(Debug, Clone)]
+pub struct LazyValue {
+    raw: Raw,
+    text: OnceCell<String>,
+}
+
+impl LazyValue {
+    /// A value that is already text.
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            raw: Raw::Text,
+            text: OnceCell::from(text.into()),
+        }
+    }
+
+    /// A host value rendered like its `Debug` output.
+    pub fn from_val(val: ScVal) -> Self {
+        Self {
+            raw: Raw::Val(val),
+            text: OnceCell::new(),
+        }
+    }
+
+    /// A storage value rendered as in a storage snapshot, with its TTL suffix.
+    pub fn from_stored(val: ScVal, live_until: Option<u32>) -> Self {
+        Self {
+            raw: Raw::Stored { val, live_until },
+            text: OnceCell::new(),
+        }
+    }
+
+    /// The rendered value, rendering it now if no one has asked before.
+    pub fn text(&self) -> &str {
+        self.text.get_or_init(|| match &self.raw {
+            Raw::Text => String::new(),
+            Raw::Val(val) => render_scval(val, max_value_depth()),
+            Raw::Stored { val, live_until } => {
+                let mut text = render_scval(val, max_value_depth());
+                if let Some(live_until) = live_until {
+                    text.push_str(&format!(" (ttl={})", live_until));
+                }
+                text
+            }
+        })
+    }
+
+    /// Whether the text has been rendered yet.
+    pub fn is_rendered(&self) -> bool {
+        self.text.get().is_some()
+    }
+}
+
+impl fmt::Display for LazyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.text())
+    }
+}
+
+impl PartialEq for LazyValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.text() == other.text()
+    }
+}
+
+impl Serialize for LazyValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.text())
+    }
+}
+
+/// Reads back as already-rendered text.
+impl<'de> Deserialize<'de> for LazyValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from_text)
+    }
+}
+
+impl From<String> for LazyValue {
+    fn from(text: String) -> Self {
+        Self::from_text(text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_once_on_first_use() {
+        let value = LazyValue::from_val(ScVal::U32(7));
+        assert!(!value.is_rendered());
+        assert_eq!(value.text(), format!("{:?}", ScVal::U32(7)));
+        assert!(value.is_rendered());
+    }
+
+    #[test]
+    fn stored_values_match_snapshot_rendering() {
+        let value = LazyValue::from_stored(ScVal::I32(-1), Some(120));
+        assert_eq!(value.text(), "I32(-1) (ttl=120)");
+        assert_eq!(LazyValue::from_stored(ScVal::Void, None).text(), "Void");
+    }
+
+    #[test]
+    fn serializes_as_its_text() {
+        let value = LazyValue::from_val(ScVal::Bool(true));
+        assert_eq!(serde_json::to_string(&value).unwrap(), "\"Bool(true)\"");
+        assert_eq!(
+            serde_json::to_string(&LazyValue::from_text("x")).unwrap(),
+            "\"x\""
+        );
+    }
+
+    #[test]
+    fn deserializes_as_rendered_text() {
+        let value: LazyValue = serde_json::from_str("\"U32(7)\"").unwrap();
+        assert!(value.is_rendered());
+        assert_eq!(value, LazyValue::from_val(ScVal::U32(7)));
+    }
+}
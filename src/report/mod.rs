@@ -0,0 +1,365 @@
+//! Shareable reports for a single contract execution.
+//!
+//! Storage values, event topics and payloads, and call arguments are held as
+//! [`LazyValue`]s, so building a report over a large run is cheap and each
+//! renderer only pays for rendering what it shows: the summary shows counts
+//! only, while JSON output renders everything.
+
+pub mod cache;
+pub mod html;
+pub mod lazy;
+pub mod pretty;
+
+use crate::compare::trace::CallEntry;
+use crate::inspector::auth::AuthNode;
+use crate::inspector::budget::BudgetInfo;
+use crate::inspector::events::ContractEvent;
+use crate::inspector::storage::StorageDiff;
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use soroban_env_host::xdr::{ContractEventBody, ScVal};
+use soroban_env_host::Host;
+use std::collections::HashMap;
+
+pub use html::render_html;
+pub use lazy::LazyValue;
+pub use pretty::render_pretty;
+
+/// Everything captured about one `run` invocation, ready to be rendered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionReport {
+    pub contract: String,
+    pub function: String,
+    pub args: Option<String>,
+    pub result: String,
+    pub sha256: String,
+    pub generated_at: String,
+    pub budget: BudgetInfo,
+    pub events: Vec<ReportEvent>,
+    pub storage_diff: ReportStorageDiff,
+    pub auth: Vec<AuthNode>,
+    pub call_trace: Vec<ReportCall>,
+}
+
+/// A contract event whose topics and payload are rendered on demand.
+///
+/// Serializes exactly like [`ContractEvent`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportEvent {
+    pub contract_id: Option<String>,
+    pub topics: Vec<LazyValue>,
+    pub data: LazyValue,
+}
+
+/// A storage diff whose values are rendered on demand.
+///
+/// Serializes exactly like [`StorageDiff`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportStorageDiff {
+    pub added: HashMap<String, LazyValue>,
+    pub modified: HashMap<String, (LazyValue, LazyValue)>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub live_until: HashMap<String, (Option<u32>, Option<u32>)>,
+    pub deleted: Vec<String>,
+    pub triggered_alerts: Vec<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub alert_predicates: HashMap<String, String>,
+}
+
+/// One call in the report's call trace.
+///
+/// Serializes exactly like [`CallEntry`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportCall {
+    pub function: String,
+    pub args: Option<LazyValue>,
+    pub depth: u32,
+}
+
+impl ExecutionReport {
+    /// Render the report as HTML and write it to `path`.
+    pub fn write_html(&self, path: &std::path::Path) -> crate::Result<()> {
+        std::fs::write(path, render_html(self)).map_err(|e| {
+            crate::DebuggerError::FileError(format!(
+                "Failed to write HTML report to {:?}: {}",
+                path, e
+            ))
+            .into()
+        })
+    }
+
+    /// The whole report as pretty-printed JSON. Renders every value.
+    pub fn to_json(&self) -> crate::Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize report: {}", e)).into()
+        })
+    }
+}
+
+impl ReportEvent {
+    /// Events emitted so far, with topics and payloads left unrendered.
+    pub fn from_host(host: &Host) -> Result<Vec<Self>> {
+        let events = host
+            .get_events()
+            .map_err(|e| DebuggerError::ExecutionError(format!("Failed to get events: {}", e)))?
+            .0;
+        Ok(events
+            .iter()
+            .map(|host_event| {
+                let event = &host_event.event;
+                let ContractEventBody::V0(v0) = &event.body;
+                Self {
+                    contract_id: event.contract_id.as_ref().map(|h| format!("{:?}", h)),
+                    topics: v0.topics.iter().cloned().map(LazyValue::from_val).collect(),
+                    data: LazyValue::from_val(v0.data.clone()),
+                }
+            })
+            .collect())
+    }
+}
+
+impl From<&ContractEvent> for ReportEvent {
+    fn from(event: &ContractEvent) -> Self {
+        Self {
+            contract_id: event.contract_id.clone(),
+            topics: event.topics.iter().cloned().map(LazyValue::from).collect(),
+            data: LazyValue::from_text(event.data.clone()),
+        }
+    }
+}
+
+impl ReportStorageDiff {
+    /// Diff two sets of captured values (see
+    /// [`StorageInspector::capture_values`](crate::inspector::storage::StorageInspector::capture_values)),
+    /// comparing host values rather than their rendering.
+    pub fn between(
+        before: &HashMap<String, (ScVal, Option<u32>)>,
+        after: &HashMap<String, (ScVal, Option<u32>)>,
+    ) -> Self {
+        let stored = |(val, live_until): &(ScVal, Option<u32>)| {
+            LazyValue::from_stored(val.clone(), *live_until)
+        };
+        let mut diff = Self::default();
+        for (key, value_after) in after {
+            match before.get(key) {
+                Some(value_before) if value_before != value_after => {
+                    diff.modified
+                        .insert(key.clone(), (stored(value_before), stored(value_after)));
+                    if value_before.1 != value_after.1 {
+                        diff.live_until
+                            .insert(key.clone(), (value_before.1, value_after.1));
+                    }
+                }
+                Some(_) => {}
+                None => {
+                    diff.added.insert(key.clone(), stored(value_after));
+                }
+            }
+        }
+        diff.deleted = before
+            .keys()
+            .filter(|key| !after.contains_key(*key))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    pub fn change_count(&self) -> usize {
+        self.added.len() + self.modified.len() + self.deleted.len()
+    }
+}
+
+impl From<&StorageDiff> for ReportStorageDiff {
+    fn from(diff: &StorageDiff) -> Self {
+        Self {
+            added: diff
+                .added
+                .iter()
+                .map(|(key, value)| (key.clone(), LazyValue::from_text(value.clone())))
+                .collect(),
+            modified: diff
+                .modified
+                .iter()
+                .map(|(key, (before, after))| {
+                    (
+                        key.clone(),
+                        (
+                            LazyValue::from_text(before.clone()),
+                            LazyValue::from_text(after.clone()),
+                        ),
+                    )
+                })
+                .collect(),
+            live_until: diff.live_until.clone(),
+            deleted: diff.deleted.clone(),
+            triggered_alerts: diff.triggered_alerts.clone(),
+            alert_predicates: diff.alert_predicates.clone(),
+        }
+    }
+}
+
+impl From<&CallEntry> for ReportCall {
+    fn from(call: &CallEntry) -> Self {
+        Self {
+            function: call.function.clone(),
+            args: call.args.clone().map(LazyValue::from_text),
+            depth: call.depth,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector::auth::AuthStatus;
+    use crate::inspector::storage::StorageInspector;
+    use soroban_env_host::xdr::{ScSymbol, ScVec};
+
+    type Captured = HashMap<String, (ScVal, Option<u32>)>;
+
+    fn symbol(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn balance() -> ScVal {
+        ScVal::Vec(Some(ScVec(vec![ScVal::I32(100)].try_into().unwrap())))
+    }
+
+    /// Storage values before and after the run, as `capture_values` returns them.
+    fn captured() -> (Captured, Captured) {
+        let before = HashMap::from([
+            ("counter".to_string(), (ScVal::U32(1), Some(100))),
+            ("nonce".to_string(), (ScVal::U32(7), Some(20))),
+            ("stale".to_string(), (ScVal::Bool(true), None)),
+        ]);
+        let after = HashMap::from([
+            ("counter".to_string(), (ScVal::U32(2), Some(100))),
+            ("nonce".to_string(), (ScVal::U32(7), Some(120))),
+            ("balance:alice".to_string(), (balance(), Some(500))),
+        ]);
+        (before, after)
+    }
+
+    /// The captured values rendered the way `capture_snapshot` renders them.
+    fn rendered(values: &Captured) -> HashMap<String, String> {
+        values
+            .iter()
+            .map(|(key, (val, ttl))| {
+                let mut text = format!("{:?}", val);
+                if let Some(ttl) = ttl {
+                    text.push_str(&format!(" (ttl={})", ttl));
+                }
+                (key.clone(), text)
+            })
+            .collect()
+    }
+
+    fn lazy_report() -> ExecutionReport {
+        let (before, after) = captured();
+        ExecutionReport {
+            contract: "counter.wasm".to_string(),
+            function: "increment".to_string(),
+            args: Some("[1]".to_string()),
+            result: "U32(2)".to_string(),
+            sha256: "abc123".to_string(),
+            generated_at: "2026-01-01T00:00:00Z".to_string(),
+            budget: BudgetInfo {
+                cpu_instructions: 50,
+                cpu_limit: 100,
+                memory_bytes: 95,
+                memory_limit: 100,
+            },
+            events: vec![ReportEvent {
+                contract_id: None,
+                topics: vec![LazyValue::from_val(symbol("increment"))],
+                data: LazyValue::from_val(ScVal::U32(2)),
+            }],
+            storage_diff: ReportStorageDiff::between(&before, &after),
+            auth: vec![AuthNode {
+                address: "GALICE".to_string(),
+                function: "increment".to_string(),
+                contract_id: "CCOUNTER".to_string(),
+                status: AuthStatus::Authorized,
+                sub_invocations: vec![],
+            }],
+            call_trace: vec![ReportCall {
+                function: "increment".to_string(),
+                args: Some(LazyValue::from_val(ScVal::U32(1))),
+                depth: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn json_matches_the_eagerly_rendered_report() {
+        let (before, after) = captured();
+        let eager_diff = StorageInspector::compute_diff(&rendered(&before), &rendered(&after), &[]);
+        let eager_events = vec![ContractEvent {
+            contract_id: None,
+            contract: None,
+            topics: vec![format!("{:?}", symbol("increment"))],
+            data: format!("{:?}", ScVal::U32(2)),
+        }];
+        let eager_calls = vec![CallEntry {
+            function: "increment".to_string(),
+            args: Some(format!("{:?}", ScVal::U32(1))),
+            depth: 0,
+        }];
+
+        let report = lazy_report();
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(
+            json["storage_diff"],
+            serde_json::to_value(&eager_diff).unwrap()
+        );
+        assert_eq!(json["events"], serde_json::to_value(&eager_events).unwrap());
+        assert_eq!(
+            json["call_trace"],
+            serde_json::to_value(&eager_calls).unwrap()
+        );
+        assert_eq!(
+            json["storage_diff"]["added"]["balance:alice"],
+            format!("{:?} (ttl=500)", balance())
+        );
+        assert_eq!(
+            json["storage_diff"]["live_until"],
+            serde_json::json!({"nonce": [20, 120]})
+        );
+    }
+
+    #[test]
+    fn text_backed_diff_serializes_like_the_storage_diff() {
+        let mut diff = StorageDiff::default();
+        diff.added.insert("a".to_string(), "U32(1)".to_string());
+        diff.modified.insert(
+            "b".to_string(),
+            ("U32(1)".to_string(), "U32(2)".to_string()),
+        );
+        diff.deleted.push("c".to_string());
+        diff.triggered_alerts.push("b".to_string());
+        diff.alert_predicates
+            .insert("b".to_string(), "b!=old".to_string());
+
+        assert_eq!(
+            serde_json::to_value(ReportStorageDiff::from(&diff)).unwrap(),
+            serde_json::to_value(&diff).unwrap()
+        );
+    }
+
+    #[test]
+    fn json_renders_every_value() {
+        let report = lazy_report();
+        assert!(!report.events[0].data.is_rendered());
+        report.to_json().unwrap();
+        assert!(report
+            .storage_diff
+            .added
+            .values()
+            .all(LazyValue::is_rendered));
+        assert!(report.events[0].data.is_rendered());
+        assert!(report.call_trace[0]
+            .args
+            .as_ref()
+            .is_some_and(LazyValue::is_rendered));
+    }
+}
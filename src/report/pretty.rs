@@ -0,0 +1,181 @@
+//! Plain-text rendering of an [`ExecutionReport`] for terminals and logs.
+//!
+//! The summary only counts events, storage changes and calls, so a
+//! summary-only render leaves every [`LazyValue`](super::LazyValue)
+//! unrendered. The full render adds one line per event, storage change and
+//! call.
+
+use super::{ExecutionReport, LazyValue};
+use std::fmt::Write as _;
+
+/// Render `report` as text: the summary, followed by the details unless
+/// `summary_only` is set.
+pub fn render_pretty(report: &ExecutionReport, summary_only: bool) -> String {
+    let mut out = String::new();
+    let budget = &report.budget;
+    let _ = writeln!(out, "Execution report: {}", report.function);
+    for (label, value) in [
+        ("Contract", report.contract.clone()),
+        ("SHA-256", report.sha256.clone()),
+        ("Generated", report.generated_at.clone()),
+        (
+            "Arguments",
+            report.args.clone().unwrap_or_else(|| "(none)".to_string()),
+        ),
+        ("Result", report.result.clone()),
+        (
+            "CPU",
+            format!(
+                "{} / {} instructions ({:.1}%)",
+                budget.cpu_instructions,
+                budget.cpu_limit,
+                budget.cpu_percentage()
+            ),
+        ),
+        (
+            "Memory",
+            format!(
+                "{} / {} bytes ({:.1}%)",
+                budget.memory_bytes,
+                budget.memory_limit,
+                budget.memory_percentage()
+            ),
+        ),
+        ("Events", report.events.len().to_string()),
+        (
+            "Storage changes",
+            report.storage_diff.change_count().to_string(),
+        ),
+        ("Authorizations", report.auth.len().to_string()),
+        ("Calls", report.call_trace.len().to_string()),
+    ] {
+        let _ = writeln!(out, "  {:<16} {}", format!("{}:", label), value);
+    }
+    if summary_only {
+        return out;
+    }
+
+    if !report.events.is_empty() {
+        out.push_str("\nEvents:\n");
+        for (i, event) in report.events.iter().enumerate() {
+            let topics: Vec<&str> = event.topics.iter().map(LazyValue::text).collect();
+            let _ = writeln!(
+                out,
+                "  #{} [{}] {} => {}",
+                i,
+                event.contract_id.as_deref().unwrap_or("-"),
+                topics.join(", "),
+                event.data
+            );
+        }
+    }
+
+    let diff = &report.storage_diff;
+    if diff.change_count() > 0 {
+        out.push_str("\nStorage changes:\n");
+        let mut added: Vec<_> = diff.added.iter().collect();
+        added.sort_by_key(|(key, _)| *key);
+        for (key, value) in added {
+            let _ = writeln!(out, "  + {} = {}", key, value);
+        }
+        let mut modified: Vec<_> = diff.modified.iter().collect();
+        modified.sort_by_key(|(key, _)| *key);
+        for (key, (before, after)) in modified {
+            let _ = write!(out, "  ~ {}: {} -> {}", key, before, after);
+            if let Some((ttl_before, ttl_after)) = diff.live_until.get(key) {
+                let ledger = |ttl: &Option<u32>| ttl.map_or("-".to_string(), |l| l.to_string());
+                let _ = write!(
+                    out,
+                    "  (live until {} -> {})",
+                    ledger(ttl_before),
+                    ledger(ttl_after)
+                );
+            }
+            out.push('\n');
+        }
+        let mut deleted: Vec<_> = diff.deleted.iter().collect();
+        deleted.sort();
+        for key in deleted {
+            let _ = writeln!(out, "  - {}", key);
+        }
+    }
+
+    if !report.call_trace.is_empty() {
+        out.push_str("\nCall trace:\n");
+        for call in &report.call_trace {
+            let _ = writeln!(
+                out,
+                "  {}{}({})",
+                "  ".repeat(call.depth as usize),
+                call.function,
+                call.args.as_ref().map(LazyValue::text).unwrap_or_default()
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inspector::budget::BudgetInfo;
+    use crate::report::{ReportCall, ReportEvent, ReportStorageDiff};
+    use soroban_env_host::xdr::ScVal;
+    use std::collections::HashMap;
+
+    fn report(entries: u32) -> ExecutionReport {
+        let after: HashMap<String, (ScVal, Option<u32>)> = (0..entries)
+            .map(|i| (format!("key_{:05}", i), (ScVal::U32(i), Some(1000))))
+            .collect();
+        ExecutionReport {
+            contract: "counter.wasm".to_string(),
+            function: "fill".to_string(),
+            args: None,
+            result: "Void".to_string(),
+            sha256: "abc123".to_string(),
+            generated_at: "2026-01-01T00:00:00Z".to_string(),
+            budget: BudgetInfo {
+                cpu_instructions: 50,
+                cpu_limit: 100,
+                memory_bytes: 25,
+                memory_limit: 100,
+            },
+            events: vec![ReportEvent {
+                contract_id: None,
+                topics: vec![LazyValue::from_val(ScVal::U32(1))],
+                data: LazyValue::from_val(ScVal::Bool(true)),
+            }],
+            storage_diff: ReportStorageDiff::between(&HashMap::new(), &after),
+            auth: Vec::new(),
+            call_trace: vec![ReportCall {
+                function: "fill".to_string(),
+                args: Some(LazyValue::from_val(ScVal::U32(entries))),
+                depth: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn summary_renders_no_values() {
+        let report = report(1_000);
+        let summary = render_pretty(&report, true);
+        assert!(summary.contains("Storage changes: 1000"));
+        assert!(summary.contains("Events:          1"));
+        assert!(!report
+            .storage_diff
+            .added
+            .values()
+            .any(LazyValue::is_rendered));
+        assert!(!report.events[0].data.is_rendered());
+        assert!(!summary.contains("key_00000"));
+    }
+
+    #[test]
+    fn full_render_lists_every_change() {
+        let report = report(3);
+        let full = render_pretty(&report, false);
+        assert!(full.contains("  + key_00002 = U32(2) (ttl=1000)"));
+        assert!(full.contains("  #0 [-] U32(1) => Bool(true)"));
+        assert!(full.contains("  fill(U32(3))"));
+    }
+}
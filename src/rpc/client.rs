@@ -0,0 +1,204 @@
+//! Typed calls against a Soroban RPC server.
+
+use crate::rpc::http::Endpoint;
+use crate::{DebuggerError, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use soroban_env_host::xdr::{LedgerEntryData, LedgerKey, Limits, ReadXdr};
+use std::cell::Cell;
+use std::time::Duration;
+
+/// `getLedgerEntries` accepts at most this many keys per request.
+pub const MAX_KEYS_PER_REQUEST: usize = 200;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A JSON-RPC 2.0 client for one RPC URL.
+#[derive(Debug)]
+pub struct RpcClient {
+    endpoint: Endpoint,
+    timeout: Duration,
+    next_id: Cell<u64>,
+}
+
+/// `getNetwork` result.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInfo {
+    pub passphrase: String,
+    pub protocol_version: u32,
+}
+
+/// `getLatestLedger` result.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestLedger {
+    pub sequence: u32,
+    pub protocol_version: u32,
+}
+
+/// `getTransaction` result. The XDR fields are only present once the
+/// transaction has been found.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInfo {
+    pub status: String,
+    #[serde(default)]
+    pub ledger: Option<u32>,
+    /// Close time of `ledger`; a string of seconds in current RPC versions.
+    #[serde(default)]
+    pub created_at: Option<Value>,
+    #[serde(default)]
+    pub envelope_xdr: Option<String>,
+    #[serde(default)]
+    pub result_xdr: Option<String>,
+    #[serde(default)]
+    pub result_meta_xdr: Option<String>,
+}
+
+impl TransactionInfo {
+    /// Close time of the transaction's ledger in seconds, if reported.
+    pub fn timestamp(&self) -> Option<u64> {
+        match self.created_at.as_ref()? {
+            Value::String(s) => s.parse().ok(),
+            Value::Number(n) => n.as_u64(),
+            _ => None,
+        }
+    }
+}
+
+/// One entry returned by `getLedgerEntries`.
+#[derive(Debug, Clone)]
+pub struct FetchedEntry {
+    pub key: LedgerKey,
+    pub data: LedgerEntryData,
+    pub last_modified_ledger: u32,
+    pub live_until_ledger: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawEntry {
+    key: String,
+    xdr: String,
+    last_modified_ledger_seq: u32,
+    #[serde(default)]
+    live_until_ledger_seq: Option<u32>,
+}
+
+impl RpcClient {
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(Self {
+            endpoint: Endpoint::parse(url)?,
+            timeout: DEFAULT_TIMEOUT,
+            next_id: Cell::new(1),
+        })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Send one request and return its `result`, turning a JSON-RPC `error`
+    /// into a [`DebuggerError::NetworkError`].
+    fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        let body = self
+            .endpoint
+            .post_json(&request.to_string(), self.timeout)?;
+        let mut response: Value = serde_json::from_str(&body).map_err(|e| {
+            DebuggerError::NetworkError(format!("Invalid JSON-RPC response to {}: {}", method, e))
+        })?;
+        if let Some(error) = response.get("error") {
+            let message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .map_or_else(|| error.to_string(), str::to_string);
+            return Err(
+                DebuggerError::NetworkError(format!("{} failed: {}", method, message)).into(),
+            );
+        }
+        match response.get_mut("result") {
+            Some(result) => Ok(result.take()),
+            None => Err(DebuggerError::NetworkError(format!(
+                "JSON-RPC response to {} has neither result nor error",
+                method
+            ))
+            .into()),
+        }
+    }
+
+    fn call_typed<T: for<'de> Deserialize<'de>>(&self, method: &str, params: Value) -> Result<T> {
+        serde_json::from_value(self.call(method, params)?).map_err(|e| {
+            DebuggerError::NetworkError(format!("Unexpected {} result: {}", method, e)).into()
+        })
+    }
+
+    pub fn get_network(&self) -> Result<NetworkInfo> {
+        self.call_typed("getNetwork", json!({}))
+    }
+
+    pub fn get_latest_ledger(&self) -> Result<LatestLedger> {
+        self.call_typed("getLatestLedger", json!({}))
+    }
+
+    /// Fetch a transaction by hash. Fails when the server does not know it,
+    /// which is also what happens once it falls out of the retention window.
+    pub fn get_transaction(&self, hash: &str) -> Result<TransactionInfo> {
+        let info: TransactionInfo = self.call_typed("getTransaction", json!({ "hash": hash }))?;
+        if info.status == "NOT_FOUND" {
+            return Err(DebuggerError::NetworkError(format!(
+                "Transaction {} was not found. RPC servers only keep recent transactions \
+                 (about a week by default); use an archive RPC for older ones",
+                hash
+            ))
+            .into());
+        }
+        Ok(info)
+    }
+
+    /// Fetch the current state of `keys`. Keys without a live entry are left
+    /// out of the result.
+    pub fn get_ledger_entries(&self, keys: &[LedgerKey]) -> Result<Vec<FetchedEntry>> {
+        let mut fetched = Vec::new();
+        for chunk in keys.chunks(MAX_KEYS_PER_REQUEST) {
+            let encoded = chunk
+                .iter()
+                .map(crate::utils::ledger_key::encode)
+                .collect::<Result<Vec<_>>>()?;
+            let result = self.call("getLedgerEntries", json!({ "keys": encoded }))?;
+            let entries: Vec<RawEntry> = match result.get("entries") {
+                Some(Value::Null) | None => Vec::new(),
+                Some(entries) => serde_json::from_value(entries.clone()).map_err(|e| {
+                    DebuggerError::NetworkError(format!(
+                        "Unexpected getLedgerEntries result: {}",
+                        e
+                    ))
+                })?,
+            };
+            for entry in entries {
+                fetched.push(FetchedEntry {
+                    key: LedgerKey::from_xdr_base64(&entry.key, Limits::none()).map_err(|e| {
+                        DebuggerError::NetworkError(format!("Invalid LedgerKey XDR: {:?}", e))
+                    })?,
+                    data: LedgerEntryData::from_xdr_base64(&entry.xdr, Limits::none()).map_err(
+                        |e| {
+                            DebuggerError::NetworkError(format!("Invalid LedgerEntry XDR: {:?}", e))
+                        },
+                    )?,
+                    last_modified_ledger: entry.last_modified_ledger_seq,
+                    live_until_ledger: entry.live_until_ledger_seq,
+                });
+            }
+        }
+        Ok(fetched)
+    }
+}
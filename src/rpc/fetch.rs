@@ -0,0 +1,262 @@
+//! Fetch contracts from RPC into a [`NetworkSnapshot`].
+//!
+//! Each contract contributes its instance entry and its code. With
+//! `follow_deps`, every contract address found in a fetched instance's
+//! storage is fetched as well, breadth-first, up to a depth limit and a
+//! budget of ledger entries. RPC cannot list a contract's persistent or
+//! temporary entries, so only instance storage is fetched and scanned.
+
+use crate::rpc::client::RpcClient;
+use crate::simulator::{ContractState, NetworkSnapshot};
+use crate::utils::arguments::scval_to_json;
+use crate::{DebuggerError, Result};
+use soroban_env_host::xdr::{
+    ContractDataDurability, ContractExecutable, LedgerEntryData, LedgerKey, LedgerKeyContractCode,
+    LedgerKeyContractData, ScAddress, ScVal,
+};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Ledger entries read per contract: its instance and its code.
+const ENTRIES_PER_CONTRACT: usize = 2;
+
+/// How far [`fetch_snapshot`] reaches beyond the requested contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// Fetch the contracts referenced from instance storage.
+    pub follow_deps: bool,
+    /// Levels of references to follow; 1 fetches direct dependencies only.
+    pub depth: u32,
+    /// Stop before reading more than this many ledger entries.
+    pub max_entries: usize,
+}
+
+/// A fetched snapshot and what was left out of it.
+#[derive(Debug, Clone)]
+pub struct FetchedSnapshot {
+    pub snapshot: NetworkSnapshot,
+    /// Depth at which each contract was reached; 0 is the requested one.
+    pub depths: BTreeMap<String, u32>,
+    /// Why contracts that were referenced were not fetched.
+    pub notes: Vec<String>,
+    /// Ledger entries read.
+    pub entries_read: usize,
+}
+
+/// Fetch `contract`, and with `options.follow_deps` the contracts it refers
+/// to, into a snapshot of the latest ledger.
+pub fn fetch_snapshot(
+    client: &RpcClient,
+    contract: &ScAddress,
+    options: &FetchOptions,
+) -> Result<FetchedSnapshot> {
+    let network = client.get_network()?;
+    let latest = client.get_latest_ledger()?;
+    // getLatestLedger reports no close time, so stamp the snapshot with the
+    // time it was taken.
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    let mut fetched = FetchedSnapshot {
+        snapshot: NetworkSnapshot::new(latest.sequence, network.passphrase, timestamp),
+        depths: BTreeMap::new(),
+        notes: Vec::new(),
+        entries_read: 0,
+    };
+
+    let mut queue = VecDeque::from([(contract.clone(), 0u32)]);
+    let mut seen = BTreeSet::from([contract.clone()]);
+    while let Some((address, depth)) = queue.pop_front() {
+        let id = address.to_string();
+        if fetched.entries_read + ENTRIES_PER_CONTRACT > options.max_entries {
+            if depth == 0 {
+                return Err(DebuggerError::InvalidArguments(format!(
+                    "--max-entries {} is too small to fetch {} (it needs {} entries)",
+                    options.max_entries, id, ENTRIES_PER_CONTRACT
+                ))
+                .into());
+            }
+            fetched.notes.push(format!(
+                "{} was not fetched: the budget of {} entries is spent",
+                id, options.max_entries
+            ));
+            continue;
+        }
+
+        let Some((state, references)) = fetch_contract(client, &address, &mut fetched)? else {
+            if depth == 0 {
+                let reason = fetched.notes.pop().unwrap_or_default();
+                return Err(DebuggerError::InvalidArguments(reason).into());
+            }
+            continue;
+        };
+        fetched.depths.insert(id.clone(), depth);
+        fetched.snapshot.add_contract(state)?;
+
+        if !options.follow_deps {
+            continue;
+        }
+        let references: Vec<ScAddress> = references.into_iter().filter(|r| *r != address).collect();
+        if references.is_empty() {
+            continue;
+        }
+        fetched.snapshot.dependencies.insert(
+            id.clone(),
+            references.iter().map(ToString::to_string).collect(),
+        );
+        for reference in references {
+            if !seen.insert(reference.clone()) {
+                continue;
+            }
+            if depth < options.depth {
+                queue.push_back((reference, depth + 1));
+            } else {
+                fetched.notes.push(format!(
+                    "{} was not fetched: it is referenced by {} beyond --depth {}",
+                    reference, id, options.depth
+                ));
+            }
+        }
+    }
+    Ok(fetched)
+}
+
+/// Read one contract's instance and code. Returns `None`, and records why
+/// in `fetched.notes`, for contracts that have no instance or no WASM.
+fn fetch_contract(
+    client: &RpcClient,
+    address: &ScAddress,
+    fetched: &mut FetchedSnapshot,
+) -> Result<Option<(ContractState, BTreeSet<ScAddress>)>> {
+    let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: address.clone(),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    });
+    let instance_entry = client
+        .get_ledger_entries(std::slice::from_ref(&instance_key))?
+        .into_iter()
+        .next();
+    fetched.entries_read += 1;
+    let instance = instance_entry.as_ref().and_then(|entry| match &entry.data {
+        LedgerEntryData::ContractData(data) => match &data.val {
+            ScVal::ContractInstance(instance) => Some((instance, entry.live_until_ledger)),
+            _ => None,
+        },
+        _ => None,
+    });
+    let Some((instance, live_until)) = instance else {
+        fetched.notes.push(format!(
+            "{} was not fetched: it has no contract instance on the network",
+            address
+        ));
+        return Ok(None);
+    };
+    let hash = match &instance.executable {
+        ContractExecutable::Wasm(hash) => hash,
+        ContractExecutable::StellarAsset => {
+            fetched.notes.push(format!(
+                "{} was not fetched: it is a Stellar Asset Contract, which has no WASM",
+                address
+            ));
+            return Ok(None);
+        }
+    };
+
+    let code_key = LedgerKey::ContractCode(LedgerKeyContractCode { hash: hash.clone() });
+    let code = match client
+        .get_ledger_entries(std::slice::from_ref(&code_key))?
+        .into_iter()
+        .next()
+        .map(|entry| entry.data)
+    {
+        Some(LedgerEntryData::ContractCode(code)) => code.code.to_vec(),
+        _ => {
+            return Err(DebuggerError::WasmLoadError(format!(
+                "Contract code for {} is not available from RPC",
+                address
+            ))
+            .into())
+        }
+    };
+    fetched.entries_read += 1;
+
+    let mut state = ContractState::new(address.to_string(), hex::encode(hash.0));
+    state.code = Some(hex::encode(&code));
+    let mut references = BTreeSet::new();
+    for entry in instance.storage.iter().flat_map(|storage| storage.iter()) {
+        let key = match &entry.key {
+            ScVal::Symbol(symbol) => symbol.to_utf8_string_lossy(),
+            other => scval_to_json(other).to_string(),
+        };
+        if let Some(live_until) = live_until {
+            state.live_until.insert(key.clone(), live_until);
+        }
+        state.set_storage(key, scval_to_json(&entry.val));
+        contract_addresses(&entry.key, &mut references);
+        contract_addresses(&entry.val, &mut references);
+    }
+    Ok(Some((state, references)))
+}
+
+/// Collect every contract address inside `value`.
+pub fn contract_addresses(value: &ScVal, found: &mut BTreeSet<ScAddress>) {
+    match value {
+        ScVal::Address(address @ ScAddress::Contract(_)) => {
+            found.insert(address.clone());
+        }
+        ScVal::Vec(Some(items)) => {
+            for item in items.iter() {
+                contract_addresses(item, found);
+            }
+        }
+        ScVal::Map(Some(entries)) => {
+            for entry in entries.iter() {
+                contract_addresses(&entry.key, found);
+                contract_addresses(&entry.val, found);
+            }
+        }
+        ScVal::ContractInstance(instance) => {
+            for entry in instance.storage.iter().flat_map(|storage| storage.iter()) {
+                contract_addresses(&entry.key, found);
+                contract_addresses(&entry.val, found);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Hash, ScMap, ScMapEntry, ScSymbol, ScVec};
+
+    fn contract(byte: u8) -> ScAddress {
+        ScAddress::Contract(Hash([byte; 32]))
+    }
+
+    #[test]
+    fn finds_contract_addresses_in_nested_values() {
+        let value = ScVal::Map(Some(
+            ScMap::sorted_from(vec![
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("oracle".try_into().unwrap())),
+                    val: ScVal::Address(contract(1)),
+                },
+                ScMapEntry {
+                    key: ScVal::Symbol(ScSymbol("pools".try_into().unwrap())),
+                    val: ScVal::Vec(Some(ScVec(
+                        vec![ScVal::Address(contract(2)), ScVal::U32(7)]
+                            .try_into()
+                            .unwrap(),
+                    ))),
+                },
+            ])
+            .unwrap(),
+        ));
+        let mut found = BTreeSet::new();
+        contract_addresses(&value, &mut found);
+        contract_addresses(&ScVal::Address(contract(1)), &mut found);
+        assert_eq!(found, BTreeSet::from([contract(1), contract(2)]));
+    }
+}
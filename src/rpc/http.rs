@@ -0,0 +1,264 @@
+//! Minimal HTTP/1.1 transport for JSON-RPC calls.
+//!
+//! Each request opens a fresh connection with `Connection: close` and reads
+//! the response to the end, so there is no connection pooling or keep-alive
+//! handling. `https` URLs are served over rustls with the platform's root
+//! certificates.
+
+use crate::{DebuggerError, Result};
+use rustls::{ClientConfig, ClientConnection, RootCertStore, ServerName, StreamOwned};
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Where a JSON-RPC server listens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub tls: bool,
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl Endpoint {
+    /// Parse an `http://` or `https://` URL.
+    pub fn parse(url: &str) -> Result<Self> {
+        let url = url.trim();
+        let (tls, rest) = if let Some(rest) = url.strip_prefix("https://") {
+            (true, rest)
+        } else if let Some(rest) = url.strip_prefix("http://") {
+            (false, rest)
+        } else {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "RPC URL '{}' must start with http:// or https://",
+                url
+            ))
+            .into());
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => {
+                let port = port.parse::<u16>().map_err(|_| {
+                    DebuggerError::InvalidArguments(format!(
+                        "Invalid port '{}' in RPC URL '{}'",
+                        port, url
+                    ))
+                })?;
+                (host, port)
+            }
+            None => (authority, if tls { 443 } else { 80 }),
+        };
+        if host.is_empty() {
+            return Err(
+                DebuggerError::InvalidArguments(format!("RPC URL '{}' has no host", url)).into(),
+            );
+        }
+
+        Ok(Self {
+            tls,
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+
+    /// POST a JSON body and return the response body of a 2xx reply.
+    pub fn post_json(&self, body: &str, timeout: Duration) -> Result<String> {
+        let request = format!(
+            "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: soroban-debug/{}\r\n\
+             Content-Type: application/json\r\nAccept: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.path,
+            self.host,
+            env!("CARGO_PKG_VERSION"),
+            body.len(),
+            body
+        );
+
+        let stream = TcpStream::connect((self.host.as_str(), self.port)).map_err(|e| {
+            DebuggerError::NetworkError(format!(
+                "Failed to connect to {}:{}: {}",
+                self.host, self.port, e
+            ))
+        })?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .and_then(|_| stream.set_write_timeout(Some(timeout)))
+            .map_err(|e| DebuggerError::NetworkError(format!("Failed to set timeout: {}", e)))?;
+
+        let raw = if self.tls {
+            let connection = ClientConnection::new(tls_config()?, self.server_name()?)
+                .map_err(|e| DebuggerError::NetworkError(format!("TLS setup failed: {}", e)))?;
+            exchange(StreamOwned::new(connection, stream), request.as_bytes())?
+        } else {
+            exchange(stream, request.as_bytes())?
+        };
+
+        let (status, body) = parse_response(&raw)?;
+        let body = String::from_utf8_lossy(&body).into_owned();
+        if !(200..300).contains(&status) {
+            return Err(DebuggerError::NetworkError(format!(
+                "RPC server {} answered HTTP {}: {}",
+                self.host,
+                status,
+                body.trim()
+            ))
+            .into());
+        }
+        Ok(body)
+    }
+
+    fn server_name(&self) -> Result<ServerName> {
+        ServerName::try_from(self.host.as_str()).map_err(|e| {
+            DebuggerError::InvalidArguments(format!("Invalid TLS host '{}': {}", self.host, e))
+                .into()
+        })
+    }
+}
+
+fn tls_config() -> Result<Arc<ClientConfig>> {
+    let certs = rustls_native_certs::load_native_certs().map_err(|e| {
+        DebuggerError::NetworkError(format!("Failed to load root certificates: {}", e))
+    })?;
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(&certs);
+    Ok(Arc::new(
+        ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
+
+/// Write `request` and read until the server closes the connection.
+fn exchange(mut stream: impl Read + Write, request: &[u8]) -> Result<Vec<u8>> {
+    stream
+        .write_all(request)
+        .and_then(|_| stream.flush())
+        .map_err(|e| DebuggerError::NetworkError(format!("Failed to send request: {}", e)))?;
+
+    let mut raw = Vec::new();
+    match stream.read_to_end(&mut raw) {
+        Ok(_) => {}
+        // Servers often close TLS connections without a close_notify.
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof && !raw.is_empty() => {}
+        Err(e) => {
+            return Err(
+                DebuggerError::NetworkError(format!("Failed to read response: {}", e)).into(),
+            )
+        }
+    }
+    Ok(raw)
+}
+
+/// Split a raw HTTP/1.1 response into its status code and decoded body.
+pub fn parse_response(raw: &[u8]) -> Result<(u16, Vec<u8>)> {
+    let malformed = |what: &str| -> miette::Report {
+        DebuggerError::NetworkError(format!("Malformed HTTP response: {}", what)).into()
+    };
+
+    let header_end = raw
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .ok_or_else(|| malformed("missing end of headers"))?;
+    let head = String::from_utf8_lossy(&raw[..header_end]);
+    let body = &raw[header_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok())
+        .ok_or_else(|| malformed("bad status line"))?;
+
+    let mut chunked = false;
+    let mut content_length = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("transfer-encoding") {
+            chunked = value.to_ascii_lowercase().contains("chunked");
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse::<usize>().ok();
+        }
+    }
+
+    let body = if chunked {
+        decode_chunked(body).ok_or_else(|| malformed("bad chunked body"))?
+    } else {
+        match content_length {
+            Some(length) if length <= body.len() => body[..length].to_vec(),
+            Some(_) => return Err(malformed("body shorter than Content-Length")),
+            None => body.to_vec(),
+        }
+    };
+    Ok((status, body))
+}
+
+fn decode_chunked(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|window| window == b"\r\n")?;
+        let size_field = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size_hex = size_field.split(';').next()?.trim();
+        let size = usize::from_str_radix(size_hex, 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_urls_with_default_and_explicit_ports() {
+        let endpoint = Endpoint::parse("https://soroban-testnet.stellar.org").unwrap();
+        assert!(endpoint.tls);
+        assert_eq!(endpoint.host, "soroban-testnet.stellar.org");
+        assert_eq!(endpoint.port, 443);
+        assert_eq!(endpoint.path, "/");
+
+        let endpoint = Endpoint::parse("http://localhost:8000/soroban/rpc").unwrap();
+        assert!(!endpoint.tls);
+        assert_eq!(endpoint.port, 8000);
+        assert_eq!(endpoint.path, "/soroban/rpc");
+
+        assert!(Endpoint::parse("ftp://example.com").is_err());
+        assert!(Endpoint::parse("http://host:port").is_err());
+    }
+
+    #[test]
+    fn reads_content_length_bodies() {
+        let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\n{}\r\nextra";
+        let (status, body) = parse_response(raw).unwrap();
+        assert_eq!(status, 200);
+        assert_eq!(body, b"{}\r\n");
+    }
+
+    #[test]
+    fn reads_chunked_bodies() {
+        let raw = b"HTTP/1.1 503 Service Unavailable\r\nTransfer-Encoding: chunked\r\n\r\n\
+                    5\r\nhello\r\n6;ext=1\r\n world\r\n0\r\n\r\n";
+        let (status, body) = parse_response(raw).unwrap();
+        assert_eq!(status, 503);
+        assert_eq!(body, b"hello world");
+    }
+
+    #[test]
+    fn rejects_truncated_responses() {
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n{}").is_err());
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+    }
+}
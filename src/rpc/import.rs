@@ -0,0 +1,891 @@
+//! Rebuild an on-chain contract invocation so it can be replayed locally.
+//!
+//! The transaction envelope supplies the contract, function, arguments, auth
+//! entries and footprint. Every footprint entry is fetched from RPC and then
+//! rewound to the state the transaction saw, using the pre-transaction
+//! `State` entries in the result meta. Entries the RPC server only knows in
+//! a newer version are reported rather than silently used.
+
+use crate::inspector::render_cache::RenderCache;
+use crate::inspector::storage::StorageInspector;
+use crate::rpc::client::{RpcClient, TransactionInfo};
+use crate::runtime::ContractExecutor;
+use crate::utils::arguments::scval_to_json;
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use soroban_env_host::xdr::{
+    AccountId, ContractExecutable, HostFunction, InnerTransactionResultResult, InvokeContractArgs,
+    InvokeHostFunctionResult, LedgerEntry, LedgerEntryChange, LedgerEntryData, LedgerEntryExt,
+    LedgerKey, LedgerKeyAccount, LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyTrustLine,
+    Limits, MuxedAccount, OperationBody, OperationResult, OperationResultTr, PublicKey, ReadXdr,
+    ScAddress, ScVal, SorobanAuthorizationEntry, Transaction, TransactionEnvelope, TransactionExt,
+    TransactionMeta, TransactionResult, TransactionResultResult, WriteXdr,
+};
+use soroban_ledger_snapshot::LedgerSnapshot;
+use soroban_sdk::testutils::Ledger as _;
+use soroban_sdk::Env;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+const SCENARIO_FILE: &str = "invocation.json";
+const LEDGER_FILE: &str = "ledger.json";
+const WASM_FILE: &str = "contract.wasm";
+
+/// The contract call made by a transaction's `InvokeHostFunction` operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub contract: ScAddress,
+    pub function: String,
+    pub args: Vec<ScVal>,
+    pub auth: Vec<SorobanAuthorizationEntry>,
+    /// The operation's source account, or the transaction's.
+    pub source: AccountId,
+    /// Read-only then read-write footprint keys.
+    pub footprint: Vec<LedgerKey>,
+}
+
+impl Invocation {
+    pub fn from_envelope_xdr(xdr: &str) -> Result<Self> {
+        let envelope = TransactionEnvelope::from_xdr_base64(xdr, Limits::none()).map_err(|e| {
+            DebuggerError::ExecutionError(format!(
+                "Failed to decode the transaction envelope ({:?}). It may use a protocol \
+                 newer than this debugger supports",
+                e
+            ))
+        })?;
+        Self::from_envelope(&envelope)
+    }
+
+    pub fn from_envelope(envelope: &TransactionEnvelope) -> Result<Self> {
+        let tx = match envelope {
+            TransactionEnvelope::Tx(v1) => &v1.tx,
+            TransactionEnvelope::TxFeeBump(bump) => match &bump.tx.inner_tx {
+                soroban_env_host::xdr::FeeBumpTransactionInnerTx::Tx(v1) => &v1.tx,
+            },
+            TransactionEnvelope::TxV0(_) => {
+                return Err(not_an_invocation("it is a v0 transaction"));
+            }
+        };
+        Self::from_transaction(tx)
+    }
+
+    fn from_transaction(tx: &Transaction) -> Result<Self> {
+        let [operation] = tx.operations.as_slice() else {
+            return Err(not_an_invocation(&format!(
+                "it has {} operations",
+                tx.operations.len()
+            )));
+        };
+        let OperationBody::InvokeHostFunction(op) = &operation.body else {
+            return Err(not_an_invocation(&format!(
+                "its operation is {}",
+                operation.body.name()
+            )));
+        };
+        let HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address,
+            function_name,
+            args,
+        }) = &op.host_function
+        else {
+            return Err(not_an_invocation(&format!(
+                "it calls {} rather than a contract function",
+                op.host_function.name()
+            )));
+        };
+        let TransactionExt::V1(soroban) = &tx.ext else {
+            return Err(not_an_invocation("it carries no Soroban footprint"));
+        };
+
+        let footprint = &soroban.resources.footprint;
+        let source = operation
+            .source_account
+            .as_ref()
+            .unwrap_or(&tx.source_account);
+        Ok(Self {
+            contract: contract_address.clone(),
+            function: function_name.to_utf8_string_lossy(),
+            args: args.to_vec(),
+            auth: op.auth.to_vec(),
+            source: account_id(source),
+            footprint: footprint
+                .read_only
+                .iter()
+                .chain(footprint.read_write.iter())
+                .cloned()
+                .collect(),
+        })
+    }
+}
+
+fn not_an_invocation(why: &str) -> miette::Report {
+    DebuggerError::InvalidArguments(format!(
+        "Transaction is not a single contract invocation: {}",
+        why
+    ))
+    .into()
+}
+
+fn account_id(account: &MuxedAccount) -> AccountId {
+    let key = match account {
+        MuxedAccount::Ed25519(key) => key.clone(),
+        MuxedAccount::MuxedEd25519(muxed) => muxed.ed25519.clone(),
+    };
+    AccountId(PublicKey::PublicKeyTypeEd25519(key))
+}
+
+/// What the transaction did on-chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OnChainOutcome {
+    /// The result code when the transaction failed.
+    pub failure: Option<String>,
+    /// The contract's return value, when the result meta was available.
+    pub return_value: Option<ScVal>,
+}
+
+impl OnChainOutcome {
+    /// Read the outcome from `getTransaction`'s result and meta XDR.
+    pub fn from_transaction(info: &TransactionInfo) -> Result<Self> {
+        let failure = match &info.result_xdr {
+            Some(xdr) => {
+                let result =
+                    TransactionResult::from_xdr_base64(xdr, Limits::none()).map_err(|e| {
+                        DebuggerError::ExecutionError(format!(
+                            "Failed to decode the transaction result: {:?}",
+                            e
+                        ))
+                    })?;
+                failure_reason(&result)
+            }
+            None if info.status == "SUCCESS" => None,
+            None => Some(info.status.clone()),
+        };
+
+        let return_value = match (&failure, &info.result_meta_xdr) {
+            (None, Some(xdr)) => match TransactionMeta::from_xdr_base64(xdr, Limits::none()) {
+                Ok(TransactionMeta::V3(meta)) => meta.soroban_meta.map(|m| m.return_value),
+                _ => None,
+            },
+            _ => None,
+        };
+        Ok(Self {
+            failure,
+            return_value,
+        })
+    }
+
+    /// How a local run ended differently, if it did.
+    pub fn divergence(&self, local: &std::result::Result<ScVal, String>) -> Option<String> {
+        match (&self.failure, local) {
+            (None, Ok(value)) => match &self.return_value {
+                Some(expected) if expected != value => Some(format!(
+                    "returned {} on-chain but {} locally",
+                    scval_to_json(expected),
+                    scval_to_json(value)
+                )),
+                _ => None,
+            },
+            (None, Err(error)) => Some(format!("succeeded on-chain but failed locally: {}", error)),
+            (Some(failure), Ok(value)) => Some(format!(
+                "failed on-chain ({}) but returned {} locally",
+                failure,
+                scval_to_json(value)
+            )),
+            (Some(_), Err(_)) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for OnChainOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.failure, &self.return_value) {
+            (Some(failure), _) => write!(f, "failed ({})", failure),
+            (None, Some(value)) => write!(f, "returned {}", scval_to_json(value)),
+            (None, None) => write!(f, "succeeded (return value not available)"),
+        }
+    }
+}
+
+/// The result code of a failed transaction, or `None` if it succeeded.
+fn failure_reason(result: &TransactionResult) -> Option<String> {
+    let operations = match &result.result {
+        TransactionResultResult::TxSuccess(_) => return None,
+        TransactionResultResult::TxFeeBumpInnerSuccess(inner) => match &inner.result.result {
+            InnerTransactionResultResult::TxSuccess(_) => return None,
+            InnerTransactionResultResult::TxFailed(operations) => operations,
+            other => return Some(other.name().to_string()),
+        },
+        TransactionResultResult::TxFeeBumpInnerFailed(inner) => match &inner.result.result {
+            InnerTransactionResultResult::TxFailed(operations) => operations,
+            other => return Some(other.name().to_string()),
+        },
+        TransactionResultResult::TxFailed(operations) => operations,
+        other => return Some(other.name().to_string()),
+    };
+    let reason = operations
+        .iter()
+        .find_map(|operation| match operation {
+            OperationResult::OpInner(OperationResultTr::InvokeHostFunction(
+                InvokeHostFunctionResult::Success(_),
+            )) => None,
+            OperationResult::OpInner(OperationResultTr::InvokeHostFunction(result)) => {
+                Some(format!("InvokeHostFunction{}", result.name()))
+            }
+            OperationResult::OpInner(_) => None,
+            other => Some(other.name().to_string()),
+        })
+        .unwrap_or_else(|| "TxFailed".to_string());
+    Some(reason)
+}
+
+/// A transaction's invocation with the ledger state it ran against.
+#[derive(Debug, Clone)]
+pub struct ImportedTransaction {
+    pub hash: String,
+    pub network_passphrase: String,
+    pub ledger: u32,
+    pub invocation: Invocation,
+    pub outcome: OnChainOutcome,
+    pub snapshot: LedgerSnapshot,
+    pub wasm: Vec<u8>,
+    /// Ways the rebuilt state may differ from what the transaction saw.
+    pub notes: Vec<String>,
+}
+
+/// Fetch `hash` and the state it ran against from `client`.
+pub fn import_transaction(client: &RpcClient, hash: &str) -> Result<ImportedTransaction> {
+    let network = client.get_network()?;
+    let info = client.get_transaction(hash)?;
+    let (Some(ledger), Some(envelope)) = (info.ledger, info.envelope_xdr.as_deref()) else {
+        return Err(DebuggerError::NetworkError(format!(
+            "getTransaction returned no ledger or envelope for {}",
+            hash
+        ))
+        .into());
+    };
+    let invocation = Invocation::from_envelope_xdr(envelope)?;
+    let outcome = OnChainOutcome::from_transaction(&info)?;
+    let mut notes = Vec::new();
+
+    let mut state = LedgerState::new(ledger);
+    state.fetch(client, &invocation.footprint)?;
+    let instance_key = LedgerKey::ContractData(LedgerKeyContractData {
+        contract: invocation.contract.clone(),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: soroban_env_host::xdr::ContractDataDurability::Persistent,
+    });
+    state.fetch(client, std::slice::from_ref(&instance_key))?;
+
+    match &info.result_meta_xdr {
+        Some(xdr) => match TransactionMeta::from_xdr_base64(xdr, Limits::none()) {
+            Ok(meta) => state.rewind(&meta),
+            Err(_) => notes.push(
+                "Result meta could not be decoded; entries the transaction changed are shown \
+                 as they are now"
+                    .to_string(),
+            ),
+        },
+        None => notes.push(
+            "RPC returned no result meta; entries the transaction changed are shown as they \
+             are now"
+                .to_string(),
+        ),
+    }
+
+    let code_key = state.code_key(&instance_key, &invocation.contract)?;
+    state.fetch(client, std::slice::from_ref(&code_key))?;
+    let wasm = match state.entries.get(&code_key).map(|entry| &entry.data) {
+        Some(LedgerEntryData::ContractCode(code)) => code.code.to_vec(),
+        _ => {
+            return Err(DebuggerError::WasmLoadError(format!(
+                "Contract code for {} is not available from RPC",
+                invocation.contract
+            ))
+            .into())
+        }
+    };
+
+    notes.extend(state.notes(&invocation.footprint));
+
+    let mut info_defaults = Env::default().ledger().get();
+    if network.protocol_version > info_defaults.protocol_version {
+        notes.push(format!(
+            "The network runs protocol {} but this debugger's host supports {}; results may differ",
+            network.protocol_version, info_defaults.protocol_version
+        ));
+    } else {
+        info_defaults.protocol_version = network.protocol_version;
+    }
+    info_defaults.sequence_number = ledger;
+    info_defaults.timestamp = info.timestamp().unwrap_or_default();
+    info_defaults.network_id = Sha256::digest(network.passphrase.as_bytes()).into();
+    let mut snapshot = LedgerSnapshot::default();
+    snapshot.set_ledger_info(info_defaults);
+    snapshot.ledger_entries = state.into_snapshot_entries();
+
+    Ok(ImportedTransaction {
+        hash: hash.to_string(),
+        network_passphrase: network.passphrase,
+        ledger,
+        invocation,
+        outcome,
+        snapshot,
+        wasm,
+        notes,
+    })
+}
+
+/// A fetched ledger entry.
+#[derive(Debug, Clone)]
+struct StateEntry {
+    data: LedgerEntryData,
+    last_modified: u32,
+    live_until: Option<u32>,
+    /// Replaced by its pre-transaction version from the result meta.
+    rewound: bool,
+}
+
+/// A ledger entry and its live-until ledger, as [`LedgerSnapshot`] holds it.
+type SnapshotEntry = (Box<LedgerKey>, (Box<LedgerEntry>, Option<u32>));
+
+/// Footprint entries as of the transaction's ledger.
+struct LedgerState {
+    ledger: u32,
+    entries: BTreeMap<LedgerKey, StateEntry>,
+    requested: BTreeSet<LedgerKey>,
+    /// Entries the transaction created, which did not exist before it.
+    created: BTreeSet<LedgerKey>,
+}
+
+impl LedgerState {
+    fn new(ledger: u32) -> Self {
+        Self {
+            ledger,
+            entries: BTreeMap::new(),
+            requested: BTreeSet::new(),
+            created: BTreeSet::new(),
+        }
+    }
+
+    fn fetch(&mut self, client: &RpcClient, keys: &[LedgerKey]) -> Result<()> {
+        let keys: Vec<LedgerKey> = keys
+            .iter()
+            .filter(|key| self.requested.insert((*key).clone()))
+            .cloned()
+            .collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+        for fetched in client.get_ledger_entries(&keys)? {
+            if self.created.contains(&fetched.key) {
+                continue;
+            }
+            self.entries.insert(
+                fetched.key,
+                StateEntry {
+                    data: fetched.data,
+                    last_modified: fetched.last_modified_ledger,
+                    live_until: fetched.live_until_ledger,
+                    rewound: false,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Put back the pre-transaction version of every entry it changed.
+    fn rewind(&mut self, meta: &TransactionMeta) {
+        let TransactionMeta::V3(meta) = meta else {
+            return;
+        };
+        for change in meta.operations.iter().flat_map(|op| op.changes.iter()) {
+            match change {
+                LedgerEntryChange::State(entry) => {
+                    if let Some(key) = entry_key(&entry.data) {
+                        let live_until = self.entries.get(&key).and_then(|e| e.live_until);
+                        self.entries.insert(
+                            key,
+                            StateEntry {
+                                data: entry.data.clone(),
+                                last_modified: entry.last_modified_ledger_seq,
+                                live_until,
+                                rewound: true,
+                            },
+                        );
+                    }
+                }
+                LedgerEntryChange::Created(entry) => {
+                    if let Some(key) = entry_key(&entry.data) {
+                        self.entries.remove(&key);
+                        self.created.insert(key);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The `ContractCode` key of the contract whose instance is at `instance_key`.
+    fn code_key(&self, instance_key: &LedgerKey, contract: &ScAddress) -> Result<LedgerKey> {
+        let instance = match self.entries.get(instance_key).map(|entry| &entry.data) {
+            Some(LedgerEntryData::ContractData(data)) => match &data.val {
+                ScVal::ContractInstance(instance) => instance,
+                _ => return Err(missing_contract(contract)),
+            },
+            _ => return Err(missing_contract(contract)),
+        };
+        match &instance.executable {
+            ContractExecutable::Wasm(hash) => Ok(LedgerKey::ContractCode(LedgerKeyContractCode {
+                hash: hash.clone(),
+            })),
+            ContractExecutable::StellarAsset => Err(DebuggerError::InvalidArguments(format!(
+                "{} is a Stellar Asset Contract, which has no WASM to debug",
+                contract
+            ))
+            .into()),
+        }
+    }
+
+    fn notes(&self, footprint: &[LedgerKey]) -> Vec<String> {
+        let mut cache = RenderCache::new();
+        let mut notes = Vec::new();
+        for (key, entry) in &self.entries {
+            if !entry.rewound && entry.last_modified >= self.ledger {
+                notes.push(format!(
+                    "{} was modified in ledger {}, at or after the transaction; its current \
+                     value is used",
+                    StorageInspector::render_ledger_key(key, &mut cache),
+                    entry.last_modified
+                ));
+            }
+        }
+        let absent = footprint
+            .iter()
+            .filter(|key| !self.entries.contains_key(*key) && !self.created.contains(*key))
+            .count();
+        if absent > 0 {
+            notes.push(format!(
+                "{} footprint entr{} not found on the network and treated as absent",
+                absent,
+                if absent == 1 { "y was" } else { "ies were" }
+            ));
+        }
+        notes
+    }
+
+    fn into_snapshot_entries(self) -> Vec<SnapshotEntry> {
+        let ledger = self.ledger;
+        self.entries
+            .into_iter()
+            .map(|(key, entry)| {
+                let has_ttl = matches!(
+                    entry.data,
+                    LedgerEntryData::ContractData(_) | LedgerEntryData::ContractCode(_)
+                );
+                // The entry was live when the transaction ran, even if it has
+                // since been archived.
+                let live_until = has_ttl.then(|| entry.live_until.unwrap_or(ledger).max(ledger));
+                let ledger_entry = LedgerEntry {
+                    last_modified_ledger_seq: entry.last_modified,
+                    data: entry.data,
+                    ext: LedgerEntryExt::V0,
+                };
+                (Box::new(key), (Box::new(ledger_entry), live_until))
+            })
+            .collect()
+    }
+}
+
+fn missing_contract(contract: &ScAddress) -> miette::Report {
+    DebuggerError::InvalidArguments(format!(
+        "No contract instance found for {} on the network",
+        contract
+    ))
+    .into()
+}
+
+fn entry_key(data: &LedgerEntryData) -> Option<LedgerKey> {
+    Some(match data {
+        LedgerEntryData::ContractData(entry) => LedgerKey::ContractData(LedgerKeyContractData {
+            contract: entry.contract.clone(),
+            key: entry.key.clone(),
+            durability: entry.durability,
+        }),
+        LedgerEntryData::ContractCode(entry) => LedgerKey::ContractCode(LedgerKeyContractCode {
+            hash: entry.hash.clone(),
+        }),
+        LedgerEntryData::Account(entry) => LedgerKey::Account(LedgerKeyAccount {
+            account_id: entry.account_id.clone(),
+        }),
+        LedgerEntryData::Trustline(entry) => LedgerKey::Trustline(LedgerKeyTrustLine {
+            account_id: entry.account_id.clone(),
+            asset: entry.asset.clone(),
+        }),
+        _ => return None,
+    })
+}
+
+/// `invocation.json`: everything but the ledger state and WASM.
+#[derive(Debug, Serialize, Deserialize)]
+struct Scenario {
+    transaction: String,
+    network_passphrase: String,
+    ledger: u32,
+    source_account: String,
+    contract: String,
+    function: String,
+    /// Arguments for reading; `args_xdr` is what gets replayed.
+    args: Vec<serde_json::Value>,
+    args_xdr: Vec<String>,
+    auth_xdr: Vec<String>,
+    footprint_xdr: Vec<String>,
+    #[serde(default)]
+    failure: Option<String>,
+    #[serde(default)]
+    return_value_xdr: Option<String>,
+    #[serde(default)]
+    notes: Vec<String>,
+}
+
+impl ImportedTransaction {
+    /// Write `invocation.json`, `ledger.json` and `contract.wasm` into `dir`.
+    pub fn save(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to create directory {:?}: {}", dir, e))
+        })?;
+        let invocation = &self.invocation;
+        let scenario = Scenario {
+            transaction: self.hash.clone(),
+            network_passphrase: self.network_passphrase.clone(),
+            ledger: self.ledger,
+            source_account: ScAddress::Account(invocation.source.clone()).to_string(),
+            contract: invocation.contract.to_string(),
+            function: invocation.function.clone(),
+            args: invocation.args.iter().map(scval_to_json).collect(),
+            args_xdr: encode_all(&invocation.args)?,
+            auth_xdr: encode_all(&invocation.auth)?,
+            footprint_xdr: encode_all(&invocation.footprint)?,
+            failure: self.outcome.failure.clone(),
+            return_value_xdr: self.outcome.return_value.as_ref().map(encode).transpose()?,
+            notes: self.notes.clone(),
+        };
+        let json = serde_json::to_string_pretty(&scenario).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize {}: {}", SCENARIO_FILE, e))
+        })?;
+        write(&dir.join(SCENARIO_FILE), json.as_bytes())?;
+        write(&dir.join(WASM_FILE), &self.wasm)?;
+        let ledger_path = dir.join(LEDGER_FILE);
+        self.snapshot.write_file(&ledger_path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to write {:?}: {}", ledger_path, e)).into()
+        })
+    }
+
+    /// Read a scenario written by [`Self::save`].
+    pub fn load(dir: &Path) -> Result<Self> {
+        let scenario_path = dir.join(SCENARIO_FILE);
+        let json = fs::read_to_string(&scenario_path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read {:?}: {}", scenario_path, e))
+        })?;
+        let scenario: Scenario = serde_json::from_str(&json).map_err(|e| {
+            DebuggerError::FileError(format!("Invalid scenario {:?}: {}", scenario_path, e))
+        })?;
+        let ledger_path = dir.join(LEDGER_FILE);
+        let snapshot = LedgerSnapshot::read_file(&ledger_path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read {:?}: {}", ledger_path, e))
+        })?;
+        let wasm_path = dir.join(WASM_FILE);
+        let wasm = fs::read(&wasm_path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read {:?}: {}", wasm_path, e))
+        })?;
+
+        let source = match crate::utils::ledger_key::parse_contract(&scenario.source_account)? {
+            ScAddress::Account(account) => account,
+            other => {
+                return Err(DebuggerError::FileError(format!(
+                    "Scenario source account {} is not an account",
+                    other
+                ))
+                .into())
+            }
+        };
+        Ok(Self {
+            hash: scenario.transaction,
+            network_passphrase: scenario.network_passphrase,
+            ledger: scenario.ledger,
+            invocation: Invocation {
+                contract: crate::utils::ledger_key::parse_contract(&scenario.contract)?,
+                function: scenario.function,
+                args: decode_all(&scenario.args_xdr)?,
+                auth: decode_all(&scenario.auth_xdr)?,
+                source,
+                footprint: decode_all(&scenario.footprint_xdr)?,
+            },
+            outcome: OnChainOutcome {
+                failure: scenario.failure,
+                return_value: scenario
+                    .return_value_xdr
+                    .as_deref()
+                    .map(decode)
+                    .transpose()?,
+            },
+            snapshot,
+            wasm,
+            notes: scenario.notes,
+        })
+    }
+
+    /// An executor holding the imported state, with the transaction's source
+    /// account and auth entries in place. With `mock_auth`, every
+    /// `require_auth` passes instead, as signatures and nonces from the
+    /// original transaction may no longer verify.
+    pub fn executor(&self, mock_auth: bool) -> Result<ContractExecutor> {
+        let mut executor = ContractExecutor::new(self.wasm.clone())?;
+        executor.load_ledger_snapshot(self.snapshot.clone(), &self.invocation.contract)?;
+        executor
+            .host()
+            .set_source_account(self.invocation.source.clone())
+            .map_err(|e| {
+                DebuggerError::ExecutionError(format!("Failed to set source account: {:?}", e))
+            })?;
+        if mock_auth {
+            executor.enable_mock_all_auths();
+        } else {
+            executor.env().set_auths(&self.invocation.auth);
+        }
+        Ok(executor)
+    }
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<()> {
+    fs::write(path, contents)
+        .map_err(|e| DebuggerError::FileError(format!("Failed to write {:?}: {}", path, e)).into())
+}
+
+fn encode(value: &impl WriteXdr) -> Result<String> {
+    value
+        .to_xdr_base64(Limits::none())
+        .map_err(|e| DebuggerError::FileError(format!("Failed to encode XDR: {:?}", e)).into())
+}
+
+fn encode_all<T: WriteXdr>(values: &[T]) -> Result<Vec<String>> {
+    values.iter().map(encode).collect()
+}
+
+fn decode<T: ReadXdr>(xdr: &str) -> Result<T> {
+    T::from_xdr_base64(xdr, Limits::none())
+        .map_err(|e| DebuggerError::FileError(format!("Invalid XDR in scenario: {:?}", e)).into())
+}
+
+fn decode_all<T: ReadXdr>(values: &[String]) -> Result<Vec<T>> {
+    values.iter().map(|xdr| decode(xdr)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractDataDurability, ContractDataEntry, ExtensionPoint, Hash, LedgerFootprint, Memo,
+        Operation, Preconditions, ScSymbol, SequenceNumber, SorobanResources,
+        SorobanTransactionData, TransactionResultExt, TransactionV1Envelope, Uint256,
+    };
+
+    const CONTRACT: &str = "CAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6N4O";
+
+    fn contract() -> ScAddress {
+        crate::utils::ledger_key::parse_contract(CONTRACT).unwrap()
+    }
+
+    fn data_key(name: &str) -> LedgerKey {
+        LedgerKey::ContractData(LedgerKeyContractData {
+            contract: contract(),
+            key: ScVal::Symbol(ScSymbol(name.try_into().unwrap())),
+            durability: ContractDataDurability::Persistent,
+        })
+    }
+
+    fn data_entry(name: &str, value: u32, last_modified: u32) -> LedgerEntry {
+        LedgerEntry {
+            last_modified_ledger_seq: last_modified,
+            data: LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: contract(),
+                key: ScVal::Symbol(ScSymbol(name.try_into().unwrap())),
+                durability: ContractDataDurability::Persistent,
+                val: ScVal::U32(value),
+            }),
+            ext: LedgerEntryExt::V0,
+        }
+    }
+
+    fn envelope(operations: Vec<Operation>) -> TransactionEnvelope {
+        TransactionEnvelope::Tx(TransactionV1Envelope {
+            tx: Transaction {
+                source_account: MuxedAccount::Ed25519(Uint256([7; 32])),
+                fee: 100,
+                seq_num: SequenceNumber(1),
+                cond: Preconditions::None,
+                memo: Memo::None,
+                operations: operations.try_into().unwrap(),
+                ext: TransactionExt::V1(SorobanTransactionData {
+                    ext: ExtensionPoint::V0,
+                    resources: SorobanResources {
+                        footprint: LedgerFootprint {
+                            read_only: vec![data_key("Admin")].try_into().unwrap(),
+                            read_write: vec![data_key("Counter")].try_into().unwrap(),
+                        },
+                        instructions: 0,
+                        read_bytes: 0,
+                        write_bytes: 0,
+                    },
+                    resource_fee: 0,
+                }),
+            },
+            signatures: Default::default(),
+        })
+    }
+
+    fn invoke(function: &str, args: Vec<ScVal>) -> Operation {
+        Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(soroban_env_host::xdr::InvokeHostFunctionOp {
+                host_function: HostFunction::InvokeContract(InvokeContractArgs {
+                    contract_address: contract(),
+                    function_name: ScSymbol(function.try_into().unwrap()),
+                    args: args.try_into().unwrap(),
+                }),
+                auth: Default::default(),
+            }),
+        }
+    }
+
+    #[test]
+    fn extracts_the_invocation_and_footprint() {
+        let envelope = envelope(vec![invoke("increment", vec![ScVal::U32(5)])]);
+        let xdr = envelope.to_xdr_base64(Limits::none()).unwrap();
+
+        let invocation = Invocation::from_envelope_xdr(&xdr).unwrap();
+        assert_eq!(invocation.contract.to_string(), CONTRACT);
+        assert_eq!(invocation.function, "increment");
+        assert_eq!(invocation.args, [ScVal::U32(5)]);
+        assert_eq!(
+            invocation.footprint,
+            [data_key("Admin"), data_key("Counter")]
+        );
+        assert_eq!(
+            invocation.source,
+            AccountId(PublicKey::PublicKeyTypeEd25519(Uint256([7; 32])))
+        );
+    }
+
+    #[test]
+    fn rejects_transactions_that_are_not_one_contract_call() {
+        let two = envelope(vec![invoke("a", vec![]), invoke("b", vec![])]);
+        let err = Invocation::from_envelope(&two).unwrap_err().to_string();
+        assert!(err.contains("2 operations"), "{}", err);
+        assert!(Invocation::from_envelope_xdr("not xdr").is_err());
+    }
+
+    #[test]
+    fn reads_failure_codes_from_the_result() {
+        let result = |result| TransactionResult {
+            fee_charged: 100,
+            result,
+            ext: TransactionResultExt::V0,
+        };
+        let trapped = result(TransactionResultResult::TxFailed(
+            vec![OperationResult::OpInner(
+                OperationResultTr::InvokeHostFunction(InvokeHostFunctionResult::Trapped),
+            )]
+            .try_into()
+            .unwrap(),
+        ));
+        assert_eq!(
+            failure_reason(&trapped).as_deref(),
+            Some("InvokeHostFunctionTrapped")
+        );
+        let succeeded = result(TransactionResultResult::TxSuccess(
+            vec![OperationResult::OpInner(
+                OperationResultTr::InvokeHostFunction(InvokeHostFunctionResult::Success(Hash(
+                    [0; 32],
+                ))),
+            )]
+            .try_into()
+            .unwrap(),
+        ));
+        assert_eq!(failure_reason(&succeeded), None);
+        assert_eq!(
+            failure_reason(&result(TransactionResultResult::TxBadSeq)).as_deref(),
+            Some("TxBadSeq")
+        );
+    }
+
+    #[test]
+    fn divergence_compares_success_and_return_value() {
+        let on_chain = OnChainOutcome {
+            failure: None,
+            return_value: Some(ScVal::U32(6)),
+        };
+        assert_eq!(on_chain.divergence(&Ok(ScVal::U32(6))), None);
+        assert!(on_chain
+            .divergence(&Ok(ScVal::U32(7)))
+            .unwrap()
+            .contains("returned 6 on-chain but 7 locally"));
+        assert!(on_chain.divergence(&Err("trap".into())).is_some());
+
+        let failed = OnChainOutcome {
+            failure: Some("InvokeHostFunctionTrapped".into()),
+            return_value: None,
+        };
+        assert_eq!(failed.divergence(&Err("trap".into())), None);
+        assert!(failed.divergence(&Ok(ScVal::Void)).is_some());
+    }
+
+    #[test]
+    fn rewind_restores_prior_state_and_drops_created_entries() {
+        let mut state = LedgerState::new(100);
+        for (name, value) in [("Counter", 6), ("Nonce", 1), ("Admin", 0)] {
+            let entry = data_entry(name, value, if name == "Admin" { 50 } else { 100 });
+            state.entries.insert(
+                data_key(name),
+                StateEntry {
+                    data: entry.data,
+                    last_modified: entry.last_modified_ledger_seq,
+                    live_until: Some(500),
+                    rewound: false,
+                },
+            );
+        }
+        let meta = TransactionMeta::V3(soroban_env_host::xdr::TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: Default::default(),
+            operations: vec![soroban_env_host::xdr::OperationMeta {
+                changes: vec![
+                    LedgerEntryChange::State(data_entry("Counter", 5, 90)),
+                    LedgerEntryChange::Updated(data_entry("Counter", 6, 100)),
+                    LedgerEntryChange::Created(data_entry("Nonce", 1, 100)),
+                ]
+                .try_into()
+                .unwrap(),
+            }]
+            .try_into()
+            .unwrap(),
+            tx_changes_after: Default::default(),
+            soroban_meta: None,
+        });
+
+        state.rewind(&meta);
+        let counter = &state.entries[&data_key("Counter")];
+        assert_eq!(counter.last_modified, 90);
+        assert_eq!(counter.live_until, Some(500));
+        assert!(!state.entries.contains_key(&data_key("Nonce")));
+        assert!(state
+            .notes(&[data_key("Counter"), data_key("Nonce"), data_key("Missing")])
+            .iter()
+            .any(|note| note.contains("1 footprint entry was not found")));
+
+        let entries = state.into_snapshot_entries();
+        assert_eq!(entries.len(), 2);
+    }
+}
@@ -0,0 +1,19 @@
+//! Soroban RPC access, used to import on-chain transactions for replay and to
+//! fetch contracts into snapshots.
+//!
+//! - [`http`]   — minimal HTTP(S) transport for JSON-RPC requests.
+//! - [`client`] — typed `getNetwork`, `getLatestLedger`, `getTransaction` and
+//!   `getLedgerEntries`.
+//! - [`import`] — rebuilding a transaction's invocation and the ledger state
+//!   it ran against.
+//! - [`fetch`]  — fetching contracts, and the contracts they refer to, into a
+//!   network snapshot.
+
+pub mod client;
+pub mod fetch;
+pub mod http;
+pub mod import;
+
+pub use client::RpcClient;
+pub use fetch::{fetch_snapshot, FetchOptions, FetchedSnapshot};
+pub use import::{import_transaction, ImportedTransaction, Invocation, OnChainOutcome};
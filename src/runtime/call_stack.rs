@@ -0,0 +1,236 @@
+//! The chain of contract calls that led to where execution stopped
+//! ([`ContractExecutor::call_stack`](crate::ContractExecutor::call_stack)).
+//!
+//! The host does not expose its frames, so the chain is rebuilt from the
+//! `fn_call` / `fn_return` diagnostic events of the invocation: the calls
+//! still open when the events end are the stack. A call that trapped inside
+//! a `try_call` has no `fn_return`; it leaves the stack when its caller makes
+//! another call or returns. Every call gets a frame of its own, so a contract
+//! that calls itself, or is re-entered further down, shows up once per call
+//! at its own depth.
+
+use crate::debugger::call_walk::CallWalk;
+use crate::inspector::storage_history::StackFrame;
+use serde::Serialize;
+use soroban_env_host::events::HostEvent;
+use std::collections::HashMap;
+
+/// One call on the stack.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContractFrame {
+    /// 0 for the top-level call.
+    pub depth: usize,
+    /// The contract ID.
+    pub contract: String,
+    /// The name the contract was linked under with `--link`, if it was.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub function: String,
+    /// The arguments, decoded.
+    pub args: Vec<String>,
+}
+
+impl ContractFrame {
+    /// `function(args) in contract`, naming a linked contract by its name.
+    pub fn describe(&self) -> String {
+        format!(
+            "{}({}) in {}",
+            self.function,
+            self.args.join(", "),
+            self.name.as_deref().unwrap_or(&self.contract)
+        )
+    }
+}
+
+/// Contract calls, outermost first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct CallStack {
+    frames: Vec<ContractFrame>,
+}
+
+impl CallStack {
+    pub fn new(frames: Vec<ContractFrame>) -> Self {
+        Self { frames }
+    }
+
+    /// The stack of `frames`, outermost first, such as the callers of a
+    /// storage write. `names` maps contract IDs to their linked names.
+    pub fn from_frames(frames: &[StackFrame], names: &HashMap<String, String>) -> Self {
+        let frames = frames
+            .iter()
+            .enumerate()
+            .map(|(depth, frame)| ContractFrame {
+                depth,
+                contract: frame.contract.clone(),
+                name: names.get(&frame.contract).cloned(),
+                function: frame.function.clone(),
+                args: frame.args.clone(),
+            })
+            .collect();
+        Self { frames }
+    }
+
+    /// The calls still open when `events` end, as the
+    /// [call walk](crate::debugger::call_walk) replays them. `names` maps
+    /// contract IDs to their linked names.
+    pub fn from_events(events: &[HostEvent], names: &HashMap<String, String>) -> Self {
+        match CallWalk::from_events(events, None) {
+            Some(walk) => Self::from_frames(&walk.unfinished(), names),
+            None => Self::default(),
+        }
+    }
+
+    pub fn frames(&self) -> &[ContractFrame] {
+        &self.frames
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// One line per frame, innermost last, each led by its depth.
+    pub fn render(&self) -> Vec<String> {
+        self.frames
+            .iter()
+            .map(|frame| format!("{}: {}", frame.depth, frame.describe()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ExtensionPoint, Hash,
+        ScAddress, ScSymbol, ScVal,
+    };
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn diagnostic(recorder: Option<u8>, topics: Vec<ScVal>, data: ScVal) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: recorder.map(|id| Hash([id; 32])),
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data,
+                }),
+            },
+            failed_call: false,
+        }
+    }
+
+    fn call(caller: Option<u8>, callee: u8, function: &str, arg: u32) -> HostEvent {
+        diagnostic(
+            caller,
+            vec![
+                sym("fn_call"),
+                ScVal::Bytes(vec![callee; 32].try_into().unwrap()),
+                sym(function),
+            ],
+            ScVal::U32(arg),
+        )
+    }
+
+    fn ret(callee: u8, function: &str) -> HostEvent {
+        diagnostic(
+            Some(callee),
+            vec![sym("fn_return"), sym(function)],
+            ScVal::Void,
+        )
+    }
+
+    fn address(id: u8) -> String {
+        ScAddress::Contract(Hash([id; 32])).to_string()
+    }
+
+    #[test]
+    fn recursion_and_reentry_keep_a_frame_per_call() {
+        // `walk` recurses twice, its innermost call enters the token, which
+        // calls back into the first contract and traps there.
+        let events = [
+            call(None, 1, "walk", 2),
+            call(Some(1), 1, "walk", 1),
+            call(Some(1), 1, "walk", 0),
+            call(Some(1), 2, "transfer", 5),
+            call(Some(2), 1, "on_transfer", 5),
+        ];
+        let names = HashMap::from([(address(2), "token".to_string())]);
+        let stack = CallStack::from_events(&events, &names);
+        assert_eq!(
+            stack.render(),
+            [
+                format!("0: walk(2) in {}", address(1)),
+                format!("1: walk(1) in {}", address(1)),
+                format!("2: walk(0) in {}", address(1)),
+                "3: transfer(5) in token".to_string(),
+                format!("4: on_transfer(5) in {}", address(1)),
+            ]
+        );
+        assert_eq!(stack.frames()[3].contract, address(2));
+
+        // Once the recursion unwinds to the top, only `walk(2)` is left.
+        let mut returned = events.to_vec();
+        returned.extend([
+            ret(1, "on_transfer"),
+            ret(2, "transfer"),
+            ret(1, "walk"),
+            ret(1, "walk"),
+        ]);
+        let stack = CallStack::from_events(&returned, &names);
+        assert_eq!(stack.render(), [format!("0: walk(2) in {}", address(1))]);
+    }
+
+    #[test]
+    fn calls_trapped_into_a_try_call_leave_the_stack() {
+        // The hook traps, the token carries on and then traps in `burn`.
+        let events = [
+            call(None, 1, "swap", 100),
+            call(Some(1), 2, "transfer", 7),
+            call(Some(2), 3, "on_transfer", 7),
+            call(Some(2), 2, "burn", 7),
+        ];
+        let stack = CallStack::from_events(&events, &HashMap::new());
+        let functions: Vec<&str> = stack
+            .frames()
+            .iter()
+            .map(|frame| frame.function.as_str())
+            .collect();
+        assert_eq!(functions, ["swap", "transfer", "burn"]);
+        assert_eq!(stack.frames()[2].depth, 2);
+
+        let mut returned = events.to_vec();
+        returned.extend([ret(2, "burn"), ret(2, "transfer"), ret(1, "swap")]);
+        assert!(CallStack::from_events(&returned, &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn frames_of_a_write_are_numbered_by_depth() {
+        let frame = |contract: &str, function: &str, args: &[&str]| StackFrame {
+            contract: contract.to_string(),
+            function: function.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        };
+        let names = HashMap::from([("CTOKEN".to_string(), "token".to_string())]);
+        let stack = CallStack::from_frames(
+            &[
+                frame("CPOOL", "swap", &["100"]),
+                frame("CTOKEN", "transfer", &["GA", "7"]),
+            ],
+            &names,
+        );
+        assert_eq!(
+            stack.render(),
+            ["0: swap(100) in CPOOL", "1: transfer(GA, 7) in token"]
+        );
+        let json = serde_json::to_value(&stack).unwrap();
+        assert_eq!(json[1]["name"], "token");
+        assert_eq!(json[1]["contract"], "CTOKEN");
+        assert!(json[0].get("name").is_none());
+    }
+}
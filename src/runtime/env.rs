@@ -1,3 +1,5 @@
+use crate::inspector::storage::FilterPattern;
+use crate::runtime::observer::{InvocationObserver, ObserverResult, StorageWrite};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -31,6 +33,52 @@ pub struct FunctionCallMetadata {
     pub error: Option<String>,
 }
 
+/// Which storage accesses are recorded (`run --trace-sample` and `--trace-limit`).
+///
+/// Function calls are always recorded, so every contract-call boundary
+/// stays in the trace however sparse the storage accesses around it are.
+#[derive(Debug, Clone)]
+pub struct TracePolicy {
+    /// Record every `sample_rate`-th storage access; 1 records all of them.
+    pub sample_rate: u64,
+    /// Stop recording storage accesses once this many have been recorded.
+    pub limit: Option<usize>,
+    /// Keys whose accesses are recorded regardless of `sample_rate`, such as
+    /// those watched with `--alert-on-change`.
+    pub watched: Vec<FilterPattern>,
+}
+
+impl Default for TracePolicy {
+    fn default() -> Self {
+        Self {
+            sample_rate: 1,
+            limit: None,
+            watched: Vec::new(),
+        }
+    }
+}
+
+/// How complete a recorded trace is, stored in the trace file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceSampling {
+    /// One in `rate` storage accesses was recorded.
+    pub rate: u64,
+    /// Storage accesses the invocation made.
+    pub observed: usize,
+    /// Storage accesses present in the trace.
+    pub recorded: usize,
+    /// Whether `--trace-limit` cut recording short.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+impl TraceSampling {
+    /// Whether accesses were skipped by sampling, rather than only by the limit.
+    pub fn is_sampled(&self) -> bool {
+        self.rate > 1
+    }
+}
+
 /// Wrapper around Soroban Host environment for debugging
 /// Tracks all storage reads/writes and function calls for inspection and stepping views
 pub struct DebugEnv {
@@ -44,6 +92,12 @@ pub struct DebugEnv {
     operation_sequence: usize,
     /// Current call depth for function tracking
     call_depth: usize,
+    /// Which storage accesses are recorded
+    policy: TracePolicy,
+    /// Storage accesses seen, recorded or not
+    observed_accesses: usize,
+    /// Whether `policy.limit` stopped recording
+    truncated: bool,
 }
 
 impl DebugEnv {
@@ -54,12 +108,60 @@ impl DebugEnv {
             key_access_index: HashMap::new(),
             operation_sequence: 0,
             call_depth: 0,
+            policy: TracePolicy::default(),
+            observed_accesses: 0,
+            truncated: false,
         }
     }
 
+    /// Set which storage accesses are recorded from now on.
+    pub fn set_trace_policy(&mut self, policy: TracePolicy) {
+        self.policy = policy;
+    }
+
+    /// How complete the recorded accesses are, or `None` when every access
+    /// is recorded.
+    pub fn trace_sampling(&self) -> Option<TraceSampling> {
+        if self.policy.sample_rate <= 1 && self.policy.limit.is_none() {
+            return None;
+        }
+        Some(TraceSampling {
+            rate: self.policy.sample_rate.max(1),
+            observed: self.observed_accesses,
+            recorded: self.storage_accesses.len(),
+            truncated: self.truncated,
+        })
+    }
+
+    /// Count an access to `key` and decide whether to record it. Skipped
+    /// accesses still take a sequence number, so recorded ones keep their
+    /// position relative to function calls.
+    fn should_record(&mut self, key: &str) -> bool {
+        let index = self.observed_accesses as u64;
+        self.observed_accesses += 1;
+        if self
+            .policy
+            .limit
+            .is_some_and(|limit| self.storage_accesses.len() >= limit)
+        {
+            self.truncated = true;
+            return false;
+        }
+        index % self.policy.sample_rate.max(1) == 0
+            || self
+                .policy
+                .watched
+                .iter()
+                .any(|pattern| pattern.matches(key))
+    }
+
     /// Record a storage read operation
     pub fn track_storage_read(&mut self, key: impl Into<String>) {
         let key_str = key.into();
+        if !self.should_record(&key_str) {
+            self.operation_sequence += 1;
+            return;
+        }
         let access = StorageAccess {
             access_type: StorageAccessType::Read,
             key: key_str.clone(),
@@ -79,6 +181,10 @@ impl DebugEnv {
     /// Record a storage write operation
     pub fn track_storage_write(&mut self, key: impl Into<String>, value: impl Into<String>) {
         let key_str = key.into();
+        if !self.should_record(&key_str) {
+            self.operation_sequence += 1;
+            return;
+        }
         let value_str = value.into();
         let access = StorageAccess {
             access_type: StorageAccessType::Write,
@@ -180,6 +286,8 @@ impl DebugEnv {
         self.key_access_index.clear();
         self.operation_sequence = 0;
         self.call_depth = 0;
+        self.observed_accesses = 0;
+        self.truncated = false;
     }
 
     /// Get operation sequence count
@@ -216,6 +324,16 @@ impl Default for DebugEnv {
     }
 }
 
+/// The trace records the writes it observes; deletions are not recorded.
+impl InvocationObserver for DebugEnv {
+    fn on_storage_write(&mut self, write: &StorageWrite) -> ObserverResult {
+        if let Some(value) = &write.new {
+            self.track_storage_write(&write.key, value);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -384,6 +502,72 @@ mod tests {
         assert_eq!(env.function_call_count(), 0);
     }
 
+    #[test]
+    fn sampling_records_every_nth_access_and_all_watched_keys() {
+        let mut env = DebugEnv::new();
+        env.set_trace_policy(TracePolicy {
+            sample_rate: 3,
+            limit: None,
+            watched: vec![FilterPattern::parse("admin").unwrap()],
+        });
+        for i in 0..7 {
+            env.track_storage_read(format!("key{}", i));
+        }
+        env.track_storage_write("admin", "G...");
+        env.enter_function("main", "transfer");
+        env.record_function_call("main", "transfer", vec![], None::<&str>, None::<&str>);
+
+        let recorded: Vec<(&str, usize)> = env
+            .storage_accesses()
+            .iter()
+            .map(|access| (access.key.as_str(), access.sequence))
+            .collect();
+        assert_eq!(
+            recorded,
+            vec![("key0", 0), ("key3", 3), ("key6", 6), ("admin", 7)]
+        );
+        assert_eq!(env.function_call_count(), 1);
+        assert_eq!(env.function_calls()[0].sequence, 8);
+        assert_eq!(
+            env.trace_sampling(),
+            Some(TraceSampling {
+                rate: 3,
+                observed: 8,
+                recorded: 4,
+                truncated: false,
+            })
+        );
+    }
+
+    #[test]
+    fn limit_counts_recorded_accesses_after_sampling() {
+        let mut env = DebugEnv::new();
+        env.set_trace_policy(TracePolicy {
+            sample_rate: 2,
+            limit: Some(3),
+            watched: Vec::new(),
+        });
+        for i in 0..10 {
+            env.track_storage_read(format!("key{}", i));
+        }
+        let keys: Vec<&str> = env
+            .storage_accesses()
+            .iter()
+            .map(|access| access.key.as_str())
+            .collect();
+        assert_eq!(keys, vec!["key0", "key2", "key4"]);
+        let sampling = env.trace_sampling().unwrap();
+        assert!(sampling.truncated);
+        assert_eq!((sampling.observed, sampling.recorded), (10, 3));
+    }
+
+    #[test]
+    fn full_recording_reports_no_sampling() {
+        let mut env = DebugEnv::new();
+        env.track_storage_read("key1");
+        assert_eq!(env.trace_sampling(), None);
+    }
+
     #[test]
     fn test_operation_sequence() {
         let mut env = DebugEnv::new();
@@ -1,4 +1,4 @@
-﻿//! Soroban contract executor â€” public faÃ§ade for the runtime sub-modules.
+//! Soroban contract executor â€” public faÃ§ade for the runtime sub-modules.
 //!
 //! [`ContractExecutor`] is the main entry-point for all contract execution.
 //! Internally it delegates to four focused sub-modules:
@@ -8,25 +8,53 @@
 //! - [`super::invoker`] â€” Function invocation with timeout protection.
 //! - [`super::result`]  â€” Result types and formatting helpers.
 
-use crate::inspector::budget::MemorySummary;
+use crate::inspector::budget::{BudgetInfo, BudgetInspector, MemorySummary};
+use crate::inspector::footprint::InvocationFootprint;
+use crate::inspector::panic::guest_panic;
+use crate::inspector::render_cache::RenderCache;
+use crate::inspector::storage::{StorageEntry, StorageInspector};
+use crate::inspector::storage_heatmap::StorageHeatmap;
+use crate::inspector::storage_history::StorageHistory;
+use crate::inspector::storage_provenance::{StorageProvenance, Writer};
+use crate::runtime::call_stack::{CallStack, ContractFrame};
 use crate::runtime::env::DebugEnv;
-use crate::runtime::mocking::{MockCallLogEntry, MockContractDispatcher, MockRegistry};
+use crate::runtime::limits::{running_call, BudgetExhaustion, BudgetLimits, Resource};
+pub use crate::runtime::loader::LedgerOverrides;
+use crate::runtime::loader::CONSTRUCTOR;
+use crate::runtime::mocking::{
+    MockCallLogEntry, MockContractDispatcher, MockRegistry, MockSummary,
+};
+use crate::runtime::observer::{
+    invocation_notifications, storage_writes, CallStart, InvocationObserver, Notification,
+    ObserverSet, StorageWrite,
+};
+use crate::runtime::ring::{RingRecord, TraceRing};
+use crate::runtime::session::SessionState;
 use crate::server::protocol::{DynamicTraceEvent, DynamicTraceEventKind};
-use crate::utils::arguments::ArgumentParser;
+use crate::utils::ledger_key::{parse_typed_val_with, Durability};
 use crate::{DebuggerError, Result};
 
-use soroban_env_host::Host;
+use soroban_env_host::budget::AsBudget;
+use soroban_env_host::storage::{AccessType, Footprint};
+use soroban_env_host::{DiagnosticLevel, Host};
 use soroban_sdk::testutils::Address as _;
-use soroban_sdk::testutils::Ledger as _;
-use soroban_sdk::{Address, Env};
-use std::collections::HashMap;
+use soroban_sdk::testutils::Snapshot;
+use soroban_sdk::xdr::{
+    AccountId, ContractEventType, LedgerKey, PublicKey, ScAddress, ScVal, Uint256,
+};
+use soroban_sdk::{Address, Env, TryFromVal, Val};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use tracing::info;
 
 // â”€â”€ re-exports so callers never need to import sub-modules directly â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
 pub use crate::runtime::mocking::MockCallLogEntry as MockCallEntry;
-pub use crate::runtime::result::{ExecutionRecord, InstructionCounts, StorageSnapshot};
+pub use crate::runtime::result::{
+    ExecutionOutcome, ExecutionRecord, ExecutionResult, InstructionCounts, StorageSnapshot,
+};
 
 /// Executes Soroban contracts in a test environment.
 pub const DEFAULT_EXECUTION_TIMEOUT_SECS: u64 = 30;
@@ -38,30 +66,184 @@ pub struct ContractExecutor {
     last_memory_summary: Option<MemorySummary>,
     mock_registry: Arc<Mutex<MockRegistry>>,
     wasm_bytes: Vec<u8>,
+    /// Parse results for `wasm_bytes`, looked up on first use.
+    artifacts: std::cell::OnceCell<Arc<crate::utils::artifact_cache::ContractArtifacts>>,
     timeout_secs: u64,
     error_db: crate::debugger::error_db::ErrorDatabase,
     debug_env: DebugEnv,
     /// Accumulated CPU instruction deltas keyed by function name.
     per_function_cpu: HashMap<String, u64>,
+    /// Instruction cost of every completed invocation, in call order.
+    call_costs: Vec<(String, u64)>,
+    /// Per-key storage accesses across every completed invocation.
+    storage_heatmap: StorageHeatmap,
+    /// Storage keys the last invocation wrote, including unchanged rewrites.
+    last_storage_writes: HashSet<String>,
+    /// Ledger entries the last invocation read and wrote.
+    last_footprint: InvocationFootprint,
+    /// Which invocation last wrote each storage key.
+    storage_provenance: StorageProvenance,
+    /// Every storage write, once [`Self::track_writes`] is called.
+    storage_history: Option<StorageHistory>,
+    /// Invocations started so far; the step index recorded as provenance.
+    invocations: usize,
+    /// Shared across storage snapshots so repeated keys/values render once.
+    render_cache: RefCell<RenderCache>,
+    /// Host budget used when the last invocation returned (or the environment
+    /// was built); everything spent after it up to the next dispatch is setup.
+    budget_mark: BudgetInfo,
+    /// Setup spent before the host last reset its budget, which seeding
+    /// storage does; added to the setup measured from `budget_mark`.
+    setup_carry: BudgetInfo,
+    /// Limits set with [`Self::set_budget_limits`]; `None` keeps the host's
+    /// default budget.
+    budget_limits: Option<BudgetLimits>,
+    /// Set when the last invocation ran out of budget.
+    budget_exhaustion: Option<BudgetExhaustion>,
+    /// Hooks registered by embedders, see [`crate::runtime::observer`].
+    observers: ObserverSet,
+    /// Host events already delivered to observers and the trace ring.
+    observed_events: usize,
+    /// Recent calls and writes, dumped when an invocation fails or times out.
+    trace_ring: Arc<Mutex<TraceRing>>,
+    /// What the running invocation had done at the last point the host could
+    /// be read, for the timeout watchdog.
+    timeout_progress: TimeoutProgress,
+    /// Extra contracts registered with [`Self::link_contract`], by name.
+    linked: BTreeMap<String, Address>,
+    /// Contract set with [`Self::set_source_account`]; invocations run from
+    /// inside its frame.
+    invoking_contract: Option<Address>,
+    /// Whether the contract is registered; see [`Self::unconstructed`].
+    constructed: bool,
+    /// The calls open when the last invocation stopped.
+    call_stack: CallStack,
+}
+
+/// A fresh PRNG seed for a run that was not given `--seed`.
+pub fn random_prng_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default(),
+    );
+    hasher.finish()
+}
+
+/// A fresh account to run as when no `--source-account` was given.
+pub fn random_source_account() -> ScAddress {
+    let mut bytes = [0u8; 32];
+    for chunk in bytes.chunks_mut(8) {
+        chunk.copy_from_slice(&random_prng_seed().to_le_bytes());
+    }
+    ScAddress::Account(AccountId(PublicKey::PublicKeyTypeEd25519(Uint256(bytes))))
 }
 
 impl ContractExecutor {
     /// Create a new contract executor by loading and registering `wasm`.
-    #[tracing::instrument(skip_all)]
     pub fn new(wasm: Vec<u8>) -> Result<Self> {
-        let loaded = crate::runtime::loader::load_contract(&wasm)?;
-        Ok(Self {
+        Self::with_ledger(wasm, &LedgerOverrides::default())
+    }
+
+    /// Like [`Self::new`], with the ledger set to `ledger` before `wasm` is
+    /// registered.
+    #[tracing::instrument(skip_all)]
+    pub fn with_ledger(wasm: Vec<u8>, ledger: &LedgerOverrides) -> Result<Self> {
+        let loaded = crate::runtime::loader::load_contract_at(&wasm, ledger)?;
+        Ok(Self::from_loaded(loaded, wasm, true))
+    }
+
+    /// Like [`Self::with_ledger`], but `wasm` is only registered by the first
+    /// call, which must be to its `__constructor`. That call is debugged like
+    /// any other: breakpoints, events and its own budget.
+    #[tracing::instrument(skip_all)]
+    pub fn unconstructed(wasm: Vec<u8>, ledger: &LedgerOverrides) -> Result<Self> {
+        let loaded = crate::runtime::loader::load_contract_unconstructed(&wasm, ledger)?;
+        Ok(Self::from_loaded(loaded, wasm, false))
+    }
+
+    /// Resume from a state saved by [`Self::export_state`]. `wasm` must be
+    /// the contract that produced it.
+    #[tracing::instrument(skip_all)]
+    pub fn from_state(wasm: Vec<u8>, state: SessionState) -> Result<Self> {
+        let wasm_hash = crate::utils::wasm::compute_wasm_sha256(&wasm);
+        if wasm_hash != state.wasm_hash {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "State was saved by contract {}, not {}; its ledger holds the old code, so save the state again with the new build",
+                state.wasm_hash, wasm_hash
+            ))
+            .into());
+        }
+        let contract: ScAddress = state.contract.parse().map_err(|_| {
+            DebuggerError::FileError(format!(
+                "Invalid state file: '{}' is not a contract address",
+                state.contract
+            ))
+        })?;
+        let mut executor = Self::unconstructed(wasm, &LedgerOverrides::default())?;
+        executor.contract_address =
+            Address::try_from_val(&executor.env, &contract).map_err(|e| {
+                DebuggerError::ExecutionError(format!(
+                    "Failed to resolve contract address {}: {:?}",
+                    contract, e
+                ))
+            })?;
+        executor.restore_env_snapshot(&state.snapshot)?;
+        Ok(executor)
+    }
+
+    /// The environment as it stands, with the contract it belongs to, for
+    /// [`Self::from_state`].
+    pub fn export_state(&self) -> SessionState {
+        SessionState::new(
+            crate::utils::wasm::compute_wasm_sha256(&self.wasm_bytes),
+            ScAddress::from(&self.contract_address).to_string(),
+            self.env.to_snapshot(),
+        )
+    }
+
+    fn from_loaded(
+        loaded: crate::runtime::loader::LoadedContract,
+        wasm: Vec<u8>,
+        constructed: bool,
+    ) -> Self {
+        let budget_mark = BudgetInspector::get_cpu_usage(loaded.env.host());
+        Self {
             env: loaded.env,
             contract_address: loaded.contract_address,
             last_execution: None,
             last_memory_summary: None,
             mock_registry: Arc::new(Mutex::new(MockRegistry::default())),
             wasm_bytes: wasm,
+            artifacts: std::cell::OnceCell::new(),
             timeout_secs: DEFAULT_EXECUTION_TIMEOUT_SECS,
             error_db: loaded.error_db,
             debug_env: DebugEnv::new(),
             per_function_cpu: HashMap::new(),
-        })
+            call_costs: Vec::new(),
+            storage_heatmap: StorageHeatmap::new(),
+            last_storage_writes: HashSet::new(),
+            last_footprint: InvocationFootprint::default(),
+            storage_provenance: StorageProvenance::new(),
+            storage_history: None,
+            invocations: 0,
+            render_cache: RefCell::new(RenderCache::new()),
+            budget_mark,
+            setup_carry: BudgetInfo::default(),
+            budget_limits: None,
+            budget_exhaustion: None,
+            observers: ObserverSet::new(),
+            observed_events: 0,
+            trace_ring: Arc::new(Mutex::new(TraceRing::default())),
+            timeout_progress: Arc::new(Mutex::new(None)),
+            linked: BTreeMap::new(),
+            invoking_contract: None,
+            constructed,
+            call_stack: CallStack::default(),
+        }
     }
 
     pub fn env(&self) -> &Env {
@@ -80,11 +262,193 @@ impl ContractExecutor {
         self.timeout_secs
     }
 
+    /// Sample or cap the storage accesses recorded for the trace and the
+    /// storage heatmap.
+    pub fn set_trace_policy(&mut self, policy: crate::runtime::env::TracePolicy) {
+        self.storage_heatmap.set_sample_rate(policy.sample_rate);
+        self.debug_env.set_trace_policy(policy);
+    }
+
+    /// The ledger timestamp calls currently run at, in seconds since the Unix epoch.
+    pub fn ledger_timestamp(&self) -> u64 {
+        self.env.ledger().timestamp()
+    }
+
+    /// Give the host a fresh budget, so a long series of calls on one
+    /// environment does not run out of it. The limits set with
+    /// [`Self::set_budget_limits`] stay in force.
+    pub fn reset_budget(&mut self) {
+        match self.budget_limits {
+            Some(limits) => self
+                .env
+                .cost_estimate()
+                .budget()
+                .reset_limits(limits.cpu_insns, limits.mem_bytes),
+            None => self.env.cost_estimate().budget().reset_default(),
+        }
+        self.budget_mark = BudgetInspector::get_cpu_usage(self.env.host());
+        self.setup_carry = BudgetInfo::default();
+        self.budget_exhaustion = None;
+    }
+
+    /// Run the following calls under `limits` instead of the host's default
+    /// budget. What was spent so far is forgotten.
+    pub fn set_budget_limits(&mut self, limits: BudgetLimits) {
+        self.budget_limits = Some(limits);
+        self.reset_budget();
+    }
+
+    /// The limits calls run under.
+    pub fn budget_limits(&self) -> BudgetLimits {
+        self.budget_limits.unwrap_or_default()
+    }
+
+    /// Budget spent so far against the limits in force. After a call ran out
+    /// of budget, the figures at the point it stopped.
+    pub fn budget_usage(&self) -> BudgetInfo {
+        match &self.budget_exhaustion {
+            Some(exhaustion) => exhaustion.usage.clone(),
+            None => BudgetInspector::get_cpu_usage(self.env.host()),
+        }
+    }
+
+    /// How the last invocation ran out of budget, if it did.
+    pub fn budget_exhaustion(&self) -> Option<&BudgetExhaustion> {
+        self.budget_exhaustion.as_ref()
+    }
+
+    /// After a failed call, record whether it ran out of budget. The host
+    /// refuses any further metered work once over the limit, events included,
+    /// so the budget is lifted afterwards to keep the failure report working.
+    fn check_budget_exhaustion(&mut self) -> Option<BudgetExhaustion> {
+        let budget = self.env.host().budget_cloned();
+        let resource = if budget.cpu_limit_exceeded().unwrap_or(false) {
+            Resource::Cpu
+        } else if budget.mem_limit_exceeded().unwrap_or(false) {
+            Resource::Memory
+        } else {
+            return None;
+        };
+        let limits = self.budget_limits();
+        let usage = BudgetInfo {
+            cpu_instructions: budget.get_cpu_insns_consumed().unwrap_or(0),
+            cpu_limit: limits.cpu_insns,
+            memory_bytes: budget.get_mem_bytes_consumed().unwrap_or(0),
+            memory_limit: limits.mem_bytes,
+        };
+        self.env.cost_estimate().budget().reset_unlimited();
+        let last_call = crate::inspector::event_export::host_events(self.env.host())
+            .ok()
+            .and_then(|events| running_call(&events));
+        let exhaustion = BudgetExhaustion {
+            resource,
+            usage,
+            last_call,
+        };
+        self.budget_exhaustion = Some(exhaustion.clone());
+        Some(exhaustion)
+    }
+
+    /// `error` of a failed invocation, replaced by what the contract panicked
+    /// with when the events after the first `events_before` recorded it. The
+    /// host error is then only logged, for `--verbose`.
+    fn explain_panic(&self, error: miette::Report, events_before: usize) -> miette::Report {
+        let Ok(events) = crate::inspector::event_export::host_events(self.env.host()) else {
+            return error;
+        };
+        let Some(panic) = guest_panic(events.get(events_before..).unwrap_or_default()) else {
+            return error;
+        };
+        let message = panic.describe(&self.error_db);
+        crate::output::record_failure_panic_message(message.clone());
+        crate::logging::log_display(
+            format!("Host error: {}", error),
+            crate::logging::LogLevel::Debug,
+        );
+        DebuggerError::ExecutionError(format!("contract panicked: {}", message)).into()
+    }
+
+    /// Seed the host's base PRNG, from which every invocation's `env.prng()`
+    /// is derived. Seed 0 is the fixed seed the SDK test environment starts with.
+    pub fn set_prng_seed(&mut self, seed: u64) -> Result<()> {
+        let mut bytes = [0u8; 32];
+        bytes[..8].copy_from_slice(&seed.to_le_bytes());
+        self.env.host().set_base_prng_seed(bytes).map_err(|e| {
+            DebuggerError::ExecutionError(format!("Failed to seed the host PRNG: {:?}", e))
+        })?;
+        Ok(())
+    }
+
+    /// The contract spec signature of `function`, if the contract has one.
+    pub fn function_signature(
+        &self,
+        function: &str,
+    ) -> Option<crate::utils::wasm::ContractFunctionSignature> {
+        self.artifacts()
+            .ok()?
+            .signatures
+            .iter()
+            .find(|signature| signature.name == function)
+            .cloned()
+    }
+
+    /// Names of the functions the contract exports.
+    pub fn exported_functions(&self) -> Vec<String> {
+        self.artifacts()
+            .map(|artifacts| artifacts.exports.clone())
+            .unwrap_or_default()
+    }
+
     /// Enable auth mocking for interactive/test-like execution flows (e.g. REPL).
     pub fn enable_mock_all_auths(&self) {
         self.env.mock_all_auths();
     }
 
+    /// Run later invocations on behalf of `source`. An account becomes the
+    /// transaction source account, so its `require_auth` passes without a
+    /// signature. A contract becomes the direct invoker of the contract under
+    /// test; it must already be registered, with `--link` or from a snapshot.
+    pub fn set_source_account(&mut self, source: &ScAddress) -> Result<()> {
+        match source {
+            ScAddress::Account(account) => {
+                self.env
+                    .host()
+                    .set_source_account(account.clone())
+                    .map_err(|e| {
+                        DebuggerError::ExecutionError(format!(
+                            "Failed to set source account: {:?}",
+                            e
+                        ))
+                    })?;
+                self.invoking_contract = None;
+            }
+            ScAddress::Contract(_) => {
+                if !self.is_registered(source) {
+                    return Err(DebuggerError::InvalidFlag(format!(
+                        "Source contract {} is not registered; link it with --link or load it from a snapshot",
+                        source
+                    ))
+                    .into());
+                }
+                self.invoking_contract = Some(Address::from_str(&self.env, &source.to_string()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the ledger holds an instance of the contract at `address`.
+    fn is_registered(&self, address: &ScAddress) -> bool {
+        self.env
+            .to_ledger_snapshot()
+            .ledger_entries
+            .iter()
+            .any(|(key, _)| {
+                matches!(&**key, LedgerKey::ContractData(data)
+                    if data.contract == *address
+                        && data.key == ScVal::LedgerKeyContractInstance)
+            })
+    }
+
     /// Generate a test account address (StrKey) for REPL shorthand aliases.
     pub fn generate_repl_account_strkey(&self) -> Result<String> {
         let addr = Address::generate(&self.env);
@@ -103,47 +467,220 @@ impl ContractExecutor {
         .into())
     }
 
-    /// Execute a contract function.
-    #[tracing::instrument(skip(self), fields(function = function))]
+    /// Execute a contract function and return its result as text. See
+    /// [`Self::execute_detailed`] for the events, storage changes and budget.
     pub fn execute(&mut self, function: &str, args: Option<&str>) -> Result<String> {
-        // 1. Validate function exists in the WASM export section.
-        let exported = crate::utils::wasm::parse_functions(&self.wasm_bytes)?;
-        if !exported.contains(&function.to_string()) {
-            return Err(DebuggerError::InvalidFunction(function.to_string()).into());
-        }
+        self.execute_detailed(function, args)
+            .map(|outcome| outcome.display)
+    }
+
+    /// Execute a contract function and return everything the call produced.
+    #[tracing::instrument(skip(self), fields(function = function))]
+    pub fn execute_detailed(
+        &mut self,
+        function: &str,
+        args: Option<&str>,
+    ) -> Result<ExecutionOutcome> {
+        self.check_callable(function)?;
 
-        // 2. Parse arguments.
         let parsed_args = match args {
-            Some(json) => {
-                crate::runtime::parser::parse_args(&self.env, &self.wasm_bytes, function, json)?
-            }
+            Some(json) => crate::runtime::parser::parse_args(
+                &self.env,
+                &self.wasm_bytes,
+                function,
+                json,
+                &self.linked,
+            )?,
             None => vec![],
         };
 
+        let events_before = crate::inspector::event_export::events_before_call(self.env.host());
+        let started = Instant::now();
+        let display = self.invoke(function, parsed_args)?;
+        let elapsed = started.elapsed();
+        self.outcome(function, display, events_before, elapsed)
+    }
+
+    /// Gather the [`ExecutionOutcome`] of the invocation that just returned
+    /// `display`, with the events after the first `events_before`.
+    fn outcome(
+        &self,
+        function: &str,
+        display: String,
+        events_before: usize,
+        elapsed: Duration,
+    ) -> Result<ExecutionOutcome> {
+        let record = self.last_execution.as_ref().ok_or_else(|| {
+            DebuggerError::ExecutionError(format!("No execution record for {}", function))
+        })?;
+        let storage_diff = StorageInspector::compute_diff_with_writes(
+            &record.storage_before,
+            &record.storage_after,
+            &self.last_storage_writes,
+            &[],
+        )
+        .with_writers(&self.storage_provenance);
+        Ok(ExecutionOutcome {
+            function: function.to_string(),
+            display,
+            return_value: self
+                .last_result()
+                .map_or(serde_json::Value::Null, |result| result.value),
+            events: self.get_events()?.into_iter().skip(events_before).collect(),
+            storage_diff,
+            budget: record.budget.clone(),
+            setup_budget: record.setup_budget.clone(),
+            elapsed,
+        })
+    }
+
+    /// Execute a contract function with arguments already in XDR form, such
+    /// as those decoded from a transaction envelope.
+    #[tracing::instrument(skip(self, args), fields(function = function))]
+    pub fn execute_xdr_args(&mut self, function: &str, args: &[ScVal]) -> Result<String> {
+        self.check_callable(function)?;
+        let parsed_args = args
+            .iter()
+            .map(|arg| {
+                Val::try_from_val(&self.env, arg).map_err(|e| {
+                    DebuggerError::InvalidArguments(format!(
+                        "Failed to convert argument {:?}: {:?}",
+                        arg, e
+                    ))
+                    .into()
+                })
+            })
+            .collect::<Result<Vec<Val>>>()?;
+        self.invoke(function, parsed_args)
+    }
+
+    /// The contract's exports, spec and name index. Hashed and looked up in
+    /// the artifact cache once per executor rather than on every call.
+    fn artifacts(&self) -> Result<Arc<crate::utils::artifact_cache::ContractArtifacts>> {
+        if let Some(artifacts) = self.artifacts.get() {
+            return Ok(Arc::clone(artifacts));
+        }
+        let artifacts = crate::utils::artifact_cache::artifacts_for(&self.wasm_bytes)?;
+        Ok(Arc::clone(self.artifacts.get_or_init(|| artifacts)))
+    }
+
+    /// Count the invocation, forget the call stack of the last one, and
+    /// check `function` is exported by the WASM and permitted by the
+    /// `[policy]` config.
+    fn check_callable(&mut self, function: &str) -> Result<()> {
+        self.invocations += 1;
+        self.call_stack = CallStack::default();
+        let artifacts = self.artifacts()?;
+        if !artifacts.exports.iter().any(|name| name == function) {
+            return Err(DebuggerError::InvalidFunction(function.to_string()).into());
+        }
+        crate::runtime::policy::function_policy().check(function)
+    }
+
+    /// Budget, contract events and storage writes so far, for the watchdog to
+    /// print if the next invocation times out.
+    fn timeout_report(&self) -> TimeoutReport {
+        let mut writes: Vec<String> = self.storage_provenance.entries().keys().cloned().collect();
+        writes.sort();
+        let now = Instant::now();
+        TimeoutReport {
+            budget: BudgetInspector::get_cpu_usage(self.env.host()),
+            events: contract_event_count(self.env.host()),
+            writes,
+            as_of: "the start of the call".to_string(),
+            started: now,
+            taken: now,
+        }
+    }
+
+    /// Whether the contract is registered; `false` until the `__constructor`
+    /// call of an [`Self::unconstructed`] executor succeeds.
+    pub fn is_constructed(&self) -> bool {
+        self.constructed
+    }
+
+    /// Invoke `function` and capture the result.
+    fn invoke(&mut self, function: &str, parsed_args: Vec<Val>) -> Result<String> {
+        let construct = !self.constructed && function == CONSTRUCTOR;
+        if !self.constructed && !construct {
+            return Err(DebuggerError::ExecutionError(format!(
+                "Cannot call {}: the contract's {} has not run yet",
+                function, CONSTRUCTOR
+            ))
+            .into());
+        }
         // Track function call entry
         let contract_addr_str = format!("{:?}", self.contract_address);
         let arg_strings: Vec<String> = parsed_args.iter().map(|val| format!("{:?}", val)).collect();
         self.debug_env.enter_function(&contract_addr_str, function);
 
-        // 3. Invoke and capture the result.
         let storage_fn = || self.get_storage_snapshot();
         let storage_before = storage_fn()?;
-
-        let timeout_guard = ExecutionTimeoutWatchdog::start(self.timeout_secs);
-        let (display, record) = crate::runtime::invoker::invoke_function(
-            &self.env,
-            &self.contract_address,
-            &self.error_db,
-            function,
-            parsed_args,
+        let instance_before = self.instance_storage_snapshot();
+        let owners_before = self
+            .storage_history
+            .is_some()
+            .then(|| self.storage_owners());
+
+        let events_before = crate::inspector::event_export::events_before_call(self.env.host());
+        let prior_footprint = self.begin_write_capture();
+        if let Ok(mut progress) = self.timeout_progress.lock() {
+            *progress = Some(self.timeout_report());
+        }
+        let timeout_guard = ExecutionTimeoutWatchdog::start(
             self.timeout_secs,
-            storage_fn,
-        )?;
+            function,
+            Arc::clone(&self.trace_ring),
+            Arc::clone(&self.timeout_progress),
+        );
+        let (setup_start, setup_carried) = match &self.invoking_contract {
+            Some(_) => self.setup_budget_across_frame(),
+            None => (self.budget_mark.clone(), self.setup_carry.clone()),
+        };
+        let call = || {
+            crate::runtime::invoker::invoke_function(
+                &self.env,
+                &self.contract_address,
+                &self.error_db,
+                function,
+                parsed_args,
+                &setup_start,
+                &setup_carried,
+                self.timeout_secs,
+                construct.then_some(self.wasm_bytes.as_slice()),
+                storage_fn,
+            )
+        };
+        let invocation = match &self.invoking_contract {
+            Some(invoker) => self.env.as_contract(invoker, call),
+            None => call(),
+        };
         drop(timeout_guard);
+        (self.last_storage_writes, self.last_footprint) = self.end_write_capture(prior_footprint);
+        self.budget_mark = BudgetInspector::get_cpu_usage(self.env.host());
+        self.setup_carry = BudgetInfo::default();
+        let (display, record) = match invocation {
+            Ok(done) => {
+                self.constructed = true;
+                done
+            }
+            Err(e) => {
+                let e = match self.check_budget_exhaustion() {
+                    Some(exhaustion) => {
+                        DebuggerError::ExecutionError(exhaustion.to_string()).into()
+                    }
+                    None => self.explain_panic(e, events_before),
+                };
+                self.call_stack = self.failed_call_stack(function, arg_strings, events_before);
+                self.notify_observers(function, Vec::new(), Some(&e.to_string()));
+                return Err(e);
+            }
+        };
 
         // Track storage changes as accesses
         let storage_after = &record.storage_after;
-        self.track_storage_changes(&storage_before, storage_after);
+        let writes = storage_writes(&storage_before, storage_after, &self.last_storage_writes);
+        self.track_storage_changes(&storage_before, storage_after, &writes);
 
         // Record completed function call
         let result_str = display.clone();
@@ -154,30 +691,317 @@ impl ContractExecutor {
             Some(result_str),
             None::<&str>,
         );
+        self.record_provenance(function, &storage_before, &instance_before, storage_after);
+        if let Some(owners_before) = owners_before {
+            self.record_history(
+                function,
+                &writes,
+                &instance_before,
+                owners_before,
+                events_before,
+            );
+        }
 
         *self
             .per_function_cpu
             .entry(function.to_string())
             .or_insert(0) += record.budget.cpu_instructions;
+        self.call_costs
+            .push((function.to_string(), record.budget.cpu_instructions));
         self.last_execution = Some(record);
+        self.notify_observers(function, writes, None);
         Ok(display)
     }
 
+    /// The calls open when the invocation after the first `events_before`
+    /// host events failed, or the top-level call alone if the host recorded
+    /// none.
+    fn failed_call_stack(
+        &self,
+        function: &str,
+        args: Vec<String>,
+        events_before: usize,
+    ) -> CallStack {
+        let stack = crate::inspector::event_export::host_events(self.env.host())
+            .map(|events| {
+                CallStack::from_events(
+                    events.get(events_before..).unwrap_or_default(),
+                    &self.contract_names(),
+                )
+            })
+            .unwrap_or_default();
+        if !stack.is_empty() {
+            return stack;
+        }
+        CallStack::new(vec![ContractFrame {
+            depth: 0,
+            contract: ScAddress::from(&self.contract_address).to_string(),
+            name: None,
+            function: function.to_string(),
+            args,
+        }])
+    }
+
+    /// The calls that were open when the last invocation stopped, outermost
+    /// first: empty once it returned, down to the call that trapped when it
+    /// failed. Each call of a recursive or re-entrant chain has its own
+    /// frame.
+    pub fn call_stack(&self) -> &CallStack {
+        &self.call_stack
+    }
+
+    /// The stack of a call to `function` about to run with the JSON `args`,
+    /// as a breakpoint on its entry sees it.
+    pub fn entry_stack(&self, function: &str, args: Option<&str>) -> CallStack {
+        let args = match args.map(serde_json::from_str::<serde_json::Value>) {
+            None => Vec::new(),
+            Some(Ok(serde_json::Value::Array(items))) => {
+                items.iter().map(ToString::to_string).collect()
+            }
+            Some(Ok(value)) => vec![value.to_string()],
+            Some(Err(_)) => args.map(str::to_string).into_iter().collect(),
+        };
+        CallStack::new(vec![ContractFrame {
+            depth: 0,
+            contract: ScAddress::from(&self.contract_address).to_string(),
+            name: None,
+            function: function.to_string(),
+            args,
+        }])
+    }
+
+    /// Register `observer` for every invocation from now on.
+    pub fn add_observer(&mut self, observer: Box<dyn InvocationObserver>) {
+        if !self.reads_events() {
+            self.observed_events = crate::inspector::event_export::host_events(self.env.host())
+                .map_or(0, |events| events.len());
+        }
+        self.observers.add(observer);
+    }
+
+    /// Replace the trace ring, e.g. to resize or disable it.
+    pub fn set_trace_ring(&mut self, ring: TraceRing) {
+        if !self.reads_events() {
+            self.observed_events = crate::inspector::event_export::host_events(self.env.host())
+                .map_or(0, |events| events.len());
+        }
+        self.trace_ring = Arc::new(Mutex::new(ring));
+    }
+
+    /// A copy of the trace ring as it is now.
+    pub fn trace_ring(&self) -> TraceRing {
+        self.trace_ring
+            .lock()
+            .map(|ring| ring.clone())
+            .unwrap_or_else(|poisoned| poisoned.into_inner().clone())
+    }
+
+    /// Whether invocations read back the host events, for observers or the
+    /// trace ring.
+    fn reads_events(&self) -> bool {
+        !self.observers.is_empty() || self.trace_ring.lock().is_ok_and(|ring| ring.is_enabled())
+    }
+
+    /// Deliver the invocation that just finished to the trace ring and the
+    /// registered observers, and turn observer failures into warnings.
+    fn notify_observers(
+        &mut self,
+        function: &str,
+        writes: Vec<StorageWrite>,
+        failure: Option<&str>,
+    ) {
+        if !self.reads_events() {
+            return;
+        }
+        let events =
+            crate::inspector::event_export::host_events(self.env.host()).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read events for observers: {:?}", e);
+                Vec::new()
+            });
+        let new_events = events.get(self.observed_events..).unwrap_or_default();
+        self.observed_events = events.len();
+        if let Ok(mut ring) = self.trace_ring.lock() {
+            ring.record_invocation(function, new_events, &writes, failure);
+        }
+        if self.observers.is_empty() {
+            return;
+        }
+        let notifications = invocation_notifications(new_events, writes);
+        for warning in self.observers.notify(&notifications) {
+            crate::logging::log_display(&warning, crate::logging::LogLevel::Warn);
+            crate::warnings::record(crate::warnings::WarningCategory::Observer, warning);
+        }
+    }
+
+    /// Swap in an empty footprint so the next invocation's accesses can be
+    /// told apart from everything recorded before it.
+    fn begin_write_capture(&self) -> Option<Footprint> {
+        self.env
+            .host()
+            .with_mut_storage(|storage| Ok(std::mem::take(&mut storage.footprint)))
+            .ok()
+    }
+
+    /// Collect the keys the invocation wrote, and its own footprint, and merge
+    /// its accesses back into the prior footprint, so [`Self::finish`] still
+    /// covers the session.
+    ///
+    /// Unlike a snapshot diff this sees writes that stored the value a key
+    /// already held. Instance storage is one ledger entry, so a write to any
+    /// instance key marks the whole instance as written.
+    fn end_write_capture(
+        &self,
+        prior: Option<Footprint>,
+    ) -> (HashSet<String>, InvocationFootprint) {
+        let Some(prior) = prior else {
+            return Default::default();
+        };
+        let host = self.env.host();
+        let budget = host.as_budget();
+        let mut cache = self.render_cache.borrow_mut();
+        host.with_mut_storage(|storage| {
+            let invocation = std::mem::replace(&mut storage.footprint, prior);
+            let mut written = HashSet::new();
+            let footprint = InvocationFootprint::from_accesses(
+                (&invocation.0)
+                    .into_iter()
+                    .map(|(key, access)| (&**key, *access)),
+            );
+            for (key, access) in &invocation.0 {
+                let is_write = matches!(access, AccessType::ReadWrite);
+                if is_write {
+                    written.insert(StorageInspector::render_ledger_key(key, &mut cache));
+                }
+                if is_write
+                    || !storage
+                        .footprint
+                        .0
+                        .contains_key::<std::rc::Rc<LedgerKey>>(key, budget)?
+                {
+                    storage.footprint.0 =
+                        storage.footprint.0.insert(key.clone(), *access, budget)?;
+                }
+            }
+            Ok((written, footprint))
+        })
+        .unwrap_or_else(|e| {
+            tracing::warn!("Failed to collect storage writes: {:?}", e);
+            Default::default()
+        })
+    }
+
+    /// The entries inside contract instances, keyed `instance:<key>`.
+    pub fn instance_storage_snapshot(&self) -> HashMap<String, String> {
+        StorageInspector::capture_instance_with_cache(
+            self.env.host(),
+            &mut self.render_cache.borrow_mut(),
+        )
+    }
+
+    /// Every storage entry with the storage it lives in, instance storage
+    /// listed by key.
+    pub fn storage_entries(&self) -> HashMap<String, StorageEntry> {
+        StorageInspector::capture_entries_with_cache(
+            self.env.host(),
+            &mut self.render_cache.borrow_mut(),
+        )
+    }
+
+    /// Attribute the keys the invocation wrote, and those inside the contract
+    /// instance it changed, to the call just recorded.
+    fn record_provenance(
+        &mut self,
+        function: &str,
+        storage_before: &HashMap<String, String>,
+        instance_before: &HashMap<String, String>,
+        storage_after: &HashMap<String, String>,
+    ) {
+        let writer = Writer {
+            step: self.invocations,
+            function: function.to_string(),
+            sequence: self
+                .debug_env
+                .function_calls()
+                .last()
+                .map_or(0, |call| call.sequence),
+        };
+        let instance_after = self.instance_storage_snapshot();
+        self.storage_provenance.record_invocation(
+            &writer,
+            storage_before,
+            storage_after,
+            &self.last_storage_writes,
+        );
+        self.storage_provenance.record_invocation(
+            &writer,
+            instance_before,
+            &instance_after,
+            &HashSet::new(),
+        );
+    }
+
+    /// The contract each storage key belongs to, see
+    /// [`StorageInspector::capture_owners_with_cache`].
+    fn storage_owners(&self) -> HashMap<String, String> {
+        StorageInspector::capture_owners_with_cache(
+            self.env.host(),
+            &mut self.render_cache.borrow_mut(),
+        )
+    }
+
+    /// Add the writes of the invocation just recorded to the storage
+    /// history, each on the call that made it, read from the host events
+    /// after the first `events_before`.
+    fn record_history(
+        &mut self,
+        function: &str,
+        writes: &[StorageWrite],
+        instance_before: &HashMap<String, String>,
+        mut owners: HashMap<String, String>,
+        events_before: usize,
+    ) {
+        let instance_after = self.instance_storage_snapshot();
+        let mut writes = writes.to_vec();
+        writes.extend(storage_writes(
+            instance_before,
+            &instance_after,
+            &HashSet::new(),
+        ));
+        owners.extend(self.storage_owners());
+        let events =
+            crate::inspector::event_export::host_events(self.env.host()).unwrap_or_else(|e| {
+                tracing::warn!("Failed to read events for the storage history: {:?}", e);
+                Vec::new()
+            });
+        let calls: Vec<CallStart> =
+            invocation_notifications(events.get(events_before..).unwrap_or_default(), Vec::new())
+                .into_iter()
+                .filter_map(|notification| match notification {
+                    Notification::CallStart(call) => Some(call),
+                    _ => None,
+                })
+                .collect();
+        let top = CallStart {
+            depth: 0,
+            contract: ScAddress::from(&self.contract_address).to_string(),
+            function: function.to_string(),
+            args: serde_json::Value::Null,
+        };
+        if let Some(history) = self.storage_history.as_mut() {
+            history.record_invocation(self.invocations, &top, &calls, &writes, &owners);
+        }
+    }
+
     /// Track storage changes by comparing before and after snapshots
     fn track_storage_changes(
         &mut self,
         storage_before: &HashMap<String, String>,
         storage_after: &HashMap<String, String>,
+        writes: &[StorageWrite],
     ) {
-        // Track writes (new or modified entries)
-        for (key, value) in storage_after {
-            if !storage_before.contains_key(key) {
-                // New write
-                self.debug_env.track_storage_write(key, value);
-            } else if storage_before.get(key) != Some(value) {
-                // Modified write
-                self.debug_env.track_storage_write(key, value);
-            }
+        // The trace records writes as an observer, like embedders' hooks.
+        for write in writes {
+            let _ = self.debug_env.on_storage_write(write);
         }
 
         // Track reads by checking which keys existed before
@@ -187,6 +1011,9 @@ impl ContractExecutor {
                 self.debug_env.track_storage_read(key);
             }
         }
+
+        self.storage_heatmap
+            .record_invocation(storage_before, storage_after);
     }
 
     // â”€â”€ accessors â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€â”€
@@ -195,6 +1022,44 @@ impl ContractExecutor {
         self.last_execution.as_ref()
     }
 
+    /// The decoded return value of the last invocation, if it succeeded.
+    pub fn last_result(&self) -> Option<ExecutionResult> {
+        let record = self.last_execution.as_ref()?;
+        let raw = record.result.as_ref().ok()?;
+        Some(ExecutionResult::new(
+            crate::runtime::result::display_value(raw),
+            raw.clone(),
+        ))
+    }
+
+    /// Storage keys written by the last invocation, rendered like
+    /// [`Self::get_storage_snapshot`] keys.
+    pub fn last_storage_writes(&self) -> &HashSet<String> {
+        &self.last_storage_writes
+    }
+
+    /// Read-only and read-write ledger entries of the last invocation.
+    pub fn last_footprint(&self) -> &InvocationFootprint {
+        &self.last_footprint
+    }
+
+    /// Which invocation last wrote each storage key.
+    pub fn storage_provenance(&self) -> &StorageProvenance {
+        &self.storage_provenance
+    }
+
+    /// Record every storage write from the next invocation on, see
+    /// [`Self::storage_history`].
+    pub fn track_writes(&mut self) {
+        self.storage_history.get_or_insert_with(StorageHistory::new);
+    }
+
+    /// Every storage write since [`Self::track_writes`], in order; `None`
+    /// when writes are not tracked.
+    pub fn storage_history(&self) -> Option<&StorageHistory> {
+        self.storage_history.as_ref()
+    }
+
     pub fn last_memory_summary(&self) -> Option<&MemorySummary> {
         self.last_memory_summary.as_ref()
     }
@@ -208,13 +1073,6 @@ impl ContractExecutor {
     }
 
     pub fn set_initial_storage(&mut self, storage_json: String) -> Result<()> {
-        #[derive(Debug, Clone, Copy)]
-        enum Durability {
-            Instance,
-            Persistent,
-            Temporary,
-        }
-
         fn is_typed_annotation(value: &serde_json::Value) -> bool {
             matches!(
                 value,
@@ -264,51 +1122,32 @@ impl ContractExecutor {
             }
         }
 
-        fn parse_one_val(env: &Env, value: &serde_json::Value) -> Result<soroban_sdk::Val> {
-            let parser = ArgumentParser::new(env.clone());
-            let json = serde_json::to_string(value).map_err(|e| {
-                DebuggerError::StorageError(format!("Failed to serialize storage JSON value: {e}"))
-            })?;
-            let mut vals = parser.parse_args_string(&json).map_err(|e| {
-                DebuggerError::StorageError(format!("Failed to parse storage value: {e}"))
-            })?;
-            if vals.len() != 1 {
-                return Err(DebuggerError::StorageError(format!(
-                    "Storage entry must resolve to exactly 1 value, got {}",
-                    vals.len()
-                ))
-                .into());
-            }
-            Ok(vals.remove(0))
-        }
-
-        fn parse_durability(raw: Option<&serde_json::Value>) -> Result<Durability> {
-            let Some(v) = raw else {
-                return Ok(Durability::Instance);
-            };
-            let Some(s) = v.as_str() else {
-                return Err(DebuggerError::StorageError(
-                    "durability must be a string: instance|persistent|temporary".to_string(),
-                )
-                .into());
-            };
-            match s {
-                "instance" => Ok(Durability::Instance),
-                "persistent" => Ok(Durability::Persistent),
-                "temporary" => Ok(Durability::Temporary),
-                other => Err(DebuggerError::StorageError(format!(
-                    "Unsupported durability '{other}'. Use instance|persistent|temporary."
-                ))
-                .into()),
-            }
+        /// Name the entry a conversion failed for, so a bad seed points at its key.
+        fn for_key(key: &str, e: miette::Report) -> miette::Report {
+            let e = e.to_string();
+            let reason = e.strip_prefix("Storage error: ").unwrap_or(&e);
+            DebuggerError::StorageError(format!("--storage key {key}: {reason}")).into()
         }
 
         info!("Setting initial storage");
         let root: serde_json::Value = serde_json::from_str(&storage_json).map_err(|e| {
             DebuggerError::StorageError(format!("Failed to parse initial storage JSON: {e}"))
         })?;
+        // A `--dump-storage` file seeds the entries it lists.
+        let root = if crate::inspector::storage_dump::is_dump(&root) {
+            serde_json::Value::Array(crate::inspector::storage_dump::dump_entries(root)?)
+        } else {
+            root
+        };
 
+        // Keys and values may name the contract's structs, unions and enums.
+        let artifacts = self.artifacts()?;
+        let parser = crate::utils::ArgumentParser::new(self.env.clone())
+            .with_types(artifacts.types.iter().cloned());
         let mut entries: Vec<(Durability, soroban_sdk::Val, soroban_sdk::Val)> = Vec::new();
+        // Ledgers persistent and temporary entries live until, as
+        // `--dump-storage` writes them.
+        let mut lifetimes: Vec<(Durability, soroban_sdk::Val, u32)> = Vec::new();
 
         match root {
             serde_json::Value::Object(map) => {
@@ -323,9 +1162,11 @@ impl ContractExecutor {
 
                 for (k, v) in map {
                     let key_json = serde_json::json!({ "type": "symbol", "value": k });
-                    let key_val = parse_one_val(&self.env, &key_json)?;
-                    let value_json = normalize_numbers(&v)?;
-                    let value_val = parse_one_val(&self.env, &value_json)?;
+                    let key_val =
+                        parse_typed_val_with(&parser, &key_json).map_err(|e| for_key(&k, e))?;
+                    let value_val = normalize_numbers(&v)
+                        .and_then(|value_json| parse_typed_val_with(&parser, &value_json))
+                        .map_err(|e| for_key(&k, e))?;
                     entries.push((Durability::Instance, key_val, value_val));
                 }
             }
@@ -333,12 +1174,12 @@ impl ContractExecutor {
                 for item in arr {
                     let serde_json::Value::Object(obj) = item else {
                         return Err(DebuggerError::StorageError(
-                            "Storage list entries must be objects with {key,value[,durability]}"
+                            "Storage list entries must be objects with {key,value[,durability][,live_until]}"
                                 .to_string(),
                         )
                         .into());
                     };
-                    let durability = parse_durability(obj.get("durability"))?;
+                    let durability = Durability::from_json(obj.get("durability"))?;
                     let Some(key) = obj.get("key") else {
                         return Err(DebuggerError::StorageError(
                             "Storage entry is missing required field 'key'".to_string(),
@@ -352,9 +1193,29 @@ impl ContractExecutor {
                         .into());
                     };
 
-                    let key_val = parse_one_val(&self.env, key)?;
-                    let value_json = normalize_numbers(value)?;
-                    let value_val = parse_one_val(&self.env, &value_json)?;
+                    let name = key.to_string();
+                    let key_val =
+                        parse_typed_val_with(&parser, key).map_err(|e| for_key(&name, e))?;
+                    let value_val = normalize_numbers(value)
+                        .and_then(|value_json| parse_typed_val_with(&parser, &value_json))
+                        .map_err(|e| for_key(&name, e))?;
+                    if let Some(live_until) = obj.get("live_until") {
+                        let Some(live_until) =
+                            live_until.as_u64().and_then(|n| u32::try_from(n).ok())
+                        else {
+                            return Err(DebuggerError::StorageError(format!(
+                                "--storage key {name}: live_until must be a ledger sequence number, got {live_until}"
+                            ))
+                            .into());
+                        };
+                        if durability == Durability::Instance {
+                            return Err(DebuggerError::StorageError(format!(
+                                "--storage key {name}: live_until applies to persistent and temporary entries; instance storage lives as long as the contract instance"
+                            ))
+                            .into());
+                        }
+                        lifetimes.push((durability, key_val, live_until));
+                    }
                     entries.push((durability, key_val, value_val));
                 }
             }
@@ -366,6 +1227,7 @@ impl ContractExecutor {
             }
         }
 
+        self.carry_setup_budget();
         let contract_address = self.contract_address.clone();
         self.env.as_contract(&contract_address, || {
             for (durability, key_val, value_val) in entries {
@@ -383,27 +1245,85 @@ impl ContractExecutor {
             }
         });
 
+        for (durability, key_val, live_until) in lifetimes {
+            self.set_live_until(durability, key_val, live_until)?;
+        }
+
         Ok(())
     }
+
+    /// Opening a contract frame at the top level resets the host budget:
+    /// carry what setup spent so far and measure the rest from zero.
+    fn carry_setup_budget(&mut self) {
+        (self.budget_mark, self.setup_carry) = self.setup_budget_across_frame();
+    }
+
+    /// The budget mark and setup carry to measure setup with once a contract
+    /// frame is opened now; see [`Self::carry_setup_budget`].
+    fn setup_budget_across_frame(&self) -> (BudgetInfo, BudgetInfo) {
+        if self.env.host().has_frame().unwrap_or(false) {
+            return (self.budget_mark.clone(), self.setup_carry.clone());
+        }
+        let spent = BudgetInspector::get_cpu_usage(self.env.host());
+        let carry = spent.delta_from(&self.budget_mark).plus(&self.setup_carry);
+        let mark = BudgetInfo {
+            cpu_instructions: 0,
+            memory_bytes: 0,
+            ..spent
+        };
+        (mark, carry)
+    }
+
+    /// Make the contract data entry under `key` live until `live_until`, or
+    /// the current ledger if that has passed.
+    fn set_live_until(&self, durability: Durability, key: Val, live_until: u32) -> Result<()> {
+        let host = self.env.host();
+        let key = ScVal::try_from_val(host, &key).map_err(|e| {
+            DebuggerError::StorageError(format!("Failed to convert storage key: {:?}", e))
+        })?;
+        let durability = match durability {
+            Durability::Temporary => soroban_sdk::xdr::ContractDataDurability::Temporary,
+            _ => soroban_sdk::xdr::ContractDataDurability::Persistent,
+        };
+        let ledger_key = std::rc::Rc::new(LedgerKey::ContractData(
+            soroban_sdk::xdr::LedgerKeyContractData {
+                contract: ScAddress::from(&self.contract_address),
+                key,
+                durability,
+            },
+        ));
+        let live_until = live_until.max(self.env.ledger().sequence());
+        host.with_mut_storage(|storage| {
+            let entry = match storage
+                .map
+                .get::<std::rc::Rc<LedgerKey>>(&ledger_key, host.as_budget())?
+            {
+                Some(Some((entry, _))) => entry.clone(),
+                _ => return Ok(()),
+            };
+            storage.map = storage.map.insert(
+                ledger_key,
+                Some((entry, Some(live_until))),
+                host.as_budget(),
+            )?;
+            Ok(())
+        })
+        .map_err(|e| {
+            DebuggerError::StorageError(format!("Failed to set storage TTL: {}", e)).into()
+        })
+    }
     /// Apply ledger metadata (sequence, timestamp, network ID) from a network snapshot.
     pub fn apply_snapshot_ledger(
         &mut self,
         snapshot: &crate::simulator::LoadedSnapshot,
     ) -> Result<()> {
-        use sha2::{Digest, Sha256};
-
         let seq = snapshot.ledger_sequence();
         let ts = snapshot.snapshot().ledger.timestamp;
-        let passphrase = snapshot.network_passphrase();
-
-        let mut hasher = Sha256::new();
-        hasher.update(passphrase.as_bytes());
-        let network_id: [u8; 32] = hasher.finalize().into();
-
-        self.env.ledger().with_mut(|l| {
-            l.sequence_number = seq;
-            l.timestamp = ts;
-            l.network_id = network_id;
+        self.apply_ledger_overrides(&LedgerOverrides {
+            timestamp: Some(ts),
+            sequence: Some(seq),
+            network_passphrase: Some(snapshot.network_passphrase().to_string()),
+            ..LedgerOverrides::default()
         });
 
         info!(
@@ -414,6 +1334,101 @@ impl ContractExecutor {
         Ok(())
     }
 
+    /// Set the ledger values `overrides` gives; the others keep their current
+    /// value. Entries already in storage keep their TTL, so moving the sequence
+    /// far ahead can archive them; [`Self::with_ledger`] avoids that for the
+    /// contract itself.
+    pub fn apply_ledger_overrides(&mut self, overrides: &LedgerOverrides) {
+        overrides.apply(&self.env);
+    }
+
+    /// The ledger sequence number calls currently run at.
+    pub fn ledger_sequence(&self) -> u32 {
+        self.env.ledger().sequence()
+    }
+
+    /// The ledger protocol version calls currently run at.
+    pub fn protocol_version(&self) -> u32 {
+        self.env.ledger().protocol_version()
+    }
+
+    /// Register `wasm` at `contract_id` next to the contract under test, so
+    /// calls to that address run it. Error codes it returns are named from its
+    /// spec.
+    pub fn register_contract_at(&mut self, contract_id: &str, wasm: &[u8]) -> Result<()> {
+        let address = catch_unwind(AssertUnwindSafe(|| {
+            Address::from_str(&self.env, contract_id)
+        }))
+        .map_err(|_| {
+            DebuggerError::InvalidArguments(format!("Invalid contract id: {contract_id}"))
+        })?;
+        catch_unwind(AssertUnwindSafe(|| {
+            self.env.register_at(&address, wasm, ());
+        }))
+        .map_err(|_| {
+            DebuggerError::ExecutionError(format!("Failed to register contract {contract_id}"))
+        })?;
+        if let Err(e) = self
+            .error_db
+            .load_linked_errors_from_wasm(contract_id, wasm)
+        {
+            tracing::warn!(
+                "Failed to load custom errors of {} from spec: {}",
+                contract_id,
+                e
+            );
+        }
+        Ok(())
+    }
+
+    /// Register `wasm` as a further contract called `name`, at a fresh address
+    /// next to the contract under test. Arguments can pass it as
+    /// `{"type": "contract", "value": name}`, and its storage and events are
+    /// labelled with `name`.
+    pub fn link_contract(&mut self, name: &str, wasm: &[u8]) -> Result<Address> {
+        if name.is_empty() {
+            return Err(DebuggerError::InvalidArguments(
+                "Linked contract name must not be empty".to_string(),
+            )
+            .into());
+        }
+        if self.linked.contains_key(name) {
+            return Err(DebuggerError::InvalidArguments(format!(
+                "Contract '{name}' is linked more than once"
+            ))
+            .into());
+        }
+        let address =
+            catch_unwind(AssertUnwindSafe(|| self.env.register(wasm, ()))).map_err(|_| {
+                DebuggerError::ExecutionError(format!("Failed to register contract '{name}'"))
+            })?;
+        self.render_cache
+            .borrow_mut()
+            .set_contract_label(ScAddress::from(&address), name);
+        if let Err(e) = self.error_db.load_linked_errors_from_wasm(name, wasm) {
+            tracing::warn!(
+                "Failed to load custom errors of '{}' from spec: {}",
+                name,
+                e
+            );
+        }
+        self.linked.insert(name.to_string(), address.clone());
+        Ok(address)
+    }
+
+    /// Contracts registered with [`Self::link_contract`], by name.
+    pub fn linked_contracts(&self) -> &BTreeMap<String, Address> {
+        &self.linked
+    }
+
+    /// The name of each linked contract, by contract ID.
+    pub fn contract_names(&self) -> HashMap<String, String> {
+        self.linked
+            .iter()
+            .map(|(name, address)| (ScAddress::from(address).to_string(), name.clone()))
+            .collect()
+    }
+
     pub fn set_mock_specs(&mut self, specs: &[String]) -> Result<()> {
         let registry = MockRegistry::from_cli_specs(&self.env, specs)?;
         self.set_mock_registry(registry)
@@ -428,19 +1443,32 @@ impl ContractExecutor {
             .map(|r| r.calls().to_vec())
             .unwrap_or_default()
     }
+    pub fn get_mock_summary(&self) -> Vec<MockSummary> {
+        self.mock_registry
+            .lock()
+            .map(|r| r.summary())
+            .unwrap_or_default()
+    }
     pub fn get_instruction_counts(&self) -> Result<InstructionCounts> {
         let mut function_counts: Vec<(String, u64)> = self
             .per_function_cpu
             .iter()
             .map(|(name, &cpu)| (name.clone(), cpu))
             .collect();
-        function_counts.sort_by(|a, b| b.1.cmp(&a.1));
+        function_counts.sort_by_key(|a| std::cmp::Reverse(a.1));
         let total = function_counts.iter().map(|(_, c)| c).sum();
         Ok(InstructionCounts {
             function_counts,
             total,
         })
     }
+    pub fn call_costs(&self) -> &[(String, u64)] {
+        &self.call_costs
+    }
+
+    pub fn storage_heatmap(&self) -> &StorageHeatmap {
+        &self.storage_heatmap
+    }
     pub fn host(&self) -> &Host {
         self.env.host()
     }
@@ -448,14 +1476,111 @@ impl ContractExecutor {
         crate::inspector::auth::AuthInspector::get_auth_tree(&self.env)
     }
     pub fn get_events(&self) -> Result<Vec<crate::inspector::events::ContractEvent>> {
-        crate::inspector::events::EventInspector::get_events(self.env.host())
+        crate::inspector::events::EventInspector::get_events_with_cache(
+            self.env.host(),
+            &mut self.render_cache.borrow_mut(),
+        )
     }
     pub fn get_storage_snapshot(&self) -> Result<HashMap<String, String>> {
-        Ok(crate::inspector::storage::StorageInspector::capture_snapshot(self.env.host()))
+        Ok(StorageInspector::capture_snapshot_with_cache(
+            self.env.host(),
+            &mut self.render_cache.borrow_mut(),
+        ))
     }
     pub fn get_ledger_snapshot(&self) -> Result<soroban_ledger_snapshot::LedgerSnapshot> {
         Ok(self.env.to_ledger_snapshot())
     }
+
+    /// Capture ledger entries, ledger info and address generators so the
+    /// environment can be rebuilt later with [`Self::restore_env_snapshot`].
+    pub fn env_snapshot(&self) -> Snapshot {
+        self.env.to_snapshot()
+    }
+
+    /// Replace the environment with one loaded from a ledger snapshot, and
+    /// point the executor at the contract already deployed at `contract`.
+    ///
+    /// Used to replay an on-chain invocation: the snapshot carries the
+    /// contract's instance and code entries, so nothing is registered.
+    pub fn load_ledger_snapshot(
+        &mut self,
+        snapshot: soroban_ledger_snapshot::LedgerSnapshot,
+        contract: &ScAddress,
+    ) -> Result<()> {
+        let env = Env::from_ledger_snapshot(snapshot);
+        env.host()
+            .set_diagnostic_level(DiagnosticLevel::Debug)
+            .map_err(|e| {
+                DebuggerError::ExecutionError(format!("Failed to set diagnostic level: {:?}", e))
+            })?;
+        self.contract_address = Address::try_from_val(&env, contract).map_err(|e| {
+            DebuggerError::ExecutionError(format!(
+                "Failed to resolve contract address {}: {:?}",
+                contract, e
+            ))
+        })?;
+        self.budget_mark = BudgetInspector::get_cpu_usage(env.host());
+        self.setup_carry = BudgetInfo::default();
+        self.env = env;
+        // A contract deployed in the snapshot was constructed on chain.
+        if self.budget_limits.is_some() {
+            self.reset_budget();
+        }
+        self.constructed = self.constructed || self.is_registered(contract);
+        self.install_mock_dispatchers()
+    }
+
+    /// Replace the environment with one rebuilt from `snapshot`.
+    ///
+    /// The contract keeps its address. The rebuilt environment starts with an
+    /// empty event log and without auth mocking.
+    pub fn restore_env_snapshot(&mut self, snapshot: &Snapshot) -> Result<()> {
+        let env = Env::from_snapshot(snapshot.clone());
+        Self::load_snapshot_entries(&env, snapshot)?;
+        env.host()
+            .set_diagnostic_level(DiagnosticLevel::Debug)
+            .map_err(|e| {
+                DebuggerError::ExecutionError(format!("Failed to set diagnostic level: {:?}", e))
+            })?;
+        let address = ScAddress::from(&self.contract_address);
+        self.contract_address = Address::try_from_val(&env, &address).map_err(|e| {
+            DebuggerError::ExecutionError(format!(
+                "Failed to move contract address into restored environment: {:?}",
+                e
+            ))
+        })?;
+        self.budget_mark = BudgetInspector::get_cpu_usage(env.host());
+        self.setup_carry = BudgetInfo::default();
+        self.env = env;
+        // A contract constructed in an earlier session is not constructed again.
+        if self.budget_limits.is_some() {
+            self.reset_budget();
+        }
+        self.constructed = self.constructed || self.is_registered(&address);
+        self.install_mock_dispatchers()
+    }
+
+    /// Load every entry of `snapshot` into the host's storage. The host reads
+    /// an entry from the snapshot only once a call touches it, so storage
+    /// listings of a restored environment would miss the rest.
+    fn load_snapshot_entries(env: &Env, snapshot: &Snapshot) -> Result<()> {
+        let host = env.host();
+        host.with_mut_storage(|storage| {
+            for (key, (entry, live_until)) in &snapshot.ledger.ledger_entries {
+                storage.map = storage.map.insert(
+                    std::rc::Rc::new((**key).clone()),
+                    Some((std::rc::Rc::new((**entry).clone()), *live_until)),
+                    host.as_budget(),
+                )?;
+            }
+            Ok(())
+        })
+        .map_err(|e| {
+            DebuggerError::ExecutionError(format!("Failed to load snapshot entries: {:?}", e))
+                .into()
+        })
+    }
+
     pub fn finish(
         &mut self,
     ) -> Result<(
@@ -554,8 +1679,9 @@ impl ContractExecutor {
             });
         }
 
-        let mut next_sequence = out.iter().map(|e| e.sequence).max().map_or(0, |n| n + 1);
-        for event in self.get_diagnostic_events().unwrap_or_default() {
+        let first_sequence = out.iter().map(|e| e.sequence).max().map_or(0, |n| n + 1);
+        let events = self.get_diagnostic_events().unwrap_or_default();
+        for (next_sequence, event) in (first_sequence..).zip(events) {
             let message = format!("{:?}", event);
             out.push(DynamicTraceEvent {
                 sequence: next_sequence,
@@ -568,7 +1694,6 @@ impl ContractExecutor {
                 storage_value: None,
                 address: None,
             });
-            next_sequence += 1;
         }
 
         out.sort_by_key(|e| e.sequence);
@@ -588,6 +1713,7 @@ impl ContractExecutor {
             let address = self.parse_contract_address(&contract_id)?;
             let dispatcher =
                 MockContractDispatcher::new(contract_id.clone(), Arc::clone(&self.mock_registry))
+                    .with_timeout_progress(Arc::clone(&self.timeout_progress))
                     .boxed();
             self.env
                 .host()
@@ -613,25 +1739,123 @@ impl ContractExecutor {
     }
 }
 
+/// The report a timed-out invocation prints, shared with the watchdog.
+pub(crate) type TimeoutProgress = Arc<Mutex<Option<TimeoutReport>>>;
+
+/// What the session had done at the last point of an invocation where the
+/// host could be read. The host belongs to the thread running the call, so
+/// the watchdog cannot read it: the report is taken when the call is
+/// dispatched and refreshed whenever the call reaches a mocked contract.
+#[derive(Debug, Clone)]
+pub(crate) struct TimeoutReport {
+    budget: BudgetInfo,
+    events: usize,
+    writes: Vec<String>,
+    /// The point of the call the figures were read at.
+    as_of: String,
+    started: Instant,
+    taken: Instant,
+}
+
+impl TimeoutReport {
+    /// Read the figures again from `host`, which is at `as_of`. Keys the
+    /// running invocation wrote are added to the ones written before it.
+    pub(crate) fn refresh(&mut self, host: &Host, as_of: String) {
+        self.budget = BudgetInspector::get_cpu_usage(host);
+        self.events = contract_event_count(host);
+        let mut cache = RenderCache::new();
+        // The invocation's own footprint, swapped in when it was dispatched.
+        let written = host.with_mut_storage(|storage| {
+            Ok((&storage.footprint.0)
+                .into_iter()
+                .filter(|(_, access)| matches!(access, AccessType::ReadWrite))
+                .map(|(key, _)| StorageInspector::render_ledger_key(key, &mut cache))
+                .collect::<Vec<_>>())
+        });
+        for key in written.unwrap_or_default() {
+            if !self.writes.contains(&key) {
+                self.writes.push(key);
+            }
+        }
+        self.writes.sort();
+        self.as_of = as_of;
+        self.taken = Instant::now();
+    }
+
+    fn lines(&self) -> Vec<String> {
+        let writes = if self.writes.is_empty() {
+            "none".to_string()
+        } else {
+            self.writes.join(", ")
+        };
+        vec![
+            format!(
+                "Diagnostics as of {}, {} ms into the call:",
+                self.as_of,
+                self.taken.duration_since(self.started).as_millis()
+            ),
+            format!("  {}", BudgetInspector::format_footer(&self.budget)),
+            format!("  Events emitted: {}", self.events),
+            format!("  Storage keys written: {}", writes),
+        ]
+    }
+}
+
+/// Contract events among the host's events so far.
+fn contract_event_count(host: &Host) -> usize {
+    crate::inspector::event_export::host_events(host).map_or(0, |events| {
+        events
+            .iter()
+            .filter(|e| e.event.type_ == ContractEventType::Contract)
+            .count()
+    })
+}
+
+/// Ends the process with exit code 124 if an invocation is still running
+/// after the timeout. A call stuck in the host cannot be interrupted, so the
+/// report is printed from the watchdog thread before exiting.
 struct ExecutionTimeoutWatchdog {
     done_tx: Option<std::sync::mpsc::Sender<()>>,
 }
 
 impl ExecutionTimeoutWatchdog {
-    fn start(timeout_secs: u64) -> Self {
+    fn start(
+        timeout_secs: u64,
+        function: &str,
+        ring: Arc<Mutex<TraceRing>>,
+        progress: TimeoutProgress,
+    ) -> Self {
         if timeout_secs == 0 {
             return Self { done_tx: None };
         }
 
+        let function = function.to_string();
         let (tx, rx) = std::sync::mpsc::channel::<()>();
         std::thread::spawn(move || {
             match rx.recv_timeout(std::time::Duration::from_secs(timeout_secs)) {
                 Ok(()) | Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {}
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
                     eprintln!(
-                    "Execution timed out after {} seconds. Aborting with exit code 124. Use --timeout to adjust.",
-                    timeout_secs
-                );
+                        "Error: execution exceeded {}s timeout in function {}",
+                        timeout_secs, function
+                    );
+                    if let Ok(progress) = progress.lock() {
+                        for line in progress.iter().flat_map(TimeoutReport::lines) {
+                            eprintln!("{}", line);
+                        }
+                    }
+                    if let Ok(mut ring) = ring.lock() {
+                        if ring.is_enabled() {
+                            ring.push(RingRecord::TimedOut {
+                                function,
+                                seconds: timeout_secs,
+                            });
+                            for line in ring.report() {
+                                eprintln!("{}", line);
+                            }
+                        }
+                    }
+                    eprintln!("Aborting with exit code 124. Use --timeout to adjust, or --timeout 0 to disable it.");
                     std::process::exit(124);
                 }
             }
@@ -763,4 +1987,53 @@ mod tests {
         assert_eq!(debug_env.get_key_writes("key1").len(), 1);
         assert_eq!(debug_env.get_key_writes("key2").len(), 1);
     }
+
+    #[test]
+    fn test_timeout_report_says_when_it_was_taken() {
+        let started = Instant::now();
+        let report = TimeoutReport {
+            budget: BudgetInfo {
+                cpu_instructions: 1_500,
+                cpu_limit: 100_000,
+                memory_bytes: 2048,
+                memory_limit: 1 << 20,
+            },
+            events: 2,
+            writes: vec!["balance".to_string(), "owner".to_string()],
+            as_of: "the start of the call".to_string(),
+            started,
+            taken: started + Duration::from_millis(250),
+        };
+        let lines = report.lines();
+        assert_eq!(
+            lines[0],
+            "Diagnostics as of the start of the call, 250 ms into the call:"
+        );
+        assert!(lines[1].starts_with("  Budget: 1,500 of 100,000 insns (1.5% of limit)"));
+        assert_eq!(lines[2], "  Events emitted: 2");
+        assert_eq!(lines[3], "  Storage keys written: balance, owner");
+    }
+
+    #[test]
+    fn test_timeout_report_refresh_reads_the_host_again() {
+        let env = Env::default();
+        let started = Instant::now();
+        let mut report = TimeoutReport {
+            budget: BudgetInfo {
+                cpu_instructions: 0,
+                cpu_limit: 0,
+                memory_bytes: 0,
+                memory_limit: 0,
+            },
+            events: 0,
+            writes: vec!["owner".to_string()],
+            as_of: "the start of the call".to_string(),
+            started,
+            taken: started,
+        };
+        report.refresh(env.host(), "the mocked call to C.price".to_string());
+        assert!(report.budget.cpu_limit > 0);
+        assert_eq!(report.writes, ["owner"]);
+        assert!(report.lines()[0].starts_with("Diagnostics as of the mocked call to C.price, "));
+    }
 }
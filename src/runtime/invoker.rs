@@ -7,25 +7,39 @@
 //! - Post-invocation result formatting via [`super::result`].
 
 use crate::debugger::error_db::ErrorDatabase;
-use crate::inspector::budget::{BudgetInspector, MemoryTracker};
+use crate::inspector::budget::{BudgetInfo, BudgetInspector, MemoryTracker};
 use crate::runtime::result::{format_invocation_result, ExecutionRecord};
 use crate::{DebuggerError, Result};
 use indicatif::{ProgressBar, ProgressStyle};
 use soroban_env_host::xdr::ScVal;
+use soroban_env_host::ConversionError;
 use soroban_env_host::TryFromVal; // needed for ScVal::try_from_val
 use soroban_sdk::{Address, Env, InvokeError, Symbol, Val, Vec as SorobanVec};
 use std::collections::HashMap;
 use tracing::info;
 
 /// Invoke `function` on the already-registered contract at `contract_address`.
+///
+/// Budget spent between `setup_start` and the dispatch into the contract
+/// (argument conversion, storage seeding), plus `setup_carried` from before
+/// the host last reset its budget, is reported as setup, separately from the
+/// contract's own execution.
+///
+/// With `constructor_wasm`, `function` is the `__constructor` of a contract
+/// not registered yet: the call registers that code at `contract_address`,
+/// which runs the constructor with the arguments.
 #[tracing::instrument(skip_all, fields(function = function))]
+#[allow(clippy::too_many_arguments)]
 pub fn invoke_function(
     env: &Env,
     contract_address: &Address,
     error_db: &ErrorDatabase,
     function: &str,
     parsed_args: Vec<Val>,
+    setup_start: &BudgetInfo,
+    setup_carried: &BudgetInfo,
     _timeout_secs: u64,
+    constructor_wasm: Option<&[u8]>,
     storage_fn: impl Fn() -> Result<HashMap<String, String>>,
 ) -> Result<(String, ExecutionRecord)> {
     info!("Executing function: {}", function);
@@ -75,8 +89,19 @@ pub fn invoke_function(
 
     // ── The actual call ───────────────────────────────────────────────────────
     let budget_before = BudgetInspector::get_cpu_usage(env.host());
-    let invocation_result =
-        env.try_invoke_contract::<Val, InvokeError>(contract_address, &func_symbol, args_vec);
+    // The host resets its budget when a top-level invocation starts, so only
+    // a call made from inside another frame is measured from `budget_before`.
+    let top_level = !env.host().has_frame().unwrap_or(false);
+    let invocation_result = match constructor_wasm {
+        Some(wasm) => construct(env, contract_address, wasm, args_vec),
+        // The host escalates errors it cannot hand back as a result, such as
+        // an exhausted budget, to a panic.
+        None => std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            env.try_invoke_contract::<Val, InvokeError>(contract_address, &func_symbol, args_vec)
+        }))
+        .unwrap_or(Err(Ok(InvokeError::Abort))),
+    };
+    let budget_after = BudgetInspector::get_cpu_usage(env.host());
     memory_tracker.record_snapshot(env.host(), "invoke:invoke");
 
     spinner.finish_and_clear();
@@ -91,8 +116,12 @@ pub fn invoke_function(
     memory_tracker.record_snapshot(env.host(), "invoke:result_convert");
 
     // Display budget / memory usage.
-    let budget_after = BudgetInspector::get_cpu_usage(env.host());
-    let execution_budget = budget_after.delta_from(&budget_before);
+    let setup_budget = budget_before.delta_from(setup_start).plus(setup_carried);
+    let execution_budget = if top_level {
+        budget_after
+    } else {
+        budget_after.delta_from(&budget_before)
+    };
     crate::inspector::BudgetInspector::display(env.host());
     let memory_summary = memory_tracker.finalize(env.host());
     memory_summary.display();
@@ -102,9 +131,65 @@ pub fn invoke_function(
         args: sc_args,
         result: record_result,
         budget: execution_budget,
+        setup_budget,
         storage_before,
         storage_after,
     };
 
     display_result.map(|s| (s, record))
 }
+
+/// Register `wasm` at `contract_address` with `args` for its constructor,
+/// with the outcome shaped like that of [`Env::try_invoke_contract`].
+///
+/// Registration reports a failed constructor by panicking with the host
+/// error, so the contract error code, if any, is read back from the message.
+fn construct(
+    env: &Env,
+    contract_address: &Address,
+    wasm: &[u8],
+    args: SorobanVec<Val>,
+) -> std::result::Result<
+    std::result::Result<Val, ConversionError>,
+    std::result::Result<InvokeError, InvokeError>,
+> {
+    let registered = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        env.register_at(contract_address, wasm, args)
+    }));
+    match registered {
+        Ok(_) => Ok(Ok(Val::VOID.to_val())),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<String>()
+                .map(String::as_str)
+                .or_else(|| payload.downcast_ref::<&str>().copied())
+                .unwrap_or_default();
+            Err(Ok(
+                contract_error_code(message).map_or(InvokeError::Abort, InvokeError::Contract)
+            ))
+        }
+    }
+}
+
+/// The code of an `Error(Contract, #N)` in a host error message.
+fn contract_error_code(message: &str) -> Option<u32> {
+    let (_, rest) = message.split_once("Error(Contract, #")?;
+    rest.split(')').next()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contract_error_code_is_read_from_host_errors() {
+        assert_eq!(
+            contract_error_code("HostError: Error(Contract, #3)\n\nEvent log (newest first):"),
+            Some(3)
+        );
+        assert_eq!(
+            contract_error_code("HostError: Error(WasmVm, InvalidAction)"),
+            None
+        );
+    }
+}
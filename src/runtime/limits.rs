@@ -0,0 +1,290 @@
+//! Host budget limits for `run --cpu-limit`, `--mem-limit` and `--limits`.
+//!
+//! The host budget is what stops a transaction on the network once it has
+//! spent its CPU instructions or memory. Limits are either given directly,
+//! taken from a preset, or read from the network's `ContractComputeV0`
+//! config setting over RPC. When a call runs out, [`BudgetExhaustion`] says
+//! which resource it was, how far the call got and where it was.
+
+use crate::inspector::budget::{group_digits, BudgetInfo, BudgetInspector};
+use crate::rpc::RpcClient;
+use crate::runtime::observer::{call_target, symbol};
+use crate::{DebuggerError, Result};
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{
+    ConfigSettingEntry, ConfigSettingId, ContractEventBody, ContractEventType, LedgerEntryData,
+    LedgerKey, LedgerKeyConfigSetting,
+};
+use std::fmt;
+
+/// CPU instructions and memory bytes a run may spend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BudgetLimits {
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+}
+
+impl Default for BudgetLimits {
+    fn default() -> Self {
+        Self::HOST_DEFAULT
+    }
+}
+
+impl BudgetLimits {
+    /// The budget the host starts every environment with.
+    pub const HOST_DEFAULT: Self = Self {
+        cpu_insns: 100_000_000,
+        mem_bytes: 40 * 1024 * 1024,
+    };
+
+    /// Per-transaction limits of mainnet and testnet as of protocol 22. Pass
+    /// an RPC URL to `--limits` to read the values a network has now.
+    pub const NETWORK: Self = Self {
+        cpu_insns: 100_000_000,
+        mem_bytes: 40 * 1024 * 1024,
+    };
+
+    pub const UNLIMITED: Self = Self {
+        cpu_insns: u64::MAX,
+        mem_bytes: u64::MAX,
+    };
+
+    /// Limits for a `--limits` value: `mainnet`, `testnet`, `default`,
+    /// `unlimited`, or the URL of an RPC server to read them from.
+    pub fn preset(spec: &str) -> Result<Self> {
+        match spec {
+            "mainnet" | "testnet" => Ok(Self::NETWORK),
+            "default" => Ok(Self::HOST_DEFAULT),
+            "unlimited" => Ok(Self::UNLIMITED),
+            url if Self::is_rpc_url(url) => Self::from_rpc(url),
+            other => Err(DebuggerError::InvalidFlag(format!(
+                "Unknown --limits preset '{}'; use mainnet, testnet, default, unlimited or an RPC URL",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    /// Whether a `--limits` value names an RPC server rather than a preset.
+    pub fn is_rpc_url(spec: &str) -> bool {
+        spec.starts_with("http://") || spec.starts_with("https://")
+    }
+
+    /// The per-transaction limits the network behind `url` enforces now.
+    pub fn from_rpc(url: &str) -> Result<Self> {
+        let key = LedgerKey::ConfigSetting(LedgerKeyConfigSetting {
+            config_setting_id: ConfigSettingId::ContractComputeV0,
+        });
+        let entries = RpcClient::new(url)?.get_ledger_entries(&[key])?;
+        let compute = entries.into_iter().find_map(|entry| match entry.data {
+            LedgerEntryData::ConfigSetting(ConfigSettingEntry::ContractComputeV0(compute)) => {
+                Some(compute)
+            }
+            _ => None,
+        });
+        let Some(compute) = compute else {
+            return Err(DebuggerError::NetworkError(format!(
+                "{} returned no ContractComputeV0 config setting",
+                url
+            ))
+            .into());
+        };
+        Ok(Self {
+            cpu_insns: u64::try_from(compute.tx_max_instructions).unwrap_or(0),
+            mem_bytes: u64::from(compute.tx_memory_limit),
+        })
+    }
+
+    /// These limits with `--cpu-limit` and `--mem-limit` applied on top.
+    pub fn with_overrides(self, cpu_insns: Option<u64>, mem_bytes: Option<u64>) -> Self {
+        Self {
+            cpu_insns: cpu_insns.unwrap_or(self.cpu_insns),
+            mem_bytes: mem_bytes.unwrap_or(self.mem_bytes),
+        }
+    }
+}
+
+impl fmt::Display for BudgetLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.cpu_insns {
+            u64::MAX => write!(f, "unlimited insns")?,
+            cpu => write!(f, "{} insns", group_digits(cpu))?,
+        }
+        match self.mem_bytes {
+            u64::MAX => write!(f, ", unlimited mem"),
+            mem => write!(f, ", {} mem", BudgetInspector::format_memory_bytes(mem)),
+        }
+    }
+}
+
+/// The limits a run asked for, if any: the `--limits` preset, or the host
+/// default, with `--cpu-limit` and `--mem-limit` on top.
+pub fn requested_limits(
+    preset: Option<&str>,
+    cpu_insns: Option<u64>,
+    mem_bytes: Option<u64>,
+) -> Result<Option<BudgetLimits>> {
+    if preset.is_none() && cpu_insns.is_none() && mem_bytes.is_none() {
+        return Ok(None);
+    }
+    let base = match preset {
+        Some(spec) => BudgetLimits::preset(spec)?,
+        None => BudgetLimits::HOST_DEFAULT,
+    };
+    Ok(Some(base.with_overrides(cpu_insns, mem_bytes)))
+}
+
+/// Budget resource a call ran out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resource {
+    Cpu,
+    Memory,
+}
+
+/// A call that stopped because it spent its budget.
+#[derive(Debug, Clone)]
+pub struct BudgetExhaustion {
+    pub resource: Resource,
+    /// Budget spent when the host stopped the call, against the limits in
+    /// force.
+    pub usage: BudgetInfo,
+    /// `contract.function` of the innermost call still running, from the last
+    /// `fn_call` diagnostic event without a matching `fn_return`.
+    pub last_call: Option<String>,
+}
+
+impl BudgetExhaustion {
+    /// How much the charge that crossed the limit went past it. The host
+    /// stops at that charge, so what the rest of the call would have cost
+    /// is not known.
+    pub fn overshoot(&self) -> u64 {
+        match self.resource {
+            Resource::Cpu => self
+                .usage
+                .cpu_instructions
+                .saturating_sub(self.usage.cpu_limit),
+            Resource::Memory => self
+                .usage
+                .memory_bytes
+                .saturating_sub(self.usage.memory_limit),
+        }
+    }
+}
+
+impl fmt::Display for BudgetExhaustion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (name, spent, limit, unit) = match self.resource {
+            Resource::Cpu => (
+                "CPU",
+                self.usage.cpu_instructions,
+                self.usage.cpu_limit,
+                "instructions",
+            ),
+            Resource::Memory => (
+                "Memory",
+                self.usage.memory_bytes,
+                self.usage.memory_limit,
+                "bytes",
+            ),
+        };
+        write!(
+            f,
+            "{} budget exceeded: stopped at {} of {} {} ({} over)",
+            name,
+            group_digits(spent),
+            group_digits(limit),
+            unit,
+            group_digits(self.overshoot())
+        )?;
+        if let Some(call) = &self.last_call {
+            write!(f, " in {}", call)?;
+        }
+        Ok(())
+    }
+}
+
+/// `contract.function` of the innermost call `events` leave open.
+pub fn running_call(events: &[HostEvent]) -> Option<String> {
+    let mut open = Vec::new();
+    for event in events {
+        if event.event.type_ != ContractEventType::Diagnostic {
+            continue;
+        }
+        let ContractEventBody::V0(body) = &event.event.body;
+        let topics = body.topics.as_slice();
+        match topics.first().and_then(symbol).as_deref() {
+            Some("fn_call") => {
+                let (contract, function) = call_target(topics);
+                open.push(format!("{}.{}", contract, function));
+            }
+            Some("fn_return") => {
+                open.pop();
+            }
+            _ => {}
+        }
+    }
+    open.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_and_overrides() {
+        assert_eq!(
+            BudgetLimits::preset("mainnet").unwrap(),
+            BudgetLimits::NETWORK
+        );
+        assert_eq!(
+            BudgetLimits::preset("unlimited").unwrap().cpu_insns,
+            u64::MAX
+        );
+        let err = BudgetLimits::preset("devnet").unwrap_err();
+        assert!(err.to_string().contains("Unknown --limits preset 'devnet'"));
+
+        assert_eq!(
+            BudgetLimits::HOST_DEFAULT.to_string(),
+            "100,000,000 insns, 40.00 MB mem"
+        );
+        assert_eq!(
+            BudgetLimits::UNLIMITED.to_string(),
+            "unlimited insns, unlimited mem"
+        );
+
+        assert_eq!(requested_limits(None, None, None).unwrap(), None);
+        assert_eq!(
+            requested_limits(Some("testnet"), None, Some(1024)).unwrap(),
+            Some(BudgetLimits {
+                cpu_insns: BudgetLimits::NETWORK.cpu_insns,
+                mem_bytes: 1024,
+            })
+        );
+        assert_eq!(
+            requested_limits(None, Some(5), None).unwrap(),
+            Some(BudgetLimits {
+                cpu_insns: 5,
+                mem_bytes: BudgetLimits::HOST_DEFAULT.mem_bytes,
+            })
+        );
+    }
+
+    #[test]
+    fn exhaustion_names_the_resource_and_the_call() {
+        let exhaustion = BudgetExhaustion {
+            resource: Resource::Cpu,
+            usage: BudgetInfo {
+                cpu_instructions: 1_250,
+                cpu_limit: 1_000,
+                memory_bytes: 10,
+                memory_limit: 100,
+            },
+            last_call: Some("CABC.transfer".to_string()),
+        };
+        assert_eq!(exhaustion.overshoot(), 250);
+        assert_eq!(
+            exhaustion.to_string(),
+            "CPU budget exceeded: stopped at 1,250 of 1,000 instructions (250 over) in CABC.transfer"
+        );
+    }
+}
@@ -3,16 +3,21 @@
 //! This module is responsible for:
 //! - Reading and validating WASM bytes.
 //! - Bootstrapping a [`soroban_sdk::Env`] in debug mode.
-//! - Registering the contract with the host.
+//! - Registering the contract with the host, or leaving that to the first
+//!   call when the contract's `__constructor` takes arguments.
 //! - Loading the custom error catalogue from the contract spec.
 //!
 //! It intentionally has **no** knowledge of argument parsing or invocation
 //! so it can be unit-tested with a minimal WASM fixture.
 
 use crate::debugger::error_db::ErrorDatabase;
+use crate::utils::wasm::FunctionParam;
 use crate::{DebuggerError, Result};
 use indicatif::{ProgressBar, ProgressStyle};
+use sha2::{Digest, Sha256};
 use soroban_env_host::DiagnosticLevel;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::testutils::Ledger as _;
 use soroban_sdk::{Address, Env};
 use tracing::{info, warn};
 
@@ -23,12 +28,150 @@ pub struct LoadedContract {
     pub error_db: ErrorDatabase,
 }
 
+/// The function the host runs when a contract is registered.
+pub const CONSTRUCTOR: &str = "__constructor";
+
+/// Parameters of `wasm`'s `__constructor`, or `None` if it declares none.
+pub fn constructor_params(wasm: &[u8]) -> Result<Option<Vec<FunctionParam>>> {
+    let artifacts = crate::utils::artifact_cache::artifacts_for(wasm)?;
+    if !artifacts.exports.iter().any(|name| name == CONSTRUCTOR) {
+        return Ok(None);
+    }
+    Ok(Some(
+        artifacts
+            .signature(CONSTRUCTOR)
+            .map(|signature| signature.params.clone())
+            .unwrap_or_default(),
+    ))
+}
+
+/// The error for registering a contract whose constructor takes `params`
+/// without any arguments.
+pub fn missing_constructor_args(params: &[FunctionParam]) -> DebuggerError {
+    DebuggerError::InvalidArguments(format!(
+        "The contract declares __constructor({}) and cannot be registered without \
+         arguments; pass them with --constructor-args",
+        params
+            .iter()
+            .map(|param| format!("{}: {}", param.name, param.type_name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
+
+/// Ledger values to run at instead of the test environment's defaults, from
+/// `--ledger-timestamp`, `--ledger-sequence`, `--network-passphrase` and
+/// `--protocol-version`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LedgerOverrides {
+    /// Seconds since the Unix epoch.
+    pub timestamp: Option<u64>,
+    pub sequence: Option<u32>,
+    /// Hashed into the ledger's network ID.
+    pub network_passphrase: Option<String>,
+    /// Must be one of [`supported_protocol_versions`]; see
+    /// [`check_protocol_version`].
+    pub protocol_version: Option<u32>,
+}
+
+impl LedgerOverrides {
+    /// Set the values given on `env`'s ledger; the others are left as they are.
+    pub fn apply(&self, env: &Env) {
+        let network_id: Option<[u8; 32]> = self
+            .network_passphrase
+            .as_ref()
+            .map(|passphrase| Sha256::digest(passphrase.as_bytes()).into());
+        env.ledger().with_mut(|l| {
+            if let Some(sequence) = self.sequence {
+                l.sequence_number = sequence;
+            }
+            if let Some(timestamp) = self.timestamp {
+                l.timestamp = timestamp;
+            }
+            if let Some(network_id) = network_id {
+                l.network_id = network_id;
+            }
+            if let Some(protocol_version) = self.protocol_version {
+                l.protocol_version = protocol_version;
+            }
+        });
+    }
+}
+
+/// The first protocol version with Soroban.
+const FIRST_SOROBAN_PROTOCOL: u32 = 20;
+
+/// Ledger protocol versions the linked host accepts, oldest first.
+///
+/// The host does not export its bounds, so each version from the first
+/// Soroban protocol up to the one a fresh environment starts at is tried on a
+/// scratch host.
+pub fn supported_protocol_versions() -> Vec<u32> {
+    let env = Env::default();
+    let newest = env.ledger().get().protocol_version;
+    (FIRST_SOROBAN_PROTOCOL..=newest)
+        .filter(|&version| {
+            let mut info = env.ledger().get();
+            info.protocol_version = version;
+            env.host().set_ledger_info(info).is_ok()
+        })
+        .collect()
+}
+
+/// `version` if the host supports it, otherwise an error listing the versions
+/// it does.
+pub fn check_protocol_version(version: u32) -> Result<u32> {
+    let supported = supported_protocol_versions();
+    if supported.contains(&version) {
+        return Ok(version);
+    }
+    Err(DebuggerError::InvalidFlag(format!(
+        "Protocol version {} is not supported by this host; supported versions: {}",
+        version,
+        supported
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+    .into())
+}
+
 /// Initialise a Soroban test environment and register `wasm` as a contract.
 ///
 /// Displays a progress bar to the terminal while work is in progress and
 /// ensures it is always cleared — even if this function returns an error.
-#[tracing::instrument(skip_all)]
 pub fn load_contract(wasm: &[u8]) -> Result<LoadedContract> {
+    load_contract_at(wasm, &LedgerOverrides::default())
+}
+
+/// [`load_contract`] with the ledger set to `ledger` first, so the contract's
+/// instance and code TTLs count from that sequence.
+///
+/// A contract whose `__constructor` takes arguments cannot be registered this
+/// way; see [`load_contract_unconstructed`].
+#[tracing::instrument(skip_all)]
+pub fn load_contract_at(wasm: &[u8], ledger: &LedgerOverrides) -> Result<LoadedContract> {
+    if let Ok(Some(params)) = constructor_params(wasm) {
+        if !params.is_empty() {
+            return Err(missing_constructor_args(&params).into());
+        }
+    }
+    load(wasm, ledger, true)
+}
+
+/// Set up the environment as [`load_contract_at`] does, but only pick the
+/// contract's address. Registering it, and so running its `__constructor`, is
+/// left to the executor's first call.
+#[tracing::instrument(skip_all)]
+pub fn load_contract_unconstructed(
+    wasm: &[u8],
+    ledger: &LedgerOverrides,
+) -> Result<LoadedContract> {
+    load(wasm, ledger, false)
+}
+
+fn load(wasm: &[u8], ledger: &LedgerOverrides, register: bool) -> Result<LoadedContract> {
     info!("Initializing contract executor");
 
     let pb = ProgressBar::new(100);
@@ -56,12 +199,17 @@ pub fn load_contract(wasm: &[u8]) -> Result<LoadedContract> {
         .map_err(|e| {
             DebuggerError::ExecutionError(format!("Failed to set diagnostic level: {:?}", e))
         })?;
+    ledger.apply(&env);
 
     guard.0.set_position(50);
     guard.0.set_message("Registering contract...");
 
     // `env.register` is the current, non-deprecated API in soroban-sdk ≥ 0.0.18.
-    let contract_address = env.register(wasm, ());
+    let contract_address = if register {
+        env.register(wasm, ())
+    } else {
+        Address::generate(&env)
+    };
 
     let mut error_db = ErrorDatabase::new();
     if let Err(e) = error_db.load_custom_errors_from_wasm(wasm) {
@@ -1,6 +1,9 @@
+use crate::runtime::executor::TimeoutProgress;
 use crate::utils::ArgumentParser;
 use crate::{DebuggerError, Result};
-use soroban_env_host::{ContractFunctionSet, Host, Symbol as HostSymbol, Val as HostVal};
+use soroban_env_host::{
+    ContractFunctionSet, Error as HostError, Host, Symbol as HostSymbol, Val as HostVal,
+};
 use soroban_sdk::{Env, Val};
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
@@ -13,20 +16,96 @@ pub struct MockKey {
     pub function: String,
 }
 
+/// Contract error code returned by a mock using [`ExhaustionPolicy::Error`]
+/// once all of its values have been served.
+pub const MOCK_EXHAUSTED_ERROR_CODE: u32 = 0x4D4F_434B;
+
+/// What a mock returns after every configured value has been served once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExhaustionPolicy {
+    /// Keep returning the last value.
+    #[default]
+    RepeatLast,
+    /// Start again from the first value.
+    Cycle,
+    /// Fail the call with [`MOCK_EXHAUSTED_ERROR_CODE`].
+    Error,
+}
+
+impl ExhaustionPolicy {
+    pub const NAMES: [&'static str; 3] = ["repeat-last", "cycle", "error"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "repeat-last" => Some(Self::RepeatLast),
+            "cycle" => Some(Self::Cycle),
+            "error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RepeatLast => "repeat-last",
+            Self::Cycle => "cycle",
+            Self::Error => "error",
+        }
+    }
+}
+
+impl std::fmt::Display for ExhaustionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct MockSpec {
     pub key: MockKey,
-    pub return_raw: String,
-    pub return_val: Val,
+    pub policy: ExhaustionPolicy,
+    /// Return values in the order they are served, as given on the command line.
+    pub return_raw: Vec<String>,
+    pub return_vals: Vec<Val>,
+    served: usize,
+}
+
+impl MockSpec {
+    /// Index of the value to serve for the next call, or `None` when the
+    /// values are exhausted under [`ExhaustionPolicy::Error`].
+    fn next_index(&mut self) -> Option<usize> {
+        let call = self.served;
+        self.served += 1;
+        let len = self.return_vals.len();
+        match self.policy {
+            ExhaustionPolicy::RepeatLast => Some(call.min(len - 1)),
+            ExhaustionPolicy::Cycle => Some(call % len),
+            ExhaustionPolicy::Error => (call < len).then_some(call),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct MockCallLogEntry {
+    /// Position of this call among all cross-contract calls of the run.
+    pub sequence: usize,
     pub contract_id: String,
     pub function: String,
     pub args_count: usize,
     pub mocked: bool,
     pub returned: Option<String>,
+    /// Set when a mock using the `error` policy ran out of values.
+    pub exhausted: bool,
+}
+
+/// Per-mock summary: configuration plus the values actually served, in call order.
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct MockSummary {
+    pub contract_id: String,
+    pub function: String,
+    pub policy: String,
+    pub values: Vec<String>,
+    pub served: Vec<String>,
+    pub exhausted_calls: usize,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -53,6 +132,8 @@ impl MockRegistry {
         self.entries.keys().map(|k| k.contract_id.clone()).collect()
     }
 
+    /// Resolve a cross-contract call. Calls reach the registry one at a time in
+    /// host execution order, so values are served in the order of the trace.
     pub fn resolve_call(
         &mut self,
         contract_id: &str,
@@ -63,22 +144,39 @@ impl MockRegistry {
             contract_id: contract_id.to_string(),
             function: function.to_string(),
         };
-        if let Some(spec) = self.entries.get(&key) {
+        let sequence = self.calls.len();
+        if let Some(spec) = self.entries.get_mut(&key) {
+            let served = spec.next_index();
             self.calls.push(MockCallLogEntry {
+                sequence,
                 contract_id: contract_id.to_string(),
                 function: function.to_string(),
                 args_count,
                 mocked: true,
-                returned: Some(spec.return_raw.clone()),
+                returned: served.map(|index| spec.return_raw[index].clone()),
+                exhausted: served.is_none(),
+            });
+            return Some(match served {
+                Some(index) => spec.return_vals[index],
+                None => {
+                    warn!(
+                        contract_id,
+                        function,
+                        calls = spec.served,
+                        "Mock values exhausted; failing the call"
+                    );
+                    HostError::from_contract_error(MOCK_EXHAUSTED_ERROR_CODE).to_val()
+                }
             });
-            return Some(spec.return_val);
         }
         self.calls.push(MockCallLogEntry {
+            sequence,
             contract_id: contract_id.to_string(),
             function: function.to_string(),
             args_count,
             mocked: false,
             returned: None,
+            exhausted: false,
         });
         None
     }
@@ -87,17 +185,58 @@ impl MockRegistry {
         &self.calls
     }
 
+    /// One summary per configured mock, sorted by contract and function.
+    pub fn summary(&self) -> Vec<MockSummary> {
+        let mut summaries: Vec<MockSummary> = self
+            .entries
+            .values()
+            .map(|spec| {
+                let calls = self.calls.iter().filter(|call| {
+                    call.mocked
+                        && call.contract_id == spec.key.contract_id
+                        && call.function == spec.key.function
+                });
+                MockSummary {
+                    contract_id: spec.key.contract_id.clone(),
+                    function: spec.key.function.clone(),
+                    policy: spec.policy.to_string(),
+                    values: spec.return_raw.clone(),
+                    served: calls.clone().filter_map(|c| c.returned.clone()).collect(),
+                    exhausted_calls: calls.filter(|c| c.exhausted).count(),
+                }
+            })
+            .collect();
+        summaries.sort_by(|a, b| {
+            (a.contract_id.as_str(), a.function.as_str())
+                .cmp(&(b.contract_id.as_str(), b.function.as_str()))
+        });
+        summaries
+    }
+
     fn parse_spec(parser: &ArgumentParser, spec: &str) -> Result<MockSpec> {
         let (signature, return_raw) = spec.split_once('=').ok_or_else(|| {
             DebuggerError::InvalidArguments(format!(
-                "Invalid mock '{spec}'. Expected CONTRACT_ID.function=return_value"
+                "Invalid mock '{spec}'. Expected CONTRACT_ID.function[:policy]=value[,value...]"
             ))
         })?;
         let (contract_id, function) = signature.rsplit_once('.').ok_or_else(|| {
             DebuggerError::InvalidArguments(format!(
-                "Invalid mock signature '{signature}'. Expected CONTRACT_ID.function"
+                "Invalid mock signature '{signature}'. Expected CONTRACT_ID.function[:policy]"
             ))
         })?;
+        let (function, policy) = match function.split_once(':') {
+            Some((function, policy)) => {
+                let policy = ExhaustionPolicy::parse(policy.trim()).ok_or_else(|| {
+                    DebuggerError::InvalidArguments(format!(
+                        "Unknown exhaustion policy '{}' in mock '{spec}'. Expected one of: {}",
+                        policy.trim(),
+                        ExhaustionPolicy::NAMES.join(", ")
+                    ))
+                })?;
+                (function, policy)
+            }
+            None => (function, ExhaustionPolicy::default()),
+        };
         let contract_id = contract_id.trim();
         let function = function.trim();
         let return_raw = return_raw.trim();
@@ -108,14 +247,28 @@ impl MockRegistry {
             .into());
         }
 
-        let parsed = parser
-            .parse_args_string(return_raw)
-            .map_err(|e| DebuggerError::InvalidArguments(e.to_string()))?;
-        if parsed.len() != 1 {
-            return Err(DebuggerError::InvalidArguments(format!(
-                "Mock '{spec}' must parse to exactly one return value"
-            ))
-            .into());
+        // `v1,v2` is read as the JSON array `[v1,v2]`; each element is one return value.
+        let values: Vec<serde_json::Value> = serde_json::from_str(&format!("[{return_raw}]"))
+            .map_err(|e| {
+                DebuggerError::InvalidArguments(format!(
+                    "Invalid return values in mock '{spec}': {e}"
+                ))
+            })?;
+        let mut raws = Vec::with_capacity(values.len());
+        let mut vals = Vec::with_capacity(values.len());
+        for value in values {
+            let raw = value.to_string();
+            let parsed = parser
+                .parse_args_string(&raw)
+                .map_err(|e| DebuggerError::InvalidArguments(e.to_string()))?;
+            if parsed.len() != 1 {
+                return Err(DebuggerError::InvalidArguments(format!(
+                    "Mock '{spec}': return value {raw} must parse to exactly one value"
+                ))
+                .into());
+            }
+            raws.push(raw);
+            vals.push(parsed[0]);
         }
 
         Ok(MockSpec {
@@ -123,8 +276,10 @@ impl MockRegistry {
                 contract_id: contract_id.to_string(),
                 function: function.to_string(),
             },
-            return_raw: return_raw.to_string(),
-            return_val: parsed[0],
+            policy,
+            return_raw: raws,
+            return_vals: vals,
+            served: 0,
         })
     }
 }
@@ -132,6 +287,7 @@ impl MockRegistry {
 pub struct MockContractDispatcher {
     contract_id: String,
     registry: Arc<Mutex<MockRegistry>>,
+    timeout_progress: Option<TimeoutProgress>,
 }
 
 impl MockContractDispatcher {
@@ -139,16 +295,24 @@ impl MockContractDispatcher {
         Self {
             contract_id,
             registry,
+            timeout_progress: None,
         }
     }
 
+    /// Refresh the running invocation's timeout report on every call, the
+    /// only points mid-call where the host can be read.
+    pub(crate) fn with_timeout_progress(mut self, progress: TimeoutProgress) -> Self {
+        self.timeout_progress = Some(progress);
+        self
+    }
+
     pub fn boxed(self) -> Rc<dyn ContractFunctionSet> {
         Rc::new(self)
     }
 }
 
 impl ContractFunctionSet for MockContractDispatcher {
-    fn call(&self, func: &HostSymbol, _host: &Host, args: &[HostVal]) -> Option<HostVal> {
+    fn call(&self, func: &HostSymbol, host: &Host, args: &[HostVal]) -> Option<HostVal> {
         let debug_str = format!("{:?}", func);
         let function = if let Some(s) = debug_str.strip_prefix("Symbol(") {
             s.trim_end_matches(')').to_string()
@@ -159,6 +323,14 @@ impl ContractFunctionSet for MockContractDispatcher {
         } else {
             debug_str
         };
+        if let Some(progress) = &self.timeout_progress {
+            if let Ok(mut progress) = progress.lock() {
+                if let Some(report) = progress.as_mut() {
+                    let as_of = format!("the mocked call to {}.{}", self.contract_id, function);
+                    report.refresh(host, as_of);
+                }
+            }
+        }
         let mut guard = match self.registry.lock() {
             Ok(g) => g,
             Err(_) => return None,
@@ -214,4 +386,105 @@ mod tests {
         assert_eq!(registry.calls().len(), 1);
         assert!(!registry.calls()[0].mocked);
     }
+
+    const CONTRACT: &str = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHK3M";
+
+    fn served(spec: &str, calls: usize) -> Vec<Option<String>> {
+        let env = Env::default();
+        let mut registry = MockRegistry::from_cli_specs(&env, &[spec.to_string()]).unwrap();
+        for _ in 0..calls {
+            registry.resolve_call(CONTRACT, "price", 0);
+        }
+        registry
+            .calls()
+            .iter()
+            .map(|c| c.returned.clone())
+            .collect()
+    }
+
+    fn some(values: &[&str]) -> Vec<Option<String>> {
+        values.iter().map(|v| Some(v.to_string())).collect()
+    }
+
+    #[test]
+    fn repeat_last_is_the_default_policy() {
+        let spec = format!("{CONTRACT}.price=1,2");
+        assert_eq!(served(&spec, 4), some(&["1", "2", "2", "2"]));
+    }
+
+    #[test]
+    fn cycle_policy_wraps_around() {
+        let spec = format!("{CONTRACT}.price:cycle=1,2,3");
+        assert_eq!(served(&spec, 5), some(&["1", "2", "3", "1", "2"]));
+    }
+
+    #[test]
+    fn error_policy_fails_the_call_once_exhausted() {
+        let env = Env::default();
+        let spec = format!("{CONTRACT}.price:error=1,2");
+        let mut registry = MockRegistry::from_cli_specs(&env, &[spec]).unwrap();
+
+        for _ in 0..2 {
+            let val = registry.resolve_call(CONTRACT, "price", 0).unwrap();
+            assert!(HostError::try_from(val).is_err());
+        }
+        let val = registry.resolve_call(CONTRACT, "price", 0).unwrap();
+        let error = HostError::try_from(val).expect("exhausted mock should return an error");
+        assert!(error.is_type(soroban_env_host::xdr::ScErrorType::Contract));
+        assert_eq!(error.get_code(), MOCK_EXHAUSTED_ERROR_CODE);
+
+        let last = registry.calls().last().unwrap();
+        assert!(last.exhausted);
+        assert_eq!(last.returned, None);
+        let summary = &registry.summary()[0];
+        assert_eq!(summary.policy, "error");
+        assert_eq!(summary.served, vec!["1".to_string(), "2".to_string()]);
+        assert_eq!(summary.exhausted_calls, 1);
+    }
+
+    #[test]
+    fn interleaved_calls_are_served_in_call_order() {
+        let env = Env::default();
+        let specs = vec![
+            format!("{CONTRACT}.price=10,11,12"),
+            format!("{CONTRACT}.decimals:cycle=7,8"),
+        ];
+        let mut registry = MockRegistry::from_cli_specs(&env, &specs).unwrap();
+        for function in [
+            "price", "decimals", "price", "decimals", "decimals", "price",
+        ] {
+            registry.resolve_call(CONTRACT, function, 0);
+        }
+
+        let log: Vec<(usize, &str, &str)> = registry
+            .calls()
+            .iter()
+            .map(|c| {
+                (
+                    c.sequence,
+                    c.function.as_str(),
+                    c.returned.as_deref().unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            log,
+            vec![
+                (0, "price", "10"),
+                (1, "decimals", "7"),
+                (2, "price", "11"),
+                (3, "decimals", "8"),
+                (4, "decimals", "7"),
+                (5, "price", "12"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_exhaustion_policy() {
+        let env = Env::default();
+        let err = MockRegistry::from_cli_specs(&env, &[format!("{CONTRACT}.price:shuffle=1")])
+            .unwrap_err();
+        assert!(err.to_string().contains("repeat-last, cycle, error"));
+    }
 }
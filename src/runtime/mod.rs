@@ -2,28 +2,44 @@
 //!
 //! Sub-modules:
 //! - [`executor`]       — Public façade; coordinates all sub-modules.
+//! - [`call_stack`]     — The chain of contract calls open where execution stopped.
 //! - [`loader`]         — WASM loading and Soroban environment bootstrap.
 //! - [`invoker`]        — Contract function invocation with timeout protection.
+//! - [`limits`]         — Host budget limits and reports of running out of them.
 //! - [`parser`]         — Argument parsing and type-aware JSON normalisation.
 //! - [`result`]         — Shared result types and formatting helpers.
 //! - [`env`]            — Debug environment utilities.
 //! - [`instruction`]    — WASM instruction parsing.
 //! - [`instrumentation`]— Instruction-level hooks for profiling.
 //! - [`mocking`]        — Mock contract registry and dispatcher.
+//! - [`observer`]       — Hooks at contract-call boundaries for embedders.
+//! - [`policy`]         — Function allow/deny policy from the config file.
+//! - [`ring`]           — Always-on ring buffer of recent calls and writes.
+//! - [`session`]        — Saved executor state for `--save-state`/`--load-state`.
 
+pub mod call_stack;
 pub mod env;
 pub mod executor;
 pub mod instruction;
 pub mod instrumentation;
 pub mod invoker;
+pub mod limits;
 pub mod loader;
 pub mod mocking;
+pub mod observer;
 pub mod parser;
+pub mod policy;
 pub mod result;
+pub mod ring;
+pub mod session;
 
 // Top-level re-exports — public API is unchanged.
 pub use env::DebugEnv;
 pub use executor::ContractExecutor;
-pub use executor::{ExecutionRecord, InstructionCounts, MockCallEntry, StorageSnapshot};
+pub use executor::{
+    ExecutionOutcome, ExecutionRecord, ExecutionResult, InstructionCounts, LedgerOverrides, MockCallEntry,
+    StorageSnapshot,
+};
 pub use instruction::{Instruction, InstructionParser};
 pub use instrumentation::{InstructionHook, Instrumenter};
+pub use session::SessionState;
@@ -0,0 +1,457 @@
+//! Hooks at contract-call boundaries for embedders of the library.
+//!
+//! An [`InvocationObserver`] registered with
+//! [`DebuggerEngine::with_observer`](crate::DebuggerEngine::with_observer) or
+//! [`ContractExecutor::add_observer`](crate::ContractExecutor::add_observer)
+//! sees every invocation as a sequence of [`Notification`]s: calls starting
+//! and ending, storage writes and contract events. Nested calls come from the
+//! `fn_call` / `fn_return` diagnostic events the host records, so each
+//! [`CallStart`] pairs with a [`CallEnd`] at the same depth. The host only
+//! exposes storage as a snapshot before and after the whole invocation, so
+//! writes are delivered just before the top-level call ends.
+//!
+//! Observers run once the host has returned and only see copies, so they
+//! cannot change what the contract did. An error returned by an observer
+//! becomes an `observer` warning in the run report; an observer that panics is
+//! reported the same way and detached for the rest of the session.
+//!
+//! The debugger's own `--show host-calls` listing, `--alert-on-change` alerts
+//! and the storage writes of the execution trace are observers as well.
+//!
+//! An observer that enforces an invariant between nested calls, here that no
+//! contract is re-entered while it is still on the call stack:
+//!
+//! ```
+//! use soroban_debugger::runtime::observer::{
+//!     CallEnd, CallStart, InvocationObserver, ObserverResult,
+//! };
+//! use soroban_debugger::{ContractExecutor, DebuggerEngine};
+//!
+//! #[derive(Default)]
+//! struct NoReentry {
+//!     stack: Vec<String>,
+//! }
+//!
+//! impl InvocationObserver for NoReentry {
+//!     fn on_call_start(&mut self, call: &CallStart) -> ObserverResult {
+//!         if self.stack.contains(&call.contract) {
+//!             return Err(format!("{} re-entered through {}", call.contract, call.function));
+//!         }
+//!         self.stack.push(call.contract.clone());
+//!         Ok(())
+//!     }
+//!
+//!     fn on_call_end(&mut self, _call: &CallEnd) -> ObserverResult {
+//!         self.stack.pop();
+//!         Ok(())
+//!     }
+//! }
+//!
+//! // Registering it on an engine; violations end up as report warnings.
+//! fn debug_swap(wasm: Vec<u8>) -> soroban_debugger::Result<String> {
+//!     let executor = ContractExecutor::new(wasm)?;
+//!     let mut engine = DebuggerEngine::new(executor, Vec::new()).with_observer(NoReentry::default());
+//!     engine.execute("swap", Some("[100]"))
+//! }
+//!
+//! // The notifications a pool calling a token that calls back into the pool produces.
+//! let start = |depth, contract: &str, function: &str| CallStart {
+//!     depth,
+//!     contract: contract.to_string(),
+//!     function: function.to_string(),
+//!     args: serde_json::json!([]),
+//! };
+//! let mut observer = NoReentry::default();
+//! assert!(observer.on_call_start(&start(0, "CPOOL", "swap")).is_ok());
+//! assert!(observer.on_call_start(&start(1, "CTOKEN", "transfer")).is_ok());
+//! assert_eq!(
+//!     observer.on_call_start(&start(2, "CPOOL", "skim")),
+//!     Err("CPOOL re-entered through skim".to_string())
+//! );
+//! ```
+
+use crate::inspector::events::{ContractEvent, EventInspector};
+use crate::inspector::render_cache::RenderCache;
+use crate::inspector::storage_decode::decode_text;
+use crate::utils::arguments::scval_to_json;
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType, Hash, ScAddress, ScVal};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// What an observer hook returns; an error becomes a report warning.
+pub type ObserverResult = std::result::Result<(), String>;
+
+/// A contract call about to run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallStart {
+    /// 0 for the top-level invocation.
+    pub depth: usize,
+    pub contract: String,
+    pub function: String,
+    /// The arguments, as a JSON array.
+    pub args: serde_json::Value,
+}
+
+/// A contract call that returned or trapped.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallEnd {
+    pub depth: usize,
+    pub contract: String,
+    pub function: String,
+    /// The return value; `None` if the call trapped.
+    pub result: Option<serde_json::Value>,
+}
+
+/// A storage key the invocation wrote, rendered like storage snapshot keys.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StorageWrite {
+    pub key: String,
+    /// `None` if the key was created.
+    pub old: Option<String>,
+    /// `None` if the key was deleted. Equal to `old` for a write that stored
+    /// the value the key already held.
+    pub new: Option<String>,
+}
+
+/// Hooks called at the boundaries of contract calls. Every hook defaults to
+/// doing nothing.
+pub trait InvocationObserver: Send {
+    /// Name used in warnings about this observer.
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    fn on_call_start(&mut self, _call: &CallStart) -> ObserverResult {
+        Ok(())
+    }
+
+    fn on_call_end(&mut self, _call: &CallEnd) -> ObserverResult {
+        Ok(())
+    }
+
+    fn on_storage_write(&mut self, _write: &StorageWrite) -> ObserverResult {
+        Ok(())
+    }
+
+    /// A contract or system event; diagnostic events are not delivered.
+    fn on_event(&mut self, _event: &ContractEvent) -> ObserverResult {
+        Ok(())
+    }
+}
+
+/// One observer hook call.
+#[derive(Debug, Clone)]
+pub enum Notification {
+    CallStart(CallStart),
+    CallEnd(CallEnd),
+    StorageWrite(StorageWrite),
+    Event(ContractEvent),
+}
+
+impl Notification {
+    /// Call the hook of `observer` this notification is for.
+    pub fn deliver(&self, observer: &mut dyn InvocationObserver) -> ObserverResult {
+        match self {
+            Notification::CallStart(call) => observer.on_call_start(call),
+            Notification::CallEnd(call) => observer.on_call_end(call),
+            Notification::StorageWrite(write) => observer.on_storage_write(write),
+            Notification::Event(event) => observer.on_event(event),
+        }
+    }
+
+    fn hook(&self) -> &'static str {
+        match self {
+            Notification::CallStart(_) => "on_call_start",
+            Notification::CallEnd(_) => "on_call_end",
+            Notification::StorageWrite(_) => "on_storage_write",
+            Notification::Event(_) => "on_event",
+        }
+    }
+}
+
+/// Deliver `notifications` to `observer` in order, stopping at the first error.
+pub fn replay(
+    notifications: &[Notification],
+    observer: &mut dyn InvocationObserver,
+) -> ObserverResult {
+    notifications
+        .iter()
+        .try_for_each(|notification| notification.deliver(observer))
+}
+
+/// The keys of `after` and `before` that changed, or that are in `written`,
+/// sorted by key.
+pub fn storage_writes(
+    before: &HashMap<String, String>,
+    after: &HashMap<String, String>,
+    written: &HashSet<String>,
+) -> Vec<StorageWrite> {
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter_map(|key| {
+            let old = before.get(key);
+            let new = after.get(key);
+            (old != new || written.contains(key)).then(|| StorageWrite {
+                key: key.clone(),
+                old: old.cloned(),
+                new: new.cloned(),
+            })
+        })
+        .collect()
+}
+
+/// The notifications for one invocation, from the host events it recorded
+/// and the storage it wrote.
+///
+/// Calls still open when the events end trapped and are closed innermost
+/// first. Writes go right before the top-level call ends, or last if the
+/// host recorded no calls (diagnostics off).
+pub fn invocation_notifications(
+    events: &[HostEvent],
+    writes: Vec<StorageWrite>,
+) -> Vec<Notification> {
+    let mut notifications = Vec::new();
+    let mut open: Vec<CallStart> = Vec::new();
+    let mut writes = Some(writes);
+    let mut cache = RenderCache::new();
+
+    let mut close = |call: CallStart,
+                     result: Option<serde_json::Value>,
+                     notifications: &mut Vec<Notification>| {
+        if call.depth == 0 {
+            if let Some(writes) = writes.take() {
+                notifications.extend(writes.into_iter().map(Notification::StorageWrite));
+            }
+        }
+        notifications.push(Notification::CallEnd(CallEnd {
+            depth: call.depth,
+            contract: call.contract,
+            function: call.function,
+            result,
+        }));
+    };
+
+    for event in events {
+        if event.event.type_ != ContractEventType::Diagnostic {
+            notifications.push(Notification::Event(EventInspector::convert(
+                &event.event,
+                &mut cache,
+            )));
+            continue;
+        }
+        let ContractEventBody::V0(body) = &event.event.body;
+        let topics = body.topics.as_slice();
+        match topics.first().and_then(symbol).as_deref() {
+            Some("fn_call") => {
+                let (contract, function) = call_target(topics);
+                let call = CallStart {
+                    depth: open.len(),
+                    contract,
+                    function,
+                    args: scval_to_json(&body.data),
+                };
+                notifications.push(Notification::CallStart(call.clone()));
+                open.push(call);
+            }
+            Some("fn_return") => {
+                if let Some(call) = open.pop() {
+                    close(call, Some(scval_to_json(&body.data)), &mut notifications);
+                }
+            }
+            _ => {}
+        }
+    }
+    while let Some(call) = open.pop() {
+        close(call, None, &mut notifications);
+    }
+    if let Some(writes) = writes {
+        notifications.extend(writes.into_iter().map(Notification::StorageWrite));
+    }
+    notifications
+}
+
+/// The contract and function named by the topics of a `fn_call` event.
+pub(crate) fn call_target(topics: &[ScVal]) -> (String, String) {
+    let contract = match topics.get(1) {
+        Some(ScVal::Bytes(bytes)) => <[u8; 32]>::try_from(bytes.as_slice())
+            .map(|id| ScAddress::Contract(Hash(id)).to_string())
+            .unwrap_or_else(|_| hex::encode(bytes.as_slice())),
+        _ => "?".to_string(),
+    };
+    (contract, topics.get(2).and_then(symbol).unwrap_or_default())
+}
+
+/// The arguments of a `fn_call` event, decoded: the host sends one
+/// argument alone and several as a vector.
+pub(crate) fn call_args(data: &ScVal) -> Vec<String> {
+    match data {
+        ScVal::Void => Vec::new(),
+        ScVal::Vec(Some(items)) => items.iter().map(decode_text).collect(),
+        other => vec![decode_text(other)],
+    }
+}
+
+pub(crate) fn symbol(value: &ScVal) -> Option<String> {
+    match value {
+        ScVal::Symbol(symbol) => Some(symbol.to_utf8_string_lossy()),
+        _ => None,
+    }
+}
+
+/// The observers registered on an executor.
+#[derive(Default)]
+pub struct ObserverSet {
+    observers: Vec<Box<dyn InvocationObserver>>,
+}
+
+impl ObserverSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, observer: Box<dyn InvocationObserver>) {
+        self.observers.push(observer);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.observers.len()
+    }
+
+    /// Deliver `notifications` to every observer and return a warning for
+    /// each hook that failed. An observer that fails stops receiving this
+    /// invocation; one that panics is removed.
+    pub fn notify(&mut self, notifications: &[Notification]) -> Vec<String> {
+        let mut warnings = Vec::new();
+        self.observers.retain_mut(|observer| {
+            for notification in notifications {
+                match catch_unwind(AssertUnwindSafe(|| notification.deliver(observer.as_mut()))) {
+                    Ok(Ok(())) => {}
+                    Ok(Err(message)) => {
+                        warnings.push(format!(
+                            "Observer {} failed in {}: {}",
+                            observer.name(),
+                            notification.hook(),
+                            message
+                        ));
+                        return true;
+                    }
+                    Err(_) => {
+                        warnings.push(format!(
+                            "Observer {} panicked in {} and was detached",
+                            observer.name(),
+                            notification.hook()
+                        ));
+                        return false;
+                    }
+                }
+            }
+            true
+        });
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(depth: usize, function: &str) -> Notification {
+        Notification::CallStart(CallStart {
+            depth,
+            contract: "C1".to_string(),
+            function: function.to_string(),
+            args: serde_json::json!([]),
+        })
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        hooks: Vec<String>,
+    }
+
+    impl InvocationObserver for Recorder {
+        fn on_call_start(&mut self, call: &CallStart) -> ObserverResult {
+            self.hooks.push(format!("start {}", call.function));
+            Ok(())
+        }
+
+        fn on_storage_write(&mut self, write: &StorageWrite) -> ObserverResult {
+            self.hooks.push(format!("write {}", write.key));
+            Ok(())
+        }
+    }
+
+    struct Failing;
+
+    impl InvocationObserver for Failing {
+        fn on_call_start(&mut self, call: &CallStart) -> ObserverResult {
+            Err(format!("{} is not allowed", call.function))
+        }
+    }
+
+    struct Panicking;
+
+    impl InvocationObserver for Panicking {
+        fn on_call_start(&mut self, _call: &CallStart) -> ObserverResult {
+            panic!("observer bug");
+        }
+    }
+
+    #[test]
+    fn writes_cover_changes_deletions_and_identical_rewrites() {
+        let before = HashMap::from([
+            ("a".to_string(), "1".to_string()),
+            ("b".to_string(), "2".to_string()),
+            ("c".to_string(), "3".to_string()),
+            ("d".to_string(), "4".to_string()),
+        ]);
+        let after = HashMap::from([
+            ("a".to_string(), "10".to_string()),
+            ("c".to_string(), "3".to_string()),
+            ("d".to_string(), "4".to_string()),
+            ("e".to_string(), "5".to_string()),
+        ]);
+        let written = HashSet::from(["c".to_string()]);
+
+        let writes = storage_writes(&before, &after, &written);
+        let keys: Vec<&str> = writes.iter().map(|write| write.key.as_str()).collect();
+        assert_eq!(keys, ["a", "b", "c", "e"]);
+        assert_eq!(writes[1].new, None);
+        assert_eq!(writes[2].old, writes[2].new);
+        assert_eq!(writes[3].old, None);
+    }
+
+    #[test]
+    fn failures_become_warnings_and_panics_detach() {
+        let mut set = ObserverSet::new();
+        set.add(Box::new(Failing));
+        set.add(Box::new(Panicking));
+        set.add(Box::new(Recorder::default()));
+
+        let warnings = set.notify(&[call(0, "swap"), call(1, "transfer")]);
+        assert_eq!(warnings.len(), 2, "{:?}", warnings);
+        assert!(warnings[0].contains("failed in on_call_start: swap is not allowed"));
+        assert!(warnings[1].contains("Panicking panicked in on_call_start"));
+        assert_eq!(set.len(), 2);
+
+        assert_eq!(set.notify(&[call(0, "swap")]).len(), 1);
+    }
+
+    #[test]
+    fn writes_without_calls_are_still_delivered() {
+        let write = StorageWrite {
+            key: "k".to_string(),
+            old: None,
+            new: Some("1".to_string()),
+        };
+        let notifications = invocation_notifications(&[], vec![write]);
+        let mut recorder = Recorder::default();
+        replay(&notifications, &mut recorder).unwrap();
+        assert_eq!(recorder.hooks, ["write k"]);
+    }
+}
@@ -1,124 +1,196 @@
-//! Contract function argument parsing and type-aware normalisation.
+//! Contract function argument parsing against the contract spec.
 //!
-//! Accepts raw JSON strings from the CLI, inspects the WASM contract spec to
-//! determine expected parameter types, and wraps values in the typed-annotation
-//! envelope (`{"type": "...", "value": ...}`) that [`ArgumentParser`] expects.
+//! Accepts raw JSON strings from the CLI and, when the function is in the
+//! WASM contract spec, converts each value as its declared parameter type,
+//! so plain JSON needs no `{"type": "...", "value": ...}` envelope.
 //!
 //! # Key responsibilities
 //! - Parse raw `--args` JSON into [`soroban_sdk::Val`] slices.
-//! - Normalise `Option<T>` and `Tuple<…>` arguments automatically so callers
-//!   do not need to spell out the annotation envelope themselves.
+//! - Convert plain values as the declared parameter types: numbers as the
+//!   declared integer width, strings as symbols, strings or addresses, and
+//!   objects as structs. Explicit annotations still win.
+//! - Hand the contract's user-defined types to the parser, so struct, union
+//!   and enum arguments are built from the spec.
+//! - Match `{"name": value, ...}` arguments to the function's parameters and
+//!   convert each as its declared type.
 
+use crate::utils::artifact_cache::ContractArtifacts;
+use crate::utils::wasm::FunctionParam;
+use crate::warnings::WarningCategory;
 use crate::{DebuggerError, Result};
 use serde_json::Value as JsonValue;
-use soroban_sdk::{Env, Val};
+use soroban_sdk::{Address, Env, Val};
+use std::collections::BTreeMap;
 use tracing::warn;
 
 /// Parse a raw JSON argument string into a `Vec<Val>` using the given environment.
 ///
-/// `wasm_bytes` is used to look up the function signature so that `Option` and
-/// `Tuple` parameters are wrapped in the typed-annotation envelope automatically.
+/// `wasm_bytes` is used to look up the function signature, and each value is
+/// converted as the type its parameter declares, see [`positional_args`].
+/// Without a signature the values are converted untyped. `contracts` are the
+/// linked contracts `{"type": "contract"}` arguments can name. The contract's
+/// structs, unions and enums can be named as annotation types. An object of
+/// parameter names to values is taken as named arguments, see [`named_args`].
 pub fn parse_args(
     env: &Env,
     wasm_bytes: &[u8],
     function: &str,
     args_json: &str,
+    contracts: &BTreeMap<String, Address>,
 ) -> Result<Vec<Val>> {
-    let parser = crate::utils::ArgumentParser::new(env.clone());
-    let normalized = normalize_args_for_function(wasm_bytes, function, args_json)?;
-    parser.parse_args_string(&normalized).map_err(|e| {
+    let artifacts = crate::utils::artifact_cache::artifacts_for(wasm_bytes)?;
+    let parser = crate::utils::ArgumentParser::new(env.clone())
+        .with_contracts(contracts.clone())
+        .with_types(artifacts.types.iter().cloned());
+    if let Some(named) = named_args(&artifacts, function, args_json)? {
+        return named
+            .into_iter()
+            .map(|(param, value)| {
+                parser
+                    .parse_as_spec_type(&param.type_name, &value)
+                    .map_err(|e| {
+                        DebuggerError::InvalidArguments(format!(
+                            "function {} expects parameter '{}' of type {}; {}",
+                            function, param.name, param.type_name, e
+                        ))
+                        .into()
+                    })
+            })
+            .collect();
+    }
+    if let Some(positional) = positional_args(&artifacts, function, args_json) {
+        return positional
+            .into_iter()
+            .map(|(index, param, value)| {
+                parser
+                    .parse_as_spec_type(&param.type_name, &value)
+                    .map_err(|e| {
+                        let (element, path) = match index {
+                            Some(i) => (format!("Array element {}: ", i), format!("args[{}]", i)),
+                            None => (String::new(), "args".to_string()),
+                        };
+                        DebuggerError::InvalidArguments(format!(
+                            "{}function {} expects parameter '{}' of type {}; {}",
+                            element,
+                            function,
+                            param.name,
+                            param.type_name,
+                            e.within(&path)
+                        ))
+                        .into()
+                    })
+            })
+            .collect();
+    }
+    parser.parse_args_string(args_json).map_err(|e| {
         warn!("Failed to parse arguments: {}", e);
         DebuggerError::InvalidArguments(e.to_string()).into()
     })
 }
 
-/// Normalise argument JSON against the contract's function signature.
+/// Positional arguments paired with the parameters of `function` they are
+/// converted as, with their index in the argument array.
 ///
-/// Wraps `Option<T>` arguments in `{"type":"option","value":…}` and
-/// `Tuple<…>` arguments in `{"type":"tuple","arity":N,"value":[…]}` so that
-/// the downstream [`ArgumentParser`] can handle them without caller involvement.
-fn normalize_args_for_function(
-    wasm_bytes: &[u8],
+/// A lone value that is not an array is the argument of a function taking
+/// one parameter. `None` when the function is not in the contract spec or
+/// the number of values does not match its parameters; the arguments are
+/// then converted without types and the host reports the mismatch.
+fn positional_args<'a>(
+    artifacts: &'a ContractArtifacts,
     function: &str,
     args_json: &str,
-) -> Result<String> {
-    let signatures = crate::utils::wasm::parse_function_signatures(wasm_bytes)?;
-    let Some(signature) = signatures.into_iter().find(|sig| sig.name == function) else {
-        return Ok(args_json.to_string());
-    };
-
-    let mut args_value: JsonValue = serde_json::from_str(args_json)
-        .map_err(|e| DebuggerError::InvalidArguments(format!("Invalid JSON in --args: {}", e)))?;
+) -> Option<Vec<(Option<usize>, &'a FunctionParam, JsonValue)>> {
+    let signature = artifacts.signature(function)?;
+    let value = serde_json::from_str::<JsonValue>(args_json).ok()?;
+    match (value, signature.params.as_slice()) {
+        (JsonValue::Array(values), params) if values.len() == params.len() => Some(
+            params
+                .iter()
+                .zip(values)
+                .enumerate()
+                .map(|(i, (param, value))| (Some(i), param, value))
+                .collect(),
+        ),
+        (JsonValue::Array(_), _) => None,
+        (value, [param]) => Some(vec![(None, param, value)]),
+        _ => None,
+    }
+}
 
-    let JsonValue::Array(args) = &mut args_value else {
-        return Ok(args_json.to_string());
+/// The values of a `{"name": value, ...}` argument object, in the order of
+/// `function`'s parameters, or `None` when the arguments are positional.
+///
+/// An object stays a single map argument when the function takes one
+/// parameter and the object has no key of that name, and, with a warning,
+/// when the function is not in the contract spec.
+fn named_args<'a>(
+    artifacts: &'a ContractArtifacts,
+    function: &str,
+    args_json: &str,
+) -> Result<Option<Vec<(&'a FunctionParam, JsonValue)>>> {
+    let Ok(value) = serde_json::from_str::<JsonValue>(args_json) else {
+        // Left for the parser to report.
+        return Ok(None);
     };
-
-    for (arg, param) in args.iter_mut().zip(signature.params.iter()) {
-        if param.type_name.starts_with("Option<") {
-            if !is_typed_annotation(arg) {
-                *arg = serde_json::json!({"type": "option", "value": arg.clone()});
-            }
-            continue;
+    if is_typed_annotation(&value) {
+        return Ok(None);
+    }
+    let JsonValue::Object(mut named) = value else {
+        return Ok(None);
+    };
+    let Some(signature) = artifacts.signature(function) else {
+        let warning = format!(
+            "Function '{}' is not in the contract spec; passing the --args object as a single map argument, not as named arguments",
+            function
+        );
+        crate::logging::log_display(&warning, crate::logging::LogLevel::Warn);
+        crate::warnings::record(WarningCategory::Arguments, warning);
+        return Ok(None);
+    };
+    if let [param] = signature.params.as_slice() {
+        if !named.contains_key(&param.name) {
+            return Ok(None);
         }
+    }
 
-        if param.type_name.starts_with("Tuple<") {
-            let arity = tuple_arity_from_type_name(&param.type_name).ok_or_else(|| {
-                DebuggerError::InvalidArguments(format!(
-                    "Invalid tuple type in function spec for '{}': {}",
-                    param.name, param.type_name
-                ))
-            })?;
-
-            let JsonValue::Array(actual_arr) = arg else {
-                return Err(DebuggerError::InvalidArguments(format!(
-                    "Argument '{}' expects tuple with {} elements, got {}",
-                    param.name,
-                    arity,
-                    json_type_name(arg)
-                ))
-                .into());
-            };
-
-            if actual_arr.len() != arity {
-                return Err(DebuggerError::InvalidArguments(format!(
-                    "Tuple arity mismatch: expected {}, got {}",
-                    arity,
-                    actual_arr.len()
-                ))
-                .into());
+    if let Some(extra) = named
+        .keys()
+        .find(|key| !signature.params.iter().any(|param| &param.name == *key))
+    {
+        let params = signature
+            .params
+            .iter()
+            .map(|param| format!("{}: {}", param.name, param.type_name))
+            .collect::<Vec<_>>();
+        return Err(DebuggerError::InvalidArguments(format!(
+            "function {} has no parameter '{}'; its parameters are {}",
+            function,
+            extra,
+            if params.is_empty() {
+                "none".to_string()
+            } else {
+                params.join(", ")
             }
-
-            *arg =
-                serde_json::json!({"type": "tuple", "arity": arity, "value": actual_arr.clone()});
-        }
+        ))
+        .into());
     }
-
-    serde_json::to_string(&args_value).map_err(|e| {
-        DebuggerError::ExecutionError(format!("Failed to normalise arguments JSON: {}", e)).into()
-    })
+    signature
+        .params
+        .iter()
+        .map(|param| match named.remove(&param.name) {
+            Some(value) => Ok((param, value)),
+            None => Err(DebuggerError::InvalidArguments(format!(
+                "function {} expects parameter '{}' of type {}; not provided",
+                function, param.name, param.type_name
+            ))
+            .into()),
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
 }
 
 // ── helpers ──────────────────────────────────────────────────────────────────
 
-fn tuple_arity_from_type_name(type_name: &str) -> Option<usize> {
-    let inner = type_name.strip_prefix("Tuple<")?.strip_suffix('>')?;
-    if inner.trim().is_empty() {
-        return Some(0);
-    }
-    let mut depth = 0usize;
-    let mut arity = 1usize;
-    for ch in inner.chars() {
-        match ch {
-            '<' => depth += 1,
-            '>' => depth = depth.saturating_sub(1),
-            ',' if depth == 0 => arity += 1,
-            _ => {}
-        }
-    }
-    Some(arity)
-}
-
 fn is_typed_annotation(value: &JsonValue) -> bool {
     matches!(
         value,
@@ -126,40 +198,132 @@ fn is_typed_annotation(value: &JsonValue) -> bool {
     )
 }
 
-fn json_type_name(value: &JsonValue) -> &'static str {
-    match value {
-        JsonValue::Null => "null",
-        JsonValue::Bool(_) => "boolean",
-        JsonValue::Number(_) => "number",
-        JsonValue::String(_) => "string",
-        JsonValue::Array(_) => "array",
-        JsonValue::Object(_) => "object",
-    }
-}
-
 // ── tests ─────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
 mod tests {
-    use super::tuple_arity_from_type_name;
+    use super::{named_args, positional_args};
+    use crate::utils::artifact_cache::{ContractArtifacts, ARTIFACT_CACHE_VERSION};
+    use crate::utils::wasm::{ContractFunctionSignature, FunctionParam};
+    use serde_json::json;
+
+    fn transfer_artifacts() -> ContractArtifacts {
+        let param = |name: &str, type_name: &str| FunctionParam {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+        };
+        ContractArtifacts {
+            version: ARTIFACT_CACHE_VERSION,
+            wasm_hash: String::new(),
+            exports: vec!["transfer".to_string(), "balance".to_string()],
+            signatures: vec![
+                ContractFunctionSignature {
+                    name: "transfer".to_string(),
+                    params: vec![
+                        param("from", "Address"),
+                        param("to", "Address"),
+                        param("amount", "I128"),
+                    ],
+                    return_type: None,
+                },
+                ContractFunctionSignature {
+                    name: "balance".to_string(),
+                    params: vec![param("id", "Address")],
+                    return_type: Some("I128".to_string()),
+                },
+            ],
+            types: Vec::new(),
+            function_names: Default::default(),
+        }
+    }
 
     #[test]
-    fn tuple_arity_counts_top_level_types() {
-        assert_eq!(tuple_arity_from_type_name("Tuple<U32, Symbol>"), Some(2));
+    fn named_arguments_follow_the_parameter_order() {
+        let artifacts = transfer_artifacts();
+        let named = named_args(
+            &artifacts,
+            "transfer",
+            r#"{"amount": "100", "to": "B", "from": "A"}"#,
+        )
+        .unwrap()
+        .unwrap();
+        let order: Vec<(&str, serde_json::Value)> = named
+            .into_iter()
+            .map(|(param, value)| (param.name.as_str(), value))
+            .collect();
         assert_eq!(
-            tuple_arity_from_type_name("Tuple<U32, Option<Vec<Symbol>>, Map<U32, String>>"),
-            Some(3)
+            order,
+            [
+                ("from", json!("A")),
+                ("to", json!("B")),
+                ("amount", json!("100"))
+            ]
         );
+
+        // Positional arrays, annotations and a lone map argument are untouched.
+        for args in [r#"["A", "B", 100]"#, r#"{"type": "i128", "value": 5}"#] {
+            assert!(named_args(&artifacts, "transfer", args).unwrap().is_none());
+        }
+        assert!(named_args(&artifacts, "balance", r#"{"a": 1}"#)
+            .unwrap()
+            .is_none());
+        assert!(named_args(&artifacts, "mint", r#"{"to": "A"}"#)
+            .unwrap()
+            .is_none());
     }
 
     #[test]
-    fn tuple_arity_zero_for_empty() {
-        assert_eq!(tuple_arity_from_type_name("Tuple<>"), Some(0));
-        assert_eq!(tuple_arity_from_type_name("Tuple<  >"), Some(0));
+    fn named_arguments_report_missing_and_extra_keys() {
+        let artifacts = transfer_artifacts();
+        let err = named_args(&artifacts, "transfer", r#"{"to": "B", "amount": 1}"#).unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "function transfer expects parameter 'from' of type Address; not provided"
+            ),
+            "{}",
+            err
+        );
+        let err = named_args(
+            &artifacts,
+            "transfer",
+            r#"{"from": "A", "to": "B", "amount": 1, "memo": "hi"}"#,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string().contains(
+                "function transfer has no parameter 'memo'; its parameters are from: Address, to: Address, amount: I128"
+            ),
+            "{}",
+            err
+        );
     }
 
     #[test]
-    fn tuple_arity_returns_none_for_bad_prefix() {
-        assert_eq!(tuple_arity_from_type_name("Vec<U32>"), None);
+    fn positional_values_pair_with_the_parameters() {
+        let artifacts = transfer_artifacts();
+        let positional = positional_args(&artifacts, "transfer", r#"["A", "B", 100]"#).unwrap();
+        let pairs: Vec<(Option<usize>, &str, serde_json::Value)> = positional
+            .into_iter()
+            .map(|(index, param, value)| (index, param.type_name.as_str(), value))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                (Some(0), "Address", json!("A")),
+                (Some(1), "Address", json!("B")),
+                (Some(2), "I128", json!(100))
+            ]
+        );
+
+        // A lone value is the argument of a one-parameter function.
+        let lone = positional_args(&artifacts, "balance", r#""A""#).unwrap();
+        assert_eq!(lone[0].0, None);
+        assert_eq!(lone[0].1.name, "id");
+
+        // Counts that do not match, and functions outside the spec, are
+        // left to the untyped parser.
+        assert!(positional_args(&artifacts, "transfer", r#"["A", "B"]"#).is_none());
+        assert!(positional_args(&artifacts, "transfer", "5").is_none());
+        assert!(positional_args(&artifacts, "mint", "[5]").is_none());
     }
 }
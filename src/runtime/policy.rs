@@ -0,0 +1,156 @@
+//! Function allow/deny policy from the `[policy]` config section.
+//!
+//! The policy is checked by [`ContractExecutor::execute`](super::executor::ContractExecutor::execute)
+//! before arguments are parsed, so every front end (run, scenario, REPL,
+//! interactive, server) refuses a forbidden function the same way. Patterns
+//! are globs where `*` matches any run of characters and `?` exactly one.
+
+use crate::config::PolicyConfig;
+use crate::{DebuggerError, Result};
+use std::sync::RwLock;
+
+static FUNCTION_POLICY: RwLock<Option<FunctionPolicy>> = RwLock::new(None);
+
+/// Deny and allow patterns for top-level contract functions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FunctionPolicy {
+    deny: Vec<String>,
+    allow: Vec<String>,
+}
+
+impl FunctionPolicy {
+    pub fn new(config: &PolicyConfig) -> Self {
+        Self {
+            deny: config.deny_functions.clone(),
+            allow: config.allow_functions.clone(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deny.is_empty() && self.allow.is_empty()
+    }
+
+    /// Whether `function` may be called.
+    ///
+    /// A matching deny pattern wins over any allow pattern. A non-empty allow
+    /// list rejects functions that match none of its patterns.
+    pub fn check(&self, function: &str) -> Result<()> {
+        if let Some(pattern) = self.deny.iter().find(|p| glob_matches(p, function)) {
+            return Err(DebuggerError::PolicyViolation(format!(
+                "`{}` is denied by pattern `{}`",
+                function, pattern
+            ))
+            .into());
+        }
+        if !self.allow.is_empty() && !self.allow.iter().any(|p| glob_matches(p, function)) {
+            return Err(DebuggerError::PolicyViolation(format!(
+                "`{}` matches none of the allowed patterns ({})",
+                function,
+                self.allow.join(", ")
+            ))
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Whether `function` passes the policy, for callers choosing targets.
+    pub fn permits(&self, function: &str) -> bool {
+        self.check(function).is_ok()
+    }
+}
+
+/// Install the process-wide policy (skipped with `--no-policy`).
+pub fn set_function_policy(policy: FunctionPolicy) {
+    if let Ok(mut current) = FUNCTION_POLICY.write() {
+        *current = (!policy.is_empty()).then_some(policy);
+    }
+}
+
+/// The process-wide policy; empty unless one was installed.
+pub fn function_policy() -> FunctionPolicy {
+    FUNCTION_POLICY
+        .read()
+        .ok()
+        .and_then(|policy| policy.clone())
+        .unwrap_or_default()
+}
+
+/// Match `text` against a glob supporting `*` and `?`.
+pub fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it is currently covering up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p).copied() {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, covered)) => {
+                    p = star + 1;
+                    t = covered + 1;
+                    backtrack = Some((star, covered + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(deny: &[&str], allow: &[&str]) -> FunctionPolicy {
+        FunctionPolicy::new(&PolicyConfig {
+            deny_functions: deny.iter().map(|s| s.to_string()).collect(),
+            allow_functions: allow.iter().map(|s| s.to_string()).collect(),
+        })
+    }
+
+    #[test]
+    fn glob_matching() {
+        assert!(glob_matches("emergency_*", "emergency_withdraw"));
+        assert!(glob_matches("emergency_*", "emergency_"));
+        assert!(!glob_matches("emergency_*", "withdraw"));
+        assert!(glob_matches("*_admin", "set_admin"));
+        assert!(glob_matches("get_?", "get_x"));
+        assert!(!glob_matches("get_?", "get_xy"));
+        assert!(glob_matches("*a*b*", "xxaxxbxx"));
+        assert!(!glob_matches("*a*b", "xxaxxbxc"));
+        assert!(glob_matches("transfer", "transfer"));
+        assert!(!glob_matches("transfer", "transfer_from"));
+        assert!(glob_matches("*", ""));
+    }
+
+    #[test]
+    fn deny_wins_and_names_the_pattern() {
+        let policy = policy(&["emergency_*"], &["*"]);
+        let err = policy.check("emergency_pause").unwrap_err().to_string();
+        assert!(err.contains("emergency_*"), "{}", err);
+        assert!(policy.check("transfer").is_ok());
+    }
+
+    #[test]
+    fn allow_list_rejects_everything_else() {
+        let policy = policy(&[], &["get_*", "balance"]);
+        assert!(policy.permits("get_count"));
+        assert!(policy.permits("balance"));
+        let err = policy.check("mint").unwrap_err().to_string();
+        assert!(err.contains("none of the allowed patterns"), "{}", err);
+    }
+
+    #[test]
+    fn empty_policy_permits_everything() {
+        assert!(FunctionPolicy::default().permits("anything"));
+    }
+}
@@ -5,10 +5,14 @@
 //! and instruction-level profiling data.
 
 use crate::inspector::budget::BudgetInfo;
+use crate::inspector::events::ContractEvent;
+use crate::inspector::storage::StorageDiff;
+use serde::Serialize;
 use soroban_env_host::xdr::ScVal;
 use soroban_env_host::{ConversionError, TryFromVal};
 use soroban_sdk::{InvokeError, Val};
 use std::collections::HashMap;
+use std::time::Duration;
 
 /// Re-export for convenience.
 pub use crate::runtime::mocking::MockCallLogEntry as MockCallEntry;
@@ -19,11 +23,119 @@ pub struct ExecutionRecord {
     pub function: String,
     pub args: Vec<ScVal>,
     pub result: std::result::Result<ScVal, String>,
+    /// Budget spent by the contract call itself.
     pub budget: BudgetInfo,
+    /// Budget spent preparing the call: argument conversion, storage seeding.
+    pub setup_budget: BudgetInfo,
     pub storage_before: HashMap<String, String>,
     pub storage_after: HashMap<String, String>,
 }
 
+/// A contract's return value, decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionResult {
+    /// The text `execute` returns; see [`display_value`].
+    pub display: String,
+    /// The value as JSON in `--args` syntax: `null` for `Void`, 128-bit
+    /// integers beyond `i64` as strings, maps with non-symbol keys as
+    /// `[key, value]` pairs.
+    pub value: serde_json::Value,
+    pub raw: ScVal,
+}
+
+impl ExecutionResult {
+    pub fn new(display: String, raw: ScVal) -> Self {
+        Self {
+            display,
+            value: crate::utils::arguments::scval_to_json(&raw),
+            raw,
+        }
+    }
+}
+
+/// Everything one successful call produced, from
+/// [`ContractExecutor::execute_detailed`](crate::runtime::ContractExecutor::execute_detailed).
+///
+/// This is the stable interface for using the debugger as a library: fields
+/// are only ever added, and every field is owned, so an outcome can be kept
+/// after the executor moves on or serialized as it is.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExecutionOutcome {
+    /// Name of the function that was called.
+    pub function: String,
+    /// The return value as `execute` returns it; see [`display_value`].
+    pub display: String,
+    /// The return value as JSON in `--args` syntax, as in
+    /// [`ExecutionResult::value`].
+    pub return_value: serde_json::Value,
+    /// Events emitted during this call only, contract and diagnostic.
+    pub events: Vec<ContractEvent>,
+    /// Storage the call added, changed or removed, with the writer of each key.
+    pub storage_diff: StorageDiff,
+    /// Budget spent by the contract call itself.
+    pub budget: BudgetInfo,
+    /// Budget spent preparing the call: argument conversion, storage seeding.
+    pub setup_budget: BudgetInfo,
+    /// Wall-clock time of the call, excluding argument parsing.
+    pub elapsed: Duration,
+}
+
+/// Render a return value the way the host prints small values (`I64(5)`,
+/// `Symbol(ok)`, `Void`), but with the decoded contents in place of the
+/// object handle the host prints for everything else (`Vec(obj#5)`).
+///
+/// Small values keep the host's text because `--expect`, scenario
+/// `expected_return` and the result cache compare against it.
+pub fn display_value(raw: &ScVal) -> String {
+    let tag = match raw {
+        ScVal::Void => return "Void".to_string(),
+        ScVal::Bool(b) => return if *b { "True" } else { "False" }.to_string(),
+        ScVal::Error(error) => return format!("{:?}", soroban_sdk::Error::from(error.clone())),
+        // Past 56 bits these are objects too; show their seconds either way.
+        ScVal::Timepoint(time) => return format!("Timepoint({})", time.0),
+        ScVal::Duration(span) => return format!("Duration({})", span.0),
+        ScVal::U32(_) => "U32",
+        ScVal::I32(_) => "I32",
+        ScVal::U64(_) => "U64",
+        ScVal::I64(_) => "I64",
+        ScVal::U128(_) => "U128",
+        ScVal::I128(_) => "I128",
+        ScVal::U256(_) => "U256",
+        ScVal::I256(_) => "I256",
+        ScVal::Bytes(_) => "Bytes",
+        ScVal::String(_) => "String",
+        ScVal::Symbol(_) => "Symbol",
+        ScVal::Vec(_) => "Vec",
+        ScVal::Map(_) => "Map",
+        ScVal::Address(_) => "Address",
+        other => return format!("{:?}", other),
+    };
+    let contents = match crate::utils::arguments::scval_to_json(raw) {
+        serde_json::Value::String(text) if tag != "String" => text,
+        other => other.to_string(),
+    };
+    format!("{}({})", tag, contents)
+}
+
+/// `display` for pretty output: a timepoint or duration `value` also shows
+/// as a date or span, as in `Timepoint(2025-01-01T00:00:00Z (1735689600))`,
+/// unless `output.humanize_time` is off.
+pub fn pretty_display(display: &str, value: &serde_json::Value) -> String {
+    use crate::utils::time::{format_timepoint, humanize_duration, humanize_time};
+
+    let tag = value.get("$type").and_then(|tag| tag.as_str());
+    let seconds = value.get("value").and_then(|seconds| seconds.as_u64());
+    match (tag, seconds) {
+        (Some("timepoint"), Some(seconds)) if humanize_time() => {
+            format!("Timepoint({})", format_timepoint(seconds))
+        }
+        (Some("duration"), Some(seconds)) if humanize_time() => {
+            format!("Duration({})", humanize_duration(seconds))
+        }
+        _ => display.to_string(),
+    }
+}
+
 /// Storage snapshot for dry-run rollback.
 #[derive(Clone)]
 pub struct StorageSnapshot {
@@ -60,7 +172,7 @@ pub(super) fn format_invocation_result(
         Ok(Ok(val)) => {
             info!("Function executed successfully");
             match ScVal::try_from_val(host, val) {
-                Ok(sc_val) => (Ok(format!("{:?}", val)), Ok(sc_val)),
+                Ok(sc_val) => (Ok(display_value(&sc_val)), Ok(sc_val)),
                 Err(e) => {
                     let msg = format!("Result conversion failed: {:?}", e);
                     (
@@ -83,11 +195,14 @@ pub(super) fn format_invocation_result(
                 InvokeError::Contract(code) => {
                     warn!("Contract returned error code: {}", code);
                     error_db.display_error(*code);
-                    format!(
-                        "The contract returned an error code: {}. This typically indicates \
+                    let error = error_db.contract_error(*code);
+                    let msg = format!(
+                        "The contract returned error {}. This typically indicates \
                          a business logic failure (e.g. `panic!` or `require!`).",
-                        code
-                    )
+                        error
+                    );
+                    crate::output::record_failure_contract_error(error);
+                    msg
                 }
                 InvokeError::Abort => {
                     warn!("Contract execution aborted");
@@ -121,8 +236,15 @@ pub enum RuntimeError {
 impl std::fmt::Display for RuntimeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            RuntimeError::Timeout { elapsed_ms, limit_ms } => {
-                write!(f, "Execution timed out after {}ms (limit: {}ms)", elapsed_ms, limit_ms)
+            RuntimeError::Timeout {
+                elapsed_ms,
+                limit_ms,
+            } => {
+                write!(
+                    f,
+                    "Execution timed out after {}ms (limit: {}ms)",
+                    elapsed_ms, limit_ms
+                )
             }
             RuntimeError::Cancelled { reason } => {
                 write!(f, "Execution cancelled: {}", reason)
@@ -134,12 +256,17 @@ impl std::fmt::Display for RuntimeError {
 impl RuntimeError {
     /// Create a timeout error with elapsed and limit durations.
     pub fn timeout(elapsed_ms: u64, limit_ms: u64) -> Self {
-        Self::Timeout { elapsed_ms, limit_ms }
+        Self::Timeout {
+            elapsed_ms,
+            limit_ms,
+        }
     }
 
     /// Create a cancellation error with a reason.
     pub fn cancelled(reason: impl Into<String>) -> Self {
-        Self::Cancelled { reason: reason.into() }
+        Self::Cancelled {
+            reason: reason.into(),
+        }
     }
 
     /// Returns true if this error is a timeout.
@@ -151,4 +278,107 @@ impl RuntimeError {
     pub fn is_cancelled(&self) -> bool {
         matches!(self, Self::Cancelled { .. })
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{Int128Parts, ScError, ScMap, ScMapEntry, ScSymbol, ScVec};
+
+    fn shown(raw: ScVal) -> (String, serde_json::Value) {
+        let result = ExecutionResult::new(display_value(&raw), raw);
+        (result.display, result.value)
+    }
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    #[test]
+    fn small_values_keep_the_host_text() {
+        assert_eq!(shown(ScVal::I64(5)), ("I64(5)".into(), 5.into()));
+        assert_eq!(shown(ScVal::Void), ("Void".into(), serde_json::Value::Null));
+
+        let env = soroban_sdk::Env::default();
+        for raw in [
+            ScVal::Bool(true),
+            ScVal::U32(7),
+            ScVal::I128(Int128Parts {
+                hi: -1,
+                lo: u64::MAX,
+            }),
+            ScVal::Error(ScError::Contract(3)),
+            sym("ok"),
+        ] {
+            let val = Val::try_from_val(&env, &raw).unwrap();
+            assert_eq!(display_value(&raw), format!("{:?}", val));
+        }
+    }
+
+    #[test]
+    fn object_values_show_their_contents() {
+        let big = ScVal::I128(Int128Parts { hi: 1, lo: 0 });
+        assert_eq!(
+            shown(big),
+            (
+                "I128(18446744073709551616)".into(),
+                "18446744073709551616".into()
+            )
+        );
+
+        // A contract struct arrives as a symbol-keyed map.
+        let point = ScVal::Map(Some(
+            ScMap::sorted_from(vec![
+                ScMapEntry {
+                    key: sym("x"),
+                    val: ScVal::U32(1),
+                },
+                ScMapEntry {
+                    key: sym("y"),
+                    val: ScVal::Vec(Some(ScVec(vec![ScVal::U32(2)].try_into().unwrap()))),
+                },
+            ])
+            .unwrap(),
+        ));
+        assert_eq!(
+            shown(point),
+            (
+                r#"Map({"x":1,"y":[2]})"#.into(),
+                serde_json::json!({"x": 1, "y": [2]})
+            )
+        );
+
+        let by_number = ScVal::Map(Some(
+            ScMap::sorted_from(vec![ScMapEntry {
+                key: ScVal::U32(7),
+                val: sym("seven"),
+            }])
+            .unwrap(),
+        ));
+        assert_eq!(
+            shown(by_number),
+            (
+                r#"Map([[7,"seven"]])"#.into(),
+                serde_json::json!([[7, "seven"]])
+            )
+        );
+    }
+
+    #[test]
+    fn time_values_show_their_seconds_and_pretty_output_a_date_or_span() {
+        use soroban_env_host::xdr::{Duration as XdrDuration, TimePoint};
+        let (display, value) = shown(ScVal::Timepoint(TimePoint(1_735_689_600)));
+        assert_eq!(display, "Timepoint(1735689600)");
+        assert_eq!(
+            pretty_display(&display, &value),
+            "Timepoint(2025-01-01T00:00:00Z (1735689600))"
+        );
+
+        // Past 56 bits the host keeps the value as an object.
+        let (display, value) = shown(ScVal::Duration(XdrDuration(u64::MAX)));
+        assert_eq!(display, format!("Duration({})", u64::MAX));
+        assert!(pretty_display(&display, &value).ends_with(&format!("({}s))", u64::MAX)));
+
+        assert_eq!(pretty_display("I64(5)", &serde_json::json!(5)), "I64(5)");
+    }
+}
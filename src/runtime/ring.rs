@@ -0,0 +1,363 @@
+//! An always-on, bounded record of recent call boundaries and storage writes.
+//!
+//! Every invocation appends to the ring, which keeps the last `capacity`
+//! records that are no older than `window`. When a run fails, traps or times
+//! out, the ring is printed with the error, so the calls leading up to the
+//! failure are visible without having asked for `--trace-output`. Records
+//! hold names and keys only; arguments and values are left to the full
+//! trace, which keeps recording cheap.
+//!
+//! Calls come from the `fn_call` / `fn_return` events the host records, which
+//! are only available once it returns. A timeout therefore shows the records
+//! of earlier invocations and the call that was running, not its nested calls.
+
+use crate::runtime::observer::{call_target, symbol, StorageWrite};
+use serde::Serialize;
+use soroban_env_host::events::HostEvent;
+use soroban_env_host::xdr::{ContractEventBody, ContractEventType};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Records kept when no `--ring-size` is given.
+pub const DEFAULT_RING_CAPACITY: usize = 4096;
+
+/// Age after which records are dropped when no `--ring-window` is given.
+pub const DEFAULT_RING_WINDOW: Duration = Duration::from_secs(300);
+
+/// One thing the ring recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RingRecord {
+    /// A contract call started; depth 0 is the invocation itself.
+    Call {
+        depth: usize,
+        contract: String,
+        function: String,
+    },
+    /// A contract call returned.
+    Return { depth: usize, function: String },
+    /// A contract call was still running when the invocation failed.
+    Trap { depth: usize, function: String },
+    /// The invocation wrote a storage key.
+    Write { key: String },
+    /// The invocation failed with `error`.
+    Failed { function: String, error: String },
+    /// The invocation was still running when the timeout fired.
+    TimedOut { function: String, seconds: u64 },
+}
+
+/// A record with its position in the session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RingEntry {
+    /// Counts every record ever pushed, so gaps show what was dropped.
+    pub seq: u64,
+    /// Milliseconds since the ring was created.
+    pub elapsed_ms: u64,
+    #[serde(flatten)]
+    pub record: RingRecord,
+}
+
+/// The last records of a session, bounded by count and age.
+#[derive(Debug, Clone)]
+pub struct TraceRing {
+    capacity: usize,
+    window: Option<Duration>,
+    started: Instant,
+    entries: VecDeque<(Instant, RingEntry)>,
+    next_seq: u64,
+}
+
+impl Default for TraceRing {
+    fn default() -> Self {
+        Self::new(DEFAULT_RING_CAPACITY, Some(DEFAULT_RING_WINDOW))
+    }
+}
+
+impl TraceRing {
+    /// A ring of `capacity` records, dropping those older than `window`. A
+    /// capacity of 0 records nothing.
+    pub fn new(capacity: usize, window: Option<Duration>) -> Self {
+        Self {
+            capacity,
+            window,
+            started: Instant::now(),
+            entries: VecDeque::with_capacity(capacity.min(DEFAULT_RING_CAPACITY)),
+            next_seq: 0,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity > 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn push(&mut self, record: RingRecord) {
+        if !self.is_enabled() {
+            return;
+        }
+        let now = Instant::now();
+        self.expire(now);
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((
+            now,
+            RingEntry {
+                seq: self.next_seq,
+                elapsed_ms: now.duration_since(self.started).as_millis() as u64,
+                record,
+            },
+        ));
+        self.next_seq += 1;
+    }
+
+    /// Record the calls in `events` and the storage `writes` of one
+    /// invocation. With `failure`, calls still open are recorded as traps,
+    /// followed by the error.
+    pub fn record_invocation(
+        &mut self,
+        function: &str,
+        events: &[HostEvent],
+        writes: &[StorageWrite],
+        failure: Option<&str>,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut open: Vec<String> = Vec::new();
+        let mut writes = Some(writes);
+        for event in events {
+            if event.event.type_ != ContractEventType::Diagnostic {
+                continue;
+            }
+            let ContractEventBody::V0(body) = &event.event.body;
+            let topics = body.topics.as_slice();
+            match topics.first().and_then(symbol).as_deref() {
+                Some("fn_call") => {
+                    let (contract, function) = call_target(topics);
+                    self.push(RingRecord::Call {
+                        depth: open.len(),
+                        contract,
+                        function: function.clone(),
+                    });
+                    open.push(function);
+                }
+                Some("fn_return") => {
+                    if let Some(function) = open.pop() {
+                        if open.is_empty() {
+                            self.push_writes(writes.take().unwrap_or_default());
+                        }
+                        self.push(RingRecord::Return {
+                            depth: open.len(),
+                            function,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+        while let Some(function) = open.pop() {
+            let depth = open.len();
+            self.push(RingRecord::Trap { depth, function });
+        }
+        self.push_writes(writes.unwrap_or_default());
+        if let Some(error) = failure {
+            self.push(RingRecord::Failed {
+                function: function.to_string(),
+                error: error.to_string(),
+            });
+        }
+    }
+
+    fn push_writes(&mut self, writes: &[StorageWrite]) {
+        for write in writes {
+            self.push(RingRecord::Write {
+                key: write.key.clone(),
+            });
+        }
+    }
+
+    /// Drop records older than the window.
+    fn expire(&mut self, now: Instant) {
+        let Some(window) = self.window else {
+            return;
+        };
+        while self
+            .entries
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) > window)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    /// The records still inside the window, oldest first.
+    pub fn entries(&self) -> Vec<RingEntry> {
+        let now = Instant::now();
+        self.entries
+            .iter()
+            .filter(|(at, _)| self.window.is_none_or(|w| now.duration_since(*at) <= w))
+            .map(|(_, entry)| entry.clone())
+            .collect()
+    }
+
+    /// Records pushed so far that are no longer in the ring.
+    pub fn dropped(&self) -> u64 {
+        self.next_seq - self.entries().len() as u64
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// The ring as printed with a failure: a header, then one line per entry.
+    pub fn report(&self) -> Vec<String> {
+        let entries = self.entries();
+        let mut lines = vec![format!(
+            "--- Trace Ring (last {} record(s), {} dropped) ---",
+            entries.len(),
+            self.next_seq - entries.len() as u64
+        )];
+        lines.extend(format_entries(&entries));
+        lines
+    }
+}
+
+/// One line per entry: `#seq +elapsed record`, calls indented by depth.
+pub fn format_entries(entries: &[RingEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .map(|entry| {
+            let record = match &entry.record {
+                RingRecord::Call {
+                    depth,
+                    contract,
+                    function,
+                } => format!("{}call {}.{}", "  ".repeat(*depth), contract, function),
+                RingRecord::Return { depth, function } => {
+                    format!("{}return {}", "  ".repeat(*depth), function)
+                }
+                RingRecord::Trap { depth, function } => {
+                    format!("{}trap {}", "  ".repeat(*depth), function)
+                }
+                RingRecord::Write { key } => format!("write {}", key),
+                RingRecord::Failed { function, error } => {
+                    format!("failed {}: {}", function, error)
+                }
+                RingRecord::TimedOut { function, seconds } => {
+                    format!("timed out {} after {}s", function, seconds)
+                }
+            };
+            format!("#{} +{}ms {}", entry.seq, entry.elapsed_ms, record)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_env_host::xdr::{
+        ContractEvent, ContractEventV0, ExtensionPoint, ScBytes, ScSymbol, ScVal,
+    };
+
+    fn diagnostic(topics: Vec<ScVal>) -> HostEvent {
+        HostEvent {
+            event: ContractEvent {
+                ext: ExtensionPoint::V0,
+                contract_id: None,
+                type_: ContractEventType::Diagnostic,
+                body: ContractEventBody::V0(ContractEventV0 {
+                    topics: topics.try_into().unwrap(),
+                    data: ScVal::Void,
+                }),
+            },
+            failed_call: false,
+        }
+    }
+
+    fn sym(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(name.try_into().unwrap()))
+    }
+
+    fn call(function: &str) -> HostEvent {
+        diagnostic(vec![
+            sym("fn_call"),
+            ScVal::Bytes(ScBytes(vec![1; 32].try_into().unwrap())),
+            sym(function),
+        ])
+    }
+
+    fn write(key: &str) -> StorageWrite {
+        StorageWrite {
+            key: key.to_string(),
+            old: None,
+            new: Some("1".to_string()),
+        }
+    }
+
+    #[test]
+    fn keeps_the_last_records_and_closes_trapped_calls() {
+        let mut ring = TraceRing::new(4, None);
+        let events = [
+            call("swap"),
+            call("transfer"),
+            diagnostic(vec![sym("fn_return")]),
+        ];
+        ring.record_invocation("swap", &events, &[write("a")], Some("trapped"));
+
+        let records: Vec<RingRecord> = ring.entries().into_iter().map(|e| e.record).collect();
+        assert_eq!(
+            records,
+            [
+                RingRecord::Return {
+                    depth: 1,
+                    function: "transfer".to_string()
+                },
+                RingRecord::Trap {
+                    depth: 0,
+                    function: "swap".to_string()
+                },
+                RingRecord::Write {
+                    key: "a".to_string()
+                },
+                RingRecord::Failed {
+                    function: "swap".to_string(),
+                    error: "trapped".to_string()
+                },
+            ]
+        );
+        assert_eq!(ring.dropped(), 2);
+        let lines = format_entries(&ring.entries());
+        assert!(lines[1].ends_with("trap swap"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn writes_precede_the_top_level_return() {
+        let mut ring = TraceRing::default();
+        let events = [call("increment"), diagnostic(vec![sym("fn_return")])];
+        ring.record_invocation("increment", &events, &[write("c")], None);
+        let lines = format_entries(&ring.entries());
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].ends_with("write c"), "{:?}", lines);
+        assert!(lines[2].ends_with("return increment"), "{:?}", lines);
+    }
+
+    #[test]
+    fn disabled_and_expired_rings_keep_nothing() {
+        let mut ring = TraceRing::new(0, None);
+        ring.push(RingRecord::Write {
+            key: "a".to_string(),
+        });
+        assert!(ring.entries().is_empty());
+
+        let mut ring = TraceRing::new(8, Some(Duration::ZERO));
+        ring.push(RingRecord::Write {
+            key: "a".to_string(),
+        });
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(ring.entries().is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//! Saved executor state for `run --save-state` and `--load-state`.
+//!
+//! A state file holds the whole environment a run finished with — contract
+//! storage, ledger info, address generators — plus the contract it belongs
+//! to, so a later run can carry on from exactly that point. Unlike a network
+//! snapshot it is the debugger's own state, not foreign ledger entries.
+//!
+//! Files carry a `format` marker and a `version`. Files from a newer build
+//! are refused; older versions are upgraded by [`migrate`] when a migration
+//! exists and refused with the version otherwise.
+
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use soroban_sdk::testutils::Snapshot;
+use std::fs;
+use std::path::Path;
+
+/// Value of the `format` field.
+pub const SESSION_STATE_FORMAT: &str = "soroban-debug-state";
+
+/// Newest state file version this build reads and writes.
+pub const SESSION_STATE_VERSION: u32 = 1;
+
+/// Everything needed to resume a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionState {
+    /// Always [`SESSION_STATE_FORMAT`].
+    pub format: String,
+    pub version: u32,
+    /// SHA-256 of the contract WASM the state was produced by; its code is in
+    /// the ledger, so the state only resumes that contract.
+    pub wasm_hash: String,
+    /// Address of the contract, as a `C...` strkey.
+    pub contract: String,
+    /// The environment: ledger info and entries, generators, auth, events.
+    pub snapshot: Snapshot,
+}
+
+impl SessionState {
+    pub fn new(wasm_hash: String, contract: String, snapshot: Snapshot) -> Self {
+        Self {
+            format: SESSION_STATE_FORMAT.to_string(),
+            version: SESSION_STATE_VERSION,
+            wasm_hash,
+            contract,
+            snapshot,
+        }
+    }
+
+    /// Parse a state file, upgrading an older version.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let value: serde_json::Value =
+            serde_json::from_slice(bytes).map_err(|e| invalid(format!("not valid JSON: {}", e)))?;
+        if value.get("format").and_then(|f| f.as_str()) != Some(SESSION_STATE_FORMAT) {
+            return Err(invalid(format!(
+                "not a {} file; write one with run --save-state",
+                SESSION_STATE_FORMAT
+            )));
+        }
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or_else(|| invalid("missing `version`".to_string()))?;
+        let value = migrate(value, version)?;
+        serde_json::from_value(value).map_err(|e| invalid(e.to_string()))
+    }
+
+    pub fn read_file(path: &Path) -> Result<Self> {
+        let bytes = fs::read(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read state file {:?}: {}", path, e))
+        })?;
+        Self::decode(&bytes).map_err(|e| e.wrap_err(format!("Failed to load state {:?}", path)))
+    }
+
+    pub fn write_file(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|e| DebuggerError::FileError(format!("Failed to serialize state: {}", e)))?;
+        fs::write(path, json).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to write state file {:?}: {}", path, e)).into()
+        })
+    }
+}
+
+/// Bring a file of `version` up to [`SESSION_STATE_VERSION`]. Version 1 is
+/// the first, so there is nothing to upgrade yet; a change to
+/// [`SessionState`] adds a step here.
+fn migrate(value: serde_json::Value, version: u64) -> Result<serde_json::Value> {
+    match version {
+        v if v == u64::from(SESSION_STATE_VERSION) => Ok(value),
+        v if v > u64::from(SESSION_STATE_VERSION) => Err(invalid(format!(
+            "state format version {} is newer than this debugger supports ({})",
+            v, SESSION_STATE_VERSION
+        ))),
+        v => Err(invalid(format!(
+            "state format version {} is no longer supported; save the state again with this build",
+            v
+        ))),
+    }
+}
+
+fn invalid(reason: String) -> miette::Report {
+    DebuggerError::FileError(format!("Invalid state file: {}", reason)).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(state: &SessionState) -> serde_json::Value {
+        serde_json::to_value(state).unwrap()
+    }
+
+    #[test]
+    fn round_trips_and_checks_the_version() {
+        let state = SessionState::new("ab".repeat(32), "C".into(), Snapshot::default());
+        let bytes = serde_json::to_vec(&state).unwrap();
+        assert_eq!(SessionState::decode(&bytes).unwrap(), state);
+
+        let mut newer = encoded(&state);
+        newer["version"] = serde_json::json!(SESSION_STATE_VERSION + 1);
+        let err = SessionState::decode(newer.to_string().as_bytes()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("is newer than this debugger supports"),
+            "{}",
+            err
+        );
+
+        let mut older = encoded(&state);
+        older["version"] = serde_json::json!(0);
+        let err = SessionState::decode(older.to_string().as_bytes()).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("state format version 0 is no longer supported"),
+            "{}",
+            err
+        );
+
+        let err = SessionState::decode(b"{\"ledger\": {}}").unwrap_err();
+        assert!(err.to_string().contains("not a soroban-debug-state file"));
+    }
+}
@@ -1,7 +1,13 @@
-use crate::cli::args::{ScenarioArgs, Verbosity};
+use crate::cli::args::{ExpectMode, ScenarioArgs, Verbosity};
+use crate::debugger::assertion::AssertionContext;
 use crate::debugger::engine::DebuggerEngine;
+use crate::environments::EnvironmentStore;
 use crate::inspector::budget::{BudgetInfo, BudgetInspector};
+use crate::inspector::cost_table::CostTableBuilder;
+use crate::inspector::event_export::{host_events, LedgerPosition, NdjsonEventWriter};
 use crate::inspector::events::{ContractEvent, EventInspector};
+use crate::inspector::storage::StorageFilter;
+use crate::inspector::storage_provenance::StorageProvenance;
 use crate::logging;
 use crate::runtime::executor::{ContractExecutor, DEFAULT_EXECUTION_TIMEOUT_SECS};
 use crate::ui::formatter::Formatter;
@@ -47,6 +53,75 @@ pub struct ScenarioStep {
     /// Later steps can reference the value using `{{var_name}}` in their `args` or
     /// `expected_return` fields.
     pub capture: Option<String>,
+    /// Overrides `--expect-mode` for this step's assertions (`"strict"` or `"warn"`).
+    #[serde(default, with = "expect_mode_serde")]
+    pub expect_mode: Option<ExpectMode>,
+    /// Expectations that each carry their own `mode`, written as one table or
+    /// a list of them, e.g. `expect = { return = "1", mode = "warn" }`.
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub expect: Vec<ScenarioExpectation>,
+    /// Conditions checked after the call: who wrote storage so far, e.g.
+    /// `"writers(admin) == initialize"`, or an expression over the return
+    /// value such as `"result > 0"`. See [`AssertionContext`].
+    pub assertions: Option<Vec<String>>,
+}
+
+/// `expect_mode` is written as on the command line. `ExpectMode` lives in
+/// the CLI arguments, which build.rs compiles without serde.
+mod expect_mode_serde {
+    use crate::cli::args::ExpectMode;
+    use clap::ValueEnum;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        mode: &Option<ExpectMode>,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        match mode {
+            Some(mode) => serializer.serialize_some(mode.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<Option<ExpectMode>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|mode| ExpectMode::from_str(&mode, false).map_err(de::Error::custom))
+            .transpose()
+    }
+}
+
+/// One entry of a step's `expect`. Each check it names is compared like the
+/// step field of the same purpose; `mode` defaults to the step's mode.
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ScenarioExpectation {
+    /// Like `expected_return`; supports `{{var}}` interpolation.
+    #[serde(rename = "return")]
+    pub return_value: Option<String>,
+    /// Like `expected_storage`.
+    pub storage: Option<HashMap<String, String>>,
+    /// Like one of `assertions`.
+    pub assert: Option<String>,
+    #[serde(default, with = "expect_mode_serde")]
+    pub mode: Option<ExpectMode>,
+}
+
+fn one_or_many<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<ScenarioExpectation>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(ScenarioExpectation),
+        Many(Vec<ScenarioExpectation>),
+    }
+
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(expectation) => vec![expectation],
+        OneOrMany::Many(expectations) => expectations,
+    })
 }
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -68,10 +143,7 @@ pub struct ScenarioBudgetAssertion {
 /// cycles (A includes B includes A) are detected and reported immediately.
 pub fn load_scenario(path: &Path, visiting: &mut HashSet<PathBuf>) -> Result<Vec<ScenarioStep>> {
     let canonical = path.canonicalize().map_err(|e| {
-        DebuggerError::FileError(format!(
-            "Cannot resolve scenario path {:?}: {}",
-            path, e
-        ))
+        DebuggerError::FileError(format!("Cannot resolve scenario path {:?}: {}", path, e))
     })?;
 
     if !visiting.insert(canonical.clone()) {
@@ -139,8 +211,30 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
         DebuggerError::WasmLoadError(format!("Failed to load WASM {:?}: {}", args.contract, e))
     })?;
 
+    let mut environment = match &args.env {
+        Some(name) => Some(EnvironmentStore::open_default()?.open(name)?),
+        None => None,
+    };
+
     let mut executor = ContractExecutor::new(wasm_file.bytes)?;
 
+    if let Some(environment) = &environment {
+        if let Some(state) = environment.load_state(&wasm_file.sha256_hash)? {
+            executor.restore_env_snapshot(&state)?;
+        }
+        if !environment.manifest().mocks.is_empty() {
+            executor.set_mock_specs(&environment.manifest().mocks)?;
+        }
+        println!(
+            "{}",
+            Formatter::info(format!(
+                "Using environment '{}' ({} commit(s))",
+                environment.name(),
+                environment.manifest().commits
+            ))
+        );
+    }
+
     if let Some(storage_json) = &args.storage {
         serde_json::from_str::<serde_json::Value>(storage_json).map_err(|e| {
             DebuggerError::StorageError(format!("Failed to parse initial storage JSON: {}", e))
@@ -153,8 +247,14 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
         Formatter::success(format!("Running {} scenario steps...\n", steps.len()))
     );
 
+    let mut events_ndjson = match &args.events_ndjson {
+        Some(path) => Some(NdjsonEventWriter::create(path)?),
+        None => None,
+    };
+
     let mut engine = DebuggerEngine::new(executor, vec![]);
     let mut all_passed = true;
+    let mut soft_failures = 0usize;
     let mut variables: HashMap<String, String> = HashMap::new();
 
     for (i, step) in steps.iter().enumerate() {
@@ -191,7 +291,18 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
         let events_before_len = engine.executor().get_events()?.len();
         let result = engine.execute(&step.function, parsed_args.as_deref());
 
+        if let Some(writer) = events_ndjson.as_mut() {
+            let events = host_events(engine.executor().host())?;
+            writer.write_invocation(
+                events.get(events_before_len..).unwrap_or_default(),
+                LedgerPosition::current(engine.executor().env()),
+            )?;
+        }
+
         let mut step_passed = true;
+        // Unexpected execution errors are not expectations and always fail the step.
+        let mut execution_failed = false;
+        let mut returned = None;
         let expects_failure = step.expected_error.is_some() || step.expected_panic.is_some();
 
         match result {
@@ -207,6 +318,7 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
                     step_passed = false;
                 } else {
                     println!("  Result: {}", res);
+                    returned = Some(res.trim().to_string());
 
                     if let Some(var_name) = &step.capture {
                         variables.insert(var_name.clone(), res.trim().to_string());
@@ -285,6 +397,7 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
                         Formatter::error(format!("? Execution failed: {}", e))
                     );
                     step_passed = false;
+                    execution_failed = true;
                 }
             }
         }
@@ -325,54 +438,123 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
         if step_passed {
             if let Some(expected_storage) = &step.expected_storage {
                 let snapshot = engine.executor().get_storage_snapshot()?;
-                let mut storage_passed = true;
-                for (key, expected_val) in expected_storage {
-                    if let Some(actual_val) = snapshot.get(key) {
-                        if actual_val.trim() == expected_val.trim() {
-                            println!(
-                                "  {}",
-                                Formatter::success(format!(
-                                    "? Storage assertion passed for key '{}'",
-                                    key
-                                ))
-                            );
-                        } else {
-                            println!("  {}", Formatter::error(format!("? Storage assertion failed for key '{}'! Expected '{}', got '{}'", key, expected_val, actual_val)));
-                            storage_passed = false;
-                        }
+                let provenance = engine.executor().storage_provenance();
+                if !print_checks(assert_expected_storage(
+                    expected_storage,
+                    &snapshot,
+                    provenance,
+                )) {
+                    step_passed = false;
+                }
+            }
+        }
+
+        if step_passed {
+            if let Some(assertions) = &step.assertions {
+                let context = AssertionContext::for_executor(engine.executor());
+                for assertion in assertions {
+                    if context.check(assertion)? {
+                        println!(
+                            "  {}",
+                            Formatter::success(format!("? Assertion passed: {}", assertion))
+                        );
                     } else {
                         println!(
                             "  {}",
                             Formatter::error(format!(
-                                "? Storage assertion failed! Key '{}' not found",
-                                key
+                                "? Assertion failed: {}{}",
+                                assertion,
+                                context.failure_detail(assertion)
                             ))
                         );
-                        storage_passed = false;
+                        step_passed = false;
                     }
                 }
-                if !storage_passed {
-                    step_passed = false;
+            }
+        }
+
+        let expect_mode = step.expect_mode.unwrap_or(args.expect_mode);
+        let mut strict_failed = !step_passed && expect_mode == ExpectMode::Strict;
+        let mut warn_failed = !step_passed && expect_mode == ExpectMode::Warn;
+        if !execution_failed {
+            for expectation in &step.expect {
+                let passed = check_expectation(
+                    expectation,
+                    returned.as_deref(),
+                    &variables,
+                    engine.executor(),
+                )?;
+                match expectation.mode.unwrap_or(expect_mode) {
+                    ExpectMode::Strict => strict_failed |= !passed,
+                    ExpectMode::Warn => warn_failed |= !passed,
                 }
             }
         }
 
-        if step_passed {
+        if execution_failed || strict_failed {
             println!(
                 "{}",
-                Formatter::success(format!("Step {} passed.\n", i + 1))
+                Formatter::warning(format!("Step {} failed.\n", i + 1))
             );
+            all_passed = false;
+            break;
+        } else if warn_failed {
+            println!(
+                "{}",
+                Formatter::warning(format!(
+                    "!!! Step {} expectations failed (expect mode: warn); continuing.\n",
+                    i + 1
+                ))
+            );
+            soft_failures += 1;
         } else {
             println!(
                 "{}",
-                Formatter::warning(format!("Step {} failed.\n", i + 1))
+                Formatter::success(format!("Step {} passed.\n", i + 1))
+            );
+        }
+    }
+
+    if args.cost_table {
+        let mut costs = CostTableBuilder::new();
+        costs.record_all(engine.executor().call_costs());
+        println!("\n{}", Formatter::info("--- Cost Table ---"));
+        println!("{}", costs.build().render());
+    }
+
+    if let Some(top) = args.storage_heatmap {
+        let filter = StorageFilter::new(&args.storage_filter)
+            .map_err(|e| DebuggerError::StorageError(format!("Invalid storage filter: {}", e)))?;
+        println!("\n{}", Formatter::info("--- Storage Heatmap ---"));
+        println!(
+            "{}",
+            engine.executor().storage_heatmap().render(top, &filter)
+        );
+    }
+
+    if all_passed && args.commit {
+        if let Some(environment) = environment.as_mut() {
+            environment.commit(&engine.executor().env_snapshot(), &wasm_file.sha256_hash)?;
+            println!(
+                "{}",
+                Formatter::success(format!(
+                    "Committed state to environment '{}'",
+                    environment.name()
+                ))
             );
-            all_passed = false;
-            break;
         }
     }
 
-    if all_passed {
+    if all_passed && soft_failures > 0 {
+        println!(
+            "{}",
+            Formatter::warning(format!(
+                "Scenario completed with {} step(s) failing expectations in warn mode.",
+                soft_failures
+            ))
+        );
+        Ok(())
+    } else if all_passed {
         println!(
             "{}",
             Formatter::success("All scenario steps passed successfully!")
@@ -383,6 +565,81 @@ pub fn run_scenario(args: ScenarioArgs, _verbosity: Verbosity) -> Result<()> {
     }
 }
 
+/// Checks one `expect` entry after the call, printing a line per check.
+/// `returned` is the step's return value, or `None` when it failed.
+fn check_expectation(
+    expectation: &ScenarioExpectation,
+    returned: Option<&str>,
+    variables: &HashMap<String, String>,
+    executor: &ContractExecutor,
+) -> Result<bool> {
+    let mut passed = true;
+    if let Some(expected) = &expectation.return_value {
+        let expected = interpolate_variables(expected, variables)?;
+        passed &= print_checks(match returned {
+            Some(actual) if actual == expected.trim() => {
+                Ok(vec!["? Return value assertion passed".to_string()])
+            }
+            Some(actual) => Err(vec![format!(
+                "? Return value assertion failed! Expected '{}', got '{}'",
+                expected, actual
+            )]),
+            None => Err(vec![format!(
+                "? Return value assertion failed! Expected '{}', but the step returned nothing",
+                expected
+            )]),
+        });
+    }
+    if let Some(expected_storage) = &expectation.storage {
+        let snapshot = executor.get_storage_snapshot()?;
+        passed &= print_checks(assert_expected_storage(
+            expected_storage,
+            &snapshot,
+            executor.storage_provenance(),
+        ));
+    }
+    if let Some(assertion) = &expectation.assert {
+        let context = AssertionContext::for_executor(executor);
+        passed &= print_checks(if context.check(assertion)? {
+            Ok(vec![format!("? Assertion passed: {}", assertion)])
+        } else {
+            Err(vec![format!(
+                "? Assertion failed: {}{}",
+                assertion,
+                context.failure_detail(assertion)
+            )])
+        });
+    }
+    Ok(passed)
+}
+
+/// Prints the messages of a check as passed or failed lines and returns
+/// whether it passed.
+fn print_checks(checks: std::result::Result<Vec<String>, Vec<String>>) -> bool {
+    match checks {
+        Ok(messages) => {
+            for message in messages {
+                println!("  {}", Formatter::success(message));
+            }
+            true
+        }
+        Err(messages) => {
+            for message in messages {
+                println!("  {}", Formatter::error(message));
+            }
+            false
+        }
+    }
+}
+
+/// ` (last written by step N f (#seq))` for `key`, or nothing if it was never written.
+fn written_by(provenance: &StorageProvenance, key: &str) -> String {
+    match provenance.lookup(key) {
+        Some(provenance) => format!(" (last written by {})", provenance.writer),
+        None => String::new(),
+    }
+}
+
 /// Replaces `{{var_name}}` placeholders in `template` with values from `variables`.
 fn interpolate_variables(template: &str, variables: &HashMap<String, String>) -> Result<String> {
     let re = Regex::new(r"\{\{(\w+)\}\}").unwrap();
@@ -456,6 +713,40 @@ fn assert_expected_events(
     ))
 }
 
+fn assert_expected_storage(
+    expected_storage: &HashMap<String, String>,
+    snapshot: &HashMap<String, String>,
+    provenance: &StorageProvenance,
+) -> std::result::Result<Vec<String>, Vec<String>> {
+    let mut passed = Vec::new();
+    let mut failed = Vec::new();
+
+    for (key, expected_val) in expected_storage {
+        match snapshot.get(key) {
+            Some(actual_val) if actual_val.trim() == expected_val.trim() => {
+                passed.push(format!("? Storage assertion passed for key '{}'", key));
+            }
+            Some(actual_val) => failed.push(format!(
+                "? Storage assertion failed for key '{}'! Expected '{}', got '{}'{}",
+                key,
+                expected_val,
+                actual_val,
+                written_by(provenance, key)
+            )),
+            None => failed.push(format!(
+                "? Storage assertion failed! Key '{}' not found",
+                key
+            )),
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(passed)
+    } else {
+        Err(failed)
+    }
+}
+
 fn assert_budget_limits(
     expected_budget: &ScenarioBudgetAssertion,
     actual_budget: &BudgetInfo,
@@ -851,6 +1142,53 @@ function = "increment"
         assert!(scenario.steps[0].expected_panic.is_none());
     }
 
+    #[test]
+    fn test_step_expect_mode_override_deserialization() {
+        let toml_str = r#"
+            [[steps]]
+            function = "increment"
+            expected_return = "2"
+            expect_mode = "warn"
+
+            [[steps]]
+            function = "increment"
+        "#;
+
+        let scenario: Scenario = toml::from_str(toml_str).unwrap();
+        assert_eq!(scenario.steps[0].expect_mode, Some(ExpectMode::Warn));
+        assert_eq!(scenario.steps[1].expect_mode, None);
+    }
+
+    #[test]
+    fn test_expect_accepts_a_table_or_a_list_with_modes() {
+        let toml_str = r#"
+            [[steps]]
+            function = "increment"
+            expect = { return = "1", mode = "warn" }
+
+            [[steps]]
+            function = "increment"
+            [[steps.expect]]
+            return = "2"
+            [[steps.expect]]
+            storage = { counter = "2" }
+            mode = "strict"
+        "#;
+
+        let scenario: Scenario = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            scenario.steps[0].expect,
+            vec![ScenarioExpectation {
+                return_value: Some("1".to_string()),
+                mode: Some(ExpectMode::Warn),
+                ..Default::default()
+            }]
+        );
+        let modes: Vec<_> = scenario.steps[1].expect.iter().map(|e| e.mode).collect();
+        assert_eq!(modes, vec![None, Some(ExpectMode::Strict)]);
+        assert!(scenario.steps[1].expect[1].storage.is_some());
+    }
+
     #[test]
     fn test_step_timeout_override_deserialization() {
         let toml_str = r#"
@@ -898,6 +1236,7 @@ function = "increment"
         }];
         let actual = vec![ContractEvent {
             contract_id: None,
+            contract: None,
             topics: vec!["topic".to_string()],
             data: "payload".to_string(),
         }];
@@ -910,6 +1249,7 @@ function = "increment"
         let expected = vec![];
         let actual = vec![ContractEvent {
             contract_id: None,
+            contract: None,
             topics: vec!["topic".to_string()],
             data: "payload".to_string(),
         }];
@@ -1,40 +1,94 @@
-use crate::debugger::breakpoint::{BreakpointManager, BreakpointSpec};
+use crate::debugger::breakpoint::{Breakpoint, BreakpointManager, BreakpointSpec};
 use crate::debugger::engine::{DebuggerEngine, StepOverResult};
 use crate::inspector::budget::BudgetInspector;
+use crate::runtime::executor::ContractExecutor;
+use crate::runtime::limits::BudgetLimits;
+use crate::runtime::SessionState;
 use crate::server::protocol::{
     negotiate_protocol_version, PROTOCOL_MAX_VERSION, PROTOCOL_MIN_VERSION,
 };
 use crate::server::protocol::{
-    BreakpointCapabilities, BreakpointDescriptor, DebugMessage, DebugRequest, DebugResponse,
+    BreakpointCapabilities, BreakpointDescriptor, ConnectionCapabilities, DebugMessage,
+    DebugRequest, DebugResponse,
 };
+use crate::server::session::{InvocationQueue, SessionControl, SessionSnapshot, SnapshotCell};
 use crate::simulator::SnapshotLoader;
 use crate::Result;
 use std::collections::HashSet;
 use std::fs;
 use std::io::BufReader as StdBufReader;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use tokio::io::AsyncBufReadExt;
 use tokio::net::TcpListener;
-use tokio::sync::Notify;
+use tokio::sync::{oneshot, Notify};
 use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
 use tokio_rustls::TlsAcceptor;
 use tracing::{error, info, warn};
 
 pub struct DebugServer {
-    engine: Option<DebuggerEngine>,
     token: Option<String>,
     tls_config: Option<ServerConfig>,
-    pending_execution: Option<PendingExecution>,
     shutdown: Arc<Notify>,
-    contract_wasm: Option<Vec<u8>>,
+    control: Arc<SessionControl>,
+    session: String,
+    /// Budget loaded contracts run under; the host's default when `None`.
+    budget_limits: Option<BudgetLimits>,
 }
 
+/// Session name used when the server is not given one.
+pub const DEFAULT_SESSION: &str = "default";
+
 struct PendingExecution {
     function: String,
     args: Option<String>,
 }
 
+/// The engine and the requests that drive it. Runs on a thread of its own,
+/// so a cancelled invocation can be left running there while a session
+/// restored from the [`Checkpoint`] takes over.
+struct Session {
+    /// Tells this session's invocations apart from those of the sessions it
+    /// replaced, in [`SessionControl`].
+    id: u64,
+    engine: Option<DebuggerEngine>,
+    pending_execution: Option<PendingExecution>,
+    contract_wasm: Option<Vec<u8>>,
+    control: Arc<SessionControl>,
+    budget_limits: Option<BudgetLimits>,
+    revision: u64,
+    /// Saved before every request that can be cancelled.
+    checkpoint: Arc<Mutex<Option<Checkpoint>>>,
+}
+
+/// What a session is rebuilt from after its invocation is cancelled.
+struct Checkpoint {
+    wasm: Vec<u8>,
+    state: SessionState,
+    breakpoints: Vec<Breakpoint>,
+    revision: u64,
+}
+
+/// A [`Session`] on its own thread, applying one request at a time.
+struct SessionWorker {
+    requests: std::sync::mpsc::Sender<(DebugRequest, oneshot::Sender<DebugResponse>)>,
+    /// Set once the owner stops waiting on this worker; it then drops
+    /// whatever its cancelled invocation returns.
+    retired: Arc<Mutex<bool>>,
+    checkpoint: Arc<Mutex<Option<Checkpoint>>>,
+}
+
+/// State shared between the session owner and every connection attached to it.
+struct SharedSession {
+    token: Option<String>,
+    /// Name clients pass to `Attach`.
+    session: String,
+    queue: InvocationQueue,
+    snapshot: SnapshotCell,
+    control: Arc<SessionControl>,
+}
+
 impl DebugServer {
     pub fn new(
         token: Option<String>,
@@ -52,21 +106,42 @@ impl DebugServer {
         };
 
         Ok(Self {
-            engine: None,
             token,
             tls_config,
-            pending_execution: None,
             shutdown: Arc::new(Notify::new()),
-            contract_wasm: None,
+            control: Arc::new(SessionControl::default()),
+            session: DEFAULT_SESSION.to_string(),
+            budget_limits: None,
         })
     }
 
-    pub async fn run(mut self, port: u16) -> Result<()> {
+    /// Name the session so read-only observers can attach to it.
+    pub fn with_session(mut self, session: impl Into<String>) -> Self {
+        self.session = session.into();
+        self
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    pub async fn run(self, port: u16) -> Result<()> {
         let addr = format!("0.0.0.0:{}", port);
         let listener = TcpListener::bind(&addr)
             .await
             .map_err(|e| miette::miette!("Failed to bind to {}: {}", addr, e))?;
         info!("Debug server listening on {}", addr);
+        self.run_on(listener).await
+    }
+
+    /// Serve clients accepted on `listener` until shutdown.
+    ///
+    /// Every client attaches to the same session. Connections are handled on
+    /// their own tasks while the session thread owns the engine; this task
+    /// hands it queued requests one at a time. A cancelled invocation is
+    /// answered with an error and the session goes on from the state it had
+    /// before the call.
+    pub async fn run_on(mut self, listener: TcpListener) -> Result<()> {
         if self.token.is_some() && self.tls_config.is_none() {
             warn!(
                 "Token authentication is enabled without TLS. Treat this as plaintext transport and \
@@ -79,34 +154,60 @@ impl DebugServer {
             .take()
             .map(|cfg| TlsAcceptor::from(Arc::new(cfg)));
 
-        let shutdown = self.shutdown.clone();
-        tokio::spawn(setup_signal_handlers(shutdown));
+        let (queue, mut commands) = InvocationQueue::new();
+        let shared = Arc::new(SharedSession {
+            token: self.token.clone(),
+            session: self.session.clone(),
+            queue,
+            snapshot: SnapshotCell::default(),
+            control: Arc::clone(&self.control),
+        });
+        let mut session_id = 1;
+        let mut worker = SessionWorker::spawn(
+            session_id,
+            Arc::clone(&self.control),
+            self.budget_limits,
+            None,
+            Arc::clone(&shared),
+        );
+
+        tokio::spawn(setup_signal_handlers(
+            self.shutdown.clone(),
+            Arc::clone(&self.control),
+        ));
+        let accept_loop = tokio::spawn(accept_connections(listener, acceptor, Arc::clone(&shared)));
 
         loop {
             tokio::select! {
-                accept_result = listener.accept() => {
-                    match accept_result {
-                        Ok((stream, addr)) => {
-                            info!("New connection from {}", addr);
-                            if let Some(ref acceptor) = acceptor {
-                                match acceptor.accept(stream).await {
-                                    Ok(tls_stream) => {
-                                        if let Err(e) = self.handle_single_connection(tls_stream).await {
-                                            error!("TLS connection error: {}", e);
-                                        }
-                                    }
-                                    Err(e) => error!("TLS accept error: {}", e),
-                                }
-                            } else if let Err(e) = self.handle_single_connection(stream).await {
-                                error!("TCP connection error: {}", e);
+                Some(command) = commands.recv() => {
+                    // Created before the request is handed over so no cancel is missed.
+                    let cancelled = self.control.cancelled();
+                    let response = tokio::select! {
+                        // A cancel that raced the reply still wins, as it was acknowledged.
+                        biased;
+
+                        _ = cancelled => {
+                            let checkpoint = worker.retire();
+                            session_id += 1;
+                            worker = SessionWorker::spawn(
+                                session_id,
+                                Arc::clone(&self.control),
+                                self.budget_limits,
+                                checkpoint,
+                                Arc::clone(&shared),
+                            );
+                            DebugResponse::Error {
+                                message: "Invocation cancelled; the session is back to its state before the call".to_string(),
                             }
                         }
-                        Err(e) => error!("Failed to accept connection: {}", e),
-                    }
+                        response = worker.submit(command.request) => response,
+                    };
+                    shared.queue.complete();
+                    let _ = command.reply.send(response);
                 }
                 _ = self.shutdown.notified() => {
                     info!("Shutting down debug server");
-                    drop(listener);
+                    accept_loop.abort();
                     break;
                 }
             }
@@ -114,849 +215,1060 @@ impl DebugServer {
 
         Ok(())
     }
+}
 
-    async fn handle_single_connection<S>(&mut self, stream: S) -> Result<()>
-    where
-        S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
-    {
-        let mut authenticated = self.token.is_none();
-        let mut handshake_done = false;
-        let (reader, writer) = tokio::io::split(stream);
-        let mut reader = tokio::io::BufReader::new(reader);
-
-        let (tx_in, mut rx_in) = tokio::sync::mpsc::unbounded_channel::<String>();
-        let (tx_out, mut rx_out) = tokio::sync::mpsc::unbounded_channel::<DebugMessage>();
-
-        tokio::spawn(async move {
-            let mut writer = writer;
-            while let Some(msg) = rx_out.recv().await {
-                if crate::server::protocol::send_response::<tokio::io::WriteHalf<S>>(
-                    &mut writer,
-                    msg,
-                )
-                .await
-                .is_err()
-                {
-                    break;
-                }
+impl SessionWorker {
+    /// Start a session thread, restored from `checkpoint` if given. It
+    /// publishes a snapshot after every request, before replying, so the
+    /// caller's next read sees its own change.
+    fn spawn(
+        id: u64,
+        control: Arc<SessionControl>,
+        budget_limits: Option<BudgetLimits>,
+        checkpoint: Option<Checkpoint>,
+        shared: Arc<SharedSession>,
+    ) -> Self {
+        let (requests, incoming) =
+            std::sync::mpsc::channel::<(DebugRequest, oneshot::Sender<DebugResponse>)>();
+        let retired = Arc::new(Mutex::new(false));
+        let saved = Arc::new(Mutex::new(None));
+        let worker = Self {
+            requests,
+            retired: Arc::clone(&retired),
+            checkpoint: Arc::clone(&saved),
+        };
+        std::thread::spawn(move || {
+            // The engine is not `Send`, so it is built on this thread.
+            let mut session = Session {
+                id,
+                engine: None,
+                pending_execution: None,
+                contract_wasm: None,
+                control,
+                budget_limits,
+                revision: 0,
+                checkpoint: saved,
+            };
+            if let Some(checkpoint) = checkpoint {
+                session.restore(checkpoint);
             }
-        });
-
-        let tx_out_reader = tx_out.clone();
-        let is_executing = Arc::new(std::sync::atomic::AtomicBool::new(false));
-        let is_executing_reader = Arc::clone(&is_executing);
-
-        tokio::spawn(async move {
-            let mut line = String::new();
-            loop {
-                line.clear();
-                let n = reader.read_line(&mut line).await.unwrap_or(0);
-                if n == 0 {
+            shared.snapshot.publish(session.session_snapshot());
+            for (request, reply) in incoming {
+                let response = session.handle_session_request(request);
+                let Ok(retired) = retired.lock() else {
                     break;
-                }
-
-                if let Ok(msg) = DebugMessage::parse(line.trim_end()) {
-                    if matches!(msg.request, Some(DebugRequest::Cancel)) {
-                        let response = DebugMessage::response(msg.id, DebugResponse::CancelAck);
-                        let _ = tx_out_reader.send(response);
-                        if is_executing_reader.load(std::sync::atomic::Ordering::SeqCst) {
-                            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
-                            eprintln!(
-                                "Execution cancelled via request. Aborting with exit code 125."
-                            );
-                            std::process::exit(125);
-                        }
-                        continue;
-                    }
-                }
-
-                if tx_in.send(line.clone()).is_err() {
+                };
+                if *retired {
                     break;
                 }
+                session.revision += 1;
+                shared.snapshot.publish(session.session_snapshot());
+                drop(retired);
+                let _ = reply.send(response);
             }
         });
+        worker
+    }
 
-        // Helper closure to abstract away tx_out
-        let send_msg = |msg: DebugMessage| -> Result<()> {
-            tx_out
-                .send(msg)
-                .map_err(|_| miette::miette!("Connection closed"))
-        };
-
-        loop {
-            let line = match rx_in.recv().await {
-                Some(l) => l,
-                None => break,
-            };
-            is_executing.store(false, std::sync::atomic::Ordering::SeqCst);
-
-            let message = match DebugMessage::parse(line.trim_end()) {
-                Ok(msg) => msg,
-                Err(e) => {
-                    warn!("Failed to parse request: {}", e);
-                    let response = DebugMessage::response(
-                        0, // ID might be unknown if parse failed, but often it's available.
-                        // For now use 0 or try to extract it if possible.
-                        DebugResponse::Error {
-                            message: format!("Malformed request: {}", e),
-                        },
-                    );
-                    let _ = send_msg(response);
-                    continue;
-                }
-            };
-            let Some(request) = message.request else {
-                warn!("Received message without request");
-                continue;
+    /// Hand `request` to the session thread and wait for its answer.
+    async fn submit(&self, request: DebugRequest) -> DebugResponse {
+        let (reply, response) = oneshot::channel();
+        if self.requests.send((request, reply)).is_err() {
+            return DebugResponse::Error {
+                message: "Debug session has shut down".to_string(),
             };
+        }
+        response.await.unwrap_or_else(|_| DebugResponse::Error {
+            message: "Debug session has shut down".to_string(),
+        })
+    }
 
-            if matches!(request, DebugRequest::Unknown) {
-                let response = DebugMessage::response(
-                    message.id,
-                    DebugResponse::Error {
-                        message: "Unknown request type. Try upgrading the server.".to_string(),
-                    },
-                );
-                send_msg(response)?;
-                continue;
-            }
+    /// Stop waiting on this worker and take the state saved before its
+    /// cancelled request.
+    fn retire(self) -> Option<Checkpoint> {
+        if let Ok(mut retired) = self.retired.lock() {
+            *retired = true;
+        }
+        let mut saved = self.checkpoint.lock().ok()?;
+        saved.take()
+    }
+}
 
-            info!("Received request: {}", summarize_request(&request));
+impl Session {
+    /// Wrap a freshly built executor in an engine, under the server's budget.
+    fn load(&mut self, mut executor: ContractExecutor, wasm: Vec<u8>) {
+        if let Some(limits) = self.budget_limits {
+            executor.set_budget_limits(limits);
+        }
+        let mut engine = DebuggerEngine::new(executor, Vec::new());
+        let _ = engine.enable_instruction_debug(&wasm);
+        self.engine = Some(engine);
+        self.pending_execution = None;
+        self.contract_wasm = Some(wasm);
+    }
 
-            if matches!(request, DebugRequest::Ping) {
-                let response = DebugMessage::response(message.id, DebugResponse::Pong);
-                send_msg(response)?;
-                continue;
-            }
+    /// Save what [`Self::restore`] needs, before a request that runs the
+    /// contract and may be cancelled.
+    fn save_checkpoint(&self) {
+        let (Some(engine), Some(wasm)) = (self.engine.as_ref(), self.contract_wasm.as_ref()) else {
+            return;
+        };
+        let checkpoint = Checkpoint {
+            wasm: wasm.clone(),
+            state: engine.executor().export_state(),
+            breakpoints: engine
+                .breakpoints()
+                .list_detailed()
+                .into_iter()
+                .cloned()
+                .collect(),
+            revision: self.revision,
+        };
+        if let Ok(mut saved) = self.checkpoint.lock() {
+            *saved = Some(checkpoint);
+        }
+    }
 
-            if let DebugRequest::Handshake {
-                client_name,
-                client_version,
-                protocol_min,
-                protocol_max,
-            } = &request
-            {
-                let server_name = "soroban-debug".to_string();
-                let server_version = env!("CARGO_PKG_VERSION").to_string();
-
-                match negotiate_protocol_version(*protocol_min, *protocol_max) {
-                    Ok(selected_version) => {
-                        handshake_done = true;
-                        let response = DebugMessage::response(
-                            message.id,
-                            DebugResponse::HandshakeAck {
-                                server_name,
-                                server_version,
-                                protocol_min: PROTOCOL_MIN_VERSION,
-                                protocol_max: PROTOCOL_MAX_VERSION,
-                                selected_version,
-                            },
-                        );
-                        send_msg(response)?;
-                        continue;
+    /// Rebuild the session from the state saved before a cancelled request.
+    fn restore(&mut self, checkpoint: Checkpoint) {
+        self.revision = checkpoint.revision + 1;
+        match ContractExecutor::from_state(checkpoint.wasm.clone(), checkpoint.state) {
+            Ok(executor) => {
+                self.load(executor, checkpoint.wasm);
+                if let Some(engine) = self.engine.as_mut() {
+                    for breakpoint in checkpoint.breakpoints {
+                        engine.breakpoints_mut().set(breakpoint);
                     }
-                    Err(e) => {
-                        let response = DebugMessage::response(
-                            message.id,
-                            DebugResponse::IncompatibleProtocol {
-                                message: format!(
-                                    "{}. Client: {}@{}. Upgrade the older component.",
-                                    e, client_name, client_version
-                                ),
-                                server_name,
-                                server_version,
-                                protocol_min: PROTOCOL_MIN_VERSION,
-                                protocol_max: PROTOCOL_MAX_VERSION,
-                            },
-                        );
-                        send_msg(response)?;
-                        return Ok(());
-                    }
-                }
-            }
-
-            // Backward compatibility: allow Authenticate before handshake.
-            if let DebugRequest::Authenticate { token } = &request {
-                let success = self
-                    .token
-                    .as_deref()
-                    .map(|server_token| server_token == token)
-                    .unwrap_or(true);
-                authenticated = success;
-                let response = DebugResponse::Authenticated {
-                    success,
-                    message: if success {
-                        "Authentication successful".to_string()
-                    } else {
-                        "Authentication failed".to_string()
-                    },
-                };
-                let response = DebugMessage::response(message.id, response);
-                send_msg(response)?;
-                if !success {
-                    return Ok(());
                 }
-                continue;
-            }
-
-            if !handshake_done {
-                let response = DebugMessage::response(
-                    message.id,
-                    DebugResponse::Error {
-                        message: "Protocol handshake required: send a Handshake request before other debug requests.".to_string(),
-                    },
-                );
-                send_msg(response)?;
-                continue;
             }
+            Err(e) => error!("Failed to restore the session after a cancel: {}", e),
+        }
+    }
 
-            if !authenticated {
-                if let DebugRequest::Authenticate { token } = request {
-                    let success = self.token.as_deref().map(|t| t == token).unwrap_or(true);
-                    authenticated = success;
-                    let response = DebugResponse::Authenticated {
-                        success,
-                        message: if success {
-                            "Authentication successful".to_string()
-                        } else {
-                            "Authentication failed".to_string()
-                        },
-                    };
-                    let response = DebugMessage::response(message.id, response);
-                    send_msg(response)?;
-                    if !success {
-                        return Ok(());
-                    }
-                    continue;
-                }
+    /// Capture the state read-only requests are answered from.
+    fn session_snapshot(&self) -> SessionSnapshot {
+        let Some(engine) = self.engine.as_ref() else {
+            return SessionSnapshot {
+                revision: self.revision,
+                ..SessionSnapshot::default()
+            };
+        };
 
-                let response = DebugMessage::response(
-                    message.id,
-                    DebugResponse::Error {
-                        message: "Authentication required".to_string(),
-                    },
-                );
-                send_msg(response)?;
-                continue;
+        let mut snapshot = SessionSnapshot {
+            revision: self.revision,
+            contract_loaded: true,
+            paused: engine.is_paused(),
+            ..SessionSnapshot::default()
+        };
+        match engine.state().lock() {
+            Ok(state) => {
+                snapshot.function = state.current_function().map(|s| s.to_string());
+                snapshot.args = state.current_args().map(|s| s.to_string());
+                snapshot.step_count = state.step_count() as u64;
+                snapshot.call_stack = state
+                    .call_stack()
+                    .get_stack()
+                    .iter()
+                    .map(|frame| {
+                        let suffix = frame
+                            .contract_id
+                            .as_ref()
+                            .map(|id| format!(" [{}]", id))
+                            .unwrap_or_default();
+                        format!("{}{}", frame.function, suffix)
+                    })
+                    .collect();
             }
+            Err(e) => warn!("Failed to acquire state lock: {}", e),
+        }
+        snapshot.storage = match engine.executor().get_storage_snapshot() {
+            Ok(storage) => serde_json::to_string(&storage)
+                .map_err(|e| format!("Failed to serialize storage snapshot: {}", e)),
+            Err(e) => Err(e.to_string()),
+        };
+        let info = BudgetInspector::get_cpu_usage(engine.executor().host());
+        snapshot.cpu_instructions = info.cpu_instructions;
+        snapshot.memory_bytes = info.memory_bytes;
+        snapshot.events = engine.executor().get_events().unwrap_or_default();
+        snapshot.trace = engine
+            .executor()
+            .debug_env()
+            .function_calls()
+            .iter()
+            .map(|call| {
+                let outcome = match (&call.result, &call.error) {
+                    (_, Some(error)) => format!("error: {}", error),
+                    (Some(result), None) => result.clone(),
+                    (None, None) => "(running)".to_string(),
+                };
+                format!(
+                    "#{} {}({}) -> {}",
+                    call.sequence,
+                    call.callee,
+                    call.arguments.join(", "),
+                    outcome
+                )
+            })
+            .collect();
+        snapshot
+    }
 
-            let is_disconnect = matches!(&request, DebugRequest::Disconnect);
-            let response = match request {
-                DebugRequest::Authenticate { .. } => DebugResponse::Authenticated {
-                    success: true,
-                    message: "Already authenticated".to_string(),
-                },
-                DebugRequest::Handshake { .. } => DebugResponse::Error {
-                    message: "Protocol handshake already completed".to_string(),
-                },
-                DebugRequest::LoadContract { contract_path } => match fs::read(&contract_path) {
-                    Ok(bytes) => {
-                        match crate::runtime::executor::ContractExecutor::new(bytes.clone()) {
-                            Ok(executor) => {
-                                let mut engine = DebuggerEngine::new(executor, Vec::new());
-                                let _ = engine.enable_instruction_debug(&bytes);
-                                self.engine = Some(engine);
-                                self.pending_execution = None;
-                                self.contract_wasm = Some(bytes);
-                                DebugResponse::ContractLoaded {
-                                    size: fs::metadata(&contract_path)
-                                        .map(|m| m.len() as usize)
-                                        .unwrap_or(0),
-                                }
-                            }
-                            Err(e) => DebugResponse::Error {
-                                message: e.to_string(),
-                            },
+    /// Apply one queued request to the session.
+    fn handle_session_request(&mut self, request: DebugRequest) -> DebugResponse {
+        if matches!(
+            request,
+            DebugRequest::Execute { .. } | DebugRequest::Continue | DebugRequest::StepOut
+        ) {
+            self.save_checkpoint();
+        }
+        match request {
+            request @ (DebugRequest::Inspect
+            | DebugRequest::GetStorage
+            | DebugRequest::GetStack
+            | DebugRequest::GetBudget
+            | DebugRequest::GetEvents
+            | DebugRequest::GetTrace) => self
+                .session_snapshot()
+                .respond(&request)
+                .expect("snapshot answers read-only requests"),
+            DebugRequest::Authenticate { .. } => DebugResponse::Authenticated {
+                success: true,
+                message: "Already authenticated".to_string(),
+            },
+            DebugRequest::Handshake { .. } => DebugResponse::Error {
+                message: "Protocol handshake already completed".to_string(),
+            },
+            DebugRequest::Attach { .. } => DebugResponse::Error {
+                message: "Attach applies to a connection, not the session".to_string(),
+            },
+            DebugRequest::LoadContract { contract_path } => match fs::read(&contract_path) {
+                Ok(bytes) => match ContractExecutor::new(bytes.clone()) {
+                    Ok(executor) => {
+                        self.load(executor, bytes);
+                        DebugResponse::ContractLoaded {
+                            size: fs::metadata(&contract_path)
+                                .map(|m| m.len() as usize)
+                                .unwrap_or(0),
                         }
                     }
                     Err(e) => DebugResponse::Error {
-                        message: format!("Failed to read contract {:?}: {}", contract_path, e),
+                        message: e.to_string(),
                     },
                 },
-                DebugRequest::ResolveSourceBreakpoints {
-                    source_path,
-                    lines,
-                    exported_functions,
-                } => match (self.engine.as_ref(), self.contract_wasm.as_deref()) {
-                    (Some(engine), Some(wasm_bytes)) => {
-                        if let Some(source_map) = engine.source_map() {
-                            let exported: HashSet<String> =
-                                exported_functions.into_iter().collect();
-                            let breakpoints = source_map.resolve_source_breakpoints(
-                                wasm_bytes,
-                                Path::new(&source_path),
-                                &lines,
-                                &exported,
-                            );
-                            DebugResponse::SourceBreakpointsResolved { breakpoints }
-                        } else {
-                            let breakpoints = lines
-                                .into_iter()
-                                .map(|line| crate::debugger::SourceBreakpointResolution {
-                                    requested_line: line,
-                                    line,
-                                    verified: false,
-                                    function: None,
-                                    reason_code: "NO_DEBUG_INFO".to_string(),
-                                    message:
-                                        "[NO_DEBUG_INFO] Contract is missing DWARF source mappings; rebuild with debug info to bind source breakpoints accurately.".to_string(),
-                                })
-                                .collect();
-                            DebugResponse::SourceBreakpointsResolved { breakpoints }
-                        }
+                Err(e) => DebugResponse::Error {
+                    message: format!("Failed to read contract {:?}: {}", contract_path, e),
+                },
+            },
+            DebugRequest::ResolveSourceBreakpoints {
+                source_path,
+                lines,
+                exported_functions,
+            } => match (self.engine.as_ref(), self.contract_wasm.as_deref()) {
+                (Some(engine), Some(wasm_bytes)) => {
+                    if let Some(source_map) = engine.source_map() {
+                        let exported: HashSet<String> = exported_functions.into_iter().collect();
+                        let breakpoints = source_map.resolve_source_breakpoints(
+                            wasm_bytes,
+                            Path::new(&source_path),
+                            &lines,
+                            &exported,
+                        );
+                        DebugResponse::SourceBreakpointsResolved { breakpoints }
+                    } else {
+                        let breakpoints = lines
+                            .into_iter()
+                            .map(|line| crate::debugger::SourceBreakpointResolution {
+                                requested_line: line,
+                                line,
+                                verified: false,
+                                function: None,
+                                reason_code: "NO_DEBUG_INFO".to_string(),
+                                message:
+                                    "[NO_DEBUG_INFO] Contract is missing DWARF source mappings; rebuild with debug info to bind source breakpoints accurately.".to_string(),
+                            })
+                            .collect();
+                        DebugResponse::SourceBreakpointsResolved { breakpoints }
                     }
-                    _ => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+                }
+                _ => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::Execute { function, args } => match self.engine.as_mut() {
-                    Some(engine) if engine.breakpoints().should_break(&function) => {
-                        match current_storage(engine) {
-                            Ok(storage) => match engine.breakpoints_mut().on_hit(
-                                &function,
-                                &storage,
-                                args.as_deref(),
-                            ) {
-                                Ok(Some(hit)) => {
-                                    for message in hit.log_messages {
-                                        println!("{message}");
-                                    }
+            },
+            DebugRequest::Execute { function, args } => match self.engine.as_mut() {
+                Some(engine) if engine.breakpoints().should_break(&function) => {
+                    let evaluator = engine.condition_evaluator(&function, args.as_deref());
+                    match engine.breakpoints_mut().on_hit(&function, &evaluator) {
+                        Ok(Some(hit)) => {
+                            for message in hit.log_messages {
+                                println!("{message}");
+                            }
 
-                                    if hit.should_pause {
-                                        engine.prepare_breakpoint_stop(&function, args.as_deref());
-                                        self.pending_execution =
-                                            Some(PendingExecution { function, args });
-                                        DebugResponse::ExecutionResult {
-                                            success: true,
-                                            output: String::new(),
-                                            error: None,
-                                            paused: true,
-                                            completed: false,
-                                            source_location: None,
-                                        }
-                                    } else {
-                                        {
-                                            is_executing
-                                                .store(true, std::sync::atomic::Ordering::SeqCst);
-                                            let r = execute_without_breakpoints(
-                                                engine, &function, args,
-                                            );
-                                            is_executing
-                                                .store(false, std::sync::atomic::Ordering::SeqCst);
-                                            r
-                                        }
-                                    }
+                            if hit.should_pause {
+                                engine.prepare_breakpoint_stop(&function, args.as_deref());
+                                self.pending_execution = Some(PendingExecution { function, args });
+                                DebugResponse::ExecutionResult {
+                                    success: true,
+                                    output: String::new(),
+                                    error: None,
+                                    paused: true,
+                                    completed: false,
+                                    source_location: None,
                                 }
-                                Ok(None) => {
-                                    is_executing.store(true, std::sync::atomic::Ordering::SeqCst);
+                            } else {
+                                {
+                                    self.control.set_executing(self.id, true);
                                     let r = execute_without_breakpoints(engine, &function, args);
-                                    is_executing.store(false, std::sync::atomic::Ordering::SeqCst);
+                                    self.control.set_executing(self.id, false);
                                     r
                                 }
-                                Err(e) => DebugResponse::Error {
-                                    message: e.to_string(),
-                                },
-                            },
-                            Err(e) => DebugResponse::Error {
-                                message: e.to_string(),
-                            },
-                        }
-                    }
-                    Some(engine) => {
-                        is_executing.store(true, std::sync::atomic::Ordering::SeqCst);
-                        let r = execute_without_breakpoints(engine, &function, args);
-                        is_executing.store(false, std::sync::atomic::Ordering::SeqCst);
-                        r
-                    }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
-                },
-                DebugRequest::Step | DebugRequest::StepIn => match self.engine.as_mut() {
-                    Some(engine) => match engine.step_into() {
-                        Ok(_) => {
-                            let (current_function, step_count) = engine
-                                .state()
-                                .lock()
-                                .map(|state| {
-                                    (
-                                        state.current_function().map(|s| s.to_string()),
-                                        state.step_count() as u64,
-                                    )
-                                })
-                                .unwrap_or((None, 0));
-                            DebugResponse::StepResult {
-                                paused: engine.is_paused(),
-                                current_function,
-                                step_count,
-                                source_location: None,
                             }
                         }
+                        Ok(None) => {
+                            self.control.set_executing(self.id, true);
+                            let r = execute_without_breakpoints(engine, &function, args);
+                            self.control.set_executing(self.id, false);
+                            r
+                        }
                         Err(e) => DebugResponse::Error {
                             message: e.to_string(),
                         },
+                    }
+                }
+                Some(engine) => {
+                    self.control.set_executing(self.id, true);
+                    let r = execute_without_breakpoints(engine, &function, args);
+                    self.control.set_executing(self.id, false);
+                    r
+                }
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
+                },
+            },
+            DebugRequest::Step | DebugRequest::StepIn => match self.engine.as_mut() {
+                Some(engine) => match engine.step_into() {
+                    Ok(_) => {
+                        let (current_function, step_count) = engine
+                            .state()
+                            .lock()
+                            .map(|state| {
+                                (
+                                    state.current_function().map(|s| s.to_string()),
+                                    state.step_count() as u64,
+                                )
+                            })
+                            .unwrap_or((None, 0));
+                        DebugResponse::StepResult {
+                            paused: engine.is_paused(),
+                            current_function,
+                            step_count,
+                            source_location: None,
+                        }
+                    }
+                    Err(e) => DebugResponse::Error {
+                        message: e.to_string(),
                     },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
+                },
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
+                },
+            },
+            DebugRequest::Next => match self.engine.as_mut() {
+                Some(engine) => match engine.step_over() {
+                    Ok(_) => {
+                        let (current_function, step_count) = engine
+                            .state()
+                            .lock()
+                            .map(|state| {
+                                (
+                                    state.current_function().map(|s| s.to_string()),
+                                    state.step_count() as u64,
+                                )
+                            })
+                            .unwrap_or((None, 0));
+                        DebugResponse::StepResult {
+                            paused: engine.is_paused(),
+                            current_function,
+                            step_count,
+                            source_location: None,
+                        }
+                    }
+                    Err(e) => DebugResponse::Error {
+                        message: e.to_string(),
                     },
                 },
-                DebugRequest::Next => match self.engine.as_mut() {
-                    Some(engine) => match engine.step_over() {
-                        Ok(_) => {
-                            let (current_function, step_count) = engine
-                                .state()
-                                .lock()
-                                .map(|state| {
-                                    (
-                                        state.current_function().map(|s| s.to_string()),
-                                        state.step_count() as u64,
-                                    )
-                                })
-                                .unwrap_or((None, 0));
-                            DebugResponse::StepResult {
-                                paused: engine.is_paused(),
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
+                },
+            },
+            DebugRequest::StepOut => match self.engine.as_mut() {
+                Some(engine) => {
+                    // When paused at a function-level breakpoint (pending execution),
+                    // step-out means executing the function to completion.
+                    if let Some(pending) = self.pending_execution.take() {
+                        let (current_function, step_count) = engine
+                            .state()
+                            .lock()
+                            .map(|state| {
+                                (
+                                    state.current_function().map(|s| s.to_string()),
+                                    state.step_count() as u64,
+                                )
+                            })
+                            .unwrap_or((None, 0));
+                        let exec_result = {
+                            self.control.set_executing(self.id, true);
+                            let r = engine.execute_without_breakpoints(
+                                &pending.function,
+                                pending.args.as_deref(),
+                            );
+                            self.control.set_executing(self.id, false);
+                            r
+                        };
+                        match exec_result {
+                            Ok(_) => DebugResponse::StepResult {
+                                paused: false,
                                 current_function,
                                 step_count,
                                 source_location: None,
-                            }
+                            },
+                            Err(e) => DebugResponse::Error {
+                                message: e.to_string(),
+                            },
                         }
-                        Err(e) => DebugResponse::Error {
-                            message: e.to_string(),
-                        },
-                    },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
-                },
-                DebugRequest::StepOut => match self.engine.as_mut() {
-                    Some(engine) => {
-                        // When paused at a function-level breakpoint (pending execution),
-                        // step-out means executing the function to completion.
-                        if let Some(pending) = self.pending_execution.take() {
-                            let (current_function, step_count) = engine
-                                .state()
-                                .lock()
-                                .map(|state| {
-                                    (
-                                        state.current_function().map(|s| s.to_string()),
-                                        state.step_count() as u64,
-                                    )
-                                })
-                                .unwrap_or((None, 0));
-                            let exec_result = {
-                                is_executing.store(true, std::sync::atomic::Ordering::SeqCst);
-                                let r = engine.execute_without_breakpoints(
-                                    &pending.function,
-                                    pending.args.as_deref(),
-                                );
-                                is_executing.store(false, std::sync::atomic::Ordering::SeqCst);
-                                r
-                            };
-                            match exec_result {
-                                Ok(_) => DebugResponse::StepResult {
-                                    paused: false,
+                    } else {
+                        match engine.step_out() {
+                            Ok(_) => {
+                                let (current_function, step_count) = engine
+                                    .state()
+                                    .lock()
+                                    .map(|state| {
+                                        (
+                                            state.current_function().map(|s| s.to_string()),
+                                            state.step_count() as u64,
+                                        )
+                                    })
+                                    .unwrap_or((None, 0));
+                                DebugResponse::StepResult {
+                                    paused: engine.is_paused(),
                                     current_function,
                                     step_count,
                                     source_location: None,
-                                },
-                                Err(e) => DebugResponse::Error {
-                                    message: e.to_string(),
-                                },
-                            }
-                        } else {
-                            match engine.step_out() {
-                                Ok(_) => {
-                                    let (current_function, step_count) = engine
-                                        .state()
-                                        .lock()
-                                        .map(|state| {
-                                            (
-                                                state.current_function().map(|s| s.to_string()),
-                                                state.step_count() as u64,
-                                            )
-                                        })
-                                        .unwrap_or((None, 0));
-                                    DebugResponse::StepResult {
-                                        paused: engine.is_paused(),
-                                        current_function,
-                                        step_count,
-                                        source_location: None,
-                                    }
                                 }
-                                Err(e) => DebugResponse::Error {
-                                    message: e.to_string(),
-                                },
                             }
+                            Err(e) => DebugResponse::Error {
+                                message: e.to_string(),
+                            },
                         }
                     }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+                }
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::StepOverLine => match self.engine.as_mut() {
-                    Some(engine) => match engine.step_over_source_line() {
-                        Ok(StepOverResult { paused, location }) => {
-                            DebugResponse::StepOverLineResult {
-                                paused,
-                                file: location
-                                    .as_ref()
-                                    .map(|l| l.file.to_string_lossy().into_owned()),
-                                line: location.as_ref().map(|l| l.line),
-                                column: location.and_then(|l| l.column),
-                            }
-                        }
-                        Err(e) => DebugResponse::Error {
-                            message: format!("StepOverLine failed: {}", e),
-                        },
+            },
+            DebugRequest::StepOverLine => match self.engine.as_mut() {
+                Some(engine) => match engine.step_over_source_line() {
+                    Ok(StepOverResult { paused, location }) => DebugResponse::StepOverLineResult {
+                        paused,
+                        file: location
+                            .as_ref()
+                            .map(|l| l.file.to_string_lossy().into_owned()),
+                        line: location.as_ref().map(|l| l.line),
+                        column: location.and_then(|l| l.column),
                     },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
+                    Err(e) => DebugResponse::Error {
+                        message: format!("StepOverLine failed: {}", e),
                     },
                 },
-                DebugRequest::Continue => match self.engine.as_mut() {
-                    Some(engine) => {
-                        if let Some(pending) = self.pending_execution.take() {
-                            let exec_result = {
-                                is_executing.store(true, std::sync::atomic::Ordering::SeqCst);
-                                let r = engine.execute_without_breakpoints(
-                                    &pending.function,
-                                    pending.args.as_deref(),
-                                );
-                                is_executing.store(false, std::sync::atomic::Ordering::SeqCst);
-                                r
-                            };
-                            match exec_result {
-                                Ok(output) => DebugResponse::ContinueResult {
-                                    completed: true,
-                                    output: Some(output),
-                                    error: None,
-                                    paused: false,
-                                    source_location: None,
-                                },
-                                Err(e) => DebugResponse::ContinueResult {
-                                    completed: false,
-                                    output: None,
-                                    error: Some(e.to_string()),
-                                    paused: false,
-                                    source_location: None,
-                                },
-                            }
-                        } else {
-                            match engine.continue_execution() {
-                                Ok(_) => DebugResponse::ContinueResult {
-                                    completed: true,
-                                    output: None,
-                                    error: None,
-                                    paused: engine.is_paused(),
-                                    source_location: None,
-                                },
-                                Err(e) => DebugResponse::ContinueResult {
-                                    completed: false,
-                                    output: None,
-                                    error: Some(e.to_string()),
-                                    paused: engine.is_paused(),
-                                    source_location: None,
-                                },
-                            }
-                        }
-                    }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::Inspect => match self.engine.as_ref() {
-                    Some(engine) => match engine.state().lock() {
-                        Ok(state) => {
-                            let call_stack = state
-                                .call_stack()
-                                .get_stack()
-                                .iter()
-                                .map(|frame| {
-                                    let suffix = frame
-                                        .contract_id
-                                        .as_ref()
-                                        .map(|id| format!(" [{}]", id))
-                                        .unwrap_or_default();
-                                    format!("{}{}", frame.function, suffix)
-                                })
-                                .collect();
-                            DebugResponse::InspectionResult {
-                                function: state.current_function().map(|s| s.to_string()),
-                                args: state.current_args().map(|s| s.to_string()),
-                                step_count: state.step_count() as u64,
-                                paused: engine.is_paused(),
-                                call_stack,
+            },
+            DebugRequest::Continue => match self.engine.as_mut() {
+                Some(engine) => {
+                    if let Some(pending) = self.pending_execution.take() {
+                        let exec_result = {
+                            self.control.set_executing(self.id, true);
+                            let r = engine.execute_without_breakpoints(
+                                &pending.function,
+                                pending.args.as_deref(),
+                            );
+                            self.control.set_executing(self.id, false);
+                            r
+                        };
+                        match exec_result {
+                            Ok(output) => DebugResponse::ContinueResult {
+                                completed: true,
+                                output: Some(output),
+                                error: None,
+                                paused: false,
+                                source_location: None,
+                            },
+                            Err(e) => DebugResponse::ContinueResult {
+                                completed: false,
+                                output: None,
+                                error: Some(e.to_string()),
+                                paused: false,
                                 source_location: None,
-                            }
-                        }
-                        Err(e) => DebugResponse::Error {
-                            message: format!("Failed to acquire state lock: {}", e),
-                        },
-                    },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
-                },
-                DebugRequest::GetStorage => match self.engine.as_ref() {
-                    Some(engine) => match engine.executor().get_storage_snapshot() {
-                        Ok(snapshot) => match serde_json::to_string(&snapshot) {
-                            Ok(json) => DebugResponse::StorageState { storage_json: json },
-                            Err(e) => DebugResponse::Error {
-                                message: format!("Failed to serialize storage snapshot: {}", e),
                             },
-                        },
-                        Err(e) => DebugResponse::Error {
-                            message: e.to_string(),
-                        },
-                    },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
-                },
-                DebugRequest::GetStack => match self.engine.as_ref() {
-                    Some(engine) => match engine.state().lock() {
-                        Ok(state) => {
-                            let stack = state
-                                .call_stack()
-                                .get_stack()
-                                .iter()
-                                .map(|frame| {
-                                    let suffix = frame
-                                        .contract_id
-                                        .as_ref()
-                                        .map(|id| format!(" [{}]", id))
-                                        .unwrap_or_default();
-                                    format!("{}{}", frame.function, suffix)
-                                })
-                                .collect();
-                            DebugResponse::CallStack { stack }
                         }
-                        Err(e) => DebugResponse::Error {
-                            message: format!("Failed to acquire state lock: {}", e),
-                        },
-                    },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
-                },
-                DebugRequest::GetBudget => match self.engine.as_ref() {
-                    Some(engine) => {
-                        let info = BudgetInspector::get_cpu_usage(engine.executor().host());
-                        DebugResponse::BudgetInfo {
-                            cpu_instructions: info.cpu_instructions,
-                            memory_bytes: info.memory_bytes,
+                    } else {
+                        match engine.continue_execution() {
+                            Ok(_) => DebugResponse::ContinueResult {
+                                completed: true,
+                                output: None,
+                                error: None,
+                                paused: engine.is_paused(),
+                                source_location: None,
+                            },
+                            Err(e) => DebugResponse::ContinueResult {
+                                completed: false,
+                                output: None,
+                                error: Some(e.to_string()),
+                                paused: engine.is_paused(),
+                                source_location: None,
+                            },
                         }
                     }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+                }
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::SetBreakpoint {
-                    id,
-                    function,
-                    condition,
-                    hit_condition,
-                    log_message,
-                } => match self.engine.as_mut() {
-                    Some(engine) => {
-                        let condition = match condition {
-                            Some(condition) => match BreakpointManager::parse_condition(&condition)
-                            {
-                                Ok(condition) => Some(condition),
+            },
+            DebugRequest::SetBreakpoint {
+                id,
+                function,
+                condition,
+                hit_condition,
+                log_message,
+            } => match self.engine.as_mut() {
+                Some(engine) => {
+                    let condition = match condition {
+                        Some(condition) => match BreakpointManager::parse_condition(&condition) {
+                            Ok(condition) => Some(condition),
+                            Err(e) => {
+                                return DebugResponse::Error {
+                                    message: e.to_string(),
+                                };
+                            }
+                        },
+                        None => None,
+                    };
+                    let hit_condition = match hit_condition {
+                        Some(hit_condition) => {
+                            match BreakpointManager::parse_hit_condition(&hit_condition) {
+                                Ok(hit_condition) => Some(hit_condition),
                                 Err(e) => {
-                                    let response = DebugMessage::response(
-                                        message.id,
-                                        DebugResponse::Error {
-                                            message: e.to_string(),
-                                        },
-                                    );
-                                    send_msg(response)?;
-                                    continue;
-                                }
-                            },
-                            None => None,
-                        };
-                        let hit_condition = match hit_condition {
-                            Some(hit_condition) => {
-                                match BreakpointManager::parse_hit_condition(&hit_condition) {
-                                    Ok(hit_condition) => Some(hit_condition),
-                                    Err(e) => {
-                                        let response = DebugMessage::response(
-                                            message.id,
-                                            DebugResponse::Error {
-                                                message: e.to_string(),
-                                            },
-                                        );
-                                        send_msg(response)?;
-                                        continue;
-                                    }
+                                    return DebugResponse::Error {
+                                        message: e.to_string(),
+                                    };
                                 }
                             }
-                            None => None,
-                        };
+                        }
+                        None => None,
+                    };
 
-                        engine.breakpoints_mut().add_spec(BreakpointSpec {
-                            id: id.clone(),
-                            function: function.clone(),
-                            condition,
-                            hit_condition,
-                            log_message,
-                        });
-                        DebugResponse::BreakpointSet { id, function }
-                    }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+                    engine.breakpoints_mut().add_spec(BreakpointSpec {
+                        id: id.clone(),
+                        function: function.clone(),
+                        condition,
+                        hit_condition,
+                        log_message,
+                    });
+                    DebugResponse::BreakpointSet { id, function }
+                }
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::ClearBreakpoint { id } => match self.engine.as_mut() {
-                    Some(engine) => {
-                        engine.breakpoints_mut().remove_by_id(&id);
-                        DebugResponse::BreakpointCleared { id }
-                    }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+            },
+            DebugRequest::ClearBreakpoint { id } => match self.engine.as_mut() {
+                Some(engine) => {
+                    engine.breakpoints_mut().remove_by_id(&id);
+                    DebugResponse::BreakpointCleared { id }
+                }
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::ListBreakpoints => match self.engine.as_mut() {
-                    Some(engine) => DebugResponse::BreakpointsList {
-                        breakpoints: engine
-                            .breakpoints_mut()
-                            .list_detailed()
-                            .into_iter()
-                            .map(|breakpoint| BreakpointDescriptor {
-                                id: breakpoint.id.clone(),
-                                function: breakpoint.function.clone(),
-                                condition: breakpoint.condition.clone(),
-                                hit_condition: breakpoint.hit_condition.clone(),
-                                log_message: breakpoint.log_message.clone(),
-                            })
-                            .collect(),
-                    },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
-                    },
+            },
+            DebugRequest::ListBreakpoints => match self.engine.as_mut() {
+                Some(engine) => DebugResponse::BreakpointsList {
+                    breakpoints: engine
+                        .breakpoints_mut()
+                        .list_detailed()
+                        .into_iter()
+                        .map(|breakpoint| BreakpointDescriptor {
+                            id: breakpoint.id.clone(),
+                            function: breakpoint.function.clone(),
+                            condition: breakpoint.condition.clone(),
+                            hit_condition: breakpoint.hit_condition.clone(),
+                            log_message: breakpoint.log_message.clone(),
+                        })
+                        .collect(),
                 },
-                DebugRequest::GetCapabilities => DebugResponse::Capabilities {
-                    breakpoints: BreakpointCapabilities {
-                        conditional_breakpoints: true,
-                        hit_conditional_breakpoints: true,
-                        log_points: true,
-                    },
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
                 },
-                DebugRequest::SetStorage { storage_json } => match self.engine.as_mut() {
-                    Some(engine) => match engine.executor_mut().set_initial_storage(storage_json) {
-                        Ok(_) => match engine.executor().get_storage_snapshot() {
-                            Ok(snapshot) => match serde_json::to_string(&snapshot) {
-                                Ok(json) => DebugResponse::StorageState { storage_json: json },
-                                Err(e) => DebugResponse::Error {
-                                    message: format!("Failed to serialize storage snapshot: {}", e),
-                                },
-                            },
+            },
+            DebugRequest::GetCapabilities => DebugResponse::Capabilities {
+                breakpoints: BreakpointCapabilities {
+                    conditional_breakpoints: true,
+                    hit_conditional_breakpoints: true,
+                    log_points: true,
+                },
+            },
+            DebugRequest::SetStorage { storage_json } => match self.engine.as_mut() {
+                Some(engine) => match engine.executor_mut().set_initial_storage(storage_json) {
+                    Ok(_) => match engine.executor().get_storage_snapshot() {
+                        Ok(snapshot) => match serde_json::to_string(&snapshot) {
+                            Ok(json) => DebugResponse::StorageState { storage_json: json },
                             Err(e) => DebugResponse::Error {
-                                message: e.to_string(),
+                                message: format!("Failed to serialize storage snapshot: {}", e),
                             },
                         },
                         Err(e) => DebugResponse::Error {
                             message: e.to_string(),
                         },
                     },
-                    None => DebugResponse::Error {
-                        message: "No contract loaded".to_string(),
+                    Err(e) => DebugResponse::Error {
+                        message: e.to_string(),
                     },
                 },
-                DebugRequest::LoadSnapshot { snapshot_path } => {
-                    match SnapshotLoader::from_file(snapshot_path) {
-                        Ok(loader) => match loader.apply_to_environment() {
-                            Ok(loaded) => DebugResponse::SnapshotLoaded {
-                                summary: loaded.format_summary(),
-                            },
-                            Err(e) => DebugResponse::Error {
-                                message: e.to_string(),
-                            },
+                None => DebugResponse::Error {
+                    message: "No contract loaded".to_string(),
+                },
+            },
+            DebugRequest::LoadSnapshot { snapshot_path } => {
+                match SnapshotLoader::from_file(snapshot_path) {
+                    Ok(loader) => match loader.apply_to_environment() {
+                        Ok(loaded) => DebugResponse::SnapshotLoaded {
+                            summary: loaded.format_summary(),
                         },
                         Err(e) => DebugResponse::Error {
                             message: e.to_string(),
                         },
-                    }
+                    },
+                    Err(e) => DebugResponse::Error {
+                        message: e.to_string(),
+                    },
                 }
-                DebugRequest::Evaluate { expression, .. } => match self.engine.as_ref() {
-                    Some(engine) => {
-                        // First try to look up the expression as a storage key
-                        match engine.executor().get_storage_snapshot() {
-                            Ok(snapshot) => {
-                                if let Some(value) = snapshot.get(&expression) {
-                                    let result = serde_json::to_string(value)
-                                        .unwrap_or_else(|_| format!("{:?}", value));
-                                    DebugResponse::EvaluateResult {
+            }
+            DebugRequest::Evaluate { expression, .. } => match self.engine.as_ref() {
+                Some(engine) => {
+                    // First try to look up the expression as a storage key
+                    match engine.executor().get_storage_snapshot() {
+                        Ok(snapshot) => {
+                            if let Some(value) = snapshot.get(&expression) {
+                                let result = serde_json::to_string(value)
+                                    .unwrap_or_else(|_| format!("{:?}", value));
+                                DebugResponse::EvaluateResult {
+                                    result,
+                                    result_type: Some("storage".to_string()),
+                                    variables_reference: 0,
+                                }
+                            } else {
+                                // Try matching built-in state fields
+                                let state_result = engine.state().lock().ok().and_then(|state| {
+                                    match expression.as_str() {
+                                        "function" | "current_function" => state
+                                            .current_function()
+                                            .map(|f| (f.to_string(), "string".to_string())),
+                                        "args" | "arguments" => state
+                                            .current_args()
+                                            .map(|a| (a.to_string(), "string".to_string())),
+                                        "step_count" | "steps" => Some((
+                                            state.step_count().to_string(),
+                                            "number".to_string(),
+                                        )),
+                                        _ => None,
+                                    }
+                                });
+
+                                let state_result = state_result.or_else(|| {
+                                    // Otherwise evaluate it as an expression over the
+                                    // current call's arguments and now().
+                                    let (function, args) =
+                                        engine.state().lock().ok().map(|state| {
+                                            (
+                                                state
+                                                    .current_function()
+                                                    .unwrap_or_default()
+                                                    .to_string(),
+                                                state.current_args().map(String::from),
+                                            )
+                                        })?;
+                                    let value = engine
+                                        .condition_evaluator(&function, args.as_deref())
+                                        .eval(&expression)
+                                        .ok()?;
+                                    Some((value.to_string(), value.kind().to_string()))
+                                });
+
+                                match state_result {
+                                    Some((result, result_type)) => DebugResponse::EvaluateResult {
                                         result,
-                                        result_type: Some("storage".to_string()),
+                                        result_type: Some(result_type),
                                         variables_reference: 0,
-                                    }
-                                } else {
-                                    // Try matching built-in state fields
-                                    let state_result = engine.state().lock().ok().and_then(
-                                        |state| match expression.as_str() {
-                                            "function" | "current_function" => state
-                                                .current_function()
-                                                .map(|f| (f.to_string(), "string".to_string())),
-                                            "args" | "arguments" => state
-                                                .current_args()
-                                                .map(|a| (a.to_string(), "string".to_string())),
-                                            "step_count" | "steps" => Some((
-                                                state.step_count().to_string(),
-                                                "number".to_string(),
-                                            )),
-                                            _ => None,
-                                        },
-                                    );
-
-                                    match state_result {
-                                        Some((result, result_type)) => {
-                                            DebugResponse::EvaluateResult {
-                                                result,
-                                                result_type: Some(result_type),
-                                                variables_reference: 0,
-                                            }
-                                        }
-                                        None => DebugResponse::Error {
-                                            message: format!(
-                                                "Cannot evaluate '{}': only storage key lookup \
-                                                 and built-in fields (function, args, \
-                                                 step_count) are supported",
-                                                expression
-                                            ),
-                                        },
-                                    }
+                                    },
+                                    None => DebugResponse::Error {
+                                        message: format!(
+                                            "Cannot evaluate '{}': only storage key lookup, \
+                                             built-in fields (function, args, step_count) \
+                                             and expressions over the call's arguments \
+                                             and now() are supported",
+                                            expression
+                                        ),
+                                    },
                                 }
                             }
-                            Err(e) => DebugResponse::Error {
-                                message: format!("Failed to access storage for evaluation: {}", e),
-                            },
                         }
+                        Err(e) => DebugResponse::Error {
+                            message: format!("Failed to access storage for evaluation: {}", e),
+                        },
                     }
-                    None => DebugResponse::Error {
-                        message: "No contract loaded. Evaluation requires an active debug session."
-                            .to_string(),
-                    },
+                }
+                None => DebugResponse::Error {
+                    message: "No contract loaded. Evaluation requires an active debug session."
+                        .to_string(),
                 },
-                DebugRequest::Ping => DebugResponse::Pong,
-                DebugRequest::Disconnect => DebugResponse::Disconnected,
-                DebugRequest::Cancel => DebugResponse::CancelAck,
-                DebugRequest::Unknown => DebugResponse::Error {
+            },
+            DebugRequest::Ping => DebugResponse::Pong,
+            DebugRequest::Disconnect => DebugResponse::Disconnected,
+            DebugRequest::Cancel => DebugResponse::CancelAck,
+            DebugRequest::Unknown => DebugResponse::Error {
+                message: "Unknown request type. Try upgrading the server.".to_string(),
+            },
+        }
+    }
+}
+
+async fn accept_connections(
+    listener: TcpListener,
+    acceptor: Option<TlsAcceptor>,
+    shared: Arc<SharedSession>,
+) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, addr)) => {
+                info!("New connection from {}", addr);
+                let acceptor = acceptor.clone();
+                let shared = Arc::clone(&shared);
+                tokio::spawn(async move {
+                    if let Some(acceptor) = acceptor {
+                        match acceptor.accept(stream).await {
+                            Ok(tls_stream) => {
+                                if let Err(e) = handle_connection(tls_stream, shared).await {
+                                    error!("TLS connection error: {}", e);
+                                }
+                            }
+                            Err(e) => error!("TLS accept error: {}", e),
+                        }
+                    } else if let Err(e) = handle_connection(stream, shared).await {
+                        error!("TCP connection error: {}", e);
+                    }
+                });
+            }
+            Err(e) => error!("Failed to accept connection: {}", e),
+        }
+    }
+}
+
+/// Serve one client. Runs on its own task and never touches the engine directly.
+async fn handle_connection<S>(stream: S, shared: Arc<SharedSession>) -> Result<()>
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    let mut authenticated = shared.token.is_none();
+    let mut handshake_done = false;
+    let mut capabilities = ConnectionCapabilities::FULL;
+    // Read by the reader task, which answers `Cancel` on its own.
+    let read_only = Arc::new(AtomicBool::new(false));
+    let (reader, writer) = tokio::io::split(stream);
+    let mut reader = tokio::io::BufReader::new(reader);
+
+    let (tx_in, mut rx_in) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let (tx_out, mut rx_out) = tokio::sync::mpsc::unbounded_channel::<DebugMessage>();
+
+    tokio::spawn(async move {
+        let mut writer = writer;
+        while let Some(msg) = rx_out.recv().await {
+            if crate::server::protocol::send_response::<tokio::io::WriteHalf<S>>(&mut writer, msg)
+                .await
+                .is_err()
+            {
+                break;
+            }
+        }
+    });
+
+    let tx_out_reader = tx_out.clone();
+    let control = Arc::clone(&shared.control);
+    let reader_read_only = Arc::clone(&read_only);
+
+    tokio::spawn(async move {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let n = reader.read_line(&mut line).await.unwrap_or(0);
+            if n == 0 {
+                break;
+            }
+
+            if let Ok(msg) = DebugMessage::parse(line.trim_end()) {
+                if matches!(msg.request, Some(DebugRequest::Cancel)) {
+                    if reader_read_only.load(Ordering::SeqCst) {
+                        let message = ConnectionCapabilities::READ_ONLY
+                            .check(&DebugRequest::Cancel)
+                            .unwrap_err();
+                        let response =
+                            DebugMessage::response(msg.id, DebugResponse::Error { message });
+                        let _ = tx_out_reader.send(response);
+                        continue;
+                    }
+                    let response = DebugMessage::response(msg.id, DebugResponse::CancelAck);
+                    let _ = tx_out_reader.send(response);
+                    if control.is_executing() {
+                        // Give the writer a moment to flush the acknowledgement.
+                        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                        control.cancel("request");
+                    }
+                    continue;
+                }
+            }
+
+            if tx_in.send(line.clone()).is_err() {
+                break;
+            }
+        }
+    });
+
+    // Helper closure to abstract away tx_out
+    let send_msg = |msg: DebugMessage| -> Result<()> {
+        tx_out
+            .send(msg)
+            .map_err(|_| miette::miette!("Connection closed"))
+    };
+
+    loop {
+        let line = match rx_in.recv().await {
+            Some(l) => l,
+            None => break,
+        };
+
+        let message = match DebugMessage::parse(line.trim_end()) {
+            Ok(msg) => msg,
+            Err(e) => {
+                warn!("Failed to parse request: {}", e);
+                let response = DebugMessage::response(
+                    0, // ID might be unknown if parse failed, but often it's available.
+                    // For now use 0 or try to extract it if possible.
+                    DebugResponse::Error {
+                        message: format!("Malformed request: {}", e),
+                    },
+                );
+                let _ = send_msg(response);
+                continue;
+            }
+        };
+        let Some(request) = message.request else {
+            warn!("Received message without request");
+            continue;
+        };
+
+        if matches!(request, DebugRequest::Unknown) {
+            let response = DebugMessage::response(
+                message.id,
+                DebugResponse::Error {
                     message: "Unknown request type. Try upgrading the server.".to_string(),
                 },
-            };
+            );
+            send_msg(response)?;
+            continue;
+        }
+
+        info!("Received request: {}", summarize_request(&request));
+
+        if matches!(request, DebugRequest::Ping) {
+            let response = DebugMessage::response(message.id, DebugResponse::Pong);
+            send_msg(response)?;
+            continue;
+        }
+
+        if let DebugRequest::Handshake {
+            client_name,
+            client_version,
+            protocol_min,
+            protocol_max,
+        } = &request
+        {
+            let server_name = "soroban-debug".to_string();
+            let server_version = env!("CARGO_PKG_VERSION").to_string();
+
+            match negotiate_protocol_version(*protocol_min, *protocol_max) {
+                Ok(selected_version) => {
+                    handshake_done = true;
+                    let response = DebugMessage::response(
+                        message.id,
+                        DebugResponse::HandshakeAck {
+                            server_name,
+                            server_version,
+                            protocol_min: PROTOCOL_MIN_VERSION,
+                            protocol_max: PROTOCOL_MAX_VERSION,
+                            selected_version,
+                        },
+                    );
+                    send_msg(response)?;
+                    continue;
+                }
+                Err(e) => {
+                    let response = DebugMessage::response(
+                        message.id,
+                        DebugResponse::IncompatibleProtocol {
+                            message: format!(
+                                "{}. Client: {}@{}. Upgrade the older component.",
+                                e, client_name, client_version
+                            ),
+                            server_name,
+                            server_version,
+                            protocol_min: PROTOCOL_MIN_VERSION,
+                            protocol_max: PROTOCOL_MAX_VERSION,
+                        },
+                    );
+                    send_msg(response)?;
+                    return Ok(());
+                }
+            }
+        }
 
+        // Backward compatibility: allow Authenticate before handshake.
+        if let DebugRequest::Authenticate { token } = &request {
+            let success = shared
+                .token
+                .as_deref()
+                .map(|server_token| server_token == token)
+                .unwrap_or(true);
+            authenticated = success;
+            let response = DebugResponse::Authenticated {
+                success,
+                message: if success {
+                    "Authentication successful".to_string()
+                } else {
+                    "Authentication failed".to_string()
+                },
+            };
             let response = DebugMessage::response(message.id, response);
             send_msg(response)?;
+            if !success {
+                return Ok(());
+            }
+            continue;
+        }
 
-            if is_disconnect {
-                break;
+        if !handshake_done {
+            let response = DebugMessage::response(
+                message.id,
+                DebugResponse::Error {
+                    message: "Protocol handshake required: send a Handshake request before other debug requests.".to_string(),
+                },
+            );
+            send_msg(response)?;
+            continue;
+        }
+
+        if !authenticated {
+            if let DebugRequest::Authenticate { token } = request {
+                let success = shared.token.as_deref().map(|t| t == token).unwrap_or(true);
+                authenticated = success;
+                let response = DebugResponse::Authenticated {
+                    success,
+                    message: if success {
+                        "Authentication successful".to_string()
+                    } else {
+                        "Authentication failed".to_string()
+                    },
+                };
+                let response = DebugMessage::response(message.id, response);
+                send_msg(response)?;
+                if !success {
+                    return Ok(());
+                }
+                continue;
             }
+
+            let response = DebugMessage::response(
+                message.id,
+                DebugResponse::Error {
+                    message: "Authentication required".to_string(),
+                },
+            );
+            send_msg(response)?;
+            continue;
         }
 
-        Ok(())
+        if matches!(request, DebugRequest::Disconnect) {
+            send_msg(DebugMessage::response(
+                message.id,
+                DebugResponse::Disconnected,
+            ))?;
+            break;
+        }
+
+        if let DebugRequest::Attach { session } = &request {
+            let response = if *session == shared.session {
+                capabilities = ConnectionCapabilities::READ_ONLY;
+                read_only.store(true, Ordering::SeqCst);
+                info!("Client attached read-only to session '{}'", session);
+                DebugResponse::Attached {
+                    session: session.clone(),
+                    capabilities,
+                }
+            } else {
+                DebugResponse::Error {
+                    message: format!(
+                        "No session named '{}' (this server hosts '{}')",
+                        session, shared.session
+                    ),
+                }
+            };
+            send_msg(DebugMessage::response(message.id, response))?;
+            continue;
+        }
+
+        if let Err(reason) = capabilities.check(&request) {
+            warn!("Refused {} from a read-only connection", request.name());
+            send_msg(DebugMessage::response(
+                message.id,
+                DebugResponse::Error { message: reason },
+            ))?;
+            continue;
+        }
+
+        // Reads are served from the last committed state and never wait in the queue.
+        if let Some(response) = shared.snapshot.load().respond(&request) {
+            send_msg(DebugMessage::response(message.id, response))?;
+            continue;
+        }
+
+        let Some((ahead, pending)) = shared.queue.submit(request) else {
+            send_msg(DebugMessage::response(
+                message.id,
+                DebugResponse::Error {
+                    message: "Debug session has shut down".to_string(),
+                },
+            ))?;
+            break;
+        };
+        if ahead > 0 {
+            send_msg(DebugMessage::response(
+                message.id,
+                DebugResponse::Queued { position: ahead },
+            ))?;
+        }
+        let response = pending.await.unwrap_or_else(|_| DebugResponse::Error {
+            message: "Debug session has shut down".to_string(),
+        });
+        send_msg(DebugMessage::response(message.id, response))?;
     }
+
+    Ok(())
 }
 
 fn execute_without_breakpoints(
@@ -984,10 +1296,6 @@ fn execute_without_breakpoints(
     }
 }
 
-fn current_storage(engine: &DebuggerEngine) -> Result<std::collections::HashMap<String, String>> {
-    engine.executor().get_storage_snapshot()
-}
-
 fn load_tls_config(cert_path: &Path, key_path: &Path) -> Result<ServerConfig> {
     let cert_file = fs::File::open(cert_path)
         .map_err(|e| miette::miette!("Failed to open cert file {:?}: {}", cert_path, e))?;
@@ -1028,31 +1336,34 @@ fn summarize_request(request: &DebugRequest) -> String {
     }
 }
 
-async fn setup_signal_handlers(shutdown: Arc<Notify>) {
-    let mut ctrl_c = Box::pin(tokio::signal::ctrl_c());
-
+async fn setup_signal_handlers(shutdown: Arc<Notify>, control: Arc<SessionControl>) {
     #[cfg(unix)]
-    {
+    let mut sigterm = {
         use tokio::signal::unix::{signal, SignalKind};
-        let mut sigterm = signal(SignalKind::terminate()).expect("Failed to setup SIGTERM handler");
+        signal(SignalKind::terminate()).expect("Failed to setup SIGTERM handler")
+    };
 
-        tokio::select! {
-            _ = &mut ctrl_c => {
-                info!("Received SIGINT, initiating shutdown");
-            }
-            _ = sigterm.recv() => {
-                info!("Received SIGTERM, initiating shutdown");
-            }
-        }
-    }
+    loop {
+        #[cfg(unix)]
+        let signal_name = tokio::select! {
+            _ = tokio::signal::ctrl_c() => "SIGINT",
+            _ = sigterm.recv() => "SIGTERM",
+        };
 
-    #[cfg(not(unix))]
-    {
-        let _ = ctrl_c.await;
-        info!("Received SIGINT, initiating shutdown");
-    }
+        #[cfg(not(unix))]
+        let signal_name = {
+            let _ = tokio::signal::ctrl_c().await;
+            "SIGINT"
+        };
 
-    shutdown.notify_one();
+        // A signal during an invocation takes the same path as a Cancel
+        // request; one while the session is idle shuts the server down.
+        if !control.cancel(signal_name) {
+            info!("Received {}, initiating shutdown", signal_name);
+            shutdown.notify_one();
+            return;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1101,10 +1412,272 @@ mod tests {
             .await;
     }
 
+    /// Several clients invoke, read and cancel against one session at once.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_clients_share_one_session_without_deadlock() {
+        use crate::client::RemoteClient;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        const WRITERS: usize = 4;
+        const INVOCATIONS: usize = 10;
+
+        let aborts = Arc::new(AtomicUsize::new(0));
+        let mut server = DebugServer::new(None, None, None).expect("Failed to create server");
+        let abort_count = Arc::clone(&aborts);
+        server.control = Arc::new(SessionControl::with_abort_handler(move |_| {
+            abort_count.fetch_add(1, Ordering::SeqCst);
+        }));
+        let shutdown = server.shutdown.clone();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let wasm = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wasm/counter.wasm");
+
+        let cancelled = Arc::clone(&aborts);
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                let server_task = tokio::task::spawn_local(server.run_on(listener));
+
+                let setup_addr = addr.clone();
+                tokio::task::spawn_blocking(move || {
+                    let mut client = RemoteClient::connect(&setup_addr, None).unwrap();
+                    client.load_contract(wasm.to_str().unwrap()).unwrap();
+                })
+                .await
+                .unwrap();
+
+                let applied = Arc::new(AtomicUsize::new(0));
+                let mut workers = Vec::new();
+                for _ in 0..WRITERS {
+                    let addr = addr.clone();
+                    let applied = Arc::clone(&applied);
+                    workers.push(tokio::task::spawn_blocking(move || {
+                        let mut client = RemoteClient::connect(&addr, None).unwrap();
+                        for _ in 0..INVOCATIONS {
+                            match client.execute("increment", None) {
+                                Ok(_) => {
+                                    applied.fetch_add(1, Ordering::SeqCst);
+                                }
+                                Err(e) => assert!(e.to_string().contains("cancelled"), "{}", e),
+                            }
+                        }
+                    }));
+                }
+                for _ in 0..2 {
+                    let addr = addr.clone();
+                    workers.push(tokio::task::spawn_blocking(move || {
+                        let mut client = RemoteClient::connect(&addr, None).unwrap();
+                        for _ in 0..25 {
+                            let storage = client.get_storage().unwrap();
+                            serde_json::from_str::<std::collections::HashMap<String, String>>(
+                                &storage,
+                            )
+                            .expect("storage snapshot should be complete JSON");
+                            client.inspect().unwrap();
+                        }
+                    }));
+                }
+                {
+                    let addr = addr.clone();
+                    workers.push(tokio::task::spawn_blocking(move || {
+                        let mut client = RemoteClient::connect(&addr, None).unwrap();
+                        for _ in 0..20 {
+                            client.cancel().unwrap();
+                            std::thread::sleep(std::time::Duration::from_millis(5));
+                        }
+                    }));
+                }
+
+                for worker in workers {
+                    tokio::time::timeout(std::time::Duration::from_secs(60), worker)
+                        .await
+                        .expect("client deadlocked")
+                        .expect("client panicked");
+                }
+
+                let final_addr = addr.clone();
+                let total = tokio::task::spawn_blocking(move || {
+                    let mut client = RemoteClient::connect(&final_addr, None).unwrap();
+                    client.execute("get", None).unwrap()
+                })
+                .await
+                .unwrap();
+                let applied = applied.load(Ordering::SeqCst);
+                assert!(
+                    total.contains(&applied.to_string()),
+                    "every invocation that was not cancelled should apply exactly once, \
+                     expected {}, got {}",
+                    applied,
+                    total
+                );
+                // Each cancel that found an invocation running undid exactly that one.
+                assert_eq!(
+                    cancelled.load(Ordering::SeqCst),
+                    WRITERS * INVOCATIONS - applied
+                );
+
+                shutdown.notify_one();
+                tokio::time::timeout(std::time::Duration::from_secs(5), server_task)
+                    .await
+                    .expect("Server shutdown timed out")
+                    .expect("Server task panicked")
+                    .expect("Server returned an error");
+            })
+            .await;
+
+        // Cancels only abort while an invocation is running; the rest are plain acks.
+        assert!(aborts.load(Ordering::SeqCst) <= 20);
+    }
+
+    /// A cancelled call that would run for seconds ends at once, and the
+    /// session carries on from where it was before the call.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn cancel_ends_a_running_invocation_and_keeps_the_session() {
+        use crate::client::RemoteClient;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let aborts = Arc::new(AtomicUsize::new(0));
+        let mut server = DebugServer::new(None, None, None).expect("Failed to create server");
+        let abort_count = Arc::clone(&aborts);
+        let control = Arc::new(SessionControl::with_abort_handler(move |_| {
+            abort_count.fetch_add(1, Ordering::SeqCst);
+        }));
+        server.control = Arc::clone(&control);
+        // Enough budget that `spin` loops well past the cancel.
+        server.budget_limits = Some(BudgetLimits {
+            cpu_insns: 2_000_000_000,
+            ..BudgetLimits::HOST_DEFAULT
+        });
+        let shutdown = server.shutdown.clone();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wasm");
+
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                let server_task = tokio::task::spawn_local(server.run_on(listener));
+
+                let spin = fixtures.join("spin.wasm");
+                let owner_addr = addr.clone();
+                let owner = tokio::task::spawn_blocking(move || {
+                    let mut client = RemoteClient::connect(&owner_addr, None).unwrap();
+                    client.load_contract(spin.to_str().unwrap()).unwrap();
+                    client.set_breakpoint("never_called", None).unwrap();
+                    let err = client.execute("spin", None).unwrap_err().to_string();
+                    (client, err)
+                });
+
+                let deadline = std::time::Instant::now() + std::time::Duration::from_secs(30);
+                while !control.is_executing() {
+                    assert!(std::time::Instant::now() < deadline, "spin never started");
+                    tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+                }
+                let cancel_addr = addr.clone();
+                tokio::task::spawn_blocking(move || {
+                    RemoteClient::connect(&cancel_addr, None)
+                        .unwrap()
+                        .cancel()
+                        .unwrap();
+                })
+                .await
+                .unwrap();
+
+                let (mut client, err) =
+                    tokio::time::timeout(std::time::Duration::from_secs(30), owner)
+                        .await
+                        .expect("cancelled invocation never returned")
+                        .expect("client panicked");
+                assert!(err.contains("Invocation cancelled"), "{}", err);
+                assert_eq!(aborts.load(Ordering::SeqCst), 1);
+                assert!(!control.is_executing());
+
+                let counter = fixtures.join("counter.wasm");
+                tokio::task::spawn_blocking(move || {
+                    // The restored session kept its contract and breakpoints.
+                    client.inspect().unwrap();
+                    assert_eq!(client.list_breakpoints().unwrap(), vec!["never_called"]);
+                    client.get_storage().unwrap();
+                    // And it still takes new work.
+                    client.load_contract(counter.to_str().unwrap()).unwrap();
+                    client.execute("increment", None).unwrap();
+                    assert!(client.execute("get", None).unwrap().contains('1'));
+                })
+                .await
+                .unwrap();
+
+                shutdown.notify_one();
+                tokio::time::timeout(std::time::Duration::from_secs(5), server_task)
+                    .await
+                    .expect("Server shutdown timed out")
+                    .expect("Server task panicked")
+                    .expect("Server returned an error");
+            })
+            .await;
+    }
+
+    /// An attached observer can read the session but not invoke or change it.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn attached_client_is_read_only() {
+        use crate::client::RemoteClient;
+
+        let server = DebugServer::new(None, None, None)
+            .expect("Failed to create server")
+            .with_session("incident-42");
+        let shutdown = server.shutdown.clone();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        let wasm = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wasm/counter.wasm");
+
+        let local = tokio::task::LocalSet::new();
+        local
+            .run_until(async move {
+                let server_task = tokio::task::spawn_local(server.run_on(listener));
+
+                tokio::task::spawn_blocking(move || {
+                    let mut owner = RemoteClient::connect(&addr, None).unwrap();
+                    owner.load_contract(wasm.to_str().unwrap()).unwrap();
+                    owner.execute("increment", None).unwrap();
+
+                    let mut observer = RemoteClient::connect(&addr, None).unwrap();
+                    let err = observer.attach("other").unwrap_err().to_string();
+                    assert!(err.contains("incident-42"), "{}", err);
+                    let caps = observer.attach("incident-42").unwrap();
+                    assert!(caps.is_read_only());
+
+                    assert!(observer.get_storage().unwrap().contains("contract_data"));
+                    assert!(observer.get_trace().unwrap()[0].contains("increment"));
+                    observer.get_events().unwrap();
+                    observer.evaluate("step_count").unwrap();
+
+                    let err = observer.execute("increment", None).unwrap_err().to_string();
+                    assert!(err.contains("Permission denied"), "{}", err);
+                    assert!(err.contains("read-only"), "{}", err);
+                    assert!(observer.set_storage("{}").is_err());
+                    assert!(observer.set_breakpoint("increment", None).is_err());
+                    assert!(observer.cancel().is_err());
+
+                    // The refused calls left the session untouched.
+                    assert!(owner.execute("get", None).unwrap().contains('1'));
+                })
+                .await
+                .unwrap();
+
+                shutdown.notify_one();
+                tokio::time::timeout(std::time::Duration::from_secs(5), server_task)
+                    .await
+                    .expect("Server shutdown timed out")
+                    .expect("Server task panicked")
+                    .expect("Server returned an error");
+            })
+            .await;
+    }
+
     #[test]
     fn test_server_initialization() {
         let server = DebugServer::new(None, None, None).expect("Failed to create server");
-        assert!(server.engine.is_none());
+        assert_eq!(server.session(), DEFAULT_SESSION);
+        assert!(server.budget_limits.is_none());
         assert!(server.token.is_none());
         assert!(server.tls_config.is_none());
     }
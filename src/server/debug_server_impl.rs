@@ -38,18 +38,19 @@ impl DebugServer {
             .map_err(|e| crate::DebuggerError::ExecutionError(format!("Bind failed: {e}")))?;
 
         let local = tokio::task::LocalSet::new();
-        local.run_until(async move {
-            loop {
-                let (stream, _) = listener.accept().await.map_err(|e| {
-                    crate::DebuggerError::ExecutionError(format!("Accept failed: {e}"))
-                })?;
-                let token = self.token.clone();
-                tokio::task::spawn_local(async move {
-                    let _ = handle_connection(stream, token).await;
-                });
-            }
-        }).await
-
+        local
+            .run_until(async move {
+                loop {
+                    let (stream, _) = listener.accept().await.map_err(|e| {
+                        crate::DebuggerError::ExecutionError(format!("Accept failed: {e}"))
+                    })?;
+                    let token = self.token.clone();
+                    tokio::task::spawn_local(async move {
+                        let _ = handle_connection(stream, token).await;
+                    });
+                }
+            })
+            .await
     }
 }
 
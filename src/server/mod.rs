@@ -1,5 +1,6 @@
 pub mod debug_server;
 pub mod protocol;
+pub mod session;
 
 pub use debug_server::DebugServer;
 pub use protocol::{DebugMessage, DebugRequest, DebugResponse};
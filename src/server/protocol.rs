@@ -74,6 +74,7 @@ pub fn negotiate_protocol_version(
 }
 
 use crate::debugger::SourceBreakpointResolution;
+use crate::inspector::events::ContractEvent;
 
 /// Structured event category used by dynamic security analysis.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
@@ -135,6 +136,65 @@ pub struct BreakpointDescriptor {
     pub log_message: Option<String>,
 }
 
+/// What a request does to the session, for per-connection permission checks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestAccess {
+    /// Observes the session or the connection only
+    Read,
+    /// Runs contract code (execute, step, continue, cancel)
+    Invoke,
+    /// Changes the session without running code (contract, storage, breakpoints)
+    Mutate,
+}
+
+/// Capabilities granted to one connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionCapabilities {
+    pub invoke: bool,
+    pub mutate: bool,
+}
+
+impl ConnectionCapabilities {
+    /// A regular client: may do anything.
+    pub const FULL: Self = Self {
+        invoke: true,
+        mutate: true,
+    };
+
+    /// An attached observer.
+    pub const READ_ONLY: Self = Self {
+        invoke: false,
+        mutate: false,
+    };
+
+    pub fn is_read_only(&self) -> bool {
+        !self.invoke && !self.mutate
+    }
+
+    /// Check `request` against these capabilities, explaining a refusal.
+    pub fn check(&self, request: &DebugRequest) -> std::result::Result<(), String> {
+        let (allowed, verb) = match request.access() {
+            RequestAccess::Read => return Ok(()),
+            RequestAccess::Invoke => (self.invoke, "invoke"),
+            RequestAccess::Mutate => (self.mutate, "modify"),
+        };
+        if allowed {
+            return Ok(());
+        }
+        Err(format!(
+            "Permission denied: {} would {} the session, but this connection is attached read-only",
+            request.name(),
+            verb
+        ))
+    }
+}
+
+impl Default for ConnectionCapabilities {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
 /// Wire protocol messages for remote debugging
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -150,6 +210,10 @@ pub enum DebugRequest {
     /// Authenticate with the server
     Authenticate { token: String },
 
+    /// Attach to the named session read-only. From then on the connection
+    /// may inspect the session but not invoke or change it.
+    Attach { session: String },
+
     /// Load a contract
     LoadContract { contract_path: String },
 
@@ -191,6 +255,12 @@ pub enum DebugRequest {
     /// Get budget information
     GetBudget,
 
+    /// Get the events emitted so far
+    GetEvents,
+
+    /// Get the function calls made so far
+    GetTrace,
+
     /// Set a breakpoint
     SetBreakpoint {
         id: String,
@@ -239,6 +309,37 @@ pub enum DebugRequest {
     Unknown,
 }
 
+impl DebugRequest {
+    pub fn access(&self) -> RequestAccess {
+        match self {
+            DebugRequest::Execute { .. }
+            | DebugRequest::Step
+            | DebugRequest::StepIn
+            | DebugRequest::Next
+            | DebugRequest::StepOut
+            | DebugRequest::StepOverLine
+            | DebugRequest::Continue
+            | DebugRequest::Cancel => RequestAccess::Invoke,
+            DebugRequest::LoadContract { .. }
+            | DebugRequest::SetBreakpoint { .. }
+            | DebugRequest::ClearBreakpoint { .. }
+            | DebugRequest::SetStorage { .. }
+            | DebugRequest::LoadSnapshot { .. } => RequestAccess::Mutate,
+            _ => RequestAccess::Read,
+        }
+    }
+
+    /// The request's `type` tag, e.g. `Execute`.
+    pub fn name(&self) -> String {
+        let debug = format!("{:?}", self);
+        debug
+            .split([' ', '{', '('])
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+}
+
 /// Response messages from the server
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -264,6 +365,12 @@ pub enum DebugResponse {
     /// Authentication result
     Authenticated { success: bool, message: String },
 
+    /// Attached to a session with the given capabilities
+    Attached {
+        session: String,
+        capabilities: ConnectionCapabilities,
+    },
+
     /// Contract loaded
     ContractLoaded { size: usize },
 
@@ -324,6 +431,12 @@ pub enum DebugResponse {
         memory_bytes: u64,
     },
 
+    /// Events emitted so far
+    Events { events: Vec<ContractEvent> },
+
+    /// Function calls made so far, oldest first
+    Trace { calls: Vec<String> },
+
     /// Breakpoint set
     BreakpointSet { id: String, function: String },
 
@@ -365,6 +478,10 @@ pub enum DebugResponse {
     /// Cancel acknowledged
     CancelAck,
 
+    /// Notification sent ahead of the final response when the request is
+    /// waiting behind invocations from other clients of the same session.
+    Queued { position: usize },
+
     /// Catch-all for forward compatibility
     #[serde(other)]
     Unknown,
@@ -520,6 +637,37 @@ mod tests {
         assert!(matches!(msg.request, Some(DebugRequest::Unknown)));
     }
 
+    #[test]
+    fn read_only_connections_refuse_invocations_and_mutations() {
+        let caps = ConnectionCapabilities::READ_ONLY;
+        assert!(caps.check(&DebugRequest::GetStorage).is_ok());
+        assert!(caps
+            .check(&DebugRequest::Evaluate {
+                expression: "step_count".to_string(),
+                frame_id: None,
+            })
+            .is_ok());
+
+        let err = caps
+            .check(&DebugRequest::Execute {
+                function: "increment".to_string(),
+                args: None,
+            })
+            .unwrap_err();
+        assert!(err.contains("Execute would invoke"), "{}", err);
+        let err = caps
+            .check(&DebugRequest::SetStorage {
+                storage_json: "{}".to_string(),
+            })
+            .unwrap_err();
+        assert!(err.contains("SetStorage would modify"), "{}", err);
+        assert!(caps.check(&DebugRequest::Cancel).is_err());
+
+        assert!(ConnectionCapabilities::FULL
+            .check(&DebugRequest::Continue)
+            .is_ok());
+    }
+
     #[test]
     fn test_dynamic_trace_event_unified_call_depth() {
         let json = r#"{
@@ -0,0 +1,361 @@
+//! Shared state for a debug session that several clients attach to.
+//!
+//! Only one thread owns the [`DebuggerEngine`](crate::debugger::engine::DebuggerEngine).
+//! Connections forward state-changing requests to it through an
+//! [`InvocationQueue`], which runs them one at a time in arrival order.
+//! Read-only queries never enter the queue: they are answered from the last
+//! [`SessionSnapshot`] the owner published, so a long invocation never blocks
+//! `GetStorage` or `Inspect` and a reader never sees a half-applied change.
+
+use crate::inspector::events::ContractEvent;
+use crate::server::protocol::{DebugRequest, DebugResponse};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use tokio::sync::futures::Notified;
+use tokio::sync::{mpsc, oneshot, Notify};
+use tracing::warn;
+
+/// Session state as of the last completed request.
+#[derive(Debug, Clone)]
+pub struct SessionSnapshot {
+    /// Number of queued requests that have completed when this snapshot was taken.
+    pub revision: u64,
+    pub contract_loaded: bool,
+    pub function: Option<String>,
+    pub args: Option<String>,
+    pub step_count: u64,
+    pub paused: bool,
+    pub call_stack: Vec<String>,
+    /// Serialized storage, or the error raised while reading it.
+    pub storage: Result<String, String>,
+    pub cpu_instructions: u64,
+    pub memory_bytes: u64,
+    pub events: Vec<ContractEvent>,
+    /// Completed function calls, rendered one per line.
+    pub trace: Vec<String>,
+}
+
+impl Default for SessionSnapshot {
+    fn default() -> Self {
+        Self {
+            revision: 0,
+            contract_loaded: false,
+            function: None,
+            args: None,
+            step_count: 0,
+            paused: false,
+            call_stack: Vec::new(),
+            storage: Ok("{}".to_string()),
+            cpu_instructions: 0,
+            memory_bytes: 0,
+            events: Vec::new(),
+            trace: Vec::new(),
+        }
+    }
+}
+
+impl SessionSnapshot {
+    /// Whether `request` can be answered from a snapshot without the engine.
+    pub fn answers(request: &DebugRequest) -> bool {
+        matches!(
+            request,
+            DebugRequest::Inspect
+                | DebugRequest::GetStorage
+                | DebugRequest::GetStack
+                | DebugRequest::GetBudget
+                | DebugRequest::GetEvents
+                | DebugRequest::GetTrace
+        )
+    }
+
+    /// Answer a read-only request. Returns `None` for requests that need the engine.
+    pub fn respond(&self, request: &DebugRequest) -> Option<DebugResponse> {
+        if !Self::answers(request) {
+            return None;
+        }
+        if !self.contract_loaded {
+            return Some(DebugResponse::Error {
+                message: "No contract loaded".to_string(),
+            });
+        }
+        Some(match request {
+            DebugRequest::Inspect => DebugResponse::InspectionResult {
+                function: self.function.clone(),
+                args: self.args.clone(),
+                step_count: self.step_count,
+                paused: self.paused,
+                call_stack: self.call_stack.clone(),
+                source_location: None,
+            },
+            DebugRequest::GetStorage => match &self.storage {
+                Ok(json) => DebugResponse::StorageState {
+                    storage_json: json.clone(),
+                },
+                Err(message) => DebugResponse::Error {
+                    message: message.clone(),
+                },
+            },
+            DebugRequest::GetStack => DebugResponse::CallStack {
+                stack: self.call_stack.clone(),
+            },
+            DebugRequest::GetEvents => DebugResponse::Events {
+                events: self.events.clone(),
+            },
+            DebugRequest::GetTrace => DebugResponse::Trace {
+                calls: self.trace.clone(),
+            },
+            _ => DebugResponse::BudgetInfo {
+                cpu_instructions: self.cpu_instructions,
+                memory_bytes: self.memory_bytes,
+            },
+        })
+    }
+}
+
+/// Holds the last published snapshot.
+///
+/// Snapshots are replaced whole, so readers either see the previous state or
+/// the new one, never a mix of both.
+#[derive(Debug, Default)]
+pub struct SnapshotCell {
+    current: RwLock<Arc<SessionSnapshot>>,
+}
+
+impl SnapshotCell {
+    pub fn load(&self) -> Arc<SessionSnapshot> {
+        match self.current.read() {
+            Ok(guard) => Arc::clone(&guard),
+            Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+        }
+    }
+
+    pub fn publish(&self, snapshot: SessionSnapshot) {
+        let snapshot = Arc::new(snapshot);
+        match self.current.write() {
+            Ok(mut guard) => *guard = snapshot,
+            Err(poisoned) => *poisoned.into_inner() = snapshot,
+        }
+    }
+}
+
+/// A request waiting for the session owner, with the channel its response goes back on.
+pub struct SessionCommand {
+    pub request: DebugRequest,
+    pub reply: oneshot::Sender<DebugResponse>,
+}
+
+/// FIFO of requests for the session owner.
+pub struct InvocationQueue {
+    sender: mpsc::UnboundedSender<SessionCommand>,
+    outstanding: AtomicUsize,
+}
+
+impl InvocationQueue {
+    pub fn new() -> (Self, mpsc::UnboundedReceiver<SessionCommand>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (
+            Self {
+                sender,
+                outstanding: AtomicUsize::new(0),
+            },
+            receiver,
+        )
+    }
+
+    /// Queue `request` and return how many requests are ahead of it together
+    /// with the receiver for its response.
+    pub fn submit(
+        &self,
+        request: DebugRequest,
+    ) -> Option<(usize, oneshot::Receiver<DebugResponse>)> {
+        let (reply, response) = oneshot::channel();
+        let ahead = self.outstanding.fetch_add(1, Ordering::SeqCst);
+        if self.sender.send(SessionCommand { request, reply }).is_err() {
+            self.outstanding.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some((ahead, response))
+    }
+
+    /// Called by the owner once a request has been answered.
+    pub fn complete(&self) {
+        self.outstanding.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Requests submitted but not yet answered.
+    pub fn len(&self) -> usize {
+        self.outstanding.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+type AbortHandler = Box<dyn Fn(&str) + Send + Sync>;
+
+/// Tracks which session is running an invocation and cancels it on request.
+///
+/// The `Cancel` request and SIGINT/SIGTERM both go through [`SessionControl::cancel`].
+/// Contract execution cannot be interrupted part way, so the session owner
+/// leaves the cancelled invocation running on its thread, where the budget
+/// ends it, and carries on with the state from before the call.
+pub struct SessionControl {
+    /// Id of the session running an invocation, or 0 when none is.
+    executing: AtomicU64,
+    cancelled: Notify,
+    on_abort: AbortHandler,
+}
+
+impl Default for SessionControl {
+    fn default() -> Self {
+        Self::with_abort_handler(|reason| {
+            eprintln!("Execution cancelled via {}.", reason);
+        })
+    }
+}
+
+impl SessionControl {
+    /// `on_abort` is told about every invocation that gets cancelled.
+    pub fn with_abort_handler(on_abort: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self {
+            executing: AtomicU64::new(0),
+            cancelled: Notify::new(),
+            on_abort: Box::new(on_abort),
+        }
+    }
+
+    /// Mark whether `session` is running an invocation. A session that was
+    /// cancelled and replaced cannot clear the mark of its successor.
+    pub fn set_executing(&self, session: u64, executing: bool) {
+        if executing {
+            self.executing.store(session, Ordering::SeqCst);
+        } else {
+            let _ = self
+                .executing
+                .compare_exchange(session, 0, Ordering::SeqCst, Ordering::SeqCst);
+        }
+    }
+
+    pub fn is_executing(&self) -> bool {
+        self.executing.load(Ordering::SeqCst) != 0
+    }
+
+    /// Cancel the running invocation, if any. Returns whether one was running.
+    pub fn cancel(&self, reason: &str) -> bool {
+        if self.executing.swap(0, Ordering::SeqCst) == 0 {
+            return false;
+        }
+        warn!("Cancelling in-flight invocation ({})", reason);
+        (self.on_abort)(reason);
+        self.cancelled.notify_waiters();
+        true
+    }
+
+    /// Resolves at the next [`Self::cancel`] of a running invocation. It
+    /// catches cancels from the moment it is created, before it is polled.
+    pub fn cancelled(&self) -> Notified<'_> {
+        self.cancelled.notified()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU64;
+
+    #[test]
+    fn snapshot_rejects_reads_before_a_contract_is_loaded() {
+        let snapshot = SessionSnapshot::default();
+        assert!(matches!(
+            snapshot.respond(&DebugRequest::GetStorage),
+            Some(DebugResponse::Error { .. })
+        ));
+        assert!(snapshot.respond(&DebugRequest::Step).is_none());
+    }
+
+    #[test]
+    fn queue_reports_position_and_drains() {
+        let (queue, mut receiver) = InvocationQueue::new();
+        let (first, _) = queue.submit(DebugRequest::Step).unwrap();
+        let (second, _) = queue.submit(DebugRequest::Continue).unwrap();
+        assert_eq!((first, second), (0, 1));
+
+        assert!(matches!(
+            receiver.try_recv().unwrap().request,
+            DebugRequest::Step
+        ));
+        queue.complete();
+        assert_eq!(queue.len(), 1);
+    }
+
+    #[test]
+    fn readers_never_observe_a_torn_snapshot() {
+        let cell = Arc::new(SnapshotCell::default());
+        let writer = {
+            let cell = Arc::clone(&cell);
+            std::thread::spawn(move || {
+                for revision in 1..=2_000u64 {
+                    cell.publish(SessionSnapshot {
+                        revision,
+                        contract_loaded: true,
+                        step_count: revision,
+                        call_stack: vec![revision.to_string(); (revision % 7) as usize],
+                        storage: Ok(format!("{{\"revision\":\"{}\"}}", revision)),
+                        ..SessionSnapshot::default()
+                    });
+                }
+            })
+        };
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let cell = Arc::clone(&cell);
+                std::thread::spawn(move || {
+                    let mut last = 0;
+                    for _ in 0..2_000 {
+                        let snapshot = cell.load();
+                        assert!(snapshot.revision >= last, "revisions went backwards");
+                        last = snapshot.revision;
+                        if snapshot.revision == 0 {
+                            continue;
+                        }
+                        assert_eq!(snapshot.step_count, snapshot.revision);
+                        assert_eq!(snapshot.call_stack.len() as u64, snapshot.revision % 7);
+                        assert_eq!(
+                            snapshot.storage.as_deref().unwrap(),
+                            format!("{{\"revision\":\"{}\"}}", snapshot.revision)
+                        );
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(cell.load().revision, 2_000);
+    }
+
+    #[test]
+    fn cancel_only_aborts_while_executing() {
+        let aborts = Arc::new(AtomicU64::new(0));
+        let counter = Arc::clone(&aborts);
+        let control = SessionControl::with_abort_handler(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+
+        assert!(!control.cancel("Cancel request"));
+        control.set_executing(1, true);
+        assert!(control.cancel("SIGINT"));
+        assert_eq!(aborts.load(Ordering::SeqCst), 1);
+        assert!(!control.is_executing());
+
+        // A replaced session finishing late leaves its successor's mark alone.
+        control.set_executing(2, true);
+        control.set_executing(1, false);
+        assert!(control.is_executing());
+        control.set_executing(2, false);
+        assert!(!control.is_executing());
+    }
+}
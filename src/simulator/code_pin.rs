@@ -0,0 +1,203 @@
+//! Pinning a local contract build to the code deployed in a snapshot
+//!
+//! `run --network-snapshot` with a local `--contract` can easily debug a
+//! different build than the one deployed when the snapshot was taken.
+//! [`compare`] matches the local WASM's SHA-256 against the `wasm_hash` of the
+//! snapshot's contract instances, and [`snapshot_code`] reads the code a
+//! snapshot carries so it can be executed instead of the local file.
+
+use super::state::{ContractState, NetworkSnapshot, SimulatorError};
+use crate::utils::wasm::compute_wasm_sha256;
+use crate::Result;
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+/// How the local WASM relates to the code in a snapshot.
+#[derive(Debug, Clone)]
+pub enum CodeMatch<'a> {
+    /// A contract in the snapshot runs exactly the local code.
+    Matches(&'a ContractState),
+    /// No contract in the snapshot runs the local code.
+    Differs(Vec<&'a ContractState>),
+    /// The snapshot has no contract instances to compare against.
+    NoContracts,
+}
+
+/// Compare `local_sha256` with the code hash of every contract in `snapshot`.
+pub fn compare<'a>(snapshot: &'a NetworkSnapshot, local_sha256: &str) -> CodeMatch<'a> {
+    if snapshot.contracts.is_empty() {
+        return CodeMatch::NoContracts;
+    }
+    let local = normalize_hash(local_sha256);
+    match snapshot
+        .contracts
+        .iter()
+        .find(|contract| normalize_hash(&contract.wasm_hash) == local)
+    {
+        Some(contract) => CodeMatch::Matches(contract),
+        None => CodeMatch::Differs(snapshot.contracts.iter().collect()),
+    }
+}
+
+/// The contract whose code `--use-snapshot-code` runs: the one matching the
+/// local build, or otherwise the only contract in the snapshot.
+pub fn deployed_contract<'a>(code_match: &CodeMatch<'a>) -> Result<&'a ContractState> {
+    match code_match {
+        CodeMatch::Matches(contract) => Ok(contract),
+        CodeMatch::Differs(contracts) if contracts.len() == 1 => Ok(contracts[0]),
+        CodeMatch::Differs(contracts) => Err(SimulatorError::ValidationError(format!(
+            "Snapshot holds {} contracts ({}) and none runs the local WASM, so it is unclear whose code to use",
+            contracts.len(),
+            contracts
+                .iter()
+                .map(|contract| contract.contract_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+        .into()),
+        CodeMatch::NoContracts => Err(SimulatorError::ContractNotFound(
+            "snapshot holds no contract instances, so there is no deployed code to use".to_string(),
+        )
+        .into()),
+    }
+}
+
+/// The WASM `contract` runs in the snapshot: its embedded `code`, or the file
+/// its `wasm_ref` names, relative to `snapshot_dir`. The bytes must hash to
+/// the contract's `wasm_hash`.
+pub fn snapshot_code(contract: &ContractState, snapshot_dir: &Path) -> Result<Vec<u8>> {
+    let code = match (&contract.code, &contract.wasm_ref) {
+        (Some(code), _) => hex::decode(code.trim()).map_err(|e| {
+            SimulatorError::Corrupted(format!(
+                "code of contract {} is not valid hex: {}",
+                contract.contract_id, e
+            ))
+        })?,
+        (None, Some(wasm_ref)) => {
+            let path = snapshot_dir.join(wasm_ref);
+            std::fs::read(&path).map_err(|e| {
+                SimulatorError::ContractNotFound(format!(
+                    "code of contract {} at {:?}: {}",
+                    contract.contract_id, path, e
+                ))
+            })?
+        }
+        (None, None) => {
+            return Err(SimulatorError::ContractNotFound(format!(
+                "contract {} has no embedded code or wasm_ref in the snapshot",
+                contract.contract_id
+            ))
+            .into())
+        }
+    };
+
+    let computed = compute_wasm_sha256(&code);
+    if computed != normalize_hash(&contract.wasm_hash) {
+        return Err(SimulatorError::Corrupted(format!(
+            "code of contract {} hashes to {}, not its wasm_hash {}",
+            contract.contract_id, computed, contract.wasm_hash
+        ))
+        .into());
+    }
+    Ok(code)
+}
+
+/// Where the executed code came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeSource {
+    /// The `--contract` file.
+    Local,
+    /// The code of a contract in the snapshot.
+    Snapshot,
+}
+
+/// Which code a run executed, relative to the snapshot it ran against.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeFingerprint {
+    pub source: CodeSource,
+    pub sha256: String,
+    /// The snapshot contract running this code, if any.
+    pub contract_id: Option<String>,
+    /// Whether a contract in the snapshot runs this code.
+    pub matches_snapshot: bool,
+}
+
+impl fmt::Display for CodeFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.source, &self.contract_id) {
+            (CodeSource::Snapshot, Some(id)) => write!(f, "snapshot code of {}", id)?,
+            (CodeSource::Snapshot, None) => f.write_str("snapshot code")?,
+            (CodeSource::Local, Some(id)) => write!(f, "local WASM, deployed as {}", id)?,
+            (CodeSource::Local, None) if self.matches_snapshot => f.write_str("local WASM")?,
+            (CodeSource::Local, None) => f.write_str("local WASM, not deployed in the snapshot")?,
+        }
+        write!(f, " (sha256 {})", self.sha256)
+    }
+}
+
+fn normalize_hash(hash: &str) -> String {
+    let hash = hash.trim();
+    hash.strip_prefix("0x")
+        .or_else(|| hash.strip_prefix("0X"))
+        .unwrap_or(hash)
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODE: &[u8] = b"\0asm\x01\0\0\0";
+
+    fn snapshot_with(contracts: &[(&str, &str)]) -> NetworkSnapshot {
+        let mut snapshot = NetworkSnapshot::new(100, "Test Network", 1234567890);
+        for (id, hash) in contracts {
+            snapshot
+                .add_contract(ContractState::new(*id, *hash))
+                .unwrap();
+        }
+        snapshot
+    }
+
+    #[test]
+    fn compares_hashes_ignoring_case_and_prefix() {
+        let local = compute_wasm_sha256(CODE);
+        let deployed = format!("0x{}", local.to_ascii_uppercase());
+        let snapshot = snapshot_with(&[("CA", "aabbccdd"), ("CB", &deployed)]);
+        match compare(&snapshot, &local) {
+            CodeMatch::Matches(contract) => assert_eq!(contract.contract_id, "CB"),
+            other => panic!("{:?}", other),
+        }
+
+        let snapshot = snapshot_with(&[("CA", "aabbccdd")]);
+        let code_match = compare(&snapshot, &local);
+        assert!(matches!(code_match, CodeMatch::Differs(ref c) if c.len() == 1));
+        assert_eq!(deployed_contract(&code_match).unwrap().contract_id, "CA");
+
+        let snapshot = snapshot_with(&[("CA", "aabbccdd"), ("CB", "eeff")]);
+        let err = deployed_contract(&compare(&snapshot, &local)).unwrap_err();
+        assert!(err.to_string().contains("CA, CB"), "{}", err);
+
+        let snapshot = snapshot_with(&[]);
+        assert!(matches!(compare(&snapshot, &local), CodeMatch::NoContracts));
+    }
+
+    #[test]
+    fn snapshot_code_is_verified_against_its_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut contract = ContractState::new("CA", compute_wasm_sha256(CODE));
+        contract.code = Some(hex::encode(CODE));
+        assert_eq!(snapshot_code(&contract, dir.path()).unwrap(), CODE);
+
+        contract.code = None;
+        std::fs::write(dir.path().join("deployed.wasm"), CODE).unwrap();
+        contract.set_wasm_ref("deployed.wasm");
+        assert_eq!(snapshot_code(&contract, dir.path()).unwrap(), CODE);
+
+        contract.wasm_hash = "aabbccdd".to_string();
+        let err = snapshot_code(&contract, dir.path()).unwrap_err();
+        assert!(err.to_string().contains("not its wasm_hash"), "{}", err);
+    }
+}
@@ -0,0 +1,334 @@
+//! Snapshot file formats
+//!
+//! v1 is the snapshot as pretty-printed JSON. v2 is a one-line JSON header
+//! followed by the body: the snapshot as compact JSON, zstd-compressed when
+//! the crate is built with the `zstd` feature. The header records what the
+//! body holds plus a SHA-256 checksum of it, so a truncated or hand-edited
+//! file is reported as corrupt instead of failing deep inside the JSON parser.
+
+use super::state::{NetworkSnapshot, SimulatorError};
+use crate::cli::args::SnapshotFormat;
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Value of the header's `format` field.
+pub const SNAPSHOT_MAGIC: &str = "soroban-debug-snapshot";
+
+/// Newest container version this build reads and writes.
+pub const SNAPSHOT_VERSION: u32 = 2;
+
+#[cfg(feature = "zstd")]
+const ZSTD_LEVEL: i32 = 3;
+
+/// How the body of a v2 snapshot is stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Zstd,
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::None => f.write_str("uncompressed"),
+            Compression::Zstd => f.write_str("zstd"),
+        }
+    }
+}
+
+/// First line of a v2 snapshot file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotHeader {
+    /// Always [`SNAPSHOT_MAGIC`].
+    pub format: String,
+    pub version: u32,
+    /// Network passphrase of the snapshot ledger.
+    pub network: String,
+    pub ledger_sequence: u32,
+    /// Accounts, contracts and contract storage entries.
+    pub entry_count: usize,
+    pub compression: Compression,
+    /// Size of the body in bytes, as stored.
+    pub body_len: u64,
+    /// Hex SHA-256 of the body, as stored.
+    pub checksum: String,
+}
+
+/// A snapshot read from disk; `header` is `None` for v1 files.
+#[derive(Debug, Clone)]
+pub struct SnapshotFile {
+    pub snapshot: NetworkSnapshot,
+    pub header: Option<SnapshotHeader>,
+}
+
+impl SnapshotFile {
+    pub fn format(&self) -> SnapshotFormat {
+        if self.header.is_some() {
+            SnapshotFormat::V2
+        } else {
+            SnapshotFormat::V1Json
+        }
+    }
+}
+
+/// Number of entries a snapshot holds, as recorded in the v2 header.
+pub fn entry_count(snapshot: &NetworkSnapshot) -> usize {
+    snapshot.accounts.len()
+        + snapshot
+            .contracts
+            .iter()
+            .map(|contract| 1 + contract.storage.len())
+            .sum::<usize>()
+}
+
+/// Serialize `snapshot` in the given format.
+pub fn encode(snapshot: &NetworkSnapshot, format: SnapshotFormat) -> Result<Vec<u8>> {
+    let serialize_error = |e: serde_json::Error| {
+        DebuggerError::FileError(format!("Failed to serialize snapshot: {}", e))
+    };
+
+    if format == SnapshotFormat::V1Json {
+        let json = serde_json::to_vec_pretty(snapshot).map_err(serialize_error)?;
+        return Ok(json);
+    }
+
+    let json = serde_json::to_vec(snapshot).map_err(serialize_error)?;
+    let (compression, body) = compress(json)?;
+    let header = SnapshotHeader {
+        format: SNAPSHOT_MAGIC.to_string(),
+        version: SNAPSHOT_VERSION,
+        network: snapshot.ledger.network_passphrase.clone(),
+        ledger_sequence: snapshot.ledger.sequence,
+        entry_count: entry_count(snapshot),
+        compression,
+        body_len: body.len() as u64,
+        checksum: checksum(&body),
+    };
+
+    let mut out = serde_json::to_vec(&header).map_err(serialize_error)?;
+    out.push(b'\n');
+    out.extend_from_slice(&body);
+    Ok(out)
+}
+
+/// Parse a snapshot file of either format, verifying the v2 checksum.
+pub fn decode(bytes: &[u8]) -> Result<SnapshotFile> {
+    let Some(header) = read_header(bytes)? else {
+        let snapshot = serde_json::from_slice(bytes).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to parse snapshot JSON: {}", e))
+        })?;
+        return Ok(SnapshotFile {
+            snapshot,
+            header: None,
+        });
+    };
+
+    let header_len = bytes
+        .iter()
+        .position(|b| *b == b'\n')
+        .unwrap_or(bytes.len());
+    let body = bytes.get(header_len + 1..).unwrap_or_default();
+    if body.len() as u64 != header.body_len {
+        return Err(corrupted(format!(
+            "body is {} bytes but the header records {} (file truncated?)",
+            body.len(),
+            header.body_len
+        )));
+    }
+    let actual = checksum(body);
+    if actual != header.checksum {
+        return Err(corrupted(format!(
+            "checksum mismatch (header {}, body {})",
+            header.checksum, actual
+        )));
+    }
+
+    let json = decompress(header.compression, body)?;
+    let snapshot: NetworkSnapshot = serde_json::from_slice(&json)
+        .map_err(|e| corrupted(format!("body is not a valid snapshot: {}", e)))?;
+    if snapshot.ledger.sequence != header.ledger_sequence
+        || snapshot.ledger.network_passphrase != header.network
+        || entry_count(&snapshot) != header.entry_count
+    {
+        return Err(corrupted(
+            "header does not describe the body (network, ledger sequence or entry count differ)"
+                .to_string(),
+        ));
+    }
+
+    Ok(SnapshotFile {
+        snapshot,
+        header: Some(header),
+    })
+}
+
+/// The v2 header, or `None` if `bytes` do not start with one (a v1 file).
+pub fn read_header(bytes: &[u8]) -> Result<Option<SnapshotHeader>> {
+    let first_line = bytes.split(|b| *b == b'\n').next().unwrap_or_default();
+    let Ok(header) = serde_json::from_slice::<SnapshotHeader>(first_line) else {
+        return Ok(None);
+    };
+    if header.format != SNAPSHOT_MAGIC {
+        return Ok(None);
+    }
+    if header.version > SNAPSHOT_VERSION {
+        return Err(DebuggerError::FileError(format!(
+            "Snapshot format version {} is newer than this debugger supports ({})",
+            header.version, SNAPSHOT_VERSION
+        ))
+        .into());
+    }
+    Ok(Some(header))
+}
+
+/// Read and decode a snapshot file of either format.
+pub fn read_file(path: &Path) -> Result<SnapshotFile> {
+    let bytes = fs::read(path).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to read snapshot file {:?}: {}", path, e))
+    })?;
+    decode(&bytes).map_err(|e| e.wrap_err(format!("Failed to load snapshot {:?}", path)))
+}
+
+/// Encode `snapshot` and write it to `path`, returning the number of bytes written.
+pub fn write_file(
+    snapshot: &NetworkSnapshot,
+    path: &Path,
+    format: SnapshotFormat,
+) -> Result<usize> {
+    let bytes = encode(snapshot, format)?;
+    fs::write(path, &bytes).map_err(|e| {
+        DebuggerError::FileError(format!("Failed to write snapshot file {:?}: {}", path, e))
+    })?;
+    Ok(bytes.len())
+}
+
+fn checksum(body: &[u8]) -> String {
+    hex::encode(Sha256::digest(body))
+}
+
+fn corrupted(reason: String) -> miette::Report {
+    SimulatorError::Corrupted(reason).into()
+}
+
+#[cfg(feature = "zstd")]
+fn compress(json: Vec<u8>) -> Result<(Compression, Vec<u8>)> {
+    let body = zstd::encode_all(json.as_slice(), ZSTD_LEVEL)
+        .map_err(|e| DebuggerError::FileError(format!("Failed to compress snapshot: {}", e)))?;
+    Ok((Compression::Zstd, body))
+}
+
+#[cfg(not(feature = "zstd"))]
+fn compress(json: Vec<u8>) -> Result<(Compression, Vec<u8>)> {
+    Ok((Compression::None, json))
+}
+
+fn decompress(compression: Compression, body: &[u8]) -> Result<Vec<u8>> {
+    match compression {
+        Compression::None => Ok(body.to_vec()),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => zstd::decode_all(body)
+            .map_err(|e| corrupted(format!("body does not decompress: {}", e))),
+        #[cfg(not(feature = "zstd"))]
+        Compression::Zstd => Err(DebuggerError::FileError(
+            "Snapshot body is zstd-compressed but this build lacks the `zstd` feature; \
+             rebuild with `--features zstd`"
+                .to_string(),
+        )
+        .into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::state::{AccountState, ContractState};
+
+    fn snapshot() -> NetworkSnapshot {
+        let mut snapshot = NetworkSnapshot::new(4242, "Test SDF Network ; September 2015", 1);
+        snapshot
+            .add_account(AccountState::new("GABCD123", "1000000", 7))
+            .unwrap();
+        let mut contract = ContractState::new("CCONTRACT1", "abcd1234");
+        contract
+            .storage
+            .insert("balance".to_string(), serde_json::json!(500));
+        snapshot.add_contract(contract).unwrap();
+        snapshot
+    }
+
+    #[test]
+    fn v2_round_trips_with_a_descriptive_header() {
+        let bytes = encode(&snapshot(), SnapshotFormat::V2).unwrap();
+        let file = decode(&bytes).unwrap();
+
+        let header = file.header.as_ref().unwrap();
+        assert_eq!(header.format, SNAPSHOT_MAGIC);
+        assert_eq!(header.version, SNAPSHOT_VERSION);
+        assert_eq!(header.network, "Test SDF Network ; September 2015");
+        assert_eq!(header.ledger_sequence, 4242);
+        assert_eq!(header.entry_count, 3);
+        assert_eq!(file.format(), SnapshotFormat::V2);
+        assert_eq!(
+            serde_json::to_value(&file.snapshot).unwrap(),
+            serde_json::to_value(snapshot()).unwrap()
+        );
+    }
+
+    #[test]
+    fn v1_json_is_still_read() {
+        let bytes = encode(&snapshot(), SnapshotFormat::V1Json).unwrap();
+        assert!(read_header(&bytes).unwrap().is_none());
+
+        let file = decode(&bytes).unwrap();
+        assert_eq!(file.format(), SnapshotFormat::V1Json);
+        assert_eq!(file.snapshot.contracts[0].storage["balance"], 500);
+    }
+
+    #[test]
+    fn flipped_body_byte_fails_the_checksum() {
+        let mut bytes = encode(&snapshot(), SnapshotFormat::V2).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+
+        let err = decode(&bytes).unwrap_err().to_string();
+        assert!(err.contains("corrupted"), "{}", err);
+        assert!(err.contains("checksum mismatch"), "{}", err);
+    }
+
+    #[test]
+    fn truncated_body_is_reported() {
+        let bytes = encode(&snapshot(), SnapshotFormat::V2).unwrap();
+        let err = decode(&bytes[..bytes.len() - 4]).unwrap_err().to_string();
+        assert!(err.contains("truncated"), "{}", err);
+    }
+
+    #[test]
+    fn edited_header_is_reported() {
+        let bytes = encode(&snapshot(), SnapshotFormat::V2).unwrap();
+        let split = bytes.iter().position(|b| *b == b'\n').unwrap();
+        let mut header: SnapshotHeader = serde_json::from_slice(&bytes[..split]).unwrap();
+        header.ledger_sequence += 1;
+        let mut edited = serde_json::to_vec(&header).unwrap();
+        edited.extend_from_slice(&bytes[split..]);
+
+        let err = decode(&edited).unwrap_err().to_string();
+        assert!(err.contains("header does not describe the body"), "{}", err);
+    }
+
+    #[test]
+    fn newer_versions_are_rejected() {
+        let bytes = encode(&snapshot(), SnapshotFormat::V2).unwrap();
+        let mut header = read_header(&bytes).unwrap().unwrap();
+        header.version = SNAPSHOT_VERSION + 1;
+        let line = serde_json::to_vec(&header).unwrap();
+        assert!(read_header(&line)
+            .unwrap_err()
+            .to_string()
+            .contains("newer"));
+    }
+}
@@ -5,7 +5,6 @@
 
 use super::state::{AccountState, ContractState, NetworkSnapshot};
 use crate::Result;
-use std::fs;
 use std::path::Path;
 use tracing::{debug, info};
 
@@ -15,23 +14,13 @@ pub struct SnapshotLoader {
 }
 
 impl SnapshotLoader {
-    /// Load a snapshot from a JSON file
+    /// Load a snapshot from a v1 JSON or v2 snapshot file
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let path = path.as_ref();
         info!("Loading network snapshot from: {:?}", path);
 
-        // Read the file
-        let contents = fs::read_to_string(path).map_err(|e| {
-            crate::DebuggerError::FileError(format!(
-                "Failed to read snapshot file {:?}: {}",
-                path, e
-            ))
-        })?;
-
-        // Parse JSON
-        let snapshot: NetworkSnapshot = serde_json::from_str(&contents).map_err(|e| {
-            crate::DebuggerError::FileError(format!("Failed to parse snapshot JSON: {}", e))
-        })?;
+        // Read the file, verifying the checksum of v2 snapshots
+        let snapshot = super::format::read_file(path)?.snapshot;
 
         // Validate the snapshot
         snapshot.validate()?;
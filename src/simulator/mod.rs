@@ -7,10 +7,14 @@
 //! - Pre-deploy contract instances with populated storage
 //! - Save and restore ledger state for iterative debugging
 
+pub mod code_pin;
+pub mod format;
 pub mod loader;
+pub mod patch;
 pub mod snapshot;
 pub mod state;
 
+pub use format::{SnapshotFile, SnapshotHeader};
 pub use loader::{LoadedSnapshot, SnapshotLoader};
 pub use snapshot::{AccountDiff, ContractDiff, SnapshotDiff, SnapshotManager};
 pub use state::{AccountState, ContractState, LedgerMetadata, NetworkSnapshot, SimulatorError};
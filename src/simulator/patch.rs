@@ -0,0 +1,582 @@
+//! Batch edits to snapshot storage (`snapshot patch`).
+//!
+//! A patch lists operations on contract storage entries selected by key
+//! pattern, in `--storage-filter` syntax. Because it names keys rather than
+//! values, one patch can be applied again to every refreshed snapshot, e.g.
+//! to drain a pool to 1% of whatever it currently holds.
+
+use crate::inspector::storage::FilterPattern;
+use crate::simulator::state::{NetworkSnapshot, SimulatorError};
+use crate::utils::ledger_key::parse_typed_val;
+use crate::{DebuggerError, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+
+/// The storage entries an operation applies to.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatchTarget {
+    /// Contract ID; every contract in the snapshot when omitted.
+    #[serde(default)]
+    pub contract: Option<String>,
+    /// Key pattern: an exact key, `prefix*` or `re:<regex>`.
+    pub key: String,
+    /// Matching no entries is not an error.
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// One patch operation, tagged by `op`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum PatchOperation {
+    /// Replace matching values. An exact key in a named contract is added
+    /// when missing.
+    Set {
+        #[serde(flatten)]
+        target: PatchTarget,
+        value: Value,
+    },
+    /// Remove matching entries.
+    Delete {
+        #[serde(flatten)]
+        target: PatchTarget,
+    },
+    /// Keep matching entries live at least `extend_to` ledgers past the
+    /// snapshot's ledger.
+    ExtendTtl {
+        #[serde(flatten)]
+        target: PatchTarget,
+        extend_to: u32,
+    },
+    /// Multiply matching integer values by `factor` (a number or decimal
+    /// string), rounding toward zero.
+    Scale {
+        #[serde(flatten)]
+        target: PatchTarget,
+        factor: Value,
+    },
+}
+
+impl PatchOperation {
+    pub fn target(&self) -> &PatchTarget {
+        match self {
+            PatchOperation::Set { target, .. }
+            | PatchOperation::Delete { target }
+            | PatchOperation::ExtendTtl { target, .. }
+            | PatchOperation::Scale { target, .. } => target,
+        }
+    }
+}
+
+impl fmt::Display for PatchOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = &self.target().key;
+        match self {
+            PatchOperation::Set { value, .. } => write!(f, "set {} = {}", key, value),
+            PatchOperation::Delete { .. } => write!(f, "delete {}", key),
+            PatchOperation::ExtendTtl { extend_to, .. } => {
+                write!(f, "extend-ttl {} by {} ledgers", key, extend_to)
+            }
+            PatchOperation::Scale { factor, .. } => {
+                let factor = match factor {
+                    Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                write!(f, "scale {} by {}", key, factor)
+            }
+        }?;
+        if let Some(contract) = &self.target().contract {
+            write!(f, " in {}", contract)?;
+        }
+        Ok(())
+    }
+}
+
+/// What one operation changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperationSummary {
+    pub operation: String,
+    /// `(contract_id, key)` of every entry the operation changed.
+    pub entries: Vec<(String, String)>,
+}
+
+/// An ordered list of patch operations.
+#[derive(Debug, Clone)]
+pub struct SnapshotPatch {
+    pub operations: Vec<PatchOperation>,
+}
+
+impl SnapshotPatch {
+    /// Parse `{"operations": [...]}` or a bare array of operations.
+    pub fn parse(json: &str) -> Result<Self> {
+        let root: Value = serde_json::from_str(json).map_err(|e| {
+            SimulatorError::ValidationError(format!("Patch is not valid JSON: {}", e))
+        })?;
+        let items = match root {
+            Value::Array(items) => items,
+            Value::Object(mut obj) => match obj.remove("operations") {
+                Some(Value::Array(items)) => items,
+                _ => {
+                    return Err(SimulatorError::ValidationError(
+                        "Patch must be an array of operations or {\"operations\": [...]}"
+                            .to_string(),
+                    )
+                    .into())
+                }
+            },
+            _ => {
+                return Err(SimulatorError::ValidationError(
+                    "Patch must be an array of operations or {\"operations\": [...]}".to_string(),
+                )
+                .into())
+            }
+        };
+        let operations = items
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let operation: PatchOperation = serde_json::from_value(item).map_err(|e| {
+                    SimulatorError::ValidationError(format!("Operation {}: {}", i + 1, e))
+                })?;
+                FilterPattern::parse(&operation.target().key).map_err(|e| {
+                    SimulatorError::ValidationError(format!("Operation {}: {}", i + 1, e))
+                })?;
+                if let PatchOperation::Scale { factor, .. } = &operation {
+                    parse_factor(factor).map_err(|e| {
+                        SimulatorError::ValidationError(format!("Operation {}: {}", i + 1, e))
+                    })?;
+                }
+                Ok(operation)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { operations })
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read patch file {:?}: {}", path, e))
+        })?;
+        Self::parse(&json).map_err(|e| e.wrap_err(format!("Invalid patch file {:?}", path)))
+    }
+
+    /// Apply the operations in order.
+    ///
+    /// Fails on the first operation that matches no entries (unless it is
+    /// optional) or would store an invalid value; `snapshot` may then be
+    /// partly patched and should be discarded.
+    pub fn apply(&self, snapshot: &mut NetworkSnapshot) -> Result<Vec<OperationSummary>> {
+        let env = soroban_sdk::Env::default();
+        let ledger = snapshot.ledger.sequence;
+        let mut summaries = Vec::with_capacity(self.operations.len());
+
+        for (i, operation) in self.operations.iter().enumerate() {
+            let fail = |message: String| -> miette::Report {
+                SimulatorError::ValidationError(format!(
+                    "Operation {} ({}): {}",
+                    i + 1,
+                    operation,
+                    message
+                ))
+                .into()
+            };
+            let target = operation.target();
+            let pattern = FilterPattern::parse(&target.key).map_err(fail)?;
+            let mut entries = Vec::new();
+
+            for contract in snapshot.contracts.iter_mut().filter(|contract| {
+                target
+                    .contract
+                    .as_ref()
+                    .is_none_or(|id| *id == contract.contract_id)
+            }) {
+                let mut keys: Vec<String> = contract
+                    .storage
+                    .keys()
+                    .filter(|key| pattern.matches(key))
+                    .cloned()
+                    .collect();
+                if let (PatchOperation::Set { .. }, Some(_), FilterPattern::Exact(key)) =
+                    (operation, &target.contract, &pattern)
+                {
+                    if keys.is_empty() {
+                        keys.push(key.clone());
+                    }
+                }
+
+                for key in keys {
+                    match operation {
+                        PatchOperation::Set { value, .. } => {
+                            check_value(&env, contract.storage.get(&key), value).map_err(|e| {
+                                fail(format!("{} in {}: {}", key, contract.contract_id, e))
+                            })?;
+                            contract.storage.insert(key.clone(), value.clone());
+                        }
+                        PatchOperation::Delete { .. } => {
+                            contract.storage.remove(&key);
+                            contract.live_until.remove(&key);
+                        }
+                        PatchOperation::ExtendTtl { extend_to, .. } => {
+                            let target = ledger.saturating_add(*extend_to);
+                            let live_until = contract.live_until.entry(key.clone()).or_default();
+                            *live_until = (*live_until).max(target);
+                        }
+                        PatchOperation::Scale { factor, .. } => {
+                            let factor = parse_factor(factor).map_err(fail)?;
+                            let value = contract.storage.get_mut(&key).expect("matched key");
+                            *value = scale_value(value, factor).map_err(|e| {
+                                fail(format!("{} in {}: {}", key, contract.contract_id, e))
+                            })?;
+                        }
+                    }
+                    entries.push((contract.contract_id.clone(), key));
+                }
+            }
+
+            if entries.is_empty() && !target.optional {
+                let scope = match &target.contract {
+                    Some(id) if snapshot.get_contract(id).is_none() => {
+                        format!("contract {} is not in the snapshot", id)
+                    }
+                    _ => "matched no entries; mark it \"optional\": true to allow this".to_string(),
+                };
+                return Err(fail(scope));
+            }
+            summaries.push(OperationSummary {
+                operation: operation.to_string(),
+                entries,
+            });
+        }
+        Ok(summaries)
+    }
+}
+
+/// Broad kind of a storage value: the annotated type of a typed value,
+/// otherwise its JSON shape, with numeric strings counting as numbers.
+fn value_kind(value: &Value) -> String {
+    match value {
+        Value::Object(obj) if obj.get("type").is_some() && obj.get("value").is_some() => {
+            obj["type"].as_str().unwrap_or("typed").to_string()
+        }
+        Value::Null => "void".to_string(),
+        Value::Bool(_) => "bool".to_string(),
+        Value::Number(_) => "number".to_string(),
+        Value::String(s) if parse_integer(s).is_some() => "number".to_string(),
+        Value::String(_) => "string".to_string(),
+        Value::Array(_) => "vec".to_string(),
+        Value::Object(_) => "map".to_string(),
+    }
+}
+
+/// Check that `new` parses as a contract value and keeps the kind of `old`.
+fn check_value(env: &soroban_sdk::Env, old: Option<&Value>, new: &Value) -> Result<()> {
+    if let Value::Number(n) = new {
+        if n.is_f64() {
+            return Err(SimulatorError::ValidationError(
+                "floating-point values are not supported".to_string(),
+            )
+            .into());
+        }
+    }
+    parse_typed_val(env, new)?;
+    if let Some(old) = old {
+        let (old_kind, new_kind) = (value_kind(old), value_kind(new));
+        if old_kind != new_kind {
+            return Err(SimulatorError::ValidationError(format!(
+                "would change a {} value into a {}",
+                old_kind, new_kind
+            ))
+            .into());
+        }
+    }
+    Ok(())
+}
+
+fn parse_integer(s: &str) -> Option<i128> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// An exact decimal scale factor, `numerator / denominator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Factor {
+    numerator: i128,
+    denominator: i128,
+}
+
+fn parse_factor(factor: &Value) -> std::result::Result<Factor, String> {
+    let text = match factor {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.trim().to_string(),
+        other => return Err(format!("factor must be a number, got {}", other)),
+    };
+    let invalid = || format!("invalid factor '{}': use a decimal such as 0.01", text);
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    if fraction.len() > 18 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let whole = if whole.is_empty() || whole == "-" {
+        format!("{}0", whole)
+    } else {
+        whole.to_string()
+    };
+    let numerator = parse_integer(&format!("{}{}", whole, fraction)).ok_or_else(invalid)?;
+    Ok(Factor {
+        numerator,
+        denominator: 10i128.pow(fraction.len() as u32),
+    })
+}
+
+/// Integer range of a typed value's annotated type.
+fn type_range(ty: &str) -> Option<(i128, i128)> {
+    Some(match ty {
+        "u32" => (0, u32::MAX as i128),
+        "i32" => (i32::MIN as i128, i32::MAX as i128),
+        "u64" => (0, u64::MAX as i128),
+        "i64" => (i64::MIN as i128, i64::MAX as i128),
+        "u128" => (0, i128::MAX),
+        "i128" => (i128::MIN, i128::MAX),
+        _ => return None,
+    })
+}
+
+/// Scale an integer value, keeping its representation: JSON number, numeric
+/// string or typed integer.
+fn scale_value(value: &Value, factor: Factor) -> std::result::Result<Value, String> {
+    let scale = |n: i128| {
+        n.checked_mul(factor.numerator)
+            .map(|n| n / factor.denominator)
+            .ok_or_else(|| format!("{} scaled by the factor overflows", n))
+    };
+    match value {
+        Value::Number(n) => {
+            let n = n
+                .as_i64()
+                .map(i128::from)
+                .or_else(|| n.as_u64().map(i128::from))
+                .ok_or_else(|| format!("{} is not an integer", n))?;
+            let scaled = scale(n)?;
+            if let Ok(v) = i64::try_from(scaled) {
+                Ok(Value::from(v))
+            } else if let Ok(v) = u64::try_from(scaled) {
+                Ok(Value::from(v))
+            } else {
+                Ok(Value::String(scaled.to_string()))
+            }
+        }
+        Value::String(s) => {
+            let n = parse_integer(s).ok_or_else(|| format!("'{}' is not an integer", s))?;
+            Ok(Value::String(scale(n)?.to_string()))
+        }
+        Value::Object(obj) if obj.get("type").is_some() && obj.get("value").is_some() => {
+            let ty = obj["type"].as_str().unwrap_or_default();
+            let (min, max) =
+                type_range(ty).ok_or_else(|| format!("a {} value cannot be scaled", ty))?;
+            let scaled = scale_value(&obj["value"], factor)?;
+            let n = match &scaled {
+                Value::Number(n) => n
+                    .as_i64()
+                    .map(i128::from)
+                    .or_else(|| n.as_u64().map(i128::from)),
+                Value::String(s) => parse_integer(s),
+                _ => None,
+            }
+            .ok_or_else(|| format!("{} is not an integer", obj["value"]))?;
+            if n < min || n > max {
+                return Err(format!("{} does not fit in {}", n, ty));
+            }
+            let mut obj = obj.clone();
+            obj.insert("value".to_string(), scaled);
+            Ok(Value::Object(obj))
+        }
+        other => Err(format!("{} is not an integer", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::ContractState;
+    use serde_json::json;
+
+    const POOL: &str = "CPOOL";
+    const TOKEN: &str = "CTOKEN";
+
+    fn snapshot() -> NetworkSnapshot {
+        let mut snapshot = NetworkSnapshot::new(1000, "Test SDF Network ; September 2015", 1);
+        let mut pool = ContractState::new(POOL, "aa");
+        pool.set_storage("reserve_a", json!("1000000000000"));
+        pool.set_storage("reserve_b", json!({"type": "i128", "value": "500"}));
+        pool.set_storage("fee_bps", json!(30));
+        pool.set_storage("admin", json!("GADMIN"));
+        snapshot.add_contract(pool).unwrap();
+        let mut token = ContractState::new(TOKEN, "bb");
+        token.set_storage("balance:alice", json!(70));
+        token.set_storage("balance:bob", json!(5));
+        snapshot.add_contract(token).unwrap();
+        snapshot
+    }
+
+    fn apply(snapshot: &mut NetworkSnapshot, patch: Value) -> Result<Vec<OperationSummary>> {
+        SnapshotPatch::parse(&patch.to_string())?.apply(snapshot)
+    }
+
+    #[test]
+    fn set_replaces_matching_values_and_adds_exact_keys() {
+        let mut snap = snapshot();
+        let summaries = apply(
+            &mut snap,
+            json!([
+                {"op": "set", "key": "balance:*", "value": 0},
+                {"op": "set", "contract": POOL, "key": "paused", "value": true},
+            ]),
+        )
+        .unwrap();
+        assert_eq!(summaries[0].entries.len(), 2);
+        assert_eq!(
+            snap.get_contract(TOKEN)
+                .unwrap()
+                .get_storage("balance:alice"),
+            Some(&json!(0))
+        );
+        assert_eq!(
+            snap.get_contract(POOL).unwrap().get_storage("paused"),
+            Some(&json!(true))
+        );
+    }
+
+    #[test]
+    fn set_rejects_values_of_another_kind() {
+        let mut snap = snapshot();
+        let err = apply(
+            &mut snap,
+            json!([{"op": "set", "key": "fee_bps", "value": "thirty"}]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Operation 1"), "{}", err);
+        assert!(apply(
+            &mut snapshot(),
+            json!([{"op": "set", "key": "fee_bps", "value": 1.5}])
+        )
+        .is_err());
+        assert!(apply(
+            &mut snapshot(),
+            json!([{"op": "set", "key": "reserve_b", "value": {"type": "u32", "value": 1}}])
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn delete_removes_entries_and_their_ttl() {
+        let mut snap = snapshot();
+        snap.get_contract_mut(TOKEN)
+            .unwrap()
+            .live_until
+            .insert("balance:bob".to_string(), 2000);
+        apply(
+            &mut snap,
+            json!({"operations": [{"op": "delete", "contract": TOKEN, "key": "re:^balance:b"}]}),
+        )
+        .unwrap();
+        let token = snap.get_contract(TOKEN).unwrap();
+        assert!(token.get_storage("balance:bob").is_none());
+        assert!(token.live_until.is_empty());
+        assert!(token.get_storage("balance:alice").is_some());
+    }
+
+    #[test]
+    fn extend_ttl_only_moves_expiry_forward() {
+        let mut snap = snapshot();
+        snap.get_contract_mut(TOKEN)
+            .unwrap()
+            .live_until
+            .insert("balance:bob".to_string(), 5000);
+        apply(
+            &mut snap,
+            json!([{"op": "extend-ttl", "contract": TOKEN, "key": "balance:*", "extend_to": 2000}]),
+        )
+        .unwrap();
+        let token = snap.get_contract(TOKEN).unwrap();
+        assert_eq!(token.live_until["balance:alice"], 3000);
+        assert_eq!(token.live_until["balance:bob"], 5000);
+    }
+
+    #[test]
+    fn scale_keeps_each_value_representation() {
+        let mut snap = snapshot();
+        let summaries = apply(
+            &mut snap,
+            json!([
+                {"op": "scale", "contract": POOL, "key": "reserve_*", "factor": "0.01"},
+                {"op": "scale", "key": "balance:*", "factor": 0.5},
+            ]),
+        )
+        .unwrap();
+        assert_eq!(summaries[0].entries.len(), 2);
+        let pool = snap.get_contract(POOL).unwrap();
+        assert_eq!(pool.get_storage("reserve_a"), Some(&json!("10000000000")));
+        assert_eq!(
+            pool.get_storage("reserve_b"),
+            Some(&json!({"type": "i128", "value": "5"}))
+        );
+        let token = snap.get_contract(TOKEN).unwrap();
+        assert_eq!(token.get_storage("balance:alice"), Some(&json!(35)));
+        assert_eq!(token.get_storage("balance:bob"), Some(&json!(2)));
+    }
+
+    #[test]
+    fn scale_rejects_non_integers_and_out_of_range_results() {
+        assert!(apply(
+            &mut snapshot(),
+            json!([{"op": "scale", "key": "admin", "factor": 2}])
+        )
+        .is_err());
+        let mut snap = snapshot();
+        snap.get_contract_mut(POOL)
+            .unwrap()
+            .set_storage("cap", json!({"type": "u32", "value": 4000000000u32}));
+        assert!(apply(
+            &mut snap,
+            json!([{"op": "scale", "key": "cap", "factor": 2}])
+        )
+        .is_err());
+        assert!(SnapshotPatch::parse(r#"[{"op": "scale", "key": "x", "factor": "1%"}]"#).is_err());
+    }
+
+    #[test]
+    fn operations_matching_nothing_fail_unless_optional() {
+        let err = apply(
+            &mut snapshot(),
+            json!([{"op": "delete", "key": "missing_*"}]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("matched no entries"), "{}", err);
+
+        let err = apply(
+            &mut snapshot(),
+            json!([{"op": "set", "contract": "CNOPE", "key": "x", "value": 1}]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not in the snapshot"), "{}", err);
+
+        let summaries = apply(
+            &mut snapshot(),
+            json!([{"op": "delete", "key": "missing_*", "optional": true}]),
+        )
+        .unwrap();
+        assert!(summaries[0].entries.is_empty());
+    }
+
+    #[test]
+    fn parse_reports_the_bad_operation() {
+        let err =
+            SnapshotPatch::parse(r#"[{"op": "delete", "key": "a"}, {"op": "rename", "key": "b"}]"#)
+                .unwrap_err();
+        assert!(err.to_string().contains("Operation 2"), "{}", err);
+        assert!(SnapshotPatch::parse(r#"{"ops": []}"#).is_err());
+    }
+}
@@ -4,9 +4,10 @@
 //! allowing users to capture the state of a ledger after debugging and
 //! restore it later for continued work.
 
+use super::format;
 use super::state::NetworkSnapshot;
+use crate::cli::args::SnapshotFormat;
 use crate::Result;
-use std::fs;
 use std::path::Path;
 use tracing::info;
 
@@ -14,49 +15,37 @@ use tracing::info;
 pub struct SnapshotManager;
 
 impl SnapshotManager {
-    /// Save a snapshot to a JSON file
+    /// Save a snapshot in the default (v2) format
     pub fn save<P: AsRef<Path>>(snapshot: &NetworkSnapshot, path: P) -> Result<()> {
+        Self::save_as(snapshot, path, SnapshotFormat::default())?;
+        Ok(())
+    }
+
+    /// Save a snapshot in the given format, returning the file size in bytes
+    pub fn save_as<P: AsRef<Path>>(
+        snapshot: &NetworkSnapshot,
+        path: P,
+        format: SnapshotFormat,
+    ) -> Result<usize> {
         let path = path.as_ref();
-        info!("Saving snapshot to: {:?}", path);
+        info!("Saving snapshot to: {:?} ({:?})", path, format);
 
         // Validate snapshot before saving
         snapshot.validate()?;
 
-        // Serialize to pretty JSON
-        let json = serde_json::to_string_pretty(snapshot).map_err(|e| {
-            crate::DebuggerError::FileError(format!("Failed to serialize snapshot: {}", e))
-        })?;
+        let written = format::write_file(snapshot, path, format)?;
 
-        // Write to file
-        fs::write(path, &json).map_err(|e| {
-            crate::DebuggerError::FileError(format!(
-                "Failed to write snapshot file {:?}: {}",
-                path, e
-            ))
-        })?;
+        info!("Snapshot saved successfully ({} bytes)", written);
 
-        info!("Snapshot saved successfully ({} bytes)", json.len());
-
-        Ok(())
+        Ok(written)
     }
 
-    /// Load a snapshot from a JSON file
+    /// Load a snapshot from a v1 JSON or v2 snapshot file
     pub fn load<P: AsRef<Path>>(path: P) -> Result<NetworkSnapshot> {
         let path = path.as_ref();
         info!("Loading snapshot from: {:?}", path);
 
-        // Read file
-        let contents = fs::read_to_string(path).map_err(|e| {
-            crate::DebuggerError::FileError(format!(
-                "Failed to read snapshot file {:?}: {}",
-                path, e
-            ))
-        })?;
-
-        // Parse JSON
-        let snapshot: NetworkSnapshot = serde_json::from_str(&contents).map_err(|e| {
-            crate::DebuggerError::FileError(format!("Failed to parse snapshot JSON: {}", e))
-        })?;
+        let snapshot = format::read_file(path)?.snapshot;
 
         // Validate loaded snapshot
         snapshot.validate()?;
@@ -27,6 +27,9 @@ pub enum SimulatorError {
     #[error("Snapshot validation failed: {0}")]
     ValidationError(String),
 
+    #[error("Snapshot is corrupted: {0}")]
+    Corrupted(String),
+
     #[error("Contract not found: {0}")]
     ContractNotFound(String),
 
@@ -55,6 +58,11 @@ pub struct NetworkSnapshot {
 
     /// Deployed contracts
     pub contracts: Vec<ContractState>,
+
+    /// Contracts each contract's instance storage refers to, recorded by
+    /// `snapshot fetch --follow-deps`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub dependencies: BTreeMap<String, Vec<String>>,
 }
 
 impl NetworkSnapshot {
@@ -68,6 +76,7 @@ impl NetworkSnapshot {
             },
             accounts: Vec::new(),
             contracts: Vec::new(),
+            dependencies: BTreeMap::new(),
         }
     }
 
@@ -287,9 +296,17 @@ pub struct ContractState {
     #[serde(default)]
     pub wasm_ref: Option<String>,
 
+    /// WASM bytecode, hex encoded, when the snapshot embeds the deployed code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+
     /// Contract instance storage (key-value pairs as JSON values)
     #[serde(default)]
     pub storage: BTreeMap<String, serde_json::Value>,
+
+    /// Ledger each storage entry lives until, for entries with a known TTL
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub live_until: BTreeMap<String, u32>,
 }
 
 impl ContractState {
@@ -299,7 +316,9 @@ impl ContractState {
             contract_id: contract_id.into(),
             wasm_hash: wasm_hash.into(),
             wasm_ref: None,
+            code: None,
             storage: BTreeMap::new(),
+            live_until: BTreeMap::new(),
         }
     }
 
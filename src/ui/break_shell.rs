@@ -0,0 +1,269 @@
+//! The prompt `run` stops at when a breakpoint fires.
+//!
+//! A function breakpoint stops before the call runs; write, event and host
+//! function breakpoints stop once it has returned. On a terminal the run
+//! waits at a `(break)` prompt with line editing and history, where the
+//! commands print what the run itself would: the storage listing, the
+//! event list, the budget footer and the call stack. Without a terminal, or
+//! with `--non-interactive`, each stop prints its summary and the run goes
+//! on, so CI logs show what was hit.
+
+use crate::debugger::engine::DebuggerEngine;
+use crate::inspector::budget::BudgetInspector;
+use crate::inspector::events::EventInspector;
+use crate::inspector::storage::{StorageFilter, StorageInspector};
+use crate::runtime::call_stack::CallStack;
+use crate::ui::formatter::Formatter;
+use crate::{DebuggerError, Result};
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+/// What the run does after a stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakAction {
+    Continue,
+    /// End the run, rolling back the storage of a call that already ran.
+    Abort,
+    /// Run the stopped call and step through the contract calls it makes,
+    /// into the next one or over the current one.
+    StepIn,
+    StepOver,
+}
+
+/// Whether the stopped call has run yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakStop {
+    /// At the entry of a call, before it runs.
+    Entry,
+    /// After the call returned or failed, or while stepping through it.
+    AfterCall,
+}
+
+/// Prompts at breakpoints, or prints them when not interactive.
+pub struct BreakShell {
+    editor: Option<DefaultEditor>,
+    /// The contract calls open at the current stop, if known.
+    stack: Option<CallStack>,
+}
+
+impl BreakShell {
+    /// A shell that prompts at each stop when `interactive`, and otherwise
+    /// prints it and lets the run go on.
+    pub fn new(interactive: bool) -> Result<Self> {
+        let editor = if interactive {
+            Some(DefaultEditor::new().map_err(|e| {
+                DebuggerError::FileError(format!("Failed to initialize breakpoint prompt: {}", e))
+            })?)
+        } else {
+            None
+        };
+        Ok(Self {
+            editor,
+            stack: None,
+        })
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    /// Stop at the breakpoint on the call the engine is about to run.
+    pub fn at_entry(&mut self, engine: &mut DebuggerEngine) -> Result<BreakAction> {
+        let (function, args) = match engine.state().lock() {
+            Ok(state) => (
+                state.current_function().unwrap_or("<unknown>").to_string(),
+                state.current_args().map(str::to_string),
+            ),
+            Err(_) => ("<unknown>".to_string(), None),
+        };
+        println!(
+            "{}",
+            Formatter::warning(format!("Breakpoint hit: {}", function))
+        );
+        print_args(engine);
+        self.stack = Some(engine.executor().entry_stack(&function, args.as_deref()));
+        print_stack(self.stack.as_ref(), engine);
+        self.prompt(engine, BreakStop::Entry)
+    }
+
+    /// Stop after a call whose write or event breakpoints have been
+    /// reported. `stack` then shows the calls open where the call failed, if
+    /// it did.
+    pub fn after_call(&mut self, engine: &mut DebuggerEngine) -> Result<BreakAction> {
+        let stack = engine.executor().call_stack();
+        self.stack = (!stack.is_empty()).then(|| stack.clone());
+        self.prompt(engine, BreakStop::AfterCall)
+    }
+
+    /// Take a `step-in` or `step-over` through the calls of the call that
+    /// just ran, then prompt again. Continues once no call is left.
+    pub fn step(&mut self, engine: &mut DebuggerEngine, into: bool) -> Result<BreakAction> {
+        let report = if into {
+            engine.step_into_call()
+        } else {
+            engine.step_over_call()
+        };
+        match report {
+            Some(report) => {
+                for line in report.lines() {
+                    println!("{}", Formatter::info(line));
+                }
+                let names = engine.executor().contract_names();
+                self.stack = engine.call_walk().and_then(|walk| {
+                    Some(CallStack::from_frames(&walk.stack(walk.current()?), &names))
+                });
+                self.prompt(engine, BreakStop::AfterCall)
+            }
+            None => {
+                println!(
+                    "{}",
+                    Formatter::info("Call finished, no calls left to step through")
+                );
+                Ok(BreakAction::Continue)
+            }
+        }
+    }
+
+    fn prompt(&mut self, engine: &mut DebuggerEngine, stop: BreakStop) -> Result<BreakAction> {
+        let Some(editor) = self.editor.as_mut() else {
+            return Ok(BreakAction::Continue);
+        };
+        println!(
+            "{}",
+            Formatter::info(
+                "Paused: storage [pattern], args, stack, events, budget, step, step-over, \
+                 continue, abort"
+            )
+        );
+        loop {
+            let line = match editor.readline("(break) ") {
+                Ok(line) => line,
+                // Ctrl-C and Ctrl-D leave the prompt the way `abort` and
+                // `continue` do.
+                Err(ReadlineError::Interrupted) => return Ok(BreakAction::Abort),
+                Err(ReadlineError::Eof) => return Ok(BreakAction::Continue),
+                Err(e) => {
+                    return Err(DebuggerError::FileError(format!(
+                        "Failed to read breakpoint command: {}",
+                        e
+                    ))
+                    .into())
+                }
+            };
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let Some(command) = parts.first() else {
+                continue;
+            };
+            let _ = editor.add_history_entry(line.as_str());
+            match *command {
+                "storage" => print_storage(engine, &parts[1..]),
+                "args" => print_args(engine),
+                "stack" => print_stack(self.stack.as_ref(), engine),
+                "events" => print_events(engine),
+                "budget" => println!(
+                    "{}",
+                    Formatter::info(BudgetInspector::format_footer(
+                        &engine.executor().budget_usage()
+                    ))
+                ),
+                "c" | "continue" => return Ok(BreakAction::Continue),
+                "abort" => return Ok(BreakAction::Abort),
+                "s" | "step" | "step-in" | "n" | "next" | "step-over" => {
+                    let into = matches!(*command, "s" | "step" | "step-in");
+                    match stop {
+                        BreakStop::Entry if into => return Ok(BreakAction::StepIn),
+                        BreakStop::Entry => return Ok(BreakAction::StepOver),
+                        BreakStop::AfterCall if engine.call_walk().is_none() => println!(
+                            "{}",
+                            Formatter::warning("No contract calls were recorded to step through")
+                        ),
+                        BreakStop::AfterCall => return self.step(engine, into),
+                    }
+                }
+                "help" => println!(
+                    "{}",
+                    Formatter::info(
+                        "storage [pattern]  storage entries, filtered as --storage-filter\n\
+                         args               arguments of the stopped call\n\
+                         stack              call stack\n\
+                         events             events emitted so far\n\
+                         budget             CPU and memory used so far\n\
+                         step | s           run to the entry of the next contract call\n\
+                         step-over | n      run the current contract call to its return\n\
+                         continue | c       resume the run\n\
+                         abort              end the run, rolling back a call that ran"
+                    )
+                ),
+                other => println!(
+                    "{}",
+                    Formatter::warning(format!(
+                        "Unknown command '{}'; type 'help' for the list",
+                        other
+                    ))
+                ),
+            }
+        }
+    }
+}
+
+/// The storage listing `run --storage-filter` prints, for `patterns`.
+fn print_storage(engine: &DebuggerEngine, patterns: &[&str]) {
+    let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+    match StorageFilter::new(&patterns) {
+        Ok(filter) => StorageInspector::with_entries(engine.executor().storage_entries())
+            .at_ledger(engine.executor().ledger_sequence())
+            .display_filtered(&filter),
+        Err(e) => println!(
+            "{}",
+            Formatter::warning(format!("Invalid storage filter: {}", e))
+        ),
+    }
+}
+
+fn print_args(engine: &DebuggerEngine) {
+    if let Ok(state) = engine.state().lock() {
+        println!(
+            "{}",
+            Formatter::info(format!(
+                "Arguments: {}",
+                state.current_args().unwrap_or("(none)")
+            ))
+        );
+    }
+}
+
+/// The calls open at the stop, innermost last, or the debugger's own call
+/// stack when they are not known.
+fn print_stack(stack: Option<&CallStack>, engine: &DebuggerEngine) {
+    match stack {
+        Some(stack) => {
+            println!("{}", Formatter::info("Call stack (innermost last):"));
+            for line in stack.render() {
+                println!("{}", Formatter::info(format!("  {}", line)));
+            }
+        }
+        None => {
+            if let Ok(state) = engine.state().lock() {
+                state.call_stack().display();
+            }
+        }
+    }
+}
+
+/// The event list `run --show events` prints.
+fn print_events(engine: &DebuggerEngine) {
+    match engine.executor().get_events() {
+        Ok(events) if events.is_empty() => {
+            println!("{}", Formatter::warning("No events captured."))
+        }
+        Ok(events) => {
+            for line in EventInspector::format_events(&events) {
+                println!("{}", Formatter::info(line));
+            }
+        }
+        Err(e) => println!(
+            "{}",
+            Formatter::warning(format!("Failed to read events: {}", e))
+        ),
+    }
+}
@@ -0,0 +1,316 @@
+//! Terminal front end for the storage browser.
+//!
+//! Translates crossterm key events into [`Key`]s for [`BrowserApp`] and draws
+//! its view models; all behaviour lives in the model.
+
+use super::model::{signature_line, Action, BrowserApp, Key, Mode, Pane, TreeRow};
+use crate::repl::executor::ReplExecutor;
+use crate::{DebuggerError, Result};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, CrosstermBackend},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame, Terminal,
+};
+use std::io;
+
+const COLOR_BORDER: Color = Color::Rgb(48, 64, 96);
+const COLOR_BORDER_ACTIVE: Color = Color::Rgb(99, 179, 237);
+const COLOR_TEXT: Color = Color::Rgb(220, 226, 240);
+const COLOR_TEXT_DIM: Color = Color::Rgb(100, 116, 140);
+const COLOR_ACCENT: Color = Color::Rgb(99, 179, 237);
+const COLOR_GREEN: Color = Color::Rgb(72, 199, 142);
+const COLOR_RED: Color = Color::Rgb(252, 87, 87);
+const COLOR_CYAN: Color = Color::Rgb(56, 210, 220);
+const COLOR_SELECTED: Color = Color::Rgb(30, 55, 55);
+
+fn terminal_error(what: &str, e: io::Error) -> miette::Report {
+    DebuggerError::FileError(format!("Failed to {}: {}", what, e)).into()
+}
+
+/// Run the browser until the user quits.
+pub(super) fn run(executor: ReplExecutor, app: BrowserApp) -> Result<()> {
+    if std::env::var_os("SOROBAN_DEBUG_TUI_SMOKE").is_some() {
+        let mut terminal = Terminal::new(ratatui::backend::TestBackend::new(120, 40))
+            .map_err(|e| terminal_error("create terminal", e))?;
+        terminal
+            .draw(|f| ui(f, &app))
+            .map_err(|e| terminal_error("draw terminal", e))?;
+        return Ok(());
+    }
+
+    enable_raw_mode().map_err(|e| terminal_error("enable raw mode", e))?;
+    let mut stdout = io::stdout();
+    execute!(stdout, EnterAlternateScreen)
+        .map_err(|e| terminal_error("execute terminal command", e))?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(stdout))
+        .map_err(|e| terminal_error("create terminal", e))?;
+
+    let res = run_app(&mut terminal, executor, app);
+
+    disable_raw_mode().map_err(|e| terminal_error("disable raw mode", e))?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)
+        .map_err(|e| terminal_error("execute terminal command", e))?;
+    terminal
+        .show_cursor()
+        .map_err(|e| terminal_error("show cursor", e))?;
+    res
+}
+
+fn run_app<B: Backend>(
+    terminal: &mut Terminal<B>,
+    mut executor: ReplExecutor,
+    mut app: BrowserApp,
+) -> Result<()> {
+    loop {
+        terminal
+            .draw(|f| ui(f, &app))
+            .map_err(|e| terminal_error("draw terminal", e))?;
+
+        let Event::Key(key) = event::read().map_err(|e| terminal_error("read event", e))? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Ok(());
+        }
+        let key = match key.code {
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::BackTab => Key::BackTab,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Backspace => Key::Backspace,
+            _ => continue,
+        };
+        match app.handle_key(key) {
+            Some(Action::Quit) => return Ok(()),
+            Some(Action::Invoke { function, args }) => {
+                super::invoke(&mut executor, &mut app, &function, args)?
+            }
+            None => {}
+        }
+    }
+}
+
+fn ui(f: &mut Frame, app: &BrowserApp) {
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(0), Constraint::Length(1)])
+        .split(f.size());
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+        .split(rows[0]);
+    let left = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(columns[0]);
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(columns[1]);
+
+    render_functions(f, app, left[0]);
+    render_form(f, app, left[1]);
+    render_storage(f, app, right[0]);
+    render_output(f, app, right[1]);
+    render_status(f, app, rows[1]);
+}
+
+fn pane_block(title: String, active: bool) -> Block<'static> {
+    let (border, title_style) = if active {
+        (
+            COLOR_BORDER_ACTIVE,
+            Style::default()
+                .fg(COLOR_ACCENT)
+                .add_modifier(Modifier::BOLD),
+        )
+    } else {
+        (COLOR_BORDER, Style::default().fg(COLOR_TEXT_DIM))
+    };
+    Block::default()
+        .title(Span::styled(title, title_style))
+        .borders(Borders::ALL)
+        .border_type(if active {
+            BorderType::Thick
+        } else {
+            BorderType::Rounded
+        })
+        .border_style(Style::default().fg(border))
+}
+
+fn render_functions(f: &mut Frame, app: &BrowserApp, area: Rect) {
+    let active = app.focus() == Pane::Functions && app.mode() == Mode::Normal;
+    let items: Vec<ListItem> = app
+        .functions
+        .functions()
+        .iter()
+        .map(|signature| {
+            ListItem::new(Span::styled(
+                signature_line(signature),
+                Style::default().fg(COLOR_TEXT),
+            ))
+        })
+        .collect();
+    let list = List::new(items)
+        .block(pane_block(
+            format!(" Functions ({}) ", app.functions.functions().len()),
+            active,
+        ))
+        .highlight_style(Style::default().bg(COLOR_SELECTED))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected(
+        app.functions
+            .selected()
+            .map(|_| app.functions.selected_index()),
+    );
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_form(f: &mut Frame, app: &BrowserApp, area: Rect) {
+    let Some(form) = &app.form else {
+        let hint = Paragraph::new(Line::from(Span::styled(
+            "Enter or i on a function to invoke it",
+            Style::default().fg(COLOR_TEXT_DIM),
+        )))
+        .block(pane_block(" Invoke ".to_string(), false));
+        f.render_widget(hint, area);
+        return;
+    };
+
+    let mut lines = Vec::new();
+    if form.params.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "(no arguments) Enter to call",
+            Style::default().fg(COLOR_TEXT_DIM),
+        )));
+    }
+    for (i, (param, value)) in form.params.iter().zip(&form.values).enumerate() {
+        let editing = i == form.field;
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!(
+                    "{}{}: {} = ",
+                    if editing { "▶ " } else { "  " },
+                    param.name,
+                    param.type_name
+                ),
+                Style::default().fg(COLOR_CYAN),
+            ),
+            Span::styled(
+                format!("{}{}", value, if editing { "▏" } else { "" }),
+                Style::default().fg(COLOR_TEXT),
+            ),
+        ]));
+    }
+    let paragraph = Paragraph::new(lines)
+        .block(pane_block(format!(" Invoke {} ", form.function), true))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn render_storage(f: &mut Frame, app: &BrowserApp, area: Rect) {
+    let tree = &app.storage;
+    let active = app.focus() == Pane::Storage;
+
+    let mut title = format!(" Storage ({} entries) ", tree.len());
+    if app.mode() == Mode::Filter || !tree.filter_text().is_empty() {
+        title.push_str(&format!("filter: {} ", tree.filter_text()));
+        if app.mode() == Mode::Filter {
+            title.push_str("▏ ");
+        }
+    }
+    if let Some(error) = tree.filter_error() {
+        title.push_str(&format!("({}) ", error));
+    }
+
+    let items: Vec<ListItem> = tree
+        .rows()
+        .into_iter()
+        .map(|row| match row {
+            TreeRow::Group {
+                name,
+                count,
+                collapsed,
+            } => ListItem::new(Span::styled(
+                format!("{} {} ({})", if collapsed { "▸" } else { "▾" }, name, count),
+                Style::default()
+                    .fg(COLOR_ACCENT)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            TreeRow::Entry { label, value, .. } => ListItem::new(Line::from(vec![
+                Span::styled(format!("  {}", label), Style::default().fg(COLOR_CYAN)),
+                Span::styled(" = ", Style::default().fg(COLOR_TEXT_DIM)),
+                Span::styled(value, Style::default().fg(COLOR_TEXT)),
+            ])),
+        })
+        .collect();
+    let empty = items.is_empty();
+    let list = List::new(items)
+        .block(pane_block(title, active))
+        .highlight_style(Style::default().bg(COLOR_SELECTED))
+        .highlight_symbol("▶ ");
+    let mut state = ListState::default().with_selected((!empty).then_some(tree.selected_index()));
+    f.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_output(f: &mut Frame, app: &BrowserApp, area: Rect) {
+    let active = app.focus() == Pane::Output;
+
+    let lines: Vec<Line> = match &app.output {
+        Some(output) => output
+            .lines()
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                let color = match (i, &output.result) {
+                    (0, Ok(_)) => COLOR_GREEN,
+                    (0, Err(_)) => COLOR_RED,
+                    _ => COLOR_TEXT,
+                };
+                Line::from(Span::styled(line, Style::default().fg(color)))
+            })
+            .collect(),
+        None => vec![Line::from(Span::styled(
+            "No calls yet",
+            Style::default().fg(COLOR_TEXT_DIM),
+        ))],
+    };
+    let paragraph = Paragraph::new(lines)
+        .block(pane_block(" Last call ".to_string(), active))
+        .scroll((app.output_scroll() as u16, 0))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+fn render_status(f: &mut Frame, app: &BrowserApp, area: Rect) {
+    let help = match app.mode() {
+        Mode::Normal => "Tab pane  ↑/↓ move  Enter open/invoke  / filter  q quit",
+        Mode::Filter => "type to filter  Enter keep  Esc clear",
+        Mode::Form => "type value  Tab next field  Enter call  Esc cancel",
+    };
+    f.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled(
+                format!(" {} ", app.contract),
+                Style::default()
+                    .fg(COLOR_ACCENT)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(help, Style::default().fg(COLOR_TEXT_DIM)),
+        ])),
+        area,
+    );
+}
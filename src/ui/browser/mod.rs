@@ -0,0 +1,114 @@
+//! Contract storage browser: `soroban-debug tui` without `--function`.
+//!
+//! Lists the contract's exported functions, shows its storage as a tree that
+//! can be filtered key by key, and invokes functions through a form, showing
+//! each call's result, events and trace. The state lives in [`model`]; the
+//! terminal front end is only built with the `tui` feature.
+
+#[cfg(feature = "tui")]
+mod draw;
+pub mod model;
+
+pub use model::BrowserApp;
+
+use crate::inspector::events::EventInspector;
+use crate::repl::executor::ReplExecutor;
+use crate::repl::ReplConfig;
+use crate::Result;
+use model::CallOutput;
+
+/// Open the browser on the contract and initial state described by `config`.
+#[cfg(feature = "tui")]
+pub fn run_browser(config: &ReplConfig) -> Result<()> {
+    let executor = ReplExecutor::new(config)?;
+    let app = BrowserApp::new(
+        config.contract_path.display().to_string(),
+        executor.signatures(),
+        executor.storage_snapshot()?,
+    );
+    draw::run(executor, app)
+}
+
+#[cfg(not(feature = "tui"))]
+pub fn run_browser(_config: &ReplConfig) -> Result<()> {
+    Err(crate::DebuggerError::InvalidArguments(
+        "The storage browser needs a terminal front end but this build lacks the `tui` \
+         feature; rebuild with `--features tui`, or pass --function for the dashboard"
+            .to_string(),
+    )
+    .into())
+}
+
+/// Call `function` and show its result, the events and calls it produced,
+/// and the storage it left behind.
+pub fn invoke(
+    executor: &mut ReplExecutor,
+    app: &mut BrowserApp,
+    function: &str,
+    args: Vec<String>,
+) -> Result<()> {
+    let events_before = executor.engine().executor().get_events()?.len();
+    let calls_before = executor
+        .engine()
+        .executor()
+        .debug_env()
+        .function_calls()
+        .len();
+
+    let result = executor.invoke(function, &args).map_err(|e| e.to_string());
+
+    let events = executor.engine().executor().get_events()?;
+    // A fresh host frame may have dropped earlier events; show them all then.
+    let start = if events.len() >= events_before {
+        events_before
+    } else {
+        0
+    };
+    let events = EventInspector::events_since(&events, start)
+        .iter()
+        .map(|event| {
+            format!(
+                "[{}] {} => {}",
+                event.contract_id.as_deref().unwrap_or("-"),
+                event.topics.join(", "),
+                event.data
+            )
+        })
+        .collect();
+
+    let trace = executor
+        .engine()
+        .executor()
+        .debug_env()
+        .function_calls()
+        .iter()
+        .skip(calls_before)
+        .map(|call| {
+            let outcome = match (&call.result, &call.error) {
+                (_, Some(error)) => format!("error: {}", error),
+                (Some(result), None) => result.clone(),
+                (None, None) => "(running)".to_string(),
+            };
+            format!(
+                "{}#{} {}({}) -> {}",
+                "  ".repeat(call.depth),
+                call.sequence,
+                call.callee,
+                call.arguments.join(", "),
+                outcome
+            )
+        })
+        .collect();
+
+    app.show_call(
+        CallOutput {
+            function: function.to_string(),
+            args,
+            result,
+            events,
+            trace,
+        },
+        executor.storage_snapshot()?,
+    );
+    Ok(())
+}
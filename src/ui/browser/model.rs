@@ -0,0 +1,772 @@
+//! View models for the storage browser.
+//!
+//! Everything here is plain state driven by [`BrowserApp::handle_key`], so the
+//! navigation, filtering and invocation flow can be tested without a terminal.
+//! Drawing lives in `draw.rs` and only reads these structs.
+
+use crate::inspector::StorageFilter;
+use crate::utils::wasm::{ContractFunctionSignature, FunctionParam};
+use std::collections::{BTreeSet, HashMap};
+
+/// A key press, independent of the terminal backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Esc,
+    Tab,
+    BackTab,
+    Up,
+    Down,
+    Backspace,
+}
+
+/// What the event loop should do after a key press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Call `function` with REPL-style arguments, then refresh storage.
+    Invoke {
+        function: String,
+        args: Vec<String>,
+    },
+    Quit,
+}
+
+/// The pane that receives navigation keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    Functions,
+    Storage,
+    Output,
+}
+
+impl Pane {
+    fn next(self) -> Self {
+        match self {
+            Pane::Functions => Pane::Storage,
+            Pane::Storage => Pane::Output,
+            Pane::Output => Pane::Functions,
+        }
+    }
+
+    fn prev(self) -> Self {
+        match self {
+            Pane::Functions => Pane::Output,
+            Pane::Storage => Pane::Functions,
+            Pane::Output => Pane::Storage,
+        }
+    }
+}
+
+/// Where typed characters go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    /// Editing the storage filter.
+    Filter,
+    /// Filling in the invocation form.
+    Form,
+}
+
+/// Exported functions from the contract spec, sorted by name.
+#[derive(Debug, Clone, Default)]
+pub struct FunctionList {
+    functions: Vec<ContractFunctionSignature>,
+    selected: usize,
+}
+
+impl FunctionList {
+    pub fn new(mut functions: Vec<ContractFunctionSignature>) -> Self {
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
+        Self {
+            functions,
+            selected: 0,
+        }
+    }
+
+    pub fn functions(&self) -> &[ContractFunctionSignature] {
+        &self.functions
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected(&self) -> Option<&ContractFunctionSignature> {
+        self.functions.get(self.selected)
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        self.selected = step(self.selected, delta, self.functions.len());
+    }
+}
+
+/// `name(a: T, b: U) -> R`, as listed in the functions pane.
+pub fn signature_line(signature: &ContractFunctionSignature) -> String {
+    let params: Vec<String> = signature
+        .params
+        .iter()
+        .map(|param| format!("{}: {}", param.name, param.type_name))
+        .collect();
+    match &signature.return_type {
+        Some(ret) => format!("{}({}) -> {}", signature.name, params.join(", "), ret),
+        None => format!("{}({})", signature.name, params.join(", ")),
+    }
+}
+
+/// One visible row of the storage tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeRow {
+    /// A durability group, e.g. `Persistent`, with the number of matching entries.
+    Group {
+        name: String,
+        count: usize,
+        collapsed: bool,
+    },
+    /// A storage entry; `label` is the key without its group prefix.
+    Entry {
+        key: String,
+        label: String,
+        value: String,
+    },
+}
+
+/// Contract storage grouped by durability, filtered as the user types.
+#[derive(Debug, Clone, Default)]
+pub struct StorageTree {
+    /// Entries sorted by key.
+    entries: Vec<(String, String)>,
+    filter_text: String,
+    filter: Option<StorageFilter>,
+    filter_error: Option<String>,
+    collapsed: BTreeSet<String>,
+    selected: usize,
+}
+
+impl StorageTree {
+    pub fn new(storage: HashMap<String, String>) -> Self {
+        let mut tree = Self::default();
+        tree.set_entries(storage);
+        tree
+    }
+
+    /// Replace the entries, keeping the filter, collapsed groups and, where
+    /// it is still visible, the selected key.
+    pub fn set_entries(&mut self, storage: HashMap<String, String>) {
+        let selected_key = self.selected_key();
+        self.entries = storage.into_iter().collect();
+        self.entries.sort();
+        self.reselect(selected_key.as_deref());
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn filter_text(&self) -> &str {
+        &self.filter_text
+    }
+
+    /// Why the current filter text could not be applied, if it could not.
+    /// The last filter that parsed stays in effect meanwhile.
+    pub fn filter_error(&self) -> Option<&str> {
+        self.filter_error.as_deref()
+    }
+
+    /// Apply `text` as the filter. Plain text matches anywhere in the key;
+    /// `prefix*` and `re:<regex>` behave as in `run --storage-filter`.
+    pub fn set_filter(&mut self, text: &str) {
+        let selected_key = self.selected_key();
+        self.filter_text = text.to_string();
+        match parse_filter(text) {
+            Ok(filter) => {
+                self.filter = filter;
+                self.filter_error = None;
+            }
+            Err(e) => self.filter_error = Some(e),
+        }
+        self.reselect(selected_key.as_deref());
+    }
+
+    /// The visible rows: each group header followed by its matching entries
+    /// unless the group is collapsed. Groups with no matches are hidden.
+    pub fn rows(&self) -> Vec<TreeRow> {
+        let mut groups: Vec<(&str, Vec<GroupEntry<'_>>)> = Vec::new();
+        for (key, value) in &self.entries {
+            let (group, label) = split_key(key);
+            let matches = self
+                .filter
+                .as_ref()
+                .is_none_or(|filter| filter.matches(key) || filter.matches(label));
+            if !matches {
+                continue;
+            }
+            match groups.iter_mut().find(|(name, _)| *name == group) {
+                Some((_, entries)) => entries.push((key, label, value)),
+                None => groups.push((group, vec![(key, label, value)])),
+            }
+        }
+        groups.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut rows = Vec::new();
+        for (group, entries) in groups {
+            let collapsed = self.collapsed.contains(group);
+            rows.push(TreeRow::Group {
+                name: group.to_string(),
+                count: entries.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(
+                    entries
+                        .into_iter()
+                        .map(|(key, label, value)| TreeRow::Entry {
+                            key: key.to_string(),
+                            label: label.to_string(),
+                            value: value.to_string(),
+                        }),
+                );
+            }
+        }
+        rows
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_row(&self) -> Option<TreeRow> {
+        self.rows().into_iter().nth(self.selected)
+    }
+
+    fn selected_key(&self) -> Option<String> {
+        match self.selected_row()? {
+            TreeRow::Entry { key, .. } => Some(key),
+            TreeRow::Group { .. } => None,
+        }
+    }
+
+    /// Collapse or expand the group under the cursor.
+    fn toggle_selected(&mut self) {
+        if let Some(TreeRow::Group { name, .. }) = self.selected_row() {
+            if !self.collapsed.remove(&name) {
+                self.collapsed.insert(name);
+            }
+        }
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        self.selected = step(self.selected, delta, self.rows().len());
+    }
+
+    /// Put the cursor back on `key` if it is still visible, otherwise keep
+    /// the position within the new row count.
+    fn reselect(&mut self, key: Option<&str>) {
+        let rows = self.rows();
+        let found = key.and_then(|key| {
+            rows.iter()
+                .position(|row| matches!(row, TreeRow::Entry { key: k, .. } if k == key))
+        });
+        self.selected = found.unwrap_or_else(|| self.selected.min(rows.len().saturating_sub(1)));
+    }
+}
+
+/// The full key, the label shown under its group and the value of an entry.
+type GroupEntry<'a> = (&'a str, &'a str, &'a str);
+
+/// Split `contract_data:Persistent:Symbol(..)` into its durability group and
+/// the rest of the key. Other keys fall into an `Other` group.
+fn split_key(key: &str) -> (&str, &str) {
+    key.strip_prefix("contract_data:")
+        .and_then(|rest| rest.split_once(':'))
+        .unwrap_or(("Other", key))
+}
+
+fn parse_filter(text: &str) -> Result<Option<StorageFilter>, String> {
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let pattern = if text.starts_with("re:") || text.ends_with('*') {
+        text.to_string()
+    } else {
+        format!("re:{}", regex::escape(text))
+    };
+    StorageFilter::new(&[pattern]).map(Some)
+}
+
+/// Result, events and trace of the most recent invocation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallOutput {
+    pub function: String,
+    pub args: Vec<String>,
+    /// The rendered return value, or the error message.
+    pub result: Result<String, String>,
+    pub events: Vec<String>,
+    pub trace: Vec<String>,
+}
+
+impl CallOutput {
+    /// The lines shown in the output pane.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![match &self.result {
+            Ok(value) => format!("{}({}) -> {}", self.function, self.args.join(", "), value),
+            Err(e) => format!("{}({}) failed: {}", self.function, self.args.join(", "), e),
+        }];
+        lines.push(String::new());
+        lines.push(format!("Events ({}):", self.events.len()));
+        lines.extend(self.events.iter().map(|event| format!("  {}", event)));
+        lines.push(String::new());
+        lines.push(format!("Trace ({}):", self.trace.len()));
+        lines.extend(self.trace.iter().map(|call| format!("  {}", call)));
+        lines
+    }
+}
+
+/// Arguments being entered for one function, one field per parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvokeForm {
+    pub function: String,
+    pub params: Vec<FunctionParam>,
+    pub values: Vec<String>,
+    /// Index of the field being edited.
+    pub field: usize,
+}
+
+impl InvokeForm {
+    pub fn new(signature: &ContractFunctionSignature) -> Self {
+        Self {
+            function: signature.name.clone(),
+            params: signature.params.clone(),
+            values: vec![String::new(); signature.params.len()],
+            field: 0,
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        if let Some(value) = self.values.get_mut(self.field) {
+            value.push(c);
+        }
+    }
+
+    fn pop(&mut self) {
+        if let Some(value) = self.values.get_mut(self.field) {
+            value.pop();
+        }
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        self.field = step(self.field, delta, self.values.len());
+    }
+
+    /// The action that runs the call. Fields are passed as typed at the REPL,
+    /// so `Address` parameters accept aliases.
+    fn submit(&self) -> Action {
+        Action::Invoke {
+            function: self.function.clone(),
+            args: self.values.iter().map(|v| v.trim().to_string()).collect(),
+        }
+    }
+}
+
+/// All state of the storage browser.
+#[derive(Debug, Clone)]
+pub struct BrowserApp {
+    pub contract: String,
+    pub functions: FunctionList,
+    pub storage: StorageTree,
+    pub output: Option<CallOutput>,
+    pub form: Option<InvokeForm>,
+    output_scroll: usize,
+    focus: Pane,
+    mode: Mode,
+}
+
+impl BrowserApp {
+    pub fn new(
+        contract: impl Into<String>,
+        functions: Vec<ContractFunctionSignature>,
+        storage: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            contract: contract.into(),
+            functions: FunctionList::new(functions),
+            storage: StorageTree::new(storage),
+            output: None,
+            form: None,
+            output_scroll: 0,
+            focus: Pane::Functions,
+            mode: Mode::Normal,
+        }
+    }
+
+    pub fn focus(&self) -> Pane {
+        self.focus
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn output_scroll(&self) -> usize {
+        self.output_scroll
+    }
+
+    /// Show the outcome of an invocation and the storage it left behind.
+    pub fn show_call(&mut self, output: CallOutput, storage: HashMap<String, String>) {
+        self.output = Some(output);
+        self.output_scroll = 0;
+        self.storage.set_entries(storage);
+    }
+
+    pub fn handle_key(&mut self, key: Key) -> Option<Action> {
+        match self.mode {
+            Mode::Normal => self.handle_normal(key),
+            Mode::Filter => {
+                self.handle_filter(key);
+                None
+            }
+            Mode::Form => self.handle_form(key),
+        }
+    }
+
+    fn handle_normal(&mut self, key: Key) -> Option<Action> {
+        match key {
+            Key::Char('q') => return Some(Action::Quit),
+            Key::Tab => self.focus = self.focus.next(),
+            Key::BackTab => self.focus = self.focus.prev(),
+            Key::Char('/') => {
+                self.focus = Pane::Storage;
+                self.mode = Mode::Filter;
+            }
+            Key::Char('i') => self.open_form(),
+            Key::Up | Key::Char('k') => self.move_by(-1),
+            Key::Down | Key::Char('j') => self.move_by(1),
+            Key::Enter => match self.focus {
+                Pane::Functions => self.open_form(),
+                Pane::Storage => self.storage.toggle_selected(),
+                Pane::Output => {}
+            },
+            Key::Esc if self.focus == Pane::Storage => self.storage.set_filter(""),
+            _ => {}
+        }
+        None
+    }
+
+    fn handle_filter(&mut self, key: Key) {
+        match key {
+            Key::Char(c) => {
+                let text = format!("{}{}", self.storage.filter_text(), c);
+                self.storage.set_filter(&text);
+            }
+            Key::Backspace => {
+                let mut text = self.storage.filter_text().to_string();
+                text.pop();
+                self.storage.set_filter(&text);
+            }
+            Key::Enter => self.mode = Mode::Normal,
+            Key::Esc => {
+                self.storage.set_filter("");
+                self.mode = Mode::Normal;
+            }
+            Key::Up => self.storage.move_by(-1),
+            Key::Down => self.storage.move_by(1),
+            _ => {}
+        }
+    }
+
+    fn handle_form(&mut self, key: Key) -> Option<Action> {
+        let form = self.form.as_mut()?;
+        match key {
+            Key::Char(c) => form.push(c),
+            Key::Backspace => form.pop(),
+            Key::Tab | Key::Down => form.move_by(1),
+            Key::BackTab | Key::Up => form.move_by(-1),
+            Key::Esc => self.close_form(),
+            Key::Enter => {
+                let action = form.submit();
+                self.close_form();
+                return Some(action);
+            }
+        }
+        None
+    }
+
+    fn open_form(&mut self) {
+        if let Some(signature) = self.functions.selected() {
+            self.form = Some(InvokeForm::new(signature));
+            self.focus = Pane::Functions;
+            self.mode = Mode::Form;
+        }
+    }
+
+    fn close_form(&mut self) {
+        self.form = None;
+        self.mode = Mode::Normal;
+    }
+
+    fn move_by(&mut self, delta: isize) {
+        match self.focus {
+            Pane::Functions => self.functions.move_by(delta),
+            Pane::Storage => self.storage.move_by(delta),
+            Pane::Output => {
+                let len = self.output.as_ref().map_or(0, |o| o.lines().len());
+                self.output_scroll = step(self.output_scroll, delta, len);
+            }
+        }
+    }
+}
+
+/// Move `index` by `delta`, staying within `0..len`.
+fn step(index: usize, delta: isize, len: usize) -> usize {
+    if len == 0 {
+        return 0;
+    }
+    index.saturating_add_signed(delta).min(len - 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(name: &str, params: &[(&str, &str)]) -> ContractFunctionSignature {
+        ContractFunctionSignature {
+            name: name.to_string(),
+            params: params
+                .iter()
+                .map(|(name, type_name)| FunctionParam {
+                    name: name.to_string(),
+                    type_name: type_name.to_string(),
+                })
+                .collect(),
+            return_type: Some("u32".to_string()),
+        }
+    }
+
+    fn storage() -> HashMap<String, String> {
+        HashMap::from([
+            (
+                "contract_data:Persistent:Symbol(balance_alice)".to_string(),
+                "I128(100)".to_string(),
+            ),
+            (
+                "contract_data:Persistent:Symbol(balance_bob)".to_string(),
+                "I128(5)".to_string(),
+            ),
+            (
+                "contract_data:Instance:Symbol(admin)".to_string(),
+                "Address(GADMIN)".to_string(),
+            ),
+        ])
+    }
+
+    fn app() -> BrowserApp {
+        BrowserApp::new(
+            "token.wasm",
+            vec![
+                signature("transfer", &[("to", "Address"), ("amount", "i128")]),
+                signature("balance", &[("id", "Address")]),
+            ],
+            storage(),
+        )
+    }
+
+    fn type_text(app: &mut BrowserApp, text: &str) {
+        for c in text.chars() {
+            app.handle_key(Key::Char(c));
+        }
+    }
+
+    fn entry_labels(tree: &StorageTree) -> Vec<String> {
+        tree.rows()
+            .into_iter()
+            .filter_map(|row| match row {
+                TreeRow::Entry { label, .. } => Some(label),
+                TreeRow::Group { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn storage_is_grouped_by_durability() {
+        let tree = StorageTree::new(storage());
+        let rows = tree.rows();
+        assert_eq!(
+            rows[0],
+            TreeRow::Group {
+                name: "Instance".to_string(),
+                count: 1,
+                collapsed: false
+            }
+        );
+        assert_eq!(
+            rows[2],
+            TreeRow::Group {
+                name: "Persistent".to_string(),
+                count: 2,
+                collapsed: false
+            }
+        );
+        assert_eq!(
+            entry_labels(&tree),
+            vec![
+                "Symbol(admin)",
+                "Symbol(balance_alice)",
+                "Symbol(balance_bob)"
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_narrows_the_tree_as_keys_are_typed() {
+        let mut app = app();
+        app.handle_key(Key::Char('/'));
+        assert_eq!(app.mode(), Mode::Filter);
+        assert_eq!(app.focus(), Pane::Storage);
+
+        type_text(&mut app, "bal");
+        assert_eq!(entry_labels(&app.storage).len(), 2);
+        type_text(&mut app, "ance_b");
+        assert_eq!(entry_labels(&app.storage), vec!["Symbol(balance_bob)"]);
+
+        app.handle_key(Key::Backspace);
+        assert_eq!(app.storage.filter_text(), "balance_");
+        assert_eq!(entry_labels(&app.storage).len(), 2);
+
+        app.handle_key(Key::Esc);
+        assert_eq!(app.mode(), Mode::Normal);
+        assert_eq!(entry_labels(&app.storage).len(), 3);
+    }
+
+    #[test]
+    fn filter_accepts_storage_filter_syntax() {
+        let mut tree = StorageTree::new(storage());
+        tree.set_filter("re:alice|admin");
+        assert_eq!(entry_labels(&tree).len(), 2);
+        tree.set_filter("contract_data:Instance:*");
+        assert_eq!(entry_labels(&tree), vec!["Symbol(admin)"]);
+    }
+
+    #[test]
+    fn incomplete_regex_keeps_the_last_filter() {
+        let mut tree = StorageTree::new(storage());
+        tree.set_filter("re:bob");
+        tree.set_filter("re:bob(");
+        assert!(tree.filter_error().is_some());
+        assert_eq!(entry_labels(&tree), vec!["Symbol(balance_bob)"]);
+        tree.set_filter("re:bob()");
+        assert!(tree.filter_error().is_none());
+    }
+
+    #[test]
+    fn groups_collapse_and_expand() {
+        let mut app = app();
+        app.handle_key(Key::Tab);
+        assert_eq!(app.focus(), Pane::Storage);
+        app.handle_key(Key::Enter);
+        assert_eq!(
+            app.storage.rows()[0],
+            TreeRow::Group {
+                name: "Instance".to_string(),
+                count: 1,
+                collapsed: true
+            }
+        );
+        assert_eq!(app.storage.rows().len(), 4);
+        app.handle_key(Key::Enter);
+        assert_eq!(app.storage.rows().len(), 5);
+    }
+
+    #[test]
+    fn selection_follows_the_key_across_refreshes() {
+        let mut tree = StorageTree::new(storage());
+        tree.move_by(3);
+        assert!(matches!(
+            tree.selected_row(),
+            Some(TreeRow::Entry { ref label, .. }) if label == "Symbol(balance_alice)"
+        ));
+
+        let mut refreshed = storage();
+        refreshed.insert(
+            "contract_data:Instance:Symbol(paused)".to_string(),
+            "Bool(false)".to_string(),
+        );
+        tree.set_entries(refreshed);
+        assert!(matches!(
+            tree.selected_row(),
+            Some(TreeRow::Entry { ref label, .. }) if label == "Symbol(balance_alice)"
+        ));
+
+        tree.set_entries(HashMap::new());
+        assert_eq!(tree.selected_index(), 0);
+        assert!(tree.selected_row().is_none());
+    }
+
+    #[test]
+    fn invoke_form_collects_one_value_per_parameter() {
+        let mut app = app();
+        assert_eq!(app.functions.selected().unwrap().name, "balance");
+        app.handle_key(Key::Down);
+        app.handle_key(Key::Enter);
+        assert_eq!(app.mode(), Mode::Form);
+        assert_eq!(app.form.as_ref().unwrap().function, "transfer");
+
+        type_text(&mut app, "alice");
+        app.handle_key(Key::Tab);
+        type_text(&mut app, "50");
+        assert_eq!(
+            app.handle_key(Key::Enter),
+            Some(Action::Invoke {
+                function: "transfer".to_string(),
+                args: vec!["alice".to_string(), "50".to_string()],
+            })
+        );
+        assert_eq!(app.mode(), Mode::Normal);
+        assert!(app.form.is_none());
+    }
+
+    #[test]
+    fn typing_in_a_form_does_not_quit() {
+        let mut app = app();
+        app.handle_key(Key::Char('i'));
+        type_text(&mut app, "q");
+        assert_eq!(app.form.as_ref().unwrap().values, vec!["q".to_string()]);
+        app.handle_key(Key::Esc);
+        assert!(app.form.is_none());
+        assert_eq!(app.handle_key(Key::Char('q')), Some(Action::Quit));
+    }
+
+    #[test]
+    fn call_output_lists_result_events_and_trace() {
+        let mut app = app();
+        app.show_call(
+            CallOutput {
+                function: "transfer".to_string(),
+                args: vec!["alice".to_string(), "50".to_string()],
+                result: Ok("Void".to_string()),
+                events: vec!["[-] Symbol(transfer) => I128(50)".to_string()],
+                trace: vec!["#0 transfer(alice, 50) -> Void".to_string()],
+            },
+            HashMap::new(),
+        );
+        let lines = app.output.as_ref().unwrap().lines();
+        assert_eq!(lines[0], "transfer(alice, 50) -> Void");
+        assert!(lines.contains(&"Events (1):".to_string()));
+        assert!(lines.contains(&"  #0 transfer(alice, 50) -> Void".to_string()));
+        assert!(app.storage.is_empty());
+    }
+
+    #[test]
+    fn signature_lines_show_parameter_types() {
+        assert_eq!(
+            signature_line(&signature(
+                "transfer",
+                &[("to", "Address"), ("amount", "i128")]
+            )),
+            "transfer(to: Address, amount: i128) -> u32"
+        );
+    }
+}
@@ -183,6 +183,11 @@ impl Formatter {
         Self::configure_colors(!no_color);
     }
 
+    /// Whether ANSI colors are enabled, see [`Self::configure_colors`].
+    pub fn colors_enabled() -> bool {
+        COLOR_ENABLED.load(Ordering::Relaxed)
+    }
+
     /// Set the global verbosity level (0 = Quiet, 1 = Normal, 2 = Verbose).
     pub fn set_verbosity(level: u8) {
         VERBOSITY_LEVEL.store(level, Ordering::Relaxed);
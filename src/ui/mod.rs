@@ -1,7 +1,10 @@
+pub mod break_shell;
+pub mod browser;
 pub mod dashboard;
 pub mod formatter;
 pub mod tui;
 
+pub use browser::run_browser;
 pub use dashboard::run_dashboard;
 pub use formatter::Formatter;
 pub use tui::DebuggerUI;
@@ -1,5 +1,8 @@
+use crate::debugger::breakpoint::{EventBreakpoints, WriteBreakpoints};
 use crate::debugger::engine::DebuggerEngine;
+use crate::debugger::host_fn::HostFnWatch;
 use crate::inspector::{BudgetInspector, StorageInspector};
+use crate::runtime::result::StorageSnapshot;
 use crate::Result;
 use std::io::{self, Write};
 
@@ -14,6 +17,9 @@ pub struct DebuggerUI {
     engine: DebuggerEngine,
     storage_inspector: StorageInspector,
     pending_execution: Option<PendingExecution>,
+    /// Storage from before a call that stopped on a write or event
+    /// breakpoint or is being stepped through, restored by `abort`.
+    rollback: Option<StorageSnapshot>,
     last_output: Option<String>,
     last_error: Option<String>,
 }
@@ -24,6 +30,7 @@ impl DebuggerUI {
             engine,
             storage_inspector: StorageInspector::new(),
             pending_execution: None,
+            rollback: None,
             last_output: None,
             last_error: None,
         })
@@ -35,6 +42,7 @@ impl DebuggerUI {
     pub fn queue_execution(&mut self, function: String, args: Option<String>) {
         self.engine.stage_execution(&function, args.as_deref());
         self.pending_execution = Some(PendingExecution { function, args });
+        self.rollback = None;
         self.last_output = None;
         self.last_error = None;
     }
@@ -97,32 +105,31 @@ impl DebuggerUI {
             }
             "c" | "continue" => {
                 if let Some(pending) = self.pending_execution.take() {
-                    match self
-                        .engine
-                        .execute_without_breakpoints(&pending.function, pending.args.as_deref())
-                    {
-                        Ok(output) => {
-                            self.last_error = None;
-                            self.last_output = Some(output.clone());
-                            crate::logging::log_display(
-                                format!("Result: {}", output),
-                                crate::logging::LogLevel::Info,
-                            );
-                        }
-                        Err(e) => {
-                            self.last_output = None;
-                            self.last_error = Some(e.to_string());
-                            crate::logging::log_display(
-                                format!("Error: {}", e),
-                                crate::logging::LogLevel::Error,
-                            );
-                        }
-                    }
+                    self.run_pending(pending, false);
                 } else {
+                    let walking = self.engine.call_walk().is_some();
+                    self.rollback = None;
                     self.engine.continue_execution()?;
                     tracing::info!("Execution continuing");
+                    if walking {
+                        self.show_result();
+                    }
                 }
             }
+            "step-in" => self.step_call(true),
+            "step-over" => self.step_call(false),
+            "abort" => match self.rollback.take() {
+                Some(snapshot) => {
+                    self.engine.executor_mut().restore_storage(&snapshot)?;
+                    self.engine.continue_execution()?;
+                    self.last_output = None;
+                    crate::logging::log_display(
+                        "Aborted: storage rolled back to before the call",
+                        crate::logging::LogLevel::Info,
+                    );
+                }
+                None => tracing::warn!("Nothing to abort: no call is stopped at a breakpoint"),
+            },
             "i" | "inspect" => {
                 self.inspect();
             }
@@ -199,6 +206,128 @@ impl DebuggerUI {
         Ok(false)
     }
 
+    /// Run a staged call. Without `walking` its result is shown right away,
+    /// and a write or event breakpoint leaves the session paused in the call
+    /// that hit it, for `inspect` and `stack`, until `continue` keeps the
+    /// call or `abort` undoes it. Watched host function calls are only
+    /// reported. While `walking` the result waits for the end of the walk.
+    fn run_pending(&mut self, pending: PendingExecution, walking: bool) {
+        let before = self.engine.executor().snapshot_storage().ok();
+        match self
+            .engine
+            .execute_without_breakpoints(&pending.function, pending.args.as_deref())
+        {
+            Ok(output) => {
+                self.last_error = None;
+                self.last_output = Some(output);
+            }
+            Err(e) => {
+                self.last_output = None;
+                self.last_error = Some(e.to_string());
+            }
+        }
+        if !walking {
+            self.show_result();
+        }
+        let names = self.engine.executor().contract_names();
+        let reports: Vec<String> = self
+            .engine
+            .write_hits()
+            .iter()
+            .map(|mutation| WriteBreakpoints::describe(mutation, &names))
+            .chain(
+                self.engine
+                    .event_hits()
+                    .iter()
+                    .map(EventBreakpoints::describe),
+            )
+            .collect();
+        for report in &reports {
+            crate::logging::log_display(report, crate::logging::LogLevel::Warn);
+        }
+        for call in self.engine.host_fn_calls() {
+            crate::logging::log_display(
+                HostFnWatch::describe(call),
+                crate::logging::LogLevel::Info,
+            );
+        }
+        if !reports.is_empty() || walking {
+            self.rollback = before;
+        }
+        if !reports.is_empty() && !walking {
+            crate::logging::log_display(
+                "Paused: 'continue' keeps the call's effects, 'abort' rolls back its storage",
+                crate::logging::LogLevel::Info,
+            );
+        }
+    }
+
+    fn show_result(&self) {
+        if let Some(output) = &self.last_output {
+            crate::logging::log_display(
+                format!("Result: {}", output),
+                crate::logging::LogLevel::Info,
+            );
+        } else if let Some(error) = &self.last_error {
+            crate::logging::log_display(
+                format!("Error: {}", error),
+                crate::logging::LogLevel::Error,
+            );
+            let stack = self.engine.executor().call_stack();
+            if !stack.is_empty() {
+                crate::logging::log_display(
+                    "Call stack (innermost last):",
+                    crate::logging::LogLevel::Info,
+                );
+                for line in stack.render() {
+                    crate::logging::log_display(
+                        format!("  {}", line),
+                        crate::logging::LogLevel::Info,
+                    );
+                }
+            }
+        }
+    }
+
+    /// `step-in` / `step-over` through the contract calls of the staged
+    /// call, which runs first, or of the call being stepped through.
+    fn step_call(&mut self, into: bool) {
+        if let Some(pending) = self.pending_execution.take() {
+            self.engine.walk_next_calls();
+            self.run_pending(pending, true);
+            if self.engine.call_walk().is_none() {
+                crate::logging::log_display(
+                    "No contract calls were recorded to step through",
+                    crate::logging::LogLevel::Warn,
+                );
+                self.show_result();
+                return;
+            }
+        } else if self.engine.call_walk().is_none() {
+            tracing::warn!("Nothing to step: no call is paused at a breakpoint");
+            return;
+        }
+        let report = if into {
+            self.engine.step_into_call()
+        } else {
+            self.engine.step_over_call()
+        };
+        match report {
+            Some(report) => {
+                crate::logging::log_display(report, crate::logging::LogLevel::Info);
+            }
+            None => {
+                // The walk is over and the call kept.
+                self.rollback = None;
+                crate::logging::log_display(
+                    "Call finished, no calls left to step through",
+                    crate::logging::LogLevel::Info,
+                );
+                self.show_result();
+            }
+        }
+    }
+
     fn inspect(&self) {
         crate::logging::log_display("\n=== Current State ===", crate::logging::LogLevel::Info);
         if let Ok(state) = self.engine.state().lock() {
@@ -249,6 +378,18 @@ impl DebuggerUI {
             "  continue | c       Continue execution",
             crate::logging::LogLevel::Info,
         );
+        crate::logging::log_display(
+            "  step-in            Run to the entry of the next contract call",
+            crate::logging::LogLevel::Info,
+        );
+        crate::logging::log_display(
+            "  step-over          Run the current contract call to its return",
+            crate::logging::LogLevel::Info,
+        );
+        crate::logging::log_display(
+            "  abort              Roll back a call stopped on a write or event",
+            crate::logging::LogLevel::Info,
+        );
         crate::logging::log_display(
             "  inspect | i        Show current state",
             crate::logging::LogLevel::Info,
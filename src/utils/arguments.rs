@@ -18,20 +18,48 @@
 //! | `i64`    | `{"type": "i64", "value": -999}`         | Signed 64-bit integer          |
 //! | `u128`   | `{"type": "u128", "value": 100}`         | Unsigned 128-bit integer       |
 //! | `i128`   | `{"type": "i128", "value": 100}`         | Signed 128-bit integer         |
+//! | `u256`   | `{"type": "u256", "value": "0x1f"}`      | Unsigned 256-bit integer, decimal or `0x` hex |
+//! | `i256`   | `{"type": "i256", "value": "-5"}`        | Signed 256-bit integer, decimal or `0x` hex |
 //! | `bool`   | `{"type": "bool", "value": true}`        | Boolean                        |
 //! | `symbol` | `{"type": "symbol", "value": "hello"}`   | Soroban Symbol (≤32 chars)     |
 //! | `string` | `{"type": "string", "value": "long..."}`  | Soroban String (any length)    |
+//! | `option` | `{"type": "option", "value": null}`      | `None` (Void), or `Some(value)` |
+//! | `address` | `{"type": "address", "value": "GAAA...WHF"}` | Account (`G...`) or contract (`C...`) strkey |
+//! | `contract` | `{"type": "contract", "value": "token"}` | Address of a contract linked with `--link` |
+//! | `map`    | `{"type": "map", "value": {"a": 1}}`     | Map with symbol keys, or `[[key, value], ...]` for any keys |
+//! | `bytes`  | `{"type": "bytes", "value": "0xdeadbeef"}` | Hex, with or without `0x`; base64 after `base64:` or with `"encoding": "base64"` |
+//! | `bytes32` | `{"type": "bytes32", "value": "<64 hex digits>"}` | `BytesN<32>`; `bytesn` with `"length"` for other sizes |
+//! | `xdr`    | `{"type": "xdr", "value": "AAAAAwAAACo="}` | Any value as base64 `ScVal` XDR, as RPC returns it |
+//! | `timepoint` | `{"type": "timepoint", "value": "2025-01-01T00:00:00Z"}` | Unix seconds, or an RFC 3339 date |
+//! | `duration` | `{"type": "duration", "value": "1d12h"}` | Seconds, or a span of `w`, `d`, `h`, `m` and `s` |
+//!
+//! `option` wraps any other value, typed or bare, e.g.
+//! `{"type": "option", "value": {"type": "u32", "value": 7}}`. Soroban encodes
+//! `None` as `Void` and `Some(x)` as `x` itself, so [`scval_to_json`] renders
+//! options back as `null` or the inner value.
+//!
+//! With the contract's types from [`ArgumentParser::with_types`], a struct,
+//! union or enum the spec declares is a type too. Structs take their fields
+//! by name (tuple structs an array), union cases are `"Case"` or
+//! `{"Case": value}`, and integer enums a case name or value:
+//! `{"type": "Asset", "value": {"Stellar": "G..."}}`. Fields and case values
+//! are converted as the spec declares them.
 //!
 //! Bare values (without type annotation) still work:
 //! - Numbers → `i128`
 //! - Strings → `Symbol`
 //! - Booleans → `Bool`
 
+use crate::utils::time::{parse_duration, parse_timepoint};
+use crate::utils::wasm::TypeDefinition;
 use hex;
 use serde_json::Value;
+use soroban_env_host::xdr::{Duration, Limits, ReadXdr, ScVal, TimePoint, WriteXdr};
 use soroban_sdk::{
-    Address, Env, Map, String as SorobanString, Symbol, TryFromVal, Val, Vec as SorobanVec,
+    Address, Env, Map, String as SorobanString, Symbol, TryFromVal, Val, Vec as SorobanVec, I256,
+    U256,
 };
+use std::collections::BTreeMap;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use thiserror::Error;
 use tracing::{debug, warn};
@@ -42,7 +70,7 @@ pub enum ArgumentParseError {
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
 
-    #[error("Unsupported type: {0}. Supported types: u32, i32, u64, u128, i128, bool, string, symbol, address, option, tuple, vec, bytes, bytesn")]
+    #[error("Unsupported type: {0}. Supported types: u32, i32, u64, u128, i128, u256, i256, bool, string, symbol, address, contract, option, tuple, vec, map, bytes, bytes32, bytesn, xdr, timepoint, duration")]
     UnsupportedType(String),
 
     #[error("Failed to convert value: {0}")]
@@ -64,22 +92,119 @@ pub enum ArgumentParseError {
         min: String,
         max: String,
     },
+
+    /// An error inside a nested value, with the path leading to it from the
+    /// argument list, e.g. `args[2].value.entries[1]`.
+    #[error("{path}: {source}")]
+    At {
+        path: String,
+        source: Box<ArgumentParseError>,
+    },
+}
+
+impl ArgumentParseError {
+    /// Place this error at `segment` (`[1]`, `.value`, `.amount`) of the
+    /// value that contains it.
+    pub fn within(self, segment: &str) -> Self {
+        match self {
+            Self::At { path, source } => Self::At {
+                path: format!("{}{}", segment, path),
+                source,
+            },
+            other => Self::At {
+                path: segment.to_string(),
+                source: Box::new(other),
+            },
+        }
+    }
 }
+
 /// Argument parser for converting JSON to Soroban values
 pub struct ArgumentParser {
     env: Env,
+    /// Contracts `{"type": "contract"}` arguments may name.
+    contracts: BTreeMap<String, Address>,
+    /// User-defined types from the contract spec, by name.
+    types: BTreeMap<String, TypeDefinition>,
 }
 
 impl ArgumentParser {
     /// Create a new argument parser with the given Soroban environment
     pub fn new(env: Env) -> Self {
-        Self { env }
+        Self {
+            env,
+            contracts: BTreeMap::new(),
+            types: BTreeMap::new(),
+        }
+    }
+
+    /// Resolve `{"type": "contract", "value": NAME}` through `contracts`.
+    pub fn with_contracts(mut self, contracts: BTreeMap<String, Address>) -> Self {
+        self.contracts = contracts;
+        self
+    }
+
+    /// Accept the contract's structs, unions and enums as annotation types,
+    /// e.g. `{"type": "Order", "value": {"price": "100", "seller": "G..."}}`.
+    pub fn with_types(mut self, types: impl IntoIterator<Item = TypeDefinition>) -> Self {
+        self.types = types
+            .into_iter()
+            .map(|definition| (definition.name().to_string(), definition))
+            .collect();
+        self
     }
 
     fn looks_like_strkey_address(s: &str) -> bool {
         s.len() == 56 && (s.starts_with('G') || s.starts_with('C'))
     }
 
+    /// Why `s` does not decode as an account (`G...`) or contract (`C...`)
+    /// strkey, or `None` when it does.
+    fn strkey_error(s: &str) -> Option<String> {
+        const BASE32: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        if s.len() != 56 {
+            return Some(format!("expected 56 characters, got {}", s.len()));
+        }
+        if !s.starts_with('G') && !s.starts_with('C') {
+            return Some(format!(
+                "starts with '{}'; account addresses start with G and contract addresses with C",
+                s.chars().next().unwrap_or_default()
+            ));
+        }
+        // 56 base32 characters are 35 bytes: version, 32-byte key, CRC16.
+        let mut bytes = Vec::with_capacity(35);
+        let (mut bits, mut len) = (0u32, 0);
+        for (i, c) in s.bytes().enumerate() {
+            let Some(digit) = BASE32.iter().position(|&b| b == c) else {
+                return Some(format!(
+                    "'{}' at position {} is not a base32 character",
+                    c as char,
+                    i + 1
+                ));
+            };
+            bits = (bits << 5) | digit as u32;
+            len += 5;
+            if len >= 8 {
+                len -= 8;
+                bytes.push((bits >> len) as u8);
+            }
+        }
+        let (data, crc) = bytes.split_at(33);
+        let expected = data.iter().fold(0u16, |crc, &byte| {
+            (0..8).fold(crc ^ (u16::from(byte) << 8), |crc, _| {
+                if crc & 0x8000 != 0 {
+                    (crc << 1) ^ 0x1021
+                } else {
+                    crc << 1
+                }
+            })
+        });
+        if crc != expected.to_le_bytes() {
+            return Some("checksum does not match; a character is probably mistyped".to_string());
+        }
+        None
+    }
+
     /// Parse a JSON string into Soroban argument values
     ///
     /// Supports:
@@ -125,8 +250,9 @@ impl ArgumentParser {
                         self.json_to_soroban_val(v).map_err(|e| {
                             warn!("Failed to parse array element {}: {}", i, e);
                             ArgumentParseError::ConversionError(format!(
-                                "Array element {}: {}",
-                                i, e
+                                "Array element {} at {}",
+                                i,
+                                e.within(&format!("args[{}]", i))
                             ))
                         })
                     })
@@ -136,11 +262,15 @@ impl ArgumentParser {
                 // Check if this is a type-annotated value
                 if self.is_typed_annotation(value) {
                     debug!("Parsing type-annotated single value");
-                    let val = self.parse_typed_value(value)?;
+                    let val = self
+                        .parse_typed_value(value)
+                        .map_err(|e| e.within("args"))?;
                     Ok(vec![val])
                 } else {
                     debug!("Parsing object as single Map argument");
-                    let map_val = self.json_to_soroban_val(value)?;
+                    let map_val = self
+                        .json_to_soroban_val(value)
+                        .map_err(|e| e.within("args"))?;
                     Ok(vec![map_val])
                 }
             }
@@ -159,15 +289,16 @@ impl ArgumentParser {
             }
 
             let type_name = obj["type"].as_str().unwrap_or_default();
-            let allowed_extra = match type_name {
-                "tuple" => Some("arity"),
-                "vec" => Some("element_type"),
-                "bytesn" => Some("length"),
-                _ => None,
+            let allowed_extra: &[&str] = match type_name {
+                "tuple" => &["arity"],
+                "vec" => &["element_type"],
+                "bytes" | "bytes32" => &["encoding"],
+                "bytesn" => &["length", "encoding"],
+                _ => &[],
             };
 
             obj.keys()
-                .all(|k| k == "type" || k == "value" || Some(k.as_str()) == allowed_extra)
+                .all(|k| k == "type" || k == "value" || allowed_extra.contains(&k.as_str()))
         } else {
             false
         }
@@ -183,8 +314,18 @@ impl ArgumentParser {
             ArgumentParseError::InvalidArgument("Type field must be a string".to_string())
         })?;
 
-        let val = &obj["value"];
+        self.convert_as(type_name, &obj["value"], obj)
+            .map_err(|e| e.within(".value"))
+    }
 
+    /// Convert `val` as `type_name`, with the annotation's other fields in
+    /// `obj`.
+    fn convert_as(
+        &self,
+        type_name: &str,
+        val: &Value,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Val, ArgumentParseError> {
         match type_name {
             "u32" => self.convert_u32(val),
             "i32" => self.convert_i32(val),
@@ -192,16 +333,38 @@ impl ArgumentParser {
             "i64" => self.convert_i64(val),
             "u128" => self.convert_u128(val),
             "i128" => self.convert_i128(val),
+            "u256" => self.convert_int256(val, false),
+            "i256" => self.convert_int256(val, true),
             "bool" => self.convert_bool(val),
             "string" => self.convert_string(val),
             "symbol" => self.convert_symbol(val),
             "address" => self.convert_address(val),
+            "contract" => self.convert_contract(val),
             "option" => self.convert_option(val),
             "tuple" => self.convert_tuple(val, obj),
             "vec" => self.convert_vec(val, obj),
-            "bytes" => self.convert_bytes(val),
-            "bytesn" => self.convert_bytesn(val, obj),
-            other => Err(ArgumentParseError::UnsupportedType(other.to_string())),
+            "map" => self.convert_map(val),
+            "bytes" => self.convert_bytes(val, obj),
+            "bytesn" => {
+                let length = obj.get("length").and_then(|l| l.as_u64()).ok_or_else(|| {
+                    ArgumentParseError::InvalidArgument(
+                        "BytesN requires a 'length' field".to_string(),
+                    )
+                })?;
+                self.convert_bytesn(val, obj, length as usize)
+            }
+            "bytes32" => self.convert_bytesn(val, obj, 32),
+            "xdr" => self.convert_xdr(val),
+            "timepoint" | "duration" => self.convert_time(val, type_name),
+            other => match self.types.get(other) {
+                Some(definition) => self.convert_user_type(definition, val),
+                None if !self.types.is_empty() => Err(ArgumentParseError::InvalidArgument(format!(
+                    "Unknown type '{}'; built-in types are lowercase (u32, address, ...) and the contract spec defines {}",
+                    other,
+                    self.types.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))),
+                None => Err(ArgumentParseError::UnsupportedType(other.to_string())),
+            },
         }
     }
 
@@ -279,34 +442,170 @@ impl ArgumentParser {
         })
     }
 
-    /// Convert a JSON number to u128 Val
+    /// Convert a JSON number (or decimal string, for values beyond u64) to u128 Val
     fn convert_u128(&self, value: &Value) -> Result<Val, ArgumentParseError> {
         let n = value
             .as_u64()
+            .map(u128::from)
+            .or_else(|| value.as_str().and_then(|s| s.parse::<u128>().ok()))
             .ok_or_else(|| ArgumentParseError::TypeMismatch {
                 expected: "u128 (non-negative integer)".to_string(),
                 actual: format!("{}", value),
             })?;
 
-        Val::try_from_val(&self.env, &(n as u128)).map_err(|e| {
+        Val::try_from_val(&self.env, &n).map_err(|e| {
             ArgumentParseError::ConversionError(format!("Failed to convert u128 to Val: {:?}", e))
         })
     }
 
-    /// Convert a JSON number to i128 Val
+    /// Convert a JSON number (or decimal string, for values beyond i64) to i128 Val
     fn convert_i128(&self, value: &Value) -> Result<Val, ArgumentParseError> {
         let n = value
             .as_i64()
+            .map(i128::from)
+            .or_else(|| value.as_str().and_then(|s| s.parse::<i128>().ok()))
             .ok_or_else(|| ArgumentParseError::TypeMismatch {
                 expected: "i128 (integer)".to_string(),
                 actual: format!("{}", value),
             })?;
 
-        Val::try_from_val(&self.env, &(n as i128)).map_err(|e| {
+        Val::try_from_val(&self.env, &n).map_err(|e| {
             ArgumentParseError::ConversionError(format!("Failed to convert i128 to Val: {:?}", e))
         })
     }
 
+    /// Convert a JSON integer, or a decimal or `0x` hex string, to a U256 or
+    /// (when `signed`) I256 Val
+    fn convert_int256(&self, value: &Value, signed: bool) -> Result<Val, ArgumentParseError> {
+        let [hi_hi, hi_lo, lo_hi, lo_lo] = int256_limbs(value, signed)?;
+        let val = if signed {
+            Val::try_from_val(
+                &self.env,
+                &I256::from_parts(&self.env, hi_hi as i64, hi_lo, lo_hi, lo_lo),
+            )
+        } else {
+            Val::try_from_val(
+                &self.env,
+                &U256::from_parts(&self.env, hi_hi, hi_lo, lo_hi, lo_lo),
+            )
+        };
+        val.map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert 256-bit integer to Val: {:?}",
+                e
+            ))
+        })
+    }
+
+    /// Convert base64 `ScVal` XDR to the Val it encodes
+    fn convert_xdr(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let b64 = value
+            .as_str()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: "base64 ScVal XDR string".to_string(),
+                actual: format!("{}", value),
+            })?;
+        let scval = ScVal::from_xdr_base64(b64.trim(), Limits::none()).map_err(|e| {
+            ArgumentParseError::InvalidArgument(format!(
+                "Invalid ScVal XDR '{}': {}",
+                xdr_prefix(b64),
+                e
+            ))
+        })?;
+        Val::try_from_val(self.env.host(), &scval).map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert ScVal XDR '{}' to Val: {:?}",
+                xdr_prefix(b64),
+                e
+            ))
+        })
+    }
+
+    /// Convert seconds, or the text forms [`parse_timepoint`] and
+    /// [`parse_duration`] read, to a Timepoint or Duration Val. `type_name`
+    /// is `timepoint` or `duration`.
+    fn convert_time(&self, value: &Value, type_name: &str) -> Result<Val, ArgumentParseError> {
+        let out_of_range = |value: String| ArgumentParseError::OutOfRange {
+            type_name: type_name.to_string(),
+            value,
+            min: "0".to_string(),
+            max: u64::MAX.to_string(),
+        };
+        let mismatch = || ArgumentParseError::TypeMismatch {
+            expected: match type_name {
+                "timepoint" => "timepoint (Unix seconds or an RFC 3339 date)",
+                _ => "duration (seconds or a span like 1d12h)",
+            }
+            .to_string(),
+            actual: value.to_string(),
+        };
+        let seconds = match value {
+            Value::Number(n) => match n.as_u64() {
+                Some(seconds) => seconds,
+                // Negative, or too large for serde_json to keep as an integer.
+                None if n.as_i64().is_some() || n.as_f64().is_some_and(|f| f.fract() == 0.0) => {
+                    return Err(out_of_range(n.to_string()))
+                }
+                None => return Err(mismatch()),
+            },
+            Value::String(text) => {
+                let text = text.trim();
+                let digits = text.strip_prefix('-').unwrap_or(text);
+                if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+                    text.parse::<u64>()
+                        .map_err(|_| out_of_range(text.to_string()))?
+                } else if type_name == "timepoint" {
+                    parse_timepoint(text).map_err(ArgumentParseError::InvalidArgument)?
+                } else {
+                    parse_duration(text).map_err(ArgumentParseError::InvalidArgument)?
+                }
+            }
+            _ => return Err(mismatch()),
+        };
+        let scval = match type_name {
+            "timepoint" => ScVal::Timepoint(TimePoint(seconds)),
+            _ => ScVal::Duration(Duration(seconds)),
+        };
+        Val::try_from_val(self.env.host(), &scval).map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert {} to Val: {:?}",
+                type_name, e
+            ))
+        })
+    }
+
+    /// The `--args-xdr` argument list, base64 XDR of an `ScVec`, as `--args`
+    /// JSON of one `xdr` annotation per argument.
+    pub fn xdr_args_to_json(b64: &str) -> Result<String, ArgumentParseError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            ArgumentParseError::InvalidArgument(format!(
+                "Invalid --args-xdr '{}': {}",
+                xdr_prefix(b64),
+                e
+            ))
+        };
+        let args = match ScVal::from_xdr_base64(b64.trim(), Limits::none()) {
+            Ok(ScVal::Vec(Some(args))) => args,
+            Ok(ScVal::Vec(None)) => Default::default(),
+            Ok(other) => {
+                return Err(invalid(&format!(
+                    "expected an ScVec of arguments, got {:?}",
+                    other.discriminant()
+                )))
+            }
+            Err(e) => return Err(invalid(&e)),
+        };
+        let annotations = args
+            .iter()
+            .map(|arg| {
+                arg.to_xdr_base64(Limits::none())
+                    .map(|value| serde_json::json!({"type": "xdr", "value": value}))
+                    .map_err(|e| invalid(&e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Value::Array(annotations).to_string())
+    }
+
     /// Convert a JSON boolean to Bool Val
     fn convert_bool(&self, value: &Value) -> Result<Val, ArgumentParseError> {
         let b = value
@@ -385,24 +684,22 @@ impl ArgumentParser {
         let mut soroban_vec = SorobanVec::<Val>::new(&self.env);
 
         for (i, item) in arr.iter().enumerate() {
-            let val = if let Some(et) = element_type {
-                // Force each element to be of the specified type
-                let mut typed_item = serde_json::Map::new();
-                typed_item.insert("type".to_string(), Value::String(et.to_string()));
-                typed_item.insert("value".to_string(), item.clone());
-
-                // If it's nested vec, we might need to handle element_type for it too.
-                // For now, let's just pass the whole typed_item back to parse_typed_value.
-                self.parse_typed_value(&Value::Object(typed_item))
-                    .map_err(|e| {
-                        ArgumentParseError::ConversionError(format!(
-                            "Vector element {} does not match element_type '{}': {}",
-                            i, et, e
-                        ))
-                    })?
-            } else {
-                self.json_to_soroban_val(item)?
-            };
+            let val = match element_type {
+                // Each element is converted as if annotated with the type.
+                Some(et) => {
+                    self.convert_as(et, item, &serde_json::Map::new())
+                        .map_err(|e| match e {
+                            // A nested element already names where it failed.
+                            ArgumentParseError::At { .. } => e,
+                            e => ArgumentParseError::ConversionError(format!(
+                                "does not match element_type '{}': {}",
+                                et, e
+                            )),
+                        })
+                }
+                None => self.json_to_soroban_val(item),
+            }
+            .map_err(|e| e.within(&format!("[{}]", i)))?;
             soroban_vec.push_back(val);
         }
 
@@ -438,43 +735,69 @@ impl ArgumentParser {
 
         let mut soroban_vec = SorobanVec::<Val>::new(&self.env);
         for (i, item) in arr.iter().enumerate() {
-            let val = self.json_to_soroban_val(item).map_err(|e| {
-                ArgumentParseError::ConversionError(format!(
-                    "Cannot convert tuple element {} to Soroban value: {}",
-                    i, e
-                ))
-            })?;
+            let val = self
+                .json_to_soroban_val(item)
+                .map_err(|e| e.within(&format!("[{}]", i)))?;
             soroban_vec.push_back(val);
         }
 
         Ok(soroban_vec.into())
     }
 
-    fn decode_bytes_string(&self, s: &str) -> Result<Vec<u8>, ArgumentParseError> {
-        if let Some(hex_part) = s.strip_prefix("0x") {
-            hex::decode(hex_part).map_err(|e| {
+    /// Decode the `value` of a `bytes`, `bytes32` or `bytesn` annotation:
+    /// hex with or without `0x`, or base64 after `base64:` or with
+    /// `"encoding": "base64"`.
+    fn decode_bytes_string(
+        &self,
+        s: &str,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Vec<u8>, ArgumentParseError> {
+        use base64::{engine::general_purpose, Engine};
+        let decode_base64 = |b64: &str| {
+            general_purpose::STANDARD.decode(b64).map_err(|e| {
+                ArgumentParseError::InvalidArgument(format!("Invalid base64 string: {}", e))
+            })
+        };
+        let decode_hex = |hex: &str| {
+            hex::decode(hex).map_err(|e| {
                 ArgumentParseError::InvalidArgument(format!("Invalid hex string: {}", e))
             })
+        };
+        match obj.get("encoding").and_then(Value::as_str) {
+            Some("base64") => return decode_base64(s.strip_prefix("base64:").unwrap_or(s)),
+            Some("hex") | None => {}
+            Some(other) => {
+                return Err(ArgumentParseError::InvalidArgument(format!(
+                    "Unknown bytes encoding '{}'; use hex or base64",
+                    other
+                )))
+            }
+        }
+        if let Some(hex_part) = s.strip_prefix("0x") {
+            decode_hex(hex_part)
         } else if let Some(b64_part) = s.strip_prefix("base64:") {
-            use base64::{engine::general_purpose, Engine};
-            general_purpose::STANDARD.decode(b64_part).map_err(|e| {
-                ArgumentParseError::InvalidArgument(format!("Invalid base64 string: {}", e))
-            })
+            decode_base64(b64_part)
+        } else if s.chars().all(|c| c.is_ascii_hexdigit()) {
+            decode_hex(s)
         } else {
             Err(ArgumentParseError::InvalidArgument(
-                "Bytes must start with '0x' or 'base64:'".to_string(),
+                "Bytes must start with '0x' or 'base64:', or be bare hex digits".to_string(),
             ))
         }
     }
 
-    fn convert_bytes(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+    fn convert_bytes(
+        &self,
+        value: &Value,
+        obj: &serde_json::Map<String, Value>,
+    ) -> Result<Val, ArgumentParseError> {
         let s = value
             .as_str()
             .ok_or_else(|| ArgumentParseError::TypeMismatch {
                 expected: "string for bytes".to_string(),
                 actual: format!("{}", value),
             })?;
-        let bytes = self.decode_bytes_string(s)?;
+        let bytes = self.decode_bytes_string(s, obj)?;
         let soroban_bytes = soroban_sdk::Bytes::from_slice(&self.env, &bytes);
         Val::try_from_val(&self.env, &soroban_bytes).map_err(|e| {
             ArgumentParseError::ConversionError(format!("Failed to convert Bytes: {:?}", e))
@@ -485,6 +808,7 @@ impl ArgumentParser {
         &self,
         value: &Value,
         obj: &serde_json::Map<String, Value>,
+        expected_length: usize,
     ) -> Result<Val, ArgumentParseError> {
         let s = value
             .as_str()
@@ -492,10 +816,7 @@ impl ArgumentParser {
                 expected: "string for bytesn".to_string(),
                 actual: format!("{}", value),
             })?;
-        let bytes = self.decode_bytes_string(s)?;
-        let expected_length = obj.get("length").and_then(|l| l.as_u64()).ok_or_else(|| {
-            ArgumentParseError::InvalidArgument("BytesN requires a 'length' field".to_string())
-        })? as usize;
+        let bytes = self.decode_bytes_string(s, obj)?;
 
         if bytes.len() != expected_length {
             return Err(ArgumentParseError::InvalidArgument(format!(
@@ -519,6 +840,12 @@ impl ArgumentParser {
                 actual: format!("{}", value),
             })?;
 
+        if let Some(reason) = Self::strkey_error(s) {
+            return Err(ArgumentParseError::InvalidArgument(format!(
+                "Invalid address {}: {}",
+                s, reason
+            )));
+        }
         let address = catch_unwind(AssertUnwindSafe(|| Address::from_str(&self.env, s)))
             .map_err(|_| ArgumentParseError::InvalidArgument(format!("Invalid address: {}", s)))?;
 
@@ -530,6 +857,30 @@ impl ArgumentParser {
         })
     }
 
+    /// Convert the name of a linked contract to its address
+    fn convert_contract(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let name = value
+            .as_str()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: "contract (name given to --link)".to_string(),
+                actual: format!("{}", value),
+            })?;
+
+        let address = self.contracts.get(name).ok_or_else(|| {
+            ArgumentParseError::InvalidArgument(format!(
+                "Unknown contract '{}'; link it with --link {}=path.wasm",
+                name, name
+            ))
+        })?;
+
+        Val::try_from_val(&self.env, address).map_err(|e| {
+            ArgumentParseError::ConversionError(format!(
+                "Failed to convert Address to Val: {:?}",
+                e
+            ))
+        })
+    }
+
     /// Convert a JSON value to a Soroban Val (bare values without type annotation)
     fn json_to_soroban_val(&self, json_value: &Value) -> Result<Val, ArgumentParseError> {
         match json_value {
@@ -642,13 +993,9 @@ impl ArgumentParser {
         let mut first_type: Option<String> = None;
 
         for (i, item) in arr.iter().enumerate() {
-            let val = self.json_to_soroban_val(item).map_err(|e| {
-                warn!("Failed to convert array element {}: {}", i, e);
-                ArgumentParseError::ConversionError(format!(
-                    "Cannot convert array element {} to Soroban value: {}",
-                    i, e
-                ))
-            })?;
+            let val = self
+                .json_to_soroban_val(item)
+                .map_err(|e| e.within(&format!("[{}]", i)))?;
 
             // Optional: Enforce homogeneity for bare arrays by comparing JSON value types
             // This meets the "Clear errors for mixed types" requirement.
@@ -699,26 +1046,790 @@ impl ArgumentParser {
         let mut soroban_map = Map::<Symbol, Val>::new(&self.env);
 
         for (key, value) in obj.iter() {
-            let key_symbol = Symbol::new(&self.env, key);
+            let key_symbol = catch_unwind(AssertUnwindSafe(|| Symbol::new(&self.env, key)))
+                .map_err(|_| {
+                    ArgumentParseError::InvalidArgument(format!(
+                        "Map key '{}' is not a valid symbol; use {{\"type\": \"map\", \"value\": [[key, value], ...]}} for other keys",
+                        key
+                    ))
+                })?;
 
-            let val = self.json_to_soroban_val(value).map_err(|e| {
-                warn!("Failed to convert map value for key '{}': {}", key, e);
-                ArgumentParseError::ConversionError(format!(
-                    "Cannot convert value for key '{}' to Soroban value: {}",
-                    key, e
-                ))
-            })?;
+            let val = self
+                .json_to_soroban_val(value)
+                .map_err(|e| e.within(&format!(".{}", key)))?;
 
             soroban_map.set(key_symbol, val);
         }
 
         Ok(soroban_map.into())
     }
+
+    /// Convert the value of a `{"type": "map"}` annotation: an object with
+    /// symbol keys, or an array of `[key, value]` pairs for any other keys.
+    /// Keys and values are converted like any other argument.
+    fn convert_map(&self, value: &Value) -> Result<Val, ArgumentParseError> {
+        let pairs = match value {
+            Value::Object(obj) => return self.object_to_soroban_map(obj),
+            Value::Array(pairs) => pairs,
+            other => {
+                return Err(ArgumentParseError::TypeMismatch {
+                    expected: "object or array of [key, value] pairs for map".to_string(),
+                    actual: format!("{}", other),
+                })
+            }
+        };
+
+        let mut soroban_map = Map::<Val, Val>::new(&self.env);
+        for (i, pair) in pairs.iter().enumerate() {
+            let Some([key, val]) = pair.as_array().map(Vec::as_slice) else {
+                return Err(ArgumentParseError::TypeMismatch {
+                    expected: "[key, value] pair".to_string(),
+                    actual: format!("{}", pair),
+                }
+                .within(&format!("[{}]", i)));
+            };
+            let key = self
+                .json_to_soroban_val(key)
+                .map_err(|e| e.within(&format!("[{}][0]", i)))?;
+            let val = self
+                .json_to_soroban_val(val)
+                .map_err(|e| e.within(&format!("[{}][1]", i)))?;
+            soroban_map.set(key, val);
+        }
+
+        Ok(soroban_map.into())
+    }
+
+    /// Convert one argument as a parameter declared with the contract spec
+    /// type `type_name` (`U32`, `Vec<Address>`, `Order`, ...).
+    pub fn parse_as_spec_type(
+        &self,
+        type_name: &str,
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        self.convert_spec_type(type_name, value)
+    }
+
+    /// Convert `value` as the contract spec type `type_name`, written as in
+    /// function signatures (`U32`, `Vec<Address>`, `Order`, ...). Values with
+    /// their own annotation are converted as annotated.
+    fn convert_spec_type(&self, type_name: &str, value: &Value) -> Result<Val, ArgumentParseError> {
+        if self.is_typed_annotation(value) {
+            return self.json_to_soroban_val(value);
+        }
+        let no_fields = serde_json::Map::new();
+        if let Some(inner) = spec_generic(type_name, "Option") {
+            return match value {
+                Value::Null => self.convert_option(value),
+                some => self.convert_spec_type(inner, some),
+            };
+        }
+        if let Some(inner) = spec_generic(type_name, "Vec") {
+            let items = value
+                .as_array()
+                .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                    expected: format!("array for {}", type_name),
+                    actual: value.to_string(),
+                })?;
+            let mut soroban_vec = SorobanVec::<Val>::new(&self.env);
+            for (i, item) in items.iter().enumerate() {
+                let val = self
+                    .convert_spec_type(inner, item)
+                    .map_err(|e| e.within(&format!("[{}]", i)))?;
+                soroban_vec.push_back(val);
+            }
+            return Ok(soroban_vec.into());
+        }
+        if let Some(inner) = spec_generic(type_name, "Tuple") {
+            let types = split_spec_types(inner);
+            return self.spec_values(type_name, &types, value).map(Into::into);
+        }
+        if let Some(inner) = spec_generic(type_name, "Map") {
+            if let [key_type, value_type] = split_spec_types(inner).as_slice() {
+                return self.convert_spec_map(key_type, value_type, value);
+            }
+        }
+        if let Some(length) = spec_generic(type_name, "BytesN").and_then(|n| n.parse().ok()) {
+            return self.convert_bytesn(value, &no_fields, length);
+        }
+        let annotation = match type_name {
+            "U32" => "u32",
+            "I32" => "i32",
+            "U64" => "u64",
+            "I64" => "i64",
+            "U128" => "u128",
+            "I128" => "i128",
+            "U256" => "u256",
+            "I256" => "i256",
+            "Bool" => "bool",
+            "Symbol" => "symbol",
+            "String" => "string",
+            "Address" => "address",
+            "Bytes" => "bytes",
+            "Timepoint" => "timepoint",
+            "Duration" => "duration",
+            user_type => {
+                return match self.types.get(user_type) {
+                    Some(definition) => self.convert_user_type(definition, value),
+                    None => self.json_to_soroban_val(value),
+                }
+            }
+        };
+        self.convert_as(annotation, value, &no_fields)
+    }
+
+    /// Convert a JSON array holding one value of each of `types`, in order,
+    /// to a Soroban Vec.
+    fn spec_values(
+        &self,
+        what: &str,
+        types: &[&str],
+        value: &Value,
+    ) -> Result<SorobanVec<Val>, ArgumentParseError> {
+        let items = match value.as_array() {
+            Some(items) if items.len() == types.len() => items,
+            _ => {
+                return Err(ArgumentParseError::TypeMismatch {
+                    expected: format!("array of {} values for {}", types.len(), what),
+                    actual: value.to_string(),
+                })
+            }
+        };
+        let mut soroban_vec = SorobanVec::<Val>::new(&self.env);
+        for (i, (type_name, item)) in types.iter().zip(items).enumerate() {
+            let val = self
+                .convert_spec_type(type_name, item)
+                .map_err(|e| e.within(&format!("[{}]", i)))?;
+            soroban_vec.push_back(val);
+        }
+        Ok(soroban_vec)
+    }
+
+    /// Convert an object, or an array of `[key, value]` pairs, to a Soroban
+    /// Map with the given key and value types. Object keys are converted
+    /// from their text.
+    fn convert_spec_map(
+        &self,
+        key_type: &str,
+        value_type: &str,
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        let mut soroban_map = Map::<Val, Val>::new(&self.env);
+        match value {
+            Value::Object(obj) => {
+                for (key, val) in obj {
+                    let segment = format!(".{}", key);
+                    let key = self
+                        .convert_spec_type(key_type, &Value::String(key.clone()))
+                        .map_err(|e| e.within(&segment))?;
+                    let val = self
+                        .convert_spec_type(value_type, val)
+                        .map_err(|e| e.within(&segment))?;
+                    soroban_map.set(key, val);
+                }
+            }
+            Value::Array(pairs) => {
+                for (i, pair) in pairs.iter().enumerate() {
+                    let Some([key, val]) = pair.as_array().map(Vec::as_slice) else {
+                        return Err(ArgumentParseError::TypeMismatch {
+                            expected: "[key, value] pair".to_string(),
+                            actual: pair.to_string(),
+                        }
+                        .within(&format!("[{}]", i)));
+                    };
+                    let key = self
+                        .convert_spec_type(key_type, key)
+                        .map_err(|e| e.within(&format!("[{}][0]", i)))?;
+                    let val = self
+                        .convert_spec_type(value_type, val)
+                        .map_err(|e| e.within(&format!("[{}][1]", i)))?;
+                    soroban_map.set(key, val);
+                }
+            }
+            other => {
+                return Err(ArgumentParseError::TypeMismatch {
+                    expected: "object or array of [key, value] pairs for map".to_string(),
+                    actual: other.to_string(),
+                })
+            }
+        }
+        Ok(soroban_map.into())
+    }
+
+    /// Convert `value` as one of the contract's user-defined types. Structs
+    /// take an object of their fields (an array for tuple structs), union
+    /// cases `"Case"` or `{"Case": value}` (an array for several values), and
+    /// integer enums a case name or value.
+    fn convert_user_type(
+        &self,
+        definition: &TypeDefinition,
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        match definition {
+            TypeDefinition::Struct { name, fields } => self.convert_struct(name, fields, value),
+            TypeDefinition::Union { name, cases } => self.convert_union(name, cases, value),
+            TypeDefinition::Enum { name, cases } => self.convert_enum(name, cases, value),
+        }
+    }
+
+    fn convert_struct(
+        &self,
+        name: &str,
+        fields: &[(String, String)],
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        let types: Vec<&str> = fields.iter().map(|(_, ty)| ty.as_str()).collect();
+        // Tuple structs are stored as a vector of their fields.
+        if !fields.is_empty() && fields.iter().all(|(field, _)| field.parse::<u32>().is_ok()) {
+            return self.spec_values(name, &types, value).map(Into::into);
+        }
+
+        let expected = || {
+            fields
+                .iter()
+                .map(|(field, ty)| format!("{}: {}", field, ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let obj = value
+            .as_object()
+            .ok_or_else(|| ArgumentParseError::TypeMismatch {
+                expected: format!("object with fields {} for {}", expected(), name),
+                actual: value.to_string(),
+            })?;
+        if let Some(unknown) = obj
+            .keys()
+            .find(|key| !fields.iter().any(|(f, _)| f == *key))
+        {
+            return Err(ArgumentParseError::InvalidArgument(format!(
+                "{} has no field '{}'; expected fields: {}",
+                name,
+                unknown,
+                expected()
+            )));
+        }
+
+        let mut soroban_map = Map::<Symbol, Val>::new(&self.env);
+        for (field, ty) in fields {
+            let Some(field_value) = obj.get(field) else {
+                return Err(ArgumentParseError::InvalidArgument(format!(
+                    "{} is missing field '{}'; expected fields: {}",
+                    name,
+                    field,
+                    expected()
+                )));
+            };
+            let val = self
+                .convert_spec_type(ty, field_value)
+                .map_err(|e| e.within(&format!(".{}", field)))?;
+            soroban_map.set(Symbol::new(&self.env, field), val);
+        }
+        Ok(soroban_map.into())
+    }
+
+    fn convert_union(
+        &self,
+        name: &str,
+        cases: &[(String, Vec<String>)],
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        let expected = || {
+            cases
+                .iter()
+                .map(|(case, types)| {
+                    if types.is_empty() {
+                        case.clone()
+                    } else {
+                        format!("{}({})", case, types.join(", "))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let (case, data) = match value {
+            Value::String(case) => (case.as_str(), None),
+            Value::Object(obj) if obj.len() == 1 => {
+                let (case, data) = obj.iter().next().expect("one entry");
+                (case.as_str(), Some(data))
+            }
+            _ => {
+                return Err(ArgumentParseError::TypeMismatch {
+                    expected: format!(
+                        "\"Case\" or {{\"Case\": value}} for {} ({})",
+                        name,
+                        expected()
+                    ),
+                    actual: value.to_string(),
+                })
+            }
+        };
+        let Some((_, types)) = cases.iter().find(|(c, _)| c == case) else {
+            return Err(ArgumentParseError::InvalidArgument(format!(
+                "{} has no case '{}'; expected cases: {}",
+                name,
+                case,
+                expected()
+            )));
+        };
+
+        let mut soroban_vec = SorobanVec::<Val>::new(&self.env);
+        soroban_vec.push_back(Symbol::new(&self.env, case).to_val());
+        let segment = format!(".{}", case);
+        match (types.as_slice(), data) {
+            ([], None | Some(Value::Null)) => {}
+            ([ty], Some(data)) => {
+                let val = self
+                    .convert_spec_type(ty, data)
+                    .map_err(|e| e.within(&segment))?;
+                soroban_vec.push_back(val);
+            }
+            (types, Some(data)) if types.len() > 1 => {
+                let types: Vec<&str> = types.iter().map(String::as_str).collect();
+                let values = self
+                    .spec_values(&format!("{}::{}", name, case), &types, data)
+                    .map_err(|e| e.within(&segment))?;
+                soroban_vec.append(&values);
+            }
+            ([], Some(data)) => {
+                return Err(ArgumentParseError::InvalidArgument(format!(
+                    "{}::{} takes no value, got {}",
+                    name, case, data
+                )))
+            }
+            (types, _) => {
+                return Err(ArgumentParseError::InvalidArgument(format!(
+                    "{}::{} takes {}; write {{\"{}\": value}}",
+                    name,
+                    case,
+                    types.join(", "),
+                    case
+                )))
+            }
+        }
+        Ok(soroban_vec.into())
+    }
+
+    fn convert_enum(
+        &self,
+        name: &str,
+        cases: &[(String, u32)],
+        value: &Value,
+    ) -> Result<Val, ArgumentParseError> {
+        let found = cases.iter().find(|(case, code)| match value {
+            Value::String(s) => s == case,
+            other => other.as_u64() == Some(u64::from(*code)),
+        });
+        let Some((_, code)) = found else {
+            return Err(ArgumentParseError::InvalidArgument(format!(
+                "{} has no case {}; expected one of {}",
+                name,
+                value,
+                cases
+                    .iter()
+                    .map(|(case, code)| format!("{} ({})", case, code))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        };
+        Val::try_from_val(&self.env, code).map_err(|e| {
+            ArgumentParseError::ConversionError(format!("Failed to convert u32 to Val: {:?}", e))
+        })
+    }
+}
+
+/// The start of a base64 string, for error messages.
+fn xdr_prefix(b64: &str) -> String {
+    const SHOWN: usize = 24;
+    match b64.char_indices().nth(SHOWN) {
+        Some((end, _)) => format!("{}...", &b64[..end]),
+        None => b64.to_string(),
+    }
+}
+
+/// The argument of `generic<...>` in a spec type name, e.g. `U32` in
+/// `Vec<U32>`.
+fn spec_generic<'a>(type_name: &'a str, generic: &str) -> Option<&'a str> {
+    type_name
+        .strip_prefix(generic)?
+        .strip_prefix('<')?
+        .strip_suffix('>')
+}
+
+/// Split a comma-separated list of spec type names, leaving commas inside
+/// nested generics alone.
+fn split_spec_types(list: &str) -> Vec<&str> {
+    let mut types = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in list.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                types.push(list[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    if !list[start..].trim().is_empty() {
+        types.push(list[start..].trim());
+    }
+    types
+}
+
+const U256_MAX: &str =
+    "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+const I256_MIN: &str =
+    "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+const I256_MAX: &str =
+    "57896044618658097711785492504343953926634992332820282019728792003956564819967";
+
+/// The 256-bit integer in `value` as four limbs, most significant first, in
+/// two's complement when `signed`. `value` is a JSON integer or a decimal or
+/// `0x` hex string, with a leading `-` for negative `i256` values.
+fn int256_limbs(value: &Value, signed: bool) -> Result<[u64; 4], ArgumentParseError> {
+    let type_name = if signed { "i256" } else { "u256" };
+    let mismatch = || ArgumentParseError::TypeMismatch {
+        expected: format!("{} (integer, or decimal or 0x-hex string)", type_name),
+        actual: value.to_string(),
+    };
+    let text = match value {
+        Value::Number(n) if n.is_i64() || n.is_u64() => n.to_string(),
+        Value::String(s) => s.trim().to_string(),
+        _ => return Err(mismatch()),
+    };
+    let (negative, unsigned) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    let (digits, radix) = match unsigned.strip_prefix("0x") {
+        Some(hex) => (hex, 16),
+        None => (unsigned, 10),
+    };
+    if digits.is_empty() {
+        return Err(mismatch());
+    }
+
+    let mut limbs = [0u64; 4];
+    let mut overflow = false;
+    for c in digits.chars() {
+        let mut carry = u128::from(c.to_digit(radix).ok_or_else(mismatch)?);
+        for limb in limbs.iter_mut().rev() {
+            let wide = u128::from(*limb) * u128::from(radix) + carry;
+            *limb = wide as u64;
+            carry = wide >> 64;
+        }
+        overflow |= carry != 0;
+    }
+
+    const SIGN: u64 = 1 << 63;
+    let in_range = !overflow
+        && match (signed, negative) {
+            (false, false) => true,
+            (false, true) => limbs == [0; 4],
+            (true, false) => limbs[0] < SIGN,
+            (true, true) => limbs[0] < SIGN || limbs == [SIGN, 0, 0, 0],
+        };
+    if !in_range {
+        let (min, max) = if signed {
+            (I256_MIN, I256_MAX)
+        } else {
+            ("0", U256_MAX)
+        };
+        return Err(ArgumentParseError::OutOfRange {
+            type_name: type_name.to_string(),
+            value: text,
+            min: min.to_string(),
+            max: max.to_string(),
+        });
+    }
+    Ok(if negative { negate(limbs) } else { limbs })
+}
+
+/// Two's complement negation of a 256-bit integer.
+fn negate(limbs: [u64; 4]) -> [u64; 4] {
+    let mut out = limbs.map(|limb| !limb);
+    for limb in out.iter_mut().rev() {
+        let (sum, carry) = limb.overflowing_add(1);
+        *limb = sum;
+        if !carry {
+            break;
+        }
+    }
+    out
+}
+
+/// Decimal text of a 256-bit integer given as limbs, most significant
+/// first, in two's complement when `signed`.
+pub(crate) fn int256_to_decimal(limbs: [u64; 4], signed: bool) -> String {
+    const CHUNK: u128 = 10_000_000_000_000_000_000;
+    let negative = signed && limbs[0] >> 63 == 1;
+    let mut magnitude = if negative { negate(limbs) } else { limbs };
+    // Base 10^19 chunks, least significant first.
+    let mut chunks = Vec::new();
+    while magnitude != [0; 4] {
+        let mut rem = 0u128;
+        for limb in magnitude.iter_mut() {
+            let wide = (rem << 64) | u128::from(*limb);
+            *limb = (wide / CHUNK) as u64;
+            rem = wide % CHUNK;
+        }
+        chunks.push(rem as u64);
+    }
+    let mut text = String::from(if negative { "-" } else { "" });
+    match chunks.pop() {
+        Some(first) => text.push_str(&first.to_string()),
+        None => text.push('0'),
+    }
+    for chunk in chunks.iter().rev() {
+        text.push_str(&format!("{:019}", chunk));
+    }
+    text
+}
+
+/// Render a contract value as the bare JSON the argument parser accepts.
+///
+/// `Void` (and so `Option::None`) becomes `null`, integers of up to 64 bits
+/// become numbers and wider ones decimal strings, symbols, strings and
+/// addresses become strings, and maps with symbol or string keys become
+/// objects. Timepoints and durations keep their raw seconds, tagged as
+/// `{"$type": "timepoint", "value": N}`. Anything else falls back to its
+/// `Debug` form.
+///
+/// Values are converted without recursion, like those in
+/// [`crate::utils::value_depth`], and containers nested deeper than
+/// `--max-value-depth` become its truncation marker.
+pub fn scval_to_json(val: &ScVal) -> Value {
+    let max_depth = crate::utils::value_depth::max_value_depth();
+    let mut stack = match open_scval_json(JsonChild::Val(None, val), 1, max_depth) {
+        ScvalJson::Leaf(_, value) => return value,
+        ScvalJson::Frame(frame) => vec![frame],
+    };
+    while let Some(top) = stack.last_mut() {
+        match top.pending.pop() {
+            Some(child) => match open_scval_json(child, top.depth, max_depth) {
+                ScvalJson::Leaf(key, value) => top.attach(key, value),
+                ScvalJson::Frame(frame) => stack.push(frame),
+            },
+            None => {
+                let (key, value) = (top.key.take(), std::mem::take(&mut top.built));
+                stack.pop();
+                match stack.last_mut() {
+                    Some(parent) => parent.attach(key, value),
+                    None => return value,
+                }
+            }
+        }
+    }
+    // The root container returns its value when it is complete.
+    Value::Null
+}
+
+/// A value [`scval_to_json`] still has to convert.
+enum JsonChild<'a> {
+    /// A value, with its member name when it goes into an object.
+    Val(Option<String>, &'a ScVal),
+    /// A map entry, written as a `[key, value]` pair.
+    Pair(&'a soroban_env_host::xdr::ScMapEntry),
+}
+
+/// An array or object [`scval_to_json`] is filling in.
+struct JsonFrame<'a> {
+    built: Value,
+    /// Member name of the container in its parent object.
+    key: Option<String>,
+    /// Children still to convert, last first.
+    pending: Vec<JsonChild<'a>>,
+    /// Depth of the children.
+    depth: usize,
+}
+
+impl JsonFrame<'_> {
+    fn attach(&mut self, key: Option<String>, value: Value) {
+        match &mut self.built {
+            Value::Array(items) => items.push(value),
+            Value::Object(members) => {
+                members.insert(key.unwrap_or_default(), value);
+            }
+            _ => {}
+        }
+    }
+}
+
+enum ScvalJson<'a> {
+    Leaf(Option<String>, Value),
+    Frame(JsonFrame<'a>),
+}
+
+/// Convert `child` at `depth`, or open the container it is.
+fn open_scval_json(child: JsonChild<'_>, depth: usize, max_depth: usize) -> ScvalJson<'_> {
+    use crate::utils::value_depth::truncation_marker;
+
+    let (key, val) = match child {
+        // The pair adds no depth of its own.
+        JsonChild::Pair(entry) => {
+            return ScvalJson::Frame(JsonFrame {
+                built: Value::Array(Vec::with_capacity(2)),
+                key: None,
+                pending: vec![
+                    JsonChild::Val(None, &entry.val),
+                    JsonChild::Val(None, &entry.key),
+                ],
+                depth,
+            })
+        }
+        JsonChild::Val(key, val) => (key, val),
+    };
+    if depth > max_depth {
+        return ScvalJson::Leaf(key, Value::String(truncation_marker(max_depth)));
+    }
+    let value = match val {
+        ScVal::Void | ScVal::Vec(None) | ScVal::Map(None) => Value::Null,
+        ScVal::Bool(b) => Value::Bool(*b),
+        ScVal::U32(n) => Value::from(*n),
+        ScVal::I32(n) => Value::from(*n),
+        ScVal::U64(n) => Value::from(*n),
+        ScVal::I64(n) => Value::from(*n),
+        ScVal::U128(parts) => {
+            Value::String(((u128::from(parts.hi) << 64) | u128::from(parts.lo)).to_string())
+        }
+        ScVal::I128(parts) => {
+            Value::String(((i128::from(parts.hi) << 64) | i128::from(parts.lo)).to_string())
+        }
+        ScVal::U256(parts) => Value::String(int256_to_decimal(
+            [parts.hi_hi, parts.hi_lo, parts.lo_hi, parts.lo_lo],
+            false,
+        )),
+        ScVal::I256(parts) => Value::String(int256_to_decimal(
+            [parts.hi_hi as u64, parts.hi_lo, parts.lo_hi, parts.lo_lo],
+            true,
+        )),
+        ScVal::Symbol(symbol) => Value::String(symbol.to_utf8_string_lossy()),
+        ScVal::String(string) => Value::String(string.to_utf8_string_lossy()),
+        ScVal::Address(address) => Value::String(address.to_string()),
+        ScVal::Bytes(bytes) => Value::String(format!("0x{}", hex::encode(bytes.as_slice()))),
+        ScVal::Timepoint(time) => serde_json::json!({"$type": "timepoint", "value": time.0}),
+        ScVal::Duration(span) => serde_json::json!({"$type": "duration", "value": span.0}),
+        ScVal::Vec(Some(items)) => {
+            return ScvalJson::Frame(JsonFrame {
+                built: Value::Array(Vec::with_capacity(items.len())),
+                key,
+                pending: items
+                    .iter()
+                    .rev()
+                    .map(|item| JsonChild::Val(None, item))
+                    .collect(),
+                depth: depth + 1,
+            })
+        }
+        ScVal::Map(Some(entries)) => {
+            let keys: Option<Vec<String>> = entries
+                .iter()
+                .map(|entry| match &entry.key {
+                    ScVal::Symbol(symbol) => Some(symbol.to_utf8_string_lossy()),
+                    ScVal::String(string) => Some(string.to_utf8_string_lossy()),
+                    _ => None,
+                })
+                .collect();
+            let (built, pending) = match keys {
+                Some(keys) => (
+                    Value::Object(serde_json::Map::new()),
+                    keys.into_iter()
+                        .zip(entries.iter())
+                        .rev()
+                        .map(|(key, entry)| JsonChild::Val(Some(key), &entry.val))
+                        .collect(),
+                ),
+                None => (
+                    Value::Array(Vec::with_capacity(entries.len())),
+                    entries.iter().rev().map(JsonChild::Pair).collect(),
+                ),
+            };
+            return ScvalJson::Frame(JsonFrame {
+                built,
+                key,
+                pending,
+                depth: depth + 1,
+            });
+        }
+        other => Value::String(format!("{:?}", other)),
+    };
+    ScvalJson::Leaf(key, value)
+}
+
+/// Render a contract value as a type annotation that parses back to the same
+/// value, e.g. `{"type": "u32", "value": 7}`.
+///
+/// Unlike [`scval_to_json`] nothing is lost: vectors and maps annotate each
+/// element, 128- and 256-bit integers beyond 64 bits are decimal strings, and
+/// values without an annotation of their own (errors, nonces, contract
+/// instances) are written as `xdr`.
+pub fn scval_to_typed_json(val: &ScVal) -> Value {
+    let typed =
+        |type_name: &str, value: Value| serde_json::json!({"type": type_name, "value": value});
+    let as_xdr = |val: &ScVal| match val.to_xdr_base64(Limits::none()) {
+        Ok(xdr) => typed("xdr", Value::String(xdr)),
+        Err(_) => Value::String(format!("{:?}", val)),
+    };
+    match val {
+        ScVal::Void => typed("option", Value::Null),
+        ScVal::Bool(b) => typed("bool", Value::Bool(*b)),
+        ScVal::U32(n) => typed("u32", Value::from(*n)),
+        ScVal::I32(n) => typed("i32", Value::from(*n)),
+        ScVal::U64(n) => typed("u64", Value::from(*n)),
+        ScVal::I64(n) => typed("i64", Value::from(*n)),
+        ScVal::U128(_) | ScVal::I128(_) | ScVal::U256(_) | ScVal::I256(_) => {
+            let type_name = match val {
+                ScVal::U128(_) => "u128",
+                ScVal::I128(_) => "i128",
+                ScVal::U256(_) => "u256",
+                _ => "i256",
+            };
+            // Decimal strings so that JSON readers do not round them.
+            let value = match scval_to_json(val) {
+                Value::Number(n) => Value::String(n.to_string()),
+                other => other,
+            };
+            typed(type_name, value)
+        }
+        ScVal::Symbol(symbol) => typed("symbol", Value::String(symbol.to_utf8_string_lossy())),
+        ScVal::String(string) => match string.to_utf8_string() {
+            Ok(text) => typed("string", Value::String(text)),
+            Err(_) => as_xdr(val),
+        },
+        ScVal::Address(address) => typed("address", Value::String(address.to_string())),
+        ScVal::Bytes(bytes) => typed(
+            "bytes",
+            Value::String(format!("0x{}", hex::encode(bytes.as_slice()))),
+        ),
+        ScVal::Timepoint(time) => typed("timepoint", Value::from(time.0)),
+        ScVal::Duration(span) => typed("duration", Value::from(span.0)),
+        ScVal::Vec(Some(items)) => typed(
+            "vec",
+            Value::Array(items.iter().map(scval_to_typed_json).collect()),
+        ),
+        ScVal::Map(Some(entries)) => typed(
+            "map",
+            Value::Array(
+                entries
+                    .iter()
+                    .map(|entry| {
+                        Value::Array(vec![
+                            scval_to_typed_json(&entry.key),
+                            scval_to_typed_json(&entry.val),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        other => as_xdr(other),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use soroban_env_host::xdr::Int128Parts;
     use soroban_sdk::Env;
 
     fn create_parser() -> ArgumentParser {
@@ -1238,6 +2349,58 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Invalid address"));
     }
 
+    #[test]
+    fn test_invalid_address_names_the_decode_failure() {
+        let parser = create_parser();
+        let reason = |addr: &str| {
+            parser
+                .parse_args_string(&format!(r#"[1, {{"type": "address", "value": "{addr}"}}]"#))
+                .unwrap_err()
+                .to_string()
+        };
+
+        let err = reason("GAAAA");
+        assert!(err.contains("Array element 1"), "{err}");
+        assert!(err.contains("expected 56 characters, got 5"), "{err}");
+
+        let err = reason("SAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF");
+        assert!(err.contains("starts with 'S'"), "{err}");
+
+        let err = reason("GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWH1");
+        assert!(err.contains("'1' at position 56"), "{err}");
+
+        let err = reason("GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHG");
+        assert!(err.contains("checksum does not match"), "{err}");
+
+        assert_eq!(
+            ArgumentParser::strkey_error(
+                "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHK3M"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_contract_names_a_linked_contract() {
+        let env = Env::default();
+        let addr = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHK3M";
+        let address = Address::from_str(&env, addr);
+        let parser = ArgumentParser::new(env.clone())
+            .with_contracts(BTreeMap::from([("token".to_string(), address.clone())]));
+
+        let vals = parser
+            .parse_args_string(r#"[{"type": "contract", "value": "token"}]"#)
+            .unwrap();
+        assert_eq!(Address::try_from_val(&env, &vals[0]).unwrap(), address);
+
+        let err = parser
+            .parse_args_string(r#"[{"type": "contract", "value": "pool"}]"#)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unknown contract 'pool'; link it with --link pool=path.wasm"));
+    }
+
     #[test]
     fn test_typed_vec_u32() {
         let parser = create_parser();
@@ -1279,6 +2442,95 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_typed_map_object_and_pairs() {
+        let parser = create_parser();
+        let map = |json: &str| match parse_to_scval(&parser, json) {
+            ScVal::Map(Some(map)) => map,
+            other => panic!("expected a map, got {:?}", other),
+        };
+
+        let by_symbol =
+            map(r#"[{"type": "map", "value": {"b": 2, "a": {"type": "u32", "value": 1}}}]"#);
+        assert_eq!(by_symbol.len(), 2);
+        assert_eq!(
+            by_symbol[0].key,
+            ScVal::Symbol(soroban_env_host::xdr::ScSymbol("a".try_into().unwrap()))
+        );
+        assert_eq!(by_symbol[0].val, ScVal::U32(1));
+
+        let by_u32 = map(
+            r#"[{"type": "map", "value": [[{"type": "u32", "value": 7}, [1, 2]], [{"type": "u32", "value": 3}, []]]}]"#,
+        );
+        assert_eq!(by_u32.len(), 2);
+        assert_eq!(by_u32[0].key, ScVal::U32(3));
+        assert!(matches!(&by_u32[1].val, ScVal::Vec(Some(items)) if items.len() == 2));
+    }
+
+    #[test]
+    fn test_map_of_vecs_of_structs() {
+        let parser = create_parser();
+        let json = r#"[{"type": "map", "value": {
+            "batches": [
+                [{"to": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF", "amounts": [1, 2]}],
+                [{"to": "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHK3M", "amounts": []}]
+            ]
+        }}]"#;
+        let ScVal::Map(Some(outer)) = parse_to_scval(&parser, json) else {
+            panic!("expected a map");
+        };
+        let ScVal::Vec(Some(batches)) = &outer[0].val else {
+            panic!("expected a vec of batches");
+        };
+        let ScVal::Vec(Some(batch)) = &batches[1] else {
+            panic!("expected a batch");
+        };
+        let ScVal::Map(Some(transfer)) = &batch[0] else {
+            panic!("expected a struct");
+        };
+        assert_eq!(
+            transfer[1].key,
+            ScVal::Symbol(soroban_env_host::xdr::ScSymbol("to".try_into().unwrap()))
+        );
+        assert!(matches!(transfer[1].val, ScVal::Address(_)));
+    }
+
+    #[test]
+    fn test_nested_errors_carry_the_path() {
+        let parser = create_parser();
+        let error = |json: &str| parser.parse_args_string(json).unwrap_err().to_string();
+
+        let err = error(
+            r#"[1, 2, {"type": "map", "value": [["a", 1], [{"type": "u32", "value": -1}, 2]]}]"#,
+        );
+        assert!(
+            err.contains("Array element 2 at args[2].value[1][0].value: Type/value mismatch"),
+            "{err}"
+        );
+
+        let err = error(
+            r#"[{"batches": [[{"amount": 1}], [{"amount": {"type": "u64", "value": "x"}}]]}]"#,
+        );
+        assert!(
+            err.contains("at args[0].batches[1][0].amount.value: "),
+            "{err}"
+        );
+
+        let err = error(r#"[{"type": "vec", "element_type": "u32", "value": [1, "x"]}]"#);
+        assert!(
+            err.contains(
+                "at args[0].value[1]: Failed to convert value: does not match element_type 'u32'"
+            ),
+            "{err}"
+        );
+
+        let err = error(r#"[{"not a symbol": 1}]"#);
+        assert!(
+            err.contains("Map key 'not a symbol' is not a valid symbol"),
+            "{err}"
+        );
+    }
+
     #[test]
     fn test_nested_vec_typed() {
         let parser = create_parser();
@@ -1395,6 +2647,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_typed_i128_accepts_decimal_string_beyond_i64() {
+        let parser = create_parser();
+        let result =
+            parser.parse_args_string(r#"[{"type": "i128", "value": "-100000000000000000000000"}]"#);
+        assert!(result.is_ok(), "i128 string failed: {:?}", result.err());
+    }
+
     #[test]
     fn test_typed_i128_type_mismatch() {
         let parser = create_parser();
@@ -1402,6 +2662,476 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // ── 256-bit integers ─────────────────────────────────────────────
+
+    #[test]
+    fn test_typed_u256_boundaries() {
+        let parser = create_parser();
+        for (input, rendered) in [
+            ("0".to_string(), Value::String("0".to_string())),
+            (
+                format!("0x{}", "f".repeat(64)),
+                Value::String(U256_MAX.to_string()),
+            ),
+            (U256_MAX.to_string(), Value::String(U256_MAX.to_string())),
+            (
+                "340282366920938463463374607431768211456".to_string(),
+                Value::String("340282366920938463463374607431768211456".to_string()),
+            ),
+        ] {
+            let json = format!(r#"[{{"type": "u256", "value": "{}"}}]"#, input);
+            let scval = parse_to_scval(&parser, &json);
+            assert!(matches!(scval, ScVal::U256(_)), "{}", input);
+            assert_eq!(scval_to_json(&scval), rendered, "{}", input);
+        }
+        let scval = parse_to_scval(&parser, r#"[{"type": "u256", "value": 42}]"#);
+        assert_eq!(scval_to_json(&scval), Value::String("42".to_string()));
+    }
+
+    #[test]
+    fn test_typed_i256_boundaries() {
+        let parser = create_parser();
+        for value in [I256_MIN, I256_MAX, "0", "-1", "-9223372036854775809"] {
+            let json = format!(r#"[{{"type": "i256", "value": "{}"}}]"#, value);
+            let scval = parse_to_scval(&parser, &json);
+            assert!(matches!(scval, ScVal::I256(_)), "{}", value);
+            assert_eq!(
+                scval_to_json(&scval),
+                Value::String(value.to_string()),
+                "{}",
+                value
+            );
+        }
+        let scval = parse_to_scval(&parser, r#"[{"type": "i256", "value": "-0x10"}]"#);
+        assert_eq!(scval_to_json(&scval), Value::String("-16".to_string()));
+    }
+
+    #[test]
+    fn test_typed_256_out_of_range() {
+        let parser = create_parser();
+        let too_big =
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936";
+        let too_small =
+            "-57896044618658097711785492504343953926634992332820282019728792003956564819969";
+        for json in [
+            format!(r#"[{{"type": "u256", "value": "{}"}}]"#, too_big),
+            format!(r#"[{{"type": "u256", "value": "0x1{}"}}]"#, "0".repeat(64)),
+            r#"[{"type": "u256", "value": "-1"}]"#.to_string(),
+            format!(r#"[{{"type": "i256", "value": "{}"}}]"#, too_small),
+            format!(r#"[{{"type": "i256", "value": "0x8{}"}}]"#, "0".repeat(63)),
+        ] {
+            let err = parser.parse_args_string(&json).unwrap_err().to_string();
+            assert!(
+                err.contains("Value out of range for type"),
+                "{}: {}",
+                json,
+                err
+            );
+        }
+        let err = parser
+            .parse_args_string(r#"[{"type": "u256", "value": "12ab"}]"#)
+            .unwrap_err();
+        assert!(err.to_string().contains("decimal or 0x-hex"), "{}", err);
+    }
+
+    // ── User-defined types from the contract spec ────────────────────
+
+    fn spec_parser() -> ArgumentParser {
+        let owned = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect()
+        };
+        create_parser().with_types([
+            TypeDefinition::Struct {
+                name: "Order".to_string(),
+                fields: owned(&[("seller", "Address"), ("price", "I128")]),
+            },
+            TypeDefinition::Struct {
+                name: "Pair".to_string(),
+                fields: owned(&[("0", "U32"), ("1", "U32")]),
+            },
+            TypeDefinition::Union {
+                name: "Asset".to_string(),
+                cases: vec![
+                    ("Native".to_string(), vec![]),
+                    ("Stellar".to_string(), vec!["Address".to_string()]),
+                    (
+                        "Pool".to_string(),
+                        vec!["Address".to_string(), "Vec<Order>".to_string()],
+                    ),
+                ],
+            },
+            TypeDefinition::Enum {
+                name: "Side".to_string(),
+                cases: vec![("Buy".to_string(), 0), ("Sell".to_string(), 1)],
+            },
+        ])
+    }
+
+    #[test]
+    fn test_struct_fields_take_their_spec_types() {
+        let parser = spec_parser();
+        let json =
+            format!(r#"[{{"type": "Order", "value": {{"price": "100", "seller": "{ACCOUNT}"}}}}]"#);
+        let ScVal::Map(Some(entries)) = parse_to_scval(&parser, &json) else {
+            panic!("struct should become a map");
+        };
+        let entries: Vec<(Value, &ScVal)> = entries
+            .iter()
+            .map(|entry| (scval_to_json(&entry.key), &entry.val))
+            .collect();
+        assert_eq!(entries[0].0, Value::from("price"));
+        assert!(matches!(entries[0].1, ScVal::I128(_)));
+        assert_eq!(entries[1].0, Value::from("seller"));
+        assert!(matches!(entries[1].1, ScVal::Address(_)));
+
+        let pair = parse_to_scval(&parser, r#"[{"type": "Pair", "value": [1, 2]}]"#);
+        assert_eq!(
+            pair,
+            ScVal::Vec(Some(vec![ScVal::U32(1), ScVal::U32(2)].try_into().unwrap()))
+        );
+    }
+
+    #[test]
+    fn test_union_cases_and_enum_values() {
+        let parser = spec_parser();
+        let native = parse_to_scval(&parser, r#"[{"type": "Asset", "value": "Native"}]"#);
+        assert_eq!(scval_to_json(&native), serde_json::json!(["Native"]));
+
+        let json = format!(r#"[{{"type": "Asset", "value": {{"Stellar": "{ACCOUNT}"}}}}]"#);
+        let stellar = parse_to_scval(&parser, &json);
+        assert_eq!(
+            scval_to_json(&stellar),
+            serde_json::json!(["Stellar", ACCOUNT])
+        );
+
+        let json = format!(
+            r#"[{{"type": "Asset", "value": {{"Pool": ["{ACCOUNT}", [{{"price": 5, "seller": "{ACCOUNT}"}}]]}}}}]"#
+        );
+        let pool = parse_to_scval(&parser, &json);
+        assert_eq!(
+            scval_to_json(&pool),
+            serde_json::json!(["Pool", ACCOUNT, [{"price": "5", "seller": ACCOUNT}]])
+        );
+
+        for value in [r#""Sell""#, "1"] {
+            let json = format!(r#"[{{"type": "Side", "value": {}}}]"#, value);
+            assert_eq!(parse_to_scval(&parser, &json), ScVal::U32(1));
+        }
+    }
+
+    #[test]
+    fn test_user_type_errors_list_what_the_spec_expects() {
+        let parser = spec_parser();
+        let cases = [
+            (
+                r#"[{"type": "Order", "value": {"price": 1}}]"#,
+                "Order is missing field 'seller'; expected fields: seller: Address, price: I128",
+            ),
+            (
+                r#"[{"type": "Order", "value": {"price": 1, "buyer": "x"}}]"#,
+                "Order has no field 'buyer'",
+            ),
+            (
+                r#"[{"type": "Asset", "value": {"Issued": 1}}]"#,
+                "Asset has no case 'Issued'; expected cases: Native, Stellar(Address), Pool(Address, Vec<Order>)",
+            ),
+            (
+                r#"[{"type": "Asset", "value": "Stellar"}]"#,
+                "Asset::Stellar takes Address",
+            ),
+            (
+                r#"[{"type": "Side", "value": "Hold"}]"#,
+                "Side has no case \"Hold\"; expected one of Buy (0), Sell (1)",
+            ),
+            (
+                r#"[{"type": "Ordr", "value": {}}]"#,
+                "Unknown type 'Ordr'; built-in types are lowercase (u32, address, ...) and the contract spec defines Asset, Order, Pair, Side",
+            ),
+        ];
+        for (json, expected) in cases {
+            let err = parser.parse_args_string(json).unwrap_err().to_string();
+            assert!(err.contains(expected), "{}: {}", json, err);
+        }
+
+        let err = parser
+            .parse_args_string(
+                r#"[{"type": "Order", "value": {"price": "x", "seller": "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF"}}]"#,
+            )
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("args[0].value.price"), "{}", err);
+    }
+
+    // ── Plain values converted as the declared parameter type ────────
+
+    fn spec_scval(parser: &ArgumentParser, type_name: &str, json: &str) -> ScVal {
+        let value: Value = serde_json::from_str(json).unwrap();
+        let val = parser.parse_as_spec_type(type_name, &value).unwrap();
+        ScVal::try_from_val(parser.env.host(), &val).unwrap()
+    }
+
+    #[test]
+    fn test_plain_values_take_the_declared_type() {
+        let parser = spec_parser();
+        assert_eq!(spec_scval(&parser, "U32", "5"), ScVal::U32(5));
+        assert_eq!(
+            spec_scval(&parser, "I128", "5"),
+            ScVal::I128(Int128Parts { hi: 0, lo: 5 })
+        );
+        assert!(matches!(
+            spec_scval(&parser, "String", r#""alice""#),
+            ScVal::String(_)
+        ));
+        assert!(matches!(
+            spec_scval(&parser, "Symbol", r#""alice""#),
+            ScVal::Symbol(_)
+        ));
+        assert!(matches!(
+            spec_scval(&parser, "Address", &format!(r#""{ACCOUNT}""#)),
+            ScVal::Address(_)
+        ));
+        assert_eq!(
+            scval_to_json(&spec_scval(
+                &parser,
+                "Order",
+                &format!(r#"{{"price": 5, "seller": "{ACCOUNT}"}}"#)
+            )),
+            serde_json::json!({"price": "5", "seller": ACCOUNT})
+        );
+
+        // Option<T> is T or null, and generics convert their elements.
+        assert_eq!(
+            spec_scval(&parser, "Option<I128>", "5"),
+            ScVal::I128(Int128Parts { hi: 0, lo: 5 })
+        );
+        assert_eq!(spec_scval(&parser, "Option<I128>", "null"), ScVal::Void);
+        assert_eq!(
+            spec_scval(&parser, "Tuple<U32, Symbol>", r#"[1, "a"]"#),
+            ScVal::Vec(Some(
+                vec![
+                    ScVal::U32(1),
+                    ScVal::Symbol(soroban_env_host::xdr::ScSymbol("a".try_into().unwrap()))
+                ]
+                .try_into()
+                .unwrap()
+            ))
+        );
+        assert!(matches!(
+            spec_scval(&parser, "BytesN<2>", r#""0xabcd""#),
+            ScVal::Bytes(_)
+        ));
+
+        // An explicit annotation wins over the declared type.
+        assert_eq!(
+            spec_scval(&parser, "U32", r#"{"type": "u64", "value": 5}"#),
+            ScVal::U64(5)
+        );
+    }
+
+    #[test]
+    fn test_plain_values_that_do_not_fit_name_the_declared_type() {
+        let parser = spec_parser();
+        let cases = [
+            ("U32", "4294967296", "out of range for type u32"),
+            ("U32", "-1", "expected u32"),
+            ("Bool", r#""yes""#, "expected bool"),
+            (
+                "Tuple<U32, Symbol>",
+                "[1, 2, 3]",
+                "array of 2 values for Tuple<U32, Symbol>",
+            ),
+            ("Vec<U32>", "5", "array for Vec<U32>"),
+        ];
+        for (type_name, json, expected) in cases {
+            let value: Value = serde_json::from_str(json).unwrap();
+            let err = parser
+                .parse_as_spec_type(type_name, &value)
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(expected), "{} as {}: {}", json, type_name, err);
+        }
+    }
+
+    #[test]
+    fn test_tuple_arity_counts_top_level_types() {
+        assert_eq!(split_spec_types("U32, Symbol").len(), 2);
+        assert_eq!(
+            split_spec_types("U32, Option<Vec<Symbol>>, Map<U32, String>"),
+            ["U32", "Option<Vec<Symbol>>", "Map<U32, String>"]
+        );
+        let parser = spec_parser();
+        let value: Value = serde_json::json!([1, ["a"], [[1, "x"]]]);
+        let tuple = parser
+            .parse_as_spec_type("Tuple<U32, Option<Vec<Symbol>>, Map<U32, String>>", &value)
+            .unwrap();
+        let ScVal::Vec(Some(items)) = ScVal::try_from_val(parser.env.host(), &tuple).unwrap()
+        else {
+            panic!("tuple should become a vec");
+        };
+        assert_eq!(items.len(), 3);
+    }
+
+    #[test]
+    fn test_tuple_arity_zero_for_empty() {
+        assert!(split_spec_types("").is_empty());
+        assert!(split_spec_types("  ").is_empty());
+        let parser = spec_parser();
+        assert_eq!(
+            spec_scval(&parser, "Tuple<>", "[]"),
+            ScVal::Vec(Some(Vec::<ScVal>::new().try_into().unwrap()))
+        );
+        let err = parser
+            .parse_as_spec_type("Tuple<  >", &serde_json::json!([1]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("array of 0 values"), "{}", err);
+    }
+
+    #[test]
+    fn test_tuple_arity_none_for_bad_prefix() {
+        assert_eq!(spec_generic("Vec<U32>", "Tuple"), None);
+        assert_eq!(spec_generic("Tuple<U32", "Tuple"), None);
+        assert_eq!(spec_generic("Tuples<U32>", "Tuple"), None);
+    }
+
+    #[test]
+    fn test_option_arguments_follow_the_spec_inner_type() {
+        let parser = spec_parser();
+        assert_eq!(spec_scval(&parser, "Option<U32>", "7"), ScVal::U32(7));
+        assert_eq!(spec_scval(&parser, "Option<U32>", "null"), ScVal::Void);
+
+        let json = format!(r#"["{ACCOUNT}", "{ACCOUNT}"]"#);
+        let ScVal::Vec(Some(items)) = spec_scval(&parser, "Option<Vec<Address>>", &json) else {
+            panic!("Option<Vec<Address>> should become a vec");
+        };
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|item| matches!(item, ScVal::Address(_))));
+        assert_eq!(
+            spec_scval(&parser, "Option<Vec<Address>>", "null"),
+            ScVal::Void
+        );
+    }
+
+    #[test]
+    fn test_explicit_option_annotations_are_kept() {
+        let parser = spec_parser();
+        assert_eq!(
+            spec_scval(
+                &parser,
+                "Option<U32>",
+                r#"{"type": "option", "value": {"type": "u32", "value": 1}}"#
+            ),
+            ScVal::U32(1)
+        );
+        assert_eq!(
+            spec_scval(
+                &parser,
+                "Option<U32>",
+                r#"{"type": "option", "value": null}"#
+            ),
+            ScVal::Void
+        );
+        // An explicit annotation inside an Option wins over the inner type.
+        assert_eq!(
+            spec_scval(&parser, "Option<U32>", r#"{"type": "u64", "value": 5}"#),
+            ScVal::U64(5)
+        );
+        // Types without an annotation of their own are still converted.
+        let ScVal::Map(Some(entries)) =
+            spec_scval(&parser, "Option<Map<Symbol, U32>>", r#"{"a": 1}"#)
+        else {
+            panic!("Option<Map<..>> should become a map");
+        };
+        assert!(matches!(entries[0].key, ScVal::Symbol(_)));
+        assert_eq!(entries[0].val, ScVal::U32(1));
+    }
+
+    #[test]
+    fn test_fixed_length_bytes_carry_their_length() {
+        let parser = spec_parser();
+        let ScVal::Bytes(bytes) = spec_scval(
+            &parser,
+            "BytesN<32>",
+            &format!(r#""0x{}""#, "ab".repeat(32)),
+        ) else {
+            panic!("BytesN<32> should become bytes");
+        };
+        assert_eq!(bytes.len(), 32);
+        let err = parser
+            .parse_as_spec_type("BytesN<32>", &serde_json::json!("0xab"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("expected 32, got 1"), "{}", err);
+    }
+
+    // ── Raw ScVal XDR ────────────────────────────────────────────────
+
+    /// Arguments of a token `transfer(from, to, 100)` as the `args` of an
+    /// InvokeContract host function, base64 XDR of an `ScVec`, the way RPC
+    /// returns them.
+    const TRANSFER_ARGS_XDR: &str = "AAAAEAAAAAEAAAADAAAAEgAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAASAAAAAQAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAACgAAAAAAAAAAAAAAAAAAAGQ=";
+
+    #[test]
+    fn test_xdr_annotation_decodes_to_the_encoded_value() {
+        let parser = create_parser();
+        let scval = parse_to_scval(&parser, r#"[{"type": "xdr", "value": "AAAAAwAAACo="}]"#);
+        assert_eq!(scval, ScVal::U32(42));
+    }
+
+    #[test]
+    fn test_xdr_argument_list_round_trips() {
+        let parser = create_parser();
+        let json = ArgumentParser::xdr_args_to_json(TRANSFER_ARGS_XDR).unwrap();
+        let vals = parser.parse_args_string(&json).unwrap();
+        let scvals: Vec<ScVal> = vals
+            .iter()
+            .map(|val| ScVal::try_from_val(parser.env.host(), val).unwrap())
+            .collect();
+        assert_eq!(
+            scvals.iter().map(scval_to_json).collect::<Vec<_>>(),
+            [
+                serde_json::json!(ACCOUNT),
+                serde_json::json!("CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAABSC4"),
+                serde_json::json!("100"),
+            ]
+        );
+
+        // Encoding the decoded arguments gives back the network's bytes.
+        let args = ScVal::Vec(Some(scvals.try_into().unwrap()));
+        assert_eq!(
+            args.to_xdr_base64(Limits::none()).unwrap(),
+            TRANSFER_ARGS_XDR
+        );
+    }
+
+    #[test]
+    fn test_xdr_errors_show_the_base64_prefix() {
+        let parser = create_parser();
+        let err = parser
+            .parse_args_string(r#"[{"type": "xdr", "value": "AAAAAwAAAC"}]"#)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid ScVal XDR 'AAAAAwAAAC'"), "{}", err);
+
+        let err = ArgumentParser::xdr_args_to_json(&"AAAAAwAAACo=".repeat(3)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Invalid --args-xdr 'AAAAAwAAACo=AAAAAwAAACo=...'"),
+            "{}",
+            err
+        );
+        let err = ArgumentParser::xdr_args_to_json("AAAAAwAAACo=").unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("expected an ScVec of arguments, got U32"),
+            "{}",
+            err
+        );
+    }
+
     // ── Invalid JSON inputs ──────────────────────────────────────────
 
     #[test]
@@ -1658,6 +3388,50 @@ mod tests {
             .contains("requires a 'length' field"));
     }
 
+    #[test]
+    fn test_bytes_bare_hex_and_encodings() {
+        let parser = create_parser();
+        let bytes = |json: &str| match parse_to_scval(&parser, json) {
+            ScVal::Bytes(bytes) => bytes.as_slice().to_vec(),
+            other => panic!("expected bytes, got {:?}", other),
+        };
+        assert_eq!(
+            bytes(r#"[{"type": "bytes", "value": "deadbeef"}]"#),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            bytes(r#"[{"type": "bytes", "encoding": "base64", "value": "3q2+7w=="}]"#),
+            vec![0xde, 0xad, 0xbe, 0xef]
+        );
+        assert_eq!(
+            bytes(&format!(
+                r#"[{{"type": "bytes32", "value": "0x{}"}}]"#,
+                "ab".repeat(32)
+            )),
+            vec![0xab; 32]
+        );
+    }
+
+    #[test]
+    fn test_bytes_errors_name_the_argument() {
+        let parser = create_parser();
+        let error = |json: &str| parser.parse_args_string(json).unwrap_err().to_string();
+
+        let err = error(r#"[1, {"type": "bytes", "value": "0xabc"}]"#);
+        assert!(err.contains("Array element 1"), "{err}");
+        assert!(err.contains("Odd number of digits"), "{err}");
+
+        let err = error(r#"[{"type": "bytes32", "value": "0xdeadbeef"}]"#);
+        assert!(err.contains("Array element 0"), "{err}");
+        assert!(err.contains("expected 32, got 4"), "{err}");
+
+        let err = error(r#"[{"type": "bytes", "encoding": "base64", "value": "***"}]"#);
+        assert!(err.contains("Invalid base64 string"), "{err}");
+
+        let err = error(r#"[{"type": "bytes", "encoding": "utf8", "value": "hi"}]"#);
+        assert!(err.contains("Unknown bytes encoding 'utf8'"), "{err}");
+    }
+
     #[test]
     fn test_bytes_non_string_value() {
         let parser = create_parser();
@@ -1720,4 +3494,219 @@ mod tests {
         let result = parser.parse_args_string(r#"[{"type": "address", "value": 42}]"#);
         assert!(result.is_err());
     }
+
+    // ── Options ──────────────────────────────────────────────────────
+
+    const ACCOUNT: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+    fn parse_to_scval(parser: &ArgumentParser, json: &str) -> ScVal {
+        let vals = parser.parse_args_string(json).unwrap();
+        ScVal::try_from_val(parser.env.host(), &vals[0]).unwrap()
+    }
+
+    #[test]
+    fn test_option_i128_explicit_forms() {
+        let parser = create_parser();
+        let none = parse_to_scval(&parser, r#"[{"type": "option", "value": null}]"#);
+        assert_eq!(none, ScVal::Void);
+        assert_eq!(scval_to_json(&none), Value::Null);
+
+        let some = parse_to_scval(
+            &parser,
+            r#"[{"type": "option", "value": {"type": "i128", "value": "-170141183460469231731687303715884105728"}}]"#,
+        );
+        assert!(matches!(some, ScVal::I128(_)));
+        assert_eq!(
+            scval_to_json(&some),
+            Value::String(i128::MIN.to_string()),
+            "i128 renders as a decimal string"
+        );
+    }
+
+    #[test]
+    fn test_option_vec_address_round_trips() {
+        let parser = create_parser();
+        let json = format!(
+            r#"[{{"type": "option", "value": {{"type": "vec", "element_type": "address", "value": ["{ACCOUNT}", "{ACCOUNT}"]}}}}]"#
+        );
+        let some = parse_to_scval(&parser, &json);
+        assert_eq!(scval_to_json(&some), serde_json::json!([ACCOUNT, ACCOUNT]));
+    }
+
+    #[test]
+    fn test_option_inner_type_is_respected_without_spec() {
+        // Without a contract spec the inner type must be spelled out: a bare
+        // number defaults to i128 even inside an option.
+        let parser = create_parser();
+        let typed = parse_to_scval(
+            &parser,
+            r#"[{"type": "option", "value": {"type": "u32", "value": 7}}]"#,
+        );
+        assert_eq!(typed, ScVal::U32(7));
+        let bare = parse_to_scval(&parser, r#"[{"type": "option", "value": 7}]"#);
+        assert!(matches!(bare, ScVal::I128(_)));
+        assert_eq!(scval_to_json(&typed), serde_json::json!(7));
+        assert_eq!(scval_to_json(&bare), serde_json::json!("7"));
+    }
+
+    #[test]
+    fn test_scval_to_json_maps_and_bytes() {
+        let parser = create_parser();
+        let map = parse_to_scval(&parser, r#"[{"owner": null, "amount": 5}]"#);
+        assert_eq!(
+            scval_to_json(&map),
+            serde_json::json!({"amount": "5", "owner": null})
+        );
+        let bytes = parse_to_scval(&parser, r#"[{"type": "bytes", "value": "0xdead"}]"#);
+        assert_eq!(scval_to_json(&bytes), Value::String("0xdead".to_string()));
+    }
+
+    #[test]
+    fn test_scval_to_json_truncates_deep_values_without_recursion() {
+        use crate::utils::value_depth::pathological::{
+            dismantle, dismantle_json, nested_map, nested_vec,
+        };
+        use crate::utils::value_depth::{json_depth, DEFAULT_MAX_VALUE_DEPTH};
+
+        // The truncation marker sits one level below the deepest container;
+        // maps with integer keys spend a second level on each `[key, value]` pair.
+        for (deep, expected) in [
+            (
+                nested_vec(100_000, ScVal::U32(1)),
+                DEFAULT_MAX_VALUE_DEPTH + 1,
+            ),
+            (
+                nested_map(100_000, ScVal::U32(1)),
+                2 * DEFAULT_MAX_VALUE_DEPTH + 1,
+            ),
+        ] {
+            let json = scval_to_json(&deep);
+            let depth = json_depth(&json);
+            dismantle(deep);
+            dismantle_json(json);
+            assert_eq!(depth, expected);
+        }
+    }
+
+    #[test]
+    fn test_scval_to_json_tags_timepoints_and_durations() {
+        assert_eq!(
+            scval_to_json(&ScVal::Timepoint(TimePoint(1_700_000_000))),
+            serde_json::json!({"$type": "timepoint", "value": 1_700_000_000u64})
+        );
+        assert_eq!(
+            scval_to_json(&ScVal::Duration(Duration(3_600))),
+            serde_json::json!({"$type": "duration", "value": 3_600})
+        );
+    }
+
+    #[test]
+    fn test_scval_to_typed_json_parses_back_to_the_same_value() {
+        use soroban_env_host::xdr::{ScError, ScErrorCode};
+
+        let parser = create_parser();
+        let built = parse_to_scval(
+            &parser,
+            &format!(
+                r#"[{{"type": "map", "value": [
+                    [{{"type": "u32", "value": 1}}, {{"type": "vec", "value": [
+                        {{"type": "i128", "value": "-170141183460469231731687303715884105728"}},
+                        {{"type": "u256", "value": "0x1f"}},
+                        {{"type": "string", "value": "hi"}},
+                        {{"type": "address", "value": "{ACCOUNT}"}},
+                        {{"type": "bytes", "value": "0xdead"}},
+                        {{"type": "duration", "value": 60}},
+                        {{"type": "option", "value": null}}
+                    ]}}],
+                    ["owner", {{"type": "u64", "value": 18446744073709551615}}]
+                ]}}]"#
+            ),
+        );
+        let error = ScVal::Error(ScError::Contract(3));
+        let code = ScVal::Error(ScError::Value(ScErrorCode::InvalidInput));
+        for val in [built, error, code] {
+            let typed = scval_to_typed_json(&val);
+            assert_eq!(
+                parse_to_scval(&parser, &Value::Array(vec![typed.clone()]).to_string()),
+                val,
+                "{}",
+                typed
+            );
+        }
+        assert_eq!(
+            scval_to_typed_json(&ScVal::I128(Int128Parts { hi: 0, lo: 1 })),
+            serde_json::json!({"type": "i128", "value": "1"})
+        );
+    }
+
+    #[test]
+    fn test_timepoints_and_durations_from_seconds_or_text() {
+        let parser = create_parser();
+        for value in ["1735689600", r#""1735689600""#, r#""2025-01-01T00:00:00Z""#] {
+            let json = format!(r#"[{{"type": "timepoint", "value": {}}}]"#, value);
+            assert_eq!(
+                parse_to_scval(&parser, &json),
+                ScVal::Timepoint(TimePoint(1_735_689_600)),
+                "{}",
+                value
+            );
+        }
+        for value in ["129600", r#""1d12h""#] {
+            let json = format!(r#"[{{"type": "duration", "value": {}}}]"#, value);
+            assert_eq!(
+                parse_to_scval(&parser, &json),
+                ScVal::Duration(Duration(129_600)),
+                "{}",
+                value
+            );
+        }
+        // Values past 56 bits are host objects rather than small values.
+        let json = format!(r#"[{{"type": "timepoint", "value": {}}}]"#, u64::MAX);
+        assert_eq!(
+            parse_to_scval(&parser, &json),
+            ScVal::Timepoint(TimePoint(u64::MAX))
+        );
+        assert_eq!(
+            spec_scval(&parser, "Duration", r#""2h""#),
+            ScVal::Duration(Duration(7_200))
+        );
+    }
+
+    #[test]
+    fn test_time_values_out_of_range_fail_at_parse_time() {
+        let parser = create_parser();
+        let cases = [
+            (
+                r#"{"type": "duration", "value": -5}"#,
+                "Value out of range for type duration: -5",
+            ),
+            (
+                r#"{"type": "duration", "value": "-1d"}"#,
+                "is negative; durations cannot be",
+            ),
+            (
+                r#"{"type": "timepoint", "value": "18446744073709551616"}"#,
+                "Value out of range for type timepoint: 18446744073709551616",
+            ),
+            (
+                r#"{"type": "timepoint", "value": 1e20}"#,
+                "Value out of range for type timepoint",
+            ),
+            (
+                r#"{"type": "timepoint", "value": "1969-12-31T23:59:59Z"}"#,
+                "before the Unix epoch",
+            ),
+            (
+                r#"{"type": "timepoint", "value": true}"#,
+                "expected timepoint (Unix seconds or an RFC 3339 date)",
+            ),
+        ];
+        for (json, expected) in cases {
+            let err = parser
+                .parse_args_string(&format!("[{}]", json))
+                .unwrap_err()
+                .to_string();
+            assert!(err.contains(expected), "{}: {}", json, err);
+        }
+    }
 }
@@ -0,0 +1,325 @@
+//! On-disk cache of per-contract artifacts keyed by WASM hash.
+//!
+//! Every CLI invocation re-parses the export section, the contract spec and
+//! the name section of the contract it runs. None of that depends on anything
+//! but the WASM bytes, so the results are written to
+//! `~/.soroban-debug/cache/<sha256>.json` and reused by later processes.
+//! `SOROBAN_DEBUG_NO_CACHE=1` keeps them in memory only.
+//!
+//! The embedded VM (wasmi) has no serializable compiled-module format, so
+//! compiled code is not cached; only parse results are.
+
+use crate::utils::wasm::{ContractFunctionSignature, TypeDefinition};
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use wasmparser::{Parser, Payload};
+
+/// Bumped whenever [`ContractArtifacts`] changes shape; older entries are ignored.
+pub const ARTIFACT_CACHE_VERSION: u32 = 2;
+
+/// Environment variable overriding the cache directory.
+pub const CACHE_DIR_ENV: &str = "SOROBAN_DEBUG_CACHE_DIR";
+
+/// Environment variable that disables the on-disk cache when set to `1`.
+pub const NO_CACHE_ENV: &str = "SOROBAN_DEBUG_NO_CACHE";
+
+/// Parse results for one contract that are reusable across processes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractArtifacts {
+    pub version: u32,
+    pub wasm_hash: String,
+    /// Names of exported functions.
+    pub exports: Vec<String>,
+    /// Contract spec signatures, empty if the contract has no spec.
+    pub signatures: Vec<ContractFunctionSignature>,
+    /// User-defined types from the contract spec, for building arguments.
+    pub types: Vec<TypeDefinition>,
+    /// Function index to name mapping from the `name` custom section.
+    pub function_names: BTreeMap<u32, String>,
+}
+
+impl ContractArtifacts {
+    /// Parse all artifacts from the WASM bytes.
+    pub fn compute(wasm_bytes: &[u8], wasm_hash: String) -> Result<Self> {
+        Ok(Self {
+            version: ARTIFACT_CACHE_VERSION,
+            wasm_hash,
+            exports: crate::utils::wasm::parse_functions(wasm_bytes)?,
+            signatures: crate::utils::wasm::parse_function_signatures(wasm_bytes)?,
+            types: crate::utils::wasm::parse_type_definitions(wasm_bytes)?,
+            function_names: parse_function_names(wasm_bytes)?,
+        })
+    }
+
+    pub fn signature(&self, function: &str) -> Option<&ContractFunctionSignature> {
+        self.signatures.iter().find(|sig| sig.name == function)
+    }
+}
+
+/// Size summary of the cache directory.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub total_bytes: u64,
+}
+
+/// A directory of cached [`ContractArtifacts`].
+#[derive(Debug, Clone)]
+pub struct ArtifactCache {
+    dir: PathBuf,
+}
+
+impl ArtifactCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `$SOROBAN_DEBUG_CACHE_DIR`, or `~/.soroban-debug/cache`.
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Ok(dir) = std::env::var(CACHE_DIR_ENV) {
+            return Some(PathBuf::from(dir));
+        }
+        dirs::home_dir().map(|home| home.join(".soroban-debug").join("cache"))
+    }
+
+    /// The cache at [`ArtifactCache::default_dir`], unless disabled via `SOROBAN_DEBUG_NO_CACHE`.
+    pub fn open_default() -> Option<Self> {
+        if std::env::var(NO_CACHE_ENV).is_ok_and(|v| v == "1") {
+            return None;
+        }
+        Self::default_dir().map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn entry_path(&self, wasm_hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", wasm_hash))
+    }
+
+    /// Load cached artifacts. Missing, corrupt or outdated entries are a miss.
+    pub fn load(&self, wasm_hash: &str) -> Option<ContractArtifacts> {
+        let content = fs::read_to_string(self.entry_path(wasm_hash)).ok()?;
+        let artifacts: ContractArtifacts = serde_json::from_str(&content).ok()?;
+        (artifacts.version == ARTIFACT_CACHE_VERSION && artifacts.wasm_hash == wasm_hash)
+            .then_some(artifacts)
+    }
+
+    /// Write artifacts atomically so concurrent processes never see a partial file.
+    pub fn store(&self, artifacts: &ContractArtifacts) -> Result<()> {
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to create cache directory {:?}: {}",
+                self.dir, e
+            ))
+        })?;
+        let json = serde_json::to_string(artifacts).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize cache entry: {}", e))
+        })?;
+        let final_path = self.entry_path(&artifacts.wasm_hash);
+        let tmp_path = self.dir.join(format!(
+            "{}.{}.tmp",
+            artifacts.wasm_hash,
+            std::process::id()
+        ));
+        fs::write(&tmp_path, json)
+            .and_then(|_| fs::rename(&tmp_path, &final_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp_path);
+                DebuggerError::FileError(format!(
+                    "Failed to write cache entry {:?}: {}",
+                    final_path, e
+                ))
+                .into()
+            })
+    }
+
+    /// Return cached artifacts for `wasm_bytes`, computing and storing them on a miss.
+    pub fn get_or_compute(&self, wasm_bytes: &[u8]) -> Result<ContractArtifacts> {
+        let wasm_hash = hash_wasm(wasm_bytes);
+        if let Some(hit) = self.load(&wasm_hash) {
+            return Ok(hit);
+        }
+        let artifacts = ContractArtifacts::compute(wasm_bytes, wasm_hash)?;
+        // A read-only or full cache directory must never fail a run.
+        if let Err(e) = self.store(&artifacts) {
+            tracing::debug!("Skipping artifact cache write: {}", e);
+        }
+        Ok(artifacts)
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        dir_stats(&self.dir)
+    }
+
+    /// Delete every cache entry, returning what was removed.
+    ///
+    /// Only cache files are removed, since `SOROBAN_DEBUG_CACHE_DIR` may point
+    /// at a directory that holds other data.
+    pub fn clear(&self) -> Result<CacheStats> {
+        clear_dir(&self.dir)
+    }
+}
+
+/// Count the `.json` cache entries directly inside `dir`.
+pub(crate) fn dir_stats(dir: &Path) -> CacheStats {
+    let mut stats = CacheStats::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return stats;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            stats.entries += 1;
+            stats.total_bytes += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+    stats
+}
+
+/// Remove the `.json` entries and leftover `.tmp` files directly inside `dir`.
+pub(crate) fn clear_dir(dir: &Path) -> Result<CacheStats> {
+    let stats = dir_stats(dir);
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(stats);
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|ext| ext == "json" || ext == "tmp")
+        {
+            fs::remove_file(&path).map_err(|e| {
+                DebuggerError::FileError(format!("Failed to remove cache entry {:?}: {}", path, e))
+            })?;
+        }
+    }
+    Ok(stats)
+}
+
+fn memory_cache() -> &'static Mutex<HashMap<String, Arc<ContractArtifacts>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Arc<ContractArtifacts>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Artifacts for `wasm_bytes`, looked up in memory, then on disk, then computed.
+pub fn artifacts_for(wasm_bytes: &[u8]) -> Result<Arc<ContractArtifacts>> {
+    let wasm_hash = hash_wasm(wasm_bytes);
+    if let Some(hit) = memory_cache()
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(&wasm_hash).cloned())
+    {
+        return Ok(hit);
+    }
+
+    let artifacts = match ArtifactCache::open_default() {
+        Some(cache) => cache.get_or_compute(wasm_bytes)?,
+        None => ContractArtifacts::compute(wasm_bytes, wasm_hash.clone())?,
+    };
+    let artifacts = Arc::new(artifacts);
+    if let Ok(mut cache) = memory_cache().lock() {
+        cache.insert(wasm_hash, Arc::clone(&artifacts));
+    }
+    Ok(artifacts)
+}
+
+fn hash_wasm(wasm_bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(wasm_bytes))
+}
+
+/// Read the function names subsection of the `name` custom section.
+fn parse_function_names(wasm_bytes: &[u8]) -> Result<BTreeMap<u32, String>> {
+    let mut names = BTreeMap::new();
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        let Payload::CustomSection(reader) = payload
+            .map_err(|e| DebuggerError::WasmLoadError(format!("Failed to parse WASM: {}", e)))?
+        else {
+            continue;
+        };
+        if reader.name() != "name" {
+            continue;
+        }
+        let subsections = wasmparser::NameSectionReader::new(reader.data(), reader.data_offset());
+        // A malformed name section only loses symbolication, so stop quietly.
+        for subsection in subsections.flatten() {
+            if let wasmparser::Name::Function(map) = subsection {
+                for naming in map.into_iter().flatten() {
+                    names.insert(naming.index, naming.name.to_string());
+                }
+            }
+        }
+    }
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Module with one exported function `run` and no spec.
+    fn make_wasm() -> Vec<u8> {
+        vec![
+            0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+            0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type: () -> ()
+            0x03, 0x02, 0x01, 0x00, // function: type 0
+            0x07, 0x07, 0x01, 0x03, b'r', b'u', b'n', 0x00, 0x00, // export "run"
+            0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b, // code: empty body
+        ]
+    }
+
+    #[test]
+    fn second_lookup_is_served_from_disk() {
+        let dir = TempDir::new().unwrap();
+        let cache = ArtifactCache::new(dir.path().join("cache"));
+        let wasm = make_wasm();
+
+        let cold = cache.get_or_compute(&wasm).unwrap();
+        assert_eq!(cold.exports, vec!["run".to_string()]);
+        assert_eq!(cache.stats().entries, 1);
+
+        let warm = cache
+            .load(&hash_wasm(&wasm))
+            .expect("entry should be cached");
+        assert_eq!(warm, cold);
+    }
+
+    #[test]
+    fn outdated_or_corrupt_entries_are_ignored() {
+        let dir = TempDir::new().unwrap();
+        let cache = ArtifactCache::new(dir.path().to_path_buf());
+        let wasm = make_wasm();
+        let hash = hash_wasm(&wasm);
+
+        fs::write(cache.entry_path(&hash), "{not json").unwrap();
+        assert!(cache.load(&hash).is_none());
+
+        let mut stale = ContractArtifacts::compute(&wasm, hash.clone()).unwrap();
+        stale.version = ARTIFACT_CACHE_VERSION + 1;
+        fs::write(
+            cache.entry_path(&hash),
+            serde_json::to_string(&stale).unwrap(),
+        )
+        .unwrap();
+        assert!(cache.load(&hash).is_none());
+    }
+
+    #[test]
+    fn clear_removes_all_entries() {
+        let dir = TempDir::new().unwrap();
+        let cache = ArtifactCache::new(dir.path().join("cache"));
+        cache.get_or_compute(&make_wasm()).unwrap();
+
+        let removed = cache.clear().unwrap();
+        assert_eq!(removed.entries, 1);
+        assert!(removed.total_bytes > 0);
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+}
@@ -0,0 +1,322 @@
+//! Locate, and optionally build, a contract crate with cargo.
+//!
+//! Lets `run` take `--package <crate>` or a `--contract` directory holding a
+//! `Cargo.toml` instead of a path under `target/`. The WASM path is derived
+//! from `cargo metadata`, so custom target directories and workspace layouts
+//! work. Cargo is taken from `$CARGO` when set (as it is under `cargo run`),
+//! otherwise from `PATH`.
+
+use crate::{DebuggerError, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Target used by `--build`.
+pub const BUILD_TARGET: &str = "wasm32-unknown-unknown";
+
+/// Targets searched for an existing artifact when not building.
+const WASM_TARGETS: &[&str] = &["wasm32-unknown-unknown", "wasm32v1-none"];
+
+/// A contract WASM resolved from a cargo package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CargoContract {
+    pub package: String,
+    pub wasm_path: PathBuf,
+}
+
+/// How to find the contract: `contract` is a crate or workspace directory
+/// (or its `Cargo.toml`), `package` selects a workspace member.
+#[derive(Debug, Clone)]
+pub struct CargoRequest<'a> {
+    pub contract: Option<&'a Path>,
+    pub package: Option<&'a str>,
+    pub build: bool,
+    pub profile: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    packages: Vec<Package>,
+    workspace_members: Vec<String>,
+    target_directory: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+    name: String,
+    id: String,
+    manifest_path: PathBuf,
+    targets: Vec<Target>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Target {
+    name: String,
+    crate_types: Vec<String>,
+}
+
+/// Whether `path` names a cargo project rather than a WASM file.
+pub fn is_cargo_project(path: &Path) -> bool {
+    if path.file_name().is_some_and(|name| name == "Cargo.toml") {
+        return path.is_file();
+    }
+    path.is_dir() && path.join("Cargo.toml").is_file()
+}
+
+/// Resolve (and with `build`, first compile) the contract WASM.
+pub fn resolve_contract(request: &CargoRequest<'_>) -> Result<CargoContract> {
+    let manifest = request.contract.map(manifest_path);
+    let metadata = load_metadata(manifest.as_deref())?;
+    let package = select_package(&metadata, manifest.as_deref(), request.package)?;
+    let artifact = cdylib_artifact(package)?;
+    let profile_dir = profile_dir(request.profile);
+
+    if request.build {
+        build(package, request.profile)?;
+        let wasm_path = metadata
+            .target_directory
+            .join(BUILD_TARGET)
+            .join(profile_dir)
+            .join(&artifact);
+        if !wasm_path.is_file() {
+            return Err(DebuggerError::BuildError(format!(
+                "cargo succeeded but {:?} was not produced",
+                wasm_path
+            ))
+            .into());
+        }
+        return Ok(CargoContract {
+            package: package.name.clone(),
+            wasm_path,
+        });
+    }
+
+    // Several targets may have been built; the most recent one is what the
+    // user is working on.
+    let wasm_path = WASM_TARGETS
+        .iter()
+        .map(|target| {
+            metadata
+                .target_directory
+                .join(target)
+                .join(profile_dir)
+                .join(&artifact)
+        })
+        .filter_map(|path| {
+            let modified = path.metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+        .ok_or_else(|| {
+            DebuggerError::WasmLoadError(format!(
+                "No {} found for package `{}` under {:?} ({} profile). Pass --build to build it.",
+                artifact, package.name, metadata.target_directory, request.profile
+            ))
+        })?;
+
+    Ok(CargoContract {
+        package: package.name.clone(),
+        wasm_path,
+    })
+}
+
+fn cargo() -> Command {
+    Command::new(std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()))
+}
+
+fn manifest_path(contract: &Path) -> PathBuf {
+    if contract
+        .file_name()
+        .is_some_and(|name| name == "Cargo.toml")
+    {
+        contract.to_path_buf()
+    } else {
+        contract.join("Cargo.toml")
+    }
+}
+
+fn load_metadata(manifest: Option<&Path>) -> Result<Metadata> {
+    let mut command = cargo();
+    command.args(["metadata", "--format-version", "1", "--no-deps"]);
+    if let Some(manifest) = manifest {
+        command.arg("--manifest-path").arg(manifest);
+    }
+    let output = command
+        .output()
+        .map_err(|e| DebuggerError::BuildError(format!("Failed to run `cargo metadata`: {}", e)))?;
+    if !output.status.success() {
+        return Err(DebuggerError::BuildError(format!(
+            "`cargo metadata` failed:\n{}",
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ))
+        .into());
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| {
+        DebuggerError::BuildError(format!("Unreadable `cargo metadata` output: {}", e)).into()
+    })
+}
+
+/// Pick the package named by `--package`, else the crate at `manifest`, else
+/// the workspace's only contract crate.
+fn select_package<'m>(
+    metadata: &'m Metadata,
+    manifest: Option<&Path>,
+    name: Option<&str>,
+) -> Result<&'m Package> {
+    let members: Vec<&Package> = metadata
+        .packages
+        .iter()
+        .filter(|package| metadata.workspace_members.contains(&package.id))
+        .collect();
+
+    if let Some(name) = name {
+        return members
+            .iter()
+            .find(|package| package.name == name)
+            .copied()
+            .ok_or_else(|| {
+                DebuggerError::BuildError(format!(
+                    "No package named `{}` in the workspace (members: {})",
+                    name,
+                    member_names(&members)
+                ))
+                .into()
+            });
+    }
+
+    if let Some(manifest) = manifest.and_then(|path| path.canonicalize().ok()) {
+        if let Some(package) = members
+            .iter()
+            .find(|package| package.manifest_path.canonicalize().ok().as_ref() == Some(&manifest))
+        {
+            return Ok(*package);
+        }
+    }
+
+    let contracts: Vec<&Package> = members
+        .iter()
+        .copied()
+        .filter(|package| cdylib_artifact(package).is_ok())
+        .collect();
+    match contracts.as_slice() {
+        [package] => Ok(*package),
+        [] => Err(
+            DebuggerError::BuildError("No workspace member has a cdylib target".to_string()).into(),
+        ),
+        _ => Err(DebuggerError::BuildError(format!(
+            "The workspace has several contract crates; choose one with --package ({})",
+            member_names(&contracts)
+        ))
+        .into()),
+    }
+}
+
+fn member_names(packages: &[&Package]) -> String {
+    packages
+        .iter()
+        .map(|package| package.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// File name of the package's WASM, e.g. `my_token.wasm` for `my-token`.
+fn cdylib_artifact(package: &Package) -> Result<String> {
+    package
+        .targets
+        .iter()
+        .find(|target| target.crate_types.iter().any(|t| t == "cdylib"))
+        .map(|target| format!("{}.wasm", target.name.replace('-', "_")))
+        .ok_or_else(|| {
+            DebuggerError::BuildError(format!(
+                "Package `{}` has no cdylib target; add `crate-type = [\"cdylib\"]` under [lib]",
+                package.name
+            ))
+            .into()
+        })
+}
+
+/// Directory cargo writes a profile's artifacts to.
+fn profile_dir(profile: &str) -> &str {
+    match profile {
+        "dev" | "test" => "debug",
+        "bench" => "release",
+        other => other,
+    }
+}
+
+fn build(package: &Package, profile: &str) -> Result<()> {
+    let output = cargo()
+        .args(["build", "--target", BUILD_TARGET, "--profile", profile])
+        .arg("--package")
+        .arg(&package.name)
+        .arg("--manifest-path")
+        .arg(&package.manifest_path)
+        .output()
+        .map_err(|e| DebuggerError::BuildError(format!("Failed to run `cargo build`: {}", e)))?;
+    if !output.status.success() {
+        return Err(DebuggerError::BuildError(format!(
+            "`cargo build` for `{}` failed:\n{}",
+            package.name,
+            String::from_utf8_lossy(&output.stderr).trim_end()
+        ))
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata(packages: &[(&str, &[&str])]) -> Metadata {
+        Metadata {
+            packages: packages
+                .iter()
+                .map(|(name, crate_types)| Package {
+                    name: name.to_string(),
+                    id: format!("{} 0.1.0", name),
+                    manifest_path: PathBuf::from(format!("/ws/{}/Cargo.toml", name)),
+                    targets: vec![Target {
+                        name: name.to_string(),
+                        crate_types: crate_types.iter().map(|t| t.to_string()).collect(),
+                    }],
+                })
+                .collect(),
+            workspace_members: packages
+                .iter()
+                .map(|(name, _)| format!("{} 0.1.0", name))
+                .collect(),
+            target_directory: PathBuf::from("/ws/target"),
+        }
+    }
+
+    #[test]
+    fn single_contract_crate_is_selected_without_package() {
+        let metadata = metadata(&[("shared-types", &["lib"]), ("my-token", &["cdylib"])]);
+        let package = select_package(&metadata, None, None).unwrap();
+        assert_eq!(package.name, "my-token");
+        assert_eq!(cdylib_artifact(package).unwrap(), "my_token.wasm");
+    }
+
+    #[test]
+    fn several_contract_crates_require_package() {
+        let metadata = metadata(&[("token", &["cdylib"]), ("vault", &["cdylib", "rlib"])]);
+        let err = select_package(&metadata, None, None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("token, vault"), "{}", err);
+        assert_eq!(
+            select_package(&metadata, None, Some("vault")).unwrap().name,
+            "vault"
+        );
+        assert!(select_package(&metadata, None, Some("missing")).is_err());
+    }
+
+    #[test]
+    fn profile_directories() {
+        assert_eq!(profile_dir("release"), "release");
+        assert_eq!(profile_dir("dev"), "debug");
+        assert_eq!(profile_dir("release-with-logs"), "release-with-logs");
+    }
+}
@@ -0,0 +1,742 @@
+//! Domain-type conversions for argument literals.
+//!
+//! String arguments such as `"100.5_USDC"`, `"7_days"` or `"12.5%"` are
+//! rewritten into typed integer annotations before the JSON reaches
+//! [`ArgumentParser`](crate::utils::ArgumentParser):
+//!
+//! | Literal        | Rule                          | Sent                                |
+//! |----------------|-------------------------------|-------------------------------------|
+//! | `100.5_USDC`   | `[conversions.USDC]`, 7 dp    | `{"type":"i128","value":1005000000}`|
+//! | `7_days`       | built-in, ×86400              | `{"type":"u64","value":604800}`     |
+//! | `1.5_hours`    | built-in, ×3600               | `{"type":"u64","value":5400}`       |
+//! | `12.5%`        | built-in, basis points (×100) | `{"type":"u32","value":1250}`       |
+//!
+//! Fractions that do not divide evenly are rounded half away from zero.
+//! Suffixes match case-insensitively; a literal matching more than one rule
+//! is rejected rather than guessed.
+//!
+//! When the contract spec describes the function, only strings passed where
+//! it expects an integer are rewritten, so a `String` or `Symbol` argument
+//! such as `"2_hours"` is sent as written. The annotation then takes the
+//! declared type rather than the rule's: `"5%"` for an `i128` parameter is
+//! sent as `{"type":"i128","value":500}` and `"7_days"` for a `Duration` as
+//! `{"type":"duration","value":604800}`, and the value must fit that type.
+
+use crate::config::ConversionConfig;
+use crate::utils::wasm::{FunctionParam, TypeDefinition};
+use crate::{DebuggerError, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Integer types a conversion can target. Rules name one of the first six;
+/// the rest come from the declared parameter type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConversionTarget {
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    U256,
+    I256,
+    Timepoint,
+    Duration,
+}
+
+impl ConversionTarget {
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "u32" => Some(Self::U32),
+            "i32" => Some(Self::I32),
+            "u64" => Some(Self::U64),
+            "i64" => Some(Self::I64),
+            "u128" => Some(Self::U128),
+            "i128" => Some(Self::I128),
+            _ => None,
+        }
+    }
+
+    /// The target for a spec type name, if that type takes an integer.
+    fn from_spec(ty: &str) -> Option<Self> {
+        match ty {
+            "U32" => Some(Self::U32),
+            "I32" => Some(Self::I32),
+            "U64" => Some(Self::U64),
+            "I64" => Some(Self::I64),
+            "U128" => Some(Self::U128),
+            "I128" => Some(Self::I128),
+            "U256" => Some(Self::U256),
+            "I256" => Some(Self::I256),
+            "Timepoint" => Some(Self::Timepoint),
+            "Duration" => Some(Self::Duration),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::U32 => "u32",
+            Self::I32 => "i32",
+            Self::U64 => "u64",
+            Self::I64 => "i64",
+            Self::U128 => "u128",
+            Self::I128 => "i128",
+            Self::U256 => "u256",
+            Self::I256 => "i256",
+            Self::Timepoint => "timepoint",
+            Self::Duration => "duration",
+        }
+    }
+
+    /// Inclusive bounds as i128; u128 and u256 are capped at i128::MAX since
+    /// that is the widest intermediate this layer computes with.
+    fn bounds(self) -> (i128, i128) {
+        match self {
+            Self::U32 => (0, u32::MAX as i128),
+            Self::I32 => (i32::MIN as i128, i32::MAX as i128),
+            Self::U64 | Self::Timepoint | Self::Duration => (0, u64::MAX as i128),
+            Self::I64 => (i64::MIN as i128, i64::MAX as i128),
+            Self::U128 | Self::U256 => (0, i128::MAX),
+            Self::I128 | Self::I256 => (i128::MIN, i128::MAX),
+        }
+    }
+}
+
+/// One suffix → integer scaling rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionRule {
+    pub suffix: String,
+    /// Multiplier applied to the literal's numeric part.
+    pub factor: i128,
+    pub target: ConversionTarget,
+    /// Human-readable description shown in errors and verbose output.
+    pub description: String,
+}
+
+/// A literal that was rewritten, for echoing in verbose output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedConversion {
+    pub literal: String,
+    pub rule: String,
+    pub raw: i128,
+    pub target: ConversionTarget,
+}
+
+impl std::fmt::Display for AppliedConversion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} -> {} ({}, {})",
+            self.literal,
+            self.raw,
+            self.target.name(),
+            self.rule
+        )
+    }
+}
+
+/// The set of active conversion rules.
+#[derive(Debug, Clone)]
+pub struct Conversions {
+    rules: Vec<ConversionRule>,
+}
+
+impl Default for Conversions {
+    fn default() -> Self {
+        Self::builtin()
+    }
+}
+
+impl Conversions {
+    /// Built-in `_days`, `_hours` and percentage rules.
+    pub fn builtin() -> Self {
+        Self {
+            rules: vec![
+                ConversionRule {
+                    suffix: "days".to_string(),
+                    factor: 86_400,
+                    target: ConversionTarget::U64,
+                    description: "days to seconds (x86400)".to_string(),
+                },
+                ConversionRule {
+                    suffix: "hours".to_string(),
+                    factor: 3_600,
+                    target: ConversionTarget::U64,
+                    description: "hours to seconds (x3600)".to_string(),
+                },
+                ConversionRule {
+                    suffix: "%".to_string(),
+                    factor: 100,
+                    target: ConversionTarget::U32,
+                    description: "percent to basis points (x100)".to_string(),
+                },
+            ],
+        }
+    }
+
+    /// Built-ins plus the `[conversions]` table from the config file.
+    pub fn from_config(config: &BTreeMap<String, ConversionConfig>) -> Result<Self> {
+        let mut conversions = Self::builtin();
+        for (suffix, rule) in config {
+            let target = ConversionTarget::parse(&rule.target_type).ok_or_else(|| {
+                DebuggerError::InvalidArguments(format!(
+                    "Conversion '{}' has unsupported type '{}' (expected u32, i32, u64, i64, u128 or i128)",
+                    suffix, rule.target_type
+                ))
+            })?;
+            let factor = 10i128.checked_pow(rule.decimals).ok_or_else(|| {
+                DebuggerError::InvalidArguments(format!(
+                    "Conversion '{}' has too many decimals: {}",
+                    suffix, rule.decimals
+                ))
+            })?;
+            conversions.rules.push(ConversionRule {
+                suffix: suffix.clone(),
+                factor,
+                target,
+                description: format!("{} with {} decimals", suffix, rule.decimals),
+            });
+        }
+        Ok(conversions)
+    }
+
+    /// Convert a single literal. Returns `Ok(None)` if it is not a conversion literal.
+    pub fn convert_literal(&self, literal: &str) -> Result<Option<AppliedConversion>> {
+        self.convert_literal_as(literal, None)
+    }
+
+    /// Convert a single literal for a parameter declared as `declared`, or
+    /// as the rule's own type when there is none.
+    fn convert_literal_as(
+        &self,
+        literal: &str,
+        declared: Option<ConversionTarget>,
+    ) -> Result<Option<AppliedConversion>> {
+        let Some((number, suffix)) = split_literal(literal) else {
+            return Ok(None);
+        };
+
+        let matches: Vec<&ConversionRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.suffix.eq_ignore_ascii_case(suffix))
+            .collect();
+        let rule = match matches.as_slice() {
+            [] => return Ok(None),
+            [rule] => *rule,
+            many => {
+                let candidates: Vec<&str> = many.iter().map(|r| r.description.as_str()).collect();
+                return Err(DebuggerError::InvalidArguments(format!(
+                    "Ambiguous suffix '{}' in '{}': matches {}",
+                    suffix,
+                    literal,
+                    candidates.join(", ")
+                ))
+                .into());
+            }
+        };
+
+        let target = declared.unwrap_or(rule.target);
+        let raw = scale(number, rule.factor).ok_or_else(|| overflow(literal, rule, target))?;
+        let (min, max) = target.bounds();
+        if raw < min || raw > max {
+            return Err(overflow(literal, rule, target));
+        }
+
+        Ok(Some(AppliedConversion {
+            literal: literal.to_string(),
+            rule: rule.description.clone(),
+            raw,
+            target,
+        }))
+    }
+
+    /// Rewrite the conversion literals in an argument JSON document.
+    ///
+    /// With a `spec`, a literal is rewritten only where the function's
+    /// parameter types put an integer, and is annotated with that type;
+    /// without one, every string is a candidate. Strings inside type annotations are left alone either way,
+    /// so an explicit `{"type":"string","value":"5_days"}` is still sent
+    /// verbatim.
+    pub fn apply(
+        &self,
+        args_json: &str,
+        spec: Option<&ArgumentSpec<'_>>,
+    ) -> Result<(String, Vec<AppliedConversion>)> {
+        let mut value: Value = serde_json::from_str(args_json).map_err(|e| {
+            DebuggerError::InvalidArguments(format!("Invalid JSON in --args: {}", e))
+        })?;
+        let mut applied = Vec::new();
+        match spec {
+            Some(spec) => match &mut value {
+                // An array lists the arguments; anything else is the first one.
+                Value::Array(items) => {
+                    for (item, param) in items.iter_mut().zip(spec.params) {
+                        self.rewrite_typed(item, &param.type_name, spec.types, &mut applied)?;
+                    }
+                }
+                value => {
+                    if let Some(param) = spec.params.first() {
+                        self.rewrite_typed(value, &param.type_name, spec.types, &mut applied)?;
+                    }
+                }
+            },
+            None => self.rewrite(&mut value, &mut applied)?,
+        }
+        if applied.is_empty() {
+            return Ok((args_json.to_string(), applied));
+        }
+        let json = serde_json::to_string(&value).map_err(|e| {
+            DebuggerError::ExecutionError(format!("Failed to serialize converted arguments: {}", e))
+        })?;
+        Ok((json, applied))
+    }
+
+    fn rewrite(&self, value: &mut Value, applied: &mut Vec<AppliedConversion>) -> Result<()> {
+        match value {
+            Value::String(_) => self.convert_string(value, None, applied)?,
+            Value::Array(items) => {
+                for item in items {
+                    self.rewrite(item, applied)?;
+                }
+            }
+            Value::Object(obj) => {
+                if obj.contains_key("type") && obj.contains_key("value") {
+                    return Ok(());
+                }
+                for item in obj.values_mut() {
+                    self.rewrite(item, applied)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Rewrite the literals in `value`, an argument of spec type `ty`.
+    fn rewrite_typed(
+        &self,
+        value: &mut Value,
+        ty: &str,
+        types: &[TypeDefinition],
+        applied: &mut Vec<AppliedConversion>,
+    ) -> Result<()> {
+        if let Value::Object(obj) = value {
+            if obj.contains_key("type") && obj.contains_key("value") {
+                return Ok(());
+            }
+        }
+        if let Some([inner]) = generic_args(ty, "Option").as_deref() {
+            return self.rewrite_typed(value, inner, types, applied);
+        }
+        if let (Value::String(_), Some(target)) = (&*value, ConversionTarget::from_spec(ty)) {
+            return self.convert_string(value, Some(target), applied);
+        }
+        match value {
+            Value::Array(items) => {
+                let element_types: Vec<&str> =
+                    if let Some([element]) = generic_args(ty, "Vec").as_deref() {
+                        vec![*element; items.len()]
+                    } else if let Some(tuple) = generic_args(ty, "Tuple") {
+                        tuple
+                    } else if let Some(TypeDefinition::Struct { fields, .. }) =
+                        types.iter().find(|def| def.name() == ty)
+                    {
+                        fields.iter().map(|(_, field)| field.as_str()).collect()
+                    } else {
+                        Vec::new()
+                    };
+                for (item, element) in items.iter_mut().zip(element_types) {
+                    self.rewrite_typed(item, element, types, applied)?;
+                }
+            }
+            Value::Object(obj) => {
+                if let Some([_, value_type]) = generic_args(ty, "Map").as_deref() {
+                    for item in obj.values_mut() {
+                        self.rewrite_typed(item, value_type, types, applied)?;
+                    }
+                } else if let Some(TypeDefinition::Struct { fields, .. }) =
+                    types.iter().find(|def| def.name() == ty)
+                {
+                    for (name, field) in fields {
+                        if let Some(item) = obj.get_mut(name) {
+                            self.rewrite_typed(item, field, types, applied)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Replace the string `value` with its typed annotation if it is a
+    /// conversion literal, typed as `declared` when the spec gives one.
+    fn convert_string(
+        &self,
+        value: &mut Value,
+        declared: Option<ConversionTarget>,
+        applied: &mut Vec<AppliedConversion>,
+    ) -> Result<()> {
+        let Value::String(s) = value else {
+            return Ok(());
+        };
+        if let Some(conversion) = self.convert_literal_as(s, declared)? {
+            *value = typed_value(&conversion);
+            applied.push(conversion);
+        }
+        Ok(())
+    }
+}
+
+/// The spec types the arguments of one call are matched against.
+#[derive(Debug, Clone, Copy)]
+pub struct ArgumentSpec<'a> {
+    /// The function's parameters, in order.
+    pub params: &'a [FunctionParam],
+    /// The contract's structs, unions and enums.
+    pub types: &'a [TypeDefinition],
+}
+
+/// The type arguments of `ty` if it is `name<...>`, split at top-level commas.
+fn generic_args<'a>(ty: &'a str, name: &str) -> Option<Vec<&'a str>> {
+    let inner = ty
+        .strip_prefix(name)?
+        .strip_prefix('<')?
+        .strip_suffix('>')?;
+    let mut args = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    args.push(inner[start..].trim());
+    Some(args)
+}
+
+/// Split `12.5_USDC` into (`12.5`, `USDC`) and `12.5%` into (`12.5`, `%`).
+fn split_literal(literal: &str) -> Option<(&str, &str)> {
+    let (number, suffix) = if let Some(number) = literal.strip_suffix('%') {
+        (number, "%")
+    } else {
+        let idx = literal.find('_')?;
+        (&literal[..idx], &literal[idx + 1..])
+    };
+
+    let digits = number.strip_prefix('-').unwrap_or(number);
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+    let numeric = !int_part.is_empty()
+        && int_part.bytes().all(|b| b.is_ascii_digit())
+        && frac_part.bytes().all(|b| b.is_ascii_digit())
+        && !(digits.contains('.') && frac_part.is_empty());
+    let valid_suffix = suffix == "%"
+        || (suffix
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic())
+            && suffix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_'));
+
+    (numeric && valid_suffix).then_some((number, suffix))
+}
+
+/// `number * factor`, rounding half away from zero. `None` on i128 overflow.
+fn scale(number: &str, factor: i128) -> Option<i128> {
+    let negative = number.starts_with('-');
+    let digits = number.trim_start_matches('-');
+    let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+
+    let mut mantissa: i128 = 0;
+    for b in int_part.bytes().chain(frac_part.bytes()) {
+        mantissa = mantissa.checked_mul(10)?.checked_add((b - b'0') as i128)?;
+    }
+    let divisor = 10i128.checked_pow(frac_part.len() as u32)?;
+    let product = mantissa.checked_mul(factor)?;
+
+    let mut quotient = product / divisor;
+    if (product % divisor).checked_mul(2)? >= divisor {
+        quotient = quotient.checked_add(1)?;
+    }
+    Some(if negative { -quotient } else { quotient })
+}
+
+fn overflow(literal: &str, rule: &ConversionRule, target: ConversionTarget) -> miette::Report {
+    let (min, max) = target.bounds();
+    DebuggerError::InvalidArguments(format!(
+        "Conversion of '{}' using rule '{}' overflows {}: valid range is {}..={}",
+        literal,
+        rule.description,
+        target.name(),
+        min,
+        max
+    ))
+    .into()
+}
+
+fn typed_value(conversion: &AppliedConversion) -> Value {
+    // Values beyond the JSON-safe 64-bit range travel as decimal strings.
+    let value = match i64::try_from(conversion.raw) {
+        Ok(n) => Value::from(n),
+        Err(_) => match u64::try_from(conversion.raw) {
+            Ok(n) => Value::from(n),
+            Err(_) => Value::String(conversion.raw.to_string()),
+        },
+    };
+    serde_json::json!({ "type": conversion.target.name(), "value": value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn with_usdc() -> Conversions {
+        let mut config = BTreeMap::new();
+        config.insert(
+            "USDC".to_string(),
+            ConversionConfig {
+                decimals: 7,
+                target_type: "i128".to_string(),
+            },
+        );
+        Conversions::from_config(&config).unwrap()
+    }
+
+    fn raw(conversions: &Conversions, literal: &str) -> i128 {
+        conversions.convert_literal(literal).unwrap().unwrap().raw
+    }
+
+    #[test]
+    fn scales_configured_and_builtin_suffixes() {
+        let conversions = with_usdc();
+        assert_eq!(raw(&conversions, "100.5_USDC"), 1_005_000_000);
+        assert_eq!(raw(&conversions, "2_days"), 172_800);
+        assert_eq!(raw(&conversions, "1.5_hours"), 5_400);
+        assert_eq!(raw(&conversions, "12.5%"), 1_250);
+        assert_eq!(raw(&conversions, "-3_usdc"), -30_000_000);
+    }
+
+    #[test]
+    fn rounds_half_away_from_zero() {
+        let conversions = with_usdc();
+        assert_eq!(raw(&conversions, "0.00000005_USDC"), 1);
+        assert_eq!(raw(&conversions, "0.00000004_USDC"), 0);
+        assert_eq!(raw(&conversions, "-0.00000005_USDC"), -1);
+        assert_eq!(raw(&conversions, "12.345%"), 1_235);
+    }
+
+    #[test]
+    fn non_literals_are_left_alone() {
+        let conversions = with_usdc();
+        for literal in [
+            "hello",
+            "user_name",
+            "5_unknown",
+            "1._days",
+            "_days",
+            "GABC",
+        ] {
+            assert!(conversions.convert_literal(literal).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn rejects_ambiguous_suffix() {
+        let mut config = BTreeMap::new();
+        config.insert(
+            "Days".to_string(),
+            ConversionConfig {
+                decimals: 0,
+                target_type: "u32".to_string(),
+            },
+        );
+        let conversions = Conversions::from_config(&config).unwrap();
+        let err = conversions.convert_literal("3_days").unwrap_err();
+        assert!(err.to_string().contains("Ambiguous suffix 'days'"));
+    }
+
+    #[test]
+    fn overflow_error_names_rule_and_boundary() {
+        let err = Conversions::builtin()
+            .convert_literal("50000000%")
+            .unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("percent to basis points"));
+        assert!(message.contains("0..=4294967295"));
+    }
+
+    #[test]
+    fn apply_rewrites_nested_literals_and_skips_annotations() {
+        let (json, applied) = with_usdc()
+            .apply(
+                r#"["100_USDC", {"ttl": "1_days"}, {"type": "string", "value": "5_days"}]"#,
+                None,
+            )
+            .unwrap();
+        assert_eq!(applied.len(), 2);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value[0],
+            serde_json::json!({"type": "i128", "value": 1_000_000_000})
+        );
+        assert_eq!(
+            value[1]["ttl"],
+            serde_json::json!({"type": "u64", "value": 86_400})
+        );
+        assert_eq!(value[2]["value"], "5_days");
+    }
+
+    #[test]
+    fn apply_with_a_spec_rewrites_integer_parameters_only() {
+        let types = vec![TypeDefinition::Struct {
+            name: "Lock".to_string(),
+            fields: vec![
+                ("label".to_string(), "String".to_string()),
+                ("ttl".to_string(), "U64".to_string()),
+            ],
+        }];
+        let params: Vec<FunctionParam> = [
+            "String",
+            "Symbol",
+            "U64",
+            "Option<U32>",
+            "Vec<U64>",
+            "Lock",
+            "Map<Symbol, U64>",
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, ty)| FunctionParam {
+            name: format!("arg{}", i),
+            type_name: ty.to_string(),
+        })
+        .collect();
+        let spec = ArgumentSpec {
+            params: &params,
+            types: &types,
+        };
+        let (json, applied) = Conversions::builtin()
+            .apply(
+                r#"["2_hours", "5%", "2_hours", "5%", ["1_days", "2_days"],
+                    {"label": "7_days", "ttl": "7_days"}, {"fee": "1_hours"}]"#,
+                Some(&spec),
+            )
+            .unwrap();
+        assert_eq!(applied.len(), 6);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0], "2_hours");
+        assert_eq!(value[1], "5%");
+        assert_eq!(value[2], serde_json::json!({"type": "u64", "value": 7_200}));
+        assert_eq!(value[3], serde_json::json!({"type": "u32", "value": 500}));
+        assert_eq!(
+            value[4][1],
+            serde_json::json!({"type": "u64", "value": 172_800})
+        );
+        assert_eq!(value[5]["label"], "7_days");
+        assert_eq!(
+            value[5]["ttl"],
+            serde_json::json!({"type": "u64", "value": 604_800})
+        );
+        assert_eq!(
+            value[6]["fee"],
+            serde_json::json!({"type": "u64", "value": 3_600})
+        );
+
+        let (json, applied) = Conversions::builtin()
+            .apply(r#""2_hours""#, Some(&spec))
+            .unwrap();
+        assert!(applied.is_empty());
+        assert_eq!(json, r#""2_hours""#);
+    }
+
+    #[test]
+    fn apply_with_a_spec_annotates_with_the_declared_type() {
+        let params: Vec<FunctionParam> = ["I128", "U32", "Duration", "Timepoint", "U256"]
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| FunctionParam {
+                name: format!("arg{}", i),
+                type_name: ty.to_string(),
+            })
+            .collect();
+        let spec = ArgumentSpec {
+            params: &params,
+            types: &[],
+        };
+        let (json, applied) = Conversions::builtin()
+            .apply(
+                r#"["5%", "7_days", "7_days", "1_hours", "1%"]"#,
+                Some(&spec),
+            )
+            .unwrap();
+        assert_eq!(applied.len(), 5);
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0], serde_json::json!({"type": "i128", "value": 500}));
+        assert_eq!(
+            value[1],
+            serde_json::json!({"type": "u32", "value": 604_800})
+        );
+        assert_eq!(
+            value[2],
+            serde_json::json!({"type": "duration", "value": 604_800})
+        );
+        assert_eq!(
+            value[3],
+            serde_json::json!({"type": "timepoint", "value": 3_600})
+        );
+        assert_eq!(value[4], serde_json::json!({"type": "u256", "value": 100}));
+    }
+
+    #[test]
+    fn apply_with_a_spec_checks_the_declared_range() {
+        let params = [FunctionParam {
+            name: "period".to_string(),
+            type_name: "U32".to_string(),
+        }];
+        let spec = ArgumentSpec {
+            params: &params,
+            types: &[],
+        };
+        let err = Conversions::builtin()
+            .apply(r#"["50000_days"]"#, Some(&spec))
+            .unwrap_err();
+        assert!(err.to_string().contains("overflows u32"), "{}", err);
+
+        let params = [FunctionParam {
+            name: "ttl".to_string(),
+            type_name: "Duration".to_string(),
+        }];
+        let spec = ArgumentSpec {
+            params: &params,
+            types: &[],
+        };
+        let err = Conversions::builtin()
+            .apply(r#"["-1_hours"]"#, Some(&spec))
+            .unwrap_err();
+        assert!(err.to_string().contains("overflows duration"), "{}", err);
+    }
+
+    #[test]
+    fn rounding_a_long_fraction_reports_overflow() {
+        let mut config = BTreeMap::new();
+        config.insert(
+            "units".to_string(),
+            ConversionConfig {
+                decimals: 0,
+                target_type: "i128".to_string(),
+            },
+        );
+        let conversions = Conversions::from_config(&config).unwrap();
+        let literal = format!("0.{}_units", "9".repeat(38));
+        let err = conversions.convert_literal(&literal).unwrap_err();
+        assert!(err.to_string().contains("overflows i128"), "{}", err);
+    }
+}
@@ -0,0 +1,104 @@
+//! `@path` values for flags that take JSON: `--args @args.json` reads the
+//! JSON from a file, and `--args @-` from stdin.
+
+use crate::{DebuggerError, Result};
+use std::io::Read;
+
+/// Replace each `@path` flag value with the contents of the file it names,
+/// or of stdin for `@-`. Plain JSON values are left alone. File contents are
+/// checked here so a syntax error names the file, line and column.
+pub fn resolve_json_flags(flags: &mut [(&str, &mut Option<String>)]) -> Result<()> {
+    let stdin_flags: Vec<&str> = flags
+        .iter()
+        .filter(|(_, value)| value.as_deref() == Some("@-"))
+        .map(|(flag, _)| *flag)
+        .collect();
+    if stdin_flags.len() > 1 {
+        return Err(DebuggerError::InvalidArguments(format!(
+            "Only one flag can read from stdin, but {} use @-",
+            stdin_flags.join(" and ")
+        ))
+        .into());
+    }
+
+    for (flag, value) in flags.iter_mut() {
+        if let Some(path) = value.as_deref().and_then(|v| v.strip_prefix('@')) {
+            **value = Some(read_json_file(flag, path)?);
+        }
+    }
+    Ok(())
+}
+
+/// The JSON text in `path`, or on stdin for `-`.
+fn read_json_file(flag: &str, path: &str) -> Result<String> {
+    let (source, text) = if path == "-" {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read {} from stdin: {}", flag, e))
+        })?;
+        ("stdin".to_string(), text)
+    } else {
+        let text = std::fs::read_to_string(path).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to read {} file {}: {}", flag, path, e))
+        })?;
+        (path.to_string(), text)
+    };
+    serde_json::from_str::<serde_json::Value>(&text).map_err(|e| {
+        DebuggerError::InvalidArguments(format!("Invalid JSON in {} file {}: {}", flag, source, e))
+    })?;
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at_path_values_are_read_and_checked() {
+        let dir = tempfile::tempdir().unwrap();
+        let args_path = dir.path().join("args.json");
+        std::fs::write(&args_path, "[1, \"two\"]\n").unwrap();
+        let bad_path = dir.path().join("bad.json");
+        std::fs::write(&bad_path, "{\n  \"a\": 1,\n  oops\n}").unwrap();
+
+        let mut args = Some(format!("@{}", args_path.display()));
+        let mut storage = Some(r#"{"k": 1}"#.to_string());
+        resolve_json_flags(&mut [("--args", &mut args), ("--storage", &mut storage)]).unwrap();
+        assert_eq!(args.as_deref(), Some("[1, \"two\"]\n"));
+        assert_eq!(storage.as_deref(), Some(r#"{"k": 1}"#));
+
+        let mut bad = Some(format!("@{}", bad_path.display()));
+        let err = resolve_json_flags(&mut [("--storage", &mut bad)]).unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("Invalid JSON in --storage file"),
+            "{}",
+            message
+        );
+        assert!(message.contains("bad.json"), "{}", message);
+        assert!(message.contains("line 3 column 3"), "{}", message);
+
+        let mut missing = Some("@does-not-exist.json".to_string());
+        let err = resolve_json_flags(&mut [("--args", &mut missing)]).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Failed to read --args file does-not-exist.json"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn stdin_can_feed_only_one_flag() {
+        let mut args = Some("@-".to_string());
+        let mut storage = Some("@-".to_string());
+        let err = resolve_json_flags(&mut [("--args", &mut args), ("--storage", &mut storage)])
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Only one flag can read from stdin, but --args and --storage use @-"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,235 @@
+//! A small JSON path syntax for picking values out of run output.
+//!
+//! Paths start with a bare name and continue with `.field`, `["key"]`,
+//! `['key']` or `[index]` segments, e.g. `result.balances["alice"]` or
+//! `storage["c"]`. There are no wildcards or filters. Breakpoint conditions
+//! and assertions read fields with the same paths.
+
+use crate::{DebuggerError, Result};
+use serde_json::Value;
+use std::fmt;
+
+/// One step of a [`JsonPath`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A parsed path such as `result.items[0]["name"]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath {
+    raw: String,
+    segments: Vec<Segment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let invalid = |reason: &str| -> miette::Report {
+            DebuggerError::InvalidArguments(format!("Invalid path '{}': {}", path, reason)).into()
+        };
+        let (parsed, len) = Self::parse_prefix(path).map_err(|reason| invalid(&reason))?;
+        match path[len..].chars().next() {
+            Some(c) => Err(invalid(&format!("unexpected '{}'", c))),
+            None => Ok(parsed),
+        }
+    }
+
+    /// Parse the path `text` starts with, up to the first character that
+    /// cannot continue it, and return it with its length in bytes. Used by
+    /// the [expression](crate::debugger::expression) tokenizer, so paths read
+    /// the same in `--extract` and in conditions. Errors are the bare reason.
+    pub fn parse_prefix(text: &str) -> std::result::Result<(Self, usize), String> {
+        let mut chars = text.char_indices().peekable();
+        let mut segments = Vec::new();
+        let mut end = 0;
+        loop {
+            let mut name = String::new();
+            while let Some(&(at, c)) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' {
+                    name.push(c);
+                    chars.next();
+                    end = at + c.len_utf8();
+                } else {
+                    break;
+                }
+            }
+            if name.is_empty() {
+                return Err("expected a field name".to_string());
+            }
+            segments.push(Segment::Key(name));
+
+            while chars.next_if(|&(_, c)| c == '[').is_some() {
+                let segment = match chars.next_if(|&(_, c)| c == '"' || c == '\'') {
+                    Some((_, quote)) => {
+                        let mut key = String::new();
+                        loop {
+                            match chars.next() {
+                                Some((_, '\\')) => match chars.next() {
+                                    Some((_, escaped)) => key.push(escaped),
+                                    None => return Err("unterminated string".to_string()),
+                                },
+                                Some((_, c)) if c == quote => break,
+                                Some((_, c)) => key.push(c),
+                                None => return Err("unterminated string".to_string()),
+                            }
+                        }
+                        Segment::Key(key)
+                    }
+                    None => {
+                        let mut digits = String::new();
+                        while let Some((_, c)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+                            digits.push(c);
+                        }
+                        let index = digits
+                            .parse()
+                            .map_err(|_| "expected an index or quoted key".to_string())?;
+                        Segment::Index(index)
+                    }
+                };
+                match chars.next() {
+                    Some((at, ']')) => end = at + 1,
+                    _ => return Err("expected ']'".to_string()),
+                }
+                segments.push(segment);
+            }
+
+            if chars.next_if(|&(_, c)| c == '.').is_none() {
+                break;
+            }
+        }
+        Ok((
+            Self {
+                raw: text[..end].to_string(),
+                segments,
+            },
+            end,
+        ))
+    }
+
+    /// The path's only segment, when it is a bare name such as `amount`.
+    pub fn as_name(&self) -> Option<&str> {
+        match self.segments.as_slice() {
+            [Segment::Key(name)] => Some(name),
+            _ => None,
+        }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// The value at this path, or `None` if any segment is missing.
+    pub fn resolve<'a>(&self, root: &'a Value) -> Option<&'a Value> {
+        resolve_segments(&self.segments, root)
+    }
+}
+
+/// The value `segments` lead to from `root`, or `None` if any is missing.
+pub fn resolve_segments<'a>(segments: &[Segment], root: &'a Value) -> Option<&'a Value> {
+    segments
+        .iter()
+        .try_fold(root, |value, segment| match (segment, value) {
+            (Segment::Key(key), Value::Object(map)) => map.get(key),
+            (Segment::Index(index), Value::Array(items)) => items.get(*index),
+            (Segment::Index(index), Value::Object(map)) => map.get(&index.to_string()),
+            _ => None,
+        })
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// Render an extracted value for scripts: scalars raw, containers as compact JSON.
+pub fn render_extracted(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn document() -> Value {
+        json!({
+            "result": {
+                "balances": { "alice": 100, "bob": "1000000000000000000000" },
+                "owners": ["GA", "GB"],
+                "nested": { "a.b": { "ok": true } },
+            },
+            "storage": { "c": 3, "[\"Balance\",\"GA\"]": 7 },
+        })
+    }
+
+    fn extract(path: &str) -> Option<String> {
+        let doc = document();
+        JsonPath::parse(path)
+            .unwrap()
+            .resolve(&doc)
+            .map(render_extracted)
+    }
+
+    #[test]
+    fn resolves_fields_keys_and_indices() {
+        assert_eq!(
+            extract("result.balances[\"alice\"]").as_deref(),
+            Some("100")
+        );
+        assert_eq!(
+            extract("result.balances.bob").as_deref(),
+            Some("1000000000000000000000")
+        );
+        assert_eq!(extract("result.owners[1]").as_deref(), Some("GB"));
+        assert_eq!(
+            extract("result['nested']['a.b'].ok").as_deref(),
+            Some("true")
+        );
+        assert_eq!(extract("storage[\"c\"]").as_deref(), Some("3"));
+        assert_eq!(
+            extract(r#"storage["[\"Balance\",\"GA\"]"]"#).as_deref(),
+            Some("7")
+        );
+    }
+
+    #[test]
+    fn containers_render_as_compact_json() {
+        assert_eq!(extract("result.owners").as_deref(), Some("[\"GA\",\"GB\"]"));
+        assert_eq!(
+            extract("result.nested").as_deref(),
+            Some("{\"a.b\":{\"ok\":true}}")
+        );
+    }
+
+    #[test]
+    fn missing_segments_do_not_resolve() {
+        assert_eq!(extract("result.balances[\"carol\"]"), None);
+        assert_eq!(extract("result.owners[2]"), None);
+        assert_eq!(extract("result.owners.first"), None);
+        assert_eq!(extract("events"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_paths() {
+        for path in [
+            "",
+            "result.",
+            ".result",
+            "result[",
+            "result[\"a]",
+            "result[x]",
+            "a b",
+        ] {
+            assert!(
+                JsonPath::parse(path).is_err(),
+                "{:?} should not parse",
+                path
+            );
+        }
+    }
+}
@@ -0,0 +1,382 @@
+//! Ledger keys for contract storage entries.
+//!
+//! Storage keys are written as in the `--storage` list form: a typed value
+//! such as `{"type":"symbol","value":"Counter"}` plus a durability.
+//! [`KeySpec::ledger_key`] turns one into the `LedgerKey` that RPC nodes and
+//! ledger snapshots index the entry by, and [`describe`] decodes it back.
+
+use crate::inspector::render_cache::RenderCache;
+use crate::inspector::storage::StorageInspector;
+use crate::utils::arguments::{scval_to_json, ArgumentParser};
+use crate::{DebuggerError, Result};
+use serde::Serialize;
+use serde_json::Value;
+use soroban_env_host::xdr::{
+    ContractDataDurability, LedgerKey, LedgerKeyContractData, Limits, ReadXdr, ScAddress, ScVal,
+    WriteXdr,
+};
+use soroban_sdk::{Env, TryFromVal};
+use std::str::FromStr;
+
+/// Storage a contract data entry lives in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Durability {
+    Instance,
+    Persistent,
+    Temporary,
+}
+
+impl Durability {
+    /// Parse the `durability` field of a storage entry. An absent field means
+    /// instance storage, as in `--storage`.
+    pub fn from_json(raw: Option<&Value>) -> Result<Self> {
+        let Some(v) = raw else {
+            return Ok(Durability::Instance);
+        };
+        let Some(s) = v.as_str() else {
+            return Err(DebuggerError::StorageError(
+                "durability must be a string: instance|persistent|temporary".to_string(),
+            )
+            .into());
+        };
+        match s {
+            "instance" => Ok(Durability::Instance),
+            "persistent" => Ok(Durability::Persistent),
+            "temporary" => Ok(Durability::Temporary),
+            other => Err(DebuggerError::StorageError(format!(
+                "Unsupported durability '{other}'. Use instance|persistent|temporary."
+            ))
+            .into()),
+        }
+    }
+
+    /// Storage of the contract data entry with this key and ledger
+    /// durability. The contract instance is persistent on the ledger but
+    /// holds the instance storage.
+    pub fn of(key: &ScVal, durability: ContractDataDurability) -> Self {
+        match (key, durability) {
+            (ScVal::LedgerKeyContractInstance, _) => Durability::Instance,
+            (_, ContractDataDurability::Persistent) => Durability::Persistent,
+            (_, ContractDataDurability::Temporary) => Durability::Temporary,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Durability::Instance => "instance",
+            Durability::Persistent => "persistent",
+            Durability::Temporary => "temporary",
+        }
+    }
+}
+
+/// Parse one typed JSON value, e.g. `{"type":"symbol","value":"Counter"}`,
+/// as the argument parser would.
+pub fn parse_typed_val(env: &Env, value: &Value) -> Result<soroban_sdk::Val> {
+    parse_typed_val_with(&ArgumentParser::new(env.clone()), value)
+}
+
+/// [`parse_typed_val`] with a parser that may know the contract's types.
+pub fn parse_typed_val_with(parser: &ArgumentParser, value: &Value) -> Result<soroban_sdk::Val> {
+    let json = serde_json::to_string(value).map_err(|e| {
+        DebuggerError::StorageError(format!("Failed to serialize storage JSON value: {e}"))
+    })?;
+    let mut vals = parser
+        .parse_args_string(&json)
+        .map_err(|e| DebuggerError::StorageError(format!("Failed to parse storage value: {e}")))?;
+    if vals.len() != 1 {
+        return Err(DebuggerError::StorageError(format!(
+            "Storage entry must resolve to exactly 1 value, got {}",
+            vals.len()
+        ))
+        .into());
+    }
+    Ok(vals.remove(0))
+}
+
+/// A storage key to build a ledger key for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySpec {
+    /// Owning contract; falls back to the one given on the command line.
+    pub contract: Option<String>,
+    /// Typed key JSON. Unused for instance storage.
+    pub key: Option<Value>,
+    pub durability: Durability,
+}
+
+impl KeySpec {
+    /// Parse a JSON array of keys.
+    ///
+    /// Elements are either typed keys, which get `durability`, or `--storage`
+    /// list entries `{"key", "durability"[, "contract"]}` whose `value`, if
+    /// any, is ignored.
+    pub fn parse_list(json: &str, durability: Durability) -> Result<Vec<KeySpec>> {
+        let root: Value = serde_json::from_str(json)
+            .map_err(|e| DebuggerError::StorageError(format!("Failed to parse key list: {e}")))?;
+        let Value::Array(items) = root else {
+            return Err(DebuggerError::StorageError(
+                "Key list must be a JSON array of typed keys or {key,durability} objects"
+                    .to_string(),
+            )
+            .into());
+        };
+        items
+            .into_iter()
+            .map(|item| match item {
+                Value::Object(mut obj) if obj.contains_key("key") => {
+                    let contract = match obj.remove("contract") {
+                        None => None,
+                        Some(Value::String(contract)) => Some(contract),
+                        Some(_) => {
+                            return Err(DebuggerError::StorageError(
+                                "Key list field 'contract' must be a string".to_string(),
+                            )
+                            .into())
+                        }
+                    };
+                    let durability = match obj.get("durability") {
+                        Some(raw) => Durability::from_json(Some(raw))?,
+                        None => durability,
+                    };
+                    Ok(KeySpec {
+                        contract,
+                        key: obj.remove("key"),
+                        durability,
+                    })
+                }
+                key => Ok(KeySpec {
+                    contract: None,
+                    key: Some(key),
+                    durability,
+                }),
+            })
+            .collect()
+    }
+
+    /// The ledger key of this entry, in `contract` unless the spec names its own.
+    ///
+    /// All instance storage lives in the contract instance entry, so instance
+    /// keys map to that entry whatever their key.
+    pub fn ledger_key(&self, env: &Env, contract: Option<&str>) -> Result<LedgerKey> {
+        let Some(contract) = self.contract.as_deref().or(contract) else {
+            return Err(DebuggerError::InvalidArguments(
+                "No contract given for storage key; pass --contract".to_string(),
+            )
+            .into());
+        };
+        let contract = parse_contract(contract)?;
+        let (key, durability) = match (self.durability, &self.key) {
+            (Durability::Instance, _) => (
+                ScVal::LedgerKeyContractInstance,
+                ContractDataDurability::Persistent,
+            ),
+            (durability, Some(key)) => {
+                let val = parse_typed_val(env, key)?;
+                let key = ScVal::try_from_val(env.host(), &val).map_err(|e| {
+                    DebuggerError::StorageError(format!("Failed to convert storage key: {e:?}"))
+                })?;
+                let durability = if durability == Durability::Temporary {
+                    ContractDataDurability::Temporary
+                } else {
+                    ContractDataDurability::Persistent
+                };
+                (key, durability)
+            }
+            (durability, None) => {
+                return Err(DebuggerError::StorageError(format!(
+                    "{} storage keys need a key",
+                    durability.name()
+                ))
+                .into())
+            }
+        };
+        Ok(LedgerKey::ContractData(LedgerKeyContractData {
+            contract,
+            key,
+            durability,
+        }))
+    }
+}
+
+/// Parse a contract (`C...`) or account (`G...`) strkey.
+pub fn parse_contract(contract: &str) -> Result<ScAddress> {
+    ScAddress::from_str(contract.trim()).map_err(|e| {
+        DebuggerError::InvalidArguments(format!("Invalid contract address '{}': {}", contract, e))
+            .into()
+    })
+}
+
+/// Parse a `--source-account`: a `G...` account or a `C...` contract address.
+pub fn parse_source_account(source: &str) -> Result<ScAddress> {
+    ScAddress::from_str(source.trim()).map_err(|_| {
+        DebuggerError::InvalidFlag(format!(
+            "Invalid source account '{}': expected a G... account or C... contract address",
+            source
+        ))
+        .into()
+    })
+}
+
+pub fn encode(key: &LedgerKey) -> Result<String> {
+    key.to_xdr_base64(Limits::none()).map_err(|e| {
+        DebuggerError::StorageError(format!("Failed to encode ledger key: {:?}", e)).into()
+    })
+}
+
+pub fn decode(xdr: &str) -> Result<LedgerKey> {
+    LedgerKey::from_xdr_base64(xdr.trim(), Limits::none()).map_err(|e| {
+        DebuggerError::InvalidArguments(format!("Invalid base64 LedgerKey XDR: {:?}", e)).into()
+    })
+}
+
+/// Decoded form of a ledger key.
+///
+/// Contract data keys show the contract, durability, key as argument JSON and
+/// the key as it appears in storage snapshots.
+pub fn describe(key: &LedgerKey) -> Value {
+    match key {
+        LedgerKey::ContractData(cd) => {
+            let durability = Durability::of(&cd.key, cd.durability);
+            serde_json::json!({
+                "type": "contract_data",
+                "contract": cd.contract.to_string(),
+                "durability": durability.name(),
+                "key": scval_to_json(&cd.key),
+                "storage_key": StorageInspector::render_ledger_key(key, &mut RenderCache::new()),
+            })
+        }
+        LedgerKey::ContractCode(code) => serde_json::json!({
+            "type": "contract_code",
+            "hash": hex::encode(code.hash.0),
+        }),
+        other => serde_json::json!({
+            "type": format!("{:?}", other.discriminant()).to_lowercase(),
+            "key": format!("{:?}", other),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTRACT: &str = "CAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6N4O";
+    const COUNTER_KEY_XDR: &str =
+        "AAAABgAAAAEAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHwAAAA8AAAAHQ291bnRlcgAAAAAB";
+
+    fn spec(key: Value, durability: Durability) -> KeySpec {
+        KeySpec {
+            contract: None,
+            key: Some(key),
+            durability,
+        }
+    }
+
+    #[test]
+    fn persistent_symbol_key_matches_known_vector() {
+        let env = Env::default();
+        let key = spec(
+            serde_json::json!({"type": "symbol", "value": "Counter"}),
+            Durability::Persistent,
+        )
+        .ledger_key(&env, Some(CONTRACT))
+        .unwrap();
+        assert_eq!(encode(&key).unwrap(), COUNTER_KEY_XDR);
+    }
+
+    #[test]
+    fn decode_round_trips_known_vector() {
+        let key = decode(COUNTER_KEY_XDR).unwrap();
+        assert_eq!(encode(&key).unwrap(), COUNTER_KEY_XDR);
+        let described = describe(&key);
+        assert_eq!(described["contract"], CONTRACT);
+        assert_eq!(described["durability"], "persistent");
+        assert_eq!(described["key"], "Counter");
+    }
+
+    #[test]
+    fn instance_keys_map_to_the_contract_instance_entry() {
+        let env = Env::default();
+        let key = KeySpec {
+            contract: None,
+            key: None,
+            durability: Durability::Instance,
+        }
+        .ledger_key(&env, Some(CONTRACT))
+        .unwrap();
+        let LedgerKey::ContractData(cd) = &key else {
+            panic!("expected a contract data key");
+        };
+        assert_eq!(cd.key, ScVal::LedgerKeyContractInstance);
+        assert_eq!(cd.durability, ContractDataDurability::Persistent);
+        assert_eq!(describe(&key)["durability"], "instance");
+    }
+
+    #[test]
+    fn source_account_is_an_account_or_a_contract() {
+        let account = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+        assert!(matches!(
+            parse_source_account(account).unwrap(),
+            ScAddress::Account(_)
+        ));
+        assert!(matches!(
+            parse_source_account(CONTRACT).unwrap(),
+            ScAddress::Contract(_)
+        ));
+        let err = parse_source_account("GABC").unwrap_err().to_string();
+        assert!(
+            err.contains("expected a G... account or C... contract address"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn temporary_keys_need_a_key_and_a_contract() {
+        let env = Env::default();
+        let missing_key = KeySpec {
+            contract: None,
+            key: None,
+            durability: Durability::Temporary,
+        };
+        assert!(missing_key.ledger_key(&env, Some(CONTRACT)).is_err());
+        let missing_contract = spec(serde_json::json!("Counter"), Durability::Temporary);
+        assert!(missing_contract.ledger_key(&env, None).is_err());
+    }
+
+    #[test]
+    fn key_list_accepts_typed_keys_and_storage_entries() {
+        let specs = KeySpec::parse_list(
+            r#"[
+                {"type": "symbol", "value": "Counter"},
+                {"key": {"type": "u32", "value": 7}, "durability": "temporary", "value": 1},
+                {"key": "Admin", "contract": "CXYZ"}
+            ]"#,
+            Durability::Persistent,
+        )
+        .unwrap();
+        assert_eq!(specs.len(), 3);
+        assert_eq!(specs[0].durability, Durability::Persistent);
+        assert_eq!(
+            specs[0].key,
+            Some(serde_json::json!({"type": "symbol", "value": "Counter"}))
+        );
+        assert_eq!(specs[1].durability, Durability::Temporary);
+        assert_eq!(
+            specs[1].key,
+            Some(serde_json::json!({"type": "u32", "value": 7}))
+        );
+        assert_eq!(specs[2].durability, Durability::Persistent);
+        assert_eq!(specs[2].contract.as_deref(), Some("CXYZ"));
+    }
+
+    #[test]
+    fn key_list_must_be_an_array() {
+        assert!(KeySpec::parse_list(r#"{"key": "Counter"}"#, Durability::Persistent).is_err());
+        assert!(
+            KeySpec::parse_list(r#"[{"key": "a", "durability": 3}]"#, Durability::Persistent)
+                .is_err()
+        );
+    }
+}
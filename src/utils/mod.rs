@@ -1,4 +1,13 @@
 pub mod arguments;
+pub mod artifact_cache;
+pub mod cargo;
+pub mod conversions;
+pub mod json_input;
+pub mod json_path;
+pub mod ledger_key;
+pub mod spec_cache;
+pub mod time;
+pub mod value_depth;
 pub mod wasm;
 
 pub use arguments::ArgumentParser;
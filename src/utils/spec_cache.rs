@@ -0,0 +1,384 @@
+//! Decoded contract specs kept as JSON sidecars next to the artifact cache.
+//!
+//! `inspect` decodes the whole `contractspecv0` section — function
+//! signatures and docs, user-defined types and error enums — every time it
+//! runs. The result only depends on the WASM bytes, so it is written to
+//! `<cache dir>/specs/<sha256>.json` and read back by later calls on the same
+//! contract. A rebuilt contract has a different hash and gets a fresh entry;
+//! entries written by another format version are ignored.
+//!
+//! `--no-spec-cache` or `SOROBAN_DEBUG_NO_CACHE=1` skips the sidecar, and
+//! `soroban-debug cache clear` removes it.
+
+use crate::utils::artifact_cache::{clear_dir, dir_stats, ArtifactCache, CacheStats, NO_CACHE_ENV};
+use crate::utils::wasm::{ContractFunctionSignature, CustomError, UserDefinedType};
+use crate::{DebuggerError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Bumped whenever [`ContractSpec`] changes shape; older entries are ignored.
+pub const SPEC_CACHE_VERSION: u32 = 1;
+
+/// Everything `inspect` reads from a contract's spec section.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractSpec {
+    pub version: u32,
+    pub wasm_hash: String,
+    pub functions: Vec<ContractFunctionSignature>,
+    /// Function doc comments by function name; undocumented functions are left out.
+    pub docs: BTreeMap<String, String>,
+    pub user_types: Vec<UserDefinedType>,
+    pub errors: Vec<CustomError>,
+}
+
+impl ContractSpec {
+    /// Decode the spec section of `wasm_bytes`.
+    pub fn decode(wasm_bytes: &[u8], wasm_hash: String) -> Result<Self> {
+        Ok(Self {
+            version: SPEC_CACHE_VERSION,
+            wasm_hash,
+            functions: crate::utils::wasm::parse_function_signatures(wasm_bytes)?,
+            docs: crate::utils::wasm::parse_function_docs(wasm_bytes)?
+                .into_iter()
+                .collect(),
+            user_types: crate::utils::wasm::parse_user_types(wasm_bytes)?,
+            errors: crate::utils::wasm::parse_custom_errors(wasm_bytes)?,
+        })
+    }
+}
+
+/// Where a [`ContractSpec`] came from, shown in verbose `inspect` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpecCacheStatus {
+    /// Read from a fresh sidecar.
+    Hit,
+    /// Decoded from the WASM and written to the sidecar.
+    Miss,
+    /// Decoded from the WASM without touching the cache.
+    Disabled,
+}
+
+impl fmt::Display for SpecCacheStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpecCacheStatus::Hit => "hit",
+            SpecCacheStatus::Miss => "miss",
+            SpecCacheStatus::Disabled => "disabled",
+        })
+    }
+}
+
+/// A directory of [`ContractSpec`] sidecars.
+#[derive(Debug, Clone)]
+pub struct SpecCache {
+    dir: PathBuf,
+}
+
+impl SpecCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// `specs/` under [`ArtifactCache::default_dir`].
+    pub fn default_dir() -> Option<PathBuf> {
+        ArtifactCache::default_dir().map(|dir| dir.join("specs"))
+    }
+
+    /// The cache at [`SpecCache::default_dir`], unless disabled via `SOROBAN_DEBUG_NO_CACHE`.
+    pub fn open_default() -> Option<Self> {
+        if std::env::var(NO_CACHE_ENV).is_ok_and(|v| v == "1") {
+            return None;
+        }
+        Self::default_dir().map(Self::new)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn entry_path(&self, wasm_hash: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", wasm_hash))
+    }
+
+    /// Load the spec stored for `wasm_hash`. Missing, corrupt or outdated entries are a miss.
+    pub fn load(&self, wasm_hash: &str) -> Option<ContractSpec> {
+        let content = fs::read_to_string(self.entry_path(wasm_hash)).ok()?;
+        let spec: ContractSpec = serde_json::from_str(&content).ok()?;
+        (spec.version == SPEC_CACHE_VERSION && spec.wasm_hash == wasm_hash).then_some(spec)
+    }
+
+    /// Write `spec` atomically so concurrent readers never see a partial file.
+    ///
+    /// The temporary file is unique per process and per call, so concurrent
+    /// writers of the same entry each rename a complete file into place.
+    pub fn store(&self, spec: &ContractSpec) -> Result<()> {
+        static WRITES: AtomicU64 = AtomicU64::new(0);
+
+        fs::create_dir_all(&self.dir).map_err(|e| {
+            DebuggerError::FileError(format!(
+                "Failed to create spec cache directory {:?}: {}",
+                self.dir, e
+            ))
+        })?;
+        let json = serde_json::to_string(spec).map_err(|e| {
+            DebuggerError::FileError(format!("Failed to serialize cached spec: {}", e))
+        })?;
+        let final_path = self.entry_path(&spec.wasm_hash);
+        let tmp_path = self.dir.join(format!(
+            "{}.{}.{}.tmp",
+            spec.wasm_hash,
+            std::process::id(),
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&tmp_path, json)
+            .and_then(|_| fs::rename(&tmp_path, &final_path))
+            .map_err(|e| {
+                let _ = fs::remove_file(&tmp_path);
+                DebuggerError::FileError(format!(
+                    "Failed to write cached spec {:?}: {}",
+                    final_path, e
+                ))
+                .into()
+            })
+    }
+
+    /// The spec for `wasm_bytes`, read from the sidecar when it is fresh and
+    /// decoded and stored otherwise.
+    pub fn get_or_decode(
+        &self,
+        wasm_bytes: &[u8],
+        wasm_hash: &str,
+    ) -> Result<(ContractSpec, SpecCacheStatus)> {
+        if let Some(hit) = self.load(wasm_hash) {
+            return Ok((hit, SpecCacheStatus::Hit));
+        }
+        let spec = ContractSpec::decode(wasm_bytes, wasm_hash.to_string())?;
+        // A read-only or full cache directory must never fail inspection.
+        if let Err(e) = self.store(&spec) {
+            tracing::debug!("Skipping spec cache write: {}", e);
+        }
+        Ok((spec, SpecCacheStatus::Miss))
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        dir_stats(&self.dir)
+    }
+
+    /// Delete every cached spec, returning what was removed.
+    pub fn clear(&self) -> Result<CacheStats> {
+        clear_dir(&self.dir)
+    }
+}
+
+/// The spec for `wasm_bytes`, from the default cache unless `use_cache` is
+/// false or the cache is disabled.
+pub fn spec_for(
+    wasm_bytes: &[u8],
+    wasm_hash: &str,
+    use_cache: bool,
+) -> Result<(ContractSpec, SpecCacheStatus)> {
+    match SpecCache::open_default().filter(|_| use_cache) {
+        Some(cache) => cache.get_or_decode(wasm_bytes, wasm_hash),
+        None => Ok((
+            ContractSpec::decode(wasm_bytes, wasm_hash.to_string())?,
+            SpecCacheStatus::Disabled,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+    use stellar_xdr::curr::{
+        Limits, ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef, ScSpecTypeMap,
+        ScSpecTypeOption, ScSpecTypeVec, ScSymbol, StringM, WriteXdr,
+    };
+    use tempfile::TempDir;
+
+    fn uleb128(mut value: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let mut byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value != 0 {
+                byte |= 0x80;
+            }
+            out.push(byte);
+            if value == 0 {
+                return out;
+            }
+        }
+    }
+
+    /// A module whose only section is a `contractspecv0` with `count`
+    /// documented functions of five parameters each.
+    fn spec_wasm(count: usize) -> Vec<u8> {
+        fn s<const N: u32>(value: String) -> StringM<N> {
+            StringM::try_from(value.into_bytes()).unwrap()
+        }
+        let map = ScSpecTypeDef::Map(Box::new(ScSpecTypeMap {
+            key_type: Box::new(ScSpecTypeDef::Address),
+            value_type: Box::new(ScSpecTypeDef::Vec(Box::new(ScSpecTypeVec {
+                element_type: Box::new(ScSpecTypeDef::I128),
+            }))),
+        }));
+        let mut payload = Vec::new();
+        for i in 0..count {
+            let inputs: Vec<ScSpecFunctionInputV0> = (0..5)
+                .map(|p| ScSpecFunctionInputV0 {
+                    doc: s(String::new()),
+                    name: s(format!("param_{}", p)),
+                    type_: if p % 2 == 0 {
+                        map.clone()
+                    } else {
+                        ScSpecTypeDef::U64
+                    },
+                })
+                .collect();
+            let entry = ScSpecEntry::FunctionV0(ScSpecFunctionV0 {
+                doc: s(format!("Function number {} of the generated spec.", i)),
+                name: ScSymbol(s(format!("function_{}", i))),
+                inputs: inputs.try_into().unwrap(),
+                outputs: vec![ScSpecTypeDef::Option(Box::new(ScSpecTypeOption {
+                    value_type: Box::new(map.clone()),
+                }))]
+                .try_into()
+                .unwrap(),
+            });
+            payload.extend(entry.to_xdr(Limits::none()).unwrap());
+        }
+
+        let name = "contractspecv0";
+        let mut section = uleb128(name.len());
+        section.extend_from_slice(name.as_bytes());
+        section.extend(payload);
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, 0x00];
+        wasm.extend(uleb128(section.len()));
+        wasm.extend(section);
+        wasm
+    }
+
+    fn fastest(mut run: impl FnMut()) -> Duration {
+        (0..5)
+            .map(|_| {
+                let start = Instant::now();
+                run();
+                start.elapsed()
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn second_lookup_is_a_hit_and_a_new_hash_misses() {
+        let dir = TempDir::new().unwrap();
+        let cache = SpecCache::new(dir.path().join("specs"));
+        let wasm = spec_wasm(3);
+
+        let (cold, status) = cache.get_or_decode(&wasm, "aa").unwrap();
+        assert_eq!(status, SpecCacheStatus::Miss);
+        assert_eq!(cold.functions.len(), 3);
+        assert_eq!(
+            cold.docs["function_1"],
+            "Function number 1 of the generated spec."
+        );
+
+        let (warm, status) = cache.get_or_decode(&wasm, "aa").unwrap();
+        assert_eq!(status, SpecCacheStatus::Hit);
+        assert_eq!(warm, cold);
+
+        let (_, status) = cache.get_or_decode(&spec_wasm(4), "bb").unwrap();
+        assert_eq!(status, SpecCacheStatus::Miss);
+        assert_eq!(cache.stats().entries, 2);
+    }
+
+    #[test]
+    fn outdated_or_mismatched_entries_are_ignored() {
+        let dir = TempDir::new().unwrap();
+        let cache = SpecCache::new(dir.path().to_path_buf());
+        let mut spec = ContractSpec::decode(&spec_wasm(1), "aa".to_string()).unwrap();
+
+        fs::write(cache.entry_path("aa"), "{not json").unwrap();
+        assert!(cache.load("aa").is_none());
+
+        spec.version = SPEC_CACHE_VERSION + 1;
+        cache.store(&spec).unwrap();
+        assert!(cache.load("aa").is_none());
+
+        spec.version = SPEC_CACHE_VERSION;
+        cache.store(&spec).unwrap();
+        fs::copy(cache.entry_path("aa"), cache.entry_path("bb")).unwrap();
+        assert!(cache.load("bb").is_none());
+        assert!(cache.load("aa").is_some());
+    }
+
+    #[test]
+    fn concurrent_writers_never_expose_a_partial_entry() {
+        let dir = TempDir::new().unwrap();
+        let cache = SpecCache::new(dir.path().to_path_buf());
+        let wasm = spec_wasm(50);
+        let expected = ContractSpec::decode(&wasm, "aa".to_string()).unwrap();
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..10 {
+                        let (spec, _) = cache.get_or_decode(&wasm, "aa").unwrap();
+                        assert_eq!(spec, expected);
+                        cache.store(&expected).unwrap();
+                        if let Some(loaded) = cache.load("aa") {
+                            assert_eq!(loaded, expected);
+                        }
+                    }
+                });
+            }
+        });
+
+        assert_eq!(cache.stats().entries, 1);
+        let leftovers = fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn warm_load_is_faster_than_decoding_a_large_spec() {
+        let dir = TempDir::new().unwrap();
+        let cache = SpecCache::new(dir.path().to_path_buf());
+        let wasm = spec_wasm(300);
+        cache.get_or_decode(&wasm, "aa").unwrap();
+
+        let cold = fastest(|| {
+            ContractSpec::decode(&wasm, "aa".to_string()).unwrap();
+        });
+        let warm = fastest(|| {
+            cache.load("aa").expect("entry should be cached");
+        });
+        println!(
+            "300 spec entries: decode {:?}, sidecar {:?} ({:.1}x)",
+            cold,
+            warm,
+            cold.as_secs_f64() / warm.as_secs_f64()
+        );
+        assert!(warm < cold, "sidecar {:?} vs decode {:?}", warm, cold);
+    }
+
+    #[test]
+    fn clear_removes_specs() {
+        let dir = TempDir::new().unwrap();
+        let cache = SpecCache::new(dir.path().join("specs"));
+        cache.get_or_decode(&spec_wasm(2), "aa").unwrap();
+
+        let removed = cache.clear().unwrap();
+        assert_eq!(removed.entries, 1);
+        assert_eq!(cache.stats(), CacheStats::default());
+    }
+}
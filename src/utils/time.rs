@@ -0,0 +1,207 @@
+//! Rendering of ledger timepoints and durations.
+//!
+//! Both are plain `u64` second counts on the wire, which makes
+//! `Timepoint(TimePoint(1700000000))` hard to read at a glance. Pretty output
+//! shows timepoints as UTC ISO-8601 timestamps and durations as spans like
+//! `1h 30m`, each with the raw value alongside. `output.humanize_time = false`
+//! turns this off; JSON output always keeps the raw integers.
+//!
+//! [`parse_timepoint`] reads a timepoint back from either form, for
+//! `--ledger-timestamp` and `timepoint` arguments, and [`parse_duration`]
+//! reads spans like `1d12h` for `duration` arguments.
+
+use chrono::DateTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HUMANIZE_TIME: AtomicBool = AtomicBool::new(true);
+
+/// Whether timepoints and durations are humanized in pretty output.
+pub fn humanize_time() -> bool {
+    HUMANIZE_TIME.load(Ordering::Relaxed)
+}
+
+/// Change the process-wide setting (`output.humanize_time`).
+pub fn set_humanize_time(enabled: bool) {
+    HUMANIZE_TIME.store(enabled, Ordering::Relaxed);
+}
+
+/// `seconds` since the Unix epoch as `2023-11-14T22:13:20Z (1700000000)`.
+///
+/// Values past the last date chrono can represent keep only the raw number.
+pub fn format_timepoint(seconds: u64) -> String {
+    match i64::try_from(seconds)
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+    {
+        Some(time) => format!("{} ({})", time.format("%Y-%m-%dT%H:%M:%SZ"), seconds),
+        None => format!("{} (out of date range)", seconds),
+    }
+}
+
+/// Parse a timepoint given as Unix seconds or as an RFC 3339 date such as
+/// `2024-01-01T00:00:00Z`.
+pub fn parse_timepoint(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<u64>() {
+        return Ok(seconds);
+    }
+    let time = DateTime::parse_from_rfc3339(text).map_err(|e| {
+        format!(
+            "'{}' is neither Unix seconds nor an RFC 3339 date ({})",
+            text, e
+        )
+    })?;
+    u64::try_from(time.timestamp()).map_err(|_| format!("'{}' is before the Unix epoch", text))
+}
+
+/// Parse a duration given as seconds or as a span of `w`, `d`, `h`, `m` and
+/// `s` units such as `1d12h` or `1h 30m`.
+pub fn parse_duration(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<u64>() {
+        return Ok(seconds);
+    }
+    if text.starts_with('-') {
+        return Err(format!("'{}' is negative; durations cannot be", text));
+    }
+    let not_a_duration = || {
+        format!(
+            "'{}' is neither seconds nor a span like 1d12h (units w, d, h, m, s)",
+            text
+        )
+    };
+    let too_long = || {
+        format!(
+            "'{}' is longer than the largest duration, {}s",
+            text,
+            u64::MAX
+        )
+    };
+    if text.is_empty() {
+        return Err(not_a_duration());
+    }
+    let mut rest = text;
+    let mut total: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .ok_or_else(not_a_duration)?;
+        if digits == 0 {
+            return Err(not_a_duration());
+        }
+        let count: u64 = rest[..digits].parse().map_err(|_| too_long())?;
+        let size: u64 = match rest[digits..].chars().next() {
+            Some('w') => 604_800,
+            Some('d') => 86_400,
+            Some('h') => 3_600,
+            Some('m') => 60,
+            Some('s') => 1,
+            _ => return Err(not_a_duration()),
+        };
+        total = count
+            .checked_mul(size)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(too_long)?;
+        rest = rest[digits + 1..].trim_start();
+    }
+    Ok(total)
+}
+
+/// `seconds` as a span such as `1d 2h 3m 4s (93784s)`.
+///
+/// Zero units are left out, and the raw value is only repeated when the
+/// span uses more than seconds.
+pub fn humanize_duration(seconds: u64) -> String {
+    if seconds < 60 {
+        return format!("{}s", seconds);
+    }
+    let units = [(86_400, "d"), (3_600, "h"), (60, "m"), (1, "s")];
+    let mut rest = seconds;
+    let mut parts = Vec::new();
+    for (size, suffix) in units {
+        let count = rest / size;
+        rest %= size;
+        if count > 0 {
+            parts.push(format!("{}{}", count, suffix));
+        }
+    }
+    format!("{} ({}s)", parts.join(" "), seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timepoints_render_across_epoch_boundaries() {
+        assert_eq!(format_timepoint(0), "1970-01-01T00:00:00Z (0)");
+        assert_eq!(
+            format_timepoint(1_700_000_000),
+            "2023-11-14T22:13:20Z (1700000000)"
+        );
+        // The 32-bit signed overflow point and the second after it.
+        assert_eq!(
+            format_timepoint(2_147_483_647),
+            "2038-01-19T03:14:07Z (2147483647)"
+        );
+        assert_eq!(
+            format_timepoint(2_147_483_648),
+            "2038-01-19T03:14:08Z (2147483648)"
+        );
+        assert_eq!(
+            format_timepoint(4_102_444_800),
+            "2100-01-01T00:00:00Z (4102444800)"
+        );
+        assert_eq!(
+            format_timepoint(u64::MAX),
+            "18446744073709551615 (out of date range)"
+        );
+    }
+
+    #[test]
+    fn timepoints_parse_from_seconds_or_rfc3339() {
+        assert_eq!(parse_timepoint("1700000000"), Ok(1_700_000_000));
+        assert_eq!(parse_timepoint("2024-01-01T00:00:00Z"), Ok(1_704_067_200));
+        assert_eq!(
+            parse_timepoint("2024-01-01T02:00:00+02:00"),
+            Ok(1_704_067_200)
+        );
+        assert!(parse_timepoint("1969-12-31T23:59:59Z")
+            .unwrap_err()
+            .contains("before the Unix epoch"));
+        assert!(parse_timepoint("tomorrow").is_err());
+    }
+
+    #[test]
+    fn durations_parse_from_seconds_or_spans() {
+        assert_eq!(parse_duration("86400"), Ok(86_400));
+        assert_eq!(parse_duration("1d12h"), Ok(129_600));
+        assert_eq!(parse_duration("1h 30m"), Ok(5_400));
+        assert_eq!(parse_duration("2w"), Ok(1_209_600));
+        assert!(parse_duration("-1d")
+            .unwrap_err()
+            .contains("is negative; durations cannot be"));
+        assert!(parse_duration("99999999999999999999w")
+            .unwrap_err()
+            .contains("longer than the largest duration"));
+        for text in ["1x", "d", "1d12", ""] {
+            assert!(
+                parse_duration(text)
+                    .unwrap_err()
+                    .contains("neither seconds nor a span"),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn durations_render_as_spans() {
+        assert_eq!(humanize_duration(0), "0s");
+        assert_eq!(humanize_duration(59), "59s");
+        assert_eq!(humanize_duration(60), "1m (60s)");
+        assert_eq!(humanize_duration(5_400), "1h 30m (5400s)");
+        assert_eq!(humanize_duration(93_784), "1d 2h 3m 4s (93784s)");
+        assert_eq!(humanize_duration(31_536_000), "365d (31536000s)");
+    }
+}
@@ -0,0 +1,438 @@
+//! Depth-limited, non-recursive walking of `ScVal` and JSON values.
+//!
+//! A contract can return or store a value nested thousands of levels deep.
+//! Walking such a value recursively overflows the stack, so everything here
+//! uses an explicit work stack and stops descending at a configurable depth,
+//! leaving a [`truncation_marker`] in place of the deeper part. Rendering
+//! never fails: a truncated value is still a value.
+
+use crate::utils::time::{format_timepoint, humanize_duration, humanize_time};
+use soroban_env_host::xdr::ScVal;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Default nesting depth rendered before truncating.
+pub const DEFAULT_MAX_VALUE_DEPTH: usize = 1_000;
+
+static MAX_VALUE_DEPTH: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_VALUE_DEPTH);
+
+/// Depth limit used by renderers that are not given one explicitly.
+pub fn max_value_depth() -> usize {
+    MAX_VALUE_DEPTH.load(Ordering::Relaxed)
+}
+
+/// Change the process-wide depth limit (`--max-value-depth`).
+pub fn set_max_value_depth(depth: usize) {
+    MAX_VALUE_DEPTH.store(depth.max(1), Ordering::Relaxed);
+}
+
+/// Placeholder rendered in place of a value nested deeper than `max_depth`.
+pub fn truncation_marker(max_depth: usize) -> String {
+    format!("<truncated: nested deeper than {}>", max_depth)
+}
+
+fn children(val: &ScVal) -> Box<dyn Iterator<Item = &ScVal> + '_> {
+    match val {
+        ScVal::Vec(Some(items)) => Box::new(items.0.iter()),
+        ScVal::Map(Some(entries)) => Box::new(entries.0.iter().flat_map(|e| [&e.key, &e.val])),
+        ScVal::ContractInstance(instance) => match &instance.storage {
+            Some(storage) => Box::new(storage.0.iter().flat_map(|e| [&e.key, &e.val])),
+            None => Box::new(std::iter::empty()),
+        },
+        _ => Box::new(std::iter::empty()),
+    }
+}
+
+/// Whether `val` nests deeper than `limit` levels (a scalar has depth 1).
+///
+/// Stops as soon as the limit is exceeded, so it is cheap on huge values.
+pub fn scval_exceeds_depth(val: &ScVal, limit: usize) -> bool {
+    let mut stack = vec![(val, 1usize)];
+    while let Some((current, depth)) = stack.pop() {
+        if depth > limit {
+            return true;
+        }
+        stack.extend(children(current).map(|child| (child, depth + 1)));
+    }
+    false
+}
+
+/// Nesting depth of `val` (a scalar has depth 1).
+pub fn scval_depth(val: &ScVal) -> usize {
+    let mut deepest = 0;
+    let mut stack = vec![(val, 1usize)];
+    while let Some((current, depth)) = stack.pop() {
+        deepest = deepest.max(depth);
+        stack.extend(children(current).map(|child| (child, depth + 1)));
+    }
+    deepest
+}
+
+enum RenderTask<'a> {
+    Val(&'a ScVal, usize),
+    Text(&'static str),
+}
+
+/// Render `val` like its `Debug` output, without recursion.
+///
+/// Composite values nested deeper than `max_depth` are replaced by
+/// [`truncation_marker`]; shallower values render exactly as `{:?}` would,
+/// except that timepoints and durations are humanized unless disabled with
+/// [`crate::utils::time::set_humanize_time`].
+pub fn render_scval(val: &ScVal, max_depth: usize) -> String {
+    let mut out = String::new();
+    let mut stack = vec![RenderTask::Val(val, 1)];
+    while let Some(task) = stack.pop() {
+        let (current, depth) = match task {
+            RenderTask::Text(text) => {
+                out.push_str(text);
+                continue;
+            }
+            RenderTask::Val(current, depth) => (current, depth),
+        };
+        let is_composite = match current {
+            ScVal::ContractInstance(instance) => instance.storage.is_some(),
+            other => children(other).next().is_some(),
+        };
+        if is_composite && depth > max_depth {
+            out.push_str(&truncation_marker(max_depth));
+            continue;
+        }
+        match current {
+            ScVal::Vec(Some(items)) => {
+                out.push_str("Vec(Some(ScVec(VecM([");
+                stack.push(RenderTask::Text("]))))"));
+                for (i, item) in items.0.iter().enumerate().rev() {
+                    stack.push(RenderTask::Val(item, depth + 1));
+                    if i > 0 {
+                        stack.push(RenderTask::Text(", "));
+                    }
+                }
+            }
+            ScVal::Map(Some(entries)) => {
+                out.push_str("Map(Some(ScMap(VecM([");
+                stack.push(RenderTask::Text("]))))"));
+                for (i, entry) in entries.0.iter().enumerate().rev() {
+                    stack.push(RenderTask::Text(" }"));
+                    stack.push(RenderTask::Val(&entry.val, depth + 1));
+                    stack.push(RenderTask::Text(", val: "));
+                    stack.push(RenderTask::Val(&entry.key, depth + 1));
+                    stack.push(RenderTask::Text("ScMapEntry { key: "));
+                    if i > 0 {
+                        stack.push(RenderTask::Text(", "));
+                    }
+                }
+            }
+            ScVal::ContractInstance(_) if scval_exceeds_depth(current, max_depth - depth + 1) => {
+                out.push_str("ContractInstance(");
+                out.push_str(&truncation_marker(max_depth));
+                out.push(')');
+            }
+            ScVal::Timepoint(time) if humanize_time() => {
+                let _ = write!(out, "Timepoint({})", format_timepoint(time.0));
+            }
+            ScVal::Duration(span) if humanize_time() => {
+                let _ = write!(out, "Duration({})", humanize_duration(span.0));
+            }
+            // Remaining variants are scalars or shallow enough for the derived impl.
+            other => {
+                let _ = write!(out, "{:?}", other);
+            }
+        }
+    }
+    out
+}
+
+enum JsonChildren<'a> {
+    Array(std::iter::Enumerate<std::slice::Iter<'a, serde_json::Value>>),
+    Object(serde_json::map::Iter<'a>),
+}
+
+struct JsonFrame<'a> {
+    path: Vec<String>,
+    depth: usize,
+    children: JsonChildren<'a>,
+    built: serde_json::Value,
+    pending_key: Option<String>,
+}
+
+enum Opened<'a> {
+    Frame(JsonFrame<'a>),
+    Leaf(serde_json::Value),
+}
+
+fn open_json(
+    value: &serde_json::Value,
+    path: Vec<String>,
+    depth: usize,
+    max_depth: usize,
+) -> Opened<'_> {
+    let children = match value {
+        serde_json::Value::Array(items) => JsonChildren::Array(items.iter().enumerate()),
+        serde_json::Value::Object(map) => JsonChildren::Object(map.iter()),
+        leaf => return Opened::Leaf(leaf.clone()),
+    };
+    if depth > max_depth {
+        return Opened::Leaf(serde_json::Value::String(truncation_marker(max_depth)));
+    }
+    let built = match children {
+        JsonChildren::Array(_) => serde_json::Value::Array(Vec::new()),
+        JsonChildren::Object(_) => serde_json::Value::Object(serde_json::Map::new()),
+    };
+    Opened::Frame(JsonFrame {
+        path,
+        depth,
+        children,
+        built,
+        pending_key: None,
+    })
+}
+
+fn attach(frame: &mut JsonFrame<'_>, key: String, value: serde_json::Value) {
+    match &mut frame.built {
+        serde_json::Value::Array(items) => items.push(value),
+        serde_json::Value::Object(map) => {
+            map.insert(key, value);
+        }
+        _ => {}
+    }
+}
+
+/// Copy a JSON value without recursion, keeping only nodes accepted by `keep`.
+///
+/// `keep` receives the path of each node (starting at `root`) and, for object
+/// members, the member name. Returns `None` if the root itself is dropped.
+/// Containers deeper than `max_depth` become [`truncation_marker`] strings.
+pub fn rebuild_json(
+    value: &serde_json::Value,
+    root: Vec<String>,
+    max_depth: usize,
+    mut keep: impl FnMut(&[String], Option<&str>) -> bool,
+) -> Option<serde_json::Value> {
+    if !keep(&root, None) {
+        return None;
+    }
+    let mut stack = match open_json(value, root, 1, max_depth) {
+        Opened::Frame(frame) => vec![frame],
+        Opened::Leaf(leaf) => return Some(leaf),
+    };
+
+    loop {
+        let top = stack.last_mut()?;
+        let next = match &mut top.children {
+            JsonChildren::Array(items) => items
+                .next()
+                .map(|(index, child)| (index.to_string(), None, child)),
+            JsonChildren::Object(members) => members
+                .next()
+                .map(|(key, child)| (key.clone(), Some(key.as_str()), child)),
+        };
+
+        let Some((segment, field, child)) = next else {
+            let done = stack.pop()?;
+            match stack.last_mut() {
+                Some(parent) => {
+                    let key = parent.pending_key.take().unwrap_or_default();
+                    attach(parent, key, done.built);
+                    continue;
+                }
+                None => return Some(done.built),
+            }
+        };
+
+        let mut child_path = top.path.clone();
+        child_path.push(segment.clone());
+        if !keep(&child_path, field) {
+            continue;
+        }
+        let depth = top.depth + 1;
+        match open_json(child, child_path, depth, max_depth) {
+            Opened::Leaf(leaf) => attach(top, segment, leaf),
+            Opened::Frame(frame) => {
+                top.pending_key = Some(segment);
+                stack.push(frame);
+            }
+        }
+    }
+}
+
+/// Copy of `value` with containers deeper than `max_depth` truncated.
+pub fn truncate_json(value: &serde_json::Value, max_depth: usize) -> serde_json::Value {
+    rebuild_json(value, Vec::new(), max_depth, |_, _| true).unwrap_or(serde_json::Value::Null)
+}
+
+/// Nesting depth of a JSON value (a scalar has depth 1), computed without recursion.
+pub fn json_depth(value: &serde_json::Value) -> usize {
+    let mut deepest = 0;
+    let mut stack = vec![(value, 1usize)];
+    while let Some((current, depth)) = stack.pop() {
+        deepest = deepest.max(depth);
+        match current {
+            serde_json::Value::Array(items) => {
+                stack.extend(items.iter().map(|child| (child, depth + 1)))
+            }
+            serde_json::Value::Object(map) => {
+                stack.extend(map.values().map(|child| (child, depth + 1)))
+            }
+            _ => {}
+        }
+    }
+    deepest
+}
+
+/// Build a `Vec` nested `depth` levels deep around `leaf`, and take one apart
+/// again, without recursion. Test support for pathological inputs.
+#[cfg(test)]
+pub(crate) mod pathological {
+    use soroban_env_host::xdr::{ScMap, ScMapEntry, ScVal, ScVec, VecM};
+
+    pub fn nested_vec(depth: usize, leaf: ScVal) -> ScVal {
+        let mut val = leaf;
+        for _ in 1..depth {
+            val = ScVal::Vec(Some(ScVec(VecM::try_from(vec![val]).unwrap())));
+        }
+        val
+    }
+
+    pub fn nested_map(depth: usize, leaf: ScVal) -> ScVal {
+        let mut val = leaf;
+        for i in 1..depth {
+            let entry = ScMapEntry {
+                key: ScVal::U32(i as u32),
+                val,
+            };
+            val = ScVal::Map(Some(ScMap(VecM::try_from(vec![entry]).unwrap())));
+        }
+        val
+    }
+
+    /// The derived `Drop` recurses too, so deep test values are dismantled by hand.
+    pub fn dismantle(val: ScVal) {
+        let mut stack = vec![val];
+        while let Some(current) = stack.pop() {
+            match current {
+                ScVal::Vec(Some(items)) => stack.extend(items.0.into_vec()),
+                ScVal::Map(Some(entries)) => {
+                    for entry in entries.0.into_vec() {
+                        stack.push(entry.key);
+                        stack.push(entry.val);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub fn nested_json(depth: usize) -> serde_json::Value {
+        let mut value = serde_json::json!(1);
+        for i in 1..depth {
+            value = if i % 2 == 0 {
+                serde_json::Value::Array(vec![value])
+            } else {
+                let mut map = serde_json::Map::new();
+                map.insert("n".to_string(), value);
+                serde_json::Value::Object(map)
+            };
+        }
+        value
+    }
+
+    pub fn dismantle_json(value: serde_json::Value) {
+        let mut stack = vec![value];
+        while let Some(current) = stack.pop() {
+            match current {
+                serde_json::Value::Array(items) => stack.extend(items),
+                serde_json::Value::Object(map) => stack.extend(map.into_iter().map(|(_, v)| v)),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pathological::*;
+    use super::*;
+    use soroban_env_host::xdr::{ScSymbol, StringM};
+
+    const DEEP: usize = 200_000;
+
+    fn symbol(name: &str) -> ScVal {
+        ScVal::Symbol(ScSymbol(StringM::try_from(name).unwrap()))
+    }
+
+    #[test]
+    fn shallow_values_render_exactly_like_debug() {
+        let val = nested_map(4, nested_vec(3, symbol("leaf")));
+        assert_eq!(
+            render_scval(&val, DEFAULT_MAX_VALUE_DEPTH),
+            format!("{:?}", val)
+        );
+        assert_eq!(scval_depth(&val), 6);
+    }
+
+    #[test]
+    fn deep_vec_renders_with_marker_instead_of_overflowing() {
+        let val = nested_vec(DEEP, ScVal::U32(7));
+        assert_eq!(scval_depth(&val), DEEP);
+        assert!(scval_exceeds_depth(&val, 10));
+
+        let rendered = render_scval(&val, 50);
+        assert!(rendered.contains(&truncation_marker(50)));
+        assert_eq!(rendered.matches("Vec(Some(").count(), 50);
+        assert!(!rendered.contains("U32(7)"));
+        dismantle(val);
+    }
+
+    #[test]
+    fn deep_map_renders_with_marker_instead_of_overflowing() {
+        let val = nested_map(DEEP, symbol("leaf"));
+        let rendered = render_scval(&val, DEFAULT_MAX_VALUE_DEPTH);
+        assert!(rendered.starts_with("Map(Some(ScMap(VecM([ScMapEntry { key: U32("));
+        assert!(rendered.contains(&truncation_marker(DEFAULT_MAX_VALUE_DEPTH)));
+        dismantle(val);
+    }
+
+    #[test]
+    fn timepoints_and_durations_are_humanized() {
+        use soroban_env_host::xdr::{Duration, TimePoint};
+        let val = ScVal::Vec(Some(
+            vec![
+                ScVal::Timepoint(TimePoint(1_700_000_000)),
+                ScVal::Duration(Duration(5_400)),
+            ]
+            .try_into()
+            .unwrap(),
+        ));
+        assert_eq!(
+            render_scval(&val, DEFAULT_MAX_VALUE_DEPTH),
+            "Vec(Some(ScVec(VecM([Timepoint(2023-11-14T22:13:20Z (1700000000)), \
+             Duration(1h 30m (5400s))]))))"
+        );
+    }
+
+    #[test]
+    fn deep_json_is_truncated_without_recursion() {
+        let value = nested_json(DEEP);
+        assert_eq!(json_depth(&value), DEEP);
+
+        let truncated = truncate_json(&value, 100);
+        assert_eq!(json_depth(&truncated), 101);
+        // Small enough now for serde's recursive serializer.
+        let text = serde_json::to_string(&truncated).unwrap();
+        assert!(text.contains(&truncation_marker(100)));
+
+        dismantle_json(truncated);
+        dismantle_json(value);
+    }
+
+    #[test]
+    fn rebuild_json_drops_rejected_members() {
+        let value = serde_json::json!({"keep": {"a": 1, "secret": 2}, "drop": [1, 2]});
+        let rebuilt = rebuild_json(&value, vec!["root".into()], 10, |path, field| {
+            field != Some("secret") && path.get(1).map(String::as_str) != Some("drop")
+        })
+        .unwrap();
+        assert_eq!(rebuilt, serde_json::json!({"keep": {"a": 1}}));
+    }
+}
@@ -483,6 +483,44 @@ pub fn parse_functions(wasm_bytes: &[u8]) -> Result<Vec<String>> {
     Ok(functions)
 }
 
+/// Host imports whose results do not follow from the invocation's inputs,
+/// as (module, function) export names from the host interface.
+const NONDETERMINISTIC_IMPORTS: &[(&str, &str, &str)] = &[
+    ("p", "_", "PRNG (prng_reseed)"),
+    ("p", "0", "PRNG (prng_bytes_new)"),
+    ("p", "1", "PRNG (prng_u64_in_inclusive_range)"),
+    ("p", "2", "PRNG (prng_vec_shuffle)"),
+    ("x", "3", "ledger sequence (get_ledger_sequence)"),
+    ("x", "4", "ledger time (get_ledger_timestamp)"),
+    ("x", "8", "ledger sequence (get_max_live_until_ledger)"),
+];
+
+/// What the contract imports that can make two runs with the same inputs
+/// differ: the host PRNG, which gets a random seed unless `--seed` is given,
+/// and the ledger clock. Empty for a contract whose result depends only on its
+/// inputs.
+pub fn nondeterminism_signals(wasm_bytes: &[u8]) -> Result<Vec<&'static str>> {
+    let mut signals = Vec::new();
+    for payload in Parser::new(0).parse_all(wasm_bytes) {
+        if let Payload::ImportSection(reader) = payload
+            .map_err(|e| DebuggerError::WasmLoadError(format!("Failed to parse WASM: {}", e)))?
+        {
+            for import in reader {
+                let import = import.map_err(|e| {
+                    DebuggerError::WasmLoadError(format!("Failed to read import: {}", e))
+                })?;
+                if let Some((_, _, signal)) = NONDETERMINISTIC_IMPORTS
+                    .iter()
+                    .find(|(module, name, _)| *module == import.module && *name == import.name)
+                {
+                    signals.push(*signal);
+                }
+            }
+        }
+    }
+    Ok(signals)
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct CrossContractCall {
     pub caller: String,
@@ -907,13 +945,55 @@ pub struct ContractFunctionSignature {
 }
 
 /// A custom error definition extracted from a contract spec.
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CustomError {
     pub code: u32,
     pub name: String,
     pub doc: String,
 }
 
+/// A user-defined type (struct, union or enum) extracted from a contract spec.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserDefinedType {
+    /// `struct`, `union` or `enum`.
+    pub kind: String,
+    pub name: String,
+    /// Field names for a struct, case names for a union or enum.
+    pub members: Vec<String>,
+    pub doc: String,
+}
+
+/// A user-defined type with the field and case types needed to build values
+/// of it from JSON. Type names are written as in [`FunctionParam::type_name`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum TypeDefinition {
+    /// Fields as `(name, type)`, in declaration order. Tuple structs name
+    /// their fields `0`, `1`, ...
+    Struct {
+        name: String,
+        fields: Vec<(String, String)>,
+    },
+    /// Cases as `(name, types)`; unit cases carry no types.
+    Union {
+        name: String,
+        cases: Vec<(String, Vec<String>)>,
+    },
+    /// Integer enum cases as `(name, value)`.
+    Enum {
+        name: String,
+        cases: Vec<(String, u32)>,
+    },
+}
+
+impl TypeDefinition {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Struct { name, .. } | Self::Union { name, .. } | Self::Enum { name, .. } => name,
+        }
+    }
+}
+
 /// Convert an XDR `ScSpecTypeDef` into a human-readable type string.
 fn spec_type_to_string(ty: &stellar_xdr::curr::ScSpecTypeDef) -> String {
     use stellar_xdr::curr::ScSpecTypeDef as T;
@@ -1028,6 +1108,47 @@ pub fn parse_function_signatures(wasm_bytes: &[u8]) -> Result<Vec<ContractFuncti
     Ok(signatures)
 }
 
+/// Parse the doc comment of each function in the `contractspecv0` section,
+/// keyed by function name. Functions without a doc comment are left out.
+pub fn parse_function_docs(wasm_bytes: &[u8]) -> Result<HashMap<String, String>> {
+    use stellar_xdr::curr::{Limited, Limits, ReadXdr, ScSpecEntry};
+
+    let mut docs = HashMap::new();
+    let parser = Parser::new(0);
+
+    for payload in parser.parse_all(wasm_bytes) {
+        let Payload::CustomSection(reader) = payload
+            .map_err(|e| DebuggerError::WasmLoadError(format!("Failed to parse WASM: {}", e)))?
+        else {
+            continue;
+        };
+
+        if reader.name() != "contractspecv0" {
+            continue;
+        }
+
+        let cursor = std::io::Cursor::new(reader.data());
+        let mut limited = Limited::new(cursor, Limits::none());
+
+        loop {
+            match ScSpecEntry::read_xdr(&mut limited) {
+                Ok(ScSpecEntry::FunctionV0(func)) if !func.doc.is_empty() => {
+                    docs.insert(
+                        stringm_to_string(func.name.0.as_slice()),
+                        stringm_to_string(func.doc.as_slice()),
+                    );
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+
+        break;
+    }
+
+    Ok(docs)
+}
+
 #[allow(dead_code)]
 fn val_type_to_wasm_type(vt: &ValType) -> WasmType {
     match vt {
@@ -1093,6 +1214,155 @@ pub fn parse_custom_errors(wasm_bytes: &[u8]) -> Result<Vec<CustomError>> {
     Ok(errors)
 }
 
+/// Parse the structs, unions and enums declared in the `contractspecv0`
+/// custom section, in declaration order. Error enums are left to
+/// [`parse_custom_errors`].
+pub fn parse_user_types(wasm_bytes: &[u8]) -> Result<Vec<UserDefinedType>> {
+    use stellar_xdr::curr::{Limited, Limits, ReadXdr, ScSpecEntry, ScSpecUdtUnionCaseV0};
+
+    let mut types = Vec::new();
+    let parser = Parser::new(0);
+
+    for payload in parser.parse_all(wasm_bytes) {
+        let Payload::CustomSection(reader) = payload
+            .map_err(|e| DebuggerError::WasmLoadError(format!("Failed to parse WASM: {}", e)))?
+        else {
+            continue;
+        };
+
+        if reader.name() != "contractspecv0" {
+            continue;
+        }
+
+        let cursor = std::io::Cursor::new(reader.data());
+        let mut limited = Limited::new(cursor, Limits::none());
+
+        loop {
+            let (kind, name, doc, members) = match ScSpecEntry::read_xdr(&mut limited) {
+                Ok(ScSpecEntry::UdtStructV0(udt)) => (
+                    "struct",
+                    udt.name,
+                    udt.doc,
+                    udt.fields
+                        .iter()
+                        .map(|field| stringm_to_string(field.name.as_slice()))
+                        .collect(),
+                ),
+                Ok(ScSpecEntry::UdtUnionV0(udt)) => (
+                    "union",
+                    udt.name,
+                    udt.doc,
+                    udt.cases
+                        .iter()
+                        .map(|case| match case {
+                            ScSpecUdtUnionCaseV0::VoidV0(case) => {
+                                stringm_to_string(case.name.as_slice())
+                            }
+                            ScSpecUdtUnionCaseV0::TupleV0(case) => {
+                                stringm_to_string(case.name.as_slice())
+                            }
+                        })
+                        .collect(),
+                ),
+                Ok(ScSpecEntry::UdtEnumV0(udt)) => (
+                    "enum",
+                    udt.name,
+                    udt.doc,
+                    udt.cases
+                        .iter()
+                        .map(|case| stringm_to_string(case.name.as_slice()))
+                        .collect(),
+                ),
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            types.push(UserDefinedType {
+                kind: kind.to_string(),
+                name: stringm_to_string(name.as_slice()),
+                members,
+                doc: stringm_to_string(doc.as_slice()),
+            });
+        }
+
+        break;
+    }
+
+    Ok(types)
+}
+
+/// Parse the structs, unions and enums of the `contractspecv0` section with
+/// their field and case types, for building arguments of those types.
+pub fn parse_type_definitions(wasm_bytes: &[u8]) -> Result<Vec<TypeDefinition>> {
+    use stellar_xdr::curr::{Limited, Limits, ReadXdr, ScSpecEntry, ScSpecUdtUnionCaseV0};
+
+    let mut types = Vec::new();
+    let parser = Parser::new(0);
+
+    for payload in parser.parse_all(wasm_bytes) {
+        let Payload::CustomSection(reader) = payload
+            .map_err(|e| DebuggerError::WasmLoadError(format!("Failed to parse WASM: {}", e)))?
+        else {
+            continue;
+        };
+
+        if reader.name() != "contractspecv0" {
+            continue;
+        }
+
+        let cursor = std::io::Cursor::new(reader.data());
+        let mut limited = Limited::new(cursor, Limits::none());
+
+        loop {
+            let definition = match ScSpecEntry::read_xdr(&mut limited) {
+                Ok(ScSpecEntry::UdtStructV0(udt)) => TypeDefinition::Struct {
+                    name: stringm_to_string(udt.name.as_slice()),
+                    fields: udt
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            (
+                                stringm_to_string(field.name.as_slice()),
+                                spec_type_to_string(&field.type_),
+                            )
+                        })
+                        .collect(),
+                },
+                Ok(ScSpecEntry::UdtUnionV0(udt)) => TypeDefinition::Union {
+                    name: stringm_to_string(udt.name.as_slice()),
+                    cases: udt
+                        .cases
+                        .iter()
+                        .map(|case| match case {
+                            ScSpecUdtUnionCaseV0::VoidV0(case) => {
+                                (stringm_to_string(case.name.as_slice()), Vec::new())
+                            }
+                            ScSpecUdtUnionCaseV0::TupleV0(case) => (
+                                stringm_to_string(case.name.as_slice()),
+                                case.type_.iter().map(spec_type_to_string).collect(),
+                            ),
+                        })
+                        .collect(),
+                },
+                Ok(ScSpecEntry::UdtEnumV0(udt)) => TypeDefinition::Enum {
+                    name: stringm_to_string(udt.name.as_slice()),
+                    cases: udt
+                        .cases
+                        .iter()
+                        .map(|case| (stringm_to_string(case.name.as_slice()), case.value))
+                        .collect(),
+                },
+                Ok(_) => continue,
+                Err(_) => break,
+            };
+            types.push(definition);
+        }
+
+        break;
+    }
+
+    Ok(types)
+}
+
 // ─── tests ────────────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -1124,6 +1394,44 @@ mod tests {
         assert_eq!(hash1, hash2);
     }
 
+    // ── Determinism signal tests ──────────────────────────────────────────────
+
+    /// Module importing the given host functions as `() -> ()`.
+    fn make_import_wasm(imports: &[(&str, &str)]) -> Vec<u8> {
+        let mut wasm = vec![0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+        wasm.extend_from_slice(&[0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        let mut section = vec![imports.len() as u8];
+        for (module, name) in imports {
+            section.push(module.len() as u8);
+            section.extend_from_slice(module.as_bytes());
+            section.push(name.len() as u8);
+            section.extend_from_slice(name.as_bytes());
+            section.extend_from_slice(&[0x00, 0x00]);
+        }
+        wasm.push(0x02);
+        wasm.push(section.len() as u8);
+        wasm.extend_from_slice(&section);
+        wasm
+    }
+
+    #[test]
+    fn prng_and_ledger_clock_imports_are_nondeterministic() {
+        let wasm = make_import_wasm(&[("l", "_"), ("p", "1"), ("x", "4")]);
+        assert_eq!(
+            nondeterminism_signals(&wasm).unwrap(),
+            vec![
+                "PRNG (prng_u64_in_inclusive_range)",
+                "ledger time (get_ledger_timestamp)"
+            ]
+        );
+    }
+
+    #[test]
+    fn storage_only_imports_are_deterministic() {
+        let wasm = make_import_wasm(&[("l", "_"), ("l", "1"), ("x", "1")]);
+        assert!(nondeterminism_signals(&wasm).unwrap().is_empty());
+    }
+
     // ── Checksum verification tests ───────────────────────────────────────────
 
     #[test]
@@ -1429,4 +1737,106 @@ implementation_notes=Line-based format
         assert_eq!(errors[1].name, "ErrorTwo");
         assert_eq!(errors[1].doc, "My Error 2");
     }
+
+    #[test]
+    fn extract_user_types() {
+        use stellar_xdr::curr::{
+            ScSpecEntry, ScSpecTypeDef, ScSpecUdtEnumCaseV0, ScSpecUdtEnumV0,
+            ScSpecUdtStructFieldV0, ScSpecUdtStructV0, ScSpecUdtUnionCaseV0,
+            ScSpecUdtUnionCaseVoidV0, ScSpecUdtUnionV0, StringM, WriteXdr,
+        };
+
+        fn s<const N: u32>(value: &str) -> StringM<N> {
+            StringM::try_from(value.as_bytes().to_vec()).unwrap()
+        }
+        let entries = [
+            ScSpecEntry::UdtStructV0(ScSpecUdtStructV0 {
+                doc: s("A position"),
+                lib: s(""),
+                name: s("Position"),
+                fields: vec![ScSpecUdtStructFieldV0 {
+                    doc: s(""),
+                    name: s("amount"),
+                    type_: ScSpecTypeDef::I128,
+                }]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtUnionV0(ScSpecUdtUnionV0 {
+                doc: s(""),
+                lib: s(""),
+                name: s("DataKey"),
+                cases: vec![ScSpecUdtUnionCaseV0::VoidV0(ScSpecUdtUnionCaseVoidV0 {
+                    doc: s(""),
+                    name: s("Admin"),
+                })]
+                .try_into()
+                .unwrap(),
+            }),
+            ScSpecEntry::UdtEnumV0(ScSpecUdtEnumV0 {
+                doc: s(""),
+                lib: s(""),
+                name: s("Side"),
+                cases: vec![
+                    ScSpecUdtEnumCaseV0 {
+                        doc: s(""),
+                        name: s("Buy"),
+                        value: 0,
+                    },
+                    ScSpecUdtEnumCaseV0 {
+                        doc: s(""),
+                        name: s("Sell"),
+                        value: 1,
+                    },
+                ]
+                .try_into()
+                .unwrap(),
+            }),
+        ];
+        let mut payload = Vec::new();
+        for entry in &entries {
+            payload.extend(entry.to_xdr(stellar_xdr::curr::Limits::none()).unwrap());
+        }
+
+        let wasm = make_custom_section_wasm("contractspecv0", &payload);
+
+        let types = parse_user_types(&wasm).expect("parsing should succeed");
+        let summary: Vec<(&str, &str, Vec<&str>)> = types
+            .iter()
+            .map(|udt| {
+                (
+                    udt.kind.as_str(),
+                    udt.name.as_str(),
+                    udt.members.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("struct", "Position", vec!["amount"]),
+                ("union", "DataKey", vec!["Admin"]),
+                ("enum", "Side", vec!["Buy", "Sell"]),
+            ]
+        );
+        assert_eq!(types[0].doc, "A position");
+
+        assert_eq!(
+            parse_type_definitions(&wasm).expect("parsing should succeed"),
+            [
+                TypeDefinition::Struct {
+                    name: "Position".to_string(),
+                    fields: vec![("amount".to_string(), "I128".to_string())],
+                },
+                TypeDefinition::Union {
+                    name: "DataKey".to_string(),
+                    cases: vec![("Admin".to_string(), vec![])],
+                },
+                TypeDefinition::Enum {
+                    name: "Side".to_string(),
+                    cases: vec![("Buy".to_string(), 0), ("Sell".to_string(), 1)],
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,208 @@
+//! Warnings collected over a command so they can be summarised at the end.
+//!
+//! Call sites still print their warning where it happens; [`record`] also
+//! files it under a stable [`WarningCategory`] so `run` can print a grouped
+//! summary, include it in JSON output and enforce `--max-warnings`.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Mutex;
+
+/// Exit code used when a run emits more warnings than `--max-warnings` allows.
+pub const MAX_WARNINGS_EXIT_CODE: i32 = 3;
+
+static COLLECTED: Mutex<Vec<Warning>> = Mutex::new(Vec::new());
+
+/// Stable identifier for a kind of warning, used by `--allow-warning`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningCategory {
+    /// Ledger entries close to expiry
+    Ttl,
+    /// Deprecated command-line flags
+    Deprecation,
+    /// Failed expectations in `--expect-mode warn`
+    Expectation,
+    /// Mocks that ran out of values
+    Mock,
+    /// Events that could not be decoded or do not match the event schema
+    Event,
+    /// Ledger footprint that could not be extracted
+    Ledger,
+    /// Unit test generation that had nothing to generate from
+    Codegen,
+    /// Execution trace that could not be written
+    Trace,
+    /// Read-only functions that wrote storage or emitted events
+    Convention,
+    /// Invocation observers that returned an error or panicked
+    Observer,
+    /// Local WASM that differs from the code deployed in the network snapshot
+    Code,
+    /// Arguments that could not be checked against the contract spec
+    Arguments,
+}
+
+impl WarningCategory {
+    pub fn id(self) -> &'static str {
+        match self {
+            WarningCategory::Ttl => "ttl",
+            WarningCategory::Deprecation => "deprecation",
+            WarningCategory::Expectation => "expectation",
+            WarningCategory::Mock => "mock",
+            WarningCategory::Event => "event",
+            WarningCategory::Ledger => "ledger",
+            WarningCategory::Codegen => "codegen",
+            WarningCategory::Trace => "trace",
+            WarningCategory::Convention => "convention",
+            WarningCategory::Observer => "observer",
+            WarningCategory::Code => "code",
+            WarningCategory::Arguments => "arguments",
+        }
+    }
+}
+
+impl fmt::Display for WarningCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Warning {
+    pub category: WarningCategory,
+    pub message: String,
+}
+
+/// Collect a warning for the end-of-command summary.
+pub fn record(category: WarningCategory, message: impl Into<String>) {
+    let warning = Warning {
+        category,
+        message: message.into(),
+    };
+    match COLLECTED.lock() {
+        Ok(mut warnings) => warnings.push(warning),
+        Err(poisoned) => poisoned.into_inner().push(warning),
+    }
+}
+
+/// Everything recorded so far, in emission order.
+pub fn collected() -> Vec<Warning> {
+    match COLLECTED.lock() {
+        Ok(warnings) => warnings.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
+
+/// Warnings grouped by category, with allowed categories set aside.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WarningSummary {
+    /// Warnings that count towards `--max-warnings`.
+    pub total: usize,
+    /// Warnings in categories passed to `--allow-warning`.
+    pub allowed: usize,
+    pub by_category: BTreeMap<WarningCategory, usize>,
+    pub warnings: Vec<Warning>,
+}
+
+impl WarningSummary {
+    pub fn new(warnings: Vec<Warning>, allow: &[WarningCategory]) -> Self {
+        let (allowed, counted): (Vec<Warning>, Vec<Warning>) = warnings
+            .into_iter()
+            .partition(|warning| allow.contains(&warning.category));
+        let mut by_category = BTreeMap::new();
+        for warning in &counted {
+            *by_category.entry(warning.category).or_insert(0) += 1;
+        }
+        Self {
+            total: counted.len(),
+            allowed: allowed.len(),
+            by_category,
+            warnings: counted,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == 0 && self.allowed == 0
+    }
+
+    /// Whether the summary breaks a `--max-warnings` limit.
+    pub fn exceeds(&self, max: Option<usize>) -> bool {
+        max.is_some_and(|max| self.total > max)
+    }
+
+    /// One line per category with its count.
+    pub fn render(&self) -> String {
+        let mut out = format!("{} warning(s)", self.total);
+        if self.allowed > 0 {
+            out.push_str(&format!(" ({} allowed)", self.allowed));
+        }
+        let width = self
+            .by_category
+            .keys()
+            .map(|category| category.id().len())
+            .max()
+            .unwrap_or(0);
+        for (category, count) in &self.by_category {
+            out.push_str(&format!("\n  {:<width$}  {}", category.id(), count));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(category: WarningCategory, message: &str) -> Warning {
+        Warning {
+            category,
+            message: message.to_string(),
+        }
+    }
+
+    fn three_categories() -> Vec<Warning> {
+        vec![
+            warning(WarningCategory::Ttl, "balance near expiry"),
+            warning(WarningCategory::Mock, "oracle.price ran out of values"),
+            warning(WarningCategory::Ttl, "admin near expiry"),
+            warning(WarningCategory::Deprecation, "--wasm is deprecated"),
+        ]
+    }
+
+    #[test]
+    fn groups_by_category() {
+        let summary = WarningSummary::new(three_categories(), &[]);
+        assert_eq!(summary.total, 4);
+        assert_eq!(summary.by_category[&WarningCategory::Ttl], 2);
+        assert_eq!(summary.by_category[&WarningCategory::Mock], 1);
+        assert_eq!(summary.by_category[&WarningCategory::Deprecation], 1);
+        assert_eq!(
+            summary.render(),
+            "4 warning(s)\n  ttl          2\n  deprecation  1\n  mock         1"
+        );
+    }
+
+    #[test]
+    fn gate_counts_only_warnings_that_are_not_allowed() {
+        let summary = WarningSummary::new(three_categories(), &[]);
+        assert!(!summary.exceeds(None));
+        assert!(!summary.exceeds(Some(4)));
+        assert!(summary.exceeds(Some(3)));
+
+        let summary = WarningSummary::new(three_categories(), &[WarningCategory::Ttl]);
+        assert_eq!((summary.total, summary.allowed), (2, 2));
+        assert!(!summary.by_category.contains_key(&WarningCategory::Ttl));
+        assert!(!summary.exceeds(Some(2)));
+        assert!(summary.render().starts_with("2 warning(s) (2 allowed)"));
+    }
+
+    #[test]
+    fn json_uses_stable_category_ids() {
+        let summary = WarningSummary::new(three_categories(), &[]);
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["by_category"]["ttl"], 2);
+        assert_eq!(json["warnings"][3]["category"], "deprecation");
+    }
+}
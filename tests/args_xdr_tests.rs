@@ -0,0 +1,27 @@
+//! `run --args-xdr`: the whole argument list as base64 XDR of an ScVec.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_echo(args_xdr: &str) -> (bool, String, String) {
+    fixtures::run(fixtures::names::ECHO, "echo", &["--args-xdr", args_xdr])
+}
+
+#[test]
+fn xdr_argument_list_is_passed_through() {
+    // An ScVec holding U32(42).
+    let (success, stdout, stderr) = run_echo("AAAAEAAAAAEAAAABAAAAAwAAACo=");
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("U32(42)"), "{}", stdout);
+}
+
+#[test]
+fn undecodable_xdr_names_the_input() {
+    let (success, _, stderr) = run_echo("not-xdr");
+    assert!(!success);
+    assert!(
+        stderr.contains("Invalid --args-xdr 'not-xdr'"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,83 @@
+//! `attach` observes a running server session without being able to change it.
+
+use soroban_debugger::client::RemoteClient;
+use soroban_debugger::server::DebugServer;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+/// Serve a session named `session` on a background thread and return its address.
+fn start_server(session: &str) -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let server = DebugServer::new(None, None, None)
+        .unwrap()
+        .with_session(session);
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let local = tokio::task::LocalSet::new();
+        local.block_on(&runtime, async move {
+            let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+            let _ = server.run_on(listener).await;
+        });
+    });
+    addr
+}
+
+#[test]
+fn attached_cli_reads_state_and_refuses_calls() {
+    let addr = start_server("staging");
+    let mut owner = RemoteClient::connect(&addr, None).unwrap();
+    owner
+        .load_contract(
+            fixtures::get_fixture_path(fixtures::names::COUNTER)
+                .to_str()
+                .unwrap(),
+        )
+        .unwrap();
+    owner.execute("increment", None).unwrap();
+
+    let output = fixtures::soroban_debug()
+        .args(["attach", "--url", &addr, "--session", "staging"])
+        .write_stdin("storage\ntrace\nwatch step_count\ncall increment\nexit\n")
+        .output()
+        .expect("Failed to execute attach");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Attached read-only to session 'staging'"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("contract_data"), "{}", stdout);
+    assert!(stdout.contains("increment()"), "{}", stdout);
+    assert!(stdout.contains("watch step_count ="), "{}", stdout);
+    assert!(
+        stdout.contains("Permission denied: Execute would invoke the session"),
+        "{}",
+        stdout
+    );
+
+    // The attached `call` did not run.
+    assert!(owner.execute("get", None).unwrap().contains('1'));
+}
+
+#[test]
+fn attach_to_unknown_session_fails() {
+    let addr = start_server("staging");
+
+    let output = fixtures::soroban_debug()
+        .args(["attach", "--url", &addr, "--session", "prod"])
+        .write_stdin("exit\n")
+        .output()
+        .expect("Failed to execute attach");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("No session named"), "{}", stderr);
+}
@@ -0,0 +1,50 @@
+//! `run --export-auth` writes the authorization entries recorded during a run.
+
+use soroban_debugger::inspector::auth_export::AuthExport;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn export_without_required_auth_is_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("auth.json");
+
+    let output = fixtures::soroban_debug()
+        .arg("--quiet")
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--function")
+        .arg("increment")
+        .arg("--export-auth")
+        .arg(&path)
+        .arg("--auth-expiration-ledger")
+        .arg("5000")
+        .output()
+        .expect("Failed to execute run command");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let export: AuthExport =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert!(export.entries.is_empty());
+}
+
+#[test]
+fn expiration_ledger_requires_export_auth() {
+    let output = fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--function")
+        .arg("increment")
+        .arg("--auth-expiration-ledger")
+        .arg("5000")
+        .output()
+        .expect("Failed to execute run command");
+    assert!(!output.status.success());
+}
@@ -0,0 +1,37 @@
+//! `run` at a breakpoint without a terminal: the stop is printed and the
+//! run goes on instead of waiting at the prompt.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn stops_are_printed_and_the_run_goes_on_without_a_terminal() {
+    let (success, stdout, stderr) = fixtures::run_counter(&["--breakpoint", "increment"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let stop = stdout
+        .split("Breakpoint hit: increment")
+        .nth(1)
+        .unwrap_or_else(|| panic!("no breakpoint stop in:\n{}", stdout));
+    assert!(stop.contains("Arguments: "), "{}", stop);
+    assert!(
+        stop.contains("Call stack (innermost last):\n  0: increment() in C"),
+        "{}",
+        stop
+    );
+    assert!(!stop.contains("(break)"), "{}", stop);
+    assert!(stop.contains("Execution Complete"), "{}", stop);
+
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--breakpoint", "increment", "--non-interactive"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("Breakpoint hit: increment"), "{}", stdout);
+}
+
+#[test]
+fn json_output_does_not_print_stops() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--breakpoint", "increment", "--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(!stdout.contains("Breakpoint hit"), "{}", stdout);
+    serde_json::from_str::<serde_json::Value>(&stdout).expect("valid JSON");
+}
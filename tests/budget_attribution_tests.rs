@@ -0,0 +1,90 @@
+//! Converting a large argument and seeding storage are charged to `setup`,
+//! not to the contract.
+
+use soroban_debugger::runtime::executor::ContractExecutor;
+use soroban_debugger::runtime::result::ExecutionRecord;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+const LARGE_ARG_BYTES: usize = 512 * 1024;
+
+fn echo_bytes(wasm: &[u8], len: usize) -> ExecutionRecord {
+    let mut executor = ContractExecutor::new(wasm.to_vec()).expect("create executor");
+    let args = format!(r#"[{{"type":"bytes","value":"0x{}"}}]"#, "ab".repeat(len));
+    executor.execute("echo", Some(&args)).expect("execute echo");
+    executor
+        .last_execution()
+        .cloned()
+        .expect("execution record")
+}
+
+#[test]
+fn large_bytes_argument_is_attributed_to_setup() {
+    let wasm = fixtures::load_fixture(fixtures::names::ECHO);
+
+    let small = echo_bytes(&wasm, 4);
+    let large = echo_bytes(&wasm, LARGE_ARG_BYTES);
+
+    assert!(
+        large.setup_budget.memory_bytes >= LARGE_ARG_BYTES as u64,
+        "expected at least {} bytes of setup memory, got {}",
+        LARGE_ARG_BYTES,
+        large.setup_budget.memory_bytes
+    );
+
+    let setup_growth = large.setup_budget.memory_bytes - small.setup_budget.memory_bytes;
+    let execution_growth = large
+        .budget
+        .memory_bytes
+        .saturating_sub(small.budget.memory_bytes);
+    assert!(
+        setup_growth > execution_growth * 4,
+        "setup grew by {} bytes but execution by {}",
+        setup_growth,
+        execution_growth
+    );
+}
+
+fn increment_with_storage(wasm: &[u8], storage: Option<&str>) -> ExecutionRecord {
+    let mut executor = ContractExecutor::new(wasm.to_vec()).expect("create executor");
+    if let Some(storage) = storage {
+        executor
+            .set_initial_storage(storage.to_string())
+            .expect("seed storage");
+    }
+    executor
+        .execute("increment", None)
+        .expect("execute increment");
+    executor
+        .last_execution()
+        .cloned()
+        .expect("execution record")
+}
+
+#[test]
+fn seeding_storage_is_attributed_to_setup() {
+    let wasm = fixtures::load_fixture(fixtures::names::COUNTER);
+
+    let unseeded = increment_with_storage(&wasm, None);
+    for storage in [
+        r#"{"x":1}"#,
+        r#"[{"key":{"type":"symbol","value":"x"},"value":1,"durability":"persistent"}]"#,
+    ] {
+        let seeded = increment_with_storage(&wasm, Some(storage));
+        assert!(
+            seeded.setup_budget.cpu_instructions > unseeded.setup_budget.cpu_instructions,
+            "seeding {} took {} setup CPU, an unseeded run {}",
+            storage,
+            seeded.setup_budget.cpu_instructions,
+            unseeded.setup_budget.cpu_instructions
+        );
+        assert!(
+            seeded.setup_budget.memory_bytes > unseeded.setup_budget.memory_bytes,
+            "seeding {} took {} setup bytes, an unseeded run {}",
+            storage,
+            seeded.setup_budget.memory_bytes,
+            unseeded.setup_budget.memory_bytes
+        );
+    }
+}
@@ -0,0 +1,88 @@
+//! The budget footer on successful and failed runs, and the raw figures in
+//! `--output json`.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn json_budget_carries_the_limits() {
+    let (success, stdout, stderr) = fixtures::run("counter", "increment", &["--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let budget = &json["result"]["budget"];
+    assert!(
+        budget["cpu_instructions"].as_u64().unwrap() > 0,
+        "{}",
+        budget
+    );
+    assert!(
+        budget["cpu_limit"].as_u64().unwrap() > budget["cpu_instructions"].as_u64().unwrap(),
+        "{}",
+        budget
+    );
+    assert!(budget["memory_limit"].as_u64().unwrap() > 0, "{}", budget);
+}
+
+#[test]
+fn failed_run_still_reports_its_budget() {
+    let (success, _, stderr) = fixtures::run("always_panic", "panic", &[]);
+    assert!(!success);
+    assert!(stderr.contains("Budget: "), "{}", stderr);
+    assert!(stderr.contains("% of limit)"), "{}", stderr);
+
+    let (success, stdout, _) = fixtures::run("always_panic", "panic", &["--output", "json"]);
+    assert!(!success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert!(
+        json["error"]["budget"]["cpu_instructions"]
+            .as_u64()
+            .unwrap()
+            > 0,
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn cpu_limit_stops_the_call_and_names_the_resource() {
+    let (success, stdout, stderr) =
+        fixtures::run("counter", "increment", &["--cpu-limit", "100000"]);
+    assert!(!success, "{}", stdout);
+    assert!(
+        stderr.contains("CPU budget exceeded: stopped at "),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains(" of 100,000 instructions ("), "{}", stderr);
+    assert!(stderr.contains("of 100,000 insns"), "{}", stderr);
+}
+
+#[test]
+fn footer_is_measured_against_the_limits_in_force() {
+    let (success, stdout, stderr) = fixtures::run(
+        "counter",
+        "increment",
+        &["--limits", "testnet", "--mem-limit", "20000000"],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(
+        stdout.contains("Budget limits: 100,000,000 insns"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("of 100,000,000 insns"), "{}", stdout);
+    assert!(stdout.contains("of 19.07 MB mem"), "{}", stdout);
+
+    let (success, stdout, stderr) =
+        fixtures::run("counter", "increment", &["--limits", "unlimited"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("insns (no limit)"), "{}", stdout);
+
+    let (success, _, stderr) = fixtures::run("counter", "increment", &["--limits", "devnet"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("Unknown --limits preset 'devnet'"),
+        "{}",
+        stderr
+    );
+}
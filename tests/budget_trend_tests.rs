@@ -168,7 +168,10 @@ fn history_prune_max_records_removes_oldest() {
     // Verify file actually has 3 records now.
     let dir = temp.path().join(".soroban-debug");
     let content = std::fs::read_to_string(dir.join("history.json")).unwrap();
-    let parsed: Vec<serde_json::Value> = serde_json::from_str(&content).unwrap();
+    let parsed: Vec<serde_json::Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
     assert_eq!(parsed.len(), 3);
 }
 
@@ -0,0 +1,89 @@
+//! `run --call` and `--calls-file`: several calls against the same contract
+//! state, reported one by one.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_counter(args: &[&str]) -> Command {
+    let mut cmd = fixtures::soroban_debug();
+    cmd.arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .args(args);
+    cmd
+}
+
+#[test]
+fn calls_share_state_and_report_each_result() {
+    run_counter(&[
+        "--call",
+        "increment",
+        "--call",
+        "increment",
+        "--call",
+        "get",
+    ])
+    .assert()
+    .success()
+    .stdout(predicate::str::contains("Calls: increment, increment, get"))
+    .stdout(predicate::str::contains("Call 1/3: increment -> I64(1)"))
+    .stdout(predicate::str::contains("Call 2/3: increment -> I64(2)"))
+    .stdout(predicate::str::contains("Call 3/3: get -> I64(2)"))
+    .stdout(predicate::str::contains("--- Storage Changes ---"));
+}
+
+#[test]
+fn a_failing_call_stops_the_sequence_and_is_named() {
+    run_counter(&["--call", "increment", "--call", "missing", "--call", "get"])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("Call 1/3: increment -> I64(1)"))
+        .stdout(predicate::str::contains("Call 3/3").not())
+        .stderr(predicate::str::contains("Call 2/3 (missing) failed"));
+}
+
+#[test]
+fn calls_file_lists_the_calls_in_json_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let calls = dir.path().join("calls.json");
+    std::fs::write(&calls, r#"["increment", {"function": "increment"}, "get"]"#).unwrap();
+
+    let output = run_counter(&["--calls-file", calls.to_str().unwrap(), "--output", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json_start = stdout.find("{\n").expect("JSON output expected");
+    let value: serde_json::Value = serde_json::Deserializer::from_str(&stdout[json_start..])
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap();
+    let calls = value["result"]["calls"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{}", value));
+    let returned: Vec<&serde_json::Value> =
+        calls.iter().map(|call| &call["return_value"]).collect();
+    assert_eq!(
+        returned,
+        [
+            &serde_json::json!(1),
+            &serde_json::json!(2),
+            &serde_json::json!(2)
+        ]
+    );
+    assert_eq!(value["result"]["return_value"], 2);
+}
+
+#[test]
+fn call_cannot_be_combined_with_repeat() {
+    run_counter(&["--call", "increment", "--repeat", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--call cannot be used with --repeat",
+        ));
+}
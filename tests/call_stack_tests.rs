@@ -0,0 +1,35 @@
+//! The chain of contract calls printed when a run fails, and listed as
+//! `call_stack` in its JSON error.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_panic(args: &[&str]) -> (bool, String, String) {
+    fixtures::run(fixtures::names::ALWAYS_PANIC, "panic", args)
+}
+
+#[test]
+fn failed_run_prints_the_calls_it_trapped_in() {
+    let (success, stdout, stderr) = run_panic(&[]);
+    assert!(!success, "{}", stdout);
+    let stack = stderr
+        .split("--- Call Stack (innermost last) ---")
+        .nth(1)
+        .unwrap_or_else(|| panic!("no call stack in:\n{}", stderr));
+    assert!(stack.contains("  0: panic() in C"), "{}", stack);
+}
+
+#[test]
+fn failed_json_run_lists_the_call_stack() {
+    let (success, stdout, _) = run_panic(&["--output", "json"]);
+    assert!(!success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let stack = json["error"]["call_stack"]
+        .as_array()
+        .unwrap_or_else(|| panic!("no call_stack in {}", json));
+    assert_eq!(stack.len(), 1, "{}", json);
+    assert_eq!(stack[0]["depth"], 0);
+    assert_eq!(stack[0]["function"], "panic");
+    assert_eq!(stack[0]["args"], serde_json::json!([]));
+    assert!(stack[0]["contract"].as_str().unwrap().starts_with('C'));
+}
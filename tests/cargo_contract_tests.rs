@@ -0,0 +1,172 @@
+//! `run --package` / `--build` resolve the contract WASM through cargo.
+//!
+//! A shell shim stands in for cargo so the tests need neither the wasm target
+//! nor a real build: it answers `cargo metadata` for a copy of the fixture
+//! workspace and "builds" by copying the counter fixture into `target/`.
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+struct Workspace {
+    _dir: tempfile::TempDir,
+    root: PathBuf,
+    shim: PathBuf,
+    build_log: PathBuf,
+    artifact: PathBuf,
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    std::fs::create_dir_all(to).unwrap();
+    for entry in std::fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        if entry.file_type().unwrap().is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else {
+            std::fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+/// Copy the fixture workspace and write a cargo shim for it. With
+/// `build_error`, `cargo build` fails printing that message to stderr.
+fn workspace(build_error: Option<&str>) -> Workspace {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path().join("ws");
+    copy_dir(&fixtures::fixtures_root().join("cargo_workspace"), &root);
+
+    let member_id = "hello-contract 0.1.0 (path+file:///ws/contracts/hello)";
+    let metadata = serde_json::json!({
+        "packages": [{
+            "name": "hello-contract",
+            "id": member_id,
+            "manifest_path": root.join("contracts/hello/Cargo.toml"),
+            "targets": [{ "name": "hello-contract", "crate_types": ["cdylib"] }],
+        }],
+        "workspace_members": [member_id],
+        "target_directory": root.join("target"),
+        "workspace_root": root,
+    });
+    let metadata_file = dir.path().join("metadata.json");
+    std::fs::write(&metadata_file, metadata.to_string()).unwrap();
+
+    let artifact = root.join("target/wasm32-unknown-unknown/release/hello_contract.wasm");
+    let build_log = dir.path().join("build.log");
+    let build = match build_error {
+        Some(message) => format!("echo '{}' >&2; exit 101", message),
+        None => format!(
+            "mkdir -p '{}' && cp '{}' '{}'",
+            artifact.parent().unwrap().display(),
+            fixtures::get_fixture_path(fixtures::names::COUNTER).display(),
+            artifact.display()
+        ),
+    };
+    let shim = dir.path().join("fake-cargo");
+    std::fs::write(
+        &shim,
+        format!(
+            "#!/bin/sh\ncase \"$1\" in\n  metadata) cat '{}' ;;\n  build) echo \"$@\" >> '{}'; {} ;;\n  *) exit 1 ;;\nesac\n",
+            metadata_file.display(),
+            build_log.display(),
+            build
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&shim, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    Workspace {
+        _dir: dir,
+        root,
+        shim,
+        build_log,
+        artifact,
+    }
+}
+
+fn run(ws: &Workspace, extra: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .current_dir(&ws.root)
+        .env("CARGO", &ws.shim)
+        .arg("run")
+        .args(extra)
+        .args(["--function", "increment"])
+        .output()
+        .expect("Failed to execute run command")
+}
+
+#[test]
+fn build_then_debug_package_by_name() {
+    let ws = workspace(None);
+
+    let output = run(&ws, &["--package", "hello-contract", "--build"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains(&ws.artifact.display().to_string()),
+        "{}",
+        stdout
+    );
+    let hash = soroban_debugger::utils::wasm::load_wasm(&ws.artifact)
+        .unwrap()
+        .sha256_hash;
+    assert!(stdout.contains(&format!("SHA-256: {}", hash)), "{}", stdout);
+
+    let log = std::fs::read_to_string(&ws.build_log).unwrap();
+    assert!(log.contains("--target wasm32-unknown-unknown"), "{}", log);
+    assert!(log.contains("--profile release"), "{}", log);
+    assert!(log.contains("--package hello-contract"), "{}", log);
+}
+
+#[test]
+fn contract_directory_resolves_an_existing_build() {
+    let ws = workspace(None);
+    std::fs::create_dir_all(ws.artifact.parent().unwrap()).unwrap();
+    std::fs::copy(
+        fixtures::get_fixture_path(fixtures::names::COUNTER),
+        &ws.artifact,
+    )
+    .unwrap();
+
+    let contract = ws.root.join("contracts/hello");
+    let output = run(&ws, &["--contract", contract.to_str().unwrap()]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("hello_contract.wasm"));
+    assert!(!ws.build_log.exists(), "no build without --build");
+}
+
+#[test]
+fn build_failure_surfaces_cargo_stderr() {
+    let ws = workspace(Some(
+        "error[E0425]: cannot find value `balance` in this scope",
+    ));
+
+    let output = run(&ws, &["--package", "hello-contract", "--build"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Contract build failed"), "{}", stderr);
+    assert!(stderr.contains("error[E0425]"), "{}", stderr);
+}
+
+#[test]
+fn missing_artifact_suggests_build() {
+    let ws = workspace(None);
+
+    let output = run(&ws, &["--package", "hello-contract"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("hello_contract.wasm"), "{}", stderr);
+    assert!(stderr.contains("--build"), "{}", stderr);
+}
@@ -372,3 +372,44 @@ fn test_inspect_with_empty_contract_file() {
         .failure();
     // Error could be about invalid WASM or other parsing issues
 }
+
+#[test]
+fn test_run_rejects_conflicting_flags_before_loading_the_contract() {
+    let mut cmd = assert_cmd::Command::cargo_bin("soroban-debug").expect("Failed to find binary");
+    cmd.args([
+        "run",
+        "--contract",
+        "/nonexistent/contract.wasm",
+        "--function",
+        "test",
+        "--dry-run",
+        "--export-storage",
+        "storage.json",
+    ])
+    .assert()
+    .failure()
+    .code(2)
+    .stderr(predicate::str::contains(
+        "--dry-run cannot be used with --export-storage: a dry run only loads the contract",
+    ))
+    .stderr(predicate::str::contains("Failed to read").not());
+}
+
+#[test]
+fn test_run_rejects_flags_missing_their_requirement() {
+    let mut cmd = assert_cmd::Command::cargo_bin("soroban-debug").expect("Failed to find binary");
+    cmd.args([
+        "run",
+        "--contract",
+        "/nonexistent/contract.wasm",
+        "--function",
+        "test",
+        "--append",
+    ])
+    .assert()
+    .failure()
+    .code(2)
+    .stderr(predicate::str::contains(
+        "--append requires --save-output: it appends to the --save-output file",
+    ));
+}
@@ -190,6 +190,79 @@ expected_return = "I64(1)"
         ));
 }
 
+#[test]
+fn scenario_tracks_which_step_wrote_storage() {
+    let wasm = fixture_wasm("counter");
+    let scenario = NamedTempFile::new().unwrap();
+    fs::write(
+        scenario.path(),
+        r#"
+[[steps]]
+function = "get"
+assertions = ["writer(c) == none"]
+
+[[steps]]
+function = "increment"
+assertions = ["writer(c) == increment", "step(c) == 2"]
+
+[[steps]]
+function = "get"
+assertions = ["writers(c) == increment", "step(c) == 2"]
+"#,
+    )
+    .unwrap();
+
+    base_cmd()
+        .args([
+            "scenario",
+            "--scenario",
+            scenario.path().to_str().unwrap(),
+            "--contract",
+            wasm.to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Assertion passed: step(c) == 2"))
+        .stdout(predicate::str::contains(
+            "All scenario steps passed successfully!",
+        ));
+}
+
+#[test]
+fn scenario_failed_invariant_names_the_writing_step() {
+    let wasm = fixture_wasm("counter");
+    let scenario = NamedTempFile::new().unwrap();
+    fs::write(
+        scenario.path(),
+        r#"
+[[steps]]
+function = "get"
+
+[[steps]]
+function = "increment"
+
+[[steps]]
+function = "get"
+assertions = ['writers(c) == "initialize"']
+"#,
+    )
+    .unwrap();
+
+    base_cmd()
+        .args([
+            "scenario",
+            "--scenario",
+            scenario.path().to_str().unwrap(),
+            "--contract",
+            wasm.to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains(
+            r#"Assertion failed: writers(c) == "initialize" (written by step 2 increment"#,
+        ));
+}
+
 #[test]
 fn scenario_accepts_timeout_defaults_and_step_overrides() {
     let wasm = fixture_wasm("counter");
@@ -669,3 +742,86 @@ fn repl_supports_conditional_breakpoints() {
         combined
     );
 }
+
+#[test]
+fn repl_script_stops_at_first_failed_assert() {
+    let wasm = fixture_wasm("counter");
+    let script = NamedTempFile::new().unwrap();
+    fs::write(
+        script.path(),
+        "# the counter is seeded with 42\ncall get\nassert result == 42\n\ncall get\nassert result > 100\ncall increment\n",
+    )
+    .unwrap();
+
+    base_cmd()
+        .args([
+            "repl",
+            "--contract",
+            wasm.to_str().unwrap(),
+            "--storage",
+            r#"{"c": 42}"#,
+            "--script",
+            script.path().to_str().unwrap(),
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("+ assert result == 42"))
+        // The error can be wrapped to the terminal width.
+        .stderr(
+            predicate::str::is_match(
+                r":6:\s+assertion\s+failed:\s+result\s+>\s+100\s+\(result\s+=\s+42\)",
+            )
+            .unwrap(),
+        )
+        .stderr(predicate::str::contains("+ call increment").not());
+}
+
+#[test]
+fn repl_script_passes_when_every_assert_holds() {
+    let wasm = fixture_wasm("counter");
+    let script = NamedTempFile::new().unwrap();
+    fs::write(
+        script.path(),
+        "call get\nassert result == 0\nexit\nassert result == 1\n",
+    )
+    .unwrap();
+
+    base_cmd()
+        .args([
+            "repl",
+            "--contract",
+            wasm.to_str().unwrap(),
+            "--script",
+            script.path().to_str().unwrap(),
+        ])
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("+ exit"));
+}
+
+#[test]
+fn config_validate_reports_problems_with_line_numbers() {
+    let config = NamedTempFile::new().unwrap();
+    fs::write(
+        config.path(),
+        "[output]\nshow_event = true\nformat = \"jsn\"\n",
+    )
+    .unwrap();
+
+    base_cmd()
+        .args(["config", "validate", config.path().to_str().unwrap()])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            ":2:1: unknown key `output.show_event` (did you mean `show_events`?)",
+        ))
+        .stderr(predicate::str::contains(
+            ":3:1: `output.format` must be one of text, json",
+        ));
+
+    fs::write(config.path(), "[output]\nshow_events = true\n").unwrap();
+    base_cmd()
+        .args(["config", "validate", config.path().to_str().unwrap()])
+        .assert()
+        .success();
+}
@@ -0,0 +1,71 @@
+//! `run --constructor-args`: contracts whose `__constructor` takes arguments
+//! are registered by running it as a call of its own.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+const ADMIN: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+#[test]
+fn constructor_runs_before_the_call() {
+    let constructor_args = format!(r#"["{}", 100]"#, ADMIN);
+    let (success, stdout, stderr) = fixtures::run(
+        "vault",
+        "cap",
+        &[
+            "--constructor-args",
+            &constructor_args,
+            "--show",
+            "events",
+            "--output",
+            "json",
+        ],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let result = &json["result"];
+    assert_eq!(result["return_value"], "100", "{}", result);
+    assert_eq!(result["constructor"]["args"][1], 100);
+    assert!(
+        result["constructor"]["budget"]["cpu_instructions"]
+            .as_u64()
+            .unwrap()
+            > 0
+    );
+    assert!(result["events"].to_string().contains("init"), "{}", result);
+}
+
+#[test]
+fn missing_arguments_name_the_constructor() {
+    let (success, stdout, stderr) = fixtures::run("vault", "cap", &[]);
+    assert!(!success, "{}", stdout);
+    assert!(
+        stderr.contains(
+            "The contract declares __constructor(admin: Address, cap: I128) and cannot be registered without arguments; pass them with --constructor-args"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("--constructor-args"), "{}", stderr);
+}
+
+#[test]
+fn failing_constructor_reports_its_error() {
+    let constructor_args = format!(r#"["{}", 0]"#, ADMIN);
+    let (success, stdout, stderr) =
+        fixtures::run("vault", "cap", &["--constructor-args", &constructor_args]);
+    assert!(!success, "{}", stdout);
+    assert!(stderr.contains("ZeroCap (#1)"), "{}", stderr);
+}
+
+#[test]
+fn arguments_without_a_constructor_are_rejected() {
+    let (success, stdout, stderr) =
+        fixtures::run("counter", "increment", &["--constructor-args", "[]"]);
+    assert!(!success, "{}", stdout);
+    assert!(
+        stderr.contains("--constructor-args was given, but the contract declares no __constructor"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,80 @@
+//! The `[conventions]` config section flags read-only functions that mutate
+//! state, and `--strict-conventions` turns the warning into an error.
+
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_in(dir: &Path, function: &str, extra: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .current_dir(dir)
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::PRICE_CACHE))
+        .arg("--function")
+        .arg(function)
+        .args(extra)
+        .output()
+        .expect("Failed to execute run command")
+}
+
+fn project_with_conventions() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(".soroban-debug.toml"),
+        "[conventions]\nreadonly_patterns = [\"get_*\", \"view_*\"]\n",
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn getter_that_caches_into_storage_is_reported() {
+    let dir = project_with_conventions();
+
+    let output = run_in(dir.path(), "get_price", &[]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("`get_price` matches readonly pattern `get_*`"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("wrote "), "{}", stdout);
+}
+
+#[test]
+fn strict_conventions_fail_the_run() {
+    let dir = project_with_conventions();
+
+    let output = run_in(dir.path(), "get_price", &["--strict-conventions"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Convention violated"), "{}", stderr);
+}
+
+#[test]
+fn read_only_getter_and_unconfigured_projects_pass() {
+    let dir = project_with_conventions();
+    let output = run_in(dir.path(), "get", &["--strict-conventions"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let empty = tempfile::tempdir().unwrap();
+    let output = run_in(empty.path(), "get_price", &["--strict-conventions"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!stdout.contains("readonly pattern"), "{}", stdout);
+}
@@ -0,0 +1,47 @@
+//! `--show diagnostics`: the host's diagnostic events after a run, and next
+//! to the error when the call traps.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run(fixture: &str, function: &str, args: &[&str]) -> (bool, String, String) {
+    fixtures::outcome(
+        fixtures::run_command(fixture, function)
+            .args(["--show", "diagnostics"])
+            .args(args),
+    )
+}
+
+#[test]
+fn successful_run_lists_the_call() {
+    let (success, stdout, stderr) = run("counter", "increment", &["--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let diagnostics = json["result"]["diagnostics"]
+        .as_array()
+        .expect("diagnostics array");
+    assert!(
+        diagnostics
+            .iter()
+            .any(|event| event["kind"] == "fn_call" && event["topics"][2] == "increment"),
+        "{:?}",
+        diagnostics
+    );
+}
+
+#[test]
+fn failed_run_still_prints_them() {
+    let (success, stdout, stderr) = run("always_panic", "panic", &[]);
+    assert!(!success, "{}", stdout);
+    assert!(stderr.contains("--- Diagnostic Events ---"), "{}", stderr);
+    assert!(stderr.contains(r#"["fn_call","#), "{}", stderr);
+
+    let (success, stdout, _) = run("always_panic", "panic", &["--output", "json"]);
+    assert!(!success);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert!(
+        !json["error"]["diagnostics"].as_array().unwrap().is_empty(),
+        "{}",
+        json
+    );
+}
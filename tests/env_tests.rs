@@ -0,0 +1,105 @@
+//! Named environments: `env create/list/delete` and `run --env NAME --commit`.
+
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn debugger(env_dir: &Path, args: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .env("SOROBAN_DEBUG_ENV_DIR", env_dir)
+        .env("SOROBAN_DEBUG_HISTORY_FILE", env_dir.join("history.json"))
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug")
+}
+
+fn stdout_of(output: &std::process::Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn committed_runs_accumulate_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let envs = dir.path();
+
+    stdout_of(&debugger(
+        envs,
+        &[
+            "env",
+            "create",
+            "staging-repro",
+            "--with-contract",
+            fixtures::get_fixture_path(fixtures::names::COUNTER)
+                .to_str()
+                .unwrap(),
+            "--label",
+            "ticket=INC-42",
+        ],
+    ));
+
+    let run = |extra: &[&str]| {
+        let mut args = vec!["run", "--env", "staging-repro"];
+        args.extend_from_slice(extra);
+        debugger(envs, &args)
+    };
+
+    let first = stdout_of(&run(&["--function", "increment", "--commit"]));
+    assert!(first.contains("I64(1)"), "{}", first);
+    let second = stdout_of(&run(&["--function", "increment", "--commit"]));
+    assert!(second.contains("I64(2)"), "{}", second);
+    assert!(second.contains("1 commit(s)"), "{}", second);
+
+    // Without --commit the post-state is discarded.
+    let third = stdout_of(&run(&["--function", "increment"]));
+    assert!(third.contains("I64(3)"), "{}", third);
+    let current = stdout_of(&run(&["--function", "get"]));
+    assert!(current.contains("I64(2)"), "{}", current);
+
+    let list = stdout_of(&debugger(envs, &["env", "list"]));
+    assert!(list.contains("staging-repro"), "{}", list);
+    assert!(list.contains("2 commit(s)"), "{}", list);
+    assert!(list.contains("ticket=INC-42"), "{}", list);
+
+    stdout_of(&debugger(envs, &["env", "delete", "staging-repro"]));
+    let output = run(&["--function", "get"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not exist"));
+}
+
+#[test]
+fn environment_in_use_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    stdout_of(&debugger(
+        dir.path(),
+        &[
+            "env",
+            "create",
+            "shared",
+            "--with-contract",
+            fixtures::get_fixture_path(fixtures::names::COUNTER)
+                .to_str()
+                .unwrap(),
+        ],
+    ));
+
+    let store = soroban_debugger::environments::EnvironmentStore::new(dir.path().to_path_buf());
+    let _held = store.open("shared").unwrap();
+
+    let output = debugger(
+        dir.path(),
+        &["run", "--env", "shared", "--function", "increment"],
+    );
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("in use by another"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,44 @@
+//! `run --break-on-event`: stopping on emitted events with their decoded
+//! topics and the call stack they were emitted in.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn diagnostic_call_events_stop_with_their_call_stack() {
+    // The host's `fn_call` diagnostic event names the function as topic 2.
+    let (success, stdout, stderr) = fixtures::run_counter(&["--break-on-event", "2:increment"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let report = stdout
+        .split("Event breakpoint hit: diagnostic event (fn_call")
+        .nth(1)
+        .unwrap_or_else(|| panic!("no event breakpoint in:\n{}", stdout));
+    assert!(report.contains("#0 increment in C"), "{}", report);
+
+    let (success, stdout, stderr) = fixtures::run_counter(&[
+        "--break-on-event",
+        "2:increment",
+        "--break-contract-events-only",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(!stdout.contains("Event breakpoint hit"), "{}", stdout);
+}
+
+#[test]
+fn event_stops_are_listed_in_the_json_result() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--break-on-event", "fn_call", "--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let hits = json["result"]["event_breakpoints"]
+        .as_array()
+        .expect("event_breakpoints list");
+    assert!(!hits.is_empty(), "{}", stdout);
+    assert_eq!(hits[0]["diagnostic"], true);
+    assert_eq!(hits[0]["decoded_topics"][0], "fn_call");
+    assert_eq!(hits[0]["decoded_topics"][2], "increment");
+
+    let (success, _, stderr) = fixtures::run_counter(&["--break-on-event", "0:"]);
+    assert!(!success);
+    assert!(stderr.contains("Invalid event breakpoint"), "{}", stderr);
+}
@@ -0,0 +1,89 @@
+//! `inspect --events` and `run --validate-events` against the `emitter`
+//! fixture, whose `relay` publishes a `transfer` event and then one under a
+//! topic chosen by the caller.
+
+use std::io::Write;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+/// Exit status and combined output of `run --function relay`.
+fn relay(topic: &str, extra: &[&str]) -> (bool, String) {
+    let call_args = format!(r#"["{}", 5]"#, topic);
+    let (success, stdout, stderr) = fixtures::outcome(
+        fixtures::run_command(fixtures::names::EMITTER, "relay")
+            .args(["--args", &call_args])
+            .args(extra),
+    );
+    (success, stdout + &stderr)
+}
+
+#[test]
+fn inspect_events_lists_the_constant_topics_with_their_data() {
+    let wasm = fixtures::get_fixture_path(fixtures::names::EMITTER);
+    let (success, stdout, stderr) = fixtures::outcome(
+        fixtures::soroban_debug()
+            .arg("inspect")
+            .arg("--contract")
+            .arg(wasm)
+            .args(["--events", "--format", "json"]),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let events: Vec<(&str, &serde_json::Value)> = json["result"]["event_schema"]["events"]
+        .as_array()
+        .unwrap_or_else(|| panic!("no event_schema in {}", json))
+        .iter()
+        .map(|event| (event["topic"].as_str().unwrap(), &event["data"]))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            ("price_updated", &serde_json::json!(["u32"])),
+            ("transfer", &serde_json::json!(["i128"])),
+        ]
+    );
+}
+
+#[test]
+fn validate_events_flags_the_off_schema_event_only() {
+    let (success, output) = relay("transfr", &["--validate-events"]);
+    assert!(success, "{}", output);
+    assert!(
+        output.contains("1 event(s) do not match the event schema"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("unknown topic 'transfr' (did you mean 'transfer'?)"),
+        "{}",
+        output
+    );
+
+    let (success, output) = relay("transfer", &["--validate-events"]);
+    assert!(success, "{}", output);
+    assert!(output.contains("match the event schema"), "{}", output);
+    assert!(!output.contains("do not match"), "{}", output);
+}
+
+#[test]
+fn a_user_schema_pins_the_data_kind() {
+    let mut schema = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        schema,
+        r#"{{"events": [{{"topic": "transfer", "data": ["u32"], "source": "user_schema"}}]}}"#
+    )
+    .unwrap();
+
+    let (success, output) = relay(
+        "transfer",
+        &["--event-schema", schema.path().to_str().unwrap()],
+    );
+    assert!(success, "{}", output);
+    assert!(
+        output.contains("'transfer' data is i128 but schema expects u32"),
+        "{}",
+        output
+    );
+}
@@ -0,0 +1,211 @@
+//! `ContractExecutor::execute` converts `--args` JSON with `ArgumentParser`
+//! before invoking, and reports a bad argument by its position.
+
+use soroban_debugger::runtime::executor::ContractExecutor;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn executor(name: &str) -> ContractExecutor {
+    let wasm = fixtures::load_fixture(name);
+    ContractExecutor::new(wasm).expect("create executor")
+}
+
+#[test]
+fn typed_argument_reaches_the_contract() {
+    let mut executor = executor("echo");
+    let result = executor
+        .execute("echo", Some(r#"[{"type":"u32","value":3}]"#))
+        .expect("execute echo");
+    assert!(result.contains("U32(3)"), "got: {result}");
+}
+
+#[test]
+fn bad_argument_is_reported_by_index_before_invoking() {
+    let mut executor = executor("echo");
+    let err = executor
+        .execute("echo", Some(r#"[1, {"type":"u32","value":-1}]"#))
+        .unwrap_err()
+        .to_string();
+    assert!(err.starts_with("Invalid arguments: "), "got: {err}");
+    assert!(err.contains("Array element 1"), "got: {err}");
+}
+
+#[test]
+fn addresses_round_trip_through_the_contract() {
+    let mut executor = executor("echo");
+    for address in [
+        "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF",
+        "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHK3M",
+    ] {
+        for args in [
+            format!(r#"[{{"type":"address","value":"{address}"}}]"#),
+            format!(r#"["{address}"]"#),
+        ] {
+            let result = executor.execute("echo", Some(&args)).expect("execute echo");
+            assert_eq!(result, format!("Address({address})"));
+            let value = executor.last_result().expect("return value").value;
+            assert_eq!(value, serde_json::json!(address));
+        }
+    }
+}
+
+#[test]
+fn malformed_address_is_reported_with_the_decode_failure() {
+    let mut executor = executor("echo");
+    let err = executor
+        .execute(
+            "echo",
+            Some(r#"[{"type":"address","value":"GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHG"}]"#),
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Array element 0"), "got: {err}");
+    assert!(err.contains("checksum does not match"), "got: {err}");
+}
+
+#[test]
+fn bytes_round_trip_through_the_contract() {
+    let mut executor = executor("echo");
+    for args in [
+        r#"[{"type":"bytes","value":"0xdeadbeef"}]"#,
+        r#"[{"type":"bytes","value":"deadbeef"}]"#,
+        r#"[{"type":"bytes","encoding":"base64","value":"3q2+7w=="}]"#,
+    ] {
+        let result = executor.execute("echo", Some(args)).expect("execute echo");
+        assert_eq!(result, "Bytes(0xdeadbeef)", "for {args}");
+    }
+
+    let hash = "ab".repeat(32);
+    let result = executor
+        .execute(
+            "echo",
+            Some(&format!(r#"[{{"type":"bytes32","value":"{hash}"}}]"#)),
+        )
+        .expect("execute echo");
+    assert_eq!(result, format!("Bytes(0x{hash})"));
+}
+
+#[test]
+fn wide_integers_round_trip_as_decimal_strings() {
+    let mut executor = executor("echo");
+    let u256_max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+    let i256_min = "-57896044618658097711785492504343953926634992332820282019728792003956564819968";
+    for (args, expected) in [
+        (
+            format!(r#"[{{"type":"u256","value":"{u256_max}"}}]"#),
+            serde_json::json!(u256_max),
+        ),
+        (
+            format!(r#"[{{"type":"u256","value":"0x{}"}}]"#, "f".repeat(64)),
+            serde_json::json!(u256_max),
+        ),
+        (
+            format!(r#"[{{"type":"i256","value":"{i256_min}"}}]"#),
+            serde_json::json!(i256_min),
+        ),
+        (
+            r#"[{"type":"u256","value":"0"}]"#.to_string(),
+            serde_json::json!("0"),
+        ),
+        (
+            r#"[{"type":"i256","value":"0"}]"#.to_string(),
+            serde_json::json!("0"),
+        ),
+    ] {
+        let outcome = executor
+            .execute_detailed("echo", Some(&args))
+            .expect("execute echo");
+        assert_eq!(outcome.return_value, expected, "for {args}");
+    }
+}
+
+#[test]
+fn user_defined_types_are_built_from_the_contract_spec() {
+    let mut executor = executor("orders");
+    let seller = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+    let cases = [
+        (
+            "order",
+            format!(r#"[{{"type":"Order","value":{{"price":"100","seller":"{seller}"}}}}]"#),
+            serde_json::json!({"price": "100", "seller": seller}),
+        ),
+        // A parameter declared with a user-defined type takes a bare value.
+        (
+            "order",
+            format!(r#"[{{"seller":"{seller}","price":7}}]"#),
+            serde_json::json!({"price": "7", "seller": seller}),
+        ),
+        (
+            "asset",
+            format!(r#"[{{"type":"Asset","value":{{"Stellar":"{seller}"}}}}]"#),
+            serde_json::json!(["Stellar", seller]),
+        ),
+        (
+            "asset",
+            r#"["Native"]"#.to_string(),
+            serde_json::json!(["Native"]),
+        ),
+        ("pair", "[[2, 3]]".to_string(), serde_json::json!(5)),
+        ("side", r#"["Sell"]"#.to_string(), serde_json::json!(1)),
+    ];
+    for (function, args, expected) in cases {
+        let outcome = executor
+            .execute_detailed(function, Some(&args))
+            .unwrap_or_else(|e| panic!("{function} {args}: {e}"));
+        assert_eq!(outcome.return_value, expected, "for {function} {args}");
+    }
+
+    let err = executor
+        .execute("order", Some(r#"[{"type":"Order","value":{"price":1}}]"#))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains(
+            "Order is missing field 'seller'; expected fields: price: I128, seller: Address"
+        ),
+        "got: {err}"
+    );
+}
+
+#[test]
+fn xdr_argument_reaches_the_contract() {
+    let mut executor = executor("echo");
+    let result = executor
+        .execute("echo", Some(r#"[{"type":"xdr","value":"AAAAAwAAACo="}]"#))
+        .expect("execute echo");
+    assert_eq!(result, "U32(42)");
+}
+
+#[test]
+fn plain_values_are_converted_as_the_declared_types() {
+    let mut heavy = executor("budget_heavy");
+    // `heavy(n: u32)`: a bare number is a u32, not the untyped i128.
+    for args in ["[10]", "10"] {
+        let result = heavy.execute("heavy", Some(args)).expect("execute heavy");
+        assert_eq!(result, "U32(10)", "for {args}");
+    }
+    let err = heavy
+        .execute("heavy", Some("[-1]"))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("Array element 0: function heavy expects parameter 'n' of type U32"),
+        "got: {err}"
+    );
+
+    let mut orders = executor("orders");
+    let outcome = orders
+        .execute_detailed("total", Some(r#"[5, 3, "Sell"]"#))
+        .expect("execute total");
+    assert_eq!(outcome.return_value, serde_json::json!("-15"));
+    let err = orders
+        .execute("total", Some(r#"[5, 4294967296, "Buy"]"#))
+        .unwrap_err()
+        .to_string();
+    assert!(
+        err.contains("function total expects parameter 'quantity' of type U32"),
+        "got: {err}"
+    );
+    assert!(err.contains("out of range for type u32"), "got: {err}");
+}
@@ -0,0 +1,44 @@
+//! `ContractExecutor::execute_detailed` returns what one call produced, not
+//! what the environment has accumulated.
+
+use soroban_debugger::runtime::executor::ContractExecutor;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn counter() -> ContractExecutor {
+    let wasm = fixtures::load_fixture(fixtures::names::COUNTER);
+    ContractExecutor::new(wasm).expect("create executor")
+}
+
+#[test]
+fn outcome_covers_only_its_own_call() {
+    let mut executor = counter();
+    executor
+        .execute("increment", None)
+        .expect("first increment");
+    let second = executor
+        .execute_detailed("increment", None)
+        .expect("second increment");
+    let third = executor
+        .execute_detailed("increment", None)
+        .expect("third increment");
+
+    assert_eq!(third.function, "increment");
+    assert_eq!(third.display, "I64(3)");
+    assert_eq!(third.return_value, serde_json::json!(3));
+    assert_eq!(third.events.len(), second.events.len());
+    assert!(!third.storage_diff.is_empty());
+    assert!(third.budget.cpu_instructions > 0);
+
+    let json = serde_json::to_value(&third).expect("serialize outcome");
+    assert_eq!(json["return_value"], serde_json::json!(3));
+    assert!(json["storage_diff"]["modified"].is_object(), "{json}");
+}
+
+#[test]
+fn execute_returns_the_outcome_display() {
+    let mut executor = counter();
+    let display = executor.execute("increment", None).expect("increment");
+    assert_eq!(display, "I64(1)");
+}
@@ -0,0 +1,113 @@
+//! Exit codes and JSON reporting for `run --expect` in strict and warn modes,
+//! and per-expectation modes in scenario steps.
+
+use serde_json::Value;
+use std::fs;
+use tempfile::NamedTempFile;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_with_expectation(mode: &str) -> std::process::Output {
+    fixtures::soroban_debug()
+        .arg("--quiet")
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--function")
+        .arg("increment")
+        .arg("--expect")
+        .arg("I64(999)")
+        .arg("--expect-mode")
+        .arg(mode)
+        .arg("--output")
+        .arg("json")
+        .output()
+        .expect("Failed to execute run command")
+}
+
+fn failed_expectations(output: &std::process::Output) -> Vec<Value> {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json: Value = serde_json::from_str(&stdout)
+        .unwrap_or_else(|_| panic!("Failed to parse JSON output: {}", stdout));
+    json["result"]["expectations"]
+        .as_array()
+        .expect("expectations should be present")
+        .iter()
+        .filter(|e| e["passed"] == Value::Bool(false))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn strict_mode_fails_the_run_and_reports_expectation() {
+    let output = run_with_expectation("strict");
+    assert!(!output.status.success());
+    let failed = failed_expectations(&output);
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0]["mode"], "strict");
+}
+
+#[test]
+fn warn_mode_reports_expectation_but_succeeds() {
+    let output = run_with_expectation("warn");
+    assert!(
+        output.status.success(),
+        "warn mode should not fail: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let failed = failed_expectations(&output);
+    assert_eq!(failed.len(), 1);
+    assert_eq!(failed[0]["expected"], "I64(999)");
+    assert_eq!(failed[0]["mode"], "warn");
+}
+
+fn run_scenario(steps: &str) -> std::process::Output {
+    let scenario = NamedTempFile::new().unwrap();
+    fs::write(scenario.path(), steps).unwrap();
+    fixtures::soroban_debug()
+        .arg("scenario")
+        .arg("--scenario")
+        .arg(scenario.path())
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .output()
+        .expect("Failed to execute scenario command")
+}
+
+#[test]
+fn one_step_mixes_strict_and_warn_expectations() {
+    let output = run_scenario(
+        r#"
+[[steps]]
+function = "increment"
+expect = [
+    { return = "I64(1)" },
+    { return = "I64(999)", mode = "warn" },
+]
+"#,
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(
+        stdout.contains("Return value assertion passed"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Expected 'I64(999)'"), "{}", stdout);
+    assert!(stdout.contains("expect mode: warn"), "{}", stdout);
+
+    let output = run_scenario(
+        r#"
+[[steps]]
+function = "increment"
+expect_mode = "warn"
+expect = [
+    { return = "I64(999)" },
+    { return = "I64(998)", mode = "strict" },
+]
+"#,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Step 1 failed."));
+}
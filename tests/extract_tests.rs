@@ -0,0 +1,37 @@
+//! `run --extract` prints only the selected values in quiet and JSON modes.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_increment(extract: &[&str], extra: &[&str]) -> std::process::Output {
+    let mut cmd = fixtures::soroban_debug();
+    cmd.arg("--quiet")
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--function")
+        .arg("increment")
+        .args(extra);
+    for path in extract {
+        cmd.arg("--extract").arg(path);
+    }
+    cmd.output().expect("Failed to execute run command")
+}
+
+#[test]
+fn prints_each_extracted_value_on_its_own_line() {
+    let output = run_increment(&["result", "storage[\"c\"]"], &["--output", "json"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "1\n1\n");
+}
+
+#[test]
+fn unresolved_path_fails() {
+    let output = run_increment(&["storage[\"missing\"]"], &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("did not resolve"));
+}
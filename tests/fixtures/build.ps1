@@ -17,8 +17,14 @@ function Get-FixtureExports {
         "budget_heavy" { return @("heavy") }
         "counter" { return @("get", "increment") }
         "cross_contract" { return @("call") }
+        "dice" { return @("roll") }
         "echo" { return @("echo") }
+        "price_cache" { return @("get", "get_price") }
         "same_return" { return @("same") }
+        "spin" { return @("spin") }
+        "vault" { return @("__constructor", "cap") }
+        "orders" { return @("asset", "order", "pair", "side", "total") }
+        "emitter" { return @("relay", "set_price", "transfer") }
         default { throw "Unknown fixture export set for '$Name'" }
     }
 }
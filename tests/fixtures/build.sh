@@ -16,8 +16,14 @@ fixture_exports_json() {
         budget_heavy) printf '["heavy"]' ;;
         counter) printf '["get","increment"]' ;;
         cross_contract) printf '["call"]' ;;
+        dice) printf '["roll"]' ;;
         echo) printf '["echo"]' ;;
+        price_cache) printf '["get","get_price"]' ;;
         same_return) printf '["same"]' ;;
+        spin) printf '["spin"]' ;;
+        vault) printf '["__constructor","cap"]' ;;
+        orders) printf '["asset","order","pair","side","total"]' ;;
+        emitter) printf '["relay","set_price","transfer"]' ;;
         *)
             echo "Unknown fixture export set for '$1'" >&2
             exit 1
@@ -0,0 +1,13 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, Env};
+
+#[contract]
+pub struct Dice;
+
+#[contractimpl]
+impl Dice {
+    // Draws from the host PRNG, so the result depends only on its seed.
+    pub fn roll(env: Env) -> u64 {
+        env.prng().gen()
+    }
+}
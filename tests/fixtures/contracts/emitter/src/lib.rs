@@ -0,0 +1,30 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, symbol_short, Address, Env, Symbol};
+
+#[contract]
+pub struct Emitter;
+
+#[contractimpl]
+impl Emitter {
+    // `total` is a storage key, not an event topic.
+    pub fn transfer(env: Env, from: Address, amount: i128) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("total"), &amount);
+        env.events()
+            .publish((symbol_short!("transfer"), from), amount);
+    }
+
+    // Too long for a short symbol, so the topic is built from a data segment.
+    pub fn set_price(env: Env, price: u32) {
+        env.events()
+            .publish((Symbol::new(&env, "price_updated"),), price);
+    }
+
+    // Publishes a `transfer` event, then one under the caller's topic, which
+    // no schema derived from the WASM can know.
+    pub fn relay(env: Env, topic: Symbol, amount: i128) {
+        env.events().publish((symbol_short!("transfer"),), amount);
+        env.events().publish((topic,), amount);
+    }
+}
@@ -0,0 +1,60 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, contracttype, Address, Env};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Order {
+    pub price: i128,
+    pub seller: Address,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Native,
+    Stellar(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pair(pub u32, pub u32);
+
+#[contracttype]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Side {
+    Buy = 0,
+    Sell = 1,
+}
+
+#[contract]
+pub struct Orders;
+
+// The functions hand back, or combine, their arguments so tests can check
+// what the debugger built from JSON.
+#[contractimpl]
+impl Orders {
+    pub fn order(_env: Env, order: Order) -> Order {
+        order
+    }
+
+    pub fn asset(_env: Env, asset: Asset) -> Asset {
+        asset
+    }
+
+    pub fn pair(_env: Env, pair: Pair) -> u32 {
+        pair.0 + pair.1
+    }
+
+    pub fn side(_env: Env, side: Side) -> Side {
+        side
+    }
+
+    pub fn total(_env: Env, price: i128, quantity: u32, side: Side) -> i128 {
+        let total = price * i128::from(quantity);
+        match side {
+            Side::Buy => total,
+            Side::Sell => -total,
+        }
+    }
+}
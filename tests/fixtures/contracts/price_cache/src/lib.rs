@@ -0,0 +1,27 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, symbol_short, Env};
+
+#[contract]
+pub struct PriceCache;
+
+#[contractimpl]
+impl PriceCache {
+    // A getter that accidentally caches: every quote is counted in instance
+    // storage, so `get_price` mutates state despite its name.
+    pub fn get_price(env: Env) -> i64 {
+        let quotes: i64 = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("c"))
+            .unwrap_or(0)
+            + 1;
+        env.storage().instance().set(&symbol_short!("c"), &quotes);
+        quotes
+    }
+    pub fn get(env: Env) -> i64 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("c"))
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg(test)]
+
+use super::*;
+use soroban_sdk::Env;
+
+#[test]
+fn test_get_price_writes_storage() {
+    let env = Env::default();
+    let contract_id = env.register_contract(None, PriceCache);
+    let client = PriceCacheClient::new(&env, &contract_id);
+
+    assert_eq!(client.get(), 0);
+    assert_eq!(client.get_price(), 1);
+    assert_eq!(client.get(), 1);
+}
@@ -0,0 +1,14 @@
+#![no_std]
+use soroban_sdk::{contract, contractimpl, Env};
+
+#[contract]
+pub struct Spin;
+
+#[contractimpl]
+impl Spin {
+    // Never returns; only the timeout or the CPU budget ends the call.
+    #[allow(clippy::empty_loop)]
+    pub fn spin(_env: Env) {
+        loop {}
+    }
+}
@@ -0,0 +1,31 @@
+#![no_std]
+use soroban_sdk::{
+    contract, contracterror, contractimpl, panic_with_error, symbol_short, Address, Env,
+};
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum VaultError {
+    ZeroCap = 1,
+}
+
+#[contract]
+pub struct Vault;
+
+#[contractimpl]
+impl Vault {
+    // Needs its arguments at registration; a zero cap fails the constructor.
+    pub fn __constructor(env: Env, admin: Address, cap: i128) {
+        if cap == 0 {
+            panic_with_error!(&env, VaultError::ZeroCap);
+        }
+        env.storage().instance().set(&symbol_short!("admin"), &admin);
+        env.storage().instance().set(&symbol_short!("cap"), &cap);
+        env.events().publish((symbol_short!("init"), admin), cap);
+    }
+
+    pub fn cap(env: Env) -> i128 {
+        env.storage().instance().get(&symbol_short!("cap")).unwrap()
+    }
+}
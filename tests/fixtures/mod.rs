@@ -98,6 +98,46 @@ pub fn get_fixture_path(name: &str) -> PathBuf {
     artifact_path(name, "release")
 }
 
+/// The `soroban-debug` binary with colour and the banner turned off, so its
+/// output can be matched as plain text.
+pub fn soroban_debug() -> assert_cmd::Command {
+    let mut cmd = assert_cmd::Command::new(env!("CARGO_BIN_EXE_soroban-debug"));
+    cmd.env("NO_COLOR", "1");
+    cmd.env("NO_BANNER", "1");
+    cmd
+}
+
+/// Whether `command` succeeded, with its stdout and stderr as text.
+pub fn outcome(command: &mut assert_cmd::Command) -> (bool, String, String) {
+    let output = command.output().expect("Failed to execute soroban-debug");
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+    )
+}
+
+/// `soroban-debug run` of `function` in the `fixture` contract, ready for
+/// more arguments.
+pub fn run_command(fixture: &str, function: &str) -> assert_cmd::Command {
+    let mut cmd = soroban_debug();
+    cmd.arg("run")
+        .arg("--contract")
+        .arg(get_fixture_path(fixture))
+        .args(["--function", function]);
+    cmd
+}
+
+/// Run `function` in the `fixture` contract with `args` appended.
+pub fn run(fixture: &str, function: &str, args: &[&str]) -> (bool, String, String) {
+    outcome(run_command(fixture, function).args(args))
+}
+
+/// Run the counter's `increment` with `args` appended.
+pub fn run_counter(args: &[&str]) -> (bool, String, String) {
+    run(names::COUNTER, "increment", args)
+}
+
 pub fn fixture_exists(name: &str) -> bool {
     get_fixture_path(name).exists()
 }
@@ -146,4 +186,10 @@ pub mod names {
     pub const BUDGET_HEAVY: &str = "budget_heavy";
     pub const CROSS_CONTRACT: &str = "cross_contract";
     pub const SAME_RETURN: &str = "same_return";
+    pub const PRICE_CACHE: &str = "price_cache";
+    pub const DICE: &str = "dice";
+    pub const SPIN: &str = "spin";
+    pub const VAULT: &str = "vault";
+    pub const ORDERS: &str = "orders";
+    pub const EMITTER: &str = "emitter";
 }
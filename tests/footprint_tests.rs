@@ -0,0 +1,86 @@
+//! `--show ledger` footprint: the entries a call only read and those it
+//! wrote, decoded and as `LedgerKey` XDR.
+
+use std::collections::HashSet;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn counter_footprint(function: &str) -> serde_json::Value {
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+    let output = fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(wasm)
+        .args([
+            "--function",
+            function,
+            "--show",
+            "ledger",
+            "--output",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute soroban-debug");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("valid JSON");
+    json["result"]["footprint"].clone()
+}
+
+fn kinds(entries: &serde_json::Value) -> Vec<&str> {
+    entries
+        .as_array()
+        .expect("footprint set")
+        .iter()
+        .map(|entry| entry["kind"].as_str().unwrap())
+        .collect()
+}
+
+fn xdrs(entries: &serde_json::Value) -> HashSet<&str> {
+    entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["xdr"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn write_moves_the_instance_to_read_write() {
+    let footprint = counter_footprint("increment");
+    assert_eq!(
+        kinds(&footprint["read_only"]),
+        ["contract_code"],
+        "{}",
+        footprint
+    );
+    assert_eq!(
+        kinds(&footprint["read_write"]),
+        ["contract_instance"],
+        "{}",
+        footprint
+    );
+    assert!(footprint["read_write"][0]["address"]
+        .as_str()
+        .unwrap()
+        .starts_with('C'));
+    assert!(xdrs(&footprint["read_only"]).is_disjoint(&xdrs(&footprint["read_write"])));
+}
+
+#[test]
+fn read_only_call_writes_nothing() {
+    let footprint = counter_footprint("get");
+    let mut read = kinds(&footprint["read_only"]);
+    read.sort();
+    assert_eq!(
+        read,
+        ["contract_code", "contract_instance"],
+        "{}",
+        footprint
+    );
+    assert!(kinds(&footprint["read_write"]).is_empty(), "{}", footprint);
+}
@@ -0,0 +1,69 @@
+//! `run --full-debug`: every diagnostic in one flag, and its `--repeat` guard.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_counter(args: &[&str]) -> Command {
+    let mut cmd = fixtures::soroban_debug();
+    cmd.arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .args(["--function", "increment"])
+        .args(args);
+    cmd
+}
+
+#[test]
+fn full_debug_lists_its_expansion_and_shows_every_section() {
+    run_counter(&["--full-debug"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "--full-debug expanded to: --show all --trace-limit 10000 --cost-table --verbose",
+        ))
+        .stdout(predicate::str::contains("--- Host Calls ---"))
+        .stdout(predicate::str::contains("--- Contract Logs ---"))
+        .stdout(predicate::str::contains("--- Ledger Entries ---"))
+        .stdout(predicate::str::contains("Execution time:"));
+}
+
+#[test]
+fn full_debug_json_reports_the_resolved_show_set() {
+    let output = run_counter(&["--full-debug", "--output", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json_start = stdout.find("{\n").expect("JSON output expected");
+    let value: serde_json::Value = serde_json::Deserializer::from_str(&stdout[json_start..])
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        value["result"]["collected"],
+        serde_json::json!([
+            "events",
+            "auth",
+            "ledger",
+            "host-calls",
+            "logs",
+            "diagnostics",
+            "storage-diff"
+        ])
+    );
+    assert!(value["result"]["logs"].is_array(), "{}", value);
+}
+
+#[test]
+fn full_debug_refuses_large_repeat_counts() {
+    run_counter(&["--full-debug", "--repeat", "50"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--full-debug cannot be used with --repeat",
+        ));
+}
@@ -0,0 +1,86 @@
+//! Parallel `run` processes sharing one history file keep every record.
+
+use soroban_debugger::history::HistoryManager;
+use std::path::{Path, PathBuf};
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_increment(history_file: &Path) -> std::process::Output {
+    fixtures::soroban_debug()
+        .arg("--quiet")
+        .arg("--history-file")
+        .arg(history_file)
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--function")
+        .arg("increment")
+        .output()
+        .expect("Failed to execute run command")
+}
+
+#[test]
+fn parallel_runs_append_every_record_intact() {
+    let dir = tempfile::tempdir().unwrap();
+    let history_file: PathBuf = dir.path().join("history.json");
+
+    let processes = 8;
+    let handles: Vec<_> = (0..processes)
+        .map(|_| {
+            let history_file = history_file.clone();
+            std::thread::spawn(move || run_increment(&history_file))
+        })
+        .collect();
+    for handle in handles {
+        let output = handle.join().unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    let load = HistoryManager::with_path(history_file.clone())
+        .read_history()
+        .unwrap();
+    assert_eq!(load.records.len(), processes);
+    assert_eq!(load.skipped, 0);
+    assert!(load
+        .records
+        .iter()
+        .all(|record| record.function == "increment" && record.cpu_used > 0));
+
+    let contents = std::fs::read_to_string(&history_file).unwrap();
+    assert_eq!(contents.lines().count(), processes);
+}
+
+#[test]
+fn compact_drops_a_torn_line_between_records() {
+    let dir = tempfile::tempdir().unwrap();
+    let history_file = dir.path().join("history.json");
+    let record = r#"{"date":"2026-01-01T00:00:00Z","contract_hash":"c","function":"f","cpu_used":1,"memory_used":1}"#;
+    std::fs::write(
+        &history_file,
+        format!("{record}\n{{\"date\":\"2026-01-0\n{record}\n"),
+    )
+    .unwrap();
+
+    let output = fixtures::soroban_debug()
+        .arg("--history-file")
+        .arg(&history_file)
+        .args(["history", "compact"])
+        .output()
+        .expect("Failed to execute history compact");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Dropped 1 unreadable line(s)"));
+
+    let history = HistoryManager::with_path(history_file)
+        .load_history()
+        .unwrap();
+    assert_eq!(history.len(), 2);
+}
@@ -0,0 +1,82 @@
+//! `history events` over a synthetic history file.
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn write_history(path: &Path) {
+    let record = |date: &str, contract: &str, topics: &[&str]| {
+        serde_json::json!({
+            "date": date,
+            "contract_hash": contract,
+            "function": "transfer",
+            "cpu_used": 100,
+            "memory_used": 100,
+            "event_topics": topics,
+        })
+        .to_string()
+    };
+    let lines = [
+        record(
+            "2026-01-01T00:00:00Z",
+            "token.wasm",
+            &["approve(address)", "transfer(address, address)"],
+        ),
+        record(
+            "2026-01-02T00:00:00Z",
+            "token.wasm",
+            &["approve(address)", "transfer(address, address)"],
+        ),
+        record(
+            "2026-01-03T00:00:00Z",
+            "token.wasm",
+            &["transfer(address, address)"],
+        ),
+        record("2026-01-03T00:00:00Z", "other.wasm", &["mint(address)"]),
+    ];
+    std::fs::write(path, lines.join("\n") + "\n").unwrap();
+}
+
+fn history_events(history_file: &Path) -> Command {
+    let mut cmd = fixtures::soroban_debug();
+    cmd.arg("--history-file").arg(history_file);
+    cmd.args(["history", "events", "--contract", "token.wasm"]);
+    cmd
+}
+
+#[test]
+fn removed_topic_is_flagged_for_the_contract() {
+    let dir = tempfile::tempdir().unwrap();
+    let history_file = dir.path().join("history.json");
+    write_history(&history_file);
+
+    history_events(&history_file)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "approve(address): 2 run(s), first 2026-01-01T00:00:00Z, last 2026-01-02T00:00:00Z  [missing]",
+        ))
+        .stdout(predicate::str::contains("transfer (3 run(s))"))
+        .stdout(predicate::str::contains("mint").not());
+}
+
+#[test]
+fn fail_on_missing_gates_on_topic_removal() {
+    let dir = tempfile::tempdir().unwrap();
+    let history_file = dir.path().join("history.json");
+    write_history(&history_file);
+
+    history_events(&history_file)
+        .args(["--fail-on", "missing"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("approve(address) (missing)"));
+
+    history_events(&history_file)
+        .args(["--fail-on", "added"])
+        .assert()
+        .success();
+}
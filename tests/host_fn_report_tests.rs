@@ -0,0 +1,54 @@
+//! `run --report-host-fn`: reporting the calls the contract made to chosen
+//! host functions, with their arguments and the calling frame.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn storage_writes_are_reported_with_the_calling_frame() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--report-host-fn", "put_contract_data", "--show", "logs"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let report = stdout
+        .split("Host function called: put_contract_data(")
+        .nth(1)
+        .unwrap_or_else(|| panic!("no host function call in:\n{}", stdout));
+    assert!(report.contains("called from: increment in C"), "{}", report);
+    // The calls the debugger logs are not the contract's logs.
+    assert!(!stdout.contains("soroban-debug host fn"), "{}", stdout);
+}
+
+#[test]
+fn host_fn_calls_are_listed_in_the_json_result() {
+    let (success, stdout, stderr) = fixtures::run_counter(&[
+        "--report-host-fn",
+        "get_contract_data",
+        "--report-host-fn",
+        "put_contract_data",
+        "--output",
+        "json",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let calls = json["result"]["host_fn_calls"]
+        .as_array()
+        .expect("host_fn_calls list");
+    let put = calls
+        .iter()
+        .find(|call| call["host_fn"] == "put_contract_data")
+        .unwrap_or_else(|| panic!("no put_contract_data call in:\n{}", stdout));
+    assert_eq!(put["args"].as_array().unwrap().len(), 3);
+    assert_eq!(put["stack"][0]["function"], "increment");
+}
+
+#[test]
+fn unknown_host_functions_are_rejected_with_the_supported_names() {
+    let (success, _, stderr) = fixtures::run_counter(&["--report-host-fn", "require_auht"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("Unknown host function 'require_auht'"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("require_auth"), "{}", stderr);
+}
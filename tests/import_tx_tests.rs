@@ -0,0 +1,107 @@
+//! `soroban-debug import-tx`: fetching a transaction from RPC and replaying
+//! saved scenarios. A local fake RPC server stands in for the network.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+/// Answer every JSON-RPC request with `respond(method)` until the listener is dropped.
+fn fake_rpc(respond: fn(&str) -> serde_json::Value) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body = loop {
+                let n = stream.read(&mut buf).unwrap_or(0);
+                if n == 0 {
+                    break None;
+                }
+                raw.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&raw).into_owned();
+                let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                    continue;
+                };
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .and_then(|n| n.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    break Some(body.to_string());
+                }
+            };
+            let Some(body) = body else { continue };
+            let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+            let reply = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": respond(request["method"].as_str().unwrap_or_default()),
+            })
+            .to_string();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            );
+        }
+    });
+    url
+}
+
+fn import_tx(args: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .env_remove("SOROBAN_RPC_URL")
+        .arg("import-tx")
+        .args(args)
+        .output()
+        .expect("Failed to execute import-tx")
+}
+
+#[test]
+fn unknown_transaction_explains_the_retention_window() {
+    let url = fake_rpc(|method| match method {
+        "getNetwork" => serde_json::json!({
+            "passphrase": "Test SDF Network ; September 2015",
+            "protocolVersion": 22,
+        }),
+        _ => serde_json::json!({ "status": "NOT_FOUND", "latestLedger": 100 }),
+    });
+
+    let output = import_tx(&["ab".repeat(32).as_str(), "--rpc-url", &url]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("was not found"), "{}", stderr);
+    assert!(stderr.contains("archive RPC"), "{}", stderr);
+}
+
+#[test]
+fn rpc_errors_are_reported() {
+    let url = fake_rpc(|_| serde_json::Value::Null);
+    let output = import_tx(&["ab".repeat(32).as_str(), "--rpc-url", &url]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("getNetwork"), "{}", stderr);
+}
+
+#[test]
+fn hash_and_rpc_url_are_required_without_from() {
+    let output = import_tx(&["ab".repeat(32).as_str()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--rpc-url"), "{}", stderr);
+}
+
+#[test]
+fn replaying_a_missing_scenario_fails_cleanly() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = import_tx(&["--from", dir.path().to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("invocation.json"), "{}", stderr);
+}
@@ -124,6 +124,31 @@ fn test_filter_no_matches() {
     assert_eq!(filtered.len(), 0);
 }
 
+// ── Value filtering ─────────────────────────────────────────────────
+
+#[test]
+fn test_filter_by_value_and_key() {
+    let mut inspector = StorageInspector::new();
+    inspector.set("balance:alice", "I128(25000)");
+    inspector.set("balance:bob", "I128(12)");
+    inspector.set("reserve", "I128(90000)");
+
+    let filter = StorageFilter::new(&[r"val:re:^I128\(\d{4,}\)$".to_string()]).unwrap();
+    let filtered = inspector.get_filtered(&filter);
+    assert_eq!(filtered.len(), 2);
+    assert!(filtered.contains_key("balance:alice"));
+    assert!(filtered.contains_key("reserve"));
+
+    let filter = StorageFilter::new(&[
+        "balance:*".to_string(),
+        r"val:re:^I128\(\d{4,}\)$".to_string(),
+    ])
+    .unwrap();
+    let filtered = inspector.get_filtered(&filter);
+    assert_eq!(filtered.len(), 1);
+    assert!(filtered.contains_key("balance:alice"));
+}
+
 #[test]
 fn test_invalid_regex_returns_error() {
     let result = StorageFilter::new(&[r"re:[invalid".to_string()]);
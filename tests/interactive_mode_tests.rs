@@ -1,32 +1,11 @@
-use assert_cmd::Command;
-
-fn fixture_wasm(name: &str) -> std::path::PathBuf {
-    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("tests")
-        .join("fixtures")
-        .join("wasm")
-        .join(format!("{name}.wasm"))
-}
-
-fn base_cmd() -> Command {
-    let mut cmd = Command::new(env!("CARGO_BIN_EXE_soroban-debug"));
-    cmd.env("NO_COLOR", "1");
-    cmd.env("NO_BANNER", "1");
-    cmd
-}
+#[path = "fixtures/mod.rs"]
+mod fixtures;
 
 #[test]
 fn interactive_accepts_basic_commands_and_exits() {
-    let wasm = fixture_wasm("counter");
-    if !wasm.exists() {
-        eprintln!(
-            "Skipping test: fixture not found at {}. Run tests/fixtures/build.sh to build fixtures.",
-            wasm.display()
-        );
-        return;
-    }
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
 
-    let output = base_cmd()
+    let output = fixtures::soroban_debug()
         .args([
             "interactive",
             "--contract",
@@ -45,3 +24,36 @@ fn interactive_accepts_basic_commands_and_exits() {
         String::from_utf8_lossy(&output.stderr)
     );
 }
+
+#[test]
+fn step_over_runs_the_staged_call_to_its_return() {
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+
+    let output = fixtures::soroban_debug()
+        .env("RUST_LOG", "soroban_debugger=info")
+        .args([
+            "interactive",
+            "--contract",
+            wasm.to_str().unwrap(),
+            "--function",
+            "increment",
+        ])
+        .write_stdin("step-over\nstack\nstep-over\nstep-in\nquit\n")
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("Stepped over increment in C"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(stderr.contains(": returned 1"), "stderr: {}", stderr);
+    assert!(
+        stderr.contains("Call finished, no calls left to step through"),
+        "stderr: {}",
+        stderr
+    );
+    assert!(stderr.contains("Nothing to step"), "stderr: {}", stderr);
+}
@@ -0,0 +1,85 @@
+//! `--args @FILE` / `--storage @FILE`: JSON flag values read from a file or
+//! stdin, with file and position in parse errors.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn soroban_debug(args: &[&str], stdin: Option<&str>) -> (bool, String, String) {
+    let wasm = fixtures::get_fixture_path(fixtures::names::ECHO);
+    let mut command = fixtures::soroban_debug();
+    command
+        .arg(args[0])
+        .arg("--contract")
+        .arg(wasm)
+        .args(&args[1..]);
+    if let Some(stdin) = stdin {
+        command.write_stdin(stdin);
+    }
+    fixtures::outcome(&mut command)
+}
+
+#[test]
+fn run_reads_args_from_a_file_and_stdin() {
+    let dir = tempfile::tempdir().unwrap();
+    let args_file = dir.path().join("args.json");
+    std::fs::write(&args_file, "[\n  {\"type\": \"u32\", \"value\": 7}\n]\n").unwrap();
+    let at_file = format!("@{}", args_file.display());
+
+    let (success, stdout, stderr) =
+        soroban_debug(&["run", "--function", "echo", "--args", &at_file], None);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("U32(7)"), "{}", stdout);
+
+    let (success, stdout, stderr) = soroban_debug(
+        &["run", "--function", "echo", "--args", "@-"],
+        Some(r#"[{"type": "u32", "value": 9}]"#),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("U32(9)"), "{}", stdout);
+}
+
+#[test]
+fn bad_files_are_reported_with_their_path_and_position() {
+    let dir = tempfile::tempdir().unwrap();
+    let storage_file = dir.path().join("state.json");
+    std::fs::write(&storage_file, "{\n  \"balance\": 1,\n}\n").unwrap();
+    let at_storage = format!("@{}", storage_file.display());
+
+    let (success, _, stderr) = soroban_debug(
+        &[
+            "run",
+            "--function",
+            "echo",
+            "--args",
+            "[1]",
+            "--storage",
+            &at_storage,
+        ],
+        None,
+    );
+    assert!(!success);
+    assert!(
+        stderr.contains("Invalid JSON in --storage file"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("state.json"), "{}", stderr);
+    assert!(stderr.contains("line 3 column 1"), "{}", stderr);
+
+    let (success, _, stderr) = soroban_debug(
+        &[
+            "analyze",
+            "--function",
+            "echo",
+            "--args",
+            "@missing-args.json",
+        ],
+        None,
+    );
+    assert!(!success);
+    assert!(
+        stderr.contains("Failed to read --args file missing-args.json"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,105 @@
+//! `soroban-debug key`: building and decoding contract storage ledger keys.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+const CONTRACT: &str = "CAAACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6N4O";
+const COUNTER_KEY_XDR: &str =
+    "AAAABgAAAAEAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHwAAAA8AAAAHQ291bnRlcgAAAAAB";
+
+fn debugger(args: &[&str]) -> String {
+    let output = fixtures::soroban_debug()
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug");
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn key_prints_known_vector_and_decodes_back() {
+    let stdout = debugger(&[
+        "key",
+        "--contract",
+        CONTRACT,
+        "--key",
+        r#"{"type":"symbol","value":"Counter"}"#,
+        "--durability",
+        "persistent",
+        "--format",
+        "json",
+    ]);
+    let built: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(built["xdr"], COUNTER_KEY_XDR);
+
+    let stdout = debugger(&["key", "--decode", COUNTER_KEY_XDR, "--format", "json"]);
+    let decoded: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(decoded["xdr"], COUNTER_KEY_XDR);
+    assert_eq!(decoded["decoded"], built["decoded"]);
+    assert_eq!(decoded["decoded"]["contract"], CONTRACT);
+    assert_eq!(decoded["decoded"]["durability"], "persistent");
+    assert_eq!(decoded["decoded"]["key"], "Counter");
+}
+
+#[test]
+fn key_pretty_output_shows_xdr_and_decoded_fields() {
+    let stdout = debugger(&[
+        "key",
+        "--contract",
+        CONTRACT,
+        "--key",
+        r#"{"type":"symbol","value":"Counter"}"#,
+    ]);
+    assert!(stdout.starts_with(COUNTER_KEY_XDR), "{}", stdout);
+    assert!(stdout.contains(CONTRACT), "{}", stdout);
+    assert!(stdout.contains("persistent"), "{}", stdout);
+}
+
+#[test]
+fn key_bulk_mode_writes_one_key_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let keys = dir.path().join("keys.json");
+    std::fs::write(
+        &keys,
+        r#"[
+            {"type": "symbol", "value": "Counter"},
+            {"key": {"type": "symbol", "value": "Counter"}, "durability": "temporary"},
+            {"key": "ignored", "durability": "instance", "value": 1}
+        ]"#,
+    )
+    .unwrap();
+    let list = dir.path().join("keys.txt");
+
+    debugger(&[
+        "key",
+        "--contract",
+        CONTRACT,
+        "--keys-file",
+        keys.to_str().unwrap(),
+        "--output",
+        list.to_str().unwrap(),
+    ]);
+
+    let written = std::fs::read_to_string(&list).unwrap();
+    let lines: Vec<&str> = written.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], COUNTER_KEY_XDR);
+
+    let durabilities: Vec<String> = lines
+        .iter()
+        .map(|xdr| {
+            let stdout = debugger(&["key", "--decode", xdr, "--format", "json"]);
+            let decoded: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+            decoded["decoded"]["durability"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(durabilities, ["persistent", "temporary", "instance"]);
+}
@@ -0,0 +1,115 @@
+//! `--ledger-timestamp`, `--ledger-sequence`, `--network-passphrase` and
+//! `--protocol-version`, and the `[ledger]` config section they override.
+
+use soroban_debugger::runtime::executor::{ContractExecutor, LedgerOverrides};
+use soroban_debugger::runtime::loader::supported_protocol_versions;
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_counter(dir: &Path, args: &[&str]) -> (bool, String, String) {
+    fixtures::outcome(
+        fixtures::run_command(fixtures::names::COUNTER, "increment")
+            .current_dir(dir)
+            .arg("--verbose")
+            .args(args),
+    )
+}
+
+#[test]
+fn contract_runs_at_a_sequence_far_past_the_default_ttl() {
+    let wasm = fixtures::load_fixture(fixtures::names::COUNTER);
+    let ledger = LedgerOverrides {
+        timestamp: Some(1_704_067_200),
+        sequence: Some(1_000_000),
+        network_passphrase: Some("Test SDF Network ; September 2015".to_string()),
+        protocol_version: None,
+    };
+    let mut executor = ContractExecutor::with_ledger(wasm, &ledger).expect("create executor");
+    assert_eq!(executor.ledger_timestamp(), 1_704_067_200);
+    assert_eq!(executor.ledger_sequence(), 1_000_000);
+    executor.execute("increment", None).expect("increment");
+}
+
+#[test]
+fn flags_are_echoed_and_accept_rfc3339() {
+    let dir = tempfile::tempdir().unwrap();
+    let (success, stdout, stderr) = run_counter(
+        dir.path(),
+        &[
+            "--ledger-timestamp",
+            "2024-01-01T00:00:00Z",
+            "--ledger-sequence",
+            "1234",
+        ],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(
+        stdout.contains("Ledger timestamp: 2024-01-01T00:00:00Z (1704067200)"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Ledger sequence: 1234"), "{}", stdout);
+
+    let (success, _, stderr) = run_counter(dir.path(), &["--ledger-timestamp", "soon"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("--ledger-timestamp 'soon' is neither Unix seconds nor an RFC 3339 date"),
+        "{}",
+        stderr
+    );
+    // The help is about the flag, not about `--args`.
+    assert!(stderr.contains("debugger::invalid_flag"), "{}", stderr);
+    assert!(!stderr.contains("--args"), "{}", stderr);
+}
+
+#[test]
+fn config_values_apply_unless_a_flag_overrides_them() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(".soroban-debug.toml"),
+        "[ledger]\ntimestamp = 1700000000\nsequence = 77\n",
+    )
+    .unwrap();
+    let (success, stdout, stderr) = run_counter(dir.path(), &["--ledger-sequence", "88"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(
+        stdout.contains("Ledger timestamp: 2023-11-14T22:13:20Z (1700000000)"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("Ledger sequence: 88"), "{}", stdout);
+}
+
+#[test]
+fn protocol_version_is_echoed_and_checked_up_front() {
+    let dir = tempfile::tempdir().unwrap();
+    let supported = supported_protocol_versions();
+    let oldest = supported[0].to_string();
+    let (success, stdout, stderr) = run_counter(dir.path(), &["--protocol-version", &oldest]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(
+        stdout.contains(&format!("Protocol version: {}", oldest)),
+        "{}",
+        stdout
+    );
+
+    let (success, stdout, stderr) = run_counter(dir.path(), &["--protocol-version", "999"]);
+    assert!(!success);
+    assert!(!stdout.contains("Starting debugger"), "{}", stdout);
+    let listed = supported
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    assert!(
+        stderr.contains(&format!(
+            "Protocol version 999 is not supported by this host; supported versions: {}",
+            listed
+        )),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("debugger::invalid_flag"), "{}", stderr);
+}
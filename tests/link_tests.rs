@@ -0,0 +1,61 @@
+//! `run --link NAME=path.wasm`: extra contracts registered next to the one
+//! under test, passed as `{"type": "contract"}` arguments, with their storage
+//! labelled by name.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn call_linked_counter(args: &[&str]) -> (bool, String, String) {
+    let counter = fixtures::get_fixture_path(fixtures::names::COUNTER);
+    fixtures::outcome(
+        fixtures::run_command(fixtures::names::CROSS_CONTRACT, "call")
+            .arg("--link")
+            .arg(format!("counter={}", counter.display()))
+            .args(args),
+    )
+}
+
+#[test]
+fn linked_contract_is_called_by_name() {
+    let (success, stdout, stderr) = call_linked_counter(&[
+        "--args",
+        r#"[{"type": "contract", "value": "counter"}, "increment", []]"#,
+        "--output",
+        "json",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let result = &json["result"];
+    assert_eq!(result["return_value"], 1, "{}", result);
+
+    let linked = result["linked_contracts"].as_array().expect("linked list");
+    assert_eq!(linked.len(), 1);
+    assert_eq!(linked[0]["name"], "counter");
+    assert!(linked[0]["address"].as_str().unwrap().starts_with('C'));
+
+    // The counter keeps its count in its instance, registered at setup, and
+    // the diff lists the instance's keys under the counter's name.
+    let added = result["storage_diff"]["added"]
+        .as_object()
+        .expect("added storage");
+    assert_eq!(
+        added.get("counter/c"),
+        Some(&serde_json::json!(1)),
+        "{:?}",
+        added
+    );
+}
+
+#[test]
+fn unknown_contract_name_points_at_link() {
+    let (success, stdout, stderr) = call_linked_counter(&[
+        "--args",
+        r#"[{"type": "contract", "value": "vault"}, "increment", []]"#,
+    ]);
+    assert!(!success, "{}", stdout);
+    assert!(
+        stderr.contains("Unknown contract 'vault'; link it with --link vault=path.wasm"),
+        "{}",
+        stderr
+    );
+}
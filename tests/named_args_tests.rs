@@ -0,0 +1,51 @@
+//! `run --args '{"name": value, ...}'`: arguments named after the function's
+//! parameters are put in order and converted as the declared types.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_total(args: &str) -> (bool, String, String) {
+    fixtures::run(
+        fixtures::names::ORDERS,
+        "total",
+        &["--args", args, "--output", "json"],
+    )
+}
+
+#[test]
+fn named_arguments_are_reordered_and_typed() {
+    let (success, stdout, stderr) = run_total(r#"{"side": "Sell", "quantity": 3, "price": "5"}"#);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["result"]["return_value"], serde_json::json!("-15"));
+
+    // Positional arguments keep working.
+    let (success, stdout, stderr) = run_total(r#"[5, {"type": "u32", "value": 3}, "Buy"]"#);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["result"]["return_value"], serde_json::json!("15"));
+}
+
+#[test]
+fn named_arguments_name_the_parameter_at_fault() {
+    let cases = [
+        (
+            r#"{"price": "5", "side": "Buy"}"#,
+            "function total expects parameter 'quantity' of type U32; not provided",
+        ),
+        (
+            r#"{"price": "5", "quantity": 3, "side": "Buy", "memo": "hi"}"#,
+            "function total has no parameter 'memo'; its parameters are price: I128, quantity: U32, side: Side",
+        ),
+        (
+            r#"{"price": "5", "quantity": -3, "side": "Buy"}"#,
+            "function total expects parameter 'quantity' of type U32;",
+        ),
+    ];
+    for (args, expected) in cases {
+        let (success, stdout, stderr) = run_total(args);
+        assert!(!success, "{}", args);
+        let output = format!("{}{}", stdout, stderr);
+        assert!(output.contains(expected), "{}: {}", args, output);
+    }
+}
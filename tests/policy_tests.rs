@@ -0,0 +1,68 @@
+//! The `[policy]` config section blocks functions unless `--no-policy` is given.
+
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_in(dir: &Path, function: &str, extra: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .current_dir(dir)
+        .args(extra)
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--function")
+        .arg(function)
+        .output()
+        .expect("Failed to execute run command")
+}
+
+fn project_with_policy(policy: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(".soroban-debug.toml"),
+        format!("[policy]\n{}\n", policy),
+    )
+    .unwrap();
+    dir
+}
+
+#[test]
+fn denied_function_is_refused_naming_the_pattern() {
+    let dir = project_with_policy(r#"deny_functions = ["incr*"]"#);
+
+    let output = run_in(dir.path(), "increment", &[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("blocked by policy"), "{}", stderr);
+    assert!(stderr.contains("incr*"), "{}", stderr);
+
+    let output = run_in(dir.path(), "get", &[]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn allow_list_refuses_unlisted_functions() {
+    let dir = project_with_policy(r#"allow_functions = ["get"]"#);
+
+    let output = run_in(dir.path(), "increment", &[]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("none of the allowed patterns"));
+}
+
+#[test]
+fn no_policy_overrides_the_config() {
+    let dir = project_with_policy(r#"deny_functions = ["increment"]"#);
+
+    let output = run_in(dir.path(), "increment", &["--no-policy"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,113 @@
+//! Saved invocations: `run --save-preset NAME` and `preset run/list/show/delete`.
+
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn debugger(dir: &Path, args: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .env("SOROBAN_DEBUG_PRESET_DIR", dir.join("presets"))
+        .env("SOROBAN_DEBUG_HISTORY_FILE", dir.join("history.json"))
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug")
+}
+
+fn stdout_of(output: &std::process::Output) -> String {
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn saved_preset_replays_with_overrides() {
+    let dir = tempfile::tempdir().unwrap();
+    let contract = dir.path().join("counter.wasm");
+    std::fs::copy(
+        fixtures::get_fixture_path(fixtures::names::COUNTER),
+        &contract,
+    )
+    .unwrap();
+
+    let saved = stdout_of(&debugger(
+        dir.path(),
+        &[
+            "run",
+            "-c",
+            contract.to_str().unwrap(),
+            "-f",
+            "increment",
+            "--save-preset",
+            "bump",
+        ],
+    ));
+    assert!(saved.contains("Saved preset 'bump'"), "{}", saved);
+    assert!(saved.contains("I64(1)"), "{}", saved);
+
+    let list = stdout_of(&debugger(dir.path(), &["preset", "list"]));
+    assert!(list.contains("bump"), "{}", list);
+    assert!(list.contains("counter.wasm increment"), "{}", list);
+
+    let show = stdout_of(&debugger(dir.path(), &["preset", "show", "bump"]));
+    assert!(show.contains("soroban-debug run --contract="), "{}", show);
+    assert!(show.contains("--function=increment"), "{}", show);
+    assert!(!show.contains("save-preset"), "{}", show);
+
+    let replay = stdout_of(&debugger(dir.path(), &["preset", "run", "bump"]));
+    assert!(replay.contains("I64(1)"), "{}", replay);
+    let overridden = stdout_of(&debugger(
+        dir.path(),
+        &["preset", "run", "bump", "--function", "get"],
+    ));
+    assert!(overridden.contains("I64(0)"), "{}", overridden);
+
+    stdout_of(&debugger(dir.path(), &["preset", "delete", "bump"]));
+    assert!(!debugger(dir.path(), &["preset", "show", "bump"])
+        .status
+        .success());
+}
+
+#[test]
+fn replay_warns_when_the_contract_changed() {
+    let dir = tempfile::tempdir().unwrap();
+    let contract = dir.path().join("counter.wasm");
+    std::fs::copy(
+        fixtures::get_fixture_path(fixtures::names::COUNTER),
+        &contract,
+    )
+    .unwrap();
+    stdout_of(&debugger(
+        dir.path(),
+        &[
+            "run",
+            "--contract",
+            contract.to_str().unwrap(),
+            "--function",
+            "get",
+            "--save-preset",
+            "read",
+        ],
+    ));
+
+    let mut bytes = std::fs::read(&contract).unwrap();
+    // An empty custom section keeps the module valid but changes its hash.
+    bytes.extend_from_slice(&[0, 2, 1, b'x']);
+    std::fs::write(&contract, bytes).unwrap();
+
+    let output = debugger(dir.path(), &["preset", "show", "read"]);
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        text.contains("has changed since the preset was saved"),
+        "{}",
+        text
+    );
+}
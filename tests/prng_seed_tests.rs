@@ -0,0 +1,60 @@
+//! `run --seed`: a contract that draws from the host PRNG returns the same
+//! value for the same seed, and the seed in effect is always reported.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn roll(args: &[&str]) -> (bool, String, String) {
+    fixtures::run(fixtures::names::DICE, "roll", args)
+}
+
+fn roll_json(args: &[&str]) -> serde_json::Value {
+    let mut args = args.to_vec();
+    args.extend_from_slice(&["--output", "json"]);
+    let (success, stdout, stderr) = roll(&args);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    json["result"].clone()
+}
+
+#[test]
+fn same_seed_gives_the_same_roll() {
+    let first = roll_json(&["--seed", "42"]);
+    let second = roll_json(&["--seed", "42"]);
+    assert_eq!(first["prng_seed"], 42);
+    assert_eq!(first["return_value"], second["return_value"]);
+
+    let other = roll_json(&["--seed", "43"]);
+    assert_ne!(first["return_value"], other["return_value"]);
+}
+
+#[test]
+fn a_drawn_seed_is_reported_and_replays_the_run() {
+    let first = roll_json(&[]);
+    let seed = first["prng_seed"]
+        .as_u64()
+        .expect("prng_seed in the output");
+    let replayed = roll_json(&["--seed", &seed.to_string()]);
+    assert_eq!(first["return_value"], replayed["return_value"]);
+
+    let (success, stdout, stderr) = roll(&["--seed", "7"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("PRNG seed: 7"), "{}", stdout);
+}
+
+#[test]
+fn repeat_reuses_the_seed_unless_asked_to_vary_it() {
+    let (success, stdout, stderr) = roll(&["--seed", "7", "--repeat", "3"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("PRNG seed: 7"), "{}", stdout);
+    assert!(
+        stdout.contains("All runs produced identical results"),
+        "{}",
+        stdout
+    );
+
+    let (success, stdout, stderr) = roll(&["--seed", "7", "--repeat", "3", "--vary-seed"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("PRNG seeds: 7 to 9"), "{}", stdout);
+    assert!(stdout.contains("Run 2 (seed 8): "), "{}", stdout);
+}
@@ -0,0 +1,89 @@
+//! `run --repeat`: iterations share one environment with storage rolled back
+//! between them, unless `--fresh-state` asks for one each.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn repeat_increment(args: &[&str]) -> String {
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+    let output = fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(wasm)
+        .args(["--function", "increment", "--repeat", "5"])
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(
+        output.status.success(),
+        "{}{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    stdout
+}
+
+#[test]
+fn shared_environment_rolls_storage_back() {
+    let stdout = repeat_increment(&[]);
+    assert!(stdout.contains("Setup: "), "{}", stdout);
+    assert!(stdout.contains(", once (about "), "{}", stdout);
+    assert!(stdout.contains("Duration per iteration:"), "{}", stdout);
+    assert!(
+        stdout.contains("All runs produced identical results"),
+        "{}",
+        stdout
+    );
+    // Every iteration counted up from the same stored value.
+    assert!(stdout.contains(r#"(1)""#), "{}", stdout);
+}
+
+#[test]
+fn fresh_state_builds_an_environment_per_iteration() {
+    let stdout = repeat_increment(&["--fresh-state"]);
+    assert!(
+        stdout.contains("over 5 environments (--fresh-state)"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("All runs produced identical results"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn json_output_is_one_document_with_the_summary() {
+    let stdout = repeat_increment(&["--output", "json", "--cost-table"]);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let summary = &json["repeat"];
+    assert_eq!(
+        summary["runs"].as_array().map(Vec::len),
+        Some(5),
+        "{}",
+        json
+    );
+    assert_eq!(summary["inconsistent_results"], false, "{}", json);
+    assert!(summary["cpu_instructions"]["avg"].as_u64().unwrap() > 0);
+    assert!(json["cost_table"].is_object(), "{}", json);
+}
+
+#[test]
+fn fresh_state_needs_repeat() {
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+    let output = fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(wasm)
+        .args(["--function", "increment", "--fresh-state"])
+        .output()
+        .expect("Failed to execute soroban-debug");
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--fresh-state requires --repeat"),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,64 @@
+//! REPL `checkpoint`/`rollback` and `reset` against the counter fixture.
+
+use soroban_debugger::repl::commands::ResetTarget;
+use soroban_debugger::repl::executor::ReplExecutor;
+use soroban_debugger::repl::ReplConfig;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn counter_repl() -> ReplExecutor {
+    let config = ReplConfig {
+        contract_path: fixtures::get_fixture_path(fixtures::names::COUNTER),
+        network_snapshot: None,
+        storage: None,
+        initial_state: None,
+        mocks: Vec::new(),
+    };
+    ReplExecutor::new(&config).expect("REPL executor should start")
+}
+
+#[tokio::test]
+async fn rollback_restores_checkpointed_storage() {
+    let mut repl = counter_repl();
+
+    repl.call_function("increment", vec![]).await.unwrap();
+    repl.checkpoint("after_first");
+    let checkpointed = repl.storage_snapshot().unwrap();
+    assert_eq!(repl.checkpoint_names(), vec!["after_first"]);
+
+    repl.call_function("increment", vec![]).await.unwrap();
+    repl.checkpoint("after_second");
+    repl.call_function("increment", vec![]).await.unwrap();
+    assert_ne!(repl.storage_snapshot().unwrap(), checkpointed);
+
+    repl.rollback("after_first").unwrap();
+    assert_eq!(repl.storage_snapshot().unwrap(), checkpointed);
+    assert_eq!(repl.checkpoint_names(), vec!["after_first"]);
+
+    // The restored environment keeps working from the checkpointed value.
+    repl.call_function("increment", vec![]).await.unwrap();
+    repl.rollback("after_first").unwrap();
+    assert_eq!(repl.storage_snapshot().unwrap(), checkpointed);
+
+    assert!(repl.rollback("after_second").is_err());
+}
+
+#[tokio::test]
+async fn reset_storage_returns_to_the_initial_state() {
+    let mut repl = counter_repl();
+    let initial = repl.storage_snapshot().unwrap();
+
+    repl.call_function("increment", vec![]).await.unwrap();
+    repl.checkpoint("dirty");
+    assert_ne!(repl.storage_snapshot().unwrap(), initial);
+
+    repl.reset(ResetTarget::Storage).unwrap();
+    assert_eq!(repl.storage_snapshot().unwrap(), initial);
+    assert_eq!(repl.checkpoint_names(), vec!["dirty"]);
+
+    repl.call_function("increment", vec![]).await.unwrap();
+    repl.reset(ResetTarget::All).unwrap();
+    assert_eq!(repl.storage_snapshot().unwrap(), initial);
+    assert!(repl.checkpoint_names().is_empty());
+}
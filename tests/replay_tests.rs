@@ -300,3 +300,25 @@ fn test_replay_trace_with_budget() {
         "Expected some output from replay command"
     );
 }
+
+#[test]
+fn test_replay_refuses_sampled_trace() {
+    let trace_file = NamedTempFile::new().unwrap();
+    let trace_json = json!({
+        "contract": "nonexistent.wasm",
+        "function": "test_function",
+        "storage": {},
+        "call_sequence": [],
+        "events": [],
+        "sampling": {"rate": 10, "observed": 500, "recorded": 50, "truncated": false}
+    });
+    fs::write(trace_file.path(), trace_json.to_string()).unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_soroban-debug"));
+    cmd.arg("replay")
+        .arg(trace_file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--trace-sample 10"))
+        .stderr(predicate::str::contains("cannot be a baseline for replay"));
+}
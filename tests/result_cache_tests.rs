@@ -0,0 +1,101 @@
+//! `run --cache-results`: reusing the report of an identical earlier run.
+
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run(cache_dir: &Path, args: &[&str]) -> String {
+    let output = fixtures::soroban_debug()
+        .env("SOROBAN_DEBUG_CACHE_DIR", cache_dir)
+        .env("SOROBAN_DEBUG_HISTORY_FILE", cache_dir.join("history.json"))
+        .env_remove("SOROBAN_DEBUG_NO_CACHE")
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::ECHO))
+        .args(["--function", "echo", "--cache-results"])
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    assert!(output.status.success(), "{}\n{}", stdout, stderr);
+    format!("{}{}", stdout, stderr)
+}
+
+#[test]
+fn identical_runs_hit_and_argument_changes_miss() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let first = run(dir.path(), &["--args", "[7]"]);
+    assert!(!first.contains("CACHED RESULT"), "{}", first);
+    assert!(first.contains("Cached result as"), "{}", first);
+
+    // Output-only flags do not change the key.
+    let second = run(dir.path(), &["--args", "[7]", "--show", "events"]);
+    assert!(second.contains("CACHED RESULT"), "{}", second);
+    assert!(second.contains("Execution report: echo"), "{}", second);
+    assert!(!second.contains("Execution Start"), "{}", second);
+
+    let changed = run(dir.path(), &["--args", "[8]"]);
+    assert!(!changed.contains("CACHED RESULT"), "{}", changed);
+    assert!(changed.contains("Execution Start"), "{}", changed);
+
+    let forced = run(dir.path(), &["--args", "[7]", "--no-cache"]);
+    assert!(!forced.contains("CACHED RESULT"), "{}", forced);
+    assert!(forced.contains("Execution Start"), "{}", forced);
+}
+
+#[test]
+fn cached_json_output_is_marked() {
+    let dir = tempfile::tempdir().unwrap();
+    run(dir.path(), &["--args", "[1]"]);
+    let output = run(dir.path(), &["--args", "[1]", "--output", "json"]);
+    let json_start = output.find('{').expect("JSON output expected");
+    let value: serde_json::Value = serde_json::Deserializer::from_str(&output[json_start..])
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(value["cached"], true);
+    assert_eq!(value["status"], "success");
+}
+
+#[test]
+fn runs_with_live_only_outputs_bypass_the_cache() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("storage.json");
+    let export = export.to_str().unwrap();
+
+    for _ in 0..2 {
+        let output = run(dir.path(), &["--args", "[3]", "--export-storage", export]);
+        assert!(
+            output.contains("Result cache bypassed: --export-storage needs a live run"),
+            "{}",
+            output
+        );
+        assert!(!output.contains("CACHED RESULT"), "{}", output);
+    }
+}
+
+#[test]
+fn cache_clear_removes_cached_results() {
+    let dir = tempfile::tempdir().unwrap();
+    run(dir.path(), &["--args", "[2]"]);
+
+    let info = fixtures::soroban_debug()
+        .env("SOROBAN_DEBUG_CACHE_DIR", dir.path())
+        .args(["cache", "info"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&info.stdout).contains("Cached results: 1"));
+
+    fixtures::soroban_debug()
+        .env("SOROBAN_DEBUG_CACHE_DIR", dir.path())
+        .args(["cache", "clear"])
+        .assert()
+        .success();
+
+    let rerun = run(dir.path(), &["--args", "[2]"]);
+    assert!(!rerun.contains("CACHED RESULT"), "{}", rerun);
+}
@@ -0,0 +1,77 @@
+//! `run --save-state` / `--load-state`: a later run carries on from the
+//! storage and ledger an earlier one finished with.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn return_value(stdout: &str) -> serde_json::Value {
+    let json: serde_json::Value = serde_json::from_str(stdout).expect("valid JSON");
+    json["result"]["return_value"].clone()
+}
+
+#[test]
+fn resumed_run_sees_the_saved_storage_and_ledger() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    let state = state.to_str().unwrap();
+    let (success, stdout, stderr) = fixtures::run(
+        "counter",
+        "increment",
+        &["--save-state", state, "--ledger-sequence", "500"],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("Saved state to"), "{}", stdout);
+
+    let (success, stdout, stderr) = fixtures::run(
+        "counter",
+        "increment",
+        &["--load-state", state, "--save-state", state],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("Resumed state from"), "{}", stdout);
+
+    let (success, stdout, stderr) = fixtures::run(
+        "counter",
+        "get",
+        &["--load-state", state, "--output", "json"],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert_eq!(return_value(&stdout), serde_json::json!(2));
+
+    let (success, stdout, stderr) =
+        fixtures::run("counter", "get", &["--load-state", state, "--verbose"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("Ledger sequence: 500"), "{}", stdout);
+}
+
+#[test]
+fn state_of_another_contract_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    let state = state.to_str().unwrap();
+    let (success, _, stderr) = fixtures::run("counter", "increment", &["--save-state", state]);
+    assert!(success, "{}", stderr);
+
+    let (success, _, stderr) = fixtures::run("always_panic", "panic", &["--load-state", state]);
+    assert!(!success);
+    assert!(stderr.contains("State was saved by contract"), "{}", stderr);
+}
+
+#[test]
+fn newer_state_version_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let state = dir.path().join("state.json");
+    std::fs::write(
+        &state,
+        r#"{"format": "soroban-debug-state", "version": 99}"#,
+    )
+    .unwrap();
+    let (success, _, stderr) =
+        fixtures::run("counter", "get", &["--load-state", state.to_str().unwrap()]);
+    assert!(!success);
+    assert!(
+        stderr.contains("state format version 99 is newer than this debugger supports"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,84 @@
+//! `run --smoke` and `symbolic --all-functions` call every spec function.
+
+use assert_cmd::Command;
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn debugger_in(dir: &Path) -> Command {
+    let mut cmd = fixtures::soroban_debug();
+    cmd.current_dir(dir);
+    cmd
+}
+
+#[test]
+fn smoke_reports_every_function_and_writes_junit() {
+    let dir = tempfile::tempdir().unwrap();
+    let junit = dir.path().join("smoke.xml");
+
+    let output = debugger_in(dir.path())
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--smoke")
+        .arg("--junit")
+        .arg(&junit)
+        .output()
+        .expect("Failed to execute run --smoke");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("increment  pass"), "{}", stdout);
+    assert!(stdout.contains("get        pass"), "{}", stdout);
+    assert!(stdout.contains("0 trapped, 0 errors"), "{}", stdout);
+
+    let xml = std::fs::read_to_string(&junit).unwrap();
+    assert!(xml.contains("<testcase classname=\"counter.wasm\" name=\"increment\"/>"));
+    assert!(xml.contains("<testcase classname=\"counter.wasm\" name=\"get\"/>"));
+}
+
+#[test]
+fn smoke_skips_functions_blocked_by_policy() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join(".soroban-debug.toml"),
+        "[policy]\ndeny_functions = [\"incr*\"]\n",
+    )
+    .unwrap();
+
+    let output = debugger_in(dir.path())
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--smoke")
+        .output()
+        .expect("Failed to execute run --smoke");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("increment  skipped"), "{}", stdout);
+    assert!(stdout.contains("1 skipped"), "{}", stdout);
+}
+
+#[test]
+fn symbolic_all_functions_explores_each_function() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let output = debugger_in(dir.path())
+        .arg("symbolic")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--all-functions")
+        .arg("--path-cap")
+        .arg("3")
+        .output()
+        .expect("Failed to execute symbolic --all-functions");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", stdout);
+    assert!(stdout.contains("increment"), "{}", stdout);
+    assert!(stdout.contains("2 passed"), "{}", stdout);
+}
@@ -0,0 +1,116 @@
+//! `run --network-snapshot` with a local WASM that is not the deployed code.
+
+use soroban_debugger::cli::args::SnapshotFormat;
+use soroban_debugger::simulator::{ContractState, NetworkSnapshot, SnapshotManager};
+use soroban_debugger::utils::wasm::compute_wasm_sha256;
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+/// A snapshot whose only contract runs the counter fixture, returning its hash.
+fn write_snapshot(path: &Path) -> String {
+    let code = fixtures::load_fixture(fixtures::names::COUNTER);
+    let hash = compute_wasm_sha256(&code);
+    let mut contract = ContractState::new("CCOUNTER", hash.clone());
+    contract.code = Some(hex::encode(&code));
+    let mut snapshot = NetworkSnapshot::new(321, "Test SDF Network ; September 2015", 1000);
+    snapshot.add_contract(contract).unwrap();
+    SnapshotManager::save_as(&snapshot, path, SnapshotFormat::V2).unwrap();
+    hash
+}
+
+fn run(snapshot: &Path, args: &[&str]) -> (bool, String) {
+    let output = fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::ECHO))
+        .arg("--network-snapshot")
+        .arg(snapshot)
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    (output.status.success(), format!("{}{}", stdout, stderr))
+}
+
+#[test]
+fn differing_code_warns_with_both_hashes() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = dir.path().join("snapshot.v2");
+    let deployed = write_snapshot(&snapshot);
+    let local = compute_wasm_sha256(&fixtures::load_fixture(fixtures::names::ECHO));
+
+    let (success, output) = run(&snapshot, &["--function", "echo", "--args", "[7]"]);
+    assert!(success, "{}", output);
+    assert!(
+        output.contains("is not the code deployed in the snapshot"),
+        "{}",
+        output
+    );
+    assert!(output.contains(&local), "{}", output);
+    assert!(
+        output.contains(&format!("CCOUNTER {}", deployed)),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("Code: local WASM, not deployed in the snapshot"),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn require_matching_code_fails_on_differing_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = dir.path().join("snapshot.v2");
+    let deployed = write_snapshot(&snapshot);
+
+    let (success, output) = run(
+        &snapshot,
+        &[
+            "--function",
+            "echo",
+            "--args",
+            "[7]",
+            "--require-matching-code",
+        ],
+    );
+    assert!(!success, "{}", output);
+    assert!(output.contains("checksum mismatch"), "{}", output);
+    assert!(output.contains(&deployed), "{}", output);
+    assert!(!output.contains("Execution Start"), "{}", output);
+}
+
+#[test]
+fn use_snapshot_code_runs_the_deployed_code() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshot = dir.path().join("snapshot.v2");
+    let deployed = write_snapshot(&snapshot);
+
+    // `get` only exists in the deployed counter, not in the local echo build.
+    let (success, output) = run(
+        &snapshot,
+        &[
+            "--function",
+            "get",
+            "--use-snapshot-code",
+            "--output",
+            "json",
+        ],
+    );
+    assert!(success, "{}", output);
+    let json_start = output.find('{').expect("JSON output expected");
+    let value: serde_json::Value = serde_json::Deserializer::from_str(&output[json_start..])
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap();
+    let code = &value["result"]["code"];
+    assert_eq!(code["source"], "snapshot", "{}", value);
+    assert_eq!(code["sha256"], deployed.as_str());
+    assert_eq!(code["contract_id"], "CCOUNTER");
+    assert_eq!(value["sha256"], deployed.as_str());
+}
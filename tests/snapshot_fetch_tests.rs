@@ -0,0 +1,306 @@
+//! `snapshot fetch --follow-deps` against a fake RPC server holding a
+//! two-level dependency chain, and `run --register-all-from-snapshot` over
+//! the result.
+//!
+//! The chain is router -> echo -> counter: each contract's instance storage
+//! holds the address of the next one.
+
+use soroban_debugger::simulator::SnapshotManager;
+use soroban_debugger::utils::wasm::compute_wasm_sha256;
+use soroban_env_host::xdr::{
+    ContractCodeEntry, ContractCodeEntryExt, ContractDataDurability, ContractDataEntry,
+    ContractExecutable, ExtensionPoint, Hash, LedgerEntryData, LedgerKey, Limits, ReadXdr,
+    ScAddress, ScContractInstance, ScMap, ScMapEntry, ScSymbol, ScVal, WriteXdr,
+};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+/// The contracts on the fake network: address byte, fixture, and the
+/// address byte its instance storage refers to.
+const CHAIN: &[(u8, &str, Option<u8>)] = &[
+    (1, "cross_contract", Some(2)),
+    (2, "echo", Some(3)),
+    (3, "counter", None),
+];
+
+fn address(byte: u8) -> ScAddress {
+    ScAddress::Contract(Hash([byte; 32]))
+}
+
+fn code_hash(name: &str) -> Hash {
+    let code = fixtures::load_fixture(name);
+    Hash(
+        hex::decode(compute_wasm_sha256(&code))
+            .unwrap()
+            .try_into()
+            .unwrap(),
+    )
+}
+
+fn entry_data(key: &LedgerKey) -> Option<LedgerEntryData> {
+    match key {
+        LedgerKey::ContractData(key) => {
+            let (_, name, next) = CHAIN
+                .iter()
+                .find(|(byte, _, _)| key.contract == address(*byte))?;
+            let storage = next.map(|next| {
+                ScMap(
+                    vec![ScMapEntry {
+                        key: ScVal::Symbol(ScSymbol("next".try_into().unwrap())),
+                        val: ScVal::Address(address(next)),
+                    }]
+                    .try_into()
+                    .unwrap(),
+                )
+            });
+            Some(LedgerEntryData::ContractData(ContractDataEntry {
+                ext: ExtensionPoint::V0,
+                contract: key.contract.clone(),
+                key: ScVal::LedgerKeyContractInstance,
+                durability: ContractDataDurability::Persistent,
+                val: ScVal::ContractInstance(ScContractInstance {
+                    executable: ContractExecutable::Wasm(code_hash(name)),
+                    storage,
+                }),
+            }))
+        }
+        LedgerKey::ContractCode(key) => {
+            let (_, name, _) = CHAIN
+                .iter()
+                .find(|(_, name, _)| code_hash(name) == key.hash)?;
+            Some(LedgerEntryData::ContractCode(ContractCodeEntry {
+                ext: ContractCodeEntryExt::V0,
+                hash: key.hash.clone(),
+                code: fixtures::load_fixture(name).try_into().unwrap(),
+            }))
+        }
+        _ => None,
+    }
+}
+
+fn respond(method: &str, params: &serde_json::Value) -> serde_json::Value {
+    match method {
+        "getNetwork" => serde_json::json!({
+            "passphrase": "Test SDF Network ; September 2015",
+            "protocolVersion": 22,
+        }),
+        "getLatestLedger" => serde_json::json!({
+            "id": "00".repeat(32),
+            "protocolVersion": 22,
+            "sequence": 500,
+        }),
+        "getLedgerEntries" => {
+            let entries: Vec<serde_json::Value> = params["keys"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|key| {
+                    let key = key.as_str().unwrap();
+                    let decoded = LedgerKey::from_xdr_base64(key, Limits::none()).unwrap();
+                    let data = entry_data(&decoded)?;
+                    Some(serde_json::json!({
+                        "key": key,
+                        "xdr": data.to_xdr_base64(Limits::none()).unwrap(),
+                        "lastModifiedLedgerSeq": 400,
+                        "liveUntilLedgerSeq": 10_000,
+                    }))
+                })
+                .collect();
+            serde_json::json!({ "entries": entries, "latestLedger": 500 })
+        }
+        _ => serde_json::Value::Null,
+    }
+}
+
+/// Answer every JSON-RPC request with [`respond`] until the listener is dropped.
+fn fake_rpc() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { break };
+            let mut raw = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body = loop {
+                let n = stream.read(&mut buf).unwrap_or(0);
+                if n == 0 {
+                    break None;
+                }
+                raw.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&raw).into_owned();
+                let Some((head, body)) = text.split_once("\r\n\r\n") else {
+                    continue;
+                };
+                let length = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("Content-Length: "))
+                    .and_then(|n| n.trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+                if body.len() >= length {
+                    break Some(body.to_string());
+                }
+            };
+            let Some(body) = body else { continue };
+            let request: serde_json::Value = serde_json::from_str(&body).unwrap();
+            let reply = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": respond(
+                    request["method"].as_str().unwrap_or_default(),
+                    &request["params"],
+                ),
+            })
+            .to_string();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                reply.len(),
+                reply
+            );
+        }
+    });
+    url
+}
+
+fn soroban_debug(args: &[&str]) -> (bool, String) {
+    let output = fixtures::soroban_debug()
+        .env_remove("SOROBAN_RPC_URL")
+        .args(args)
+        .output()
+        .expect("Failed to execute soroban-debug");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    (output.status.success(), format!("{}{}", stdout, stderr))
+}
+
+fn fetch(out: &Path, extra: &[&str]) -> (bool, String) {
+    let url = fake_rpc();
+    let router = address(1).to_string();
+    let mut args = vec![
+        "snapshot",
+        "fetch",
+        router.as_str(),
+        "--rpc-url",
+        url.as_str(),
+        "--out",
+        out.to_str().unwrap(),
+    ];
+    args.extend_from_slice(extra);
+    soroban_debug(&args)
+}
+
+#[test]
+fn follow_deps_fetches_the_whole_chain_and_records_it() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("chain.v2");
+
+    let (success, output) = fetch(&out, &["--follow-deps", "--depth", "2"]);
+    assert!(success, "{}", output);
+    assert!(output.contains("Fetched 3 contract(s)"), "{}", output);
+
+    let snapshot = SnapshotManager::load(&out).unwrap();
+    assert_eq!(snapshot.ledger.sequence, 500);
+    let ids: Vec<String> = CHAIN
+        .iter()
+        .map(|(b, _, _)| address(*b).to_string())
+        .collect();
+    for (id, (_, name, _)) in ids.iter().zip(CHAIN) {
+        let contract = snapshot.get_contract(id).expect(id);
+        let code = fixtures::load_fixture(name);
+        assert_eq!(contract.wasm_hash, compute_wasm_sha256(&code));
+        assert_eq!(contract.code.as_deref(), Some(hex::encode(&code).as_str()));
+    }
+    assert_eq!(
+        snapshot.get_contract(&ids[0]).unwrap().get_storage("next"),
+        Some(&serde_json::json!(ids[1]))
+    );
+    assert_eq!(snapshot.dependencies.len(), 2);
+    assert_eq!(snapshot.dependencies[&ids[0]], [ids[1].clone()]);
+    assert_eq!(snapshot.dependencies[&ids[1]], [ids[2].clone()]);
+}
+
+#[test]
+fn depth_and_entry_budget_limit_the_fetch() {
+    let dir = tempfile::tempdir().unwrap();
+    let echo = address(2).to_string();
+    let counter = address(3).to_string();
+
+    let shallow = dir.path().join("shallow.v2");
+    let (success, output) = fetch(&shallow, &["--follow-deps"]);
+    assert!(success, "{}", output);
+    assert!(output.contains("beyond --depth 1"), "{}", output);
+    let snapshot = SnapshotManager::load(&shallow).unwrap();
+    assert_eq!(snapshot.contracts.len(), 2);
+    assert!(snapshot.get_contract(&counter).is_none());
+    assert_eq!(snapshot.dependencies[&echo], std::slice::from_ref(&counter));
+
+    let budget = dir.path().join("budget.v2");
+    let (success, output) = fetch(
+        &budget,
+        &["--follow-deps", "--depth", "2", "--max-entries", "3"],
+    );
+    assert!(success, "{}", output);
+    assert!(
+        output.contains("budget of 3 entries is spent"),
+        "{}",
+        output
+    );
+    let snapshot = SnapshotManager::load(&budget).unwrap();
+    assert_eq!(snapshot.contracts.len(), 1);
+
+    let alone = dir.path().join("alone.v2");
+    let (success, output) = fetch(&alone, &[]);
+    assert!(success, "{}", output);
+    let snapshot = SnapshotManager::load(&alone).unwrap();
+    assert_eq!(snapshot.contracts.len(), 1);
+    assert!(snapshot.dependencies.is_empty());
+}
+
+#[test]
+fn register_all_from_snapshot_lets_calls_reach_fetched_contracts() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("chain.v2");
+    let (success, output) = fetch(&out, &["--follow-deps", "--depth", "2"]);
+    assert!(success, "{}", output);
+
+    let echo = address(2).to_string();
+    let call_args = format!(
+        r#"["{}", {{"type": "symbol", "value": "echo"}}, [7]]"#,
+        echo
+    );
+    let contract = fixtures::get_fixture_path(fixtures::names::CROSS_CONTRACT);
+    let run = |register: bool| {
+        let mut args = vec![
+            "run",
+            "--contract",
+            contract.to_str().unwrap(),
+            "--function",
+            "call",
+            "--args",
+            call_args.as_str(),
+            "--network-snapshot",
+            out.to_str().unwrap(),
+        ];
+        if register {
+            args.push("--register-all-from-snapshot");
+        }
+        soroban_debug(&args)
+    };
+
+    let (success, output) = run(true);
+    assert!(success, "{}", output);
+    assert!(
+        output.contains("Registered 3 contract(s) from the snapshot"),
+        "{}",
+        output
+    );
+    assert!(output.contains(&echo), "{}", output);
+
+    // Without registration the echo contract does not exist locally.
+    let (success, output) = run(false);
+    assert!(!success, "{}", output);
+}
@@ -0,0 +1,174 @@
+//! `snapshot show`, `snapshot convert` and `snapshot patch` across the v1 JSON
+//! and v2 formats.
+
+use soroban_debugger::cli::args::SnapshotFormat;
+use soroban_debugger::simulator::{AccountState, ContractState, NetworkSnapshot, SnapshotManager};
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn write_snapshot(path: &Path, format: SnapshotFormat) {
+    let mut snapshot = NetworkSnapshot::new(321, "Test SDF Network ; September 2015", 1000);
+    snapshot
+        .add_account(AccountState::new("GABCD123", "1000000", 1))
+        .unwrap();
+    SnapshotManager::save_as(&snapshot, path, format).unwrap();
+}
+
+fn snapshot_cmd(args: &[&str]) -> std::process::Output {
+    fixtures::soroban_debug()
+        .arg("snapshot")
+        .args(args)
+        .output()
+        .expect("Failed to execute snapshot command")
+}
+
+#[test]
+fn show_reads_both_formats() {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, format, expected) in [
+        ("v1.json", SnapshotFormat::V1Json, "Format: v1-json"),
+        ("snapshot.v2", SnapshotFormat::V2, "Format: v2"),
+    ] {
+        let path = dir.path().join(name);
+        write_snapshot(&path, format);
+
+        let output = snapshot_cmd(&["show", path.to_str().unwrap()]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert!(stdout.contains(expected), "{}", stdout);
+        assert!(stdout.contains("Ledger sequence: 321"), "{}", stdout);
+        assert!(stdout.contains("Entries: 1 (1 accounts"), "{}", stdout);
+    }
+}
+
+#[test]
+fn show_reports_a_corrupted_v2_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("snapshot.v2");
+    write_snapshot(&path, SnapshotFormat::V2);
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0xff;
+    std::fs::write(&path, bytes).unwrap();
+
+    let output = snapshot_cmd(&["show", path.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("checksum mismatch"));
+}
+
+#[test]
+fn convert_to_v1_json_keeps_the_snapshot() {
+    let dir = tempfile::tempdir().unwrap();
+    let v2 = dir.path().join("snapshot.v2");
+    let v1 = dir.path().join("snapshot.json");
+    write_snapshot(&v2, SnapshotFormat::V2);
+
+    let output = snapshot_cmd(&[
+        "convert",
+        v2.to_str().unwrap(),
+        v1.to_str().unwrap(),
+        "--format",
+        "v1-json",
+    ]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let json: NetworkSnapshot =
+        serde_json::from_str(&std::fs::read_to_string(&v1).unwrap()).unwrap();
+    assert_eq!(json.ledger.sequence, 321);
+    assert_eq!(json.accounts[0].address, "GABCD123");
+}
+
+fn write_pool_snapshot(path: &Path) {
+    let mut snapshot = NetworkSnapshot::new(321, "Test SDF Network ; September 2015", 1000);
+    let mut pool = ContractState::new("CPOOL", "aa");
+    pool.set_storage("reserve_a", serde_json::json!("1000000"));
+    pool.set_storage("reserve_b", serde_json::json!(400));
+    pool.set_storage("admin", serde_json::json!("GADMIN"));
+    snapshot.add_contract(pool).unwrap();
+    SnapshotManager::save_as(&snapshot, path, SnapshotFormat::V2).unwrap();
+}
+
+#[test]
+fn patch_applies_operations_and_keeps_the_input_format() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("snapshot.v2");
+    let out = dir.path().join("patched.v2");
+    let patch = dir.path().join("changes.json");
+    write_pool_snapshot(&input);
+    std::fs::write(
+        &patch,
+        r#"{"operations": [
+            {"op": "scale", "key": "reserve_*", "factor": "0.01"},
+            {"op": "extend-ttl", "contract": "CPOOL", "key": "reserve_a", "extend_to": 1000},
+            {"op": "delete", "key": "admin"},
+            {"op": "set", "contract": "CPOOL", "key": "paused", "value": true}
+        ]}"#,
+    )
+    .unwrap();
+
+    let output = snapshot_cmd(&[
+        "patch",
+        input.to_str().unwrap(),
+        "--patch",
+        patch.to_str().unwrap(),
+        "--out",
+        out.to_str().unwrap(),
+    ]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("scale reserve_* by 0.01: 2 entries"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("delete admin: 1 entry"), "{}", stdout);
+
+    let file = soroban_debugger::simulator::format::read_file(&out).unwrap();
+    assert!(file.header.is_some());
+    let pool = file.snapshot.get_contract("CPOOL").unwrap();
+    assert_eq!(
+        pool.get_storage("reserve_a"),
+        Some(&serde_json::json!("10000"))
+    );
+    assert_eq!(pool.get_storage("reserve_b"), Some(&serde_json::json!(4)));
+    assert_eq!(pool.get_storage("paused"), Some(&serde_json::json!(true)));
+    assert!(pool.get_storage("admin").is_none());
+    assert_eq!(pool.live_until["reserve_a"], 1321);
+}
+
+#[test]
+fn patch_fails_when_an_operation_matches_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let input = dir.path().join("snapshot.v2");
+    let out = dir.path().join("patched.v2");
+    let patch = dir.path().join("changes.json");
+    write_pool_snapshot(&input);
+    std::fs::write(&patch, r#"[{"op": "delete", "key": "fees:*"}]"#).unwrap();
+
+    let output = snapshot_cmd(&[
+        "patch",
+        input.to_str().unwrap(),
+        "--patch",
+        patch.to_str().unwrap(),
+        "--out",
+        out.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("matched no entries"), "{}", stderr);
+    assert!(!out.exists());
+}
@@ -0,0 +1,52 @@
+//! `run --source-account`: the account or contract a call runs on behalf of.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+const ACCOUNT: &str = "GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAWHF";
+
+#[test]
+fn given_account_is_reported() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--source-account", ACCOUNT, "--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["result"]["source_account"], ACCOUNT);
+}
+
+#[test]
+fn default_account_is_random() {
+    let (success, stdout, stderr) = fixtures::run_counter(&["--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let source = json["result"]["source_account"].as_str().unwrap();
+    assert!(source.starts_with('G'), "{}", source);
+    assert_ne!(source, ACCOUNT);
+}
+
+#[test]
+fn malformed_address_is_rejected() {
+    let (success, stdout, stderr) = fixtures::run_counter(&["--source-account", "GABC"]);
+    assert!(!success, "{}", stdout);
+    assert!(
+        stderr.contains(
+            "Invalid source account 'GABC': expected a G... account or C... contract address"
+        ),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("debugger::invalid_flag"), "{}", stderr);
+}
+
+#[test]
+fn unregistered_contract_is_rejected() {
+    let contract = "CAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAHK3M";
+    let (success, stdout, stderr) = fixtures::run_counter(&["--source-account", contract]);
+    assert!(!success, "{}", stdout);
+    assert!(
+        stderr.contains(&format!("Source contract {} is not registered", contract)),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("debugger::invalid_flag"), "{}", stderr);
+}
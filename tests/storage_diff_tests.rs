@@ -0,0 +1,59 @@
+//! The storage diff `run` prints after the call, decoded from the stored
+//! ScVals.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn pretty_run_prints_the_diff_under_its_heading() {
+    let (success, stdout, stderr) = fixtures::run_counter(&[]);
+    assert!(success, "{}{}", stdout, stderr);
+
+    let diff = stdout
+        .split_once("--- Storage Changes ---")
+        .map(|(_, rest)| rest)
+        .expect("storage heading");
+    assert!(diff.contains("Storage Changes:"), "{}", stdout);
+    assert!(diff.contains("  + c = 1"), "{}", stdout);
+    assert!(!stdout.contains('\x1b'), "{:?}", stdout);
+    assert!(!stderr.contains("Storage Changes:"), "{}", stderr);
+}
+
+#[test]
+fn no_storage_diff_leaves_it_out() {
+    let (success, stdout, stderr) = fixtures::run_counter(&["--no-storage-diff"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(!stdout.contains("Storage Changes"), "{}", stdout);
+}
+
+#[test]
+fn instance_changes_are_shown_decoded_per_key() {
+    let (success, stdout, stderr) = fixtures::run_counter(&["--storage", r#"{"c": 5}"#]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("  ~ c: 5 -> 6"), "{}", stdout);
+    assert!(!stdout.contains("ContractInstance("), "{}", stdout);
+    assert!(!stdout.contains("xdr:"), "{}", stdout);
+}
+
+#[test]
+fn json_diff_holds_decoded_values() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--storage", r#"{"c": 5}"#, "--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let diff = &json["result"]["storage_diff"];
+    assert_eq!(diff["modified"]["c"], serde_json::json!([5, 6]), "{}", diff);
+    assert_eq!(diff["writers"]["c"]["function"], "increment", "{}", diff);
+    assert!(diff.get("xdr").is_none(), "{}", diff);
+}
+
+#[test]
+fn raw_xdr_adds_the_base64_of_changed_entries() {
+    let (success, stdout, stderr) = fixtures::run_counter(&["--raw-xdr", "--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let xdr = &json["result"]["storage_diff"]["xdr"]["c"];
+    assert_eq!(xdr["key"], "AAAADwAAAAFjAAAA", "{}", xdr);
+    assert!(xdr["old"].is_null(), "{}", xdr);
+    assert!(xdr["new"].is_string(), "{}", xdr);
+}
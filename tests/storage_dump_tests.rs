@@ -0,0 +1,149 @@
+//! `run --dump-storage` / `--storage-file`: the storage a run finished with,
+//! written so that a later run starts from it.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn dump_then_load_round_trips() {
+    let dir = tempfile::tempdir().unwrap();
+    let first = dir.path().join("first.json");
+    let second = dir.path().join("second.json");
+    let (success, stdout, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "increment",
+        &[
+            "--persistent-storage",
+            r#"{"supply": 2}"#,
+            "--temporary-storage",
+            r#"{"nonce": 3}"#,
+            "--dump-storage",
+            first.to_str().unwrap(),
+        ],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("Dumped 3 storage entries"), "{}", stdout);
+
+    let dump: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&first).unwrap()).unwrap();
+    assert_eq!(dump["format"], "soroban-debug-storage");
+    assert_eq!(dump["version"], 1);
+    let entries = dump["entries"].as_array().expect("a list of entries");
+    assert_eq!(
+        entries[0],
+        serde_json::json!({
+            "key": {"type": "symbol", "value": "c"},
+            "value": {"type": "i64", "value": 1},
+            "durability": "instance",
+        })
+    );
+    assert_eq!(entries[1]["durability"], "persistent");
+    assert_eq!(entries[2]["durability"], "temporary");
+    assert!(entries[2]["live_until"].is_u64(), "{}", dump);
+
+    let storage = format!("@{}", first.display());
+    let (success, stdout, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "get",
+        &[
+            "--storage",
+            &storage,
+            "--dump-storage",
+            second.to_str().unwrap(),
+        ],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("I64(1)"), "{}", stdout);
+    assert_eq!(
+        std::fs::read_to_string(&first).unwrap(),
+        std::fs::read_to_string(&second).unwrap()
+    );
+}
+
+#[test]
+fn storage_file_is_applied_before_inline_storage() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("storage.json");
+    let path = path.to_str().unwrap();
+    let (success, stdout, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "increment",
+        &["--storage", r#"{"c": 41}"#, "--dump-storage", path],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+
+    let (success, stdout, stderr) =
+        fixtures::run(fixtures::names::COUNTER, "get", &["--storage-file", path]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("I64(42)"), "{}", stdout);
+
+    // The inline value wins over the file's.
+    let (success, stdout, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "get",
+        &["--storage-file", path, "--storage", r#"{"c": 7}"#],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("I64(7)"), "{}", stdout);
+}
+
+#[test]
+fn newer_dump_version_is_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("storage.json");
+    std::fs::write(
+        &path,
+        r#"{"format": "soroban-debug-storage", "version": 99, "entries": []}"#,
+    )
+    .unwrap();
+    let (success, _, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "get",
+        &["--storage-file", path.to_str().unwrap()],
+    );
+    assert!(!success);
+    assert!(
+        stderr.contains("storage dump version 99 is newer than this debugger supports"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("upgrade soroban-debug"), "{}", stderr);
+
+    std::fs::write(&path, r#"{"c": 1}"#).unwrap();
+    let (success, _, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "get",
+        &["--storage-file", path.to_str().unwrap()],
+    );
+    assert!(!success);
+    assert!(
+        stderr.contains("is not a --dump-storage file"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn existing_dump_needs_overwrite() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("storage.json");
+    std::fs::write(&path, "{}").unwrap();
+    let path = path.to_str().unwrap();
+
+    let (success, _, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "increment",
+        &["--dump-storage", path],
+    );
+    assert!(!success);
+    assert!(stderr.contains("pass --overwrite"), "{}", stderr);
+    assert_eq!(std::fs::read_to_string(path).unwrap(), "{}");
+
+    let (success, stdout, stderr) = fixtures::run(
+        fixtures::names::COUNTER,
+        "increment",
+        &["--dump-storage", path, "--overwrite"],
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(std::fs::read_to_string(path).unwrap().contains("\"c\""));
+}
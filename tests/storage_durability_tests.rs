@@ -0,0 +1,339 @@
+//! `run --instance-storage` / `--persistent-storage` / `--temporary-storage`:
+//! each seeds its own kind of storage, and the storage listing groups the
+//! entries by it. `--durability` lists one kind only, and entries close to
+//! the end of their TTL are flagged, and every entry is listed with its size
+//! and counted in a rent estimate. `--sort`, `--limit` and `--offset` page
+//! the listing.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+/// Run the counter's `get`, which reads the storage the flags seed.
+fn run_get(args: &[&str]) -> (bool, String, String) {
+    fixtures::run(fixtures::names::COUNTER, "get", args)
+}
+
+fn line_with<'a>(stdout: &'a str, needle: &str) -> &'a str {
+    stdout
+        .lines()
+        .find(|line| line.contains(needle))
+        .unwrap_or_else(|| panic!("no line with {:?} in:\n{}", needle, stdout))
+}
+
+/// The heading of the storage group the line with `needle` is listed under.
+fn group_of<'a>(stdout: &'a str, needle: &str) -> &'a str {
+    let lines: Vec<&str> = stdout.lines().collect();
+    let at = lines
+        .iter()
+        .position(|line| line.contains(needle))
+        .unwrap_or_else(|| panic!("no line with {:?} in:\n{}", needle, stdout));
+    lines[..at]
+        .iter()
+        .rev()
+        .find(|line| line.contains(" storage ("))
+        .copied()
+        .unwrap_or_else(|| {
+            panic!(
+                "{:?} is not under a storage heading in:\n{}",
+                needle, stdout
+            )
+        })
+}
+
+const SEEDS: [&str; 6] = [
+    "--instance-storage",
+    r#"{"admin": 1}"#,
+    "--persistent-storage",
+    r#"{"supply": 2}"#,
+    "--temporary-storage",
+    r#"{"nonce": 3}"#,
+];
+
+#[test]
+fn entries_land_in_and_are_grouped_by_their_storage() {
+    let (success, stdout, stderr) = run_get(&[&SEEDS[..], &["--storage-filter", "*"]].concat());
+    assert!(success, "{}{}", stdout, stderr);
+    // Keys and values are listed decoded.
+    assert!(
+        line_with(&stdout, "admin")
+            .trim_start()
+            .starts_with("admin = "),
+        "{}",
+        stdout
+    );
+    assert!(
+        group_of(&stdout, "admin").contains("Instance storage"),
+        "{}",
+        stdout
+    );
+    assert!(
+        group_of(&stdout, "supply").contains("Persistent storage"),
+        "{}",
+        stdout
+    );
+    assert!(
+        group_of(&stdout, "nonce").contains("Temporary storage"),
+        "{}",
+        stdout
+    );
+}
+
+#[test]
+fn durability_flag_lists_only_that_storage() {
+    let (success, stdout, stderr) = run_get(&[&SEEDS[..], &["--durability", "temporary"]].concat());
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("nonce"), "{}", stdout);
+    assert!(!stdout.contains("supply"), "{}", stdout);
+    assert!(!stdout.contains("admin"), "{}", stdout);
+}
+
+#[test]
+fn json_storage_entries_carry_their_durability() {
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &["--storage-filter", "*", "--durability", "instance"],
+            &["--durability", "persistent", "--output", "json"],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let storage = json["result"]["storage"].as_array().expect("storage list");
+    let durability_of = |needle: &str| {
+        storage
+            .iter()
+            .find(|entry| {
+                entry["key"]
+                    .as_str()
+                    .is_some_and(|key| key.contains(needle))
+            })
+            .map(|entry| entry["durability"].clone())
+    };
+    assert_eq!(durability_of("admin"), Some(serde_json::json!("instance")));
+    assert_eq!(
+        durability_of("supply"),
+        Some(serde_json::json!("persistent"))
+    );
+    assert_eq!(durability_of("nonce"), None, "{}", stdout);
+}
+
+#[test]
+fn temporary_entries_are_checked_against_the_ttl_threshold() {
+    // TTLs are ledgers left, so a late sequence does not hide a short one.
+    let (success, stdout, stderr) = run_get(&[
+        "--temporary-storage",
+        r#"{"nonce": 3}"#,
+        "--ledger-sequence",
+        "1000000",
+        "--show",
+        "ledger",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    let output = format!("{}{}", stdout, stderr);
+    assert!(output.contains("near expiration"), "{}", output);
+    assert!(output.contains("Temporary"), "{}", output);
+}
+
+#[test]
+fn listing_shows_live_until_and_warns_on_short_ttls() {
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &["--storage-filter", "*", "--ledger-sequence", "500"],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let nonce = line_with(&stdout, "nonce");
+    assert!(
+        nonce.contains("live until") && nonce.contains("left)"),
+        "{}",
+        stdout
+    );
+    // Warnings may be logged to either stream.
+    let output = format!("{}{}", stdout, stderr);
+    assert!(
+        output.contains("storage entry near expiration (TTL < 1000)"),
+        "{}",
+        output
+    );
+    assert!(
+        line_with(&output, "near expiry").contains("nonce"),
+        "{}",
+        output
+    );
+
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &["--storage-filter", "*", "--ledger-sequence", "500"],
+            &["--output", "json"],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let warnings = json["result"]["ttl_warnings"]
+        .as_array()
+        .expect("ttl_warnings list");
+    assert_eq!(warnings.len(), 1, "{}", stdout);
+    assert!(warnings[0]["key"].as_str().unwrap().contains("nonce"));
+    assert_eq!(warnings[0]["durability"], "temporary");
+    assert_eq!(warnings[0]["expired"], false);
+    let live_until = warnings[0]["live_until"].as_u64().unwrap();
+    assert!(live_until >= 500, "{}", stdout);
+    assert_eq!(
+        warnings[0]["ledgers_left"].as_u64().unwrap(),
+        live_until - 500
+    );
+}
+
+#[test]
+fn listing_shows_sizes_and_a_rent_estimate() {
+    let (success, stdout, stderr) = run_get(&[&SEEDS[..], &["--storage-filter", "*"]].concat());
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(
+        line_with(&stdout, "supply").contains(" B, live until"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("Rent estimate (testnet rates, 518400 ledgers):"),
+        "{}",
+        stdout
+    );
+    // Seeded entries were there before the call, which wrote nothing. The
+    // temporary ones include the nonce registering the contract stored.
+    assert!(
+        line_with(&stdout, "  temporary: 2 entries, ")
+            .contains("(this run: 0 entries, 0 B, 0 stroops)"),
+        "{}",
+        stdout
+    );
+
+    let (success, stdout, stderr) =
+        run_get(&[&SEEDS[..], &["--storage-filter", "*", "--output", "json"]].concat());
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let storage = json["result"]["storage"].as_array().expect("storage list");
+    assert!(
+        storage
+            .iter()
+            .all(|entry| entry["size_bytes"].as_u64().is_some_and(|size| size > 0)),
+        "{}",
+        stdout
+    );
+    let estimate = &json["result"]["rent_estimate"];
+    let classes: Vec<&str> = estimate["classes"]
+        .as_array()
+        .expect("rent classes")
+        .iter()
+        .map(|class| class["durability"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        classes,
+        ["instance", "persistent", "temporary"],
+        "{}",
+        stdout
+    );
+    assert!(
+        estimate["total"]["stroops"].as_u64().unwrap() > 0,
+        "{}",
+        stdout
+    );
+    assert_eq!(estimate["written"]["entries"], 0, "{}", stdout);
+}
+
+#[test]
+fn raw_xdr_adds_the_base64_of_keys_and_values() {
+    let (success, stdout, stderr) =
+        run_get(&[&SEEDS[..], &["--raw-xdr", "--durability", "persistent"]].concat());
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(
+        !line_with(&stdout, "supply = ").contains("Symbol("),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("    key xdr: AAAA"), "{}", stdout);
+    assert!(stdout.contains("    value xdr: AAAA"), "{}", stdout);
+
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &[
+                "--storage-filter",
+                "supply",
+                "--raw-xdr",
+                "--output",
+                "json",
+            ],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let storage = json["result"]["storage"].as_array().expect("storage list");
+    assert_eq!(storage.len(), 1, "{}", stdout);
+    assert_eq!(storage[0]["decoded_key"], "supply");
+    assert_eq!(storage[0]["decoded_value"], 2);
+    assert!(storage[0]["key_xdr"].as_str().is_some(), "{}", stdout);
+    assert!(storage[0]["value_xdr"].as_str().is_some(), "{}", stdout);
+}
+
+#[test]
+fn limit_and_offset_page_the_sorted_listing() {
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &["--durability", "persistent", "--durability", "temporary"],
+            // Leaves out the nonce registering the contract stored.
+            &["--storage-filter", "supply", "--storage-filter", "nonce"],
+            &["--sort", "key", "--limit", "1", "--offset", "1"],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("nonce = "), "{}", stdout);
+    assert!(!stdout.contains("supply = "), "{}", stdout);
+    assert!(
+        line_with(&stdout, "showing 2-2 of 2 entries").contains("(filtered from "),
+        "{}",
+        stdout
+    );
+
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &["--storage-filter", "*", "--limit", "1", "--output", "json"],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let storage = json["result"]["storage"].as_array().expect("storage list");
+    assert_eq!(storage.len(), 1, "{}", stdout);
+
+    let (success, stdout, stderr) = run_get(
+        &[
+            &SEEDS[..],
+            &["--storage-filter", "*", "--limit", "0", "--output", "json"],
+        ]
+        .concat(),
+    );
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let storage = json["result"]["storage"].as_array().expect("storage list");
+    assert!(storage.len() >= 3, "{}", stdout);
+}
+
+#[test]
+fn durability_flags_take_an_object() {
+    let (success, _, stderr) = run_get(&["--temporary-storage", "[1, 2]"]);
+    assert!(!success);
+    assert!(
+        stderr.contains("--temporary-storage expects a JSON object mapping keys to values"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,131 @@
+//! `run --track-writes`: every storage write of the run with the call that
+//! made it, and the frames `--alert-on-change` alerts and `--break-on-write`
+//! stops name.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn writes_are_listed_with_the_call_that_made_them() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--storage", r#"{"c": 4}"#, "--track-writes"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let section = stdout
+        .split("--- Storage Writes ---")
+        .nth(1)
+        .unwrap_or_else(|| panic!("no storage writes section in:\n{}", stdout));
+    let write = section
+        .lines()
+        .find(|line| line.contains("I64(5)"))
+        .unwrap_or_else(|| panic!("no write of the counter in:\n{}", section));
+    assert!(write.contains("set: I64(4) -> I64(5)"), "{}", write);
+    assert!(write.contains("[increment at depth 0 in C"), "{}", write);
+
+    // Without --track-writes nothing is listed.
+    let (success, stdout, _) = fixtures::run_counter(&[]);
+    assert!(success);
+    assert!(!stdout.contains("--- Storage Writes ---"), "{}", stdout);
+}
+
+#[test]
+fn storage_filter_narrows_the_listing_but_not_the_json() {
+    let (success, stdout, stderr) = fixtures::run_counter(&[
+        "--persistent-storage",
+        r#"{"supply": 2}"#,
+        "--track-writes",
+        "--storage-filter",
+        "balance:*",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("No storage writes recorded"), "{}", stdout);
+
+    let (success, stdout, stderr) = fixtures::run_counter(&[
+        "--track-writes",
+        "--storage-filter",
+        "balance:*",
+        "--output",
+        "json",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let writes = json["result"]["storage_writes"]
+        .as_array()
+        .expect("storage_writes list");
+    let increment = writes
+        .iter()
+        .find(|write| write["function"] == "increment")
+        .unwrap_or_else(|| panic!("no write by increment in {}", stdout));
+    assert_eq!(increment["depth"], 0);
+    assert_eq!(increment["operation"], "set");
+    assert_eq!(increment["old"], serde_json::Value::Null);
+    assert_eq!(increment["new"], "I64(1)");
+}
+
+#[test]
+fn alerts_carry_the_values_storage_and_writing_frame() {
+    let (success, stdout, stderr) = fixtures::run_counter(&[
+        "--storage",
+        r#"{"c": 4}"#,
+        "--alert-on-change",
+        "instance:*",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    // Alerts are logged as errors.
+    let alert = stderr
+        .lines()
+        .find(|line| line.contains("was modified in instance storage: I64(4)"))
+        .unwrap_or_else(|| panic!("no alert on the counter in:\n{}", stderr));
+    assert!(alert.contains("-> I64(5)"), "{}", alert);
+    assert!(
+        alert.contains("[set by increment at depth 0 in C"),
+        "{}",
+        alert
+    );
+    // Alerts track writes for themselves, without listing them.
+    assert!(!stdout.contains("--- Storage Writes ---"), "{}", stdout);
+
+    let (success, stdout, stderr) = fixtures::run_counter(&[
+        "--storage",
+        r#"{"c": 4}"#,
+        "--alert-on-change",
+        "instance:*",
+        "--output",
+        "json",
+    ]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    let alerts = json["result"]["alerts"].as_array().expect("alerts list");
+    assert_eq!(alerts.len(), 1, "{}", stdout);
+    assert_eq!(alerts[0]["change"], "modified");
+    assert_eq!(alerts[0]["durability"], "instance");
+    assert_eq!(alerts[0]["writes"][0]["function"], "increment");
+    assert!(json["result"].get("storage_writes").is_none(), "{}", stdout);
+}
+
+#[test]
+fn break_on_write_reports_the_write_and_its_call_stack() {
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--storage", r#"{"c": 4}"#, "--break-on-write", "instance:*"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let report = stdout
+        .split("Write breakpoint hit: instance:")
+        .nth(1)
+        .unwrap_or_else(|| panic!("no write breakpoint in:\n{}", stdout));
+    assert!(report.contains("set: I64(4) -> I64(5)"), "{}", report);
+    assert!(
+        report.contains("in increment at depth 0 in C"),
+        "{}",
+        report
+    );
+    assert!(report.contains("0: increment() in C"), "{}", report);
+
+    let (success, stdout, stderr) =
+        fixtures::run_counter(&["--break-on-write", "balance:*", "--output", "json"]);
+    assert!(success, "{}{}", stdout, stderr);
+    let json: serde_json::Value = serde_json::from_str(&stdout).expect("valid JSON");
+    assert_eq!(json["result"]["write_breakpoints"], serde_json::json!([]));
+
+    let (success, _, stderr) = fixtures::run_counter(&["--break-on-write", "re:("]);
+    assert!(!success);
+    assert!(stderr.contains("Invalid write breakpoint"), "{}", stderr);
+}
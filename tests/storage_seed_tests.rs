@@ -1,25 +1,11 @@
 use soroban_debugger::runtime::executor::ContractExecutor;
 
-fn fixture_wasm(name: &str) -> std::path::PathBuf {
-    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("tests")
-        .join("fixtures")
-        .join("wasm")
-        .join(format!("{name}.wasm"))
-}
+#[path = "fixtures/mod.rs"]
+mod fixtures;
 
 #[test]
 fn storage_seed_changes_execution_and_snapshot() {
-    let wasm_path = fixture_wasm("counter");
-    if !wasm_path.exists() {
-        eprintln!(
-            "Skipping test: fixture not found at {}. Run tests/fixtures/build.sh to build fixtures.",
-            wasm_path.display()
-        );
-        return;
-    }
-
-    let wasm = std::fs::read(&wasm_path).expect("read fixture wasm");
+    let wasm = fixtures::load_fixture(fixtures::names::COUNTER);
     let mut executor = ContractExecutor::new(wasm).expect("create executor");
 
     executor
@@ -49,12 +35,7 @@ fn storage_seed_changes_execution_and_snapshot() {
 
 #[test]
 fn storage_seed_rejects_malformed_json() {
-    let wasm_path = fixture_wasm("counter");
-    if !wasm_path.exists() {
-        return;
-    }
-
-    let wasm = std::fs::read(&wasm_path).unwrap();
+    let wasm = fixtures::load_fixture(fixtures::names::COUNTER);
     let mut executor = ContractExecutor::new(wasm).unwrap();
     let err = executor
         .set_initial_storage("{not_json".to_string())
@@ -62,3 +43,22 @@ fn storage_seed_rejects_malformed_json() {
         .to_string();
     assert!(err.contains("Failed to parse initial storage JSON"));
 }
+
+#[test]
+fn storage_seed_errors_name_the_offending_key() {
+    let wasm = fixtures::load_fixture(fixtures::names::COUNTER);
+    let mut executor = ContractExecutor::new(wasm).unwrap();
+    let err = executor
+        .set_initial_storage(
+            r#"{"c": 1, "ADMIN": {"type": "address", "value": "not-an-address"}}"#.to_string(),
+        )
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("--storage key ADMIN"), "{err}");
+
+    let err = executor
+        .set_initial_storage(r#"{"ratio": 1.5}"#.to_string())
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("--storage key ratio"), "{err}");
+}
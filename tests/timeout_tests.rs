@@ -0,0 +1,40 @@
+//! `run --timeout`: a contract that never returns ends the run in bounded
+//! time with a non-zero exit code.
+
+use std::time::{Duration, Instant};
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn looping_contract_is_stopped() {
+    let wasm = fixtures::get_fixture_path(fixtures::names::SPIN);
+    let started = Instant::now();
+    let output = fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(wasm)
+        // A budget the loop cannot spend in a second, so the timeout fires.
+        .args(["--function", "spin", "--timeout", "1"])
+        .args(["--cpu-limit", "1000000000000"])
+        .timeout(Duration::from_secs(60))
+        .output()
+        .expect("Failed to execute soroban-debug");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(started.elapsed() < Duration::from_secs(30), "{}", stderr);
+    assert_eq!(output.status.code(), Some(124), "{}", stderr);
+    assert!(
+        stderr.contains("Error: execution exceeded 1s timeout in function spin"),
+        "{}",
+        stderr
+    );
+    // The host cannot be read while the loop runs and nothing is mocked, so
+    // the report is the one taken at dispatch.
+    assert!(
+        stderr.contains("Diagnostics as of the start of the call, "),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("Budget: "), "{}", stderr);
+}
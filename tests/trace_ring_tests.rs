@@ -0,0 +1,55 @@
+//! The always-on trace ring: printed when a run fails, discarded when it
+//! succeeds unless `--dump-ring` asks for it.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+#[test]
+fn trapping_run_prints_the_ring_with_its_error() {
+    let (success, stdout, stderr) = fixtures::run("always_panic", "panic", &[]);
+    assert!(!success, "{}{}", stdout, stderr);
+    assert!(stderr.contains("--- Trace Ring (last"), "{}", stderr);
+    assert!(stderr.contains("failed panic: "), "{}", stderr);
+}
+
+#[test]
+fn ring_size_zero_turns_the_dump_off() {
+    let (success, stdout, stderr) = fixtures::run("always_panic", "panic", &["--ring-size", "0"]);
+    assert!(!success);
+    assert!(!stderr.contains("Trace Ring"), "{}", stderr);
+    assert!(!stdout.contains("Trace Ring"), "{}", stdout);
+}
+
+#[test]
+fn successful_run_discards_the_ring() {
+    let (success, stdout, stderr) = fixtures::run("counter", "increment", &[]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(!stdout.contains("Trace Ring"), "{}", stdout);
+    assert!(!stderr.contains("Trace Ring"), "{}", stderr);
+}
+
+#[test]
+fn dump_ring_prints_it_after_a_successful_run() {
+    let (success, stdout, stderr) = fixtures::run("counter", "increment", &["--dump-ring"]);
+    assert!(success, "{}{}", stdout, stderr);
+    assert!(stdout.contains("--- Trace Ring (last"), "{}", stdout);
+    assert!(stdout.contains("ms write "), "{}", stdout);
+
+    let (success, stdout, _) =
+        fixtures::run("counter", "increment", &["--dump-ring", "--output", "json"]);
+    assert!(success);
+    let json_start = stdout.find("{\n").expect("JSON output expected");
+    let value: serde_json::Value = serde_json::Deserializer::from_str(&stdout[json_start..])
+        .into_iter()
+        .next()
+        .unwrap()
+        .unwrap();
+    let ring = value["result"]["trace_ring"]
+        .as_array()
+        .unwrap_or_else(|| panic!("{}", value));
+    assert!(
+        ring.iter().any(|entry| entry["kind"] == "write"),
+        "{}",
+        value
+    );
+}
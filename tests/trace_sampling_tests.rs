@@ -0,0 +1,91 @@
+//! `run --trace-sample` and `--trace-limit`: recording part of the storage accesses.
+
+use predicates::prelude::*;
+use std::path::Path;
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+fn run_traced(trace: &Path, extra: &[&str]) -> serde_json::Value {
+    fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .args(["--function", "increment", "--trace-output"])
+        .arg(trace)
+        .args(extra)
+        .assert()
+        .success();
+    serde_json::from_str(&std::fs::read_to_string(trace).unwrap()).unwrap()
+}
+
+#[test]
+fn full_traces_are_not_marked_as_sampled() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = run_traced(&dir.path().join("trace.json"), &[]);
+    assert!(trace.get("sampling").is_none(), "{}", trace);
+    assert!(!trace["storage_accesses"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn sampled_traces_record_the_rate_and_keep_contract_calls() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = run_traced(&dir.path().join("trace.json"), &["--trace-sample", "5"]);
+    assert_eq!(trace["sampling"]["rate"], 5);
+    assert_eq!(trace["sampling"]["truncated"], false);
+    assert_eq!(trace["call_sequence"][0]["function"], "increment");
+    let recorded = trace["storage_accesses"].as_array().unwrap().len();
+    assert_eq!(trace["sampling"]["recorded"], recorded);
+}
+
+#[test]
+fn the_limit_counts_accesses_recorded_after_sampling() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = run_traced(
+        &dir.path().join("trace.json"),
+        &["--trace-sample", "2", "--trace-limit", "0"],
+    );
+    assert_eq!(trace["sampling"]["rate"], 2);
+    assert_eq!(trace["sampling"]["recorded"], 0);
+    assert_eq!(trace["sampling"]["truncated"], true);
+    assert!(trace["sampling"]["observed"].as_u64().unwrap() > 0);
+    assert!(trace.get("storage_accesses").is_none());
+}
+
+#[test]
+fn replay_refuses_a_sampled_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("trace.json");
+    run_traced(&path, &["--trace-sample", "3"]);
+
+    fixtures::soroban_debug()
+        .arg("replay")
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("--trace-sample 3"));
+}
+
+#[test]
+fn a_limit_alone_does_not_mark_the_trace_as_sampled() {
+    let dir = tempfile::tempdir().unwrap();
+    let trace = run_traced(&dir.path().join("trace.json"), &["--trace-limit", "100"]);
+    assert_eq!(trace["sampling"]["rate"], 1);
+    assert_eq!(trace["sampling"]["truncated"], false);
+    assert_eq!(trace["sampling"]["recorded"], trace["sampling"]["observed"]);
+}
+
+#[test]
+fn sample_rate_must_be_positive_and_not_combined_with_repeat() {
+    fixtures::soroban_debug()
+        .args(["run", "--contract", "x.wasm", "--function", "f"])
+        .args(["--trace-sample", "0"])
+        .assert()
+        .failure();
+    fixtures::soroban_debug()
+        .args(["run", "--contract", "x.wasm", "--function", "f"])
+        .args(["--trace-sample", "2", "--repeat", "3"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
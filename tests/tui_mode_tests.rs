@@ -1,31 +1,11 @@
-use assert_cmd::Command;
-
-fn fixture_wasm(name: &str) -> std::path::PathBuf {
-    std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"))
-        .join("tests")
-        .join("fixtures")
-        .join("wasm")
-        .join(format!("{name}.wasm"))
-}
-fn base_cmd() -> Command {
-    let mut cmd = Command::new(env!("CARGO_BIN_EXE_soroban-debug"));
-    cmd.env("NO_COLOR", "1");
-    cmd.env("NO_BANNER", "1");
-    cmd
-}
+#[path = "fixtures/mod.rs"]
+mod fixtures;
 
 #[test]
 fn tui_smoke_starts_and_exits_cleanly() {
-    let wasm = fixture_wasm("counter");
-    if !wasm.exists() {
-        eprintln!(
-            "Skipping test: fixture not found at {}. Run tests/fixtures/build.sh to build fixtures.",
-            wasm.display()
-        );
-        return;
-    }
-
-    let output = base_cmd()
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+
+    let output = fixtures::soroban_debug()
         .env("SOROBAN_DEBUG_TUI_SMOKE", "1")
         .args([
             "tui",
@@ -44,3 +24,45 @@ fn tui_smoke_starts_and_exits_cleanly() {
         String::from_utf8_lossy(&output.stderr)
     );
 }
+
+#[cfg(feature = "tui")]
+#[test]
+fn storage_browser_smoke_starts_without_a_function() {
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+    let dir = tempfile::tempdir().unwrap();
+    let storage = dir.path().join("storage.json");
+    std::fs::write(&storage, r#"{"entries":{}}"#).unwrap();
+
+    let output = fixtures::soroban_debug()
+        .env("SOROBAN_DEBUG_TUI_SMOKE", "1")
+        .args([
+            "tui",
+            "--contract",
+            wasm.to_str().unwrap(),
+            "--load-storage",
+            storage.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+
+    assert!(
+        output.status.success(),
+        "stdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[cfg(not(feature = "tui"))]
+#[test]
+fn storage_browser_requires_the_tui_feature() {
+    let wasm = fixtures::get_fixture_path(fixtures::names::COUNTER);
+
+    let output = fixtures::soroban_debug()
+        .args(["tui", "--contract", wasm.to_str().unwrap()])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--features tui"));
+}
@@ -0,0 +1,63 @@
+//! End-of-run warning summary and the `--max-warnings` gate.
+
+#[path = "fixtures/mod.rs"]
+mod fixtures;
+
+const MAX_WARNINGS_EXIT_CODE: i32 = 3;
+
+/// Run `increment` so that it warns once each for `deprecation`, `expectation` and `trace`.
+fn run_with_three_warnings(extra: &[&str]) -> std::process::Output {
+    let missing_dir = tempfile::tempdir().unwrap();
+    let trace_path = missing_dir.path().join("missing").join("trace.json");
+    fixtures::soroban_debug()
+        .arg("run")
+        .arg("--contract")
+        .arg(fixtures::get_fixture_path(fixtures::names::COUNTER))
+        .arg("--show-events")
+        .arg("--function")
+        .arg("increment")
+        .arg("--expect")
+        .arg("I64(999)")
+        .arg("--expect-mode")
+        .arg("warn")
+        .arg("--trace-output")
+        .arg(&trace_path)
+        .args(extra)
+        .output()
+        .expect("Failed to execute run command")
+}
+
+#[test]
+fn summary_groups_warnings_by_category() {
+    let output = run_with_three_warnings(&[]);
+    assert!(
+        output.status.success(),
+        "no limit should not fail: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("--- Warnings ---"), "{}", stdout);
+    assert!(stdout.contains("3 warning(s)"), "{}", stdout);
+    for category in ["deprecation", "expectation", "trace"] {
+        assert!(stdout.contains(&format!("  {}", category)), "{}", stdout);
+    }
+}
+
+#[test]
+fn exceeding_max_warnings_uses_dedicated_exit_code() {
+    let output = run_with_three_warnings(&["--max-warnings", "2"]);
+    assert_eq!(output.status.code(), Some(MAX_WARNINGS_EXIT_CODE));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("exceed --max-warnings 2"));
+}
+
+#[test]
+fn allowed_categories_do_not_count_towards_the_limit() {
+    let output =
+        run_with_three_warnings(&["--max-warnings", "2", "--allow-warning", "deprecation"]);
+    assert!(
+        output.status.success(),
+        "allowed warning should not fail: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 warning(s) (1 allowed)"));
+}